@@ -1,8 +1,11 @@
 //! # Backend Service
 //!
 //! Thin entry point that delegates to lib-web for server setup.
+//!
+//! `backend --self-test` checks configuration, database, migrations and the
+//! external APIs without starting the server, and exits non-zero on failure.
 
-use lib_web::{start_server, ServerConfig};
+use lib_web::{run_self_test, start_server, ServerConfig};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -15,6 +18,11 @@ async fn main() -> anyhow::Result<()> {
         ..Default::default()
     };
 
+    if std::env::args().skip(1).any(|arg| arg == "--self-test") {
+        let report = run_self_test(&config).await;
+        println!("{}", report);
+        std::process::exit(if report.passed() { 0 } else { 1 });
+    }
+
     start_server(config).await
 }
-
@@ -7,5 +7,5 @@ pub mod token;
 
 // Re-export commonly used types
 pub use pwd::{hash_password, verify_password};
-pub use token::{Claims, encode_jwt, decode_jwt};
+pub use token::{Claims, encode_jwt, decode_jwt, SCOPE_READ, SCOPE_WRITE};
 
@@ -5,8 +5,18 @@
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Scope letting an API key call endpoints that only read (`GET`)
+pub const SCOPE_READ: &str = "read";
+
+/// Scope letting an API key call endpoints that change state
+pub const SCOPE_WRITE: &str = "write";
 
 /// JWT Claims structure containing user authentication information.
+///
+/// Requests authenticated with an API key get the same claims, built from the
+/// key's owner, with `scopes` set to the key's scopes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     /// Subject (user ID)
@@ -17,6 +27,35 @@ pub struct Claims {
     pub exp: i64,
     /// Issued at time (Unix timestamp)
     pub iat: i64,
+    /// Unique token id, used to revoke this token on logout
+    #[serde(default)]
+    pub jti: String,
+    /// Scopes of the API key the request was made with; `None` for a login session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+}
+
+impl Claims {
+    /// Key this token is revoked under.
+    ///
+    /// Tokens issued before `jti` existed fall back to user id and issue time.
+    pub fn revocation_id(&self) -> String {
+        if self.jti.is_empty() {
+            format!("{}:{}", self.sub, self.iat)
+        } else {
+            self.jti.clone()
+        }
+    }
+
+    /// Whether the request was made with an API key rather than a login session
+    pub fn is_api_key(&self) -> bool {
+        self.scopes.is_some()
+    }
+
+    /// Whether the caller may do what `scope` covers; login sessions may do anything
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.as_ref().is_none_or(|scopes| scopes.iter().any(|s| s == scope))
+    }
 }
 
 /// Encode a JWT token with user claims.
@@ -34,6 +73,8 @@ pub fn encode_jwt(
         username,
         exp: exp.timestamp(),
         iat: now.timestamp(),
+        jti: Uuid::new_v4().to_string(),
+        scopes: None,
     };
 
     encode(
@@ -74,4 +115,29 @@ mod tests {
         assert_eq!(claims.sub, user_id.to_string());
         assert_eq!(claims.username, username);
     }
+
+    #[test]
+    fn test_jwt_has_unique_jti() {
+        let secret = "test-secret-key-must-be-at-least-32-chars-long!";
+
+        let first = decode_jwt(&encode_jwt(1, "a".to_string(), secret, 24).unwrap(), secret).unwrap();
+        let second = decode_jwt(&encode_jwt(1, "a".to_string(), secret, 24).unwrap(), secret).unwrap();
+
+        assert!(!first.jti.is_empty());
+        assert_ne!(first.jti, second.jti);
+        assert_eq!(first.revocation_id(), first.jti);
+    }
+
+    #[test]
+    fn test_scopes() {
+        let secret = "test-secret-key-must-be-at-least-32-chars-long!";
+        let mut claims = decode_jwt(&encode_jwt(1, "a".to_string(), secret, 24).unwrap(), secret).unwrap();
+        assert!(!claims.is_api_key());
+        assert!(claims.has_scope(SCOPE_WRITE));
+
+        claims.scopes = Some(vec![SCOPE_READ.to_string()]);
+        assert!(claims.is_api_key());
+        assert!(claims.has_scope(SCOPE_READ));
+        assert!(!claims.has_scope(SCOPE_WRITE));
+    }
 }
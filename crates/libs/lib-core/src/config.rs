@@ -35,6 +35,28 @@ pub struct Config {
     /// After this period, users must re-authenticate.
     /// Valid range: 1-720 hours (1 hour to 30 days)
     pub jwt_expiration_hours: i64,
+
+    /// Symbols seeded into the price stream on first run (`STREAMED_SYMBOLS`)
+    ///
+    /// Only read while the `streamed_symbols` table is empty; after that the
+    /// database is authoritative.
+    pub streamed_symbols: Vec<String>,
+
+    /// Usernames allowed to call `/api/admin/*` endpoints (`ADMIN_USERNAMES`)
+    pub admin_usernames: Vec<String>,
+}
+
+/// Default price stream universe when `STREAMED_SYMBOLS` is unset
+pub const DEFAULT_STREAMED_SYMBOLS: &[&str] = &["SOL", "USDC", "USDT", "BTC", "ETH", "JUP", "RAY"];
+
+/// Split a comma-separated environment value into trimmed, non-empty items
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
 }
 
 impl Config {
@@ -52,10 +74,20 @@ impl Config {
             .parse()
             .map_err(|e| format!("JWT_EXPIRATION_HOURS must be a valid number: {}", e))?;
 
+        let streamed_symbols = env::var("STREAMED_SYMBOLS")
+            .map(|v| parse_list(&v.to_uppercase()))
+            .unwrap_or_else(|_| DEFAULT_STREAMED_SYMBOLS.iter().map(|s| s.to_string()).collect());
+
+        let admin_usernames = env::var("ADMIN_USERNAMES")
+            .map(|v| parse_list(&v))
+            .unwrap_or_default();
+
         Ok(Self {
             database_url,
             jwt_secret,
             jwt_expiration_hours,
+            streamed_symbols,
+            admin_usernames,
         })
     }
 
@@ -71,6 +103,11 @@ impl Config {
 
         Ok(())
     }
+
+    /// Whether `username` may call admin endpoints
+    pub fn is_admin(&self, username: &str) -> bool {
+        self.admin_usernames.iter().any(|admin| admin == username)
+    }
 }
 
 /// Global configuration instance (initialized once at startup).
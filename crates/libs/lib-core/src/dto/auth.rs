@@ -15,11 +15,16 @@
 //! ### Traditional Authentication
 //! - `POST /api/auth/signup` - [`SignupRequest`] -> [`AuthResponse`]
 //! - `POST /api/auth/login` - [`LoginRequest`] -> [`AuthResponse`]
+//! - `POST /api/auth/password-reset/request` - [`PasswordResetRequest`] -> [`PasswordResetResponse`]
+//! - `POST /api/auth/password-reset/confirm` - [`PasswordResetConfirmRequest`] -> [`PasswordResetResponse`]
+//! - `PUT /api/auth/password` - [`ChangePasswordRequest`] -> [`AuthResponse`] (requires auth)
+//! - `PUT /api/auth/profile` - [`UpdateProfileRequest`] -> [`UserInfo`] (requires auth)
 //!
 //! ### Wallet Authentication Flow
 //! 1. `GET /api/wallet/setup/validate?token=...` - [`WalletSetupValidateRequest`] (query) -> [`WalletSetupValidateResponse`]
 //! 2. `POST /api/wallet/setup/complete` - [`WalletSetupCompleteRequest`] -> [`WalletSetupCompleteResponse`]
-//! 3. `POST /api/wallet/login` - [`WalletLoginRequest`] -> [`AuthResponse`]
+//! 3. `GET /api/wallet/login/challenge?wallet_address=...` - [`WalletLoginChallengeRequest`] (query) -> [`WalletLoginChallengeResponse`]
+//! 4. `POST /api/auth/wallet-login` - [`WalletLoginRequest`] -> [`AuthResponse`]
 //!
 //! ## Wire Format
 //!
@@ -184,6 +189,62 @@ pub struct SignupRequest {
     pub password: String,
 }
 
+/// Password reset request.
+///
+/// Used by `POST /api/auth/password-reset/request`. The response is the same
+/// whether or not an account uses `email`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+/// Password reset confirmation.
+///
+/// Used by `POST /api/auth/password-reset/confirm`.
+///
+/// # Fields
+///
+/// * `token` - One-time code from the reset email (valid for 30 minutes)
+/// * `new_password` - New plaintext password (same rules as signup)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PasswordResetConfirmRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+/// Response of both password reset endpoints.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PasswordResetResponse {
+    pub message: String,
+}
+
+/// Password change for the logged-in user.
+///
+/// Used by `PUT /api/auth/password`. The response is an [`AuthResponse`] with
+/// a fresh token; every other session of the user is logged out.
+///
+/// # Fields
+///
+/// * `current_password` - The password being replaced
+/// * `new_password` - New plaintext password (same rules as signup)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Profile update for the logged-in user.
+///
+/// Used by `PUT /api/auth/profile`; returns the updated [`UserInfo`].
+///
+/// # Fields
+///
+/// * `email` - New email address (must not be used by another account)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UpdateProfileRequest {
+    pub email: String,
+}
+
 /// Authentication response returned on successful login or signup.
 ///
 /// Used by:
@@ -524,28 +585,62 @@ pub struct WalletSetupCompleteResponse {
     pub message: String,
 }
 
+/// Wallet login challenge request (query parameters).
+///
+/// Used by `GET /api/wallet/login/challenge?wallet_address=...` before
+/// [`WalletLoginRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WalletLoginChallengeRequest {
+    pub wallet_address: String,
+}
+
+/// Server-issued wallet login challenge.
+///
+/// # Fields
+///
+/// * `challenge` - Single-use nonce, echoed back in [`WalletLoginRequest`]
+/// * `message` - Exact text for the wallet to sign
+/// * `expires_at` - Unix timestamp (seconds) after which the challenge is rejected
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "challenge": "9b4e7f2a8c5d1e3f6a9b2c5d8e1f4a7b",
+///   "message": "Login to XForce Terminal\n\nChallenge: 9b4e7f2a8c5d1e3f6a9b2c5d8e1f4a7b",
+///   "expires_at": 1735689900
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WalletLoginChallengeResponse {
+    pub challenge: String,
+    pub message: String,
+    pub expires_at: i64,
+}
+
 /// Wallet login request (sign challenge to prove wallet ownership).
 ///
-/// Used by `POST /api/wallet/login` to authenticate using a Phantom wallet.
+/// Used by `POST /api/auth/wallet-login` to authenticate using a Phantom wallet.
 /// No email/password required - authentication is purely cryptographic.
 ///
 /// # Fields
 ///
 /// * `wallet_address` - Solana wallet public key (base58 encoded)
 /// * `signature` - Ed25519 signature of the challenge (base58 encoded)
-/// * `challenge` - Challenge string that was signed (from separate challenge endpoint)
+/// * `challenge` - Challenge from [`WalletLoginChallengeResponse`]
 ///
 /// # Authentication Flow
 ///
-/// 1. Frontend requests a challenge (separate endpoint, not shown here)
-/// 2. User signs the challenge with Phantom wallet
+/// 1. Frontend requests a challenge for the wallet
+/// 2. User signs the challenge's `message` with Phantom wallet
 /// 3. Frontend sends this request with wallet address, signature, and challenge
-/// 4. Server verifies signature and returns [`AuthResponse`] with JWT token
+/// 4. Server verifies signature, consumes the challenge and returns [`AuthResponse`] with JWT token
 ///
 /// # Security
 ///
 /// - No passwords involved - uses Ed25519 public key cryptography
-/// - Challenge prevents replay attacks
+/// - Challenges are issued by the server, single-use and short-lived, so a
+///   captured signature can't be replayed (401)
 /// - Server verifies the signature using the wallet's public key
 ///
 /// # JSON Example
@@ -554,7 +649,7 @@ pub struct WalletSetupCompleteResponse {
 /// {
 ///   "wallet_address": "9aE476sH92Vz7DMPyq5WLPkrKWivxeuTKEFKd2sZZcde",
 ///   "signature": "2Kv8xYQ7pN9L2mJ4Z1hR5tB3vC8nD6fE4gH2kM9jL3nP...",
-///   "challenge": "Login challenge: 9b4e7f2a8c5d1e3f6a9b2c5d8e1f4a7b"
+///   "challenge": "9b4e7f2a8c5d1e3f6a9b2c5d8e1f4a7b"
 /// }
 /// ```
 ///
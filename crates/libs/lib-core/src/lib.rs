@@ -1,14 +1,16 @@
 //! # Core Library
 //!
-//! Core models, database, configuration, and context for the application.
+//! Core models, database, configuration, metrics, and context for the application.
 
 pub mod config;
 pub mod error;
 pub mod model;
 pub mod dto;
+pub mod metrics;
 
 // Re-export commonly used types
 pub use config::Config;
 pub use error::{AppError, Result};
+pub use metrics::Metrics;
 pub use model::store::{DbPool, create_pool};
 
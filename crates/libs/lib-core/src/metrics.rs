@@ -0,0 +1,316 @@
+//! # Metrics Registry
+//!
+//! Counters, gauges and histograms rendered in the Prometheus text format
+//! (served by the backend at `GET /metrics`).
+//!
+//! Every metric is declared once as a [`MetricDef`] below, with its help text
+//! and type; series are created on first use, one per distinct label set.
+//! Label values should come from a small, fixed set (route templates, not
+//! paths; upstream names, not URLs) so the number of series stays bounded.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lib_core::metrics::{Metrics, HTTP_REQUESTS};
+//!
+//! let metrics = Metrics::new();
+//! metrics.increment(&HTTP_REQUESTS, &[("method", "GET"), ("route", "/api/health"), ("status", "200")]);
+//! assert!(metrics.render().contains(r#"xforce_http_requests_total{method="GET",route="/api/health",status="200"} 1"#));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Upper bounds of the histogram buckets, in seconds (Prometheus' defaults)
+pub const DURATION_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Kind of a metric, as written on its `# TYPE` line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+/// Name, help text and kind of a metric
+#[derive(Debug)]
+pub struct MetricDef {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+}
+
+/// HTTP requests handled, by `method`, matched `route` and `status`
+pub static HTTP_REQUESTS: MetricDef = MetricDef {
+    name: "xforce_http_requests_total",
+    help: "HTTP requests handled, by method, route and status",
+    kind: MetricKind::Counter,
+};
+
+/// Time to handle an HTTP request, by `method` and `route`
+pub static HTTP_REQUEST_DURATION: MetricDef = MetricDef {
+    name: "xforce_http_request_duration_seconds",
+    help: "Time to handle an HTTP request, by method and route",
+    kind: MetricKind::Histogram,
+};
+
+/// Duration of calls to Jupiter and Pyth, by `upstream` and `call`
+pub static UPSTREAM_DURATION: MetricDef = MetricDef {
+    name: "xforce_upstream_request_duration_seconds",
+    help: "Duration of calls to upstream APIs, by upstream and call",
+    kind: MetricKind::Histogram,
+};
+
+/// Failed calls to Jupiter and Pyth, by `upstream` and `call`
+pub static UPSTREAM_FAILURES: MetricDef = MetricDef {
+    name: "xforce_upstream_failures_total",
+    help: "Failed calls to upstream APIs, by upstream and call",
+    kind: MetricKind::Counter,
+};
+
+/// Clients connected to the price WebSocket
+pub static WEBSOCKET_CLIENTS: MetricDef = MetricDef {
+    name: "xforce_websocket_clients",
+    help: "Clients connected to the price WebSocket",
+    kind: MetricKind::Gauge,
+};
+
+/// Database pool connections, by `state` (`idle`, `in_use`, `max`)
+pub static DB_POOL_CONNECTIONS: MetricDef = MetricDef {
+    name: "xforce_db_pool_connections",
+    help: "Database pool connections, by state",
+    kind: MetricKind::Gauge,
+};
+
+/// Label pairs of a series, in the order they were given
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug, Clone)]
+enum Series {
+    Counter(u64),
+    Gauge(f64),
+    Histogram {
+        /// Observations per bucket of [`DURATION_BUCKETS`] (not cumulative)
+        buckets: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+#[derive(Debug)]
+struct Family {
+    def: &'static MetricDef,
+    series: BTreeMap<Labels, Series>,
+}
+
+/// Registry of every metric of the process
+#[derive(Debug, Default)]
+pub struct Metrics {
+    families: Mutex<BTreeMap<&'static str, Family>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one to a counter
+    pub fn increment(&self, def: &'static MetricDef, labels: &[(&'static str, &str)]) {
+        self.update(def, labels, |series| {
+            if let Series::Counter(value) = series {
+                *value += 1;
+            }
+        });
+    }
+
+    /// Set a gauge
+    pub fn set(&self, def: &'static MetricDef, labels: &[(&'static str, &str)], value: f64) {
+        self.update(def, labels, |series| {
+            if let Series::Gauge(current) = series {
+                *current = value;
+            }
+        });
+    }
+
+    /// Record a duration, in seconds, in a histogram
+    pub fn observe(&self, def: &'static MetricDef, labels: &[(&'static str, &str)], seconds: f64) {
+        self.update(def, labels, |series| {
+            if let Series::Histogram { buckets, sum, count } = series {
+                if let Some(bucket) = DURATION_BUCKETS.iter().position(|bound| seconds <= *bound) {
+                    buckets[bucket] += 1;
+                }
+                *sum += seconds;
+                *count += 1;
+            }
+        });
+    }
+
+    /// Current value of a counter; 0 if it was never incremented
+    pub fn counter(&self, def: &'static MetricDef, labels: &[(&'static str, &str)]) -> u64 {
+        let families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        match families.get(def.name).and_then(|family| family.series.get(&to_labels(labels))) {
+            Some(Series::Counter(value)) => *value,
+            _ => 0,
+        }
+    }
+
+    /// Every metric in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+        for family in families.values() {
+            let def = family.def;
+            let _ = writeln!(out, "# HELP {} {}", def.name, def.help);
+            let _ = writeln!(out, "# TYPE {} {}", def.name, def.kind.as_str());
+            for (labels, series) in &family.series {
+                match series {
+                    Series::Counter(value) => {
+                        let _ = writeln!(out, "{}{} {}", def.name, format_labels(labels, None), value);
+                    }
+                    Series::Gauge(value) => {
+                        let _ = writeln!(out, "{}{} {}", def.name, format_labels(labels, None), value);
+                    }
+                    Series::Histogram { buckets, sum, count } => {
+                        let mut cumulative = 0;
+                        for (bound, observed) in DURATION_BUCKETS.iter().zip(buckets) {
+                            cumulative += observed;
+                            let le = bound.to_string();
+                            let _ = writeln!(out, "{}_bucket{} {}", def.name, format_labels(labels, Some(&le)), cumulative);
+                        }
+                        let _ = writeln!(out, "{}_bucket{} {}", def.name, format_labels(labels, Some("+Inf")), count);
+                        let _ = writeln!(out, "{}_sum{} {}", def.name, format_labels(labels, None), sum);
+                        let _ = writeln!(out, "{}_count{} {}", def.name, format_labels(labels, None), count);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn update(&self, def: &'static MetricDef, labels: &[(&'static str, &str)], apply: impl FnOnce(&mut Series)) {
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let family = families.entry(def.name).or_insert_with(|| Family { def, series: BTreeMap::new() });
+        let series = family.series.entry(to_labels(labels)).or_insert_with(|| match def.kind {
+            MetricKind::Counter => Series::Counter(0),
+            MetricKind::Gauge => Series::Gauge(0.0),
+            MetricKind::Histogram => Series::Histogram { buckets: vec![0; DURATION_BUCKETS.len()], sum: 0.0, count: 0 },
+        });
+        apply(series);
+    }
+}
+
+/// Time a call to an upstream API, counting it as failed when it errors.
+///
+/// Without a registry the call is just awaited.
+pub async fn time_upstream<T, E>(
+    metrics: Option<&Metrics>,
+    upstream: &str,
+    call: &str,
+    future: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let Some(metrics) = metrics else {
+        return future.await;
+    };
+    let started = Instant::now();
+    let result = future.await;
+    let labels = [("upstream", upstream), ("call", call)];
+    metrics.observe(&UPSTREAM_DURATION, &labels, started.elapsed().as_secs_f64());
+    if result.is_err() {
+        metrics.increment(&UPSTREAM_FAILURES, &labels);
+    }
+    result
+}
+
+fn to_labels(labels: &[(&'static str, &str)]) -> Labels {
+    labels.iter().map(|(name, value)| (*name, value.to_string())).collect()
+}
+
+/// `{name="value",...}`, with `le` last for histogram buckets; empty without labels
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters_and_gauges() {
+        let metrics = Metrics::new();
+        let labels = [("method", "GET"), ("route", "/api/health"), ("status", "200")];
+        metrics.increment(&HTTP_REQUESTS, &labels);
+        metrics.increment(&HTTP_REQUESTS, &labels);
+        metrics.set(&WEBSOCKET_CLIENTS, &[], 3.0);
+        metrics.set(&DB_POOL_CONNECTIONS, &[("state", "say \"hi\"")], 1.0);
+
+        assert_eq!(metrics.counter(&HTTP_REQUESTS, &labels), 2);
+        assert_eq!(metrics.counter(&HTTP_REQUESTS, &[("method", "POST")]), 0);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE xforce_http_requests_total counter\n"));
+        assert!(text.contains("xforce_http_requests_total{method=\"GET\",route=\"/api/health\",status=\"200\"} 2\n"));
+        assert!(text.contains("xforce_websocket_clients 3\n"));
+        assert!(text.contains("xforce_db_pool_connections{state=\"say \\\"hi\\\"\"} 1\n"));
+    }
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let metrics = Metrics::new();
+        let labels = [("upstream", "jupiter"), ("call", "quote")];
+        metrics.observe(&UPSTREAM_DURATION, &labels, 0.003);
+        metrics.observe(&UPSTREAM_DURATION, &labels, 0.2);
+        metrics.observe(&UPSTREAM_DURATION, &labels, 60.0);
+
+        let text = metrics.render();
+        let name = "xforce_upstream_request_duration_seconds";
+        assert!(text.contains(&format!("{}_bucket{{upstream=\"jupiter\",call=\"quote\",le=\"0.005\"}} 1\n", name)));
+        assert!(text.contains(&format!("{}_bucket{{upstream=\"jupiter\",call=\"quote\",le=\"0.25\"}} 2\n", name)));
+        assert!(text.contains(&format!("{}_bucket{{upstream=\"jupiter\",call=\"quote\",le=\"10\"}} 2\n", name)));
+        assert!(text.contains(&format!("{}_bucket{{upstream=\"jupiter\",call=\"quote\",le=\"+Inf\"}} 3\n", name)));
+        assert!(text.contains(&format!("{}_count{{upstream=\"jupiter\",call=\"quote\"}} 3\n", name)));
+    }
+
+    #[tokio::test]
+    async fn test_time_upstream_counts_failures() {
+        let metrics = Metrics::new();
+        let ok: Result<u8, String> = time_upstream(Some(&metrics), "pyth", "prices", async { Ok(1) }).await;
+        assert_eq!(ok, Ok(1));
+        let failed: Result<u8, String> =
+            time_upstream(Some(&metrics), "pyth", "prices", async { Err("timeout".to_string()) }).await;
+        assert!(failed.is_err());
+        let untracked: Result<u8, String> = time_upstream(None, "pyth", "prices", async { Err("timeout".to_string()) }).await;
+        assert!(untracked.is_err());
+
+        let labels = [("upstream", "pyth"), ("call", "prices")];
+        assert_eq!(metrics.counter(&UPSTREAM_FAILURES, &labels), 1);
+        assert!(metrics.render().contains("xforce_upstream_request_duration_seconds_count{upstream=\"pyth\",call=\"prices\"} 2\n"));
+    }
+}
@@ -0,0 +1,185 @@
+//! # API Key Repository
+//!
+//! Provides database access layer for per-user API keys.
+//!
+//! Only a hash of each key is stored; keys are looked up by that hash. Every
+//! query that takes a key id from a request also takes the user id, so one
+//! user can never list or revoke another user's keys.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::api_key_repository::ApiKeyRepository;
+//! use lib_core::create_pool;
+//!
+//! # async fn example(key_hash: &str) -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! if let Some(key) = ApiKeyRepository::find_by_hash(&pool, key_hash).await? {
+//!     println!("key {} of user {} ({})", key.label, key.user_id, key.scopes);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::models::ApiKey;
+use super::DbPool;
+use chrono::{DateTime, Duration, Utc};
+
+/// `last_used_at` is only rewritten once it is this old, so busy scripts
+/// don't turn every read into a write
+const LAST_USED_RESOLUTION: Duration = Duration::minutes(1);
+
+/// API key repository for database operations.
+pub struct ApiKeyRepository;
+
+impl ApiKeyRepository {
+    /// Store a key hash for `user_id`.
+    pub async fn create(
+        pool: &DbPool,
+        user_id: i64,
+        key_hash: &str,
+        prefix: &str,
+        label: &str,
+        scopes: &str,
+    ) -> Result<ApiKey, sqlx::Error> {
+        sqlx::query_as::<_, ApiKey>(
+            r#"
+            INSERT INTO api_keys (user_id, key_hash, prefix, label, scopes)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            RETURNING id, user_id, prefix, label, scopes, created_at, last_used_at
+            "#
+        )
+        .bind(user_id)
+        .bind(key_hash)
+        .bind(prefix)
+        .bind(label)
+        .bind(scopes)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// All keys of a user, oldest first.
+    pub async fn find_by_user(pool: &DbPool, user_id: i64) -> Result<Vec<ApiKey>, sqlx::Error> {
+        sqlx::query_as::<_, ApiKey>(
+            r#"
+            SELECT id, user_id, prefix, label, scopes, created_at, last_used_at
+            FROM api_keys
+            WHERE user_id = ?
+            ORDER BY id
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The key with this hash, if any.
+    pub async fn find_by_hash(pool: &DbPool, key_hash: &str) -> Result<Option<ApiKey>, sqlx::Error> {
+        sqlx::query_as::<_, ApiKey>(
+            r#"
+            SELECT id, user_id, prefix, label, scopes, created_at, last_used_at
+            FROM api_keys
+            WHERE key_hash = ?
+            "#
+        )
+        .bind(key_hash)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record that a key was used at `now`.
+    ///
+    /// Skipped while the recorded time is less than a minute old.
+    pub async fn record_use(pool: &DbPool, id: i64, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE api_keys SET last_used_at = ?1 WHERE id = ?2 AND (last_used_at IS NULL OR last_used_at <= ?3)")
+            .bind(now)
+            .bind(id)
+            .bind(now - LAST_USED_RESOLUTION)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete a key of `user_id`; requests made with it fail from then on.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - The key was deleted
+    /// * `Ok(false)` - No such key for this user
+    pub async fn delete(pool: &DbPool, id: i64, user_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM api_keys WHERE id = ?1 AND user_id = ?2")
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE api_keys (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                key_hash TEXT NOT NULL UNIQUE,
+                prefix TEXT NOT NULL,
+                label TEXT NOT NULL,
+                scopes TEXT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                last_used_at DATETIME
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create api_keys table");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_keys_are_scoped_to_their_user() {
+        let pool = setup_test_db().await;
+        let key = ApiKeyRepository::create(&pool, 1, "hash-a", "xfk_abcd", "Spreadsheet", "read").await.unwrap();
+        ApiKeyRepository::create(&pool, 2, "hash-b", "xfk_efgh", "Bot", "read,write").await.unwrap();
+
+        assert_eq!(ApiKeyRepository::find_by_user(&pool, 1).await.unwrap(), vec![key.clone()]);
+        assert_eq!(ApiKeyRepository::find_by_hash(&pool, "hash-a").await.unwrap(), Some(key.clone()));
+        assert!(ApiKeyRepository::find_by_hash(&pool, "unknown").await.unwrap().is_none());
+
+        assert!(!ApiKeyRepository::delete(&pool, key.id, 2).await.unwrap());
+        assert!(ApiKeyRepository::delete(&pool, key.id, 1).await.unwrap());
+        assert!(ApiKeyRepository::find_by_hash(&pool, "hash-a").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_record_use_is_throttled() {
+        let pool = setup_test_db().await;
+        let key = ApiKeyRepository::create(&pool, 1, "hash-a", "xfk_abcd", "Spreadsheet", "read").await.unwrap();
+        assert_eq!(key.last_used_at, None);
+
+        let now = Utc::now();
+        ApiKeyRepository::record_use(&pool, key.id, now).await.unwrap();
+        ApiKeyRepository::record_use(&pool, key.id, now + Duration::seconds(10)).await.unwrap();
+        let used = ApiKeyRepository::find_by_hash(&pool, "hash-a").await.unwrap().unwrap();
+        assert_eq!(used.last_used_at, Some(now));
+
+        let later = now + Duration::minutes(2);
+        ApiKeyRepository::record_use(&pool, key.id, later).await.unwrap();
+        let used = ApiKeyRepository::find_by_hash(&pool, "hash-a").await.unwrap().unwrap();
+        assert_eq!(used.last_used_at, Some(later));
+    }
+}
@@ -0,0 +1,174 @@
+//! # Imported Trade Repository
+//!
+//! Provides database access layer for trades imported from other platforms.
+//!
+//! Each row carries a `dedupe_key` built from its normalized contents, unique
+//! per user, so importing the same export twice stores nothing the second
+//! time.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::imported_trade_repository::ImportedTradeRepository;
+//! use lib_core::create_pool;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! for trade in ImportedTradeRepository::find_by_user(&pool, 1).await? {
+//!     println!("{} {} {}/{} @ {}", trade.side, trade.amount, trade.base, trade.quote, trade.price);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::models::{ImportedTrade, ImportedTradeForCreate};
+use super::DbPool;
+use sqlx::query_as;
+use std::collections::HashSet;
+
+/// Imported trade repository for database operations.
+pub struct ImportedTradeRepository;
+
+impl ImportedTradeRepository {
+    /// Store imported trades in one transaction, skipping rows already stored.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - Number of rows actually inserted
+    /// * `Err(sqlx::Error)` - Database error (nothing is stored)
+    pub async fn insert_many(
+        pool: &DbPool,
+        user_id: i64,
+        trades: &[ImportedTradeForCreate],
+    ) -> Result<usize, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let mut inserted = 0;
+
+        for trade in trades {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO imported_trades
+                    (user_id, traded_at, base, quote, side, amount, price, fee, source, dedupe_key)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 'import', ?9)
+                ON CONFLICT(user_id, dedupe_key) DO NOTHING
+                "#
+            )
+            .bind(user_id)
+            .bind(trade.traded_at)
+            .bind(&trade.base)
+            .bind(&trade.quote)
+            .bind(&trade.side)
+            .bind(trade.amount)
+            .bind(trade.price)
+            .bind(trade.fee)
+            .bind(&trade.dedupe_key)
+            .execute(&mut *tx)
+            .await?;
+            inserted += result.rows_affected() as usize;
+        }
+
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    /// Dedupe keys of every trade the user has imported.
+    pub async fn dedupe_keys(pool: &DbPool, user_id: i64) -> Result<HashSet<String>, sqlx::Error> {
+        let keys: Vec<String> = sqlx::query_scalar("SELECT dedupe_key FROM imported_trades WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(keys.into_iter().collect())
+    }
+
+    /// All imported trades of a user, oldest first.
+    pub async fn find_by_user(pool: &DbPool, user_id: i64) -> Result<Vec<ImportedTrade>, sqlx::Error> {
+        query_as::<_, ImportedTrade>(
+            "SELECT * FROM imported_trades WHERE user_id = ? ORDER BY traded_at, id"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS imported_trades (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                traded_at DATETIME NOT NULL,
+                base TEXT NOT NULL,
+                quote TEXT NOT NULL,
+                side TEXT NOT NULL CHECK(side IN ('buy', 'sell')),
+                amount REAL NOT NULL,
+                price REAL NOT NULL,
+                fee REAL NOT NULL DEFAULT 0,
+                source TEXT NOT NULL DEFAULT 'import',
+                dedupe_key TEXT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(user_id, dedupe_key)
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create imported_trades table");
+
+        pool
+    }
+
+    fn trade(day: u32, side: &str, key: &str) -> ImportedTradeForCreate {
+        ImportedTradeForCreate {
+            traded_at: Utc.with_ymd_and_hms(2024, 3, day, 12, 0, 0).unwrap(),
+            base: "SOL".to_string(),
+            quote: "USDC".to_string(),
+            side: side.to_string(),
+            amount: 2.0,
+            price: 100.0,
+            fee: 0.1,
+            dedupe_key: key.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_many_skips_duplicates() {
+        let pool = setup_test_db().await;
+        let batch = vec![trade(2, "sell", "b"), trade(1, "buy", "a")];
+
+        assert_eq!(ImportedTradeRepository::insert_many(&pool, 1, &batch).await.unwrap(), 2);
+        assert_eq!(ImportedTradeRepository::insert_many(&pool, 1, &batch).await.unwrap(), 0);
+        // Keys are unique per user only
+        assert_eq!(ImportedTradeRepository::insert_many(&pool, 2, &batch).await.unwrap(), 2);
+
+        let trades = ImportedTradeRepository::find_by_user(&pool, 1).await.unwrap();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].side, "buy");
+        assert_eq!(trades[0].source, "import");
+
+        let keys = ImportedTradeRepository::dedupe_keys(&pool, 1).await.unwrap();
+        assert!(keys.contains("a") && keys.contains("b"));
+    }
+
+    #[tokio::test]
+    async fn test_insert_many_is_atomic() {
+        let pool = setup_test_db().await;
+        let batch = vec![trade(1, "buy", "a"), trade(2, "hold", "b")];
+
+        assert!(ImportedTradeRepository::insert_many(&pool, 1, &batch).await.is_err());
+        assert!(ImportedTradeRepository::find_by_user(&pool, 1).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+//! # Maintenance Repository
+//!
+//! Database-wide queries used by operators rather than request handlers:
+//! row counts per table and compaction.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::maintenance_repository::MaintenanceRepository;
+//! use lib_core::create_pool;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! for (table, rows) in MaintenanceRepository::table_counts(&pool).await? {
+//!     println!("{table}: {rows}");
+//! }
+//! MaintenanceRepository::vacuum(&pool).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::DbPool;
+
+/// Tables reported by [`MaintenanceRepository::table_counts`], in migration order.
+pub const COUNTED_TABLES: &[&str] = &[
+    "users",
+    "friendships",
+    "direct_messages",
+    "swaps",
+    "program_versions",
+    "streamed_symbols",
+    "imported_trades",
+    "revoked_tokens",
+    "password_reset_tokens",
+    "revoked_sessions",
+    "share_links",
+    "webhooks",
+    "webhook_deliveries",
+    "token_unlocks",
+    "wallet_transfers",
+    "usage_counts",
+];
+
+/// Maintenance repository for database-wide operations.
+pub struct MaintenanceRepository;
+
+impl MaintenanceRepository {
+    /// Count the rows of every table in [`COUNTED_TABLES`].
+    pub async fn table_counts(pool: &DbPool) -> Result<Vec<(&'static str, i64)>, sqlx::Error> {
+        let mut counts = Vec::with_capacity(COUNTED_TABLES.len());
+        for &table in COUNTED_TABLES {
+            // Table names come from the constant above, never from input
+            let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+                .fetch_one(pool)
+                .await?;
+            counts.push((table, count));
+        }
+        Ok(counts)
+    }
+
+    /// Size of the database file in bytes.
+    pub async fn database_size(pool: &DbPool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()")
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Rebuild the database file to return the space left by deleted rows.
+    pub async fn vacuum(pool: &DbPool) -> Result<(), sqlx::Error> {
+        sqlx::query("VACUUM").execute(pool).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_table_counts_and_vacuum() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::migrate!("../../../migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        sqlx::query("INSERT INTO users (username, email, password_hash) VALUES ('alice', 'a@example.com', 'x')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let counts = MaintenanceRepository::table_counts(&pool).await.unwrap();
+        assert_eq!(counts.len(), COUNTED_TABLES.len());
+        assert_eq!(counts[0], ("users", 1));
+        assert!(counts[1..].iter().all(|&(_, rows)| rows == 0));
+
+        MaintenanceRepository::vacuum(&pool).await.unwrap();
+        assert!(MaintenanceRepository::database_size(&pool).await.unwrap() > 0);
+    }
+}
@@ -6,11 +6,37 @@
 pub mod models;
 pub mod user_repository;
 pub mod swap_repository;
+pub mod position_repository;
+pub mod program_version_repository;
+pub mod streamed_symbol_repository;
+pub mod token_unlock_repository;
+pub mod imported_trade_repository;
+pub mod revoked_token_repository;
+pub mod password_reset_repository;
+pub mod share_link_repository;
+pub mod webhook_repository;
+pub mod maintenance_repository;
+pub mod wallet_transfer_repository;
+pub mod usage_count_repository;
+pub mod api_key_repository;
 pub mod users;
 // endregion: --- Modules
 
 // region: --- Re-exports
 pub use user_repository::UserRepository;
+pub use position_repository::PositionRepository;
+pub use program_version_repository::ProgramVersionRepository;
+pub use streamed_symbol_repository::StreamedSymbolRepository;
+pub use token_unlock_repository::TokenUnlockRepository;
+pub use imported_trade_repository::ImportedTradeRepository;
+pub use revoked_token_repository::RevokedTokenRepository;
+pub use password_reset_repository::PasswordResetRepository;
+pub use share_link_repository::ShareLinkRepository;
+pub use webhook_repository::WebhookRepository;
+pub use maintenance_repository::MaintenanceRepository;
+pub use wallet_transfer_repository::WalletTransferRepository;
+pub use usage_count_repository::UsageCountRepository;
+pub use api_key_repository::ApiKeyRepository;
 // endregion: --- Re-exports
 
 // region: --- Types and Functions
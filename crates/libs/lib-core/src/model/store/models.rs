@@ -153,4 +153,205 @@ pub struct Swap {
     pub error_message: Option<String>,
     pub created_at: DateTime<Utc>,
     pub confirmed_at: Option<DateTime<Utc>>,
+    /// USD value of the swap when it was submitted, if a price was known
+    pub value_usd: Option<f64>,
+    /// Network fee of the transaction, if it could be estimated at submission
+    pub network_fee_lamports: Option<i64>,
+}
+
+/// Holding of one token acquired through swaps.
+///
+/// `quantity` is in base units; `cost_basis_usd` is the weighted-average
+/// cost of that quantity. See `position_repository` for the fill math.
+#[derive(Debug, Clone, PartialEq, FromRow, Serialize, Deserialize)]
+pub struct Position {
+    pub id: i64,
+    pub user_id: i64,
+    pub mint: String,
+    pub quantity: i64,
+    pub cost_basis_usd: f64,
+    pub realized_pnl_usd: f64,
+    /// A sell exceeded the tracked quantity or a fill had no USD value, so
+    /// the cost basis and PnL don't cover everything
+    pub incomplete: bool,
+    pub opened_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Set by `close_position`; a later buy reopens the position
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+/// Scheduled release of locked tokens.
+///
+/// `source` is `"import"` for rows from the unlocks dataset and `"admin"`
+/// once an admin created, edited or removed the row; the importer leaves
+/// admin rows alone.
+#[derive(Debug, Clone, PartialEq, FromRow, Serialize, Deserialize)]
+pub struct TokenUnlock {
+    pub id: i64,
+    pub mint: String,
+    pub symbol: String,
+    pub unlock_at: DateTime<Utc>,
+    /// Tokens released, in whole tokens
+    pub amount: f64,
+    /// Circulating supply the dataset reported, in whole tokens
+    pub circulating_supply: Option<f64>,
+    /// Allocation bucket, e.g. "Team"; empty when unknown
+    pub allocation: String,
+    pub source: String,
+    /// Username of the admin who last changed the row
+    pub updated_by: Option<String>,
+    /// Removed by an admin; kept so the importer doesn't re-add it
+    pub removed: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data structure for creating or replacing a token unlock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenUnlockForCreate {
+    pub mint: String,
+    pub symbol: String,
+    pub unlock_at: DateTime<Utc>,
+    pub amount: f64,
+    pub circulating_supply: Option<f64>,
+    pub allocation: String,
+}
+
+/// Observed deployment of an upgradeable program.
+///
+/// A new row is recorded whenever the deployed slot, program data hash, or
+/// upgrade authority differs from the previous observation.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ProgramVersion {
+    pub id: i64,
+    pub program_id: String,
+    pub programdata_address: String,
+    pub deployed_slot: i64,
+    pub data_hash: String,
+    pub upgrade_authority: Option<String>,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Symbol in the backend's price stream universe.
+///
+/// Seeded from `Config::streamed_symbols` on first run, then managed through
+/// the admin endpoints.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct StreamedSymbol {
+    pub id: i64,
+    pub symbol: String,
+    pub mint: String,
+    /// Whether the symbol must have a Pyth feed (validated when added)
+    pub require_pyth: bool,
+    /// Username of the admin who added it (`None` for seeded symbols)
+    pub added_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Trade imported from another platform.
+///
+/// Stored apart from [`Swap`] so it is never reconciled against the chain;
+/// `source` is always `"import"`.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ImportedTrade {
+    pub id: i64,
+    pub user_id: i64,
+    pub traded_at: DateTime<Utc>,
+    pub base: String,
+    pub quote: String,
+    /// `"buy"` or `"sell"`
+    pub side: String,
+    pub amount: f64,
+    pub price: f64,
+    pub fee: f64,
+    pub source: String,
+    pub dedupe_key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Data structure for storing an imported trade.
+#[derive(Debug, Clone)]
+pub struct ImportedTradeForCreate {
+    pub traded_at: DateTime<Utc>,
+    pub base: String,
+    pub quote: String,
+    pub side: String,
+    pub amount: f64,
+    pub price: f64,
+    pub fee: f64,
+    pub dedupe_key: String,
+}
+
+/// Public share link with its JSON snapshot.
+///
+/// `kind` is `"chart"` or `"portfolio"`; `payload` deserializes to
+/// `shared::dto::share::SharePayload`.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ShareLink {
+    pub slug: String,
+    pub user_id: i64,
+    pub kind: String,
+    pub payload: String,
+    /// `None` for links that never expire
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outgoing webhook registered by a user.
+///
+/// `events` holds comma separated `shared::dto::webhooks::WebhookEventType`
+/// wire names.
+#[derive(Debug, Clone, FromRow)]
+pub struct Webhook {
+    pub id: i64,
+    pub user_id: i64,
+    pub url: String,
+    /// HMAC key for the delivery signatures
+    pub secret: String,
+    pub events: String,
+    /// Only set for webhooks subscribed to `balance_threshold`
+    pub balance_threshold_lamports: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outcome of delivering one event to one webhook.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_id: String,
+    pub event: String,
+    pub delivered: bool,
+    pub attempts: i64,
+    pub response_status: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// SOL balance change of a user's wallet that no swap accounts for.
+///
+/// `lamports` is positive for a deposit and negative for a withdrawal.
+#[derive(Debug, Clone, PartialEq, FromRow, Serialize, Deserialize)]
+pub struct WalletTransfer {
+    pub id: i64,
+    pub user_id: i64,
+    pub wallet: String,
+    pub lamports: i64,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// API key of a user. Only a hash of the key is kept, and never read back.
+///
+/// `scopes` holds comma separated `shared::dto::api_keys::ApiKeyScope` wire
+/// names.
+#[derive(Debug, Clone, PartialEq, FromRow, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: i64,
+    pub user_id: i64,
+    /// First characters of the key, to tell keys apart
+    pub prefix: String,
+    pub label: String,
+    pub scopes: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
 }
@@ -0,0 +1,243 @@
+//! # Password Reset Repository
+//!
+//! Provides database access layer for one-time password reset tokens.
+//!
+//! Only a hash of each token is stored. A token is spent by marking it used
+//! in the same transaction that replaces the password, so two concurrent
+//! confirmations can't both succeed.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::password_reset_repository::PasswordResetRepository;
+//! use lib_core::create_pool;
+//!
+//! # async fn example(token_hash: &str, new_hash: &str) -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! match PasswordResetRepository::reset_password(&pool, token_hash, new_hash, chrono::Utc::now()).await? {
+//!     Some(user_id) => println!("password of user {} changed", user_id),
+//!     None => println!("token unknown, used or expired"),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::DbPool;
+use chrono::{DateTime, Utc};
+
+/// Password reset token repository for database operations.
+pub struct PasswordResetRepository;
+
+impl PasswordResetRepository {
+    /// Store a reset token hash for `user_id`, valid until `expires_at`.
+    pub async fn create(
+        pool: &DbPool,
+        token_hash: &str,
+        user_id: i64,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO password_reset_tokens (token_hash, user_id, expires_at) VALUES (?1, ?2, ?3)"
+        )
+        .bind(token_hash)
+        .bind(user_id)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Expiry of the newest unused token of `user_id` that is still valid at `now`.
+    pub async fn latest_expiry(
+        pool: &DbPool,
+        user_id: i64,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT expires_at FROM password_reset_tokens
+            WHERE user_id = ?1 AND used_at IS NULL AND expires_at > ?2
+            ORDER BY expires_at DESC LIMIT 1
+            "#
+        )
+        .bind(user_id)
+        .bind(now)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Spend a token and set its user's password hash.
+    ///
+    /// The user's other unused tokens are deleted as well.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(i64))` - Id of the user whose password was changed
+    /// * `Ok(None)` - Token unknown, already used, or expired at `now` (nothing changed)
+    /// * `Err(sqlx::Error)` - Database error (nothing changed)
+    pub async fn reset_password(
+        pool: &DbPool,
+        token_hash: &str,
+        password_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let user_id: Option<i64> = sqlx::query_scalar(
+            r#"
+            UPDATE password_reset_tokens SET used_at = ?1
+            WHERE token_hash = ?2 AND used_at IS NULL AND expires_at > ?1
+            RETURNING user_id
+            "#
+        )
+        .bind(now)
+        .bind(token_hash)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(user_id) = user_id else {
+            return Ok(None);
+        };
+
+        sqlx::query("UPDATE users SET password_hash = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(password_hash)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM password_reset_tokens WHERE user_id = ? AND used_at IS NULL")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(Some(user_id))
+    }
+
+    /// Delete tokens that have expired by `now`, used or not.
+    ///
+    /// # Returns
+    ///
+    /// Number of rows deleted.
+    pub async fn delete_expired(pool: &DbPool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM password_reset_tokens WHERE expires_at <= ?")
+            .bind(now)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Count the tokens [`Self::delete_expired`] would delete at `now`.
+    pub async fn count_expired(pool: &DbPool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM password_reset_tokens WHERE expires_at <= ?")
+            .bind(now)
+            .fetch_one(pool)
+            .await?;
+        Ok(count as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE users (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                password_hash TEXT NOT NULL,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create users table");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS password_reset_tokens (
+                token_hash TEXT PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                expires_at DATETIME NOT NULL,
+                used_at DATETIME,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create password_reset_tokens table");
+
+        sqlx::query("INSERT INTO users (id, password_hash) VALUES (1, 'old')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    async fn password_hash(pool: &DbPool) -> String {
+        sqlx::query_scalar("SELECT password_hash FROM users WHERE id = 1")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_token_is_single_use() {
+        let pool = setup_test_db().await;
+        let now = Utc::now();
+        PasswordResetRepository::create(&pool, "a", 1, now + Duration::minutes(30)).await.unwrap();
+        PasswordResetRepository::create(&pool, "b", 1, now + Duration::minutes(30)).await.unwrap();
+
+        assert_eq!(PasswordResetRepository::reset_password(&pool, "a", "new", now).await.unwrap(), Some(1));
+        assert_eq!(password_hash(&pool).await, "new");
+
+        assert_eq!(PasswordResetRepository::reset_password(&pool, "a", "again", now).await.unwrap(), None);
+        // Other outstanding tokens of the user are gone too
+        assert_eq!(PasswordResetRepository::reset_password(&pool, "b", "again", now).await.unwrap(), None);
+        assert_eq!(password_hash(&pool).await, "new");
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_is_rejected() {
+        let pool = setup_test_db().await;
+        let now = Utc::now();
+        PasswordResetRepository::create(&pool, "a", 1, now + Duration::minutes(30)).await.unwrap();
+
+        let later = now + Duration::minutes(30);
+        assert_eq!(PasswordResetRepository::reset_password(&pool, "a", "new", later).await.unwrap(), None);
+        assert_eq!(password_hash(&pool).await, "old");
+
+        assert_eq!(PasswordResetRepository::delete_expired(&pool, later).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_latest_expiry_ignores_spent_and_expired_tokens() {
+        let pool = setup_test_db().await;
+        let now = Utc::now();
+        assert_eq!(PasswordResetRepository::latest_expiry(&pool, 1, now).await.unwrap(), None);
+
+        let first = now + Duration::minutes(10);
+        let second = now + Duration::minutes(30);
+        PasswordResetRepository::create(&pool, "a", 1, first).await.unwrap();
+        PasswordResetRepository::create(&pool, "b", 1, second).await.unwrap();
+        assert_eq!(PasswordResetRepository::latest_expiry(&pool, 1, now).await.unwrap(), Some(second));
+        assert_eq!(PasswordResetRepository::latest_expiry(&pool, 2, now).await.unwrap(), None);
+        assert_eq!(PasswordResetRepository::latest_expiry(&pool, 1, second).await.unwrap(), None);
+
+        PasswordResetRepository::reset_password(&pool, "a", "new", now).await.unwrap();
+        assert_eq!(PasswordResetRepository::latest_expiry(&pool, 1, now).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,448 @@
+//! # Position Repository
+//!
+//! Provides database access layer for positions: what a user holds of each
+//! token through the swaps made in the terminal.
+//!
+//! A confirmed swap is two fills, a sell of the input token and a buy of the
+//! output token, both priced at the swap's USD value. Buys add to the
+//! quantity and the cost basis; sells remove quantity at the weighted-average
+//! cost and realize the difference to their proceeds.
+//!
+//! Tokens can also arrive outside the terminal (transfers, airdrops), so a
+//! sell may exceed the tracked quantity. Only the tracked part realizes PnL;
+//! the rest is clamped and the position flagged `incomplete`.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::position_repository::{Fill, PositionRepository};
+//! use lib_core::create_pool;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! let mut conn = pool.acquire().await?;
+//! let (position, _) = PositionRepository::apply_fill(&mut conn, 1, "So11...", &Fill::buy(2_000_000_000, Some(290.0))).await?;
+//! println!("{} base units at ${:.2}", position.quantity, position.cost_basis_usd);
+//! # Ok(())
+//! # }
+//! ```
+
+use super::models::{Position, Swap};
+use super::DbPool;
+use chrono::Utc;
+use sqlx::{query_as, SqliteConnection};
+
+/// Side of a [`Fill`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillSide {
+    Buy,
+    Sell,
+}
+
+/// One change to a position
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub side: FillSide,
+    /// Base units bought or sold
+    pub quantity: i64,
+    /// USD paid (buy) or received (sell) for the whole quantity, if known
+    pub value_usd: Option<f64>,
+}
+
+impl Fill {
+    pub fn buy(quantity: i64, value_usd: Option<f64>) -> Self {
+        Self { side: FillSide::Buy, quantity, value_usd }
+    }
+
+    pub fn sell(quantity: i64, value_usd: Option<f64>) -> Self {
+        Self { side: FillSide::Sell, quantity, value_usd }
+    }
+}
+
+/// What applying a [`Fill`] did
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FillOutcome {
+    /// PnL realized by a sell
+    pub realized_pnl_usd: f64,
+    /// Base units of a sell beyond the tracked quantity, ignored
+    pub clamped: i64,
+}
+
+impl Position {
+    /// Empty position of `mint`, as a first fill finds it
+    pub fn empty(user_id: i64, mint: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: 0,
+            user_id,
+            mint: mint.to_string(),
+            quantity: 0,
+            cost_basis_usd: 0.0,
+            realized_pnl_usd: 0.0,
+            incomplete: false,
+            opened_at: now,
+            updated_at: now,
+            closed_at: None,
+        }
+    }
+
+    /// Apply a fill at weighted-average cost.
+    ///
+    /// A buy adds its quantity and value to the cost basis. A sell removes
+    /// the average cost of the quantity sold and realizes the proceeds minus
+    /// that cost. A sell beyond the tracked quantity only counts up to it,
+    /// with the matching share of the proceeds. Missing or invalid values and
+    /// clamped sells flag the position `incomplete`; fills of no quantity
+    /// change nothing.
+    pub fn apply(&mut self, fill: &Fill) -> FillOutcome {
+        let quantity = fill.quantity;
+        if quantity <= 0 {
+            return FillOutcome::default();
+        }
+        let value = fill.value_usd.filter(|v| v.is_finite() && *v >= 0.0);
+        if value.is_none() {
+            self.incomplete = true;
+        }
+
+        match fill.side {
+            FillSide::Buy => {
+                self.quantity = self.quantity.saturating_add(quantity);
+                self.cost_basis_usd += value.unwrap_or(0.0);
+                FillOutcome::default()
+            }
+            FillSide::Sell => {
+                let sold = quantity.min(self.quantity);
+                let clamped = quantity - sold;
+                if clamped > 0 {
+                    self.incomplete = true;
+                }
+                if sold == 0 {
+                    return FillOutcome { realized_pnl_usd: 0.0, clamped };
+                }
+
+                let cost = self.cost_basis_usd * (sold as f64 / self.quantity as f64);
+                let realized = value.map_or(0.0, |value| value * (sold as f64 / quantity as f64) - cost);
+                self.quantity -= sold;
+                // Nothing left means no cost left, whatever rounding says
+                self.cost_basis_usd = if self.quantity == 0 { 0.0 } else { self.cost_basis_usd - cost };
+                self.realized_pnl_usd += realized;
+                FillOutcome { realized_pnl_usd: realized, clamped }
+            }
+        }
+    }
+}
+
+/// Position repository for database operations.
+pub struct PositionRepository;
+
+impl PositionRepository {
+    /// All positions of a user, open ones first, most recently changed first.
+    ///
+    /// Closed and sold-out positions are included for their realized PnL.
+    pub async fn get_for_user(pool: &DbPool, user_id: i64) -> Result<Vec<Position>, sqlx::Error> {
+        query_as::<_, Position>(
+            "SELECT * FROM positions WHERE user_id = ? ORDER BY closed_at IS NOT NULL, updated_at DESC, id DESC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Apply a fill to the user's position in `mint`, creating it if needed.
+    ///
+    /// Takes a connection so it can run inside the caller's transaction. A
+    /// buy reopens a closed position.
+    pub async fn apply_fill(
+        conn: &mut SqliteConnection,
+        user_id: i64,
+        mint: &str,
+        fill: &Fill,
+    ) -> Result<(Position, FillOutcome), sqlx::Error> {
+        let mut position = query_as::<_, Position>("SELECT * FROM positions WHERE user_id = ? AND mint = ?")
+            .bind(user_id)
+            .bind(mint)
+            .fetch_optional(&mut *conn)
+            .await?
+            .unwrap_or_else(|| Position::empty(user_id, mint));
+
+        let outcome = position.apply(fill);
+        let now = Utc::now();
+        if fill.side == FillSide::Buy && position.closed_at.is_some() {
+            position.closed_at = None;
+            position.opened_at = now;
+        }
+
+        let position = query_as::<_, Position>(
+            r#"
+            INSERT INTO positions
+                (user_id, mint, quantity, cost_basis_usd, realized_pnl_usd, incomplete, opened_at, updated_at, closed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            ON CONFLICT(user_id, mint) DO UPDATE SET
+                quantity = excluded.quantity,
+                cost_basis_usd = excluded.cost_basis_usd,
+                realized_pnl_usd = excluded.realized_pnl_usd,
+                incomplete = excluded.incomplete,
+                opened_at = excluded.opened_at,
+                updated_at = excluded.updated_at,
+                closed_at = excluded.closed_at
+            RETURNING *
+            "#
+        )
+        .bind(user_id)
+        .bind(mint)
+        .bind(position.quantity)
+        .bind(position.cost_basis_usd)
+        .bind(position.realized_pnl_usd)
+        .bind(position.incomplete)
+        .bind(position.opened_at)
+        .bind(now)
+        .bind(position.closed_at)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok((position, outcome))
+    }
+
+    /// Apply both legs of a confirmed swap: sell the input, buy the output.
+    pub async fn apply_swap(conn: &mut SqliteConnection, swap: &Swap) -> Result<(), sqlx::Error> {
+        let sell = Fill::sell(swap.input_amount, swap.value_usd);
+        let (_, outcome) = Self::apply_fill(conn, swap.user_id, &swap.input_mint, &sell).await?;
+        if outcome.clamped > 0 {
+            tracing::debug!(
+                signature = %swap.signature,
+                mint = %swap.input_mint,
+                clamped = outcome.clamped,
+                "Swap sold more than the tracked position"
+            );
+        }
+
+        let buy = Fill::buy(swap.output_amount, swap.value_usd);
+        Self::apply_fill(conn, swap.user_id, &swap.output_mint, &buy).await?;
+        Ok(())
+    }
+
+    /// Stop tracking what is left of a position, e.g. after the tokens were
+    /// sent out of the wallet.
+    ///
+    /// The remaining cost basis is dropped without realizing anything; the
+    /// realized PnL so far is kept.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - The position was closed
+    /// * `Ok(false)` - No open position in this mint
+    pub async fn close_position(pool: &DbPool, user_id: i64, mint: &str) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            r#"
+            UPDATE positions
+            SET quantity = 0, cost_basis_usd = 0, closed_at = ?1, updated_at = ?1
+            WHERE user_id = ?2 AND mint = ?3 AND closed_at IS NULL
+            "#
+        )
+        .bind(now)
+        .bind(user_id)
+        .bind(mint)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::migrate!("../../../migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+        sqlx::query("INSERT INTO users (username, email, password_hash) VALUES ('alice', 'a@example.com', 'x')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    // ==================== Fill Math Tests ====================
+
+    #[test]
+    fn test_buys_average_cost() {
+        let mut position = Position::empty(1, "SOL");
+        position.apply(&Fill::buy(2, Some(200.0)));
+        position.apply(&Fill::buy(3, Some(450.0)));
+
+        assert_eq!(position.quantity, 5);
+        assert_close(position.cost_basis_usd, 650.0);
+        assert_close(position.realized_pnl_usd, 0.0);
+        assert!(!position.incomplete);
+    }
+
+    #[test]
+    fn test_sell_realizes_against_average_cost() {
+        let mut position = Position::empty(1, "SOL");
+        position.apply(&Fill::buy(4, Some(400.0)));
+        position.apply(&Fill::buy(4, Some(800.0)));
+
+        // Average cost 150: selling 2 for 400 realizes 100
+        let outcome = position.apply(&Fill::sell(2, Some(400.0)));
+        assert_close(outcome.realized_pnl_usd, 100.0);
+        assert_eq!(outcome.clamped, 0);
+        assert_eq!(position.quantity, 6);
+        assert_close(position.cost_basis_usd, 900.0);
+
+        // A loss, and the average cost doesn't move on sells
+        let outcome = position.apply(&Fill::sell(3, Some(300.0)));
+        assert_close(outcome.realized_pnl_usd, -150.0);
+        assert_close(position.cost_basis_usd / position.quantity as f64, 150.0);
+        assert_close(position.realized_pnl_usd, -50.0);
+        assert!(!position.incomplete);
+    }
+
+    #[test]
+    fn test_selling_everything_clears_cost_basis() {
+        let mut position = Position::empty(1, "SOL");
+        // Thirds don't add up exactly in floating point
+        for _ in 0..3 {
+            position.apply(&Fill::buy(1, Some(0.1)));
+        }
+        position.apply(&Fill::sell(1, Some(0.2)));
+        position.apply(&Fill::sell(2, Some(0.2)));
+
+        assert_eq!(position.quantity, 0);
+        assert_eq!(position.cost_basis_usd, 0.0);
+        assert_close(position.realized_pnl_usd, 0.1);
+    }
+
+    #[test]
+    fn test_oversell_is_clamped_and_flagged() {
+        let mut position = Position::empty(1, "SOL");
+        position.apply(&Fill::buy(2, Some(100.0)));
+
+        // 5 sold for 500, only 2 of them tracked: proceeds 200 against cost 100
+        let outcome = position.apply(&Fill::sell(5, Some(500.0)));
+        assert_eq!(outcome.clamped, 3);
+        assert_close(outcome.realized_pnl_usd, 100.0);
+        assert_eq!(position.quantity, 0);
+        assert_eq!(position.cost_basis_usd, 0.0);
+        assert!(position.incomplete);
+    }
+
+    #[test]
+    fn test_sell_without_position() {
+        let mut position = Position::empty(1, "SOL");
+        let outcome = position.apply(&Fill::sell(7, Some(70.0)));
+
+        assert_eq!(outcome, FillOutcome { realized_pnl_usd: 0.0, clamped: 7 });
+        assert_eq!(position.quantity, 0);
+        assert!(position.incomplete);
+    }
+
+    #[test]
+    fn test_missing_or_invalid_values_are_flagged() {
+        let mut position = Position::empty(1, "BONK");
+        position.apply(&Fill::buy(10, Some(100.0)));
+        position.apply(&Fill::buy(10, None));
+        assert_eq!(position.quantity, 20);
+        assert_close(position.cost_basis_usd, 100.0);
+        assert!(position.incomplete);
+
+        // The cost still leaves with the units, nothing is realized
+        let mut position = Position::empty(1, "BONK");
+        position.apply(&Fill::buy(10, Some(100.0)));
+        let outcome = position.apply(&Fill::sell(5, Some(f64::NAN)));
+        assert_close(outcome.realized_pnl_usd, 0.0);
+        assert_close(position.cost_basis_usd, 50.0);
+        assert!(position.incomplete);
+
+        let mut position = Position::empty(1, "BONK");
+        position.apply(&Fill::buy(10, Some(-5.0)));
+        assert_eq!(position.cost_basis_usd, 0.0);
+        assert!(position.incomplete);
+    }
+
+    #[test]
+    fn test_empty_fills_are_ignored() {
+        let mut position = Position::empty(1, "SOL");
+        position.apply(&Fill::buy(3, Some(30.0)));
+        assert_eq!(position.apply(&Fill::sell(-2, Some(10.0))), FillOutcome::default());
+        assert_eq!(position.apply(&Fill::buy(-2, Some(10.0))), FillOutcome::default());
+        position.apply(&Fill::buy(0, None));
+
+        assert_eq!(position.quantity, 3);
+        assert_close(position.cost_basis_usd, 30.0);
+        assert!(!position.incomplete);
+    }
+
+    #[test]
+    fn test_large_quantities_keep_precision() {
+        // 1B BONK (5 decimals) bought twice
+        let mut position = Position::empty(1, "BONK");
+        position.apply(&Fill::buy(100_000_000_000_000, Some(20_000.0)));
+        position.apply(&Fill::buy(100_000_000_000_000, Some(30_000.0)));
+        let outcome = position.apply(&Fill::sell(50_000_000_000_000, Some(15_000.0)));
+
+        assert_close(outcome.realized_pnl_usd, 2_500.0);
+        assert_close(position.cost_basis_usd, 37_500.0);
+        assert_eq!(position.apply(&Fill::buy(i64::MAX, Some(1.0))).clamped, 0);
+        assert_eq!(position.quantity, i64::MAX);
+    }
+
+    // ==================== Repository Tests ====================
+
+    async fn fill(pool: &DbPool, mint: &str, fill: Fill) -> (Position, FillOutcome) {
+        let mut conn = pool.acquire().await.unwrap();
+        PositionRepository::apply_fill(&mut conn, 1, mint, &fill).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_apply_fill_persists_position() {
+        let pool = setup_test_db().await;
+
+        fill(&pool, "SOL", Fill::buy(4, Some(400.0))).await;
+        let (position, outcome) = fill(&pool, "SOL", Fill::sell(1, Some(150.0))).await;
+
+        assert_close(outcome.realized_pnl_usd, 50.0);
+        assert_eq!(PositionRepository::get_for_user(&pool, 1).await.unwrap(), vec![position.clone()]);
+        assert_eq!(position.quantity, 3);
+        assert_close(position.cost_basis_usd, 300.0);
+        assert!(PositionRepository::get_for_user(&pool, 2).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_close_and_reopen_position() {
+        let pool = setup_test_db().await;
+        fill(&pool, "SOL", Fill::buy(2, Some(100.0))).await;
+        fill(&pool, "SOL", Fill::sell(1, Some(80.0))).await;
+        fill(&pool, "USDC", Fill::buy(5, Some(5.0))).await;
+
+        assert!(PositionRepository::close_position(&pool, 1, "SOL").await.unwrap());
+        assert!(!PositionRepository::close_position(&pool, 1, "SOL").await.unwrap());
+        assert!(!PositionRepository::close_position(&pool, 1, "BONK").await.unwrap());
+
+        let positions = PositionRepository::get_for_user(&pool, 1).await.unwrap();
+        assert_eq!(positions[0].mint, "USDC");
+        let sol = &positions[1];
+        assert!(sol.closed_at.is_some());
+        assert_eq!(sol.quantity, 0);
+        assert_close(sol.realized_pnl_usd, 30.0);
+
+        // Buying again reopens it with a fresh cost basis and the old realized PnL
+        let (sol, _) = fill(&pool, "SOL", Fill::buy(1, Some(60.0))).await;
+        assert!(sol.closed_at.is_none());
+        assert_close(sol.cost_basis_usd, 60.0);
+        assert_close(sol.realized_pnl_usd, 30.0);
+    }
+}
@@ -0,0 +1,187 @@
+//! # Program Version Repository
+//!
+//! Provides database access layer for observed program deployments.
+//!
+//! The program monitor records one row per distinct deployment of a watched
+//! upgradeable program (deployed slot, program data hash, upgrade authority),
+//! giving a history of upgrades and authority transfers.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::program_version_repository::ProgramVersionRepository;
+//! use lib_core::create_pool;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! let latest = ProgramVersionRepository::find_latest(&pool, "program_id").await?;
+//! if let Some(version) = latest {
+//!     println!("Deployed at slot {}", version.deployed_slot);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::models::ProgramVersion;
+use super::DbPool;
+use sqlx::query_as;
+use chrono::Utc;
+
+/// Program version repository for database operations.
+///
+/// Provides methods for recording and querying program deployment history.
+pub struct ProgramVersionRepository;
+
+impl ProgramVersionRepository {
+    /// Record a newly observed program deployment.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `program_id` - Program address
+    /// * `programdata_address` - Address of the program's ProgramData account
+    /// * `deployed_slot` - Slot the current program data was deployed at
+    /// * `data_hash` - Hex-encoded SHA-256 of the deployed program bytes
+    /// * `upgrade_authority` - Current upgrade authority (`None` if immutable)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ProgramVersion)` - The newly recorded version
+    /// * `Err(sqlx::Error)` - Database error occurred
+    pub async fn record(
+        pool: &DbPool,
+        program_id: &str,
+        programdata_address: &str,
+        deployed_slot: i64,
+        data_hash: &str,
+        upgrade_authority: Option<&str>,
+    ) -> Result<ProgramVersion, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO program_versions (program_id, programdata_address, deployed_slot, data_hash, upgrade_authority, observed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#
+        )
+        .bind(program_id)
+        .bind(programdata_address)
+        .bind(deployed_slot)
+        .bind(data_hash)
+        .bind(upgrade_authority)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        query_as::<_, ProgramVersion>("SELECT * FROM program_versions WHERE id = ?")
+            .bind(result.last_insert_rowid())
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Find the most recent observation for a program.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ProgramVersion))` - Latest recorded version
+    /// * `Ok(None)` - Program has never been observed
+    /// * `Err(sqlx::Error)` - Database error occurred
+    pub async fn find_latest(pool: &DbPool, program_id: &str) -> Result<Option<ProgramVersion>, sqlx::Error> {
+        query_as::<_, ProgramVersion>(
+            "SELECT * FROM program_versions WHERE program_id = ? ORDER BY id DESC LIMIT 1"
+        )
+        .bind(program_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// List recorded versions for a program, newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `program_id` - Program address
+    /// * `limit` - Maximum number of versions to return
+    pub async fn find_history(
+        pool: &DbPool,
+        program_id: &str,
+        limit: usize,
+    ) -> Result<Vec<ProgramVersion>, sqlx::Error> {
+        query_as::<_, ProgramVersion>(
+            "SELECT * FROM program_versions WHERE program_id = ? ORDER BY id DESC LIMIT ?"
+        )
+        .bind(program_id)
+        .bind(limit as i64)
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS program_versions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                program_id TEXT NOT NULL,
+                programdata_address TEXT NOT NULL,
+                deployed_slot INTEGER NOT NULL,
+                data_hash TEXT NOT NULL,
+                upgrade_authority TEXT,
+                observed_at TIMESTAMP NOT NULL
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create program_versions table");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_record_and_find_latest() {
+        let pool = setup_test_db().await;
+
+        assert!(ProgramVersionRepository::find_latest(&pool, "prog").await.unwrap().is_none());
+
+        ProgramVersionRepository::record(&pool, "prog", "data", 100, "aa", Some("auth"))
+            .await
+            .unwrap();
+        let second = ProgramVersionRepository::record(&pool, "prog", "data", 200, "bb", None)
+            .await
+            .unwrap();
+
+        let latest = ProgramVersionRepository::find_latest(&pool, "prog").await.unwrap().unwrap();
+        assert_eq!(latest.id, second.id);
+        assert_eq!(latest.deployed_slot, 200);
+        assert!(latest.upgrade_authority.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_history_is_scoped_and_limited() {
+        let pool = setup_test_db().await;
+
+        for slot in 1..=5 {
+            ProgramVersionRepository::record(&pool, "prog", "data", slot, "hash", None)
+                .await
+                .unwrap();
+        }
+        ProgramVersionRepository::record(&pool, "other", "data", 9, "hash", None)
+            .await
+            .unwrap();
+
+        let history = ProgramVersionRepository::find_history(&pool, "prog", 3).await.unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].deployed_slot, 5);
+        assert!(history.iter().all(|v| v.program_id == "prog"));
+    }
+}
@@ -0,0 +1,230 @@
+//! # Revoked Token Repository
+//!
+//! Provides database access layer for JWTs revoked by logout.
+//!
+//! A revoked token only needs to be remembered until it would have expired
+//! on its own, so every row carries the token's expiry and
+//! [`RevokedTokenRepository::delete_expired`] prunes the table.
+//!
+//! Changing a password ends all of a user's sessions at once, which can't be
+//! done token by token since issued tokens aren't recorded. Instead
+//! [`RevokedTokenRepository::revoke_sessions`] stores a per-user cutoff in
+//! `revoked_sessions`, and tokens issued up to then are rejected.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::revoked_token_repository::RevokedTokenRepository;
+//! use lib_core::create_pool;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! if RevokedTokenRepository::is_revoked(&pool, "6f1c...").await? {
+//!     println!("token was logged out");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::DbPool;
+use chrono::{DateTime, Utc};
+
+/// Revoked token repository for database operations.
+pub struct RevokedTokenRepository;
+
+impl RevokedTokenRepository {
+    /// Revoke a token until `expires_at`. Revoking twice is a no-op.
+    pub async fn revoke(
+        pool: &DbPool,
+        jti: &str,
+        user_id: i64,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO revoked_tokens (jti, user_id, expires_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(jti) DO NOTHING
+            "#
+        )
+        .bind(jti)
+        .bind(user_id)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Whether the token has been revoked.
+    pub async fn is_revoked(pool: &DbPool, jti: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM revoked_tokens WHERE jti = ?)")
+            .bind(jti)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Revoke every token of `user_id` issued at or before `issued_until`,
+    /// except the token with id `kept_jti`.
+    ///
+    /// JWT issue times have whole-second precision, so a token issued later
+    /// in the same second as `issued_until` is revoked as well.
+    pub async fn revoke_sessions(
+        pool: &DbPool,
+        user_id: i64,
+        issued_until: DateTime<Utc>,
+        kept_jti: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO revoked_sessions (user_id, revoked_before, kept_jti)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(user_id) DO UPDATE SET revoked_before = ?2, kept_jti = ?3
+            "#
+        )
+        .bind(user_id)
+        .bind(issued_until)
+        .bind(kept_jti)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Whether the token `jti` of `user_id`, issued at `issued_at`, was
+    /// revoked by [`revoke_sessions`](Self::revoke_sessions).
+    pub async fn is_session_revoked(
+        pool: &DbPool,
+        user_id: i64,
+        issued_at: DateTime<Utc>,
+        jti: &str,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM revoked_sessions
+                WHERE user_id = ?1 AND revoked_before >= ?2 AND (kept_jti IS NULL OR kept_jti != ?3)
+            )
+            "#
+        )
+        .bind(user_id)
+        .bind(issued_at)
+        .bind(jti)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Delete revocations of tokens that have expired by `now`.
+    ///
+    /// # Returns
+    ///
+    /// Number of rows deleted.
+    pub async fn delete_expired(pool: &DbPool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= ?")
+            .bind(now)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Count the revocations [`Self::delete_expired`] would delete at `now`.
+    pub async fn count_expired(pool: &DbPool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM revoked_tokens WHERE expires_at <= ?")
+            .bind(now)
+            .fetch_one(pool)
+            .await?;
+        Ok(count as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS revoked_tokens (
+                jti TEXT PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                expires_at DATETIME NOT NULL,
+                revoked_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create revoked_tokens table");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS revoked_sessions (
+                user_id INTEGER PRIMARY KEY,
+                revoked_before DATETIME NOT NULL,
+                kept_jti TEXT
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create revoked_sessions table");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_revoke_and_check() {
+        let pool = setup_test_db().await;
+        let expires_at = Utc::now() + Duration::hours(1);
+
+        assert!(!RevokedTokenRepository::is_revoked(&pool, "a").await.unwrap());
+        RevokedTokenRepository::revoke(&pool, "a", 1, expires_at).await.unwrap();
+        RevokedTokenRepository::revoke(&pool, "a", 1, expires_at).await.unwrap();
+
+        assert!(RevokedTokenRepository::is_revoked(&pool, "a").await.unwrap());
+        assert!(!RevokedTokenRepository::is_revoked(&pool, "b").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_delete_expired() {
+        let pool = setup_test_db().await;
+        let now = Utc::now();
+
+        RevokedTokenRepository::revoke(&pool, "old", 1, now - Duration::minutes(1)).await.unwrap();
+        RevokedTokenRepository::revoke(&pool, "live", 1, now + Duration::hours(1)).await.unwrap();
+
+        assert_eq!(RevokedTokenRepository::delete_expired(&pool, now).await.unwrap(), 1);
+        assert!(!RevokedTokenRepository::is_revoked(&pool, "old").await.unwrap());
+        assert!(RevokedTokenRepository::is_revoked(&pool, "live").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_sessions_keeps_one_token() {
+        let pool = setup_test_db().await;
+        let now = DateTime::from_timestamp(1_700_000_000, 500_000_000).unwrap();
+        let same_second = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let earlier = now - Duration::hours(1);
+        let later = now + Duration::seconds(1);
+
+        assert!(!RevokedTokenRepository::is_session_revoked(&pool, 1, earlier, "old").await.unwrap());
+        RevokedTokenRepository::revoke_sessions(&pool, 1, now, Some("fresh")).await.unwrap();
+
+        assert!(RevokedTokenRepository::is_session_revoked(&pool, 1, earlier, "old").await.unwrap());
+        assert!(RevokedTokenRepository::is_session_revoked(&pool, 1, same_second, "other").await.unwrap());
+        assert!(!RevokedTokenRepository::is_session_revoked(&pool, 1, same_second, "fresh").await.unwrap());
+        assert!(!RevokedTokenRepository::is_session_revoked(&pool, 1, later, "new-login").await.unwrap());
+        // Other users are unaffected
+        assert!(!RevokedTokenRepository::is_session_revoked(&pool, 2, earlier, "old").await.unwrap());
+
+        // A second change moves the cutoff
+        RevokedTokenRepository::revoke_sessions(&pool, 1, later, None).await.unwrap();
+        assert!(RevokedTokenRepository::is_session_revoked(&pool, 1, same_second, "fresh").await.unwrap());
+    }
+}
@@ -0,0 +1,171 @@
+//! # Share Link Repository
+//!
+//! Provides database access layer for public share links.
+//!
+//! Expiry is enforced on read: an expired link is never returned, even before
+//! [`ShareLinkRepository::delete_expired`] has removed it.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::share_link_repository::ShareLinkRepository;
+//! use lib_core::create_pool;
+//!
+//! # async fn example(slug: &str) -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! match ShareLinkRepository::find_active(&pool, slug, chrono::Utc::now()).await? {
+//!     Some(link) => println!("{} snapshot: {}", link.kind, link.payload),
+//!     None => println!("no such link, or it expired"),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::models::ShareLink;
+use super::DbPool;
+use chrono::{DateTime, Utc};
+
+/// Share link repository for database operations.
+pub struct ShareLinkRepository;
+
+impl ShareLinkRepository {
+    /// Store a share link for `user_id`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ShareLink)` - The stored link
+    /// * `Err(sqlx::Error)` - Database error, including a slug collision
+    pub async fn create(
+        pool: &DbPool,
+        slug: &str,
+        user_id: i64,
+        kind: &str,
+        payload: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<ShareLink, sqlx::Error> {
+        sqlx::query_as::<_, ShareLink>(
+            r#"
+            INSERT INTO share_links (slug, user_id, kind, payload, expires_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            RETURNING slug, user_id, kind, payload, expires_at, created_at
+            "#
+        )
+        .bind(slug)
+        .bind(user_id)
+        .bind(kind)
+        .bind(payload)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Find a link that hasn't expired at `now`.
+    pub async fn find_active(
+        pool: &DbPool,
+        slug: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<ShareLink>, sqlx::Error> {
+        sqlx::query_as::<_, ShareLink>(
+            r#"
+            SELECT slug, user_id, kind, payload, expires_at, created_at
+            FROM share_links
+            WHERE slug = ?1 AND (expires_at IS NULL OR expires_at > ?2)
+            "#
+        )
+        .bind(slug)
+        .bind(now)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Delete links that have expired by `now`.
+    ///
+    /// # Returns
+    ///
+    /// Number of rows deleted.
+    pub async fn delete_expired(pool: &DbPool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM share_links WHERE expires_at IS NOT NULL AND expires_at <= ?")
+            .bind(now)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Count the links [`Self::delete_expired`] would delete at `now`.
+    pub async fn count_expired(pool: &DbPool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM share_links WHERE expires_at IS NOT NULL AND expires_at <= ?")
+            .bind(now)
+            .fetch_one(pool)
+            .await?;
+        Ok(count as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE share_links (
+                slug TEXT PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                expires_at DATETIME,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create share_links table");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_link_expires() {
+        let pool = setup_test_db().await;
+        let now = Utc::now();
+        let link = ShareLinkRepository::create(&pool, "abc", 1, "chart", "{}", Some(now + Duration::hours(1)))
+            .await
+            .unwrap();
+        assert_eq!(link.kind, "chart");
+
+        assert!(ShareLinkRepository::find_active(&pool, "abc", now).await.unwrap().is_some());
+        let later = now + Duration::hours(1);
+        assert!(ShareLinkRepository::find_active(&pool, "abc", later).await.unwrap().is_none());
+
+        assert_eq!(ShareLinkRepository::delete_expired(&pool, later).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_link_without_expiry_is_kept() {
+        let pool = setup_test_db().await;
+        let now = Utc::now();
+        ShareLinkRepository::create(&pool, "forever", 1, "portfolio", "{}", None).await.unwrap();
+
+        let much_later = now + Duration::days(3650);
+        assert!(ShareLinkRepository::find_active(&pool, "forever", much_later).await.unwrap().is_some());
+        assert_eq!(ShareLinkRepository::delete_expired(&pool, much_later).await.unwrap(), 0);
+        assert!(ShareLinkRepository::find_active(&pool, "missing", now).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_slug_collision_is_an_error() {
+        let pool = setup_test_db().await;
+        ShareLinkRepository::create(&pool, "abc", 1, "chart", "{}", None).await.unwrap();
+        assert!(ShareLinkRepository::create(&pool, "abc", 2, "chart", "{}", None).await.is_err());
+    }
+}
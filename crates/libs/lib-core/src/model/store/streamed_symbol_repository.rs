@@ -0,0 +1,199 @@
+//! # Streamed Symbol Repository
+//!
+//! Provides database access layer for the price stream's symbol universe.
+//!
+//! The table is seeded once from `Config::streamed_symbols`; after that the
+//! database is the source of truth and admins add or remove symbols at
+//! runtime.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::streamed_symbol_repository::StreamedSymbolRepository;
+//! use lib_core::create_pool;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! for symbol in StreamedSymbolRepository::list(&pool).await? {
+//!     println!("{} ({})", symbol.symbol, symbol.mint);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::models::StreamedSymbol;
+use super::DbPool;
+use sqlx::query_as;
+use chrono::Utc;
+
+/// Streamed symbol repository for database operations.
+pub struct StreamedSymbolRepository;
+
+impl StreamedSymbolRepository {
+    /// List all streamed symbols in the order they were added.
+    pub async fn list(pool: &DbPool) -> Result<Vec<StreamedSymbol>, sqlx::Error> {
+        query_as::<_, StreamedSymbol>("SELECT * FROM streamed_symbols ORDER BY id")
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Find a streamed symbol (case-insensitive).
+    pub async fn find(pool: &DbPool, symbol: &str) -> Result<Option<StreamedSymbol>, sqlx::Error> {
+        query_as::<_, StreamedSymbol>("SELECT * FROM streamed_symbols WHERE symbol = ?")
+            .bind(symbol.to_uppercase())
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Add a symbol. Symbols are stored uppercase.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(StreamedSymbol)` - The newly added symbol
+    /// * `Err(sqlx::Error)` - Database error (including a duplicate symbol)
+    pub async fn insert(
+        pool: &DbPool,
+        symbol: &str,
+        mint: &str,
+        require_pyth: bool,
+        added_by: Option<&str>,
+    ) -> Result<StreamedSymbol, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO streamed_symbols (symbol, mint, require_pyth, added_by, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#
+        )
+        .bind(symbol.to_uppercase())
+        .bind(mint)
+        .bind(require_pyth)
+        .bind(added_by)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        query_as::<_, StreamedSymbol>("SELECT * FROM streamed_symbols WHERE id = ?")
+            .bind(result.last_insert_rowid())
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Remove a symbol (case-insensitive).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - Symbol was removed
+    /// * `Ok(false)` - Symbol was not streamed
+    pub async fn delete(pool: &DbPool, symbol: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM streamed_symbols WHERE symbol = ?")
+            .bind(symbol.to_uppercase())
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Seed the table on first run.
+    ///
+    /// Does nothing if the table already has rows, so symbols removed by an
+    /// admin are not brought back on restart.
+    ///
+    /// # Returns
+    ///
+    /// Number of symbols inserted.
+    pub async fn seed_if_empty(pool: &DbPool, symbols: &[(String, String)]) -> Result<usize, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM streamed_symbols")
+            .fetch_one(&mut *tx)
+            .await?;
+        if count > 0 {
+            return Ok(0);
+        }
+
+        let now = Utc::now();
+        for (symbol, mint) in symbols {
+            sqlx::query(
+                "INSERT OR IGNORE INTO streamed_symbols (symbol, mint, require_pyth, created_at) VALUES (?1, ?2, 0, ?3)"
+            )
+            .bind(symbol.to_uppercase())
+            .bind(mint)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(symbols.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS streamed_symbols (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                symbol TEXT NOT NULL UNIQUE,
+                mint TEXT NOT NULL,
+                require_pyth BOOLEAN NOT NULL DEFAULT 0,
+                added_by TEXT,
+                created_at TIMESTAMP NOT NULL
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create streamed_symbols table");
+
+        pool
+    }
+
+    fn seed() -> Vec<(String, String)> {
+        vec![
+            ("SOL".to_string(), "So11111111111111111111111111111111111111112".to_string()),
+            ("usdc".to_string(), "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string()),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_seed_only_on_first_run() {
+        let pool = setup_test_db().await;
+
+        assert_eq!(StreamedSymbolRepository::seed_if_empty(&pool, &seed()).await.unwrap(), 2);
+        StreamedSymbolRepository::delete(&pool, "SOL").await.unwrap();
+
+        // A restart must not bring back the removed symbol
+        assert_eq!(StreamedSymbolRepository::seed_if_empty(&pool, &seed()).await.unwrap(), 0);
+        let symbols = StreamedSymbolRepository::list(&pool).await.unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].symbol, "USDC");
+    }
+
+    #[tokio::test]
+    async fn test_insert_find_and_delete() {
+        let pool = setup_test_db().await;
+
+        let added = StreamedSymbolRepository::insert(&pool, "jup", "JUPmint", true, Some("admin"))
+            .await
+            .unwrap();
+        assert_eq!(added.symbol, "JUP");
+        assert!(added.require_pyth);
+
+        assert!(StreamedSymbolRepository::insert(&pool, "JUP", "other", false, None).await.is_err());
+        assert!(StreamedSymbolRepository::find(&pool, "Jup").await.unwrap().is_some());
+
+        assert!(StreamedSymbolRepository::delete(&pool, "jup").await.unwrap());
+        assert!(!StreamedSymbolRepository::delete(&pool, "jup").await.unwrap());
+    }
+}
@@ -25,6 +25,7 @@
 //!     2000000,
 //!     Some(0.05),
 //!     Some(50),
+//!     Some(290.0),
 //! ).await?;
 //!
 //! // Find swap by signature
@@ -35,9 +36,35 @@
 //! ```
 
 use super::models::{Swap, SwapStatus};
+use super::position_repository::PositionRepository;
 use super::DbPool;
 use sqlx::query_as;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+
+/// Filters for [`SwapRepository::find_page`]. `None` matches everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SwapHistoryFilter {
+    /// Swaps created at or after this instant
+    pub from: Option<DateTime<Utc>>,
+    /// Swaps created before this instant
+    pub to: Option<DateTime<Utc>>,
+    pub input_mint: Option<String>,
+    pub output_mint: Option<String>,
+    pub status: Option<SwapStatus>,
+    /// Fragment of the transaction signature (case-sensitive, like base58)
+    pub signature: Option<String>,
+}
+
+/// Shared by the page and count queries of [`SwapRepository::find_page`]
+const HISTORY_FILTER_SQL: &str = r#"
+    WHERE user_id = ?1
+      AND (?2 IS NULL OR created_at >= ?2)
+      AND (?3 IS NULL OR created_at < ?3)
+      AND (?4 IS NULL OR input_mint = ?4)
+      AND (?5 IS NULL OR output_mint = ?5)
+      AND (?6 IS NULL OR status = ?6)
+      AND (?7 IS NULL OR instr(signature, ?7) > 0)
+"#;
 
 /// Swap repository for database operations.
 ///
@@ -59,6 +86,8 @@ impl SwapRepository {
     /// * `output_amount` - Output amount in smallest unit
     /// * `price_impact` - Optional price impact percentage
     /// * `slippage_bps` - Optional slippage tolerance in basis points
+    /// * `value_usd` - USD value of the swap at submission; prices both legs
+    ///   in the user's positions once the swap confirms
     ///
     /// # Returns
     ///
@@ -83,11 +112,13 @@ impl SwapRepository {
     ///     2000000,
     ///     Some(0.05),
     ///     Some(50),
+    ///     Some(290.0),
     /// ).await?;
     /// println!("Created swap with ID: {}", swap.id);
     /// # Ok(())
     /// # }
     /// ```
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         pool: &DbPool,
         user_id: i64,
@@ -98,6 +129,7 @@ impl SwapRepository {
         output_amount: i64,
         price_impact: Option<f64>,
         slippage_bps: Option<i32>,
+        value_usd: Option<f64>,
     ) -> Result<Swap, sqlx::Error> {
         let status = SwapStatus::Pending;
         let created_at = Utc::now();
@@ -105,8 +137,8 @@ impl SwapRepository {
         // Insert swap record
         sqlx::query(
             r#"
-            INSERT INTO swaps (user_id, signature, input_mint, output_mint, input_amount, output_amount, price_impact, slippage_bps, status, created_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            INSERT INTO swaps (user_id, signature, input_mint, output_mint, input_amount, output_amount, price_impact, slippage_bps, status, created_at, value_usd)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
             "#
         )
         .bind(user_id)
@@ -119,6 +151,7 @@ impl SwapRepository {
         .bind(slippage_bps)
         .bind(status.to_string())
         .bind(created_at)
+        .bind(value_usd)
         .execute(pool)
         .await?;
 
@@ -212,8 +245,194 @@ impl SwapRepository {
         }
     }
 
+    /// Find one page of a user's swaps matching `filter`, newest first.
+    ///
+    /// Filtering, ordering and paging all happen in SQL. Returns the page and
+    /// the total number of matching swaps; an offset past the end gives an
+    /// empty page with the real total.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use backend::database::{SwapRepository, create_pool};
+    /// use backend::database::swap_repository::SwapHistoryFilter;
+    ///
+    /// # async fn example() -> Result<(), sqlx::Error> {
+    /// let pool = create_pool().await?;
+    /// let filter = SwapHistoryFilter { input_mint: Some("So11...".to_string()), ..Default::default() };
+    /// let (swaps, total) = SwapRepository::find_page(&pool, 1, &filter, 20, 40).await?;
+    /// println!("Showing {} of {} swaps", swaps.len(), total);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn find_page(
+        pool: &DbPool,
+        user_id: i64,
+        filter: &SwapHistoryFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Swap>, i64), sqlx::Error> {
+        let status = filter.status.as_ref().map(|s| s.to_string());
+
+        let page_sql = format!(
+            "SELECT * FROM swaps {} ORDER BY created_at DESC, id DESC LIMIT ?8 OFFSET ?9",
+            HISTORY_FILTER_SQL
+        );
+        let swaps = query_as::<_, Swap>(&page_sql)
+            .bind(user_id)
+            .bind(filter.from)
+            .bind(filter.to)
+            .bind(filter.input_mint.as_deref())
+            .bind(filter.output_mint.as_deref())
+            .bind(status.as_deref())
+            .bind(filter.signature.as_deref())
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(pool)
+            .await?;
+
+        let count_sql = format!("SELECT COUNT(*) FROM swaps {}", HISTORY_FILTER_SQL);
+        let total: i64 = sqlx::query_scalar(&count_sql)
+            .bind(user_id)
+            .bind(filter.from)
+            .bind(filter.to)
+            .bind(filter.input_mint.as_deref())
+            .bind(filter.output_mint.as_deref())
+            .bind(status.as_deref())
+            .bind(filter.signature.as_deref())
+            .fetch_one(pool)
+            .await?;
+
+        Ok((swaps, total))
+    }
+
+    /// Find up to `limit` of a user's swaps matching `filter` that come after
+    /// `after` in history order (newest first).
+    ///
+    /// `after` is the `(created_at, id)` of the last swap already seen, so a
+    /// walk through a long history costs one indexed query per batch instead
+    /// of a growing offset, and swaps added meanwhile don't shift it.
+    pub async fn find_after(
+        pool: &DbPool,
+        user_id: i64,
+        filter: &SwapHistoryFilter,
+        after: Option<(DateTime<Utc>, i64)>,
+        limit: i64,
+    ) -> Result<Vec<Swap>, sqlx::Error> {
+        let status = filter.status.as_ref().map(|s| s.to_string());
+
+        let sql = format!(
+            "SELECT * FROM swaps {} AND (?8 IS NULL OR created_at < ?8 OR (created_at = ?8 AND id < ?9))
+             ORDER BY created_at DESC, id DESC LIMIT ?10",
+            HISTORY_FILTER_SQL
+        );
+        query_as::<_, Swap>(&sql)
+            .bind(user_id)
+            .bind(filter.from)
+            .bind(filter.to)
+            .bind(filter.input_mint.as_deref())
+            .bind(filter.output_mint.as_deref())
+            .bind(status.as_deref())
+            .bind(filter.signature.as_deref())
+            .bind(after.map(|(created_at, _)| created_at))
+            .bind(after.map(|(_, id)| id))
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// All of a user's confirmed swaps that confirmed before `before`, oldest
+    /// first: the ledger positions are built from, as it stood then.
+    ///
+    /// Swaps confirmed before `confirmed_at` was recorded count at their
+    /// creation time.
+    pub async fn find_confirmed_before(
+        pool: &DbPool,
+        user_id: i64,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<Swap>, sqlx::Error> {
+        query_as::<_, Swap>(
+            r#"
+            SELECT * FROM swaps
+            WHERE user_id = ?1 AND status = 'confirmed' AND COALESCE(confirmed_at, created_at) < ?2
+            ORDER BY COALESCE(confirmed_at, created_at), id
+            "#
+        )
+        .bind(user_id)
+        .bind(before)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// A user's swaps created at or after `from` and before `to`, oldest
+    /// first, whatever their status.
+    pub async fn find_created_between(
+        pool: &DbPool,
+        user_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Swap>, sqlx::Error> {
+        query_as::<_, Swap>(
+            "SELECT * FROM swaps WHERE user_id = ?1 AND created_at >= ?2 AND created_at < ?3 ORDER BY created_at, id"
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record the network fee of a swap's transaction.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - The fee was recorded
+    /// * `Ok(false)` - No swap with this signature
+    pub async fn set_network_fee(pool: &DbPool, signature: &str, lamports: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE swaps SET network_fee_lamports = ? WHERE signature = ?")
+            .bind(lamports)
+            .bind(signature)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record how long each stage of a swap's pipeline took.
+    ///
+    /// `latency` is the JSON breakdown the terminal reported; it replaces any
+    /// earlier one.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - The breakdown was recorded
+    /// * `Ok(false)` - No swap with this signature
+    pub async fn set_latency(pool: &DbPool, signature: &str, latency: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE swaps SET latency = ? WHERE signature = ?")
+            .bind(latency)
+            .bind(signature)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Latency breakdowns of all users' swaps created at or after `since`, oldest first.
+    ///
+    /// Swaps without a breakdown are left out.
+    pub async fn find_latencies_since(pool: &DbPool, since: DateTime<Utc>) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT latency FROM swaps WHERE latency IS NOT NULL AND created_at >= ? ORDER BY created_at, id"
+        )
+        .bind(since)
+        .fetch_all(pool)
+        .await
+    }
+
     /// Update swap status.
     ///
+    /// When this confirms the swap, its fills are applied to the user's
+    /// positions in the same transaction, so a swap counts towards them
+    /// exactly once.
+    ///
     /// # Arguments
     ///
     /// * `pool` - Database connection pool
@@ -224,7 +443,7 @@ impl SwapRepository {
     /// # Returns
     ///
     /// * `Ok(())` - Status updated successfully
-    /// * `Err(sqlx::Error)` - Database error occurred
+    /// * `Err(sqlx::Error)` - Database error occurred (nothing is changed)
     ///
     /// # Example
     ///
@@ -250,6 +469,12 @@ impl SwapRepository {
             None
         };
 
+        let mut tx = pool.begin().await?;
+        let previous = query_as::<_, Swap>("SELECT * FROM swaps WHERE signature = ?")
+            .bind(signature)
+            .fetch_optional(&mut *tx)
+            .await?;
+
         sqlx::query(
             r#"
             UPDATE swaps 
@@ -261,9 +486,16 @@ impl SwapRepository {
         .bind(error_message)
         .bind(confirmed_at)
         .bind(signature)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        if let Some(swap) = previous.filter(|swap| swap.status != SwapStatus::Confirmed) {
+            if status == SwapStatus::Confirmed {
+                PositionRepository::apply_swap(&mut tx, &swap).await?;
+            }
+        }
+
+        tx.commit().await?;
         Ok(())
     }
 }
@@ -294,7 +526,10 @@ mod tests {
                 status TEXT NOT NULL,
                 error_message TEXT,
                 created_at TIMESTAMP NOT NULL,
-                confirmed_at TIMESTAMP
+                confirmed_at TIMESTAMP,
+                value_usd REAL,
+                network_fee_lamports INTEGER,
+                latency TEXT
             )
             "#
         )
@@ -302,6 +537,27 @@ mod tests {
         .await
         .expect("Failed to create swaps table");
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS positions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                mint TEXT NOT NULL,
+                quantity BIGINT NOT NULL DEFAULT 0,
+                cost_basis_usd REAL NOT NULL DEFAULT 0,
+                realized_pnl_usd REAL NOT NULL DEFAULT 0,
+                incomplete BOOLEAN NOT NULL DEFAULT 0,
+                opened_at DATETIME NOT NULL,
+                updated_at DATETIME NOT NULL,
+                closed_at DATETIME,
+                UNIQUE(user_id, mint)
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create positions table");
+
         pool
     }
 
@@ -319,6 +575,7 @@ mod tests {
             2000000,
             Some(0.05),
             Some(50),
+            None,
         )
         .await
         .unwrap();
@@ -342,6 +599,7 @@ mod tests {
             2000000,
             None,
             None,
+            None,
         )
         .await
         .unwrap();
@@ -368,6 +626,7 @@ mod tests {
             2000000,
             None,
             None,
+            None,
         )
         .await
         .unwrap();
@@ -384,5 +643,221 @@ mod tests {
         assert_eq!(swap.status, SwapStatus::Confirmed);
         assert!(swap.confirmed_at.is_some());
     }
-}
 
+    #[tokio::test]
+    async fn test_confirmation_updates_positions_once() {
+        let pool = setup_test_db().await;
+        for (signature, input, output, input_amount, output_amount, value) in [
+            ("buy", "USDC", "SOL", 300_000_000, 2_000_000_000, Some(300.0)),
+            ("sell", "SOL", "USDC", 1_000_000_000, 200_000_000, Some(200.0)),
+            ("failed", "USDC", "SOL", 100_000_000, 500_000_000, Some(100.0)),
+        ] {
+            SwapRepository::create(&pool, 1, signature, input, output, input_amount, output_amount, None, None, value)
+                .await
+                .unwrap();
+        }
+
+        SwapRepository::update_status(&pool, "buy", SwapStatus::Confirmed, None).await.unwrap();
+        // Confirming again must not count the swap twice
+        SwapRepository::update_status(&pool, "buy", SwapStatus::Confirmed, None).await.unwrap();
+        SwapRepository::update_status(&pool, "sell", SwapStatus::Confirmed, None).await.unwrap();
+        SwapRepository::update_status(&pool, "failed", SwapStatus::Failed, Some("slippage")).await.unwrap();
+
+        let positions = PositionRepository::get_for_user(&pool, 1).await.unwrap();
+        let sol = positions.iter().find(|p| p.mint == "SOL").unwrap();
+        assert_eq!(sol.quantity, 1_000_000_000);
+        assert!((sol.cost_basis_usd - 150.0).abs() < 1e-9);
+        assert!((sol.realized_pnl_usd - 50.0).abs() < 1e-9);
+        assert!(!sol.incomplete);
+
+        // USDC was spent before any was bought through a swap
+        let usdc = positions.iter().find(|p| p.mint == "USDC").unwrap();
+        assert_eq!(usdc.quantity, 200_000_000);
+        assert!(usdc.incomplete);
+    }
+
+    // ==================== History Page Tests ====================
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        use chrono::TimeZone;
+        Utc.with_ymd_and_hms(2025, 2, 18, hour, 0, 0).unwrap()
+    }
+
+    /// Swaps for user 1 at 10:00..=14:00 plus one for user 2
+    async fn seed_history(pool: &DbPool) {
+        let rows = [
+            (1, "sig10", "SOL", "USDC", "confirmed", 10),
+            (1, "sig11", "SOL", "BONK", "failed", 11),
+            (1, "sig12", "USDC", "SOL", "confirmed", 12),
+            (1, "sig13", "SOL", "USDC", "pending", 13),
+            (1, "sig14", "SOL", "USDC", "confirmed", 14),
+            (2, "other", "SOL", "USDC", "confirmed", 12),
+        ];
+        for (user_id, signature, input, output, status, hour) in rows {
+            sqlx::query(
+                "INSERT INTO swaps (user_id, signature, input_mint, output_mint, input_amount, output_amount, status, created_at)
+                 VALUES (?, ?, ?, ?, 1, 1, ?, ?)",
+            )
+            .bind(user_id)
+            .bind(signature)
+            .bind(input)
+            .bind(output)
+            .bind(status)
+            .bind(at(hour))
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    fn signatures(swaps: &[Swap]) -> Vec<&str> {
+        swaps.iter().map(|s| s.signature.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_find_page_without_filters() {
+        let pool = setup_test_db().await;
+        seed_history(&pool).await;
+
+        let (swaps, total) = SwapRepository::find_page(&pool, 1, &SwapHistoryFilter::default(), 2, 0)
+            .await
+            .unwrap();
+        assert_eq!(total, 5);
+        assert_eq!(signatures(&swaps), vec!["sig14", "sig13"]);
+
+        let (swaps, _) = SwapRepository::find_page(&pool, 1, &SwapHistoryFilter::default(), 2, 4)
+            .await
+            .unwrap();
+        assert_eq!(signatures(&swaps), vec!["sig10"]);
+    }
+
+    #[tokio::test]
+    async fn test_find_page_combined_filters() {
+        let pool = setup_test_db().await;
+        seed_history(&pool).await;
+
+        let filter = SwapHistoryFilter {
+            from: Some(at(10)),
+            to: Some(at(14)),
+            input_mint: Some("SOL".to_string()),
+            output_mint: Some("USDC".to_string()),
+            status: None,
+            signature: None,
+        };
+        let (swaps, total) = SwapRepository::find_page(&pool, 1, &filter, 50, 0).await.unwrap();
+        // 14:00 is excluded by `to`, user 2's swap by user_id
+        assert_eq!(total, 2);
+        assert_eq!(signatures(&swaps), vec!["sig13", "sig10"]);
+
+        let confirmed = SwapHistoryFilter { status: Some(SwapStatus::Confirmed), ..filter };
+        let (swaps, total) = SwapRepository::find_page(&pool, 1, &confirmed, 50, 0).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(signatures(&swaps), vec!["sig10"]);
+    }
+
+    #[tokio::test]
+    async fn test_find_page_offset_past_end() {
+        let pool = setup_test_db().await;
+        seed_history(&pool).await;
+
+        let filter = SwapHistoryFilter { status: Some(SwapStatus::Failed), ..Default::default() };
+        let (swaps, total) = SwapRepository::find_page(&pool, 1, &filter, 10, 10).await.unwrap();
+        assert!(swaps.is_empty());
+        assert_eq!(total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_page_signature_fragment() {
+        let pool = setup_test_db().await;
+        seed_history(&pool).await;
+
+        let filter = SwapHistoryFilter { signature: Some("g1".to_string()), ..Default::default() };
+        let (swaps, total) = SwapRepository::find_page(&pool, 1, &filter, 10, 0).await.unwrap();
+        assert_eq!(total, 5);
+        assert_eq!(swaps.len(), 5);
+
+        // Only this user's swaps, and the match is case-sensitive
+        let filter = SwapHistoryFilter { signature: Some("oth".to_string()), ..Default::default() };
+        assert_eq!(SwapRepository::find_page(&pool, 1, &filter, 10, 0).await.unwrap().1, 0);
+        let filter = SwapHistoryFilter { signature: Some("SIG12".to_string()), ..Default::default() };
+        assert_eq!(SwapRepository::find_page(&pool, 1, &filter, 10, 0).await.unwrap().1, 0);
+
+        let filter = SwapHistoryFilter { signature: Some("ig12".to_string()), ..Default::default() };
+        let (swaps, _) = SwapRepository::find_page(&pool, 1, &filter, 10, 0).await.unwrap();
+        assert_eq!(signatures(&swaps), vec!["sig12"]);
+    }
+
+    #[tokio::test]
+    async fn test_find_after_walks_history_in_order() {
+        let pool = setup_test_db().await;
+        seed_history(&pool).await;
+        // Same timestamp as sig12; the later row sorts first
+        sqlx::query(
+            "INSERT INTO swaps (user_id, signature, input_mint, output_mint, input_amount, output_amount, status, created_at)
+             VALUES (1, 'sig12b', 'SOL', 'USDC', 1, 1, 'confirmed', ?)",
+        )
+        .bind(at(12))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut walked = Vec::new();
+        let mut after = None;
+        loop {
+            let batch = SwapRepository::find_after(&pool, 1, &SwapHistoryFilter::default(), after, 2)
+                .await
+                .unwrap();
+            let Some(last) = batch.last() else { break };
+            after = Some((last.created_at, last.id));
+            walked.extend(batch.iter().map(|s| s.signature.clone()));
+        }
+        assert_eq!(walked, vec!["sig14", "sig13", "sig12b", "sig12", "sig11", "sig10"]);
+
+        // Filters still apply past the cursor
+        let filter = SwapHistoryFilter { status: Some(SwapStatus::Confirmed), ..Default::default() };
+        let swaps = SwapRepository::find_after(&pool, 1, &filter, Some((at(12), i64::MAX)), 10).await.unwrap();
+        assert_eq!(signatures(&swaps), vec!["sig12b", "sig12", "sig10"]);
+    }
+
+    #[tokio::test]
+    async fn test_find_confirmed_before_is_the_ledger_up_to_then() {
+        let pool = setup_test_db().await;
+        seed_history(&pool).await;
+
+        let swaps = SwapRepository::find_confirmed_before(&pool, 1, at(14)).await.unwrap();
+        // Oldest first; failed, pending and other users' swaps are left out
+        assert_eq!(signatures(&swaps), vec!["sig10", "sig12"]);
+        assert!(SwapRepository::find_confirmed_before(&pool, 1, at(10)).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_created_between_and_network_fee() {
+        let pool = setup_test_db().await;
+        seed_history(&pool).await;
+
+        let swaps = SwapRepository::find_created_between(&pool, 1, at(11), at(14)).await.unwrap();
+        assert_eq!(signatures(&swaps), vec!["sig11", "sig12", "sig13"]);
+        assert_eq!(swaps[1].network_fee_lamports, None);
+
+        assert!(SwapRepository::set_network_fee(&pool, "sig12", 5_000).await.unwrap());
+        assert!(!SwapRepository::set_network_fee(&pool, "missing", 5_000).await.unwrap());
+        let swap = SwapRepository::find_by_signature(&pool, "sig12").await.unwrap().unwrap();
+        assert_eq!(swap.network_fee_lamports, Some(5_000));
+    }
+
+    #[tokio::test]
+    async fn test_latency_is_kept_with_the_swap() {
+        let pool = setup_test_db().await;
+        seed_history(&pool).await;
+
+        assert!(SwapRepository::set_latency(&pool, "sig13", r#"{"build_ms":900}"#).await.unwrap());
+        assert!(SwapRepository::set_latency(&pool, "sig11", r#"{"build_ms":400}"#).await.unwrap());
+        assert!(SwapRepository::set_latency(&pool, "other", r#"{"build_ms":700}"#).await.unwrap());
+        assert!(!SwapRepository::set_latency(&pool, "missing", "{}").await.unwrap());
+
+        // All users, oldest first, only swaps that have one
+        let latencies = SwapRepository::find_latencies_since(&pool, at(11)).await.unwrap();
+        assert_eq!(latencies, vec![r#"{"build_ms":400}"#, r#"{"build_ms":700}"#, r#"{"build_ms":900}"#]);
+        assert_eq!(SwapRepository::find_latencies_since(&pool, at(12)).await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,337 @@
+//! # Token Unlock Repository
+//!
+//! Provides database access layer for scheduled token unlocks.
+//!
+//! Unlocks are written by two parties: the dataset importer, through
+//! [`TokenUnlockRepository::import`], and admins. An admin change marks the
+//! row `"admin"` and the importer skips such rows from then on, so a manual
+//! correction survives the next import. Admin removal is a soft delete for
+//! the same reason.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::token_unlock_repository::TokenUnlockRepository;
+//! use lib_core::create_pool;
+//! use chrono::{Duration, Utc};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! let now = Utc::now();
+//! for unlock in TokenUnlockRepository::upcoming(&pool, now, now + Duration::days(30)).await? {
+//!     println!("{} unlocks {} on {}", unlock.symbol, unlock.amount, unlock.unlock_at);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::models::{TokenUnlock, TokenUnlockForCreate};
+use super::DbPool;
+use chrono::{DateTime, Utc};
+use sqlx::query_as;
+
+/// Source of rows written by the dataset importer
+pub const SOURCE_IMPORT: &str = "import";
+
+/// Source of rows created or changed by an admin
+pub const SOURCE_ADMIN: &str = "admin";
+
+/// Outcome of importing a batch of unlocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UnlockImportCounts {
+    /// Rows inserted or updated
+    pub upserted: usize,
+    /// Rows left alone because an admin owns them
+    pub skipped: usize,
+}
+
+/// Token unlock repository for database operations.
+pub struct TokenUnlockRepository;
+
+impl TokenUnlockRepository {
+    /// Unlocks scheduled in `[from, until]`, soonest first. Removed rows are
+    /// left out.
+    pub async fn upcoming(
+        pool: &DbPool,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<TokenUnlock>, sqlx::Error> {
+        query_as::<_, TokenUnlock>(
+            r#"
+            SELECT * FROM token_unlocks
+            WHERE removed = 0 AND unlock_at >= ?1 AND unlock_at <= ?2
+            ORDER BY unlock_at, id
+            "#
+        )
+        .bind(from)
+        .bind(until)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Find an unlock by id, including removed ones.
+    pub async fn find(pool: &DbPool, id: i64) -> Result<Option<TokenUnlock>, sqlx::Error> {
+        query_as::<_, TokenUnlock>("SELECT * FROM token_unlocks WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Create an unlock as an admin.
+    ///
+    /// An existing row for the same mint, time and allocation (imported, or
+    /// removed earlier) is taken over instead, so admins can correct imported
+    /// rows without knowing their id.
+    pub async fn create(
+        pool: &DbPool,
+        unlock: &TokenUnlockForCreate,
+        admin: &str,
+    ) -> Result<TokenUnlock, sqlx::Error> {
+        let now = Utc::now();
+        query_as::<_, TokenUnlock>(
+            r#"
+            INSERT INTO token_unlocks
+                (mint, symbol, unlock_at, amount, circulating_supply, allocation, source, updated_by, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)
+            ON CONFLICT(mint, unlock_at, allocation) DO UPDATE SET
+                symbol = excluded.symbol,
+                amount = excluded.amount,
+                circulating_supply = excluded.circulating_supply,
+                source = excluded.source,
+                updated_by = excluded.updated_by,
+                removed = 0,
+                updated_at = excluded.updated_at
+            RETURNING *
+            "#
+        )
+        .bind(&unlock.mint)
+        .bind(unlock.symbol.to_uppercase())
+        .bind(unlock.unlock_at)
+        .bind(unlock.amount)
+        .bind(unlock.circulating_supply)
+        .bind(&unlock.allocation)
+        .bind(SOURCE_ADMIN)
+        .bind(admin)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Replace an unlock's fields as an admin.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(TokenUnlock))` - The updated unlock
+    /// * `Ok(None)` - No unlock with that id
+    /// * `Err(sqlx::Error)` - Database error (including a clash with another row)
+    pub async fn update(
+        pool: &DbPool,
+        id: i64,
+        unlock: &TokenUnlockForCreate,
+        admin: &str,
+    ) -> Result<Option<TokenUnlock>, sqlx::Error> {
+        query_as::<_, TokenUnlock>(
+            r#"
+            UPDATE token_unlocks SET
+                mint = ?1, symbol = ?2, unlock_at = ?3, amount = ?4, circulating_supply = ?5,
+                allocation = ?6, source = ?7, updated_by = ?8, removed = 0, updated_at = ?9
+            WHERE id = ?10
+            RETURNING *
+            "#
+        )
+        .bind(&unlock.mint)
+        .bind(unlock.symbol.to_uppercase())
+        .bind(unlock.unlock_at)
+        .bind(unlock.amount)
+        .bind(unlock.circulating_supply)
+        .bind(&unlock.allocation)
+        .bind(SOURCE_ADMIN)
+        .bind(admin)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Remove an unlock as an admin.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - Unlock was removed
+    /// * `Ok(false)` - No such unlock, or it was already removed
+    pub async fn remove(pool: &DbPool, id: i64, admin: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE token_unlocks SET removed = 1, source = ?1, updated_by = ?2, updated_at = ?3 WHERE id = ?4 AND removed = 0"
+        )
+        .bind(SOURCE_ADMIN)
+        .bind(admin)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Upsert a batch from the unlocks dataset in one transaction.
+    ///
+    /// Rows are matched on mint, unlock time and allocation; matches owned
+    /// by an admin are skipped.
+    pub async fn import(pool: &DbPool, unlocks: &[TokenUnlockForCreate]) -> Result<UnlockImportCounts, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let now = Utc::now();
+        let mut counts = UnlockImportCounts::default();
+
+        for unlock in unlocks {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO token_unlocks
+                    (mint, symbol, unlock_at, amount, circulating_supply, allocation, source, created_at, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)
+                ON CONFLICT(mint, unlock_at, allocation) DO UPDATE SET
+                    symbol = excluded.symbol,
+                    amount = excluded.amount,
+                    circulating_supply = excluded.circulating_supply,
+                    updated_at = excluded.updated_at
+                WHERE token_unlocks.source = ?7
+                "#
+            )
+            .bind(&unlock.mint)
+            .bind(unlock.symbol.to_uppercase())
+            .bind(unlock.unlock_at)
+            .bind(unlock.amount)
+            .bind(unlock.circulating_supply)
+            .bind(&unlock.allocation)
+            .bind(SOURCE_IMPORT)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+
+            if result.rows_affected() > 0 {
+                counts.upserted += 1;
+            } else {
+                counts.skipped += 1;
+            }
+        }
+        tx.commit().await?;
+
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        sqlx::migrate!("../../../migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    fn unlock(symbol: &str, day: u32, amount: f64) -> TokenUnlockForCreate {
+        TokenUnlockForCreate {
+            mint: format!("{}mint", symbol),
+            symbol: symbol.to_string(),
+            unlock_at: Utc.with_ymd_and_hms(2025, 3, day, 0, 0, 0).unwrap(),
+            amount,
+            circulating_supply: Some(1_000_000.0),
+            allocation: "Team".to_string(),
+        }
+    }
+
+    fn march() -> (DateTime<Utc>, DateTime<Utc>) {
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        (start, start + Duration::days(31))
+    }
+
+    #[tokio::test]
+    async fn test_upcoming_window_and_order() {
+        let pool = setup_test_db().await;
+        let counts = TokenUnlockRepository::import(
+            &pool,
+            &[unlock("jup", 20, 5.0), unlock("PYTH", 5, 1.0), unlock("ARB", 1, 2.0)],
+        )
+        .await
+        .unwrap();
+        assert_eq!(counts, UnlockImportCounts { upserted: 3, skipped: 0 });
+
+        let (_, until) = march();
+        let from = Utc.with_ymd_and_hms(2025, 3, 2, 0, 0, 0).unwrap();
+        let upcoming = TokenUnlockRepository::upcoming(&pool, from, until).await.unwrap();
+        let symbols: Vec<_> = upcoming.iter().map(|u| u.symbol.as_str()).collect();
+        assert_eq!(symbols, ["PYTH", "JUP"]);
+        assert!(upcoming.iter().all(|u| u.source == SOURCE_IMPORT));
+    }
+
+    #[tokio::test]
+    async fn test_reimport_updates_imported_rows() {
+        let pool = setup_test_db().await;
+        TokenUnlockRepository::import(&pool, &[unlock("JUP", 20, 5.0)]).await.unwrap();
+        TokenUnlockRepository::import(&pool, &[unlock("JUP", 20, 7.5)]).await.unwrap();
+
+        let (from, until) = march();
+        let upcoming = TokenUnlockRepository::upcoming(&pool, from, until).await.unwrap();
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].amount, 7.5);
+    }
+
+    #[tokio::test]
+    async fn test_import_skips_admin_rows() {
+        let pool = setup_test_db().await;
+        TokenUnlockRepository::import(&pool, &[unlock("JUP", 20, 5.0), unlock("PYTH", 5, 1.0)])
+            .await
+            .unwrap();
+        let (from, until) = march();
+        let imported = TokenUnlockRepository::upcoming(&pool, from, until).await.unwrap();
+        let pyth = imported.iter().find(|u| u.symbol == "PYTH").unwrap();
+
+        // Correct JUP by re-posting it, remove PYTH
+        let corrected = TokenUnlockRepository::create(&pool, &unlock("JUP", 20, 6.0), "ops").await.unwrap();
+        assert_eq!(corrected.source, SOURCE_ADMIN);
+        assert!(TokenUnlockRepository::remove(&pool, pyth.id, "ops").await.unwrap());
+        assert!(!TokenUnlockRepository::remove(&pool, pyth.id, "ops").await.unwrap());
+
+        let counts = TokenUnlockRepository::import(&pool, &[unlock("JUP", 20, 5.0), unlock("PYTH", 5, 1.0)])
+            .await
+            .unwrap();
+        assert_eq!(counts, UnlockImportCounts { upserted: 0, skipped: 2 });
+
+        let upcoming = TokenUnlockRepository::upcoming(&pool, from, until).await.unwrap();
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].amount, 6.0);
+        assert_eq!(upcoming[0].updated_by.as_deref(), Some("ops"));
+        assert!(TokenUnlockRepository::find(&pool, pyth.id).await.unwrap().unwrap().removed);
+    }
+
+    #[tokio::test]
+    async fn test_update_restores_removed_row() {
+        let pool = setup_test_db().await;
+        let created = TokenUnlockRepository::create(&pool, &unlock("JUP", 20, 5.0), "ops").await.unwrap();
+        TokenUnlockRepository::remove(&pool, created.id, "ops").await.unwrap();
+
+        let updated = TokenUnlockRepository::update(&pool, created.id, &unlock("JUP", 21, 4.0), "lead")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!updated.removed);
+        assert_eq!(updated.unlock_at, Utc.with_ymd_and_hms(2025, 3, 21, 0, 0, 0).unwrap());
+        assert_eq!(updated.updated_by.as_deref(), Some("lead"));
+
+        assert!(TokenUnlockRepository::update(&pool, 999, &unlock("JUP", 21, 4.0), "lead")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,134 @@
+//! # Usage Count Repository
+//!
+//! Provides database access layer for the anonymous daily usage counts
+//! terminals upload when their user opted in. Rows are keyed by a random
+//! install id, never by a user.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::usage_count_repository::UsageCountRepository;
+//! use lib_core::create_pool;
+//!
+//! # async fn example(install_id: &str) -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! let deleted = UsageCountRepository::delete_install(&pool, install_id).await?;
+//! println!("removed {deleted} counts");
+//! # Ok(())
+//! # }
+//! ```
+
+use super::DbPool;
+use chrono::NaiveDate;
+
+/// Usage count repository for database operations.
+pub struct UsageCountRepository;
+
+impl UsageCountRepository {
+    /// Store an install's counts, given as `(day, event, count)`, in one
+    /// transaction.
+    ///
+    /// A count already stored for the same day and event is replaced, so
+    /// uploading a day twice doesn't double it.
+    pub async fn record(
+        pool: &DbPool,
+        install_id: &str,
+        app_version: &str,
+        counts: &[(NaiveDate, &str, i64)],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for &(day, event, count) in counts {
+            sqlx::query(
+                r#"
+                INSERT INTO usage_counts (install_id, day, event, count, app_version)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                ON CONFLICT (install_id, day, event) DO UPDATE SET
+                    count = excluded.count,
+                    app_version = excluded.app_version,
+                    received_at = CURRENT_TIMESTAMP
+                "#
+            )
+            .bind(install_id)
+            .bind(day)
+            .bind(event)
+            .bind(count)
+            .bind(app_version)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Count of `event` summed over all installs on `day`.
+    pub async fn total(pool: &DbPool, day: NaiveDate, event: &str) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COALESCE(SUM(count), 0) FROM usage_counts WHERE day = ?1 AND event = ?2")
+            .bind(day)
+            .bind(event)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Delete everything an install uploaded.
+    ///
+    /// # Returns
+    ///
+    /// Number of rows deleted.
+    pub async fn delete_install(pool: &DbPool, install_id: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM usage_counts WHERE install_id = ?")
+            .bind(install_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::migrate!("../../../migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+        pool
+    }
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reupload_replaces_counts() {
+        let pool = setup_test_db().await;
+        UsageCountRepository::record(&pool, "a", "0.1.0", &[(day(1), "feature.swap", 2), (day(1), "screen.wallet", 5)])
+            .await
+            .unwrap();
+        UsageCountRepository::record(&pool, "a", "0.1.0", &[(day(1), "feature.swap", 3)]).await.unwrap();
+        UsageCountRepository::record(&pool, "b", "0.1.0", &[(day(1), "feature.swap", 1)]).await.unwrap();
+
+        assert_eq!(UsageCountRepository::total(&pool, day(1), "feature.swap").await.unwrap(), 4);
+        assert_eq!(UsageCountRepository::total(&pool, day(1), "screen.wallet").await.unwrap(), 5);
+        assert_eq!(UsageCountRepository::total(&pool, day(2), "feature.swap").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_delete_install_keeps_other_installs() {
+        let pool = setup_test_db().await;
+        UsageCountRepository::record(&pool, "a", "0.1.0", &[(day(1), "feature.swap", 2), (day(2), "feature.swap", 1)])
+            .await
+            .unwrap();
+        UsageCountRepository::record(&pool, "b", "0.1.0", &[(day(1), "feature.swap", 7)]).await.unwrap();
+
+        assert_eq!(UsageCountRepository::delete_install(&pool, "a").await.unwrap(), 2);
+        assert_eq!(UsageCountRepository::delete_install(&pool, "a").await.unwrap(), 0);
+        assert_eq!(UsageCountRepository::total(&pool, day(1), "feature.swap").await.unwrap(), 7);
+    }
+}
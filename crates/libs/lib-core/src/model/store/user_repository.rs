@@ -38,6 +38,27 @@ use sqlx::query_as;
 pub struct UserRepository;
 
 impl UserRepository {
+    /// Find a user by their ID.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(User))` - User found
+    /// * `Ok(None)` - No user with that ID
+    /// * `Err(sqlx::Error)` - Database error occurred
+    pub async fn find_by_id(pool: &DbPool, id: i64) -> Result<Option<User>, sqlx::Error> {
+        query_as::<_, User>("SELECT * FROM users WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// List all users in the order they signed up.
+    pub async fn list(pool: &DbPool) -> Result<Vec<User>, sqlx::Error> {
+        query_as::<_, User>("SELECT * FROM users ORDER BY id")
+            .fetch_all(pool)
+            .await
+    }
+
     /// Find a user by their email address.
     ///
     /// # Arguments
@@ -355,6 +376,51 @@ impl UserRepository {
         Ok(())
     }
 
+    /// Replace a user's password hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `id` - The user ID to update
+    /// * `password_hash` - The new hashed password (use `auth::hash_password`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - Password changed
+    /// * `Ok(false)` - No user with that ID
+    /// * `Err(sqlx::Error)` - Database error occurred
+    pub async fn update_password(pool: &DbPool, id: i64, password_hash: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE users SET password_hash = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(password_hash)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Change a user's email address.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `id` - The user ID to update
+    /// * `email` - The new email address (must be unique)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(User)` - The updated user
+    /// * `Err(sqlx::Error)` - Database error (e.g., UNIQUE constraint violation
+    ///   if another user has the email, or `RowNotFound` for an unknown ID)
+    pub async fn update_email(pool: &DbPool, id: i64, email: &str) -> Result<User, sqlx::Error> {
+        query_as::<_, User>(
+            "UPDATE users SET email = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ? RETURNING *"
+        )
+        .bind(email)
+        .bind(id)
+        .fetch_one(pool)
+        .await
+    }
+
     /// Find a user by their Solana wallet address.
     ///
     /// # Arguments
@@ -434,6 +500,24 @@ impl UserRepository {
         Ok(())
     }
 
+    /// Delete one user.
+    ///
+    /// Friendships, messages, swaps and other rows owned by the user are
+    /// removed with it by the schema's `ON DELETE CASCADE` constraints.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - User was deleted
+    /// * `Ok(false)` - No user with that ID
+    /// * `Err(sqlx::Error)` - Database error occurred
+    pub async fn delete(pool: &DbPool, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM users WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Delete all users from the database.
     ///
     /// **WARNING**: This is a destructive operation that cannot be undone.
@@ -674,6 +758,53 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    // ========== Account Update Tests ==========
+
+    #[tokio::test]
+    async fn test_find_by_id() {
+        let pool = setup_test_db().await;
+        let user = UserRepository::create(&pool, "testuser", "test@example.com", "hash")
+            .await
+            .unwrap();
+
+        let found = UserRepository::find_by_id(&pool, user.id).await.unwrap();
+        assert_eq!(found.unwrap().username, "testuser");
+        assert!(UserRepository::find_by_id(&pool, user.id + 1).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_password() {
+        let pool = setup_test_db().await;
+        let user = UserRepository::create(&pool, "testuser", "test@example.com", "old_hash")
+            .await
+            .unwrap();
+
+        assert!(UserRepository::update_password(&pool, user.id, "new_hash").await.unwrap());
+        assert!(!UserRepository::update_password(&pool, user.id + 1, "new_hash").await.unwrap());
+
+        let found = UserRepository::find_by_id(&pool, user.id).await.unwrap().unwrap();
+        assert_eq!(found.password_hash, "new_hash");
+    }
+
+    #[tokio::test]
+    async fn test_update_email_rejects_duplicate() {
+        let pool = setup_test_db().await;
+        let alice = UserRepository::create(&pool, "alice", "alice@example.com", "hash")
+            .await
+            .unwrap();
+        UserRepository::create(&pool, "bob", "bob@example.com", "hash")
+            .await
+            .unwrap();
+
+        let updated = UserRepository::update_email(&pool, alice.id, "alice@new.example.com")
+            .await
+            .unwrap();
+        assert_eq!(updated.email, "alice@new.example.com");
+
+        let duplicate = UserRepository::update_email(&pool, alice.id, "bob@example.com").await;
+        assert!(duplicate.is_err());
+    }
+
     // ========== Wallet Tests ==========
 
     #[tokio::test]
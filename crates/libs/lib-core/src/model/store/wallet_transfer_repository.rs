@@ -0,0 +1,123 @@
+//! # Wallet Transfer Repository
+//!
+//! Provides database access layer for the SOL deposits and withdrawals the
+//! wallet activity watcher observes.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::wallet_transfer_repository::WalletTransferRepository;
+//! use lib_core::create_pool;
+//! use chrono::{Duration, Utc};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! let now = Utc::now();
+//! for transfer in WalletTransferRepository::find_between(&pool, 1, now - Duration::days(30), now).await? {
+//!     println!("{}: {} lamports", transfer.observed_at, transfer.lamports);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::models::WalletTransfer;
+use super::DbPool;
+use chrono::{DateTime, Utc};
+
+/// Wallet transfer repository for database operations.
+pub struct WalletTransferRepository;
+
+impl WalletTransferRepository {
+    /// Record a balance change of `lamports` (negative for a withdrawal)
+    /// observed at `observed_at`.
+    pub async fn create(
+        pool: &DbPool,
+        user_id: i64,
+        wallet: &str,
+        lamports: i64,
+        observed_at: DateTime<Utc>,
+    ) -> Result<WalletTransfer, sqlx::Error> {
+        sqlx::query_as::<_, WalletTransfer>(
+            r#"
+            INSERT INTO wallet_transfers (user_id, wallet, lamports, observed_at)
+            VALUES (?1, ?2, ?3, ?4)
+            RETURNING *
+            "#
+        )
+        .bind(user_id)
+        .bind(wallet)
+        .bind(lamports)
+        .bind(observed_at)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// A user's transfers observed at or after `from` and before `to`,
+    /// oldest first.
+    pub async fn find_between(
+        pool: &DbPool,
+        user_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<WalletTransfer>, sqlx::Error> {
+        sqlx::query_as::<_, WalletTransfer>(
+            r#"
+            SELECT * FROM wallet_transfers
+            WHERE user_id = ?1 AND observed_at >= ?2 AND observed_at < ?3
+            ORDER BY observed_at, id
+            "#
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::migrate!("../../../migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+        for name in ["alice", "bob"] {
+            sqlx::query("INSERT INTO users (username, email, password_hash) VALUES (?, ?, 'x')")
+                .bind(name)
+                .bind(format!("{}@example.com", name))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool
+    }
+
+    fn day(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, day, 12, 0, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_find_between_is_half_open_and_per_user() {
+        let pool = setup_test_db().await;
+        WalletTransferRepository::create(&pool, 1, "wallet", 5_000, day(1)).await.unwrap();
+        WalletTransferRepository::create(&pool, 1, "wallet", -2_000, day(3)).await.unwrap();
+        WalletTransferRepository::create(&pool, 1, "wallet", 1_000, day(5)).await.unwrap();
+        WalletTransferRepository::create(&pool, 2, "other", 9_000, day(3)).await.unwrap();
+
+        let transfers = WalletTransferRepository::find_between(&pool, 1, day(1), day(5)).await.unwrap();
+        let lamports: Vec<i64> = transfers.iter().map(|t| t.lamports).collect();
+        assert_eq!(lamports, vec![5_000, -2_000]);
+        assert_eq!(transfers[1].observed_at, day(3));
+    }
+}
@@ -0,0 +1,279 @@
+//! # Webhook Repository
+//!
+//! Provides database access layer for outgoing webhooks and their delivery log.
+//!
+//! Every query that takes a webhook id from a request also takes the user id,
+//! so one user can never read or delete another user's webhooks.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use lib_core::model::store::webhook_repository::WebhookRepository;
+//! use lib_core::create_pool;
+//!
+//! # async fn example(user_id: i64) -> anyhow::Result<()> {
+//! let pool = create_pool().await?;
+//!
+//! for webhook in WebhookRepository::find_by_user(&pool, user_id).await? {
+//!     let deliveries = WebhookRepository::find_deliveries(&pool, webhook.id, 5).await?;
+//!     println!("{} ({} recent deliveries)", webhook.url, deliveries.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::models::{Webhook, WebhookDelivery};
+use super::DbPool;
+
+/// Webhook repository for database operations.
+pub struct WebhookRepository;
+
+impl WebhookRepository {
+    /// Register a webhook for `user_id`.
+    pub async fn create(
+        pool: &DbPool,
+        user_id: i64,
+        url: &str,
+        secret: &str,
+        events: &str,
+        balance_threshold_lamports: Option<i64>,
+    ) -> Result<Webhook, sqlx::Error> {
+        sqlx::query_as::<_, Webhook>(
+            r#"
+            INSERT INTO webhooks (user_id, url, secret, events, balance_threshold_lamports)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            RETURNING id, user_id, url, secret, events, balance_threshold_lamports, created_at
+            "#
+        )
+        .bind(user_id)
+        .bind(url)
+        .bind(secret)
+        .bind(events)
+        .bind(balance_threshold_lamports)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// All webhooks of a user, oldest first.
+    pub async fn find_by_user(pool: &DbPool, user_id: i64) -> Result<Vec<Webhook>, sqlx::Error> {
+        sqlx::query_as::<_, Webhook>(
+            r#"
+            SELECT id, user_id, url, secret, events, balance_threshold_lamports, created_at
+            FROM webhooks
+            WHERE user_id = ?
+            ORDER BY id
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// A webhook, if it belongs to `user_id`.
+    pub async fn find_for_user(pool: &DbPool, id: i64, user_id: i64) -> Result<Option<Webhook>, sqlx::Error> {
+        sqlx::query_as::<_, Webhook>(
+            r#"
+            SELECT id, user_id, url, secret, events, balance_threshold_lamports, created_at
+            FROM webhooks
+            WHERE id = ?1 AND user_id = ?2
+            "#
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Ids of users with at least one webhook, for the activity watcher.
+    pub async fn find_user_ids(pool: &DbPool) -> Result<Vec<i64>, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>("SELECT DISTINCT user_id FROM webhooks ORDER BY user_id")
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Delete a webhook of `user_id` along with its delivery log.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - The webhook was deleted
+    /// * `Ok(false)` - No such webhook for this user
+    pub async fn delete(pool: &DbPool, id: i64, user_id: i64) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = ?1 AND user_id = ?2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        sqlx::query("DELETE FROM webhook_deliveries WHERE webhook_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Log the outcome of one event and drop all but the newest `keep` rows
+    /// of that webhook.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record_delivery(
+        pool: &DbPool,
+        webhook_id: i64,
+        event_id: &str,
+        event: &str,
+        delivered: bool,
+        attempts: i64,
+        response_status: Option<i64>,
+        error: Option<&str>,
+        keep: i64,
+    ) -> Result<WebhookDelivery, sqlx::Error> {
+        let delivery = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            INSERT INTO webhook_deliveries
+                (webhook_id, event_id, event, delivered, attempts, response_status, error)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            RETURNING id, webhook_id, event_id, event, delivered, attempts, response_status, error, created_at
+            "#
+        )
+        .bind(webhook_id)
+        .bind(event_id)
+        .bind(event)
+        .bind(delivered)
+        .bind(attempts)
+        .bind(response_status)
+        .bind(error)
+        .fetch_one(pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM webhook_deliveries
+            WHERE webhook_id = ?1 AND id NOT IN (
+                SELECT id FROM webhook_deliveries WHERE webhook_id = ?1 ORDER BY id DESC LIMIT ?2
+            )
+            "#
+        )
+        .bind(webhook_id)
+        .bind(keep)
+        .execute(pool)
+        .await?;
+
+        Ok(delivery)
+    }
+
+    /// Newest deliveries of a webhook first.
+    pub async fn find_deliveries(
+        pool: &DbPool,
+        webhook_id: i64,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT id, webhook_id, event_id, event, delivered, attempts, response_status, error, created_at
+            FROM webhook_deliveries
+            WHERE webhook_id = ?
+            ORDER BY id DESC
+            LIMIT ?
+            "#
+        )
+        .bind(webhook_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE webhooks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                url TEXT NOT NULL,
+                secret TEXT NOT NULL,
+                events TEXT NOT NULL,
+                balance_threshold_lamports INTEGER,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create webhooks table");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE webhook_deliveries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                webhook_id INTEGER NOT NULL,
+                event_id TEXT NOT NULL,
+                event TEXT NOT NULL,
+                delivered BOOLEAN NOT NULL,
+                attempts INTEGER NOT NULL,
+                response_status INTEGER,
+                error TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create webhook_deliveries table");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_webhooks_are_scoped_to_their_user() {
+        let pool = setup_test_db().await;
+        let hook = WebhookRepository::create(&pool, 1, "https://bot.example/hook", "s3cret", "incoming_transfer", None)
+            .await
+            .unwrap();
+        WebhookRepository::create(&pool, 2, "https://other.example", "x", "swap_confirmed", Some(5))
+            .await
+            .unwrap();
+
+        assert_eq!(WebhookRepository::find_by_user(&pool, 1).await.unwrap().len(), 1);
+        assert!(WebhookRepository::find_for_user(&pool, hook.id, 2).await.unwrap().is_none());
+        assert!(!WebhookRepository::delete(&pool, hook.id, 2).await.unwrap());
+        assert_eq!(WebhookRepository::find_user_ids(&pool).await.unwrap(), vec![1, 2]);
+
+        WebhookRepository::record_delivery(&pool, hook.id, "evt", "test", true, 1, Some(200), None, 50)
+            .await
+            .unwrap();
+        assert!(WebhookRepository::delete(&pool, hook.id, 1).await.unwrap());
+        assert!(WebhookRepository::find_deliveries(&pool, hook.id, 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delivery_log_keeps_newest() {
+        let pool = setup_test_db().await;
+        let hook = WebhookRepository::create(&pool, 1, "https://bot.example/hook", "s3cret", "test", None)
+            .await
+            .unwrap();
+        for i in 0..5 {
+            let event_id = format!("evt-{}", i);
+            WebhookRepository::record_delivery(&pool, hook.id, &event_id, "test", i % 2 == 0, 3, Some(500), Some("HTTP 500"), 3)
+                .await
+                .unwrap();
+        }
+
+        let deliveries = WebhookRepository::find_deliveries(&pool, hook.id, 10).await.unwrap();
+        let ids: Vec<&str> = deliveries.iter().map(|d| d.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["evt-4", "evt-3", "evt-2"]);
+        assert_eq!(deliveries[0].response_status, Some(500));
+    }
+}
@@ -0,0 +1,221 @@
+//! # Price Aggregation
+//!
+//! Compares the prices the sources (Pyth, Jupiter) report for one token.
+//!
+//! ## Divergence
+//!
+//! The spread between the highest and the lowest source price, as a percent
+//! of their mid, is the divergence. Above the threshold
+//! ([`DEFAULT_DIVERGENCE_THRESHOLD_PCT`] unless `PRICE_DIVERGENCE_THRESHOLD_PCT`
+//! says otherwise) the price is flagged: sources disagreeing that much usually
+//! means one of them is stale or the token is depegging.
+//!
+//! With fewer than two sources there is nothing to compare and nothing is
+//! flagged.
+//!
+//! ## Sustained Divergence
+//!
+//! A single tick over the threshold is often just one source updating before
+//! the other. [`DivergenceMonitor`] only reports a symbol once it has stayed
+//! over the threshold for [`DEFAULT_DIVERGENCE_SUSTAIN`] (or
+//! `PRICE_DIVERGENCE_SUSTAIN_SECS`), and only clears it once it has stayed
+//! under for as long.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// Divergence above which a price is flagged, in percent
+pub const DEFAULT_DIVERGENCE_THRESHOLD_PCT: f64 = 1.0;
+
+/// How long a divergence has to last before it is reported
+pub const DEFAULT_DIVERGENCE_SUSTAIN: Duration = Duration::from_secs(30);
+
+/// Price reported by each source, by source name
+pub type SourcePrices = BTreeMap<String, f64>;
+
+/// Mid of the highest and lowest source price; `None` without sources
+pub fn mid_price(sources: &SourcePrices) -> Option<f64> {
+    let (low, high) = range(sources)?;
+    Some((low + high) / 2.0)
+}
+
+/// Spread of the source prices in percent of their mid; `None` with fewer
+/// than two sources
+pub fn divergence_pct(sources: &SourcePrices) -> Option<f64> {
+    if sources.len() < 2 {
+        return None;
+    }
+    let (low, high) = range(sources)?;
+    let mid = (low + high) / 2.0;
+    (mid > 0.0).then(|| (high - low) / mid * 100.0)
+}
+
+/// Whether the sources disagree by more than `threshold_pct` percent
+pub fn is_divergent(sources: &SourcePrices, threshold_pct: f64) -> bool {
+    divergence_pct(sources).is_some_and(|pct| pct > threshold_pct)
+}
+
+/// Divergence threshold from `PRICE_DIVERGENCE_THRESHOLD_PCT`
+pub fn divergence_threshold_from_env() -> f64 {
+    std::env::var("PRICE_DIVERGENCE_THRESHOLD_PCT")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|pct| pct.is_finite() && *pct > 0.0)
+        .unwrap_or(DEFAULT_DIVERGENCE_THRESHOLD_PCT)
+}
+
+/// How long divergence must last, from `PRICE_DIVERGENCE_SUSTAIN_SECS`
+pub fn divergence_sustain_from_env() -> Duration {
+    std::env::var("PRICE_DIVERGENCE_SUSTAIN_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_DIVERGENCE_SUSTAIN)
+}
+
+/// Change in whether a symbol's sources disagree for a sustained period
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DivergenceAlert {
+    /// Over the threshold for the whole sustain period; the latest divergence
+    Started { divergence_pct: f64 },
+    /// Back under the threshold for the whole sustain period
+    Cleared,
+}
+
+/// Per-symbol sustained divergence detection
+///
+/// Feed it every update with [`DivergenceMonitor::observe`]. Updates without
+/// a divergence (fewer than two sources) count as agreeing.
+#[derive(Debug)]
+pub struct DivergenceMonitor {
+    threshold_pct: f64,
+    sustain: Duration,
+    symbols: HashMap<String, SymbolDivergence>,
+}
+
+#[derive(Debug, Default)]
+struct SymbolDivergence {
+    /// Whether a `Started` alert is outstanding
+    alerted: bool,
+    /// Since when updates have contradicted `alerted`
+    contradicted_since: Option<Instant>,
+}
+
+impl DivergenceMonitor {
+    pub fn new(threshold_pct: f64, sustain: Duration) -> Self {
+        Self { threshold_pct, sustain, symbols: HashMap::new() }
+    }
+
+    /// Record one update of `symbol`, returning an alert when its sustained
+    /// state changes
+    pub fn observe(&mut self, symbol: &str, divergence_pct: Option<f64>, now: Instant) -> Option<DivergenceAlert> {
+        let state = self.symbols.entry(symbol.to_string()).or_default();
+        let over = divergence_pct.filter(|pct| *pct > self.threshold_pct);
+
+        if over.is_some() == state.alerted {
+            state.contradicted_since = None;
+            return None;
+        }
+        let since = *state.contradicted_since.get_or_insert(now);
+        if now.duration_since(since) < self.sustain {
+            return None;
+        }
+
+        state.contradicted_since = None;
+        state.alerted = over.is_some();
+        Some(match over {
+            Some(divergence_pct) => DivergenceAlert::Started { divergence_pct },
+            None => DivergenceAlert::Cleared,
+        })
+    }
+
+    /// Stop tracking symbols that are no longer streamed
+    pub fn retain(&mut self, symbols: &[String]) {
+        self.symbols.retain(|symbol, _| symbols.contains(symbol));
+    }
+}
+
+fn range(sources: &SourcePrices) -> Option<(f64, f64)> {
+    let prices = sources.values().copied().filter(|p| p.is_finite() && *p > 0.0);
+    prices.fold(None, |range, price| match range {
+        None => Some((price, price)),
+        Some((low, high)) => Some((f64::min(low, price), f64::max(high, price))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources(prices: &[(&str, f64)]) -> SourcePrices {
+        prices.iter().map(|(source, price)| (source.to_string(), *price)).collect()
+    }
+
+    #[test]
+    fn test_divergence() {
+        let agreeing = sources(&[("pyth", 150.0), ("jupiter", 150.9)]);
+        assert_eq!(mid_price(&agreeing), Some(150.45));
+        assert!(divergence_pct(&agreeing).unwrap() < 1.0);
+        assert!(!is_divergent(&agreeing, DEFAULT_DIVERGENCE_THRESHOLD_PCT));
+
+        // A stablecoin slipping off its peg on one source
+        let depeg = sources(&[("pyth", 0.97), ("jupiter", 1.0)]);
+        assert!((divergence_pct(&depeg).unwrap() - 3.0457).abs() < 1e-3);
+        assert!(is_divergent(&depeg, DEFAULT_DIVERGENCE_THRESHOLD_PCT));
+        assert!(!is_divergent(&depeg, 5.0));
+    }
+
+    #[test]
+    fn test_single_or_bad_sources_never_diverge() {
+        let single = sources(&[("jupiter", 150.0)]);
+        assert_eq!(mid_price(&single), Some(150.0));
+        assert_eq!(divergence_pct(&single), None);
+        assert!(!is_divergent(&SourcePrices::new(), 0.0));
+        assert_eq!(mid_price(&SourcePrices::new()), None);
+
+        // A zero price is a broken quote, not a 100% divergence
+        let broken = sources(&[("pyth", 0.0), ("jupiter", 150.0)]);
+        assert_eq!(divergence_pct(&broken), Some(0.0));
+    }
+    #[test]
+    fn test_divergence_alerts_only_when_sustained() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut monitor = DivergenceMonitor::new(1.0, Duration::from_secs(30));
+
+        // Single ticks over the threshold don't alert
+        assert_eq!(monitor.observe("SOL", Some(2.5), at(0)), None);
+        assert_eq!(monitor.observe("SOL", Some(0.2), at(5)), None);
+        assert_eq!(monitor.observe("SOL", Some(2.5), at(10)), None);
+        assert_eq!(monitor.observe("SOL", Some(2.6), at(39)), None);
+
+        // 30s over in a row does, once
+        assert_eq!(
+            monitor.observe("SOL", Some(2.7), at(40)),
+            Some(DivergenceAlert::Started { divergence_pct: 2.7 })
+        );
+        assert_eq!(monitor.observe("SOL", Some(3.0), at(80)), None);
+
+        // Other symbols are independent
+        assert_eq!(monitor.observe("USDC", Some(0.1), at(80)), None);
+    }
+
+    #[test]
+    fn test_divergence_clears_only_when_sustained() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut monitor = DivergenceMonitor::new(1.0, Duration::from_secs(30));
+        monitor.observe("SOL", Some(2.0), at(0));
+        assert!(monitor.observe("SOL", Some(2.0), at(30)).is_some());
+
+        // Dipping under for a tick keeps the alert
+        assert_eq!(monitor.observe("SOL", Some(0.5), at(31)), None);
+        assert_eq!(monitor.observe("SOL", Some(2.0), at(45)), None);
+        assert_eq!(monitor.observe("SOL", Some(0.5), at(50)), None);
+
+        // Losing the second source counts as agreeing
+        assert_eq!(monitor.observe("SOL", None, at(70)), None);
+        assert_eq!(monitor.observe("SOL", Some(0.4), at(80)), Some(DivergenceAlert::Cleared));
+        assert_eq!(monitor.observe("SOL", Some(0.4), at(200)), None);
+    }
+}
@@ -0,0 +1,233 @@
+//! # Return Analytics
+//!
+//! Historical volatility and return correlations from daily closes.
+//!
+//! ## Method
+//!
+//! - **Returns**: daily log returns `ln(close[t] / close[t-1])`
+//! - **Volatility**: sample standard deviation of the returns, annualized by
+//!   `sqrt(365)` since tokens trade every day
+//! - **Correlation**: Pearson correlation of two symbols' returns on the days
+//!   both have a close
+//!
+//! A window of `n` days needs `n` returns, so `n + 1` closes. Series with
+//! fewer are reported as insufficient instead of being computed over
+//! whatever history exists, which would make young tokens look calmer or
+//! more correlated than they are.
+//!
+//! Everything here is pure so it can be checked against known answers.
+
+/// Return periods per year for daily data
+pub const DAILY_PERIODS_PER_YEAR: f64 = 365.0;
+
+/// Volatility of one series over a window
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolatilityEstimate {
+    /// Annualized volatility as a fraction; `None` when history is insufficient
+    pub volatility: Option<f64>,
+    /// Returns available within the window
+    pub observations: usize,
+    pub insufficient_history: bool,
+}
+
+/// Log returns of consecutive closes. `None` if any close is not a positive, finite number.
+pub fn log_returns(closes: &[f64]) -> Option<Vec<f64>> {
+    if closes.iter().any(|c| !(c.is_finite() && *c > 0.0)) {
+        return None;
+    }
+    Some(closes.windows(2).map(|w| (w[1] / w[0]).ln()).collect())
+}
+
+/// Sample standard deviation (n - 1 denominator); needs at least two values
+pub fn sample_std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    Some(variance.sqrt())
+}
+
+/// Standard deviation of per-period returns scaled to a year
+pub fn annualized_volatility(returns: &[f64], periods_per_year: f64) -> Option<f64> {
+    sample_std_dev(returns).map(|sd| sd * periods_per_year.sqrt())
+}
+
+/// Pearson correlation of two equally long samples.
+///
+/// `None` for fewer than two pairs or when either side has no variance.
+pub fn pearson_correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() != b.len() || a.len() < 2 {
+        return None;
+    }
+    let n = a.len() as f64;
+    let mean_a = a.iter().sum::<f64>() / n;
+    let mean_b = b.iter().sum::<f64>() / n;
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        let (dx, dy) = (x - mean_a, y - mean_b);
+        cov += dx * dy;
+        var_a += dx * dx;
+        var_b += dy * dy;
+    }
+    if var_a <= f64::EPSILON || var_b <= f64::EPSILON {
+        return None;
+    }
+    Some((cov / (var_a * var_b).sqrt()).clamp(-1.0, 1.0))
+}
+
+/// The last `window + 1` closes of a `(timestamp, close)` series, oldest first
+fn window_closes(series: &[(u64, f64)], window: usize) -> &[(u64, f64)] {
+    &series[series.len().saturating_sub(window + 1)..]
+}
+
+/// Annualized volatility of the last `window` daily returns
+pub fn volatility_over_window(series: &[(u64, f64)], window: usize) -> VolatilityEstimate {
+    let closes: Vec<f64> = window_closes(series, window).iter().map(|(_, close)| *close).collect();
+    let observations = closes.len().saturating_sub(1);
+    let insufficient_history = observations < window || window < 2;
+    let volatility = if insufficient_history {
+        None
+    } else {
+        log_returns(&closes).and_then(|returns| annualized_volatility(&returns, DAILY_PERIODS_PER_YEAR))
+    };
+    VolatilityEstimate { volatility, observations, insufficient_history }
+}
+
+/// Returns of both series over the days they share, oldest first.
+/// Both series must be sorted by timestamp.
+pub fn aligned_log_returns(a: &[(u64, f64)], b: &[(u64, f64)]) -> Option<(Vec<f64>, Vec<f64>)> {
+    let (mut closes_a, mut closes_b) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].0.cmp(&b[j].0) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                closes_a.push(a[i].1);
+                closes_b.push(b[j].1);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    Some((log_returns(&closes_a)?, log_returns(&closes_b)?))
+}
+
+/// Pairwise return correlations over the last `window` days.
+///
+/// A pair is only computed when both series have `window` returns on shared
+/// days; the diagonal is 1 for series with enough history.
+pub fn correlation_matrix(series: &[Vec<(u64, f64)>], window: usize) -> Vec<Vec<Option<f64>>> {
+    let windows: Vec<&[(u64, f64)]> = series.iter().map(|s| window_closes(s, window)).collect();
+    let mut matrix = vec![vec![None; series.len()]; series.len()];
+    for i in 0..series.len() {
+        if window >= 2 && windows[i].len() > window {
+            matrix[i][i] = Some(1.0);
+        }
+        for j in i + 1..series.len() {
+            let correlation = aligned_log_returns(windows[i], windows[j])
+                .filter(|(a, _)| window >= 2 && a.len() >= window)
+                .and_then(|(a, b)| pearson_correlation(&a, &b));
+            matrix[i][j] = correlation;
+            matrix[j][i] = correlation;
+        }
+    }
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    fn daily(closes: &[f64]) -> Vec<(u64, f64)> {
+        closes.iter().enumerate().map(|(i, c)| (i as u64 * DAY, *c)).collect()
+    }
+
+    #[test]
+    fn test_log_returns_and_volatility_known_answer() {
+        let returns = log_returns(&[100.0, 110.0, 99.0, 108.9]).unwrap();
+        assert_close(returns[0], 0.095_310_179_804_324_93);
+        assert_close(returns[1], -0.105_360_515_657_826_28);
+        assert_close(returns[2], 0.095_310_179_804_324_93);
+
+        assert_close(sample_std_dev(&returns).unwrap(), 0.115_857_280_043_542_41);
+        assert_close(annualized_volatility(&returns, DAILY_PERIODS_PER_YEAR).unwrap(), 2.213_450_227_307_370_6);
+
+        assert_eq!(log_returns(&[100.0, 0.0, 99.0]), None);
+        assert_eq!(log_returns(&[100.0, f64::NAN]), None);
+        assert_eq!(sample_std_dev(&[0.01]), None);
+    }
+
+    #[test]
+    fn test_pearson_correlation_known_answer() {
+        let a = [0.01, -0.02, 0.03, 0.0, 0.015];
+        let b = [0.02, -0.01, 0.025, -0.005, 0.01];
+        assert_close(pearson_correlation(&a, &b).unwrap(), 0.900_366_140_265_039_4);
+
+        let up = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_close(pearson_correlation(&up, &[2.0, 4.0, 6.0, 8.0, 10.0]).unwrap(), 1.0);
+        assert_close(pearson_correlation(&up, &[5.0, 4.0, 3.0, 2.0, 1.0]).unwrap(), -1.0);
+        assert_close(pearson_correlation(&[1.0, 2.0, 3.0], &[1.0, 3.0, 2.0]).unwrap(), 0.5);
+
+        // No variance, mismatched or too short samples
+        assert_eq!(pearson_correlation(&up, &[1.0; 5]), None);
+        assert_eq!(pearson_correlation(&up, &[1.0, 2.0]), None);
+        assert_eq!(pearson_correlation(&[1.0], &[1.0]), None);
+    }
+
+    #[test]
+    fn test_short_history_is_flagged_not_computed() {
+        let series = daily(&[100.0, 110.0, 99.0, 108.9]);
+
+        let estimate = volatility_over_window(&series, 3);
+        assert!(!estimate.insufficient_history);
+        assert_eq!(estimate.observations, 3);
+        assert_close(estimate.volatility.unwrap(), 2.213_450_227_307_370_6);
+
+        let estimate = volatility_over_window(&series, 30);
+        assert!(estimate.insufficient_history);
+        assert_eq!(estimate.observations, 3);
+        assert_eq!(estimate.volatility, None);
+    }
+
+    #[test]
+    fn test_volatility_uses_only_the_window() {
+        // An old spike outside the window doesn't count
+        let series = daily(&[1.0, 100.0, 110.0, 99.0, 108.9]);
+        assert_close(volatility_over_window(&series, 3).volatility.unwrap(), 2.213_450_227_307_370_6);
+    }
+
+    #[test]
+    fn test_aligned_log_returns_skips_missing_days() {
+        let a = vec![(0, 100.0), (DAY, 110.0), (2 * DAY, 121.0)];
+        let b = vec![(0, 10.0), (2 * DAY, 12.0)];
+        let (ra, rb) = aligned_log_returns(&a, &b).unwrap();
+        assert_eq!(ra.len(), 1);
+        assert_close(ra[0], (121.0f64 / 100.0).ln());
+        assert_close(rb[0], (12.0f64 / 10.0).ln());
+    }
+
+    #[test]
+    fn test_correlation_matrix() {
+        let sol = daily(&[100.0, 110.0, 99.0, 108.9, 105.0]);
+        let mirror = daily(&[10.0, 11.0, 9.9, 10.89, 10.5]);
+        let young = vec![(3 * DAY, 5.0), (4 * DAY, 6.0)];
+
+        let matrix = correlation_matrix(&[sol, mirror, young], 4);
+        assert_eq!(matrix[0][0], Some(1.0));
+        assert_close(matrix[0][1].unwrap(), 1.0);
+        assert_eq!(matrix[0][1], matrix[1][0]);
+        // Not enough shared history with the young series
+        assert_eq!(matrix[0][2], None);
+        assert_eq!(matrix[2][1], None);
+        assert_eq!(matrix[2][2], None);
+    }
+}
@@ -1,12 +1,14 @@
 //! # Price Caching Module
 //!
 //! This module provides intelligent price caching with multiple data sources.
-//! It implements a fallback strategy: Pyth Network (oracle) → Jupiter API → Mock data.
+//! Pyth Network (oracle) and Jupiter API are asked together and both answers
+//! are kept per source; the primary price falls back Pyth → Jupiter.
 //!
 //! ## Features
 //! - Automatic cache expiration (configurable TTL)
 //! - Background refresh for popular tokens
 //! - Multi-source fallback for reliability
+//! - Per-source prices, for comparing the sources (see [`crate::aggregate`])
 //! - Thread-safe concurrent access
 //!
 //! ## Example
@@ -15,12 +17,28 @@
 //! let price = cache.get_price("SOL").await?;
 //! println!("SOL price: ${}", price.price);
 //! ```
+//!
+//! ## Response Caches
+//!
+//! [`TtlCache`] caches whole endpoint responses, keyed by the request shape
+//! (the symbol set for prices, symbol/timeframe/limit for candles). Concurrent
+//! misses on the same key are single-flight: the first caller fetches while
+//! the rest wait for its answer, so a burst of identical requests from
+//! several terminals costs one upstream call. [`MarketCaches`] holds one per
+//! market endpoint, with TTLs from [`MarketCacheTtls::from_env`].
 
+use crate::aggregate::SourcePrices;
 use crate::jupiter::JupiterClient;
-use crate::pyth::PythClient;
-use crate::types::PriceData;
+use crate::pyth::{PythClient, PythPrice};
+use crate::types::{PriceData, PriceResponse};
+use lib_core::dto::market::OHLC;
+use shared::dto::market::TokenListItem;
+use shared::dto::system::CacheStats;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
@@ -31,10 +49,14 @@ struct CachedPrice {
     price: f64,
     /// Confidence interval (only available from some sources)
     confidence: Option<f64>,
+    /// When the oracle published the price (only available from Pyth)
+    publish_time: Option<i64>,
     /// 24-hour price change percentage
     change_24h: Option<f64>,
     /// Data source identifier (e.g., "pyth", "jupiter")
     source: String,
+    /// Price reported by each source that answered
+    sources: SourcePrices,
     /// When this price was cached
     timestamp: Instant,
     /// How long until this cache entry expires
@@ -66,6 +88,35 @@ fn get_unix_timestamp() -> u64 {
         })
 }
 
+/// Price data from what each source answered, Pyth first; `None` if neither did
+///
+/// `divergent` is left for the caller, which knows the threshold.
+fn combine_sources(pyth: Option<PythPrice>, jupiter: Option<f64>, now: u64) -> Option<PriceData> {
+    let mut sources = SourcePrices::new();
+    if let Some(pyth) = &pyth {
+        sources.insert("pyth".to_string(), pyth.price);
+    }
+    if let Some(jupiter) = jupiter {
+        sources.insert("jupiter".to_string(), jupiter);
+    }
+
+    let (price, confidence, source, publish_time) = match (pyth, jupiter) {
+        (Some(pyth), _) => (pyth.price, Some(pyth.confidence), "pyth", Some(pyth.publish_time)),
+        (None, Some(jupiter)) => (jupiter, None, "jupiter", None),
+        (None, None) => return None,
+    };
+    Some(PriceData {
+        price,
+        confidence,
+        source: source.into(),
+        change_24h: None,
+        last_updated: now,
+        publish_time,
+        sources,
+        divergent: false,
+    })
+}
+
 impl PriceCache {
     pub fn new(jupiter: Arc<JupiterClient>, pyth: Arc<PythClient>) -> Self {
         Self {
@@ -106,6 +157,9 @@ impl PriceCache {
                         source: cached.source.clone(),
                         change_24h: cached.change_24h,
                         last_updated: get_unix_timestamp(),
+                        publish_time: cached.publish_time,
+                        sources: cached.sources.clone(),
+                        divergent: false,
                     });
                 } else {
                     debug!("Cache expired for {}", symbol);
@@ -124,8 +178,10 @@ impl PriceCache {
                 CachedPrice {
                     price: price_data.price,
                     confidence: price_data.confidence,
+                    publish_time: price_data.publish_time,
                     change_24h: price_data.change_24h,
                     source: price_data.source.clone(),
+                    sources: price_data.sources.clone(),
                     timestamp: Instant::now(),
                     // TODO: Make TTL configurable via config system
                     ttl: Duration::from_secs(10),
@@ -136,56 +192,47 @@ impl PriceCache {
         Ok(price_data)
     }
 
-    /// Fetch fresh price data from external sources.
+    /// Fetch fresh price data from every source.
     ///
-    /// Implements a fallback strategy:
-    /// 1. Try Pyth Network (on-chain oracle - most reliable)
-    /// 2. Fall back to Jupiter API (aggregator)
-    /// 3. Return error if both fail
+    /// Pyth and Jupiter are queried concurrently and both answers are kept in
+    /// `sources`. The primary price is still chosen by priority:
+    /// 1. Pyth Network (on-chain oracle - most reliable)
+    /// 2. Jupiter API (aggregator)
     ///
     /// # Arguments
     /// * `symbol` - Token symbol to fetch price for
     ///
     /// # Returns
-    /// * `Ok(PriceData)` - Fresh price data from first available source
+    /// * `Ok(PriceData)` - Fresh price data from the highest priority source that answered
     /// * `Err(_)` - If all sources fail
     async fn fetch_fresh(&self, symbol: &str) -> anyhow::Result<PriceData> {
-        // Priority 1: Pyth Network (real on-chain oracle data)
-        // This is the most reliable source as it's an on-chain oracle
-        match self.pyth.get_price(symbol).await {
-            Ok(pyth_price) => {
-                info!("REAL LIVE DATA - Pyth: {} = ${:.4}", symbol, pyth_price);
-                return Ok(PriceData {
-                    price: pyth_price,
-                    confidence: None,
-                    source: "pyth".into(),
-                    change_24h: None,
-                    last_updated: get_unix_timestamp(),
-                });
+        let (pyth, jupiter) = tokio::join!(self.pyth.get_price(symbol), self.jupiter.get_price(symbol));
+
+        let pyth = match pyth {
+            Ok(price) => {
+                info!("REAL LIVE DATA - Pyth: {} = ${:.4} ± {:.4}", symbol, price.price, price.confidence);
+                Some(price)
             }
             Err(e) => {
-                debug!("Pyth failed for {}, trying Jupiter: {}", symbol, e);
+                debug!("Pyth failed for {}: {}", symbol, e);
+                None
             }
-        }
-
-        // Priority 2: Jupiter API fallback
-        // Jupiter aggregates from multiple DEXes
-        match self.jupiter.get_price(symbol).await {
-            Ok(jup_price) => {
-                debug!("Jupiter price for {}: ${:.4}", symbol, jup_price);
-                Ok(PriceData {
-                    price: jup_price,
-                    confidence: None,
-                    source: "jupiter".into(),
-                    change_24h: None,
-                    last_updated: get_unix_timestamp(),
-                })
+        };
+        let jupiter = match jupiter {
+            Ok(price) => {
+                debug!("Jupiter price for {}: ${:.4}", symbol, price);
+                Some(price)
             }
             Err(e) => {
-                warn!("All APIs failed for {}: {}", symbol, e);
-                Err(anyhow::anyhow!("No price data available for {}", symbol))
+                debug!("Jupiter failed for {}: {}", symbol, e);
+                None
             }
-        }
+        };
+
+        combine_sources(pyth, jupiter, get_unix_timestamp()).ok_or_else(|| {
+            warn!("All APIs failed for {}", symbol);
+            anyhow::anyhow!("No price data available for {}", symbol)
+        })
     }
 
     /// Get multiple prices at once, returning only successful fetches.
@@ -264,3 +311,320 @@ impl PriceCache {
         });
     }
 }
+
+/// A value served by [`TtlCache::get_or_fetch`]
+#[derive(Debug, Clone)]
+pub struct Cached<V> {
+    pub value: V,
+    /// Served from cache rather than fetched by this call
+    pub cached: bool,
+    /// Time since the value was fetched
+    pub age: Duration,
+}
+
+impl<V> Cached<V> {
+    /// Age in whole milliseconds, as reported in responses
+    pub fn age_ms(&self) -> u64 {
+        self.age.as_millis() as u64
+    }
+}
+
+/// One key's slot; its lock is what makes concurrent misses single-flight
+type Slot<V> = Arc<tokio::sync::Mutex<Option<(V, Instant)>>>;
+
+/// Generic response cache with a fixed TTL and single-flight fetches.
+///
+/// Errors are not cached: callers that were waiting on a failed fetch try
+/// again themselves, one at a time.
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    slots: Mutex<HashMap<K, Slot<V>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            slots: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Cached value for `key`, or the result of `fetch` when it is missing or expired.
+    ///
+    /// Only one `fetch` per key runs at a time; callers arriving meanwhile wait
+    /// for it and are then served its value as a hit.
+    pub async fn get_or_fetch<F, Fut, E>(&self, key: K, fetch: F) -> Result<Cached<V>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        let slot = {
+            let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+            Arc::clone(slots.entry(key).or_default())
+        };
+        let mut entry = slot.lock().await;
+
+        if let Some((value, fetched)) = entry.as_ref() {
+            let age = fetched.elapsed();
+            if age < self.ttl {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(Cached { value: value.clone(), cached: true, age });
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = fetch().await?;
+        *entry = Some((value.clone(), Instant::now()));
+        Ok(Cached { value, cached: false, age: Duration::ZERO })
+    }
+
+    /// Drop the cached value for `key`, so the next request fetches it again
+    pub fn invalidate(&self, key: &K) {
+        self.slots.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+    }
+
+    /// Drop expired entries that no fetch is using, returning how many were removed
+    pub fn cleanup(&self) -> usize {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        let before = slots.len();
+        slots.retain(|_, slot| match slot.try_lock() {
+            Ok(entry) => entry.as_ref().is_some_and(|(_, fetched)| fetched.elapsed() < self.ttl),
+            // A fetch is in flight
+            Err(_) => true,
+        });
+        before - slots.len()
+    }
+
+    /// Hit/miss counters for the health report
+    pub fn stats(&self, name: &str) -> CacheStats {
+        CacheStats {
+            name: name.to_string(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.slots.lock().unwrap_or_else(|e| e.into_inner()).len(),
+            ttl_ms: self.ttl.as_millis() as u64,
+        }
+    }
+}
+
+/// Default TTL of the `/api/market/prices` cache
+pub const DEFAULT_PRICES_TTL: Duration = Duration::from_secs(2);
+/// Default TTL of the `/api/market/tokens` cache
+pub const DEFAULT_TOKEN_LIST_TTL: Duration = Duration::from_secs(3600);
+/// Default TTL of the `/api/market/candles` cache
+pub const DEFAULT_OHLC_TTL: Duration = Duration::from_secs(30);
+
+/// Per-endpoint TTLs of the market response caches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketCacheTtls {
+    pub prices: Duration,
+    pub token_list: Duration,
+    pub ohlc: Duration,
+}
+
+impl Default for MarketCacheTtls {
+    fn default() -> Self {
+        Self {
+            prices: DEFAULT_PRICES_TTL,
+            token_list: DEFAULT_TOKEN_LIST_TTL,
+            ohlc: DEFAULT_OHLC_TTL,
+        }
+    }
+}
+
+impl MarketCacheTtls {
+    /// TTLs from `MARKET_CACHE_PRICES_TTL_MS`, `MARKET_CACHE_TOKENS_TTL_MS` and
+    /// `MARKET_CACHE_OHLC_TTL_MS`, falling back to the defaults
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            prices: ttl_from_env("MARKET_CACHE_PRICES_TTL_MS", defaults.prices),
+            token_list: ttl_from_env("MARKET_CACHE_TOKENS_TTL_MS", defaults.token_list),
+            ohlc: ttl_from_env("MARKET_CACHE_OHLC_TTL_MS", defaults.ohlc),
+        }
+    }
+}
+
+fn ttl_from_env(var: &str, default: Duration) -> Duration {
+    std::env::var(var)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(default)
+}
+
+/// Candle cache key: symbol, timeframe and limit
+pub type OhlcKey = (String, String, usize);
+
+/// Response caches of the market endpoints, shared by every request
+pub struct MarketCaches {
+    /// Keyed by the sorted, deduplicated symbol set
+    pub prices: TtlCache<Vec<String>, PriceResponse>,
+    pub token_list: TtlCache<(), Vec<TokenListItem>>,
+    pub ohlc: TtlCache<OhlcKey, Vec<OHLC>>,
+}
+
+impl MarketCaches {
+    pub fn new(ttls: MarketCacheTtls) -> Self {
+        Self {
+            prices: TtlCache::new(ttls.prices),
+            token_list: TtlCache::new(ttls.token_list),
+            ohlc: TtlCache::new(ttls.ohlc),
+        }
+    }
+
+    /// Cache key for a price request; order and repeats don't change the answer
+    pub fn prices_key(symbols: &[&str]) -> Vec<String> {
+        let mut key: Vec<String> = symbols.iter().map(|s| s.to_string()).collect();
+        key.sort();
+        key.dedup();
+        key
+    }
+
+    pub fn stats(&self) -> Vec<CacheStats> {
+        vec![
+            self.prices.stats("prices"),
+            self.token_list.stats("token_list"),
+            self.ohlc.stats("ohlc"),
+        ]
+    }
+
+    /// Periodically drop expired entries so one-off symbol sets don't pile up
+    pub fn spawn_cleanup(self: &Arc<Self>, every: Duration) {
+        let caches = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let removed = caches.prices.cleanup() + caches.token_list.cleanup() + caches.ohlc.cleanup();
+                if removed > 0 {
+                    debug!("Dropped {} expired market cache entries", removed);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine_sources_prefers_pyth_and_keeps_both() {
+        let pyth = PythPrice { price: 150.2, confidence: 0.08, publish_time: 1_704_067_199 };
+        let data = combine_sources(Some(pyth), Some(150.0), 1_704_067_200).unwrap();
+        assert_eq!(data.price, 150.2);
+        assert_eq!(data.source, "pyth");
+        assert_eq!(data.publish_time, Some(1_704_067_199));
+        assert_eq!(data.sources.get("pyth"), Some(&150.2));
+        assert_eq!(data.sources.get("jupiter"), Some(&150.0));
+
+        let data = combine_sources(None, Some(150.0), 1_704_067_200).unwrap();
+        assert_eq!((data.price, data.source.as_str(), data.confidence), (150.0, "jupiter", None));
+        assert_eq!(data.sources.len(), 1);
+
+        assert!(combine_sources(None, None, 1_704_067_200).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_ttl_cache_single_flight() {
+        let cache = Arc::new(TtlCache::<&str, u32>::new(Duration::from_secs(60)));
+        let fetches = Arc::new(AtomicU64::new(0));
+
+        let requests: Vec<_> = (0..20)
+            .map(|_| {
+                let cache = Arc::clone(&cache);
+                let fetches = Arc::clone(&fetches);
+                tokio::spawn(async move {
+                    cache
+                        .get_or_fetch("SOL", || async move {
+                            fetches.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Ok::<_, String>(150)
+                        })
+                        .await
+                })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for request in requests {
+            results.push(request.await.unwrap().unwrap());
+        }
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert!(results.iter().all(|r| r.value == 150));
+        assert_eq!(results.iter().filter(|r| !r.cached).count(), 1);
+
+        let stats = cache.stats("prices");
+        assert_eq!((stats.hits, stats.misses, stats.entries), (19, 1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_ttl_cache_keys_fetch_independently() {
+        let cache = Arc::new(TtlCache::<String, String>::new(Duration::from_secs(60)));
+        let fetches = Arc::new(AtomicU64::new(0));
+
+        let requests = ["SOL", "USDC", "SOL", "USDC"].map(|symbol| {
+            let cache = Arc::clone(&cache);
+            let fetches = Arc::clone(&fetches);
+            tokio::spawn(async move {
+                cache
+                    .get_or_fetch(symbol.to_string(), || async move {
+                        fetches.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        Ok::<_, String>(symbol.to_string())
+                    })
+                    .await
+                    .unwrap()
+                    .value
+            })
+        });
+        let mut values = Vec::new();
+        for request in requests {
+            values.push(request.await.unwrap());
+        }
+
+        assert_eq!(values, ["SOL", "USDC", "SOL", "USDC"]);
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_ttl_cache_expires_and_does_not_cache_errors() {
+        let cache = TtlCache::<(), u32>::new(Duration::from_millis(30));
+
+        let failed = cache.get_or_fetch((), || async { Err::<u32, _>("rate limited") }).await;
+        assert_eq!(failed.unwrap_err(), "rate limited");
+
+        let first = cache.get_or_fetch((), || async { Ok::<_, String>(1) }).await.unwrap();
+        assert!(!first.cached);
+        let hit = cache.get_or_fetch((), || async { Ok::<_, String>(2) }).await.unwrap();
+        assert!(hit.cached);
+        assert_eq!(hit.value, 1);
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(cache.cleanup(), 1);
+        let refetched = cache.get_or_fetch((), || async { Ok::<_, String>(3) }).await.unwrap();
+        assert_eq!((refetched.value, refetched.cached), (3, false));
+
+        let stats = cache.stats("token_list");
+        assert_eq!((stats.hits, stats.misses), (1, 3));
+    }
+
+    #[test]
+    fn test_prices_key_ignores_order_and_repeats() {
+        assert_eq!(MarketCaches::prices_key(&["USDC", "SOL", "SOL"]), ["SOL", "USDC"]);
+    }
+}
@@ -3,10 +3,19 @@
 //! Aggregates real-time price updates into OHLC (Open, High, Low, Close) candlestick data
 //! for multiple timeframes. This service receives price updates from the websocket stream
 //! and automatically creates/updates candles based on the timeframe.
+//!
+//! When a symbol is removed from the stream its series is ended with
+//! [`CandleAggregator::end_series`]: open candles are closed into history and
+//! late updates are ignored until the symbol is streamed again.
+//!
+//! Every change to a candle is also published as a [`CandleUpdate`] (see
+//! [`CandleAggregator::subscribe`]): the forming candle on each price tick
+//! with `is_closed: false`, and the final one when its period closes.
 
-use std::collections::HashMap;
+use shared::dto::market::{CandleUpdate, OHLC};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{trace, debug, info};
 
 /// Timeframe for candle aggregation
@@ -44,8 +53,24 @@ impl Timeframe {
             Timeframe::OneDay => "1d",
         }
     }
+
+    /// Same timeframe as sent to clients
+    pub fn to_dto(&self) -> shared::dto::market::Timeframe {
+        use shared::dto::market::Timeframe as Dto;
+        match self {
+            Timeframe::OneMinute => Dto::OneMinute,
+            Timeframe::FiveMinutes => Dto::FiveMinutes,
+            Timeframe::FifteenMinutes => Dto::FifteenMinutes,
+            Timeframe::OneHour => Dto::OneHour,
+            Timeframe::FourHours => Dto::FourHours,
+            Timeframe::OneDay => Dto::OneDay,
+        }
+    }
 }
 
+/// Candle updates buffered per subscriber before it starts lagging
+const UPDATE_BUFFER: usize = 1024;
+
 /// OHLC candle data
 #[derive(Debug, Clone)]
 pub struct Candle {
@@ -57,6 +82,16 @@ pub struct Candle {
     pub volume: f64,
 }
 
+impl Candle {
+    fn to_ohlc(&self) -> OHLC {
+        OHLC::new(self.timestamp as i64, self.open, self.high, self.low, self.close, self.volume)
+    }
+}
+
+/// Candle that changed on a price update: its timeframe, the candle and
+/// whether it is closed
+type ChangedCandle = (Timeframe, Candle, bool);
+
 /// Current candle being built for a symbol/timeframe
 #[derive(Debug, Clone)]
 struct CurrentCandle {
@@ -132,7 +167,13 @@ impl SymbolCandles {
         }
     }
 
-    fn add_price_update(&mut self, price: f64, timestamp: u64, symbol: &str) {
+    /// Apply a price to every timeframe
+    ///
+    /// # Returns
+    /// The changed candles: for each timeframe the previous candle if this
+    /// price closed it, then the forming one
+    fn add_price_update(&mut self, price: f64, timestamp: u64, symbol: &str) -> Vec<ChangedCandle> {
+        let mut changed = Vec::new();
         // Update all timeframes
         for timeframe in &[
             Timeframe::OneMinute,
@@ -158,6 +199,7 @@ impl SymbolCandles {
                     let completed_candle = prev_candle.to_candle();
                     let completed = self.completed.entry(*timeframe).or_default();
                     completed.push(completed_candle.clone());
+                    changed.push((*timeframe, completed_candle.clone(), true));
                     
                     // Log candle completion
                     info!(
@@ -187,11 +229,13 @@ impl SymbolCandles {
                     open = price,
                     "New candle created"
                 );
+                changed.push((*timeframe, new_candle.to_candle(), false));
                 self.current.insert(*timeframe, new_candle);
             } else {
                 // Update current candle
                 if let Some(current) = self.current.get_mut(timeframe) {
                     current.update(price);
+                    changed.push((*timeframe, current.to_candle(), false));
                     trace!(
                         symbol = %symbol,
                         timeframe = %timeframe.label(),
@@ -201,6 +245,25 @@ impl SymbolCandles {
                 }
             }
         }
+        changed
+    }
+
+    /// Close every open candle into the completed history
+    ///
+    /// # Returns
+    /// The candles closed
+    fn close_all(&mut self) -> Vec<ChangedCandle> {
+        let mut closed = Vec::new();
+        for (timeframe, current) in self.current.drain() {
+            let candle = current.to_candle();
+            let completed = self.completed.entry(timeframe).or_default();
+            completed.push(candle.clone());
+            if completed.len() > self.max_candles {
+                completed.remove(0);
+            }
+            closed.push((timeframe, candle, true));
+        }
+        closed
     }
 
     fn get_candles(&self, timeframe: Timeframe, limit: usize) -> Vec<Candle> {
@@ -240,6 +303,10 @@ pub struct CandleAggregator {
     candles: Arc<RwLock<HashMap<String, SymbolCandles>>>,
     /// Maximum number of candles to keep per symbol/timeframe
     max_candles: usize,
+    /// Symbols whose series were ended (updates are ignored)
+    ended: Arc<RwLock<HashSet<String>>>,
+    /// Publishes every forming and closed candle
+    update_tx: broadcast::Sender<CandleUpdate>,
 }
 
 impl CandleAggregator {
@@ -249,9 +316,36 @@ impl CandleAggregator {
     /// * `max_candles` - Maximum number of candles to keep per symbol/timeframe (default: 500)
     pub fn new(max_candles: usize) -> Self {
         info!(max_candles = max_candles, "Candle aggregator created");
+        let (update_tx, _) = broadcast::channel(UPDATE_BUFFER);
         Self {
             candles: Arc::new(RwLock::new(HashMap::new())),
             max_candles,
+            ended: Arc::new(RwLock::new(HashSet::new())),
+            update_tx,
+        }
+    }
+
+    /// Receive every candle change from now on
+    ///
+    /// A price update yields the forming candle of each timeframe
+    /// (`is_closed: false`), preceded by the previous candle
+    /// (`is_closed: true`) when it started a new period.
+    pub fn subscribe(&self) -> broadcast::Receiver<CandleUpdate> {
+        self.update_tx.subscribe()
+    }
+
+    fn publish(&self, symbol: &str, changed: Vec<ChangedCandle>) {
+        // Without subscribers there is no one to tell
+        if self.update_tx.receiver_count() == 0 {
+            return;
+        }
+        for (timeframe, candle, is_closed) in changed {
+            let _ = self.update_tx.send(CandleUpdate {
+                symbol: symbol.to_string(),
+                timeframe: timeframe.to_dto(),
+                candle: candle.to_ohlc(),
+                is_closed,
+            });
         }
     }
 
@@ -263,6 +357,10 @@ impl CandleAggregator {
     /// * `timestamp` - Unix timestamp in seconds
     pub async fn add_price_update(&self, symbol: &str, price: f64, timestamp: u64) {
         let symbol_upper = symbol.to_uppercase();
+        if self.ended.read().await.contains(&symbol_upper) {
+            trace!(symbol = %symbol_upper, "Ignoring price update for ended series");
+            return;
+        }
         let mut candles = self.candles.write().await;
         
         let is_new_symbol = !candles.contains_key(&symbol_upper);
@@ -274,11 +372,42 @@ impl CandleAggregator {
             info!(symbol = %symbol_upper, "First price update received for symbol, initializing candles");
         }
         
-        symbol_candles.add_price_update(price, timestamp, &symbol_upper);
-        
+        let changed = symbol_candles.add_price_update(price, timestamp, &symbol_upper);
+        drop(candles);
+        self.publish(&symbol_upper, changed);
+
         trace!(symbol = %symbol_upper, price = price, timestamp = timestamp, "Updated candles for symbol");
     }
 
+    /// End a symbol's candle series.
+    ///
+    /// Open candles are closed into history (so charts keep the final bars)
+    /// and further updates are ignored until [`Self::resume_series`].
+    ///
+    /// # Returns
+    /// `true` if the symbol had candles
+    pub async fn end_series(&self, symbol: &str) -> bool {
+        let symbol_upper = symbol.to_uppercase();
+        self.ended.write().await.insert(symbol_upper.clone());
+
+        let mut candles = self.candles.write().await;
+        match candles.get_mut(&symbol_upper) {
+            Some(symbol_candles) => {
+                let closed = symbol_candles.close_all();
+                drop(candles);
+                self.publish(&symbol_upper, closed);
+                info!(symbol = %symbol_upper, "Candle series ended");
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Accept updates again for a symbol whose series was ended
+    pub async fn resume_series(&self, symbol: &str) {
+        self.ended.write().await.remove(&symbol.to_uppercase());
+    }
+
     /// Get candles for a symbol and timeframe
     ///
     /// # Arguments
@@ -340,6 +469,28 @@ mod tests {
         assert_eq!(candle.close, 102.0);
     }
 
+    #[tokio::test]
+    async fn test_end_series_closes_candles_and_ignores_late_updates() {
+        let aggregator = CandleAggregator::new(100);
+        let base_time = 1000000;
+
+        aggregator.add_price_update("SOL", 100.0, base_time).await;
+        assert!(aggregator.end_series("sol").await);
+
+        // Late update from an in-flight poll is dropped
+        aggregator.add_price_update("SOL", 500.0, base_time + 10).await;
+        let candles = aggregator.get_candles("SOL", Timeframe::OneMinute, 10).await;
+        assert_eq!(candles.len(), 1);
+        assert_eq!(candles[0].close, 100.0);
+
+        // Re-added symbols start a fresh candle
+        aggregator.resume_series("SOL").await;
+        aggregator.add_price_update("SOL", 101.0, base_time + 20).await;
+        let candles = aggregator.get_candles("SOL", Timeframe::OneMinute, 10).await;
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[1].open, 101.0);
+    }
+
     #[tokio::test]
     async fn test_multiple_timeframes() {
         let aggregator = CandleAggregator::new(100);
@@ -354,5 +505,43 @@ mod tests {
         assert!(one_min.is_some());
         assert!(one_hour.is_some());
     }
-}
 
+    #[tokio::test]
+    async fn test_forming_and_closed_updates_are_published() {
+        let aggregator = CandleAggregator::new(100);
+        let mut updates = aggregator.subscribe();
+        let base_time = 1_000_020; // start of a minute
+
+        aggregator.add_price_update("sol", 100.0, base_time).await;
+        aggregator.add_price_update("SOL", 102.0, base_time + 10).await;
+        let minute = |update: &CandleUpdate| update.timeframe == shared::dto::market::Timeframe::OneMinute;
+
+        // Every tick publishes the forming candle of each timeframe
+        let mut received = Vec::new();
+        while let Ok(update) = updates.try_recv() {
+            received.push(update);
+        }
+        assert_eq!(received.len(), 12);
+        let forming: Vec<_> = received.iter().filter(|u| minute(u)).collect();
+        assert_eq!(forming.len(), 2);
+        assert!(forming.iter().all(|u| u.symbol == "SOL" && !u.is_closed));
+        assert_eq!(forming[1].candle.timestamp, 1_000_020);
+        assert_eq!((forming[1].candle.open, forming[1].candle.close), (100.0, 102.0));
+
+        // The next minute closes the previous candle before the new one forms
+        aggregator.add_price_update("SOL", 103.0, base_time + 60).await;
+        let received: Vec<_> = std::iter::from_fn(|| updates.try_recv().ok()).filter(|u| minute(u)).collect();
+        assert_eq!(received.len(), 2);
+        assert!(received[0].is_closed);
+        assert_eq!(received[0].candle.close, 102.0);
+        assert!(!received[1].is_closed);
+        assert_eq!(received[1].candle.timestamp, 1_000_080);
+        assert_eq!(received[1].candle.open, 103.0);
+
+        // Ending the series closes every open candle
+        aggregator.end_series("SOL").await;
+        let closed: Vec<_> = std::iter::from_fn(|| updates.try_recv().ok()).collect();
+        assert_eq!(closed.len(), 6);
+        assert!(closed.iter().all(|u| u.is_closed));
+    }
+}
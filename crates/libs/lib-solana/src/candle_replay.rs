@@ -0,0 +1,299 @@
+//! # Candle Replay
+//!
+//! Rebuilds candles from a [`tick_journal`](crate::tick_journal) range with a
+//! scratch [`CandleAggregator`] and diffs them against the candles the live
+//! aggregator is serving. Used to reproduce "this candle is wrong" reports
+//! and to check a fix against the same ticks.
+//!
+//! ## Orders
+//!
+//! - [`ReplayOrder::Arrival`] feeds ticks in the order the stream received
+//!   them, reproducing exactly what the live aggregator computed. A diff in
+//!   this order means the stored candles don't follow from their own input.
+//! - [`ReplayOrder::EventTime`] sorts ticks by timestamp first, giving the
+//!   candles the aggregator *should* have built. A diff only in this order
+//!   points at ordering bugs (late or out-of-order ticks).
+//!
+//! Only whole candles inside the range are compared: the range is narrowed to
+//! timeframe boundaries, and the live aggregator's open candle is left out.
+
+use crate::candle_aggregator::{Candle, CandleAggregator, Timeframe};
+use crate::tick_journal::{JournalContents, TickRecord};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Relative difference below which two prices count as equal
+const PRICE_TOLERANCE: f64 = 1e-9;
+
+/// Order ticks are replayed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayOrder {
+    /// As the stream received them (what the live aggregator saw)
+    Arrival,
+    /// Sorted by tick timestamp (what it should have computed)
+    #[default]
+    EventTime,
+}
+
+/// OHLCV values of one candle in a report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandleValues {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl From<&Candle> for CandleValues {
+    fn from(candle: &Candle) -> Self {
+        Self { open: candle.open, high: candle.high, low: candle.low, close: candle.close, volume: candle.volume }
+    }
+}
+
+/// How a stored candle differs from the replayed one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Mismatch {
+    /// Replay built a candle the live aggregator doesn't have
+    Missing { replayed: CandleValues },
+    /// The live aggregator has a candle no journaled tick falls in
+    Unexpected { stored: CandleValues },
+    /// Both have the candle with different values (the last stored copy is compared)
+    Different { fields: Vec<String>, replayed: CandleValues, stored: CandleValues },
+    /// The live aggregator has more candles for the same period than the replay
+    Duplicate { count: usize },
+}
+
+/// One mismatching candle period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandleMismatch {
+    /// Candle start, Unix seconds
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub mismatch: Mismatch,
+}
+
+/// Result of replaying a journal range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayReport {
+    pub symbol: String,
+    pub timeframe: String,
+    pub order: ReplayOrder,
+    /// Compared range after narrowing to whole candles, Unix seconds
+    pub from: u64,
+    pub to: u64,
+    /// Journaled ticks in the range
+    pub ticks: usize,
+    /// Candles built by the replay / served by the live aggregator, in range
+    pub replayed: usize,
+    pub stored: usize,
+    /// Journal sampling interval; above 1 the volumes (and possibly prices) can't match
+    pub sample_every: u64,
+    pub mismatches: Vec<CandleMismatch>,
+}
+
+/// Narrow `from..to` to whole candles of `timeframe`
+pub fn candle_range(timeframe: Timeframe, from: u64, to: u64) -> (u64, u64) {
+    let duration = timeframe.seconds();
+    let from = from.div_ceil(duration) * duration;
+    let to = (to / duration) * duration;
+    (from, to.max(from))
+}
+
+/// Run `ticks` for `symbol` through a fresh aggregator
+pub async fn replay_candles(ticks: &[TickRecord], symbol: &str, timeframe: Timeframe, order: ReplayOrder) -> Vec<Candle> {
+    let mut ticks: Vec<&TickRecord> = ticks.iter().filter(|t| t.symbol.eq_ignore_ascii_case(symbol)).collect();
+    if order == ReplayOrder::EventTime {
+        // Stable: ticks with the same timestamp keep their arrival order
+        ticks.sort_by_key(|t| t.timestamp);
+    }
+
+    let scratch = CandleAggregator::new(usize::MAX);
+    for tick in &ticks {
+        scratch.add_price_update(symbol, tick.price, tick.timestamp).await;
+    }
+    scratch.get_candles(symbol, timeframe, usize::MAX).await
+}
+
+fn same_price(a: f64, b: f64) -> bool {
+    (a - b).abs() <= PRICE_TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
+
+fn differing_fields(replayed: &Candle, stored: &Candle) -> Vec<String> {
+    [
+        ("open", replayed.open, stored.open),
+        ("high", replayed.high, stored.high),
+        ("low", replayed.low, stored.low),
+        ("close", replayed.close, stored.close),
+        ("volume", replayed.volume, stored.volume),
+    ]
+    .into_iter()
+    .filter(|(_, a, b)| !same_price(*a, *b))
+    .map(|(name, _, _)| name.to_string())
+    .collect()
+}
+
+/// Compare replayed and stored candles starting in `from..to`
+pub fn diff_candles(replayed: &[Candle], stored: &[Candle], from: u64, to: u64) -> Vec<CandleMismatch> {
+    let in_range = |c: &&Candle| (from..to).contains(&c.timestamp);
+    let mut periods: BTreeMap<u64, (Vec<&Candle>, Vec<&Candle>)> = BTreeMap::new();
+    for candle in replayed.iter().filter(in_range) {
+        periods.entry(candle.timestamp).or_default().0.push(candle);
+    }
+    for candle in stored.iter().filter(in_range) {
+        periods.entry(candle.timestamp).or_default().1.push(candle);
+    }
+
+    let mut mismatches = Vec::new();
+    for (timestamp, (replayed, stored)) in periods {
+        // An arrival-order replay repeats the live aggregator's duplicates
+        if stored.len() > 1 && stored.len() != replayed.len() {
+            mismatches.push(CandleMismatch { timestamp, mismatch: Mismatch::Duplicate { count: stored.len() } });
+        }
+        let mismatch = match (replayed.last(), stored.last()) {
+            (Some(replayed), None) => Mismatch::Missing { replayed: (*replayed).into() },
+            (None, Some(stored)) => Mismatch::Unexpected { stored: (*stored).into() },
+            (Some(replayed), Some(stored)) => {
+                let fields = differing_fields(replayed, stored);
+                if fields.is_empty() {
+                    continue;
+                }
+                Mismatch::Different { fields, replayed: (*replayed).into(), stored: (*stored).into() }
+            }
+            (None, None) => continue,
+        };
+        mismatches.push(CandleMismatch { timestamp, mismatch });
+    }
+    mismatches
+}
+
+/// Replay the journaled ticks of `symbol` over `from..to` and diff the
+/// result against `stored`, the live aggregator's candles.
+pub async fn replay_and_diff(
+    journal: &JournalContents,
+    stored: &[Candle],
+    symbol: &str,
+    timeframe: Timeframe,
+    order: ReplayOrder,
+    from: u64,
+    to: u64,
+) -> ReplayReport {
+    let (from, to) = candle_range(timeframe, from, to);
+    let ticks: Vec<TickRecord> = journal.ticks.iter().filter(|t| (from..to).contains(&t.timestamp)).cloned().collect();
+    let replayed = replay_candles(&ticks, symbol, timeframe, order).await;
+    let mismatches = diff_candles(&replayed, stored, from, to);
+
+    let count = |candles: &[Candle]| candles.iter().filter(|c| (from..to).contains(&c.timestamp)).count();
+    ReplayReport {
+        symbol: symbol.to_uppercase(),
+        timeframe: timeframe.label().to_string(),
+        order,
+        from,
+        to,
+        ticks: ticks.len(),
+        replayed: count(&replayed),
+        stored: count(stored),
+        sample_every: journal.sample_every,
+        mismatches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: u64 = 1_718_006_400; // Aligned to the day
+
+    fn tick(seq: u64, offset: u64, price: f64) -> TickRecord {
+        TickRecord { received_ms: seq, timestamp: BASE + offset, source: "jupiter".to_string(), symbol: "SOL".to_string(), price }
+    }
+
+    fn journal(ticks: &[TickRecord]) -> JournalContents {
+        JournalContents { ticks: ticks.to_vec(), sample_every: 1, malformed: 0 }
+    }
+
+    /// What the live aggregator stores for ticks in arrival order
+    async fn live(ticks: &[TickRecord]) -> Vec<Candle> {
+        let aggregator = CandleAggregator::new(500);
+        for tick in ticks {
+            aggregator.add_price_update(&tick.symbol, tick.price, tick.timestamp).await;
+        }
+        aggregator.get_candles("SOL", Timeframe::OneMinute, 500).await
+    }
+
+    #[test]
+    fn test_candle_range() {
+        assert_eq!(candle_range(Timeframe::OneMinute, BASE + 10, BASE + 130), (BASE + 60, BASE + 120));
+        assert_eq!(candle_range(Timeframe::OneMinute, BASE, BASE + 120), (BASE, BASE + 120));
+        assert_eq!(candle_range(Timeframe::OneHour, BASE + 10, BASE + 20), (BASE + 3600, BASE + 3600));
+    }
+
+    #[tokio::test]
+    async fn test_clean_journal_replays_exactly() {
+        let ticks = [tick(1, 0, 100.0), tick(2, 20, 104.0), tick(3, 61, 103.0), tick(4, 70, 99.0), tick(5, 125, 101.0)];
+        let stored = live(&ticks).await;
+
+        for order in [ReplayOrder::Arrival, ReplayOrder::EventTime] {
+            let report = replay_and_diff(&journal(&ticks), &stored, "sol", Timeframe::OneMinute, order, BASE, BASE + 180).await;
+            assert!(report.mismatches.is_empty(), "{:?}: {:?}", order, report.mismatches);
+            assert_eq!((report.ticks, report.replayed, report.stored), (5, 3, 3));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_out_of_order_tick_is_reported() {
+        // A late tick for the first minute arrives after the second minute started
+        let ticks = [tick(1, 0, 100.0), tick(2, 61, 105.0), tick(3, 30, 90.0), tick(4, 75, 106.0)];
+        let stored = live(&ticks).await;
+
+        // Arrival order reproduces the live candles, bug included
+        let arrival = replay_and_diff(&journal(&ticks), &stored, "SOL", Timeframe::OneMinute, ReplayOrder::Arrival, BASE, BASE + 120).await;
+        assert!(arrival.mismatches.is_empty(), "{:?}", arrival.mismatches);
+
+        // Event time shows what went wrong: both minutes were split in two
+        let report = replay_and_diff(&journal(&ticks), &stored, "SOL", Timeframe::OneMinute, ReplayOrder::EventTime, BASE, BASE + 120).await;
+        let kinds: Vec<(u64, &Mismatch)> = report.mismatches.iter().map(|m| (m.timestamp - BASE, &m.mismatch)).collect();
+        assert!(matches!(kinds[0], (0, Mismatch::Duplicate { count: 2 })));
+        match kinds[1] {
+            (0, Mismatch::Different { fields, replayed, stored }) => {
+                assert_eq!(replayed.low, 90.0);
+                assert_eq!(stored.open, 90.0);
+                assert!(fields.contains(&"open".to_string()));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(kinds[2], (60, Mismatch::Duplicate { count: 2 })));
+        assert!(matches!(kinds[3], (60, Mismatch::Different { .. })));
+        assert_eq!(kinds.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_missing_and_unexpected_candles() {
+        let ticks = [tick(1, 0, 100.0), tick(2, 60, 101.0)];
+        // The live aggregator lost the first minute and made up the third
+        let stored = vec![
+            Candle { timestamp: BASE + 60, open: 101.0, high: 101.0, low: 101.0, close: 101.0, volume: 0.0 },
+            Candle { timestamp: BASE + 120, open: 1.0, high: 1.0, low: 1.0, close: 1.0, volume: 0.0 },
+        ];
+
+        let report = replay_and_diff(&journal(&ticks), &stored, "SOL", Timeframe::OneMinute, ReplayOrder::EventTime, BASE, BASE + 180).await;
+        assert_eq!(report.mismatches.len(), 2);
+        assert!(matches!(report.mismatches[0].mismatch, Mismatch::Missing { .. }));
+        assert_eq!(report.mismatches[1].timestamp, BASE + 120);
+        assert!(matches!(report.mismatches[1].mismatch, Mismatch::Unexpected { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_partial_candles_at_range_edges_are_ignored() {
+        let ticks = [tick(1, 30, 100.0), tick(2, 60, 101.0), tick(3, 130, 102.0)];
+        // Stored history only has the middle candle; the edges fall outside
+        let stored = live(&ticks[1..2]).await;
+
+        let report = replay_and_diff(&journal(&ticks), &stored, "SOL", Timeframe::OneMinute, ReplayOrder::EventTime, BASE + 30, BASE + 150).await;
+        assert_eq!((report.from, report.to), (BASE + 60, BASE + 120));
+        assert_eq!(report.ticks, 1);
+        assert!(report.mismatches.is_empty());
+    }
+}
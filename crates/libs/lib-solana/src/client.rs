@@ -101,6 +101,80 @@ pub struct EpochInfo {
     pub slots_in_epoch: u64,
 }
 
+/// An account as it would look after a simulated transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedAccount {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// Outcome of `simulateTransaction`.
+///
+/// # Fields
+///
+/// * `err` - Transaction error as reported by the RPC (JSON), `None` if it would succeed
+/// * `logs` - Program logs, in execution order
+/// * `units_consumed` - Compute units the transaction used
+/// * `accounts` - Post-simulation state of the requested addresses, in request order;
+///   `None` for accounts that would not exist
+#[derive(Debug, Clone, Default)]
+pub struct SimulationResult {
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    pub accounts: Vec<Option<SimulatedAccount>>,
+}
+
+/// Parse the `value` of a `simulateTransaction` response requested with
+/// base64 account encoding.
+pub fn parse_simulation_result(value: &serde_json::Value) -> anyhow::Result<SimulationResult> {
+    use base64::{engine::general_purpose, Engine as _};
+    use std::str::FromStr;
+
+    let err = match &value["err"] {
+        serde_json::Value::Null => None,
+        err => Some(err.to_string()),
+    };
+    let logs = value["logs"]
+        .as_array()
+        .map(|logs| logs.iter().filter_map(|log| log.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+
+    let accounts = value["accounts"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|account| {
+            if account.is_null() {
+                return Ok(None);
+            }
+            let owner = account["owner"]
+                .as_str()
+                .and_then(|owner| Pubkey::from_str(owner).ok())
+                .ok_or_else(|| anyhow::anyhow!("Simulated account without a valid owner"))?;
+            let data = account["data"][0]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Simulated account without base64 data"))?;
+            Ok(Some(SimulatedAccount {
+                lamports: account["lamports"].as_u64().unwrap_or(0),
+                owner,
+                data: general_purpose::STANDARD
+                    .decode(data)
+                    .map_err(|e| anyhow::anyhow!("Invalid simulated account data: {}", e))?,
+            }))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(SimulationResult {
+        err,
+        logs,
+        units_consumed: value["unitsConsumed"].as_u64(),
+        accounts,
+    })
+}
+
 /// Solana network selection.
 ///
 /// Determines which Solana cluster the client connects to. Each network
@@ -542,6 +616,138 @@ impl SolanaClient {
         Ok(signature.to_string())
     }
 
+    /// Send a signed versioned transaction to the Solana blockchain.
+    ///
+    /// Same as [`Self::send_transaction`] for transactions that may carry a v0
+    /// message loading accounts from Address Lookup Tables.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - Transaction signature as base58 string
+    /// * `Err(_)` - If transaction fails simulation, submission, or confirmation
+    pub async fn send_versioned_transaction(
+        &self,
+        transaction: &solana_sdk::transaction::VersionedTransaction,
+    ) -> anyhow::Result<String> {
+        let signature = self.rpc
+            .send_and_confirm_transaction(transaction)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send transaction: {}", e))?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Look up whether a transaction has landed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(None)` - Not seen by the cluster (yet), or too old to be tracked
+    /// * `Ok(Some(Ok(())))` - Confirmed successfully
+    /// * `Ok(Some(Err(reason)))` - Landed but failed
+    /// * `Err(_)` - Invalid signature or RPC request failed
+    pub async fn get_signature_status(&self, signature: &str) -> anyhow::Result<Option<Result<(), String>>> {
+        let signature = signature
+            .parse::<solana_sdk::signature::Signature>()
+            .map_err(|e| anyhow::anyhow!("Invalid signature: {}", e))?;
+        let status = self.rpc.get_signature_status(&signature).await
+            .map_err(|e| anyhow::anyhow!("Failed to get signature status: {}", e))?;
+        Ok(status.map(|result| result.map_err(|e| e.to_string())))
+    }
+
+    /// Fetch several accounts in one request (at most 100).
+    ///
+    /// Accounts that don't exist come back as `None`, in request order.
+    pub async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> anyhow::Result<Vec<Option<solana_sdk::account::Account>>> {
+        self.rpc.get_multiple_accounts(pubkeys).await
+            .map_err(|e| anyhow::anyhow!("RPC error: {}", e))
+    }
+
+    /// Fetch Address Lookup Tables with the addresses they hold.
+    ///
+    /// Tables come back in request order. Fails if one doesn't exist or isn't
+    /// a lookup table, since a message loading from it can't be resolved.
+    pub async fn get_address_lookup_tables(
+        &self,
+        addresses: &[Pubkey],
+    ) -> anyhow::Result<Vec<solana_sdk::message::AddressLookupTableAccount>> {
+        use solana_address_lookup_table_interface::{program, state::AddressLookupTable};
+
+        let accounts = self.get_multiple_accounts(addresses).await?;
+        addresses
+            .iter()
+            .zip(accounts)
+            .map(|(address, account)| {
+                let account = account
+                    .ok_or_else(|| anyhow::anyhow!("Lookup table {} not found", address))?;
+                if account.owner != program::ID {
+                    return Err(anyhow::anyhow!("Account {} is not a lookup table", address));
+                }
+                let table = AddressLookupTable::deserialize(&account.data)
+                    .map_err(|e| anyhow::anyhow!("Invalid lookup table {}: {}", address, e))?;
+                Ok(solana_sdk::message::AddressLookupTableAccount {
+                    key: *address,
+                    addresses: table.addresses.to_vec(),
+                })
+            })
+            .collect()
+    }
+
+    /// Simulate a transaction without submitting it.
+    ///
+    /// The transaction doesn't need to be signed: signature verification is
+    /// off and the blockhash is replaced with a recent one, so a transaction
+    /// built a little while ago still simulates. The state of `addresses`
+    /// after the transaction is returned alongside logs and compute units.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Base64-encoded (bincode) legacy or versioned transaction
+    /// * `addresses` - Accounts whose post-simulation state should be returned
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SimulationResult)` - Also when the transaction would fail; see `err`
+    /// * `Err(_)` - If the RPC request itself fails
+    pub async fn simulate_transaction(
+        &self,
+        transaction: &str,
+        addresses: &[Pubkey],
+    ) -> anyhow::Result<SimulationResult> {
+        let addresses: Vec<String> = addresses.iter().map(ToString::to_string).collect();
+        let params = serde_json::json!([
+            transaction,
+            {
+                "encoding": "base64",
+                "sigVerify": false,
+                "replaceRecentBlockhash": true,
+                "commitment": "confirmed",
+                "accounts": { "encoding": "base64", "addresses": addresses },
+            },
+        ]);
+        let response: serde_json::Value = self.rpc
+            .send(solana_client::rpc_request::RpcRequest::SimulateTransaction, params)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to simulate transaction: {}", e))?;
+
+        parse_simulation_result(&response["value"])
+    }
+
+    /// Network fee for a legacy or v0 message at the current fee rate, in lamports.
+    ///
+    /// Fails when the message's blockhash is no longer known to the cluster.
+    pub async fn get_fee_for_message(&self, message: &solana_sdk::message::VersionedMessage) -> anyhow::Result<u64> {
+        use solana_sdk::message::VersionedMessage;
+
+        let fee = match message {
+            VersionedMessage::Legacy(message) => self.rpc.get_fee_for_message(message).await,
+            VersionedMessage::V0(message) => self.rpc.get_fee_for_message(message).await,
+        };
+        fee.map_err(|e| anyhow::anyhow!("Failed to get fee for message: {}", e))
+    }
+
     /// Get the latest blockhash from the blockchain.
     ///
     /// Returns the most recent blockhash, which is required for building transactions.
@@ -569,4 +775,121 @@ impl SolanaClient {
         self.rpc.get_latest_blockhash().await
             .map_err(|e| anyhow::anyhow!("Failed to get latest blockhash: {}", e))
     }
+
+    /// Latest blockhash and the last block height a transaction using it can land at.
+    pub async fn get_latest_blockhash_with_height(&self) -> anyhow::Result<(solana_sdk::hash::Hash, u64)> {
+        self.rpc.get_latest_blockhash_with_commitment(self.rpc.commitment()).await
+            .map_err(|e| anyhow::anyhow!("Failed to get latest blockhash: {}", e))
+    }
+
+    /// Lamports an account of `data_len` bytes needs to be rent exempt.
+    pub async fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> anyhow::Result<u64> {
+        self.rpc.get_minimum_balance_for_rent_exemption(data_len).await
+            .map_err(|e| anyhow::anyhow!("Failed to get rent-exempt minimum: {}", e))
+    }
+
+    /// Smallest delegation the stake program accepts, in lamports.
+    pub async fn get_stake_minimum_delegation(&self) -> anyhow::Result<u64> {
+        self.rpc.get_stake_minimum_delegation().await
+            .map_err(|e| anyhow::anyhow!("Failed to get minimum stake delegation: {}", e))
+    }
+
+    /// Stake accounts whose withdraw authority is `withdrawer`, with their balances.
+    ///
+    /// Fetched raw with a filter on the withdraw authority, so accounts the
+    /// wallet can take SOL out of are listed even if another key stakes them.
+    /// Accounts that aren't initialized are skipped.
+    pub async fn get_stake_accounts(
+        &self,
+        withdrawer: &Pubkey,
+    ) -> anyhow::Result<Vec<(Pubkey, u64, crate::stake::ParsedStakeAccount)>> {
+        use base64::{engine::general_purpose, Engine as _};
+        use std::str::FromStr;
+
+        let params = serde_json::json!([
+            crate::stake::STAKE_PROGRAM_ID.to_string(),
+            {
+                "encoding": "base64",
+                "commitment": "confirmed",
+                "filters": [
+                    { "dataSize": shared::dto::staking::STAKE_ACCOUNT_LEN },
+                    { "memcmp": { "offset": crate::stake::WITHDRAWER_OFFSET, "bytes": withdrawer.to_string() } },
+                ],
+            },
+        ]);
+        let response: serde_json::Value = self.rpc
+            .send(solana_client::rpc_request::RpcRequest::GetProgramAccounts, params)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch stake accounts: {}", e))?;
+
+        let mut accounts = Vec::new();
+        for keyed in response.as_array().map(Vec::as_slice).unwrap_or_default() {
+            let address = keyed["pubkey"].as_str().and_then(|key| Pubkey::from_str(key).ok())
+                .ok_or_else(|| anyhow::anyhow!("Stake account without a valid address"))?;
+            let encoded = keyed["account"]["data"][0].as_str()
+                .ok_or_else(|| anyhow::anyhow!("Stake account {} without base64 data", address))?;
+            let data = general_purpose::STANDARD.decode(encoded)
+                .map_err(|e| anyhow::anyhow!("Invalid data of stake account {}: {}", address, e))?;
+            let lamports = keyed["account"]["lamports"].as_u64().unwrap_or_default();
+            match crate::stake::parse_stake_account(&data) {
+                Ok(parsed) => accounts.push((address, lamports, parsed)),
+                Err(e) => tracing::debug!("Skipping stake account {}: {}", address, e),
+            }
+        }
+        Ok(accounts)
+    }
+
+    /// Inflation rewards paid to `addresses` for `epoch`, in request order.
+    ///
+    /// `None` for an address that earned nothing that epoch.
+    pub async fn get_inflation_rewards(
+        &self,
+        addresses: &[Pubkey],
+        epoch: Epoch,
+    ) -> anyhow::Result<Vec<Option<solana_client::rpc_response::RpcInflationReward>>> {
+        self.rpc.get_inflation_reward(addresses, Some(epoch)).await
+            .map_err(|e| anyhow::anyhow!("Failed to get inflation rewards for epoch {}: {}", epoch, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simulation_result() {
+        // Trimmed response of a swap that failed on slippage
+        let value = serde_json::json!({
+            "err": { "InstructionError": [3, { "Custom": 6001 }] },
+            "logs": [
+                "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+                "Program log: Error: SlippageToleranceExceeded",
+                "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: custom program error: 0x1771"
+            ],
+            "unitsConsumed": 48213,
+            "accounts": [
+                null,
+                {
+                    "lamports": 2039280,
+                    "owner": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+                    "data": ["AQID", "base64"],
+                    "executable": false,
+                    "rentEpoch": 0
+                }
+            ]
+        });
+
+        let result = parse_simulation_result(&value).unwrap();
+        assert_eq!(result.err.as_deref(), Some(r#"{"InstructionError":[3,{"Custom":6001}]}"#));
+        assert_eq!(result.logs.len(), 3);
+        assert_eq!(result.units_consumed, Some(48_213));
+        assert_eq!(result.accounts[0], None);
+        let account = result.accounts[1].as_ref().unwrap();
+        assert_eq!(account.lamports, 2_039_280);
+        assert_eq!(account.data, vec![1, 2, 3]);
+
+        let ok = parse_simulation_result(&serde_json::json!({ "err": null, "logs": null, "accounts": null })).unwrap();
+        assert!(ok.err.is_none());
+        assert!(ok.logs.is_empty() && ok.accounts.is_empty());
+    }
 }
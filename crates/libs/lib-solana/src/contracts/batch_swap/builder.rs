@@ -1,98 +1,147 @@
 //! # Batch Swap Transaction Builder
 //!
 //! Transaction building logic for batch swap operations.
+//!
+//! Every swap of a batch gets its own Jupiter route; the routes are then
+//! packed into as few transactions as the size limit allows (see
+//! [`BatchSwapTransactionBuilder::build_batch_swap_transactions`]).
+//!
+//! Jupiter returns v0 transactions loading accounts from Address Lookup
+//! Tables; those tables are fetched so the route's instructions can be
+//! resolved and the combined transaction compiled against them.
 
 use super::types::{BatchSwapRequest, ExecuteSwapRequest};
-use crate::contracts::transaction_builder::BatchSwapTransactionBuilder;
+use super::validator::validate_swap_accounts;
+use crate::contracts::transaction_builder::{
+    decode_jupiter_transaction, jupiter_instructions, lookup_table_addresses, BatchSwapLeg,
+    BatchSwapTransaction, BatchSwapTransactionBuilder, TransactionBuilderError,
+};
 use crate::contracts::SwapParams as ClientSwapParams;
 use crate::mod_rs::SolanaState;
-use solana_sdk::pubkey::Pubkey;
+use shared::dto::transactions::TransactionVersion;
+use solana_sdk::{instruction::Instruction, message::AddressLookupTableAccount, pubkey::Pubkey};
 use std::str::FromStr;
 use std::sync::Arc;
+use thiserror::Error;
+
+/// Batch swap building errors
+#[derive(Debug, Error)]
+pub enum BatchSwapBuildError {
+    /// A swap of the request can't be executed as asked
+    #[error("{0}")]
+    Rejected(String),
+
+    /// Jupiter, the RPC or the transaction builder failed
+    #[error("{0}")]
+    Failed(String),
+}
 
-/// Build a batch swap transaction
-pub async fn build_batch_swap_transaction(
+/// Instructions of a Jupiter swap transaction and the lookup tables it loads accounts from
+async fn resolve_jupiter_route(
+    solana_state: &Arc<SolanaState>,
+    jupiter_tx_base64: &str,
+) -> Result<(Vec<Instruction>, Vec<AddressLookupTableAccount>), String> {
+    let jupiter_tx = decode_jupiter_transaction(jupiter_tx_base64).map_err(|e| e.to_string())?;
+    let table_addresses = lookup_table_addresses(&jupiter_tx.message);
+    let lookup_tables = if table_addresses.is_empty() {
+        Vec::new()
+    } else {
+        solana_state.rpc.get_address_lookup_tables(&table_addresses).await
+            .map_err(|e| format!("Failed to fetch lookup tables: {}", e))?
+    };
+    let instructions = jupiter_instructions(&jupiter_tx.message, &lookup_tables).map_err(|e| e.to_string())?;
+    Ok((instructions, lookup_tables))
+}
+
+/// Build the transactions of a batch swap
+///
+/// # Returns
+///
+/// The unsigned transactions in execution order with the swaps each one
+/// executes, and the last valid block height shared by all of them
+pub async fn build_batch_swap_transactions(
     solana_state: &Arc<SolanaState>,
     plugin_program_id: Pubkey,
     request: &BatchSwapRequest,
-) -> Result<(String, u64), String> {
+) -> Result<(Vec<BatchSwapTransaction>, u64), BatchSwapBuildError> {
+    let failed = BatchSwapBuildError::Failed;
+
     // Parse user public key
     let user_pubkey = Pubkey::from_str(&request.user_public_key)
-        .map_err(|e| format!("Invalid user public key: {}", e))?;
+        .map_err(|e| BatchSwapBuildError::Rejected(format!("Invalid user public key: {}", e)))?;
+
+    if request.swaps.is_empty() {
+        return Err(BatchSwapBuildError::Rejected("No swaps provided".to_string()));
+    }
 
-    // Convert swap requests to client swap params
-    let swaps: Vec<ClientSwapParams> = request.swaps
-        .iter()
-        .map(|swap| {
-            ClientSwapParams {
+    let slippage_bps = 50; // Default slippage tolerance
+    let mut legs = Vec::with_capacity(request.swaps.len());
+    let mut last_valid_block_height = u64::MAX;
+    for (index, swap) in request.swaps.iter().enumerate() {
+        // Get Jupiter quote and transaction for this swap
+        let quote = solana_state.jupiter.get_swap_quote(
+            &swap.input_mint,
+            &swap.output_mint,
+            swap.amount,
+            slippage_bps,
+        ).await.map_err(|e| failed(format!("Failed to get Jupiter quote for swap {}: {}", index, e)))?;
+
+        let jupiter_tx_response = solana_state.jupiter.get_swap_transaction(
+            &quote,
+            &request.user_public_key,
+        ).await.map_err(|e| failed(format!("Failed to get Jupiter transaction for swap {}: {}", index, e)))?;
+
+        let (instructions, lookup_tables) = resolve_jupiter_route(solana_state, &jupiter_tx_response.swap_transaction)
+            .await
+            .map_err(|e| failed(format!("Swap {}: {}", index, e)))?;
+        validate_swap_accounts(index, &user_pubkey, &instructions, &lookup_tables)
+            .map_err(|e| BatchSwapBuildError::Rejected(e.to_string()))?;
+
+        // The batch expires with the first route that does
+        last_valid_block_height = last_valid_block_height.min(jupiter_tx_response.last_valid_block_height);
+        legs.push(BatchSwapLeg {
+            params: ClientSwapParams {
                 input_mint: Pubkey::from_str(&swap.input_mint)
                     .expect("Input mint validation should have caught this"),
                 output_mint: Pubkey::from_str(&swap.output_mint)
                     .expect("Output mint validation should have caught this"),
                 amount: swap.amount,
                 min_output_amount: swap.min_output_amount,
-            }
-        })
-        .collect();
-
-    if swaps.is_empty() {
-        return Err("No swaps provided".to_string());
+            },
+            instructions,
+            lookup_tables,
+        });
     }
 
-    // Get Jupiter quote and transaction for the first swap
-    let first_swap = &swaps[0];
-    let slippage_bps = 50; // Default slippage tolerance
-    
-    // Get Jupiter quote
-    let quote = solana_state.jupiter.get_swap_quote(
-        &first_swap.input_mint.to_string(),
-        &first_swap.output_mint.to_string(),
-        first_swap.amount,
-        slippage_bps,
-    ).await.map_err(|e| format!("Failed to get Jupiter quote: {}", e))?;
-
-    // Get Jupiter swap transaction
-    let jupiter_tx_response = solana_state.jupiter.get_swap_transaction(
-        &quote,
-        &request.user_public_key,
-    ).await.map_err(|e| format!("Failed to get Jupiter transaction: {}", e))?;
-
     // Get recent blockhash from RPC
     let recent_blockhash = solana_state.rpc.get_latest_blockhash().await
-        .map_err(|e| format!("Failed to get recent blockhash: {}", e))?;
+        .map_err(|e| failed(format!("Failed to get recent blockhash: {}", e)))?;
 
-    // Build batch swap transaction using transaction builder
+    // Build batch swap transactions using transaction builder
     let tx_builder = BatchSwapTransactionBuilder::new(plugin_program_id);
-    
-    // Convert ClientSwapParams to SwapParams for transaction builder
-    let swap_params: Vec<ClientSwapParams> = swaps
-        .into_iter()
-        .map(|s| ClientSwapParams {
-            input_mint: s.input_mint,
-            output_mint: s.output_mint,
-            amount: s.amount,
-            min_output_amount: s.min_output_amount,
-        })
-        .collect();
-
-    let combined_tx_base64 = tx_builder.build_batch_swap_transaction(
-        &jupiter_tx_response.swap_transaction,
+    let transactions = tx_builder.build_batch_swap_transactions(
         &user_pubkey,
-        swap_params,
+        &legs,
         None, // fee_recipient - can be added later
         recent_blockhash,
-        jupiter_tx_response.last_valid_block_height,
-    ).map_err(|e| format!("Failed to build batch swap transaction: {}", e))?;
+    ).map_err(|e| match e {
+        TransactionBuilderError::TransactionTooLarge { .. } => BatchSwapBuildError::Rejected(e.to_string()),
+        e => failed(format!("Failed to build batch swap transactions: {}", e)),
+    })?;
 
-    Ok((combined_tx_base64, jupiter_tx_response.last_valid_block_height))
+    Ok((transactions, last_valid_block_height))
 }
 
 /// Build an execute swap transaction
+///
+/// # Returns
+///
+/// The unsigned transaction, its message format and its last valid block height
 pub async fn build_execute_swap_transaction(
     solana_state: &Arc<SolanaState>,
     plugin_program_id: Pubkey,
     request: &ExecuteSwapRequest,
-) -> Result<(String, u64), String> {
+) -> Result<(String, TransactionVersion, u64), String> {
     // Parse user public key
     let user_pubkey = Pubkey::from_str(&request.user_public_key)
         .map_err(|e| format!("Invalid user public key: {}", e))?;
@@ -117,6 +166,7 @@ pub async fn build_execute_swap_transaction(
         &quote,
         &request.user_public_key,
     ).await.map_err(|e| format!("Failed to get Jupiter transaction: {}", e))?;
+    let (instructions, lookup_tables) = resolve_jupiter_route(solana_state, &jupiter_tx_response.swap_transaction).await?;
 
     // Get recent blockhash from RPC
     let recent_blockhash = solana_state.rpc.get_latest_blockhash().await
@@ -132,8 +182,9 @@ pub async fn build_execute_swap_transaction(
     // Build execute swap transaction using transaction builder
     let tx_builder = BatchSwapTransactionBuilder::new(plugin_program_id);
     
-    let combined_tx_base64 = tx_builder.build_execute_swap_transaction(
-        &jupiter_tx_response.swap_transaction,
+    let (combined_tx_base64, version) = tx_builder.build_execute_swap_transaction(
+        instructions,
+        &lookup_tables,
         &user_pubkey,
         &input_token_account,
         &output_token_account,
@@ -147,6 +198,6 @@ pub async fn build_execute_swap_transaction(
         jupiter_tx_response.last_valid_block_height,
     ).map_err(|e| format!("Failed to build execute swap transaction: {}", e))?;
 
-    Ok((combined_tx_base64, jupiter_tx_response.last_valid_block_height))
+    Ok((combined_tx_base64, version, jupiter_tx_response.last_valid_block_height))
 }
 
@@ -145,7 +145,7 @@ impl ContractPlugin for BatchSwapRouterPlugin {
             name: self.name.clone(),
             version: self.version.clone(),
             program_id: self.program_id,
-            description: "Batch swap router for executing multiple swaps in a single transaction".to_string(),
+            description: "Batch swap router for executing multiple swaps in as few transactions as fit".to_string(),
             instructions: vec![
                 "batch_swap".to_string(),
                 "execute_swap".to_string(),
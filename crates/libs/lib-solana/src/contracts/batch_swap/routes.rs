@@ -2,9 +2,9 @@
 //!
 //! HTTP route handlers for batch swap operations.
 
-use super::types::{BatchSwapRequest, BatchSwapResponse, ExecuteSwapRequest};
+use super::types::{BatchSwapRequest, BatchSwapResponse, BatchTransactionManifest, ExecuteSwapRequest};
 use super::validator::{validate_batch_swap_request, validate_execute_swap_request};
-use super::builder::{build_batch_swap_transaction, build_execute_swap_transaction};
+use super::builder::{build_batch_swap_transactions, build_execute_swap_transaction, BatchSwapBuildError};
 use super::BatchSwapRouterPlugin;
 use crate::contracts::plugin::{ContractMetadata, ContractPlugin};
 use axum::{
@@ -30,6 +30,8 @@ pub async fn handle_batch_swap_app_state(
             Json(BatchSwapResponse {
                 signature: None,
                 transaction: None,
+                transactions: None,
+                version: None,
                 status: "error".to_string(),
                 last_valid_block_height: None,
                 error: Some(e.to_string()),
@@ -46,6 +48,8 @@ pub async fn handle_batch_swap_app_state(
                 Json(BatchSwapResponse {
                     signature: None,
                     transaction: None,
+                    transactions: None,
+                    version: None,
                     status: "error".to_string(),
                     last_valid_block_height: None,
                     error: Some("Plugin not initialized".to_string()),
@@ -53,15 +57,30 @@ pub async fn handle_batch_swap_app_state(
             )
         })?;
 
-    // Build transaction
-    match build_batch_swap_transaction(solana_state, plugin.program_id(), &request).await {
-        Ok((transaction, last_valid_block_height)) => {
-            info!("Batch swap transaction built successfully");
+    // Build transactions; batches too large for one are split
+    match build_batch_swap_transactions(solana_state, plugin.program_id(), &request).await {
+        Ok((transactions, last_valid_block_height)) => {
+            info!("Batch swap built as {} transactions", transactions.len());
+            let (transaction, version) = match transactions.as_slice() {
+                [only] => (Some(only.transaction.clone()), Some(only.version)),
+                _ => (None, None),
+            };
+            let manifest = transactions
+                .into_iter()
+                .map(|tx| BatchTransactionManifest {
+                    transaction: tx.transaction,
+                    version: tx.version,
+                    swap_indices: tx.swap_indices,
+                    size_bytes: tx.size,
+                })
+                .collect();
             Ok((
                 StatusCode::OK,
                 Json(BatchSwapResponse {
                     signature: None,
-                    transaction: Some(transaction),
+                    transaction,
+                    transactions: Some(manifest),
+                    version,
                     status: "success".to_string(),
                     last_valid_block_height: Some(last_valid_block_height),
                     error: None,
@@ -69,15 +88,21 @@ pub async fn handle_batch_swap_app_state(
             ))
         }
         Err(e) => {
-            error!("Failed to build batch swap transaction: {}", e);
+            error!("Failed to build batch swap transactions: {}", e);
+            let status = match e {
+                BatchSwapBuildError::Rejected(_) => StatusCode::BAD_REQUEST,
+                BatchSwapBuildError::Failed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
             Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
+                status,
                 Json(BatchSwapResponse {
                     signature: None,
                     transaction: None,
+                    transactions: None,
+                    version: None,
                     status: "error".to_string(),
                     last_valid_block_height: None,
-                    error: Some(e),
+                    error: Some(e.to_string()),
                 })
             ))
         }
@@ -99,6 +124,8 @@ pub async fn handle_execute_swap_app_state(
             Json(BatchSwapResponse {
                 signature: None,
                 transaction: None,
+                transactions: None,
+                version: None,
                 status: "error".to_string(),
                 last_valid_block_height: None,
                 error: Some(e.to_string()),
@@ -115,6 +142,8 @@ pub async fn handle_execute_swap_app_state(
                 Json(BatchSwapResponse {
                     signature: None,
                     transaction: None,
+                    transactions: None,
+                    version: None,
                     status: "error".to_string(),
                     last_valid_block_height: None,
                     error: Some("Plugin not initialized".to_string()),
@@ -124,13 +153,15 @@ pub async fn handle_execute_swap_app_state(
 
     // Build transaction
     match build_execute_swap_transaction(solana_state, plugin.program_id(), &request).await {
-        Ok((transaction, last_valid_block_height)) => {
+        Ok((transaction, version, last_valid_block_height)) => {
             info!("Execute swap transaction built successfully");
             Ok((
                 StatusCode::OK,
                 Json(BatchSwapResponse {
                     signature: None,
                     transaction: Some(transaction),
+                    transactions: None,
+                    version: Some(version),
                     status: "success".to_string(),
                     last_valid_block_height: Some(last_valid_block_height),
                     error: None,
@@ -144,6 +175,8 @@ pub async fn handle_execute_swap_app_state(
                 Json(BatchSwapResponse {
                     signature: None,
                     transaction: None,
+                    transactions: None,
+                    version: None,
                     status: "error".to_string(),
                     last_valid_block_height: None,
                     error: Some(e),
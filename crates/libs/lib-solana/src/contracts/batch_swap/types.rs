@@ -3,6 +3,7 @@
 //! Request and response types for batch swap operations.
 
 use serde::{Deserialize, Serialize};
+use shared::dto::transactions::TransactionVersion;
 
 /// Request to execute a batch of swaps
 #[derive(Debug, Deserialize, Clone)]
@@ -36,8 +37,17 @@ pub struct BatchSwapResponse {
     /// Transaction signature (if executed) or transaction data (if unsigned)
     pub signature: Option<String>,
     /// Unsigned transaction (base64 encoded) for client-side signing
+    ///
+    /// For a batch, only set when it fits in one transaction.
     #[serde(rename = "transaction", skip_serializing_if = "Option::is_none")]
     pub transaction: Option<String>,
+    /// Message format of `transaction`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<TransactionVersion>,
+    /// Transactions of a batch swap, to sign and send in order, with the
+    /// swaps each one executes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transactions: Option<Vec<BatchTransactionManifest>>,
     /// Status of the operation
     pub status: String,
     /// Last valid block height (for transaction expiration)
@@ -48,6 +58,21 @@ pub struct BatchSwapResponse {
     pub error: Option<String>,
 }
 
+/// One transaction of a batch swap
+#[derive(Debug, Serialize)]
+pub struct BatchTransactionManifest {
+    /// Unsigned transaction (base64 encoded)
+    pub transaction: String,
+    /// Message format of `transaction`
+    pub version: TransactionVersion,
+    /// Indices into the request's `swaps` executed by this transaction
+    #[serde(rename = "swapIndices")]
+    pub swap_indices: Vec<usize>,
+    /// Serialized size in bytes
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: usize,
+}
+
 /// Request to execute a single swap
 #[derive(Debug, Deserialize)]
 pub struct ExecuteSwapRequest {
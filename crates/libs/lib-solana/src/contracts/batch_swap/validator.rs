@@ -4,7 +4,9 @@
 
 use super::types::{BatchSwapRequest, ExecuteSwapRequest};
 use crate::contracts::plugin::PluginError;
-use solana_sdk::pubkey::Pubkey;
+use crate::contracts::transaction_builder::{MAX_TRANSACTION_SIZE, TRANSACTION_OVERHEAD};
+use solana_sdk::{instruction::Instruction, message::AddressLookupTableAccount, pubkey::Pubkey};
+use std::collections::HashSet;
 use std::str::FromStr;
 
 /// Validate batch swap request
@@ -70,6 +72,58 @@ pub fn validate_batch_swap_request(request: &BatchSwapRequest) -> Result<(), Plu
     Ok(())
 }
 
+/// Validate that a swap's accounts fit in a transaction
+///
+/// Checked once the swap's instructions are known. Every distinct account
+/// listed in the message costs 32 bytes, so a route touching too many
+/// accounts can't be executed, alone or batched. Accounts held by the
+/// route's lookup tables cost one byte each instead, except for signers and
+/// programs, which a v0 message must still list.
+///
+/// # Arguments
+///
+/// * `index` - Position of the swap in the batch
+/// * `user_pubkey` - Fee payer, counted even if no instruction names it
+/// * `instructions` - The swap's instructions
+/// * `lookup_tables` - Lookup tables the swap's route loads accounts from
+pub fn validate_swap_accounts(
+    index: usize,
+    user_pubkey: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Result<(), PluginError> {
+    let mut listed: HashSet<Pubkey> = HashSet::from([*user_pubkey]);
+    let mut accounts: HashSet<Pubkey> = HashSet::new();
+    for instruction in instructions {
+        listed.insert(instruction.program_id);
+        for meta in &instruction.accounts {
+            if meta.is_signer {
+                listed.insert(meta.pubkey);
+            } else {
+                accounts.insert(meta.pubkey);
+            }
+        }
+    }
+    let loadable: HashSet<&Pubkey> = lookup_tables.iter().flat_map(|table| &table.addresses).collect();
+    let (loaded, unlisted): (Vec<Pubkey>, Vec<Pubkey>) = accounts
+        .difference(&listed)
+        .partition(|account| loadable.contains(account));
+    listed.extend(unlisted);
+    let loaded = loaded.len();
+
+    let size = TRANSACTION_OVERHEAD + listed.len() * 32 + loaded;
+    if size > MAX_TRANSACTION_SIZE {
+        return Err(PluginError::ContractError(
+            format!(
+                "Swap at index {} uses {} accounts, which alone need {} of the {} bytes a transaction can hold",
+                index, listed.len() + loaded, size, MAX_TRANSACTION_SIZE
+            )
+        ));
+    }
+
+    Ok(())
+}
+
 /// Validate execute swap request
 pub fn validate_execute_swap_request(request: &ExecuteSwapRequest) -> Result<(), PluginError> {
     // Validate amount
@@ -114,3 +168,42 @@ pub fn validate_execute_swap_request(request: &ExecuteSwapRequest) -> Result<(),
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::AccountMeta;
+
+    /// One instruction touching `accounts` distinct accounts, program included
+    fn route(user: &Pubkey, accounts: usize) -> Vec<Instruction> {
+        vec![Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: std::iter::once(AccountMeta::new(*user, true))
+                .chain((2..accounts).map(|_| AccountMeta::new(Pubkey::new_unique(), false)))
+                .collect(),
+            data: vec![],
+        }]
+    }
+
+    #[test]
+    fn test_validate_swap_accounts_boundary() {
+        let user = Pubkey::new_unique();
+        // 102 bytes of overhead leave room for 35 accounts of 32 bytes
+        assert!(validate_swap_accounts(0, &user, &route(&user, 35), &[]).is_ok());
+
+        let err = validate_swap_accounts(3, &user, &route(&user, 36), &[]).unwrap_err();
+        assert!(err.to_string().contains("index 3"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_swap_accounts_with_lookup_table() {
+        let user = Pubkey::new_unique();
+        let instructions = route(&user, 60);
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: instructions[0].accounts.iter().skip(1).map(|meta| meta.pubkey).collect(),
+        };
+        assert!(validate_swap_accounts(0, &user, &instructions, &[]).is_err());
+        // Only the user and the program stay listed
+        assert!(validate_swap_accounts(0, &user, &instructions, &[table]).is_ok());
+    }
+}
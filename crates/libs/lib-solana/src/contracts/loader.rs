@@ -61,21 +61,44 @@
 //! - **Key**: Contract identifier (e.g., "batch-swap-router")
 //! - **Value**: [`ContractConfig`] with program ID, cluster, RPC URL, etc.
 //!
+//! Operators keep overrides in a JSON file of the same shape
+//! ([`PluginLoader::config_path`]), edited with `xforce-admin plugins` and
+//! read by the server at startup:
+//!
+//! ```json
+//! {
+//!   "batch-swap-router": {
+//!     "program_id": "HS63bw1V1qTM5uWf92q3uaFdqogrc4SN9qUJSR8aqBMx",
+//!     "enabled": true,
+//!     "cluster": "Devnet",
+//!     "rpc_url": null
+//!   }
+//! }
+//! ```
+//!
 //! ## Supported Plugins
 //!
 //! - **batch-swap-router**: Batch swap router contract (see [`BatchSwapRouterPlugin`])
 //!
 //! Additional plugins can be added by extending `load_from_config()` method.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use crate::contracts::{
     plugin::{ContractPlugin, PluginConfig, PluginError, Cluster, CommitmentLevel},
     registry::ContractRegistry,
     batch_swap::BatchSwapRouterPlugin,
 };
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::sync::Arc;
 
+/// Identifier of the batch swap router plugin
+pub const BATCH_SWAP_ROUTER: &str = "batch-swap-router";
+
+/// Plugin config file used when `CONTRACT_PLUGINS_FILE` isn't set
+pub const DEFAULT_CONFIG_FILE: &str = "contract-plugins.json";
+
 /// Configuration for a Solana contract plugin.
 ///
 /// Specifies how a contract plugin should be initialized, including the program ID,
@@ -103,9 +126,10 @@ use std::sync::Arc;
 ///     rpc_url: Some("https://api.devnet.solana.com".to_string()),
 /// };
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContractConfig {
     /// The Solana program ID for this contract.
+    #[serde(with = "base58_pubkey")]
     pub program_id: Pubkey,
     
     /// Whether this plugin should be loaded and registered.
@@ -116,14 +140,32 @@ pub struct ContractConfig {
     /// Optional network cluster (overrides default if set).
     ///
     /// If `None`, uses the default cluster from `load_from_config()`.
+    #[serde(default)]
     pub cluster: Option<Cluster>,
     
     /// Optional RPC URL (overrides default if set).
     ///
     /// If `None`, uses the default RPC URL from `load_from_config()`.
+    #[serde(default)]
     pub rpc_url: Option<String>,
 }
 
+/// Program IDs are written to the config file as base58, not byte arrays
+mod base58_pubkey {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(program_id: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(program_id)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Pubkey::from_str(&value).map_err(|e| D::Error::custom(format!("invalid program ID {}: {}", value, e)))
+    }
+}
+
 /// Loads and initializes contract plugins from configuration.
 ///
 /// The plugin loader reads configuration and dynamically loads contract plugins,
@@ -153,6 +195,44 @@ pub struct PluginLoader {
 }
 
 impl PluginLoader {
+    /// Plugins [`Self::load_from_config`] knows how to load
+    pub const SUPPORTED_PLUGINS: &'static [&'static str] = &[BATCH_SWAP_ROUTER];
+
+    /// Path of the plugin config file: `CONTRACT_PLUGINS_FILE`, or
+    /// [`DEFAULT_CONFIG_FILE`] in the working directory.
+    pub fn config_path() -> PathBuf {
+        std::env::var("CONTRACT_PLUGINS_FILE")
+            .ok()
+            .filter(|path| !path.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE))
+    }
+
+    /// Read a plugin config file. A missing file is an empty config.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(HashMap)` - Contract identifiers and their configuration
+    /// * `Err(PluginError::ConfigError)` - The file can't be read or isn't valid
+    pub fn read_config(path: &Path) -> Result<HashMap<String, ContractConfig>, PluginError> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(PluginError::ConfigError(format!("Failed to read {}: {}", path.display(), e))),
+        };
+        serde_json::from_str(&contents)
+            .map_err(|e| PluginError::ConfigError(format!("Invalid plugin config {}: {}", path.display(), e)))
+    }
+
+    /// Write a plugin config file, sorted by contract identifier
+    pub fn write_config(path: &Path, contracts: &HashMap<String, ContractConfig>) -> Result<(), PluginError> {
+        let sorted: BTreeMap<&String, &ContractConfig> = contracts.iter().collect();
+        let json = serde_json::to_string_pretty(&sorted)
+            .map_err(|e| PluginError::ConfigError(format!("Failed to encode plugin config: {}", e)))?;
+        std::fs::write(path, json + "\n")
+            .map_err(|e| PluginError::ConfigError(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
     /// Create a new plugin loader with the given contract registry.
     ///
     /// # Arguments
@@ -234,7 +314,7 @@ impl PluginLoader {
         default_rpc_url: String,
     ) -> Result<(), PluginError> {
         // Load batch swap router plugin if configured and enabled
-        if let Some(config) = contracts.get("batch-swap-router") {
+        if let Some(config) = contracts.get(BATCH_SWAP_ROUTER) {
             if config.enabled {
                 self.load_batch_swap_router(config, &default_cluster, &default_rpc_url).await?;
             }
@@ -322,3 +402,35 @@ impl PluginLoader {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_config_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("contract-plugins-{}.json", std::process::id()));
+        assert!(PluginLoader::read_config(&path).unwrap().is_empty());
+
+        let program_id = Pubkey::from_str("HS63bw1V1qTM5uWf92q3uaFdqogrc4SN9qUJSR8aqBMx").unwrap();
+        let mut contracts = HashMap::new();
+        contracts.insert(
+            BATCH_SWAP_ROUTER.to_string(),
+            ContractConfig { program_id, enabled: false, cluster: Some(Cluster::Devnet), rpc_url: None },
+        );
+        PluginLoader::write_config(&path, &contracts).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("\"program_id\": \"HS63bw1V1qTM5uWf92q3uaFdqogrc4SN9qUJSR8aqBMx\""));
+        let read = PluginLoader::read_config(&path).unwrap();
+        let config = &read[BATCH_SWAP_ROUTER];
+        assert_eq!(config.program_id, program_id);
+        assert!(!config.enabled);
+        assert_eq!(config.cluster.as_ref().map(Cluster::as_str), Some("devnet"));
+
+        std::fs::write(&path, r#"{"batch-swap-router": {"program_id": "nope", "enabled": true}}"#).unwrap();
+        assert!(matches!(PluginLoader::read_config(&path), Err(PluginError::ConfigError(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
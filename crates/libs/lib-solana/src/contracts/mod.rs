@@ -18,7 +18,7 @@ pub use xforce_terminal_contracts_client::{
 
 pub use plugin::{ContractPlugin, PluginConfig, PluginError, ContractMetadata, Cluster, CommitmentLevel};
 pub use registry::ContractRegistry;
-pub use loader::PluginLoader;
+pub use loader::{ContractConfig, PluginLoader};
 pub use batch_swap::{
     BatchSwapRouterPlugin, 
     create_batch_swap_routes,
@@ -29,6 +29,6 @@ pub use batch_swap::routes::{
     handle_health_app_state,
     handle_metadata_app_state,
 };
-pub use transaction_builder::BatchSwapTransactionBuilder;
+pub use transaction_builder::{BatchSwapLeg, BatchSwapTransaction, BatchSwapTransactionBuilder, LEGACY_ACCOUNT_LIMIT, MAX_TRANSACTION_SIZE};
 pub use idl_handler::{IdlHandler, load_batch_swap_idl};
 
@@ -59,6 +59,15 @@ impl Cluster {
             Cluster::Mainnet => "mainnet",
         }
     }
+    /// Parse the name produced by [`Self::as_str`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "localnet" => Some(Cluster::Localnet),
+            "devnet" => Some(Cluster::Devnet),
+            "mainnet" => Some(Cluster::Mainnet),
+            _ => None,
+        }
+    }
 }
 
 /// Commitment level for Solana transactions
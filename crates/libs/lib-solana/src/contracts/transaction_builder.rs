@@ -2,13 +2,32 @@
 //!
 //! Builds Solana transactions that combine Jupiter swap instructions with
 //! batch swap router contract instructions.
+//!
+//! A transaction can't exceed [`MAX_TRANSACTION_SIZE`] serialized bytes,
+//! which a batch of more than a few swaps does. Batches are therefore packed
+//! greedily: swaps are added to a transaction while its measured size stays
+//! within the limit, and the next swap starts a new transaction. Each
+//! transaction carries its own router instruction covering only its swaps.
+//!
+//! ## Versioned Messages
+//!
+//! Every account of a legacy message costs 32 bytes, so no more than
+//! [`LEGACY_ACCOUNT_LIMIT`] fit. Jupiter routes often touch more and come as
+//! v0 transactions loading accounts from Address Lookup Tables.
+//! [`compile_message`] keeps the legacy format while the accounts fit, and
+//! compiles a v0 message against the route's lookup tables once they don't.
 
 use base64::{Engine as _, engine::general_purpose};
+use shared::dto::transactions::TransactionVersion;
 use solana_sdk::{
+    hash::Hash,
     instruction::{AccountMeta, Instruction},
+    message::{v0, AddressLookupTableAccount, Message, VersionedMessage},
     pubkey::Pubkey,
-    transaction::Transaction,
+    signature::Signature,
+    transaction::VersionedTransaction,
 };
+use std::collections::HashSet;
 use thiserror::Error;
 use tracing::{info, warn};
 
@@ -33,6 +52,188 @@ pub enum TransactionBuilderError {
     
     #[error("Invalid account: {0}")]
     InvalidAccount(String),
+
+    #[error("Failed to compile versioned message: {0}")]
+    CompileError(String),
+
+    #[error("Swap {swap_index} needs a {size}-byte transaction on its own, over the {MAX_TRANSACTION_SIZE}-byte limit")]
+    TransactionTooLarge { swap_index: usize, size: usize },
+}
+
+/// Largest serialized transaction the network accepts (the packet data size)
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Bytes of a one-signer legacy transaction besides its account keys and
+/// instructions: signature count and signature, message header, key count,
+/// blockhash and instruction count
+pub(crate) const TRANSACTION_OVERHEAD: usize = 1 + 64 + 3 + 1 + 32 + 1;
+
+/// Most distinct accounts a one-signer legacy transaction can list
+pub const LEGACY_ACCOUNT_LIMIT: usize = (MAX_TRANSACTION_SIZE - TRANSACTION_OVERHEAD) / 32;
+
+/// Compute budget program; a transaction may set each budget only once
+const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+/// One swap of a batch with the instructions that route it
+pub struct BatchSwapLeg {
+    /// Parameters checked by the router instruction
+    pub params: SwapParams,
+    /// Swap instructions (from the swap's Jupiter transaction)
+    pub instructions: Vec<Instruction>,
+    /// Lookup tables the swap's Jupiter transaction loaded accounts from
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+/// One unsigned transaction of a batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSwapTransaction {
+    /// Base64-encoded transaction ready for signing
+    pub transaction: String,
+    /// Message format of `transaction`
+    pub version: TransactionVersion,
+    /// Positions in the batch of the swaps this transaction executes
+    pub swap_indices: Vec<usize>,
+    /// Serialized size in bytes
+    pub size: usize,
+}
+
+/// Decode a base64-encoded Jupiter swap transaction, legacy or v0
+pub fn decode_jupiter_transaction(jupiter_tx_base64: &str) -> Result<VersionedTransaction, TransactionBuilderError> {
+    let jupiter_tx_bytes = general_purpose::STANDARD
+        .decode(jupiter_tx_base64)
+        .map_err(|e| TransactionBuilderError::DecodeError(format!("Failed to decode Jupiter transaction: {}", e)))?;
+
+    bincode::deserialize(&jupiter_tx_bytes)
+        .map_err(|e| TransactionBuilderError::DecodeError(format!("Failed to deserialize Jupiter transaction: {}", e)))
+}
+
+/// Addresses of the lookup tables a message loads accounts from
+pub fn lookup_table_addresses(message: &VersionedMessage) -> Vec<Pubkey> {
+    message.address_table_lookups()
+        .unwrap_or_default()
+        .iter()
+        .map(|lookup| lookup.account_key)
+        .collect()
+}
+
+/// Instructions of a decoded Jupiter swap transaction
+///
+/// Accounts a v0 message loads are resolved through `lookup_tables`, which
+/// must hold every table of [`lookup_table_addresses`]; a legacy message
+/// needs none.
+pub fn jupiter_instructions(
+    message: &VersionedMessage,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Result<Vec<Instruction>, TransactionBuilderError> {
+    // Loaded accounts follow the static keys: every table's writable ones, then every table's read-only ones
+    let mut account_keys = message.static_account_keys().to_vec();
+    let lookups = message.address_table_lookups().unwrap_or_default();
+    for writable in [true, false] {
+        for lookup in lookups {
+            let table = lookup_tables.iter()
+                .find(|table| table.key == lookup.account_key)
+                .ok_or_else(|| TransactionBuilderError::InvalidAccount(format!("Lookup table {} not loaded", lookup.account_key)))?;
+            let indexes = if writable { &lookup.writable_indexes } else { &lookup.readonly_indexes };
+            for &index in indexes {
+                let address = table.addresses.get(index as usize)
+                    .ok_or_else(|| TransactionBuilderError::InvalidAccount(format!("Lookup table {} has no entry {}", table.key, index)))?;
+                account_keys.push(*address);
+            }
+        }
+    }
+
+    let account = |index: u8| {
+        account_keys.get(index as usize)
+            .copied()
+            .ok_or_else(|| TransactionBuilderError::DecodeError(format!("Account index {} out of range", index)))
+    };
+
+    // Convert CompiledInstructions to Instructions so we can add our instructions;
+    // the message is compiled again from scratch
+    message.instructions().iter()
+        .map(|compiled_ix| {
+            Ok(Instruction {
+                program_id: account(compiled_ix.program_id_index)?,
+                accounts: compiled_ix.accounts.iter()
+                    .map(|&idx| {
+                        let pubkey = account(idx)?;
+                        let is_signer = message.is_signer(idx as usize);
+                        let is_writable = message.is_maybe_writable(idx as usize, None);
+                        Ok(if is_writable {
+                            AccountMeta::new(pubkey, is_signer)
+                        } else {
+                            AccountMeta::new_readonly(pubkey, is_signer)
+                        })
+                    })
+                    .collect::<Result<_, TransactionBuilderError>>()?,
+                data: compiled_ix.data.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Distinct accounts of `instructions`, fee payer and programs included
+pub fn account_count(payer: &Pubkey, instructions: &[Instruction]) -> usize {
+    let mut accounts: HashSet<Pubkey> = HashSet::from([*payer]);
+    for instruction in instructions {
+        accounts.insert(instruction.program_id);
+        accounts.extend(instruction.accounts.iter().map(|meta| meta.pubkey));
+    }
+    accounts.len()
+}
+
+/// Compile `instructions` into a message paid by `payer`
+///
+/// Stays legacy up to [`LEGACY_ACCOUNT_LIMIT`] accounts, or when there are
+/// no lookup tables to load accounts from. Past the limit, a v0 message is
+/// compiled and every account the tables hold (signers and programs aside)
+/// is loaded from them instead of listed.
+pub fn compile_message(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedMessage, TransactionBuilderError> {
+    if lookup_tables.is_empty() || account_count(payer, instructions) <= LEGACY_ACCOUNT_LIMIT {
+        // Message::new handles account key ordering
+        let mut message = Message::new(instructions, Some(payer));
+        message.recent_blockhash = recent_blockhash;
+        return Ok(VersionedMessage::Legacy(message));
+    }
+
+    v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)
+        .map(VersionedMessage::V0)
+        .map_err(|e| TransactionBuilderError::CompileError(e.to_string()))
+}
+
+/// Message format of a compiled message
+pub fn message_version(message: &VersionedMessage) -> TransactionVersion {
+    match message {
+        VersionedMessage::Legacy(_) => TransactionVersion::Legacy,
+        VersionedMessage::V0(_) => TransactionVersion::V0,
+    }
+}
+
+/// Unsigned transaction carrying `message`, with room for its signatures
+pub(crate) fn unsigned_transaction(message: VersionedMessage) -> VersionedTransaction {
+    VersionedTransaction {
+        signatures: vec![Signature::default(); message.header().num_required_signatures as usize],
+        message,
+    }
+}
+
+/// Serialized size of a transaction in bytes
+pub fn serialized_size(transaction: &VersionedTransaction) -> Result<usize, TransactionBuilderError> {
+    bincode::serialized_size(transaction)
+        .map(|size| size as usize)
+        .map_err(|e| TransactionBuilderError::SerializeError(format!("Failed to measure transaction: {}", e)))
+}
+
+/// Encode an unsigned transaction for the client to sign
+pub(crate) fn encode_transaction(transaction: &VersionedTransaction) -> Result<(String, usize), TransactionBuilderError> {
+    let tx_bytes = bincode::serialize(transaction)
+        .map_err(|e| TransactionBuilderError::SerializeError(format!("Failed to serialize transaction: {}", e)))?;
+    Ok((general_purpose::STANDARD.encode(&tx_bytes), tx_bytes.len()))
 }
 
 /// Transaction builder for batch swap router
@@ -71,103 +272,116 @@ impl BatchSwapTransactionBuilder {
         }
     }
 
-    /// Build a batch swap transaction that combines Jupiter swap instructions
-    /// with the batch swap router instruction
+    /// Build the transactions executing a batch of swaps
     ///
-    /// This function takes a Jupiter transaction (which contains swap instructions)
-    /// and adds the batch swap router instruction to validate and track the swaps.
+    /// Swaps keep their order. Each transaction starts with a router
+    /// instruction validating the swaps it contains, followed by their swap
+    /// instructions; only the first swap of a transaction keeps its compute
+    /// budget instructions. A transaction is compiled as v0 against its
+    /// swaps' lookup tables when its accounts don't fit a legacy message.
     ///
     /// # Arguments
     ///
-    /// * `jupiter_tx_base64` - Base64-encoded Jupiter swap transaction
-    /// * `user_pubkey` - User's public key (authority)
-    /// * `swaps` - Vector of swap parameters
+    /// * `user_pubkey` - User's public key (authority and fee payer)
+    /// * `legs` - Swaps of the batch with their swap instructions
     /// * `fee_recipient` - Optional fee recipient
-    /// * `recent_blockhash` - Recent blockhash for the transaction
-    /// * `last_valid_block_height` - Last valid block height from Jupiter
+    /// * `recent_blockhash` - Recent blockhash for the transactions
     ///
     /// # Returns
     ///
-    /// Base64-encoded transaction ready for signing and last valid block height
-    pub fn build_batch_swap_transaction(
+    /// The unsigned transactions in execution order, or
+    /// [`TransactionBuilderError::TransactionTooLarge`] for a swap that doesn't
+    /// fit in a transaction even alone
+    pub fn build_batch_swap_transactions(
         &self,
-        jupiter_tx_base64: &str,
         user_pubkey: &Pubkey,
-        swaps: Vec<SwapParams>,
+        legs: &[BatchSwapLeg],
         fee_recipient: Option<Pubkey>,
-        recent_blockhash: solana_sdk::hash::Hash,
-        _last_valid_block_height: u64,
-    ) -> Result<String, TransactionBuilderError> {
-        info!("Building batch swap transaction with {} swaps", swaps.len());
-
-        // Step 1: Decode Jupiter transaction
-        let jupiter_tx_bytes = general_purpose::STANDARD
-            .decode(jupiter_tx_base64)
-            .map_err(|e| TransactionBuilderError::DecodeError(format!("Failed to decode Jupiter transaction: {}", e)))?;
-
-        let jupiter_tx: Transaction = bincode::deserialize(&jupiter_tx_bytes)
-            .map_err(|e| TransactionBuilderError::DecodeError(format!("Failed to deserialize Jupiter transaction: {}", e)))?;
-
-        // Step 2: Extract instructions from Jupiter transaction
-        // Convert CompiledInstructions to Instructions so we can add our instruction
-        // Message::new will recompile everything properly
-        let mut instructions: Vec<Instruction> = jupiter_tx.message.instructions.iter()
-            .map(|compiled_ix| {
-                Instruction {
-                    program_id: jupiter_tx.message.account_keys[compiled_ix.program_id_index as usize],
-                    accounts: compiled_ix.accounts.iter()
-                        .map(|&idx| {
-                            let pubkey = jupiter_tx.message.account_keys[idx as usize];
-                            let is_signer = jupiter_tx.message.is_signer(idx as usize);
-                            // Check if account is writable by checking if it's in the writable accounts list
-                            let is_writable = jupiter_tx.message.is_maybe_writable(idx as usize, None);
-                            if is_writable {
-                                AccountMeta::new(pubkey, is_signer)
-                            } else {
-                                AccountMeta::new_readonly(pubkey, is_signer)
-                            }
-                        })
-                        .collect(),
-                    data: compiled_ix.data.clone(),
-                }
-            })
-            .collect();
-
-        // Step 3: Build batch swap router instruction
-        // Create a temporary account_keys vec for the instruction builder
-        // Message::new will handle the final account ordering
-        let mut temp_account_keys = jupiter_tx.message.account_keys.clone();
-        let batch_swap_instruction = self.build_batch_swap_instruction(
-            user_pubkey,
-            &swaps,
-            fee_recipient,
-            &mut temp_account_keys,
-        )?;
-
-        // Step 4: Add batch swap router instruction at the beginning
-        // This allows the contract to validate parameters before swaps execute
-        instructions.insert(0, batch_swap_instruction);
+        recent_blockhash: Hash,
+    ) -> Result<Vec<BatchSwapTransaction>, TransactionBuilderError> {
+        info!("Building batch swap transactions for {} swaps", legs.len());
+
+        let mut transactions = Vec::new();
+        let mut group: Vec<usize> = Vec::new();
+        let mut fitting = None;
+        for index in 0..legs.len() {
+            group.push(index);
+            let transaction = self.compile(user_pubkey, legs, &group, fee_recipient, recent_blockhash)?;
+            let size = serialized_size(&transaction)?;
+            if size <= MAX_TRANSACTION_SIZE {
+                fitting = Some(transaction);
+                continue;
+            }
+            if group.len() == 1 {
+                return Err(TransactionBuilderError::TransactionTooLarge { swap_index: index, size });
+            }
+
+            // Close the transaction without this swap and start the next one with it
+            group.pop();
+            if let Some(transaction) = fitting.take() {
+                transactions.push(Self::finish(&transaction, &group)?);
+            }
+            group = vec![index];
+            let transaction = self.compile(user_pubkey, legs, &group, fee_recipient, recent_blockhash)?;
+            let size = serialized_size(&transaction)?;
+            if size > MAX_TRANSACTION_SIZE {
+                return Err(TransactionBuilderError::TransactionTooLarge { swap_index: index, size });
+            }
+            fitting = Some(transaction);
+        }
+        if let Some(transaction) = fitting {
+            transactions.push(Self::finish(&transaction, &group)?);
+        }
 
-        // Step 5: Create new transaction with combined instructions
-        // Use Message::new to compile instructions properly - this handles account key ordering
-        let message = solana_sdk::message::Message::new(&instructions, Some(user_pubkey));
-        let combined_tx = Transaction {
-            signatures: vec![solana_sdk::signature::Signature::default(); message.header.num_required_signatures as usize],
-            message,
-        };
-        // Override the blockhash with the one we want to use
-        let mut combined_tx = combined_tx;
-        combined_tx.message.recent_blockhash = recent_blockhash;
+        info!("Batch of {} swaps built as {} transactions", legs.len(), transactions.len());
+        Ok(transactions)
+    }
 
-        // Step 6: Serialize transaction
-        let tx_bytes = bincode::serialize(&combined_tx)
-            .map_err(|e| TransactionBuilderError::SerializeError(format!("Failed to serialize transaction: {}", e)))?;
+    /// Unsigned transaction executing the swaps of `legs` at `group`
+    fn compile(
+        &self,
+        user_pubkey: &Pubkey,
+        legs: &[BatchSwapLeg],
+        group: &[usize],
+        fee_recipient: Option<Pubkey>,
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction, TransactionBuilderError> {
+        let swaps: Vec<&SwapParams> = group.iter().map(|&i| &legs[i].params).collect();
+        let mut account_keys = Vec::new();
+        let mut instructions = vec![
+            // The router validates parameters before the swaps execute
+            self.build_batch_swap_instruction(user_pubkey, &swaps, fee_recipient, &mut account_keys)?,
+        ];
 
-        let tx_base64 = general_purpose::STANDARD.encode(&tx_bytes);
+        let compute_budget = Pubkey::from_str(COMPUTE_BUDGET_PROGRAM_ID).expect("Invalid compute budget program ID");
+        let mut lookup_tables: Vec<AddressLookupTableAccount> = Vec::new();
+        for (position, &i) in group.iter().enumerate() {
+            instructions.extend(
+                legs[i].instructions.iter()
+                    .filter(|ix| position == 0 || ix.program_id != compute_budget)
+                    .cloned(),
+            );
+            // Routes through the same pools share tables
+            for table in &legs[i].lookup_tables {
+                if !lookup_tables.iter().any(|known| known.key == table.key) {
+                    lookup_tables.push(table.clone());
+                }
+            }
+        }
 
-        info!("Batch swap transaction built successfully");
+        let message = compile_message(user_pubkey, &instructions, &lookup_tables, recent_blockhash)?;
+        Ok(unsigned_transaction(message))
+    }
 
-        Ok(tx_base64)
+    /// Encode the transaction executing the swaps at `group`
+    fn finish(transaction: &VersionedTransaction, group: &[usize]) -> Result<BatchSwapTransaction, TransactionBuilderError> {
+        let (encoded, size) = encode_transaction(transaction)?;
+        Ok(BatchSwapTransaction {
+            transaction: encoded,
+            version: message_version(&transaction.message),
+            swap_indices: group.to_vec(),
+            size,
+        })
     }
 
     /// Build batch swap instruction manually
@@ -188,7 +402,7 @@ impl BatchSwapTransactionBuilder {
     fn build_batch_swap_instruction(
         &self,
         authority: &Pubkey,
-        swaps: &[SwapParams],
+        swaps: &[&SwapParams],
         fee_recipient: Option<Pubkey>,
         account_keys: &mut Vec<Pubkey>,
     ) -> Result<Instruction, TransactionBuilderError> {
@@ -357,7 +571,9 @@ impl BatchSwapTransactionBuilder {
     ///
     /// # Arguments
     ///
-    /// * `jupiter_tx_base64` - Base64-encoded Jupiter swap transaction
+    /// * `jupiter_instructions` - Instructions of the Jupiter swap transaction
+    ///   (see [`jupiter_instructions`])
+    /// * `lookup_tables` - Lookup tables the Jupiter transaction loaded accounts from
     /// * `user_pubkey` - User's public key (authority)
     /// * `input_token_account` - Input token account
     /// * `output_token_account` - Output token account
@@ -372,10 +588,11 @@ impl BatchSwapTransactionBuilder {
     ///
     /// # Returns
     ///
-    /// Base64-encoded transaction ready for signing
+    /// Base64-encoded transaction ready for signing, and its message format
     pub fn build_execute_swap_transaction(
         &self,
-        jupiter_tx_base64: &str,
+        jupiter_instructions: Vec<Instruction>,
+        lookup_tables: &[AddressLookupTableAccount],
         user_pubkey: &Pubkey,
         input_token_account: &Pubkey,
         output_token_account: &Pubkey,
@@ -387,45 +604,11 @@ impl BatchSwapTransactionBuilder {
         fee_recipient: Option<Pubkey>,
         recent_blockhash: solana_sdk::hash::Hash,
         _last_valid_block_height: u64,
-    ) -> Result<String, TransactionBuilderError> {
+    ) -> Result<(String, TransactionVersion), TransactionBuilderError> {
         info!("Building execute swap transaction");
 
-        // Step 1: Decode Jupiter transaction
-        let jupiter_tx_bytes = general_purpose::STANDARD
-            .decode(jupiter_tx_base64)
-            .map_err(|e| TransactionBuilderError::DecodeError(format!("Failed to decode Jupiter transaction: {}", e)))?;
-
-        let jupiter_tx: Transaction = bincode::deserialize(&jupiter_tx_bytes)
-            .map_err(|e| TransactionBuilderError::DecodeError(format!("Failed to deserialize Jupiter transaction: {}", e)))?;
-
-        // Step 2: Extract instructions from Jupiter transaction
-        // Convert CompiledInstructions to Instructions so we can add our instruction
-        // Message::new will recompile everything properly
-        let mut instructions: Vec<Instruction> = jupiter_tx.message.instructions.iter()
-            .map(|compiled_ix| {
-                Instruction {
-                    program_id: jupiter_tx.message.account_keys[compiled_ix.program_id_index as usize],
-                    accounts: compiled_ix.accounts.iter()
-                        .map(|&idx| {
-                            let pubkey = jupiter_tx.message.account_keys[idx as usize];
-                            let is_signer = jupiter_tx.message.is_signer(idx as usize);
-                            // Check if account is writable by checking if it's in the writable accounts list
-                            let is_writable = jupiter_tx.message.is_maybe_writable(idx as usize, None);
-                            if is_writable {
-                                AccountMeta::new(pubkey, is_signer)
-                            } else {
-                                AccountMeta::new_readonly(pubkey, is_signer)
-                            }
-                        })
-                        .collect(),
-                    data: compiled_ix.data.clone(),
-                }
-            })
-            .collect();
-
-        // Step 3: Build execute swap instruction (use the private method with account_keys)
-        // Create a temporary account_keys vec for the instruction builder
-        let mut temp_account_keys = jupiter_tx.message.account_keys.clone();
+        // Step 1: Build execute swap instruction (use the private method with account_keys)
+        let mut temp_account_keys = Vec::new();
         let execute_swap_instruction = self.build_execute_swap_instruction_with_accounts(
             user_pubkey,
             input_token_account,
@@ -439,28 +622,20 @@ impl BatchSwapTransactionBuilder {
             &mut temp_account_keys,
         )?;
 
-        // Step 4: Add execute swap instruction at the beginning
+        // Step 2: Add execute swap instruction at the beginning
+        let mut instructions = jupiter_instructions;
         instructions.insert(0, execute_swap_instruction);
 
-        // Step 5: Create new transaction
-        // Use Message::new to compile instructions properly - this handles account key ordering
-        let message = solana_sdk::message::Message::new(&instructions, Some(user_pubkey));
-        let mut combined_tx = Transaction {
-            signatures: vec![solana_sdk::signature::Signature::default(); message.header.num_required_signatures as usize],
-            message,
-        };
-        // Override the blockhash with the one we want to use
-        combined_tx.message.recent_blockhash = recent_blockhash;
-
-        // Step 6: Serialize transaction
-        let tx_bytes = bincode::serialize(&combined_tx)
-            .map_err(|e| TransactionBuilderError::SerializeError(format!("Failed to serialize transaction: {}", e)))?;
+        // Step 3: Compile as legacy, or as v0 if the route's accounts need its lookup tables
+        let message = compile_message(user_pubkey, &instructions, lookup_tables, recent_blockhash)?;
+        let version = message_version(&message);
 
-        let tx_base64 = general_purpose::STANDARD.encode(&tx_bytes);
+        // Step 4: Serialize transaction
+        let (tx_base64, _) = encode_transaction(&unsigned_transaction(message))?;
 
-        info!("Execute swap transaction built successfully");
+        info!("Execute swap transaction built successfully ({})", version.as_str());
 
-        Ok(tx_base64)
+        Ok((tx_base64, version))
     }
 
     /// Build execute swap instruction with account indices
@@ -543,4 +718,242 @@ impl BatchSwapTransactionBuilder {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::transaction::Transaction;
+
+    const JUPITER_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+    fn builder() -> BatchSwapTransactionBuilder {
+        BatchSwapTransactionBuilder::new(Pubkey::new_unique())
+    }
+
+    /// A swap routed by one instruction carrying `data_len` bytes, with two accounts of its own
+    fn leg(user: &Pubkey, data_len: usize) -> BatchSwapLeg {
+        BatchSwapLeg {
+            params: SwapParams {
+                input_mint: Pubkey::new_unique(),
+                output_mint: Pubkey::new_unique(),
+                amount: 1_000_000,
+                min_output_amount: 990_000,
+            },
+            instructions: vec![Instruction {
+                program_id: Pubkey::from_str(JUPITER_PROGRAM_ID).unwrap(),
+                accounts: vec![
+                    AccountMeta::new(*user, true),
+                    AccountMeta::new(Pubkey::new_unique(), false),
+                    AccountMeta::new(Pubkey::new_unique(), false),
+                ],
+                data: vec![7; data_len],
+            }],
+            lookup_tables: vec![],
+        }
+    }
+
+    /// A route touching `accounts` distinct accounts, with a lookup table holding all but the user and program
+    fn route(user: &Pubkey, accounts: usize) -> (Vec<Instruction>, AddressLookupTableAccount) {
+        let pools: Vec<Pubkey> = (2..accounts).map(|_| Pubkey::new_unique()).collect();
+        let instruction = Instruction {
+            program_id: Pubkey::from_str(JUPITER_PROGRAM_ID).unwrap(),
+            accounts: std::iter::once(AccountMeta::new(*user, true))
+                .chain(pools.iter().enumerate().map(|(i, pool)| {
+                    // Mix writable and read-only accounts
+                    if i % 3 == 0 { AccountMeta::new_readonly(*pool, false) } else { AccountMeta::new(*pool, false) }
+                }))
+                .collect(),
+            data: vec![1, 2, 3],
+        };
+        let table = AddressLookupTableAccount { key: Pubkey::new_unique(), addresses: pools };
+        (vec![instruction], table)
+    }
+
+    /// Size of one transaction executing all of `legs`
+    fn size_of_all(builder: &BatchSwapTransactionBuilder, user: &Pubkey, legs: &[BatchSwapLeg]) -> usize {
+        let group: Vec<usize> = (0..legs.len()).collect();
+        serialized_size(&builder.compile(user, legs, &group, None, Hash::default()).unwrap()).unwrap()
+    }
+
+    /// Pad the last leg's data until all legs together take exactly `target` bytes
+    fn fill_to(builder: &BatchSwapTransactionBuilder, user: &Pubkey, legs: &mut [BatchSwapLeg], target: usize) {
+        // Start past 127 bytes so the data length prefix doesn't grow while padding
+        let last = legs.len() - 1;
+        legs[last].instructions[0].data = vec![7; 200];
+        let size = size_of_all(builder, user, legs);
+        legs[last].instructions[0].data = vec![7; 200 + target - size];
+        assert_eq!(size_of_all(builder, user, legs), target);
+    }
+
+    #[test]
+    fn test_small_batch_stays_in_one_transaction() {
+        let builder = builder();
+        let user = Pubkey::new_unique();
+        let legs: Vec<BatchSwapLeg> = (0..3).map(|_| leg(&user, 40)).collect();
+        let blockhash = Hash::new_unique();
+
+        let transactions = builder.build_batch_swap_transactions(&user, &legs, None, blockhash).unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].swap_indices, vec![0, 1, 2]);
+        assert!(transactions[0].size <= MAX_TRANSACTION_SIZE);
+
+        let bytes = general_purpose::STANDARD.decode(&transactions[0].transaction).unwrap();
+        assert_eq!(bytes.len(), transactions[0].size);
+        let transaction: Transaction = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(transaction.message.recent_blockhash, blockhash);
+        // Router instruction first, then one instruction per swap
+        assert_eq!(transaction.message.instructions.len(), 4);
+    }
+
+    #[test]
+    fn test_split_at_the_size_limit() {
+        let builder = builder();
+        let user = Pubkey::new_unique();
+        let mut legs = vec![leg(&user, 100), leg(&user, 0)];
+
+        // Exactly at the limit still fits
+        fill_to(&builder, &user, &mut legs, MAX_TRANSACTION_SIZE);
+        let transactions = builder.build_batch_swap_transactions(&user, &legs, None, Hash::default()).unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].size, MAX_TRANSACTION_SIZE);
+
+        // One byte over moves the second swap to its own transaction
+        legs[1].instructions[0].data.push(7);
+        let transactions = builder.build_batch_swap_transactions(&user, &legs, None, Hash::default()).unwrap();
+        let indices: Vec<Vec<usize>> = transactions.iter().map(|tx| tx.swap_indices.clone()).collect();
+        assert_eq!(indices, vec![vec![0], vec![1]]);
+        assert!(transactions.iter().all(|tx| tx.size <= MAX_TRANSACTION_SIZE));
+    }
+
+    #[test]
+    fn test_large_batch_is_packed_in_order() {
+        let builder = builder();
+        let user = Pubkey::new_unique();
+        let legs: Vec<BatchSwapLeg> = (0..7).map(|_| leg(&user, 300)).collect();
+
+        let transactions = builder.build_batch_swap_transactions(&user, &legs, None, Hash::default()).unwrap();
+        assert!(transactions.len() > 1);
+        let indices: Vec<usize> = transactions.iter().flat_map(|tx| tx.swap_indices.clone()).collect();
+        assert_eq!(indices, (0..7).collect::<Vec<_>>());
+        for (tx, next) in transactions.iter().zip(transactions.iter().skip(1)) {
+            assert!(tx.size <= MAX_TRANSACTION_SIZE);
+            // Packing is greedy: the next swap didn't fit
+            let mut group = tx.swap_indices.clone();
+            group.push(next.swap_indices[0]);
+            let size = serialized_size(&builder.compile(&user, &legs, &group, None, Hash::default()).unwrap()).unwrap();
+            assert!(size > MAX_TRANSACTION_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_swap_too_large_on_its_own() {
+        let builder = builder();
+        let user = Pubkey::new_unique();
+        let mut legs = vec![leg(&user, 40), leg(&user, 0)];
+        fill_to(&builder, &user, &mut legs[1..], MAX_TRANSACTION_SIZE + 1);
+
+        match builder.build_batch_swap_transactions(&user, &legs, None, Hash::default()) {
+            Err(TransactionBuilderError::TransactionTooLarge { swap_index, size }) => {
+                assert_eq!(swap_index, 1);
+                assert_eq!(size, MAX_TRANSACTION_SIZE + 1);
+            }
+            other => panic!("expected TransactionTooLarge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_compute_budget_set_once_per_transaction() {
+        let builder = builder();
+        let user = Pubkey::new_unique();
+        let compute_budget = Pubkey::from_str(COMPUTE_BUDGET_PROGRAM_ID).unwrap();
+        let legs: Vec<BatchSwapLeg> = (0..2)
+            .map(|_| {
+                let mut leg = leg(&user, 20);
+                leg.instructions.insert(0, Instruction::new_with_bytes(compute_budget, &[2, 0, 0, 0, 0], vec![]));
+                leg
+            })
+            .collect();
+
+        let transactions = builder.build_batch_swap_transactions(&user, &legs, None, Hash::default()).unwrap();
+        assert_eq!(transactions.len(), 1);
+        let bytes = general_purpose::STANDARD.decode(&transactions[0].transaction).unwrap();
+        let transaction: Transaction = bincode::deserialize(&bytes).unwrap();
+        let message = &transaction.message;
+        let budgets = message.instructions.iter()
+            .filter(|ix| message.account_keys[ix.program_id_index as usize] == compute_budget)
+            .count();
+        assert_eq!(budgets, 1);
+    }
+
+    #[test]
+    fn test_compile_message_below_account_limit_stays_legacy() {
+        let user = Pubkey::new_unique();
+        let (instructions, table) = route(&user, LEGACY_ACCOUNT_LIMIT);
+        let blockhash = Hash::new_unique();
+
+        let message = compile_message(&user, &instructions, &[table], blockhash).unwrap();
+        assert_eq!(message_version(&message), TransactionVersion::Legacy);
+        assert_eq!(message.static_account_keys().len(), LEGACY_ACCOUNT_LIMIT);
+        assert!(lookup_table_addresses(&message).is_empty());
+        assert_eq!(*message.recent_blockhash(), blockhash);
+    }
+
+    #[test]
+    fn test_compile_message_above_account_limit_uses_lookup_tables() {
+        let user = Pubkey::new_unique();
+        let (instructions, table) = route(&user, LEGACY_ACCOUNT_LIMIT + 1);
+        let blockhash = Hash::new_unique();
+
+        let message = compile_message(&user, &instructions, std::slice::from_ref(&table), blockhash).unwrap();
+        assert_eq!(message_version(&message), TransactionVersion::V0);
+        // Only the signer and the invoked program stay in the message
+        assert_eq!(message.static_account_keys(), &[user, instructions[0].program_id]);
+        assert_eq!(lookup_table_addresses(&message), vec![table.key]);
+        assert_eq!(*message.recent_blockhash(), blockhash);
+
+        let transaction = unsigned_transaction(message);
+        assert!(serialized_size(&transaction).unwrap() <= MAX_TRANSACTION_SIZE);
+
+        // Decoding through the table gives back the same instructions, writability included
+        let decoded = decode_jupiter_transaction(&encode_transaction(&transaction).unwrap().0).unwrap();
+        assert_eq!(jupiter_instructions(&decoded.message, &[table]).unwrap(), instructions);
+        assert!(matches!(
+            jupiter_instructions(&decoded.message, &[]),
+            Err(TransactionBuilderError::InvalidAccount(_))
+        ));
+    }
+
+    #[test]
+    fn test_compile_message_above_account_limit_without_tables_stays_legacy() {
+        let user = Pubkey::new_unique();
+        let (instructions, _) = route(&user, LEGACY_ACCOUNT_LIMIT + 1);
+
+        let message = compile_message(&user, &instructions, &[], Hash::default()).unwrap();
+        assert_eq!(message_version(&message), TransactionVersion::Legacy);
+        assert!(serialized_size(&unsigned_transaction(message)).unwrap() > MAX_TRANSACTION_SIZE);
+    }
 
+    #[test]
+    fn test_batch_over_account_limit_is_compiled_as_v0() {
+        let builder = builder();
+        let user = Pubkey::new_unique();
+        let legs: Vec<BatchSwapLeg> = (0..2)
+            .map(|_| {
+                let (instructions, table) = route(&user, 25);
+                BatchSwapLeg {
+                    params: leg(&user, 0).params,
+                    instructions,
+                    lookup_tables: vec![table],
+                }
+            })
+            .collect();
+
+        // With the router's accounts, over 50 accounts: too many for one legacy transaction
+        let transactions = builder.build_batch_swap_transactions(&user, &legs, None, Hash::default()).unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].version, TransactionVersion::V0);
+        assert_eq!(transactions[0].swap_indices, vec![0, 1]);
+
+        let transaction = decode_jupiter_transaction(&transactions[0].transaction).unwrap();
+        assert_eq!(lookup_table_addresses(&transaction.message).len(), 2);
+    }
+}
@@ -1,13 +1,28 @@
 //! # Jupiter HTTP Client
 //!
 //! HTTP client wrapper and token caching for Jupiter API.
+//!
+//! The token list is answered from memory whenever one is cached. It starts
+//! warm from the copy on disk (see [`super::token_store`]) and is revalidated
+//! with `If-None-Match`/`If-Modified-Since` once it is older than
+//! [`TOKEN_LIST_MAX_AGE`], in the background.
 
+use super::token_store::{self, StoredTokenList};
 use super::types::TokenInfo;
-use reqwest::Client;
+use reqwest::{header, Client, StatusCode};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::RwLock;
-use tracing::info;
+use tracing::{info, warn};
+
+/// How long the cached token list is served before it is revalidated
+pub const TOKEN_LIST_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Least time between background token list refreshes, so a Jupiter outage
+/// isn't hit on every request
+pub const TOKEN_REFRESH_RETRY: Duration = Duration::from_secs(60);
 
 /// Cached token list data structure
 pub struct TokenCache {
@@ -15,8 +30,56 @@ pub struct TokenCache {
     symbol_to_mint: HashMap<String, String>,
     /// Full list of tokens
     tokens: Vec<TokenInfo>,
-    /// When the cache was last refreshed
-    last_refresh: std::time::Instant,
+    /// When Jupiter last confirmed the list, by sending it or answering `304`
+    last_refresh: SystemTime,
+    /// Validators Jupiter sent with the list
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl TokenCache {
+    fn new(tokens: Vec<TokenInfo>, etag: Option<String>, last_modified: Option<String>, last_refresh: SystemTime) -> Self {
+        let mut symbol_to_mint = HashMap::new();
+        for token in &tokens {
+            let symbol_upper = token.symbol.to_uppercase();
+            // Keep a verified token's mint when unverified ones share its symbol
+            if token.is_verified() || !symbol_to_mint.contains_key(&symbol_upper) {
+                symbol_to_mint.insert(symbol_upper, token.address.clone());
+            }
+        }
+        Self { symbol_to_mint, tokens, last_refresh, etag, last_modified }
+    }
+
+    fn from_stored(stored: StoredTokenList) -> Self {
+        let fetched = stored.fetched_time();
+        Self::new(stored.tokens, stored.etag, stored.last_modified, fetched)
+    }
+
+    fn to_stored(&self) -> StoredTokenList {
+        StoredTokenList {
+            fetched_at: token_store::unix_secs(self.last_refresh),
+            etag: self.etag.clone(),
+            last_modified: self.last_modified.clone(),
+            tokens: self.tokens.clone(),
+        }
+    }
+
+    /// Time since Jupiter last confirmed the list
+    pub fn age(&self) -> Duration {
+        self.last_refresh.elapsed().unwrap_or_default()
+    }
+}
+
+/// Answer to a conditional token list request
+pub enum TokenListFetch {
+    /// A list, with the validators to send next time
+    Modified {
+        tokens: Vec<TokenInfo>,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+    /// `304 Not Modified`: the cached list is current
+    NotModified,
 }
 
 /// HTTP client wrapper for Jupiter API
@@ -26,6 +89,10 @@ pub struct JupiterHttpClient {
     pub token_api_base: String,
     /// Cached token list with symbol→mint mapping
     pub token_cache: Arc<RwLock<Option<TokenCache>>>,
+    /// File the token list is kept in across restarts; `None` keeps it in memory only
+    pub token_cache_path: Option<PathBuf>,
+    /// When the last background token list refresh started
+    pub token_refresh_started: Arc<std::sync::Mutex<Option<Instant>>>,
 }
 
 impl JupiterHttpClient {
@@ -35,66 +102,90 @@ impl JupiterHttpClient {
             .timeout(std::time::Duration::from_secs(10))
             .build()
             .map_err(|e| anyhow::anyhow!("Failed to build HTTP client: {}", e))?;
+        let token_cache_path = token_store::path_from_env();
 
         Ok(Self {
             http,
             price_api_base: "https://price.jup.ag/v6".into(),
             token_api_base: "https://token.jup.ag".into(),
-            token_cache: Arc::new(RwLock::new(None)),
+            token_cache: warm_token_cache(token_cache_path.as_deref()),
+            token_cache_path,
+            token_refresh_started: Default::default(),
         })
     }
 
-    /// Load and cache token list from Jupiter API
+    /// Revalidate the token list with Jupiter, then cache and persist it
+    ///
+    /// Sends the cached list's validators, so an unchanged list costs a `304`.
     pub async fn load_token_list(&self) -> anyhow::Result<()> {
-        let tokens = self.get_token_list().await?;
-        
-        let mut symbol_to_mint = HashMap::new();
-        
-        for token in &tokens {
-            let symbol_upper = token.symbol.to_uppercase();
-            symbol_to_mint.insert(symbol_upper, token.address.clone());
-        }
-        
-        let cache = TokenCache {
-            symbol_to_mint,
-            tokens,
-            last_refresh: std::time::Instant::now(),
-        };
-        
-        *self.token_cache.write().await = Some(cache);
-        info!("Token list cached ({} tokens)", self.token_cache.read().await.as_ref().map(|c| c.tokens.len()).unwrap_or(0));
-        
-        Ok(())
+        self.refresh_token_list(false).await
     }
 
-    /// Get mint address for a token symbol
-    pub async fn get_mint_for_symbol(&self, symbol: &str) -> Option<String> {
-        let needs_refresh = {
+    /// Fetch the token list; `force` downloads it in full even if it's unchanged
+    pub async fn refresh_token_list(&self, force: bool) -> anyhow::Result<()> {
+        let (etag, last_modified) = if force {
+            (None, None)
+        } else {
             let cache = self.token_cache.read().await;
-            if let Some(ref cache) = *cache {
-                if cache.last_refresh.elapsed() > std::time::Duration::from_secs(3600) {
-                    true
-                } else {
-                    return cache.symbol_to_mint.get(&symbol.to_uppercase()).cloned();
+            cache.as_ref().map(|c| (c.etag.clone(), c.last_modified.clone())).unwrap_or_default()
+        };
+        let fetch = self.fetch_token_list(etag.as_deref(), last_modified.as_deref()).await?;
+
+        let stored = {
+            let mut cache = self.token_cache.write().await;
+            match fetch {
+                TokenListFetch::NotModified => {
+                    let cache = cache
+                        .as_mut()
+                        .ok_or_else(|| anyhow::anyhow!("Jupiter answered 304 but no token list is cached"))?;
+                    cache.last_refresh = SystemTime::now();
+                    info!("Token list unchanged ({} tokens)", cache.tokens.len());
+                }
+                TokenListFetch::Modified { tokens, etag, last_modified } => {
+                    info!("Token list cached ({} tokens)", tokens.len());
+                    *cache = Some(TokenCache::new(tokens, etag, last_modified, SystemTime::now()));
                 }
-            } else {
-                false
             }
+            cache.as_ref().map(TokenCache::to_stored)
         };
-        
-        if needs_refresh {
-            let client = self.clone_for_refresh();
-            tokio::spawn(async move {
-                if let Err(e) = client.load_token_list().await {
-                    tracing::warn!("Failed to refresh token list: {}", e);
-                }
-            });
-            return self.token_cache.read().await.as_ref()
-                .and_then(|c| c.symbol_to_mint.get(&symbol.to_uppercase()))
-                .cloned();
+
+        // Losing the disk copy only costs a download on the next restart
+        if let (Some(path), Some(stored)) = (&self.token_cache_path, stored) {
+            if let Err(e) = token_store::save(path, &stored).await {
+                warn!("Failed to save token list to {}: {}", path.display(), e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Revalidate the token list in the background
+    ///
+    /// Skipped when a refresh started less than [`TOKEN_REFRESH_RETRY`] ago,
+    /// whether it is still running or failed.
+    pub fn spawn_token_refresh(&self) {
+        {
+            let mut started = self.token_refresh_started.lock().unwrap_or_else(|e| e.into_inner());
+            if started.is_some_and(|at| at.elapsed() < TOKEN_REFRESH_RETRY) {
+                return;
+            }
+            *started = Some(Instant::now());
+        }
+        let client = self.clone_for_refresh();
+        tokio::spawn(async move {
+            if let Err(e) = client.load_token_list().await {
+                warn!("Failed to refresh token list, serving cached copy: {}", e);
+            }
+        });
+    }
+
+    /// Get mint address for a token symbol
+    pub async fn get_mint_for_symbol(&self, symbol: &str) -> Option<String> {
+        let cache = self.token_cache.read().await;
+        let cache = cache.as_ref()?;
+        if cache.age() > Duration::from_secs(3600) {
+            self.spawn_token_refresh();
         }
-        
-        None
+        cache.symbol_to_mint.get(&symbol.to_uppercase()).cloned()
     }
 
     /// Get all cached tokens
@@ -103,6 +194,29 @@ impl JupiterHttpClient {
         cache.as_ref().map(|c| c.tokens.clone())
     }
 
+    /// Cached token list, answered right away whenever one is cached.
+    ///
+    /// A list older than [`TOKEN_LIST_MAX_AGE`] is revalidated in the
+    /// background and served meanwhile, and for as long as Jupiter can't be
+    /// reached. Only with nothing cached, in memory or on disk, is Jupiter
+    /// waited on and its error returned.
+    pub async fn get_cached_token_list(&self) -> anyhow::Result<Vec<TokenInfo>> {
+        {
+            let cache = self.token_cache.read().await;
+            if let Some(cache) = cache.as_ref() {
+                if cache.age() >= TOKEN_LIST_MAX_AGE {
+                    self.spawn_token_refresh();
+                }
+                return Ok(cache.tokens.clone());
+            }
+        }
+
+        self.load_token_list().await?;
+        self.get_all_tokens()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Token list cache is empty"))
+    }
+
     /// Clone self for async refresh task
     fn clone_for_refresh(&self) -> Self {
         Self {
@@ -110,21 +224,193 @@ impl JupiterHttpClient {
             price_api_base: self.price_api_base.clone(),
             token_api_base: self.token_api_base.clone(),
             token_cache: Arc::clone(&self.token_cache),
+            token_cache_path: self.token_cache_path.clone(),
+            token_refresh_started: Arc::clone(&self.token_refresh_started),
         }
     }
 
     /// Fetch complete token list with metadata from Jupiter
     pub async fn get_token_list(&self) -> anyhow::Result<Vec<TokenInfo>> {
+        match self.fetch_token_list(None, None).await? {
+            TokenListFetch::Modified { tokens, .. } => Ok(tokens),
+            TokenListFetch::NotModified => Err(anyhow::anyhow!("Jupiter answered 304 to an unconditional request")),
+        }
+    }
+
+    /// Request the token list, conditionally when validators are given
+    pub async fn fetch_token_list(&self, etag: Option<&str>, last_modified: Option<&str>) -> anyhow::Result<TokenListFetch> {
         let url = format!("{}/all", self.token_api_base);
+        let mut request = self.http.get(&url);
+        if let Some(etag) = etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
 
-        self.http
-            .get(&url)
+        let response = request
             .send()
             .await
-            .map_err(|e| anyhow::anyhow!("Jupiter token list request failed: {}", e))?
+            .map_err(|e| anyhow::anyhow!("Jupiter token list request failed: {}", e))?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(TokenListFetch::NotModified);
+        }
+        let response = response
+            .error_for_status()
+            .map_err(|e| anyhow::anyhow!("Jupiter token list request failed: {}", e))?;
+
+        let validator = |name: header::HeaderName| {
+            response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string)
+        };
+        let etag = validator(header::ETAG);
+        let last_modified = validator(header::LAST_MODIFIED);
+        let tokens = response
             .json()
             .await
-            .map_err(|e| anyhow::anyhow!("Jupiter token list parse failed: {}", e))
+            .map_err(|e| anyhow::anyhow!("Jupiter token list parse failed: {}", e))?;
+        Ok(TokenListFetch::Modified { tokens, etag, last_modified })
     }
 }
 
+/// Token cache holding the list stored at `path`, if there is one
+pub fn warm_token_cache(path: Option<&Path>) -> Arc<RwLock<Option<TokenCache>>> {
+    let stored = path.and_then(|path| {
+        let stored = token_store::load(path)?;
+        info!("Token list loaded from {} ({} tokens)", path.display(), stored.tokens.len());
+        Some(stored)
+    });
+    Arc::new(RwLock::new(stored.map(TokenCache::from_stored)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::HeaderMap, response::{IntoResponse, Response}, routing::get, Json, Router};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    const ETAG: &str = "\"v1\"";
+
+    /// Stand-in for token.jup.ag
+    #[derive(Default)]
+    struct MockJupiter {
+        down: AtomicBool,
+        full: AtomicUsize,
+        not_modified: AtomicUsize,
+    }
+
+    async fn all(State(mock): State<Arc<MockJupiter>>, headers: HeaderMap) -> Response {
+        if mock.down.load(Ordering::SeqCst) {
+            return axum::http::StatusCode::SERVICE_UNAVAILABLE.into_response();
+        }
+        if headers.get(axum::http::header::IF_NONE_MATCH).is_some_and(|v| v == ETAG) {
+            mock.not_modified.fetch_add(1, Ordering::SeqCst);
+            return axum::http::StatusCode::NOT_MODIFIED.into_response();
+        }
+        mock.full.fetch_add(1, Ordering::SeqCst);
+        ([(axum::http::header::ETAG, ETAG)], Json(vec![token("SOL")])).into_response()
+    }
+
+    async fn serve(mock: Arc<MockJupiter>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/all", get(all)).with_state(mock);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn token(symbol: &str) -> TokenInfo {
+        TokenInfo {
+            address: format!("{}Mint1111111111111111111111111111111111111", symbol),
+            symbol: symbol.to_string(),
+            name: symbol.to_string(),
+            decimals: 9,
+            logo_uri: None,
+            tags: vec!["verified".to_string()],
+            daily_volume: None,
+        }
+    }
+
+    fn client(base: String, path: Option<PathBuf>) -> JupiterHttpClient {
+        JupiterHttpClient {
+            http: Client::new(),
+            price_api_base: base.clone(),
+            token_api_base: base,
+            token_cache: warm_token_cache(path.as_deref()),
+            token_cache_path: path,
+            token_refresh_started: Default::default(),
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("jupiter-tokens-{}-{}.json", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_token_list_loads_warm_from_disk() {
+        let mock = Arc::new(MockJupiter::default());
+        let base = serve(mock.clone()).await;
+        let path = temp_path("warm");
+
+        let first = client(base.clone(), Some(path.clone()));
+        assert_eq!(first.get_cached_token_list().await.unwrap().len(), 1);
+        assert_eq!(mock.full.load(Ordering::SeqCst), 1);
+
+        // A restart answers from the file without asking Jupiter
+        mock.down.store(true, Ordering::SeqCst);
+        let restarted = client(base, Some(path.clone()));
+        let tokens = restarted.get_cached_token_list().await.unwrap();
+        assert_eq!(tokens[0].symbol, "SOL");
+        assert_eq!(restarted.get_mint_for_symbol("sol").await, Some(tokens[0].address.clone()));
+        assert_eq!(mock.full.load(Ordering::SeqCst), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_stale_list_served_while_jupiter_down() {
+        let mock = Arc::new(MockJupiter::default());
+        mock.down.store(true, Ordering::SeqCst);
+        let base = serve(mock.clone()).await;
+
+        // Nothing cached: the outage is an error
+        assert!(client(base.clone(), None).get_cached_token_list().await.is_err());
+
+        let path = temp_path("stale");
+        let two_days_ago = token_store::unix_secs(SystemTime::now()) - 2 * 24 * 60 * 60;
+        let stored = StoredTokenList { fetched_at: two_days_ago, etag: None, last_modified: None, tokens: vec![token("JUP")] };
+        token_store::save(&path, &stored).await.unwrap();
+
+        let client = client(base, Some(path.clone()));
+        assert_eq!(client.get_cached_token_list().await.unwrap()[0].symbol, "JUP");
+        // A refresh was started in the background; it fails and the list stays
+        assert!(client.token_refresh_started.lock().unwrap().is_some());
+        assert!(client.refresh_token_list(false).await.is_err());
+        assert_eq!(client.get_cached_token_list().await.unwrap()[0].symbol, "JUP");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_etag_revalidation() {
+        let mock = Arc::new(MockJupiter::default());
+        let base = serve(mock.clone()).await;
+        let client = client(base, None);
+
+        client.load_token_list().await.unwrap();
+        assert_eq!(client.token_cache.read().await.as_ref().unwrap().etag.as_deref(), Some(ETAG));
+
+        // Unchanged: a 304 keeps the list and counts as confirmed
+        client.token_cache.write().await.as_mut().unwrap().last_refresh = SystemTime::now() - TOKEN_LIST_MAX_AGE;
+        client.load_token_list().await.unwrap();
+        assert_eq!(mock.not_modified.load(Ordering::SeqCst), 1);
+        assert_eq!(mock.full.load(Ordering::SeqCst), 1);
+        let cache = client.token_cache.read().await;
+        assert_eq!(cache.as_ref().unwrap().tokens.len(), 1);
+        assert!(cache.as_ref().unwrap().age() < Duration::from_secs(60));
+        drop(cache);
+
+        // A forced refresh downloads it regardless
+        client.refresh_token_list(true).await.unwrap();
+        assert_eq!(mock.full.load(Ordering::SeqCst), 2);
+    }
+}
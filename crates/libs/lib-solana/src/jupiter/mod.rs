@@ -5,6 +5,7 @@
 // region: --- Modules
 pub mod types;
 pub mod client;
+pub mod token_store;
 pub mod quote;
 pub mod swap;
 pub mod price;
@@ -21,6 +22,7 @@ pub struct JupiterClientBuilder {
     timeout: Option<std::time::Duration>,
     price_api_base: Option<String>,
     token_api_base: Option<String>,
+    token_cache_path: Option<std::path::PathBuf>,
 }
 
 impl Default for JupiterClientBuilder {
@@ -29,6 +31,7 @@ impl Default for JupiterClientBuilder {
             timeout: Some(std::time::Duration::from_secs(10)),
             price_api_base: Some("https://price.jup.ag/v6".to_string()),
             token_api_base: Some("https://token.jup.ag".to_string()),
+            token_cache_path: token_store::path_from_env(),
         }
     }
 }
@@ -52,7 +55,15 @@ impl JupiterClientBuilder {
         self
     }
 
+    /// Set the file the token list is kept in across restarts (`None`: memory only).
+    pub fn token_cache_path(mut self, path: Option<std::path::PathBuf>) -> Self {
+        self.token_cache_path = path;
+        self
+    }
+
     /// Build the JupiterClient with configured settings.
+    ///
+    /// The token list stored at the cache path, if any, is loaded here.
     pub fn build(self) -> anyhow::Result<JupiterClient> {
         let http = reqwest::Client::builder()
             .timeout(self.timeout.unwrap_or_else(|| std::time::Duration::from_secs(10)))
@@ -63,7 +74,9 @@ impl JupiterClientBuilder {
             http,
             price_api_base: self.price_api_base.unwrap_or_else(|| "https://price.jup.ag/v6".to_string()),
             token_api_base: self.token_api_base.unwrap_or_else(|| "https://token.jup.ag".to_string()),
-            token_cache: std::sync::Arc::new(tokio::sync::RwLock::new(None)),
+            token_cache: client::warm_token_cache(self.token_cache_path.as_deref()),
+            token_cache_path: self.token_cache_path,
+            token_refresh_started: Default::default(),
         };
 
         Ok(JupiterClient { inner })
@@ -112,6 +125,21 @@ impl JupiterClient {
         self.inner.get_all_tokens().await
     }
 
+    /// Resolve a token symbol to `(mint, decimals)` using the cached token list
+    pub async fn resolve_token(&self, symbol: &str) -> Option<(String, u8)> {
+        self.inner.resolve_token(symbol).await
+    }
+
+    /// Token list from the cache, revalidated daily (see [`client::TOKEN_LIST_MAX_AGE`])
+    pub async fn get_cached_token_list(&self) -> anyhow::Result<Vec<types::TokenInfo>> {
+        self.inner.get_cached_token_list().await
+    }
+
+    /// Download the token list again now, even if Jupiter says it is unchanged
+    pub async fn force_refresh_token_list(&self) -> anyhow::Result<()> {
+        self.inner.refresh_token_list(true).await
+    }
+
     /// Fetch complete token list with metadata from Jupiter (direct API call, not cached)
     pub async fn get_token_list(&self) -> anyhow::Result<Vec<types::TokenInfo>> {
         self.inner.get_token_list().await
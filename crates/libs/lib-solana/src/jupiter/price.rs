@@ -31,6 +31,28 @@ impl JupiterHttpClient {
         }
     }
 
+    /// Resolve a token symbol to its mint address and decimals
+    pub async fn resolve_token(&self, symbol: &str) -> Option<(String, u8)> {
+        let symbol_upper = symbol.to_uppercase();
+        if let Some(tokens) = self.get_all_tokens().await {
+            // Scam tokens reuse well-known symbols, so a verified match wins
+            let matching = || tokens.iter().filter(|t| t.symbol.to_uppercase() == symbol_upper);
+            if let Some(token) = matching().find(|t| t.is_verified()).or_else(|| matching().next()) {
+                return Some((token.address.clone(), token.decimals));
+            }
+        }
+
+        // Fallback decimals for the hardcoded mints above
+        let decimals = match symbol_upper.as_str() {
+            "SOL" => 9,
+            "BTC" | "WBTC" | "ETH" | "WETH" => 8,
+            "BONK" => 5,
+            "USDC" | "USDT" | "JUP" | "RAY" | "ORCA" | "WIF" => 6,
+            _ => return None,
+        };
+        self.symbol_to_mint(symbol).await.map(|mint| (mint, decimals))
+    }
+
     /// Fetch prices for multiple tokens in a single API call
     pub async fn get_prices(&self, symbols: &[&str]) -> anyhow::Result<HashMap<String, f64>> {
         // Convert symbols to mint addresses
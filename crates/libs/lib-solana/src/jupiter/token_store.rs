@@ -0,0 +1,102 @@
+//! # Token List Store
+//!
+//! The Jupiter token list is several megabytes, so the last copy fetched is
+//! kept on disk and loaded as a warm cache at startup. The file also keeps
+//! the validators Jupiter sent with it (`ETag`, `Last-Modified`), so the
+//! first refresh after a restart is usually a `304 Not Modified`.
+//!
+//! ## Configuration
+//!
+//! - `TOKEN_LIST_CACHE_PATH` - File to keep the list in (default:
+//!   `./data/jupiter-token-list.json`); set it empty to keep the list in
+//!   memory only
+//!
+//! ## File Format
+//!
+//! ```json
+//! {
+//!   "fetched_at": 1718668800,
+//!   "etag": "\"5f3c-1a2b\"",
+//!   "last_modified": "Mon, 17 Jun 2024 12:00:00 GMT",
+//!   "tokens": [{ "address": "So11111111111111111111111111111111111111112", "symbol": "SOL", ... }]
+//! }
+//! ```
+//!
+//! `fetched_at` (Unix seconds) is when Jupiter last confirmed the list,
+//! by sending it or by answering `304`.
+
+use super::types::TokenInfo;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Token list file used when `TOKEN_LIST_CACHE_PATH` isn't set
+const DEFAULT_TOKEN_LIST_PATH: &str = "./data/jupiter-token-list.json";
+
+/// Token list as kept on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredTokenList {
+    /// When Jupiter last confirmed the list, in Unix seconds
+    pub fetched_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    pub tokens: Vec<TokenInfo>,
+}
+
+impl StoredTokenList {
+    /// `fetched_at` as a [`SystemTime`]
+    pub fn fetched_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.fetched_at)
+    }
+}
+
+/// Seconds since the Unix epoch for `time`
+pub fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Path from `TOKEN_LIST_CACHE_PATH`, falling back to the default; `None` when disabled
+pub fn path_from_env() -> Option<PathBuf> {
+    match std::env::var("TOKEN_LIST_CACHE_PATH") {
+        Ok(path) if path.trim().is_empty() => None,
+        Ok(path) => Some(PathBuf::from(path)),
+        Err(_) => Some(PathBuf::from(DEFAULT_TOKEN_LIST_PATH)),
+    }
+}
+
+/// Read the stored list; a missing or unreadable file is no list
+///
+/// Runs once at startup, before there is anything to serve, so it reads
+/// synchronously.
+pub fn load(path: &Path) -> Option<StoredTokenList> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            tracing::warn!("Failed to read token list cache {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    match serde_json::from_slice::<StoredTokenList>(&bytes) {
+        Ok(stored) if !stored.tokens.is_empty() => Some(stored),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!("Ignoring corrupt token list cache {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Write the list, replacing the file only once the new copy is complete
+pub async fn save(path: &Path, stored: &StoredTokenList) -> anyhow::Result<()> {
+    let bytes = serde_json::to_vec(stored)?;
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let partial = path.with_extension("json.partial");
+    tokio::fs::write(&partial, &bytes).await?;
+    tokio::fs::rename(&partial, path).await?;
+    Ok(())
+}
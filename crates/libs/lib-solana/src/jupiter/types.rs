@@ -24,6 +24,9 @@ pub struct JupiterPriceData {
     pub price: f64,
 }
 
+/// Jupiter tags that mark a token as reviewed rather than merely listed
+pub const VERIFIED_TAGS: [&str; 3] = ["verified", "strict", "community"];
+
 /// Token information from Jupiter token list
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TokenInfo {
@@ -31,9 +34,32 @@ pub struct TokenInfo {
     pub symbol: String,
     pub name: String,
     pub decimals: u8,
-    #[serde(rename = "logoURI")]
+    #[serde(rename = "logoURI", default)]
     pub logo_uri: Option<String>,
+    /// Jupiter tags, e.g. `verified`, `strict`, `lst`, `token-2022`.
+    /// Unreviewed tokens may send `null` or no tags at all.
+    #[serde(default, deserialize_with = "null_as_empty")]
     pub tags: Vec<String>,
+    /// Traded volume over the last 24h in USD, when Jupiter reports it
+    #[serde(default)]
+    pub daily_volume: Option<f64>,
+}
+
+impl TokenInfo {
+    /// Whether Jupiter has reviewed this token. Anyone can list a token
+    /// under a well-known name, so unverified ones should be treated as suspect.
+    pub fn is_verified(&self) -> bool {
+        self.tags
+            .iter()
+            .any(|tag| VERIFIED_TAGS.iter().any(|verified| tag.eq_ignore_ascii_case(verified)))
+    }
+}
+
+fn null_as_empty<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<Vec<String>>::deserialize(deserializer)?.unwrap_or_default())
 }
 
 /// Response from Jupiter quote API
@@ -94,3 +120,59 @@ pub struct SwapTransactionResponse {
     pub prioritization_fee_lamports: Option<u64>,
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_list_tags_from_jupiter_response() {
+        // Trimmed from token.jup.ag/all: extra fields are ignored, and
+        // unreviewed tokens come with null or missing tags and logo
+        let json = r#"[
+            {
+                "address": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+                "chainId": 101,
+                "decimals": 6,
+                "name": "USD Coin",
+                "symbol": "USDC",
+                "logoURI": "https://example.com/usdc.png",
+                "tags": ["verified", "community", "strict"],
+                "extensions": { "coingeckoId": "usd-coin" }
+            },
+            {
+                "address": "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn",
+                "decimals": 9,
+                "name": "Jito Staked SOL",
+                "symbol": "JitoSOL",
+                "logoURI": null,
+                "tags": ["Strict", "lst"]
+            },
+            {
+                "address": "FakeUsdc111111111111111111111111111111111111",
+                "decimals": 6,
+                "name": "USD Coin",
+                "symbol": "USDC",
+                "tags": null
+            },
+            {
+                "address": "Pump1111111111111111111111111111111111111111",
+                "decimals": 6,
+                "name": "Moon",
+                "symbol": "MOON",
+                "tags": ["unknown", "pump"]
+            }
+        ]"#;
+
+        let tokens: Vec<TokenInfo> = serde_json::from_str(json).unwrap();
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[0].tags, vec!["verified", "community", "strict"]);
+        assert_eq!(tokens[0].logo_uri.as_deref(), Some("https://example.com/usdc.png"));
+        assert!(tokens[0].is_verified());
+        assert!(tokens[1].is_verified());
+        assert_eq!(tokens[1].logo_uri, None);
+        assert!(tokens[2].tags.is_empty());
+        assert!(!tokens[2].is_verified());
+        assert!(!tokens[3].is_verified());
+    }
+}
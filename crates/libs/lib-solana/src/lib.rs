@@ -10,8 +10,13 @@ pub mod pyth;
 pub mod types;
 pub mod cache;
 pub mod candle_aggregator;
+pub mod candle_replay;
+pub mod tick_journal;
+pub mod analytics;
+pub mod aggregate;
 pub mod price_stream;
 pub mod spl_token;
+pub mod stake;
 
 // Import mod_rs to re-export its content
 pub mod mod_rs;
@@ -97,10 +97,10 @@ use crate::price_stream;
 use crate::candle_aggregator;
 
 // Re-export types for convenience
-pub use client::{SolanaClient, Network};
+pub use client::{SolanaClient, Network, SimulatedAccount, SimulationResult};
 pub use jupiter::{JupiterClient, JupiterPriceData, TokenInfo};
-pub use pyth::PythClient;
-pub use cache::PriceCache;
+pub use pyth::{PythClient, PythPrice};
+pub use cache::{MarketCacheTtls, MarketCaches, PriceCache};
 pub use types::{PriceData, PriceResponse, PriceQuery};
 pub use spl_token::{SplTokenClient, TokenAccountInfo, TokenBalance};
 pub use contracts::{ContractRegistry, PluginLoader, ContractPlugin};
@@ -122,6 +122,7 @@ use std::sync::Arc;
 /// * `jupiter` - Jupiter Aggregator client for DEX swaps and token metadata
 /// * `pyth` - Pyth Network oracle client for real-time price feeds
 /// * `price_cache` - Intelligent caching layer with multi-source fallback
+/// * `market_cache` - Response caches of the market endpoints, shared across requests
 /// * `spl_token` - SPL token client for querying token accounts and balances
 ///
 /// # Example
@@ -151,6 +152,7 @@ pub struct SolanaState {
     pub jupiter: Arc<JupiterClient>,
     pub pyth: Arc<PythClient>,
     pub price_cache: Arc<PriceCache>,
+    pub market_cache: Arc<MarketCaches>,
     pub spl_token: Arc<SplTokenClient>,
     pub contracts: Arc<ContractRegistry>,
 }
@@ -222,6 +224,13 @@ impl SolanaState {
         let price_cache = Arc::new(PriceCache::new(jupiter.clone(), pyth.clone()));
         tracing::info!("Price cache initialized (Pyth + Jupiter fallback)");
 
+        let ttls = MarketCacheTtls::from_env();
+        let market_cache = Arc::new(MarketCaches::new(ttls));
+        tracing::info!(
+            "Market response caches ready (prices {:?}, tokens {:?}, candles {:?})",
+            ttls.prices, ttls.token_list, ttls.ohlc
+        );
+
         // Create RPC URL for SPL token client
         let rpc_url = match network {
             Network::Mainnet => {
@@ -245,6 +254,7 @@ impl SolanaState {
             jupiter,
             pyth,
             price_cache,
+            market_cache,
             spl_token,
             contracts,
         })
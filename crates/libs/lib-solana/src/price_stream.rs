@@ -1,39 +1,48 @@
 //! # Real-Time Price Stream Server
 //!
-//! WebSocket server that streams real-time price updates for all tokens from Jupiter API.
+//! WebSocket server that streams real-time price updates from Jupiter API.
 //!
 //! ## Features
 //! - Sub-second price updates (500ms-1s polling)
-//! - Supports all tokens from Jupiter token list
+//! - Streams a managed symbol universe (see [`PriceStreamServer::set_tracked_symbols`]),
+//!   which can be changed at runtime without a restart
 //! - Broadcasts updates to all connected WebSocket clients
 //! - Automatic reconnection handling
 //! - Rate limiting to respect Jupiter API limits
+//! - With a Pyth client ([`PriceStreamServer::with_pyth`]), each update also
+//!   carries the oracle's price and is flagged when the two disagree (see
+//!   [`crate::aggregate`]). Pyth is polled every [`PYTH_REFRESH_INTERVAL`], not
+//!   at the stream's rate.
+//! - A symbol whose sources keep disagreeing raises a
+//!   [`NoticeCategory::PriceDivergence`] system notice, and another once they
+//!   agree again (see [`PriceStreamServer::subscribe_notices`])
+//! - With a [`TickJournal`] ([`PriceStreamServer::with_journal`]), every tick
+//!   fed to the candle aggregator is also journaled for replay
+//! - Every tick also updates the forming candles, which are streamed to
+//!   clients as they change (see [`PriceStreamServer::subscribe_candles`])
+//! - With a [`Metrics`] registry ([`PriceStreamServer::with_metrics`]), the
+//!   Jupiter and Pyth polls are timed and their failures counted
 
+use crate::aggregate::{self, DivergenceAlert, DivergenceMonitor, SourcePrices};
 use crate::jupiter::JupiterClient;
 use crate::candle_aggregator::CandleAggregator;
+use crate::pyth::{PythClient, PythPrice};
+use crate::tick_journal::TickJournal;
+use lib_core::metrics::{time_upstream, Metrics, UPSTREAM_DURATION, UPSTREAM_FAILURES};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
-use tokio::time::Duration;
+use tokio::time::{Duration, Instant};
 use tracing::{debug, info, warn};
-use serde::{Deserialize, Serialize};
-
-/// Price update message sent to WebSocket clients
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PriceUpdateMessage {
-    #[serde(rename = "type")]
-    pub message_type: String,
-    pub data: PriceUpdateData,
-}
 
-/// Price update data payload
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PriceUpdateData {
-    pub symbol: String,
-    pub mint: String,
-    pub price: f64,
-    pub source: String,
-    pub timestamp: u64,
-}
+pub use shared::dto::market::{CandleUpdate, PriceUpdateData, PriceUpdateMessage};
+use shared::dto::system::{NoticeCategory, NoticeLevel, SystemNotice};
+
+/// Time between two polls of the Pyth prices of the tracked symbols
+pub const PYTH_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Oldest Pyth poll still compared with Jupiter's prices
+const PYTH_MAX_AGE: Duration = Duration::from_secs(15);
 
 /// Price stream server that polls Jupiter API and broadcasts updates
 pub struct PriceStreamServer {
@@ -47,6 +56,24 @@ pub struct PriceStreamServer {
     update_interval_ms: u64,
     /// Candle aggregator for OHLC data
     candle_aggregator: Arc<CandleAggregator>,
+    /// When a poll last returned prices (for health reports)
+    last_publish: Arc<RwLock<Option<Instant>>>,
+    /// Pyth client compared against, if any
+    pyth: Option<Arc<PythClient>>,
+    /// Latest Pyth price per symbol and when it was polled
+    pyth_prices: Arc<RwLock<HashMap<String, (PythPrice, Instant)>>>,
+    /// Spread between sources above which an update is flagged, in percent
+    divergence_threshold_pct: f64,
+    /// How long a divergence lasts before it raises a notice
+    divergence_sustain: Duration,
+    /// Sustained divergence per symbol
+    divergence_monitor: std::sync::Mutex<DivergenceMonitor>,
+    /// Divergence and maintenance notices for WebSocket handlers
+    notice_tx: broadcast::Sender<SystemNotice>,
+    /// Journal of the ticks fed to the candle aggregator, if enabled
+    journal: Option<Arc<TickJournal>>,
+    /// Registry the upstream polls are recorded in, if any
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl PriceStreamServer {
@@ -68,9 +95,58 @@ impl PriceStreamServer {
             tracked_symbols: Arc::new(RwLock::new(Vec::new())),
             update_interval_ms,
             candle_aggregator,
+            last_publish: Arc::new(RwLock::new(None)),
+            pyth: None,
+            pyth_prices: Arc::new(RwLock::new(HashMap::new())),
+            divergence_threshold_pct: aggregate::DEFAULT_DIVERGENCE_THRESHOLD_PCT,
+            divergence_sustain: aggregate::DEFAULT_DIVERGENCE_SUSTAIN,
+            divergence_monitor: std::sync::Mutex::new(DivergenceMonitor::new(
+                aggregate::DEFAULT_DIVERGENCE_THRESHOLD_PCT,
+                aggregate::DEFAULT_DIVERGENCE_SUSTAIN,
+            )),
+            notice_tx: broadcast::channel(100).0,
+            journal: None,
+            metrics: None,
         }
     }
 
+    /// Compare every update with Pyth's price for the symbol
+    pub fn with_pyth(mut self, pyth: Arc<PythClient>) -> Self {
+        self.pyth = Some(pyth);
+        self
+    }
+
+    /// Flag updates whose sources disagree by more than `pct` percent
+    pub fn with_divergence_threshold(mut self, pct: f64) -> Self {
+        self.divergence_threshold_pct = pct;
+        self.divergence_monitor = std::sync::Mutex::new(DivergenceMonitor::new(pct, self.divergence_sustain));
+        self
+    }
+
+    /// Raise a divergence notice once a symbol has been flagged for `sustain`
+    pub fn with_divergence_sustain(mut self, sustain: Duration) -> Self {
+        self.divergence_sustain = sustain;
+        self.divergence_monitor = std::sync::Mutex::new(DivergenceMonitor::new(self.divergence_threshold_pct, sustain));
+        self
+    }
+
+    /// Journal every tick fed to the candle aggregator
+    pub fn with_journal(mut self, journal: TickJournal) -> Self {
+        self.journal = Some(Arc::new(journal));
+        self
+    }
+
+    /// Record the duration and failures of the Jupiter and Pyth polls
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// The tick journal, when journaling is enabled
+    pub fn journal(&self) -> Option<Arc<TickJournal>> {
+        self.journal.clone()
+    }
+
     /// Get reference to candle aggregator
     pub fn candle_aggregator(&self) -> Arc<CandleAggregator> {
         Arc::clone(&self.candle_aggregator)
@@ -81,11 +157,33 @@ impl PriceStreamServer {
         self.price_tx.subscribe()
     }
 
+    /// Get a receiver for forming and closed candles (used by WebSocket handlers)
+    pub fn subscribe_candles(&self) -> broadcast::Receiver<CandleUpdate> {
+        self.candle_aggregator.subscribe()
+    }
+
+    /// WebSocket clients currently subscribed to price updates
+    pub fn client_count(&self) -> usize {
+        self.price_tx.receiver_count()
+    }
+
+    /// Get a receiver for divergence and maintenance notices (used by WebSocket handlers)
+    pub fn subscribe_notices(&self) -> broadcast::Receiver<SystemNotice> {
+        self.notice_tx.subscribe()
+    }
+
+    /// Push a notice to every connected WebSocket client
+    ///
+    /// Returns how many clients it went out to.
+    pub fn broadcast_notice(&self, notice: SystemNotice) -> usize {
+        self.notice_tx.send(notice).unwrap_or(0)
+    }
+
     /// Start the price streaming service.
     ///
     /// This spawns a background task that:
-    /// 1. Loads all tokens from Jupiter token list (with retry logic)
-    /// 2. Polls Jupiter API every `update_interval_ms` for all token prices
+    /// 1. Loads the Jupiter token list used for mint lookups (with retry logic)
+    /// 2. Polls Jupiter API every `update_interval_ms` for the tracked symbols' prices
     /// 3. Broadcasts updates to all connected WebSocket clients
     ///
     /// # Arguments
@@ -108,58 +206,44 @@ impl PriceStreamServer {
                               attempt, e, delay_ms);
                         tokio::time::sleep(Duration::from_millis(delay_ms)).await;
                     } else {
-                        warn!("Failed to load token list after {} attempts: {}. Price stream will start with empty token list and retry in background.", attempt, e);
+                        warn!("Failed to load token list after {} attempts: {}. Price stream will retry in background.", attempt, e);
                     }
                 }
             }
         }
         
-        // Get all tokens and extract symbols (if available)
-        let tokens = self.jupiter.get_all_tokens().await;
-        
-        let symbols: Vec<String> = if let Some(tokens) = tokens {
-            tokens.iter()
-                .map(|t| t.symbol.clone())
-                .collect()
-        } else {
-            warn!("Token list not available. Price stream will start with empty token list.");
-            Vec::new()
-        };
-        
-        // Update tracked symbols
-        *self.tracked_symbols.write().await = symbols.clone();
-        if !symbols.is_empty() {
-            info!("Tracking {} tokens for real-time price updates", symbols.len());
-        } else {
-            info!("Price stream started with empty token list. Will retry loading tokens in background.");
-            
-            // Spawn background task to retry loading token list
+        // The token list is only needed for mint lookups; keep retrying in the
+        // background if it could not be loaded
+        if self.jupiter.get_all_tokens().await.is_none() {
+            warn!("Token list not available. Mint lookups will fail until it loads; retrying in background.");
             let jupiter_clone = Arc::clone(&self.jupiter);
-            let symbols_clone = Arc::clone(&self.tracked_symbols);
             tokio::spawn(async move {
                 let mut retry_interval = tokio::time::interval(Duration::from_secs(30));
                 retry_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
                 
                 loop {
                     retry_interval.tick().await;
-                    
-                    if let Ok(()) = jupiter_clone.load_token_list().await {
-                        if let Some(tokens) = jupiter_clone.get_all_tokens().await {
-                            let new_symbols: Vec<String> = tokens.iter()
-                                .map(|t| t.symbol.clone())
-                                .collect();
-                            
-                            *symbols_clone.write().await = new_symbols.clone();
-                            info!("Successfully loaded {} tokens in background retry", new_symbols.len());
-                            break; // Stop retrying once successful
-                        }
+                    if jupiter_clone.load_token_list().await.is_ok() {
+                        info!("Successfully loaded token list in background retry");
+                        break; // Stop retrying once successful
                     }
                 }
             });
         }
+
+        let tracked = self.tracked_symbols.read().await.len();
+        if tracked == 0 {
+            info!("Price stream started with no symbols. Add symbols to begin streaming.");
+        } else {
+            info!("Tracking {} tokens for real-time price updates", tracked);
+        }
         
+        if let Some(pyth) = self.pyth.clone() {
+            self.spawn_pyth_refresh(pyth);
+        }
+
         // Spawn background polling task
-        // This task will run even if token list is empty (it will just skip until tokens are loaded)
+        // This task will run even if no symbols are tracked (it will just skip until some are added)
         let server = Arc::clone(&self);
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_millis(server.update_interval_ms));
@@ -170,8 +254,7 @@ impl PriceStreamServer {
                 
                 let symbols = server.tracked_symbols.read().await.clone();
                 if symbols.is_empty() {
-                    // No tokens loaded yet - skip this cycle but continue running
-                    // The background retry task will load tokens eventually
+                    // Nothing to stream yet - skip this cycle but continue running
                     continue;
                 }
                 
@@ -183,8 +266,17 @@ impl PriceStreamServer {
                 for chunk in symbols.chunks(BATCH_SIZE) {
                     let symbol_refs: Vec<&str> = chunk.iter().map(|s| s.as_str()).collect();
                     
-                    match server.jupiter.get_prices(&symbol_refs).await {
+                    let prices = server.jupiter.get_prices(&symbol_refs);
+                    match time_upstream(server.metrics.as_deref(), "jupiter", "prices", prices).await {
                         Ok(prices) => {
+                            *server.last_publish.write().await = Some(Instant::now());
+
+                            // Drop symbols removed while the request was in flight
+                            let tracked = server.tracked_symbols.read().await.clone();
+                            let prices = prices.into_iter().filter(|(symbol, _)| {
+                                tracked.contains(&symbol.to_uppercase())
+                            });
+
                             // Broadcast each price update
                             for (symbol, price) in prices {
                                 // Get mint address for this symbol
@@ -194,6 +286,10 @@ impl PriceStreamServer {
                                         .unwrap_or_default()
                                         .as_secs();
                                     
+                                    if let Some(journal) = &server.journal {
+                                        journal.record("jupiter", &symbol, price, timestamp);
+                                    }
+
                                     // Update candle aggregator (non-blocking, errors are logged but don't stop the stream)
                                     let candle_agg = Arc::clone(&server.candle_aggregator);
                                     let symbol_clone = symbol.clone();
@@ -201,16 +297,22 @@ impl PriceStreamServer {
                                         candle_agg.add_price_update(&symbol_clone, price, timestamp).await;
                                     });
                                     
-                                    let update = PriceUpdateMessage {
-                                        message_type: "price_update".to_string(),
-                                        data: PriceUpdateData {
-                                            symbol: symbol.clone(),
-                                            mint,
-                                            price,
-                                            source: "jupiter".to_string(),
-                                            timestamp,
-                                        },
-                                    };
+                                    // Jupiter has no confidence interval or publish time
+                                    let sources = server.source_prices(&symbol, price).await;
+                                    let divergence_pct = aggregate::divergence_pct(&sources);
+                                    server.watch_divergence(&symbol, divergence_pct, &sources, timestamp);
+                                    let update = PriceUpdateMessage::new(PriceUpdateData {
+                                        symbol: symbol.clone(),
+                                        mint,
+                                        price,
+                                        source: "jupiter".to_string(),
+                                        timestamp,
+                                        confidence: None,
+                                        publish_time: None,
+                                        divergent: divergence_pct.is_some_and(|pct| pct > server.divergence_threshold_pct),
+                                        divergence_pct,
+                                        sources,
+                                    });
                                     
                                     // Broadcast to all subscribers (non-blocking)
                                     // If send fails (no subscribers), that's fine - just continue
@@ -240,27 +342,184 @@ impl PriceStreamServer {
         Ok(())
     }
 
+    /// Keep the Pyth prices of the tracked symbols fresh in the background
+    fn spawn_pyth_refresh(self: &Arc<Self>, pyth: Arc<PythClient>) {
+        let server = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PYTH_REFRESH_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+            loop {
+                interval.tick().await;
+                // Most of the universe has no Pyth feed; skip those rather than log a failure per poll
+                let symbols: Vec<String> = server
+                    .tracked_symbols
+                    .read()
+                    .await
+                    .iter()
+                    .map(|s| s.to_uppercase())
+                    .filter(|s| pyth.has_price_feed(s))
+                    .collect();
+                let symbol_refs: Vec<&str> = symbols.iter().map(|s| s.as_str()).collect();
+                let prices = if symbol_refs.is_empty() {
+                    HashMap::new()
+                } else {
+                    let started = Instant::now();
+                    let prices = pyth.get_prices(&symbol_refs).await;
+                    if let Some(metrics) = &server.metrics {
+                        // Pyth leaves out the feeds it couldn't read; a poll
+                        // that got none of them counts as failed
+                        let labels = [("upstream", "pyth"), ("call", "prices")];
+                        metrics.observe(&UPSTREAM_DURATION, &labels, started.elapsed().as_secs_f64());
+                        if prices.is_empty() {
+                            metrics.increment(&UPSTREAM_FAILURES, &labels);
+                        }
+                    }
+                    prices
+                };
+                debug!("Refreshed {} of {} Pyth prices", prices.len(), symbols.len());
+
+                let polled_at = Instant::now();
+                let mut pyth_prices = server.pyth_prices.write().await;
+                pyth_prices.retain(|symbol, _| symbols.contains(symbol));
+                for (symbol, price) in prices {
+                    pyth_prices.insert(symbol, (price, polled_at));
+                }
+            }
+        });
+    }
+
+    /// Prices of `symbol` by source: Jupiter's `price` and, when recent, Pyth's
+    async fn source_prices(&self, symbol: &str, price: f64) -> SourcePrices {
+        let mut sources = SourcePrices::from([("jupiter".to_string(), price)]);
+        if let Some((pyth, polled_at)) = self.pyth_prices.read().await.get(&symbol.to_uppercase()) {
+            if polled_at.elapsed() < PYTH_MAX_AGE {
+                sources.insert("pyth".to_string(), pyth.price);
+            }
+        }
+        sources
+    }
+
+    /// Feed an update to the divergence monitor, broadcasting a notice when
+    /// the symbol starts or stops disagreeing for a sustained period
+    fn watch_divergence(&self, symbol: &str, divergence_pct: Option<f64>, sources: &SourcePrices, timestamp: u64) {
+        let alert = match self.divergence_monitor.lock() {
+            Ok(mut monitor) => monitor.observe(&symbol.to_uppercase(), divergence_pct, std::time::Instant::now()),
+            Err(_) => return,
+        };
+        let Some(alert) = alert else {
+            return;
+        };
+
+        let notice = divergence_notice(symbol, alert, sources, self.divergence_sustain, timestamp as i64);
+        warn!(symbol, ?alert, "{}", notice.title);
+        // No WebSocket clients is fine
+        let _ = self.notice_tx.send(notice);
+    }
+
+    /// Configured polling interval
+    pub fn update_interval(&self) -> Duration {
+        Duration::from_millis(self.update_interval_ms)
+    }
+
+    /// Time since a poll last returned prices, or `None` if none has yet
+    pub async fn last_publish_age(&self) -> Option<Duration> {
+        self.last_publish.read().await.map(|at| at.elapsed())
+    }
+
+    /// Symbols currently being streamed (uppercase)
+    pub async fn tracked_symbols(&self) -> Vec<String> {
+        self.tracked_symbols.read().await.clone()
+    }
+
+    /// Replace the streamed symbol universe.
+    ///
+    /// Symbols no longer present have their candle series ended.
+    pub async fn set_tracked_symbols(&self, symbols: &[&str]) {
+        let current = self.tracked_symbols().await;
+        let wanted: Vec<String> = symbols.iter().map(|s| s.to_uppercase()).collect();
+
+        let removed: Vec<&str> = current
+            .iter()
+            .filter(|s| !wanted.contains(s))
+            .map(String::as_str)
+            .collect();
+        self.remove_tokens(&removed).await;
+
+        let wanted_refs: Vec<&str> = wanted.iter().map(String::as_str).collect();
+        self.add_tokens(&wanted_refs).await;
+    }
+
     /// Add tokens to track (dynamically add new tokens)
     pub async fn add_tokens(&self, symbols: &[&str]) {
         let mut tracked = self.tracked_symbols.write().await;
         for symbol in symbols {
             let symbol_upper = symbol.to_uppercase();
             if !tracked.contains(&symbol_upper) {
+                self.candle_aggregator.resume_series(&symbol_upper).await;
                 tracked.push(symbol_upper);
             }
         }
         info!("Now tracking {} tokens", tracked.len());
     }
 
-    /// Remove tokens from tracking
+    /// Remove tokens from tracking and end their candle series
     pub async fn remove_tokens(&self, symbols: &[&str]) {
+        if symbols.is_empty() {
+            return;
+        }
         let mut tracked = self.tracked_symbols.write().await;
         for symbol in symbols {
             let symbol_upper = symbol.to_uppercase();
             tracked.retain(|s| s != &symbol_upper);
+            self.candle_aggregator.end_series(&symbol_upper).await;
+        }
+        if let Ok(mut monitor) = self.divergence_monitor.lock() {
+            monitor.retain(&tracked);
         }
         info!("Now tracking {} tokens", tracked.len());
     }
 }
 
+/// Notice raised when `symbol`'s sources start or stop disagreeing
+fn divergence_notice(
+    symbol: &str,
+    alert: DivergenceAlert,
+    sources: &SourcePrices,
+    sustain: Duration,
+    timestamp: i64,
+) -> SystemNotice {
+    let prices: Vec<String> = sources
+        .iter()
+        .map(|(source, price)| format!("{} ${:.4}", source, price))
+        .collect();
+    let (level, title, message) = match alert {
+        DivergenceAlert::Started { divergence_pct } => (
+            NoticeLevel::Warning,
+            format!("{} price sources disagree", symbol),
+            format!(
+                "{} is {:.2}% apart across sources ({}) for over {}s. One feed may be stale or the token depegging; check before trading.",
+                symbol,
+                divergence_pct,
+                prices.join(", "),
+                sustain.as_secs()
+            ),
+        ),
+        DivergenceAlert::Cleared => (
+            NoticeLevel::Info,
+            format!("{} price sources agree again", symbol),
+            format!("{} prices are back within the divergence threshold", symbol),
+        ),
+    };
+
+    SystemNotice {
+        level,
+        title,
+        message,
+        timestamp,
+        category: NoticeCategory::PriceDivergence,
+        maintenance: None,
+    }
+}
+
 
@@ -17,8 +17,8 @@
 //! ## Example
 //! ```no_run
 //! let client = PythClient::new()?;
-//! let sol_price = client.get_price("SOL").await?;
-//! println!("SOL price from Pyth: ${:.2}", sol_price);
+//! let sol = client.get_price("SOL").await?;
+//! println!("SOL price from Pyth: ${:.2} ± {:.2}", sol.price, sol.confidence);
 //! ```
 //!
 //! ## Documentation
@@ -41,6 +41,17 @@ pub struct PythClient {
     hermes_url: String,
 }
 
+/// Latest aggregate price of a Pyth feed, scaled to USD.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PythPrice {
+    /// Aggregate price in USD
+    pub price: f64,
+    /// Confidence interval in USD: the true price is within `price ± confidence`
+    pub confidence: f64,
+    /// Unix timestamp (seconds) the price was published at
+    pub publish_time: i64,
+}
+
 /// Pyth Hermes API response containing price feed data.
 #[derive(Debug, Deserialize)]
 struct ParsedPrice {
@@ -63,16 +74,28 @@ struct ParsedPrice {
 struct PythPriceData {
     /// Raw price as string (e.g., "14550")
     price: String,
-    /// Confidence interval as string
-    #[allow(dead_code)] // Deserialized but not currently used (may be used in future)
+    /// Confidence interval as string, in the same scale as `price`
     conf: String,
     /// Price exponent (e.g., -2 means divide by 100)
     expo: i32,
     /// Unix timestamp of price publication
-    #[allow(dead_code)] // Deserialized but not currently used (may be used in future)
     publish_time: i64,
 }
 
+impl PythPriceData {
+    /// Apply the exponent to price and confidence
+    fn to_price(&self) -> Result<PythPrice> {
+        let scale = 10_f64.powi(self.expo);
+        let price_raw: i64 = self.price.parse()?;
+        let conf_raw: u64 = self.conf.parse()?;
+        Ok(PythPrice {
+            price: price_raw as f64 * scale,
+            confidence: conf_raw as f64 * scale,
+            publish_time: self.publish_time,
+        })
+    }
+}
+
 impl PythClient {
     /// Create a new Pyth Network API client.
     ///
@@ -125,10 +148,16 @@ impl PythClient {
         }
     }
 
+    /// Whether Pyth publishes a price feed for `symbol`
+    pub fn has_price_feed(&self, symbol: &str) -> bool {
+        self.symbol_to_price_feed_id(symbol).is_some()
+    }
+
     /// Fetch the latest price for a token from Pyth Network.
     ///
     /// This queries the Pyth Hermes API for the most recent price update. Pyth prices
-    /// are typically updated sub-second and include confidence intervals.
+    /// are typically updated sub-second and include confidence intervals and the
+    /// time they were published, which callers use to spot a stalled feed.
     ///
     /// # Price Encoding
     /// Pyth encodes prices as `raw_price * 10^expo` where:
@@ -136,19 +165,21 @@ impl PythClient {
     /// - `expo` is typically negative (e.g., -2)
     /// - Final price = 14550 * 10^(-2) = $145.50
     ///
+    /// The confidence interval uses the same exponent.
+    ///
     /// # Arguments
     /// * `symbol` - Token symbol (e.g., "SOL", "BTC")
     ///
     /// # Returns
-    /// * `Ok(price)` - Current price in USD
+    /// * `Ok(price)` - Current price, confidence and publish time
     /// * `Err(_)` - Unknown symbol, API failure, or parse error
     ///
     /// # Example
     /// ```no_run
-    /// let price = client.get_price("SOL").await?;
-    /// println!("SOL: ${:.2}", price);
+    /// let sol = client.get_price("SOL").await?;
+    /// println!("SOL: ${:.2} ± {:.2}", sol.price, sol.confidence);
     /// ```
-    pub async fn get_price(&self, symbol: &str) -> Result<f64> {
+    pub async fn get_price(&self, symbol: &str) -> Result<PythPrice> {
         let feed_id = self
             .symbol_to_price_feed_id(symbol)
             .ok_or_else(|| anyhow::anyhow!("No Pyth feed for symbol: {}", symbol))?;
@@ -177,13 +208,13 @@ impl PythClient {
             .first()
             .ok_or_else(|| anyhow::anyhow!("No price data in Pyth response"))?;
 
-        // Convert price string to f64 and apply exponent
         // Pyth prices are encoded as: price = raw_price * 10^expo
-        let price_raw: i64 = parsed.price.price.parse()?;
-        let expo = parsed.price.expo;
-        let price = (price_raw as f64) * 10_f64.powi(expo);
+        let price = parsed.price.to_price()?;
 
-        debug!("Pyth LIVE: {} = ${:.4} (raw: {}, expo: {})", symbol, price, price_raw, expo);
+        debug!(
+            "Pyth LIVE: {} = ${:.4} ± {:.4} (published: {})",
+            symbol, price.price, price.confidence, price.publish_time
+        );
         Ok(price)
     }
 
@@ -203,7 +234,7 @@ impl PythClient {
     /// let prices = client.get_prices(&["SOL", "BTC", "ETH"]).await;
     /// println!("Fetched {} prices from Pyth", prices.len());
     /// ```
-    pub async fn get_prices(&self, symbols: &[&str]) -> HashMap<String, f64> {
+    pub async fn get_prices(&self, symbols: &[&str]) -> HashMap<String, PythPrice> {
         let mut prices = HashMap::new();
 
         for symbol in symbols {
@@ -228,3 +259,25 @@ impl Default for PythClient {
         Self::new().expect("Failed to create default PythClient")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_and_confidence_use_exponent() {
+        let data = PythPriceData {
+            price: "14550123".to_string(),
+            conf: "7250".to_string(),
+            expo: -5,
+            publish_time: 1_704_067_200,
+        };
+        let price = data.to_price().unwrap();
+        assert!((price.price - 145.50123).abs() < 1e-9);
+        assert!((price.confidence - 0.0725).abs() < 1e-9);
+        assert_eq!(price.publish_time, 1_704_067_200);
+
+        let bad = PythPriceData { conf: "-1".to_string(), ..data };
+        assert!(bad.to_price().is_err());
+    }
+}
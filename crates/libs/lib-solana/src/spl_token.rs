@@ -1,10 +1,52 @@
-use anyhow::{Context, Result};
+//! # SPL Token Client
+//!
+//! Token balances of a wallet across both token programs: classic SPL Token
+//! and Token-2022 (Token Extensions).
+//!
+//! Accounts are fetched raw (base64) and parsed here rather than through the
+//! RPC's `jsonParsed` encoding, so the transfer-fee extension of Token-2022
+//! mints can be read without depending on the node's parser.
+//!
+//! ## Token-2022 account layout
+//!
+//! ```text
+//! 0..165   base state (mints use the first 82 bytes, the rest is padding)
+//! 165      account type (1 = mint, 2 = token account)
+//! 166..    extensions, each: type u16 LE | length u16 LE | value
+//! ```
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
 use serde::{Deserialize, Serialize};
-use solana_client::rpc_client::RpcClient;
+use shared::dto::tokens::{TokenProgram, TransferFee};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
 use solana_sdk::pubkey::Pubkey;
-use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::{get_associated_token_address, get_associated_token_address_with_program_id};
+use std::collections::HashMap;
 use std::str::FromStr;
 
+/// Size of the base token account state
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Size of the base mint state
+const MINT_LEN: usize = 82;
+
+/// Offset of the account type byte in Token-2022 accounts with extensions
+const ACCOUNT_TYPE_OFFSET: usize = TOKEN_ACCOUNT_LEN;
+
+/// Account type byte of a mint
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+/// Extension type of `TransferFeeConfig`
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+
+/// Length of the `TransferFeeConfig` extension value
+const TRANSFER_FEE_CONFIG_LEN: usize = 108;
+
+/// Most accounts `getMultipleAccounts` accepts per call
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenAccountInfo {
     pub mint: String,
@@ -13,6 +55,10 @@ pub struct TokenAccountInfo {
     pub decimals: u8,
     pub ui_amount: f64,
     pub token_symbol: Option<String>,
+    /// Program owning the account (and its mint)
+    pub program: TokenProgram,
+    /// Transfer fee for the current epoch, for Token-2022 mints with the extension
+    pub transfer_fee: Option<TransferFee>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +71,149 @@ pub struct TokenBalance {
     pub usd_value: Option<f64>,
 }
 
+/// Base fields of a token account, common to both programs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedTokenAccount {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+/// Fee schedule starting at an epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpochTransferFee {
+    pub epoch: u64,
+    pub fee: TransferFee,
+}
+
+/// `TransferFeeConfig` extension of a Token-2022 mint
+///
+/// A mint keeps two schedules so a fee change only takes effect from a
+/// future epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferFeeConfig {
+    pub withheld_amount: u64,
+    pub older: EpochTransferFee,
+    pub newer: EpochTransferFee,
+}
+
+impl TransferFeeConfig {
+    /// Fee in effect during `epoch`
+    pub fn fee_for_epoch(&self, epoch: u64) -> TransferFee {
+        if epoch >= self.newer.epoch {
+            self.newer.fee
+        } else {
+            self.older.fee
+        }
+    }
+}
+
+/// Mint fields needed to show and send balances
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedMint {
+    pub decimals: u8,
+    pub transfer_fee_config: Option<TransferFeeConfig>,
+}
+
+/// Parse the base state of a token account owned by either token program.
+pub fn parse_token_account(data: &[u8]) -> Result<ParsedTokenAccount> {
+    if data.len() < TOKEN_ACCOUNT_LEN {
+        bail!("Token account too short: {} bytes", data.len());
+    }
+    Ok(ParsedTokenAccount {
+        mint: read_pubkey(&data[0..32]),
+        owner: read_pubkey(&data[32..64]),
+        amount: read_u64(&data[64..72]),
+    })
+}
+
+/// Parse a mint owned by either token program, including the transfer-fee
+/// extension when the mint has one.
+pub fn parse_mint(data: &[u8]) -> Result<ParsedMint> {
+    if data.len() < MINT_LEN {
+        bail!("Mint account too short: {} bytes", data.len());
+    }
+    if data[45] != 1 {
+        bail!("Mint is not initialized");
+    }
+
+    let mut transfer_fee_config = None;
+    // Mints without extensions stop at 82 bytes
+    if data.len() > ACCOUNT_TYPE_OFFSET {
+        if data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT {
+            bail!("Account type {} is not a mint", data[ACCOUNT_TYPE_OFFSET]);
+        }
+        if let Some(value) = find_extension(&data[ACCOUNT_TYPE_OFFSET + 1..], EXTENSION_TRANSFER_FEE_CONFIG)? {
+            transfer_fee_config = Some(parse_transfer_fee_config(value)?);
+        }
+    }
+
+    Ok(ParsedMint {
+        decimals: data[44],
+        transfer_fee_config,
+    })
+}
+
+/// Value of the first extension of type `wanted` in a TLV area
+fn find_extension(mut tlv: &[u8], wanted: u16) -> Result<Option<&[u8]>> {
+    while tlv.len() >= 4 {
+        let extension_type = read_u16(&tlv[0..2]);
+        let length = read_u16(&tlv[2..4]) as usize;
+        // Type 0 marks the unused tail of the account
+        if extension_type == 0 {
+            break;
+        }
+        let value = tlv
+            .get(4..4 + length)
+            .ok_or_else(|| anyhow!("Extension {} overruns the account", extension_type))?;
+        if extension_type == wanted {
+            return Ok(Some(value));
+        }
+        tlv = &tlv[4 + length..];
+    }
+    Ok(None)
+}
+
+fn parse_transfer_fee_config(value: &[u8]) -> Result<TransferFeeConfig> {
+    if value.len() != TRANSFER_FEE_CONFIG_LEN {
+        bail!("TransferFeeConfig has {} bytes, expected {}", value.len(), TRANSFER_FEE_CONFIG_LEN);
+    }
+    // Skip the config and withdraw-withheld authorities (2 x 32 bytes)
+    Ok(TransferFeeConfig {
+        withheld_amount: read_u64(&value[64..72]),
+        older: read_epoch_fee(&value[72..90]),
+        newer: read_epoch_fee(&value[90..108]),
+    })
+}
+
+fn read_epoch_fee(bytes: &[u8]) -> EpochTransferFee {
+    EpochTransferFee {
+        epoch: read_u64(&bytes[0..8]),
+        fee: TransferFee {
+            maximum_fee: read_u64(&bytes[8..16]),
+            basis_points: read_u16(&bytes[16..18]),
+        },
+    }
+}
+
+fn read_pubkey(bytes: &[u8]) -> Pubkey {
+    let mut key = [0u8; 32];
+    key.copy_from_slice(bytes);
+    Pubkey::new_from_array(key)
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().expect("8 bytes"))
+}
+
+fn read_u16(bytes: &[u8]) -> u16 {
+    u16::from_le_bytes([bytes[0], bytes[1]])
+}
+
+fn ui_amount(amount: u64, decimals: u8) -> f64 {
+    amount as f64 / 10f64.powi(decimals as i32)
+}
+
 pub struct SplTokenClient {
     rpc_client: RpcClient,
 }
@@ -36,16 +225,106 @@ impl SplTokenClient {
         }
     }
 
-    /// Get all SPL token accounts for a given wallet address
-    /// 
-    /// Note: This implementation is a placeholder. The actual token account fetching
-    /// is handled by the WalletService which uses the Solana RPC client directly.
-    /// This method is kept for API compatibility.
-    pub async fn get_token_accounts(&self, _wallet_address: &str) -> Result<Vec<TokenAccountInfo>> {
-        // TODO: Implement proper token account fetching using RPC client
-        // For now, return empty - the WalletService handles this via direct RPC calls
-        // This maintains API compatibility while the implementation is being refined
-        Ok(Vec::new())
+    /// Get all token accounts of a wallet, from both the SPL Token and the
+    /// Token-2022 program
+    ///
+    /// Decimals come from the mints; Token-2022 mints with the transfer-fee
+    /// extension also report the fee for the current epoch.
+    pub async fn get_token_accounts(&self, wallet_address: &str) -> Result<Vec<TokenAccountInfo>> {
+        Pubkey::from_str(wallet_address).context("Invalid wallet address")?;
+
+        let mut accounts = Vec::new();
+        for program in [TokenProgram::Spl, TokenProgram::Token2022] {
+            for data in self.fetch_accounts_by_owner(wallet_address, program).await? {
+                accounts.push((program, parse_token_account(&data)?));
+            }
+        }
+        if accounts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut mint_keys: Vec<Pubkey> = accounts.iter().map(|(_, account)| account.mint).collect();
+        mint_keys.sort();
+        mint_keys.dedup();
+        let mints = self.fetch_mints(&mint_keys).await?;
+
+        let epoch = if mints.values().any(|mint| mint.transfer_fee_config.is_some()) {
+            self.rpc_client
+                .get_epoch_info()
+                .await
+                .context("Failed to fetch epoch info")?
+                .epoch
+        } else {
+            0
+        };
+
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(program, account)| {
+                let mint = mints.get(&account.mint)?;
+                Some(TokenAccountInfo {
+                    mint: account.mint.to_string(),
+                    owner: account.owner.to_string(),
+                    amount: account.amount,
+                    decimals: mint.decimals,
+                    ui_amount: ui_amount(account.amount, mint.decimals),
+                    token_symbol: None,
+                    program,
+                    transfer_fee: mint.transfer_fee_config.map(|config| config.fee_for_epoch(epoch)),
+                })
+            })
+            .collect())
+    }
+
+    /// Raw data of the token accounts a wallet holds under one program
+    async fn fetch_accounts_by_owner(&self, wallet_address: &str, program: TokenProgram) -> Result<Vec<Vec<u8>>> {
+        let params = serde_json::json!([
+            wallet_address,
+            { "programId": program.program_id() },
+            { "encoding": "base64", "commitment": "confirmed" },
+        ]);
+        let response: serde_json::Value = self
+            .rpc_client
+            .send(RpcRequest::GetTokenAccountsByOwner, params)
+            .await
+            .with_context(|| format!("Failed to fetch {:?} token accounts", program))?;
+
+        response["value"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|keyed| {
+                let encoded = keyed["account"]["data"][0]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("Token account without base64 data"))?;
+                general_purpose::STANDARD
+                    .decode(encoded)
+                    .context("Invalid base64 token account data")
+            })
+            .collect()
+    }
+
+    /// Parsed mints by address; mints that are missing or unreadable are left out
+    async fn fetch_mints(&self, mint_keys: &[Pubkey]) -> Result<HashMap<Pubkey, ParsedMint>> {
+        let mut mints = HashMap::with_capacity(mint_keys.len());
+        for chunk in mint_keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self
+                .rpc_client
+                .get_multiple_accounts(chunk)
+                .await
+                .context("Failed to fetch mint accounts")?;
+            for (key, account) in chunk.iter().zip(accounts) {
+                let Some(account) = account else { continue };
+                match parse_mint(&account.data) {
+                    Ok(mint) => {
+                        mints.insert(*key, mint);
+                    }
+                    Err(e) => tracing::warn!(mint = %key, error = %e, "Skipping unreadable mint"),
+                }
+            }
+        }
+        Ok(mints)
     }
 
     /// Get token balance for a specific mint
@@ -74,48 +353,46 @@ impl SplTokenClient {
         Ok(ata.to_string())
     }
 
+    /// Get Associated Token Account address for a mint of the given token program
+    ///
+    /// Token-2022 ATAs are derived with the Token-2022 program id, so they
+    /// differ from the classic address for the same wallet and mint.
+    pub fn get_associated_token_address_for_program(
+        wallet_address: &str,
+        mint_address: &str,
+        program: TokenProgram,
+    ) -> Result<String> {
+        let wallet_pubkey = Pubkey::from_str(wallet_address)
+            .context("Invalid wallet address")?;
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .context("Invalid mint address")?;
+        let program_id = Pubkey::from_str(program.program_id())
+            .context("Invalid token program id")?;
+
+        let ata = get_associated_token_address_with_program_id(&wallet_pubkey, &mint_pubkey, &program_id);
+        Ok(ata.to_string())
+    }
+
     /// Check if an Associated Token Account exists
     pub async fn ata_exists(&self, ata_address: &str) -> Result<bool> {
-        use tokio::task;
-        
         let ata_pubkey = Pubkey::from_str(ata_address)
             .context("Invalid ATA address")?;
-        
-        let rpc_url = self.rpc_client.url().to_string();
-        
-        let result = task::spawn_blocking(move || {
-            let rpc = RpcClient::new(rpc_url);
-            rpc.get_account(&ata_pubkey)
-        })
-        .await
-        .context("Failed to spawn blocking task")?;
-
-        match result {
-            Ok(_) => Ok(true),
-            Err(_) => Ok(false),
-        }
+
+        Ok(self.rpc_client.get_account(&ata_pubkey).await.is_ok())
     }
 
-    /// Get decimals for a token mint
-    async fn get_mint_decimals(&self, mint_address: &str) -> Result<u8> {
-        use tokio::task;
-        
+    /// Get decimals for a token mint of either token program
+    pub async fn get_mint_decimals(&self, mint_address: &str) -> Result<u8> {
         let mint_pubkey = Pubkey::from_str(mint_address)
             .context("Invalid mint address")?;
-        
-        let rpc_url = self.rpc_client.url().to_string();
-        
-        let _account = task::spawn_blocking(move || {
-            let rpc = RpcClient::new(rpc_url);
-            rpc.get_account(&mint_pubkey)
-        })
-        .await
-        .context("Failed to spawn blocking task")?
-        .context("Failed to fetch mint account")?;
 
-        // Try to parse as token mint account
-        // For now, return default - decimals are usually available from token account data
-        Ok(9) // Default to 9 decimals - actual decimals come from token account parsing
+        let account = self
+            .rpc_client
+            .get_account(&mint_pubkey)
+            .await
+            .context("Failed to fetch mint account")?;
+
+        Ok(parse_mint(&account.data)?.decimals)
     }
 }
 
@@ -123,6 +400,137 @@ impl SplTokenClient {
 mod tests {
     use super::*;
 
+    /// Base mint state: no mint authority, the given supply and decimals, no freeze authority
+    fn mint_base(supply: u64, decimals: u8) -> Vec<u8> {
+        let mut data = vec![0u8; MINT_LEN];
+        data[36..44].copy_from_slice(&supply.to_le_bytes());
+        data[44] = decimals;
+        data[45] = 1;
+        data
+    }
+
+    fn epoch_fee(epoch: u64, maximum_fee: u64, basis_points: u16) -> Vec<u8> {
+        let mut bytes = epoch.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&maximum_fee.to_le_bytes());
+        bytes.extend_from_slice(&basis_points.to_le_bytes());
+        bytes
+    }
+
+    fn extension(extension_type: u16, value: &[u8]) -> Vec<u8> {
+        let mut bytes = extension_type.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(value);
+        bytes
+    }
+
+    /// Token-2022 mint: base state padded to 165 bytes, mint account type,
+    /// then the given extensions
+    fn token_2022_mint(decimals: u8, extensions: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = mint_base(1_000_000, decimals);
+        data.resize(ACCOUNT_TYPE_OFFSET, 0);
+        data.push(ACCOUNT_TYPE_MINT);
+        for ext in extensions {
+            data.extend_from_slice(ext);
+        }
+        data
+    }
+
+    fn transfer_fee_config(older: (u64, u64, u16), newer: (u64, u64, u16)) -> Vec<u8> {
+        let mut value = vec![7u8; 32]; // config authority
+        value.extend_from_slice(&[8u8; 32]); // withdraw withheld authority
+        value.extend_from_slice(&42u64.to_le_bytes());
+        value.extend_from_slice(&epoch_fee(older.0, older.1, older.2));
+        value.extend_from_slice(&epoch_fee(newer.0, newer.1, newer.2));
+        extension(EXTENSION_TRANSFER_FEE_CONFIG, &value)
+    }
+
+    #[test]
+    fn test_parse_classic_mint() {
+        let mint = parse_mint(&mint_base(5, 6)).unwrap();
+        assert_eq!(mint.decimals, 6);
+        assert!(mint.transfer_fee_config.is_none());
+
+        let mut uninitialized = mint_base(5, 6);
+        uninitialized[45] = 0;
+        assert!(parse_mint(&uninitialized).is_err());
+        assert!(parse_mint(&[0u8; 40]).is_err());
+    }
+
+    #[test]
+    fn test_parse_transfer_fee_config() {
+        // A metadata-pointer extension (type 18) ahead of the fee config must be skipped
+        let data = token_2022_mint(
+            9,
+            &[
+                extension(18, &[1u8; 64]),
+                transfer_fee_config((100, 1_000, 25), (250, 5_000_000, 50)),
+            ],
+        );
+
+        let mint = parse_mint(&data).unwrap();
+        assert_eq!(mint.decimals, 9);
+        let config = mint.transfer_fee_config.expect("transfer fee config");
+        assert_eq!(config.withheld_amount, 42);
+        assert_eq!(config.older, EpochTransferFee {
+            epoch: 100,
+            fee: TransferFee { basis_points: 25, maximum_fee: 1_000 },
+        });
+        assert_eq!(config.newer.fee, TransferFee { basis_points: 50, maximum_fee: 5_000_000 });
+
+        // The newer schedule only applies from its epoch on
+        assert_eq!(config.fee_for_epoch(249).basis_points, 25);
+        assert_eq!(config.fee_for_epoch(250).basis_points, 50);
+    }
+
+    #[test]
+    fn test_parse_token_2022_mint_without_fee() {
+        let data = token_2022_mint(2, &[extension(18, &[1u8; 64])]);
+        let mint = parse_mint(&data).unwrap();
+        assert_eq!(mint.decimals, 2);
+        assert!(mint.transfer_fee_config.is_none());
+
+        // Zeroed space after the last extension ends the list
+        let mut padded = token_2022_mint(2, &[]);
+        padded.extend_from_slice(&[0u8; 16]);
+        assert!(parse_mint(&padded).unwrap().transfer_fee_config.is_none());
+    }
+
+    #[test]
+    fn test_parse_mint_rejects_malformed_extensions() {
+        // Declared length runs past the end of the account
+        let mut truncated = token_2022_mint(6, &[]);
+        truncated.extend_from_slice(&EXTENSION_TRANSFER_FEE_CONFIG.to_le_bytes());
+        truncated.extend_from_slice(&108u16.to_le_bytes());
+        truncated.extend_from_slice(&[0u8; 20]);
+        assert!(parse_mint(&truncated).is_err());
+
+        // A token account (type 2) is not a mint
+        let mut account = token_2022_mint(6, &[]);
+        account[ACCOUNT_TYPE_OFFSET] = 2;
+        assert!(parse_mint(&account).is_err());
+    }
+
+    #[test]
+    fn test_parse_token_account() {
+        let mint = Pubkey::new_from_array([1u8; 32]);
+        let owner = Pubkey::new_from_array([2u8; 32]);
+        let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+        data[0..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data[64..72].copy_from_slice(&1_500u64.to_le_bytes());
+        data[108] = 1; // initialized
+
+        let account = parse_token_account(&data).unwrap();
+        assert_eq!(account, ParsedTokenAccount { mint, owner, amount: 1_500 });
+
+        // Token-2022 accounts carry extensions after the base state
+        data.push(2);
+        data.extend_from_slice(&extension(7, &[]));
+        assert_eq!(parse_token_account(&data).unwrap().amount, 1_500);
+
+        assert!(parse_token_account(&data[..100]).is_err());
+    }
+
     #[tokio::test]
     #[ignore] // Requires RPC connection
     async fn test_get_token_accounts() {
@@ -140,5 +548,11 @@ mod tests {
 
         let ata = SplTokenClient::get_associated_token_address(wallet, mint);
         assert!(ata.is_ok());
+
+        let classic = SplTokenClient::get_associated_token_address_for_program(wallet, mint, TokenProgram::Spl).unwrap();
+        let token_2022 =
+            SplTokenClient::get_associated_token_address_for_program(wallet, mint, TokenProgram::Token2022).unwrap();
+        assert_eq!(classic, ata.unwrap());
+        assert_ne!(classic, token_2022);
     }
 }
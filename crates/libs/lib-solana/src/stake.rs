@@ -0,0 +1,354 @@
+//! # Native Staking
+//!
+//! Stake accounts and the instructions that create, delegate, deactivate and
+//! withdraw them. Like the token accounts in [`crate::spl_token`], accounts
+//! are parsed from raw data, and the instructions are encoded here so no
+//! stake program crate is needed.
+//!
+//! ## Stake account layout (`StakeStateV2`)
+//!
+//! ```text
+//! 0..4     state u32 LE (0 uninitialized, 1 initialized, 2 delegated, 3 rewards pool)
+//! 4..12    rent-exempt reserve
+//! 12..44   stake authority
+//! 44..76   withdraw authority
+//! 76..124  lockup: unix timestamp i64, epoch, custodian
+//! 124..156 vote account delegated to          (delegated only)
+//! 156..164 delegated lamports
+//! 164..172 activation epoch
+//! 172..180 deactivation epoch (u64::MAX while not deactivated)
+//! ```
+//!
+//! ## New stake accounts
+//!
+//! New accounts are derived from the wallet with `CreateAccountWithSeed`
+//! (seeds `stake:0`, `stake:1`, …) rather than from a fresh keypair, so the
+//! wallet is the only signer and the transaction can be signed by the
+//! terminal alone.
+
+use crate::contracts::transaction_builder::{compile_message, encode_transaction, message_version, unsigned_transaction};
+use anyhow::{bail, Result};
+use shared::dto::staking::{StakeAccountInfo, StakeActivation, STAKE_ACCOUNT_LEN};
+use shared::dto::transactions::TransactionVersion;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+
+pub const STAKE_PROGRAM_ID: Pubkey = Pubkey::from_str_const("Stake11111111111111111111111111111111111111");
+pub const VOTE_PROGRAM_ID: Pubkey = Pubkey::from_str_const("Vote111111111111111111111111111111111111111");
+const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::from_str_const("11111111111111111111111111111111");
+const STAKE_CONFIG_ID: Pubkey = Pubkey::from_str_const("StakeConfig11111111111111111111111111111111");
+const CLOCK_SYSVAR_ID: Pubkey = Pubkey::from_str_const("SysvarC1ock11111111111111111111111111111111");
+const RENT_SYSVAR_ID: Pubkey = Pubkey::from_str_const("SysvarRent111111111111111111111111111111111");
+const STAKE_HISTORY_SYSVAR_ID: Pubkey = Pubkey::from_str_const("SysvarStakeHistory1111111111111111111111111");
+
+/// Offset of the withdraw authority, for filtering a wallet's accounts
+pub const WITHDRAWER_OFFSET: usize = 44;
+
+/// Seeds tried when deriving a new stake account
+pub const MAX_STAKE_SEEDS: u32 = 100;
+
+const STATE_INITIALIZED: u32 = 1;
+const STATE_DELEGATED: u32 = 2;
+
+/// Bytes of a delegated account that are read
+const DELEGATED_LEN: usize = 180;
+
+/// System program `CreateAccountWithSeed`
+const SYSTEM_CREATE_ACCOUNT_WITH_SEED: u32 = 3;
+
+const STAKE_INITIALIZE: u32 = 0;
+const STAKE_DELEGATE: u32 = 2;
+const STAKE_WITHDRAW: u32 = 4;
+const STAKE_DEACTIVATE: u32 = 5;
+
+/// Delegation of a delegated stake account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delegation {
+    pub voter: Pubkey,
+    pub stake: u64,
+    pub activation_epoch: u64,
+    /// None while the stake hasn't been deactivated
+    pub deactivation_epoch: Option<u64>,
+}
+
+/// Initialized or delegated stake account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedStakeAccount {
+    pub rent_exempt_reserve: u64,
+    pub staker: Pubkey,
+    pub withdrawer: Pubkey,
+    pub lockup_unix_timestamp: i64,
+    pub lockup_epoch: u64,
+    pub delegation: Option<Delegation>,
+}
+
+impl ParsedStakeAccount {
+    /// Lifecycle state at `epoch`
+    pub fn activation(&self, epoch: u64) -> StakeActivation {
+        match &self.delegation {
+            Some(delegation) => {
+                StakeActivation::at_epoch(delegation.activation_epoch, delegation.deactivation_epoch, epoch)
+            }
+            None => StakeActivation::Inactive,
+        }
+    }
+
+    /// The lockup still forbids withdrawals at `epoch` and `unix_timestamp`
+    pub fn is_locked(&self, epoch: u64, unix_timestamp: i64) -> bool {
+        self.lockup_epoch > epoch || self.lockup_unix_timestamp > unix_timestamp
+    }
+
+    /// Summary for the terminal
+    pub fn to_info(&self, address: &Pubkey, lamports: u64, epoch: u64) -> StakeAccountInfo {
+        let delegation = self.delegation.as_ref();
+        StakeAccountInfo {
+            address: address.to_string(),
+            lamports,
+            rent_exempt_reserve: self.rent_exempt_reserve,
+            voter: delegation.map(|d| d.voter.to_string()),
+            delegated_lamports: delegation.map_or(0, |d| d.stake),
+            activation_epoch: delegation.map(|d| d.activation_epoch),
+            deactivation_epoch: delegation.and_then(|d| d.deactivation_epoch),
+            state: self.activation(epoch),
+        }
+    }
+}
+
+/// Parse an initialized or delegated stake account.
+pub fn parse_stake_account(data: &[u8]) -> Result<ParsedStakeAccount> {
+    if data.len() < WITHDRAWER_OFFSET + 80 {
+        bail!("Stake account data too short: {} bytes", data.len());
+    }
+    let state = u32::from_le_bytes(data[0..4].try_into().expect("4 bytes"));
+    let delegation = match state {
+        STATE_INITIALIZED => None,
+        STATE_DELEGATED => {
+            if data.len() < DELEGATED_LEN {
+                bail!("Delegated stake account data too short: {} bytes", data.len());
+            }
+            let deactivation_epoch = read_u64(&data[172..180]);
+            Some(Delegation {
+                voter: read_pubkey(&data[124..156]),
+                stake: read_u64(&data[156..164]),
+                activation_epoch: read_u64(&data[164..172]),
+                deactivation_epoch: (deactivation_epoch != u64::MAX).then_some(deactivation_epoch),
+            })
+        }
+        other => bail!("Not an initialized stake account (state {})", other),
+    };
+    Ok(ParsedStakeAccount {
+        rent_exempt_reserve: read_u64(&data[4..12]),
+        staker: read_pubkey(&data[12..44]),
+        withdrawer: read_pubkey(&data[44..76]),
+        lockup_unix_timestamp: i64::from_le_bytes(data[76..84].try_into().expect("8 bytes")),
+        lockup_epoch: read_u64(&data[84..92]),
+        delegation,
+    })
+}
+
+/// Seed of the `index`th derived stake account
+pub fn stake_seed(index: u32) -> String {
+    format!("stake:{}", index)
+}
+
+/// Address of the stake account derived from `wallet` with `seed`
+pub fn stake_address(wallet: &Pubkey, seed: &str) -> Result<Pubkey> {
+    Pubkey::create_with_seed(wallet, seed, &STAKE_PROGRAM_ID)
+        .map_err(|e| anyhow::anyhow!("Invalid stake seed {}: {}", seed, e))
+}
+
+/// Create a stake account at the address derived from `wallet` and `seed`,
+/// funded with `lamports` and with `wallet` as both authorities, and
+/// delegate it to `vote_account`
+pub fn create_and_delegate_instructions(
+    wallet: &Pubkey,
+    seed: &str,
+    lamports: u64,
+    vote_account: &Pubkey,
+) -> Result<Vec<Instruction>> {
+    let stake = stake_address(wallet, seed)?;
+
+    let mut create = SYSTEM_CREATE_ACCOUNT_WITH_SEED.to_le_bytes().to_vec();
+    create.extend_from_slice(wallet.as_ref());
+    create.extend_from_slice(&(seed.len() as u64).to_le_bytes());
+    create.extend_from_slice(seed.as_bytes());
+    create.extend_from_slice(&lamports.to_le_bytes());
+    create.extend_from_slice(&(STAKE_ACCOUNT_LEN as u64).to_le_bytes());
+    create.extend_from_slice(STAKE_PROGRAM_ID.as_ref());
+
+    // Authorized { staker, withdrawer }, then a lockup that's never in force
+    let mut initialize = STAKE_INITIALIZE.to_le_bytes().to_vec();
+    initialize.extend_from_slice(wallet.as_ref());
+    initialize.extend_from_slice(wallet.as_ref());
+    initialize.extend_from_slice(&[0u8; 8 + 8 + 32]);
+
+    Ok(vec![
+        Instruction::new_with_bytes(
+            SYSTEM_PROGRAM_ID,
+            &create,
+            vec![AccountMeta::new(*wallet, true), AccountMeta::new(stake, false), AccountMeta::new_readonly(*wallet, true)],
+        ),
+        Instruction::new_with_bytes(
+            STAKE_PROGRAM_ID,
+            &initialize,
+            vec![AccountMeta::new(stake, false), AccountMeta::new_readonly(RENT_SYSVAR_ID, false)],
+        ),
+        Instruction::new_with_bytes(
+            STAKE_PROGRAM_ID,
+            &STAKE_DELEGATE.to_le_bytes(),
+            vec![
+                AccountMeta::new(stake, false),
+                AccountMeta::new_readonly(*vote_account, false),
+                AccountMeta::new_readonly(CLOCK_SYSVAR_ID, false),
+                AccountMeta::new_readonly(STAKE_HISTORY_SYSVAR_ID, false),
+                AccountMeta::new_readonly(STAKE_CONFIG_ID, false),
+                AccountMeta::new_readonly(*wallet, true),
+            ],
+        ),
+    ])
+}
+
+/// Deactivate `stake`, signed by its stake authority
+pub fn deactivate_instruction(stake: &Pubkey, staker: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        STAKE_PROGRAM_ID,
+        &STAKE_DEACTIVATE.to_le_bytes(),
+        vec![
+            AccountMeta::new(*stake, false),
+            AccountMeta::new_readonly(CLOCK_SYSVAR_ID, false),
+            AccountMeta::new_readonly(*staker, true),
+        ],
+    )
+}
+
+/// Withdraw `lamports` from `stake` to its withdraw authority
+pub fn withdraw_instruction(stake: &Pubkey, withdrawer: &Pubkey, lamports: u64) -> Instruction {
+    let mut data = STAKE_WITHDRAW.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    Instruction::new_with_bytes(
+        STAKE_PROGRAM_ID,
+        &data,
+        vec![
+            AccountMeta::new(*stake, false),
+            AccountMeta::new(*withdrawer, false),
+            AccountMeta::new_readonly(CLOCK_SYSVAR_ID, false),
+            AccountMeta::new_readonly(STAKE_HISTORY_SYSVAR_ID, false),
+            AccountMeta::new_readonly(*withdrawer, true),
+        ],
+    )
+}
+
+/// Unsigned transaction of `instructions` paid by `payer`, base64-encoded
+pub fn encode_unsigned(payer: &Pubkey, instructions: &[Instruction], recent_blockhash: Hash) -> Result<(String, TransactionVersion)> {
+    let message = compile_message(payer, instructions, &[], recent_blockhash)?;
+    let version = message_version(&message);
+    let (encoded, _) = encode_transaction(&unsigned_transaction(message))?;
+    Ok((encoded, version))
+}
+
+fn read_pubkey(bytes: &[u8]) -> Pubkey {
+    let mut key = [0u8; 32];
+    key.copy_from_slice(bytes);
+    Pubkey::new_from_array(key)
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().expect("8 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const VOTER: Pubkey = Pubkey::new_from_array([9u8; 32]);
+
+    /// Stake account in `state` with `WALLET` as both authorities
+    fn stake_data(state: u32, delegation: Option<(u64, u64, u64)>) -> Vec<u8> {
+        let mut data = vec![0u8; STAKE_ACCOUNT_LEN];
+        data[0..4].copy_from_slice(&state.to_le_bytes());
+        data[4..12].copy_from_slice(&2_282_880u64.to_le_bytes());
+        data[12..44].copy_from_slice(WALLET.as_ref());
+        data[44..76].copy_from_slice(WALLET.as_ref());
+        if let Some((stake, activation, deactivation)) = delegation {
+            data[124..156].copy_from_slice(VOTER.as_ref());
+            data[156..164].copy_from_slice(&stake.to_le_bytes());
+            data[164..172].copy_from_slice(&activation.to_le_bytes());
+            data[172..180].copy_from_slice(&deactivation.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_delegated_stake_account() {
+        let account = parse_stake_account(&stake_data(2, Some((5_000_000_000, 600, u64::MAX)))).unwrap();
+        assert_eq!(account.rent_exempt_reserve, 2_282_880);
+        assert_eq!(account.withdrawer, WALLET);
+        let delegation = account.delegation.clone().unwrap();
+        assert_eq!(delegation.voter, VOTER);
+        assert_eq!(delegation.stake, 5_000_000_000);
+        assert_eq!(delegation.deactivation_epoch, None);
+        assert_eq!(account.activation(600), StakeActivation::Activating);
+        assert_eq!(account.activation(601), StakeActivation::Active);
+        assert!(!account.is_locked(601, 0));
+
+        let info = account.to_info(&WALLET, 5_002_282_880, 601);
+        assert_eq!(info.voter.as_deref(), Some(VOTER.to_string().as_str()));
+        assert!(info.can_deactivate());
+
+        let deactivated = parse_stake_account(&stake_data(2, Some((5_000_000_000, 600, 610)))).unwrap();
+        assert_eq!(deactivated.activation(610), StakeActivation::Deactivating);
+        assert_eq!(deactivated.activation(611), StakeActivation::Inactive);
+    }
+
+    #[test]
+    fn test_parse_rejects_other_states() {
+        let initialized = parse_stake_account(&stake_data(1, None)).unwrap();
+        assert!(initialized.delegation.is_none());
+        assert_eq!(initialized.activation(5), StakeActivation::Inactive);
+
+        assert!(parse_stake_account(&stake_data(0, None)).is_err());
+        assert!(parse_stake_account(&stake_data(3, None)).is_err());
+        assert!(parse_stake_account(&[2u8; 100]).is_err());
+    }
+
+    #[test]
+    fn test_create_and_delegate_instructions() {
+        let seed = stake_seed(3);
+        let instructions = create_and_delegate_instructions(&WALLET, &seed, 1_002_282_880, &VOTER).unwrap();
+        let stake = stake_address(&WALLET, &seed).unwrap();
+        assert_eq!(instructions.len(), 3);
+
+        let create = &instructions[0];
+        assert_eq!(create.program_id, SYSTEM_PROGRAM_ID);
+        assert_eq!(&create.data[0..4], &3u32.to_le_bytes());
+        assert_eq!(&create.data[36..44], &7u64.to_le_bytes());
+        assert_eq!(&create.data[44..51], b"stake:3");
+        assert_eq!(&create.data[51..59], &1_002_282_880u64.to_le_bytes());
+        assert_eq!(&create.data[59..67], &200u64.to_le_bytes());
+        assert_eq!(&create.data[67..99], STAKE_PROGRAM_ID.as_ref());
+        assert_eq!(create.accounts[1].pubkey, stake);
+
+        assert_eq!(instructions[1].data.len(), 4 + 64 + 48);
+        let delegate = &instructions[2];
+        assert_eq!(delegate.data, 2u32.to_le_bytes());
+        assert_eq!(delegate.accounts[1].pubkey, VOTER);
+        assert!(delegate.accounts[5].is_signer);
+
+        // The wallet is the only signer
+        let (encoded, version) = encode_unsigned(&WALLET, &instructions, Hash::default()).unwrap();
+        assert_eq!(version, TransactionVersion::Legacy);
+        assert!(!encoded.is_empty());
+    }
+
+    #[test]
+    fn test_withdraw_instruction() {
+        let stake = stake_address(&WALLET, &stake_seed(0)).unwrap();
+        let withdraw = withdraw_instruction(&stake, &WALLET, 42);
+        assert_eq!(&withdraw.data[0..4], &4u32.to_le_bytes());
+        assert_eq!(&withdraw.data[4..12], &42u64.to_le_bytes());
+        assert_eq!(withdraw.accounts[1].pubkey, WALLET);
+        assert_eq!(deactivate_instruction(&stake, &WALLET).data, 5u32.to_le_bytes());
+    }
+}
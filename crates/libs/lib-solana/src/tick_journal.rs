@@ -0,0 +1,424 @@
+//! # Price Tick Journal
+//!
+//! Optional append-only record of every price tick the stream feeds into the
+//! [`CandleAggregator`](crate::candle_aggregator::CandleAggregator), so a
+//! candle someone reports as wrong can be rebuilt later from exactly the
+//! ticks that produced it (see [`crate::candle_replay`]).
+//!
+//! ## Format
+//!
+//! One tick per line, comma separated:
+//!
+//! ```text
+//! # xforce tick journal v1 sample=1
+//! 1718000000123,1718000000,jupiter,SOL,171.2345
+//! ```
+//!
+//! Columns are arrival time (ms), tick timestamp (s), source, symbol and
+//! price. Lines in a file are in arrival order, which is also the order the
+//! aggregator saw them in.
+//!
+//! ## Overhead
+//!
+//! [`TickJournal::record`] does no I/O and never blocks: the tick is handed
+//! to a writer thread through a bounded queue, and dropped (and counted) if
+//! the queue is full. The writer buffers and flushes about once a
+//! second. With `sample_every > 1` only every Nth tick is kept, which makes
+//! the journal smaller but replays approximate.
+//!
+//! ## Rotation
+//!
+//! The live file is `ticks.log`. Once it reaches `max_file_bytes` it becomes
+//! `ticks.log.1` (older files shift up) and files past `max_files` are deleted.
+//!
+//! ## Configuration
+//!
+//! - `TICK_JOURNAL_DIR` - Directory to journal into; journaling is off without it
+//! - `TICK_JOURNAL_SAMPLE_EVERY` - Keep every Nth tick (default: 1, all of them)
+//! - `TICK_JOURNAL_MAX_FILE_MB` - Size at which the file rotates (default: 64)
+//! - `TICK_JOURNAL_MAX_FILES` - Rotated files kept (default: 8)
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender, TrySendError};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Name of the live journal file
+pub const JOURNAL_FILE: &str = "ticks.log";
+
+/// Ticks waiting for the writer before new ones are dropped
+const QUEUE_CAPACITY: usize = 16_384;
+
+/// How often buffered ticks are written out
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+const HEADER_PREFIX: &str = "# xforce tick journal v1 sample=";
+
+/// One price tick as the aggregator received it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickRecord {
+    /// When the stream received the tick, in Unix milliseconds
+    pub received_ms: u64,
+    /// Tick timestamp passed to the aggregator, in Unix seconds
+    pub timestamp: u64,
+    /// Price source, e.g. "jupiter"
+    pub source: String,
+    pub symbol: String,
+    pub price: f64,
+}
+
+impl TickRecord {
+    fn to_line(&self) -> String {
+        format!("{},{},{},{},{}", self.received_ms, self.timestamp, self.source, self.symbol, self.price)
+    }
+
+    fn parse_line(line: &str) -> Option<Self> {
+        let mut parts = line.split(',');
+        let record = Self {
+            received_ms: parts.next()?.parse().ok()?,
+            timestamp: parts.next()?.parse().ok()?,
+            source: parts.next()?.to_string(),
+            symbol: parts.next()?.to_string(),
+            price: parts.next()?.parse().ok().filter(|p: &f64| p.is_finite())?,
+        };
+        parts.next().is_none().then_some(record)
+    }
+}
+
+/// Where and how ticks are journaled
+#[derive(Debug, Clone)]
+pub struct JournalConfig {
+    pub dir: PathBuf,
+    /// Keep every Nth tick; 1 keeps all of them
+    pub sample_every: u64,
+    pub max_file_bytes: u64,
+    pub max_files: usize,
+}
+
+impl JournalConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), sample_every: 1, max_file_bytes: 64 * 1024 * 1024, max_files: 8 }
+    }
+
+    /// Read the `TICK_JOURNAL_*` variables; `None` when journaling is off
+    pub fn from_env() -> Option<Self> {
+        let dir = std::env::var("TICK_JOURNAL_DIR").ok().filter(|d| !d.trim().is_empty())?;
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok()).filter(|v| *v > 0);
+        let mut config = Self::new(dir);
+        if let Some(sample_every) = var("TICK_JOURNAL_SAMPLE_EVERY") {
+            config.sample_every = sample_every;
+        }
+        if let Some(mb) = var("TICK_JOURNAL_MAX_FILE_MB") {
+            config.max_file_bytes = mb * 1024 * 1024;
+        }
+        if let Some(files) = var("TICK_JOURNAL_MAX_FILES") {
+            config.max_files = files as usize;
+        }
+        Some(config)
+    }
+}
+
+/// Appends ticks to the current file and rotates it
+struct JournalWriter {
+    config: JournalConfig,
+    file: BufWriter<File>,
+    written: u64,
+}
+
+impl JournalWriter {
+    fn open(config: JournalConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.dir)?;
+        let path = config.dir.join(JOURNAL_FILE);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        let mut writer = Self { config, file: BufWriter::new(file), written };
+        if writer.written == 0 {
+            writer.write_header()?;
+        }
+        Ok(writer)
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        let header = format!("{}{}\n", HEADER_PREFIX, self.config.sample_every);
+        self.file.write_all(header.as_bytes())?;
+        self.written += header.len() as u64;
+        Ok(())
+    }
+
+    fn write(&mut self, record: &TickRecord) -> io::Result<()> {
+        if self.written >= self.config.max_file_bytes {
+            self.rotate()?;
+        }
+        let line = record.to_line();
+        self.file.write_all(line.as_bytes())?;
+        self.file.write_all(b"\n")?;
+        self.written += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// `ticks.log` -> `ticks.log.1` -> `ticks.log.2` ..., dropping the oldest
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let dir = &self.config.dir;
+        let rotated = |n: usize| dir.join(format!("{}.{}", JOURNAL_FILE, n));
+        let _ = fs::remove_file(rotated(self.config.max_files));
+        for n in (1..self.config.max_files).rev() {
+            if rotated(n).exists() {
+                fs::rename(rotated(n), rotated(n + 1))?;
+            }
+        }
+        if self.config.max_files > 0 {
+            fs::rename(dir.join(JOURNAL_FILE), rotated(1))?;
+        }
+
+        let file = OpenOptions::new().create(true).write(true).truncate(true).open(dir.join(JOURNAL_FILE))?;
+        self.file = BufWriter::new(file);
+        self.written = 0;
+        self.write_header()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Handle the price stream records ticks through
+pub struct TickJournal {
+    config: JournalConfig,
+    tx: SyncSender<TickRecord>,
+    seen: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl TickJournal {
+    /// Open the journal and start its writer thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or file can't be created.
+    pub fn start(config: JournalConfig) -> io::Result<Self> {
+        let mut writer = JournalWriter::open(config.clone())?;
+        let (tx, rx) = mpsc::sync_channel::<TickRecord>(QUEUE_CAPACITY);
+
+        std::thread::Builder::new().name("tick-journal".to_string()).spawn(move || {
+            let mut failed = false;
+            loop {
+                let result = match rx.recv_timeout(FLUSH_INTERVAL) {
+                    Ok(record) => writer.write(&record),
+                    Err(RecvTimeoutError::Timeout) => writer.flush(),
+                    Err(RecvTimeoutError::Disconnected) => {
+                        let _ = writer.flush();
+                        break;
+                    }
+                };
+                // One warning per failure streak rather than one per tick
+                match result {
+                    Err(e) if !failed => {
+                        warn!(error = %e, "Tick journal write failed");
+                        failed = true;
+                    }
+                    Ok(()) => failed = false,
+                    Err(_) => {}
+                }
+            }
+        })?;
+
+        info!(dir = %config.dir.display(), sample_every = config.sample_every, "Tick journal enabled");
+        Ok(Self { config, tx, seen: AtomicU64::new(0), dropped: AtomicU64::new(0) })
+    }
+
+    /// Record a tick (or skip it, when sampling). Never blocks.
+    pub fn record(&self, source: &str, symbol: &str, price: f64, timestamp: u64) {
+        let seen = self.seen.fetch_add(1, Ordering::Relaxed);
+        if !seen.is_multiple_of(self.config.sample_every) {
+            return;
+        }
+        let record = TickRecord {
+            received_ms: now_ms(),
+            timestamp,
+            source: source.to_string(),
+            symbol: symbol.to_uppercase(),
+            price,
+        };
+        if let Err(TrySendError::Full(_) | TrySendError::Disconnected(_)) = self.tx.try_send(record) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.config.dir
+    }
+
+    /// Ticks lost because the writer fell behind
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Ticks read back from a journal directory
+#[derive(Debug, Default)]
+pub struct JournalContents {
+    /// Oldest file first, arrival order within each file
+    pub ticks: Vec<TickRecord>,
+    /// Largest sampling interval any file was written with
+    pub sample_every: u64,
+    /// Lines that couldn't be parsed (e.g. cut off by a crash)
+    pub malformed: usize,
+}
+
+/// Read every journal file in `dir`, keeping ticks with a timestamp in
+/// `from..to` (and, if given, for `symbol`).
+///
+/// # Errors
+///
+/// Returns an error if the directory has no journal or a file can't be read.
+pub fn read_journal(dir: &Path, symbol: Option<&str>, from: u64, to: u64) -> io::Result<JournalContents> {
+    let mut files: Vec<(usize, PathBuf)> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let age = if name == JOURNAL_FILE {
+                0
+            } else {
+                name.strip_prefix(JOURNAL_FILE)?.strip_prefix('.')?.parse().ok()?
+            };
+            Some((age, entry.path()))
+        })
+        .collect();
+    if files.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("No tick journal in {}", dir.display())));
+    }
+    // Highest number is the oldest
+    files.sort_by_key(|(age, _)| std::cmp::Reverse(*age));
+
+    let symbol = symbol.map(str::to_uppercase);
+    let mut contents = JournalContents { sample_every: 1, ..Default::default() };
+    for (_, path) in files {
+        for line in BufReader::new(File::open(&path)?).lines() {
+            let line = line?;
+            if let Some(sample) = line.strip_prefix(HEADER_PREFIX) {
+                let sample = sample.trim().parse().unwrap_or(1);
+                contents.sample_every = contents.sample_every.max(sample);
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match TickRecord::parse_line(&line) {
+                Some(tick) => {
+                    let wanted = (from..to).contains(&tick.timestamp)
+                        && symbol.as_ref().is_none_or(|s| *s == tick.symbol);
+                    if wanted {
+                        contents.ticks.push(tick);
+                    }
+                }
+                None => contents.malformed += 1,
+            }
+        }
+    }
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(received_ms: u64, timestamp: u64, symbol: &str, price: f64) -> TickRecord {
+        TickRecord { received_ms, timestamp, source: "jupiter".to_string(), symbol: symbol.to_string(), price }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tick-journal-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_line_round_trip() {
+        let record = tick(1_718_000_000_123, 1_718_000_000, "SOL", 171.2345);
+        assert_eq!(TickRecord::parse_line(&record.to_line()), Some(record));
+
+        assert_eq!(TickRecord::parse_line("1718000000123,1718000000,jupiter,SOL"), None);
+        assert_eq!(TickRecord::parse_line("1,2,jupiter,SOL,NaN"), None);
+        assert_eq!(TickRecord::parse_line("1,2,jupiter,SOL,3,extra"), None);
+    }
+
+    #[test]
+    fn test_rotation_keeps_arrival_order() {
+        let dir = temp_dir("rotate");
+        let mut config = JournalConfig::new(&dir);
+        config.max_file_bytes = 120;
+        config.max_files = 2;
+
+        let mut writer = JournalWriter::open(config).unwrap();
+        // Four ticks per file with these sizes
+        for i in 0..16 {
+            writer.write(&tick(i, 1_000 + i, "SOL", 100.0 + i as f64)).unwrap();
+        }
+        writer.flush().unwrap();
+        assert!(dir.join("ticks.log.1").exists());
+        assert!(!dir.join("ticks.log.3").exists());
+
+        let contents = read_journal(&dir, Some("sol"), 0, u64::MAX).unwrap();
+        let times: Vec<u64> = contents.ticks.iter().map(|t| t.received_ms).collect();
+        // The oldest file was rotated out, the rest read back in order
+        assert_eq!(times, (4..16).collect::<Vec<_>>());
+        assert_eq!(contents.sample_every, 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_read_filters_and_skips_malformed_lines() {
+        let dir = temp_dir("read");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(JOURNAL_FILE),
+            "# xforce tick journal v1 sample=4\n\
+             1,100,jupiter,SOL,10\n\
+             2,100,jupiter,BONK,0.00002\n\
+             3,160,jupiter,SOL,11\n\
+             4,2\n\
+             5,220,jupiter,SOL,12\n",
+        )
+        .unwrap();
+
+        let contents = read_journal(&dir, Some("SOL"), 100, 220).unwrap();
+        assert_eq!(contents.ticks.iter().map(|t| t.price).collect::<Vec<_>>(), [10.0, 11.0]);
+        assert_eq!(contents.malformed, 1);
+        assert_eq!(contents.sample_every, 4);
+
+        assert!(read_journal(&dir.join("missing"), None, 0, u64::MAX).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sampling_keeps_every_nth_tick() {
+        let dir = temp_dir("sample");
+        let mut config = JournalConfig::new(&dir);
+        config.sample_every = 3;
+        let journal = TickJournal::start(config).unwrap();
+        for i in 0..9 {
+            journal.record("jupiter", "sol", 100.0 + i as f64, 1_000 + i);
+        }
+        drop(journal);
+        // The writer thread flushes once the handle is gone
+        std::thread::sleep(Duration::from_millis(200));
+
+        let contents = read_journal(&dir, None, 0, u64::MAX).unwrap();
+        assert_eq!(contents.ticks.iter().map(|t| t.timestamp).collect::<Vec<_>>(), [1_000, 1_003, 1_006]);
+        assert_eq!(contents.ticks[0].symbol, "SOL");
+        assert_eq!(contents.sample_every, 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -20,7 +20,7 @@
 //!
 //! ```rust
 //! use backend::solana::types::{PriceData, PriceResponse};
-//! use std::collections::HashMap;
+//! use std::collections::{BTreeMap, HashMap};
 //!
 //! // Create price data
 //! let sol_price = PriceData {
@@ -29,12 +29,15 @@
 //!     source: "pyth".to_string(),
 //!     change_24h: Some(5.2),
 //!     last_updated: 1234567890,
+//!     publish_time: Some(1234567889),
+//!     sources: Default::default(),
+//!     divergent: false,
 //! };
 //!
 //! // Build response with multiple prices
 //! let mut prices = HashMap::new();
 //! prices.insert("SOL".to_string(), sol_price);
-//! let response = PriceResponse { prices };
+//! let response = PriceResponse { prices, cached: false, age_ms: 0 };
 //! ```
 
 use serde::{Deserialize, Serialize};
@@ -53,11 +56,15 @@ use std::collections::HashMap;
 /// * `source` - Data source identifier (e.g., "pyth", "jupiter", "coingecko", "mock")
 /// * `change_24h` - Optional 24-hour price change percentage (positive = increase, negative = decrease)
 /// * `last_updated` - Unix timestamp of when this price was last updated
+/// * `publish_time` - Unix timestamp the oracle published the price at. Only available from Pyth.
+/// * `sources` - Price reported by each source that answered
+/// * `divergent` - Whether those prices disagree by more than the divergence threshold
 ///
 /// # Serialization
 ///
-/// The `confidence` and `change_24h` fields are skipped when `None` during JSON serialization
-/// to produce cleaner API responses.
+/// The `confidence`, `change_24h` and `publish_time` fields are skipped when `None`, and
+/// `sources` and `divergent` when empty or `false`, during JSON serialization to produce
+/// cleaner API responses.
 ///
 /// # Example
 ///
@@ -71,6 +78,9 @@ use std::collections::HashMap;
 ///     source: "pyth".to_string(),
 ///     change_24h: Some(5.2),   // +5.2% in 24h
 ///     last_updated: 1234567890,
+///     publish_time: Some(1234567889),  // when Pyth published it
+///     sources: Default::default(),
+///     divergent: false,
 /// };
 ///
 /// // Price from Jupiter (no confidence data)
@@ -80,6 +90,9 @@ use std::collections::HashMap;
 ///     source: "jupiter".to_string(),
 ///     change_24h: None,
 ///     last_updated: 1234567890,
+///     publish_time: None,
+///     sources: Default::default(),
+///     divergent: false,
 /// };
 ///
 /// // Display price with source
@@ -111,6 +124,24 @@ pub struct PriceData {
 
     /// Unix timestamp (seconds since epoch) of last price update
     pub last_updated: u64,
+
+    /// Unix timestamp (seconds since epoch) the oracle published this price at
+    ///
+    /// `last_updated` is when we fetched or served it; an oracle can keep
+    /// serving an old price, so staleness is judged by this one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish_time: Option<i64>,
+
+    /// Price each source reported, by source name (e.g. `{"jupiter": 145.28, "pyth": 145.32}`)
+    ///
+    /// `price` is the primary source's; this lets clients compare them.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sources: BTreeMap<String, f64>,
+
+    /// The sources disagree by more than the divergence threshold, see
+    /// [`crate::aggregate`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub divergent: bool,
 }
 
 /// API response containing prices for multiple tokens.
@@ -134,6 +165,9 @@ pub struct PriceData {
 ///     source: "pyth".to_string(),
 ///     change_24h: Some(5.2),
 ///     last_updated: 1234567890,
+///     publish_time: Some(1234567889),
+///     sources: Default::default(),
+///     divergent: false,
 /// });
 ///
 /// prices.insert("USDC".to_string(), PriceData {
@@ -142,9 +176,12 @@ pub struct PriceData {
 ///     source: "pyth".to_string(),
 ///     change_24h: Some(0.0),
 ///     last_updated: 1234567890,
+///     publish_time: Some(1234567889),
+///     sources: Default::default(),
+///     divergent: false,
 /// });
 ///
-/// let response = PriceResponse { prices };
+/// let response = PriceResponse { prices, cached: false, age_ms: 0 };
 /// Json(response)
 /// # }
 /// ```
@@ -159,20 +196,29 @@ pub struct PriceData {
 ///       "confidence": 0.05,
 ///       "source": "pyth",
 ///       "change_24h": 5.2,
-///       "last_updated": 1234567890
+///       "last_updated": 1234567890,
+///       "publish_time": 1234567889
 ///     },
 ///     "USDC": {
 ///       "price": 1.0,
 ///       "source": "jupiter",
 ///       "last_updated": 1234567890
 ///     }
-///   }
+///   },
+///   "cached": true,
+///   "age_ms": 850
 /// }
 /// ```
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceResponse {
     /// Map of token symbol to price data
     pub prices: HashMap<String, PriceData>,
+    /// Served from the backend's response cache
+    #[serde(default)]
+    pub cached: bool,
+    /// Milliseconds since the response was fetched upstream
+    #[serde(default)]
+    pub age_ms: u64,
 }
 
 /// Query parameters for price requests.
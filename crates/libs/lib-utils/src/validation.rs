@@ -1,6 +1,9 @@
 //! # Validation Utilities
 //!
 //! Input validation helpers.
+//!
+//! Account fields (username, email, password) have their own rules in
+//! [`shared::validation`], which the signup handler and terminal share.
 
 /// Validate that a string is not empty.
 pub fn validate_not_empty(value: &str, field_name: &str) -> Result<(), String> {
@@ -11,12 +14,11 @@ pub fn validate_not_empty(value: &str, field_name: &str) -> Result<(), String> {
     }
 }
 
-/// Validate email format (basic check).
+/// Validate email format, using the same rule as signup.
 pub fn validate_email(email: &str) -> Result<(), String> {
-    if email.contains('@') && email.contains('.') {
-        Ok(())
-    } else {
-        Err("Invalid email format".to_string())
+    match shared::validation::validate_email(email).into_iter().next() {
+        Some(error) => Err(error.message),
+        None => Ok(()),
     }
 }
 
@@ -3,11 +3,25 @@
 //! AI bot that integrates with the Braid messaging protocol using rust-genai.
 //! Supports multiple AI providers (DeepSeek, OpenAI, Anthropic, Gemini, etc.)
 //! and responds to messages via Braid PUT protocol.
+//!
+//! Replies are streamed: subscribers see each chunk as it arrives (see
+//! [`crate::chat::stream`]) and the finished reply is then posted as a
+//! message. `POST /api/chat/{conversation_id}/cancel` stops a reply early.
+//!
+//! While replying the model can call the tools in [`crate::chat::tools`] to
+//! look up live prices, candles, swap quotes and the user's wallet balance.
 
-use crate::{chat::state::ChatState, chat::db as chat_db};
-use lib_core::dto::Message;
+use crate::{chat::state::ChatState, chat::db as chat_db, chat::summary::SummaryProvider};
+use crate::chat::stream::{ReplyStream, MAX_REPLY_LENGTH};
+#[cfg(feature = "genai")]
+use crate::chat::tools::{run_turns, ToolCall, ToolContext, ToolModel, ToolSpec, TranscriptItem};
+use lib_core::dto::{AiStreamEvent, AiStreamOutcome, Message};
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, watch};
+
+/// Minimum response length for conversation summaries, which need more room
+/// than a chat reply
+const SUMMARY_MAX_TOKENS: u32 = 800;
 
 /// AI Provider type
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -48,6 +62,14 @@ impl AiProvider {
             AiProvider::Gemini => "GEMINI_API_KEY",
         }
     }
+
+    /// First provider with an API key in the environment, in the order
+    /// DeepSeek, OpenAI, Anthropic, Gemini
+    pub fn from_env() -> Option<Self> {
+        [AiProvider::DeepSeek, AiProvider::OpenAI, AiProvider::Anthropic, AiProvider::Gemini]
+            .into_iter()
+            .find(|provider| std::env::var(provider.api_key_env()).is_ok_and(|key| !key.is_empty()))
+    }
 }
 
 /// Bot configuration
@@ -133,8 +155,8 @@ impl Default for BotConfig {
 /// This function spawns a background task that:
 /// 1. Subscribes to message broadcasts for the conversation
 /// 2. Detects when to respond (when mentioned or all messages)
-/// 3. Generates AI responses using rust-genai
-/// 4. Posts responses back via the Braid protocol
+/// 3. Streams AI responses from rust-genai to the conversation's subscribers
+/// 4. Posts the finished responses back via the Braid protocol
 pub fn start_ai_bot_for_conversation(
     conversation_id: String,
     chat_state: Arc<ChatAppState>,
@@ -180,9 +202,6 @@ pub fn start_ai_bot_for_conversation(
                         if should_respond {
                             tracing::info!("🤖 Bot detected message from {}: {}", last_message.author, last_message.text);
                             
-                            // Add a small delay to make the bot feel more natural (1-2 seconds)
-                            tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
-                            
                             // Get context (recent messages)
                             let context_messages: Vec<Message> = messages
                                 .iter()
@@ -192,22 +211,25 @@ pub fn start_ai_bot_for_conversation(
                                 .cloned()
                                 .collect();
                             
-                            // Generate response using rust-genai
-                            let response_text = match generate_response(&config, &context_messages).await {
-                                Ok(text) => text,
-                                Err(e) => {
-                                    tracing::error!("🤖 Failed to generate AI response: {:?}", e);
-                                    last_processed_version = Some(version);
-                                    continue;
-                                }
-                            };
+                            // Stream the response using rust-genai
+                            let cancelled = chat_state.begin_ai_stream(&conversation_id).await;
+                            let (reply, outcome) = stream_response(&config, &context_messages, &chat_state, &conversation_id, last_message.author_id, cancelled.clone()).await;
+                            chat_state.end_ai_stream(&conversation_id, &cancelled).await;
+                            match &outcome {
+                                AiStreamOutcome::Completed => {}
+                                AiStreamOutcome::Cancelled => tracing::info!("🤖 AI response cancelled"),
+                                AiStreamOutcome::Failed { error } => tracing::error!("🤖 AI response failed: {}", error),
+                            }
                             
-                            // Post response via Braid PUT
-                            if let Err(e) = post_bot_response(&chat_state, &config, &conversation_id, &response_text, &version).await {
-                                tracing::error!("🤖 Failed to post bot response: {:?}", e);
-                            } else {
-                                tracing::info!("🤖 Bot posted response: {}", response_text);
+                            // Post what was generated via Braid PUT, even if it was cut short
+                            if let Some(response_text) = reply.finish(&outcome) {
+                                if let Err(e) = post_bot_response(&chat_state, &config, &conversation_id, &response_text, &version).await {
+                                    tracing::error!("🤖 Failed to post bot response: {:?}", e);
+                                } else {
+                                    tracing::info!("🤖 Bot posted response: {}", response_text);
+                                }
                             }
+                            chat_state.broadcast_ai_stream(&conversation_id, AiStreamEvent::End { outcome }).await;
                         }
                     }
                     
@@ -226,17 +248,153 @@ pub fn start_ai_bot_for_conversation(
     });
 }
 
-/// Generate AI response using rust-genai
+/// Stream an AI response using rust-genai, pushing each chunk to the
+/// conversation's subscribers
+///
+/// The model may call the tools in [`ChatAppState::ai_tools`] before it
+/// answers; their results go back into the request (see
+/// [`crate::chat::tools::run_turns`]). `requester_id` is the authenticated
+/// author of the message being answered. Stops early when `cancelled` flips,
+/// dropping the provider's stream.
 #[cfg(feature = "genai")]
-async fn generate_response(
+async fn stream_response(
     config: &BotConfig,
     context_messages: &[Message],
-) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    use genai::chat::{ChatMessage, ChatOptions, ChatRequest};
+    chat_state: &ChatAppState,
+    conversation_id: &str,
+    requester_id: i64,
+    mut cancelled: watch::Receiver<bool>,
+) -> (ReplyStream, AiStreamOutcome) {
+    let mut reply = ReplyStream::new(MAX_REPLY_LENGTH);
+    let model = GenaiModel { config, client: genai_client(config) };
+    let ctx = ToolContext::new(conversation_id, requester_id, config.bot_user_id);
+    
+    // Add recent messages to context
+    let mut transcript: Vec<TranscriptItem> = context_messages
+        .iter()
+        .map(|msg| if msg.author == config.name {
+            TranscriptItem::Assistant(msg.text.clone())
+        } else {
+            TranscriptItem::User(msg.text.clone())
+        })
+        .collect();
+    
+    // Chunks are pushed from a sync callback, so grab the sender up front
+    let sender = chat_state.ai_stream_broadcast_senders.read().await.get(conversation_id).cloned();
+    let result = {
+        let mut on_text = |delta: &str| {
+            if let (Some(event), Some(sender)) = (reply.push(delta), &sender) {
+                let _ = sender.send(event);
+            }
+            !reply.is_full()
+        };
+        tracing::debug!("🤖 Streaming from AI API with model: {}", config.model);
+        tokio::select! {
+            result = run_turns(&model, &chat_state.ai_tools, &ctx, &mut transcript, &mut on_text) => Some(result),
+            Ok(_) = cancelled.wait_for(|cancelled| *cancelled) => None,
+        }
+    };
+    
+    let outcome = match result {
+        None => AiStreamOutcome::Cancelled,
+        Some(Err(error)) => AiStreamOutcome::Failed { error },
+        Some(Ok(())) if reply.text().trim().is_empty() => {
+            AiStreamOutcome::Failed { error: "Empty response from AI".to_string() }
+        }
+        Some(Ok(())) => AiStreamOutcome::Completed,
+    };
+    (reply, outcome)
+}
+
+/// rust-genai chat model for [`run_turns`]
+#[cfg(feature = "genai")]
+struct GenaiModel<'a> {
+    config: &'a BotConfig,
+    client: genai::Client,
+}
+
+#[cfg(feature = "genai")]
+#[async_trait::async_trait]
+impl ToolModel for GenaiModel<'_> {
+    async fn turn(
+        &self,
+        transcript: &[TranscriptItem],
+        tools: &[ToolSpec],
+        on_text: &mut (dyn FnMut(&str) -> bool + Send),
+    ) -> Result<Vec<ToolCall>, String> {
+        use futures_util::StreamExt;
+        use genai::chat::{ChatMessage, ChatOptions, ChatRequest, ChatStreamEvent, Tool, ToolResponse};
+        
+        // Build chat request with system message
+        let system_prompt = self.config.system_prompt.clone().unwrap_or_else(|| {
+            "You are a helpful AI assistant in a Solana trading terminal chat.".to_string()
+        });
+        let mut chat_req = ChatRequest::default().with_system(&system_prompt);
+        for item in transcript {
+            let message = match item {
+                TranscriptItem::User(text) => ChatMessage::user(text),
+                TranscriptItem::Assistant(text) => ChatMessage::assistant(text),
+                TranscriptItem::ToolCalls(calls) => ChatMessage::from(
+                    calls
+                        .iter()
+                        .map(|call| genai::chat::ToolCall {
+                            call_id: call.id.clone(),
+                            fn_name: call.name.clone(),
+                            fn_arguments: call.arguments.clone(),
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                TranscriptItem::ToolResult { call_id, content } => {
+                    ChatMessage::from(ToolResponse::new(call_id.clone(), content.clone()))
+                }
+            };
+            chat_req = chat_req.append_message(message);
+        }
+        if !tools.is_empty() {
+            chat_req = chat_req.with_tools(tools.iter().map(|spec| {
+                Tool::new(spec.name)
+                    .with_description(spec.description)
+                    .with_schema(spec.parameters.clone())
+            }).collect::<Vec<_>>());
+        }
+        
+        let chat_options = ChatOptions::default()
+            .with_temperature(self.config.temperature as f64)
+            .with_max_tokens(self.config.max_tokens)
+            .with_capture_tool_calls(true);
+        let mut stream = self
+            .client
+            .exec_chat_stream(&self.config.model, chat_req, Some(&chat_options))
+            .await
+            .map_err(|e| format!("AI API error: {}", e))?
+            .stream;
+        
+        while let Some(event) = stream.next().await {
+            match event.map_err(|e| e.to_string())? {
+                ChatStreamEvent::Chunk(chunk) => {
+                    if !on_text(&chunk.content) {
+                        return Ok(Vec::new());
+                    }
+                }
+                ChatStreamEvent::End(end) => {
+                    let calls = end.captured_into_tool_calls().unwrap_or_default();
+                    return Ok(calls
+                        .into_iter()
+                        .map(|call| ToolCall { id: call.call_id, name: call.fn_name, arguments: call.fn_arguments })
+                        .collect());
+                }
+                _ => {}
+            }
+        }
+        Ok(Vec::new())
+    }
+}
+
+/// Build a rust-genai client that authenticates with the configured API key
+#[cfg(feature = "genai")]
+fn genai_client(config: &BotConfig) -> genai::Client {
     use genai::resolver::{AuthData, AuthResolver};
-    use genai::Client;
     
-    // Build auth resolver for custom API key
     let api_key = config.api_key.clone();
     let auth_resolver = AuthResolver::from_resolver_fn(
         move |_model_iden| -> Result<Option<AuthData>, genai::resolver::Error> {
@@ -244,87 +402,53 @@ async fn generate_response(
         },
     );
     
-    // Build client with auth resolver
-    let client = Client::builder()
+    genai::Client::builder()
         .with_auth_resolver(auth_resolver)
-        .build();
-    
-    // Build chat request with system message
-    let system_prompt = config.system_prompt.clone().unwrap_or_else(|| {
-        "You are a helpful AI assistant in a Solana trading terminal chat.".to_string()
-    });
-    
-    let mut chat_req = ChatRequest::default().with_system(&system_prompt);
-    
-    // Add recent messages to context
-    for msg in context_messages {
-        if msg.author == config.name {
-            chat_req = chat_req.append_message(ChatMessage::assistant(&msg.text));
-        } else {
-            chat_req = chat_req.append_message(ChatMessage::user(&msg.text));
-        }
-    }
-    
-    // Build chat options
-    let chat_options = ChatOptions::default()
-        .with_temperature(config.temperature as f64)
-        .with_max_tokens(config.max_tokens);
-    
-    // Call AI provider
-    tracing::debug!("🤖 Calling AI API with model: {}", config.model);
-    let chat_res = client.exec_chat(&config.model, chat_req, Some(&chat_options)).await
-        .map_err(|e| format!("AI API error: {:?}", e))?;
-    
-    // Extract response text
-    let mut response_text = chat_res.first_text()
-        .ok_or_else(|| "No response from AI".to_string())?
-        .trim()
-        .to_string();
-    
-    if response_text.is_empty() {
-        return Err("Empty response from AI".into());
-    }
-    
-    // Post-process response: clean up common AI artifacts
-    response_text = response_text
-        .replace("As an AI assistant, ", "")
-        .replace("As an AI, ", "")
-        .replace("I'm an AI assistant, ", "")
-        .replace("I'm an AI, ", "")
-        .replace("As a language model, ", "")
-        .trim()
-        .to_string();
-    
-    // Validate response is not empty after post-processing
-    if response_text.trim().is_empty() {
-        return Err("Empty response from AI after post-processing".into());
+        .build()
+}
+
+/// Conversation summaries use the bot's provider, model and API key.
+///
+/// Temperature is kept low so summaries stick to the transcript; the bot's
+/// own system prompt is replaced by the summary instructions.
+#[cfg(feature = "genai")]
+#[async_trait::async_trait]
+impl SummaryProvider for BotConfig {
+    async fn complete(&self, system: &str, prompt: &str) -> anyhow::Result<String> {
+        use genai::chat::{ChatMessage, ChatOptions, ChatRequest};
+        
+        let chat_req = ChatRequest::default()
+            .with_system(system)
+            .append_message(ChatMessage::user(prompt));
+        let chat_options = ChatOptions::default()
+            .with_temperature(0.2)
+            .with_max_tokens(self.max_tokens.max(SUMMARY_MAX_TOKENS));
+        
+        tracing::debug!("🤖 Requesting conversation summary from model: {}", self.model);
+        let chat_res = genai_client(self)
+            .exec_chat(&self.model, chat_req, Some(&chat_options))
+            .await
+            .map_err(|e| anyhow::anyhow!("AI API error: {:?}", e))?;
+        
+        chat_res
+            .first_text()
+            .map(|text| text.trim().to_string())
+            .ok_or_else(|| anyhow::anyhow!("No response from AI"))
     }
-    
-    // Limit response length
-    const MAX_RESPONSE_LENGTH: usize = 1000;
-    let response_text = if response_text.len() > MAX_RESPONSE_LENGTH {
-        // Try to cut at a sentence boundary
-        if let Some(cut_point) = response_text[..MAX_RESPONSE_LENGTH].rfind('.') {
-            format!("{}...", &response_text[..=cut_point])
-        } else if let Some(cut_point) = response_text[..MAX_RESPONSE_LENGTH].rfind(' ') {
-            format!("{}...", &response_text[..cut_point])
-        } else {
-            format!("{}...", &response_text[..MAX_RESPONSE_LENGTH])
-        }
-    } else {
-        response_text
-    };
-    
-    Ok(response_text)
 }
 
 /// Fallback when genai feature is not enabled
 #[cfg(not(feature = "genai"))]
-async fn generate_response(
+async fn stream_response(
     _config: &BotConfig,
     _context_messages: &[Message],
-) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    Err("AI chat is not enabled. Please enable the 'genai' feature.".into())
+    _chat_state: &ChatAppState,
+    _conversation_id: &str,
+    _requester_id: i64,
+    _cancelled: watch::Receiver<bool>,
+) -> (ReplyStream, AiStreamOutcome) {
+    let error = "AI chat is not enabled. Please enable the 'genai' feature.".to_string();
+    (ReplyStream::new(MAX_REPLY_LENGTH), AiStreamOutcome::Failed { error })
 }
 
 /// Post bot response via Braid PUT protocol
@@ -3,9 +3,10 @@
 //! Provides database operations for persisting chat messages and conversation state.
 
 use lib_core::DbPool;
-use lib_core::dto::Message;
+use lib_core::dto::{Attachment, Message, ReceiptStatus};
 use sqlx::FromRow;
 use chrono::Utc;
+use std::collections::HashMap;
 
 /// Save a message to the database
 pub async fn save_message(
@@ -65,6 +66,7 @@ pub async fn load_messages_for_conversation(
     .fetch_all(pool)
     .await?;
     
+    let mut attachments = attachments_by_message(pool, conversation_id).await?;
     let messages = rows
         .into_iter()
         .map(|row| {
@@ -75,6 +77,9 @@ pub async fn load_messages_for_conversation(
                 author: format!("User{}", row.author_id), // Temporary, will fix with join
                 author_id: row.author_id,
                 timestamp: row.timestamp,
+                attachments: row.version.as_ref()
+                    .and_then(|version| attachments.remove(version))
+                    .unwrap_or_default(),
                 version: row.version,
             }
         })
@@ -262,6 +267,7 @@ pub async fn load_messages_with_usernames(
     .fetch_all(pool)
     .await?;
     
+    let mut attachments = attachments_by_message(pool, conversation_id).await?;
     let messages = rows
         .into_iter()
         .map(|row| Message {
@@ -269,6 +275,9 @@ pub async fn load_messages_with_usernames(
             author: row.username,
             author_id: row.sender_id,
             timestamp: row.timestamp,
+            attachments: row.version.as_ref()
+                .and_then(|version| attachments.remove(version))
+                .unwrap_or_default(),
             version: row.version,
         })
         .collect();
@@ -276,3 +285,298 @@ pub async fn load_messages_with_usernames(
     Ok(messages)
 }
 
+
+/// Whether a conversation is end-to-end encrypted
+///
+/// Conversations without a state row yet are not encrypted.
+pub async fn is_conversation_encrypted(
+    pool: &DbPool,
+    conversation_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let encrypted = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT is_encrypted
+        FROM conversation_state
+        WHERE conversation_id = ?
+        "#
+    )
+    .bind(conversation_id)
+    .fetch_optional(pool)
+    .await?;
+    
+    Ok(encrypted.unwrap_or(false))
+}
+
+/// `conversation_state` column holding a participant's receipt marker
+fn receipt_column(status: ReceiptStatus, is_user1: bool) -> &'static str {
+    match (status, is_user1) {
+        (ReceiptStatus::Delivered, true) => "user1_last_delivered_message_id",
+        (ReceiptStatus::Delivered, false) => "user2_last_delivered_message_id",
+        (ReceiptStatus::Read, true) => "user1_last_read_message_id",
+        (ReceiptStatus::Read, false) => "user2_last_read_message_id",
+    }
+}
+
+/// Move `user_id`'s delivered or read marker up to the newest message sent to them
+///
+/// # Returns
+///
+/// * `Ok(Some(version))` - The marker moved; `version` is the newest message it covers
+/// * `Ok(None)` - Nothing new to mark, or no conversation state yet
+pub async fn advance_receipt(
+    pool: &DbPool,
+    conversation_id: &str,
+    user_id: i64,
+    status: ReceiptStatus,
+) -> Result<Option<String>, sqlx::Error> {
+    let user1_id = sqlx::query_scalar::<_, i64>(
+        "SELECT user1_id FROM conversation_state WHERE conversation_id = ?"
+    )
+    .bind(conversation_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some(user1_id) = user1_id else {
+        return Ok(None);
+    };
+    
+    let latest = sqlx::query_as::<_, (i64, Option<String>)>(
+        r#"
+        SELECT id, version
+        FROM direct_messages
+        WHERE conversation_id = ? AND receiver_id = ?
+        ORDER BY id DESC
+        LIMIT 1
+        "#
+    )
+    .bind(conversation_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some((message_id, version)) = latest else {
+        return Ok(None);
+    };
+    
+    // Column names come from `receipt_column`, never from input
+    let column = receipt_column(status, user_id == user1_id);
+    let result = sqlx::query(&format!(
+        "UPDATE conversation_state SET {column} = ?, updated_at = CURRENT_TIMESTAMP
+         WHERE conversation_id = ? AND COALESCE({column}, 0) < ?"
+    ))
+    .bind(message_id)
+    .bind(conversation_id)
+    .bind(message_id)
+    .execute(pool)
+    .await?;
+    
+    Ok(version.filter(|_| result.rows_affected() > 0))
+}
+
+/// Newest message versions `user_id` has been delivered and has read, in that order
+pub async fn receipt_markers(
+    pool: &DbPool,
+    conversation_id: &str,
+    user_id: i64,
+) -> Result<(Option<String>, Option<String>), sqlx::Error> {
+    Ok((
+        receipt_marker(pool, conversation_id, user_id, ReceiptStatus::Delivered).await?,
+        receipt_marker(pool, conversation_id, user_id, ReceiptStatus::Read).await?,
+    ))
+}
+
+async fn receipt_marker(
+    pool: &DbPool,
+    conversation_id: &str,
+    user_id: i64,
+    status: ReceiptStatus,
+) -> Result<Option<String>, sqlx::Error> {
+    let version = sqlx::query_scalar::<_, Option<String>>(&format!(
+        r#"
+        SELECT dm.version
+        FROM conversation_state cs
+        JOIN direct_messages dm ON dm.id = CASE WHEN cs.user1_id = ? THEN cs.{} ELSE cs.{} END
+        WHERE cs.conversation_id = ?
+        "#,
+        receipt_column(status, true),
+        receipt_column(status, false),
+    ))
+    .bind(user_id)
+    .bind(conversation_id)
+    .fetch_optional(pool)
+    .await?;
+    
+    Ok(version.flatten())
+}
+
+/// Unread messages of `user_id` by conversation ID
+///
+/// Counts the messages sent to them past their read marker; conversations
+/// with nothing unread are left out.
+pub async fn unread_counts(
+    pool: &DbPool,
+    user_id: i64,
+) -> Result<HashMap<String, i64>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (String, i64)>(
+        r#"
+        SELECT dm.conversation_id, COUNT(*)
+        FROM direct_messages dm
+        LEFT JOIN conversation_state cs ON cs.conversation_id = dm.conversation_id
+        WHERE dm.receiver_id = ?
+          AND dm.id > COALESCE(
+              CASE WHEN cs.user1_id = ? THEN cs.user1_last_read_message_id
+                   ELSE cs.user2_last_read_message_id END,
+              0)
+        GROUP BY dm.conversation_id
+        "#
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.into_iter().collect())
+}
+
+/// Uploaded attachment with its ownership details
+#[derive(Debug, Clone, FromRow)]
+pub struct AttachmentRow {
+    pub id: String,
+    pub uploader_id: i64,
+    pub conversation_id: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i64,
+    /// Version of the message it was sent in (`None` until sent)
+    pub message_version: Option<String>,
+}
+
+impl AttachmentRow {
+    pub fn attachment(&self) -> Attachment {
+        Attachment {
+            id: self.id.clone(),
+            filename: self.filename.clone(),
+            content_type: self.content_type.clone(),
+            size: self.size.max(0) as u64,
+        }
+    }
+}
+
+/// Record an uploaded attachment, not yet sent in a message
+pub async fn save_attachment(
+    pool: &DbPool,
+    uploader_id: i64,
+    conversation_id: &str,
+    attachment: &Attachment,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO chat_attachments (id, uploader_id, conversation_id, filename, content_type, size)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&attachment.id)
+    .bind(uploader_id)
+    .bind(conversation_id)
+    .bind(&attachment.filename)
+    .bind(&attachment.content_type)
+    .bind(attachment.size as i64)
+    .execute(pool)
+    .await?;
+    
+    Ok(())
+}
+
+/// Get an attachment by ID
+pub async fn get_attachment(
+    pool: &DbPool,
+    attachment_id: &str,
+) -> Result<Option<AttachmentRow>, sqlx::Error> {
+    sqlx::query_as::<_, AttachmentRow>(
+        r#"
+        SELECT id, uploader_id, conversation_id, filename, content_type, size, message_version
+        FROM chat_attachments
+        WHERE id = ?
+        "#
+    )
+    .bind(attachment_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Mark unsent attachments as sent in the message `version_id`
+pub async fn attach_to_message(
+    pool: &DbPool,
+    attachment_ids: &[String],
+    version_id: &str,
+) -> Result<(), sqlx::Error> {
+    for attachment_id in attachment_ids {
+        sqlx::query(
+            r#"
+            UPDATE chat_attachments
+            SET message_version = ?
+            WHERE id = ? AND message_version IS NULL
+            "#
+        )
+        .bind(version_id)
+        .bind(attachment_id)
+        .execute(pool)
+        .await?;
+    }
+    
+    Ok(())
+}
+
+/// Sent attachments of a conversation by message version, in upload order
+pub async fn attachments_by_message(
+    pool: &DbPool,
+    conversation_id: &str,
+) -> Result<HashMap<String, Vec<Attachment>>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, AttachmentRow>(
+        r#"
+        SELECT id, uploader_id, conversation_id, filename, content_type, size, message_version
+        FROM chat_attachments
+        WHERE conversation_id = ? AND message_version IS NOT NULL
+        ORDER BY created_at ASC, rowid ASC
+        "#
+    )
+    .bind(conversation_id)
+    .fetch_all(pool)
+    .await?;
+    
+    let mut by_message: HashMap<String, Vec<Attachment>> = HashMap::new();
+    for row in rows {
+        let attachment = row.attachment();
+        if let Some(version) = row.message_version {
+            by_message.entry(version).or_default().push(attachment);
+        }
+    }
+    Ok(by_message)
+}
+
+/// Delete a conversation's messages, state and attachment records
+///
+/// # Returns
+///
+/// IDs of the deleted attachments, whose files the caller removes
+pub async fn delete_conversation(
+    pool: &DbPool,
+    conversation_id: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    
+    let attachment_ids = sqlx::query_scalar::<_, String>(
+        "SELECT id FROM chat_attachments WHERE conversation_id = ?"
+    )
+    .bind(conversation_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    
+    for table in ["chat_attachments", "direct_messages", "conversation_state"] {
+        sqlx::query(&format!("DELETE FROM {table} WHERE conversation_id = ?"))
+            .bind(conversation_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    
+    tx.commit().await?;
+    Ok(attachment_ids)
+}
@@ -0,0 +1,545 @@
+//! # Attachment Handlers
+//!
+//! Handlers for uploading, downloading and cleaning up message attachments.
+//!
+//! Files are uploaded first, then referenced by ID from the message sent with
+//! [`super::handle_braid_put`]. The bytes are stored on disk under
+//! [`ChatAppState::attachments_dir`], the metadata in `chat_attachments`.
+
+use super::utils::{extract_user_id_from_token, parse_conversation_id, check_friendship, is_ai_bot_conversation};
+use crate::chat::db as chat_db;
+use crate::chat::state::ChatAppState;
+use axum::{
+    body::Body,
+    extract::{multipart::MultipartRejection, Multipart, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::Response,
+    Json,
+};
+use lib_core::dto::{Attachment, ErrorResponse};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Largest accepted attachment (5 MB)
+pub const MAX_ATTACHMENT_BYTES: usize = 5 * 1024 * 1024;
+
+/// Request body limit of the upload route
+///
+/// Leaves room for the multipart framing and the `conversation_id` field, so
+/// a file just under [`MAX_ATTACHMENT_BYTES`] isn't cut off by the body limit.
+pub const MAX_UPLOAD_BODY_BYTES: usize = MAX_ATTACHMENT_BYTES + 64 * 1024;
+
+/// Content types accepted for upload
+pub const ALLOWED_CONTENT_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "application/pdf",
+    "text/plain",
+    "text/csv",
+];
+
+const MAX_FILENAME_LENGTH: usize = 255;
+
+type AttachmentError = (StatusCode, Json<ErrorResponse>);
+
+fn error(status: StatusCode, message: &str) -> AttachmentError {
+    (status, Json(ErrorResponse { error: message.to_string() }))
+}
+
+fn too_large() -> AttachmentError {
+    error(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        &format!("Attachments are limited to {} MB", MAX_ATTACHMENT_BYTES / (1024 * 1024)),
+    )
+}
+
+/// Upload a file to attach to a message
+///
+/// `POST /api/chat/attachments` (multipart form)
+///
+/// Fields:
+/// * `conversation_id` - Conversation the file will be sent in
+/// * `file` - The file; its content type falls back to a guess from the file name
+///
+/// # Errors
+///
+/// * `400` - Malformed form, or a missing field
+/// * `401` - Missing or invalid token
+/// * `403` - Caller can't send messages in the conversation
+/// * `413` - File larger than [`MAX_ATTACHMENT_BYTES`]
+/// * `415` - Content type not in [`ALLOWED_CONTENT_TYPES`]
+pub async fn handle_upload_attachment(
+    State(app_state): State<Arc<ChatAppState>>,
+    headers: HeaderMap,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Result<(StatusCode, Json<Attachment>), AttachmentError> {
+    // Authenticate user
+    let user_id = extract_user_id_from_token(&headers, &app_state.config)
+        .map_err(|status| error(status, "Authentication required"))?;
+    let mut multipart = multipart
+        .map_err(|_| error(StatusCode::BAD_REQUEST, "Expected a multipart/form-data upload"))?;
+
+    let mut conversation_id = None;
+    let mut file = None;
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        let name = field.name().map(str::to_string);
+        match name.as_deref() {
+            Some("conversation_id") => {
+                conversation_id = Some(field.text().await.map_err(multipart_error)?.trim().to_string());
+            }
+            Some("file") => {
+                let filename = sanitize_filename(field.file_name().unwrap_or_default());
+                let content_type = field.content_type()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| mime_guess::from_path(&filename).first_or_octet_stream().to_string());
+
+                // Stop reading as soon as the file is too large
+                let mut bytes = Vec::new();
+                while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+                    if bytes.len() + chunk.len() > MAX_ATTACHMENT_BYTES {
+                        return Err(too_large());
+                    }
+                    bytes.extend_from_slice(&chunk);
+                }
+                file = Some((filename, content_type, bytes));
+            }
+            _ => {}
+        }
+    }
+
+    let conversation_id = conversation_id
+        .ok_or_else(|| error(StatusCode::BAD_REQUEST, "Missing conversation_id field"))?;
+    let (filename, content_type, bytes) = file
+        .ok_or_else(|| error(StatusCode::BAD_REQUEST, "Missing file field"))?;
+
+    ensure_can_send(&app_state, user_id, &conversation_id).await?;
+
+    let content_type = normalize_content_type(&content_type);
+    if !ALLOWED_CONTENT_TYPES.contains(&content_type.as_str()) {
+        return Err(error(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            &format!("Files of type {} can't be attached", content_type),
+        ));
+    }
+    if bytes.is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "The file is empty"));
+    }
+
+    let attachment = Attachment {
+        id: Uuid::new_v4().to_string(),
+        filename,
+        content_type,
+        size: bytes.len() as u64,
+    };
+
+    let stored = async {
+        tokio::fs::create_dir_all(&app_state.attachments_dir).await?;
+        tokio::fs::write(app_state.attachment_path(&attachment.id), &bytes).await
+    };
+    stored.await.map_err(|e| {
+        tracing::error!("Failed to store attachment: {:?}", e);
+        error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to store attachment")
+    })?;
+
+    if let Err(e) = chat_db::save_attachment(&app_state.db, user_id, &conversation_id, &attachment).await {
+        tracing::error!("Failed to save attachment: {:?}", e);
+        remove_attachment_file(&app_state, &attachment.id).await;
+        return Err(error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to store attachment"));
+    }
+
+    Ok((StatusCode::CREATED, Json(attachment)))
+}
+
+/// Download an attachment
+///
+/// `GET /api/chat/attachments/{attachment_id}`
+///
+/// Available to both participants once sent; until then only to the uploader.
+///
+/// # Errors
+///
+/// * `401` - Missing or invalid token
+/// * `404` - No such attachment, or the caller can't see it
+pub async fn handle_download_attachment(
+    Path(attachment_id): Path<String>,
+    State(app_state): State<Arc<ChatAppState>>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, AttachmentError> {
+    // Authenticate user
+    let user_id = extract_user_id_from_token(&headers, &app_state.config)
+        .map_err(|status| error(status, "Authentication required"))?;
+
+    let not_found = || error(StatusCode::NOT_FOUND, "Attachment not found");
+    let row = chat_db::get_attachment(&app_state.db, &attachment_id).await
+        .map_err(|e| {
+            tracing::error!("Failed to load attachment: {:?}", e);
+            error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load attachment")
+        })?
+        .ok_or_else(not_found)?;
+
+    // Don't reveal attachments of other conversations
+    let (user1_id, user2_id) = parse_conversation_id(&row.conversation_id).map_err(|_| not_found())?;
+    let visible = if row.message_version.is_some() {
+        user_id == user1_id || user_id == user2_id
+    } else {
+        user_id == row.uploader_id
+    };
+    if !visible {
+        return Err(not_found());
+    }
+
+    let bytes = tokio::fs::read(app_state.attachment_path(&row.id)).await
+        .map_err(|e| {
+            tracing::error!("Attachment {} is missing its file: {:?}", row.id, e);
+            not_found()
+        })?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, &row.content_type)
+        .header(header::CONTENT_LENGTH, bytes.len())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", row.filename.replace(['"', '\\'], "_")),
+        )
+        .body(Body::from(bytes))
+        .map_err(|_| error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to send attachment"))
+}
+
+/// Delete a conversation for both participants, along with its attachments
+///
+/// `DELETE /api/chat/conversations/{conversation_id}`
+///
+/// # Errors
+///
+/// * `401` - Missing or invalid token
+/// * `403` - Caller isn't part of the conversation
+pub async fn handle_delete_conversation(
+    Path(conversation_id): Path<String>,
+    State(app_state): State<Arc<ChatAppState>>,
+    headers: HeaderMap,
+) -> Result<StatusCode, StatusCode> {
+    // Authenticate user
+    let user_id = extract_user_id_from_token(&headers, &app_state.config)?;
+
+    // Verify user is part of this conversation
+    let (user1_id, user2_id) = parse_conversation_id(&conversation_id)?;
+    if user_id != user1_id && user_id != user2_id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let attachment_ids = chat_db::delete_conversation(&app_state.db, &conversation_id).await
+        .map_err(|e| {
+            tracing::error!("Failed to delete conversation: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    for attachment_id in &attachment_ids {
+        remove_attachment_file(&app_state, attachment_id).await;
+    }
+
+    app_state.chat_states.write().await.remove(&conversation_id);
+
+    tracing::info!(conversation_id = %conversation_id, attachments = attachment_ids.len(), "Conversation deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check that `user_id` may send messages in a conversation, as
+/// [`super::handle_braid_put`] does
+async fn ensure_can_send(app_state: &ChatAppState, user_id: i64, conversation_id: &str) -> Result<(), AttachmentError> {
+    let (user1_id, user2_id) = parse_conversation_id(conversation_id)
+        .map_err(|status| error(status, "Invalid conversation ID"))?;
+    if user_id != user1_id && user_id != user2_id {
+        return Err(error(StatusCode::FORBIDDEN, "You are not part of this conversation"));
+    }
+
+    if !is_ai_bot_conversation(&app_state.db, user1_id, user2_id).await {
+        let friendship_status = check_friendship(&app_state.db, user1_id, user2_id).await
+            .map_err(|_| error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to check friendship"))?;
+        if friendship_status != "accepted" {
+            return Err(error(StatusCode::FORBIDDEN, "You can only send files to friends"));
+        }
+    }
+    Ok(())
+}
+
+fn multipart_error(e: axum::extract::multipart::MultipartError) -> AttachmentError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        too_large()
+    } else {
+        error(e.status(), &e.body_text())
+    }
+}
+
+async fn remove_attachment_file(app_state: &ChatAppState, attachment_id: &str) {
+    let path = app_state.attachment_path(attachment_id);
+    if let Err(e) = tokio::fs::remove_file(&path).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Failed to remove attachment file {:?}: {:?}", path, e);
+        }
+    }
+}
+
+/// Bare, lowercase MIME type, without parameters like `charset`
+fn normalize_content_type(content_type: &str) -> String {
+    content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase()
+}
+
+/// File name without any directory part, capped in length
+fn sanitize_filename(filename: &str) -> String {
+    let name = filename.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+    let name: String = name.chars().filter(|c| !c.is_control()).take(MAX_FILENAME_LENGTH).collect();
+    if name.is_empty() || name == "." || name == ".." {
+        "attachment".to_string()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        extract::DefaultBodyLimit,
+        http::Request,
+        routing::{delete, get, post, put},
+        Router,
+    };
+    use crate::chat::handlers::handle_braid_put;
+    use lib_auth::encode_jwt;
+    use lib_core::{Config, DbPool};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "test-secret-key-must-be-at-least-32-characters-long!";
+    const BOUNDARY: &str = "xforce-test-boundary";
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        for statement in [
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, username TEXT NOT NULL, email TEXT NOT NULL)",
+            "CREATE TABLE friendships (sender_id INTEGER NOT NULL, receiver_id INTEGER NOT NULL, status TEXT NOT NULL)",
+            "CREATE TABLE conversation_state (
+                conversation_id TEXT NOT NULL UNIQUE,
+                user1_id INTEGER NOT NULL,
+                user2_id INTEGER NOT NULL,
+                last_version TEXT,
+                last_message_at DATETIME,
+                user1_unread_count INTEGER NOT NULL DEFAULT 0,
+                user2_unread_count INTEGER NOT NULL DEFAULT 0,
+                updated_at DATETIME
+            )",
+            "CREATE TABLE direct_messages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                sender_id INTEGER NOT NULL,
+                receiver_id INTEGER NOT NULL,
+                conversation_id TEXT NOT NULL,
+                text TEXT NOT NULL,
+                version TEXT,
+                timestamp TEXT NOT NULL,
+                created_at DATETIME
+            )",
+            "CREATE TABLE chat_attachments (
+                id TEXT PRIMARY KEY,
+                uploader_id INTEGER NOT NULL,
+                conversation_id TEXT NOT NULL,
+                filename TEXT NOT NULL,
+                content_type TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                message_version TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )",
+            "INSERT INTO users (id, username, email) VALUES
+                (1, 'alice', 'a@example.com'), (2, 'bob', 'b@example.com'), (3, 'carol', 'c@example.com')",
+            "INSERT INTO friendships (sender_id, receiver_id, status) VALUES (1, 2, 'accepted')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.expect("Failed to set up test schema");
+        }
+
+        pool
+    }
+
+    async fn attachments_app() -> (Router, Arc<ChatAppState>) {
+        let config = Config {
+            database_url: "sqlite::memory:".to_string(),
+            jwt_secret: SECRET.to_string(),
+            jwt_expiration_hours: 24,
+            streamed_symbols: Vec::new(),
+            admin_usernames: Vec::new(),
+        };
+        let dir = std::env::temp_dir().join(format!("xforce-chat-attachments-{}", Uuid::new_v4()));
+        let chat_state = Arc::new(ChatAppState::new(setup_test_db().await, config).with_attachments_dir(dir));
+        let app = Router::new()
+            .route(
+                "/api/chat/attachments",
+                post(handle_upload_attachment).layer(DefaultBodyLimit::max(MAX_UPLOAD_BODY_BYTES)),
+            )
+            .route("/api/chat/attachments/{attachment_id}", get(handle_download_attachment))
+            .route("/api/chat/conversations/{conversation_id}", delete(handle_delete_conversation))
+            .route("/api/chat/{conversation_id}", put(handle_braid_put))
+            .with_state(chat_state.clone());
+        (app, chat_state)
+    }
+
+    fn token(user_id: i64) -> String {
+        format!("Bearer {}", encode_jwt(user_id, "user".to_string(), SECRET, 1).unwrap())
+    }
+
+    fn upload_body(conversation_id: &str, filename: &str, content_type: &str, contents: &[u8]) -> Vec<u8> {
+        let mut body = format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"conversation_id\"\r\n\r\n{conversation_id}\r\n\
+             --{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{filename}\"\r\n\
+             Content-Type: {content_type}\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(contents);
+        body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+        body
+    }
+
+    async fn upload(app: &Router, user_id: i64, body: Vec<u8>) -> (StatusCode, serde_json::Value) {
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/chat/attachments")
+                    .header("Authorization", token(user_id))
+                    .header("Content-Type", format!("multipart/form-data; boundary={BOUNDARY}"))
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    async fn send(app: &Router, user_id: i64, conversation_id: &str, message: serde_json::Value) -> StatusCode {
+        app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri(format!("/api/chat/{}", conversation_id))
+                    .header("Authorization", token(user_id))
+                    .body(Body::from(message.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    async fn download(app: &Router, user_id: i64, attachment_id: &str) -> (StatusCode, Vec<u8>) {
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/chat/attachments/{}", attachment_id))
+                    .header("Authorization", token(user_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        (status, axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_upload_send_and_download() {
+        let (app, state) = attachments_app().await;
+
+        let (status, attachment) = upload(&app, 1, upload_body("1:2", "../chart.png", "image/png", b"png bytes")).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(attachment["filename"], "chart.png");
+        assert_eq!(attachment["size"], 9);
+        let id = attachment["id"].as_str().unwrap().to_string();
+
+        // Unsent uploads are private to the uploader
+        assert_eq!(download(&app, 2, &id).await.0, StatusCode::NOT_FOUND);
+
+        let message = serde_json::json!({
+            "text": "", "author": "", "author_id": 0, "timestamp": "2025-02-22T10:00:00+00:00",
+            "attachments": [{ "id": id, "filename": "renamed.exe", "content_type": "text/plain", "size": 1 }],
+        });
+        assert_eq!(send(&app, 1, "1:2", message.clone()).await, StatusCode::OK);
+
+        // The stored metadata wins over what the client sent
+        let messages = chat_db::load_messages_with_usernames(&state.db, "1:2").await.unwrap();
+        assert_eq!(messages[0].attachments.len(), 1);
+        assert_eq!(messages[0].attachments[0].filename, "chart.png");
+        assert!(messages[0].attachments[0].is_image());
+
+        assert_eq!(download(&app, 2, &id).await, (StatusCode::OK, b"png bytes".to_vec()));
+        assert_eq!(download(&app, 3, &id).await.0, StatusCode::NOT_FOUND);
+
+        // An attachment can only be sent once
+        assert_eq!(send(&app, 1, "1:2", message).await, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_upload_is_413_json() {
+        let (app, _) = attachments_app().await;
+
+        let contents = vec![0u8; MAX_ATTACHMENT_BYTES + 1];
+        let (status, body) = upload(&app, 1, upload_body("1:2", "big.png", "image/png", &contents)).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["error"], "Attachments are limited to 5 MB");
+
+        // Past the body limit too
+        let contents = vec![0u8; MAX_UPLOAD_BODY_BYTES + 1];
+        let (status, body) = upload(&app, 1, upload_body("1:2", "big.png", "image/png", &contents)).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["error"], "Attachments are limited to 5 MB");
+    }
+
+    #[tokio::test]
+    async fn test_upload_rejects_other_types_and_outsiders() {
+        let (app, _) = attachments_app().await;
+
+        let (status, _) = upload(&app, 1, upload_body("1:2", "run.sh", "application/x-sh", b"echo")).await;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let (status, _) = upload(&app, 3, upload_body("1:2", "notes.txt", "text/plain", b"hi")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_deleting_conversation_removes_attachments() {
+        let (app, state) = attachments_app().await;
+        let (_, attachment) = upload(&app, 1, upload_body("1:2", "notes.txt", "text/plain; charset=utf-8", b"hi")).await;
+        let id = attachment["id"].as_str().unwrap().to_string();
+        assert_eq!(attachment["content_type"], "text/plain");
+        assert!(state.attachment_path(&id).exists());
+
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri("/api/chat/conversations/1:2")
+                    .header("Authorization", token(1))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        assert!(!state.attachment_path(&id).exists());
+        assert!(chat_db::get_attachment(&state.db, &id).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("C:\\Users\\me\\report.pdf"), "report.pdf");
+        assert_eq!(sanitize_filename("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_filename(".."), "attachment");
+        assert_eq!(sanitize_filename(""), "attachment");
+    }
+}
@@ -0,0 +1,120 @@
+//! # AI Reply Cancel Handler
+//!
+//! Handler for stopping an AI bot reply while it streams in.
+
+use super::utils::{extract_user_id_from_token, parse_conversation_id};
+use crate::chat::state::ChatAppState;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use std::sync::Arc;
+
+/// Stop the AI reply being generated in a conversation
+///
+/// `POST /api/chat/{conversation_id}/cancel`
+///
+/// What was generated so far is still posted, marked as cancelled.
+///
+/// # Errors
+///
+/// * `401` - Missing or invalid token
+/// * `403` - Caller isn't part of the conversation
+/// * `404` - No reply is being generated
+pub async fn handle_cancel_ai_reply(
+    Path(conversation_id): Path<String>,
+    State(app_state): State<Arc<ChatAppState>>,
+    headers: HeaderMap,
+) -> Result<StatusCode, StatusCode> {
+    // Authenticate user
+    let user_id = extract_user_id_from_token(&headers, &app_state.config)?;
+
+    // Verify user is part of this conversation
+    let (user1_id, user2_id) = parse_conversation_id(&conversation_id)?;
+    if user_id != user1_id && user_id != user2_id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    if app_state.cancel_ai_stream(&conversation_id).await {
+        tracing::info!(conversation_id = %conversation_id, "AI reply cancelled");
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::post, Router};
+    use lib_auth::encode_jwt;
+    use lib_core::Config;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "test-secret-key-must-be-at-least-32-characters-long!";
+
+    async fn cancel_app() -> (Router, Arc<ChatAppState>) {
+        let config = Config {
+            database_url: "sqlite::memory:".to_string(),
+            jwt_secret: SECRET.to_string(),
+            jwt_expiration_hours: 24,
+            streamed_symbols: Vec::new(),
+            admin_usernames: Vec::new(),
+        };
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        let chat_state = Arc::new(ChatAppState::new(db, config));
+        let app = Router::new()
+            .route("/api/chat/{conversation_id}/cancel", post(handle_cancel_ai_reply))
+            .with_state(chat_state.clone());
+        (app, chat_state)
+    }
+
+    async fn cancel(app: &Router, user_id: i64, conversation_id: &str) -> StatusCode {
+        let token = encode_jwt(user_id, "user".to_string(), SECRET, 1).unwrap();
+        app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/chat/{}/cancel", conversation_id))
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_reply_in_progress() {
+        let (app, state) = cancel_app().await;
+        let cancelled = state.begin_ai_stream("0:1").await;
+
+        assert_eq!(cancel(&app, 2, "0:1").await, StatusCode::FORBIDDEN);
+        assert!(!*cancelled.borrow());
+
+        assert_eq!(cancel(&app, 1, "0:1").await, StatusCode::NO_CONTENT);
+        assert!(*cancelled.borrow());
+
+        // Already stopped
+        assert_eq!(cancel(&app, 1, "0:1").await, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_new_reply_replaces_running_one() {
+        let (app, state) = cancel_app().await;
+        let first = state.begin_ai_stream("0:1").await;
+        let second = state.begin_ai_stream("0:1").await;
+        assert!(*first.borrow());
+
+        // The first reply finishing late must not forget the second
+        state.end_ai_stream("0:1", &first).await;
+        assert_eq!(cancel(&app, 1, "0:1").await, StatusCode::NO_CONTENT);
+        assert!(*second.borrow());
+    }
+}
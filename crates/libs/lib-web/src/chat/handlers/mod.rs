@@ -7,10 +7,18 @@ pub mod utils;
 pub mod subscription;
 pub mod put;
 pub mod typing;
+pub mod summary;
+pub mod read;
+pub mod attachments;
+pub mod cancel;
 // endregion: --- Modules
 
 // region: --- Re-exports
 pub use subscription::handle_braid_subscription;
 pub use put::handle_braid_put;
 pub use typing::handle_typing_event;
+pub use summary::handle_conversation_summary;
+pub use read::handle_mark_read;
+pub use attachments::{handle_upload_attachment, handle_download_attachment, handle_delete_conversation};
+pub use cancel::handle_cancel_ai_reply;
 // endregion: --- Re-exports
@@ -2,7 +2,7 @@
 //!
 //! Handler for creating new messages via Braid PUT protocol.
 
-use super::utils::{extract_user_id_from_token, parse_conversation_id, check_friendship, get_username};
+use super::utils::{extract_user_id_from_token, parse_conversation_id, check_friendship, get_username, is_ai_bot_conversation};
 use crate::chat::state::{ChatAppState, ChatState};
 use crate::chat::db as chat_db;
 use lib_core::dto::Message;
@@ -33,32 +33,7 @@ pub async fn handle_braid_put(
     }
     
     // Check if this is a conversation with the AI bot
-    // User ID 0 is reserved for the AI bot, or check for system@ai.bot user
-    let is_ai_bot_conversation = {
-        // First check if user_id 0 is involved (AI bot uses ID 0)
-        if user1_id == 0 || user2_id == 0 {
-            true
-        } else {
-            // Otherwise check if bot user exists in database
-            let bot_user_id_result = sqlx::query_scalar::<_, i64>(
-                r#"
-                SELECT id
-                FROM users
-                WHERE email = 'system@ai.bot'
-                LIMIT 1
-                "#
-            )
-            .fetch_optional(&app_state.db)
-            .await
-            .unwrap_or(None);
-            
-            if let Some(bot_user_id) = bot_user_id_result {
-                user_id == bot_user_id || user1_id == bot_user_id || user2_id == bot_user_id
-            } else {
-                false
-            }
-        }
-    };
+    let is_ai_bot_conversation = is_ai_bot_conversation(&app_state.db, user1_id, user2_id).await;
     
     // Check friendship status (skip for AI bot conversations)
     if !is_ai_bot_conversation {
@@ -84,8 +59,8 @@ pub async fn handle_braid_put(
     let mut message: Message = serde_json::from_slice(&body)
         .map_err(|_| StatusCode::BAD_REQUEST)?;
     
-    // Validate message
-    if message.text.trim().is_empty() {
+    // Validate message; a message with attachments may have no text
+    if message.text.trim().is_empty() && message.attachments.is_empty() {
         return Err(StatusCode::BAD_REQUEST);
     }
     
@@ -94,6 +69,29 @@ pub async fn handle_braid_put(
         return Err(StatusCode::BAD_REQUEST);
     }
     
+    const MAX_ATTACHMENTS_PER_MESSAGE: usize = 10;
+    if message.attachments.len() > MAX_ATTACHMENTS_PER_MESSAGE {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    
+    // Only the caller's own unsent uploads to this conversation can be attached;
+    // their stored metadata replaces whatever the client sent
+    let mut attachment_ids = Vec::with_capacity(message.attachments.len());
+    for attachment in &mut message.attachments {
+        let row = chat_db::get_attachment(&app_state.db, &attachment.id).await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::BAD_REQUEST)?;
+        if row.uploader_id != user_id
+            || row.conversation_id != conversation_id
+            || row.message_version.is_some()
+            || attachment_ids.contains(&row.id)
+        {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        *attachment = row.attachment();
+        attachment_ids.push(row.id);
+    }
+    
     // Set author info
     message.author = username;
     message.author_id = user_id;
@@ -143,8 +141,16 @@ pub async fn handle_braid_put(
     } else {
         tracing::debug!("Skipping database save for AI bot conversation (user_id 0)");
     }
+    if !attachment_ids.is_empty() {
+        if let Err(e) = chat_db::attach_to_message(&app_state.db, &attachment_ids, &version_id).await {
+            tracing::error!("Failed to link attachments to message: {:?}", e);
+        }
+    }
     
-    // Update conversation state
+    // Update conversation state, creating it on the first message
+    if let Err(e) = chat_db::get_or_create_conversation_state(&app_state.db, &conversation_id, user1_id, user2_id).await {
+        tracing::error!("Failed to create conversation state: {:?}", e);
+    }
     if let Err(e) = chat_db::update_conversation_state(
         &app_state.db,
         &conversation_id,
@@ -202,22 +208,10 @@ pub async fn handle_braid_put(
             if bot_in_conversation {
                 use crate::chat::ai_bot::{start_ai_bot_for_conversation, BotConfig, AiProvider};
                 
-                let provider = if std::env::var("DEEPSEEK_API_KEY").is_ok() {
-                    AiProvider::DeepSeek
-                } else if std::env::var("OPENAI_API_KEY").is_ok() {
-                    AiProvider::OpenAI
-                } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
-                    AiProvider::Anthropic
-                } else if std::env::var("GEMINI_API_KEY").is_ok() {
-                    AiProvider::Gemini
-                } else {
-                    AiProvider::DeepSeek
-                };
-                
-                let api_key = std::env::var(provider.api_key_env())
-                    .unwrap_or_else(|_| String::new());
-                
-                if !api_key.is_empty() {
+                if let Some(provider) = AiProvider::from_env() {
+                    let api_key = std::env::var(provider.api_key_env())
+                        .unwrap_or_else(|_| String::new());
+                    
                     // Detect if this is an AI chat conversation (format: "0:user_id")
                     // For AI chat, the bot should respond to all messages, not just when mentioned
                     let is_ai_chat = conversation_id.starts_with("0:");
@@ -0,0 +1,192 @@
+//! # Mark-as-Read Handler
+//!
+//! Handler for marking a conversation as read.
+
+use super::utils::{extract_user_id_from_token, parse_conversation_id, is_ai_bot_conversation};
+use crate::chat::db as chat_db;
+use crate::chat::state::ChatAppState;
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Response,
+};
+use lib_core::dto::ReceiptStatus;
+use std::sync::Arc;
+
+/// Mark every message sent to the caller in a conversation as read
+///
+/// `POST /api/chat/conversations/{conversation_id}/read`
+///
+/// The other participant is sent a read receipt over their subscription,
+/// except in AI bot conversations.
+///
+/// # Errors
+///
+/// * `401` - Missing or invalid token
+/// * `403` - Caller isn't part of the conversation
+pub async fn handle_mark_read(
+    Path(conversation_id): Path<String>,
+    State(app_state): State<Arc<ChatAppState>>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, StatusCode> {
+    // Authenticate user
+    let user_id = extract_user_id_from_token(&headers, &app_state.config)?;
+
+    // Verify user is part of this conversation
+    let (user1_id, user2_id) = parse_conversation_id(&conversation_id)?;
+    if user_id != user1_id && user_id != user2_id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    chat_db::get_or_create_conversation_state(&app_state.db, &conversation_id, user1_id, user2_id).await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    chat_db::mark_conversation_read(&app_state.db, &conversation_id, user_id, user1_id, user2_id).await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Reading implies delivery; only the read receipt is worth a checkmark update
+    let notify = !is_ai_bot_conversation(&app_state.db, user1_id, user2_id).await;
+    app_state.record_receipt(&conversation_id, user_id, ReceiptStatus::Delivered, false).await;
+    app_state.record_receipt(&conversation_id, user_id, ReceiptStatus::Read, notify).await;
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .body(Body::empty())
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::Request, routing::post, Router};
+    use lib_auth::encode_jwt;
+    use lib_core::{Config, DbPool};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "test-secret-key-must-be-at-least-32-characters-long!";
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        for statement in [
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, username TEXT NOT NULL, email TEXT NOT NULL)",
+            "CREATE TABLE conversation_state (
+                conversation_id TEXT NOT NULL UNIQUE,
+                user1_id INTEGER NOT NULL,
+                user2_id INTEGER NOT NULL,
+                last_version TEXT,
+                last_message_at DATETIME,
+                user1_unread_count INTEGER NOT NULL DEFAULT 0,
+                user2_unread_count INTEGER NOT NULL DEFAULT 0,
+                user1_last_read_at DATETIME,
+                user2_last_read_at DATETIME,
+                user1_last_read_message_id INTEGER,
+                user2_last_read_message_id INTEGER,
+                user1_last_delivered_message_id INTEGER,
+                user2_last_delivered_message_id INTEGER,
+                updated_at DATETIME
+            )",
+            "CREATE TABLE direct_messages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                sender_id INTEGER NOT NULL,
+                receiver_id INTEGER NOT NULL,
+                conversation_id TEXT NOT NULL,
+                text TEXT NOT NULL,
+                version TEXT,
+                timestamp TEXT NOT NULL
+            )",
+            "INSERT INTO users (id, username, email) VALUES
+                (1, 'alice', 'a@example.com'), (2, 'bob', 'b@example.com'),
+                (3, 'carol', 'c@example.com'), (4, 'bot', 'system@ai.bot')",
+            "INSERT INTO conversation_state (conversation_id, user1_id, user2_id, user1_unread_count)
+                VALUES ('1:2', 1, 2, 2), ('1:4', 1, 4, 1)",
+            "INSERT INTO direct_messages (sender_id, receiver_id, conversation_id, text, version, timestamp) VALUES
+                (2, 1, '1:2', 'gm', 'v1', '2025-02-21T09:00:00+00:00'),
+                (2, 1, '1:2', 'SOL is moving', 'v2', '2025-02-21T09:01:00+00:00'),
+                (4, 1, '1:4', 'How can I help?', 'v3', '2025-02-21T09:02:00+00:00')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.expect("Failed to set up test schema");
+        }
+
+        pool
+    }
+
+    async fn read_app() -> (Router, Arc<ChatAppState>) {
+        let config = Config {
+            database_url: "sqlite::memory:".to_string(),
+            jwt_secret: SECRET.to_string(),
+            jwt_expiration_hours: 24,
+            streamed_symbols: Vec::new(),
+            admin_usernames: Vec::new(),
+        };
+        let chat_state = Arc::new(ChatAppState::new(setup_test_db().await, config));
+        let app = Router::new()
+            .route("/api/chat/conversations/{conversation_id}/read", post(handle_mark_read))
+            .with_state(chat_state.clone());
+        (app, chat_state)
+    }
+
+    async fn mark_read(app: &Router, user_id: i64, conversation_id: &str) -> StatusCode {
+        let token = encode_jwt(user_id, "user".to_string(), SECRET, 1).unwrap();
+        app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/chat/conversations/{}/read", conversation_id))
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_mark_read_clears_unread_and_sends_receipt() {
+        let (app, state) = read_app().await;
+        let mut receipts = state.subscribe_receipts("1:2").await;
+        assert_eq!(chat_db::unread_counts(&state.db, 1).await.unwrap().get("1:2"), Some(&2));
+
+        assert_eq!(mark_read(&app, 1, "1:2").await, StatusCode::OK);
+
+        assert!(!chat_db::unread_counts(&state.db, 1).await.unwrap().contains_key("1:2"));
+        assert_eq!(
+            chat_db::receipt_markers(&state.db, "1:2", 1).await.unwrap(),
+            (Some("v2".to_string()), Some("v2".to_string()))
+        );
+        let receipt = receipts.try_recv().unwrap();
+        assert_eq!((receipt.user_id, receipt.version.as_str(), receipt.status), (1, "v2", ReceiptStatus::Read));
+        // The delivered marker moved silently
+        assert!(receipts.try_recv().is_err());
+
+        // Nothing new to read, so nothing to send
+        assert_eq!(mark_read(&app, 1, "1:2").await, StatusCode::OK);
+        assert!(receipts.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bot_conversation_has_no_receipts() {
+        let (app, state) = read_app().await;
+        let mut receipts = state.subscribe_receipts("1:4").await;
+
+        assert_eq!(mark_read(&app, 1, "1:4").await, StatusCode::OK);
+
+        // Still counted as read, but the bot is never told
+        assert!(!chat_db::unread_counts(&state.db, 1).await.unwrap().contains_key("1:4"));
+        assert!(receipts.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_outsider_cannot_mark_read() {
+        let (app, state) = read_app().await;
+
+        assert_eq!(mark_read(&app, 3, "1:2").await, StatusCode::FORBIDDEN);
+        assert_eq!(chat_db::unread_counts(&state.db, 1).await.unwrap().get("1:2"), Some(&2));
+    }
+}
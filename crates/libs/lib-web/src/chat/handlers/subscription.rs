@@ -1,10 +1,23 @@
 //! # Chat Subscription Handler
 //!
 //! WebSocket subscription handler for Braid protocol.
+//!
+//! Besides `{"version", "messages"}` updates, the stream carries the other
+//! participant's delivered and read receipts as `{"receipt": {...}}` events,
+//! and the first snapshot lists their current markers under `"receipts"`.
+//! Messages count as delivered once a subscription has streamed them.
+//! AI bot conversations have no receipts; instead, their replies stream in as
+//! `{"ai_stream": {...}}` events while being generated.
+//!
+//! When the server shuts down, the last event is
+//! `{"closing": {"reason": "server restarting"}}` and the stream ends.
 
-use super::utils::{extract_user_id_from_token, parse_conversation_id};
+use super::utils::{extract_user_id_from_token, parse_conversation_id, is_ai_bot_conversation};
 use crate::chat::state::{ChatAppState, ChatState};
 use crate::chat::db as chat_db;
+use crate::shutdown::{Shutdown, SERVER_RESTARTING_REASON};
+use lib_core::dto::{AiStreamEvent, Message, MessageReceipt, ReceiptStatus};
+use tokio::sync::broadcast;
 use axum::{
     extract::{Path, State},
     http::{HeaderMap, StatusCode},
@@ -70,75 +83,59 @@ pub async fn handle_braid_subscription(
     let initial_messages = chat_state.get_messages_since(parents_header.as_ref());
     let initial_version = chat_state.current_version.clone();
     
-    // Mark conversation as read for this user
-    let _ = chat_db::mark_conversation_read(&app_state.db, &conversation_id, user_id, user1_id, user2_id).await;
+    // The snapshot delivers everything sent so far
+    let receipts_enabled = !is_ai_bot_conversation(&app_state.db, user1_id, user2_id).await;
+    app_state.record_receipt(&conversation_id, user_id, ReceiptStatus::Delivered, receipts_enabled).await;
     
-    // Subscribe to broadcast channel for real-time updates
+    // The other participant's markers, so checkmarks survive a reconnect
+    let mut initial_receipts = Vec::new();
+    if receipts_enabled {
+        let other_id = if user_id == user1_id { user2_id } else { user1_id };
+        let (delivered, read) = chat_db::receipt_markers(&app_state.db, &conversation_id, other_id).await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        for (version, status) in [(delivered, ReceiptStatus::Delivered), (read, ReceiptStatus::Read)] {
+            if let Some(version) = version {
+                initial_receipts.push(MessageReceipt { user_id: other_id, version, status });
+            }
+        }
+    }
+    
+    // Subscribe to broadcast channels for real-time updates
     let broadcast_rx = app_state.get_broadcast_sender(conversation_id.as_str()).await.subscribe();
+    let receipt_rx = app_state.subscribe_receipts(&conversation_id).await;
+    let ai_stream_rx = app_state.subscribe_ai_stream(&conversation_id).await;
     
     // Prepare initial snapshot data
     let initial_event_data_str = {
         let event_data = serde_json::json!({
             "version": initial_version,
-            "messages": initial_messages
+            "messages": initial_messages,
+            "receipts": initial_receipts,
         });
         
         serde_json::to_string(&event_data)
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
     };
     
-    let last_version_str = initial_version.as_ref().map(|s| s.clone()).unwrap_or_else(|| String::new());
+    let subscriber = Subscriber {
+        shutdown: app_state.shutdown.clone(),
+        closed: false,
+        messages: broadcast_rx,
+        receipts: receipt_rx,
+        ai_stream: ai_stream_rx,
+        last_version: initial_version.unwrap_or_default(),
+        initial: Some(initial_event_data_str),
+        app_state,
+        conversation_id,
+        user_id,
+        receipts_enabled,
+    };
     
-    // Create stream that sends initial snapshot, then listens to broadcast channel
-    let stream = stream::unfold(
-        (broadcast_rx, last_version_str, false, initial_event_data_str),
-        move |(mut rx, mut last_version, sent_initial, initial_data)| async move {
-            // Send initial snapshot first
-            if !sent_initial {
-                let event = Event::default().data(initial_data);
-                return Some((
-                    Ok(event),
-                    (rx, last_version, true, String::new()),
-                ));
-            }
-            
-            // After initial snapshot, listen to broadcast channel for new messages
-            loop {
-                match rx.recv().await {
-                    Ok((new_messages, new_version)) => {
-                        if new_version != last_version && !new_messages.is_empty() {
-                            let event_data = serde_json::json!({
-                                "version": new_version,
-                                "messages": new_messages
-                            });
-                            
-                            let event_data_str = match serde_json::to_string(&event_data) {
-                                Ok(s) => s,
-                                Err(_) => continue,
-                            };
-                            
-                            let event = Event::default().data(event_data_str);
-                            last_version = new_version.clone();
-                            
-                            return Some((
-                                Ok(event),
-                                (rx, last_version, true, String::new()),
-                            ));
-                        } else {
-                            last_version = new_version;
-                            continue;
-                        }
-                    }
-                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
-                        continue;
-                    }
-                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {
-                        return None;
-                    }
-                }
-            }
-        },
-    );
+    // Create stream that sends initial snapshot, then listens to the broadcast channels
+    let stream = stream::unfold(subscriber, |mut subscriber| async move {
+        let event = subscriber.next_event().await?;
+        Some((Ok(Event::default().data(event)), subscriber))
+    });
     
     // Create SSE response with keep-alive
     let sse = Sse::new(stream)
@@ -147,3 +144,86 @@ pub async fn handle_braid_subscription(
     Ok(sse)
 }
 
+/// One participant's subscription to a conversation
+struct Subscriber {
+    shutdown: Shutdown,
+    /// Set once the `closing` event went out
+    closed: bool,
+    messages: broadcast::Receiver<(Vec<Message>, String)>,
+    receipts: broadcast::Receiver<MessageReceipt>,
+    ai_stream: broadcast::Receiver<AiStreamEvent>,
+    last_version: String,
+    /// Snapshot sent before any update
+    initial: Option<String>,
+    app_state: Arc<ChatAppState>,
+    conversation_id: String,
+    user_id: i64,
+    receipts_enabled: bool,
+}
+
+impl Subscriber {
+    /// Data of the next SSE event; `None` ends the stream
+    async fn next_event(&mut self) -> Option<String> {
+        if self.closed {
+            return None;
+        }
+        if let Some(initial) = self.initial.take() {
+            return Some(initial);
+        }
+        
+        loop {
+            tokio::select! {
+                _ = self.shutdown.triggered() => {
+                    self.closed = true;
+                    return Some(serde_json::json!({ "closing": { "reason": SERVER_RESTARTING_REASON } }).to_string());
+                }
+                received = self.messages.recv() => match received {
+                    Ok((new_messages, new_version)) => {
+                        if new_version == self.last_version || new_messages.is_empty() {
+                            self.last_version = new_version;
+                            continue;
+                        }
+                        
+                        let event_data = serde_json::json!({
+                            "version": new_version,
+                            "messages": new_messages
+                        });
+                        let Ok(event_data_str) = serde_json::to_string(&event_data) else {
+                            continue;
+                        };
+                        self.last_version = new_version;
+                        
+                        if new_messages.iter().any(|m| m.author_id != self.user_id) {
+                            self.app_state
+                                .record_receipt(&self.conversation_id, self.user_id, ReceiptStatus::Delivered, self.receipts_enabled)
+                                .await;
+                        }
+                        return Some(event_data_str);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+                received = self.receipts.recv() => match received {
+                    // Only the other participant's receipts are of interest
+                    Ok(receipt) if receipt.user_id != self.user_id => {
+                        match serde_json::to_string(&serde_json::json!({ "receipt": receipt })) {
+                            Ok(event_data_str) => return Some(event_data_str),
+                            Err(_) => continue,
+                        }
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+                received = self.ai_stream.recv() => match received {
+                    Ok(event) => match serde_json::to_string(&serde_json::json!({ "ai_stream": event })) {
+                        Ok(event_data_str) => return Some(event_data_str),
+                        Err(_) => continue,
+                    },
+                    // Missed chunks are made up for by the final message
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! # Conversation Summary Handler
+//!
+//! Handler for AI summaries of a conversation's recent messages.
+
+use super::utils::{extract_user_id_from_token, parse_conversation_id};
+use crate::chat::db as chat_db;
+use crate::chat::state::ChatAppState;
+use crate::chat::summary::{self, DEFAULT_SUMMARY_MESSAGES, MAX_SUMMARY_MESSAGES};
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use lib_core::dto::{ErrorResponse, Message};
+use lib_core::dto::messaging::ConversationSummaryResponse;
+use serde::Deserialize;
+use std::sync::Arc;
+
+type SummaryError = (StatusCode, Json<ErrorResponse>);
+
+fn error(status: StatusCode, message: &str) -> SummaryError {
+    (status, Json(ErrorResponse { error: message.to_string() }))
+}
+
+/// Query parameters for a summary
+#[derive(Debug, Default, Deserialize)]
+pub struct SummaryQuery {
+    /// Number of most recent messages to summarize (default 100, max 500)
+    pub limit: Option<usize>,
+}
+
+/// Summarize the most recent messages of a conversation
+///
+/// `POST /api/chat/{conversation_id}/summary?limit=N`
+///
+/// # Errors
+///
+/// * `401` - Missing or invalid token
+/// * `403` - Caller isn't part of the conversation
+/// * `422` - Conversation is end-to-end encrypted, or has no messages
+/// * `503` - No AI provider is configured
+/// * `502` - The AI provider failed
+pub async fn handle_conversation_summary(
+    Path(conversation_id): Path<String>,
+    Query(query): Query<SummaryQuery>,
+    State(app_state): State<Arc<ChatAppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ConversationSummaryResponse>, SummaryError> {
+    // Authenticate user
+    let user_id = extract_user_id_from_token(&headers, &app_state.config)
+        .map_err(|status| error(status, "Authentication required"))?;
+
+    // Verify user is part of this conversation
+    let (user1_id, user2_id) = parse_conversation_id(&conversation_id)
+        .map_err(|status| error(status, "Invalid conversation ID"))?;
+    if user_id != user1_id && user_id != user2_id {
+        return Err(error(StatusCode::FORBIDDEN, "You are not part of this conversation"));
+    }
+
+    // Encrypted conversations must never be sent to a third party
+    let encrypted = chat_db::is_conversation_encrypted(&app_state.db, &conversation_id).await
+        .map_err(|e| {
+            tracing::error!("Failed to check conversation encryption: {:?}", e);
+            error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load conversation")
+        })?;
+    if encrypted {
+        return Err(error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Summaries are not available for end-to-end encrypted conversations",
+        ));
+    }
+
+    let Some(summarizer) = app_state.summarizer.clone() else {
+        return Err(error(StatusCode::SERVICE_UNAVAILABLE, "AI summaries are not configured on this server"));
+    };
+
+    let limit = query.limit.unwrap_or(DEFAULT_SUMMARY_MESSAGES).clamp(1, MAX_SUMMARY_MESSAGES);
+    let mut messages = recent_messages(&app_state, &conversation_id).await?;
+    if messages.is_empty() {
+        return Err(error(StatusCode::UNPROCESSABLE_ENTITY, "This conversation has no messages to summarize"));
+    }
+    let skip = messages.len().saturating_sub(limit);
+    messages.drain(..skip);
+
+    let (parsed, prompt) = summary::summarize(summarizer.as_ref(), &messages, summary::token_budget_from_env())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to summarize conversation {}: {:?}", conversation_id, e);
+            error(StatusCode::BAD_GATEWAY, "The AI provider could not summarize this conversation")
+        })?;
+
+    tracing::info!(
+        "Summarized {} messages of conversation {} ({} omitted for length)",
+        prompt.included, conversation_id, prompt.omitted
+    );
+
+    Ok(Json(ConversationSummaryResponse {
+        conversation_id,
+        summary: parsed.summary,
+        action_items: parsed.action_items,
+        tokens: parsed.tokens,
+        messages_summarized: prompt.included,
+        messages_omitted: prompt.omitted + skip,
+    }))
+}
+
+/// Messages of a conversation, oldest first, from memory if it is loaded
+async fn recent_messages(app_state: &ChatAppState, conversation_id: &str) -> Result<Vec<Message>, SummaryError> {
+    if let Some(state) = app_state.chat_states.read().await.get(conversation_id) {
+        return Ok(state.messages.clone());
+    }
+
+    chat_db::load_messages_with_usernames(&app_state.db, conversation_id).await
+        .map_err(|e| {
+            tracing::error!("Failed to load messages for summary: {:?}", e);
+            error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load conversation")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::summary::SummaryProvider;
+    use async_trait::async_trait;
+    use axum::{body::Body, http::Request, routing::post, Router};
+    use lib_auth::encode_jwt;
+    use lib_core::{Config, DbPool};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Mutex;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "test-secret-key-must-be-at-least-32-characters-long!";
+
+    /// Returns a canned summary and keeps the prompts it was sent
+    #[derive(Default)]
+    struct MockProvider {
+        prompts: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl SummaryProvider for MockProvider {
+        async fn complete(&self, _system: &str, prompt: &str) -> anyhow::Result<String> {
+            self.prompts.lock().unwrap().push(prompt.to_string());
+            Ok(r#"{"summary": ["alice wants SOL"], "action_items": ["bob checks JUP"], "tokens": ["SOL", "JUP"]}"#.to_string())
+        }
+    }
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        for statement in [
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, username TEXT NOT NULL)",
+            "CREATE TABLE conversation_state (
+                conversation_id TEXT NOT NULL UNIQUE,
+                is_encrypted BOOLEAN NOT NULL DEFAULT 0
+            )",
+            "CREATE TABLE direct_messages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                sender_id INTEGER NOT NULL,
+                conversation_id TEXT NOT NULL,
+                text TEXT NOT NULL,
+                version TEXT,
+                timestamp TEXT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )",
+            "INSERT INTO users (id, username) VALUES (1, 'alice'), (2, 'bob'), (3, 'carol')",
+            "INSERT INTO conversation_state (conversation_id, is_encrypted) VALUES ('1:2', 0), ('1:3', 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.expect("Failed to set up test schema");
+        }
+
+        for (conversation_id, sender_id, text) in [
+            ("1:2", 1, "thinking of adding SOL"),
+            ("1:2", 2, "I'll look at JUP first"),
+            ("1:3", 3, "secret plans"),
+        ] {
+            sqlx::query("INSERT INTO direct_messages (sender_id, conversation_id, text, timestamp) VALUES (?, ?, ?, '2025-02-17T14:05:00+00:00')")
+                .bind(sender_id)
+                .bind(conversation_id)
+                .bind(text)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        pool
+    }
+
+    async fn summary_app() -> (Router, Arc<MockProvider>) {
+        let config = Config {
+            database_url: "sqlite::memory:".to_string(),
+            jwt_secret: SECRET.to_string(),
+            jwt_expiration_hours: 24,
+            streamed_symbols: Vec::new(),
+            admin_usernames: Vec::new(),
+        };
+        let provider = Arc::new(MockProvider::default());
+        let chat_state = ChatAppState::new(setup_test_db().await, config).with_summarizer(provider.clone());
+        let app = Router::new()
+            .route("/api/chat/{conversation_id}/summary", post(handle_conversation_summary))
+            .with_state(Arc::new(chat_state));
+        (app, provider)
+    }
+
+    async fn request_summary(app: &Router, user_id: i64, uri: &str) -> (StatusCode, String) {
+        let token = encode_jwt(user_id, "user".to_string(), SECRET, 1).unwrap();
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_summary_of_conversation() {
+        let (app, provider) = summary_app().await;
+
+        let (status, body) = request_summary(&app, 1, "/api/chat/1:2/summary").await;
+
+        assert_eq!(status, StatusCode::OK);
+        let summary: ConversationSummaryResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(summary.summary, vec!["alice wants SOL"]);
+        assert_eq!(summary.action_items, vec!["bob checks JUP"]);
+        assert_eq!(summary.tokens, vec!["SOL", "JUP"]);
+        assert_eq!(summary.messages_summarized, 2);
+        assert_eq!(summary.messages_omitted, 0);
+
+        let prompts = provider.prompts.lock().unwrap();
+        assert!(prompts[0].contains("alice: thinking of adding SOL"));
+        assert!(prompts[0].contains("bob: I'll look at JUP first"));
+    }
+
+    #[tokio::test]
+    async fn test_summary_limit_keeps_latest_messages() {
+        let (app, provider) = summary_app().await;
+
+        let (status, body) = request_summary(&app, 2, "/api/chat/1:2/summary?limit=1").await;
+
+        assert_eq!(status, StatusCode::OK);
+        let summary: ConversationSummaryResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(summary.messages_summarized, 1);
+        assert_eq!(summary.messages_omitted, 1);
+        let prompts = provider.prompts.lock().unwrap();
+        assert!(!prompts[0].contains("thinking of adding SOL"));
+        assert!(prompts[0].contains("I'll look at JUP first"));
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_conversation_is_refused() {
+        let (app, provider) = summary_app().await;
+
+        let (status, body) = request_summary(&app, 1, "/api/chat/1:3/summary").await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(body.contains("end-to-end encrypted"));
+        // Nothing was sent to the AI provider
+        assert!(provider.prompts.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_outsider_cannot_summarize() {
+        let (app, provider) = summary_app().await;
+
+        let (status, _) = request_summary(&app, 3, "/api/chat/1:2/summary").await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(provider.prompts.lock().unwrap().is_empty());
+    }
+}
@@ -84,3 +84,26 @@ pub async fn get_username(pool: &DbPool, user_id: i64) -> Result<String, sqlx::E
     Ok(username)
 }
 
+/// Whether a conversation is with the AI bot
+///
+/// User ID 0 is reserved for the bot; a `system@ai.bot` user in the
+/// database counts too.
+pub async fn is_ai_bot_conversation(pool: &DbPool, user1_id: i64, user2_id: i64) -> bool {
+    if user1_id == 0 || user2_id == 0 {
+        return true;
+    }
+    
+    let bot_user_id = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT id
+        FROM users
+        WHERE email = 'system@ai.bot'
+        LIMIT 1
+        "#
+    )
+    .fetch_optional(pool)
+    .await
+    .unwrap_or(None);
+    
+    bot_user_id.is_some_and(|bot_user_id| user1_id == bot_user_id || user2_id == bot_user_id)
+}
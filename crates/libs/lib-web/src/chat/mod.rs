@@ -8,11 +8,14 @@
 pub mod state;
 pub mod handlers;
 pub mod db;
+pub mod summary;
+pub mod stream;
+pub mod tools;
 #[cfg(feature = "genai")]
 pub mod ai_bot;
 
 pub use state::{ChatState, ChatAppState};
-pub use handlers::{handle_braid_subscription, handle_braid_put, handle_typing_event};
+pub use handlers::{handle_braid_subscription, handle_braid_put, handle_typing_event, handle_conversation_summary, handle_mark_read, handle_upload_attachment, handle_download_attachment, handle_delete_conversation, handle_cancel_ai_reply};
 #[cfg(feature = "genai")]
 pub use ai_bot::{start_ai_bot_for_conversation, BotConfig, AiProvider};
 
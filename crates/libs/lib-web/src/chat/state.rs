@@ -3,10 +3,15 @@
 //! Manages server-side chat state for direct message conversations.
 //! Implements Braid protocol version tracking using a DAG structure.
 
-use lib_core::{Config, DbPool, dto::Message};
+use super::db as chat_db;
+use super::summary::{self, SummaryProvider};
+use super::tools::ToolRegistry;
+use crate::shutdown::Shutdown;
+use lib_core::{Config, DbPool, dto::{AiStreamEvent, Message, MessageReceipt, ReceiptStatus}};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::{RwLock, broadcast};
+use tokio::sync::{RwLock, broadcast, watch};
 use uuid::Uuid;
 
 /// Chat state for a single conversation
@@ -100,8 +105,25 @@ pub struct ChatAppState {
     pub chat_states: Arc<RwLock<HashMap<String, ChatState>>>,
     pub broadcast_senders: Arc<RwLock<HashMap<String, broadcast::Sender<(Vec<Message>, String)>>>>,
     pub typing_broadcast_senders: Arc<RwLock<HashMap<String, broadcast::Sender<(i64, String, bool)>>>>,
+    /// Delivered and read receipts by conversation ID
+    pub receipt_broadcast_senders: Arc<RwLock<HashMap<String, broadcast::Sender<MessageReceipt>>>>,
+    /// AI provider for conversation summaries (`None` if no provider is configured)
+    pub summarizer: Option<Arc<dyn SummaryProvider>>,
+    /// Directory holding uploaded attachment files, named by attachment ID
+    pub attachments_dir: PathBuf,
+    /// Progress of AI bot replies by conversation ID
+    pub ai_stream_broadcast_senders: Arc<RwLock<HashMap<String, broadcast::Sender<AiStreamEvent>>>>,
+    /// Cancellation switches of the AI replies being generated, by conversation ID
+    pub ai_stream_cancels: Arc<RwLock<HashMap<String, watch::Sender<bool>>>>,
+    /// Tools the AI bot may call while replying (empty unless configured)
+    pub ai_tools: ToolRegistry,
+    /// Ends the subscriptions when the server shuts down
+    pub shutdown: Shutdown,
 }
 
+/// Attachment directory used when `CHAT_ATTACHMENTS_DIR` isn't set
+const DEFAULT_ATTACHMENTS_DIR: &str = "./data/chat-attachments";
+
 impl ChatAppState {
     pub fn new(db: DbPool, config: Config) -> Self {
         Self {
@@ -110,9 +132,50 @@ impl ChatAppState {
             chat_states: Arc::new(RwLock::new(HashMap::new())),
             broadcast_senders: Arc::new(RwLock::new(HashMap::new())),
             typing_broadcast_senders: Arc::new(RwLock::new(HashMap::new())),
+            receipt_broadcast_senders: Arc::new(RwLock::new(HashMap::new())),
+            summarizer: summary::provider_from_env(),
+            attachments_dir: std::env::var("CHAT_ATTACHMENTS_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from(DEFAULT_ATTACHMENTS_DIR)),
+            ai_stream_broadcast_senders: Arc::new(RwLock::new(HashMap::new())),
+            ai_stream_cancels: Arc::new(RwLock::new(HashMap::new())),
+            ai_tools: ToolRegistry::default(),
+            shutdown: Shutdown::new(),
         }
     }
     
+    /// Use `summarizer` for conversation summaries instead of the provider
+    /// configured in the environment
+    pub fn with_summarizer(mut self, summarizer: Arc<dyn SummaryProvider>) -> Self {
+        self.summarizer = Some(summarizer);
+        self
+    }
+    
+    /// Store attachment files in `dir` instead of the configured directory
+    pub fn with_attachments_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.attachments_dir = dir.into();
+        self
+    }
+    
+    /// Offer `tools` to the AI bot
+    pub fn with_ai_tools(mut self, tools: ToolRegistry) -> Self {
+        self.ai_tools = tools;
+        self
+    }
+    
+    /// Close subscriptions when `shutdown` is triggered
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+    
+    /// Path of an attachment's file
+    ///
+    /// Attachment IDs are server-generated UUIDs, so they're safe as file names.
+    pub fn attachment_path(&self, attachment_id: &str) -> PathBuf {
+        self.attachments_dir.join(attachment_id)
+    }
+    
     pub async fn get_broadcast_sender(&self, conversation_id: &str) -> broadcast::Sender<(Vec<Message>, String)> {
         let mut senders = self.broadcast_senders.write().await;
         
@@ -146,6 +209,82 @@ impl ChatAppState {
         let sender = self.get_typing_broadcast_sender(conversation_id).await;
         let _ = sender.send((user_id, username, is_typing));
     }
+    
+    /// Receive the delivered and read receipts of a conversation
+    pub async fn subscribe_receipts(&self, conversation_id: &str) -> broadcast::Receiver<MessageReceipt> {
+        let mut senders = self.receipt_broadcast_senders.write().await;
+        senders
+            .entry(conversation_id.to_string())
+            .or_insert_with(|| broadcast::channel(100).0)
+            .subscribe()
+    }
+    
+    /// Move `user_id`'s delivered or read marker to the newest message sent to
+    /// them, and tell the other participant when `notify` is set
+    ///
+    /// Callers pass `notify: false` for AI bot conversations, which have no
+    /// receipts.
+    pub async fn record_receipt(&self, conversation_id: &str, user_id: i64, status: ReceiptStatus, notify: bool) {
+        let version = match chat_db::advance_receipt(&self.db, conversation_id, user_id, status).await {
+            Ok(Some(version)) => version,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!("Failed to record {:?} receipt: {:?}", status, e);
+                return;
+            }
+        };
+        if notify {
+            if let Some(sender) = self.receipt_broadcast_senders.read().await.get(conversation_id) {
+                let _ = sender.send(MessageReceipt { user_id, version, status });
+            }
+        }
+    }
+    
+    /// Receive the progress of AI bot replies in a conversation
+    pub async fn subscribe_ai_stream(&self, conversation_id: &str) -> broadcast::Receiver<AiStreamEvent> {
+        let mut senders = self.ai_stream_broadcast_senders.write().await;
+        senders
+            .entry(conversation_id.to_string())
+            .or_insert_with(|| broadcast::channel(256).0)
+            .subscribe()
+    }
+    
+    pub async fn broadcast_ai_stream(&self, conversation_id: &str, event: AiStreamEvent) {
+        if let Some(sender) = self.ai_stream_broadcast_senders.read().await.get(conversation_id) {
+            let _ = sender.send(event);
+        }
+    }
+    
+    /// Register an AI reply being generated in a conversation
+    ///
+    /// The returned receiver flips to `true` when the reply is cancelled. A
+    /// reply still running in the conversation is cancelled, since only the
+    /// newest one can be stopped from now on.
+    pub async fn begin_ai_stream(&self, conversation_id: &str) -> watch::Receiver<bool> {
+        let (tx, rx) = watch::channel(false);
+        if let Some(previous) = self.ai_stream_cancels.write().await.insert(conversation_id.to_string(), tx) {
+            let _ = previous.send(true);
+        }
+        rx
+    }
+    
+    /// Cancel the AI reply being generated in a conversation
+    ///
+    /// Returns whether there was one.
+    pub async fn cancel_ai_stream(&self, conversation_id: &str) -> bool {
+        match self.ai_stream_cancels.write().await.remove(conversation_id) {
+            Some(cancel) => cancel.send(true).is_ok(),
+            None => false,
+        }
+    }
+    
+    /// Forget a finished AI reply, unless a newer one took its place
+    pub async fn end_ai_stream(&self, conversation_id: &str, cancelled: &watch::Receiver<bool>) {
+        let mut cancels = self.ai_stream_cancels.write().await;
+        if cancels.get(conversation_id).is_some_and(|cancel| cancel.subscribe().same_channel(cancelled)) {
+            cancels.remove(conversation_id);
+        }
+    }
 }
 
 impl axum::extract::FromRef<ChatAppState> for DbPool {
@@ -0,0 +1,151 @@
+//! # AI Reply Streaming
+//!
+//! Assembles an AI bot reply from the chunks the provider streams back.
+//!
+//! Each chunk is pushed to the conversation's subscribers as an
+//! [`AiStreamEvent::Chunk`] as soon as it arrives; once the stream ends, the
+//! assembled text is posted as a regular message. Replies that are cancelled
+//! or fail partway are still posted, marked by [`AiStreamOutcome::finalize`].
+
+use lib_core::dto::{AiStreamEvent, AiStreamOutcome};
+
+/// Longest reply the bot posts, in bytes
+pub const MAX_REPLY_LENGTH: usize = 1000;
+
+/// Filler phrases models like to open with
+const REPLY_ARTIFACTS: &[&str] = &[
+    "As an AI assistant, ",
+    "As an AI, ",
+    "I'm an AI assistant, ",
+    "I'm an AI, ",
+    "As a language model, ",
+];
+
+/// Reply being assembled from streamed chunks
+#[derive(Debug, Clone)]
+pub struct ReplyStream {
+    text: String,
+    limit: usize,
+    truncated: bool,
+}
+
+impl ReplyStream {
+    pub fn new(limit: usize) -> Self {
+        Self { text: String::new(), limit, truncated: false }
+    }
+
+    /// Add the next chunk, returning the event to push to subscribers
+    ///
+    /// Chunks past the length limit are cut at a character boundary; once the
+    /// limit is reached nothing more is accepted and the caller should stop
+    /// reading the provider's stream.
+    pub fn push(&mut self, delta: &str) -> Option<AiStreamEvent> {
+        if self.truncated || delta.is_empty() {
+            return None;
+        }
+        let room = self.limit.saturating_sub(self.text.len());
+        let accepted = if delta.len() > room {
+            self.truncated = true;
+            let mut end = room;
+            while !delta.is_char_boundary(end) {
+                end -= 1;
+            }
+            &delta[..end]
+        } else {
+            delta
+        };
+        if accepted.is_empty() {
+            return None;
+        }
+        self.text.push_str(accepted);
+        Some(AiStreamEvent::Chunk { delta: accepted.to_string() })
+    }
+
+    /// Whether the length limit cut the reply short
+    pub fn is_full(&self) -> bool {
+        self.truncated
+    }
+
+    /// Text received so far
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Text to post once the stream ended with `outcome` (`None` if nothing
+    /// was generated)
+    pub fn finish(&self, outcome: &AiStreamOutcome) -> Option<String> {
+        match outcome {
+            AiStreamOutcome::Completed => {
+                let mut text = clean_reply(&self.text);
+                if self.truncated {
+                    text.push_str("...");
+                }
+                outcome.finalize(&text)
+            }
+            _ => outcome.finalize(&self.text),
+        }
+    }
+}
+
+/// Reply text without the usual filler phrases and surrounding whitespace
+pub fn clean_reply(text: &str) -> String {
+    REPLY_ARTIFACTS
+        .iter()
+        .fold(text.to_string(), |text, artifact| text.replace(artifact, ""))
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(event: Option<AiStreamEvent>) -> Option<String> {
+        match event {
+            Some(AiStreamEvent::Chunk { delta }) => Some(delta),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_chunks_assemble_into_reply() {
+        let mut stream = ReplyStream::new(MAX_REPLY_LENGTH);
+        for chunk in ["As an AI, ", "SOL is ", "", "up 4% today. "] {
+            stream.push(chunk);
+        }
+
+        assert_eq!(stream.text(), "As an AI, SOL is up 4% today. ");
+        assert_eq!(stream.finish(&AiStreamOutcome::Completed).as_deref(), Some("SOL is up 4% today."));
+    }
+
+    #[test]
+    fn test_length_limit_cuts_at_char_boundary() {
+        let mut stream = ReplyStream::new(9);
+        assert_eq!(delta(stream.push("Hello ")).as_deref(), Some("Hello "));
+        // "€" is three bytes and would straddle the limit
+        assert_eq!(delta(stream.push("wé€ld")).as_deref(), Some("wé"));
+        assert!(stream.is_full());
+        assert_eq!(stream.push("more"), None);
+
+        assert_eq!(stream.finish(&AiStreamOutcome::Completed).as_deref(), Some("Hello wé..."));
+    }
+
+    #[test]
+    fn test_interrupted_replies_keep_partial_text() {
+        let mut stream = ReplyStream::new(MAX_REPLY_LENGTH);
+        stream.push("Jupiter routes through ");
+
+        assert_eq!(
+            stream.finish(&AiStreamOutcome::Cancelled).as_deref(),
+            Some("Jupiter routes through [cancelled]")
+        );
+        assert_eq!(
+            stream.finish(&AiStreamOutcome::Failed { error: "timeout".to_string() }).as_deref(),
+            Some("Jupiter routes through [response interrupted: timeout]")
+        );
+
+        // Nothing generated, nothing to post
+        let empty = ReplyStream::new(MAX_REPLY_LENGTH);
+        assert_eq!(empty.finish(&AiStreamOutcome::Cancelled), None);
+    }
+}
@@ -0,0 +1,348 @@
+//! # Conversation Summaries
+//!
+//! Condenses the recent history of a conversation into bullet points, action
+//! items and the tokens people mentioned, using the same AI provider as the
+//! chat bot.
+//!
+//! Models only see a limited amount of text, so [`build_prompt`] keeps the
+//! newest messages that fit in the token budget and reports how many older
+//! ones were left out. The model is asked for JSON; [`parse_summary`] falls
+//! back to reading bullet lines when it answers in prose instead.
+//!
+//! ## Environment
+//!
+//! - `AI_SUMMARY_TOKEN_BUDGET` - Approximate tokens of transcript sent to the
+//!   model (default [`DEFAULT_TOKEN_BUDGET`])
+
+use async_trait::async_trait;
+use lib_core::dto::Message;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Messages summarized when the client doesn't ask for a number
+pub const DEFAULT_SUMMARY_MESSAGES: usize = 100;
+
+/// Most messages a single summary can cover
+pub const MAX_SUMMARY_MESSAGES: usize = 500;
+
+/// Transcript budget used when `AI_SUMMARY_TOKEN_BUDGET` is unset
+pub const DEFAULT_TOKEN_BUDGET: usize = 3000;
+
+/// Instructions sent as the system prompt of every summary request
+pub const SUMMARY_SYSTEM_PROMPT: &str = "You summarize chat conversations between traders \
+    in a Solana trading terminal. Reply with a single JSON object and nothing else:\n\
+    {\"summary\": [\"...\"], \"action_items\": [\"...\"], \"tokens\": [\"SOL\"]}\n\
+    - summary: 2-6 short bullet points covering what was discussed and decided\n\
+    - action_items: things someone said they would do or asked another person to do, \
+    naming who (empty if none)\n\
+    - tokens: ticker symbols of the tokens mentioned, uppercase, without a leading $ \
+    (empty if none)\n\
+    Only use information from the transcript.";
+
+/// Completes a prompt with a language model
+#[async_trait]
+pub trait SummaryProvider: Send + Sync {
+    async fn complete(&self, system: &str, prompt: &str) -> anyhow::Result<String>;
+}
+
+/// Rough token count of `text` (about four characters per token)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Transcript prepared for the model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryPrompt {
+    /// Prompt text including the transcript
+    pub text: String,
+    /// Messages in the transcript
+    pub included: usize,
+    /// Older messages left out to stay within the budget
+    pub omitted: usize,
+}
+
+/// One transcript line for `message`
+fn transcript_line(message: &Message) -> String {
+    let time = chrono::DateTime::parse_from_rfc3339(&message.timestamp)
+        .map(|t| t.format("%H:%M").to_string())
+        .unwrap_or_default();
+    let text = message.text.replace('\n', " ");
+    if time.is_empty() {
+        format!("{}: {}", message.author, text)
+    } else {
+        format!("[{}] {}: {}", time, message.author, text)
+    }
+}
+
+/// Build the prompt for `messages` (oldest first) within `token_budget`.
+///
+/// Messages are taken newest first until the next one would go over the
+/// budget. If even the newest message is too long on its own, it is cut
+/// short so there is always something to summarize.
+pub fn build_prompt(messages: &[Message], token_budget: usize) -> SummaryPrompt {
+    let mut lines = Vec::new();
+    let mut used = 0;
+
+    for message in messages.iter().rev() {
+        let line = transcript_line(message);
+        let cost = estimate_tokens(&line) + 1;
+        if used + cost > token_budget {
+            if lines.is_empty() {
+                let keep = token_budget.saturating_sub(1) * 4;
+                let mut cut: String = line.chars().take(keep.saturating_sub(3)).collect();
+                cut.push_str("...");
+                lines.push(cut);
+            }
+            break;
+        }
+        used += cost;
+        lines.push(line);
+    }
+    lines.reverse();
+
+    let included = lines.len();
+    let omitted = messages.len() - included;
+    let mut text = String::from("Conversation transcript, oldest message first");
+    if omitted > 0 {
+        text.push_str(&format!(" ({} earlier messages not shown)", omitted));
+    }
+    text.push_str(":\n\n");
+    text.push_str(&lines.join("\n"));
+
+    SummaryPrompt { text, included, omitted }
+}
+
+/// Summary extracted from a model reply
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ParsedSummary {
+    #[serde(default)]
+    pub summary: Vec<String>,
+    #[serde(default)]
+    pub action_items: Vec<String>,
+    #[serde(default)]
+    pub tokens: Vec<String>,
+}
+
+/// Read a model reply into a [`ParsedSummary`].
+///
+/// Accepts the requested JSON object, optionally wrapped in a code fence or
+/// surrounded by prose. Anything else is read as a bulleted list.
+pub fn parse_summary(reply: &str) -> ParsedSummary {
+    let json = match (reply.find('{'), reply.rfind('}')) {
+        (Some(start), Some(end)) if start < end => serde_json::from_str::<ParsedSummary>(&reply[start..=end]).ok(),
+        _ => None,
+    };
+
+    let mut parsed = json.unwrap_or_else(|| ParsedSummary {
+        summary: reply
+            .lines()
+            .map(str::trim)
+            .filter_map(|line| {
+                line.strip_prefix("- ")
+                    .or_else(|| line.strip_prefix("* "))
+                    .or_else(|| line.strip_prefix("• "))
+            })
+            .map(str::to_string)
+            .collect(),
+        ..ParsedSummary::default()
+    });
+
+    if parsed.summary.is_empty() && !reply.trim().is_empty() && !reply.contains('{') {
+        parsed.summary.push(reply.trim().to_string());
+    }
+
+    parsed.summary.retain(|s| !s.trim().is_empty());
+    parsed.action_items.retain(|s| !s.trim().is_empty());
+    parsed.tokens = normalize_tokens(&parsed.tokens);
+    parsed
+}
+
+/// Uppercase, strip `$`, drop anything that isn't a plausible symbol, dedupe
+fn normalize_tokens(tokens: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for token in tokens {
+        let symbol = token.trim().trim_start_matches('$').to_uppercase();
+        let plausible = !symbol.is_empty()
+            && symbol.len() <= 12
+            && symbol.chars().all(|c| c.is_ascii_alphanumeric());
+        if plausible && !normalized.contains(&symbol) {
+            normalized.push(symbol);
+        }
+    }
+    normalized
+}
+
+/// Summarize `messages` (oldest first) with `provider`.
+///
+/// # Returns
+///
+/// The parsed summary and the prompt it was generated from.
+pub async fn summarize(
+    provider: &dyn SummaryProvider,
+    messages: &[Message],
+    token_budget: usize,
+) -> anyhow::Result<(ParsedSummary, SummaryPrompt)> {
+    let prompt = build_prompt(messages, token_budget);
+    let reply = provider.complete(SUMMARY_SYSTEM_PROMPT, &prompt.text).await?;
+    let summary = parse_summary(&reply);
+    if summary.summary.is_empty() {
+        anyhow::bail!("AI provider returned an empty summary");
+    }
+    Ok((summary, prompt))
+}
+
+/// Transcript budget from `AI_SUMMARY_TOKEN_BUDGET`
+pub fn token_budget_from_env() -> usize {
+    std::env::var("AI_SUMMARY_TOKEN_BUDGET")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|budget| *budget > 0)
+        .unwrap_or(DEFAULT_TOKEN_BUDGET)
+}
+
+/// The configured AI provider, if any.
+///
+/// `None` when the `genai` feature is disabled or no provider API key is set.
+pub fn provider_from_env() -> Option<Arc<dyn SummaryProvider>> {
+    #[cfg(feature = "genai")]
+    {
+        use super::ai_bot::{AiProvider, BotConfig};
+
+        let provider = AiProvider::from_env()?;
+        let config = BotConfig {
+            api_key: std::env::var(provider.api_key_env()).ok()?,
+            model: provider.default_model().to_string(),
+            provider,
+            ..BotConfig::default()
+        };
+        Some(Arc::new(config))
+    }
+    #[cfg(not(feature = "genai"))]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records prompts and replies with a canned answer
+    struct MockProvider {
+        reply: String,
+        prompts: Mutex<Vec<(String, String)>>,
+    }
+
+    impl MockProvider {
+        fn new(reply: &str) -> Self {
+            Self { reply: reply.to_string(), prompts: Mutex::new(Vec::new()) }
+        }
+    }
+
+    #[async_trait]
+    impl SummaryProvider for MockProvider {
+        async fn complete(&self, system: &str, prompt: &str) -> anyhow::Result<String> {
+            self.prompts.lock().unwrap().push((system.to_string(), prompt.to_string()));
+            Ok(self.reply.clone())
+        }
+    }
+
+    fn message(author: &str, text: &str) -> Message {
+        Message {
+            text: text.to_string(),
+            author: author.to_string(),
+            author_id: 1,
+            timestamp: "2025-02-17T14:05:00+00:00".to_string(),
+            version: None,
+            attachments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_prompt_lists_messages_oldest_first() {
+        let messages = vec![message("alice", "SOL looks strong"), message("bob", "I'll buy\nsome JUP")];
+
+        let prompt = build_prompt(&messages, DEFAULT_TOKEN_BUDGET);
+
+        assert_eq!(prompt.included, 2);
+        assert_eq!(prompt.omitted, 0);
+        let alice = prompt.text.find("[14:05] alice: SOL looks strong").unwrap();
+        let bob = prompt.text.find("[14:05] bob: I'll buy some JUP").unwrap();
+        assert!(alice < bob);
+        assert!(!prompt.text.contains("not shown"));
+    }
+
+    #[test]
+    fn test_prompt_drops_oldest_messages_over_budget() {
+        let messages: Vec<Message> = (0..50)
+            .map(|i| message("alice", &format!("message number {} {}", i, "x".repeat(40))))
+            .collect();
+
+        let prompt = build_prompt(&messages, 100);
+
+        assert!(prompt.included > 0 && prompt.included < 50);
+        assert_eq!(prompt.included + prompt.omitted, 50);
+        assert!(prompt.text.contains("message number 49"));
+        assert!(!prompt.text.contains("message number 0 "));
+        assert!(prompt.text.contains(&format!("({} earlier messages not shown)", prompt.omitted)));
+        let transcript = prompt.text.split_once(":\n\n").unwrap().1;
+        assert!(estimate_tokens(transcript) <= 100);
+    }
+
+    #[test]
+    fn test_prompt_cuts_single_oversized_message() {
+        let messages = vec![message("alice", &"y".repeat(10_000))];
+
+        let prompt = build_prompt(&messages, 50);
+
+        assert_eq!(prompt.included, 1);
+        assert!(prompt.text.ends_with("..."));
+        let transcript = prompt.text.split_once(":\n\n").unwrap().1;
+        assert!(estimate_tokens(transcript) <= 50);
+    }
+
+    #[test]
+    fn test_parse_json_reply_in_code_fence() {
+        let reply = "```json\n{\"summary\": [\"Talked about SOL\", \" \"], \
+            \"action_items\": [\"bob buys JUP\"], \"tokens\": [\"$sol\", \"JUP\", \"SOL\", \"not a token\"]}\n```";
+
+        let parsed = parse_summary(reply);
+
+        assert_eq!(parsed.summary, vec!["Talked about SOL"]);
+        assert_eq!(parsed.action_items, vec!["bob buys JUP"]);
+        assert_eq!(parsed.tokens, vec!["SOL", "JUP"]);
+    }
+
+    #[test]
+    fn test_parse_bullet_reply() {
+        let parsed = parse_summary("Here you go:\n- First point\n* Second point\n");
+
+        assert_eq!(parsed.summary, vec!["First point", "Second point"]);
+        assert!(parsed.action_items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_summarize_sends_system_prompt_and_transcript() {
+        let provider = MockProvider::new("{\"summary\": [\"Bullish on SOL\"], \"tokens\": [\"SOL\"]}");
+        let messages = vec![message("alice", "SOL to 300")];
+
+        let (summary, prompt) = summarize(&provider, &messages, DEFAULT_TOKEN_BUDGET).await.unwrap();
+
+        assert_eq!(summary.summary, vec!["Bullish on SOL"]);
+        assert_eq!(summary.tokens, vec!["SOL"]);
+        let prompts = provider.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].0, SUMMARY_SYSTEM_PROMPT);
+        assert_eq!(prompts[0].1, prompt.text);
+    }
+
+    #[tokio::test]
+    async fn test_summarize_rejects_empty_reply() {
+        let provider = MockProvider::new("{}");
+
+        let result = summarize(&provider, &[message("alice", "gm")], DEFAULT_TOKEN_BUDGET).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,638 @@
+//! # AI Bot Tools
+//!
+//! Functions the AI bot can call while answering, so questions like "what's
+//! SOL's price right now" get live answers instead of guesses:
+//!
+//! - `get_price(symbol)` - current USD price
+//! - `get_candles(symbol, timeframe, n)` - recent OHLC candles
+//! - `get_wallet_balance()` - SOL balance of the user talking to the bot
+//! - `get_swap_quote(input, output, amount)` - Jupiter quote for a swap
+//!
+//! Tools implement [`AiTool`] and are collected in a [`ToolRegistry`], which
+//! logs every call and refuses tools that expose a wallet unless the caller
+//! owns the conversation. [`run_turns`] drives the exchange with a
+//! [`ToolModel`]: the model asks for tools, the results are appended to the
+//! transcript, and the model is asked again until it answers in text.
+
+use crate::services::{MarketService, SwapService, WalletService};
+use async_trait::async_trait;
+use lib_core::model::store::UserRepository;
+use lib_core::DbPool;
+use lib_solana::candle_aggregator::Timeframe;
+use lib_solana::{PriceStreamServer, SolanaState};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Rounds of tool calls per reply; the model must answer after the last one
+pub const MAX_TOOL_ROUNDS: usize = 3;
+
+/// Most candles `get_candles` returns
+pub const MAX_TOOL_CANDLES: usize = 100;
+
+/// Slippage used for quotes the bot looks up
+const QUOTE_SLIPPAGE_BPS: u16 = 50;
+
+/// Who a tool call is made for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolContext {
+    pub conversation_id: String,
+    /// Author of the message being answered, as authenticated by their JWT
+    pub requester_id: i64,
+    /// The person talking to the bot (`None` if the bot isn't a participant)
+    pub owner_id: Option<i64>,
+}
+
+impl ToolContext {
+    /// Context for answering `requester_id` in a `"user1_id:user2_id"`
+    /// conversation, where the participant that isn't the bot is the owner
+    pub fn new(conversation_id: &str, requester_id: i64, bot_user_id: i64) -> Self {
+        let owner_id = conversation_id
+            .split_once(':')
+            .and_then(|(a, b)| Some((a.parse::<i64>().ok()?, b.parse::<i64>().ok()?)))
+            .and_then(|(user1_id, user2_id)| match (user1_id == bot_user_id, user2_id == bot_user_id) {
+                (true, _) => Some(user2_id),
+                (_, true) => Some(user1_id),
+                _ => None,
+            });
+        Self { conversation_id: conversation_id.to_string(), requester_id, owner_id }
+    }
+
+    /// Whether the requester owns the conversation
+    pub fn is_owner(&self) -> bool {
+        self.owner_id == Some(self.requester_id)
+    }
+}
+
+/// Tool description offered to the model
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON schema of the arguments object
+    pub parameters: Value,
+}
+
+/// Tool call requested by the model
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    /// Provider-assigned id, echoed back with the result
+    pub id: String,
+    pub name: String,
+    pub arguments: Value,
+}
+
+/// One entry of the conversation as the model sees it
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptItem {
+    User(String),
+    Assistant(String),
+    /// Tools the model asked for
+    ToolCalls(Vec<ToolCall>),
+    /// JSON result of one of those calls
+    ToolResult { call_id: String, content: String },
+}
+
+/// A function the AI bot can call
+#[async_trait]
+pub trait AiTool: Send + Sync {
+    fn spec(&self) -> ToolSpec;
+
+    /// Whether the tool reads the conversation owner's wallet, and so may
+    /// only run for the owner
+    fn owner_only(&self) -> bool {
+        false
+    }
+
+    async fn call(&self, args: &Value, ctx: &ToolContext) -> Result<Value, String>;
+}
+
+/// Tools offered to the AI bot
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    tools: Vec<Arc<dyn AiTool>>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a tool, replacing any registered under the same name
+    pub fn register(&mut self, tool: impl AiTool + 'static) {
+        let name = tool.spec().name;
+        self.tools.retain(|existing| existing.spec().name != name);
+        self.tools.push(Arc::new(tool));
+    }
+
+    pub fn with(mut self, tool: impl AiTool + 'static) -> Self {
+        self.register(tool);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    pub fn specs(&self) -> Vec<ToolSpec> {
+        self.tools.iter().map(|tool| tool.spec()).collect()
+    }
+
+    /// Run a tool call and return its result as JSON for the model
+    ///
+    /// Failures are returned to the model as `{"error": ...}` so it can tell
+    /// the user; they never abort the reply.
+    pub async fn call(&self, call: &ToolCall, ctx: &ToolContext) -> String {
+        let started = Instant::now();
+        let result = match self.tools.iter().find(|tool| tool.spec().name == call.name) {
+            None => Err(format!("Unknown tool: {}", call.name)),
+            Some(tool) if tool.owner_only() && !ctx.is_owner() => {
+                tracing::warn!(
+                    conversation_id = %ctx.conversation_id,
+                    requester_id = ctx.requester_id,
+                    owner_id = ?ctx.owner_id,
+                    tool = %call.name,
+                    "AI tool call refused: requester doesn't own the conversation"
+                );
+                Err("Only available to the owner of this conversation".to_string())
+            }
+            Some(tool) => tool.call(&call.arguments, ctx).await,
+        };
+        tracing::info!(
+            conversation_id = %ctx.conversation_id,
+            requester_id = ctx.requester_id,
+            tool = %call.name,
+            arguments = %call.arguments,
+            ok = result.is_ok(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "AI tool call"
+        );
+        match result {
+            Ok(value) => value.to_string(),
+            Err(error) => json!({ "error": error }).to_string(),
+        }
+    }
+}
+
+/// Chat model that can request tool calls
+#[async_trait]
+pub trait ToolModel: Send + Sync {
+    /// Run one model turn over `transcript`, handing reply text to `on_text`
+    /// as it arrives
+    ///
+    /// Returns the tool calls the model asked for, or nothing once it has
+    /// answered. `on_text` returns `false` when no more text is wanted.
+    async fn turn(
+        &self,
+        transcript: &[TranscriptItem],
+        tools: &[ToolSpec],
+        on_text: &mut (dyn FnMut(&str) -> bool + Send),
+    ) -> Result<Vec<ToolCall>, String>;
+}
+
+/// Let `model` answer the transcript, running the tools it asks for
+///
+/// Tool calls and their results are appended to `transcript`. The model is
+/// offered no tools in its last round, so it has to answer in text.
+pub async fn run_turns(
+    model: &dyn ToolModel,
+    registry: &ToolRegistry,
+    ctx: &ToolContext,
+    transcript: &mut Vec<TranscriptItem>,
+    on_text: &mut (dyn FnMut(&str) -> bool + Send),
+) -> Result<(), String> {
+    let specs = registry.specs();
+    for round in 0..=MAX_TOOL_ROUNDS {
+        let tools = if round < MAX_TOOL_ROUNDS { &specs[..] } else { &[] };
+        let calls = model.turn(transcript, tools, on_text).await?;
+        if calls.is_empty() {
+            return Ok(());
+        }
+        transcript.push(TranscriptItem::ToolCalls(calls.clone()));
+        for call in &calls {
+            let content = registry.call(call, ctx).await;
+            transcript.push(TranscriptItem::ToolResult { call_id: call.id.clone(), content });
+        }
+    }
+    Ok(())
+}
+
+/// Where the market tools get their data
+#[async_trait]
+pub trait MarketDataSource: Send + Sync {
+    async fn price(&self, symbol: &str) -> Result<Value, String>;
+    async fn candles(&self, symbol: &str, timeframe: Timeframe, count: usize) -> Result<Value, String>;
+    /// SOL balance of the wallet linked to a user's account
+    async fn wallet_balance(&self, user_id: i64) -> Result<Value, String>;
+    /// Quote for swapping `amount` of `input` (in whole tokens) into `output`;
+    /// tokens are given by symbol or mint
+    async fn swap_quote(&self, input: &str, output: &str, amount: f64) -> Result<Value, String>;
+}
+
+/// The standard tools: price, candles, wallet balance and swap quote
+pub fn market_tools(source: Arc<dyn MarketDataSource>) -> ToolRegistry {
+    ToolRegistry::new()
+        .with(GetPrice(Arc::clone(&source)))
+        .with(GetCandles(Arc::clone(&source)))
+        .with(GetWalletBalance(Arc::clone(&source)))
+        .with(GetSwapQuote(source))
+}
+
+fn str_arg<'a>(args: &'a Value, name: &str) -> Result<&'a str, String> {
+    args.get(name)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("Missing argument: {}", name))
+}
+
+/// `get_price(symbol)`
+pub struct GetPrice(pub Arc<dyn MarketDataSource>);
+
+#[async_trait]
+impl AiTool for GetPrice {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "get_price",
+            description: "Current USD price of a token",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "symbol": { "type": "string", "description": "Token symbol, e.g. SOL" }
+                },
+                "required": ["symbol"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: &Value, _ctx: &ToolContext) -> Result<Value, String> {
+        let symbol = str_arg(args, "symbol")?.to_uppercase();
+        self.0.price(&symbol).await
+    }
+}
+
+/// `get_candles(symbol, timeframe, n)`
+pub struct GetCandles(pub Arc<dyn MarketDataSource>);
+
+#[async_trait]
+impl AiTool for GetCandles {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "get_candles",
+            description: "Most recent OHLC candles of a token, oldest first",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "symbol": { "type": "string", "description": "Token symbol, e.g. SOL" },
+                    "timeframe": { "type": "string", "enum": ["1m", "5m", "15m", "1h", "4h", "1d"] },
+                    "n": { "type": "integer", "minimum": 1, "maximum": MAX_TOOL_CANDLES }
+                },
+                "required": ["symbol", "timeframe", "n"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: &Value, _ctx: &ToolContext) -> Result<Value, String> {
+        let symbol = str_arg(args, "symbol")?.to_uppercase();
+        let timeframe = crate::handlers::market::parse_timeframe(str_arg(args, "timeframe")?)?;
+        let count = args.get("n").and_then(Value::as_u64).ok_or("Missing argument: n")?;
+        let count = (count as usize).clamp(1, MAX_TOOL_CANDLES);
+        self.0.candles(&symbol, timeframe, count).await
+    }
+}
+
+/// `get_wallet_balance()`, for the conversation owner only
+pub struct GetWalletBalance(pub Arc<dyn MarketDataSource>);
+
+#[async_trait]
+impl AiTool for GetWalletBalance {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "get_wallet_balance",
+            description: "SOL balance of the wallet of the user you are talking to",
+            parameters: json!({ "type": "object", "properties": {} }),
+        }
+    }
+
+    fn owner_only(&self) -> bool {
+        true
+    }
+
+    async fn call(&self, _args: &Value, ctx: &ToolContext) -> Result<Value, String> {
+        self.0.wallet_balance(ctx.requester_id).await
+    }
+}
+
+/// `get_swap_quote(input, output, amount)`
+pub struct GetSwapQuote(pub Arc<dyn MarketDataSource>);
+
+#[async_trait]
+impl AiTool for GetSwapQuote {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "get_swap_quote",
+            description: "Jupiter quote for swapping an amount of one token into another",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "input": { "type": "string", "description": "Token to sell, by symbol or mint" },
+                    "output": { "type": "string", "description": "Token to buy, by symbol or mint" },
+                    "amount": { "type": "number", "description": "Amount of the input token, in whole tokens" }
+                },
+                "required": ["input", "output", "amount"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: &Value, _ctx: &ToolContext) -> Result<Value, String> {
+        let input = str_arg(args, "input")?;
+        let output = str_arg(args, "output")?;
+        let amount = args
+            .get("amount")
+            .and_then(Value::as_f64)
+            .filter(|amount| *amount > 0.0)
+            .ok_or("amount must be a positive number")?;
+        self.0.swap_quote(input, output, amount).await
+    }
+}
+
+/// Tool data from the market, swap and wallet services
+pub struct ServiceDataSource {
+    market: MarketService,
+    swap: SwapService,
+    wallet: WalletService,
+    price_stream: Arc<PriceStreamServer>,
+    db: DbPool,
+}
+
+impl ServiceDataSource {
+    pub fn new(solana: Arc<SolanaState>, price_stream: Arc<PriceStreamServer>, db: DbPool) -> Self {
+        Self {
+            market: MarketService::new(Arc::clone(&solana)),
+            swap: SwapService::new(Arc::clone(&solana)),
+            wallet: WalletService::new(solana),
+            price_stream,
+            db,
+        }
+    }
+
+    /// Token list entry by mint or symbol, preferring verified tokens since
+    /// scam tokens reuse popular symbols
+    async fn resolve_token(&self, token: &str) -> Result<shared::dto::market::TokenListItem, String> {
+        let tokens = self.market.get_token_list().await.map_err(|e| e.to_string())?.tokens;
+        let matching = |item: &&shared::dto::market::TokenListItem| {
+            item.mint == token || item.symbol.eq_ignore_ascii_case(token)
+        };
+        tokens
+            .iter()
+            .filter(matching)
+            .max_by_key(|item| (item.mint == token, item.verified))
+            .cloned()
+            .ok_or_else(|| format!("Unknown token: {}", token))
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for ServiceDataSource {
+    async fn price(&self, symbol: &str) -> Result<Value, String> {
+        let response = self.market.get_prices(&[symbol]).await.map_err(|e| e.to_string())?;
+        let price = response
+            .prices
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(symbol))
+            .map(|(_, price)| price)
+            .ok_or_else(|| format!("No price available for {}", symbol))?;
+        serde_json::to_value(price).map_err(|e| e.to_string())
+    }
+
+    async fn candles(&self, symbol: &str, timeframe: Timeframe, count: usize) -> Result<Value, String> {
+        let candles = self.price_stream.candle_aggregator().get_candles(symbol, timeframe, count).await;
+        if candles.is_empty() {
+            return Err(format!("No candles available for {}", symbol));
+        }
+        Ok(candles
+            .iter()
+            .map(|candle| {
+                json!({
+                    "time": candle.timestamp,
+                    "open": candle.open,
+                    "high": candle.high,
+                    "low": candle.low,
+                    "close": candle.close,
+                    "volume": candle.volume,
+                })
+            })
+            .collect())
+    }
+
+    async fn wallet_balance(&self, user_id: i64) -> Result<Value, String> {
+        let address = UserRepository::find_by_id(&self.db, user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .and_then(|user| user.wallet_address)
+            .ok_or("No wallet is linked to this account")?;
+        let balance = self.wallet.get_wallet_balance(&address).await.map_err(|e| e.to_string())?;
+        serde_json::to_value(balance).map_err(|e| e.to_string())
+    }
+
+    async fn swap_quote(&self, input: &str, output: &str, amount: f64) -> Result<Value, String> {
+        let input = self.resolve_token(input).await?;
+        let output = self.resolve_token(output).await?;
+        let base_units = (amount * 10f64.powi(input.decimals as i32)).round() as u64;
+        let quote = self
+            .swap
+            .get_swap_quote(&input.mint, &output.mint, base_units, QUOTE_SLIPPAGE_BPS)
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut value = serde_json::to_value(quote).map_err(|e| e.to_string())?;
+        value["inputSymbol"] = json!(input.symbol);
+        value["outputSymbol"] = json!(output.symbol);
+        value["outputDecimals"] = json!(output.decimals);
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// Data source with fixed answers that records wallet lookups
+    #[derive(Default)]
+    struct FixedData {
+        wallet_lookups: Mutex<Vec<i64>>,
+    }
+
+    #[async_trait]
+    impl MarketDataSource for FixedData {
+        async fn price(&self, symbol: &str) -> Result<Value, String> {
+            match symbol {
+                "SOL" => Ok(json!({ "price": 142.5, "source": "pyth" })),
+                _ => Err(format!("No price available for {}", symbol)),
+            }
+        }
+
+        async fn candles(&self, _symbol: &str, _timeframe: Timeframe, count: usize) -> Result<Value, String> {
+            Ok((0..count).map(|i| json!({ "close": 140.0 + i as f64 })).collect())
+        }
+
+        async fn wallet_balance(&self, user_id: i64) -> Result<Value, String> {
+            self.wallet_lookups.lock().unwrap().push(user_id);
+            Ok(json!({ "balance_sol": 3.25 }))
+        }
+
+        async fn swap_quote(&self, input: &str, output: &str, amount: f64) -> Result<Value, String> {
+            Ok(json!({ "input": input, "output": output, "amount": amount, "outAmount": "213750000" }))
+        }
+    }
+
+    /// One scripted model turn
+    struct Step {
+        text: &'static str,
+        calls: Vec<ToolCall>,
+    }
+
+    /// Model that plays back scripted turns and records what it was given
+    struct ScriptedModel {
+        steps: Mutex<VecDeque<Step>>,
+        seen: Mutex<Vec<(Vec<TranscriptItem>, usize)>>,
+    }
+
+    impl ScriptedModel {
+        fn new(steps: Vec<Step>) -> Self {
+            Self { steps: Mutex::new(steps.into()), seen: Mutex::new(Vec::new()) }
+        }
+    }
+
+    #[async_trait]
+    impl ToolModel for ScriptedModel {
+        async fn turn(
+            &self,
+            transcript: &[TranscriptItem],
+            tools: &[ToolSpec],
+            on_text: &mut (dyn FnMut(&str) -> bool + Send),
+        ) -> Result<Vec<ToolCall>, String> {
+            self.seen.lock().unwrap().push((transcript.to_vec(), tools.len()));
+            let step = self.steps.lock().unwrap().pop_front().ok_or("model ran out of turns")?;
+            if !step.text.is_empty() {
+                on_text(step.text);
+            }
+            Ok(step.calls)
+        }
+    }
+
+    fn call(id: &str, name: &str, arguments: Value) -> ToolCall {
+        ToolCall { id: id.to_string(), name: name.to_string(), arguments }
+    }
+
+    fn result(content: &str) -> Value {
+        serde_json::from_str(content).unwrap()
+    }
+
+    async fn run(model: &ScriptedModel, tools: &ToolRegistry, ctx: &ToolContext) -> (Vec<TranscriptItem>, String) {
+        let mut transcript = vec![TranscriptItem::User("What's SOL at, and what would 2 SOL get me in USDC?".into())];
+        let mut text = String::new();
+        let mut on_text = |delta: &str| {
+            text.push_str(delta);
+            true
+        };
+        run_turns(model, tools, ctx, &mut transcript, &mut on_text).await.unwrap();
+        (transcript, text)
+    }
+
+    #[tokio::test]
+    async fn test_tool_results_are_fed_back_to_model() {
+        let data = Arc::new(FixedData::default());
+        let tools = market_tools(data);
+        let model = ScriptedModel::new(vec![
+            Step {
+                text: "",
+                calls: vec![
+                    call("c1", "get_price", json!({ "symbol": "sol" })),
+                    call("c2", "get_swap_quote", json!({ "input": "SOL", "output": "USDC", "amount": 2 })),
+                ],
+            },
+            Step { text: "SOL is $142.50; 2 SOL gets you about 213.75 USDC.", calls: vec![] },
+        ]);
+        let ctx = ToolContext::new("0:5", 5, 0);
+
+        let (transcript, text) = run(&model, &tools, &ctx).await;
+
+        assert_eq!(text, "SOL is $142.50; 2 SOL gets you about 213.75 USDC.");
+        assert_eq!(transcript.len(), 4);
+        let TranscriptItem::ToolResult { call_id, content } = &transcript[2] else {
+            panic!("expected a tool result, got {:?}", transcript[2]);
+        };
+        assert_eq!(call_id, "c1");
+        assert_eq!(result(content)["price"], 142.5);
+        let TranscriptItem::ToolResult { call_id, content } = &transcript[3] else {
+            panic!("expected a tool result, got {:?}", transcript[3]);
+        };
+        assert_eq!(call_id, "c2");
+        assert_eq!(result(content)["outAmount"], "213750000");
+
+        // The second turn saw both results
+        let seen = model.seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[1].0, transcript);
+        assert_eq!(seen[0].1, 4);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_balance_is_only_for_conversation_owner() {
+        let data = Arc::new(FixedData::default());
+        let tools = market_tools(data.clone());
+        let balance = call("c1", "get_wallet_balance", json!({}));
+
+        // User 7 isn't part of the bot's conversation with user 5
+        let content = tools.call(&balance, &ToolContext::new("0:5", 7, 0)).await;
+        assert!(result(&content)["error"].is_string());
+        // Neither participant is the bot, so nobody owns it
+        let content = tools.call(&balance, &ToolContext::new("5:7", 5, 0)).await;
+        assert!(result(&content)["error"].is_string());
+        assert!(data.wallet_lookups.lock().unwrap().is_empty());
+
+        let content = tools.call(&balance, &ToolContext::new("0:5", 5, 0)).await;
+        assert_eq!(result(&content)["balance_sol"], 3.25);
+        assert_eq!(*data.wallet_lookups.lock().unwrap(), vec![5]);
+    }
+
+    #[tokio::test]
+    async fn test_bad_calls_are_reported_to_model() {
+        let tools = market_tools(Arc::new(FixedData::default()));
+        let ctx = ToolContext::new("0:5", 5, 0);
+
+        for bad in [
+            call("c1", "place_order", json!({})),
+            call("c2", "get_price", json!({})),
+            call("c3", "get_price", json!({ "symbol": "NOPE" })),
+            call("c4", "get_candles", json!({ "symbol": "SOL", "timeframe": "3m", "n": 5 })),
+            call("c5", "get_swap_quote", json!({ "input": "SOL", "output": "USDC", "amount": -1 })),
+        ] {
+            let content = tools.call(&bad, &ctx).await;
+            assert!(result(&content)["error"].is_string(), "{} should fail: {}", bad.id, content);
+        }
+
+        // Candle counts are capped
+        let content = tools.call(&call("c6", "get_candles", json!({ "symbol": "SOL", "timeframe": "1h", "n": 5000 })), &ctx).await;
+        assert_eq!(result(&content).as_array().unwrap().len(), MAX_TOOL_CANDLES);
+    }
+
+    #[tokio::test]
+    async fn test_last_round_offers_no_tools() {
+        let tools = market_tools(Arc::new(FixedData::default()));
+        let price = || Step { text: "", calls: vec![call("c", "get_price", json!({ "symbol": "SOL" }))] };
+        let mut steps: Vec<Step> = (0..MAX_TOOL_ROUNDS).map(|_| price()).collect();
+        steps.push(Step { text: "SOL is $142.50.", calls: vec![] });
+        let model = ScriptedModel::new(steps);
+
+        let (_, text) = run(&model, &tools, &ToolContext::new("0:5", 5, 0)).await;
+
+        assert_eq!(text, "SOL is $142.50.");
+        let seen = model.seen.lock().unwrap();
+        assert_eq!(seen.len(), MAX_TOOL_ROUNDS + 1);
+        assert!(seen[..MAX_TOOL_ROUNDS].iter().all(|(_, tools)| *tools == 4));
+        assert_eq!(seen[MAX_TOOL_ROUNDS].1, 0);
+    }
+}
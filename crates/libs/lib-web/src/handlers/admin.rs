@@ -0,0 +1,399 @@
+//! # Admin Handlers
+//!
+//! Operator endpoints for managing the price stream's symbol universe and
+//! the token unlock schedule, for checking served candles against the tick
+//! journal, for announcing scheduled maintenance, and for watching how fast
+//! swaps go through.
+//!
+//! ## Endpoints
+//!
+//! - `POST /api/admin/streamed-symbols` - Add a symbol to the price stream
+//! - `DELETE /api/admin/streamed-symbols/{symbol}` - Remove a symbol and end its candle series
+//! - `POST /api/admin/token-unlocks` - Add an unlock (or correct an imported one)
+//! - `PUT /api/admin/token-unlocks/{id}` - Replace an unlock
+//! - `DELETE /api/admin/token-unlocks/{id}` - Remove an unlock
+//! - `POST /api/admin/replay-candles` - Rebuild candles from the tick journal and diff them
+//! - `POST /api/admin/maintenance` - Warn connected clients of upcoming downtime
+//! - `GET /api/admin/swap-latency` - Per-stage swap pipeline latency percentiles
+//!
+//! ## Authentication
+//!
+//! Requires a JWT (`Authorization: Bearer <token>`) for a user listed in
+//! `ADMIN_USERNAMES`. Other users get `403 Forbidden`.
+//!
+//! ## Request Examples
+//!
+//! ```bash
+//! curl -X POST http://localhost:3001/api/admin/streamed-symbols \
+//!   -H "Authorization: Bearer $TOKEN" \
+//!   -H "Content-Type: application/json" \
+//!   -d '{"symbol": "BONK", "require_pyth": false}'
+//!
+//! curl -X DELETE http://localhost:3001/api/admin/streamed-symbols/BONK \
+//!   -H "Authorization: Bearer $TOKEN"
+//!
+//! curl -X POST http://localhost:3001/api/admin/token-unlocks \
+//!   -H "Authorization: Bearer $TOKEN" \
+//!   -H "Content-Type: application/json" \
+//!   -d '{"mint": "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN", "symbol": "JUP",
+//!        "unlock_at": 1740787200, "amount": 53472222, "circulating_supply": 1350000000,
+//!        "allocation": "Team"}'
+//!
+//! curl -X POST http://localhost:3001/api/admin/replay-candles \
+//!   -H "Authorization: Bearer $TOKEN" \
+//!   -H "Content-Type: application/json" \
+//!   -d '{"symbol": "SOL", "timeframe": "4h", "from": 1718582400, "to": 1718668800}'
+//!
+//! curl -X POST http://localhost:3001/api/admin/maintenance \
+//!   -H "Authorization: Bearer $TOKEN" \
+//!   -H "Content-Type: application/json" \
+//!   -d '{"starts_at": 1718668800, "expected_duration_secs": 600, "message": "Database upgrade"}'
+//!
+//! curl "http://localhost:3001/api/admin/swap-latency?days=1" \
+//!   -H "Authorization: Bearer $TOKEN"
+//! ```
+
+use crate::handlers::market::parse_timeframe;
+use crate::services::swap::SwapService;
+use crate::services::{StreamedSymbolService, TokenUnlockService};
+use axum::{extract::{Path, Query, State}, http::{HeaderMap, StatusCode, header::AUTHORIZATION}, Json};
+use lib_auth::decode_jwt;
+use lib_core::{dto::ErrorResponse, Config, DbPool};
+use lib_solana::candle_replay::{replay_and_diff, ReplayOrder, ReplayReport};
+use lib_solana::tick_journal::read_journal;
+use lib_solana::PriceStreamServer;
+use serde::Deserialize;
+use shared::dto::latency::SwapLatencySummary;
+use shared::dto::market::{AddStreamedSymbolRequest, StreamedSymbolInfo};
+use shared::dto::system::{MaintenanceNoticeRequest, MaintenanceWindow, NoticeCategory, NoticeLevel, SystemNotice};
+use shared::dto::unlocks::{TokenUnlockInfo, TokenUnlockRequest};
+use std::sync::Arc;
+use tracing::{info, instrument, warn};
+
+pub(crate) type AdminError = (StatusCode, Json<ErrorResponse>);
+
+fn admin_error(status: StatusCode, message: &str) -> AdminError {
+    (status, Json(ErrorResponse { error: message.to_string() }))
+}
+
+/// Authenticate the caller and check they are an admin. Returns the username.
+pub(crate) fn require_admin(headers: &HeaderMap, config: &Config) -> Result<String, AdminError> {
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .ok_or_else(|| admin_error(StatusCode::UNAUTHORIZED, "Missing or invalid authorization header"))?;
+
+    let claims = decode_jwt(token, &config.jwt_secret)
+        .map_err(|_| admin_error(StatusCode::UNAUTHORIZED, "Invalid token"))?;
+
+    if !config.is_admin(&claims.username) {
+        warn!("[ADMIN] Rejected admin request from {}", claims.username);
+        return Err(admin_error(StatusCode::FORBIDDEN, "Admin access required"));
+    }
+    Ok(claims.username)
+}
+
+/// Add a symbol to the price stream.
+///
+/// **Route**: `POST /api/admin/streamed-symbols`
+///
+/// # Returns
+///
+/// Success (201): The added symbol
+/// Error (400): Invalid or duplicate symbol, unknown mint, or missing Pyth feed
+/// Error (401/403): Not authenticated / not an admin
+#[instrument(skip(service, config, headers))]
+pub async fn add_streamed_symbol(
+    State(service): State<Arc<StreamedSymbolService>>,
+    State(config): State<Config>,
+    headers: HeaderMap,
+    Json(payload): Json<AddStreamedSymbolRequest>,
+) -> Result<(StatusCode, Json<StreamedSymbolInfo>), AdminError> {
+    let admin = require_admin(&headers, &config)?;
+
+    let added = service
+        .add(&payload.symbol, payload.require_pyth, &admin)
+        .await
+        .map_err(|e| (e.status_code(), Json(ErrorResponse { error: e.user_message() })))?;
+    Ok((StatusCode::CREATED, Json(added)))
+}
+
+/// Remove a symbol from the price stream.
+///
+/// **Route**: `DELETE /api/admin/streamed-symbols/{symbol}`
+///
+/// # Returns
+///
+/// Success (204): Symbol removed; its candle series is closed
+/// Error (404): Symbol is not streamed
+/// Error (401/403): Not authenticated / not an admin
+#[instrument(skip(service, config, headers))]
+pub async fn remove_streamed_symbol(
+    State(service): State<Arc<StreamedSymbolService>>,
+    State(config): State<Config>,
+    headers: HeaderMap,
+    Path(symbol): Path<String>,
+) -> Result<StatusCode, AdminError> {
+    require_admin(&headers, &config)?;
+
+    service
+        .remove(&symbol)
+        .await
+        .map_err(|e| (e.status_code(), Json(ErrorResponse { error: e.user_message() })))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Add a token unlock, or take over the existing one for the same mint,
+/// time and allocation.
+///
+/// **Route**: `POST /api/admin/token-unlocks`
+///
+/// The importer leaves unlocks saved here alone.
+///
+/// # Returns
+///
+/// Success (201): The saved unlock
+/// Error (400): Missing mint, bad symbol, non-positive amount or supply
+/// Error (401/403): Not authenticated / not an admin
+#[instrument(skip(service, config, headers, payload))]
+pub async fn create_token_unlock(
+    State(service): State<Arc<TokenUnlockService>>,
+    State(config): State<Config>,
+    headers: HeaderMap,
+    Json(payload): Json<TokenUnlockRequest>,
+) -> Result<(StatusCode, Json<TokenUnlockInfo>), AdminError> {
+    let admin = require_admin(&headers, &config)?;
+
+    let unlock = service
+        .create(payload, &admin)
+        .await
+        .map_err(|e| (e.status_code(), Json(ErrorResponse { error: e.user_message() })))?;
+    Ok((StatusCode::CREATED, Json(unlock)))
+}
+
+/// Replace a token unlock.
+///
+/// **Route**: `PUT /api/admin/token-unlocks/{id}`
+///
+/// # Returns
+///
+/// Success (200): The updated unlock
+/// Error (400): Invalid fields
+/// Error (404): No unlock with that id
+/// Error (401/403): Not authenticated / not an admin
+#[instrument(skip(service, config, headers, payload))]
+pub async fn update_token_unlock(
+    State(service): State<Arc<TokenUnlockService>>,
+    State(config): State<Config>,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+    Json(payload): Json<TokenUnlockRequest>,
+) -> Result<Json<TokenUnlockInfo>, AdminError> {
+    let admin = require_admin(&headers, &config)?;
+
+    service
+        .update(id, payload, &admin)
+        .await
+        .map(Json)
+        .map_err(|e| (e.status_code(), Json(ErrorResponse { error: e.user_message() })))
+}
+
+/// Remove a token unlock. Removed unlocks are not re-imported.
+///
+/// **Route**: `DELETE /api/admin/token-unlocks/{id}`
+///
+/// # Returns
+///
+/// Success (204): Unlock removed
+/// Error (404): No such unlock, or already removed
+/// Error (401/403): Not authenticated / not an admin
+#[instrument(skip(service, config, headers))]
+pub async fn remove_token_unlock(
+    State(service): State<Arc<TokenUnlockService>>,
+    State(config): State<Config>,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, AdminError> {
+    let admin = require_admin(&headers, &config)?;
+
+    service
+        .remove(id, &admin)
+        .await
+        .map_err(|e| (e.status_code(), Json(ErrorResponse { error: e.user_message() })))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Request body for `POST /api/admin/replay-candles`
+#[derive(Debug, Deserialize)]
+pub struct ReplayCandlesRequest {
+    pub symbol: String,
+    /// "1m", "5m", "15m", "1h", "4h" or "1d"
+    pub timeframe: String,
+    /// Range to replay, Unix seconds (narrowed to whole candles)
+    pub from: u64,
+    pub to: u64,
+    /// Defaults to event time, which is what exposes ordering bugs
+    #[serde(default)]
+    pub order: ReplayOrder,
+}
+
+/// Rebuild a symbol's candles from the tick journal and diff them against
+/// the candles being served.
+///
+/// **Route**: `POST /api/admin/replay-candles`
+///
+/// The replay runs in a scratch aggregator; served candles are untouched.
+/// Only candles the live aggregator still holds can be compared, so ranges
+/// older than its history report every replayed candle as missing.
+///
+/// # Returns
+///
+/// Success (200): `ReplayReport` - Counts and every mismatching candle period
+/// Error (400): Unknown timeframe or an empty range
+/// Error (404): Journaling is off, or nothing has been journaled yet
+/// Error (401/403): Not authenticated / not an admin
+#[instrument(skip(price_stream, config, headers))]
+pub async fn replay_candles(
+    State(price_stream): State<Arc<PriceStreamServer>>,
+    State(config): State<Config>,
+    headers: HeaderMap,
+    Json(payload): Json<ReplayCandlesRequest>,
+) -> Result<Json<ReplayReport>, AdminError> {
+    require_admin(&headers, &config)?;
+
+    let timeframe = parse_timeframe(&payload.timeframe).map_err(|e| admin_error(StatusCode::BAD_REQUEST, &e))?;
+    // Ticks reach the journal file up to a second late; leave them out
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let to = payload.to.min(now.saturating_sub(2));
+    if payload.from >= to {
+        return Err(admin_error(StatusCode::BAD_REQUEST, "from must be before to (and in the past)"));
+    }
+    let journal = price_stream
+        .journal()
+        .ok_or_else(|| admin_error(StatusCode::NOT_FOUND, "Tick journaling is off (set TICK_JOURNAL_DIR)"))?;
+
+    let dir = journal.dir().to_path_buf();
+    let symbol = payload.symbol.clone();
+    let from = payload.from;
+    let contents = tokio::task::spawn_blocking(move || read_journal(&dir, Some(&symbol), from, to))
+        .await
+        .map_err(|_| admin_error(StatusCode::INTERNAL_SERVER_ERROR, "Journal read failed"))?
+        .map_err(|e| admin_error(StatusCode::NOT_FOUND, &e.to_string()))?;
+
+    let stored = price_stream.candle_aggregator().get_candles(&payload.symbol, timeframe, usize::MAX).await;
+    let report = replay_and_diff(&contents, &stored, &payload.symbol, timeframe, payload.order, from, to).await;
+    info!(
+        symbol = %report.symbol,
+        timeframe = %report.timeframe,
+        ticks = report.ticks,
+        mismatches = report.mismatches.len(),
+        malformed = contents.malformed,
+        "[ADMIN] Candle replay finished"
+    );
+    Ok(Json(report))
+}
+
+/// Announce scheduled maintenance to every connected client.
+///
+/// **Route**: `POST /api/admin/maintenance`
+///
+/// The notice goes out over the price stream WebSocket. Terminals count down
+/// to `starts_at`, stop starting swaps in the last two minutes, and show the
+/// outage as maintenance until `GET /api/health` answers again. Clients that
+/// connect later don't get it, so announce again closer to the start if
+/// needed; a repeated announcement moves the start.
+///
+/// # Returns
+///
+/// Success (202): The notice that was broadcast
+/// Error (400): `starts_at` is in the past, or the message is empty
+/// Error (401/403): Not authenticated / not an admin
+#[instrument(skip(price_stream, config, headers))]
+pub async fn announce_maintenance(
+    State(price_stream): State<Arc<PriceStreamServer>>,
+    State(config): State<Config>,
+    headers: HeaderMap,
+    Json(payload): Json<MaintenanceNoticeRequest>,
+) -> Result<(StatusCode, Json<SystemNotice>), AdminError> {
+    let admin = require_admin(&headers, &config)?;
+
+    let now = chrono::Utc::now().timestamp();
+    if payload.starts_at <= now {
+        return Err(admin_error(StatusCode::BAD_REQUEST, "starts_at must be in the future"));
+    }
+    let message = payload.message.trim();
+    if message.is_empty() {
+        return Err(admin_error(StatusCode::BAD_REQUEST, "A message is required"));
+    }
+
+    let notice = SystemNotice {
+        level: NoticeLevel::Warning,
+        title: "Scheduled maintenance".to_string(),
+        message: message.to_string(),
+        timestamp: now,
+        category: NoticeCategory::Maintenance,
+        maintenance: Some(MaintenanceWindow {
+            starts_at: payload.starts_at,
+            expected_duration_secs: payload.expected_duration_secs,
+        }),
+    };
+    let clients = price_stream.broadcast_notice(notice.clone());
+    info!(
+        admin = %admin,
+        starts_at = payload.starts_at,
+        expected_duration_secs = ?payload.expected_duration_secs,
+        clients,
+        "[ADMIN] Maintenance announced"
+    );
+    Ok((StatusCode::ACCEPTED, Json(notice)))
+}
+
+/// Longest window `GET /api/admin/swap-latency` summarizes
+const MAX_LATENCY_DAYS: i64 = 90;
+
+/// Query of `GET /api/admin/swap-latency`
+#[derive(Debug, Deserialize)]
+pub struct SwapLatencyQuery {
+    /// Summarize swaps created in the last this many days
+    #[serde(default = "default_latency_days")]
+    pub days: i64,
+}
+
+fn default_latency_days() -> i64 {
+    7
+}
+
+/// Summarize how long each stage of the swap pipeline takes.
+///
+/// **Route**: `GET /api/admin/swap-latency?days=7`
+///
+/// Covers every user's swaps whose terminal reported a latency breakdown,
+/// from the Swap click to the RPC acknowledging the transaction.
+///
+/// # Returns
+///
+/// Success (200): `SwapLatencySummary` - p50, p95 and max per stage and end to end
+/// Error (400): `days` is not between 1 and 90
+/// Error (401/403): Not authenticated / not an admin
+#[instrument(skip(pool, config, headers))]
+pub async fn get_swap_latency(
+    State(pool): State<DbPool>,
+    State(config): State<Config>,
+    headers: HeaderMap,
+    Query(query): Query<SwapLatencyQuery>,
+) -> Result<Json<SwapLatencySummary>, AdminError> {
+    require_admin(&headers, &config)?;
+
+    if !(1..=MAX_LATENCY_DAYS).contains(&query.days) {
+        return Err(admin_error(StatusCode::BAD_REQUEST, "days must be between 1 and 90"));
+    }
+    let since = chrono::Utc::now() - chrono::Duration::days(query.days);
+    let summary = SwapService::latency_summary(&pool, since)
+        .await
+        .map_err(|e| (e.status_code(), Json(ErrorResponse { error: e.user_message() })))?;
+    Ok(Json(summary))
+}
@@ -0,0 +1,113 @@
+//! # API Key Handlers
+//!
+//! Manage the caller's API keys for scripted access. All routes need a login
+//! session (an API key can't create or revoke keys) and only ever touch the
+//! caller's own keys.
+//!
+//! ## Endpoints
+//!
+//! - `POST /api/keys` - Create a key (returns the key once)
+//! - `GET /api/keys` - List the caller's keys
+//! - `DELETE /api/keys/{id}` - Revoke a key
+//!
+//! ## Request Example
+//!
+//! ```bash
+//! curl -X POST http://localhost:3001/api/keys \
+//!   -H "Authorization: Bearer $TOKEN" \
+//!   -H "Content-Type: application/json" \
+//!   -d '{"label": "Spreadsheet", "scopes": []}'
+//!
+//! # Then, from the script
+//! curl -H "X-Api-Key: $KEY" http://localhost:3001/api/swap/history
+//! ```
+
+use crate::services::ApiKeyService;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use lib_auth::Claims;
+use lib_core::{dto::ErrorResponse, AppError};
+use shared::dto::api_keys::{ApiKeyListResponse, CreateApiKeyRequest, CreateApiKeyResponse};
+use std::sync::Arc;
+use tracing::{instrument, warn};
+
+type HandlerError = (StatusCode, Json<ErrorResponse>);
+
+fn user_id(claims: &Claims) -> Result<i64, HandlerError> {
+    claims.sub.parse::<i64>().map_err(|_| {
+        (StatusCode::UNAUTHORIZED, Json(ErrorResponse {
+            error: "Invalid user ID in token".to_string(),
+        }))
+    })
+}
+
+fn to_response(e: AppError) -> HandlerError {
+    (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+}
+
+/// Create an API key for the authenticated user.
+///
+/// **Route**: `POST /api/keys`
+///
+/// **Authentication**: Required (JWT token in Authorization header; API keys are refused)
+///
+/// # Returns
+///
+/// Success (200): `Json<CreateApiKeyResponse>` - The key's details and the key itself
+///
+/// Error (400): Missing or too long label, or too many keys
+/// Error (401): Unauthorized (missing or invalid JWT)
+/// Error (403): Made with an API key
+/// Error (500): Database error
+#[instrument(skip(service, claims, request), fields(user_id = %claims.sub))]
+pub async fn create_api_key(
+    State(service): State<Arc<ApiKeyService>>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Result<Json<CreateApiKeyResponse>, HandlerError> {
+    let user_id = user_id(&claims)?;
+    let response = service.create(user_id, request).await.map_err(|e| {
+        warn!("[API KEYS] Key rejected: {}", e);
+        to_response(e)
+    })?;
+    Ok(Json(response))
+}
+
+/// List the authenticated user's API keys.
+///
+/// **Route**: `GET /api/keys`
+///
+/// **Authentication**: Required (JWT token in Authorization header; API keys are refused)
+#[instrument(skip(service, claims), fields(user_id = %claims.sub))]
+pub async fn list_api_keys(
+    State(service): State<Arc<ApiKeyService>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiKeyListResponse>, HandlerError> {
+    let user_id = user_id(&claims)?;
+    service.list(user_id).await.map(Json).map_err(to_response)
+}
+
+/// Revoke one of the authenticated user's API keys.
+///
+/// **Route**: `DELETE /api/keys/{id}`
+///
+/// **Authentication**: Required (JWT token in Authorization header; API keys are refused)
+///
+/// # Returns
+///
+/// Success (204): No content
+///
+/// Error (404): No such key for this user
+#[instrument(skip(service, claims), fields(user_id = %claims.sub))]
+pub async fn revoke_api_key(
+    State(service): State<Arc<ApiKeyService>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, HandlerError> {
+    let user_id = user_id(&claims)?;
+    service.revoke(user_id, id).await.map_err(to_response)?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -9,6 +9,10 @@
 //! - User login with email or username
 //! - JWT token generation
 //! - Wallet setup token generation
+//! - Logout (server-side token revocation)
+//! - Session refresh (a fresh JWT for a still-valid one)
+//! - Password change and profile (email) update for the logged-in user
+//! - Password reset with an emailed one-time token
 //!
 //! ## Example
 //!
@@ -21,13 +25,21 @@
 //!     .route("/login", post(login));
 //! ```
 
-use lib_auth::{encode_jwt, hash_password, verify_password};
+use lib_auth::{decode_jwt, encode_jwt, hash_password, verify_password, Claims};
 use lib_core::{Config, DbPool, dto::{AuthResponse, ErrorResponse, LoginRequest, SignupRequest, UserInfo}};
+use lib_core::dto::{ChangePasswordRequest, UpdateProfileRequest};
+use lib_core::dto::{PasswordResetConfirmRequest, PasswordResetRequest, PasswordResetResponse};
+use lib_core::model::store::models::User;
 use lib_core::model::store::user_repository::UserRepository;
+use lib_core::model::store::RevokedTokenRepository;
+use crate::services::PasswordResetService;
 use axum::{
-    extract::{Json, State},
+    extract::{Extension, Json, State},
     http::StatusCode,
+    response::{IntoResponse, Response},
 };
+use shared::validation::{validate_signup, ValidationErrorResponse};
+use std::sync::Arc;
 use tracing::{debug, error, info, warn, instrument};
 
 /// Signup handler - creates a new user account.
@@ -41,15 +53,16 @@ use tracing::{debug, error, info, warn, instrument};
 /// # Returns
 ///
 /// * `Ok((StatusCode::CREATED, AuthResponse))` - User created successfully with JWT token and wallet setup token
-/// * `Err((StatusCode, ErrorResponse))` - Validation error, duplicate user, or server error
+/// * `Err((StatusCode::BAD_REQUEST, ValidationErrorResponse))` - Every field that failed [`shared::validation`], at once
+/// * `Err((StatusCode, ErrorResponse))` - Duplicate user or server error
 ///
 /// # Validation
 ///
-/// - Username must be at least 3 characters
-/// - Email must contain '@' symbol
+/// - Username: 3-32 letters, digits, `_`, `-` or `.` (any script)
+/// - Email: `local@domain.tld` shape; plus-addressing is fine
+/// - Password: at least 8 characters with a letter and a digit
 /// - Email must be unique
 /// - Username must be unique
-/// - Password must be at least 8 characters (validated in hash_password)
 ///
 /// # Example
 ///
@@ -71,31 +84,26 @@ pub async fn signup(
     State(pool): State<DbPool>,
     State(config): State<Config>,
     Json(req): Json<SignupRequest>,
-) -> Result<(StatusCode, Json<AuthResponse>), (StatusCode, Json<ErrorResponse>)> {
+) -> Result<(StatusCode, Json<AuthResponse>), Response> {
     info!("[SIGNUP]  NEW USER SIGNUP REQUEST");
     debug!("   Username: {}", req.username);
     debug!("   Email: {}", req.email);
 
-    if req.username.len() < 3 {
-        warn!("[SIGNUP]  Username too short");
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Username must be at least 3 characters".to_string(),
-            }),
-        ));
+    let errors = validate_signup(&req.username, &req.email, &req.password);
+    if !errors.is_empty() {
+        warn!("[SIGNUP]  Rejected {} invalid field(s)", errors.len());
+        return Err((StatusCode::BAD_REQUEST, Json(ValidationErrorResponse::from(errors))).into_response());
     }
 
-    if !req.email.contains('@') {
-        warn!("[SIGNUP]  Invalid email format");
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Invalid email format".to_string(),
-            }),
-        ));
-    }
+    create_account(pool, config, req).await.map_err(IntoResponse::into_response)
+}
 
+/// Create the user behind a validated signup request
+async fn create_account(
+    pool: DbPool,
+    config: Config,
+    req: SignupRequest,
+) -> Result<(StatusCode, Json<AuthResponse>), (StatusCode, Json<ErrorResponse>)> {
     match UserRepository::find_by_email(&pool, &req.email).await {
         Ok(Some(_)) => {
             warn!("[SIGNUP]  Email already registered: {}", req.email);
@@ -388,6 +396,398 @@ pub async fn login(
     ))
 }
 
+/// Logout handler - revokes the caller's JWT.
+///
+/// Must be mounted behind [`require_auth`](crate::middleware::require_auth),
+/// which validates the token and provides its claims. The token is rejected
+/// by `require_auth` from then on; other sessions of the user stay valid.
+///
+/// # Returns
+///
+/// * `Ok(StatusCode::NO_CONTENT)` - Token revoked
+/// * `Err((StatusCode, ErrorResponse))` - Malformed claims or database error
+///
+/// # Example
+///
+/// ```text
+/// POST /api/auth/logout
+/// Authorization: Bearer <token>
+/// Response: 204 No Content
+/// ```
+#[instrument(skip(pool, claims), fields(user_id = %claims.sub))]
+pub async fn logout(
+    State(pool): State<DbPool>,
+    Extension(claims): Extension<Claims>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let bad_claims = || {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Invalid token".to_string(),
+            }),
+        )
+    };
+    let user_id = claims.sub.parse::<i64>().map_err(|_| bad_claims())?;
+    let expires_at = chrono::DateTime::from_timestamp(claims.exp, 0).ok_or_else(bad_claims)?;
+
+    RevokedTokenRepository::revoke(&pool, &claims.revocation_id(), user_id, expires_at)
+        .await
+        .map_err(|e| {
+            error!("[LOGOUT]  Failed to revoke token: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Database error".to_string(),
+                }),
+            )
+        })?;
+
+    info!("[LOGOUT]  User {} logged out", claims.username);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Refresh handler - swaps the caller's still-valid JWT for a fresh one.
+///
+/// Must be mounted behind [`require_auth`](crate::middleware::require_auth),
+/// so an expired or revoked token can't be refreshed; clients refresh shortly
+/// before expiry. The old token is revoked once the new one is issued.
+///
+/// # Returns
+///
+/// * `Ok(AuthResponse)` - The user and a token with a full lifetime
+/// * `Err((StatusCode::UNAUTHORIZED, ErrorResponse))` - The user no longer exists
+/// * `Err((StatusCode::INTERNAL_SERVER_ERROR, ErrorResponse))` - Token or database error
+///
+/// # Example
+///
+/// ```text
+/// POST /api/auth/refresh
+/// Authorization: Bearer <token>
+/// Response: 200 { "user": {...}, "token": "<fresh token>", "message": "Session refreshed" }
+/// ```
+#[instrument(skip(pool, config, claims), fields(user_id = %claims.sub))]
+pub async fn refresh_session(
+    State(pool): State<DbPool>,
+    State(config): State<Config>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<AuthResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let user = claimed_user(&pool, &claims).await?;
+    let server_error = |message: &str| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: message.to_string(),
+            }),
+        )
+    };
+
+    let token = encode_jwt(user.id, user.username.clone(), &config.jwt_secret, config.jwt_expiration_hours)
+        .map_err(|e| {
+            error!("[REFRESH]  JWT encoding failed: {}", e);
+            server_error("Failed to generate token")
+        })?;
+
+    let expires_at = chrono::DateTime::from_timestamp(claims.exp, 0).unwrap_or_else(chrono::Utc::now);
+    RevokedTokenRepository::revoke(&pool, &claims.revocation_id(), user.id, expires_at)
+        .await
+        .map_err(|e| {
+            error!("[REFRESH]  Failed to revoke old token: {}", e);
+            server_error("Database error")
+        })?;
+
+    debug!("[REFRESH]  Session refreshed for user {}", user.username);
+    Ok(Json(AuthResponse {
+        user: user_info(user),
+        token,
+        message: "Session refreshed".to_string(),
+        wallet_setup_required: None,
+        wallet_setup_token: None,
+    }))
+}
+
+/// Public info of `user`
+fn user_info(user: User) -> UserInfo {
+    UserInfo {
+        id: user.id.to_string(),
+        username: user.username,
+        email: user.email,
+        created_at: user.created_at.to_string(),
+        wallet_address: user.wallet_address,
+    }
+}
+
+/// Load the user a token belongs to
+async fn claimed_user(pool: &DbPool, claims: &Claims) -> Result<User, (StatusCode, Json<ErrorResponse>)> {
+    let invalid_token = || {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Invalid token".to_string(),
+            }),
+        )
+    };
+    let user_id = claims.sub.parse::<i64>().map_err(|_| invalid_token())?;
+
+    match UserRepository::find_by_id(pool, user_id).await {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => Err(invalid_token()),
+        Err(e) => {
+            error!("[ACCOUNT]  Database error loading user {}: {}", user_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Database error".to_string(),
+                }),
+            ))
+        }
+    }
+}
+
+/// Change password handler - replaces the logged-in user's password.
+///
+/// Must be mounted behind [`require_auth`](crate::middleware::require_auth).
+/// The current password is checked with [`verify_password`] before the new
+/// one is hashed. Every existing session of the user, including the one that
+/// made the request, is revoked; the response carries a fresh token to
+/// continue with.
+///
+/// # Returns
+///
+/// * `Ok(AuthResponse)` - Password changed; `token` replaces the caller's token
+/// * `Err((StatusCode::FORBIDDEN, ErrorResponse))` - Current password is wrong
+/// * `Err((StatusCode::BAD_REQUEST, ErrorResponse))` - New password is too weak
+///
+/// # Example
+///
+/// ```text
+/// PUT /api/auth/password
+/// Authorization: Bearer <token>
+/// { "current_password": "OldPassword123!", "new_password": "NewPassword456!" }
+/// Response: 200 { "user": {...}, "token": "<fresh token>", "message": "Password changed..." }
+/// ```
+#[instrument(skip(pool, config, claims, req), fields(user_id = %claims.sub))]
+pub async fn change_password(
+    State(pool): State<DbPool>,
+    State(config): State<Config>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<ChangePasswordRequest>,
+) -> Result<Json<AuthResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let user = claimed_user(&pool, &claims).await?;
+    let server_error = |message: &str| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: message.to_string(),
+            }),
+        )
+    };
+
+    match verify_password(&req.current_password, &user.password_hash) {
+        Ok(true) => {}
+        Ok(false) => {
+            warn!("[ACCOUNT]  Wrong current password for user {}", user.username);
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(ErrorResponse {
+                    error: "Current password is incorrect".to_string(),
+                }),
+            ));
+        }
+        Err(e) => {
+            error!("[ACCOUNT]  Password verification error: {}", e);
+            return Err(server_error("Authentication error"));
+        }
+    }
+
+    let password_hash = hash_password(&req.new_password).map_err(|e| {
+        warn!("[ACCOUNT]  New password rejected: {}", e);
+        (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e }))
+    })?;
+
+    // The fresh token is exempt from the revocation below
+    let token = encode_jwt(user.id, user.username.clone(), &config.jwt_secret, config.jwt_expiration_hours)
+        .map_err(|e| {
+            error!("[ACCOUNT]  JWT encoding failed: {}", e);
+            server_error("Failed to generate token")
+        })?;
+    let fresh_claims = decode_jwt(&token, &config.jwt_secret).map_err(|e| {
+        error!("[ACCOUNT]  Fresh JWT failed to decode: {}", e);
+        server_error("Failed to generate token")
+    })?;
+
+    // Revoke first: if the password update then fails, the user is logged out
+    // rather than left with sessions the change should have ended
+    RevokedTokenRepository::revoke_sessions(&pool, user.id, chrono::Utc::now(), Some(&fresh_claims.revocation_id()))
+        .await
+        .map_err(|e| {
+            error!("[ACCOUNT]  Failed to revoke sessions: {}", e);
+            server_error("Database error")
+        })?;
+    UserRepository::update_password(&pool, user.id, &password_hash)
+        .await
+        .map_err(|e| {
+            error!("[ACCOUNT]  Failed to update password: {}", e);
+            server_error("Database error")
+        })?;
+
+    info!("[ACCOUNT]  Password changed for user {}; other sessions revoked", user.username);
+    Ok(Json(AuthResponse {
+        user: user_info(user),
+        token,
+        message: "Password changed. Other sessions have been logged out".to_string(),
+        wallet_setup_required: None,
+        wallet_setup_token: None,
+    }))
+}
+
+/// Update profile handler - changes the logged-in user's email.
+///
+/// Must be mounted behind [`require_auth`](crate::middleware::require_auth).
+///
+/// # Returns
+///
+/// * `Ok(UserInfo)` - The updated user
+/// * `Err((StatusCode::BAD_REQUEST, ErrorResponse))` - Invalid email format
+/// * `Err((StatusCode::CONFLICT, ErrorResponse))` - Another account uses the email
+///
+/// # Example
+///
+/// ```text
+/// PUT /api/auth/profile
+/// Authorization: Bearer <token>
+/// { "email": "alice@new.example.com" }
+/// Response: 200 { "id": "1", "username": "alice", "email": "alice@new.example.com", ... }
+/// ```
+#[instrument(skip(pool, claims, req), fields(user_id = %claims.sub))]
+pub async fn update_profile(
+    State(pool): State<DbPool>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<UpdateProfileRequest>,
+) -> Result<Json<UserInfo>, (StatusCode, Json<ErrorResponse>)> {
+    let user = claimed_user(&pool, &claims).await?;
+    let email = req.email.trim();
+    let conflict = || {
+        (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Email already registered".to_string(),
+            }),
+        )
+    };
+    let database_error = || {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Database error".to_string(),
+            }),
+        )
+    };
+
+    if !email.contains('@') {
+        warn!("[ACCOUNT]  Invalid email format");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid email format".to_string(),
+            }),
+        ));
+    }
+
+    if email == user.email {
+        return Ok(Json(user_info(user)));
+    }
+
+    match UserRepository::find_by_email(&pool, email).await {
+        Ok(Some(_)) => {
+            warn!("[ACCOUNT]  Email already registered: {}", email);
+            return Err(conflict());
+        }
+        Ok(None) => {}
+        Err(e) => {
+            error!("[ACCOUNT]  Database error checking email: {}", e);
+            return Err(database_error());
+        }
+    }
+
+    // The UNIQUE constraint still catches a signup that took the email meanwhile
+    let updated = UserRepository::update_email(&pool, user.id, email).await.map_err(|e| {
+        if e.as_database_error().is_some_and(|db| db.is_unique_violation()) {
+            warn!("[ACCOUNT]  Email taken during update: {}", email);
+            conflict()
+        } else {
+            error!("[ACCOUNT]  Failed to update email: {}", e);
+            database_error()
+        }
+    })?;
+
+    info!("[ACCOUNT]  Email updated for user {}", updated.username);
+    Ok(Json(user_info(updated)))
+}
+
+/// Response to every password reset request, whether or not the account exists
+pub const PASSWORD_RESET_REQUESTED: &str =
+    "If an account uses that email, a password reset code has been sent to it";
+
+/// Password reset request handler - emails a one-time reset token.
+///
+/// # Returns
+///
+/// * `Ok(PasswordResetResponse)` - Always [`PASSWORD_RESET_REQUESTED`], so the
+///   endpoint can't be used to find out which emails have accounts
+/// * `Err((StatusCode, ErrorResponse))` - Database error
+///
+/// # Example
+///
+/// ```text
+/// POST /api/auth/password-reset/request
+/// { "email": "alice@example.com" }
+/// Response: 200 { "message": "If an account uses that email, ..." }
+/// ```
+#[instrument(skip(service, req))]
+pub async fn request_password_reset(
+    State(service): State<Arc<PasswordResetService>>,
+    Json(req): Json<PasswordResetRequest>,
+) -> Result<Json<PasswordResetResponse>, (StatusCode, Json<ErrorResponse>)> {
+    service.request(&req.email).await.map_err(|e| {
+        error!("[PASSWORD RESET]  Request failed: {}", e);
+        (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+    })?;
+
+    Ok(Json(PasswordResetResponse {
+        message: PASSWORD_RESET_REQUESTED.to_string(),
+    }))
+}
+
+/// Password reset confirmation handler - sets a new password with a reset token.
+///
+/// # Returns
+///
+/// * `Ok(PasswordResetResponse)` - Password changed; the token can't be used again
+/// * `Err((StatusCode::BAD_REQUEST, ErrorResponse))` - Weak password, or token
+///   unknown, already used or expired
+///
+/// # Example
+///
+/// ```text
+/// POST /api/auth/password-reset/confirm
+/// { "token": "9b4e7f2a8c5d1e3f6a9b2c5d8e1f4a7b", "new_password": "NewPassword123!" }
+/// Response: 200 { "message": "Password updated. Log in with your new password" }
+/// ```
+#[instrument(skip(service, req))]
+pub async fn confirm_password_reset(
+    State(service): State<Arc<PasswordResetService>>,
+    Json(req): Json<PasswordResetConfirmRequest>,
+) -> Result<Json<PasswordResetResponse>, (StatusCode, Json<ErrorResponse>)> {
+    service.confirm(&req.token, &req.new_password).await.map_err(|e| {
+        warn!("[PASSWORD RESET]  Confirmation rejected: {}", e);
+        (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+    })?;
+
+    Ok(Json(PasswordResetResponse {
+        message: "Password updated. Log in with your new password".to_string(),
+    }))
+}
+
 #[cfg(test)]
 mod tests;
 
@@ -0,0 +1,152 @@
+//! # Account Tests
+//!
+//! Password change and profile update through `PUT /password` and
+//! `PUT /profile` behind `require_auth`.
+
+use super::*;
+use crate::middleware::require_auth;
+use axum::body::Body;
+use axum::http::{header::AUTHORIZATION, Request, StatusCode};
+use serde::Serialize;
+
+const PASSWORD: &str = "OldPassword123!";
+const NEW_PASSWORD: &str = "NewPassword456!";
+
+/// App with the account routes and the id of a signed-up user
+async fn account_app() -> (Router, DbPool, Config, i64) {
+    let pool = setup_test_db().await;
+    create_revocation_tables(&pool).await;
+
+    let password_hash = hash_password(PASSWORD).expect("Password hashing should succeed in test");
+    let user = UserRepository::create(&pool, "testuser", "test@example.com", &password_hash)
+        .await
+        .expect("User creation should succeed in test");
+    UserRepository::create(&pool, "other", "other@example.com", &password_hash)
+        .await
+        .expect("User creation should succeed in test");
+
+    let config = test_config();
+    let state = AuthedState { pool: pool.clone(), config: config.clone() };
+    let app = Router::new()
+        .route("/password", axum::routing::put(change_password))
+        .route("/profile", axum::routing::put(update_profile))
+        .route("/me", axum::routing::get(|| async { StatusCode::OK }))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_auth))
+        .with_state(state);
+    (app, pool, config, user.id)
+}
+
+async fn send(app: &Router, method: &str, uri: &str, token: &str, body: Option<&impl Serialize>) -> (StatusCode, String) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json");
+    let body = match body {
+        Some(body) => Body::from(serde_json::to_string(body).unwrap()),
+        None => Body::empty(),
+    };
+    let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+async fn me(app: &Router, token: &str) -> StatusCode {
+    send(app, "GET", "/me", token, None::<&()>).await.0
+}
+
+fn change(current: &str, new: &str) -> ChangePasswordRequest {
+    ChangePasswordRequest {
+        current_password: current.to_string(),
+        new_password: new.to_string(),
+    }
+}
+
+async fn password_is(pool: &DbPool, user_id: i64, password: &str) -> bool {
+    let user = UserRepository::find_by_id(pool, user_id).await.unwrap().unwrap();
+    verify_password(password, &user.password_hash).unwrap()
+}
+
+#[tokio::test]
+async fn test_change_password_revokes_other_sessions() {
+    // Arrange
+    let (app, pool, config, user_id) = account_app().await;
+    let token = encode_jwt(user_id, "testuser".to_string(), &config.jwt_secret, 24).unwrap();
+    let other_session = encode_jwt(user_id, "testuser".to_string(), &config.jwt_secret, 24).unwrap();
+    let other_user = encode_jwt(user_id + 1, "other".to_string(), &config.jwt_secret, 24).unwrap();
+
+    // Act
+    let (status, body) = send(&app, "PUT", "/password", &token, Some(&change(PASSWORD, NEW_PASSWORD))).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let response: AuthResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(response.user.username, "testuser");
+    assert!(password_is(&pool, user_id, NEW_PASSWORD).await);
+
+    assert_eq!(me(&app, &response.token).await, StatusCode::OK);
+    assert_eq!(me(&app, &token).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(me(&app, &other_session).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(me(&app, &other_user).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_change_password_requires_current_password() {
+    let (app, pool, config, user_id) = account_app().await;
+    let token = encode_jwt(user_id, "testuser".to_string(), &config.jwt_secret, 24).unwrap();
+
+    let (status, body) = send(&app, "PUT", "/password", &token, Some(&change("WrongPassword1!", NEW_PASSWORD))).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body.contains("Current password is incorrect"));
+    assert!(password_is(&pool, user_id, PASSWORD).await);
+    // Nothing was revoked
+    assert_eq!(me(&app, &token).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_change_password_rejects_weak_password() {
+    let (app, pool, config, user_id) = account_app().await;
+    let token = encode_jwt(user_id, "testuser".to_string(), &config.jwt_secret, 24).unwrap();
+
+    let (status, body) = send(&app, "PUT", "/password", &token, Some(&change(PASSWORD, "short"))).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("at least 8 characters"));
+    assert!(password_is(&pool, user_id, PASSWORD).await);
+    assert_eq!(me(&app, &token).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_update_profile_changes_email() {
+    let (app, pool, config, user_id) = account_app().await;
+    let token = encode_jwt(user_id, "testuser".to_string(), &config.jwt_secret, 24).unwrap();
+    let request = UpdateProfileRequest { email: " new@example.com ".to_string() };
+
+    let (status, body) = send(&app, "PUT", "/profile", &token, Some(&request)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let user: UserInfo = serde_json::from_str(&body).unwrap();
+    assert_eq!(user.email, "new@example.com");
+    let stored = UserRepository::find_by_id(&pool, user_id).await.unwrap().unwrap();
+    assert_eq!(stored.email, "new@example.com");
+}
+
+#[tokio::test]
+async fn test_update_profile_rejects_taken_or_invalid_email() {
+    let (app, pool, config, user_id) = account_app().await;
+    let token = encode_jwt(user_id, "testuser".to_string(), &config.jwt_secret, 24).unwrap();
+
+    let taken = UpdateProfileRequest { email: "other@example.com".to_string() };
+    let (status, body) = send(&app, "PUT", "/profile", &token, Some(&taken)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(body.contains("Email already registered"));
+
+    let invalid = UpdateProfileRequest { email: "not-an-email".to_string() };
+    let (status, _) = send(&app, "PUT", "/profile", &token, Some(&invalid)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let stored = UserRepository::find_by_id(&pool, user_id).await.unwrap().unwrap();
+    assert_eq!(stored.email, "test@example.com");
+}
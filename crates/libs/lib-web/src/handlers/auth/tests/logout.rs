@@ -0,0 +1,61 @@
+//! # Logout Tests
+//!
+//! Token revocation through `POST /logout` behind `require_auth`.
+
+use super::*;
+use crate::middleware::require_auth;
+use axum::body::Body;
+use axum::http::{header::AUTHORIZATION, Request, StatusCode};
+
+async fn logout_app() -> (Router, Config) {
+    let pool = setup_test_db().await;
+    create_revocation_tables(&pool).await;
+
+    let config = test_config();
+    let state = AuthedState { pool, config: config.clone() };
+    let app = Router::new()
+        .route("/logout", axum::routing::post(logout))
+        .route("/me", axum::routing::get(|| async { StatusCode::OK }))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_auth))
+        .with_state(state);
+    (app, config)
+}
+
+async fn send(app: &Router, method: &str, uri: &str, token: &str) -> StatusCode {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_logout_revokes_token() {
+    // Arrange
+    let (app, config) = logout_app().await;
+    let token = encode_jwt(1, "testuser".to_string(), &config.jwt_secret, 24).unwrap();
+    let other_session = encode_jwt(1, "testuser".to_string(), &config.jwt_secret, 24).unwrap();
+    assert_eq!(send(&app, "GET", "/me", &token).await, StatusCode::OK);
+
+    // Act
+    let status = send(&app, "POST", "/logout", &token).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(send(&app, "GET", "/me", &token).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(send(&app, "POST", "/logout", &token).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(send(&app, "GET", "/me", &other_session).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_logout_requires_valid_token() {
+    let (app, _) = logout_app().await;
+    assert_eq!(send(&app, "POST", "/logout", "garbage").await, StatusCode::UNAUTHORIZED);
+}
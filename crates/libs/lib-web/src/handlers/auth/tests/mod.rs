@@ -1,10 +1,15 @@
 //! # Auth Handler Tests
 //!
-//! Test suite for authentication handlers (signup and login).
+//! Test suite for authentication handlers (signup, login, logout, session
+//! refresh, password reset, and password/profile changes).
 
 mod signup;
 mod login;
 mod integration;
+mod logout;
+mod refresh;
+mod password_reset;
+mod account;
 
 use super::*;
 use lib_auth::hash_password;
@@ -54,6 +59,57 @@ pub fn test_config() -> Config {
         database_url: "sqlite::memory:".to_string(),
         jwt_secret: "test-secret-key-must-be-at-least-32-characters-long!".to_string(),
         jwt_expiration_hours: 24,
+        streamed_symbols: Vec::new(),
+        admin_usernames: Vec::new(),
+    }
+}
+
+/// Create the tables `require_auth` checks for revoked tokens and sessions
+pub async fn create_revocation_tables(pool: &DbPool) {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS revoked_tokens (
+            jti TEXT PRIMARY KEY,
+            user_id INTEGER NOT NULL,
+            expires_at DATETIME NOT NULL,
+            revoked_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create revoked_tokens table");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS revoked_sessions (
+            user_id INTEGER PRIMARY KEY,
+            revoked_before DATETIME NOT NULL,
+            kept_jti TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create revoked_sessions table");
+}
+
+/// State for test routes behind `require_auth`
+#[derive(Clone)]
+pub struct AuthedState {
+    pub pool: DbPool,
+    pub config: Config,
+}
+
+impl axum::extract::FromRef<AuthedState> for DbPool {
+    fn from_ref(state: &AuthedState) -> Self {
+        state.pool.clone()
+    }
+}
+
+impl axum::extract::FromRef<AuthedState> for Config {
+    fn from_ref(state: &AuthedState) -> Self {
+        state.config.clone()
     }
 }
 
@@ -0,0 +1,177 @@
+//! # Password Reset Tests
+//!
+//! Reset request and confirmation, with a mailer that captures the emails.
+
+use super::*;
+use crate::services::mailer::{Email, Mailer};
+use async_trait::async_trait;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use lib_auth::verify_password;
+use serde::Serialize;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Hands every sent email to the test
+struct ChannelMailer(mpsc::UnboundedSender<Email>);
+
+#[async_trait]
+impl Mailer for ChannelMailer {
+    async fn send(&self, email: Email) -> anyhow::Result<()> {
+        self.0.send(email).map_err(|e| anyhow::anyhow!(e.to_string()))
+    }
+}
+
+const OLD_PASSWORD: &str = "OldPassword123!";
+const NEW_PASSWORD: &str = "NewPassword456!";
+
+async fn reset_app() -> (Router, DbPool, mpsc::UnboundedReceiver<Email>) {
+    let pool = setup_test_db().await;
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS password_reset_tokens (
+            token_hash TEXT PRIMARY KEY,
+            user_id INTEGER NOT NULL,
+            expires_at DATETIME NOT NULL,
+            used_at DATETIME,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create password_reset_tokens table");
+
+    let password_hash = hash_password(OLD_PASSWORD).expect("Password hashing should succeed in test");
+    UserRepository::create(&pool, "testuser", "test@example.com", &password_hash)
+        .await
+        .expect("User creation should succeed in test");
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    let service = Arc::new(PasswordResetService::new(pool.clone(), Arc::new(ChannelMailer(tx))));
+    let app = Router::new()
+        .route("/request", axum::routing::post(request_password_reset))
+        .route("/confirm", axum::routing::post(confirm_password_reset))
+        .with_state(service);
+    (app, pool, rx)
+}
+
+async fn post_json(app: &Router, uri: &str, body: &impl Serialize) -> (StatusCode, String) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+/// Request a reset for the test user and pull the token out of the email
+async fn requested_token(app: &Router, emails: &mut mpsc::UnboundedReceiver<Email>) -> String {
+    let request = PasswordResetRequest { email: "test@example.com".to_string() };
+    let (status, _) = post_json(app, "/request", &request).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let email = tokio::time::timeout(Duration::from_secs(1), emails.recv())
+        .await
+        .expect("Reset email should be sent")
+        .unwrap();
+    assert_eq!(email.to, "test@example.com");
+    email
+        .body
+        .lines()
+        .map(str::trim)
+        .find(|line| line.len() == 32 && line.chars().all(|c| c.is_ascii_hexdigit()))
+        .expect("Email should contain the token")
+        .to_string()
+}
+
+async fn confirm(app: &Router, token: &str, new_password: &str) -> (StatusCode, String) {
+    let request = PasswordResetConfirmRequest {
+        token: token.to_string(),
+        new_password: new_password.to_string(),
+    };
+    post_json(app, "/confirm", &request).await
+}
+
+async fn password_is(pool: &DbPool, password: &str) -> bool {
+    let user = UserRepository::find_by_email(pool, "test@example.com").await.unwrap().unwrap();
+    verify_password(password, &user.password_hash).unwrap()
+}
+
+#[tokio::test]
+async fn test_reset_changes_password_once() {
+    // Arrange
+    let (app, pool, mut emails) = reset_app().await;
+    let token = requested_token(&app, &mut emails).await;
+
+    // Act
+    let (status, _) = confirm(&app, &token, NEW_PASSWORD).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    assert!(password_is(&pool, NEW_PASSWORD).await);
+
+    // The token can't be reused
+    let (status, body) = confirm(&app, &token, "AnotherPassword789!").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("Invalid or expired reset token"));
+    assert!(password_is(&pool, NEW_PASSWORD).await);
+}
+
+#[tokio::test]
+async fn test_unknown_email_gets_same_response() {
+    let (app, _pool, mut emails) = reset_app().await;
+
+    let unknown = PasswordResetRequest { email: "nobody@example.com".to_string() };
+    let known = PasswordResetRequest { email: "test@example.com".to_string() };
+    let unknown_response = post_json(&app, "/request", &unknown).await;
+    let known_response = post_json(&app, "/request", &known).await;
+
+    assert_eq!(unknown_response, known_response);
+    assert_eq!(unknown_response.0, StatusCode::OK);
+
+    // Only the real account got an email
+    let email = tokio::time::timeout(Duration::from_secs(1), emails.recv()).await.unwrap().unwrap();
+    assert_eq!(email.to, "test@example.com");
+    assert!(emails.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_expired_token_is_rejected() {
+    let (app, pool, mut emails) = reset_app().await;
+    let token = requested_token(&app, &mut emails).await;
+
+    sqlx::query("UPDATE password_reset_tokens SET expires_at = ?")
+        .bind(chrono::Utc::now() - chrono::Duration::minutes(1))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, _) = confirm(&app, &token, NEW_PASSWORD).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(password_is(&pool, OLD_PASSWORD).await);
+}
+
+#[tokio::test]
+async fn test_weak_password_is_rejected_and_token_stays_usable() {
+    let (app, pool, mut emails) = reset_app().await;
+    let token = requested_token(&app, &mut emails).await;
+
+    let (status, body) = confirm(&app, &token, "short").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("at least 8 characters"));
+    assert!(password_is(&pool, OLD_PASSWORD).await);
+
+    let (status, _) = confirm(&app, &token, NEW_PASSWORD).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(password_is(&pool, NEW_PASSWORD).await);
+}
@@ -0,0 +1,79 @@
+//! # Session Refresh Tests
+//!
+//! Trading a valid token for a fresh one through `POST /refresh` behind
+//! `require_auth`.
+
+use super::*;
+use crate::middleware::require_auth;
+use axum::body::Body;
+use axum::http::{header::AUTHORIZATION, Request, StatusCode};
+
+async fn refresh_app() -> (Router, Config, i64) {
+    let pool = setup_test_db().await;
+    create_revocation_tables(&pool).await;
+
+    let password_hash = hash_password("Password123!").expect("Password hashing should succeed in test");
+    let user = UserRepository::create(&pool, "testuser", "test@example.com", &password_hash)
+        .await
+        .expect("User creation should succeed in test");
+
+    let config = test_config();
+    let state = AuthedState { pool, config: config.clone() };
+    let app = Router::new()
+        .route("/refresh", axum::routing::post(refresh_session))
+        .route("/me", axum::routing::get(|| async { StatusCode::OK }))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_auth))
+        .with_state(state);
+    (app, config, user.id)
+}
+
+async fn send(app: &Router, method: &str, uri: &str, token: &str) -> (StatusCode, String) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_refresh_issues_fresh_token_and_revokes_old() {
+    // Arrange
+    let (app, config, user_id) = refresh_app().await;
+    let token = encode_jwt(user_id, "testuser".to_string(), &config.jwt_secret, 1).unwrap();
+    let other_session = encode_jwt(user_id, "testuser".to_string(), &config.jwt_secret, 1).unwrap();
+
+    // Act
+    let (status, body) = send(&app, "POST", "/refresh", &token).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let response: AuthResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(response.user.username, "testuser");
+    let old = decode_jwt(&token, &config.jwt_secret).unwrap();
+    let fresh = decode_jwt(&response.token, &config.jwt_secret).unwrap();
+    assert!(fresh.exp > old.exp);
+    assert_ne!(fresh.jti, old.jti);
+
+    assert_eq!(send(&app, "GET", "/me", &response.token).await.0, StatusCode::OK);
+    assert_eq!(send(&app, "GET", "/me", &token).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(send(&app, "POST", "/refresh", &token).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(send(&app, "GET", "/me", &other_session).await.0, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_refresh_rejects_deleted_user() {
+    let (app, config, user_id) = refresh_app().await;
+    let token = encode_jwt(user_id + 1, "ghost".to_string(), &config.jwt_secret, 1).unwrap();
+
+    assert_eq!(send(&app, "POST", "/refresh", &token).await.0, StatusCode::UNAUTHORIZED);
+}
@@ -250,3 +250,41 @@ async fn test_signup_password_too_short() {
     assert_eq!(error_response.error, "Password must be at least 8 characters long");
 }
 
+#[tokio::test]
+async fn test_signup_reports_all_invalid_fields() {
+    // Arrange
+    let pool = setup_test_db().await;
+    let config = test_config();
+    let app = test_app(pool, config);
+
+    let signup_req = SignupRequest {
+        username: "ab".to_string(),
+        email: "alice@localhost".to_string(),
+        password: "password".to_string(), // No digit
+    };
+
+    // Act
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/signup")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&signup_req).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error_response: shared::validation::ValidationErrorResponse = serde_json::from_slice(&body).unwrap();
+
+    let fields: Vec<&str> = error_response.fields.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(fields, ["username", "email", "password"]);
+    assert_eq!(error_response.error, "Username must be at least 3 characters");
+}
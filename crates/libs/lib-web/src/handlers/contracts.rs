@@ -4,7 +4,8 @@
 //!
 //! This module provides HTTP handlers for interacting with Solana contract plugins.
 //! It includes generic contract management endpoints (list, metadata, health) and
-//! contract-specific endpoints (batch swap, execute swap).
+//! contract-specific endpoints (batch swap, execute swap), plus the deployment
+//! history recorded by the program monitor.
 
 use axum::{
     extract::State,
@@ -14,6 +15,7 @@ use serde::Serialize;
 use std::sync::Arc;
 use tracing::instrument;
 use lib_solana::contracts::ContractRegistry;
+use crate::services::ProgramMonitor;
 
 /// Contract route handlers
 ///
@@ -111,3 +113,32 @@ pub async fn get_metadata_handler(
     Ok(Json(plugin.metadata()))
 }
 
+
+/// Number of recorded versions returned per monitored program
+const PROGRAM_HISTORY_LIMIT: usize = 20;
+
+#[derive(Serialize)]
+pub struct MonitoredProgram {
+    program_id: String,
+    history: Vec<lib_core::model::store::models::ProgramVersion>,
+}
+
+#[derive(Serialize)]
+pub struct MonitoredProgramsResponse {
+    programs: Vec<MonitoredProgram>,
+}
+
+/// List monitored programs with their recorded deployment history (newest first).
+#[instrument(skip(monitor))]
+pub async fn list_monitored_programs_handler(
+    State(monitor): State<Arc<ProgramMonitor>>,
+) -> Result<Json<MonitoredProgramsResponse>, lib_core::AppError> {
+    let mut programs = Vec::with_capacity(monitor.program_ids().len());
+    for program_id in monitor.program_ids() {
+        programs.push(MonitoredProgram {
+            program_id: program_id.to_string(),
+            history: monitor.history(program_id, PROGRAM_HISTORY_LIMIT).await?,
+        });
+    }
+    Ok(Json(MonitoredProgramsResponse { programs }))
+}
@@ -16,6 +16,8 @@ use serde::Deserialize;
 use lib_core::dto::messaging::*;
 use lib_auth::decode_jwt;
 use lib_core::{Config, DbPool};
+use crate::chat::db as chat_db;
+use crate::chat::handlers::utils::compute_conversation_id;
 use tracing::instrument;
 
 /// Helper to extract user ID from JWT token
@@ -273,7 +275,6 @@ pub async fn get_friends(
         friend_id: i64,
         username: String,
         last_message_at: Option<String>,
-        unread_count: i32,
     }
     
     let friends = sqlx::query_as::<_, FriendRow>(
@@ -285,11 +286,7 @@ pub async fn get_friends(
                 ELSE f.sender_id
             END as friend_id,
             u.username,
-            cs.last_message_at,
-            CASE
-                WHEN f.sender_id = ? THEN cs.user2_unread_count
-                ELSE cs.user1_unread_count
-            END as unread_count
+            cs.last_message_at
         FROM friendships f
         JOIN users u ON u.id = CASE 
             WHEN f.sender_id = ? THEN f.receiver_id
@@ -308,18 +305,24 @@ pub async fn get_friends(
     .bind(user_id)
     .bind(user_id)
     .bind(user_id)
-    .bind(user_id)
     .fetch_all(&db)
     .await
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
     
+    // Messages past each conversation's read marker
+    let unread_counts = chat_db::unread_counts(&db, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
+    
     let friends: Vec<Friend> = friends.into_iter().map(|row| {
         Friend {
             id: row.friendship_id,
             user_id: row.friend_id,
             username: row.username,
             friendship_id: row.friendship_id,
-            unread_count: row.unread_count,
+            unread_count: unread_counts
+                .get(&compute_conversation_id(user_id, row.friend_id))
+                .map_or(0, |&count| count as i32),
             last_message_at: row.last_message_at,
             last_message_preview: None, // TODO: Add last message preview
         }
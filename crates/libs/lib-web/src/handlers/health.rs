@@ -0,0 +1,47 @@
+//! # Health Handlers
+//!
+//! Backend health report used by the terminal's status bar and health widget.
+//!
+//! ## Endpoints
+//!
+//! - `GET /api/health` - Per-dependency report (database, Solana RPC, Jupiter,
+//!   Pyth, price stream) with probe latency plus uptime, and hit/miss
+//!   counters of the market response caches
+//!
+//! ## Authentication
+//!
+//! This endpoint is public so clients can check the backend before logging in.
+//!
+//! ## Status Codes
+//!
+//! The report is returned with `200 OK` while the backend can serve requests
+//! (`ok` or `degraded`) and with `503 Service Unavailable` when it is `down`,
+//! so load balancers can use it without parsing the body. The plain-text
+//! `GET /health` liveness check is unchanged.
+
+use crate::services::HealthService;
+use axum::{extract::State, http::StatusCode, Json};
+use shared::dto::system::{HealthResponse, HealthStatus};
+use std::sync::Arc;
+use tracing::{debug, instrument};
+
+/// Get the backend health report.
+///
+/// **Route**: `GET /api/health`
+///
+/// # Example
+///
+/// ```bash
+/// curl http://localhost:3001/api/health
+/// ```
+#[instrument(skip(health))]
+pub async fn get_health(State(health): State<Arc<HealthService>>) -> (StatusCode, Json<HealthResponse>) {
+    let report = health.check().await;
+    debug!("[HEALTH] status={:?} uptime={}s", report.status, report.uptime_secs);
+
+    let status = match report.status {
+        HealthStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+        HealthStatus::Ok | HealthStatus::Degraded => StatusCode::OK,
+    };
+    (status, Json(report))
+}
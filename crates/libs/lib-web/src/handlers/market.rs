@@ -7,6 +7,11 @@
 //! - `GET /api/market/prices` - Get real-time prices for Solana tokens
 //! - `GET /api/market/tokens` - Get list of available tokens with metadata
 //! - `GET /api/market/candles` - Get OHLC candlestick data for charting
+//! - `GET /api/market/candles/batch` - Candles for up to 20 symbols in one request
+//! - `GET /api/market/depth` - Get effective price at a ladder of trade sizes
+//! - `GET /api/market/analytics` - Volatility and return correlations from daily candles
+//! - `GET /api/market/streamed-symbols` - List symbols on the price stream
+//! - `GET /api/market/unlocks` - Upcoming token unlocks with their size relative to supply
 //!
 //! ## Authentication
 //!
@@ -20,6 +25,9 @@
 //!
 //! # Get full token list
 //! curl http://localhost:3001/api/market/tokens
+//!
+//! # Download the token list again (admins only)
+//! curl -H "Authorization: Bearer $TOKEN" "http://localhost:3001/api/market/tokens?force_refresh=true"
 //! ```
 //!
 //! ## Data Sources
@@ -27,12 +35,21 @@
 //! - Price data is cached from Solana price feeds and Jupiter aggregator
 //! - Token metadata is fetched from Jupiter token list
 //! - Prices are refreshed periodically by the price cache service
+//! - Prices, the token list and candles are cached as whole responses
+//!   (~2s, ~1h and ~30s by default; see `MARKET_CACHE_*_TTL_MS`) and say so
+//!   with `cached` and `age_ms`
+//! - Depth ladders are built from Jupiter route quotes and cached for ~10 seconds
 
 use crate::services::market::MarketService;
+use crate::services::token_unlocks::DEFAULT_UNLOCK_WINDOW_DAYS;
+use crate::services::{AnalyticsService, DepthService, StreamedSymbolService, TokenUnlockService};
 use lib_solana::{SolanaState, types::PriceQuery, candle_aggregator::Timeframe};
 use lib_solana::price_stream::PriceStreamServer;
-use axum::{extract::{Query, State}, http::StatusCode, Json};
+use axum::{extract::{Query, State}, http::{HeaderMap, HeaderName, StatusCode}, Json};
 use lib_core::dto::{ErrorResponse, market::OHLC};
+use lib_core::{Config, Metrics};
+use shared::dto::market::{CandleBatchResponse, DepthResponse, MarketAnalyticsResponse, StreamedSymbolsResponse, TokenListResponse};
+use shared::dto::unlocks::TokenUnlocksResponse;
 use serde::Deserialize;
 use std::sync::Arc;
 use tracing::{debug, error, info, warn, instrument};
@@ -51,6 +68,11 @@ use tracing::{debug, error, info, warn, instrument};
 /// - `price`: Current price in USD
 /// - `source`: Price data source (e.g., "jupiter", "pyth")
 /// - `last_updated`: Timestamp of last price update
+/// - `sources`: Price each source reported, when more than one answered
+/// - `divergent`: Present and `true` when the sources disagree past the threshold
+///
+/// Alongside `prices`, `cached` says whether the response came from the
+/// backend's cache and `age_ms` how old it is.
 ///
 /// Error (404): Token not found or no prices available
 /// Error (500): Internal server error fetching prices
@@ -67,26 +89,30 @@ use tracing::{debug, error, info, warn, instrument};
 ///   "prices": {
 ///     "SOL": {
 ///       "price": 24.50,
-///       "source": "jupiter",
-///       "last_updated": "2025-10-25T12:00:00Z"
+///       "source": "pyth",
+///       "last_updated": "2025-10-25T12:00:00Z",
+///       "sources": { "jupiter": 24.52, "pyth": 24.50 }
 ///     },
 ///     "USDC": {
 ///       "price": 1.00,
 ///       "source": "jupiter",
 ///       "last_updated": "2025-10-25T12:00:00Z"
 ///     }
-///   }
+///   },
+///   "cached": true,
+///   "age_ms": 640
 /// }
 /// ```
-#[instrument(skip(solana), fields(symbols = %params.symbols))]
+#[instrument(skip(solana, metrics), fields(symbols = %params.symbols))]
 pub async fn get_prices(
     State(solana): State<Arc<SolanaState>>,
+    State(metrics): State<Arc<Metrics>>,
     Query(params): Query<PriceQuery>,
 ) -> Result<(StatusCode, Json<lib_solana::types::PriceResponse>), (StatusCode, Json<ErrorResponse>)> {
     info!("[MARKET] Symbols: {}", params.symbols);
     
     let symbols: Vec<&str> = params.symbols.split(',').collect();
-    let service = MarketService::new(solana);
+    let service = MarketService::new(solana).with_metrics(metrics);
     
     let response = service.get_prices(&symbols).await.map_err(|e| {
         error!("[MARKET] Failed to get prices: {}", e);
@@ -104,24 +130,43 @@ pub async fn get_prices(
     Ok((StatusCode::OK, Json(response)))
 }
 
+/// Query parameters for the token list endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct TokenListQuery {
+    /// Download the list from Jupiter again first (admins only)
+    #[serde(default)]
+    pub force_refresh: bool,
+}
+
 /// Get list of available tokens with metadata.
 ///
 /// **Route**: `GET /api/market/tokens`
 ///
 /// # Parameters
 ///
-/// None
+/// - `force_refresh` (query, optional) - Download the list from Jupiter
+///   again before answering. Requires an admin JWT
+///   (`Authorization: Bearer <token>`)
 ///
 /// # Returns
 ///
-/// Success (200): `Json<Vec<TokenInfo>>` - Array of token metadata including:
-/// - `address`: Token mint address on Solana
+/// Success (200): `Json<TokenListResponse>` - Token metadata including:
+/// - `mint`: Token mint address on Solana
 /// - `symbol`: Token trading symbol (e.g., "SOL", "USDC")
 /// - `name`: Full token name
 /// - `decimals`: Number of decimal places
-/// - `logoURI`: URL to token logo image (optional)
+/// - `logo_uri`: URL to token logo image (optional)
+/// - `tags`: Jupiter tags (e.g., "verified", "lst")
+/// - `verified`: Whether Jupiter has reviewed the token
+///
+/// The list is kept on disk across restarts and revalidated with Jupiter
+/// once a day in the background; the response is cached for an hour
+/// (`cached` and `age_ms` report which). If Jupiter can't be reached the
+/// last list is served, and with nothing cached yet an empty list is
+/// returned so the terminal keeps working.
 ///
-/// Error (500): Failed to fetch token list from Jupiter
+/// Error (401/403): `force_refresh` without an admin JWT
+/// Error (502): `force_refresh` when Jupiter can't be reached
 ///
 /// # Example
 ///
@@ -131,40 +176,66 @@ pub async fn get_prices(
 ///
 /// Response:
 /// ```json
-/// [
-///   {
-///     "address": "So11111111111111111111111111111111111111112",
-///     "symbol": "SOL",
-///     "name": "Wrapped SOL",
-///     "decimals": 9,
-///     "logoURI": "https://raw.githubusercontent.com/solana-labs/token-list/main/assets/mainnet/So11111111111111111111111111111111111111112/logo.png"
-///   },
-///   {
-///     "address": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
-///     "symbol": "USDC",
-///     "name": "USD Coin",
-///     "decimals": 6,
-///     "logoURI": "https://raw.githubusercontent.com/solana-labs/token-list/main/assets/mainnet/EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v/logo.png"
-///   }
-/// ]
+/// {
+///   "tokens": [
+///     {
+///       "symbol": "USDC",
+///       "name": "USD Coin",
+///       "mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+///       "decimals": 6,
+///       "logo_uri": "https://raw.githubusercontent.com/solana-labs/token-list/main/assets/mainnet/EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v/logo.png",
+///       "tags": ["verified", "strict"],
+///       "verified": true
+///     },
+///     {
+///       "symbol": "USDC",
+///       "name": "USD Coin",
+///       "mint": "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU",
+///       "decimals": 6,
+///       "tags": [],
+///       "verified": false
+///     }
+///   ],
+///   "cached": false,
+///   "age_ms": 0
+/// }
 /// ```
-#[instrument(skip(solana))]
+#[instrument(skip(solana, metrics, config, headers))]
 pub async fn get_token_list(
     State(solana): State<Arc<SolanaState>>,
-) -> Result<(StatusCode, Json<Vec<lib_solana::jupiter::TokenInfo>>), (StatusCode, Json<ErrorResponse>)> {
+    State(metrics): State<Arc<Metrics>>,
+    State(config): State<Config>,
+    headers: HeaderMap,
+    Query(query): Query<TokenListQuery>,
+) -> Result<(StatusCode, Json<TokenListResponse>), (StatusCode, Json<ErrorResponse>)> {
     info!("[MARKET] Token list request");
     
-    let service = MarketService::new(solana);
+    let service = MarketService::new(solana).with_metrics(metrics);
+    if query.force_refresh {
+        let admin = crate::handlers::admin::require_admin(&headers, &config)?;
+        info!("[MARKET] {} forced a token list refresh", admin);
+        service.force_refresh_token_list().await.map_err(|e| {
+            warn!("[MARKET] Forced token list refresh failed: {}", e);
+            (StatusCode::BAD_GATEWAY, Json(ErrorResponse {
+                error: "Jupiter could not be reached; the cached token list is kept".to_string(),
+            }))
+        })?;
+    }
     match service.get_token_list().await {
-        Ok(tokens) => {
-            info!("[MARKET] Fetched {} tokens", tokens.len());
-            Ok((StatusCode::OK, Json(tokens)))
+        Ok(response) => {
+            info!(
+                "[MARKET] Serving {} tokens ({} verified, cached: {})",
+                response.tokens.len(),
+                response.tokens.iter().filter(|t| t.verified).count(),
+                response.cached
+            );
+            Ok((StatusCode::OK, Json(response)))
         }
         Err(e) => {
             warn!("[MARKET] Failed to fetch token list: {}. Returning empty list.", e);
             // Return empty list instead of error to allow frontend to work
             // Frontend can handle empty token list gracefully
-            Ok((StatusCode::OK, Json(Vec::new())))
+            Ok((StatusCode::OK, Json(TokenListResponse { tokens: Vec::new(), cached: false, age_ms: 0 })))
         }
     }
 }
@@ -186,7 +257,7 @@ fn default_candle_limit() -> usize {
 }
 
 /// Parse timeframe string to enum
-fn parse_timeframe(s: &str) -> Result<Timeframe, String> {
+pub(crate) fn parse_timeframe(s: &str) -> Result<Timeframe, String> {
     match s.to_lowercase().as_str() {
         "1m" => Ok(Timeframe::OneMinute),
         "5m" => Ok(Timeframe::FiveMinutes),
@@ -212,6 +283,10 @@ fn parse_timeframe(s: &str) -> Result<Timeframe, String> {
 ///
 /// Success (200): `Json<Vec<OHLC>>` - Array of OHLC candles in chronological order (oldest first)
 ///
+/// Candles are cached per symbol, timeframe and limit. As the body is a bare
+/// array, the cache state is reported in headers: `x-cache` (`hit` or `miss`)
+/// and `x-cache-age-ms`.
+///
 /// Error (400): Invalid timeframe or missing symbol
 /// Error (404): No candles available for symbol
 /// Error (500): Internal server error
@@ -243,11 +318,12 @@ fn parse_timeframe(s: &str) -> Result<Timeframe, String> {
 ///   }
 /// ]
 /// ```
-#[instrument(skip(price_stream), fields(symbol = %params.symbol, timeframe = ?params.timeframe))]
+#[instrument(skip(solana, price_stream), fields(symbol = %params.symbol, timeframe = ?params.timeframe))]
 pub async fn get_candles(
+    State(solana): State<Arc<SolanaState>>,
     State(price_stream): State<Arc<PriceStreamServer>>,
     Query(params): Query<CandleQuery>,
-) -> Result<(StatusCode, Json<Vec<OHLC>>), (StatusCode, Json<ErrorResponse>)> {
+) -> Result<(StatusCode, [(HeaderName, String); 2], Json<Vec<OHLC>>), (StatusCode, Json<ErrorResponse>)> {
     debug!(
         symbol = %params.symbol,
         timeframe = %params.timeframe,
@@ -269,54 +345,277 @@ pub async fn get_candles(
         )
     })?;
     
-    // Limit maximum candles
-    let limit = params.limit.min(500);
-    
-    // Get candles from aggregator
+    let service = MarketService::new(solana);
     let aggregator = price_stream.candle_aggregator();
-    let candles = aggregator.get_candles(&params.symbol, timeframe, limit).await;
-    
-    debug!(
-        symbol = %params.symbol,
-        timeframe = %params.timeframe,
-        requested_limit = params.limit,
-        actual_limit = limit,
-        candle_count = candles.len(),
-        "Candles retrieved from aggregator"
-    );
-    
-    if candles.is_empty() {
-        warn!(
-            symbol = %params.symbol,
-            timeframe = %params.timeframe,
-            "No candles available for symbol"
-        );
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("No candles available for symbol: {}", params.symbol),
-            }),
-        ));
-    }
-    
-    // Convert internal Candle to shared OHLC
-    let ohlc_data: Vec<OHLC> = candles
-        .into_iter()
-        .map(|c| OHLC {
-            timestamp: c.timestamp as i64,
-            open: c.open,
-            high: c.high,
-            low: c.low,
-            close: c.close,
-            volume: c.volume,
-        })
-        .collect();
-    
+    let ohlc_data = service
+        .get_candles(&aggregator, &params.symbol, timeframe, params.limit)
+        .await
+        .map_err(|e| (e.status_code(), Json(ErrorResponse { error: e.user_message() })))?;
+
     info!(
         symbol = %params.symbol,
         timeframe = %params.timeframe,
-        count = ohlc_data.len(),
+        count = ohlc_data.value.len(),
+        cached = ohlc_data.cached,
         "Returning candles to client"
     );
-    Ok((StatusCode::OK, Json(ohlc_data)))
+    let headers = [
+        (HeaderName::from_static("x-cache"), if ohlc_data.cached { "hit" } else { "miss" }.to_string()),
+        (HeaderName::from_static("x-cache-age-ms"), ohlc_data.age_ms().to_string()),
+    ];
+    Ok((StatusCode::OK, headers, Json(ohlc_data.value)))
+}
+
+/// Query parameters for the candle batch endpoint
+#[derive(Debug, Deserialize)]
+pub struct CandleBatchQuery {
+    /// Comma-separated token symbols (e.g., "SOL,BONK,JUP")
+    pub symbols: String,
+    /// Timeframe: "1m", "5m", "15m", "1h", "4h", "1d"
+    pub timeframe: String,
+    /// Maximum number of candles per symbol (default: 100)
+    #[serde(default = "default_candle_limit")]
+    pub limit: usize,
+}
+
+/// Get OHLC candlestick data for several tokens in one request.
+///
+/// **Route**: `GET /api/market/candles/batch`
+///
+/// # Parameters
+///
+/// - `symbols` (query, required) - Comma-separated token symbols, at most 20
+/// - `timeframe` (query, required) - Candle timeframe: "1m", "5m", "15m", "1h", "4h", "1d"
+/// - `limit` (query, optional) - Maximum number of candles per symbol (default: 100, max: 500)
+///
+/// # Returns
+///
+/// Success (200): `Json<CandleBatchResponse>` - Candles per symbol, oldest
+/// first. Symbols are fetched concurrently and share the single-symbol
+/// endpoint's cache; one without candles is reported under `errors` instead
+/// of failing the request.
+///
+/// Error (400): Invalid timeframe, no symbols, or more than 20 symbols
+///
+/// # Example
+///
+/// ```bash
+/// curl "http://localhost:3001/api/market/candles/batch?symbols=SOL,BONK,JUP&timeframe=1h&limit=48"
+/// ```
+#[instrument(skip(solana, price_stream), fields(symbols = %params.symbols, timeframe = %params.timeframe))]
+pub async fn get_candles_batch(
+    State(solana): State<Arc<SolanaState>>,
+    State(price_stream): State<Arc<PriceStreamServer>>,
+    Query(params): Query<CandleBatchQuery>,
+) -> Result<(StatusCode, Json<CandleBatchResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let timeframe = parse_timeframe(&params.timeframe)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+
+    let service = MarketService::new(solana);
+    let aggregator = price_stream.candle_aggregator();
+    let response = service
+        .get_candles_batch(&aggregator, &params.symbols, timeframe, params.limit)
+        .await
+        .map_err(|e| {
+            warn!("[MARKET] Candle batch request failed: {}", e);
+            (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+        })?;
+
+    info!(
+        "[MARKET] Returning {} candle series ({} without candles)",
+        response.candles.len(),
+        response.errors.len()
+    );
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Query parameters for depth endpoint
+#[derive(Debug, Deserialize)]
+pub struct DepthQuery {
+    /// Input token symbol (e.g., "SOL")
+    pub input: String,
+    /// Output token symbol (e.g., "USDC")
+    pub output: String,
+}
+
+/// Get a depth ladder for a token pair from Jupiter route quotes.
+///
+/// **Route**: `GET /api/market/depth`
+///
+/// # Parameters
+///
+/// - `input` (query, required) - Input token symbol (e.g., "SOL")
+/// - `output` (query, required) - Output token symbol (e.g., "USDC")
+///
+/// # Returns
+///
+/// Success (200): `Json<DepthResponse>` - One rung per ladder size (0.1 to 1000
+/// input units) with the effective price and price impact. Rungs Jupiter could
+/// not quote carry an `error` instead of failing the response.
+///
+/// Error (400): Unknown token symbol or identical input/output
+///
+/// # Example
+///
+/// ```bash
+/// curl "http://localhost:3001/api/market/depth?input=SOL&output=USDC"
+/// ```
+#[instrument(skip(depth), fields(input = %params.input, output = %params.output))]
+pub async fn get_depth(
+    State(depth): State<Arc<DepthService>>,
+    Query(params): Query<DepthQuery>,
+) -> Result<(StatusCode, Json<DepthResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let response = depth.get_depth(&params.input, &params.output).await.map_err(|e| {
+        warn!("[MARKET] Depth request failed: {}", e);
+        (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+    })?;
+
+    let failed = response.rungs.iter().filter(|r| r.error.is_some()).count();
+    info!(
+        "[MARKET] Returning depth for {}/{} ({} rungs, {} failed)",
+        response.input,
+        response.output,
+        response.rungs.len(),
+        failed
+    );
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Query parameters for analytics endpoint
+#[derive(Debug, Deserialize)]
+pub struct AnalyticsQuery {
+    /// Comma-separated token symbols (e.g., "SOL,BONK,JUP")
+    pub symbols: String,
+    /// Window in days (default: 30)
+    pub window: Option<usize>,
+}
+
+/// Get annualized volatility per symbol and the correlation matrix of their daily returns.
+///
+/// **Route**: `GET /api/market/analytics`
+///
+/// # Parameters
+///
+/// - `symbols` (query, required) - Comma-separated token symbols, at most 20
+/// - `window` (query, optional) - Window in days, 7 to 365 (default: 30)
+///
+/// # Returns
+///
+/// Success (200): `Json<MarketAnalyticsResponse>` - Volatility per symbol (in
+/// request order) and `correlations[i][j]` between symbols `i` and `j`.
+/// Symbols with fewer daily candles than the window are flagged with
+/// `insufficient_history` and have no volatility or correlations.
+///
+/// Error (400): No symbols, too many symbols, or window out of range
+///
+/// # Example
+///
+/// ```bash
+/// curl "http://localhost:3001/api/market/analytics?symbols=SOL,BONK&window=30"
+/// ```
+#[instrument(skip(analytics), fields(symbols = %params.symbols, window = ?params.window))]
+pub async fn get_analytics(
+    State(analytics): State<Arc<AnalyticsService>>,
+    Query(params): Query<AnalyticsQuery>,
+) -> Result<(StatusCode, Json<MarketAnalyticsResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let response = analytics.get_analytics(&params.symbols, params.window).await.map_err(|e| {
+        warn!("[MARKET] Analytics request failed: {}", e);
+        (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+    })?;
+
+    let flagged = response.symbols.iter().filter(|s| s.insufficient_history).count();
+    info!(
+        "[MARKET] Returning analytics for {} symbols over {} days ({} with insufficient history)",
+        response.symbols.len(),
+        response.window_days,
+        flagged
+    );
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// List the symbols currently on the price stream.
+///
+/// **Route**: `GET /api/market/streamed-symbols`
+///
+/// Clients should use this list instead of assuming which symbols stream;
+/// admins change it at runtime via `/api/admin/streamed-symbols`.
+///
+/// # Example
+///
+/// ```bash
+/// curl http://localhost:3001/api/market/streamed-symbols
+/// ```
+///
+/// Response:
+/// ```json
+/// {
+///   "symbols": [
+///     { "symbol": "SOL", "mint": "So11111111111111111111111111111111111111112", "require_pyth": false }
+///   ]
+/// }
+/// ```
+#[instrument(skip(service))]
+pub async fn get_streamed_symbols(
+    State(service): State<Arc<StreamedSymbolService>>,
+) -> Result<(StatusCode, Json<StreamedSymbolsResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let symbols = service.list().await.map_err(|e| {
+        error!("[MARKET] Failed to list streamed symbols: {}", e);
+        (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+    })?;
+
+    debug!("[MARKET] Returning {} streamed symbols", symbols.len());
+    Ok((StatusCode::OK, Json(StreamedSymbolsResponse { symbols })))
+}
+
+/// Query parameters for the unlocks endpoint
+#[derive(Debug, Deserialize)]
+pub struct UnlocksQuery {
+    /// Days ahead to include (default: 90)
+    pub days: Option<i64>,
+}
+
+/// List upcoming token unlocks.
+///
+/// **Route**: `GET /api/market/unlocks`
+///
+/// # Parameters
+///
+/// - `days` (query, optional) - Days ahead to include, 1 to 365 (default: 90)
+///
+/// # Returns
+///
+/// Success (200): `Json<TokenUnlocksResponse>` - Unlocks soonest first;
+/// `pct_of_circulating` is set when the circulating supply is known
+///
+/// # Example
+///
+/// ```bash
+/// curl "http://localhost:3001/api/market/unlocks?days=30"
+/// ```
+///
+/// Response:
+/// ```json
+/// {
+///   "unlocks": [
+///     { "id": 12, "mint": "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN", "symbol": "JUP",
+///       "unlock_at": 1740787200, "amount": 53472222.0, "circulating_supply": 1350000000.0,
+///       "pct_of_circulating": 3.96, "allocation": "Team", "source": "import" }
+///   ]
+/// }
+/// ```
+#[instrument(skip(service))]
+pub async fn get_unlocks(
+    State(service): State<Arc<TokenUnlockService>>,
+    Query(params): Query<UnlocksQuery>,
+) -> Result<(StatusCode, Json<TokenUnlocksResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let response = service
+        .upcoming(params.days.unwrap_or(DEFAULT_UNLOCK_WINDOW_DAYS))
+        .await
+        .map_err(|e| {
+            error!("[MARKET] Failed to list token unlocks: {}", e);
+            (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+        })?;
+
+    debug!("[MARKET] Returning {} token unlocks", response.unlocks.len());
+    Ok((StatusCode::OK, Json(response)))
 }
@@ -0,0 +1,70 @@
+//! # Metrics Handler
+//!
+//! Prometheus scrape endpoint for operating the backend.
+//!
+//! ## Endpoints
+//!
+//! - `GET /metrics` - Every metric of the registry in the Prometheus text
+//!   format: HTTP requests by route and status with their duration, Jupiter
+//!   and Pyth call durations and failures, price WebSocket clients and
+//!   database pool usage
+//!
+//! ## Authentication
+//!
+//! None, like `/api/health`. The metrics hold no user data, but route
+//! latencies and upstream error rates are nobody else's business: keep the
+//! path off the public proxy.
+//!
+//! ## Scrape Config
+//!
+//! ```yaml
+//! scrape_configs:
+//!   - job_name: xforce-backend
+//!     static_configs:
+//!       - targets: ["localhost:3001"]
+//! ```
+
+use axum::{
+    extract::State,
+    http::header,
+    response::IntoResponse,
+};
+use lib_core::metrics::{DB_POOL_CONNECTIONS, WEBSOCKET_CLIENTS};
+use lib_core::{DbPool, Metrics};
+use lib_solana::PriceStreamServer;
+use std::sync::Arc;
+use tracing::instrument;
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Render the metrics registry.
+///
+/// **Route**: `GET /metrics`
+///
+/// Gauges (WebSocket clients, pool connections) are sampled at scrape time.
+///
+/// # Example
+///
+/// ```bash
+/// curl http://localhost:3001/metrics
+/// ```
+#[instrument(skip_all)]
+pub async fn get_metrics(
+    State(metrics): State<Arc<Metrics>>,
+    State(price_stream): State<Arc<PriceStreamServer>>,
+    State(db): State<DbPool>,
+) -> impl IntoResponse {
+    metrics.set(&WEBSOCKET_CLIENTS, &[], price_stream.client_count() as f64);
+    record_pool_usage(&metrics, &db);
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], metrics.render())
+}
+
+/// Sample the connections of the database pool
+fn record_pool_usage(metrics: &Metrics, db: &DbPool) {
+    let idle = db.num_idle();
+    let open = db.size() as usize;
+    metrics.set(&DB_POOL_CONNECTIONS, &[("state", "idle")], idle as f64);
+    metrics.set(&DB_POOL_CONNECTIONS, &[("state", "in_use")], open.saturating_sub(idle) as f64);
+    metrics.set(&DB_POOL_CONNECTIONS, &[("state", "max")], db.options().get_max_connections() as f64);
+}
@@ -11,16 +11,37 @@
 //! - **[`auth`]**: User authentication endpoints (signup, login)
 //!   - `POST /api/auth/signup` - Create new user account
 //!   - `POST /api/auth/login` - Authenticate with email/password
+//!   - `POST /api/auth/refresh` - Trade a still-valid token for a fresh one
 //!
 //! - **[`wallet_auth`]**: Wallet-based authentication endpoints
 //!   - `GET /api/wallet/setup/validate` - Validate wallet setup token
 //!   - `POST /api/wallet/setup/complete` - Complete wallet setup with signature
 //!   - `POST /api/wallet/login` - Authenticate with wallet signature
 //!
+//! - **[`admin`]**: Operator endpoints (admin users only)
+//!   - `POST /api/admin/streamed-symbols` - Add a symbol to the price stream
+//!   - `DELETE /api/admin/streamed-symbols/{symbol}` - Remove a streamed symbol
+//!   - `POST /api/admin/token-unlocks` - Add or correct a token unlock
+//!   - `PUT /api/admin/token-unlocks/{id}` - Replace a token unlock
+//!   - `DELETE /api/admin/token-unlocks/{id}` - Remove a token unlock
+//!   - `POST /api/admin/replay-candles` - Diff served candles against the tick journal
+//!   - `POST /api/admin/maintenance` - Announce scheduled downtime to connected clients
+//!
 //! - **[`market`]**: Market data endpoints (prices, token lists, charts)
 //!   - `GET /api/market/prices` - Get token prices
 //!   - `GET /api/market/tokens` - Get available tokens
 //!   - `GET /api/market/ohlc` - Get OHLC chart data
+//!   - `GET /api/market/candles/batch` - OHLC chart data for several symbols
+//!   - `GET /api/market/unlocks` - Upcoming token unlocks
+//!
+//! - **[`onramp`]**: Fiat on-ramp providers
+//!   - `GET /api/onramp/providers` - Providers for the "Buy SOL" dialog
+//!
+//! - **[`health`]**: Backend health report
+//!   - `GET /api/health` - Database, RPC and Jupiter reachability, uptime
+//!
+//! - **[`metrics`]**: Prometheus scrape endpoint (no auth)
+//!   - `GET /metrics` - Request, upstream, WebSocket and database pool metrics
 //!
 //! - **[`wallet`]**: Wallet query endpoints
 //!   - `GET /api/wallet/balance` - Get SOL balance
@@ -29,15 +50,49 @@
 //! - **[`swap`]**: Token swap operation endpoints
 //!   - `GET /api/swap/quote` - Get swap quote from Jupiter
 //!   - `POST /api/swap/execute` - Get unsigned swap transaction
-//!   - `GET /api/swap/history` - Get user's swap history
+//!   - `GET /api/swap/history` - Filtered, paginated swap history
+//!
+//! - **[`trades`]**: Trade import and statistics endpoints
+//!   - `POST /api/swap/history/import` - Import trades from a CSV export
+//!   - `GET /api/swap/stats` - Realized PnL over swaps and imported trades
+//!
+//! - **[`portfolio`]**: Positions from confirmed swaps
+//!   - `GET /api/portfolio/positions` - Cost basis, realized and unrealized PnL
+//!
+//! - **[`reports`]**: Printable account reports
+//!   - `GET /api/reports/monthly` - Monthly portfolio and history report (PDF)
+//!
+//! - **[`share`]**: Public share links
+//!   - `POST /api/share` - Publish a chart or portfolio snapshot
+//!   - `GET /share/{slug}` - Server-rendered snapshot page (no auth)
+//!
+//! - **[`webhooks`]**: Outgoing wallet activity webhooks
+//!   - `POST /api/webhooks` - Register a webhook
+//!   - `GET /api/webhooks` - List webhooks
+//!   - `DELETE /api/webhooks/{id}` - Remove a webhook
+//!   - `GET /api/webhooks/{id}/deliveries` - Delivery log
+//!   - `POST /api/webhooks/{id}/test` - Send a test event
+//!
+//! - **[`api_keys`]**: API keys for scripts (login session only)
+//!   - `POST /api/keys` - Create a key
+//!   - `GET /api/keys` - List keys
+//!   - `DELETE /api/keys/{id}` - Revoke a key
+//!
+//! - **[`telemetry`]**: Opt-in anonymous usage counts (no auth)
+//!   - `POST /api/telemetry/usage` - Upload daily counts for an install id
+//!   - `DELETE /api/telemetry/usage/{install_id}` - Delete an install's counts
 //!
 //! - **[`transaction`]**: Transaction management endpoints
 //!   - `POST /api/transaction/submit` - Submit signed transaction
 //!   - `GET /api/transaction/history` - Get transaction history
+//!   - `PUT /api/transaction/{signature}/status` - Record a swap's confirmation outcome
 //!
 //! - **[`staking`]**: Staking operation endpoints
 //!   - `GET /api/staking/info` - Get staking information
 //!   - `GET /api/staking/rewards` - Get staking rewards
+//!   - `POST /api/staking/delegate` - Build a transaction staking with a validator
+//!   - `POST /api/staking/deactivate` - Build a transaction deactivating stake
+//!   - `POST /api/staking/withdraw` - Build a transaction withdrawing inactive stake
 //!
 //! - **[`contracts`]**: Contract plugin endpoints
 //!   - `GET /api/contracts` - List registered contracts
@@ -120,13 +175,24 @@
 //!
 //! See [`crate`] module docs for complete endpoint listing with curl examples.
 
+pub mod admin;
 pub mod auth;
 pub mod friends;
 pub mod market;
+pub mod onramp;
+pub mod health;
+pub mod metrics;
 pub mod wallet;
 pub mod transaction;
 pub mod staking;
 pub mod swap;
+pub mod trades;
+pub mod portfolio;
+pub mod reports;
+pub mod share;
+pub mod webhooks;
+pub mod api_keys;
+pub mod telemetry;
 pub mod wallet_auth;
 pub mod contracts;
 pub mod websocket;
@@ -0,0 +1,77 @@
+//! # On-Ramp Handlers
+//!
+//! Fiat on-ramp providers for the terminal's "Buy SOL" dialog.
+//!
+//! ## Endpoints
+//!
+//! - `GET /api/onramp/providers` - Configured providers, optionally for one region
+//!
+//! ## Authentication
+//!
+//! Public: the list is the same for everyone, and new users look at it
+//! before they hold anything.
+
+use crate::services::OnRampService;
+use axum::{extract::{Query, State}, http::StatusCode, Json};
+use lib_core::dto::ErrorResponse;
+use serde::Deserialize;
+use shared::dto::onramp::{is_region_code, OnRampProvidersResponse};
+use std::sync::Arc;
+use tracing::{debug, instrument};
+
+/// Query parameters for `GET /api/onramp/providers`
+#[derive(Debug, Deserialize)]
+pub struct ProvidersQuery {
+    /// ISO 3166-1 alpha-2 country code
+    pub region: Option<String>,
+}
+
+/// List on-ramp providers.
+///
+/// **Route**: `GET /api/onramp/providers`
+///
+/// # Parameters
+///
+/// - `region` (query, optional) - Two-letter country code; providers with a
+///   region list only show up for regions on it
+///
+/// # Returns
+///
+/// Success (200): `Json<OnRampProvidersResponse>` - In the configured order;
+/// empty when the operator hasn't set any up
+///
+/// Error (400): the region isn't a two-letter code
+///
+/// # Example
+///
+/// ```bash
+/// curl "http://localhost:3001/api/onramp/providers?region=GB"
+/// ```
+///
+/// Response:
+/// ```json
+/// {
+///   "providers": [
+///     { "id": "rampco", "name": "RampCo",
+///       "url_template": "https://buy.rampco.example/?crypto=SOL&address={address}&fiat_amount={amount}",
+///       "regions": ["US", "GB", "DE"] }
+///   ]
+/// }
+/// ```
+#[instrument(skip(service))]
+pub async fn get_providers(
+    State(service): State<Arc<OnRampService>>,
+    Query(params): Query<ProvidersQuery>,
+) -> Result<(StatusCode, Json<OnRampProvidersResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let region = params.region.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if region.is_some_and(|r| !is_region_code(r)) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: "region must be a two-letter country code".to_string() }),
+        ));
+    }
+
+    let response = service.providers(region);
+    debug!("[ONRAMP] Returning {} providers", response.providers.len());
+    Ok((StatusCode::OK, Json(response)))
+}
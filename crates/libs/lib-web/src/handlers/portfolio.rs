@@ -0,0 +1,53 @@
+//! # Portfolio Handlers
+//!
+//! Positions built from the authenticated user's confirmed swaps.
+//!
+//! ## Endpoints
+//!
+//! - `GET /api/portfolio/positions` - Positions with cost basis, realized and unrealized PnL
+//!
+//! ## Request Example
+//!
+//! ```bash
+//! curl http://localhost:3001/api/portfolio/positions \
+//!   -H "Authorization: Bearer $TOKEN"
+//! ```
+
+use crate::services::PortfolioService;
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use lib_auth::Claims;
+use lib_core::dto::ErrorResponse;
+use shared::dto::positions::PositionsResponse;
+use std::sync::Arc;
+use tracing::instrument;
+
+/// Get the authenticated user's positions at current prices.
+///
+/// **Route**: `GET /api/portfolio/positions`
+///
+/// **Authentication**: Required (JWT token in Authorization header)
+///
+/// # Returns
+///
+/// Success (200): `Json<PositionsResponse>` - Open positions first, then closed ones;
+/// tokens without a current price have no unrealized PnL
+///
+/// Error (401): Unauthorized (missing or invalid JWT)
+/// Error (500): Database error
+#[instrument(skip(service, claims), fields(user_id = %claims.sub))]
+pub async fn get_positions(
+    State(service): State<Arc<PortfolioService>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<PositionsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let user_id = claims.sub.parse::<i64>().map_err(|_| {
+        (StatusCode::UNAUTHORIZED, Json(ErrorResponse {
+            error: "Invalid user ID in token".to_string(),
+        }))
+    })?;
+
+    service
+        .positions(user_id)
+        .await
+        .map(Json)
+        .map_err(|e| (e.status_code(), Json(ErrorResponse { error: e.user_message() })))
+}
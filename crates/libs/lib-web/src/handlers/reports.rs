@@ -0,0 +1,79 @@
+//! # Report Handlers
+//!
+//! Printable account reports for the authenticated user.
+//!
+//! ## Endpoints
+//!
+//! - `GET /api/reports/monthly?month=YYYY-MM` - Monthly report as a PDF download
+//!
+//! ## Request Example
+//!
+//! ```bash
+//! curl "http://localhost:3001/api/reports/monthly?month=2025-03" \
+//!   -H "Authorization: Bearer $TOKEN" \
+//!   -o xforce-report-2025-03.pdf
+//! ```
+
+use crate::services::ReportService;
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use lib_auth::Claims;
+use lib_core::dto::ErrorResponse;
+use serde::Deserialize;
+use shared::dto::reports::ReportMonth;
+use std::sync::Arc;
+use tracing::instrument;
+
+#[derive(Debug, Deserialize)]
+pub struct MonthlyReportQuery {
+    /// `YYYY-MM`, in UTC
+    pub month: String,
+}
+
+/// Download the authenticated user's report for a month.
+///
+/// **Route**: `GET /api/reports/monthly?month=YYYY-MM`
+///
+/// **Authentication**: Required (JWT token in Authorization header)
+///
+/// # Returns
+///
+/// Success (200): `application/pdf` attachment named `xforce-report-YYYY-MM.pdf`;
+/// the current month is reported up to now
+///
+/// Error (400): Malformed month, or a month that hasn't started
+/// Error (401): Unauthorized (missing or invalid JWT)
+/// Error (500): Database or rendering error
+#[instrument(skip(service, claims), fields(user_id = %claims.sub))]
+pub async fn get_monthly_report(
+    State(service): State<Arc<ReportService>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<MonthlyReportQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let user_id = claims.sub.parse::<i64>().map_err(|_| {
+        (StatusCode::UNAUTHORIZED, Json(ErrorResponse {
+            error: "Invalid user ID in token".to_string(),
+        }))
+    })?;
+    let month = query.month.parse::<ReportMonth>().map_err(|error| {
+        (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }))
+    })?;
+
+    let (filename, pdf) = service
+        .monthly_report_pdf(user_id, month)
+        .await
+        .map_err(|e| (e.status_code(), Json(ErrorResponse { error: e.user_message() })))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        pdf,
+    )
+        .into_response())
+}
@@ -0,0 +1,92 @@
+//! # Share Link Handlers
+//!
+//! Read-only public snapshots of charts and portfolio allocations.
+//!
+//! ## Endpoints
+//!
+//! - `POST /api/share` - Publish a snapshot (authenticated)
+//! - `GET /share/{slug}` - Server-rendered page for a snapshot (public)
+//!
+//! ## Request Example
+//!
+//! ```bash
+//! curl -X POST http://localhost:3001/api/share \
+//!   -H "Authorization: Bearer $TOKEN" \
+//!   -H "Content-Type: application/json" \
+//!   -d '{"payload": {"kind": "portfolio", "allocations": [{"symbol": "SOL", "allocation_pct": 100.0}]},
+//!        "expires_in_hours": 24, "anonymize": true}'
+//! ```
+
+use crate::services::ShareService;
+use crate::templates::share::{not_found_page, share_page};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Html,
+    Extension, Json,
+};
+use lib_auth::Claims;
+use lib_core::{dto::ErrorResponse, AppError};
+use shared::dto::share::{CreateShareRequest, CreateShareResponse};
+use std::sync::Arc;
+use tracing::{instrument, warn};
+
+/// Publish a share link for the authenticated user.
+///
+/// **Route**: `POST /api/share`
+///
+/// **Authentication**: Required (JWT token in Authorization header)
+///
+/// # Returns
+///
+/// Success (200): `Json<CreateShareResponse>` - Slug and page path (`/share/{slug}`)
+///
+/// Error (400): Bad expiry, empty or oversized snapshot, or no candles in the chart range
+/// Error (401): Unauthorized (missing or invalid JWT)
+/// Error (500): Database error
+#[instrument(skip(service, claims, request), fields(user_id = %claims.sub))]
+pub async fn create_share(
+    State(service): State<Arc<ShareService>>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<CreateShareRequest>,
+) -> Result<Json<CreateShareResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let user_id = claims.sub.parse::<i64>().map_err(|_| {
+        (StatusCode::UNAUTHORIZED, Json(ErrorResponse {
+            error: "Invalid user ID in token".to_string(),
+        }))
+    })?;
+
+    let response = service.create(user_id, request).await.map_err(|e| {
+        warn!("[SHARE] Share link rejected: {}", e);
+        (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+    })?;
+    Ok(Json(response))
+}
+
+/// Public page for a share link.
+///
+/// **Route**: `GET /share/{slug}`
+///
+/// # Returns
+///
+/// Success (200): HTML page with the snapshot
+///
+/// Error (404): Unknown or expired link (HTML page)
+/// Error (500): Database error (HTML page)
+#[instrument(skip(service))]
+pub async fn get_share_page(
+    State(service): State<Arc<ShareService>>,
+    Path(slug): Path<String>,
+) -> (StatusCode, Html<String>) {
+    match service.get(&slug).await {
+        Ok((payload, expires_at)) => (StatusCode::OK, Html(share_page(&payload, expires_at).into_string())),
+        Err(e @ AppError::NotFound(_)) => {
+            tracing::debug!("[SHARE] {}", e);
+            (StatusCode::NOT_FOUND, Html(not_found_page().into_string()))
+        }
+        Err(e) => {
+            warn!("[SHARE] Failed to load share link: {}", e);
+            (e.status_code(), Html(not_found_page().into_string()))
+        }
+    }
+}
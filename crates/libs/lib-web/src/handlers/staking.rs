@@ -1,55 +1,57 @@
 //! # Staking Handlers
 //!
-//! HTTP endpoints for querying Solana staking information and epoch data.
+//! HTTP endpoints for native SOL staking: a wallet's stake accounts and
+//! rewards, and unsigned transactions that stake, deactivate and withdraw.
 //!
 //! ## Endpoints
 //!
-//! - `GET /api/staking/info` - Get basic staking information for a wallet
+//! - `GET /api/staking/info` - Stake accounts of a wallet, the current epoch and staking minimums
+//! - `GET /api/staking/rewards` - Inflation rewards of those accounts over recent epochs
+//! - `POST /api/staking/delegate` - Unsigned transaction creating and delegating a stake account
+//! - `POST /api/staking/deactivate` - Unsigned transaction deactivating a stake account
+//! - `POST /api/staking/withdraw` - Unsigned transaction withdrawing an inactive stake account
 //!
 //! ## Authentication
 //!
-//! These endpoints are public and do not require authentication.
-//! Any valid Solana wallet address can be queried.
+//! These endpoints are public and do not require authentication. The
+//! transactions they return do nothing until the wallet signs them, and are
+//! submitted through the usual transaction submit endpoint.
 //!
 //! ## Request Examples
 //!
 //! ```bash
 //! # Get staking info
 //! curl "http://localhost:3001/api/staking/info?address=8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL"
-//! ```
-//!
-//! ## Implementation Notes
-//!
-//! This is a simplified staking endpoint that provides epoch information.
-//! Full staking account details (stake accounts, validators, rewards) require
-//! additional RPC calls and parsing of stake account data structures.
 //!
-//! Future enhancements could include:
-//! - Detailed stake account information
-//! - Active/inactive stake amounts
-//! - Validator information
-//! - Historical staking rewards
+//! # Rewards over the last 5 epochs
+//! curl "http://localhost:3001/api/staking/rewards?address=8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL&epochs=5"
+//! ```
 
 use crate::services::staking::StakingService;
-use lib_solana::SolanaState;
 use axum::{extract::{Query, State}, http::StatusCode, Json};
+use lib_core::AppError;
+use lib_solana::SolanaState;
 use serde::{Deserialize, Serialize};
+use shared::dto::staking::{
+    DelegateStakeRequest, StakeAccountRequest, StakeTransactionResponse, StakingInfo, StakingRewardsResponse,
+};
 use std::sync::Arc;
 use tracing::instrument;
 
-use lib_core::AppError;
+/// Epochs of rewards returned when the query doesn't say
+const DEFAULT_REWARD_EPOCHS: u64 = 5;
 
 #[derive(Debug, Deserialize)]
 pub struct StakingQuery {
     pub address: String,
 }
 
-#[derive(Debug, Serialize)]
-pub struct StakingInfo {
-    pub wallet_address: String,
-    pub epoch: u64,
-    pub rent_epoch: u64,
-    pub message: String,
+/// Query parameters for `GET /api/staking/rewards`
+#[derive(Debug, Deserialize)]
+pub struct RewardsQuery {
+    pub address: String,
+    /// Completed epochs to look back over (default 5, at most 10)
+    pub epochs: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -57,19 +59,15 @@ pub struct StakingErrorResponse {
     pub error: String,
 }
 
-fn app_error_to_staking_response(err: AppError) -> (StatusCode, Json<StakingErrorResponse>) {
-    let status = match &err {
-        AppError::NotFound(_) => StatusCode::NOT_FOUND,
-        AppError::InvalidInput(_) => StatusCode::BAD_REQUEST,
-        _ => StatusCode::INTERNAL_SERVER_ERROR,
-    };
+type StakingResult<T> = Result<(StatusCode, Json<T>), (StatusCode, Json<StakingErrorResponse>)>;
 
-    (status, Json(StakingErrorResponse {
-        error: err.to_string(),
+fn app_error_to_staking_response(err: AppError) -> (StatusCode, Json<StakingErrorResponse>) {
+    (err.status_code(), Json(StakingErrorResponse {
+        error: err.user_message(),
     }))
 }
 
-/// Get basic staking and epoch information for a Solana wallet.
+/// Get the stake accounts of a Solana wallet.
 ///
 /// **Route**: `GET /api/staking/info`
 ///
@@ -79,29 +77,20 @@ fn app_error_to_staking_response(err: AppError) -> (StatusCode, Json<StakingErro
 ///
 /// # Returns
 ///
-/// Success (200): `Json<StakingInfo>` - Staking information containing:
-/// - `wallet_address`: The queried wallet address
-/// - `epoch`: Current epoch number on Solana
-/// - `rent_epoch`: Rent epoch for the wallet account
-/// - `message`: Informational message about staking status
+/// Success (200): `Json<StakingInfo>` - Stake accounts the wallet is the
+/// withdraw authority of, with their activation state; the current epoch and
+/// how far into it the cluster is; the minimum delegation and the rent-exempt
+/// reserve of a new stake account
 ///
 /// Error (400): Invalid Solana address format
-/// Error (404): Wallet account not found
-/// Error (500): Failed to fetch account or epoch information
-///
-/// # Notes
-///
-/// - This is a simplified endpoint showing basic epoch information
-/// - Full staking details require parsing stake account program data
-/// - Rent epoch indicates when rent was last collected for the account
-/// - Current epoch shows the network's current epoch cycle
+/// Error (502): Failed to query the RPC
 ///
 /// # Solana Epochs
 ///
 /// Solana epochs are time periods of approximately 2-3 days where:
 /// - Validator schedules are determined
 /// - Staking rewards are calculated
-/// - Network parameters can be updated
+/// - Stake warms up and cools down
 ///
 /// # Example
 ///
@@ -112,38 +101,147 @@ fn app_error_to_staking_response(err: AppError) -> (StatusCode, Json<StakingErro
 /// Response:
 /// ```json
 /// {
-///   "wallet_address": "8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL",
+///   "address": "8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL",
 ///   "epoch": 450,
-///   "rent_epoch": 450,
-///   "message": "Current epoch: 450, Wallet rent epoch: 450. Staking details require additional RPC calls."
+///   "slot_index": 120000,
+///   "slots_in_epoch": 432000,
+///   "accounts": [
+///     { "address": "3kTb…", "lamports": 1002282880, "rent_exempt_reserve": 2282880,
+///       "voter": "Vote…", "delegated_lamports": 1000000000,
+///       "activation_epoch": 448, "state": "active" }
+///   ],
+///   "minimum_delegation": 1,
+///   "rent_exempt_reserve": 2282880
 /// }
 /// ```
 #[instrument(skip(solana), fields(address = %params.address))]
 pub async fn get_staking_info(
     State(solana): State<Arc<SolanaState>>,
     Query(params): Query<StakingQuery>,
-) -> Result<(StatusCode, Json<StakingInfo>), (StatusCode, Json<StakingErrorResponse>)> {
+) -> StakingResult<StakingInfo> {
     let service = StakingService::new(solana);
-    let info = service.get_staking_info(&params.address).await.map_err(|e| {
-        let status = match &e {
-            AppError::InvalidInput(_) => StatusCode::BAD_REQUEST,
-            AppError::NotFound(_) => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        };
-        (status, Json(StakingErrorResponse {
-            error: e.to_string(),
-        }))
-    })?;
+    let info = service.get_staking_info(&params.address).await.map_err(app_error_to_staking_response)?;
+    Ok((StatusCode::OK, Json(info)))
+}
 
-    // Convert service result to handler response
-    // Note: The service currently returns a placeholder, so we'll create a basic response
-    // When staking is fully implemented, this will return actual staking data
-    let staking_info = StakingInfo {
-        wallet_address: info.address,
-        epoch: 0, // Placeholder - will be populated when staking is implemented
-        rent_epoch: 0, // Placeholder - will be populated when staking is implemented
-        message: "Staking service is not yet fully implemented".to_string(),
-    };
+/// Get the inflation rewards of a wallet's stake accounts.
+///
+/// **Route**: `GET /api/staking/rewards`
+///
+/// # Parameters
+///
+/// - `address` (query) - Solana wallet public key address
+/// - `epochs` (query, optional) - Completed epochs to look back over (default 5, at most 10)
+///
+/// # Returns
+///
+/// Success (200): `Json<StakingRewardsResponse>` - Rewards per account and
+/// epoch, newest epoch first
+///
+/// Error (400): Invalid address or epoch count
+/// Error (502): Failed to query the RPC
+#[instrument(skip(solana), fields(address = %params.address))]
+pub async fn get_staking_rewards(
+    State(solana): State<Arc<SolanaState>>,
+    Query(params): Query<RewardsQuery>,
+) -> StakingResult<StakingRewardsResponse> {
+    let service = StakingService::new(solana);
+    let epochs = params.epochs.unwrap_or(DEFAULT_REWARD_EPOCHS);
+    let rewards = service.get_rewards(&params.address, epochs).await.map_err(app_error_to_staking_response)?;
+    Ok((StatusCode::OK, Json(rewards)))
+}
 
-    Ok((StatusCode::OK, Json(staking_info)))
+/// Build a transaction that stakes SOL with a validator.
+///
+/// **Route**: `POST /api/staking/delegate`
+///
+/// # Request Body
+///
+/// `DelegateStakeRequest` - wallet, validator vote account, and lamports to
+/// stake (the new account's rent-exempt reserve is added on top)
+///
+/// # Returns
+///
+/// Success (200): `Json<StakeTransactionResponse>` - Unsigned transaction and
+/// the address of the stake account it creates
+///
+/// Error (400): Invalid address, amount under the minimum delegation, or not a vote account
+/// Error (404): All derived stake addresses are taken
+/// Error (502): Failed to query the RPC
+///
+/// # Example
+///
+/// ```bash
+/// curl -X POST http://localhost:3001/api/staking/delegate \
+///   -H "Content-Type: application/json" \
+///   -d '{
+///     "wallet": "8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL",
+///     "vote_account": "CertusDeBmqN8ZawdkxK5kFGMwBXdudvWHYwtNgNhvLu",
+///     "lamports": 1000000000
+///   }'
+/// ```
+#[instrument(skip(solana, payload), fields(wallet = %payload.wallet))]
+pub async fn delegate_stake(
+    State(solana): State<Arc<SolanaState>>,
+    Json(payload): Json<DelegateStakeRequest>,
+) -> StakingResult<StakeTransactionResponse> {
+    let service = StakingService::new(solana);
+    let response = service.build_delegate(&payload).await.map_err(app_error_to_staking_response)?;
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Build a transaction that deactivates a stake account.
+///
+/// **Route**: `POST /api/staking/deactivate`
+///
+/// # Request Body
+///
+/// `StakeAccountRequest` - wallet (the stake authority) and stake account
+///
+/// # Returns
+///
+/// Success (200): `Json<StakeTransactionResponse>` - Unsigned transaction
+///
+/// Error (400): Invalid address, or the wallet isn't the stake authority
+/// Error (404): Not a stake account, or not active
+/// Error (502): Failed to query the RPC
+///
+/// # Notes
+///
+/// Deactivated stake stops earning at once but can only be withdrawn once
+/// the epoch ends.
+#[instrument(skip(solana, payload), fields(wallet = %payload.wallet))]
+pub async fn deactivate_stake(
+    State(solana): State<Arc<SolanaState>>,
+    Json(payload): Json<StakeAccountRequest>,
+) -> StakingResult<StakeTransactionResponse> {
+    let service = StakingService::new(solana);
+    let response = service.build_deactivate(&payload).await.map_err(app_error_to_staking_response)?;
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Build a transaction that withdraws an inactive stake account to the wallet.
+///
+/// **Route**: `POST /api/staking/withdraw`
+///
+/// # Request Body
+///
+/// `StakeAccountRequest` - wallet (the withdraw authority) and stake account
+///
+/// # Returns
+///
+/// Success (200): `Json<StakeTransactionResponse>` - Unsigned transaction
+/// moving the account's whole balance, which closes it
+///
+/// Error (400): Invalid address, or the wallet isn't the withdraw authority
+/// Error (404): Not a stake account, still active or cooling down, or locked up
+/// Error (502): Failed to query the RPC
+#[instrument(skip(solana, payload), fields(wallet = %payload.wallet))]
+pub async fn withdraw_stake(
+    State(solana): State<Arc<SolanaState>>,
+    Json(payload): Json<StakeAccountRequest>,
+) -> StakingResult<StakeTransactionResponse> {
+    let service = StakingService::new(solana);
+    let response = service.build_withdraw(&payload).await.map_err(app_error_to_staking_response)?;
+    Ok((StatusCode::OK, Json(response)))
 }
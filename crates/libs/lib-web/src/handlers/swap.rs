@@ -6,12 +6,15 @@
 //!
 //! - `GET /api/swap/quote` - Get a swap quote for token exchange
 //! - `POST /api/swap/execute` - Build an unsigned swap transaction (requires auth)
+//! - `POST /api/swap/simulate` - Simulate an unsigned swap transaction before signing (requires auth)
 //! - `POST /api/transactions/submit` - Submit a signed swap transaction (requires auth)
+//! - `GET /api/swap/history` - Filtered, paginated swap history (requires auth)
+//! - `GET /api/swap/history/stream` - Whole swap history as resumable NDJSON (requires auth)
 //!
 //! ## Authentication
 //!
 //! - Quote endpoint is public and does not require authentication
-//! - Execute and submit endpoints require valid JWT authentication
+//! - Execute, simulate, submit and history endpoints require valid JWT authentication
 //!
 //! ## Request Examples
 //!
@@ -31,12 +34,21 @@
 //!     "userPublicKey": "8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL"
 //!   }'
 //!
+//! # Second page of failed SOL -> USDC swaps (requires auth)
+//! curl "http://localhost:3001/api/swap/history?limit=20&offset=20&input_token=So11111111111111111111111111111111111111112&output_token=EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v&status=failed" \
+//!   -H "Authorization: Bearer YOUR_JWT_TOKEN"
+//!
+//! # Every swap as NDJSON, resuming after a cursor from a dropped stream (requires auth)
+//! curl -N "http://localhost:3001/api/swap/history/stream?cursor=1739872800000000000_42" \
+//!   -H "Authorization: Bearer YOUR_JWT_TOKEN"
+//!
 //! # Submit signed transaction (requires auth)
 //! curl -X POST http://localhost:3001/api/transactions/submit \
 //!   -H "Authorization: Bearer YOUR_JWT_TOKEN" \
 //!   -H "Content-Type: application/json" \
 //!   -d '{
 //!     "signedTransaction": "BASE64_ENCODED_SIGNED_TX",
+//!     "version": "v0",
 //!     "inputMint": "So11111111111111111111111111111111111111112",
 //!     "outputMint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
 //!     "inputAmount": 1000000000,
@@ -50,8 +62,9 @@
 //!
 //! 1. **Get Quote**: Query Jupiter for best swap route and estimated output
 //! 2. **Execute**: Build unsigned transaction with user's wallet
-//! 3. **Sign**: Client signs transaction with user's private key
-//! 4. **Submit**: Send signed transaction to Solana and record in database
+//! 3. **Simulate**: Preview balance changes and catch failures before signing
+//! 4. **Sign**: Client signs transaction with user's private key
+//! 5. **Submit**: Send signed transaction to Solana and record in database
 //!
 //! ## Jupiter Integration
 //!
@@ -61,15 +74,20 @@
 //! - Supports partial fills and multi-hop swaps
 //! - Provides price impact and slippage protection
 
-use axum::{extract::{Query, State}, http::StatusCode, Json, Extension};
+use axum::{body::Body, extract::{Query, State}, http::{header, StatusCode}, response::{IntoResponse, Response}, Json, Extension};
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
+use shared::dto::history::HistoryStreamLine;
+use shared::dto::transactions::TransactionVersion;
 use std::sync::Arc;
 use tracing::instrument;
 
-use crate::services::swap::SwapService;
+use crate::services::swap::{SwapHistoryParams, SwapService};
+use crate::services::swap_simulation::SwapSimulationService;
 use lib_auth::Claims;
-use lib_core::AppError;
+use lib_core::{AppError, Metrics};
 use lib_solana::SolanaState;
+use shared::dto::simulation::{SimulateSwapRequest, SimulateSwapResponse};
 
 #[derive(Debug, Deserialize)]
 pub struct SwapQuoteQuery {
@@ -188,12 +206,13 @@ fn app_error_to_swap_response(err: AppError) -> (StatusCode, Json<SwapErrorRespo
 ///   ]
 /// }
 /// ```
-#[instrument(skip(solana), fields(input_mint = %params.input_mint, output_mint = %params.output_mint, amount = params.amount))]
+#[instrument(skip(solana, metrics), fields(input_mint = %params.input_mint, output_mint = %params.output_mint, amount = params.amount))]
 pub async fn get_swap_quote(
     State(solana): State<Arc<SolanaState>>,
+    State(metrics): State<Arc<Metrics>>,
     Query(params): Query<SwapQuoteQuery>,
 ) -> Result<(StatusCode, Json<SwapQuoteResponse>), (StatusCode, Json<SwapErrorResponse>)> {
-    let service = SwapService::new(solana);
+    let service = SwapService::new(solana).with_metrics(metrics);
     let quote_result = service
         .get_swap_quote(
             &params.input_mint,
@@ -254,6 +273,7 @@ pub struct SwapExecuteRequest {
 pub struct SwapExecuteResponse {
     #[serde(rename = "transaction")]
     pub transaction: String, // Base64-encoded unsigned transaction
+    pub version: TransactionVersion, // Message format of `transaction`
     #[serde(rename = "lastValidBlockHeight")]
     pub last_valid_block_height: u64,
     #[serde(rename = "inputMint")]
@@ -286,6 +306,7 @@ pub struct SwapExecuteResponse {
 ///
 /// Success (200): `Json<SwapExecuteResponse>` - Unsigned transaction data:
 /// - `transaction`: Base64-encoded unsigned transaction
+/// - `version`: Its message format, `"legacy"` or `"v0"` (loads accounts from lookup tables)
 /// - `lastValidBlockHeight`: Block height until which transaction is valid
 /// - `inputMint`: Input token mint address
 /// - `outputMint`: Output token mint address
@@ -302,8 +323,8 @@ pub struct SwapExecuteResponse {
 /// 1. Server fetches quote from Jupiter
 /// 2. Server builds unsigned transaction
 /// 3. Client receives base64 transaction
-/// 4. Client deserializes and signs transaction
-/// 5. Client submits signed transaction via `/api/transactions/submit`
+/// 4. Client deserializes and signs transaction according to `version`
+/// 5. Client submits signed transaction and its `version` via `/api/transactions/submit`
 ///
 /// # Security
 ///
@@ -330,6 +351,7 @@ pub struct SwapExecuteResponse {
 /// ```json
 /// {
 ///   "transaction": "AQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABAAEDoQ...",
+///   "version": "legacy",
 ///   "lastValidBlockHeight": 123456789,
 ///   "inputMint": "So11111111111111111111111111111111111111112",
 ///   "outputMint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
@@ -338,13 +360,14 @@ pub struct SwapExecuteResponse {
 ///   "priceImpactPct": 0.05
 /// }
 /// ```
-#[instrument(skip(solana), fields(input_mint = %payload.input_mint, output_mint = %payload.output_mint))]
+#[instrument(skip(solana, metrics), fields(input_mint = %payload.input_mint, output_mint = %payload.output_mint))]
 pub async fn execute_swap(
     State(solana): State<Arc<SolanaState>>,
+    State(metrics): State<Arc<Metrics>>,
     Extension(claims): Extension<Claims>,
     Json(payload): Json<SwapExecuteRequest>,
 ) -> Result<(StatusCode, Json<SwapExecuteResponse>), (StatusCode, Json<SwapErrorResponse>)> {
-    let service = SwapService::new(solana);
+    let service = SwapService::new(solana).with_metrics(metrics);
     let tx_result = service
         .execute_swap(
             &payload.input_mint,
@@ -365,6 +388,7 @@ pub async fn execute_swap(
     // Convert service result to handler response
     let response = SwapExecuteResponse {
         transaction: tx_result.transaction,
+        version: tx_result.version,
         last_valid_block_height: tx_result.last_valid_block_height,
         input_mint: tx_result.input_mint,
         output_mint: tx_result.output_mint,
@@ -376,10 +400,87 @@ pub async fn execute_swap(
     Ok((StatusCode::OK, Json(response)))
 }
 
+/// Simulate an unsigned swap transaction against the cluster before it is signed.
+///
+/// **Route**: `POST /api/swap/simulate`
+///
+/// **Authentication**: Required (JWT token in Authorization header)
+///
+/// # Parameters
+///
+/// - `transaction` (body) - Base64-encoded unsigned transaction from `/api/swap/execute`
+/// - `userPublicKey` (body) - Wallet that will sign; must be the fee payer
+/// - `inputMint` (body) - Input token mint address
+/// - `outputMint` (body) - Output token mint address
+///
+/// # Returns
+///
+/// Success (200): `Json<SimulateSwapResponse>` - Expected outcome:
+/// - `success`: Whether the transaction would succeed
+/// - `error`: Readable failure reason (insufficient funds, slippage exceeded, ...)
+/// - `computeUnits`: Compute units consumed
+/// - `networkFeeLamports`: Network fee
+/// - `solChangeLamports`: Change of the wallet's SOL balance, fee included
+/// - `tokenChanges`: Changes of the wallet's token balances (`mint`, `decimals`, `change`)
+/// - `logs` / `logTail`: Program logs, and their last lines for error reports
+///
+/// A transaction that would fail is still a 200 with `success: false`.
+///
+/// Error (400): Invalid transaction, or fee payer is not `userPublicKey`
+/// Error (401): Unauthorized (missing or invalid JWT)
+/// Error (502): RPC unavailable
+///
+/// # Example
+///
+/// ```bash
+/// curl -X POST http://localhost:3001/api/swap/simulate \
+///   -H "Authorization: Bearer YOUR_JWT_TOKEN" \
+///   -H "Content-Type: application/json" \
+///   -d '{
+///     "transaction": "BASE64_ENCODED_UNSIGNED_TX",
+///     "userPublicKey": "8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL",
+///     "inputMint": "So11111111111111111111111111111111111111112",
+///     "outputMint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+///   }'
+/// ```
+///
+/// Response:
+/// ```json
+/// {
+///   "success": true,
+///   "computeUnits": 184523,
+///   "networkFeeLamports": 5000,
+///   "solChangeLamports": -1000005000,
+///   "tokenChanges": [
+///     { "mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "decimals": 6, "change": 24500000 }
+///   ],
+///   "logs": ["Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]", "..."],
+///   "logTail": ["..."]
+/// }
+/// ```
+#[instrument(skip(solana, claims, payload), fields(user_id = %claims.sub))]
+pub async fn simulate_swap(
+    State(solana): State<Arc<SolanaState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<SimulateSwapRequest>,
+) -> Result<(StatusCode, Json<SimulateSwapResponse>), (StatusCode, Json<SwapErrorResponse>)> {
+    let service = SwapSimulationService::new(solana);
+    let response = service.simulate_swap(&payload).await.map_err(|e| {
+        let status = e.status_code();
+        (status, Json(SwapErrorResponse {
+            error: e.user_message(),
+        }))
+    })?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TransactionSubmitRequest {
     #[serde(rename = "signedTransaction")]
     pub signed_transaction: String, // Base64-encoded signed transaction
+    #[serde(default)]
+    pub version: TransactionVersion, // Message format of `signedTransaction`
     #[serde(rename = "inputMint")]
     pub input_mint: String,
     #[serde(rename = "outputMint")]
@@ -409,6 +510,7 @@ pub struct TransactionSubmitResponse {
 /// # Parameters
 ///
 /// - `signedTransaction` (body) - Base64-encoded signed transaction
+/// - `version` (body, optional) - `"legacy"` (default) or `"v0"`, as returned by `/api/swap/execute`
 /// - `inputMint` (body) - Input token mint address
 /// - `outputMint` (body) - Output token mint address
 /// - `inputAmount` (body) - Amount swapped in (smallest unit)
@@ -422,7 +524,8 @@ pub struct TransactionSubmitResponse {
 /// - `signature`: Transaction signature (unique identifier on Solana)
 /// - `status`: Transaction status ("pending" initially)
 ///
-/// Error (400): Invalid transaction format or base64 encoding
+/// Error (400): Invalid transaction format or base64 encoding, or a `version`
+/// that doesn't match the transaction
 /// Error (401): Unauthorized (missing or invalid JWT)
 /// Error (500): Failed to submit transaction or record in database
 ///
@@ -489,6 +592,7 @@ pub async fn submit_transaction(
     // Convert handler request to service request
     let request = SwapTransactionSubmitRequest {
         signed_transaction: payload.signed_transaction,
+        version: payload.version,
         input_mint: payload.input_mint,
         output_mint: payload.output_mint,
         input_amount: payload.input_amount,
@@ -513,3 +617,193 @@ pub async fn submit_transaction(
 
     Ok((StatusCode::OK, Json(response)))
 }
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SwapHistoryQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub from_ts: Option<i64>,
+    pub to_ts: Option<i64>,
+    pub input_token: Option<String>,
+    pub output_token: Option<String>,
+    pub status: Option<String>,
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SwapHistoryItem {
+    pub id: i64,
+    pub signature: String,
+    #[serde(rename = "inputMint")]
+    pub input_mint: String,
+    #[serde(rename = "outputMint")]
+    pub output_mint: String,
+    #[serde(rename = "inputAmount")]
+    pub input_amount: i64,
+    #[serde(rename = "outputAmount")]
+    pub output_amount: i64,
+    pub status: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SwapHistoryResponse {
+    pub swaps: Vec<SwapHistoryItem>,
+    #[serde(rename = "totalCount")]
+    pub total_count: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Get the authenticated user's swap history, newest first.
+///
+/// **Route**: `GET /api/swap/history`
+///
+/// **Authentication**: Required (JWT token in Authorization header)
+///
+/// # Parameters
+///
+/// - `limit` (query, optional) - Page size, default 50, clamped to 1..=200
+/// - `offset` (query, optional) - Swaps to skip, default 0
+/// - `from_ts` (query, optional) - Unix seconds, inclusive lower bound on `created_at`
+/// - `to_ts` (query, optional) - Unix seconds, exclusive upper bound on `created_at`
+/// - `input_token` (query, optional) - Input mint address
+/// - `output_token` (query, optional) - Output mint address
+/// - `status` (query, optional) - `pending`, `confirmed` or `failed`
+/// - `signature` (query, optional) - Fragment of the transaction signature (case-sensitive)
+///
+/// # Returns
+///
+/// Success (200): `Json<SwapHistoryResponse>` - One page of swaps plus `totalCount`,
+/// the number of swaps matching the filters across all pages. An offset past the
+/// end returns an empty page.
+///
+/// Error (400): Negative offset, invalid timestamp range or unknown status
+/// Error (401): Unauthorized (missing or invalid JWT)
+/// Error (500): Database error
+#[instrument(skip(pool, claims, query), fields(user_id = %claims.sub))]
+pub async fn get_swap_history(
+    State(pool): State<lib_core::DbPool>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<SwapHistoryQuery>,
+) -> Result<Json<SwapHistoryResponse>, (StatusCode, Json<SwapErrorResponse>)> {
+    let user_id = claims.sub.parse::<i64>().map_err(|_| {
+        (StatusCode::UNAUTHORIZED, Json(SwapErrorResponse {
+            error: "Invalid user ID in token".to_string(),
+        }))
+    })?;
+
+    let params = SwapHistoryParams {
+        limit: query.limit,
+        offset: query.offset,
+        from_ts: query.from_ts,
+        to_ts: query.to_ts,
+        input_token: query.input_token,
+        output_token: query.output_token,
+        status: query.status,
+        signature: query.signature,
+    };
+
+    let page = SwapService::get_swap_history(&pool, user_id, params)
+        .await
+        .map_err(|e| {
+            (e.status_code(), Json(SwapErrorResponse {
+                error: e.user_message(),
+            }))
+        })?;
+
+    Ok(Json(SwapHistoryResponse {
+        swaps: page.swaps.into_iter().map(history_item).collect(),
+        total_count: page.total_count,
+        limit: page.limit,
+        offset: page.offset,
+    }))
+}
+
+fn history_item(swap: lib_core::model::store::models::Swap) -> SwapHistoryItem {
+    SwapHistoryItem {
+        id: swap.id,
+        signature: swap.signature,
+        input_mint: swap.input_mint,
+        output_mint: swap.output_mint,
+        input_amount: swap.input_amount,
+        output_amount: swap.output_amount,
+        status: swap.status.to_string(),
+        created_at: swap.created_at.to_rfc3339(),
+    }
+}
+
+/// Filters of `GET /api/swap/history/stream` and where to resume
+#[derive(Debug, Default, Deserialize)]
+pub struct SwapHistoryStreamQuery {
+    /// Last cursor received from an earlier, interrupted stream
+    pub cursor: Option<String>,
+    pub from_ts: Option<i64>,
+    pub to_ts: Option<i64>,
+    pub input_token: Option<String>,
+    pub output_token: Option<String>,
+    pub status: Option<String>,
+    pub signature: Option<String>,
+}
+
+/// Stream the authenticated user's whole swap history, newest first.
+///
+/// **Route**: `GET /api/swap/history/stream`
+///
+/// **Authentication**: Required (JWT token in Authorization header)
+///
+/// # Parameters
+///
+/// - `cursor` (query, optional) - Continue after this cursor from an earlier stream
+/// - `from_ts`, `to_ts`, `input_token`, `output_token`, `status`, `signature` -
+///   Same filters as `GET /api/swap/history`
+///
+/// # Returns
+///
+/// Success (200): `application/x-ndjson`, one [`HistoryStreamLine`] of
+/// `SwapHistoryItem` per line: the swaps, a cursor every
+/// `HISTORY_CURSOR_INTERVAL` swaps and after the last one, then `{"end":{}}`.
+/// A body without the end line was cut off; resume from its last cursor.
+///
+/// Error (400): Invalid cursor, invalid timestamp range or unknown status
+/// Error (401): Unauthorized (missing or invalid JWT)
+#[instrument(skip(pool, claims, query), fields(user_id = %claims.sub, resumed = query.cursor.is_some()))]
+pub async fn stream_swap_history(
+    State(pool): State<lib_core::DbPool>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<SwapHistoryStreamQuery>,
+) -> Result<Response, (StatusCode, Json<SwapErrorResponse>)> {
+    use crate::services::swap::HistoryCursor;
+
+    let user_id = claims.sub.parse::<i64>().map_err(|_| {
+        (StatusCode::UNAUTHORIZED, Json(SwapErrorResponse {
+            error: "Invalid user ID in token".to_string(),
+        }))
+    })?;
+    let error = |e: lib_core::AppError| (e.status_code(), Json(SwapErrorResponse { error: e.user_message() }));
+
+    let after = query.cursor.as_deref().map(HistoryCursor::parse).transpose().map_err(error)?;
+    let params = SwapHistoryParams {
+        from_ts: query.from_ts,
+        to_ts: query.to_ts,
+        input_token: query.input_token,
+        output_token: query.output_token,
+        status: query.status,
+        signature: query.signature,
+        ..Default::default()
+    };
+    let lines = SwapService::stream_swap_history(pool, user_id, params, after).map_err(error)?;
+
+    let body = Body::from_stream(lines.map(|line| {
+        let line = match line {
+            HistoryStreamLine::Record(swap) => HistoryStreamLine::Record(history_item(swap)),
+            HistoryStreamLine::Cursor(cursor) => HistoryStreamLine::Cursor(cursor),
+            HistoryStreamLine::End {} => HistoryStreamLine::End {},
+        };
+        let mut json = serde_json::to_vec(&line)?;
+        json.push(b'\n');
+        Ok::<_, serde_json::Error>(json)
+    }));
+    Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], body).into_response())
+}
@@ -0,0 +1,87 @@
+//! # Usage Telemetry Handlers
+//!
+//! Anonymous daily usage counts from terminals that opted in under
+//! Settings > Privacy.
+//!
+//! ## Endpoints
+//!
+//! - `POST /api/telemetry/usage` - Upload a day-bucketed usage report
+//! - `DELETE /api/telemetry/usage/{install_id}` - Delete an install's counts
+//!
+//! ## Authentication
+//!
+//! Public: reports belong to a random install id, not an account, so that
+//! nothing links the counts to a user. The id is all it takes to delete them.
+
+use crate::services::TelemetryService;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use lib_core::dto::ErrorResponse;
+use shared::dto::telemetry::UsageReport;
+use std::sync::Arc;
+use tracing::{instrument, warn};
+
+/// Store a usage report.
+///
+/// **Route**: `POST /api/telemetry/usage`
+///
+/// Days already uploaded by the same install are replaced.
+///
+/// # Returns
+///
+/// Success (204): No content
+///
+/// Error (400): Malformed install id, unknown event key, too many days, days
+/// out of order or implausible counts
+/// Error (500): Database error
+///
+/// # Example
+///
+/// ```bash
+/// curl -X POST http://localhost:3001/api/telemetry/usage \
+///   -H "Content-Type: application/json" \
+///   -d '{"install_id": "0f8fad5b-d9cb-469f-a165-70867728950e", "app_version": "0.1.0",
+///        "days": [{"day": "2025-03-01", "counts": {"screen.wallet": 4, "feature.swap": 1}}]}'
+/// ```
+#[instrument(skip(service, report), fields(days = report.days.len()))]
+pub async fn upload_usage(
+    State(service): State<Arc<TelemetryService>>,
+    Json(report): Json<UsageReport>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    service.record(&report).await.map_err(|e| {
+        warn!("[TELEMETRY] Usage report rejected: {}", e);
+        (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Delete everything an install uploaded.
+///
+/// **Route**: `DELETE /api/telemetry/usage/{install_id}`
+///
+/// # Returns
+///
+/// Success (204): No content, also when the install never uploaded
+///
+/// Error (400): `install_id` isn't an install id
+/// Error (500): Database error
+///
+/// # Example
+///
+/// ```bash
+/// curl -X DELETE http://localhost:3001/api/telemetry/usage/0f8fad5b-d9cb-469f-a165-70867728950e
+/// ```
+#[instrument(skip(service, install_id))]
+pub async fn delete_usage(
+    State(service): State<Arc<TelemetryService>>,
+    Path(install_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    service.delete(&install_id).await.map_err(|e| {
+        warn!("[TELEMETRY] Usage deletion rejected: {}", e);
+        (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,103 @@
+//! # Trade Import Handlers
+//!
+//! HTTP endpoints for importing trades from other platforms and for trade
+//! statistics.
+//!
+//! ## Endpoints
+//!
+//! - `POST /api/swap/history/import` - Validate (and optionally store) a CSV of trades
+//! - `GET /api/swap/stats` - Realized PnL per pair over swaps and imported trades
+//!
+//! ## Authentication
+//!
+//! Both endpoints require a JWT (`Authorization: Bearer <token>`); trades are
+//! stored and read for the token's user.
+//!
+//! ## Request Examples
+//!
+//! ```bash
+//! # Preview: validate only, nothing is stored
+//! curl -X POST http://localhost:3001/api/swap/history/import \
+//!   -H "Authorization: Bearer $TOKEN" \
+//!   -H "Content-Type: application/json" \
+//!   -d '{"csv": "timestamp,pair,side,amount,price,fee\n2024-03-01,SOL/USDC,buy,2,100,0.1\n"}'
+//!
+//! # Commit with a custom column mapping
+//! curl -X POST http://localhost:3001/api/swap/history/import \
+//!   -H "Authorization: Bearer $TOKEN" \
+//!   -H "Content-Type: application/json" \
+//!   -d '{"csv": "...", "commit": true,
+//!        "mapping": {"timestamp": "Date(UTC)", "pair": "Market", "side": "Type",
+//!                    "amount": "Amount", "price": "Price", "fee": "Fee"}}'
+//! ```
+
+use crate::services::TradeImportService;
+use axum::{extract::State, http::{HeaderMap, StatusCode, header::AUTHORIZATION}, Json};
+use lib_auth::decode_jwt;
+use lib_core::{dto::ErrorResponse, AppError, Config};
+use shared::dto::trades::{TradeImportRequest, TradeImportResponse, TradeStatsResponse};
+use std::sync::Arc;
+use tracing::instrument;
+
+type TradeError = (StatusCode, Json<ErrorResponse>);
+
+fn trade_error(e: AppError) -> TradeError {
+    (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+}
+
+/// Authenticated user id from the bearer token
+fn extract_user_id(headers: &HeaderMap, config: &Config) -> Result<i64, TradeError> {
+    let unauthorized = |message: &str| {
+        (StatusCode::UNAUTHORIZED, Json(ErrorResponse { error: message.to_string() }))
+    };
+
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .ok_or_else(|| unauthorized("Missing or invalid authorization header"))?;
+
+    let claims = decode_jwt(token, &config.jwt_secret).map_err(|_| unauthorized("Invalid token"))?;
+    claims.sub.parse::<i64>().map_err(|_| unauthorized("Invalid user ID"))
+}
+
+/// Validate a CSV of historical trades and, with `commit: true`, store it.
+///
+/// **Route**: `POST /api/swap/history/import`
+///
+/// # Returns
+///
+/// Success (200): Headers, valid rows, per-row errors and duplicate count;
+/// `imported` is the number of rows stored
+/// Error (400): Empty file, broken quoting or too many rows
+/// Error (401): Not authenticated
+#[instrument(skip(service, config, headers, payload))]
+pub async fn import_trades(
+    State(service): State<Arc<TradeImportService>>,
+    State(config): State<Config>,
+    headers: HeaderMap,
+    Json(payload): Json<TradeImportRequest>,
+) -> Result<Json<TradeImportResponse>, TradeError> {
+    let user_id = extract_user_id(&headers, &config)?;
+    let response = service.import(user_id, &payload).await.map_err(trade_error)?;
+    Ok(Json(response))
+}
+
+/// Trade statistics for the authenticated user.
+///
+/// **Route**: `GET /api/swap/stats`
+///
+/// # Returns
+///
+/// Success (200): Trade counts by source and realized PnL per pair
+/// Error (401): Not authenticated
+#[instrument(skip(service, config, headers))]
+pub async fn get_trade_stats(
+    State(service): State<Arc<TradeImportService>>,
+    State(config): State<Config>,
+    headers: HeaderMap,
+) -> Result<Json<TradeStatsResponse>, TradeError> {
+    let user_id = extract_user_id(&headers, &config)?;
+    let stats = service.stats(user_id).await.map_err(trade_error)?;
+    Ok(Json(stats))
+}
@@ -5,11 +5,14 @@
 //! ## Endpoints
 //!
 //! - `GET /api/transactions/history` - Get recent transaction history for a wallet
+//! - `PUT /api/transaction/{signature}/status` - Record the confirmation outcome of a swap
+//! - `PUT /api/transaction/{signature}/latency` - Record how long each stage of a swap took
 //!
 //! ## Authentication
 //!
-//! These endpoints are public and do not require authentication.
-//! Any valid Solana wallet address can be queried.
+//! The history and submit endpoints are public; any valid Solana wallet
+//! address can be queried. Status and latency updates require a JWT and only
+//! touch the caller's own swaps.
 //!
 //! ## Request Examples
 //!
@@ -26,11 +29,15 @@
 //! Transaction queries are rate-limited by the Solana RPC endpoint.
 //! Consider caching results for frequently accessed wallets.
 
+use crate::services::swap::SwapService;
 use crate::services::transaction::TransactionService;
+use lib_auth::Claims;
 use lib_solana::SolanaState;
 use lib_core::DbPool;
-use axum::{extract::{Query, State}, http::StatusCode, Json};
+use axum::{extract::{Path, Query, State}, http::StatusCode, Extension, Json};
 use serde::{Deserialize, Serialize};
+use shared::dto::latency::SwapLatency;
+use shared::dto::transactions::TransactionStatusUpdate;
 use shared::{ErrorResponse, SubmitTransactionRequest, SubmitTransactionResponse};
 use std::sync::Arc;
 use tracing::{error, info, instrument};
@@ -52,6 +59,7 @@ pub struct TransactionSummary {
     pub slot: u64,
     pub block_time: Option<i64>,
     pub status: String,
+    pub memo: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -78,6 +86,7 @@ pub struct TransactionHistory {
 ///   - `slot`: Blockchain slot number where transaction was processed
 ///   - `block_time`: Unix timestamp of when transaction was confirmed (optional)
 ///   - `status`: Transaction status ("Success" or "Failed")
+///   - `memo`: SPL Memo text as reported by the RPC (`"[len] text"`), or null
 ///
 /// Error (400): Invalid Solana address format
 /// Error (500): Failed to fetch transaction signatures from Solana RPC
@@ -104,13 +113,15 @@ pub struct TransactionHistory {
 ///       "signature": "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW",
 ///       "slot": 123456789,
 ///       "block_time": 1729857600,
-///       "status": "Success"
+///       "status": "Success",
+///       "memo": "[10] invoice 42"
 ///     },
 ///     {
 ///       "signature": "4hXTCkRzt9WyecNzV1XPgCDfGAZzQKNxLXgynz5QDuWWPSAZBZSHptvWRL3BjCvzUXRdKvHL2b7yGrRQcWyaqsaBrq",
 ///       "slot": 123456780,
 ///       "block_time": 1729857500,
-///       "status": "Failed"
+///       "status": "Failed",
+///       "memo": null
 ///     }
 ///   ]
 /// }
@@ -145,6 +156,7 @@ pub async fn get_transaction_history(
             slot: tx.slot,
             block_time: tx.block_time,
             status: tx.status,
+            memo: tx.memo,
         })
         .collect();
 
@@ -255,3 +267,90 @@ pub async fn submit_transaction(
         message: "Transaction submitted successfully".to_string(),
     }))
 }
+
+/// Record the confirmation outcome of one of the user's swaps.
+///
+/// **Route**: `PUT /api/transaction/{signature}/status`
+///
+/// The terminal tracks every transaction it submits and reports where it
+/// ended up, so the swap history doesn't keep it pending forever.
+///
+/// # Request Body
+///
+/// - `status` - `pending`, `processed`, `confirmed`, `finalized`, `failed` or `expired`
+/// - `error` (optional) - Why the transaction failed
+///
+/// # Returns
+///
+/// Success (204): Status recorded, or nothing to change
+///
+/// Error (401): Unauthorized (missing or invalid JWT)
+/// Error (404): No swap with this signature for this user
+/// Error (500): Database error
+#[instrument(skip(pool, claims, update), fields(user_id = %claims.sub))]
+pub async fn update_transaction_status(
+    State(pool): State<DbPool>,
+    Extension(claims): Extension<Claims>,
+    Path(signature): Path<String>,
+    Json(update): Json<TransactionStatusUpdate>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let user_id = claims.sub.parse::<i64>().map_err(|_| {
+        (StatusCode::UNAUTHORIZED, Json(ErrorResponse {
+            error: "Invalid user ID in token".to_string(),
+        }))
+    })?;
+
+    SwapService::update_swap_status(&pool, user_id, &signature, &update)
+        .await
+        .map_err(|e| {
+            (e.status_code(), Json(ErrorResponse {
+                error: e.user_message(),
+            }))
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Record how long each stage of one of the user's swaps took.
+///
+/// **Route**: `PUT /api/transaction/{signature}/latency`
+///
+/// The terminal times its swap pipeline and reports the breakdown once the
+/// RPC acknowledged the transaction. Operators see the percentiles at
+/// `GET /api/admin/swap-latency`.
+///
+/// # Request Body
+///
+/// - `build_ms`, `revalidation_ms`, `signing_ms`, `submission_ms`,
+///   `acknowledgement_ms` - Milliseconds spent in each stage
+///
+/// # Returns
+///
+/// Success (204): Breakdown recorded
+///
+/// Error (401): Unauthorized (missing or invalid JWT)
+/// Error (404): No swap with this signature for this user
+/// Error (500): Database error
+#[instrument(skip(pool, claims, latency), fields(user_id = %claims.sub))]
+pub async fn record_transaction_latency(
+    State(pool): State<DbPool>,
+    Extension(claims): Extension<Claims>,
+    Path(signature): Path<String>,
+    Json(latency): Json<SwapLatency>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let user_id = claims.sub.parse::<i64>().map_err(|_| {
+        (StatusCode::UNAUTHORIZED, Json(ErrorResponse {
+            error: "Invalid user ID in token".to_string(),
+        }))
+    })?;
+
+    SwapService::record_swap_latency(&pool, user_id, &signature, &latency)
+        .await
+        .map_err(|e| {
+            (e.status_code(), Json(ErrorResponse {
+                error: e.user_message(),
+            }))
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
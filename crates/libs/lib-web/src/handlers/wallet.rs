@@ -6,7 +6,7 @@
 //!
 //! - `GET /api/wallet/balance` - Get SOL balance for a wallet address
 //! - `GET /api/wallet/info` - Get full wallet info including SOL and token balances
-//! - `GET /api/wallet/tokens` - Get SPL token balances for a wallet
+//! - `GET /api/wallet/tokens` - Get SPL and Token-2022 token balances for a wallet
 //!
 //! ## Authentication
 //!
@@ -36,6 +36,7 @@ use lib_solana::SolanaState;
 use axum::{extract::{Query, State}, http::StatusCode, Json};
 use serde::{Deserialize, Serialize};
 use lib_core::dto::ErrorResponse;
+use shared::dto::tokens::{TokenProgram, TransferFee};
 use std::sync::Arc;
 use tracing::{error, info, instrument};
 
@@ -57,6 +58,11 @@ pub struct TokenBalance {
     pub symbol: Option<String>,
     pub balance: f64,
     pub ui_amount: String,
+    pub amount: u64,
+    pub decimals: u8,
+    pub program: TokenProgram,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_fee: Option<TransferFee>,
 }
 
 #[derive(Debug, Serialize)]
@@ -146,8 +152,10 @@ pub async fn get_wallet_balance(
 /// - `token_accounts`: Array of SPL token balances, each containing:
 ///   - `mint`: Token mint address
 ///   - `symbol`: Token symbol (e.g., "USDC") if available
-///   - `balance`: Raw token balance
+///   - `balance`: Token balance (human-readable)
 ///   - `ui_amount`: Human-readable token amount (respecting decimals)
+///   - `program`: `"spl"` or `"token2022"`
+///   - `transfer_fee`: Current transfer fee, for Token-2022 mints that charge one
 ///
 /// Error (400): Invalid Solana address format
 /// Error (500): Failed to query Solana RPC or token accounts
@@ -203,6 +211,10 @@ pub async fn get_wallet_info(
             symbol: tb.symbol,
             balance: tb.balance,
             ui_amount: tb.ui_amount,
+            amount: tb.amount,
+            decimals: tb.decimals,
+            program: tb.program,
+            transfer_fee: tb.transfer_fee,
         })
         .collect();
 
@@ -230,8 +242,13 @@ pub async fn get_wallet_info(
 /// Success (200): `Json<Vec<TokenBalance>>` - Array of token balances:
 /// - `mint`: Token mint address
 /// - `symbol`: Token symbol (e.g., "USDC", "BONK") if available from Jupiter token list
-/// - `balance`: Raw token balance (without decimal adjustment)
+/// - `balance`: Token balance (human-readable)
 /// - `ui_amount`: Human-readable amount adjusted for token decimals
+/// - `amount`: Balance in base units
+/// - `decimals`: Mint decimals
+/// - `program`: `"spl"` for the classic token program, `"token2022"` for Token Extensions
+/// - `transfer_fee`: `{ basis_points, maximum_fee }` for the current epoch, only on
+///   Token-2022 mints with the transfer-fee extension
 ///
 /// Error (400): Invalid Solana address format
 /// Error (500): Failed to fetch token accounts from Solana RPC
@@ -254,14 +271,30 @@ pub async fn get_wallet_info(
 ///   {
 ///     "mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
 ///     "symbol": "USDC",
-///     "balance": 1000000,
-///     "ui_amount": "1.0"
+///     "balance": 1.0,
+///     "ui_amount": "1.0",
+///     "amount": 1000000,
+///     "decimals": 6,
+///     "program": "spl"
 ///   },
 ///   {
 ///     "mint": "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263",
 ///     "symbol": "BONK",
-///     "balance": 1000000000,
-///     "ui_amount": "1000.0"
+///     "balance": 1000.0,
+///     "ui_amount": "1000.0",
+///     "amount": 100000000,
+///     "decimals": 5,
+///     "program": "spl"
+///   },
+///   {
+///     "mint": "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo",
+///     "symbol": "PYUSD",
+///     "balance": 25.0,
+///     "ui_amount": "25",
+///     "amount": 25000000,
+///     "decimals": 6,
+///     "program": "token2022",
+///     "transfer_fee": { "basis_points": 0, "maximum_fee": 0 }
 ///   }
 /// ]
 /// ```
@@ -293,6 +326,10 @@ pub async fn get_token_balances(
             symbol: tb.symbol,
             balance: tb.balance,
             ui_amount: tb.ui_amount,
+            amount: tb.amount,
+            decimals: tb.decimals,
+            program: tb.program,
+            transfer_fee: tb.transfer_fee,
         })
         .collect();
 
@@ -7,6 +7,7 @@
 //! This module implements wallet-based authentication flow:
 //! - Wallet setup validation (verifying setup tokens)
 //! - Wallet linking (connecting Solana wallet to user account via signature verification)
+//! - Login challenges (single-use, server-issued nonces)
 //! - Wallet-based login (sign-in using Solana wallet signature)
 //!
 //! All wallet operations use Ed25519 signature verification to prove wallet ownership.
@@ -25,23 +26,27 @@
 //! 2. User calls /validate with setup token → receives challenge
 //! 3. User signs challenge with wallet → sends to /complete
 //! 4. Server verifies signature → links wallet to account
-//! 5. User can now login with wallet signature
+//! 5. User can now login: GET /api/wallet/login/challenge → sign message →
+//!    POST /api/auth/wallet-login (the challenge is consumed)
 //! ```
 
 use axum::{
-    extract::{Query, State},
+    extract::{ConnectInfo, Extension, Query, State},
     http::StatusCode,
     Json,
 };
+use crate::services::login_challenge::{login_message, ChallengeError, LoginChallengeStore};
 use lib_core::dto::{
-    ErrorResponse, WalletLoginRequest, WalletSetupCompleteRequest, WalletSetupCompleteResponse,
+    ErrorResponse, WalletLoginChallengeRequest, WalletLoginChallengeResponse, WalletLoginRequest, WalletSetupCompleteRequest, WalletSetupCompleteResponse,
     WalletSetupValidateRequest, WalletSetupValidateResponse, AuthResponse, UserInfo,
 };
 use lib_auth::encode_jwt;
 use lib_core::{Config, DbPool};
 use lib_core::model::store::users;
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::net::SocketAddr;
 use std::str::FromStr;
+use std::sync::Arc;
 use tracing::{error, info, warn, instrument};
 use uuid::Uuid;
 use sqlx::FromRow;
@@ -366,12 +371,80 @@ pub async fn complete_wallet_setup(
     }))
 }
 
+/// Issue a single-use login challenge for a wallet.
+///
+/// # Arguments
+///
+/// * `challenges` - Pending login challenge store
+/// * `client` - Address of the caller; pending challenges are capped per address
+/// * `req` - Query parameters containing the wallet address
+///
+/// # Returns
+///
+/// * `Ok(WalletLoginChallengeResponse)` - Challenge and the exact message to sign
+/// * `Err((StatusCode, ErrorResponse))` - Invalid wallet address (400), too many
+///   unused challenges from this address (429), or too many pending overall (503)
+///
+/// # Security
+///
+/// - Challenges are random, bound to the requesting wallet, and expire after
+///   [`CHALLENGE_TTL`](crate::services::login_challenge::CHALLENGE_TTL)
+/// - Each challenge is accepted by [`wallet_login`] once
+/// - The route is rate limited like the login routes
+///
+/// # Example
+///
+/// ```text
+/// GET /api/wallet/login/challenge?wallet_address=7xKXtg...
+/// Response: {
+///   "challenge": "9b4e7f2a8c5d1e3f6a9b2c5d8e1f4a7b",
+///   "message": "Login to XForce Terminal\n\nChallenge: 9b4e7f2a8c5d1e3f6a9b2c5d8e1f4a7b",
+///   "expires_at": 1735689900
+/// }
+/// ```
+#[instrument(skip(challenges, client))]
+pub async fn wallet_login_challenge(
+    State(challenges): State<Arc<LoginChallengeStore>>,
+    client: Option<Extension<ConnectInfo<SocketAddr>>>,
+    Query(req): Query<WalletLoginChallengeRequest>,
+) -> Result<Json<WalletLoginChallengeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    Pubkey::from_str(&req.wallet_address).map_err(|e| {
+        warn!("[WALLET LOGIN] Challenge requested for invalid wallet address: {}", e);
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid wallet address".into(),
+            }),
+        )
+    })?;
+
+    let client = client
+        .map(|Extension(ConnectInfo(addr))| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let challenge = challenges.issue(&req.wallet_address, &client).map_err(|e| {
+        warn!("[WALLET LOGIN] Challenge not issued to {}: {}", client, e);
+        let status = match e {
+            ChallengeError::TooManyForClient => StatusCode::TOO_MANY_REQUESTS,
+            _ => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        (status, Json(ErrorResponse { error: e.to_string() }))
+    })?;
+
+    let ttl = chrono::Duration::from_std(challenges.ttl()).unwrap_or_default();
+    Ok(Json(WalletLoginChallengeResponse {
+        message: login_message(&challenge),
+        challenge,
+        expires_at: (chrono::Utc::now() + ttl).timestamp(),
+    }))
+}
+
 /// Login with wallet by verifying signature to prove wallet ownership.
 ///
 /// # Arguments
 ///
 /// * `db` - Database connection pool
 /// * `config` - Application configuration (JWT secret and expiration)
+/// * `challenges` - Pending login challenges; the one used is consumed
 /// * `req` - Request containing wallet address, signature, and challenge
 ///
 /// # Returns
@@ -383,7 +456,8 @@ pub async fn complete_wallet_setup(
 ///
 /// - Verifies Ed25519 signature using Solana SDK
 /// - Message format: "Login to XForce Terminal\n\nChallenge: {challenge}"
-/// - Challenge should be generated client-side (UUID) to prevent replay attacks
+/// - Challenge must come from [`wallet_login_challenge`] for the same wallet
+/// - Challenges are single-use: a reused or expired challenge returns 401
 /// - Generates JWT token for stateless authentication
 ///
 /// # Validation
@@ -391,16 +465,17 @@ pub async fn complete_wallet_setup(
 /// - Wallet address must be valid Solana public key
 /// - Signature must be valid Ed25519 signature
 /// - Signature must match the challenge message
+/// - Challenge must be pending (issued, unused, not expired)
 /// - Wallet must be linked to an existing user account
 ///
 /// # Example
 ///
 /// ```text
-/// POST /api/wallet/login
+/// POST /api/auth/wallet-login
 /// Body: {
 ///   "wallet_address": "7xKXtg...",
 ///   "signature": "5J7B...",
-///   "challenge": "client-generated-uuid"
+///   "challenge": "9b4e7f2a8c5d1e3f6a9b2c5d8e1f4a7b"
 /// }
 /// Response: {
 ///   "user": { ... },
@@ -408,10 +483,11 @@ pub async fn complete_wallet_setup(
 ///   "message": "Successfully logged in with wallet"
 /// }
 /// ```
-#[instrument(skip(db, config))]
+#[instrument(skip(db, config, challenges))]
 pub async fn wallet_login(
     State(db): State<DbPool>,
     State(config): State<Config>,
+    State(challenges): State<Arc<LoginChallengeStore>>,
     Json(req): Json<WalletLoginRequest>,
 ) -> Result<
     Json<AuthResponse>,
@@ -442,7 +518,7 @@ pub async fn wallet_login(
     })?;
 
     // 3. Construct message
-    let message = format!("Login to XForce Terminal\n\nChallenge: {}", req.challenge);
+    let message = login_message(&req.challenge);
 
     // 4. Verify signature
     if !signature.verify(wallet_pubkey.as_ref(), message.as_bytes()) {
@@ -455,9 +531,18 @@ pub async fn wallet_login(
         ));
     }
 
+    // 5. Use up the challenge; only a signature over a pending challenge counts
+    challenges.consume(&req.wallet_address, &req.challenge).map_err(|e| {
+        warn!("[WALLET LOGIN] Challenge rejected for {}: {}", req.wallet_address, e);
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse { error: e.to_string() }),
+        )
+    })?;
+
     info!("[WALLET LOGIN] Signature verified");
 
-    // 5. Find user by wallet address
+    // 6. Find user by wallet address
     let user = users::find_by_wallet(&db, &req.wallet_address)
         .await
         .map_err(|e| {
@@ -483,7 +568,7 @@ pub async fn wallet_login(
         }
     };
 
-    // 6. Generate JWT token
+    // 7. Generate JWT token
     let token = encode_jwt(
         user.id,
         user.username.clone(),
@@ -501,8 +586,8 @@ pub async fn wallet_login(
 
     info!("[WALLET LOGIN] User {} logged in via wallet", user.username);
 
-        Ok(Json(AuthResponse {
-            user: UserInfo {
+    Ok(Json(AuthResponse {
+        user: UserInfo {
             id: user.id.to_string(),
             username: user.username,
             email: user.email,
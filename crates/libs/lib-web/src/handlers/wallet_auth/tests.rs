@@ -4,6 +4,8 @@ use axum::http::{Request, StatusCode};
 use axum::Router;
 use sqlx::sqlite::SqlitePoolOptions;
 use tower::ServiceExt;
+use crate::services::login_challenge::ChallengeError;
+use std::time::Duration;
 use solana_sdk::signer::{keypair::Keypair, Signer};
 
 /// Setup test database with schema
@@ -45,6 +47,8 @@ fn test_config() -> Config {
         database_url: "sqlite::memory:".to_string(),
         jwt_secret: "test-secret-key-must-be-at-least-32-characters-long!".to_string(),
         jwt_expiration_hours: 24,
+        streamed_symbols: Vec::new(),
+        admin_usernames: Vec::new(),
     }
 }
 
@@ -468,28 +472,32 @@ async fn test_wallet_login_success() {
     struct AppState {
         pool: DbPool,
         config: Config,
+        challenges: Arc<LoginChallengeStore>,
     }
 
+    let challenges = Arc::new(LoginChallengeStore::default());
     let state = AppState {
         pool: pool.clone(),
         config: config.clone(),
+        challenges: challenges.clone(),
     };
 
     let app = Router::new()
         .route("/wallet-login", axum::routing::post(|
-            axum::extract::State(AppState { pool, config }): axum::extract::State<AppState>,
+            axum::extract::State(AppState { pool, config, challenges }): axum::extract::State<AppState>,
             Json(req): Json<WalletLoginRequest>,
         | async move {
             wallet_login(
                 axum::extract::State(pool),
                 axum::extract::State(config),
+                axum::extract::State(challenges),
                 Json(req),
             ).await
         }))
         .with_state(state);
 
-    let challenge = Uuid::new_v4().to_string();
-    let message = format!("Login to XForce Terminal\n\nChallenge: {}", challenge);
+    let challenge = challenges.issue(&wallet_address, "127.0.0.1").unwrap();
+    let message = login_message(&challenge);
     let signature = keypair.sign_message(message.as_bytes());
 
     let request = WalletLoginRequest {
@@ -539,21 +547,25 @@ async fn test_wallet_login_invalid_signature() {
     struct AppState {
         pool: DbPool,
         config: Config,
+        challenges: Arc<LoginChallengeStore>,
     }
 
+    let challenges = Arc::new(LoginChallengeStore::default());
     let state = AppState {
         pool: pool.clone(),
         config: config.clone(),
+        challenges: challenges.clone(),
     };
 
     let app = Router::new()
         .route("/wallet-login", axum::routing::post(|
-            axum::extract::State(AppState { pool, config }): axum::extract::State<AppState>,
+            axum::extract::State(AppState { pool, config, challenges }): axum::extract::State<AppState>,
             Json(req): Json<WalletLoginRequest>,
         | async move {
             wallet_login(
                 axum::extract::State(pool),
                 axum::extract::State(config),
+                axum::extract::State(challenges),
                 Json(req),
             ).await
         }))
@@ -607,28 +619,32 @@ async fn test_wallet_login_wallet_not_linked() {
     struct AppState {
         pool: DbPool,
         config: Config,
+        challenges: Arc<LoginChallengeStore>,
     }
 
+    let challenges = Arc::new(LoginChallengeStore::default());
     let state = AppState {
         pool: pool.clone(),
         config: config.clone(),
+        challenges: challenges.clone(),
     };
 
     let app = Router::new()
         .route("/wallet-login", axum::routing::post(|
-            axum::extract::State(AppState { pool, config }): axum::extract::State<AppState>,
+            axum::extract::State(AppState { pool, config, challenges }): axum::extract::State<AppState>,
             Json(req): Json<WalletLoginRequest>,
         | async move {
             wallet_login(
                 axum::extract::State(pool),
                 axum::extract::State(config),
+                axum::extract::State(challenges),
                 Json(req),
             ).await
         }))
         .with_state(state);
 
-    let challenge = Uuid::new_v4().to_string();
-    let message = format!("Login to XForce Terminal\n\nChallenge: {}", challenge);
+    let challenge = challenges.issue(&wallet_address, "127.0.0.1").unwrap();
+    let message = login_message(&challenge);
     let signature = keypair.sign_message(message.as_bytes());
 
     let request = WalletLoginRequest {
@@ -671,18 +687,20 @@ async fn test_wallet_login_invalid_wallet_address() {
     struct AppState {
         pool: DbPool,
         config: Config,
+        challenges: Arc<LoginChallengeStore>,
     }
 
-    let state = AppState { pool, config };
+    let state = AppState { pool, config, challenges: Arc::new(LoginChallengeStore::default()) };
 
     let app = Router::new()
         .route("/wallet-login", axum::routing::post(|
-            axum::extract::State(AppState { pool, config }): axum::extract::State<AppState>,
+            axum::extract::State(AppState { pool, config, challenges }): axum::extract::State<AppState>,
             Json(req): Json<WalletLoginRequest>,
         | async move {
             wallet_login(
                 axum::extract::State(pool),
                 axum::extract::State(config),
+                axum::extract::State(challenges),
                 Json(req),
             ).await
         }))
@@ -718,3 +736,167 @@ async fn test_wallet_login_invalid_wallet_address() {
     assert_eq!(error_response.error, "Invalid wallet address");
 }
 
+
+// ========== Login Challenge Tests ==========
+
+/// Router with the challenge and login endpoints over one challenge store
+fn login_app(pool: DbPool, challenges: Arc<LoginChallengeStore>) -> Router {
+    #[derive(Clone)]
+    struct AppState {
+        pool: DbPool,
+        config: Config,
+        challenges: Arc<LoginChallengeStore>,
+    }
+
+    let state = AppState { pool, config: test_config(), challenges };
+
+    Router::new()
+        .route("/challenge", axum::routing::get(|
+            axum::extract::State(AppState { challenges, .. }): axum::extract::State<AppState>,
+            query: Query<WalletLoginChallengeRequest>,
+        | async move {
+            wallet_login_challenge(axum::extract::State(challenges), None, query).await
+        }))
+        .route("/wallet-login", axum::routing::post(|
+            axum::extract::State(AppState { pool, config, challenges }): axum::extract::State<AppState>,
+            Json(req): Json<WalletLoginRequest>,
+        | async move {
+            wallet_login(
+                axum::extract::State(pool),
+                axum::extract::State(config),
+                axum::extract::State(challenges),
+                Json(req),
+            ).await
+        }))
+        .with_state(state)
+}
+
+async fn request_challenge(app: &Router, wallet_address: &str) -> (StatusCode, Vec<u8>) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/challenge?wallet_address={}", wallet_address))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, body.to_vec())
+}
+
+async fn post_login(app: &Router, request: &WalletLoginRequest) -> (StatusCode, Vec<u8>) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/wallet-login")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(request).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, body.to_vec())
+}
+
+fn signed_login(keypair: &Keypair, message: &str, challenge: &str) -> WalletLoginRequest {
+    WalletLoginRequest {
+        wallet_address: keypair.pubkey().to_string(),
+        signature: keypair.sign_message(message.as_bytes()).to_string(),
+        challenge: challenge.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_wallet_login_with_issued_challenge() {
+    let pool = setup_test_db().await;
+    let keypair = Keypair::new();
+    let wallet_address = keypair.pubkey().to_string();
+    create_test_user_with_wallet(&pool, &wallet_address).await;
+    let app = login_app(pool, Arc::new(LoginChallengeStore::default()));
+
+    let (status, body) = request_challenge(&app, &wallet_address).await;
+    assert_eq!(status, StatusCode::OK);
+    let issued: WalletLoginChallengeResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(issued.message, login_message(&issued.challenge));
+    assert!(issued.expires_at > chrono::Utc::now().timestamp());
+
+    // The wallet signs exactly the message it was given
+    let (status, body) = post_login(&app, &signed_login(&keypair, &issued.message, &issued.challenge)).await;
+    assert_eq!(status, StatusCode::OK);
+    let auth_response: lib_core::dto::AuthResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(auth_response.user.username, "walletuser");
+}
+
+#[tokio::test]
+async fn test_wallet_login_challenge_invalid_wallet_address() {
+    let pool = setup_test_db().await;
+    let challenges = Arc::new(LoginChallengeStore::default());
+    let app = login_app(pool, challenges.clone());
+
+    let (status, body) = request_challenge(&app, "invalid-address").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error_response: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error_response.error, "Invalid wallet address");
+    assert!(challenges.is_empty());
+}
+
+#[tokio::test]
+async fn test_wallet_login_replay_rejected() {
+    let pool = setup_test_db().await;
+    let keypair = Keypair::new();
+    let wallet_address = keypair.pubkey().to_string();
+    create_test_user_with_wallet(&pool, &wallet_address).await;
+    let challenges = Arc::new(LoginChallengeStore::default());
+    let app = login_app(pool, challenges.clone());
+
+    let challenge = challenges.issue(&wallet_address, "127.0.0.1").unwrap();
+    let request = signed_login(&keypair, &login_message(&challenge), &challenge);
+
+    assert_eq!(post_login(&app, &request).await.0, StatusCode::OK);
+
+    // Same signed message again: the challenge is gone
+    let (status, body) = post_login(&app, &request).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let error_response: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error_response.error, ChallengeError::Unknown.to_string());
+}
+
+#[tokio::test]
+async fn test_wallet_login_client_generated_challenge_rejected() {
+    let pool = setup_test_db().await;
+    let keypair = Keypair::new();
+    let wallet_address = keypair.pubkey().to_string();
+    create_test_user_with_wallet(&pool, &wallet_address).await;
+    let app = login_app(pool, Arc::new(LoginChallengeStore::default()));
+
+    let challenge = Uuid::new_v4().to_string();
+    let request = signed_login(&keypair, &login_message(&challenge), &challenge);
+
+    assert_eq!(post_login(&app, &request).await.0, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_wallet_login_expired_challenge_rejected() {
+    let pool = setup_test_db().await;
+    let keypair = Keypair::new();
+    let wallet_address = keypair.pubkey().to_string();
+    create_test_user_with_wallet(&pool, &wallet_address).await;
+    let challenges = Arc::new(LoginChallengeStore::new(Duration::from_millis(10)));
+    let app = login_app(pool, challenges.clone());
+
+    let challenge = challenges.issue(&wallet_address, "127.0.0.1").unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let request = signed_login(&keypair, &login_message(&challenge), &challenge);
+    let (status, body) = post_login(&app, &request).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let error_response: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error_response.error, ChallengeError::Expired.to_string());
+}
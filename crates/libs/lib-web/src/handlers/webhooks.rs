@@ -0,0 +1,159 @@
+//! # Webhook Handlers
+//!
+//! Manage outgoing webhooks for wallet activity. All routes are authenticated
+//! and only ever touch the caller's own webhooks.
+//!
+//! ## Endpoints
+//!
+//! - `POST /api/webhooks` - Register a webhook (returns the signing secret once)
+//! - `GET /api/webhooks` - List the caller's webhooks
+//! - `DELETE /api/webhooks/{id}` - Remove a webhook and its delivery log
+//! - `GET /api/webhooks/{id}/deliveries` - Recent deliveries, newest first
+//! - `POST /api/webhooks/{id}/test` - Send a test event and report the outcome
+//!
+//! ## Request Example
+//!
+//! ```bash
+//! curl -X POST http://localhost:3001/api/webhooks \
+//!   -H "Authorization: Bearer $TOKEN" \
+//!   -H "Content-Type: application/json" \
+//!   -d '{"url": "https://bot.example/hook", "events": ["incoming_transfer", "balance_threshold"],
+//!        "balance_threshold_sol": 2.5}'
+//! ```
+
+use crate::services::WebhookService;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use lib_auth::Claims;
+use lib_core::{dto::ErrorResponse, AppError};
+use shared::dto::webhooks::{
+    CreateWebhookRequest, CreateWebhookResponse, WebhookDeliveriesResponse, WebhookDeliveryInfo,
+    WebhookListResponse,
+};
+use std::sync::Arc;
+use tracing::{instrument, warn};
+
+type HandlerError = (StatusCode, Json<ErrorResponse>);
+
+fn user_id(claims: &Claims) -> Result<i64, HandlerError> {
+    claims.sub.parse::<i64>().map_err(|_| {
+        (StatusCode::UNAUTHORIZED, Json(ErrorResponse {
+            error: "Invalid user ID in token".to_string(),
+        }))
+    })
+}
+
+fn to_response(e: AppError) -> HandlerError {
+    (e.status_code(), Json(ErrorResponse { error: e.user_message() }))
+}
+
+/// Register a webhook for the authenticated user.
+///
+/// **Route**: `POST /api/webhooks`
+///
+/// **Authentication**: Required (JWT token in Authorization header)
+///
+/// # Returns
+///
+/// Success (200): `Json<CreateWebhookResponse>` - The webhook and its signing secret
+///
+/// Error (400): Bad URL, no or unknown events, missing threshold, or too many webhooks
+/// Error (401): Unauthorized (missing or invalid JWT)
+/// Error (500): Database error
+#[instrument(skip(service, claims, request), fields(user_id = %claims.sub))]
+pub async fn create_webhook(
+    State(service): State<Arc<WebhookService>>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<Json<CreateWebhookResponse>, HandlerError> {
+    let user_id = user_id(&claims)?;
+    let response = service.create(user_id, request).await.map_err(|e| {
+        warn!("[WEBHOOKS] Webhook rejected: {}", e);
+        to_response(e)
+    })?;
+    Ok(Json(response))
+}
+
+/// List the authenticated user's webhooks.
+///
+/// **Route**: `GET /api/webhooks`
+///
+/// **Authentication**: Required (JWT token in Authorization header)
+#[instrument(skip(service, claims), fields(user_id = %claims.sub))]
+pub async fn list_webhooks(
+    State(service): State<Arc<WebhookService>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<WebhookListResponse>, HandlerError> {
+    let user_id = user_id(&claims)?;
+    service.list(user_id).await.map(Json).map_err(to_response)
+}
+
+/// Delete one of the authenticated user's webhooks.
+///
+/// **Route**: `DELETE /api/webhooks/{id}`
+///
+/// **Authentication**: Required (JWT token in Authorization header)
+///
+/// # Returns
+///
+/// Success (204): No content
+///
+/// Error (404): No such webhook for this user
+#[instrument(skip(service, claims), fields(user_id = %claims.sub))]
+pub async fn delete_webhook(
+    State(service): State<Arc<WebhookService>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, HandlerError> {
+    let user_id = user_id(&claims)?;
+    service.delete(user_id, id).await.map_err(to_response)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Recent deliveries of one of the authenticated user's webhooks.
+///
+/// **Route**: `GET /api/webhooks/{id}/deliveries`
+///
+/// **Authentication**: Required (JWT token in Authorization header)
+///
+/// # Returns
+///
+/// Success (200): `Json<WebhookDeliveriesResponse>` - Newest first
+///
+/// Error (404): No such webhook for this user
+#[instrument(skip(service, claims), fields(user_id = %claims.sub))]
+pub async fn list_webhook_deliveries(
+    State(service): State<Arc<WebhookService>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+) -> Result<Json<WebhookDeliveriesResponse>, HandlerError> {
+    let user_id = user_id(&claims)?;
+    service.deliveries(user_id, id).await.map(Json).map_err(to_response)
+}
+
+/// Send a test event to one of the authenticated user's webhooks.
+///
+/// **Route**: `POST /api/webhooks/{id}/test`
+///
+/// **Authentication**: Required (JWT token in Authorization header)
+///
+/// Makes a single attempt and waits for it, so the outcome can be shown right
+/// away. A receiver that fails still gives 200; check `delivered`.
+///
+/// # Returns
+///
+/// Success (200): `Json<WebhookDeliveryInfo>` - The logged delivery
+///
+/// Error (404): No such webhook for this user
+#[instrument(skip(service, claims), fields(user_id = %claims.sub))]
+pub async fn test_webhook(
+    State(service): State<Arc<WebhookService>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+) -> Result<Json<WebhookDeliveryInfo>, HandlerError> {
+    let user_id = user_id(&claims)?;
+    service.send_test(user_id, id).await.map(Json).map_err(to_response)
+}
@@ -7,17 +7,40 @@
 //! - `GET /api/ws/prices` - WebSocket connection for real-time price updates
 
 use lib_solana::price_stream::{PriceStreamServer, PriceUpdateMessage};
+use crate::services::quote_stream::QuoteStreamHandle;
+use crate::services::{ProgramMonitor, QuoteStreamHub};
+use crate::shutdown::{Shutdown, SERVER_RESTARTING_REASON};
+use shared::dto::market::{
+    PriceSubscribeMessage, QuoteSubscribeMessage, QuoteSubscription, QuoteUnsubscribeMessage, QuoteUpdate,
+    CandleUpdate, CandleUpdateMessage, QuoteUpdateMessage,
+};
+use shared::dto::system::{SystemNotice, SystemNoticeMessage};
 use axum::extract::{ws::WebSocketUpgrade, State, ConnectInfo};
 use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Response};
 use std::net::SocketAddr;
 use futures_util::{SinkExt, StreamExt};
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+/// How often the server pings each client
+pub const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Unanswered pings after which a client is dropped as dead
+pub const MAX_MISSED_PONGS: u32 = 3;
+
+/// Pings a client has left unanswered after `silence` without hearing from it
+///
+/// Pongs come back within a round trip, so a live client is never silent for
+/// a whole [`PING_INTERVAL`]; each full interval of silence is one missed pong.
+pub fn missed_pongs(silence: Duration) -> u32 {
+    (silence.as_millis() / PING_INTERVAL.as_millis()).min(u32::MAX as u128) as u32
+}
+
 /// WebSocket handler for real-time price streaming.
 ///
 /// **Route**: `GET /api/ws/prices`
@@ -39,6 +62,38 @@ use uuid::Uuid;
 /// }
 /// ```
 ///
+/// Oracle prices also carry `confidence` (± USD) and `publish_time` (Unix
+/// seconds); both are left out when the source has none, so clients written
+/// before they existed keep working.
+///
+/// Candles are streamed as they form, as `"type": "candle_update"` with
+/// `symbol`, `timeframe`, `candle` (OHLC) and `is_closed`: every price tick
+/// sends the forming candle of each timeframe with `is_closed: false`, and a
+/// candle is sent once more with `is_closed: true` when its period ends.
+///
+/// System notices raised by backend jobs (e.g. a monitored program upgrade, a
+/// symbol whose price sources keep disagreeing, or an announced maintenance
+/// window) are pushed on the same connection with `"type": "system_notice"`.
+///
+/// A client may narrow the price and candle updates to some symbols by sending
+/// `{"type": "subscribe", "data": {"symbols": ["SOL", "BONK"]}}`; an empty
+/// list streams everything again. System notices are always sent.
+///
+/// A client may also stream quotes for one swap by sending
+/// `{"type": "quote_subscribe", "data": {"input_mint": .., "output_mint": ..,
+/// "amount": 1000000000, "slippage": 50}}`; a later `quote_subscribe`
+/// replaces it and `{"type": "quote_unsubscribe"}` ends it. Quotes arrive as
+/// `"type": "quote_update"` from the [`QuoteStreamHub`], which shares one
+/// Jupiter poller between all clients asking for the same quote.
+///
+/// The server pings every [`PING_INTERVAL`] and closes connections that miss
+/// [`MAX_MISSED_PONGS`] pongs in a row, so half-dead connections (e.g. after a
+/// NAT timeout) don't keep their subscription and broadcast receiver forever.
+///
+/// When the server shuts down, every connection is closed with `1012 Service
+/// Restart` and [`SERVER_RESTARTING_REASON`], so clients can hold off their
+/// reconnects until it's back.
+///
 /// # Example
 ///
 /// ```javascript
@@ -53,6 +108,9 @@ pub async fn price_stream_websocket(
     headers: HeaderMap,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     State(price_stream): State<Arc<PriceStreamServer>>,
+    State(program_monitor): State<Arc<ProgramMonitor>>,
+    State(quote_streams): State<QuoteStreamHub>,
+    State(shutdown): State<Shutdown>,
 ) -> Response {
     // Extract connection metadata from request
     let client_id = Uuid::new_v4().to_string();
@@ -112,6 +170,9 @@ pub async fn price_stream_websocket(
         "[WS] Subscribing to price stream..."
     );
     let price_rx = price_stream.subscribe();
+    let notice_rx = program_monitor.subscribe();
+    let divergence_rx = price_stream.subscribe_notices();
+    let candle_rx = price_stream.subscribe_candles();
     
     // Verify price stream receiver is valid
     let receiver_is_empty = price_rx.is_empty();
@@ -159,7 +220,7 @@ pub async fn price_stream_websocket(
         
         // Spawn handler task with panic handling
         let handle = tokio::task::spawn(async move {
-            handle_price_websocket(socket, price_rx, client_id_clone, client_ip_clone, user_agent_clone).await;
+            handle_price_websocket(socket, price_rx, candle_rx, notice_rx, divergence_rx, quote_streams, shutdown, client_id_clone, client_ip_clone, user_agent_clone).await;
         });
         
         // Wait for handler and log any panics
@@ -190,12 +251,23 @@ pub async fn price_stream_websocket(
 /// # Arguments
 /// * `socket` - WebSocket stream
 /// * `price_rx` - Receiver for price updates from the stream server
+/// * `candle_rx` - Receiver for forming and closed candles from the stream server
+/// * `notice_rx` - Receiver for system notices from the program monitor
+/// * `divergence_rx` - Receiver for divergence and maintenance notices from the stream server
+/// * `quote_streams` - Hub the client's quote subscription is served from
+/// * `shutdown` - Closes the connection when the server shuts down
 /// * `client_id` - Unique identifier for this connection
 /// * `client_ip` - Client IP address if available
 /// * `user_agent` - User agent string if available
+#[allow(clippy::too_many_arguments)]
 async fn handle_price_websocket(
     socket: axum::extract::ws::WebSocket,
     mut price_rx: tokio::sync::broadcast::Receiver<PriceUpdateMessage>,
+    mut candle_rx: tokio::sync::broadcast::Receiver<CandleUpdate>,
+    mut notice_rx: tokio::sync::broadcast::Receiver<SystemNotice>,
+    mut divergence_rx: tokio::sync::broadcast::Receiver<SystemNotice>,
+    quote_streams: QuoteStreamHub,
+    shutdown: Shutdown,
     client_id: String,
     client_ip: Option<String>,
     _user_agent: Option<String>,
@@ -204,6 +276,13 @@ async fn handle_price_websocket(
     let connection_start = Instant::now();
     let messages_sent = Arc::new(AtomicU64::new(0));
     let messages_received = Arc::new(AtomicU64::new(0));
+    // Uppercase symbols the client subscribed to; `None` streams everything
+    let subscription: Arc<RwLock<Option<HashSet<String>>>> = Arc::new(RwLock::new(None));
+    // Milliseconds since `connection_start` at which the client was last heard from
+    let last_seen_ms = Arc::new(AtomicU64::new(0));
+    // Quote subscription changes, from the receiving task to the sending one,
+    // which holds the stream; `None` ends it
+    let (quote_tx, mut quote_rx) = tokio::sync::mpsc::unbounded_channel::<Option<QuoteSubscription>>();
     
     info!(
         client_id = %client_id,
@@ -213,12 +292,103 @@ async fn handle_price_websocket(
         client_ip
     );
     
-    // Spawn task to send price updates to client
+    // Spawn task to send price updates and system notices to client
     let client_id_send = client_id.clone();
     let messages_sent_send = Arc::clone(&messages_sent);
+    let subscription_send = Arc::clone(&subscription);
+    let last_seen_send = Arc::clone(&last_seen_ms);
     let mut send_task = tokio::spawn(async move {
-        while let Ok(update) = price_rx.recv().await {
-            let json = match serde_json::to_string(&update) {
+        let mut quote_stream: Option<QuoteStreamHandle> = None;
+        let mut ping_interval = tokio::time::interval_at(tokio::time::Instant::now() + PING_INTERVAL, PING_INTERVAL);
+        loop {
+            let (message_type, serialized) = tokio::select! {
+                _ = shutdown.triggered() => {
+                    info!(
+                        client_id = %client_id_send,
+                        "[WS] SHUTDOWN client_id={} - closing connection",
+                        client_id_send
+                    );
+                    let frame = axum::extract::ws::CloseFrame {
+                        code: axum::extract::ws::close_code::RESTART,
+                        reason: SERVER_RESTARTING_REASON.into(),
+                    };
+                    let _ = sender.send(axum::extract::ws::Message::Close(Some(frame))).await;
+                    break;
+                }
+                _ = ping_interval.tick() => {
+                    let last_seen = Duration::from_millis(last_seen_send.load(Ordering::Relaxed));
+                    let missed = missed_pongs(connection_start.elapsed().saturating_sub(last_seen));
+                    if missed >= MAX_MISSED_PONGS {
+                        warn!(
+                            client_id = %client_id_send,
+                            missed_pongs = missed,
+                            "[WS] DEAD_CLIENT client_id={} missed_pongs={} - closing connection",
+                            client_id_send,
+                            missed
+                        );
+                        break;
+                    }
+                    if let Err(e) = sender.send(axum::extract::ws::Message::Ping(Default::default())).await {
+                        warn!(
+                            client_id = %client_id_send,
+                            error = %e,
+                            "[WS] PING_ERROR client_id={} error={}",
+                            client_id_send,
+                            e
+                        );
+                        break;
+                    }
+                    continue;
+                }
+                update = price_rx.recv() => match update {
+                    Ok(update) => {
+                        if !is_subscribed(&subscription_send, &update.data.symbol) {
+                            continue;
+                        }
+                        ("price_update", serde_json::to_string(&update))
+                    }
+                    Err(_) => break,
+                },
+                update = candle_rx.recv() => match update {
+                    Ok(update) => {
+                        if !is_subscribed(&subscription_send, &update.symbol) {
+                            continue;
+                        }
+                        (CandleUpdateMessage::TYPE, serde_json::to_string(&CandleUpdateMessage::new(update)))
+                    }
+                    // A forming candle is superseded by the next tick anyway
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                },
+                notice = notice_rx.recv() => match notice {
+                    Ok(notice) => (SystemNoticeMessage::TYPE, serde_json::to_string(&SystemNoticeMessage::new(notice))),
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                },
+                notice = divergence_rx.recv() => match notice {
+                    Ok(notice) => (SystemNoticeMessage::TYPE, serde_json::to_string(&SystemNoticeMessage::new(notice))),
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                },
+                change = quote_rx.recv() => {
+                    let Some(subscription) = change else {
+                        break;
+                    };
+                    // Joined before the old handle is dropped, so resubscribing
+                    // to the same quote keeps its poller running
+                    let next = subscription.map(|subscription| quote_streams.subscribe(subscription));
+                    quote_stream = next;
+                    continue;
+                }
+                update = next_quote(&mut quote_stream) => match update {
+                    Some(update) => (QuoteUpdateMessage::TYPE, serde_json::to_string(&QuoteUpdateMessage::new(QuoteUpdate::clone(&update)))),
+                    None => {
+                        quote_stream = None;
+                        continue;
+                    }
+                },
+            };
+            let json = match serialized {
                 Ok(json) => json,
                 Err(e) => {
                     error!(
@@ -238,11 +408,12 @@ async fn handle_price_websocket(
                     let count = messages_sent_send.fetch_add(1, Ordering::Relaxed) + 1;
                     debug!(
                         client_id = %client_id_send,
-                        message_type = "price_update",
+                        message_type,
                         message_size,
                         total_sent = count,
-                        "[WS] MESSAGE_SENT client_id={} type=price_update size={} total={}",
+                        "[WS] MESSAGE_SENT client_id={} type={} size={} total={}",
                         client_id_send,
+                        message_type,
                         message_size,
                         count
                     );
@@ -267,8 +438,12 @@ async fn handle_price_websocket(
     // Handle incoming messages from client (ping/pong, close, etc.)
     let client_id_recv = client_id.clone();
     let messages_received_recv = Arc::clone(&messages_received);
+    let subscription_recv = Arc::clone(&subscription);
+    let last_seen_recv = Arc::clone(&last_seen_ms);
     let mut recv_task = tokio::spawn(async move {
         while let Some(msg) = receiver.next().await {
+            // Any frame proves the client is alive, not only pongs
+            last_seen_recv.store(connection_start.elapsed().as_millis() as u64, Ordering::Relaxed);
             match msg {
                 Ok(axum::extract::ws::Message::Close(frame)) => {
                     let close_reason = frame
@@ -316,7 +491,53 @@ async fn handle_price_websocket(
                         text.len(),
                         text
                     );
-                    // Could implement subscription filtering here if needed
+                    if let Ok(message) = serde_json::from_str::<PriceSubscribeMessage>(&text) {
+                        if message.message_type == PriceSubscribeMessage::TYPE {
+                            let symbols: HashSet<String> =
+                                message.data.symbols.iter().map(|s| s.to_uppercase()).collect();
+                            info!(
+                                client_id = %client_id_recv,
+                                symbols = symbols.len(),
+                                "[WS] SUBSCRIBED client_id={} symbols={:?}",
+                                client_id_recv,
+                                symbols
+                            );
+                            if let Ok(mut subscription) = subscription_recv.write() {
+                                *subscription = (!symbols.is_empty()).then_some(symbols);
+                            }
+                            continue;
+                        }
+                    }
+                    if let Ok(message) = serde_json::from_str::<QuoteSubscribeMessage>(&text) {
+                        if message.message_type == QuoteSubscribeMessage::TYPE {
+                            if let Err(reason) = validate_quote_subscription(&message.data) {
+                                warn!(
+                                    client_id = %client_id_recv,
+                                    "[WS] QUOTE_REJECTED client_id={} reason={}",
+                                    client_id_recv,
+                                    reason
+                                );
+                                continue;
+                            }
+                            info!(
+                                client_id = %client_id_recv,
+                                "[WS] QUOTE_SUBSCRIBED client_id={} pair={}->{} amount={}",
+                                client_id_recv,
+                                message.data.input_mint,
+                                message.data.output_mint,
+                                message.data.amount
+                            );
+                            if quote_tx.send(Some(message.data)).is_err() {
+                                break;
+                            }
+                            continue;
+                        }
+                    }
+                    if let Ok(message) = serde_json::from_str::<QuoteUnsubscribeMessage>(&text) {
+                        if message.message_type == QuoteUnsubscribeMessage::TYPE && quote_tx.send(None).is_err() {
+                            break;
+                        }
+                    }
                 }
                 Ok(axum::extract::ws::Message::Binary(data)) => {
                     messages_received_recv.fetch_add(1, Ordering::Relaxed);
@@ -370,6 +591,11 @@ async fn handle_price_websocket(
         }
     }
     
+    // Release the client's subscription; its broadcast receivers and quote stream went with the tasks
+    if let Ok(mut subscription) = subscription.write() {
+        *subscription = None;
+    }
+
     let duration = connection_start.elapsed();
     let sent_count = messages_sent.load(Ordering::Relaxed);
     let received_count = messages_received.load(Ordering::Relaxed);
@@ -389,3 +615,65 @@ async fn handle_price_websocket(
         received_count
     );
 }
+
+/// Wait for the next quote of `stream`; never completes without one
+async fn next_quote(stream: &mut Option<QuoteStreamHandle>) -> Option<Arc<QuoteUpdate>> {
+    match stream {
+        Some(stream) => stream.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Reject quote subscriptions Jupiter would refuse anyway
+/// Whether a client's symbol subscription covers `symbol` (no subscription
+/// covers everything)
+fn is_subscribed(subscription: &RwLock<Option<HashSet<String>>>, symbol: &str) -> bool {
+    subscription
+        .read()
+        .map(|s| s.as_ref().is_none_or(|symbols| symbols.contains(&symbol.to_uppercase())))
+        .unwrap_or(true)
+}
+
+fn validate_quote_subscription(subscription: &QuoteSubscription) -> Result<(), &'static str> {
+    if subscription.input_mint.is_empty() || subscription.output_mint.is_empty() {
+        return Err("missing mint");
+    }
+    if subscription.input_mint == subscription.output_mint {
+        return Err("input and output are the same token");
+    }
+    if subscription.amount == 0 {
+        return Err("zero amount");
+    }
+    if subscription.slippage_bps > 10_000 {
+        return Err("slippage over 100%");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missed_pongs_counts_whole_intervals() {
+        assert_eq!(missed_pongs(Duration::ZERO), 0);
+        // A pong answered just before the next ping is not missed
+        assert_eq!(missed_pongs(PING_INTERVAL - Duration::from_millis(1)), 0);
+        assert_eq!(missed_pongs(PING_INTERVAL), 1);
+        assert_eq!(missed_pongs(PING_INTERVAL * 3 - Duration::from_millis(1)), 2);
+        assert_eq!(missed_pongs(PING_INTERVAL * 3), MAX_MISSED_PONGS);
+        assert_eq!(missed_pongs(Duration::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn test_dead_client_dropped_after_three_unanswered_pings() {
+        // The client answers the ping at 10s and then goes silent. Pings at
+        // 20s, 30s and 40s go unanswered, so the tick at 50s drops it.
+        let last_seen = PING_INTERVAL + Duration::from_millis(40);
+        let is_dead = |tick: u32| missed_pongs(PING_INTERVAL * tick - last_seen) >= MAX_MISSED_PONGS;
+        assert!(!is_dead(2));
+        assert!(!is_dead(3));
+        assert!(!is_dead(4));
+        assert!(is_dead(5));
+    }
+}
@@ -1,13 +1,18 @@
 //! # Web Library
 //!
-//! HTTP handlers, middleware, routes, and web services.
+//! HTTP handlers, middleware, routes, web services, and HTML templates.
 
 pub mod handlers;
 pub mod middleware;
 pub mod routes;
 pub mod services;
+pub mod templates;
 pub mod chat;
 pub mod server;
+pub mod self_test;
+pub mod shutdown;
+pub mod wallet_assets;
 
 pub use server::{start_server, ServerConfig, AppState};
+pub use self_test::run_self_test;
 
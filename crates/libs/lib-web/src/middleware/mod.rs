@@ -1,10 +1,13 @@
 //! # Middleware
 //!
-//! Axum middleware for authentication, request stamping, and response mapping.
+//! Axum middleware for authentication, rate limiting, request stamping,
+//! metrics, and response mapping.
 //!
 //! ## Modules
 //!
-//! - **[`mw_auth`]**: JWT authentication middleware
+//! - **[`mw_auth`]**: JWT and API key authentication middleware
+//! - **[`mw_metrics`]**: Request counts and durations for `GET /metrics`
+//! - **[`mw_rate_limit`]**: Token-bucket limiter for the login endpoints
 //! - **[`mw_req_stamp`]**: Request ID and timestamp stamping
 //! - **[`mw_res_map`]**: Response mapping and standardization
 
@@ -13,12 +16,16 @@ pub mod mw_auth;
 pub mod mw_req_stamp;
 pub mod mw_res_map;
 pub mod mw_logging;
+pub mod mw_rate_limit;
+pub mod mw_metrics;
 // endregion: --- Modules
 
 // region: --- Re-exports
-pub use mw_auth::require_auth;
+pub use mw_auth::{require_auth, require_session};
 pub use mw_req_stamp::{stamp_req, RequestStamp};
 pub use mw_res_map::map_res;
 pub use mw_logging::log_requests;
+pub use mw_rate_limit::{rate_limit_auth, RateLimiter};
+pub use mw_metrics::record_metrics;
 // endregion: --- Re-exports
 
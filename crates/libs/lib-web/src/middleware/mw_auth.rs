@@ -3,17 +3,22 @@
 //! Axum middleware for JWT token validation and user authentication.
 //!
 //! This middleware extracts and validates JWT tokens from the `Authorization` header,
-//! then injects the authenticated user's claims into the request extensions.
+//! rejects tokens revoked by logout, then injects the authenticated user's claims
+//! into the request extensions. Scripts can send an API key in the `X-Api-Key`
+//! header instead; it resolves to the same claims, limited to the key's scopes.
 //!
 //! ## Usage
 //!
+//! The middleware reads the database pool and config from the router state:
+//!
 //! ```rust,no_run
 //! use axum::{Router, routing::get};
 //! use lib_web::middleware::mw_auth::require_auth;
 //!
 //! let app = Router::new()
 //!     .route("/protected", get(protected_handler))
-//!     .layer(require_auth());
+//!     .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_auth))
+//!     .with_state(state);
 //! ```
 //!
 //! Handlers can then extract claims using `Extension<Claims>`:
@@ -26,26 +31,53 @@
 //!     format!("Hello, user {}!", claims.username)
 //! }
 //! ```
+//!
+//! ## Revocation
+//!
+//! `POST /api/auth/logout` stores the token's [`Claims::revocation_id`] in the
+//! `revoked_tokens` table until the token's own expiry;
+//! [`spawn_revocation_cleanup`] deletes rows past that point.
+//!
+//! `PUT /api/auth/password` ends every other session of the user by recording
+//! a cutoff in `revoked_sessions`; tokens issued before it are rejected.
+//!
+//! ## API Keys
+//!
+//! A request without an `Authorization` header may authenticate with an
+//! `X-Api-Key` header (see [`crate::services::api_keys`]). Read-only keys may
+//! only make `GET` requests; anything else needs the `write` scope and is
+//! otherwise answered with `403 Forbidden`. Routes that manage the account
+//! itself are additionally wrapped in [`require_session`], which turns away
+//! API keys altogether.
 
+use crate::services::api_keys;
 use axum::{
-    extract::Request,
-    http::{header::AUTHORIZATION, StatusCode},
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, Method, StatusCode},
     middleware::Next,
     response::Response,
 };
-use lib_auth::decode_jwt;
-use lib_core::config::core_config;
-use tracing::{debug, warn};
+use lib_auth::{decode_jwt, Claims, SCOPE_READ, SCOPE_WRITE};
+use shared::dto::api_keys::API_KEY_HEADER;
+use lib_core::model::store::RevokedTokenRepository;
+use lib_core::{Config, DbPool};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
 
-/// Authentication middleware that validates JWT tokens.
+/// Authentication middleware that validates JWT tokens and API keys.
 ///
 /// Extracts the `Authorization: Bearer <token>` header, validates the JWT token,
-/// and injects the `Claims` into request extensions for use by handlers.
+/// checks it has not been revoked, and injects the `Claims` into request
+/// extensions for use by handlers. Without an `Authorization` header, an
+/// `X-Api-Key` header is resolved to its owner's claims instead.
 ///
 /// # Behavior
 ///
 /// - **Valid token**: Continues to next middleware/handler with `Claims` in extensions
-/// - **Missing/invalid token**: Returns `401 Unauthorized` with error message
+/// - **Missing/invalid/revoked token, or issued before a password change**: Returns `401 Unauthorized`
+/// - **Unknown or revoked API key**: Returns `401 Unauthorized`
+/// - **API key without the scope the method needs**: Returns `403 Forbidden`
+/// - **Revocation or key lookup fails**: Returns `500 Internal Server Error` (fails closed)
 ///
 /// # Example
 ///
@@ -55,9 +87,27 @@ use tracing::{debug, warn};
 ///
 /// let app = Router::new()
 ///     .route("/api/protected", get(handler))
-///     .layer(require_auth());
+///     .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_auth));
 /// ```
-pub async fn require_auth(mut req: Request, next: Next) -> Result<Response, StatusCode> {
+pub async fn require_auth(
+    State(db): State<DbPool>,
+    State(config): State<Config>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    // Scripts send an API key instead of a session token
+    if !req.headers().contains_key(AUTHORIZATION) {
+        if let Some(key) = req.headers().get(API_KEY_HEADER) {
+            let key = key.to_str().map_err(|_| {
+                warn!("[AUTH] Malformed API key header");
+                StatusCode::UNAUTHORIZED
+            })?;
+            let claims = api_key_claims(&db, key, req.method()).await?;
+            req.extensions_mut().insert(claims);
+            return Ok(next.run(req).await);
+        }
+    }
+
     // Extract Authorization header
     let auth_header = req
         .headers()
@@ -77,13 +127,44 @@ pub async fn require_auth(mut req: Request, next: Next) -> Result<Response, Stat
         })?;
 
     // Decode and validate JWT
-    let config = core_config();
-    let claims = decode_jwt(token, &config.jwt_secret)
+    let claims: Claims = decode_jwt(token, &config.jwt_secret)
         .map_err(|e| {
             warn!("[AUTH] JWT validation failed: {}", e);
             StatusCode::UNAUTHORIZED
         })?;
 
+    // Reject tokens that were logged out
+    let revoked = RevokedTokenRepository::is_revoked(&db, &claims.revocation_id())
+        .await
+        .map_err(|e| {
+            error!("[AUTH] Revocation lookup failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if revoked {
+        warn!("[AUTH] Revoked token used by user {}", claims.sub);
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    // Reject tokens issued before the user's last password change
+    let user_id = claims.sub.parse::<i64>().map_err(|_| {
+        warn!("[AUTH] Token subject is not a user id: {}", claims.sub);
+        StatusCode::UNAUTHORIZED
+    })?;
+    let issued_at = chrono::DateTime::from_timestamp(claims.iat, 0).ok_or_else(|| {
+        warn!("[AUTH] Token issue time out of range: {}", claims.iat);
+        StatusCode::UNAUTHORIZED
+    })?;
+    let session_revoked = RevokedTokenRepository::is_session_revoked(&db, user_id, issued_at, &claims.revocation_id())
+        .await
+        .map_err(|e| {
+            error!("[AUTH] Session revocation lookup failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if session_revoked {
+        warn!("[AUTH] Token from before a password change used by user {}", claims.sub);
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
     debug!("[AUTH] Authenticated user: {} (id: {})", claims.username, claims.sub);
 
     // Inject claims into request extensions
@@ -93,3 +174,271 @@ pub async fn require_auth(mut req: Request, next: Next) -> Result<Response, Stat
     Ok(next.run(req).await)
 }
 
+/// Reject requests authenticated with an API key.
+///
+/// Layered inside [`require_auth`] on the routes that manage the account
+/// itself (session, password, API keys), so a leaked key can't be turned
+/// into a login session or more keys.
+///
+/// # Behavior
+///
+/// - **Login session**: Continues to next middleware/handler
+/// - **API key**: Returns `403 Forbidden`
+/// - **No claims** (not behind `require_auth`): Returns `401 Unauthorized`
+pub async fn require_session(req: Request, next: Next) -> Result<Response, StatusCode> {
+    match req.extensions().get::<Claims>() {
+        Some(claims) if !claims.is_api_key() => Ok(next.run(req).await),
+        Some(claims) => {
+            warn!("[AUTH] API key of user {} used on {}", claims.sub, req.uri().path());
+            Err(StatusCode::FORBIDDEN)
+        }
+        None => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+/// Scope a request with `method` needs
+fn required_scope(method: &Method) -> &'static str {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        SCOPE_READ
+    } else {
+        SCOPE_WRITE
+    }
+}
+
+/// Claims of an API key's owner, if the key may make a `method` request
+async fn api_key_claims(db: &DbPool, key: &str, method: &Method) -> Result<Claims, StatusCode> {
+    let claims = api_keys::authenticate(db, key)
+        .await
+        .map_err(|e| {
+            error!("[AUTH] API key lookup failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or_else(|| {
+            warn!("[AUTH] Unknown or revoked API key");
+            StatusCode::UNAUTHORIZED
+        })?;
+
+    let scope = required_scope(method);
+    if !claims.has_scope(scope) {
+        warn!("[AUTH] API key of user {} lacks the {} scope for {}", claims.sub, scope, method);
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    debug!("[AUTH] Authenticated user: {} (id: {}) with an API key", claims.username, claims.sub);
+    Ok(claims)
+}
+
+/// Delete revocations of expired tokens every `every` in the background
+pub fn spawn_revocation_cleanup(db: DbPool, every: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match RevokedTokenRepository::delete_expired(&db, chrono::Utc::now()).await {
+                Ok(0) => {}
+                Ok(removed) => info!("[AUTH] Dropped {} expired token revocations", removed),
+                Err(e) => error!("[AUTH] Token revocation cleanup failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::api_keys::{hash_api_key, new_api_key};
+    use axum::{body::Body, extract::FromRef, routing::{get, post}, Extension, Router};
+    use lib_auth::encode_jwt;
+    use lib_core::model::store::ApiKeyRepository;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tower::ServiceExt;
+
+    #[derive(Clone)]
+    struct TestState {
+        db: DbPool,
+        config: Config,
+    }
+
+    impl FromRef<TestState> for DbPool {
+        fn from_ref(state: &TestState) -> Self {
+            state.db.clone()
+        }
+    }
+
+    impl FromRef<TestState> for Config {
+        fn from_ref(state: &TestState) -> Self {
+            state.config.clone()
+        }
+    }
+
+    async fn setup() -> (Router, TestState) {
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE revoked_tokens (jti TEXT PRIMARY KEY, user_id INTEGER NOT NULL, expires_at DATETIME NOT NULL, revoked_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP)"
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "CREATE TABLE revoked_sessions (user_id INTEGER PRIMARY KEY, revoked_before DATETIME NOT NULL, kept_jti TEXT)"
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            CREATE TABLE users (
+                id INTEGER PRIMARY KEY, username TEXT NOT NULL, email TEXT NOT NULL, password_hash TEXT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP, updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                last_login DATETIME, is_active BOOLEAN NOT NULL DEFAULT 1, wallet_address TEXT, wallet_connected_at DATETIME,
+                wallet_setup_token TEXT, wallet_setup_token_expires_at DATETIME
+            )
+            "#
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            CREATE TABLE api_keys (
+                id INTEGER PRIMARY KEY AUTOINCREMENT, user_id INTEGER NOT NULL, key_hash TEXT NOT NULL UNIQUE,
+                prefix TEXT NOT NULL, label TEXT NOT NULL, scopes TEXT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP, last_used_at DATETIME
+            )
+            "#
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO users (id, username, email, password_hash) VALUES (1, 'alice', 'alice@example.com', 'x')")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let state = TestState {
+            db,
+            config: Config {
+                database_url: "sqlite::memory:".to_string(),
+                jwt_secret: "test-secret-key-must-be-at-least-32-characters-long!".to_string(),
+                jwt_expiration_hours: 24,
+                streamed_symbols: Vec::new(),
+                admin_usernames: Vec::new(),
+            },
+        };
+        let me = |Extension(claims): Extension<Claims>| async move { claims.username };
+        let session_routes = Router::new()
+            .route("/session", post(me))
+            .route_layer(axum::middleware::from_fn(require_session));
+        let app = Router::new()
+            .merge(session_routes)
+            .route("/me", get(me).post(me))
+            .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_auth))
+            .with_state(state.clone());
+        (app, state)
+    }
+
+    /// A key of alice with `scopes`
+    async fn create_key(state: &TestState, scopes: &str) -> (i64, String) {
+        let key = new_api_key();
+        let api_key = ApiKeyRepository::create(&state.db, 1, &hash_api_key(&key), "xfk_test", "Script", scopes)
+            .await
+            .unwrap();
+        (api_key.id, key)
+    }
+
+    async fn with_key(app: &Router, method: Method, path: &str, key: &str) -> StatusCode {
+        let request = Request::builder().method(method).uri(path).header(API_KEY_HEADER, key);
+        app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap().status()
+    }
+
+    async fn get_me(app: &Router, token: Option<&str>) -> StatusCode {
+        let mut request = Request::get("/me");
+        if let Some(token) = token {
+            request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_valid_token_passes_and_revoked_token_is_rejected() {
+        let (app, state) = setup().await;
+        let token = encode_jwt(1, "alice".to_string(), &state.config.jwt_secret, 24).unwrap();
+        let other = encode_jwt(1, "alice".to_string(), &state.config.jwt_secret, 24).unwrap();
+
+        assert_eq!(get_me(&app, Some(&token)).await, StatusCode::OK);
+
+        let claims = decode_jwt(&token, &state.config.jwt_secret).unwrap();
+        let expires_at = chrono::DateTime::from_timestamp(claims.exp, 0).unwrap();
+        RevokedTokenRepository::revoke(&state.db, &claims.revocation_id(), 1, expires_at)
+            .await
+            .unwrap();
+
+        assert_eq!(get_me(&app, Some(&token)).await, StatusCode::UNAUTHORIZED);
+        // Other sessions of the same user are unaffected
+        assert_eq!(get_me(&app, Some(&other)).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_before_password_change_are_rejected() {
+        let (app, state) = setup().await;
+        let secret = &state.config.jwt_secret;
+        let old = encode_jwt(1, "alice".to_string(), secret, 24).unwrap();
+        let kept = encode_jwt(1, "alice".to_string(), secret, 24).unwrap();
+        let other_user = encode_jwt(2, "bob".to_string(), secret, 24).unwrap();
+
+        let kept_claims = decode_jwt(&kept, secret).unwrap();
+        RevokedTokenRepository::revoke_sessions(&state.db, 1, chrono::Utc::now(), Some(&kept_claims.revocation_id()))
+            .await
+            .unwrap();
+
+        assert_eq!(get_me(&app, Some(&old)).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(get_me(&app, Some(&kept)).await, StatusCode::OK);
+        assert_eq!(get_me(&app, Some(&other_user)).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_key_cannot_write() {
+        let (app, state) = setup().await;
+        let (_, key) = create_key(&state, "read").await;
+
+        assert_eq!(with_key(&app, Method::GET, "/me", &key).await, StatusCode::OK);
+        assert_eq!(with_key(&app, Method::POST, "/me", &key).await, StatusCode::FORBIDDEN);
+
+        let (_, write_key) = create_key(&state, "read,write").await;
+        assert_eq!(with_key(&app, Method::POST, "/me", &write_key).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_keys_cannot_manage_the_account() {
+        let (app, state) = setup().await;
+        let (_, key) = create_key(&state, "read,write").await;
+        assert_eq!(with_key(&app, Method::POST, "/session", &key).await, StatusCode::FORBIDDEN);
+
+        let token = encode_jwt(1, "alice".to_string(), &state.config.jwt_secret, 24).unwrap();
+        let request = Request::post("/session").header(AUTHORIZATION, format!("Bearer {}", token));
+        let status = app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap().status();
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_or_revoked_key_is_rejected() {
+        let (app, state) = setup().await;
+        let (id, key) = create_key(&state, "read").await;
+        assert_eq!(with_key(&app, Method::GET, "/me", &new_api_key()).await, StatusCode::UNAUTHORIZED);
+
+        assert!(ApiKeyRepository::delete(&state.db, id, 1).await.unwrap());
+        assert_eq!(with_key(&app, Method::GET, "/me", &key).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_missing_or_invalid_token_is_rejected() {
+        let (app, _) = setup().await;
+        assert_eq!(get_me(&app, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(get_me(&app, Some("not-a-jwt")).await, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,109 @@
+//! # Metrics Middleware
+//!
+//! Counts every HTTP request and times it, for the `GET /metrics` scrape.
+//!
+//! Requests are labelled with their method, the route template they matched
+//! (`/api/webhooks/{id}`, never the concrete path, so the series stay bounded)
+//! and the response status. Requests that matched no route share the
+//! `unmatched` route label.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use axum::{Router, routing::get};
+//! use lib_core::Metrics;
+//! use lib_web::middleware::mw_metrics::record_metrics;
+//! use std::sync::Arc;
+//! # async fn handler() {}
+//!
+//! let metrics = Arc::new(Metrics::new());
+//! let app: Router = Router::new()
+//!     .route("/api/endpoint", get(handler))
+//!     .layer(axum::middleware::from_fn_with_state(metrics, record_metrics));
+//! ```
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use lib_core::metrics::{HTTP_REQUESTS, HTTP_REQUEST_DURATION};
+use lib_core::Metrics;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Route label of requests that matched no route
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Record the count, status and duration of a request
+pub async fn record_metrics(State(metrics): State<Arc<Metrics>>, req: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+
+    let response = next.run(req).await;
+
+    let status = response.status().as_u16().to_string();
+    let labels = [("method", method.as_str()), ("route", route.as_str())];
+    metrics.increment(&HTTP_REQUESTS, &[labels[0], labels[1], ("status", status.as_str())]);
+    metrics.observe(&HTTP_REQUEST_DURATION, &labels, started.elapsed().as_secs_f64());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(metrics: Arc<Metrics>) -> Router {
+        Router::new()
+            .route("/items/{id}", get(|| async { "item" }))
+            .route("/broken", get(|| async { StatusCode::INTERNAL_SERVER_ERROR }))
+            .layer(axum::middleware::from_fn_with_state(metrics, record_metrics))
+    }
+
+    async fn get_path(app: &Router, path: &str) -> StatusCode {
+        app.clone()
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_counts_requests_by_route_template_and_status() {
+        let metrics = Arc::new(Metrics::new());
+        let app = app(Arc::clone(&metrics));
+
+        assert_eq!(get_path(&app, "/items/1").await, StatusCode::OK);
+        assert_eq!(get_path(&app, "/items/2").await, StatusCode::OK);
+        assert_eq!(get_path(&app, "/broken").await, StatusCode::INTERNAL_SERVER_ERROR);
+
+        let ok = [("method", "GET"), ("route", "/items/{id}"), ("status", "200")];
+        assert_eq!(metrics.counter(&HTTP_REQUESTS, &ok), 2);
+        let failed = [("method", "GET"), ("route", "/broken"), ("status", "500")];
+        assert_eq!(metrics.counter(&HTTP_REQUESTS, &failed), 1);
+
+        let text = metrics.render();
+        assert!(text.contains(
+            "xforce_http_request_duration_seconds_count{method=\"GET\",route=\"/items/{id}\"} 2\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unmatched_requests_share_a_label() {
+        let metrics = Arc::new(Metrics::new());
+        let app = app(Arc::clone(&metrics));
+
+        assert_eq!(get_path(&app, "/nope/1").await, StatusCode::NOT_FOUND);
+        assert_eq!(get_path(&app, "/nope/2").await, StatusCode::NOT_FOUND);
+
+        let labels = [("method", "GET"), ("route", UNMATCHED_ROUTE), ("status", "404")];
+        assert_eq!(metrics.counter(&HTTP_REQUESTS, &labels), 2);
+    }
+}
@@ -0,0 +1,494 @@
+//! # Rate Limiting Middleware
+//!
+//! Token-bucket brute-force protection for the login endpoints.
+//!
+//! Every attempt takes a token from two buckets:
+//!
+//! - one keyed by client IP plus the identity being attempted
+//!   (`email_or_username`, `wallet_address` or `email` from the JSON body), so one
+//!   attacker can't lock out a user from another address
+//! - one keyed by client IP alone, with a larger budget, so one address can't
+//!   spray many accounts by rotating the identity either
+//!
+//! ## Behavior
+//!
+//! - A bucket holds [`RateLimitConfig::capacity`] tokens and refills one per
+//!   [`RateLimitConfig::refill_every`]; the per-IP bucket uses
+//!   [`RateLimitConfig::per_ip`] unless [`RateLimiter::with_ip_limit`] says otherwise
+//! - An attempt goes through only if both buckets have a token; otherwise it
+//!   returns `429 Too Many Requests` with a `Retry-After` header and a
+//!   [`RateLimitedResponse`] body, and takes nothing from either
+//! - A successful login resets the IP-plus-identity bucket. The per-IP bucket
+//!   keeps counting, so logging in to one account can't buy more guesses at others
+//! - Buckets that have refilled completely carry no state and are dropped by
+//!   [`RateLimiter::spawn_cleanup`]
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use axum::{Router, routing::post};
+//! use lib_web::middleware::mw_rate_limit::{rate_limit_auth, RateLimiter, RateLimitConfig};
+//! use std::sync::Arc;
+//! # async fn login() {}
+//!
+//! let limiter = Arc::new(RateLimiter::new(RateLimitConfig::default()));
+//! let app: Router = Router::new()
+//!     .route("/api/auth/login", post(login))
+//!     .route_layer(axum::middleware::from_fn_with_state(limiter, rate_limit_auth));
+//! ```
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, Request, State},
+    http::{header::RETRY_AFTER, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Largest login body the middleware will buffer to find the identity
+const MAX_BODY_BYTES: usize = 16 * 1024;
+
+/// Token-bucket settings
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitConfig {
+    /// Attempts allowed in a burst
+    pub capacity: u32,
+    /// Time to regain one attempt
+    pub refill_every: Duration,
+}
+
+impl Default for RateLimitConfig {
+    /// 5 attempts, then one every 12 seconds (5 per minute sustained)
+    fn default() -> Self {
+        Self {
+            capacity: 5,
+            refill_every: Duration::from_secs(12),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Budget shared by every identity tried from one IP: 20 attempts, then
+    /// one every 3 seconds (20 per minute sustained), enough for a few users
+    /// behind the same NAT
+    pub fn per_ip() -> Self {
+        Self {
+            capacity: 20,
+            refill_every: Duration::from_secs(3),
+        }
+    }
+}
+
+/// `ErrorResponse` with the time until the next attempt is allowed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RateLimitedResponse {
+    pub error: String,
+    pub retry_after_seconds: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+    config: RateLimitConfig,
+}
+
+/// Token buckets keyed by client IP and attempted identity, plus one per client IP
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    ip_config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Limit each IP-plus-identity by `config` and each IP by [`RateLimitConfig::per_ip`]
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            ip_config: RateLimitConfig::per_ip(),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Limit each IP by `ip_config` instead
+    pub fn with_ip_limit(mut self, ip_config: RateLimitConfig) -> Self {
+        self.ip_config = ip_config;
+        self
+    }
+
+    /// Take a token for an attempt on `identity` from `ip`, from both the
+    /// `ip|identity` and the `ip` bucket.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Attempt allowed
+    /// * `Err(Duration)` - Limited; time until both buckets have a token
+    pub fn check_attempt(&self, ip: &str, identity: &str) -> Result<(), Duration> {
+        self.check_attempt_at(ip, identity, Instant::now())
+    }
+
+    fn check_attempt_at(&self, ip: &str, identity: &str, now: Instant) -> Result<(), Duration> {
+        let key = attempt_key(ip, identity);
+        self.take_at(&[(&key, self.config), (ip, self.ip_config)], now)
+    }
+
+    /// Take a token for `key`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Attempt allowed
+    /// * `Err(Duration)` - Limited; time until the next token
+    pub fn check(&self, key: &str) -> Result<(), Duration> {
+        self.check_at(key, Instant::now())
+    }
+
+    fn check_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        self.take_at(&[(key, self.config)], now)
+    }
+
+    /// Take one token from every bucket in `keys`, or from none of them if
+    /// any is empty
+    fn take_at(&self, keys: &[(&str, RateLimitConfig)], now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut limited: Option<Duration> = None;
+        for (key, config) in keys {
+            let capacity = f64::from(config.capacity);
+            let refill_secs = config.refill_every.as_secs_f64();
+            let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+                tokens: capacity,
+                last_refill: now,
+                config: *config,
+            });
+
+            let elapsed = now.saturating_duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed / refill_secs).min(capacity);
+            bucket.last_refill = now;
+
+            if bucket.tokens < 1.0 {
+                let wait = Duration::from_secs_f64((1.0 - bucket.tokens) * refill_secs);
+                limited = Some(limited.map_or(wait, |longest| longest.max(wait)));
+            }
+        }
+        if let Some(wait) = limited {
+            return Err(wait);
+        }
+
+        for (key, _) in keys {
+            if let Some(bucket) = buckets.get_mut(*key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Forget `key` (called after a successful login)
+    pub fn reset(&self, key: &str) {
+        self.buckets.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+    }
+
+    /// Drop buckets that have refilled completely.
+    ///
+    /// # Returns
+    ///
+    /// Number of buckets removed.
+    pub fn cleanup(&self) -> usize {
+        self.cleanup_at(Instant::now())
+    }
+
+    fn cleanup_at(&self, now: Instant) -> usize {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let before = buckets.len();
+        buckets.retain(|_, b| {
+            let full_after = b.config.refill_every * b.config.capacity;
+            now.saturating_duration_since(b.last_refill) < full_after
+        });
+        before - buckets.len()
+    }
+
+    /// Number of tracked keys
+    pub fn len(&self) -> usize {
+        self.buckets.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Run [`RateLimiter::cleanup`] every `every` in the background
+    pub fn spawn_cleanup(self: &Arc<Self>, every: Duration) {
+        let limiter = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let removed = limiter.cleanup();
+                if removed > 0 {
+                    debug!("[RATE LIMIT] Dropped {} idle buckets ({} left)", removed, limiter.len());
+                }
+            }
+        });
+    }
+}
+
+/// Key of the bucket for attempts on `identity` from `ip`
+fn attempt_key(ip: &str, identity: &str) -> String {
+    format!("{}|{}", ip, identity)
+}
+
+/// Identity being attempted, taken from the login body
+fn attempted_identity(body: &[u8]) -> String {
+    serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| {
+            ["email_or_username", "wallet_address", "email"]
+                .iter()
+                .find_map(|field| v.get(field).and_then(|f| f.as_str()).map(str::to_string))
+        })
+        .map(|s| s.trim().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Rate limiting middleware for the login routes.
+///
+/// Buffers the (small) JSON body to find the attempted identity, takes a
+/// token for both `ip|identity` and `ip`, and resets `ip|identity` when the
+/// handler succeeds.
+pub async fn rate_limit_auth(
+    State(limiter): State<Arc<RateLimiter>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let (parts, body) = req.into_parts();
+    let bytes = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+    };
+    let identity = attempted_identity(&bytes);
+    let key = attempt_key(&ip, &identity);
+
+    if let Err(retry_after) = limiter.check_attempt(&ip, &identity) {
+        let retry_after_seconds = retry_after.as_secs_f64().ceil() as u64;
+        warn!("[RATE LIMIT] {} {} limited for {}s", parts.uri.path(), key, retry_after_seconds);
+
+        let mut response = (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(RateLimitedResponse {
+                error: format!("Too many login attempts. Try again in {}s", retry_after_seconds),
+                retry_after_seconds,
+            }),
+        )
+            .into_response();
+        if let Ok(value) = HeaderValue::from_str(&retry_after_seconds.to_string()) {
+            response.headers_mut().insert(RETRY_AFTER, value);
+        }
+        return response;
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(bytes))).await;
+    if response.status().is_success() {
+        limiter.reset(&key);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Router};
+    use tower::ServiceExt;
+
+    fn limiter(capacity: u32, refill_secs: u64) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            capacity,
+            refill_every: Duration::from_secs(refill_secs),
+        })
+    }
+
+    #[test]
+    fn test_burst_then_limited() {
+        let limiter = limiter(3, 10);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check_at("1.2.3.4|alice", now).is_ok());
+        }
+        let retry = limiter.check_at("1.2.3.4|alice", now).unwrap_err();
+        assert_eq!(retry.as_secs(), 10);
+
+        // Other keys have their own bucket
+        assert!(limiter.check_at("1.2.3.4|bob", now).is_ok());
+    }
+
+    #[test]
+    fn test_rotating_identities_hit_the_ip_limit() {
+        let limiter = limiter(2, 10).with_ip_limit(RateLimitConfig {
+            capacity: 5,
+            refill_every: Duration::from_secs(10),
+        });
+        let now = Instant::now();
+
+        // Each username is well within its own budget, the IP's runs out
+        for user in ["alice", "bob", "carol", "dave", "erin"] {
+            assert!(limiter.check_attempt_at("1.2.3.4", user, now).is_ok(), "{}", user);
+        }
+        let retry = limiter.check_attempt_at("1.2.3.4", "frank", now).unwrap_err();
+        assert_eq!(retry.as_secs(), 10);
+
+        // A refused attempt takes nothing from frank's own bucket
+        assert!(limiter.check_at("1.2.3.4|frank", now).is_ok());
+        assert!(limiter.check_at("1.2.3.4|frank", now).is_ok());
+        assert!(limiter.check_at("1.2.3.4|frank", now).is_err());
+
+        // Other addresses are unaffected
+        assert!(limiter.check_attempt_at("5.6.7.8", "frank", now).is_ok());
+
+        // A success resets the identity, not the IP
+        limiter.reset(&attempt_key("1.2.3.4", "alice"));
+        assert!(limiter.check_attempt_at("1.2.3.4", "alice", now).is_err());
+    }
+
+    #[test]
+    fn test_refill() {
+        let limiter = limiter(2, 10);
+        let start = Instant::now();
+
+        assert!(limiter.check_at("k", start).is_ok());
+        assert!(limiter.check_at("k", start).is_ok());
+        assert!(limiter.check_at("k", start + Duration::from_secs(5)).is_err());
+
+        // One token back after a full refill interval, never more than capacity
+        assert!(limiter.check_at("k", start + Duration::from_secs(11)).is_ok());
+        assert!(limiter.check_at("k", start + Duration::from_secs(11)).is_err());
+
+        let later = start + Duration::from_secs(1000);
+        assert!(limiter.check_at("k", later).is_ok());
+        assert!(limiter.check_at("k", later).is_ok());
+        assert!(limiter.check_at("k", later).is_err());
+    }
+
+    #[test]
+    fn test_reset_and_cleanup() {
+        let limiter = limiter(1, 10);
+        let now = Instant::now();
+
+        assert!(limiter.check_at("a", now).is_ok());
+        assert!(limiter.check_at("a", now).is_err());
+        limiter.reset("a");
+        assert!(limiter.check_at("a", now).is_ok());
+
+        assert!(limiter.check_at("b", now).is_ok());
+        assert_eq!(limiter.cleanup_at(now + Duration::from_secs(5)), 0);
+        assert_eq!(limiter.cleanup_at(now + Duration::from_secs(10)), 2);
+        assert!(limiter.is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_requests_share_bucket() {
+        let limiter = Arc::new(limiter(5, 60));
+
+        let allowed: usize = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..32)
+                .map(|_| {
+                    let limiter = Arc::clone(&limiter);
+                    s.spawn(move || limiter.check("10.0.0.1|alice").is_ok())
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap() as usize).sum()
+        });
+
+        assert_eq!(allowed, 5);
+    }
+
+    #[test]
+    fn test_attempted_identity() {
+        assert_eq!(attempted_identity(br#"{"email_or_username":" Alice ","password":"x"}"#), "alice");
+        assert_eq!(attempted_identity(br#"{"wallet_address":"ABC","signature":"s"}"#), "abc");
+        assert_eq!(attempted_identity(br#"{"email":"Bob@Example.com"}"#), "bob@example.com");
+        assert_eq!(attempted_identity(b"not json"), "");
+    }
+
+    #[tokio::test]
+    async fn test_middleware_returns_429_and_resets_on_success() {
+        let limiter = Arc::new(limiter(2, 60));
+        let app = Router::new()
+            .route(
+                "/login",
+                post(|body: String| async move {
+                    if body.contains("good") { StatusCode::OK } else { StatusCode::UNAUTHORIZED }
+                }),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(Arc::clone(&limiter), rate_limit_auth));
+
+        let attempt = |password: &'static str| {
+            let app = app.clone();
+            async move {
+                app.oneshot(
+                    Request::post("/login")
+                        .body(Body::from(format!(r#"{{"email_or_username":"alice","password":"{}"}}"#, password)))
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+            }
+        };
+
+        assert_eq!(attempt("bad").await.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(attempt("good").await.status(), StatusCode::OK);
+
+        // The success reset the bucket, so two more failures are allowed
+        assert_eq!(attempt("bad").await.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(attempt("bad").await.status(), StatusCode::UNAUTHORIZED);
+
+        let limited = attempt("bad").await;
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(limited.headers()[RETRY_AFTER], "60");
+        let body = to_bytes(limited.into_body(), MAX_BODY_BYTES).await.unwrap();
+        let body: RateLimitedResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.retry_after_seconds, 60);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_limits_username_rotation_from_one_ip() {
+        let limiter = Arc::new(limiter(5, 60).with_ip_limit(RateLimitConfig {
+            capacity: 3,
+            refill_every: Duration::from_secs(60),
+        }));
+        let app = Router::new()
+            .route("/login", post(|| async { StatusCode::UNAUTHORIZED }))
+            .route_layer(axum::middleware::from_fn_with_state(Arc::clone(&limiter), rate_limit_auth));
+
+        let mut statuses = Vec::new();
+        for user in ["alice", "bob", "carol", "dave"] {
+            let request = Request::post("/login")
+                .extension(ConnectInfo(SocketAddr::from(([203, 0, 113, 7], 40000))))
+                .body(Body::from(format!(r#"{{"email_or_username":"{}","password":"x"}}"#, user)))
+                .unwrap();
+            statuses.push(app.clone().oneshot(request).await.unwrap().status());
+        }
+        assert_eq!(
+            statuses,
+            vec![
+                StatusCode::UNAUTHORIZED,
+                StatusCode::UNAUTHORIZED,
+                StatusCode::UNAUTHORIZED,
+                StatusCode::TOO_MANY_REQUESTS
+            ]
+        );
+    }
+}
@@ -6,9 +6,11 @@
 //! registers all routes, applies middleware, and starts the HTTP server.
 
 // region: --- Imports
-use axum::{routing::{get, post}, Router};
-use lib_core::{Config, DbPool, create_pool};
+use axum::{routing::{delete, get, post, put}, Router};
+use lib_core::{Config, DbPool, Metrics, create_pool};
 use lib_solana::{SolanaState, Network, ContractRegistry, PluginLoader, PriceStreamServer};
+use lib_solana::tick_journal::{JournalConfig, TickJournal};
+use lib_solana::contracts::loader::BATCH_SWAP_ROUTER;
 use lib_solana::contracts::{
     BatchSwapRouterPlugin, PluginConfig, Cluster, CommitmentLevel, ContractPlugin,
 };
@@ -18,10 +20,20 @@ use lib_solana::contracts::batch_swap::routes::{
     handle_health_app_state,
     handle_metadata_app_state,
 };
-use crate::chat::{ChatAppState, handle_braid_subscription, handle_braid_put, handle_typing_event};
+use crate::chat::{ChatAppState, handle_braid_subscription, handle_braid_put, handle_typing_event, handle_conversation_summary, handle_mark_read, handle_upload_attachment, handle_download_attachment, handle_delete_conversation, handle_cancel_ai_reply};
+use crate::chat::handlers::attachments::MAX_UPLOAD_BODY_BYTES;
+use crate::chat::tools::{self, ServiceDataSource};
 use crate::handlers;
-use crate::middleware::{stamp_req, log_requests};
+use crate::services::program_monitor::{self, ProgramMonitor};
+use crate::services::{
+    mailer_from_env, ApiKeyService, AnalyticsService, DepthService, HealthService, JupiterQuoteSource, LoginChallengeStore, PasswordResetService,
+    OnRampService, PortfolioService, QuoteBudget, ReportService, QuoteStreamHub, ShareService, StreamedSymbolService, TelemetryService, TokenUnlockService, TradeImportService, WalletActivityWatcher, WebhookConfig, WebhookDispatcher, WebhookService,
+};
+use crate::middleware::{stamp_req, log_requests, rate_limit_auth, record_metrics, require_auth, require_session, RateLimiter};
+use crate::middleware::mw_auth::spawn_revocation_cleanup;
+use crate::shutdown::{self, Shutdown};
 use std::sync::Arc;
+use std::time::Duration;
 use tower_http::cors::CorsLayer;
 use tracing::info;
 // endregion: --- Imports
@@ -36,6 +48,31 @@ pub struct AppState {
     pub contract_registry: Arc<ContractRegistry>,
     pub batch_swap_plugin: Arc<BatchSwapRouterPlugin>,
     pub price_stream: Arc<PriceStreamServer>,
+    pub program_monitor: Arc<ProgramMonitor>,
+    pub depth: Arc<DepthService>,
+    pub analytics: Arc<AnalyticsService>,
+    pub health: Arc<HealthService>,
+    pub streamed_symbols: Arc<StreamedSymbolService>,
+    pub token_unlocks: Arc<TokenUnlockService>,
+    pub onramp: Arc<OnRampService>,
+    pub trade_import: Arc<TradeImportService>,
+    pub portfolio: Arc<PortfolioService>,
+    pub reports: Arc<ReportService>,
+    /// Brute-force protection for the login routes
+    pub auth_rate_limiter: Arc<RateLimiter>,
+    /// Pending single-use wallet login challenges
+    pub login_challenges: Arc<LoginChallengeStore>,
+    pub password_reset: Arc<PasswordResetService>,
+    pub share: Arc<ShareService>,
+    pub telemetry: Arc<TelemetryService>,
+    pub webhooks: Arc<WebhookService>,
+    pub api_keys: Arc<ApiKeyService>,
+    /// Live swap quotes shared by WebSocket clients
+    pub quote_streams: QuoteStreamHub,
+    /// Registry served at `GET /metrics`
+    pub metrics: Arc<Metrics>,
+    /// Triggered on SIGINT/SIGTERM to close the long-lived streams
+    pub shutdown: Shutdown,
 }
 
 impl axum::extract::FromRef<AppState> for DbPool {
@@ -73,6 +110,127 @@ impl axum::extract::FromRef<AppState> for Arc<PriceStreamServer> {
         state.price_stream.clone()
     }
 }
+
+impl axum::extract::FromRef<AppState> for Arc<ProgramMonitor> {
+    fn from_ref(state: &AppState) -> Self {
+        state.program_monitor.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<DepthService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.depth.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<AnalyticsService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.analytics.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<HealthService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.health.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<StreamedSymbolService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.streamed_symbols.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<TokenUnlockService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.token_unlocks.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<TradeImportService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.trade_import.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<PortfolioService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.portfolio.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<ReportService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.reports.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<RateLimiter> {
+    fn from_ref(state: &AppState) -> Self {
+        state.auth_rate_limiter.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<LoginChallengeStore> {
+    fn from_ref(state: &AppState) -> Self {
+        state.login_challenges.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<PasswordResetService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.password_reset.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<ShareService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.share.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<TelemetryService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.telemetry.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<OnRampService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.onramp.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<WebhookService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.webhooks.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<ApiKeyService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.api_keys.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for QuoteStreamHub {
+    fn from_ref(state: &AppState) -> Self {
+        state.quote_streams.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<Metrics> {
+    fn from_ref(state: &AppState) -> Self {
+        state.metrics.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Shutdown {
+    fn from_ref(state: &AppState) -> Self {
+        state.shutdown.clone()
+    }
+}
+
 // endregion: --- AppState
 
 // region: --- Server Configuration
@@ -84,24 +242,43 @@ pub struct ServerConfig {
     pub allowed_origins: Vec<String>,
     /// Database migrations path
     pub migrations_path: &'static str,
+    /// How long in-flight requests get to finish after SIGINT/SIGTERM
+    pub shutdown_timeout: Duration,
 }
 
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
             bind_address: "127.0.0.1:3001".to_string(),
-            allowed_origins: vec![
-                "http://localhost:3000".to_string(),
-                "http://127.0.0.1:3000".to_string(),
-                "http://localhost:3002".to_string(),
-                "http://127.0.0.1:3002".to_string(),
-                "http://localhost:8080".to_string(),
-                "http://127.0.0.1:8080".to_string(),
-            ],
+            allowed_origins: default_allowed_origins(),
             migrations_path: "./migrations",
+            shutdown_timeout: Duration::from_secs(30),
         }
     }
 }
+
+/// Local development origins; the wallet-web dev server's are left out when
+/// the wallet is embedded and served from the API's own origin
+fn default_allowed_origins() -> Vec<String> {
+    let mut ports = vec![3000, 3002];
+    if crate::wallet_assets::mount_path().is_none() {
+        ports.push(8080);
+    }
+    ports
+        .into_iter()
+        .flat_map(|port| [format!("http://localhost:{}", port), format!("http://127.0.0.1:{}", port)])
+        .collect()
+}
+
+/// Solana network from `SOLANA_NETWORK` (case-insensitive, defaults to mainnet)
+pub fn network_from_env() -> Network {
+    std::env::var("SOLANA_NETWORK")
+        .ok()
+        .map(|s| s.to_lowercase())
+        .filter(|s| s == "devnet")
+        .map(|_| Network::Devnet)
+        .unwrap_or(Network::Mainnet)
+}
 // endregion: --- Server Configuration
 
 // region: --- Server Setup
@@ -124,6 +301,9 @@ impl Default for ServerConfig {
 /// - Solana client initialization fails
 /// - Contract plugin initialization fails
 /// - Server binding fails
+///
+/// On SIGINT or SIGTERM the server drains for up to
+/// [`ServerConfig::shutdown_timeout`] and returns (see [`crate::shutdown`]).
 pub async fn start_server(config: ServerConfig) -> anyhow::Result<()> {
     // Configure tracing subscriber with detailed formatting
     let log_level = std::env::var("LOG_LEVEL")
@@ -187,20 +367,10 @@ pub async fn start_server(config: ServerConfig) -> anyhow::Result<()> {
     info!(" Connecting to Solana...");
     let helius_key = std::env::var("HELIUS_API_KEY").ok();
     
-    // Determine network from environment (case-insensitive, defaults to mainnet)
-    let network = std::env::var("SOLANA_NETWORK")
-        .ok()
-        .as_deref()
-        .map(str::to_lowercase)
-        .filter(|s| s == "devnet")
-        .map(|_| Network::Devnet)
-        .unwrap_or_else(|| {
-            info!("MAINNET MODE - Using Solana Mainnet");
-            Network::Mainnet
-        });
-    
-    if matches!(network, Network::Devnet) {
-        info!("DEVNET MODE - Using Solana Devnet");
+    let network = network_from_env();
+    match network {
+        Network::Mainnet => info!("MAINNET MODE - Using Solana Mainnet"),
+        Network::Devnet => info!("DEVNET MODE - Using Solana Devnet"),
     }
     let solana = Arc::new(SolanaState::new(network.clone(), helius_key.clone()).await?);
     info!(" Solana initialized");
@@ -220,17 +390,25 @@ pub async fn start_server(config: ServerConfig) -> anyhow::Result<()> {
         Network::Devnet => "https://api.devnet.solana.com".to_string(),
     };
     
+    // Operator overrides from the plugin config file (edited with `xforce-admin plugins`)
+    let plugins_path = PluginLoader::config_path();
+    let plugin_overrides = PluginLoader::read_config(&plugins_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load plugin config: {}", e))?;
+    let batch_swap_override = plugin_overrides.get(BATCH_SWAP_ROUTER);
+
     // Create and initialize batch swap router plugin
     let mut batch_swap_plugin = BatchSwapRouterPlugin::new();
     let plugin_config = PluginConfig {
-        program_id: ContractPlugin::program_id(&batch_swap_plugin),
-        cluster: match network {
+        program_id: batch_swap_override
+            .map(|c| c.program_id)
+            .unwrap_or_else(|| ContractPlugin::program_id(&batch_swap_plugin)),
+        cluster: batch_swap_override.and_then(|c| c.cluster.clone()).unwrap_or(match network {
             Network::Mainnet => Cluster::Mainnet,
             Network::Devnet => Cluster::Devnet,
-        },
-        rpc_url: rpc_url.clone(),
+        }),
+        rpc_url: batch_swap_override.and_then(|c| c.rpc_url.clone()).unwrap_or_else(|| rpc_url.clone()),
         commitment: CommitmentLevel::Confirmed,
-        enabled: true,
+        enabled: batch_swap_override.is_none_or(|c| c.enabled),
     };
     
     ContractPlugin::initialize(&mut batch_swap_plugin, plugin_config).await
@@ -240,10 +418,14 @@ pub async fn start_server(config: ServerConfig) -> anyhow::Result<()> {
     let batch_swap_plugin_arc = Arc::new(batch_swap_plugin);
     
     // Register plugin in registry (convert Arc<BatchSwapRouterPlugin> to Arc<dyn ContractPlugin>)
-    let plugin_trait: Arc<dyn lib_solana::contracts::ContractPlugin> = batch_swap_plugin_arc.clone() as Arc<dyn lib_solana::contracts::ContractPlugin>;
-    contract_registry.register(plugin_trait).await
-        .map_err(|e| anyhow::anyhow!("Failed to register batch swap plugin: {}", e))?;
-    info!(" Batch swap router plugin registered");
+    if batch_swap_override.is_none_or(|c| c.enabled) {
+        let plugin_trait: Arc<dyn lib_solana::contracts::ContractPlugin> = batch_swap_plugin_arc.clone() as Arc<dyn lib_solana::contracts::ContractPlugin>;
+        contract_registry.register(plugin_trait).await
+            .map_err(|e| anyhow::anyhow!("Failed to register batch swap plugin: {}", e))?;
+        info!(" Batch swap router plugin registered");
+    } else {
+        info!(" Batch swap router plugin disabled in {}", plugins_path.display());
+    }
 
     tokio::spawn({
         let cache = solana.price_cache.clone();
@@ -253,12 +435,42 @@ pub async fn start_server(config: ServerConfig) -> anyhow::Result<()> {
     });
     info!(" Background price refresh started (10s interval)");
 
+    // Symbol sets and candle queries that stop being requested are swept
+    solana.market_cache.spawn_cleanup(std::time::Duration::from_secs(300));
+
+    // Request, upstream, WebSocket and pool metrics for GET /metrics
+    let metrics = Arc::new(Metrics::new());
+
     // Initialize price stream server
     info!(" Initializing price stream server...");
-    let price_stream = Arc::new(PriceStreamServer::new(
+    let mut price_stream = PriceStreamServer::new(
         Arc::clone(&solana.jupiter),
         500, // 500ms update interval for sub-second updates
+    )
+    .with_pyth(Arc::clone(&solana.pyth))
+    .with_divergence_threshold(lib_solana::aggregate::divergence_threshold_from_env())
+    .with_divergence_sustain(lib_solana::aggregate::divergence_sustain_from_env())
+    .with_metrics(Arc::clone(&metrics));
+
+    // Journal price ticks for candle replays (off unless TICK_JOURNAL_DIR is set)
+    if let Some(journal_config) = JournalConfig::from_env() {
+        match TickJournal::start(journal_config) {
+            Ok(journal) => price_stream = price_stream.with_journal(journal),
+            Err(e) => tracing::error!(error = %e, "Failed to open tick journal - journaling disabled"),
+        }
+    }
+    let price_stream = Arc::new(price_stream);
+
+    // Load the streamed symbol universe (seeded from config on first run)
+    let streamed_symbols = Arc::new(StreamedSymbolService::new(
+        pool.clone(),
+        Arc::clone(&solana.jupiter),
+        Arc::clone(&solana.pyth),
+        Arc::clone(&price_stream),
     ));
+    if let Err(e) = streamed_symbols.init(&app_config.streamed_symbols).await {
+        tracing::error!(error = %e, "Failed to load streamed symbols - price stream starts empty");
+    }
     
     // Start the price stream server in background
     // Note: Even if start() fails, we still add price_stream to AppState
@@ -283,18 +495,107 @@ pub async fn start_server(config: ServerConfig) -> anyhow::Result<()> {
     });
     info!(" Price stream server initialized (background task started)");
 
+    // Watch deployments of the programs we route through
+    let monitored_programs = program_monitor::monitored_program_ids_from_env(&[
+        ContractPlugin::program_id(batch_swap_plugin_arc.as_ref()),
+    ]);
+    let program_monitor = Arc::new(ProgramMonitor::new(
+        pool.clone(),
+        Arc::clone(&solana.rpc),
+        monitored_programs,
+    ));
+    tokio::spawn(Arc::clone(&program_monitor).start());
+    info!(" Program monitor started ({} programs)", program_monitor.program_ids().len());
+
+    // Unlock schedule; imported from TOKEN_UNLOCKS_DATASET_URL when set
+    let token_unlocks = Arc::new(TokenUnlockService::from_env(pool.clone()));
+    tokio::spawn(Arc::clone(&token_unlocks).start());
+
+    // "Buy SOL" providers, from ONRAMP_PROVIDERS_FILE
+    let onramp = Arc::new(OnRampService::from_env());
+
     // Create chat app state
     let chat_config = app_config.clone();
     let chat_db = pool.clone();
-    let chat_state = Arc::new(ChatAppState::new(chat_db, chat_config));
+    let ai_tools = tools::market_tools(Arc::new(ServiceDataSource::new(
+        Arc::clone(&solana),
+        Arc::clone(&price_stream),
+        pool.clone(),
+    )));
+    let shutdown = Shutdown::new();
+    let chat_state = Arc::new(
+        ChatAppState::new(chat_db, chat_config)
+            .with_ai_tools(ai_tools)
+            .with_shutdown(shutdown.clone()),
+    );
+
+    // Uptime in health reports is measured from here
+    let health = Arc::new(HealthService::new(pool.clone(), Arc::clone(&solana), Arc::clone(&price_stream)));
+
+    let trade_import = Arc::new(TradeImportService::new(pool.clone(), Arc::clone(&solana.jupiter)));
+    let portfolio = Arc::new(PortfolioService::new(pool.clone(), Arc::clone(&solana)));
+    let reports = Arc::new(ReportService::new(pool.clone(), Arc::clone(&solana)));
+
+    // Login attempt limiter; idle buckets are swept every minute
+    let auth_rate_limiter = Arc::new(RateLimiter::new(Default::default()));
+    auth_rate_limiter.spawn_cleanup(std::time::Duration::from_secs(60));
+
+    let login_challenges = Arc::new(LoginChallengeStore::default());
+    login_challenges.spawn_cleanup(std::time::Duration::from_secs(60));
+
+    // Revocations outlive their token only until it expires; swept hourly
+    spawn_revocation_cleanup(pool.clone(), std::time::Duration::from_secs(3600));
+
+    let password_reset = Arc::new(PasswordResetService::new(pool.clone(), mailer_from_env()?));
+    password_reset.spawn_cleanup(std::time::Duration::from_secs(3600));
+
+    // Chart snapshots copy their candles out of the price stream's aggregator
+    let share = Arc::new(ShareService::new(pool.clone(), Arc::clone(&price_stream)));
+    share.spawn_cleanup(std::time::Duration::from_secs(3600));
+
+    // Opted-in terminals' anonymous usage counts
+    let telemetry = Arc::new(TelemetryService::new(pool.clone()));
+
+    // Wallets of users with webhooks are polled for activity to deliver
+    let webhooks = Arc::new(WebhookService::new(pool.clone(), WebhookDispatcher::new(WebhookConfig::from_env())));
+    let wallet_activity = Arc::new(WalletActivityWatcher::new(
+        pool.clone(),
+        Arc::clone(&solana.rpc),
+        Arc::clone(&webhooks),
+    ));
+    tokio::spawn(wallet_activity.start());
+    info!(" Wallet activity watcher started");
 
     let state = AppState {
-        db: pool,
+        db: pool.clone(),
         config: app_config,
         solana: Arc::clone(&solana),
         contract_registry: Arc::clone(&contract_registry),
         batch_swap_plugin: Arc::clone(&batch_swap_plugin_arc),
         price_stream: Arc::clone(&price_stream),
+        program_monitor: Arc::clone(&program_monitor),
+        depth: Arc::new(DepthService::new(Arc::clone(&solana))),
+        analytics: Arc::new(AnalyticsService::new(Arc::clone(&price_stream))),
+        health,
+        streamed_symbols,
+        token_unlocks,
+        onramp,
+        trade_import,
+        portfolio,
+        reports,
+        auth_rate_limiter,
+        login_challenges,
+        password_reset,
+        share,
+        telemetry,
+        webhooks,
+        api_keys: Arc::new(ApiKeyService::new(pool.clone())),
+        quote_streams: QuoteStreamHub::new(
+            Arc::new(JupiterQuoteSource::new(Arc::clone(&solana))),
+            QuoteBudget::from_env(),
+        ),
+        metrics,
+        shutdown: shutdown.clone(),
     };
 
     // Create router
@@ -306,9 +607,21 @@ pub async fn start_server(config: ServerConfig) -> anyhow::Result<()> {
     info!(" SERVER READY: http://{}", config.bind_address);
     log_server_info();
 
-    // Use into_make_service_with_connect_info to enable ConnectInfo extraction
-    // This is required for WebSocket handlers that need client connection info
-    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await?;
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            shutdown::os_signal().await;
+            info!(" SHUTTING DOWN: closing streams and draining requests");
+            shutdown.trigger();
+        }
+    });
+
+    // Serves with ConnectInfo, which the WebSocket handlers need
+    let drained = shutdown::serve_with_drain(listener, app, shutdown, config.shutdown_timeout).await?;
+
+    // Waits for the connections still checked out, so their writes land
+    pool.close().await;
+    info!(" SERVER STOPPED (drained: {})", drained);
     Ok(())
 }
 
@@ -327,30 +640,99 @@ fn create_router(
 
     let cors = CorsLayer::new()
         .allow_origin(origins)
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::OPTIONS])
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
         .allow_headers([
             axum::http::header::CONTENT_TYPE,
             axum::http::header::AUTHORIZATION,
+            axum::http::header::HeaderName::from_static("x-api-key"),
             axum::http::header::HeaderName::from_static("subscribe"),
             axum::http::header::HeaderName::from_static("parents"),
             axum::http::header::HeaderName::from_static("version"),
         ]);
 
+    // Login routes are rate limited per client IP and attempted identity
+    let login_routes = Router::new()
+        .route("/api/auth/login", post(handlers::auth::login))
+        .route("/api/auth/wallet-login", post(handlers::wallet_auth::wallet_login))
+        .route("/api/wallet/login/challenge", get(handlers::wallet_auth::wallet_login_challenge))
+        .route("/api/auth/password-reset/request", post(handlers::auth::request_password_reset))
+        .route("/api/auth/password-reset/confirm", post(handlers::auth::confirm_password_reset))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.auth_rate_limiter.clone(),
+            rate_limit_auth,
+        ));
+
+    // Account management needs a login session; API keys are turned away
+    let session_routes = Router::new()
+        .route("/api/auth/logout", post(handlers::auth::logout))
+        .route("/api/auth/refresh", post(handlers::auth::refresh_session))
+        .route("/api/auth/password", put(handlers::auth::change_password))
+        .route("/api/auth/profile", put(handlers::auth::update_profile))
+        .route("/api/keys", post(handlers::api_keys::create_api_key).get(handlers::api_keys::list_api_keys))
+        .route("/api/keys/{id}", delete(handlers::api_keys::revoke_api_key))
+        .route_layer(axum::middleware::from_fn(require_session));
+
+    // Routes that need a valid, unrevoked JWT, or an API key with the right scope
+    let authed_routes = Router::new()
+        .merge(session_routes)
+        .route("/api/admin/streamed-symbols", post(handlers::admin::add_streamed_symbol))
+        .route("/api/admin/streamed-symbols/{symbol}", delete(handlers::admin::remove_streamed_symbol))
+        .route("/api/admin/token-unlocks", post(handlers::admin::create_token_unlock))
+        .route("/api/admin/replay-candles", post(handlers::admin::replay_candles))
+        .route("/api/admin/maintenance", post(handlers::admin::announce_maintenance))
+        .route("/api/admin/swap-latency", get(handlers::admin::get_swap_latency))
+        .route(
+            "/api/admin/token-unlocks/{id}",
+            put(handlers::admin::update_token_unlock).delete(handlers::admin::remove_token_unlock),
+        )
+        .route("/api/swap/simulate", post(handlers::swap::simulate_swap))
+        .route("/api/swap/history", get(handlers::swap::get_swap_history))
+        .route("/api/swap/history/stream", get(handlers::swap::stream_swap_history))
+        .route("/api/swap/history/import", post(handlers::trades::import_trades))
+        .route("/api/swap/stats", get(handlers::trades::get_trade_stats))
+        .route("/api/portfolio/positions", get(handlers::portfolio::get_positions))
+        .route("/api/reports/monthly", get(handlers::reports::get_monthly_report))
+        .route("/api/transaction/{signature}/status", put(handlers::transaction::update_transaction_status))
+        .route("/api/transaction/{signature}/latency", put(handlers::transaction::record_transaction_latency))
+        // Friend management routes
+        .route("/api/friends/request", post(handlers::friends::send_friend_request))
+        .route("/api/friends/accept/{id}", post(handlers::friends::accept_friend_request))
+        .route("/api/friends/reject/{id}", post(handlers::friends::reject_friend_request))
+        .route("/api/friends/block/{user_id}", post(handlers::friends::block_user))
+        .route("/api/friends", get(handlers::friends::get_friends))
+        .route("/api/friends/search", get(handlers::friends::search_users))
+        .route("/api/share", post(handlers::share::create_share))
+        .route("/api/webhooks", post(handlers::webhooks::create_webhook).get(handlers::webhooks::list_webhooks))
+        .route("/api/webhooks/{id}", delete(handlers::webhooks::delete_webhook))
+        .route("/api/webhooks/{id}/deliveries", get(handlers::webhooks::list_webhook_deliveries))
+        .route("/api/webhooks/{id}/test", post(handlers::webhooks::test_webhook))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_auth));
+
+    let metrics = state.metrics.clone();
+
     // Create main router with AppState
     // Note: Contract routes are added directly here to avoid state type conflicts when nesting/merging
     info!("[ROUTE SETUP] Registering HTTP routes...");
     let app = Router::new()
+        .merge(login_routes)
+        .merge(authed_routes)
         .route("/api/auth/signup", post(handlers::auth::signup))
-        .route("/api/auth/login", post(handlers::auth::login))
         .route("/api/auth/wallet-setup/validate", get(handlers::wallet_auth::validate_wallet_setup))
         .route("/api/auth/wallet-setup/complete", post(handlers::wallet_auth::complete_wallet_setup))
         // Also support the frontend's expected path
         .route("/api/wallet/setup/validate", get(handlers::wallet_auth::validate_wallet_setup))
         .route("/api/wallet/setup/complete", post(handlers::wallet_auth::complete_wallet_setup))
-        .route("/api/auth/wallet-login", post(handlers::wallet_auth::wallet_login))
         .route("/api/market/prices", get(handlers::market::get_prices))
         .route("/api/market/tokens", get(handlers::market::get_token_list))
         .route("/api/market/candles", get(handlers::market::get_candles))
+        .route("/api/market/candles/batch", get(handlers::market::get_candles_batch))
+        .route("/api/market/depth", get(handlers::market::get_depth))
+        .route("/api/market/analytics", get(handlers::market::get_analytics))
+        .route("/api/market/streamed-symbols", get(handlers::market::get_streamed_symbols))
+        .route("/api/market/unlocks", get(handlers::market::get_unlocks))
+        .route("/api/onramp/providers", get(handlers::onramp::get_providers))
+        .route("/api/telemetry/usage", post(handlers::telemetry::upload_usage))
+        .route("/api/telemetry/usage/{install_id}", delete(handlers::telemetry::delete_usage))
         .route("/api/ws/prices", get(handlers::websocket::price_stream_websocket))
         .route("/api/wallet/balance", get(handlers::wallet::get_wallet_balance))
         .route("/api/wallet/info", get(handlers::wallet::get_wallet_info))
@@ -359,26 +741,28 @@ fn create_router(
         .route("/api/transactions/submit", post(handlers::swap::submit_transaction))
         .route("/api/transaction/submit", post(handlers::transaction::submit_transaction))
         .route("/api/staking/info", get(handlers::staking::get_staking_info))
+        .route("/api/staking/rewards", get(handlers::staking::get_staking_rewards))
+        .route("/api/staking/delegate", post(handlers::staking::delegate_stake))
+        .route("/api/staking/deactivate", post(handlers::staking::deactivate_stake))
+        .route("/api/staking/withdraw", post(handlers::staking::withdraw_stake))
         .route("/api/swap/quote", get(handlers::swap::get_swap_quote))
         .route("/api/swap/execute", post(handlers::swap::execute_swap))
-        // Friend management routes
-        .route("/api/friends/request", post(handlers::friends::send_friend_request))
-        .route("/api/friends/accept/{id}", post(handlers::friends::accept_friend_request))
-        .route("/api/friends/reject/{id}", post(handlers::friends::reject_friend_request))
-        .route("/api/friends/block/{user_id}", post(handlers::friends::block_user))
-        .route("/api/friends", get(handlers::friends::get_friends))
-        .route("/api/friends/search", get(handlers::friends::search_users))
         // Contract routes - added directly to avoid state type conflicts
         .route("/api/contracts/contracts", get(handlers::contracts::list_contracts_handler))
         .route("/api/contracts/contracts/{name}", get(handlers::contracts::get_contract_handler))
         .route("/api/contracts/contracts/{name}/health", get(handlers::contracts::health_check_handler))
         .route("/api/contracts/contracts/{name}/metadata", get(handlers::contracts::get_metadata_handler))
+        .route("/api/contracts/programs", get(handlers::contracts::list_monitored_programs_handler))
         // Batch swap routes - handlers extract Arc<BatchSwapRouterPlugin> from AppState via FromRef
         .route("/api/contracts/batch-swap-router/batch-swap", post(handle_batch_swap_app_state))
         .route("/api/contracts/batch-swap-router/execute-swap", post(handle_execute_swap_app_state))
         .route("/api/contracts/batch-swap-router/health", get(handle_health_app_state))
         .route("/api/contracts/batch-swap-router/metadata", get(handle_metadata_app_state))
+        .route("/api/health", get(handlers::health::get_health))
+        .route("/metrics", get(handlers::metrics::get_metrics))
+        .route("/share/{slug}", get(handlers::share::get_share_page))
         .route("/health", get(|| async { "OK" }))
+        .merge(crate::wallet_assets::embedded_router())
         .fallback(|| async {
             info!("[404 HANDLER] Unmatched route - returning 404");
             (axum::http::StatusCode::NOT_FOUND, "Route not found")
@@ -387,9 +771,21 @@ fn create_router(
             Router::new()
                 .route("/api/chat/{conversation_id}", get(handle_braid_subscription).put(handle_braid_put))
                 .route("/api/chat/{conversation_id}/typing", post(handle_typing_event))
+                .route("/api/chat/{conversation_id}/summary", post(handle_conversation_summary))
+                .route("/api/chat/{conversation_id}/cancel", post(handle_cancel_ai_reply))
+                .route("/api/chat/conversations/{conversation_id}/read", post(handle_mark_read))
+                .route("/api/chat/conversations/{conversation_id}", delete(handle_delete_conversation))
+                // Uploads get their own body limit; the default would cut them off at 2 MB
+                .route(
+                    "/api/chat/attachments",
+                    post(handle_upload_attachment).layer(axum::extract::DefaultBodyLimit::max(MAX_UPLOAD_BODY_BYTES)),
+                )
+                .route("/api/chat/attachments/{attachment_id}", get(handle_download_attachment))
                 .with_state(chat_state)
         )
         .with_state(state)
+        // Request counts and durations by matched route
+        .layer(axum::middleware::from_fn_with_state(metrics, record_metrics))
         // Request stamping (adds request ID) - must be first
         .layer(axum::middleware::from_fn(stamp_req))
         // Comprehensive request/response logging
@@ -440,24 +836,82 @@ fn log_server_info() {
     info!("SOLANA MARKET DATA:");
     info!("   • GET  /api/market/prices?symbols=SOL,USDC,JUP");
     info!("   • GET  /api/market/tokens");
+    info!("   • GET  /api/market/candles/batch?symbols=SOL,BONK&timeframe=1h");
+    info!("   • GET  /api/market/depth?input=SOL&output=USDC");
+    info!("   • GET  /api/market/analytics?symbols=SOL,BONK&window=30");
+    info!("   • GET  /api/market/streamed-symbols");
+    info!("   • GET  /api/market/unlocks?days=90");
+    info!("   • GET  /api/onramp/providers?region=US");
+    info!(" TELEMETRY (opt-in, anonymous):");
+    info!("   • POST /api/telemetry/usage");
+    info!("   • DELETE /api/telemetry/usage/{{install_id}}");
     info!(" WALLET:");
     info!("   • GET  /api/wallet/balance?address={{pubkey}}");
     info!("   • GET  /api/wallet/info?address={{pubkey}}");
     info!(" TRANSACTIONS:");
     info!("   • GET  /api/transactions?address={{pubkey}}&limit=10");
+    info!("   • PUT  /api/transaction/{{signature}}/status");
+    info!("   • PUT  /api/transaction/{{signature}}/latency");
     info!(" STAKING:");
     info!("   • GET  /api/staking/info?address={{pubkey}}");
+    info!("   • GET  /api/staking/rewards?address={{pubkey}}&epochs={{n}}");
+    info!("   • POST /api/staking/delegate");
+    info!("   • POST /api/staking/deactivate");
+    info!("   • POST /api/staking/withdraw");
     info!(" SWAP/TRADING:");
     info!("   • GET  /api/swap/quote?inputMint={{mint}}&outputMint={{mint}}&amount={{lamports}}&slippageBps=50");
+    info!("   • POST /api/swap/simulate");
+    info!("   • GET  /api/swap/history?limit=50&offset=0&from_ts=&to_ts=&input_token=&output_token=&status=");
+    info!("   • GET  /api/swap/history/stream?cursor=&from_ts=&to_ts=&input_token=&output_token=&status= (NDJSON)");
+    info!("   • POST /api/swap/history/import");
+    info!("   • GET  /api/swap/stats");
+    info!(" PORTFOLIO:");
+    info!("   • GET  /api/portfolio/positions");
+    info!("   • GET  /api/reports/monthly (PDF)");
     info!(" AUTH:");
     info!("   • POST /api/auth/signup");
-    info!("   • POST /api/auth/login");
+    info!("   • POST /api/auth/login (rate limited)");
+    info!("   • POST /api/auth/logout (revokes the bearer token)");
+    info!("   • POST /api/auth/refresh (trades the bearer token for a fresh one)");
+    info!("   • PUT  /api/auth/password (revokes other sessions, returns a fresh token)");
+    info!("   • PUT  /api/auth/profile");
+    info!("   • POST /api/auth/password-reset/request (rate limited, emails a one-time code)");
+    info!("   • POST /api/auth/password-reset/confirm (rate limited)");
     info!("   • GET  /api/auth/wallet-setup/validate?token={{setup_token}}");
     info!("   • GET  /api/wallet/setup/validate?token={{setup_token}} (alternative path)");
     info!("   • POST /api/auth/wallet-setup/complete");
-    info!("   • POST /api/auth/wallet-login");
+    info!("   • GET  /api/wallet/login/challenge?wallet_address={{pubkey}} (rate limited)");
+    info!("   • POST /api/auth/wallet-login (rate limited, consumes the challenge)");
+    info!(" API KEYS (login session only; scripts send X-Api-Key instead of a bearer token):");
+    info!("   • POST   /api/keys (returns the key once)");
+    info!("   • GET    /api/keys");
+    info!("   • DELETE /api/keys/{{id}}");
+    info!(" WEBHOOKS:");
+    info!("   • POST   /api/webhooks (returns the signing secret once)");
+    info!("   • GET    /api/webhooks");
+    info!("   • DELETE /api/webhooks/{{id}}");
+    info!("   • GET    /api/webhooks/{{id}}/deliveries");
+    info!("   • POST   /api/webhooks/{{id}}/test");
+    info!(" ADMIN:");
+    info!("   • POST   /api/admin/streamed-symbols");
+    info!("   • DELETE /api/admin/streamed-symbols/{{symbol}}");
+    info!("   • POST   /api/admin/token-unlocks");
+    info!("   • PUT    /api/admin/token-unlocks/{{id}}");
+    info!("   • DELETE /api/admin/token-unlocks/{{id}}");
+    info!("   • POST   /api/admin/replay-candles");
+    info!("   • POST   /api/admin/maintenance");
+    info!("   • GET    /api/admin/swap-latency?days=7");
+    info!(" CONTRACTS:");
+    info!("   • GET  /api/contracts/programs");
     info!(" HEALTH:");
+    info!("   • GET  /api/health");
     info!("   • GET  /health");
+    info!(" METRICS (Prometheus):");
+    info!("   • GET  /metrics");
+    if let Some(path) = crate::wallet_assets::mount_path() {
+        info!(" WALLET WEB (embedded):");
+        info!("   • GET  {}/", path);
+    }
 }
 // endregion: --- Server Setup
 
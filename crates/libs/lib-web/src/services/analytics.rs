@@ -0,0 +1,219 @@
+//! # Market Analytics Service
+//!
+//! Annualized volatility and pairwise return correlations for a set of
+//! symbols, computed from the price stream's daily candles with
+//! [`lib_solana::analytics`].
+//!
+//! Results only move once a day's candle does, so they are cached per
+//! (symbols, window) for [`ANALYTICS_CACHE_TTL`]. Symbols without enough daily
+//! candles for the window are flagged in the response and left out of the
+//! matrix rather than computed over a shorter history.
+
+use lib_core::AppError;
+use lib_solana::analytics::{correlation_matrix, volatility_over_window};
+use lib_solana::candle_aggregator::Timeframe;
+use lib_solana::price_stream::PriceStreamServer;
+use shared::dto::market::{MarketAnalyticsResponse, SymbolVolatility};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, instrument};
+
+/// Window used when the request doesn't give one, in days
+pub const DEFAULT_ANALYTICS_WINDOW: usize = 30;
+
+/// Shortest and longest windows accepted, in days
+pub const ANALYTICS_WINDOW_RANGE: std::ops::RangeInclusive<usize> = 7..=365;
+
+/// Most symbols in one matrix
+pub const MAX_ANALYTICS_SYMBOLS: usize = 20;
+
+/// How long a result is served from cache
+pub const ANALYTICS_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Cache key: symbols in request order (the matrix follows it) and window
+type AnalyticsKey = (Vec<String>, usize);
+
+/// Validate the `symbols` and `window` query parameters.
+///
+/// Symbols are upper-cased and de-duplicated, keeping their order.
+pub fn parse_analytics_query(symbols: &str, window: Option<usize>) -> Result<AnalyticsKey, AppError> {
+    let mut parsed: Vec<String> = Vec::new();
+    for symbol in symbols.split(',').map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()) {
+        if !parsed.contains(&symbol) {
+            parsed.push(symbol);
+        }
+    }
+    if parsed.is_empty() {
+        return Err(AppError::InvalidInput("At least one symbol is required".to_string()));
+    }
+    if parsed.len() > MAX_ANALYTICS_SYMBOLS {
+        return Err(AppError::InvalidInput(format!(
+            "At most {} symbols per request",
+            MAX_ANALYTICS_SYMBOLS
+        )));
+    }
+
+    let window = window.unwrap_or(DEFAULT_ANALYTICS_WINDOW);
+    if !ANALYTICS_WINDOW_RANGE.contains(&window) {
+        return Err(AppError::InvalidInput(format!(
+            "Window must be between {} and {} days",
+            ANALYTICS_WINDOW_RANGE.start(),
+            ANALYTICS_WINDOW_RANGE.end()
+        )));
+    }
+    Ok((parsed, window))
+}
+
+/// Compute the response from each symbol's `(timestamp, close)` daily series
+pub fn build_analytics(
+    symbols: &[String],
+    series: &[Vec<(u64, f64)>],
+    window: usize,
+    timestamp: i64,
+) -> MarketAnalyticsResponse {
+    let volatilities = symbols
+        .iter()
+        .zip(series)
+        .map(|(symbol, closes)| {
+            let estimate = volatility_over_window(closes, window);
+            SymbolVolatility {
+                symbol: symbol.clone(),
+                volatility: estimate.volatility,
+                observations: estimate.observations,
+                insufficient_history: estimate.insufficient_history,
+            }
+        })
+        .collect();
+
+    MarketAnalyticsResponse {
+        window_days: window,
+        symbols: volatilities,
+        correlations: correlation_matrix(series, window),
+        timestamp,
+    }
+}
+
+/// Per-(symbols, window) result cache with a fixed time-to-live
+#[derive(Debug, Default)]
+pub struct AnalyticsCache {
+    entries: HashMap<AnalyticsKey, (Instant, MarketAnalyticsResponse)>,
+}
+
+impl AnalyticsCache {
+    /// Cached result if it is younger than `ttl`
+    pub fn get(&self, key: &AnalyticsKey, ttl: Duration, now: Instant) -> Option<MarketAnalyticsResponse> {
+        self.entries
+            .get(key)
+            .filter(|(computed, _)| now.saturating_duration_since(*computed) < ttl)
+            .map(|(_, response)| response.clone())
+    }
+
+    /// Store a result, dropping any entries that have expired
+    pub fn insert(&mut self, key: AnalyticsKey, response: MarketAnalyticsResponse, ttl: Duration, now: Instant) {
+        self.entries
+            .retain(|_, (computed, _)| now.saturating_duration_since(*computed) < ttl);
+        self.entries.insert(key, (now, response));
+    }
+}
+
+/// Service for volatility and correlation analytics.
+///
+/// Held in the server state so the cache outlives individual requests.
+pub struct AnalyticsService {
+    price_stream: Arc<PriceStreamServer>,
+    cache: RwLock<AnalyticsCache>,
+}
+
+impl AnalyticsService {
+    pub fn new(price_stream: Arc<PriceStreamServer>) -> Self {
+        Self {
+            price_stream,
+            cache: RwLock::new(AnalyticsCache::default()),
+        }
+    }
+
+    /// Volatility per symbol and the correlation matrix over `window` days.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(MarketAnalyticsResponse)` - One entry per distinct symbol, in request order
+    /// * `Err(AppError::InvalidInput)` - No symbols, too many, or window out of range
+    #[instrument(skip(self))]
+    pub async fn get_analytics(&self, symbols: &str, window: Option<usize>) -> Result<MarketAnalyticsResponse, AppError> {
+        let key = parse_analytics_query(symbols, window)?;
+        if let Some(cached) = self.cache.read().await.get(&key, ANALYTICS_CACHE_TTL, Instant::now()) {
+            debug!("Analytics cache hit for {:?} over {} days", key.0, key.1);
+            return Ok(cached);
+        }
+
+        let (symbols, window) = &key;
+        let aggregator = self.price_stream.candle_aggregator();
+        let mut series = Vec::with_capacity(symbols.len());
+        for symbol in symbols {
+            let candles = aggregator.get_candles(symbol, Timeframe::OneDay, window + 1).await;
+            series.push(candles.into_iter().map(|c| (c.timestamp, c.close)).collect::<Vec<_>>());
+        }
+
+        let response = build_analytics(symbols, &series, *window, chrono::Utc::now().timestamp());
+        self.cache
+            .write()
+            .await
+            .insert(key, response.clone(), ANALYTICS_CACHE_TTL, Instant::now());
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+
+    fn daily(closes: &[f64]) -> Vec<(u64, f64)> {
+        closes.iter().enumerate().map(|(i, c)| (i as u64 * DAY, *c)).collect()
+    }
+
+    #[test]
+    fn test_parse_analytics_query() {
+        let (symbols, window) = parse_analytics_query(" sol,BONK,,Sol ", None).unwrap();
+        assert_eq!(symbols, vec!["SOL".to_string(), "BONK".to_string()]);
+        assert_eq!(window, DEFAULT_ANALYTICS_WINDOW);
+
+        assert!(parse_analytics_query("", None).is_err());
+        assert!(parse_analytics_query("SOL", Some(6)).is_err());
+        assert!(parse_analytics_query("SOL", Some(366)).is_err());
+        let many: Vec<String> = (0..=MAX_ANALYTICS_SYMBOLS).map(|i| format!("T{}", i)).collect();
+        assert!(parse_analytics_query(&many.join(","), None).is_err());
+    }
+
+    #[test]
+    fn test_build_analytics_flags_short_history() {
+        let closes: Vec<f64> = (0..=7).map(|i| 100.0 + (i % 3) as f64).collect();
+        let symbols = vec!["SOL".to_string(), "NEW".to_string()];
+        let series = vec![daily(&closes), daily(&closes[..3])];
+
+        let response = build_analytics(&symbols, &series, 7, 0);
+        assert_eq!(response.window_days, 7);
+        assert!(!response.symbols[0].insufficient_history);
+        assert!(response.symbols[0].volatility.is_some());
+        assert!(response.symbols[1].insufficient_history);
+        assert_eq!(response.symbols[1].observations, 2);
+        assert_eq!(response.symbols[1].volatility, None);
+        assert_eq!(response.correlations, vec![vec![Some(1.0), None], vec![None, None]]);
+    }
+
+    #[test]
+    fn test_cache_is_keyed_by_symbols_and_window() {
+        let mut cache = AnalyticsCache::default();
+        let start = Instant::now();
+        let key = (vec!["SOL".to_string(), "BONK".to_string()], 30);
+        cache.insert(key.clone(), build_analytics(&[], &[], 30, 0), ANALYTICS_CACHE_TTL, start);
+
+        assert!(cache.get(&key, ANALYTICS_CACHE_TTL, start).is_some());
+        assert!(cache.get(&(key.0.clone(), 60), ANALYTICS_CACHE_TTL, start).is_none());
+        assert!(cache.get(&(vec!["BONK".to_string(), "SOL".to_string()], 30), ANALYTICS_CACHE_TTL, start).is_none());
+        assert!(cache.get(&key, ANALYTICS_CACHE_TTL, start + ANALYTICS_CACHE_TTL).is_none());
+    }
+}
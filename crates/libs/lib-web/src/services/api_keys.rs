@@ -0,0 +1,215 @@
+//! # API Key Service
+//!
+//! Per-user API keys for scripted access to the backend.
+//!
+//! ## Keys
+//!
+//! A key is [`API_KEY_PREFIX`] followed by 256 random bits. It is returned
+//! once, by [`ApiKeyService::create`]; only its SHA-256 hash and first few
+//! characters are stored. [`authenticate`] resolves a key to the same
+//! [`Claims`] a login session carries, with `scopes` set to the key's scopes,
+//! so handlers behind `require_auth` work with either.
+//!
+//! ## Scopes
+//!
+//! Every key can read. Keys are read-only unless they were created with the
+//! `write` scope; `require_auth` turns away other requests of read-only keys
+//! with 403.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use lib_auth::Claims;
+use lib_core::model::store::models::ApiKey;
+use lib_core::model::store::{ApiKeyRepository, UserRepository};
+use lib_core::{AppError, DbPool};
+use sha2::{Digest, Sha256};
+use shared::dto::api_keys::{
+    ApiKeyInfo, ApiKeyListResponse, ApiKeyScope, CreateApiKeyRequest, CreateApiKeyResponse, API_KEY_PREFIX,
+    MAX_API_KEYS_PER_USER, MAX_API_KEY_LABEL_LEN,
+};
+use tracing::{debug, instrument, warn};
+use uuid::Uuid;
+
+/// Characters of a key kept to tell keys apart
+const SHOWN_PREFIX_LEN: usize = 12;
+
+/// Hex SHA-256 of a key; only this is stored
+pub fn hash_api_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// A new random API key
+pub fn new_api_key() -> String {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(Uuid::new_v4().as_bytes());
+    bytes[16..].copy_from_slice(Uuid::new_v4().as_bytes());
+    format!("{}{}", API_KEY_PREFIX, URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Scopes of a key row; `read` is always included
+pub fn parse_scopes(scopes: &str) -> Vec<ApiKeyScope> {
+    let mut parsed = vec![ApiKeyScope::Read];
+    for scope in scopes.split(',').filter_map(|name| ApiKeyScope::parse(name.trim())) {
+        if !parsed.contains(&scope) {
+            parsed.push(scope);
+        }
+    }
+    parsed
+}
+
+/// Public view of a key row
+pub fn api_key_info(key: &ApiKey) -> ApiKeyInfo {
+    ApiKeyInfo {
+        id: key.id,
+        label: key.label.clone(),
+        prefix: key.prefix.clone(),
+        scopes: parse_scopes(&key.scopes),
+        created_at: key.created_at.to_rfc3339(),
+        last_used_at: key.last_used_at.map(|at| at.to_rfc3339()),
+    }
+}
+
+/// Check a create request; returns the trimmed label and the scopes to store
+fn validate_request(request: &CreateApiKeyRequest) -> Result<(String, String), AppError> {
+    let label = request.label.trim();
+    if label.is_empty() {
+        return Err(AppError::InvalidInput("Give the key a label".to_string()));
+    }
+    if label.chars().count() > MAX_API_KEY_LABEL_LEN {
+        return Err(AppError::InvalidInput(format!(
+            "Key labels are at most {} characters",
+            MAX_API_KEY_LABEL_LEN
+        )));
+    }
+
+    let mut scopes = vec![ApiKeyScope::Read];
+    for scope in &request.scopes {
+        if !scopes.contains(scope) {
+            scopes.push(*scope);
+        }
+    }
+    let scopes = scopes.iter().map(ApiKeyScope::as_str).collect::<Vec<_>>().join(",");
+    Ok((label.to_string(), scopes))
+}
+
+/// Resolve an API key to its owner's claims.
+///
+/// The key's last use is recorded.
+///
+/// # Returns
+///
+/// * `Ok(Some(Claims))` - Claims of the key's owner, with the key's scopes
+/// * `Ok(None)` - Unknown or revoked key, or the owner's account is disabled
+/// * `Err(AppError::Internal)` - Database error
+pub async fn authenticate(db: &DbPool, key: &str) -> Result<Option<Claims>, AppError> {
+    let Some(api_key) = ApiKeyRepository::find_by_hash(db, &hash_api_key(key)).await.map_err(db_error)? else {
+        return Ok(None);
+    };
+    let Some(user) = UserRepository::find_by_id(db, api_key.user_id).await.map_err(db_error)? else {
+        return Ok(None);
+    };
+    if !user.is_active {
+        warn!(key_id = api_key.id, "[API KEYS] Key of disabled user {} used", user.id);
+        return Ok(None);
+    }
+
+    let now = chrono::Utc::now();
+    ApiKeyRepository::record_use(db, api_key.id, now).await.map_err(db_error)?;
+
+    Ok(Some(Claims {
+        sub: user.id.to_string(),
+        username: user.username,
+        exp: now.timestamp(),
+        iat: api_key.created_at.timestamp(),
+        jti: format!("api-key:{}", api_key.id),
+        scopes: Some(parse_scopes(&api_key.scopes).iter().map(|scope| scope.as_str().to_string()).collect()),
+    }))
+}
+
+/// Service for API keys
+pub struct ApiKeyService {
+    db: DbPool,
+}
+
+impl ApiKeyService {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+
+    /// Create a key for `user_id`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CreateApiKeyResponse)` - The key's details and the key itself (only returned here)
+    /// * `Err(AppError::InvalidInput)` - Bad label, or the per-user limit is reached
+    #[instrument(skip(self, request))]
+    pub async fn create(&self, user_id: i64, request: CreateApiKeyRequest) -> Result<CreateApiKeyResponse, AppError> {
+        let (label, scopes) = validate_request(&request)?;
+        let existing = ApiKeyRepository::find_by_user(&self.db, user_id).await.map_err(db_error)?;
+        if existing.len() >= MAX_API_KEYS_PER_USER {
+            return Err(AppError::InvalidInput(format!(
+                "At most {} API keys per account",
+                MAX_API_KEYS_PER_USER
+            )));
+        }
+
+        let key = new_api_key();
+        let prefix: String = key.chars().take(SHOWN_PREFIX_LEN).collect();
+        let api_key = ApiKeyRepository::create(&self.db, user_id, &hash_api_key(&key), &prefix, &label, &scopes)
+            .await
+            .map_err(db_error)?;
+        debug!(key_id = api_key.id, scopes = %api_key.scopes, "API key created");
+        Ok(CreateApiKeyResponse { api_key: api_key_info(&api_key), key })
+    }
+
+    /// The user's keys, oldest first
+    pub async fn list(&self, user_id: i64) -> Result<ApiKeyListResponse, AppError> {
+        let keys = ApiKeyRepository::find_by_user(&self.db, user_id).await.map_err(db_error)?;
+        Ok(ApiKeyListResponse { api_keys: keys.iter().map(api_key_info).collect() })
+    }
+
+    /// Revoke a key; requests made with it fail from then on
+    pub async fn revoke(&self, user_id: i64, id: i64) -> Result<(), AppError> {
+        if ApiKeyRepository::delete(&self.db, id, user_id).await.map_err(db_error)? {
+            debug!(key_id = id, "API key revoked");
+            Ok(())
+        } else {
+            Err(AppError::NotFound("API key not found".to_string()))
+        }
+    }
+}
+
+fn db_error(e: sqlx::Error) -> AppError {
+    AppError::Internal(format!("API key database error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_api_key() {
+        let key = new_api_key();
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_eq!(key.len(), API_KEY_PREFIX.len() + 43);
+        assert_ne!(new_api_key(), key);
+        assert_eq!(hash_api_key(&key).len(), 64);
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let request = |label: &str, scopes: Vec<ApiKeyScope>| CreateApiKeyRequest { label: label.to_string(), scopes };
+
+        let (label, scopes) = validate_request(&request(" Spreadsheet ", vec![])).unwrap();
+        assert_eq!(label, "Spreadsheet");
+        assert_eq!(scopes, "read");
+        let (_, scopes) = validate_request(&request("Bot", vec![ApiKeyScope::Write, ApiKeyScope::Write])).unwrap();
+        assert_eq!(scopes, "read,write");
+        assert_eq!(parse_scopes(&scopes), vec![ApiKeyScope::Read, ApiKeyScope::Write]);
+
+        assert!(validate_request(&request("  ", vec![])).is_err());
+        assert!(validate_request(&request(&"x".repeat(MAX_API_KEY_LABEL_LEN + 1), vec![])).is_err());
+    }
+}
@@ -0,0 +1,266 @@
+//! # Depth Service
+//!
+//! Builds an order-book style depth ladder for a token pair from Jupiter route
+//! quotes.
+//!
+//! ## Overview
+//!
+//! Jupiter has no order book, but quoting the same pair at increasing sizes
+//! shows how the effective price degrades as a trade walks through the
+//! available liquidity:
+//!
+//! ```text
+//! size     0.1   1     10    100   1000  (input units)
+//!            │     │     │     │     │
+//!            └─────┴──┬──┴─────┴─────┘
+//!                     │  at most 3 quotes in flight
+//!                     ▼
+//!              JupiterClient::get_swap_quote
+//! ```
+//!
+//! Each rung reports its own error, so a size Jupiter cannot route does not
+//! fail the ladder. Complete ladders are cached per pair for
+//! [`DEPTH_CACHE_TTL`] so terminals polling the widget share one set of quotes.
+
+use futures_util::{stream, Future, StreamExt};
+use lib_core::AppError;
+use lib_solana::SolanaState;
+use shared::dto::market::{DepthResponse, DepthRung};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, instrument, warn};
+
+/// Ladder sizes in input token units
+pub const DEPTH_LADDER: [f64; 5] = [0.1, 1.0, 10.0, 100.0, 1000.0];
+
+/// Maximum number of concurrent Jupiter quote requests per ladder
+pub const DEPTH_CONCURRENCY: usize = 3;
+
+/// How long a ladder is served from cache
+pub const DEPTH_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// Slippage used for depth quotes (does not affect the quoted route)
+const DEPTH_SLIPPAGE_BPS: u16 = 50;
+
+/// Outcome of quoting a single rung: `(output units, price impact %)`
+pub type RungQuote = Result<(f64, f64), String>;
+
+/// Quote every size with at most `concurrency` requests in flight.
+///
+/// Rungs are returned in the order of `sizes`.
+pub async fn build_ladder<F, Fut>(sizes: &[f64], concurrency: usize, quote: F) -> Vec<DepthRung>
+where
+    F: Fn(f64) -> Fut,
+    Fut: Future<Output = RungQuote>,
+{
+    stream::iter(sizes.iter().copied())
+        .map(|size| {
+            let fut = quote(size);
+            async move {
+                match fut.await {
+                    Ok((out_amount, impact)) if out_amount > 0.0 => DepthRung {
+                        size,
+                        effective_price: Some(out_amount / size),
+                        price_impact_pct: Some(impact),
+                        error: None,
+                    },
+                    Ok(_) => DepthRung {
+                        size,
+                        effective_price: None,
+                        price_impact_pct: None,
+                        error: Some("No output for this size".to_string()),
+                    },
+                    Err(e) => DepthRung {
+                        size,
+                        effective_price: None,
+                        price_impact_pct: None,
+                        error: Some(e),
+                    },
+                }
+            }
+        })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+/// Per-pair ladder cache with a fixed time-to-live
+#[derive(Debug, Default)]
+pub struct DepthCache {
+    entries: HashMap<(String, String), (Instant, DepthResponse)>,
+}
+
+impl DepthCache {
+    /// Cached ladder for the pair if it is younger than `ttl`
+    pub fn get(&self, key: &(String, String), ttl: Duration, now: Instant) -> Option<DepthResponse> {
+        self.entries
+            .get(key)
+            .filter(|(fetched, _)| now.saturating_duration_since(*fetched) < ttl)
+            .map(|(_, response)| response.clone())
+    }
+
+    /// Store a ladder, dropping any entries that have expired
+    pub fn insert(&mut self, key: (String, String), response: DepthResponse, ttl: Duration, now: Instant) {
+        self.entries
+            .retain(|_, (fetched, _)| now.saturating_duration_since(*fetched) < ttl);
+        self.entries.insert(key, (now, response));
+    }
+}
+
+/// Service for route-quote depth ladders.
+///
+/// Held in the server state so the cache outlives individual requests.
+pub struct DepthService {
+    solana: Arc<SolanaState>,
+    cache: RwLock<DepthCache>,
+}
+
+impl DepthService {
+    pub fn new(solana: Arc<SolanaState>) -> Self {
+        Self {
+            solana,
+            cache: RwLock::new(DepthCache::default()),
+        }
+    }
+
+    /// Get the depth ladder for `input` → `output` (token symbols).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(DepthResponse)` - Ladder with one rung per [`DEPTH_LADDER`] size
+    /// * `Err(AppError::InvalidInput)` - Unknown symbol or identical tokens
+    #[instrument(skip(self))]
+    pub async fn get_depth(&self, input: &str, output: &str) -> Result<DepthResponse, AppError> {
+        let input = input.trim().to_uppercase();
+        let output = output.trim().to_uppercase();
+        if input == output {
+            return Err(AppError::InvalidInput("Input and output tokens must differ".to_string()));
+        }
+
+        let key = (input.clone(), output.clone());
+        if let Some(cached) = self.cache.read().await.get(&key, DEPTH_CACHE_TTL, Instant::now()) {
+            debug!("Depth cache hit for {}/{}", input, output);
+            return Ok(cached);
+        }
+
+        let jupiter = &self.solana.jupiter;
+        let (input_mint, input_decimals) = jupiter
+            .resolve_token(&input)
+            .await
+            .ok_or_else(|| AppError::InvalidInput(format!("Unknown token: {}", input)))?;
+        let (output_mint, output_decimals) = jupiter
+            .resolve_token(&output)
+            .await
+            .ok_or_else(|| AppError::InvalidInput(format!("Unknown token: {}", output)))?;
+
+        let rungs = build_ladder(&DEPTH_LADDER, DEPTH_CONCURRENCY, |size| {
+            let input_mint = input_mint.clone();
+            let output_mint = output_mint.clone();
+            async move {
+                let amount = (size * 10f64.powi(input_decimals as i32)) as u64;
+                let quote = jupiter
+                    .get_swap_quote(&input_mint, &output_mint, amount, DEPTH_SLIPPAGE_BPS)
+                    .await
+                    .map_err(|e| {
+                        warn!("Depth quote for {} failed: {}", size, e);
+                        e.to_string()
+                    })?;
+                let out_amount = quote
+                    .out_amount
+                    .parse::<u64>()
+                    .map_err(|e| format!("Invalid out amount: {}", e))?;
+                Ok((
+                    out_amount as f64 / 10f64.powi(output_decimals as i32),
+                    quote.price_impact_pct,
+                ))
+            }
+        })
+        .await;
+
+        let response = DepthResponse {
+            input,
+            output,
+            rungs,
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+        self.cache
+            .write()
+            .await
+            .insert(key, response.clone(), DEPTH_CACHE_TTL, Instant::now());
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn response(input: &str) -> DepthResponse {
+        DepthResponse {
+            input: input.to_string(),
+            output: "USDC".to_string(),
+            rungs: Vec::new(),
+            timestamp: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ladder_reports_failures_per_rung() {
+        let rungs = build_ladder(&DEPTH_LADDER, DEPTH_CONCURRENCY, |size| async move {
+            if size >= 1000.0 {
+                Err("no route".to_string())
+            } else {
+                Ok((size * 150.0, size / 100.0))
+            }
+        })
+        .await;
+
+        assert_eq!(rungs.len(), 5);
+        assert_eq!(rungs.iter().map(|r| r.size).collect::<Vec<_>>(), DEPTH_LADDER);
+        assert_eq!(rungs[2].effective_price, Some(150.0));
+        assert_eq!(rungs[2].price_impact_pct, Some(0.1));
+        assert_eq!(rungs[4].effective_price, None);
+        assert_eq!(rungs[4].error.as_deref(), Some("no route"));
+    }
+
+    #[tokio::test]
+    async fn test_ladder_bounds_concurrency() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let sizes: Vec<f64> = (1..=10).map(f64::from).collect();
+
+        build_ladder(&sizes, DEPTH_CONCURRENCY, |size| {
+            let in_flight = in_flight.clone();
+            let peak = peak.clone();
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok((size, 0.0))
+            }
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), DEPTH_CONCURRENCY);
+    }
+
+    #[test]
+    fn test_cache_expires_after_ttl() {
+        let mut cache = DepthCache::default();
+        let start = Instant::now();
+        let key = ("SOL".to_string(), "USDC".to_string());
+        cache.insert(key.clone(), response("SOL"), DEPTH_CACHE_TTL, start);
+
+        assert!(cache.get(&key, DEPTH_CACHE_TTL, start + Duration::from_secs(9)).is_some());
+        assert!(cache.get(&key, DEPTH_CACHE_TTL, start + Duration::from_secs(10)).is_none());
+
+        // Inserting another pair evicts the expired entry
+        let later = start + Duration::from_secs(11);
+        cache.insert(("JUP".to_string(), "USDC".to_string()), response("JUP"), DEPTH_CACHE_TTL, later);
+        assert_eq!(cache.entries.len(), 1);
+    }
+}
@@ -0,0 +1,229 @@
+//! # Health Service
+//!
+//! Reports whether the backend and the services it depends on are reachable.
+//!
+//! ## Probes
+//!
+//! | Subsystem    | Probe                          | When failing |
+//! |--------------|--------------------------------|--------------|
+//! | `database`   | `SELECT 1`                     | `down`       |
+//! | `solana_rpc` | `getEpochInfo`                 | `degraded`   |
+//! | `jupiter`    | SOL price lookup               | `degraded`   |
+//! | `pyth`       | SOL oracle price lookup        | `degraded`   |
+//! | `websocket`  | Age of the last price stream poll | `degraded` |
+//!
+//! All probes run concurrently and each is capped at [`PROBE_TIMEOUT`], so a
+//! dead RPC node costs the caller at most one timeout instead of hanging the
+//! whole response.
+//!
+//! The report also carries the hit/miss counters of the market response
+//! caches, read from [`SolanaState::market_cache`] without probing anything.
+//!
+//! The `ping_*` functions are the probes themselves; `backend --self-test`
+//! runs the same ones before the server is started.
+
+use lib_core::DbPool;
+use lib_solana::{JupiterClient, PriceStreamServer, PythClient, SolanaClient, SolanaState};
+use shared::dto::system::{HealthResponse, HealthStatus, SubsystemHealth};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{instrument, warn};
+
+/// Upper bound for a single subsystem probe
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Run one probe with a timeout and record its latency
+pub async fn probe<F, E>(name: &str, timeout: Duration, check: F) -> SubsystemHealth
+where
+    F: Future<Output = Result<(), E>>,
+    E: std::fmt::Display,
+{
+    let started = Instant::now();
+    let result = tokio::time::timeout(timeout, check).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let error = match result {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!("Timed out after {}s", timeout.as_secs_f32())),
+    };
+    if let Some(err) = &error {
+        warn!("Health probe {} failed: {}", name, err);
+    }
+
+    SubsystemHealth {
+        name: name.to_string(),
+        status: if error.is_some() { HealthStatus::Down } else { HealthStatus::Ok },
+        latency_ms,
+        error,
+    }
+}
+
+/// `SELECT 1` against the database
+pub async fn ping_database(db: &DbPool) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT 1").execute(db).await.map(|_| ())
+}
+
+/// Fetch the current epoch from the RPC node
+pub async fn ping_rpc(rpc: &SolanaClient) -> anyhow::Result<()> {
+    rpc.get_epoch_info().await.map(|_| ())
+}
+
+/// Look up the SOL price on Jupiter
+pub async fn ping_jupiter(jupiter: &JupiterClient) -> anyhow::Result<()> {
+    jupiter.get_price("SOL").await.map(|_| ())
+}
+
+/// Look up the SOL oracle price on Pyth
+pub async fn ping_pyth(pyth: &PythClient) -> anyhow::Result<()> {
+    pyth.get_price("SOL").await.map(|_| ())
+}
+
+/// How long the price stream may go without publishing before it is reported down
+pub const STREAM_STALE_AFTER: Duration = Duration::from_secs(15);
+
+/// Check the price stream from the age of its last successful poll.
+///
+/// A stream with nothing to track never publishes, so it is only judged once
+/// it has symbols.
+pub fn check_stream(last_publish_age: Option<Duration>, tracking: bool) -> Result<(), String> {
+    if !tracking {
+        return Ok(());
+    }
+    match last_publish_age {
+        Some(age) if age <= STREAM_STALE_AFTER => Ok(()),
+        Some(age) => Err(format!("No price updates for {}s", age.as_secs())),
+        None => Err("Price stream has not published yet".to_string()),
+    }
+}
+
+/// Overall status: the backend is down without its database and degraded
+/// when any other dependency fails.
+pub fn overall_status(subsystems: &[SubsystemHealth]) -> HealthStatus {
+    let failing = |name: &str| {
+        subsystems
+            .iter()
+            .any(|s| s.name == name && s.status != HealthStatus::Ok)
+    };
+
+    if failing("database") {
+        HealthStatus::Down
+    } else if subsystems.iter().any(|s| s.status != HealthStatus::Ok) {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Ok
+    }
+}
+
+/// Service for backend health reports
+pub struct HealthService {
+    db: DbPool,
+    solana: Arc<SolanaState>,
+    price_stream: Arc<PriceStreamServer>,
+    started_at: Instant,
+}
+
+impl HealthService {
+    /// Create the service; uptime is measured from this call
+    pub fn new(db: DbPool, solana: Arc<SolanaState>, price_stream: Arc<PriceStreamServer>) -> Self {
+        Self {
+            db,
+            solana,
+            price_stream,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Probe every subsystem concurrently
+    #[instrument(skip(self))]
+    pub async fn check(&self) -> HealthResponse {
+        let (database, solana_rpc, jupiter, pyth, websocket) = tokio::join!(
+            probe("database", PROBE_TIMEOUT, ping_database(&self.db)),
+            probe("solana_rpc", PROBE_TIMEOUT, ping_rpc(&self.solana.rpc)),
+            probe("jupiter", PROBE_TIMEOUT, ping_jupiter(&self.solana.jupiter)),
+            probe("pyth", PROBE_TIMEOUT, ping_pyth(&self.solana.pyth)),
+            probe("websocket", PROBE_TIMEOUT, async {
+                let tracking = !self.price_stream.tracked_symbols().await.is_empty();
+                check_stream(self.price_stream.last_publish_age().await, tracking)
+            }),
+        );
+        let subsystems = vec![database, solana_rpc, jupiter, pyth, websocket];
+
+        HealthResponse {
+            status: overall_status(&subsystems),
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            subsystems,
+            caches: self.solana.market_cache.stats(),
+            timestamp: chrono::Utc::now().timestamp(),
+            wallet_path: crate::wallet_assets::mount_path().map(str::to_string),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subsystem(name: &str, status: HealthStatus) -> SubsystemHealth {
+        SubsystemHealth {
+            name: name.to_string(),
+            status,
+            latency_ms: 0,
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_times_out() {
+        let started = Instant::now();
+        let health = probe("solana_rpc", Duration::from_millis(20), async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok::<(), String>(())
+        })
+        .await;
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(health.status, HealthStatus::Down);
+        assert!(health.error.unwrap().starts_with("Timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_reports_error() {
+        let health = probe("jupiter", PROBE_TIMEOUT, async { Err::<(), _>("503 Service Unavailable") }).await;
+        assert_eq!(health.status, HealthStatus::Down);
+        assert_eq!(health.error.as_deref(), Some("503 Service Unavailable"));
+    }
+
+    #[test]
+    fn test_check_stream() {
+        assert!(check_stream(None, false).is_ok());
+        assert!(check_stream(Some(Duration::from_secs(1)), true).is_ok());
+        assert_eq!(
+            check_stream(Some(STREAM_STALE_AFTER + Duration::from_secs(5)), true).unwrap_err(),
+            "No price updates for 20s"
+        );
+        assert!(check_stream(None, true).is_err());
+    }
+
+    #[test]
+    fn test_overall_status() {
+        let ok = [
+            subsystem("database", HealthStatus::Ok),
+            subsystem("solana_rpc", HealthStatus::Ok),
+        ];
+        assert_eq!(overall_status(&ok), HealthStatus::Ok);
+
+        let rpc_down = [
+            subsystem("database", HealthStatus::Ok),
+            subsystem("solana_rpc", HealthStatus::Down),
+        ];
+        assert_eq!(overall_status(&rpc_down), HealthStatus::Degraded);
+
+        let db_down = [
+            subsystem("database", HealthStatus::Down),
+            subsystem("solana_rpc", HealthStatus::Ok),
+        ];
+        assert_eq!(overall_status(&db_down), HealthStatus::Down);
+    }
+}
@@ -0,0 +1,258 @@
+//! # Wallet Login Challenges
+//!
+//! Server-issued, single-use nonces for wallet login.
+//!
+//! `GET /api/wallet/login/challenge` issues a random challenge bound to one
+//! wallet address; `POST /api/auth/wallet-login` accepts a signature only over
+//! a challenge this store issued for that wallet, and consumes it. A captured
+//! signed message is therefore useless once it has been used or has expired.
+//!
+//! Challenges live in memory: a server restart invalidates pending logins,
+//! which only costs the user a second signature. Each client address may hold
+//! at most [`MAX_PENDING_PER_CLIENT`] unused challenges, so one client can't
+//! fill the store and lock everyone else out of wallet login; the route is
+//! also behind the login rate limiter.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+use uuid::Uuid;
+
+/// How long an issued challenge can be used
+pub const CHALLENGE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Most challenges held at once; issuing fails past this until some expire
+const MAX_PENDING: usize = 10_000;
+
+/// Most unused challenges one client address may hold at once
+pub const MAX_PENDING_PER_CLIENT: usize = 10;
+
+/// Message the wallet signs for `challenge`
+pub fn login_message(challenge: &str) -> String {
+    format!("Login to XForce Terminal\n\nChallenge: {}", challenge)
+}
+
+/// Why a challenge was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ChallengeError {
+    /// Never issued for this wallet, or already used
+    #[error("Unknown or already used login challenge")]
+    Unknown,
+    /// Issued, but the TTL has passed
+    #[error("Login challenge expired. Request a new one")]
+    Expired,
+    /// Too many challenges pending
+    #[error("Too many pending login challenges. Try again shortly")]
+    TooManyPending,
+    /// This client already holds [`MAX_PENDING_PER_CLIENT`] unused challenges
+    #[error("Too many unused login challenges from this address. Use one or wait for them to expire")]
+    TooManyForClient,
+}
+
+#[derive(Debug, Clone)]
+struct PendingChallenge {
+    wallet_address: String,
+    /// Address of the client that asked for it
+    client: String,
+    expires_at: Instant,
+}
+
+/// Pending login challenges keyed by challenge string
+#[derive(Debug)]
+pub struct LoginChallengeStore {
+    ttl: Duration,
+    pending: Mutex<HashMap<String, PendingChallenge>>,
+}
+
+impl Default for LoginChallengeStore {
+    fn default() -> Self {
+        Self::new(CHALLENGE_TTL)
+    }
+}
+
+impl LoginChallengeStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Time an issued challenge stays valid
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Issue a new challenge for `wallet_address`, requested by `client`
+    /// (its IP address).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The challenge; sign [`login_message`] of it
+    /// * `Err(ChallengeError::TooManyForClient)` - `client` holds too many unused challenges
+    /// * `Err(ChallengeError::TooManyPending)` - Store is full
+    pub fn issue(&self, wallet_address: &str, client: &str) -> Result<String, ChallengeError> {
+        self.issue_at(wallet_address, client, Instant::now())
+    }
+
+    fn issue_at(&self, wallet_address: &str, client: &str, now: Instant) -> Result<String, ChallengeError> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let held = pending.values().filter(|c| c.client == client && c.expires_at > now).count();
+        if held >= MAX_PENDING_PER_CLIENT {
+            return Err(ChallengeError::TooManyForClient);
+        }
+        if pending.len() >= MAX_PENDING {
+            pending.retain(|_, c| c.expires_at > now);
+            if pending.len() >= MAX_PENDING {
+                return Err(ChallengeError::TooManyPending);
+            }
+        }
+
+        let challenge = Uuid::new_v4().simple().to_string();
+        pending.insert(
+            challenge.clone(),
+            PendingChallenge {
+                wallet_address: wallet_address.to_string(),
+                client: client.to_string(),
+                expires_at: now + self.ttl,
+            },
+        );
+        Ok(challenge)
+    }
+
+    /// Use up `challenge` for `wallet_address`.
+    ///
+    /// A challenge issued for another wallet is left in place so it can't be
+    /// burned by someone who only saw the challenge.
+    pub fn consume(&self, wallet_address: &str, challenge: &str) -> Result<(), ChallengeError> {
+        self.consume_at(wallet_address, challenge, Instant::now())
+    }
+
+    fn consume_at(&self, wallet_address: &str, challenge: &str, now: Instant) -> Result<(), ChallengeError> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        match pending.get(challenge) {
+            Some(c) if c.wallet_address != wallet_address => Err(ChallengeError::Unknown),
+            Some(_) => {
+                let entry = pending.remove(challenge).expect("checked above");
+                if entry.expires_at > now {
+                    Ok(())
+                } else {
+                    Err(ChallengeError::Expired)
+                }
+            }
+            None => Err(ChallengeError::Unknown),
+        }
+    }
+
+    /// Drop expired challenges.
+    ///
+    /// # Returns
+    ///
+    /// Number of challenges removed.
+    pub fn cleanup(&self) -> usize {
+        self.cleanup_at(Instant::now())
+    }
+
+    fn cleanup_at(&self, now: Instant) -> usize {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let before = pending.len();
+        pending.retain(|_, c| c.expires_at > now);
+        before - pending.len()
+    }
+
+    /// Number of pending challenges
+    pub fn len(&self) -> usize {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Run [`LoginChallengeStore::cleanup`] every `every` in the background
+    pub fn spawn_cleanup(self: &Arc<Self>, every: Duration) {
+        let store = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let removed = store.cleanup();
+                if removed > 0 {
+                    debug!("[LOGIN CHALLENGE] Dropped {} expired challenges ({} left)", removed, store.len());
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: &str = "9aE476sH92Vz7DMPyq5WLPkrKWivxeuTKEFKd2sZZcde";
+    const CLIENT: &str = "203.0.113.7";
+
+    #[test]
+    fn test_issue_and_consume_once() {
+        let store = LoginChallengeStore::default();
+        let challenge = store.issue(WALLET, CLIENT).unwrap();
+        assert_eq!(challenge.len(), 32);
+        assert_ne!(store.issue(WALLET, CLIENT).unwrap(), challenge);
+
+        assert_eq!(store.consume(WALLET, &challenge), Ok(()));
+        assert_eq!(store.consume(WALLET, &challenge), Err(ChallengeError::Unknown));
+        assert_eq!(store.consume(WALLET, "client-made-up"), Err(ChallengeError::Unknown));
+    }
+
+    #[test]
+    fn test_challenge_is_bound_to_wallet() {
+        let store = LoginChallengeStore::default();
+        let challenge = store.issue(WALLET, CLIENT).unwrap();
+
+        assert_eq!(store.consume("someone-else", &challenge), Err(ChallengeError::Unknown));
+        // Still usable by the wallet it was issued for
+        assert_eq!(store.consume(WALLET, &challenge), Ok(()));
+    }
+
+    #[test]
+    fn test_expiry_and_cleanup() {
+        let store = LoginChallengeStore::new(Duration::from_secs(60));
+        let now = Instant::now();
+
+        let expired = store.issue_at(WALLET, CLIENT, now).unwrap();
+        assert_eq!(
+            store.consume_at(WALLET, &expired, now + Duration::from_secs(60)),
+            Err(ChallengeError::Expired)
+        );
+        // An expired challenge is gone after the failed attempt
+        assert_eq!(store.consume_at(WALLET, &expired, now), Err(ChallengeError::Unknown));
+
+        store.issue_at(WALLET, CLIENT, now).unwrap();
+        let fresh = store.issue_at(WALLET, CLIENT, now + Duration::from_secs(30)).unwrap();
+        assert_eq!(store.cleanup_at(now + Duration::from_secs(61)), 1);
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.consume_at(WALLET, &fresh, now + Duration::from_secs(61)), Ok(()));
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_one_client_cannot_fill_the_store() {
+        let store = LoginChallengeStore::new(Duration::from_secs(60));
+        let now = Instant::now();
+
+        // Random wallets from one address stop at the per-client cap
+        for i in 0..MAX_PENDING_PER_CLIENT {
+            store.issue_at(&format!("wallet-{}", i), CLIENT, now).unwrap();
+        }
+        assert_eq!(store.issue_at("wallet-x", CLIENT, now), Err(ChallengeError::TooManyForClient));
+
+        // Everyone else can still log in
+        let other = store.issue_at(WALLET, "198.51.100.2", now).unwrap();
+        assert_eq!(store.consume_at(WALLET, &other, now), Ok(()));
+
+        // Expired challenges no longer count against the client
+        assert!(store.issue_at("wallet-x", CLIENT, now + Duration::from_secs(61)).is_ok());
+    }
+}
@@ -0,0 +1,118 @@
+//! # Mailer
+//!
+//! Outgoing email behind the [`Mailer`] trait, so services can send mail
+//! without knowing how it is delivered (and tests can capture it).
+//!
+//! [`mailer_from_env`] picks the implementation:
+//!
+//! | `SMTP_HOST` | Mailer                                             |
+//! |-------------|----------------------------------------------------|
+//! | set         | [`SmtpMailer`] (STARTTLS, `SMTP_PORT` default 587) |
+//! | unset       | [`LogMailer`] - emails are logged, not sent        |
+//!
+//! ## Environment
+//!
+//! - `SMTP_HOST` / `SMTP_PORT` - Relay to submit mail to
+//! - `SMTP_USERNAME` / `SMTP_PASSWORD` - Relay credentials (optional)
+//! - `SMTP_FROM` - Sender mailbox (default [`DEFAULT_FROM`])
+
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Sender used when `SMTP_FROM` is unset
+pub const DEFAULT_FROM: &str = "XForce Terminal <no-reply@localhost>";
+
+/// A plain-text email
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivers emails
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> anyhow::Result<()>;
+}
+
+/// Development mailer that writes each email to the log
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: Email) -> anyhow::Result<()> {
+        info!(
+            "[MAIL] Not sent (SMTP_HOST unset)\n   To: {}\n   Subject: {}\n\n{}",
+            email.to, email.subject, email.body
+        );
+        Ok(())
+    }
+}
+
+/// Mailer that submits to an SMTP relay over STARTTLS
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    /// Create a mailer for `host:port`; nothing is sent until [`Mailer::send`]
+    pub fn new(host: &str, port: u16, credentials: Option<Credentials>, from: &str) -> anyhow::Result<Self> {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?.port(port);
+        if let Some(credentials) = credentials {
+            builder = builder.credentials(credentials);
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: from.parse()?,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: Email) -> anyhow::Result<()> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(email.to.parse()?)
+            .subject(email.subject)
+            .body(email.body)?;
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
+
+/// Build the mailer configured by the `SMTP_*` environment variables.
+///
+/// # Errors
+///
+/// Returns an error if `SMTP_PORT` or `SMTP_FROM` is malformed.
+pub fn mailer_from_env() -> anyhow::Result<Arc<dyn Mailer>> {
+    let Ok(host) = std::env::var("SMTP_HOST") else {
+        warn!("SMTP_HOST not set - emails will be logged instead of sent");
+        return Ok(Arc::new(LogMailer));
+    };
+
+    let port = match std::env::var("SMTP_PORT") {
+        Ok(port) => port
+            .parse()
+            .map_err(|e| anyhow::anyhow!("SMTP_PORT must be a valid port: {}", e))?,
+        Err(_) => 587,
+    };
+    let credentials = match (std::env::var("SMTP_USERNAME"), std::env::var("SMTP_PASSWORD")) {
+        (Ok(username), Ok(password)) => Some(Credentials::new(username, password)),
+        _ => None,
+    };
+    let from = std::env::var("SMTP_FROM").unwrap_or_else(|_| DEFAULT_FROM.to_string());
+
+    let mailer = SmtpMailer::new(&host, port, credentials, &from)
+        .map_err(|e| anyhow::anyhow!("Invalid SMTP configuration: {}", e))?;
+    info!("SMTP mailer configured ({}:{})", host, port);
+    Ok(Arc::new(mailer))
+}
@@ -10,8 +10,16 @@
 //! ## Features
 //!
 //! - **Price Fetching**: Get real-time prices for multiple tokens via cached price feeds
-//! - **Token Lists**: Fetch available tokens with metadata from Jupiter
+//! - **Source Comparison**: Each price carries what Pyth and Jupiter report and
+//!   is flagged `divergent` when they disagree by more than
+//!   `PRICE_DIVERGENCE_THRESHOLD_PCT` (1% by default)
+//! - **Token Lists**: Available tokens with metadata and verification from Jupiter, cached for a day
+//! - **Response Caching**: Whole responses are kept in [`MarketCaches`]
+//!   (prices ~2s per symbol set, token list ~1h), with concurrent identical
+//!   requests sharing one fetch; responses carry `cached` and `age_ms`
 //! - **Error Handling**: Comprehensive error handling with user-friendly messages
+//! - **Metrics**: With [`MarketService::with_metrics`], upstream fetches are
+//!   timed and their failures counted
 //!
 //! ## Usage
 //!
@@ -28,7 +36,7 @@
 //! let prices = service.get_prices(&["SOL", "USDC", "BTC"]).await?;
 //!
 //! // Get token list
-//! let tokens = service.get_token_list().await?;
+//! let tokens = service.get_token_list().await?.tokens;
 //! # Ok(())
 //! # }
 //! ```
@@ -44,22 +52,90 @@
 //! The service uses the price cache for efficient price lookups:
 //!
 //! ```text
-//! MarketService → PriceCache → (Pyth → Jupiter → Mock)
-//!              → JupiterClient → Token List API
+//! MarketService → MarketCaches → PriceCache → (Pyth ∥ Jupiter → Mock)
+//!                            → JupiterClient → Token List API
 //! ```
 
+use futures_util::future::join_all;
 use lib_core::AppError;
-use lib_solana::{SolanaState, types::PriceResponse};
+use lib_core::dto::market::OHLC;
+use lib_core::metrics::{time_upstream, Metrics};
+use lib_solana::cache::Cached;
+use lib_solana::candle_aggregator::{CandleAggregator, Timeframe};
+use lib_solana::{MarketCaches, SolanaState, aggregate, types::PriceResponse};
+use shared::dto::market::{CandleBatchResponse, TokenListItem, TokenListResponse};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, warn, instrument};
 
+/// Most symbols in one candle batch
+pub const MAX_CANDLE_BATCH_SYMBOLS: usize = 20;
+
+/// Most candles per symbol in one response
+pub const MAX_CANDLE_LIMIT: usize = 500;
+
+/// Validate the `symbols` parameter of a candle batch.
+///
+/// Symbols are upper-cased and de-duplicated, keeping their order.
+pub fn parse_candle_symbols(symbols: &str) -> Result<Vec<String>, AppError> {
+    let mut parsed: Vec<String> = Vec::new();
+    for symbol in symbols.split(',').map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()) {
+        if !parsed.contains(&symbol) {
+            parsed.push(symbol);
+        }
+    }
+    if parsed.is_empty() {
+        return Err(AppError::InvalidInput("At least one symbol is required".to_string()));
+    }
+    if parsed.len() > MAX_CANDLE_BATCH_SYMBOLS {
+        return Err(AppError::InvalidInput(format!(
+            "At most {} symbols per request",
+            MAX_CANDLE_BATCH_SYMBOLS
+        )));
+    }
+    Ok(parsed)
+}
+
+/// Sort per-symbol results into a batch response
+pub fn candle_batch_response(
+    timeframe: Timeframe,
+    results: Vec<(String, Result<Vec<OHLC>, AppError>)>,
+) -> CandleBatchResponse {
+    let mut response = CandleBatchResponse { timeframe: timeframe.label().to_string(), ..Default::default() };
+    for (symbol, result) in results {
+        match result {
+            Ok(candles) => {
+                let candles = candles
+                    .into_iter()
+                    .map(|c| shared::dto::market::OHLC {
+                        timestamp: c.timestamp,
+                        open: c.open,
+                        high: c.high,
+                        low: c.low,
+                        close: c.close,
+                        volume: c.volume,
+                    })
+                    .collect();
+                response.candles.insert(symbol, candles);
+            }
+            Err(e) => {
+                response.errors.insert(symbol, e.user_message());
+            }
+        }
+    }
+    response
+}
+
 /// Service for market data operations.
 ///
 /// This service provides business logic for fetching token prices and token lists.
 /// It coordinates with the Solana integration layer to retrieve market data.
 pub struct MarketService {
     solana: Arc<SolanaState>,
+    /// Spread between sources above which a price is flagged, in percent
+    divergence_threshold_pct: f64,
+    /// Registry upstream fetches are recorded in, if any
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl MarketService {
@@ -83,13 +159,24 @@ impl MarketService {
     /// # }
     /// ```
     pub fn new(solana: Arc<SolanaState>) -> Self {
-        Self { solana }
+        Self {
+            solana,
+            divergence_threshold_pct: aggregate::divergence_threshold_from_env(),
+            metrics: None,
+        }
+    }
+
+    /// Record the duration and failures of upstream fetches in `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
     }
 
     /// Get real-time prices for multiple tokens.
     ///
-    /// Fetches prices from the price cache, which aggregates data from multiple
-    /// sources (Pyth, Jupiter, mocks) with automatic fallback.
+    /// Fetches prices from the price cache, which asks Pyth and Jupiter
+    /// concurrently and falls back to mocks when both fail. Symbols are fetched
+    /// concurrently too.
     ///
     /// # Arguments
     ///
@@ -127,15 +214,48 @@ impl MarketService {
     /// - Prices are fetched from the cache, which is refreshed periodically
     /// - Missing prices for individual symbols are logged but don't fail the request
     /// - If no prices are available for any symbol, returns `NotFound` error
-    /// - Prices may come from different sources (Pyth, Jupiter, mocks)
+    /// - Prices may come from different sources (Pyth, Jupiter, mocks); `sources`
+    ///   lists each one's price and `divergent` is set when they disagree
+    /// - The whole response is cached per symbol set, whatever their order;
+    ///   `cached` and `age_ms` say whether this one came from the cache
     #[instrument(skip(self), fields(symbols = ?symbols.iter().collect::<Vec<_>>()))]
     pub async fn get_prices(&self, symbols: &[&str]) -> Result<PriceResponse, AppError> {
+        let key = MarketCaches::prices_key(symbols);
+        let response = self
+            .solana
+            .market_cache
+            .prices
+            .get_or_fetch(key, || self.fetch_prices(symbols))
+            .await?;
+        if response.cached {
+            debug!(age_ms = response.age_ms(), "Serving cached prices");
+        }
+
+        Ok(PriceResponse {
+            cached: response.cached,
+            age_ms: response.age_ms(),
+            ..response.value
+        })
+    }
+
+    /// Fetch every symbol from the price cache, skipping the ones that fail
+    async fn fetch_prices(&self, symbols: &[&str]) -> Result<PriceResponse, AppError> {
         let mut prices = HashMap::new();
 
-        for symbol in symbols {
+        // The price cache asks Pyth and Jupiter concurrently
+        let fetches = symbols.iter().map(|symbol| async move {
             debug!("Fetching price for {}...", symbol);
-            match self.solana.price_cache.get_price(symbol).await {
-                Ok(price_data) => {
+            let price = self.solana.price_cache.get_price(symbol);
+            (*symbol, time_upstream(self.metrics.as_deref(), "pyth_jupiter", "price", price).await)
+        });
+        for (symbol, result) in join_all(fetches).await {
+            match result {
+                Ok(mut price_data) => {
+                    price_data.divergent =
+                        aggregate::is_divergent(&price_data.sources, self.divergence_threshold_pct);
+                    if price_data.divergent {
+                        warn!(symbol, sources = ?price_data.sources, "Price sources disagree");
+                    }
                     debug!(
                         "{}: ${:.4} (source: {})",
                         symbol, price_data.price, price_data.source
@@ -155,17 +275,22 @@ impl MarketService {
             ));
         }
 
-        Ok(PriceResponse { prices })
+        Ok(PriceResponse {
+            prices,
+            cached: false,
+            age_ms: 0,
+        })
     }
 
     /// Get list of available tokens with metadata.
     ///
-    /// Fetches token metadata from Jupiter's token list API, which includes
-    /// token addresses, symbols, names, decimals, and logo URIs.
+    /// Token metadata comes from Jupiter's token list API and includes token
+    /// addresses, symbols, names, decimals, logo URIs and Jupiter's tags, from
+    /// which each token's `verified` flag is derived.
     ///
     /// # Returns
     ///
-    /// * `Ok(Vec<TokenInfo>)` - List of tokens with metadata
+    /// * `Ok(TokenListResponse)` - List of tokens with metadata
     /// * `Err(AppError::Internal)` - Failed to fetch token list from Jupiter
     ///
     /// # Example
@@ -179,11 +304,11 @@ impl MarketService {
     /// let solana = Arc::new(SolanaState::new(/* ... */).await?);
     /// let service = MarketService::new(solana);
     ///
-    /// let tokens = service.get_token_list().await?;
+    /// let response = service.get_token_list().await?;
     ///
-    /// println!("Found {} tokens", tokens.len());
-    /// for token in &tokens {
-    ///     println!("{}: {}", token.symbol, token.address);
+    /// println!("Found {} tokens", response.tokens.len());
+    /// for token in &response.tokens {
+    ///     println!("{}: {} (verified: {})", token.symbol, token.mint, token.verified);
     /// }
     /// # Ok(())
     /// # }
@@ -191,20 +316,151 @@ impl MarketService {
     ///
     /// # Notes
     ///
-    /// - Token list is fetched directly from Jupiter API (not cached)
-    /// - May take a few seconds for large token lists
+    /// - Token list is kept on disk across restarts and revalidated with
+    ///   Jupiter once a day in the background; while Jupiter is down the
+    ///   previous list keeps being served
+    /// - The converted response is cached on top of that (~1h), so concurrent
+    ///   requests share one conversion of the list
+    /// - The first fetch may take a few seconds for large token lists
     /// - Token metadata includes addresses, symbols, names, decimals, and logos
-    pub async fn get_token_list(&self) -> Result<Vec<lib_solana::jupiter::TokenInfo>, AppError> {
+    pub async fn get_token_list(&self) -> Result<TokenListResponse, AppError> {
+        let tokens = self
+            .solana
+            .market_cache
+            .token_list
+            .get_or_fetch((), || async {
+                let tokens = time_upstream(
+                    self.metrics.as_deref(),
+                    "jupiter",
+                    "token_list",
+                    self.solana.jupiter.get_cached_token_list(),
+                )
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to fetch token list: {}", e)))?;
+                Ok::<_, AppError>(tokens.iter().map(token_list_item).collect())
+            })
+            .await?;
+
+        Ok(TokenListResponse {
+            cached: tokens.cached,
+            age_ms: tokens.age_ms(),
+            tokens: tokens.value,
+        })
+    }
+
+    /// Download the Jupiter token list again and drop the cached response.
+    ///
+    /// For operators: the list is otherwise only revalidated once a day.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The new list is cached (and saved to disk)
+    /// * `Err(AppError::Internal)` - Jupiter couldn't be reached; the old list is kept
+    pub async fn force_refresh_token_list(&self) -> Result<(), AppError> {
         self.solana
             .jupiter
-            .get_token_list()
+            .force_refresh_token_list()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to refresh token list: {}", e)))?;
+        self.solana.market_cache.token_list.invalidate(&());
+        Ok(())
+    }
+
+    /// Get OHLC candles for one symbol from the price stream's aggregator.
+    ///
+    /// Candles are cached per symbol, timeframe and limit (~30s); `limit` is
+    /// capped at [`MAX_CANDLE_LIMIT`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Cached<Vec<OHLC>>)` - Candles oldest first, with their cache state
+    /// * `Err(AppError::NotFound)` - The aggregator has no candles for the symbol
+    #[instrument(skip(self, aggregator))]
+    pub async fn get_candles(
+        &self,
+        aggregator: &CandleAggregator,
+        symbol: &str,
+        timeframe: Timeframe,
+        limit: usize,
+    ) -> Result<Cached<Vec<OHLC>>, AppError> {
+        let limit = limit.min(MAX_CANDLE_LIMIT);
+        let key = (symbol.to_string(), timeframe.label().to_string(), limit);
+        self.solana
+            .market_cache
+            .ohlc
+            .get_or_fetch(key, || async {
+                let candles = aggregator.get_candles(symbol, timeframe, limit).await;
+                debug!(symbol, timeframe = timeframe.label(), limit, count = candles.len(), "Candles retrieved from aggregator");
+
+                // An empty answer is not cached, so the first candle shows up right away
+                if candles.is_empty() {
+                    warn!(symbol, timeframe = timeframe.label(), "No candles available for symbol");
+                    return Err(AppError::NotFound(format!("No candles available for symbol: {}", symbol)));
+                }
+
+                Ok(candles
+                    .into_iter()
+                    .map(|c| OHLC {
+                        timestamp: c.timestamp as i64,
+                        open: c.open,
+                        high: c.high,
+                        low: c.low,
+                        close: c.close,
+                        volume: c.volume,
+                    })
+                    .collect())
+            })
             .await
-            .map_err(|e| AppError::Internal(format!("Failed to fetch token list: {}", e)))
+    }
+
+    /// Get OHLC candles for several symbols at once.
+    ///
+    /// Symbols are fetched concurrently, each through the same cache as
+    /// [`MarketService::get_candles`]. A symbol that fails lands in the
+    /// response's `errors` rather than failing the batch.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CandleBatchResponse)` - Candles or an error for every distinct symbol
+    /// * `Err(AppError::InvalidInput)` - No symbols or more than [`MAX_CANDLE_BATCH_SYMBOLS`]
+    #[instrument(skip(self, aggregator))]
+    pub async fn get_candles_batch(
+        &self,
+        aggregator: &CandleAggregator,
+        symbols: &str,
+        timeframe: Timeframe,
+        limit: usize,
+    ) -> Result<CandleBatchResponse, AppError> {
+        let symbols = parse_candle_symbols(symbols)?;
+        let fetches = symbols.into_iter().map(|symbol| async move {
+            let result = self
+                .get_candles(aggregator, &symbol, timeframe, limit)
+                .await
+                .map(|candles| candles.value);
+            (symbol, result)
+        });
+        Ok(candle_batch_response(timeframe, join_all(fetches).await))
+    }
+}
+
+/// Wire form of a Jupiter token, with the verification tags resolved
+pub fn token_list_item(token: &lib_solana::jupiter::TokenInfo) -> TokenListItem {
+    TokenListItem {
+        symbol: token.symbol.clone(),
+        name: token.name.clone(),
+        mint: token.address.clone(),
+        decimals: token.decimals,
+        logo_uri: token.logo_uri.clone(),
+        tags: token.tags.clone(),
+        verified: token.is_verified(),
+        daily_volume: token.daily_volume,
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     // Note: These tests would require mocking SolanaState
     // For now, we'll add integration tests in the handlers
 
@@ -219,5 +475,35 @@ mod tests {
     async fn test_get_token_list() {
         // TODO: Add test with mock SolanaState
     }
+
+    #[test]
+    fn test_parse_candle_symbols() {
+        assert_eq!(parse_candle_symbols(" sol,BONK,,Sol ").unwrap(), vec!["SOL".to_string(), "BONK".to_string()]);
+        assert!(parse_candle_symbols(" , ").is_err());
+
+        let max: Vec<String> = (0..MAX_CANDLE_BATCH_SYMBOLS).map(|i| format!("T{}", i)).collect();
+        assert_eq!(parse_candle_symbols(&max.join(",")).unwrap().len(), MAX_CANDLE_BATCH_SYMBOLS);
+        let over: Vec<String> = (0..=MAX_CANDLE_BATCH_SYMBOLS).map(|i| format!("T{}", i)).collect();
+        let err = parse_candle_symbols(&over.join(",")).unwrap_err();
+        assert_eq!(err.status_code(), axum::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_candle_batch_keeps_per_symbol_errors() {
+        let candle = OHLC { timestamp: 3600, open: 1.0, high: 2.0, low: 0.5, close: 1.5, volume: 10.0 };
+        let response = candle_batch_response(
+            Timeframe::OneHour,
+            vec![
+                ("SOL".to_string(), Ok(vec![candle])),
+                ("NEW".to_string(), Err(AppError::NotFound("No candles available for symbol: NEW".to_string()))),
+            ],
+        );
+
+        assert_eq!(response.timeframe, "1h");
+        assert_eq!(response.candles["SOL"].len(), 1);
+        assert_eq!(response.candles["SOL"][0].close, 1.5);
+        assert!(!response.candles.contains_key("NEW"));
+        assert_eq!(response.errors["NEW"], "No candles available for symbol: NEW");
+    }
 }
 
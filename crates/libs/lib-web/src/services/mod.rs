@@ -15,10 +15,30 @@
 //! ## Module Organization
 //!
 //! - [`market`] - Market data services (prices, token lists)
+//! - [`depth`] - Route-quote depth ladders (cached per pair)
+//! - [`analytics`] - Volatility and correlation matrices from daily candles
+//! - [`health`] - Backend dependency health probes
+//! - [`streamed_symbols`] - Price stream symbol universe (seeded from config, admin-managed)
+//! - [`token_unlocks`] - Token unlock schedule (admin-managed, dataset import job)
+//! - [`onramp`] - Fiat on-ramp providers (operator-maintained provider file)
 //! - [`swap`] - Token swap services (quotes, execution)
+//! - [`quote_stream`] - Live quote streams shared by WebSocket clients
+//! - [`swap_simulation`] - Swap preflight simulation (expected balance changes, failure reasons)
 //! - [`wallet`] - Wallet operation services (balances, token accounts)
 //! - [`transaction`] - Transaction services (history, submission)
-//! - [`staking`] - Staking services (staking info, positions)
+//! - [`staking`] - Staking services (stake accounts, rewards, staking transactions)
+//! - [`trade_import`] - CSV trade imports and trade statistics (PnL)
+//! - [`portfolio`] - Swap positions valued at current prices
+//! - [`reports`] - Monthly account reports (PDF)
+//! - [`login_challenge`] - Single-use wallet login challenges
+//! - [`password_reset`] - Emailed one-time password reset tokens
+//! - [`api_keys`] - Per-user API keys for scripts (hashed, scoped)
+//! - [`share`] - Public read-only share links
+//! - [`telemetry`] - Opt-in anonymous usage counts, keyed by install id
+//! - [`mailer`] - Outgoing email (SMTP, or logged in development)
+//! - [`program_monitor`] - Program upgrade/authority monitoring job
+//! - [`webhooks`] - Signed outgoing webhooks with retries and a delivery log
+//! - [`wallet_activity`] - Wallet activity job raising webhook events
 //!
 //! ## Service Pattern
 //!
@@ -55,15 +75,55 @@
 //! ```
 
 pub mod market;
+pub mod depth;
+pub mod analytics;
+pub mod health;
+pub mod streamed_symbols;
+pub mod token_unlocks;
+pub mod onramp;
 pub mod swap;
+pub mod quote_stream;
+pub mod swap_simulation;
 pub mod wallet;
 pub mod transaction;
 pub mod staking;
+pub mod trade_import;
+pub mod portfolio;
+pub mod reports;
+pub mod login_challenge;
+pub mod password_reset;
+pub mod api_keys;
+pub mod share;
+pub mod telemetry;
+pub mod mailer;
+pub mod program_monitor;
+pub mod webhooks;
+pub mod wallet_activity;
 
 // Re-export services for convenience
 pub use market::MarketService;
+pub use depth::DepthService;
+pub use analytics::AnalyticsService;
+pub use health::HealthService;
+pub use streamed_symbols::StreamedSymbolService;
+pub use token_unlocks::TokenUnlockService;
+pub use onramp::OnRampService;
 pub use swap::SwapService;
+pub use quote_stream::{JupiterQuoteSource, QuoteBudget, QuoteStreamHub};
+pub use swap_simulation::SwapSimulationService;
 pub use wallet::WalletService;
 pub use transaction::TransactionService;
 pub use staking::StakingService;
+pub use trade_import::TradeImportService;
+pub use portfolio::PortfolioService;
+pub use reports::ReportService;
+pub use login_challenge::LoginChallengeStore;
+pub use password_reset::PasswordResetService;
+pub use api_keys::ApiKeyService;
+pub use share::ShareService;
+pub use telemetry::TelemetryService;
+pub use mailer::{mailer_from_env, Mailer};
+pub use program_monitor::ProgramMonitor;
+pub use webhooks::{WebhookConfig, WebhookDispatcher, WebhookService};
+pub use wallet_activity::WalletActivityWatcher;
 
@@ -0,0 +1,179 @@
+//! # Fiat On-Ramp Service
+//!
+//! Serves the on-ramp providers new users can buy SOL from. The list comes
+//! from a JSON file the operator maintains, so providers can be added, moved
+//! between regions or dropped without a release.
+//!
+//! ## Provider File
+//!
+//! A JSON array of providers, or an object with a `providers` array:
+//!
+//! ```json
+//! [
+//!   {
+//!     "id": "rampco",
+//!     "name": "RampCo",
+//!     "url_template": "https://buy.rampco.example/?crypto=SOL&address={address}&fiat_amount={amount}",
+//!     "regions": ["US", "GB", "DE"]
+//!   }
+//! ]
+//! ```
+//!
+//! Providers with an invalid URL template (see [`shared::dto::onramp`]), an
+//! invalid region code or an id already used are skipped with a warning; the
+//! rest are still served.
+//!
+//! ## Configuration
+//!
+//! - `ONRAMP_PROVIDERS_FILE` - Provider file; no providers are offered without it
+
+use serde::Deserialize;
+use shared::dto::onramp::{is_region_code, validate_template, OnRampProvider, OnRampProvidersResponse};
+use std::collections::HashSet;
+use tracing::{info, warn};
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ProviderFile {
+    List(Vec<serde_json::Value>),
+    Wrapped { providers: Vec<serde_json::Value> },
+}
+
+/// Providers parsed from a provider file, and how many entries were dropped
+#[derive(Debug, Default)]
+pub struct ParsedProviders {
+    pub providers: Vec<OnRampProvider>,
+    pub invalid: usize,
+}
+
+/// Check one provider, normalizing its region codes to upper case
+fn validate_provider(mut provider: OnRampProvider) -> Result<OnRampProvider, String> {
+    provider.id = provider.id.trim().to_string();
+    provider.name = provider.name.trim().to_string();
+    if provider.id.is_empty() || provider.name.is_empty() {
+        return Err("id and name are required".to_string());
+    }
+    validate_template(&provider.url_template).map_err(|e| e.to_string())?;
+    for region in &mut provider.regions {
+        if !is_region_code(region) {
+            return Err(format!("'{}' is not a two-letter country code", region));
+        }
+        region.make_ascii_uppercase();
+    }
+    Ok(provider)
+}
+
+/// Parse a provider file.
+///
+/// # Errors
+///
+/// Returns an error when the file isn't a JSON list of providers at all;
+/// individual bad entries are only counted.
+pub fn parse_providers(body: &str) -> Result<ParsedProviders, String> {
+    let entries = match serde_json::from_str::<ProviderFile>(body).map_err(|e| format!("Invalid provider file: {}", e))? {
+        ProviderFile::List(entries) | ProviderFile::Wrapped { providers: entries } => entries,
+    };
+
+    let mut parsed = ParsedProviders::default();
+    let mut ids = HashSet::new();
+    for entry in entries {
+        let provider = serde_json::from_value::<OnRampProvider>(entry)
+            .map_err(|e| e.to_string())
+            .and_then(validate_provider)
+            .and_then(|p| if ids.insert(p.id.clone()) { Ok(p) } else { Err(format!("duplicate id '{}'", p.id)) });
+        match provider {
+            Ok(provider) => parsed.providers.push(provider),
+            Err(e) => {
+                warn!(error = %e, "Skipping on-ramp provider");
+                parsed.invalid += 1;
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// Configured on-ramp providers
+#[derive(Debug, Default)]
+pub struct OnRampService {
+    providers: Vec<OnRampProvider>,
+}
+
+impl OnRampService {
+    pub fn new(providers: Vec<OnRampProvider>) -> Self {
+        Self { providers }
+    }
+
+    /// Load providers from `ONRAMP_PROVIDERS_FILE`.
+    ///
+    /// A missing or unreadable file leaves the list empty rather than
+    /// stopping the server; the terminal then says no providers are set up.
+    pub fn from_env() -> Self {
+        let Some(path) = std::env::var("ONRAMP_PROVIDERS_FILE").ok().filter(|p| !p.trim().is_empty()) else {
+            return Self::default();
+        };
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))
+            .and_then(|body| parse_providers(&body));
+        match parsed {
+            Ok(parsed) => {
+                info!(path = %path, providers = parsed.providers.len(), invalid = parsed.invalid, "Loaded on-ramp providers");
+                Self::new(parsed.providers)
+            }
+            Err(e) => {
+                warn!(error = %e, "No on-ramp providers loaded");
+                Self::default()
+            }
+        }
+    }
+
+    /// Providers serving `region`, or all of them without one
+    pub fn providers(&self, region: Option<&str>) -> OnRampProvidersResponse {
+        let providers = self
+            .providers
+            .iter()
+            .filter(|p| region.is_none_or(|region| p.supports_region(region)))
+            .cloned()
+            .collect();
+        OnRampProvidersResponse { providers }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"{
+        "providers": [
+            { "id": "global", "name": "Global Ramp", "url_template": "https://global.example/?to={address}" },
+            { "id": "eu", "name": "EU Ramp", "url_template": "https://eu.example/buy?wallet={address}&eur={amount}", "regions": ["de", "FR"] },
+            { "id": "bad-template", "name": "Bad", "url_template": "https://{address}.example/" },
+            { "id": "bad-region", "name": "Bad", "url_template": "https://x.example/?to={address}", "regions": ["Europe"] },
+            { "id": "eu", "name": "EU Ramp again", "url_template": "https://eu2.example/?to={address}" },
+            { "name": "No id" }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_skips_invalid_providers() {
+        let parsed = parse_providers(FILE).unwrap();
+        let ids: Vec<&str> = parsed.providers.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, ["global", "eu"]);
+        assert_eq!(parsed.providers[1].regions, ["DE", "FR"]);
+        assert_eq!(parsed.invalid, 4);
+
+        // A bare list works too
+        let parsed = parse_providers(r#"[{ "id": "a", "name": "A", "url_template": "https://a.example/?w={address}" }]"#).unwrap();
+        assert_eq!(parsed.providers.len(), 1);
+
+        assert!(parse_providers("not json").is_err());
+    }
+
+    #[test]
+    fn test_region_filter() {
+        let service = OnRampService::new(parse_providers(FILE).unwrap().providers);
+        let ids = |region| service.providers(region).providers.into_iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(ids(None), ["global", "eu"]);
+        assert_eq!(ids(Some("fr")), ["global", "eu"]);
+        assert_eq!(ids(Some("US")), ["global"]);
+    }
+}
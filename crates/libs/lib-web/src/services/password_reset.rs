@@ -0,0 +1,195 @@
+//! # Password Reset Service
+//!
+//! Emailed one-time tokens for users who forgot their password.
+//!
+//! ## Flow
+//!
+//! 1. `POST /api/auth/password-reset/request` - [`PasswordResetService::request`]
+//!    stores a hash of a random token (valid for [`RESET_TOKEN_TTL_MINUTES`])
+//!    and emails the token to the account, at most once per
+//!    [`RESEND_COOLDOWN_MINUTES`]
+//! 2. `POST /api/auth/password-reset/confirm` - [`PasswordResetService::confirm`]
+//!    checks the new password, spends the token and replaces the password hash
+//!
+//! ## Account Enumeration
+//!
+//! Requesting a reset for an unknown email succeeds like any other request,
+//! and the email is sent in a background task so response times don't differ
+//! either. Requests inside the resend cooldown are answered the same way
+//! without sending anything, and both routes sit behind the login rate limiter.
+
+use super::mailer::{Email, Mailer};
+use chrono::{DateTime, Utc};
+use lib_auth::hash_password;
+use lib_core::model::store::{PasswordResetRepository, UserRepository};
+use lib_core::{AppError, DbPool};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+/// How long an emailed token can be used
+pub const RESET_TOKEN_TTL_MINUTES: i64 = 30;
+
+/// Minimum time between two reset emails to the same account
+pub const RESEND_COOLDOWN_MINUTES: i64 = 5;
+
+/// Whether a token expiring at `latest_expiry` was issued less than
+/// [`RESEND_COOLDOWN_MINUTES`] before `now`
+fn in_cooldown(latest_expiry: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    latest_expiry.is_some_and(|expires_at| {
+        let issued_at = expires_at - chrono::Duration::minutes(RESET_TOKEN_TTL_MINUTES);
+        now - issued_at < chrono::Duration::minutes(RESEND_COOLDOWN_MINUTES)
+    })
+}
+
+/// Hex SHA-256 of a token; only this is stored
+fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// The reset email for `token`
+fn reset_email(to: &str, username: &str, token: &str) -> Email {
+    Email {
+        to: to.to_string(),
+        subject: "Reset your XForce Terminal password".to_string(),
+        body: format!(
+            "Hi {},\n\n\
+             Use this code to choose a new password:\n\n    {}\n\n\
+             The code expires in {} minutes and works once. If you didn't ask to reset \
+             your password, ignore this email; your password stays the same.\n",
+            username, token, RESET_TOKEN_TTL_MINUTES
+        ),
+    }
+}
+
+/// Service for password reset tokens
+pub struct PasswordResetService {
+    db: DbPool,
+    mailer: Arc<dyn Mailer>,
+}
+
+impl PasswordResetService {
+    pub fn new(db: DbPool, mailer: Arc<dyn Mailer>) -> Self {
+        Self { db, mailer }
+    }
+
+    /// Email a reset token to `email` if an active account uses it.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Token sent, no such account, or a token was already sent
+    ///   within [`RESEND_COOLDOWN_MINUTES`] (indistinguishable to the caller)
+    /// * `Err(AppError)` - Database error
+    pub async fn request(&self, email: &str) -> Result<(), AppError> {
+        let user = match UserRepository::find_by_email(&self.db, email.trim()).await? {
+            Some(user) if user.is_active => user,
+            _ => {
+                debug!("[PASSWORD RESET] No active account for requested email");
+                return Ok(());
+            }
+        };
+
+        let now = Utc::now();
+        let latest_expiry = PasswordResetRepository::latest_expiry(&self.db, user.id, now).await?;
+        if in_cooldown(latest_expiry, now) {
+            debug!("[PASSWORD RESET] Token for user {} sent recently, not resending", user.id);
+            return Ok(());
+        }
+
+        let token = Uuid::new_v4().simple().to_string();
+        let expires_at = now + chrono::Duration::minutes(RESET_TOKEN_TTL_MINUTES);
+        PasswordResetRepository::create(&self.db, &hash_token(&token), user.id, expires_at).await?;
+
+        let email = reset_email(&user.email, &user.username, &token);
+        let mailer = Arc::clone(&self.mailer);
+        tokio::spawn(async move {
+            if let Err(e) = mailer.send(email).await {
+                error!("[PASSWORD RESET] Failed to send reset email: {}", e);
+            }
+        });
+
+        info!("[PASSWORD RESET] Reset token issued for user {}", user.id);
+        Ok(())
+    }
+
+    /// Set a new password using an emailed token.
+    ///
+    /// The password is checked first, so a rejected password leaves the
+    /// token usable.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Password changed; the token and any other pending ones are spent
+    /// * `Err(AppError::InvalidInput)` - Weak password, or token unknown, used or expired
+    pub async fn confirm(&self, token: &str, new_password: &str) -> Result<(), AppError> {
+        let password_hash = hash_password(new_password).map_err(AppError::InvalidInput)?;
+
+        let user_id = PasswordResetRepository::reset_password(
+            &self.db,
+            &hash_token(token.trim()),
+            &password_hash,
+            chrono::Utc::now(),
+        )
+        .await?
+        .ok_or_else(|| AppError::InvalidInput("Invalid or expired reset token".to_string()))?;
+
+        info!("[PASSWORD RESET] Password changed for user {}", user_id);
+        Ok(())
+    }
+
+    /// Delete expired tokens every `every` in the background
+    pub fn spawn_cleanup(self: &Arc<Self>, every: Duration) {
+        let service = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                match PasswordResetRepository::delete_expired(&service.db, chrono::Utc::now()).await {
+                    Ok(0) => {}
+                    Ok(removed) => debug!("[PASSWORD RESET] Dropped {} expired tokens", removed),
+                    Err(e) => error!("[PASSWORD RESET] Token cleanup failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_token() {
+        let hash = hash_token("abc");
+        assert_eq!(hash, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_ne!(hash_token("abd"), hash);
+    }
+
+    #[test]
+    fn test_reset_email_contains_token() {
+        let email = reset_email("alice@example.com", "alice", "0123abcd");
+        assert_eq!(email.to, "alice@example.com");
+        assert!(email.body.contains("0123abcd"));
+        assert!(email.body.contains("30 minutes"));
+    }
+
+    #[test]
+    fn test_in_cooldown() {
+        let now = Utc::now();
+        let ttl = chrono::Duration::minutes(RESET_TOKEN_TTL_MINUTES);
+        assert!(!in_cooldown(None, now));
+        // Issued just now, and a minute before the cooldown ends
+        assert!(in_cooldown(Some(now + ttl), now));
+        let almost = chrono::Duration::minutes(RESEND_COOLDOWN_MINUTES - 1);
+        assert!(in_cooldown(Some(now + ttl - almost), now));
+        // Issued exactly one cooldown ago
+        let cooldown = chrono::Duration::minutes(RESEND_COOLDOWN_MINUTES);
+        assert!(!in_cooldown(Some(now + ttl - cooldown), now));
+    }
+}
@@ -0,0 +1,244 @@
+//! # Portfolio Service
+//!
+//! Positions built from the user's confirmed swaps, valued at current
+//! prices for unrealized PnL.
+//!
+//! Positions are kept in base units by `lib_core`'s position repository;
+//! this service resolves mints through the token list for symbols and
+//! decimals. Prices are looked up by symbol, so only verified tokens are
+//! priced: scam tokens reuse popular symbols and would borrow their price.
+
+use crate::services::market::MarketService;
+use lib_core::model::store::models::Position;
+use lib_core::model::store::PositionRepository;
+use lib_core::{AppError, DbPool};
+use lib_solana::SolanaState;
+use shared::dto::positions::{PositionItem, PositionsResponse};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{instrument, warn};
+
+/// Token list entry needed to value a position
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenMeta {
+    pub symbol: String,
+    pub decimals: u8,
+    pub verified: bool,
+}
+
+/// Service for positions and swap valuation
+pub struct PortfolioService {
+    db: DbPool,
+    market: MarketService,
+}
+
+impl PortfolioService {
+    pub fn new(db: DbPool, solana: Arc<SolanaState>) -> Self {
+        Self { db, market: MarketService::new(solana) }
+    }
+
+    /// The user's positions, valued at current prices.
+    ///
+    /// A failing token list or price lookup leaves positions unpriced
+    /// instead of failing the request.
+    #[instrument(skip(self))]
+    pub async fn positions(&self, user_id: i64) -> Result<PositionsResponse, AppError> {
+        let positions = PositionRepository::get_for_user(&self.db, user_id).await?;
+        if positions.is_empty() {
+            return Ok(PositionsResponse::default());
+        }
+
+        let tokens = self.tokens().await;
+        let mut symbols: Vec<&str> = positions
+            .iter()
+            .filter(|p| p.quantity > 0)
+            .filter_map(|p| tokens.get(&p.mint))
+            .filter(|t| t.verified)
+            .map(|t| t.symbol.as_str())
+            .collect();
+        symbols.sort_unstable();
+        symbols.dedup();
+        let prices = self.prices(&symbols).await;
+
+        Ok(value_positions(&positions, &tokens, &prices))
+    }
+
+    /// USD value of a swap at current prices: its input side's, or its
+    /// output side's when the input has no price.
+    ///
+    /// `None` when neither side can be priced; the swap then counts towards
+    /// positions without a value.
+    pub async fn swap_value_usd(
+        &self,
+        input_mint: &str,
+        input_amount: i64,
+        output_mint: &str,
+        output_amount: i64,
+    ) -> Option<f64> {
+        let tokens = self.tokens().await;
+        let input = tokens.get(input_mint).filter(|t| t.verified);
+        let output = tokens.get(output_mint).filter(|t| t.verified);
+        let symbols: Vec<&str> = input.iter().chain(output.iter()).map(|t| t.symbol.as_str()).collect();
+        if symbols.is_empty() {
+            return None;
+        }
+        let prices = self.prices(&symbols).await;
+
+        let leg = |token: Option<&TokenMeta>, amount: i64| {
+            let token = token?;
+            prices.get(&token.symbol).map(|price| ui_amount(amount, token.decimals) * price)
+        };
+        leg(input, input_amount).or_else(|| leg(output, output_amount))
+    }
+
+    /// Token list by mint; empty when it can't be fetched
+    pub(crate) async fn tokens(&self) -> HashMap<String, TokenMeta> {
+        match self.market.get_token_list().await {
+            Ok(list) => list
+                .tokens
+                .into_iter()
+                .map(|t| (t.mint, TokenMeta { symbol: t.symbol, decimals: t.decimals, verified: t.verified }))
+                .collect(),
+            Err(e) => {
+                warn!(error = %e, "Token list unavailable, positions stay unpriced");
+                HashMap::new()
+            }
+        }
+    }
+
+    /// USD prices by symbol; symbols without a price are left out
+    async fn prices(&self, symbols: &[&str]) -> HashMap<String, f64> {
+        if symbols.is_empty() {
+            return HashMap::new();
+        }
+        match self.market.get_prices(symbols).await {
+            Ok(response) => response.prices.into_iter().map(|(symbol, data)| (symbol, data.price)).collect(),
+            Err(e) => {
+                warn!(error = %e, ?symbols, "Prices unavailable for positions");
+                HashMap::new()
+            }
+        }
+    }
+}
+
+pub(crate) fn ui_amount(amount: i64, decimals: u8) -> f64 {
+    amount as f64 / 10f64.powi(decimals as i32)
+}
+
+/// Convert positions to whole tokens and value them at `prices` (by symbol).
+///
+/// Positions of mints missing from `tokens` keep their base-unit quantity
+/// and get no price; unverified tokens get no price either.
+pub fn value_positions(
+    positions: &[Position],
+    tokens: &HashMap<String, TokenMeta>,
+    prices: &HashMap<String, f64>,
+) -> PositionsResponse {
+    let mut response = PositionsResponse::default();
+    for position in positions {
+        let token = tokens.get(&position.mint);
+        let quantity = ui_amount(position.quantity, token.map_or(0, |t| t.decimals));
+        let price_usd = token
+            .filter(|t| t.verified)
+            .and_then(|t| prices.get(&t.symbol))
+            .copied()
+            .filter(|_| position.quantity > 0);
+        let market_value_usd = price_usd.map(|price| price * quantity);
+        let unrealized_pnl_usd = market_value_usd.map(|value| value - position.cost_basis_usd);
+
+        response.total_unrealized_pnl_usd += unrealized_pnl_usd.unwrap_or(0.0);
+        response.total_realized_pnl_usd += position.realized_pnl_usd;
+        response.positions.push(PositionItem {
+            mint: position.mint.clone(),
+            symbol: token.map(|t| t.symbol.clone()),
+            quantity,
+            cost_basis_usd: position.cost_basis_usd,
+            average_cost_usd: (position.quantity > 0).then(|| position.cost_basis_usd / quantity),
+            price_usd,
+            market_value_usd,
+            unrealized_pnl_usd,
+            realized_pnl_usd: position.realized_pnl_usd,
+            incomplete: position.incomplete,
+            closed_at: position.closed_at.map(|at| at.timestamp()),
+        });
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL: &str = "So11111111111111111111111111111111111111112";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn position(mint: &str, quantity: i64, cost_basis_usd: f64, realized_pnl_usd: f64) -> Position {
+        Position {
+            quantity,
+            cost_basis_usd,
+            realized_pnl_usd,
+            ..Position::empty(1, mint)
+        }
+    }
+
+    fn tokens() -> HashMap<String, TokenMeta> {
+        HashMap::from([
+            (SOL.to_string(), TokenMeta { symbol: "SOL".to_string(), decimals: 9, verified: true }),
+            (BONK.to_string(), TokenMeta { symbol: "BONK".to_string(), decimals: 5, verified: true }),
+            ("FAKE".to_string(), TokenMeta { symbol: "SOL".to_string(), decimals: 9, verified: false }),
+        ])
+    }
+
+    #[test]
+    fn test_value_positions_unrealized_pnl() {
+        let positions = [
+            position(SOL, 2_000_000_000, 250.0, 10.0),
+            position(BONK, 100_000_000_000, 30.0, -5.0),
+        ];
+        let prices = HashMap::from([("SOL".to_string(), 150.0), ("BONK".to_string(), 0.00002)]);
+
+        let response = value_positions(&positions, &tokens(), &prices);
+
+        let sol = &response.positions[0];
+        assert_eq!(sol.symbol.as_deref(), Some("SOL"));
+        assert_eq!(sol.quantity, 2.0);
+        assert_eq!(sol.average_cost_usd, Some(125.0));
+        assert_eq!(sol.market_value_usd, Some(300.0));
+        assert_eq!(sol.unrealized_pnl_usd, Some(50.0));
+
+        // 1M BONK worth 20 against a cost of 30
+        let bonk = &response.positions[1];
+        assert_eq!(bonk.quantity, 1_000_000.0);
+        assert!((bonk.unrealized_pnl_usd.unwrap() + 10.0).abs() < 1e-9);
+
+        assert!((response.total_unrealized_pnl_usd - 40.0).abs() < 1e-9);
+        assert_eq!(response.total_realized_pnl_usd, 5.0);
+    }
+
+    #[test]
+    fn test_value_positions_without_price() {
+        let positions = [
+            position("UNKNOWN", 500, 7.0, 0.0),
+            position("FAKE", 1_000_000_000, 1.0, 0.0),
+            position(SOL, 0, 0.0, 12.0),
+        ];
+        let prices = HashMap::from([("SOL".to_string(), 150.0)]);
+
+        let response = value_positions(&positions, &tokens(), &prices);
+
+        // Unknown mint: base units, no symbol, no price
+        let unknown = &response.positions[0];
+        assert_eq!(unknown.symbol, None);
+        assert_eq!(unknown.quantity, 500.0);
+        assert_eq!(unknown.price_usd, None);
+        assert_eq!(unknown.unrealized_pnl_usd, None);
+        // An unverified token doesn't get the real SOL price
+        assert_eq!(response.positions[1].price_usd, None);
+        // Sold out: only realized PnL
+        let sol = &response.positions[2];
+        assert_eq!(sol.average_cost_usd, None);
+        assert_eq!(sol.unrealized_pnl_usd, None);
+        assert_eq!(response.total_unrealized_pnl_usd, 0.0);
+        assert_eq!(response.total_realized_pnl_usd, 12.0);
+    }
+}
@@ -0,0 +1,515 @@
+//! # Program Monitor Service
+//!
+//! Background job that watches the on-chain deployments of the programs the
+//! backend depends on and raises a system notice when one of them changes.
+//!
+//! ## Overview
+//!
+//! Every Solana program owned by the upgradeable BPF loader has two accounts:
+//!
+//! ```text
+//! Program account      → UpgradeableLoaderState::Program { programdata_address }
+//! ProgramData account  → UpgradeableLoaderState::ProgramData { slot, upgrade_authority }
+//!                        followed by the deployed ELF bytes
+//! ```
+//!
+//! For each configured program id the monitor resolves the ProgramData account,
+//! records its deployed slot, a SHA-256 of the program bytes, and the upgrade
+//! authority in the `program_versions` table, and compares them against the
+//! previous observation. Any difference is broadcast as a [`SystemNotice`],
+//! which the price stream WebSocket forwards to connected terminals.
+//!
+//! ## Configuration
+//!
+//! - `MONITORED_PROGRAM_IDS` - Comma-separated program ids (defaults to the
+//!   batch swap router and Jupiter aggregator v6)
+//! - `PROGRAM_MONITOR_INTERVAL_SECS` - Poll interval (default: 300)
+
+use lib_core::model::store::models::ProgramVersion;
+use lib_core::model::store::ProgramVersionRepository;
+use lib_core::{AppError, DbPool};
+use lib_solana::client::SolanaClient;
+use shared::dto::system::{NoticeCategory, NoticeLevel, SystemNotice};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::time::Duration;
+use tracing::{debug, info, instrument, warn};
+
+/// Jupiter aggregator v6 program id
+const JUPITER_V6_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QcvCu1NR";
+
+/// Default poll interval in seconds
+const DEFAULT_INTERVAL_SECS: u64 = 300;
+
+/// `UpgradeableLoaderState::Program` discriminant
+const PROGRAM_TAG: u32 = 2;
+
+/// `UpgradeableLoaderState::ProgramData` discriminant
+const PROGRAM_DATA_TAG: u32 = 3;
+
+/// Size of the ProgramData header: tag (4) + slot (8) + Option<Pubkey> (1 + 32)
+pub const PROGRAM_DATA_METADATA_SIZE: usize = 45;
+
+/// Deployment details parsed from a ProgramData account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramDataInfo {
+    /// Slot the current program bytes were deployed at
+    pub deployed_slot: u64,
+    /// Upgrade authority (`None` once the program is made immutable)
+    pub upgrade_authority: Option<Pubkey>,
+    /// Hex-encoded SHA-256 of the program bytes (header excluded)
+    pub data_hash: String,
+}
+
+/// A single difference between two observations of a program.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgramChange {
+    /// Program was redeployed
+    Redeployed { previous_slot: i64, new_slot: u64 },
+    /// Program bytes changed
+    BytecodeChanged { previous_hash: String, new_hash: String },
+    /// Upgrade authority was transferred or revoked
+    AuthorityChanged { previous: Option<String>, new: Option<String> },
+}
+
+impl std::fmt::Display for ProgramChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProgramChange::Redeployed { previous_slot, new_slot } => {
+                write!(f, "redeployed at slot {} (was {})", new_slot, previous_slot)
+            }
+            ProgramChange::BytecodeChanged { previous_hash, new_hash } => {
+                write!(f, "bytecode hash {}… → {}…", short_hash(previous_hash), short_hash(new_hash))
+            }
+            ProgramChange::AuthorityChanged { previous, new } => write!(
+                f,
+                "upgrade authority {} → {}",
+                previous.as_deref().unwrap_or("none"),
+                new.as_deref().unwrap_or("none (immutable)")
+            ),
+        }
+    }
+}
+
+fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(8)]
+}
+
+/// Extract the ProgramData address from an upgradeable Program account.
+pub fn parse_program_account(data: &[u8]) -> Result<Pubkey, AppError> {
+    if data.len() < 36 || read_tag(data) != PROGRAM_TAG {
+        return Err(AppError::Decoding("Account is not an upgradeable program".to_string()));
+    }
+    Ok(read_pubkey(&data[4..36]))
+}
+
+/// Parse a ProgramData account into its deployment details.
+///
+/// # Layout
+///
+/// ```text
+/// [0..4)   u32 tag (3 = ProgramData)
+/// [4..12)  u64 deployed slot
+/// [12]     Option tag (0 = None, 1 = Some)
+/// [13..45) upgrade authority pubkey (present even when None)
+/// [45..)   program bytes
+/// ```
+pub fn parse_program_data(data: &[u8]) -> Result<ProgramDataInfo, AppError> {
+    if data.len() < PROGRAM_DATA_METADATA_SIZE || read_tag(data) != PROGRAM_DATA_TAG {
+        return Err(AppError::Decoding("Account is not a ProgramData account".to_string()));
+    }
+
+    let mut slot_bytes = [0u8; 8];
+    slot_bytes.copy_from_slice(&data[4..12]);
+    let deployed_slot = u64::from_le_bytes(slot_bytes);
+
+    let upgrade_authority = match data[12] {
+        0 => None,
+        1 => Some(read_pubkey(&data[13..45])),
+        other => {
+            return Err(AppError::Decoding(format!("Invalid upgrade authority option tag: {}", other)));
+        }
+    };
+
+    let program_bytes = &data[PROGRAM_DATA_METADATA_SIZE..];
+    let data_hash = solana_sdk::hash::hash(program_bytes)
+        .to_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    Ok(ProgramDataInfo {
+        deployed_slot,
+        upgrade_authority,
+        data_hash,
+    })
+}
+
+fn read_tag(data: &[u8]) -> u32 {
+    u32::from_le_bytes([data[0], data[1], data[2], data[3]])
+}
+
+fn read_pubkey(bytes: &[u8]) -> Pubkey {
+    let mut key = [0u8; 32];
+    key.copy_from_slice(bytes);
+    Pubkey::new_from_array(key)
+}
+
+/// Compare the latest recorded version with a fresh observation.
+///
+/// Returns an empty list for the first observation of a program, since there
+/// is nothing to compare against.
+pub fn detect_changes(previous: Option<&ProgramVersion>, current: &ProgramDataInfo) -> Vec<ProgramChange> {
+    let Some(previous) = previous else {
+        return Vec::new();
+    };
+
+    let mut changes = Vec::new();
+
+    if previous.deployed_slot != current.deployed_slot as i64 {
+        changes.push(ProgramChange::Redeployed {
+            previous_slot: previous.deployed_slot,
+            new_slot: current.deployed_slot,
+        });
+    }
+
+    if previous.data_hash != current.data_hash {
+        changes.push(ProgramChange::BytecodeChanged {
+            previous_hash: previous.data_hash.clone(),
+            new_hash: current.data_hash.clone(),
+        });
+    }
+
+    let current_authority = current.upgrade_authority.map(|a| a.to_string());
+    if previous.upgrade_authority != current_authority {
+        changes.push(ProgramChange::AuthorityChanged {
+            previous: previous.upgrade_authority.clone(),
+            new: current_authority,
+        });
+    }
+
+    changes
+}
+
+/// Build the notice raised for a set of changes to one program.
+///
+/// Authority changes are flagged as critical since they affect who can push
+/// new code to a contract the terminal routes funds through.
+pub fn build_notice(program_id: &str, changes: &[ProgramChange]) -> SystemNotice {
+    let level = if changes.iter().any(|c| matches!(c, ProgramChange::AuthorityChanged { .. })) {
+        NoticeLevel::Critical
+    } else {
+        NoticeLevel::Warning
+    };
+
+    let details: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+
+    SystemNotice {
+        level,
+        title: "Monitored program changed".to_string(),
+        message: format!("Program {}: {}", program_id, details.join("; ")),
+        timestamp: chrono::Utc::now().timestamp(),
+        category: NoticeCategory::System,
+        maintenance: None,
+    }
+}
+
+/// Read monitored program ids from `MONITORED_PROGRAM_IDS`.
+pub fn monitored_program_ids_from_env(defaults: &[Pubkey]) -> Vec<Pubkey> {
+    match std::env::var("MONITORED_PROGRAM_IDS") {
+        Ok(value) if !value.trim().is_empty() => value
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| match Pubkey::from_str(s) {
+                Ok(pubkey) => Some(pubkey),
+                Err(e) => {
+                    warn!(program_id = %s, error = %e, "Ignoring invalid monitored program id");
+                    None
+                }
+            })
+            .collect(),
+        _ => {
+            let mut ids = defaults.to_vec();
+            if let Ok(jupiter) = Pubkey::from_str(JUPITER_V6_PROGRAM_ID) {
+                if !ids.contains(&jupiter) {
+                    ids.push(jupiter);
+                }
+            }
+            ids
+        }
+    }
+}
+
+/// Watches program deployments and broadcasts notices on change.
+pub struct ProgramMonitor {
+    db: DbPool,
+    rpc: Arc<SolanaClient>,
+    program_ids: Vec<Pubkey>,
+    notice_tx: broadcast::Sender<SystemNotice>,
+}
+
+impl ProgramMonitor {
+    /// Create a new program monitor.
+    ///
+    /// # Arguments
+    /// * `db` - Database pool holding the `program_versions` table
+    /// * `rpc` - Solana RPC client used to fetch program accounts
+    /// * `program_ids` - Programs to watch
+    pub fn new(db: DbPool, rpc: Arc<SolanaClient>, program_ids: Vec<Pubkey>) -> Self {
+        let (notice_tx, _) = broadcast::channel(64);
+        Self {
+            db,
+            rpc,
+            program_ids,
+            notice_tx,
+        }
+    }
+
+    /// Get a receiver for system notices (used by WebSocket handlers)
+    pub fn subscribe(&self) -> broadcast::Receiver<SystemNotice> {
+        self.notice_tx.subscribe()
+    }
+
+    /// Programs being watched
+    pub fn program_ids(&self) -> &[Pubkey] {
+        &self.program_ids
+    }
+
+    /// Recorded history for a monitored program, newest first.
+    pub async fn history(&self, program_id: &Pubkey, limit: usize) -> Result<Vec<ProgramVersion>, AppError> {
+        ProgramVersionRepository::find_history(&self.db, &program_id.to_string(), limit)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to load program history: {}", e)))
+    }
+
+    /// Run the monitor loop until the process exits.
+    pub async fn start(self: Arc<Self>) {
+        let interval_secs = std::env::var("PROGRAM_MONITOR_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_INTERVAL_SECS);
+
+        info!(
+            programs = self.program_ids.len(),
+            interval_secs,
+            "Program monitor started"
+        );
+
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            self.check_all().await;
+        }
+    }
+
+    /// Check every monitored program once.
+    pub async fn check_all(&self) {
+        for program_id in &self.program_ids {
+            if let Err(e) = self.check_program(program_id).await {
+                warn!(program_id = %program_id, error = %e, "Program monitor check failed");
+            }
+        }
+    }
+
+    /// Fetch the current deployment of a program and record it.
+    #[instrument(skip(self), fields(program_id = %program_id))]
+    pub async fn check_program(&self, program_id: &Pubkey) -> Result<Option<SystemNotice>, AppError> {
+        let program_account = self.rpc.get_account(program_id).await
+            .map_err(|e| AppError::Rpc(e.to_string()))?;
+        let programdata_address = parse_program_account(&program_account.data)?;
+
+        let programdata_account = self.rpc.get_account(&programdata_address).await
+            .map_err(|e| AppError::Rpc(e.to_string()))?;
+        let info = parse_program_data(&programdata_account.data)?;
+
+        self.record_observation(program_id, &programdata_address, &info).await
+    }
+
+    /// Compare an observation with the stored history, persist it if it differs,
+    /// and broadcast a notice when a previously recorded deployment changed.
+    pub async fn record_observation(
+        &self,
+        program_id: &Pubkey,
+        programdata_address: &Pubkey,
+        info: &ProgramDataInfo,
+    ) -> Result<Option<SystemNotice>, AppError> {
+        let program_id_str = program_id.to_string();
+        let db_err = |e: sqlx::Error| AppError::Internal(format!("Program version store error: {}", e));
+
+        let previous = ProgramVersionRepository::find_latest(&self.db, &program_id_str)
+            .await
+            .map_err(db_err)?;
+        let changes = detect_changes(previous.as_ref(), info);
+
+        if previous.is_some() && changes.is_empty() {
+            debug!(program_id = %program_id_str, slot = info.deployed_slot, "Program unchanged");
+            return Ok(None);
+        }
+
+        let authority = info.upgrade_authority.map(|a| a.to_string());
+        ProgramVersionRepository::record(
+            &self.db,
+            &program_id_str,
+            &programdata_address.to_string(),
+            info.deployed_slot as i64,
+            &info.data_hash,
+            authority.as_deref(),
+        )
+        .await
+        .map_err(db_err)?;
+
+        if changes.is_empty() {
+            info!(program_id = %program_id_str, slot = info.deployed_slot, "Recorded initial program version");
+            return Ok(None);
+        }
+
+        let notice = build_notice(&program_id_str, &changes);
+        warn!(program_id = %program_id_str, message = %notice.message, "Monitored program changed");
+        // No subscribers just means no terminal is connected right now
+        let _ = self.notice_tx.send(notice.clone());
+
+        Ok(Some(notice))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lib_solana::Network;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    /// Build a ProgramData account fixture
+    fn program_data_fixture(slot: u64, authority: Option<Pubkey>, program: &[u8]) -> Vec<u8> {
+        let mut data = Vec::with_capacity(PROGRAM_DATA_METADATA_SIZE + program.len());
+        data.extend_from_slice(&PROGRAM_DATA_TAG.to_le_bytes());
+        data.extend_from_slice(&slot.to_le_bytes());
+        match authority {
+            Some(key) => {
+                data.push(1);
+                data.extend_from_slice(key.as_ref());
+            }
+            None => {
+                data.push(0);
+                data.extend_from_slice(&[0u8; 32]);
+            }
+        }
+        data.extend_from_slice(program);
+        data
+    }
+
+    async fn setup_monitor() -> ProgramMonitor {
+        let pool = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS program_versions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                program_id TEXT NOT NULL,
+                programdata_address TEXT NOT NULL,
+                deployed_slot INTEGER NOT NULL,
+                data_hash TEXT NOT NULL,
+                upgrade_authority TEXT,
+                observed_at TIMESTAMP NOT NULL
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create program_versions table");
+
+        let rpc = Arc::new(SolanaClient::new(Network::Devnet, None));
+        ProgramMonitor::new(pool, rpc, Vec::new())
+    }
+
+    #[test]
+    fn test_parse_program_account() {
+        let programdata = Pubkey::new_unique();
+        let mut data = PROGRAM_TAG.to_le_bytes().to_vec();
+        data.extend_from_slice(programdata.as_ref());
+
+        assert_eq!(parse_program_account(&data).unwrap(), programdata);
+        assert!(parse_program_account(&data[..20]).is_err());
+    }
+
+    #[test]
+    fn test_parse_program_data_layout() {
+        let authority = Pubkey::new_unique();
+        let data = program_data_fixture(123_456, Some(authority), b"\x7fELF program v1");
+
+        let info = parse_program_data(&data).unwrap();
+        assert_eq!(info.deployed_slot, 123_456);
+        assert_eq!(info.upgrade_authority, Some(authority));
+        assert_eq!(info.data_hash.len(), 64);
+
+        let immutable = parse_program_data(&program_data_fixture(1, None, b"x")).unwrap();
+        assert!(immutable.upgrade_authority.is_none());
+
+        let mut wrong_tag = data.clone();
+        wrong_tag[0] = PROGRAM_TAG as u8;
+        assert!(parse_program_data(&wrong_tag).is_err());
+    }
+
+    #[test]
+    fn test_hash_covers_program_bytes_only() {
+        let authority = Pubkey::new_unique();
+        let v1 = parse_program_data(&program_data_fixture(10, Some(authority), b"program v1")).unwrap();
+        let v1_other_slot = parse_program_data(&program_data_fixture(11, None, b"program v1")).unwrap();
+        let v2 = parse_program_data(&program_data_fixture(10, Some(authority), b"program v2")).unwrap();
+
+        assert_eq!(v1.data_hash, v1_other_slot.data_hash);
+        assert_ne!(v1.data_hash, v2.data_hash);
+    }
+
+    #[tokio::test]
+    async fn test_notice_emitted_on_upgrade() {
+        let monitor = setup_monitor().await;
+        let mut notices = monitor.subscribe();
+        let program_id = Pubkey::new_unique();
+        let programdata = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+
+        // First observation is recorded silently
+        let v1 = parse_program_data(&program_data_fixture(100, Some(authority), b"program v1")).unwrap();
+        assert!(monitor.record_observation(&program_id, &programdata, &v1).await.unwrap().is_none());
+
+        // Same deployment again is a no-op
+        assert!(monitor.record_observation(&program_id, &programdata, &v1).await.unwrap().is_none());
+        assert!(notices.try_recv().is_err());
+
+        // Upgrade with new bytes raises a warning
+        let v2 = parse_program_data(&program_data_fixture(200, Some(authority), b"program v2")).unwrap();
+        let notice = monitor.record_observation(&program_id, &programdata, &v2).await.unwrap().unwrap();
+        assert_eq!(notice.level, NoticeLevel::Warning);
+        assert!(notice.message.contains("slot 200"));
+        assert_eq!(notices.try_recv().unwrap(), notice);
+
+        let history = monitor.history(&program_id, 10).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].deployed_slot, 200);
+    }
+
+    #[tokio::test]
+    async fn test_authority_change_is_critical() {
+        let monitor = setup_monitor().await;
+        let mut notices = monitor.subscribe();
+        let program_id = Pubkey::new_unique();
+        let programdata = Pubkey::new_unique();
+
+        let v1 = parse_program_data(&program_data_fixture(100, Some(Pubkey::new_unique()), b"program")).unwrap();
+        monitor.record_observation(&program_id, &programdata, &v1).await.unwrap();
+
+        let revoked = parse_program_data(&program_data_fixture(100, None, b"program")).unwrap();
+        let notice = monitor.record_observation(&program_id, &programdata, &revoked).await.unwrap().unwrap();
+
+        assert_eq!(notice.level, NoticeLevel::Critical);
+        assert!(notice.message.contains("immutable"));
+        assert_eq!(notices.try_recv().unwrap().level, NoticeLevel::Critical);
+    }
+}
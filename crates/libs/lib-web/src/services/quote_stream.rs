@@ -0,0 +1,395 @@
+//! # Quote Streams
+//!
+//! Live Jupiter quotes for the swap panels of connected terminals.
+//!
+//! ```text
+//! WS client ─ quote_subscribe ─┐
+//! WS client ─ quote_subscribe ─┼─▶ QuoteStreamHub ─▶ one poller per QuoteSubscription
+//! WS client ─ quote_subscribe ─┘        │                  │ every QUOTE_STREAM_INTERVAL,
+//!                                       │                  │ if the budget allows
+//!                                       ◀── watch ─────────┘ QuoteSource::quote
+//! ```
+//!
+//! Identical subscriptions share one poller. Each subscriber holds a
+//! [`QuoteStreamHandle`]; dropping the last handle of a subscription stops
+//! its poller, so a closed panel or a dropped connection costs nothing.
+//!
+//! All pollers draw from one [`QuoteBudget`], so many open panels can't
+//! exhaust the Jupiter rate limit the rest of the backend relies on. A poller
+//! refused by the budget skips its turn and its subscribers keep the last
+//! quote, which they can tell is ageing by `quoted_at`.
+//!
+//! ## Configuration
+//!
+//! - `QUOTE_STREAM_BUDGET_PER_MIN` - Upstream quote requests per minute across
+//!   all streams (default [`DEFAULT_QUOTE_BUDGET_PER_MIN`])
+
+use async_trait::async_trait;
+use lib_solana::SolanaState;
+use shared::dto::market::{QuoteSubscription, QuoteUpdate};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+/// Time between quotes of one stream
+pub const QUOTE_STREAM_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Upstream quote requests per minute when `QUOTE_STREAM_BUDGET_PER_MIN` is unset
+pub const DEFAULT_QUOTE_BUDGET_PER_MIN: u32 = 60;
+
+/// Where quotes come from
+#[async_trait]
+pub trait QuoteSource: Send + Sync {
+    async fn quote(&self, subscription: &QuoteSubscription) -> anyhow::Result<QuoteUpdate>;
+}
+
+/// Quotes from Jupiter Aggregator
+pub struct JupiterQuoteSource {
+    solana: Arc<SolanaState>,
+}
+
+impl JupiterQuoteSource {
+    pub fn new(solana: Arc<SolanaState>) -> Self {
+        Self { solana }
+    }
+}
+
+#[async_trait]
+impl QuoteSource for JupiterQuoteSource {
+    async fn quote(&self, subscription: &QuoteSubscription) -> anyhow::Result<QuoteUpdate> {
+        let quote = self
+            .solana
+            .jupiter
+            .get_swap_quote(
+                &subscription.input_mint,
+                &subscription.output_mint,
+                subscription.amount,
+                subscription.slippage_bps,
+            )
+            .await?;
+        Ok(QuoteUpdate {
+            subscription: subscription.clone(),
+            in_amount: quote.in_amount,
+            out_amount: quote.out_amount,
+            price_impact_pct: quote.price_impact_pct,
+            quoted_at: chrono::Utc::now().timestamp_millis(),
+        })
+    }
+}
+
+/// Upstream requests allowed per minute, shared by every poller
+#[derive(Debug)]
+pub struct QuoteBudget {
+    per_minute: u32,
+    window_start: Option<Instant>,
+    used: u32,
+}
+
+impl QuoteBudget {
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            window_start: None,
+            used: 0,
+        }
+    }
+
+    /// Budget from `QUOTE_STREAM_BUDGET_PER_MIN`
+    pub fn from_env() -> Self {
+        let per_minute = std::env::var("QUOTE_STREAM_BUDGET_PER_MIN")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_QUOTE_BUDGET_PER_MIN);
+        Self::new(per_minute)
+    }
+
+    /// Take one request from the budget; `false` when this minute's is spent
+    pub fn try_take(&mut self, now: Instant) -> bool {
+        let expired = self
+            .window_start
+            .is_none_or(|start| now.saturating_duration_since(start) >= Duration::from_secs(60));
+        if expired {
+            self.window_start = Some(now);
+            self.used = 0;
+        }
+        if self.used >= self.per_minute {
+            return false;
+        }
+        self.used += 1;
+        true
+    }
+}
+
+/// One shared stream
+struct Stream {
+    subscribers: usize,
+    sender: Arc<watch::Sender<Option<Arc<QuoteUpdate>>>>,
+    poller: tokio::task::AbortHandle,
+}
+
+struct Inner {
+    source: Arc<dyn QuoteSource>,
+    budget: Arc<Mutex<QuoteBudget>>,
+    interval: Duration,
+    streams: Mutex<HashMap<QuoteSubscription, Stream>>,
+}
+
+/// Quote streams shared by all WebSocket connections
+///
+/// Held in the server state; cheap to clone.
+#[derive(Clone)]
+pub struct QuoteStreamHub {
+    inner: Arc<Inner>,
+}
+
+impl QuoteStreamHub {
+    pub fn new(source: Arc<dyn QuoteSource>, budget: QuoteBudget) -> Self {
+        Self::with_interval(source, budget, QUOTE_STREAM_INTERVAL)
+    }
+
+    /// Hub polling every `interval` instead of [`QUOTE_STREAM_INTERVAL`]
+    pub fn with_interval(source: Arc<dyn QuoteSource>, budget: QuoteBudget, interval: Duration) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                source,
+                budget: Arc::new(Mutex::new(budget)),
+                interval,
+                streams: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Join the stream for `subscription`, starting its poller if it has none
+    ///
+    /// A stream that already has a quote delivers it right away.
+    pub fn subscribe(&self, subscription: QuoteSubscription) -> QuoteStreamHandle {
+        let mut streams = self.inner.streams.lock().unwrap_or_else(|e| e.into_inner());
+        let stream = streams.entry(subscription.clone()).or_insert_with(|| {
+            let sender = Arc::new(watch::Sender::new(None));
+            let poller = tokio::spawn(poll(
+                Arc::clone(&self.inner.source),
+                Arc::clone(&self.inner.budget),
+                self.inner.interval,
+                subscription.clone(),
+                Arc::clone(&sender),
+            ))
+            .abort_handle();
+            info!(
+                "[QUOTES] Stream started for {} -> {} ({} @ {} bps)",
+                subscription.input_mint, subscription.output_mint, subscription.amount, subscription.slippage_bps
+            );
+            Stream {
+                subscribers: 0,
+                sender,
+                poller,
+            }
+        });
+        stream.subscribers += 1;
+
+        let mut receiver = stream.sender.subscribe();
+        if receiver.borrow().is_some() {
+            receiver.mark_changed();
+        }
+        QuoteStreamHandle {
+            hub: Arc::clone(&self.inner),
+            subscription,
+            receiver,
+        }
+    }
+
+    /// Subscribers of the stream for `subscription`; 0 when it has none
+    pub fn subscribers(&self, subscription: &QuoteSubscription) -> usize {
+        let streams = self.inner.streams.lock().unwrap_or_else(|e| e.into_inner());
+        streams.get(subscription).map_or(0, |stream| stream.subscribers)
+    }
+
+    /// Streams with at least one subscriber
+    pub fn active_streams(&self) -> usize {
+        self.inner.streams.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+impl Inner {
+    fn release(&self, subscription: &QuoteSubscription) {
+        let mut streams = self.streams.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(stream) = streams.get_mut(subscription) else {
+            return;
+        };
+        stream.subscribers -= 1;
+        if stream.subscribers == 0 {
+            stream.poller.abort();
+            streams.remove(subscription);
+            debug!("[QUOTES] Stream stopped for {} -> {}", subscription.input_mint, subscription.output_mint);
+        }
+    }
+}
+
+/// A subscriber's place in a quote stream; leaves it when dropped
+pub struct QuoteStreamHandle {
+    hub: Arc<Inner>,
+    subscription: QuoteSubscription,
+    receiver: watch::Receiver<Option<Arc<QuoteUpdate>>>,
+}
+
+impl QuoteStreamHandle {
+    pub fn subscription(&self) -> &QuoteSubscription {
+        &self.subscription
+    }
+
+    /// Wait for the next quote; `None` once the stream has ended
+    pub async fn next(&mut self) -> Option<Arc<QuoteUpdate>> {
+        loop {
+            self.receiver.changed().await.ok()?;
+            if let Some(update) = self.receiver.borrow_and_update().clone() {
+                return Some(update);
+            }
+        }
+    }
+}
+
+impl Drop for QuoteStreamHandle {
+    fn drop(&mut self) {
+        self.hub.release(&self.subscription);
+    }
+}
+
+/// Quote `subscription` every `interval` while the budget allows
+async fn poll(
+    source: Arc<dyn QuoteSource>,
+    budget: Arc<Mutex<QuoteBudget>>,
+    interval: Duration,
+    subscription: QuoteSubscription,
+    sender: Arc<watch::Sender<Option<Arc<QuoteUpdate>>>>,
+) {
+    let mut ticks = tokio::time::interval(interval);
+    ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticks.tick().await;
+        let allowed = budget.lock().unwrap_or_else(|e| e.into_inner()).try_take(Instant::now());
+        if !allowed {
+            debug!("[QUOTES] Budget spent, skipping {} -> {}", subscription.input_mint, subscription.output_mint);
+            continue;
+        }
+        match source.quote(&subscription).await {
+            Ok(update) => {
+                sender.send_replace(Some(Arc::new(update)));
+            }
+            Err(e) => warn!(
+                "[QUOTES] Quote for {} -> {} failed: {}",
+                subscription.input_mint, subscription.output_mint, e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts requests and quotes each subscription's amount back
+    #[derive(Default)]
+    struct CountingSource {
+        requests: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl QuoteSource for CountingSource {
+        async fn quote(&self, subscription: &QuoteSubscription) -> anyhow::Result<QuoteUpdate> {
+            let n = self.requests.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(QuoteUpdate {
+                subscription: subscription.clone(),
+                in_amount: subscription.amount.to_string(),
+                out_amount: (subscription.amount * 2).to_string(),
+                price_impact_pct: 0.1,
+                quoted_at: n as i64,
+            })
+        }
+    }
+
+    fn subscription(amount: u64) -> QuoteSubscription {
+        QuoteSubscription {
+            input_mint: "So11111111111111111111111111111111111111112".to_string(),
+            output_mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            amount,
+            slippage_bps: 50,
+        }
+    }
+
+    fn hub(source: Arc<CountingSource>, budget: u32) -> QuoteStreamHub {
+        QuoteStreamHub::with_interval(source, QuoteBudget::new(budget), Duration::from_millis(20))
+    }
+
+    #[tokio::test]
+    async fn test_identical_subscriptions_share_one_poller() {
+        let source = Arc::new(CountingSource::default());
+        let hub = hub(Arc::clone(&source), 1000);
+
+        let mut first = hub.subscribe(subscription(1_000));
+        let mut second = hub.subscribe(subscription(1_000));
+        let other = hub.subscribe(subscription(2_000));
+        assert_eq!(hub.subscribers(&subscription(1_000)), 2);
+        assert_eq!(hub.subscribers(&subscription(2_000)), 1);
+        assert_eq!(hub.active_streams(), 2);
+
+        let a = first.next().await.unwrap();
+        let b = second.next().await.unwrap();
+        assert_eq!(a.subscription, subscription(1_000));
+        assert_eq!(b.out_amount, "2000");
+
+        // A late subscriber gets the current quote without waiting for a poll
+        let mut late = hub.subscribe(subscription(1_000));
+        let current = tokio::time::timeout(Duration::from_millis(5), late.next()).await;
+        assert!(current.unwrap().is_some());
+        drop(other);
+    }
+
+    #[tokio::test]
+    async fn test_last_subscriber_leaving_stops_the_poller() {
+        let source = Arc::new(CountingSource::default());
+        let hub = hub(Arc::clone(&source), 1000);
+
+        let mut first = hub.subscribe(subscription(1_000));
+        let second = hub.subscribe(subscription(1_000));
+        first.next().await.unwrap();
+
+        drop(second);
+        assert_eq!(hub.subscribers(&subscription(1_000)), 1);
+        first.next().await.unwrap();
+
+        drop(first);
+        assert_eq!(hub.subscribers(&subscription(1_000)), 0);
+        assert_eq!(hub.active_streams(), 0);
+        let stopped_at = source.requests.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(source.requests.load(Ordering::SeqCst), stopped_at);
+
+        // Subscribing again starts a fresh stream
+        let mut again = hub.subscribe(subscription(1_000));
+        assert!(again.next().await.is_some());
+        assert_eq!(hub.active_streams(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_streams_share_the_budget() {
+        let source = Arc::new(CountingSource::default());
+        let hub = hub(Arc::clone(&source), 3);
+
+        let _a = hub.subscribe(subscription(1_000));
+        let _b = hub.subscribe(subscription(2_000));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(source.requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_budget_resets_every_minute() {
+        let start = Instant::now();
+        let mut budget = QuoteBudget::new(2);
+        assert!(budget.try_take(start));
+        assert!(budget.try_take(start + Duration::from_secs(1)));
+        assert!(!budget.try_take(start + Duration::from_secs(59)));
+        assert!(budget.try_take(start + Duration::from_secs(60)));
+    }
+}
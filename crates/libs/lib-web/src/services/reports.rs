@@ -0,0 +1,450 @@
+//! # Report Service
+//!
+//! Monthly account reports for `GET /api/reports/monthly`, rendered to PDF by
+//! [`crate::templates::monthly_report`].
+//!
+//! Everything comes from what the backend recorded, so a report for a past
+//! month reads the same whenever it is generated:
+//!
+//! - **Holdings** replay the user's confirmed swaps up to the month's start
+//!   and end with the position fill math (see `lib_core`'s position
+//!   repository), so realized PnL matches the Positions endpoint.
+//! - **Values** mark each token at the price of its last swap before that
+//!   moment (the swap's USD value over the amount of the token); tokens never
+//!   swapped with a known value stay unpriced.
+//! - **Fees** are the network fees stored with the month's confirmed swaps.
+//! - **Deposits and withdrawals** are the SOL balance changes the wallet
+//!   activity watcher stored, valued at SOL's mark at the time.
+
+use crate::services::portfolio::{ui_amount, PortfolioService, TokenMeta};
+use crate::templates::monthly_report;
+use chrono::{DateTime, Utc};
+use lib_core::model::store::models::{Position, Swap, SwapStatus, WalletTransfer};
+use lib_core::model::store::position_repository::Fill;
+use lib_core::model::store::swap_repository::SwapRepository;
+use lib_core::model::store::{UserRepository, WalletTransferRepository};
+use lib_core::{AppError, DbPool};
+use lib_solana::SolanaState;
+use shared::dto::reports::ReportMonth;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tracing::instrument;
+
+/// Wrapped SOL, the mint SOL is swapped as
+pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// An amount of one token as the report prints it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportAmount {
+    /// Whole tokens, or base units for mints missing from the token list
+    pub quantity: f64,
+    /// Symbol, or the shortened mint when the token list doesn't know it
+    pub symbol: String,
+}
+
+/// A holding at the start or end of the month
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportHolding {
+    pub amount: ReportAmount,
+    pub cost_basis_usd: f64,
+    /// Price per whole token (per base unit when the decimals are unknown)
+    pub price_usd: Option<f64>,
+    pub value_usd: Option<f64>,
+}
+
+/// Holdings at one moment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReportSnapshot {
+    /// Largest value first; unpriced holdings last, by symbol
+    pub holdings: Vec<ReportHolding>,
+    /// Sum over the priced holdings
+    pub value_usd: f64,
+}
+
+/// A swap submitted during the month
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportSwap {
+    pub created_at: DateTime<Utc>,
+    pub sold: ReportAmount,
+    pub bought: ReportAmount,
+    pub value_usd: Option<f64>,
+    pub status: SwapStatus,
+}
+
+/// A deposit (positive) or withdrawal (negative) of SOL
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportTransfer {
+    pub observed_at: DateTime<Utc>,
+    pub amount_sol: f64,
+    pub value_usd: Option<f64>,
+}
+
+/// Everything a monthly report shows
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthlyReport {
+    /// Username of the account holder
+    pub account: String,
+    pub month: ReportMonth,
+    pub generated_at: DateTime<Utc>,
+    /// Holdings when the month began
+    pub opening: ReportSnapshot,
+    /// Holdings when the month ended, or at `generated_at` for the current month
+    pub closing: ReportSnapshot,
+    /// PnL realized by swaps confirmed during the month
+    pub realized_pnl_usd: f64,
+    /// Network fees of the month's confirmed swaps
+    pub fees_sol: f64,
+    /// `fees_sol` at SOL's mark when each swap was made; swaps without a mark add nothing
+    pub fees_usd: f64,
+    /// Oldest first, whatever their status
+    pub swaps: Vec<ReportSwap>,
+    /// Oldest first
+    pub transfers: Vec<ReportTransfer>,
+    /// A swap in the ledger had no USD value or sold more than was tracked,
+    /// so cost basis and PnL don't cover everything
+    pub incomplete: bool,
+}
+
+impl MonthlyReport {
+    /// Whether the month was still running when the report was generated
+    pub fn is_partial(&self) -> bool {
+        self.generated_at < self.month.end()
+    }
+
+    /// File name offered for the download
+    pub fn filename(&self) -> String {
+        format!("xforce-report-{}.pdf", self.month)
+    }
+}
+
+/// Service for downloadable account reports
+pub struct ReportService {
+    db: DbPool,
+    portfolio: PortfolioService,
+}
+
+impl ReportService {
+    pub fn new(db: DbPool, solana: Arc<SolanaState>) -> Self {
+        Self { portfolio: PortfolioService::new(db.clone(), solana), db }
+    }
+
+    /// The user's report for `month`, as of now.
+    ///
+    /// # Returns
+    ///
+    /// * `Err(AppError::InvalidInput)` - The month hasn't started yet
+    /// * `Err(AppError::NotFound)` - No such user
+    #[instrument(skip(self))]
+    pub async fn monthly_report(&self, user_id: i64, month: ReportMonth) -> Result<MonthlyReport, AppError> {
+        let now = Utc::now();
+        if month.start() > now {
+            return Err(AppError::InvalidInput(format!("{} hasn't started yet", month.label())));
+        }
+        let end = month.end().min(now);
+
+        let user = UserRepository::find_by_id(&self.db, user_id)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        let ledger = SwapRepository::find_confirmed_before(&self.db, user_id, end).await.map_err(db_error)?;
+        let swaps = SwapRepository::find_created_between(&self.db, user_id, month.start(), end)
+            .await
+            .map_err(db_error)?;
+        let transfers = WalletTransferRepository::find_between(&self.db, user_id, month.start(), end)
+            .await
+            .map_err(db_error)?;
+        let tokens = self.portfolio.tokens().await;
+
+        Ok(build_report(user.username, month, now, &ledger, &swaps, &transfers, &tokens))
+    }
+
+    /// The user's report for `month` as a PDF, with its file name.
+    pub async fn monthly_report_pdf(&self, user_id: i64, month: ReportMonth) -> Result<(String, Vec<u8>), AppError> {
+        let report = self.monthly_report(user_id, month).await?;
+        let filename = report.filename();
+        // Laying out a long history takes a moment; keep it off the runtime's workers
+        let pdf = tokio::task::spawn_blocking(move || monthly_report::render_pdf(&report))
+            .await
+            .map_err(|e| AppError::Internal(format!("Report rendering panicked: {}", e)))??;
+        Ok((filename, pdf))
+    }
+}
+
+/// Assemble a report from the ledger.
+///
+/// `ledger` is every confirmed swap of the user that confirmed before the end
+/// of the report (the month's end, or `generated_at`), oldest first. `swaps`
+/// and `transfers` are those of the month, oldest first.
+pub fn build_report(
+    account: String,
+    month: ReportMonth,
+    generated_at: DateTime<Utc>,
+    ledger: &[Swap],
+    swaps: &[Swap],
+    transfers: &[WalletTransfer],
+    tokens: &HashMap<String, TokenMeta>,
+) -> MonthlyReport {
+    let start = month.start();
+    let mut positions: BTreeMap<&str, Position> = BTreeMap::new();
+    // USD per base unit, from the last swap with a value
+    let mut marks: HashMap<&str, f64> = HashMap::new();
+    let mut sol_marks: Vec<(DateTime<Utc>, f64)> = Vec::new();
+    let mut opening = None;
+    let mut realized_pnl_usd = 0.0;
+
+    for swap in ledger {
+        let confirmed_at = swap.confirmed_at.unwrap_or(swap.created_at);
+        if confirmed_at >= start && opening.is_none() {
+            opening = Some(snapshot(&positions, &marks, tokens));
+        }
+
+        let sell = Fill::sell(swap.input_amount, swap.value_usd);
+        let outcome = positions
+            .entry(swap.input_mint.as_str())
+            .or_insert_with(|| Position::empty(swap.user_id, &swap.input_mint))
+            .apply(&sell);
+        if confirmed_at >= start {
+            realized_pnl_usd += outcome.realized_pnl_usd;
+        }
+        positions
+            .entry(swap.output_mint.as_str())
+            .or_insert_with(|| Position::empty(swap.user_id, &swap.output_mint))
+            .apply(&Fill::buy(swap.output_amount, swap.value_usd));
+
+        let Some(value) = swap.value_usd.filter(|v| v.is_finite() && *v > 0.0) else {
+            continue;
+        };
+        for (mint, amount) in [(swap.input_mint.as_str(), swap.input_amount), (swap.output_mint.as_str(), swap.output_amount)] {
+            if amount > 0 {
+                marks.insert(mint, value / amount as f64);
+                if mint == SOL_MINT {
+                    sol_marks.push((confirmed_at, value / amount as f64));
+                }
+            }
+        }
+    }
+    let opening = opening.unwrap_or_else(|| snapshot(&positions, &marks, tokens));
+    let closing = snapshot(&positions, &marks, tokens);
+
+    let sol_at = |at: DateTime<Utc>| -> Option<f64> {
+        let i = sol_marks.partition_point(|(marked_at, _)| *marked_at <= at);
+        i.checked_sub(1).map(|i| sol_marks[i].1 * LAMPORTS_PER_SOL)
+    };
+
+    let mut fees_sol = 0.0;
+    let mut fees_usd = 0.0;
+    for swap in swaps.iter().filter(|s| s.status == SwapStatus::Confirmed) {
+        let Some(lamports) = swap.network_fee_lamports else {
+            continue;
+        };
+        let fee = lamports as f64 / LAMPORTS_PER_SOL;
+        fees_sol += fee;
+        fees_usd += sol_at(swap.created_at).map_or(0.0, |price| fee * price);
+    }
+
+    MonthlyReport {
+        account,
+        month,
+        generated_at,
+        opening,
+        closing,
+        realized_pnl_usd,
+        fees_sol,
+        fees_usd,
+        swaps: swaps
+            .iter()
+            .map(|swap| ReportSwap {
+                created_at: swap.created_at,
+                sold: report_amount(&swap.input_mint, swap.input_amount, tokens),
+                bought: report_amount(&swap.output_mint, swap.output_amount, tokens),
+                value_usd: swap.value_usd,
+                status: swap.status.clone(),
+            })
+            .collect(),
+        transfers: transfers
+            .iter()
+            .map(|transfer| {
+                let amount_sol = transfer.lamports as f64 / LAMPORTS_PER_SOL;
+                ReportTransfer {
+                    observed_at: transfer.observed_at,
+                    amount_sol,
+                    value_usd: sol_at(transfer.observed_at).map(|price| amount_sol * price),
+                }
+            })
+            .collect(),
+        incomplete: positions.values().any(|p| p.incomplete),
+    }
+}
+
+/// Held positions valued at their marks
+fn snapshot(
+    positions: &BTreeMap<&str, Position>,
+    marks: &HashMap<&str, f64>,
+    tokens: &HashMap<String, TokenMeta>,
+) -> ReportSnapshot {
+    let mut holdings: Vec<ReportHolding> = positions
+        .iter()
+        .filter(|(_, position)| position.quantity > 0)
+        .map(|(mint, position)| {
+            let amount = report_amount(mint, position.quantity, tokens);
+            let value_usd = marks.get(mint).map(|mark| mark * position.quantity as f64);
+            ReportHolding {
+                price_usd: value_usd.map(|value| value / amount.quantity),
+                value_usd,
+                cost_basis_usd: position.cost_basis_usd,
+                amount,
+            }
+        })
+        .collect();
+    holdings.sort_by(|a, b| {
+        let value = |h: &ReportHolding| h.value_usd.unwrap_or(f64::NEG_INFINITY);
+        value(b)
+            .total_cmp(&value(a))
+            .then_with(|| a.amount.symbol.cmp(&b.amount.symbol))
+    });
+    let value_usd = holdings.iter().filter_map(|h| h.value_usd).sum();
+    ReportSnapshot { holdings, value_usd }
+}
+
+fn report_amount(mint: &str, amount: i64, tokens: &HashMap<String, TokenMeta>) -> ReportAmount {
+    match tokens.get(mint) {
+        Some(token) => ReportAmount { quantity: ui_amount(amount, token.decimals), symbol: token.symbol.clone() },
+        None => ReportAmount { quantity: amount as f64, symbol: short_mint(mint) },
+    }
+}
+
+/// First and last four characters of a mint
+fn short_mint(mint: &str) -> String {
+    if mint.len() <= 10 || !mint.is_ascii() {
+        return mint.to_string();
+    }
+    format!("{}..{}", &mint[..4], &mint[mint.len() - 4..])
+}
+
+fn db_error(e: sqlx::Error) -> AppError {
+    AppError::Internal(format!("Report database error: {}", e))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    pub const BONK_MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    pub fn tokens() -> HashMap<String, TokenMeta> {
+        HashMap::from([
+            (SOL_MINT.to_string(), TokenMeta { symbol: "SOL".to_string(), decimals: 9, verified: true }),
+            (USDC_MINT.to_string(), TokenMeta { symbol: "USDC".to_string(), decimals: 6, verified: true }),
+            (BONK_MINT.to_string(), TokenMeta { symbol: "BONK".to_string(), decimals: 5, verified: true }),
+        ])
+    }
+
+    pub fn at(month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, month, day, hour, 0, 0).unwrap()
+    }
+
+    /// A swap selling `input` whole tokens for `output`, worth `value_usd`
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap(
+        id: i64,
+        created_at: DateTime<Utc>,
+        input_mint: &str,
+        input: f64,
+        output_mint: &str,
+        output: f64,
+        value_usd: Option<f64>,
+        status: SwapStatus,
+    ) -> Swap {
+        let base = |mint: &str, amount: f64| {
+            let decimals = tokens().get(mint).map_or(0, |t| t.decimals);
+            (amount * 10f64.powi(decimals as i32)).round() as i64
+        };
+        Swap {
+            id,
+            user_id: 1,
+            signature: format!("sig{}", id),
+            input_mint: input_mint.to_string(),
+            output_mint: output_mint.to_string(),
+            input_amount: base(input_mint, input),
+            output_amount: base(output_mint, output),
+            price_impact: None,
+            slippage_bps: Some(50),
+            confirmed_at: (status == SwapStatus::Confirmed).then_some(created_at),
+            status,
+            error_message: None,
+            created_at,
+            value_usd,
+            network_fee_lamports: Some(5_000),
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_report_replays_ledger_around_the_month() {
+        let march = ReportMonth::new(2025, 3).unwrap();
+        let confirmed = SwapStatus::Confirmed;
+        // February: buy 10 SOL at 100; March: sell 4 at 150, buy BONK
+        let ledger = vec![
+            swap(1, at(2, 10, 12), USDC_MINT, 1_000.0, SOL_MINT, 10.0, Some(1_000.0), confirmed.clone()),
+            swap(2, at(3, 5, 12), SOL_MINT, 4.0, USDC_MINT, 600.0, Some(600.0), confirmed.clone()),
+            swap(3, at(3, 20, 12), USDC_MINT, 100.0, BONK_MINT, 5_000_000.0, Some(100.0), confirmed.clone()),
+        ];
+        let failed = swap(4, at(3, 21, 12), SOL_MINT, 1.0, USDC_MINT, 150.0, Some(150.0), SwapStatus::Failed);
+        let swaps = vec![ledger[1].clone(), ledger[2].clone(), failed];
+        let transfers = vec![WalletTransfer {
+            id: 1,
+            user_id: 1,
+            wallet: "wallet".to_string(),
+            lamports: 2_000_000_000,
+            observed_at: at(3, 6, 0),
+        }];
+
+        let report = build_report("alice".to_string(), march, at(4, 2, 0), &ledger, &swaps, &transfers, &tokens());
+
+        // Opening: 10 SOL marked at the February price
+        assert_eq!(report.opening.holdings.len(), 1);
+        assert_eq!(report.opening.holdings[0].amount, ReportAmount { quantity: 10.0, symbol: "SOL".to_string() });
+        assert_close(report.opening.value_usd, 1_000.0);
+        // Closing: 6 SOL at 150, the 500 USDC not spent on BONK, and BONK at its purchase price
+        let symbols: Vec<&str> = report.closing.holdings.iter().map(|h| h.amount.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["SOL", "USDC", "BONK"]);
+        assert_close(report.closing.holdings[0].price_usd.unwrap(), 150.0);
+        assert_close(report.closing.value_usd, 900.0 + 500.0 + 100.0);
+        // Sold 4 SOL costing 400 for 600
+        assert_close(report.realized_pnl_usd, 200.0);
+        // Two confirmed swaps paid fees, the first at SOL's 150 mark
+        assert_close(report.fees_sol, 0.00001);
+        assert_close(report.fees_usd, 0.000005 * 150.0 + 0.000005 * 150.0);
+        assert_eq!(report.swaps.len(), 3);
+        assert_close(report.transfers[0].amount_sol, 2.0);
+        assert_close(report.transfers[0].value_usd.unwrap(), 300.0);
+        assert!(!report.is_partial());
+        assert_eq!(report.filename(), "xforce-report-2025-03.pdf");
+    }
+
+    #[test]
+    fn test_report_without_marks_or_token_list() {
+        let march = ReportMonth::new(2025, 3).unwrap();
+        let ledger = vec![swap(1, at(3, 2, 0), "UnknownMint1111111111111111111111111111111", 5.0, SOL_MINT, 1.0, None, SwapStatus::Confirmed)];
+
+        let report = build_report("bob".to_string(), march, at(3, 15, 0), &ledger, &ledger, &[], &HashMap::new());
+
+        // Nothing to mark with, and no token list for symbols
+        assert!(report.opening.holdings.is_empty());
+        let holding = &report.closing.holdings[0];
+        assert_eq!(holding.amount.symbol, "So11..1112");
+        assert_eq!(holding.amount.quantity, 1_000_000_000.0);
+        assert_eq!(holding.value_usd, None);
+        assert_eq!(report.closing.value_usd, 0.0);
+        assert_eq!(report.fees_usd, 0.0);
+        assert!(report.incomplete);
+        assert!(report.is_partial());
+    }
+}
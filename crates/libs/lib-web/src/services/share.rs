@@ -0,0 +1,265 @@
+//! # Share Link Service
+//!
+//! Publishes read-only snapshots of a chart setup or a portfolio allocation
+//! under an unguessable slug.
+//!
+//! ## Flow
+//!
+//! 1. `POST /api/share` - [`ShareService::create`] validates the payload,
+//!    copies the referenced candles out of the live aggregator (charts) or
+//!    anonymizes the allocation (portfolios), and stores it
+//! 2. `GET /share/{slug}` - [`ShareService::get`] returns the snapshot for the
+//!    public page until the link expires
+//!
+//! ## Slugs
+//!
+//! A slug is 16 bytes from the OS random number generator (a v4 UUID, so
+//! [`SLUG_ENTROPY_BITS`] of them are random) in URL-safe base64. Links are
+//! public, so the slug is the only thing keeping them private.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use lib_core::model::store::ShareLinkRepository;
+use lib_core::{AppError, DbPool};
+use lib_solana::candle_aggregator::Timeframe as AggregatorTimeframe;
+use lib_solana::PriceStreamServer;
+use shared::dto::market::{Timeframe, OHLC};
+use shared::dto::share::{ChartShare, CreateShareRequest, CreateShareResponse, SharePayload, MAX_SHARE_TTL_HOURS};
+use std::sync::Arc;
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+/// Random bits in a slug (a v4 UUID fixes 6 of its 128 bits)
+pub const SLUG_ENTROPY_BITS: u32 = 122;
+
+/// Most candles a chart snapshot may hold
+pub const MAX_SHARED_CANDLES: usize = 500;
+
+/// Most annotations or indicators a chart snapshot may hold
+const MAX_CHART_OVERLAYS: usize = 20;
+
+/// Most allocation rows a portfolio snapshot may hold
+const MAX_ALLOCATIONS: usize = 50;
+
+/// A new random slug
+pub fn new_slug() -> String {
+    URL_SAFE_NO_PAD.encode(Uuid::new_v4().as_bytes())
+}
+
+/// Expiry for a link created at `now`
+fn expiry(expires_in_hours: Option<i64>, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, AppError> {
+    match expires_in_hours {
+        None => Ok(None),
+        Some(hours) if (1..=MAX_SHARE_TTL_HOURS).contains(&hours) => Ok(Some(now + Duration::hours(hours))),
+        Some(_) => Err(AppError::InvalidInput(format!(
+            "Expiry must be between 1 and {} hours",
+            MAX_SHARE_TTL_HOURS
+        ))),
+    }
+}
+
+/// Candle aggregator timeframe for a chart timeframe
+fn aggregator_timeframe(timeframe: Timeframe) -> Result<AggregatorTimeframe, AppError> {
+    match timeframe {
+        Timeframe::OneMinute => Ok(AggregatorTimeframe::OneMinute),
+        Timeframe::FiveMinutes => Ok(AggregatorTimeframe::FiveMinutes),
+        Timeframe::FifteenMinutes => Ok(AggregatorTimeframe::FifteenMinutes),
+        Timeframe::OneHour => Ok(AggregatorTimeframe::OneHour),
+        Timeframe::FourHours => Ok(AggregatorTimeframe::FourHours),
+        Timeframe::OneDay => Ok(AggregatorTimeframe::OneDay),
+        Timeframe::OneWeek => Err(AppError::InvalidInput("Weekly charts can't be shared".to_string())),
+    }
+}
+
+/// Check a chart snapshot and fill in the candles of its range
+fn prepare_chart(mut chart: ChartShare, candles: Vec<OHLC>) -> Result<ChartShare, AppError> {
+    if chart.from > chart.to {
+        return Err(AppError::InvalidInput("Candle range starts after it ends".to_string()));
+    }
+    if chart.annotations.len() > MAX_CHART_OVERLAYS || chart.indicators.len() > MAX_CHART_OVERLAYS {
+        return Err(AppError::InvalidInput(format!(
+            "At most {} annotations and {} indicators can be shared",
+            MAX_CHART_OVERLAYS, MAX_CHART_OVERLAYS
+        )));
+    }
+    if chart.annotations.iter().any(|a| !a.price.is_finite()) {
+        return Err(AppError::InvalidInput("Annotation prices must be finite".to_string()));
+    }
+
+    chart.candles = candles
+        .into_iter()
+        .filter(|c| (chart.from..=chart.to).contains(&c.timestamp))
+        .take(MAX_SHARED_CANDLES)
+        .collect();
+    if chart.candles.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "No {} candles available for {} in that range",
+            chart.timeframe.label(),
+            chart.symbol
+        )));
+    }
+    Ok(chart)
+}
+
+/// Service for public share links
+pub struct ShareService {
+    db: DbPool,
+    price_stream: Arc<PriceStreamServer>,
+}
+
+impl ShareService {
+    pub fn new(db: DbPool, price_stream: Arc<PriceStreamServer>) -> Self {
+        Self { db, price_stream }
+    }
+
+    /// Publish a snapshot for `user_id`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CreateShareResponse)` - Slug and page path of the new link
+    /// * `Err(AppError::InvalidInput)` - Bad expiry, empty or oversized snapshot,
+    ///   or no candles for the chart's range
+    /// * `Err(AppError::Internal)` - Database error
+    pub async fn create(&self, user_id: i64, request: CreateShareRequest) -> Result<CreateShareResponse, AppError> {
+        let now = Utc::now();
+        let expires_at = expiry(request.expires_in_hours, now)?;
+
+        let payload = match request.payload {
+            SharePayload::Chart(chart) => {
+                let timeframe = aggregator_timeframe(chart.timeframe)?;
+                let candles = self
+                    .price_stream
+                    .candle_aggregator()
+                    .get_candles(&chart.symbol, timeframe, MAX_SHARED_CANDLES)
+                    .await
+                    .into_iter()
+                    .map(|c| OHLC {
+                        timestamp: c.timestamp as i64,
+                        open: c.open,
+                        high: c.high,
+                        low: c.low,
+                        close: c.close,
+                        volume: c.volume,
+                    })
+                    .collect();
+                SharePayload::Chart(prepare_chart(chart, candles)?)
+            }
+            SharePayload::Portfolio(portfolio) => {
+                if portfolio.allocations.is_empty() || portfolio.allocations.len() > MAX_ALLOCATIONS {
+                    return Err(AppError::InvalidInput(format!(
+                        "A portfolio snapshot needs 1 to {} holdings",
+                        MAX_ALLOCATIONS
+                    )));
+                }
+                SharePayload::Portfolio(portfolio.anonymized(request.anonymize))
+            }
+        };
+
+        let slug = new_slug();
+        let json = serde_json::to_string(&payload)
+            .map_err(|e| AppError::Internal(format!("Failed to encode share payload: {}", e)))?;
+        ShareLinkRepository::create(&self.db, &slug, user_id, payload.kind(), &json, expires_at).await?;
+
+        info!("[SHARE] User {} published a {} snapshot", user_id, payload.kind());
+        Ok(CreateShareResponse {
+            path: format!("/share/{}", slug),
+            slug,
+            expires_at: expires_at.map(|t| t.to_rfc3339()),
+        })
+    }
+
+    /// The snapshot behind `slug` and its expiry.
+    ///
+    /// # Returns
+    ///
+    /// * `Err(AppError::NotFound)` - Unknown or expired link
+    pub async fn get(&self, slug: &str) -> Result<(SharePayload, Option<DateTime<Utc>>), AppError> {
+        let link = ShareLinkRepository::find_active(&self.db, slug, Utc::now())
+            .await?
+            .ok_or_else(|| AppError::NotFound("This share link doesn't exist or has expired".to_string()))?;
+        let payload = serde_json::from_str(&link.payload)
+            .map_err(|e| AppError::Internal(format!("Stored share payload {} is invalid: {}", slug, e)))?;
+        Ok((payload, link.expires_at))
+    }
+
+    /// Delete expired links every `every`.
+    pub fn spawn_cleanup(self: &Arc<Self>, every: std::time::Duration) {
+        let service = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                match ShareLinkRepository::delete_expired(&service.db, Utc::now()).await {
+                    Ok(0) => {}
+                    Ok(removed) => debug!("[SHARE] Dropped {} expired links", removed),
+                    Err(e) => error!("[SHARE] Link cleanup failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::dto::share::ChartAnnotation;
+    use std::collections::HashSet;
+
+    fn candle(timestamp: i64) -> OHLC {
+        OHLC { timestamp, open: 1.0, high: 2.0, low: 0.5, close: 1.5, volume: 10.0 }
+    }
+
+    fn chart(from: i64, to: i64) -> ChartShare {
+        ChartShare {
+            symbol: "SOL".to_string(),
+            timeframe: Timeframe::OneHour,
+            indicators: vec!["SMA 20".to_string()],
+            annotations: vec![ChartAnnotation { label: "Entry".to_string(), price: 1.2 }],
+            from,
+            to,
+            candles: vec![candle(1)],
+        }
+    }
+
+    #[test]
+    fn test_slugs_are_unguessable() {
+        let slugs: HashSet<String> = (0..1000).map(|_| new_slug()).collect();
+        assert_eq!(slugs.len(), 1000);
+        for slug in &slugs {
+            // 128 bits in unpadded base64, safe in a URL path
+            assert_eq!(slug.len(), 22);
+            assert!(slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        }
+        assert!(SLUG_ENTROPY_BITS >= 120);
+    }
+
+    #[test]
+    fn test_expiry_bounds() {
+        let now = Utc::now();
+        assert_eq!(expiry(None, now).unwrap(), None);
+        assert_eq!(expiry(Some(24), now).unwrap(), Some(now + Duration::hours(24)));
+        assert!(expiry(Some(MAX_SHARE_TTL_HOURS), now).is_ok());
+        assert!(matches!(expiry(Some(0), now), Err(AppError::InvalidInput(_))));
+        assert!(matches!(expiry(Some(-5), now), Err(AppError::InvalidInput(_))));
+        assert!(matches!(expiry(Some(MAX_SHARE_TTL_HOURS + 1), now), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_prepare_chart_keeps_the_range() {
+        let prepared = prepare_chart(chart(3600, 7200), vec![candle(0), candle(3600), candle(7200), candle(10800)]).unwrap();
+        // Client-sent candles are replaced by the server's
+        let timestamps: Vec<i64> = prepared.candles.iter().map(|c| c.timestamp).collect();
+        assert_eq!(timestamps, vec![3600, 7200]);
+    }
+
+    #[test]
+    fn test_prepare_chart_rejects_bad_snapshots() {
+        assert!(prepare_chart(chart(7200, 3600), vec![candle(3600)]).is_err());
+        assert!(prepare_chart(chart(0, 10), vec![candle(3600)]).is_err());
+
+        let mut bad_price = chart(0, 3600);
+        bad_price.annotations[0].price = f64::NAN;
+        assert!(prepare_chart(bad_price, vec![candle(0)]).is_err());
+    }
+}
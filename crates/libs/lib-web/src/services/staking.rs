@@ -1,15 +1,23 @@
 //! # Staking Service
 //!
-//! Business logic for Solana staking operations.
+//! Business logic for native SOL staking.
 //!
 //! ## Overview
 //!
-//! This service provides staking-related functionality. Currently, this is a placeholder
-//! for future staking features.
+//! Lists a wallet's stake accounts and their rewards, and builds the unsigned
+//! transactions that stake, deactivate and withdraw. The terminal signs them
+//! locally and submits them through `/api/transactions/submit`, as it does
+//! swaps.
 //!
 //! ## Features
 //!
-//! - **Staking Info**: Get staking information for a wallet (placeholder)
+//! - **Staking Info**: Stake accounts the wallet can withdraw from, with
+//!   their activation state, plus the current epoch and staking minimums
+//! - **Rewards**: Inflation rewards of those accounts over recent epochs
+//! - **Delegate**: Create a stake account derived from the wallet (see
+//!   [`lib_solana::stake`]) and delegate it to a validator
+//! - **Deactivate / Withdraw**: Start the cooldown of a stake account, and
+//!   empty it back into the wallet once it's inactive
 //!
 //! ## Usage
 //!
@@ -22,32 +30,27 @@
 //! let solana = Arc::new(SolanaState::new(/* ... */).await?);
 //! let service = StakingService::new(solana);
 //!
-//! // Get staking info (placeholder)
 //! let info = service.get_staking_info("wallet_address").await?;
+//! println!("{} stake accounts in epoch {}", info.accounts.len(), info.epoch);
 //! # Ok(())
 //! # }
 //! ```
 
 use lib_core::AppError;
+use lib_solana::stake::{self, MAX_STAKE_SEEDS, STAKE_PROGRAM_ID, VOTE_PROGRAM_ID};
 use lib_solana::SolanaState;
+use shared::dto::staking::{
+    validate_stake_amount, DelegateStakeRequest, StakeAccountRequest, StakeReward, StakeTransactionResponse,
+    StakingInfo, StakingRewardsResponse, MAX_REWARD_EPOCHS, STAKE_ACCOUNT_LEN,
+};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
 use std::sync::Arc;
-use tracing::{warn, instrument};
-
-/// Staking information (placeholder).
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct StakingInfo {
-    /// Wallet address
-    pub address: String,
-    /// Staking positions (empty for now)
-    pub positions: Vec<String>,
-}
+use tracing::{debug, info, instrument};
 
 /// Service for staking operations.
-///
-/// This service provides business logic for staking operations.
-/// Currently, this is a placeholder for future staking features.
 pub struct StakingService {
-    #[allow(dead_code)] // Will be used when staking is implemented
     solana: Arc<SolanaState>,
 }
 
@@ -57,57 +60,292 @@ impl StakingService {
     /// # Arguments
     ///
     /// * `solana` - Shared Solana state
+    pub fn new(solana: Arc<SolanaState>) -> Self {
+        Self { solana }
+    }
+
+    /// Get the stake accounts of a wallet and the current staking parameters.
     ///
-    /// # Example
+    /// # Arguments
     ///
-    /// ```rust,no_run
-    /// use backend::services::staking::StakingService;
-    /// use backend::solana::SolanaState;
-    /// use std::sync::Arc;
+    /// * `address` - Solana wallet public key address (the withdraw authority)
     ///
-    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-    /// let solana = Arc::new(SolanaState::new(/* ... */).await?);
-    /// let service = StakingService::new(solana);
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub fn new(solana: Arc<SolanaState>) -> Self {
-        Self { solana }
+    /// # Returns
+    ///
+    /// * `Ok(StakingInfo)` - Accounts sorted by address; empty if the wallet has none
+    /// * `Err(AppError::InvalidInput)` - Invalid address
+    /// * `Err(AppError::Rpc)` - The RPC could not be queried
+    #[instrument(skip(self), fields(address = %address))]
+    pub async fn get_staking_info(&self, address: &str) -> Result<StakingInfo, AppError> {
+        let wallet = parse_pubkey(address, "wallet address")?;
+        let rpc = &self.solana.rpc;
+
+        let epoch_info = rpc.get_epoch_info().await.map_err(rpc_error)?;
+        let mut accounts: Vec<_> = rpc
+            .get_stake_accounts(&wallet)
+            .await
+            .map_err(rpc_error)?
+            .iter()
+            .map(|(address, lamports, parsed)| parsed.to_info(address, *lamports, epoch_info.epoch))
+            .collect();
+        accounts.sort_by(|a, b| a.address.cmp(&b.address));
+
+        Ok(StakingInfo {
+            address: wallet.to_string(),
+            epoch: epoch_info.epoch,
+            slot_index: epoch_info.slot_index,
+            slots_in_epoch: epoch_info.slots_in_epoch,
+            accounts,
+            minimum_delegation: rpc.get_stake_minimum_delegation().await.map_err(rpc_error)?,
+            rent_exempt_reserve: rpc
+                .get_minimum_balance_for_rent_exemption(STAKE_ACCOUNT_LEN)
+                .await
+                .map_err(rpc_error)?,
+        })
     }
 
-    /// Get staking information for a wallet (placeholder).
+    /// Get the inflation rewards of a wallet's stake accounts.
     ///
     /// # Arguments
     ///
     /// * `address` - Solana wallet public key address
+    /// * `epochs` - Completed epochs to look back over, at most [`MAX_REWARD_EPOCHS`]
     ///
     /// # Returns
     ///
-    /// * `Ok(StakingInfo)` - Staking information (currently empty)
-    /// * `Err(AppError::Internal)` - Error (currently not used)
+    /// * `Ok(StakingRewardsResponse)` - Newest epoch first; epochs an account
+    ///   earned nothing in are left out
+    /// * `Err(AppError::InvalidInput)` - Invalid address or epoch count
+    /// * `Err(AppError::Rpc)` - The RPC could not be queried
+    #[instrument(skip(self), fields(address = %address))]
+    pub async fn get_rewards(&self, address: &str, epochs: u64) -> Result<StakingRewardsResponse, AppError> {
+        let wallet = parse_pubkey(address, "wallet address")?;
+        if epochs == 0 || epochs > MAX_REWARD_EPOCHS {
+            return Err(AppError::InvalidInput(format!("epochs must be between 1 and {}", MAX_REWARD_EPOCHS)));
+        }
+        let rpc = &self.solana.rpc;
+
+        let stake_accounts: Vec<Pubkey> = rpc
+            .get_stake_accounts(&wallet)
+            .await
+            .map_err(rpc_error)?
+            .into_iter()
+            .map(|(address, _, _)| address)
+            .collect();
+        let mut rewards = Vec::new();
+        if stake_accounts.is_empty() {
+            return Ok(StakingRewardsResponse { address: wallet.to_string(), rewards });
+        }
+
+        let current_epoch = rpc.get_epoch_info().await.map_err(rpc_error)?.epoch;
+        for epoch in reward_epochs(current_epoch, epochs) {
+            let paid = rpc.get_inflation_rewards(&stake_accounts, epoch).await.map_err(rpc_error)?;
+            for (stake_account, reward) in stake_accounts.iter().zip(paid) {
+                let Some(reward) = reward.filter(|reward| reward.amount > 0) else {
+                    continue;
+                };
+                rewards.push(StakeReward {
+                    epoch: reward.epoch,
+                    stake_account: stake_account.to_string(),
+                    lamports: reward.amount,
+                    post_balance: reward.post_balance,
+                    commission: reward.commission,
+                });
+            }
+        }
+        debug!("[STAKING] {} rewards over {} epochs", rewards.len(), epochs);
+
+        Ok(StakingRewardsResponse { address: wallet.to_string(), rewards })
+    }
+
+    /// Build a transaction that creates a stake account and delegates it.
+    ///
+    /// The account is derived from the wallet with the first unused seed and
+    /// funded with the stake plus its rent-exempt reserve.
     ///
-    /// # Notes
+    /// # Errors
     ///
-    /// This is a placeholder method. Staking functionality will be implemented in the future.
-    #[instrument(skip(self), fields(address = %address))]
-    pub async fn get_staking_info(&self, address: &str) -> Result<StakingInfo, AppError> {
-        warn!("Staking service is not yet implemented");
-        Ok(StakingInfo {
-            address: address.to_string(),
-            positions: Vec::new(),
+    /// * `AppError::InvalidInput` - Bad address, an amount under the minimum
+    ///   delegation, or a vote account that isn't one
+    /// * `AppError::Account` - Every derived stake address is taken
+    /// * `AppError::Rpc` - The RPC could not be queried
+    #[instrument(skip(self, request), fields(wallet = %request.wallet))]
+    pub async fn build_delegate(&self, request: &DelegateStakeRequest) -> Result<StakeTransactionResponse, AppError> {
+        let wallet = parse_pubkey(&request.wallet, "wallet address")?;
+        let vote_account = parse_pubkey(&request.vote_account, "vote account")?;
+        let rpc = &self.solana.rpc;
+
+        let minimum_delegation = rpc.get_stake_minimum_delegation().await.map_err(rpc_error)?;
+        let rent_exempt_reserve = rpc
+            .get_minimum_balance_for_rent_exemption(STAKE_ACCOUNT_LEN)
+            .await
+            .map_err(rpc_error)?;
+        let lamports = validate_stake_amount(request.lamports, minimum_delegation, rent_exempt_reserve)
+            .map_err(AppError::InvalidInput)?;
+
+        let vote = rpc.get_multiple_accounts(&[vote_account]).await.map_err(rpc_error)?;
+        if !vote.first().and_then(Option::as_ref).is_some_and(|account| account.owner == VOTE_PROGRAM_ID) {
+            return Err(AppError::InvalidInput(format!("{} is not a validator vote account", vote_account)));
+        }
+
+        let seed = self.free_seed(&wallet).await?;
+        let stake_account = stake::stake_address(&wallet, &seed).map_err(|e| AppError::Internal(e.to_string()))?;
+        let instructions = stake::create_and_delegate_instructions(&wallet, &seed, lamports, &vote_account)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        info!("[STAKING] Delegating {} lamports from {} to {} via {}", lamports, wallet, vote_account, stake_account);
+
+        self.unsigned(&wallet, &instructions, stake_account, lamports).await
+    }
+
+    /// Build a transaction that deactivates a stake account.
+    ///
+    /// # Errors
+    ///
+    /// * `AppError::InvalidInput` - Bad address, or a wallet that isn't the
+    ///   account's stake authority
+    /// * `AppError::Account` - Not a stake account, or not active
+    /// * `AppError::Rpc` - The RPC could not be queried
+    #[instrument(skip(self, request), fields(wallet = %request.wallet, stake_account = %request.stake_account))]
+    pub async fn build_deactivate(&self, request: &StakeAccountRequest) -> Result<StakeTransactionResponse, AppError> {
+        let wallet = parse_pubkey(&request.wallet, "wallet address")?;
+        let (stake_account, lamports, parsed) = self.stake_account(&request.stake_account).await?;
+        if parsed.staker != wallet {
+            return Err(AppError::InvalidInput("The wallet is not the stake authority of this account".to_string()));
+        }
+        let epoch = self.solana.rpc.get_epoch_info().await.map_err(rpc_error)?.epoch;
+        if !parsed.to_info(&stake_account, lamports, epoch).can_deactivate() {
+            return Err(AppError::Account(format!(
+                "Stake account is {}, not active",
+                parsed.activation(epoch).as_str().to_lowercase()
+            )));
+        }
+
+        let instruction = stake::deactivate_instruction(&stake_account, &wallet);
+        self.unsigned(&wallet, &[instruction], stake_account, 0).await
+    }
+
+    /// Build a transaction that withdraws a stake account's whole balance to
+    /// the wallet, closing it.
+    ///
+    /// # Errors
+    ///
+    /// * `AppError::InvalidInput` - Bad address, or a wallet that isn't the
+    ///   account's withdraw authority
+    /// * `AppError::Account` - Not a stake account, still (de)activating or
+    ///   active, or under a lockup
+    /// * `AppError::Rpc` - The RPC could not be queried
+    #[instrument(skip(self, request), fields(wallet = %request.wallet, stake_account = %request.stake_account))]
+    pub async fn build_withdraw(&self, request: &StakeAccountRequest) -> Result<StakeTransactionResponse, AppError> {
+        let wallet = parse_pubkey(&request.wallet, "wallet address")?;
+        let (stake_account, lamports, parsed) = self.stake_account(&request.stake_account).await?;
+        if parsed.withdrawer != wallet {
+            return Err(AppError::InvalidInput("The wallet is not the withdraw authority of this account".to_string()));
+        }
+        let epoch = self.solana.rpc.get_epoch_info().await.map_err(rpc_error)?.epoch;
+        let state = parsed.activation(epoch);
+        if !parsed.to_info(&stake_account, lamports, epoch).can_withdraw() {
+            return Err(AppError::Account(format!(
+                "Stake account is {}; only inactive stake can be withdrawn",
+                state.as_str().to_lowercase()
+            )));
+        }
+        if parsed.is_locked(epoch, chrono::Utc::now().timestamp()) {
+            return Err(AppError::Account("Stake account is under a lockup".to_string()));
+        }
+
+        let instruction = stake::withdraw_instruction(&stake_account, &wallet, lamports);
+        self.unsigned(&wallet, &[instruction], stake_account, lamports).await
+    }
+
+    /// Fetch and parse a stake account
+    async fn stake_account(&self, address: &str) -> Result<(Pubkey, u64, stake::ParsedStakeAccount), AppError> {
+        let address = parse_pubkey(address, "stake account")?;
+        let account = self
+            .solana
+            .rpc
+            .get_multiple_accounts(&[address])
+            .await
+            .map_err(rpc_error)?
+            .into_iter()
+            .next()
+            .flatten()
+            .ok_or_else(|| AppError::Account(format!("Stake account {} not found", address)))?;
+        if account.owner != STAKE_PROGRAM_ID {
+            return Err(AppError::Account(format!("{} is not a stake account", address)));
+        }
+        let parsed = stake::parse_stake_account(&account.data).map_err(|e| AppError::Account(e.to_string()))?;
+        Ok((address, account.lamports, parsed))
+    }
+
+    /// First seed whose derived stake account doesn't exist yet
+    async fn free_seed(&self, wallet: &Pubkey) -> Result<String, AppError> {
+        let seeds: Vec<String> = (0..MAX_STAKE_SEEDS).map(stake::stake_seed).collect();
+        let addresses = seeds
+            .iter()
+            .map(|seed| stake::stake_address(wallet, seed))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let accounts = self.solana.rpc.get_multiple_accounts(&addresses).await.map_err(rpc_error)?;
+        seeds
+            .into_iter()
+            .zip(accounts)
+            .find_map(|(seed, account)| account.is_none().then_some(seed))
+            .ok_or_else(|| AppError::Account(format!("All {} derived stake accounts are in use", MAX_STAKE_SEEDS)))
+    }
+
+    /// Encode `instructions` as an unsigned transaction with a fresh blockhash
+    async fn unsigned(
+        &self,
+        wallet: &Pubkey,
+        instructions: &[Instruction],
+        stake_account: Pubkey,
+        lamports: u64,
+    ) -> Result<StakeTransactionResponse, AppError> {
+        let (blockhash, last_valid_block_height) =
+            self.solana.rpc.get_latest_blockhash_with_height().await.map_err(rpc_error)?;
+        let (transaction, version) = stake::encode_unsigned(wallet, instructions, blockhash)
+            .map_err(|e| AppError::Transaction(e.to_string()))?;
+        Ok(StakeTransactionResponse {
+            transaction,
+            version,
+            last_valid_block_height,
+            stake_account: stake_account.to_string(),
+            lamports,
         })
     }
 }
 
+/// Completed epochs to read rewards for, newest first
+///
+/// Rewards for an epoch are paid at the start of the next, so the current
+/// epoch has none yet.
+fn reward_epochs(current_epoch: u64, epochs: u64) -> impl Iterator<Item = u64> {
+    (current_epoch.saturating_sub(epochs)..current_epoch).rev()
+}
+
+fn parse_pubkey(value: &str, what: &str) -> Result<Pubkey, AppError> {
+    Pubkey::from_str(value.trim()).map_err(|e| AppError::InvalidInput(format!("Invalid {}: {}", what, e)))
+}
+
+fn rpc_error(e: anyhow::Error) -> AppError {
+    AppError::Rpc(e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
-    // Note: These tests would require mocking SolanaState
-    // For now, we'll add integration tests when staking is implemented
+    use super::*;
 
-    #[tokio::test]
-    #[ignore] // Requires SolanaState setup
-    async fn test_get_staking_info() {
-        // TODO: Add test with mock SolanaState when staking is implemented
+    #[test]
+    fn test_reward_epochs() {
+        assert_eq!(reward_epochs(700, 3).collect::<Vec<_>>(), vec![699, 698, 697]);
+        assert_eq!(reward_epochs(1, 5).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(reward_epochs(0, 5).count(), 0);
     }
-}
 
+    #[test]
+    fn test_parse_pubkey() {
+        assert!(parse_pubkey(" 8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL ", "wallet address").is_ok());
+        let err = parse_pubkey("not-a-key", "vote account").unwrap_err();
+        assert!(err.to_string().contains("vote account"));
+    }
+}
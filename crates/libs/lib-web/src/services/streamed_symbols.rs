@@ -0,0 +1,264 @@
+//! # Streamed Symbol Service
+//!
+//! Manages the symbol universe of the price stream.
+//!
+//! ## Source of Truth
+//!
+//! ```text
+//! Config::streamed_symbols ──(first run only)──► streamed_symbols table
+//!                                                      │
+//!                    admin add/remove ────────────────►│
+//!                                                      ▼
+//!                                   PriceStreamServer tracked symbols
+//!                                   CandleAggregator (series ended on remove)
+//! ```
+//!
+//! Every change is written to the database first and then applied to the
+//! running stream, so it takes effect on the next poll without a restart and
+//! survives one.
+
+use lib_core::model::store::StreamedSymbolRepository;
+use lib_core::{AppError, DbPool};
+use lib_solana::jupiter::JupiterClient;
+use lib_solana::pyth::PythClient;
+use lib_solana::PriceStreamServer;
+use shared::dto::market::StreamedSymbolInfo;
+use std::sync::Arc;
+use tracing::{info, instrument, warn};
+
+/// Longest symbol accepted
+const MAX_SYMBOL_LEN: usize = 16;
+
+/// Normalize and validate a symbol typed by an admin
+pub fn normalize_symbol(symbol: &str) -> Result<String, AppError> {
+    let symbol = symbol.trim().to_uppercase();
+    if symbol.is_empty() || symbol.len() > MAX_SYMBOL_LEN {
+        return Err(AppError::InvalidInput(format!(
+            "Symbol must be 1-{} characters",
+            MAX_SYMBOL_LEN
+        )));
+    }
+    if !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(AppError::InvalidInput("Symbol must be alphanumeric".to_string()));
+    }
+    Ok(symbol)
+}
+
+/// Service for the price stream's symbol universe
+pub struct StreamedSymbolService {
+    db: DbPool,
+    jupiter: Arc<JupiterClient>,
+    pyth: Arc<PythClient>,
+    price_stream: Arc<PriceStreamServer>,
+}
+
+impl StreamedSymbolService {
+    pub fn new(
+        db: DbPool,
+        jupiter: Arc<JupiterClient>,
+        pyth: Arc<PythClient>,
+        price_stream: Arc<PriceStreamServer>,
+    ) -> Self {
+        Self {
+            db,
+            jupiter,
+            pyth,
+            price_stream,
+        }
+    }
+
+    /// Seed the table from config on first run, then load it into the stream.
+    ///
+    /// Configured symbols whose mint cannot be resolved are skipped.
+    #[instrument(skip(self, seed))]
+    pub async fn init(&self, seed: &[String]) -> Result<(), AppError> {
+        let mut resolved = Vec::new();
+        for symbol in seed {
+            match self.jupiter.resolve_token(symbol).await {
+                Some((mint, _)) => resolved.push((symbol.to_uppercase(), mint)),
+                None => warn!("Configured streamed symbol {} has no known mint, skipping", symbol),
+            }
+        }
+
+        let seeded = StreamedSymbolRepository::seed_if_empty(&self.db, &resolved).await?;
+        if seeded > 0 {
+            info!("Seeded {} streamed symbols from config", seeded);
+        }
+
+        let symbols = self.list().await?;
+        let refs: Vec<&str> = symbols.iter().map(|s| s.symbol.as_str()).collect();
+        self.price_stream.set_tracked_symbols(&refs).await;
+        info!("Price stream universe loaded ({} symbols)", refs.len());
+        Ok(())
+    }
+
+    /// List streamed symbols
+    pub async fn list(&self) -> Result<Vec<StreamedSymbolInfo>, AppError> {
+        Ok(StreamedSymbolRepository::list(&self.db)
+            .await?
+            .into_iter()
+            .map(|s| StreamedSymbolInfo {
+                symbol: s.symbol,
+                mint: s.mint,
+                require_pyth: s.require_pyth,
+            })
+            .collect())
+    }
+
+    /// Add a symbol to the stream.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(StreamedSymbolInfo)` - Symbol added and streaming from the next poll
+    /// * `Err(AppError::InvalidInput)` - Bad symbol, already streamed, unknown
+    ///   mint, or no Pyth feed when `require_pyth` is set
+    #[instrument(skip(self))]
+    pub async fn add(
+        &self,
+        symbol: &str,
+        require_pyth: bool,
+        added_by: &str,
+    ) -> Result<StreamedSymbolInfo, AppError> {
+        let symbol = normalize_symbol(symbol)?;
+        if StreamedSymbolRepository::find(&self.db, &symbol).await?.is_some() {
+            return Err(AppError::InvalidInput(format!("{} is already streamed", symbol)));
+        }
+
+        let (mint, _) = self
+            .jupiter
+            .resolve_token(&symbol)
+            .await
+            .ok_or_else(|| AppError::InvalidInput(format!("{} does not resolve to a mint on Jupiter", symbol)))?;
+        if require_pyth && !self.pyth.has_price_feed(&symbol) {
+            return Err(AppError::InvalidInput(format!("{} has no Pyth price feed", symbol)));
+        }
+
+        let added = StreamedSymbolRepository::insert(&self.db, &symbol, &mint, require_pyth, Some(added_by)).await?;
+        self.price_stream.add_tokens(&[added.symbol.as_str()]).await;
+        info!("{} added {} to the price stream", added_by, added.symbol);
+
+        Ok(StreamedSymbolInfo {
+            symbol: added.symbol,
+            mint: added.mint,
+            require_pyth: added.require_pyth,
+        })
+    }
+
+    /// Remove a symbol from the stream and end its candle series.
+    ///
+    /// # Returns
+    ///
+    /// * `Err(AppError::NotFound)` - Symbol is not streamed
+    #[instrument(skip(self))]
+    pub async fn remove(&self, symbol: &str) -> Result<(), AppError> {
+        let symbol = normalize_symbol(symbol)?;
+        if !StreamedSymbolRepository::delete(&self.db, &symbol).await? {
+            return Err(AppError::NotFound(format!("{} is not streamed", symbol)));
+        }
+
+        self.price_stream.remove_tokens(&[symbol.as_str()]).await;
+        info!("Removed {} from the price stream", symbol);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lib_solana::candle_aggregator::Timeframe;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup() -> (StreamedSymbolService, Arc<PriceStreamServer>) {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS streamed_symbols (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                symbol TEXT NOT NULL UNIQUE,
+                mint TEXT NOT NULL,
+                require_pyth BOOLEAN NOT NULL DEFAULT 0,
+                added_by TEXT,
+                created_at TIMESTAMP NOT NULL
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create streamed_symbols table");
+
+        // Known symbols resolve from built-in mints, so no network is needed
+        let jupiter = Arc::new(JupiterClient::new().unwrap());
+        let pyth = Arc::new(PythClient::new().unwrap());
+        let price_stream = Arc::new(PriceStreamServer::new(Arc::clone(&jupiter), 500));
+        let service = StreamedSymbolService::new(pool, jupiter, pyth, Arc::clone(&price_stream));
+        (service, price_stream)
+    }
+
+    #[test]
+    fn test_normalize_symbol() {
+        assert_eq!(normalize_symbol(" jup ").unwrap(), "JUP");
+        assert!(normalize_symbol("").is_err());
+        assert!(normalize_symbol("SOL/USDC").is_err());
+        assert!(normalize_symbol(&"A".repeat(MAX_SYMBOL_LEN + 1)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_init_seeds_and_loads_stream() {
+        let (service, stream) = setup().await;
+        let seed = vec!["SOL".to_string(), "USDC".to_string(), "NOTAREALTOKEN".to_string()];
+
+        service.init(&seed).await.unwrap();
+        assert_eq!(stream.tracked_symbols().await, vec!["SOL", "USDC"]);
+
+        // Removed symbols stay removed across restarts
+        service.remove("USDC").await.unwrap();
+        service.init(&seed).await.unwrap();
+        assert_eq!(stream.tracked_symbols().await, vec!["SOL"]);
+    }
+
+    #[tokio::test]
+    async fn test_runtime_add_propagates_to_stream() {
+        let (service, stream) = setup().await;
+        service.init(&["SOL".to_string()]).await.unwrap();
+
+        let added = service.add("jup", false, "admin").await.unwrap();
+        assert_eq!(added.symbol, "JUP");
+        assert!(stream.tracked_symbols().await.contains(&"JUP".to_string()));
+        assert_eq!(service.list().await.unwrap().len(), 2);
+
+        // Duplicates, unknown mints and missing Pyth feeds are rejected
+        assert!(service.add("JUP", false, "admin").await.is_err());
+        assert!(service.add("NOTAREALTOKEN", false, "admin").await.is_err());
+        assert!(service.add("RAY", true, "admin").await.is_err());
+        assert!(service.add("USDC", true, "admin").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_runtime_remove_ends_candle_series() {
+        let (service, stream) = setup().await;
+        service.init(&["SOL".to_string(), "USDC".to_string()]).await.unwrap();
+
+        let candles = stream.candle_aggregator();
+        candles.add_price_update("SOL", 100.0, 1_000_000).await;
+
+        service.remove("sol").await.unwrap();
+        assert_eq!(stream.tracked_symbols().await, vec!["USDC"]);
+
+        // The open candle is kept as history, later updates are ignored
+        candles.add_price_update("SOL", 120.0, 1_000_010).await;
+        let history = candles.get_candles("SOL", Timeframe::OneMinute, 10).await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].close, 100.0);
+
+        assert!(matches!(service.remove("SOL").await, Err(AppError::NotFound(_))));
+
+        // Re-adding resumes the series
+        service.add("SOL", false, "admin").await.unwrap();
+        candles.add_price_update("SOL", 130.0, 1_000_020).await;
+        assert_eq!(candles.get_candles("SOL", Timeframe::OneMinute, 10).await.len(), 2);
+    }
+}
@@ -12,6 +12,11 @@
 //! - **Quote Fetching**: Get optimal swap quotes from Jupiter Aggregator
 //! - **Transaction Building**: Build unsigned swap transactions for client signing
 //! - **Route Information**: Extract routing information from quotes
+//! - **History**: Filtered, paginated swap history from the database, or
+//!   all of it as a resumable stream
+//! - **Status**: Confirmation outcomes reported by the terminal
+//! - **Latency**: Per-stage pipeline timings reported by the terminal, and
+//!   their percentiles across all users
 //! - **Error Handling**: Comprehensive error handling with user-friendly messages
 //!
 //! ## Usage
@@ -53,10 +58,53 @@
 //! SwapService → JupiterClient → Jupiter Aggregator API
 //! ```
 
-use lib_core::AppError;
+use lib_core::model::store::models::{Swap, SwapStatus};
+use lib_core::model::store::swap_repository::{SwapHistoryFilter, SwapRepository};
+use chrono::{DateTime, Utc};
+use futures_util::stream::{self, Stream};
+use lib_core::metrics::{time_upstream, Metrics};
+use lib_core::{AppError, DbPool};
+use lib_solana::contracts::transaction_builder::{decode_jupiter_transaction, message_version};
 use lib_solana::SolanaState;
+use shared::dto::history::{HistoryStreamLine, HISTORY_CURSOR_INTERVAL};
+use shared::dto::latency::{SwapLatency, SwapLatencySummary};
+use shared::dto::transactions::{TransactionStatus, TransactionStatusUpdate, TransactionVersion};
+use std::collections::VecDeque;
 use std::sync::Arc;
-use tracing::{debug, instrument};
+use tracing::{debug, error, instrument};
+
+/// Page size when the client doesn't ask for one
+pub const DEFAULT_HISTORY_LIMIT: i64 = 50;
+/// Largest page the history endpoint returns
+pub const MAX_HISTORY_LIMIT: i64 = 200;
+/// Swaps read per query while streaming history
+pub const HISTORY_STREAM_BATCH: i64 = 500;
+
+/// Swap history request, as received from the client.
+///
+/// Timestamps are Unix seconds; `from_ts` is inclusive and `to_ts` exclusive.
+#[derive(Debug, Clone, Default)]
+pub struct SwapHistoryParams {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub from_ts: Option<i64>,
+    pub to_ts: Option<i64>,
+    pub input_token: Option<String>,
+    pub output_token: Option<String>,
+    pub status: Option<String>,
+    /// Fragment of the transaction signature
+    pub signature: Option<String>,
+}
+
+/// One page of swap history.
+#[derive(Debug, Clone)]
+pub struct SwapHistoryPage {
+    pub swaps: Vec<Swap>,
+    /// Swaps matching the filters across all pages
+    pub total_count: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
 
 /// Route information extracted from Jupiter quote.
 #[derive(Debug, Clone, serde::Serialize)]
@@ -105,6 +153,8 @@ pub struct SwapTransactionResult {
     /// Base64-encoded unsigned transaction
     #[serde(rename = "transaction")]
     pub transaction: String,
+    /// Message format of `transaction`; routes touching many accounts are v0
+    pub version: TransactionVersion,
     /// Last valid block height
     #[serde(rename = "lastValidBlockHeight")]
     pub last_valid_block_height: u64,
@@ -132,6 +182,8 @@ pub struct SwapTransactionResult {
 /// swap routes.
 pub struct SwapService {
     solana: Arc<SolanaState>,
+    /// Registry Jupiter calls are recorded in, if any
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl SwapService {
@@ -155,7 +207,13 @@ impl SwapService {
     /// # }
     /// ```
     pub fn new(solana: Arc<SolanaState>) -> Self {
-        Self { solana }
+        Self { solana, metrics: None }
+    }
+
+    /// Record the duration and failures of Jupiter calls in `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
     }
 
     /// Get a swap quote from Jupiter Aggregator.
@@ -219,9 +277,7 @@ impl SwapService {
         );
 
         let quote = self
-            .solana
-            .jupiter
-            .get_swap_quote(input_mint, output_mint, amount, slippage_bps)
+            .quote(input_mint, output_mint, amount, slippage_bps)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to get swap quote: {}", e)))?;
 
@@ -295,6 +351,8 @@ impl SwapService {
     /// # Notes
     ///
     /// - Transaction is unsigned and must be signed by the client
+    /// - Jupiter builds v0 transactions loading accounts from lookup tables
+    ///   when a route needs them; `version` tells the client which it got
     /// - Transaction includes recent blockhash and expires after ~60 seconds
     /// - Client should submit the signed transaction via the transaction service
     #[instrument(skip(self), fields(input_mint = %input_mint, output_mint = %output_mint, amount, user_public_key = %user_public_key))]
@@ -310,22 +368,26 @@ impl SwapService {
 
         // Step 1: Get quote from Jupiter
         let quote = self
-            .solana
-            .jupiter
-            .get_swap_quote(input_mint, output_mint, amount, slippage_bps)
+            .quote(input_mint, output_mint, amount, slippage_bps)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to get swap quote: {}", e)))?;
 
         // Step 2: Get swap transaction from Jupiter
-        let swap_tx = self
-            .solana
-            .jupiter
-            .get_swap_transaction(&quote, user_public_key)
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to build swap transaction: {}", e)))?;
+        let swap_tx = time_upstream(
+            self.metrics.as_deref(),
+            "jupiter",
+            "swap_transaction",
+            self.solana.jupiter.get_swap_transaction(&quote, user_public_key),
+        )
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to build swap transaction: {}", e)))?;
+        let version = decode_jupiter_transaction(&swap_tx.swap_transaction)
+            .map(|transaction| message_version(&transaction.message))
+            .map_err(|e| AppError::Internal(e.to_string()))?;
 
         Ok(SwapTransactionResult {
             transaction: swap_tx.swap_transaction,
+            version,
             last_valid_block_height: swap_tx.last_valid_block_height,
             input_mint: quote.input_mint,
             output_mint: quote.output_mint,
@@ -334,12 +396,275 @@ impl SwapService {
             price_impact_pct: quote.price_impact_pct,
         })
     }
+
+    /// Ask Jupiter for a quote, timing the call
+    async fn quote(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> anyhow::Result<lib_solana::jupiter::QuoteResponse> {
+        let quote = self.solana.jupiter.get_swap_quote(input_mint, output_mint, amount, slippage_bps);
+        time_upstream(self.metrics.as_deref(), "jupiter", "quote", quote).await
+    }
+
+    /// Get a page of a user's swap history.
+    ///
+    /// Needs only the database, so it doesn't take `&self`. The limit is
+    /// clamped to `1..=MAX_HISTORY_LIMIT`; an offset past the last swap
+    /// returns an empty page with the real `total_count`.
+    ///
+    /// # Errors
+    ///
+    /// * `AppError::InvalidInput` - Negative offset, out-of-range timestamp,
+    ///   `from_ts` after `to_ts`, or unknown status
+    /// * `AppError::Internal` - Query failed
+    #[instrument(skip(pool, params))]
+    pub async fn get_swap_history(
+        pool: &DbPool,
+        user_id: i64,
+        params: SwapHistoryParams,
+    ) -> Result<SwapHistoryPage, AppError> {
+        let limit = params.limit.unwrap_or(DEFAULT_HISTORY_LIMIT).clamp(1, MAX_HISTORY_LIMIT);
+        let offset = params.offset.unwrap_or(0);
+        if offset < 0 {
+            return Err(AppError::InvalidInput("offset must not be negative".to_string()));
+        }
+
+        let filter = history_filter(params)?;
+        let (swaps, total_count) = SwapRepository::find_page(pool, user_id, &filter, limit, offset).await?;
+        debug!(returned = swaps.len(), total_count, "Loaded swap history page");
+
+        Ok(SwapHistoryPage { swaps, total_count, limit, offset })
+    }
+
+    /// Stream a user's whole swap history, newest first, as NDJSON lines.
+    ///
+    /// Swaps are read [`HISTORY_STREAM_BATCH`] at a time, so memory stays
+    /// bounded however long the history is. A cursor follows every
+    /// [`HISTORY_CURSOR_INTERVAL`] swaps and the last swap; passing one back
+    /// as `after` continues right behind it. A query failure ends the stream
+    /// without an `end` line, which clients treat as a dropped connection.
+    ///
+    /// # Errors
+    ///
+    /// * `AppError::InvalidInput` - Out-of-range timestamp, `from_ts` after
+    ///   `to_ts`, or unknown status
+    pub fn stream_swap_history(
+        pool: DbPool,
+        user_id: i64,
+        params: SwapHistoryParams,
+        after: Option<HistoryCursor>,
+    ) -> Result<impl Stream<Item = HistoryStreamLine<Swap>> + Send + 'static, AppError> {
+        let filter = history_filter(params)?;
+        let state = HistoryStream {
+            pool,
+            user_id,
+            filter,
+            after,
+            batch: VecDeque::new(),
+            since_cursor: 0,
+            exhausted: false,
+            ended: false,
+        };
+
+        Ok(stream::unfold(state, |mut state| async move {
+            loop {
+                if state.ended {
+                    return None;
+                }
+                let batch_done = state.batch.is_empty() && state.exhausted;
+                if state.since_cursor == HISTORY_CURSOR_INTERVAL || (batch_done && state.since_cursor > 0) {
+                    state.since_cursor = 0;
+                    if let Some(after) = state.after {
+                        return Some((HistoryStreamLine::Cursor(after.encode()), state));
+                    }
+                }
+                if let Some(swap) = state.batch.pop_front() {
+                    state.after = Some(HistoryCursor::after(&swap));
+                    state.since_cursor += 1;
+                    return Some((HistoryStreamLine::Record(swap), state));
+                }
+                if state.exhausted {
+                    state.ended = true;
+                    return Some((HistoryStreamLine::End {}, state));
+                }
+
+                let after = state.after.map(|cursor| (cursor.created_at, cursor.id));
+                match SwapRepository::find_after(&state.pool, state.user_id, &state.filter, after, HISTORY_STREAM_BATCH).await {
+                    Ok(swaps) => {
+                        state.exhausted = swaps.len() < HISTORY_STREAM_BATCH as usize;
+                        state.batch = swaps.into();
+                    }
+                    Err(e) => {
+                        error!(user_id = state.user_id, error = %e, "Swap history stream query failed");
+                        return None;
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Record the confirmation outcome the terminal observed for one of the
+    /// user's swaps.
+    ///
+    /// The table only knows pending, confirmed and failed: `processed` leaves
+    /// the row alone, `finalized` counts as confirmed and `expired` as failed.
+    /// A row that already has an outcome is not changed again.
+    ///
+    /// # Errors
+    ///
+    /// * `AppError::NotFound` - No swap with this signature for this user
+    /// * `AppError::Internal` - Query failed
+    #[instrument(skip(pool, update), fields(status = update.status.as_str()))]
+    pub async fn update_swap_status(
+        pool: &DbPool,
+        user_id: i64,
+        signature: &str,
+        update: &TransactionStatusUpdate,
+    ) -> Result<(), AppError> {
+        let swap = SwapRepository::find_by_signature(pool, signature)
+            .await?
+            .filter(|swap| swap.user_id == user_id)
+            .ok_or_else(|| AppError::NotFound(format!("Swap {} not found", signature)))?;
+        if swap.status != SwapStatus::Pending {
+            debug!(current = %swap.status, "Swap already has an outcome");
+            return Ok(());
+        }
+
+        let (status, error) = match update.status {
+            TransactionStatus::Pending | TransactionStatus::Processed => return Ok(()),
+            TransactionStatus::Confirmed | TransactionStatus::Finalized => (SwapStatus::Confirmed, None),
+            TransactionStatus::Failed => (
+                SwapStatus::Failed,
+                Some(update.error.as_deref().unwrap_or("Transaction failed")),
+            ),
+            TransactionStatus::Expired => (SwapStatus::Failed, Some("Transaction expired before it landed")),
+        };
+        debug!(%status, "Updating swap status");
+        SwapRepository::update_status(pool, signature, status, error).await?;
+        Ok(())
+    }
+
+    /// Keep the pipeline latency the terminal measured with one of the user's swaps.
+    ///
+    /// # Errors
+    ///
+    /// * `AppError::NotFound` - No swap with this signature for this user
+    /// * `AppError::Internal` - Query failed
+    #[instrument(skip(pool, latency), fields(total_ms = latency.total_ms()))]
+    pub async fn record_swap_latency(
+        pool: &DbPool,
+        user_id: i64,
+        signature: &str,
+        latency: &SwapLatency,
+    ) -> Result<(), AppError> {
+        SwapRepository::find_by_signature(pool, signature)
+            .await?
+            .filter(|swap| swap.user_id == user_id)
+            .ok_or_else(|| AppError::NotFound(format!("Swap {} not found", signature)))?;
+        let json = serde_json::to_string(latency).map_err(|e| AppError::Internal(e.to_string()))?;
+        SwapRepository::set_latency(pool, signature, &json).await?;
+        Ok(())
+    }
+
+    /// Per-stage latency percentiles of all users' swaps created at or after `since`.
+    ///
+    /// Swaps without a breakdown, or with one that no longer parses, are left out.
+    pub async fn latency_summary(pool: &DbPool, since: DateTime<Utc>) -> Result<SwapLatencySummary, AppError> {
+        let rows = SwapRepository::find_latencies_since(pool, since).await?;
+        let latencies: Vec<SwapLatency> = rows.iter().filter_map(|json| serde_json::from_str(json).ok()).collect();
+        if latencies.len() < rows.len() {
+            debug!(skipped = rows.len() - latencies.len(), "Skipped unreadable latency breakdowns");
+        }
+        Ok(SwapLatencySummary::of(&latencies))
+    }
+}
+
+/// Validate the filters of a history request
+fn history_filter(params: SwapHistoryParams) -> Result<SwapHistoryFilter, AppError> {
+    let timestamp = |ts: Option<i64>, name: &str| {
+        ts.map(|ts| {
+            chrono::DateTime::from_timestamp(ts, 0)
+                .ok_or_else(|| AppError::InvalidInput(format!("{} is out of range", name)))
+        })
+        .transpose()
+    };
+    let from = timestamp(params.from_ts, "from_ts")?;
+    let to = timestamp(params.to_ts, "to_ts")?;
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(AppError::InvalidInput("from_ts must not be after to_ts".to_string()));
+        }
+    }
+
+    let status = params
+        .status
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| s.trim().parse::<SwapStatus>().map_err(AppError::InvalidInput))
+        .transpose()?;
+    let token = |t: Option<String>| t.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+
+    Ok(SwapHistoryFilter {
+        from,
+        to,
+        input_mint: token(params.input_token),
+        output_mint: token(params.output_token),
+        status,
+        signature: token(params.signature),
+    })
+}
+
+/// Position in a swap history stream, just past the swap it was taken at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: i64,
+}
+
+impl HistoryCursor {
+    pub fn after(swap: &Swap) -> Self {
+        Self { created_at: swap.created_at, id: swap.id }
+    }
+
+    /// `{created_at in Unix nanoseconds}_{id}`; opaque to clients
+    pub fn encode(&self) -> String {
+        let nanos = self.created_at.timestamp_nanos_opt().unwrap_or(i64::MAX);
+        format!("{}_{}", nanos, self.id)
+    }
+
+    pub fn parse(cursor: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::InvalidInput(format!("Invalid history cursor: {}", cursor));
+        let (nanos, id) = cursor.split_once('_').ok_or_else(invalid)?;
+        let nanos = nanos.parse::<i64>().map_err(|_| invalid())?;
+        let id = id.parse::<i64>().map_err(|_| invalid())?;
+        Ok(Self { created_at: DateTime::from_timestamp_nanos(nanos), id })
+    }
+}
+
+/// Where [`SwapService::stream_swap_history`] is
+struct HistoryStream {
+    pool: DbPool,
+    user_id: i64,
+    filter: SwapHistoryFilter,
+    /// Last swap sent (or the cursor the client resumed from)
+    after: Option<HistoryCursor>,
+    batch: VecDeque<Swap>,
+    /// Swaps sent since the last cursor
+    since_cursor: usize,
+    /// The last batch came back short, nothing follows `batch`
+    exhausted: bool,
+    ended: bool,
 }
 
 #[cfg(test)]
 mod tests {
     // Note: These tests would require mocking SolanaState
     // For now, we'll add integration tests in the handlers
+    use super::*;
+    use shared::dto::latency::LatencyStage;
+    use sqlx::sqlite::SqlitePoolOptions;
 
     #[tokio::test]
     #[ignore] // Requires SolanaState setup
@@ -352,5 +677,267 @@ mod tests {
     async fn test_execute_swap() {
         // TODO: Add test with mock SolanaState
     }
-}
 
+    async fn history_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::query(
+            r#"
+            CREATE TABLE swaps (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                signature TEXT UNIQUE NOT NULL,
+                input_mint TEXT NOT NULL,
+                output_mint TEXT NOT NULL,
+                input_amount INTEGER NOT NULL,
+                output_amount INTEGER NOT NULL,
+                price_impact REAL,
+                slippage_bps INTEGER,
+                status TEXT NOT NULL,
+                error_message TEXT,
+                created_at TIMESTAMP NOT NULL,
+                confirmed_at TIMESTAMP,
+                value_usd REAL,
+                network_fee_lamports INTEGER,
+                latency TEXT
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // One swap per hour from 1_700_000_000, alternating SOL->USDC / USDC->SOL
+        for i in 0..6i64 {
+            let (input, output) = if i % 2 == 0 { ("SOL", "USDC") } else { ("USDC", "SOL") };
+            sqlx::query(
+                "INSERT INTO swaps (user_id, signature, input_mint, output_mint, input_amount, output_amount, status, created_at)
+                 VALUES (1, ?, ?, ?, 1, 1, ?, ?)",
+            )
+            .bind(format!("sig{}", i))
+            .bind(input)
+            .bind(output)
+            .bind(if i == 4 { "failed" } else { "confirmed" })
+            .bind(chrono::DateTime::from_timestamp(1_700_000_000 + i * 3600, 0).unwrap())
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_history_combined_filters() {
+        let pool = history_db().await;
+        let params = SwapHistoryParams {
+            from_ts: Some(1_700_000_000 + 3600),
+            to_ts: Some(1_700_000_000 + 6 * 3600),
+            input_token: Some(" SOL ".to_string()),
+            output_token: Some("USDC".to_string()),
+            status: Some("Confirmed".to_string()),
+            ..Default::default()
+        };
+
+        let page = SwapService::get_swap_history(&pool, 1, params).await.unwrap();
+
+        // sig0 is before from_ts, sig4 failed
+        assert_eq!(page.total_count, 1);
+        assert_eq!(page.swaps[0].signature, "sig2");
+        assert_eq!((page.limit, page.offset), (DEFAULT_HISTORY_LIMIT, 0));
+    }
+
+    #[tokio::test]
+    async fn test_history_paging_and_out_of_range_offset() {
+        let pool = history_db().await;
+
+        let page = SwapService::get_swap_history(&pool, 1, SwapHistoryParams {
+            limit: Some(4),
+            offset: Some(4),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert_eq!(page.total_count, 6);
+        assert_eq!(page.swaps.iter().map(|s| s.signature.as_str()).collect::<Vec<_>>(), vec!["sig1", "sig0"]);
+
+        let past_end = SwapService::get_swap_history(&pool, 1, SwapHistoryParams {
+            offset: Some(1_000),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert!(past_end.swaps.is_empty());
+        assert_eq!(past_end.total_count, 6);
+
+        let clamped = SwapService::get_swap_history(&pool, 1, SwapHistoryParams {
+            limit: Some(10_000),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert_eq!(clamped.limit, MAX_HISTORY_LIMIT);
+    }
+
+    #[tokio::test]
+    async fn test_update_swap_status() {
+        let pool = history_db().await;
+        sqlx::query(
+            "INSERT INTO swaps (user_id, signature, input_mint, output_mint, input_amount, output_amount, status, created_at)
+             VALUES (1, 'pending1', 'SOL', 'USDC', 1, 1, 'pending', ?), (1, 'pending2', 'SOL', 'USDC', 1, 1, 'pending', ?)",
+        )
+        .bind(chrono::Utc::now())
+        .bind(chrono::Utc::now())
+        .execute(&pool)
+        .await
+        .unwrap();
+        let update = |status, error: Option<&str>| TransactionStatusUpdate { status, error: error.map(str::to_string) };
+        let status_of = |signature: &'static str| {
+            let pool = pool.clone();
+            async move {
+                let swap = SwapRepository::find_by_signature(&pool, signature).await.unwrap().unwrap();
+                (swap.status, swap.error_message)
+            }
+        };
+
+        // Intermediate steps leave the row pending
+        SwapService::update_swap_status(&pool, 1, "pending1", &update(TransactionStatus::Processed, None)).await.unwrap();
+        assert_eq!(status_of("pending1").await.0, SwapStatus::Pending);
+
+        SwapService::update_swap_status(&pool, 1, "pending1", &update(TransactionStatus::Finalized, None)).await.unwrap();
+        assert_eq!(status_of("pending1").await, (SwapStatus::Confirmed, None));
+
+        SwapService::update_swap_status(&pool, 1, "pending2", &update(TransactionStatus::Expired, None)).await.unwrap();
+        let (status, error) = status_of("pending2").await;
+        assert_eq!(status, SwapStatus::Failed);
+        assert!(error.unwrap().contains("expired"));
+
+        // An outcome is not overwritten
+        SwapService::update_swap_status(&pool, 1, "pending1", &update(TransactionStatus::Failed, Some("late"))).await.unwrap();
+        assert_eq!(status_of("pending1").await.0, SwapStatus::Confirmed);
+
+        // Someone else's swap, or none at all
+        for (user_id, signature) in [(2, "pending1"), (1, "missing")] {
+            let result = SwapService::update_swap_status(&pool, user_id, signature, &update(TransactionStatus::Finalized, None)).await;
+            assert!(matches!(result, Err(AppError::NotFound(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_swap_latency_recorded_and_summarized() {
+        let pool = history_db().await;
+        let latency = |build_ms, acknowledgement_ms| SwapLatency { build_ms, acknowledgement_ms, ..Default::default() };
+
+        SwapService::record_swap_latency(&pool, 1, "sig1", &latency(800, 1_500)).await.unwrap();
+        SwapService::record_swap_latency(&pool, 1, "sig3", &latency(400, 700)).await.unwrap();
+        SwapService::record_swap_latency(&pool, 1, "sig5", &latency(600, 900)).await.unwrap();
+        for (user_id, signature) in [(2, "sig2"), (1, "missing")] {
+            let result = SwapService::record_swap_latency(&pool, user_id, signature, &latency(1, 1)).await;
+            assert!(matches!(result, Err(AppError::NotFound(_))));
+        }
+        SwapRepository::set_latency(&pool, "sig4", "not json").await.unwrap();
+
+        let summary = SwapService::latency_summary(&pool, DateTime::from_timestamp(1_700_000_000, 0).unwrap()).await.unwrap();
+        assert_eq!(summary.swaps, 3);
+        assert_eq!(summary.stages[&LatencyStage::Build].p50_ms, 600);
+        assert_eq!(summary.stages[&LatencyStage::Acknowledgement].p95_ms, 1_500);
+        assert_eq!(summary.total.max_ms, 2_300);
+
+        // sig1 is before the window
+        let since = DateTime::from_timestamp(1_700_000_000 + 2 * 3600, 0).unwrap();
+        assert_eq!(SwapService::latency_summary(&pool, since).await.unwrap().swaps, 2);
+    }
+
+    #[tokio::test]
+    async fn test_history_rejects_invalid_params() {
+        let pool = history_db().await;
+        for params in [
+            SwapHistoryParams { offset: Some(-1), ..Default::default() },
+            SwapHistoryParams { from_ts: Some(i64::MAX), ..Default::default() },
+            SwapHistoryParams { from_ts: Some(20), to_ts: Some(10), ..Default::default() },
+            SwapHistoryParams { status: Some("settled".to_string()), ..Default::default() },
+        ] {
+            let result = SwapService::get_swap_history(&pool, 1, params.clone()).await;
+            assert!(matches!(result, Err(AppError::InvalidInput(_))), "{:?} should be rejected", params);
+        }
+    }
+
+    /// Lines of a history stream, with records reduced to their signatures
+    async fn stream_lines(pool: &DbPool, after: Option<HistoryCursor>) -> Vec<HistoryStreamLine<String>> {
+        use futures_util::StreamExt;
+        SwapService::stream_swap_history(pool.clone(), 1, SwapHistoryParams::default(), after)
+            .unwrap()
+            .map(|line| match line {
+                HistoryStreamLine::Record(swap) => HistoryStreamLine::Record(swap.signature),
+                HistoryStreamLine::Cursor(cursor) => HistoryStreamLine::Cursor(cursor),
+                HistoryStreamLine::End {} => HistoryStreamLine::End {},
+            })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_history_stream_has_cursors_and_resumes() {
+        let pool = history_db().await;
+        // 1,000 more swaps after the 6 seeded ones, spanning two query batches
+        for i in 6..1_006i64 {
+            sqlx::query(
+                "INSERT INTO swaps (user_id, signature, input_mint, output_mint, input_amount, output_amount, status, created_at)
+                 VALUES (1, ?, 'SOL', 'USDC', 1, 1, 'confirmed', ?)",
+            )
+            .bind(format!("sig{}", i))
+            .bind(chrono::DateTime::from_timestamp(1_700_000_000 + i * 3600, 0).unwrap())
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let lines = stream_lines(&pool, None).await;
+        let records: Vec<&String> = lines
+            .iter()
+            .filter_map(|line| match line {
+                HistoryStreamLine::Record(signature) => Some(signature),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(records.len(), 1_006);
+        assert_eq!((records[0].as_str(), records[1_005].as_str()), ("sig1005", "sig0"));
+        // A cursor after every 100 records and after the last, then the end
+        let cursors: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| matches!(line, HistoryStreamLine::Cursor(_)))
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(cursors.len(), 11);
+        assert_eq!(cursors[0], HISTORY_CURSOR_INTERVAL);
+        assert_eq!(cursors[10], lines.len() - 2);
+        assert_eq!(lines.last(), Some(&HistoryStreamLine::End {}));
+
+        // Resuming from the sixth cursor continues right after its record
+        let HistoryStreamLine::Cursor(cursor) = &lines[cursors[5]] else { unreachable!() };
+        let resumed = stream_lines(&pool, Some(HistoryCursor::parse(cursor).unwrap())).await;
+        assert_eq!(resumed[0], HistoryStreamLine::Record(records[600].clone()));
+        assert_eq!(&resumed[..], &lines[cursors[5] + 1..]);
+
+        // Resuming from the final cursor only ends the stream
+        let HistoryStreamLine::Cursor(cursor) = &lines[cursors[10]] else { unreachable!() };
+        let resumed = stream_lines(&pool, Some(HistoryCursor::parse(cursor).unwrap())).await;
+        assert_eq!(resumed, vec![HistoryStreamLine::End {}]);
+    }
+
+    #[test]
+    fn test_history_cursor_round_trip() {
+        let cursor = HistoryCursor {
+            created_at: chrono::DateTime::from_timestamp(1_739_872_800, 123_456_789).unwrap(),
+            id: 42,
+        };
+        assert_eq!(cursor.encode(), "1739872800123456789_42");
+        assert_eq!(HistoryCursor::parse(&cursor.encode()).unwrap(), cursor);
+        for bad in ["", "42", "x_42", "1739872800123456789_", "1_2_3"] {
+            assert!(HistoryCursor::parse(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+}
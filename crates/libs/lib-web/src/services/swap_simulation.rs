@@ -0,0 +1,355 @@
+//! # Swap Simulation Service
+//!
+//! Preflight check for swap transactions: runs the unsigned transaction
+//! through `simulateTransaction` and reports what it would do to the wallet.
+//!
+//! ## Balance changes
+//!
+//! The RPC only returns post-simulation state, so the current state of the
+//! same accounts is read first and the two are diffed:
+//!
+//! ```text
+//! watched = fee payer + static account keys + payer's ATAs for both mints (both token programs)
+//! pre     = getMultipleAccounts(watched)
+//! post    = simulateTransaction(tx, accounts: watched)
+//! SOL     = post.lamports - pre.lamports of the payer
+//! tokens  = post.amount - pre.amount, summed per mint, over token accounts the payer owns
+//! ```
+//!
+//! Accounts that don't exist on one side count as empty, which covers token
+//! accounts the swap creates or closes (e.g. temporary wrapped SOL).
+
+use lib_core::AppError;
+use lib_solana::client::SimulatedAccount;
+use lib_solana::spl_token::{parse_mint, parse_token_account, SplTokenClient};
+use lib_solana::SolanaState;
+use shared::dto::simulation::{SimulateSwapRequest, SimulateSwapResponse, TokenBalanceChange, SIMULATION_LOG_TAIL};
+use shared::dto::tokens::TokenProgram;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, instrument, warn};
+
+/// Base fee per signature, used when the RPC can't price the message
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Most accounts `getMultipleAccounts` accepts per call
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Base token account length; Token-2022 accounts may be longer
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Jupiter's `SlippageToleranceExceeded` error code
+const JUPITER_SLIPPAGE_EXCEEDED: &str = "0x1771";
+
+/// Whether an account is a token account owned by `wallet`, and if so its mint and amount
+fn wallet_token_amount(account: &SimulatedAccount, wallet: &Pubkey) -> Option<(Pubkey, u64)> {
+    TokenProgram::from_program_id(&account.owner.to_string())?;
+    if account.data.len() < TOKEN_ACCOUNT_LEN {
+        return None;
+    }
+    let parsed = parse_token_account(&account.data).ok()?;
+    (parsed.owner == *wallet).then_some((parsed.mint, parsed.amount))
+}
+
+/// SOL change of `wallet` and token changes per mint between two snapshots of
+/// the same accounts. Mints whose balance doesn't change are left out.
+pub fn wallet_balance_changes(
+    wallet: &Pubkey,
+    addresses: &[Pubkey],
+    pre: &[Option<SimulatedAccount>],
+    post: &[Option<SimulatedAccount>],
+) -> (i64, BTreeMap<Pubkey, i64>) {
+    let mut sol_change = 0i64;
+    let mut token_changes: BTreeMap<Pubkey, i64> = BTreeMap::new();
+
+    for (index, address) in addresses.iter().enumerate() {
+        let before = pre.get(index).and_then(Option::as_ref);
+        let after = post.get(index).and_then(Option::as_ref);
+
+        if address == wallet {
+            let lamports = |account: Option<&SimulatedAccount>| account.map(|a| a.lamports as i64).unwrap_or(0);
+            sol_change = lamports(after) - lamports(before);
+            continue;
+        }
+
+        let before = before.and_then(|account| wallet_token_amount(account, wallet));
+        let after = after.and_then(|account| wallet_token_amount(account, wallet));
+        let Some(mint) = before.or(after).map(|(mint, _)| mint) else {
+            continue;
+        };
+        let amount = |side: Option<(Pubkey, u64)>| side.map(|(_, amount)| amount as i64).unwrap_or(0);
+        *token_changes.entry(mint).or_default() += amount(after) - amount(before);
+    }
+
+    token_changes.retain(|_, change| *change != 0);
+    (sol_change, token_changes)
+}
+
+/// Last `count` log lines
+pub fn log_tail(logs: &[String], count: usize) -> Vec<String> {
+    logs[logs.len().saturating_sub(count)..].to_vec()
+}
+
+/// Readable reason for a failed simulation, from the RPC error and the program logs
+pub fn describe_failure(err: &str, logs: &[String]) -> String {
+    let logs_contain = |needle: &str| logs.iter().any(|log| log.contains(needle));
+
+    if err.contains("AccountNotFound") {
+        "The wallet has no SOL to pay fees with".to_string()
+    } else if err.contains("InsufficientFundsForRent") {
+        "Not enough SOL left to keep the accounts rent-exempt".to_string()
+    } else if err.contains("InsufficientFundsForFee") || logs_contain("insufficient lamports") {
+        "Insufficient SOL for this swap and its fees".to_string()
+    } else if logs_contain("insufficient funds") {
+        "Insufficient token balance for this swap".to_string()
+    } else if logs_contain("SlippageToleranceExceeded") || logs_contain(JUPITER_SLIPPAGE_EXCEEDED) {
+        "Slippage tolerance exceeded; the price moved, request a new quote or raise slippage".to_string()
+    } else if err.contains("BlockhashNotFound") {
+        "The transaction expired; request a new quote".to_string()
+    } else {
+        format!("Simulation failed: {}", err)
+    }
+}
+
+/// Service for swap preflight simulation.
+pub struct SwapSimulationService {
+    solana: Arc<SolanaState>,
+}
+
+impl SwapSimulationService {
+    /// Create a new simulation service.
+    ///
+    /// # Arguments
+    ///
+    /// * `solana` - Shared Solana state; its RPC client runs the simulation
+    pub fn new(solana: Arc<SolanaState>) -> Self {
+        Self { solana }
+    }
+
+    /// Simulate an unsigned swap transaction for the wallet that will sign it.
+    ///
+    /// # Errors
+    ///
+    /// * `AppError::InvalidInput` - Bad public key or mint, undecodable transaction,
+    ///   or a transaction whose fee payer is not `user_public_key`
+    /// * `AppError::Rpc` - The RPC could not be queried
+    ///
+    /// A transaction that would fail is not an error: the response has
+    /// `success: false` with the reason and the log tail.
+    #[instrument(skip(self, request), fields(user_public_key = %request.user_public_key))]
+    pub async fn simulate_swap(&self, request: &SimulateSwapRequest) -> Result<SimulateSwapResponse, AppError> {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let wallet = parse_pubkey(&request.user_public_key, "user public key")?;
+        parse_pubkey(&request.input_mint, "input mint")?;
+        parse_pubkey(&request.output_mint, "output mint")?;
+
+        let bytes = general_purpose::STANDARD
+            .decode(request.transaction.trim())
+            .map_err(|e| AppError::InvalidInput(format!("Transaction is not valid base64: {}", e)))?;
+        let transaction: VersionedTransaction = bincode::deserialize(&bytes)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid transaction: {}", e)))?;
+        let account_keys = transaction.message.static_account_keys();
+        if account_keys.first() != Some(&wallet) {
+            return Err(AppError::InvalidInput("Transaction fee payer is not the given wallet".to_string()));
+        }
+
+        // Payer first, then the transaction's keys, then ATAs it may create
+        let mut addresses = vec![wallet];
+        let mut watch = |address: Pubkey| {
+            if !addresses.contains(&address) {
+                addresses.push(address);
+            }
+        };
+        account_keys.iter().copied().for_each(&mut watch);
+        let wallet_address = wallet.to_string();
+        for mint in [&request.input_mint, &request.output_mint] {
+            for program in [TokenProgram::Spl, TokenProgram::Token2022] {
+                let ata = SplTokenClient::get_associated_token_address_for_program(
+                    &wallet_address,
+                    mint.trim(),
+                    program,
+                )
+                .map_err(|e| AppError::InvalidInput(e.to_string()))?;
+                watch(parse_pubkey(&ata, "token account")?);
+            }
+        }
+
+        let pre = self.fetch_accounts(&addresses).await?;
+        let simulation = self
+            .solana
+            .rpc
+            .simulate_transaction(request.transaction.trim(), &addresses)
+            .await
+            .map_err(|e| AppError::Rpc(e.to_string()))?;
+
+        let network_fee_lamports = match self.solana.rpc.get_fee_for_message(&transaction.message).await {
+            Ok(fee) => fee,
+            Err(e) => {
+                debug!(error = %e, "Falling back to the base fee");
+                LAMPORTS_PER_SIGNATURE * transaction.message.header().num_required_signatures as u64
+            }
+        };
+
+        let (sol_change_lamports, changes) =
+            wallet_balance_changes(&wallet, &addresses, &pre, &simulation.accounts);
+        let token_changes = self.with_decimals(changes).await?;
+
+        let error = simulation.err.as_deref().map(|err| describe_failure(err, &simulation.logs));
+        if let Some(error) = &error {
+            warn!(error = %error, "Swap simulation failed");
+        }
+
+        Ok(SimulateSwapResponse {
+            success: error.is_none(),
+            error,
+            compute_units: simulation.units_consumed,
+            network_fee_lamports,
+            sol_change_lamports,
+            token_changes,
+            log_tail: log_tail(&simulation.logs, SIMULATION_LOG_TAIL),
+            logs: simulation.logs,
+        })
+    }
+
+    async fn fetch_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<SimulatedAccount>>, AppError> {
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let fetched = self
+                .solana
+                .rpc
+                .get_multiple_accounts(chunk)
+                .await
+                .map_err(|e| AppError::Rpc(e.to_string()))?;
+            accounts.extend(fetched.into_iter().map(|account| {
+                account.map(|account| SimulatedAccount {
+                    lamports: account.lamports,
+                    owner: account.owner,
+                    data: account.data,
+                })
+            }));
+        }
+        Ok(accounts)
+    }
+
+    /// Attach mint decimals to raw token changes
+    async fn with_decimals(&self, changes: BTreeMap<Pubkey, i64>) -> Result<Vec<TokenBalanceChange>, AppError> {
+        let mints: Vec<Pubkey> = changes.keys().copied().collect();
+        let accounts = self.fetch_accounts(&mints).await?;
+
+        Ok(changes
+            .into_iter()
+            .zip(accounts)
+            .map(|((mint, change), account)| TokenBalanceChange {
+                mint: mint.to_string(),
+                decimals: account
+                    .and_then(|account| parse_mint(&account.data).ok())
+                    .map(|parsed| parsed.decimals)
+                    .unwrap_or(0),
+                change,
+            })
+            .collect())
+    }
+}
+
+fn parse_pubkey(value: &str, what: &str) -> Result<Pubkey, AppError> {
+    Pubkey::from_str(value.trim()).map_err(|e| AppError::InvalidInput(format!("Invalid {}: {}", what, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN_PROGRAM: Pubkey = Pubkey::from_str_const(shared::dto::tokens::SPL_TOKEN_PROGRAM_ID);
+
+    fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Option<SimulatedAccount> {
+        let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+        data[0..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data[108] = 1;
+        Some(SimulatedAccount { lamports: 2_039_280, owner: TOKEN_PROGRAM, data })
+    }
+
+    fn system_account(lamports: u64) -> Option<SimulatedAccount> {
+        Some(SimulatedAccount { lamports, owner: Pubkey::default(), data: Vec::new() })
+    }
+
+    #[test]
+    fn test_wallet_balance_changes() {
+        let wallet = Pubkey::new_unique();
+        let someone_else = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+        let bonk = Pubkey::new_unique();
+        let addresses: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let addresses = [vec![wallet], addresses].concat();
+
+        let pre = vec![
+            system_account(3_000_000_000),
+            token_account(usdc, wallet, 0),
+            None, // BONK account the swap creates
+            token_account(usdc, someone_else, 900), // pool vault, not ours
+            token_account(bonk, wallet, 10),        // untouched
+            system_account(1),
+        ];
+        let post = vec![
+            system_account(1_997_995_000),
+            token_account(usdc, wallet, 145_000_000),
+            token_account(bonk, wallet, 5),
+            token_account(usdc, someone_else, 100),
+            token_account(bonk, wallet, 10),
+            system_account(1),
+        ];
+
+        let (sol, tokens) = wallet_balance_changes(&wallet, &addresses, &pre, &post);
+        assert_eq!(sol, -1_002_005_000);
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[&usdc], 145_000_000);
+        assert_eq!(tokens[&bonk], 5);
+    }
+
+    #[test]
+    fn test_closed_token_account_counts_as_emptied() {
+        let wallet = Pubkey::new_unique();
+        let wsol = Pubkey::new_unique();
+        let addresses = [wallet, Pubkey::new_unique()];
+
+        let pre = vec![system_account(10), token_account(wsol, wallet, 700)];
+        let post = vec![system_account(10), None];
+
+        let (_, tokens) = wallet_balance_changes(&wallet, &addresses, &pre, &post);
+        assert_eq!(tokens[&wsol], -700);
+    }
+
+    #[test]
+    fn test_describe_failure() {
+        let logs = |lines: &[&str]| lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+
+        // Captured from a Jupiter route with too tight a slippage
+        let slippage = logs(&[
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program log: AnchorError occurred. Error Code: SlippageToleranceExceeded. Error Number: 6001.",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: custom program error: 0x1771",
+        ]);
+        assert!(describe_failure(r#"{"InstructionError":[3,{"Custom":6001}]}"#, &slippage).starts_with("Slippage"));
+
+        let no_sol = logs(&["Transfer: insufficient lamports 1000, need 1000000000"]);
+        assert!(describe_failure(r#"{"InstructionError":[1,{"Custom":1}]}"#, &no_sol).starts_with("Insufficient SOL"));
+
+        let no_tokens = logs(&["Program log: Error: insufficient funds"]);
+        assert!(describe_failure(r#"{"InstructionError":[2,{"Custom":1}]}"#, &no_tokens).starts_with("Insufficient token"));
+
+        assert!(describe_failure("\"AccountNotFound\"", &[]).contains("no SOL"));
+        assert_eq!(describe_failure("\"Weird\"", &[]), "Simulation failed: \"Weird\"");
+    }
+
+    #[test]
+    fn test_log_tail() {
+        let logs: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+        assert_eq!(log_tail(&logs, 3), vec!["17", "18", "19"]);
+        assert_eq!(log_tail(&logs[..2], 3).len(), 2);
+    }
+}
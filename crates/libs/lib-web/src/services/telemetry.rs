@@ -0,0 +1,150 @@
+//! # Usage Telemetry Service
+//!
+//! Stores the anonymous daily usage counts terminals upload when their user
+//! opted in, and deletes them again on request.
+//!
+//! Neither operation takes a login. A report names only a random install id,
+//! and the terminal holding that id is the one that may delete its counts.
+//! Reports are checked against the closed event taxonomy of
+//! [`shared::dto::telemetry`] before anything is stored.
+
+use lib_core::model::store::UsageCountRepository;
+use lib_core::{AppError, DbPool};
+use shared::dto::telemetry::{is_install_id, UsageReport};
+use tracing::{debug, error, info};
+
+/// Service for anonymous usage counts
+pub struct TelemetryService {
+    db: DbPool,
+}
+
+impl TelemetryService {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+
+    /// Store an uploaded report.
+    ///
+    /// Days uploaded before are replaced, so a terminal that retries an
+    /// upload doesn't count its day twice.
+    ///
+    /// # Errors
+    ///
+    /// * `AppError::InvalidInput` - Malformed install id, too many days, days
+    ///   out of order or implausible counts
+    /// * `AppError::Internal` - Database error
+    pub async fn record(&self, report: &UsageReport) -> Result<(), AppError> {
+        report.validate().map_err(AppError::InvalidInput)?;
+
+        let counts: Vec<_> = report
+            .days
+            .iter()
+            .flat_map(|day| day.counts.iter().map(move |(event, &count)| (day.day, event.key(), count as i64)))
+            .collect();
+        let rows: Vec<_> = counts.iter().map(|(day, event, count)| (*day, event.as_str(), *count)).collect();
+
+        UsageCountRepository::record(&self.db, &report.install_id, &report.app_version, &rows)
+            .await
+            .map_err(|e| {
+                error!("[TELEMETRY] Failed to store usage report: {}", e);
+                AppError::Internal("Failed to store usage report".to_string())
+            })?;
+        debug!(days = report.days.len(), counts = rows.len(), "[TELEMETRY] Usage report stored");
+        Ok(())
+    }
+
+    /// Delete everything `install_id` uploaded.
+    ///
+    /// Deleting an install with no counts succeeds, so the terminal can reset
+    /// its id whether or not it ever uploaded.
+    ///
+    /// # Errors
+    ///
+    /// * `AppError::InvalidInput` - `install_id` isn't an install id
+    /// * `AppError::Internal` - Database error
+    pub async fn delete(&self, install_id: &str) -> Result<u64, AppError> {
+        if !is_install_id(install_id) {
+            return Err(AppError::InvalidInput("Not an install id".to_string()));
+        }
+        let deleted = UsageCountRepository::delete_install(&self.db, install_id).await.map_err(|e| {
+            error!("[TELEMETRY] Failed to delete usage counts: {}", e);
+            AppError::Internal("Failed to delete usage counts".to_string())
+        })?;
+        info!(deleted, "[TELEMETRY] Usage counts of an install deleted");
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use shared::dto::telemetry::{UsageDay, UsageEvent, UsageFeature, UsageScreen};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::collections::BTreeMap;
+
+    const INSTALL: &str = "0f8fad5b-d9cb-469f-a165-70867728950e";
+
+    async fn setup() -> (TelemetryService, DbPool) {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::query(
+            r#"
+            CREATE TABLE usage_counts (
+                install_id TEXT NOT NULL,
+                day DATE NOT NULL,
+                event TEXT NOT NULL,
+                count BIGINT NOT NULL,
+                app_version TEXT NOT NULL,
+                received_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (install_id, day, event)
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create usage_counts table");
+        (TelemetryService::new(pool.clone()), pool)
+    }
+
+    fn report(install_id: &str, swaps: u64) -> UsageReport {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let counts = BTreeMap::from([
+            (UsageEvent::ScreenView(UsageScreen::Wallet), 4),
+            (UsageEvent::Feature(UsageFeature::Swap), swaps),
+        ]);
+        UsageReport {
+            install_id: install_id.to_string(),
+            app_version: "0.1.0".to_string(),
+            days: vec![UsageDay { day, counts }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_report_is_stored_and_deleted() {
+        let (service, pool) = setup().await;
+        let day = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        service.record(&report(INSTALL, 1)).await.unwrap();
+        service.record(&report(INSTALL, 2)).await.unwrap();
+        assert_eq!(UsageCountRepository::total(&pool, day, "feature.swap").await.unwrap(), 2);
+        assert_eq!(UsageCountRepository::total(&pool, day, "screen.wallet").await.unwrap(), 4);
+
+        assert_eq!(service.delete(INSTALL).await.unwrap(), 2);
+        assert_eq!(UsageCountRepository::total(&pool, day, "feature.swap").await.unwrap(), 0);
+        assert_eq!(service.delete(INSTALL).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_reports_are_refused() {
+        let (service, _pool) = setup().await;
+        assert!(matches!(service.record(&report("my-laptop", 1)).await, Err(AppError::InvalidInput(_))));
+        assert!(matches!(
+            service.record(&report(INSTALL, shared::dto::telemetry::MAX_DAILY_COUNT + 1)).await,
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(service.delete("../users").await, Err(AppError::InvalidInput(_))));
+    }
+}
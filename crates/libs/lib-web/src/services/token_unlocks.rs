@@ -0,0 +1,402 @@
+//! # Token Unlock Service
+//!
+//! Scheduled token unlocks: the public listing, admin edits, and a background
+//! job importing a public unlocks dataset.
+//!
+//! ## Dataset Format
+//!
+//! The importer fetches `TOKEN_UNLOCKS_DATASET_URL` and expects a JSON array
+//! of unlocks, or an object with an `unlocks` array:
+//!
+//! ```json
+//! [
+//!   {
+//!     "mint": "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN",
+//!     "symbol": "JUP",
+//!     "unlock_date": "2025-03-01T00:00:00Z",
+//!     "amount": 53472222,
+//!     "circulating_supply": 1350000000,
+//!     "allocation": "Team"
+//!   }
+//! ]
+//! ```
+//!
+//! `unlock_date` may also be Unix seconds. Entries that don't parse or fail
+//! validation are skipped and counted; the rest are imported.
+//!
+//! ## Configuration
+//!
+//! - `TOKEN_UNLOCKS_DATASET_URL` - Dataset to import; no import job without it
+//! - `TOKEN_UNLOCKS_IMPORT_INTERVAL_SECS` - Time between imports (default: 21600)
+
+use lib_core::model::store::models::{TokenUnlock, TokenUnlockForCreate};
+use lib_core::model::store::token_unlock_repository::UnlockImportCounts;
+use lib_core::model::store::TokenUnlockRepository;
+use lib_core::{AppError, DbPool};
+use chrono::{DateTime, DurationRound, TimeDelta, TimeZone, Utc};
+use serde::Deserialize;
+use shared::dto::unlocks::{relative_size_pct, TokenUnlockInfo, TokenUnlockRequest, TokenUnlocksResponse};
+use std::sync::Arc;
+use tokio::time::Duration;
+use tracing::{info, instrument, warn};
+
+/// Default time between dataset imports in seconds
+const DEFAULT_IMPORT_INTERVAL_SECS: u64 = 6 * 3600;
+
+/// Upcoming window when the client doesn't ask for one, in days
+pub const DEFAULT_UNLOCK_WINDOW_DAYS: i64 = 90;
+
+/// Longest upcoming window served, in days
+pub const MAX_UNLOCK_WINDOW_DAYS: i64 = 365;
+
+/// Timeout for fetching the dataset
+const DATASET_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// One entry of the unlocks dataset
+#[derive(Debug, Deserialize)]
+struct DatasetEntry {
+    mint: String,
+    symbol: String,
+    unlock_date: DatasetTime,
+    amount: f64,
+    #[serde(default)]
+    circulating_supply: Option<f64>,
+    #[serde(default)]
+    allocation: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DatasetTime {
+    Secs(i64),
+    Text(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Dataset {
+    List(Vec<serde_json::Value>),
+    Wrapped { unlocks: Vec<serde_json::Value> },
+}
+
+/// Unlocks parsed from a dataset, and how many entries were dropped
+#[derive(Debug, Default)]
+pub struct ParsedDataset {
+    pub unlocks: Vec<TokenUnlockForCreate>,
+    pub invalid: usize,
+}
+
+/// Parse the unlocks dataset.
+///
+/// Fails only when the document itself isn't a list of unlocks; bad entries
+/// are counted in [`ParsedDataset::invalid`].
+pub fn parse_dataset(body: &str) -> Result<ParsedDataset, AppError> {
+    let entries = match serde_json::from_str::<Dataset>(body)
+        .map_err(|e| AppError::Decoding(format!("Unlocks dataset is not a list of unlocks: {}", e)))?
+    {
+        Dataset::List(entries) | Dataset::Wrapped { unlocks: entries } => entries,
+    };
+
+    let mut parsed = ParsedDataset::default();
+    for entry in entries {
+        let unlock = serde_json::from_value::<DatasetEntry>(entry)
+            .map_err(|e| e.to_string())
+            .and_then(|entry| {
+                let unlock_at = match entry.unlock_date {
+                    DatasetTime::Secs(secs) => Utc.timestamp_opt(secs, 0).single(),
+                    DatasetTime::Text(text) => DateTime::parse_from_rfc3339(&text).ok().map(|t| t.with_timezone(&Utc)),
+                }
+                .ok_or("invalid unlock_date")?;
+                validate(entry.mint, entry.symbol, unlock_at, entry.amount, entry.circulating_supply, entry.allocation)
+            });
+        match unlock {
+            Ok(unlock) => parsed.unlocks.push(unlock),
+            Err(reason) => {
+                warn!(%reason, "Skipping unlocks dataset entry");
+                parsed.invalid += 1;
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// Check and normalize an unlock from the dataset or an admin.
+///
+/// Times are truncated to whole seconds, since rows are matched on them.
+fn validate(
+    mint: String,
+    symbol: String,
+    unlock_at: DateTime<Utc>,
+    amount: f64,
+    circulating_supply: Option<f64>,
+    allocation: Option<String>,
+) -> Result<TokenUnlockForCreate, String> {
+    let mint = mint.trim().to_string();
+    if mint.is_empty() {
+        return Err("mint is required".to_string());
+    }
+    let symbol = symbol.trim().to_uppercase();
+    if symbol.is_empty() || !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("invalid symbol {:?}", symbol));
+    }
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err(format!("amount must be positive, got {}", amount));
+    }
+    if circulating_supply.is_some_and(|s| !s.is_finite() || s <= 0.0) {
+        return Err("circulating_supply must be positive".to_string());
+    }
+    Ok(TokenUnlockForCreate {
+        mint,
+        symbol,
+        unlock_at: unlock_at.duration_trunc(TimeDelta::seconds(1)).unwrap_or(unlock_at),
+        amount,
+        circulating_supply,
+        allocation: allocation.map(|a| a.trim().to_string()).unwrap_or_default(),
+    })
+}
+
+/// Convert a stored unlock to its DTO, with its size relative to supply
+pub fn to_info(unlock: TokenUnlock) -> TokenUnlockInfo {
+    TokenUnlockInfo {
+        id: unlock.id,
+        pct_of_circulating: relative_size_pct(unlock.amount, unlock.circulating_supply),
+        mint: unlock.mint,
+        symbol: unlock.symbol,
+        unlock_at: unlock.unlock_at.timestamp(),
+        amount: unlock.amount,
+        circulating_supply: unlock.circulating_supply,
+        allocation: Some(unlock.allocation).filter(|a| !a.is_empty()),
+        source: unlock.source,
+    }
+}
+
+/// Service for token unlocks and the dataset import job
+pub struct TokenUnlockService {
+    db: DbPool,
+    client: reqwest::Client,
+    dataset_url: Option<String>,
+}
+
+impl TokenUnlockService {
+    /// Create the service; `dataset_url` enables [`TokenUnlockService::start`].
+    pub fn new(db: DbPool, dataset_url: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DATASET_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self { db, client, dataset_url }
+    }
+
+    /// Create the service with the dataset from `TOKEN_UNLOCKS_DATASET_URL`.
+    pub fn from_env(db: DbPool) -> Self {
+        let url = std::env::var("TOKEN_UNLOCKS_DATASET_URL").ok().filter(|u| !u.trim().is_empty());
+        Self::new(db, url)
+    }
+
+    /// Unlocks in the next `days` days (clamped to 1..=365), soonest first.
+    #[instrument(skip(self))]
+    pub async fn upcoming(&self, days: i64) -> Result<TokenUnlocksResponse, AppError> {
+        let now = Utc::now();
+        let until = now + TimeDelta::days(days.clamp(1, MAX_UNLOCK_WINDOW_DAYS));
+        let unlocks = TokenUnlockRepository::upcoming(&self.db, now, until).await?;
+        Ok(TokenUnlocksResponse { unlocks: unlocks.into_iter().map(to_info).collect() })
+    }
+
+    /// Create an unlock, or take over the existing one for the same mint,
+    /// time and allocation.
+    pub async fn create(&self, request: TokenUnlockRequest, admin: &str) -> Result<TokenUnlockInfo, AppError> {
+        let unlock = Self::from_request(request)?;
+        let created = TokenUnlockRepository::create(&self.db, &unlock, admin).await?;
+        info!(id = created.id, symbol = %created.symbol, admin, "Token unlock saved");
+        Ok(to_info(created))
+    }
+
+    /// Replace an unlock's fields.
+    ///
+    /// # Returns
+    ///
+    /// * `Err(AppError::NotFound)` - No unlock with that id
+    pub async fn update(&self, id: i64, request: TokenUnlockRequest, admin: &str) -> Result<TokenUnlockInfo, AppError> {
+        let unlock = Self::from_request(request)?;
+        let updated = TokenUnlockRepository::update(&self.db, id, &unlock, admin)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Token unlock {} not found", id)))?;
+        info!(id, symbol = %updated.symbol, admin, "Token unlock updated");
+        Ok(to_info(updated))
+    }
+
+    /// Remove an unlock; the importer won't bring it back.
+    pub async fn remove(&self, id: i64, admin: &str) -> Result<(), AppError> {
+        if !TokenUnlockRepository::remove(&self.db, id, admin).await? {
+            return Err(AppError::NotFound(format!("Token unlock {} not found", id)));
+        }
+        info!(id, admin, "Token unlock removed");
+        Ok(())
+    }
+
+    fn from_request(request: TokenUnlockRequest) -> Result<TokenUnlockForCreate, AppError> {
+        let unlock_at = Utc
+            .timestamp_opt(request.unlock_at, 0)
+            .single()
+            .ok_or_else(|| AppError::InvalidInput("Invalid unlock_at".to_string()))?;
+        validate(
+            request.mint,
+            request.symbol,
+            unlock_at,
+            request.amount,
+            request.circulating_supply,
+            request.allocation,
+        )
+        .map_err(AppError::InvalidInput)
+    }
+
+    /// Fetch the dataset and import it once.
+    #[instrument(skip(self))]
+    pub async fn import_dataset(&self) -> Result<UnlockImportCounts, AppError> {
+        let Some(url) = &self.dataset_url else {
+            return Err(AppError::Internal("No unlocks dataset configured".to_string()));
+        };
+        let body = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AppError::Internal(format!("Failed to fetch unlocks dataset: {}", e)))?
+            .text()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read unlocks dataset: {}", e)))?;
+
+        let parsed = parse_dataset(&body)?;
+        let counts = TokenUnlockRepository::import(&self.db, &parsed.unlocks).await?;
+        info!(
+            upserted = counts.upserted,
+            skipped = counts.skipped,
+            invalid = parsed.invalid,
+            "Imported unlocks dataset"
+        );
+        Ok(counts)
+    }
+
+    /// Run the import job until the process exits. Returns at once when no
+    /// dataset is configured.
+    pub async fn start(self: Arc<Self>) {
+        if self.dataset_url.is_none() {
+            info!("TOKEN_UNLOCKS_DATASET_URL not set, unlocks are admin-managed only");
+            return;
+        }
+        let interval_secs = std::env::var("TOKEN_UNLOCKS_IMPORT_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_IMPORT_INTERVAL_SECS);
+
+        info!(interval_secs, "Token unlock importer started");
+
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.import_dataset().await {
+                warn!(error = %e, "Token unlock import failed");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(amount: f64, circulating_supply: Option<f64>, allocation: &str) -> TokenUnlock {
+        let at = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        TokenUnlock {
+            id: 7,
+            mint: "JUPmint".to_string(),
+            symbol: "JUP".to_string(),
+            unlock_at: at,
+            amount,
+            circulating_supply,
+            allocation: allocation.to_string(),
+            source: "import".to_string(),
+            updated_by: None,
+            removed: false,
+            created_at: at,
+            updated_at: at,
+        }
+    }
+
+    #[test]
+    fn test_parse_dataset_skips_bad_entries() {
+        let body = r#"[
+            {"mint": "JUPmint", "symbol": "jup", "unlock_date": "2025-03-01T00:00:00.250Z",
+             "amount": 53472222, "circulating_supply": 1350000000, "allocation": " Team "},
+            {"mint": "PYTHmint", "symbol": "PYTH", "unlock_date": 1747699200, "amount": 2.1e9},
+            {"mint": "BADmint", "symbol": "BAD", "unlock_date": "next tuesday", "amount": 1},
+            {"mint": "NEGmint", "symbol": "NEG", "unlock_date": 1747699200, "amount": -5},
+            {"mint": "ZEROmint", "symbol": "ZERO", "unlock_date": 1747699200, "amount": 5, "circulating_supply": 0},
+            {"symbol": "NOMINT", "unlock_date": 1747699200, "amount": 5},
+            "not an object"
+        ]"#;
+
+        let parsed = parse_dataset(body).unwrap();
+        assert_eq!(parsed.invalid, 5);
+        assert_eq!(parsed.unlocks.len(), 2);
+
+        let jup = &parsed.unlocks[0];
+        assert_eq!(jup.symbol, "JUP");
+        assert_eq!(jup.allocation, "Team");
+        // Sub-second precision would break matching on re-import
+        assert_eq!(jup.unlock_at, Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap());
+        assert_eq!(jup.circulating_supply, Some(1_350_000_000.0));
+
+        let pyth = &parsed.unlocks[1];
+        assert_eq!(pyth.unlock_at.timestamp(), 1_747_699_200);
+        assert_eq!(pyth.allocation, "");
+        assert_eq!(pyth.circulating_supply, None);
+    }
+
+    #[test]
+    fn test_parse_dataset_wrapped_and_invalid_documents() {
+        let wrapped = r#"{"unlocks": [{"mint": "JUPmint", "symbol": "JUP", "unlock_date": 1747699200, "amount": 1}]}"#;
+        assert_eq!(parse_dataset(wrapped).unwrap().unlocks.len(), 1);
+        assert_eq!(parse_dataset("[]").unwrap().unlocks.len(), 0);
+
+        assert!(matches!(parse_dataset("<html>rate limited</html>"), Err(AppError::Decoding(_))));
+        assert!(matches!(parse_dataset(r#"{"data": []}"#), Err(AppError::Decoding(_))));
+    }
+
+    #[test]
+    fn test_admin_request_validation() {
+        let request = TokenUnlockRequest {
+            mint: "JUPmint".to_string(),
+            symbol: "JUP".to_string(),
+            unlock_at: 1_747_699_200,
+            amount: 1_000.0,
+            circulating_supply: Some(10_000.0),
+            allocation: None,
+        };
+        assert!(TokenUnlockService::from_request(request.clone()).is_ok());
+
+        for bad in [
+            TokenUnlockRequest { amount: 0.0, ..request.clone() },
+            TokenUnlockRequest { symbol: "J/UP".to_string(), ..request.clone() },
+            TokenUnlockRequest { mint: " ".to_string(), ..request.clone() },
+            TokenUnlockRequest { circulating_supply: Some(f64::NAN), ..request.clone() },
+            TokenUnlockRequest { unlock_at: i64::MAX, ..request.clone() },
+        ] {
+            assert!(matches!(TokenUnlockService::from_request(bad), Err(AppError::InvalidInput(_))));
+        }
+    }
+
+    #[test]
+    fn test_to_info_relative_size() {
+        let info = to_info(stored(50_000.0, Some(1_000_000.0), "Investors"));
+        assert_eq!(info.pct_of_circulating, Some(5.0));
+        assert_eq!(info.allocation.as_deref(), Some("Investors"));
+        assert_eq!(info.unlock_at, Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap().timestamp());
+
+        let info = to_info(stored(50_000.0, None, ""));
+        assert_eq!(info.pct_of_circulating, None);
+        assert_eq!(info.allocation, None);
+    }
+}
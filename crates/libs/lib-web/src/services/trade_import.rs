@@ -0,0 +1,811 @@
+//! # Trade Import Service
+//!
+//! Imports historical trades from other platforms' CSV exports and computes
+//! trade statistics over imported trades and confirmed on-chain swaps.
+//!
+//! ## Import Pipeline
+//!
+//! ```text
+//! CSV text ─► parse_csv ─► header lookup (ColumnMapping) ─► per-row parse
+//!                                                              │
+//!              existing dedupe keys ──► duplicate check ◄──────┘
+//!                                              │
+//!                      commit: false ──► preview (valid rows + RowErrors)
+//!                      commit: true  ──► imported_trades (source = 'import')
+//! ```
+//!
+//! Imported trades live in their own table, so they never take part in
+//! on-chain reconciliation of the `swaps` table, but [`TradeImportService::stats`]
+//! counts them alongside confirmed swaps.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use lib_core::model::store::models::{ImportedTradeForCreate, SwapStatus};
+use lib_core::model::store::swap_repository::SwapRepository;
+use lib_core::model::store::ImportedTradeRepository;
+use lib_core::{AppError, DbPool};
+use lib_solana::jupiter::JupiterClient;
+use shared::dto::trades::{
+    ColumnMapping, PairStats, ParsedTrade, RowError, TradeImportRequest, TradeImportResponse,
+    TradeSide, TradeStatsResponse,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+/// Most data rows accepted in one import
+pub const MAX_IMPORT_ROWS: usize = 10_000;
+
+/// Unix timestamps above this are taken to be milliseconds
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// Positions smaller than this are treated as closed
+const POSITION_EPSILON: f64 = 1e-9;
+
+/// Date-time layouts accepted besides RFC 3339 and Unix time (all UTC)
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%m/%d/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M",
+];
+
+/// Date-only layouts (midnight UTC)
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%m/%d/%Y"];
+
+/// Quote currency preference when turning a swap into a pair; earlier wins
+const QUOTE_PRIORITY: &[&str] = &["USDC", "USDT", "SOL"];
+
+/// Split CSV text into records (RFC 4180).
+///
+/// Handles quoted fields with embedded commas, newlines and doubled quotes,
+/// CRLF line endings and a leading byte-order mark. Blank lines are skipped.
+pub fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                push_record(&mut records, std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("Unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        push_record(&mut records, record);
+    }
+    Ok(records)
+}
+
+fn push_record(records: &mut Vec<Vec<String>>, record: Vec<String>) {
+    if !(record.len() == 1 && record[0].trim().is_empty()) {
+        records.push(record);
+    }
+}
+
+/// Parse a timestamp into Unix seconds (see the module docs of
+/// [`shared::dto::trades`] for the accepted formats).
+pub fn parse_timestamp(value: &str) -> Result<i64, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("Timestamp is empty".to_string());
+    }
+
+    if value.chars().all(|c| c.is_ascii_digit()) {
+        let n: i64 = value.parse().map_err(|_| format!("Timestamp '{}' is out of range", value))?;
+        return Ok(if n >= MILLIS_THRESHOLD { n / 1000 } else { n });
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.timestamp());
+    }
+    for format in DATETIME_FORMATS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(dt.and_utc().timestamp());
+        }
+    }
+    for format in DATE_FORMATS {
+        if let Ok(date) = NaiveDate::parse_from_str(value, format) {
+            return Ok(date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc().timestamp()).unwrap_or_default());
+        }
+    }
+    Err(format!("Unrecognized timestamp '{}'", value))
+}
+
+/// Parse a number, ignoring thousands separators, spaces and a `$` sign.
+pub fn parse_number(value: &str) -> Result<f64, String> {
+    let cleaned: String = value
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ',' | '$' | ' ' | '_'))
+        .collect();
+    if cleaned.is_empty() {
+        return Err("Value is empty".to_string());
+    }
+    match cleaned.parse::<f64>() {
+        Ok(n) if n.is_finite() => Ok(n),
+        _ => Err(format!("'{}' is not a number", value.trim())),
+    }
+}
+
+/// Split a pair like `SOL/USDC`, `SOL-USDC` or `SOL_USDC` into (base, quote).
+pub fn parse_pair(value: &str) -> Result<(String, String), String> {
+    let value = value.trim();
+    let parts: Vec<&str> = value.split(['/', '-', '_']).map(str::trim).collect();
+    match parts.as_slice() {
+        [base, quote]
+            if !base.is_empty()
+                && !quote.is_empty()
+                && base.chars().chain(quote.chars()).all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            Ok((base.to_uppercase(), quote.to_uppercase()))
+        }
+        _ => Err(format!("Pair '{}' must look like BASE/QUOTE", value)),
+    }
+}
+
+/// Parse a trade side (`buy`, `sell`, `b`, `s`; any case).
+pub fn parse_side(value: &str) -> Result<TradeSide, String> {
+    match value.trim().to_lowercase().as_str() {
+        "buy" | "b" => Ok(TradeSide::Buy),
+        "sell" | "s" => Ok(TradeSide::Sell),
+        other => Err(format!("Side '{}' must be buy or sell", other)),
+    }
+}
+
+/// Key identifying a trade regardless of which file or row it came from
+pub fn dedupe_key(trade: &ParsedTrade) -> String {
+    format!(
+        "{}|{}/{}|{}|{}|{}|{}",
+        trade.timestamp, trade.base, trade.quote, trade.side, trade.amount, trade.price, trade.fee
+    )
+}
+
+/// Parse one data row. Returns every field error, not just the first.
+fn parse_row(row: usize, record: &[String], columns: &ResolvedColumns) -> Result<ParsedTrade, Vec<RowError>> {
+    let mut errors = Vec::new();
+    let timestamp = cell(row, record, "timestamp", columns.timestamp, &mut errors);
+    let pair = cell(row, record, "pair", columns.pair, &mut errors);
+    let side = cell(row, record, "side", columns.side, &mut errors);
+    let amount = cell(row, record, "amount", columns.amount, &mut errors);
+    let price = cell(row, record, "price", columns.price, &mut errors);
+    let fee = columns.fee.map(|i| record.get(i).map(String::as_str).unwrap_or(""));
+
+    let mut check = |name: &str, result: Result<(), String>| {
+        if let Err(message) = result {
+            errors.push(RowError { row, field: Some(name.to_string()), message });
+        }
+    };
+
+    let mut trade = ParsedTrade {
+        row,
+        timestamp: 0,
+        base: String::new(),
+        quote: String::new(),
+        side: TradeSide::Buy,
+        amount: 0.0,
+        price: 0.0,
+        fee: 0.0,
+    };
+
+    if let Some(value) = timestamp {
+        check("timestamp", parse_timestamp(value).map(|t| trade.timestamp = t));
+    }
+    if let Some(value) = pair {
+        check("pair", parse_pair(value).map(|(base, quote)| {
+            trade.base = base;
+            trade.quote = quote;
+        }));
+    }
+    if let Some(value) = side {
+        check("side", parse_side(value).map(|s| trade.side = s));
+    }
+    if let Some(value) = amount {
+        check("amount", positive(value).map(|n| trade.amount = n));
+    }
+    if let Some(value) = price {
+        check("price", positive(value).map(|n| trade.price = n));
+    }
+    if let Some(value) = fee.filter(|v| !v.trim().is_empty()) {
+        check("fee", parse_number(value).and_then(|n| {
+            if n < 0.0 {
+                Err("Fee cannot be negative".to_string())
+            } else {
+                trade.fee = n;
+                Ok(())
+            }
+        }));
+    }
+
+    if errors.is_empty() {
+        Ok(trade)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Value of a required column, or an error when the row is too short
+fn cell<'a>(
+    row: usize,
+    record: &'a [String],
+    field: &str,
+    index: usize,
+    errors: &mut Vec<RowError>,
+) -> Option<&'a str> {
+    let value = record.get(index).map(String::as_str);
+    if value.is_none() {
+        errors.push(RowError {
+            row,
+            field: Some(field.to_string()),
+            message: format!("Row has {} columns, expected at least {}", record.len(), index + 1),
+        });
+    }
+    value
+}
+
+fn positive(value: &str) -> Result<f64, String> {
+    let n = parse_number(value)?;
+    if n > 0.0 {
+        Ok(n)
+    } else {
+        Err("Must be greater than zero".to_string())
+    }
+}
+
+/// Column index of each mapped field
+struct ResolvedColumns {
+    timestamp: usize,
+    pair: usize,
+    side: usize,
+    amount: usize,
+    price: usize,
+    fee: Option<usize>,
+}
+
+impl ResolvedColumns {
+    /// Look up the mapped headers (case-insensitive); reports each missing one
+    fn resolve(headers: &[String], mapping: &ColumnMapping) -> Result<Self, Vec<RowError>> {
+        let mut errors = Vec::new();
+        let mut find = |field: &str, header: &str| {
+            let index = headers.iter().position(|h| h.trim().eq_ignore_ascii_case(header.trim()));
+            if index.is_none() {
+                errors.push(RowError {
+                    row: 0,
+                    field: Some(field.to_string()),
+                    message: format!("Column '{}' not found in header", header),
+                });
+            }
+            index.unwrap_or_default()
+        };
+
+        let columns = Self {
+            timestamp: find("timestamp", &mapping.timestamp),
+            pair: find("pair", &mapping.pair),
+            side: find("side", &mapping.side),
+            amount: find("amount", &mapping.amount),
+            price: find("price", &mapping.price),
+            fee: mapping
+                .fee
+                .as_deref()
+                .filter(|h| !h.trim().is_empty())
+                .map(|h| find("fee", h)),
+        };
+
+        if errors.is_empty() {
+            Ok(columns)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Validate a CSV export against a column mapping.
+///
+/// Rows repeating an earlier row of the file or a key in `existing` are
+/// counted as duplicates and left out of `valid`. Nothing is stored.
+///
+/// # Returns
+///
+/// * `Ok(TradeImportResponse)` - Preview; header problems are row 0 errors
+/// * `Err(AppError::InvalidInput)` - Empty file, broken quoting, or too many rows
+pub fn validate(
+    csv: &str,
+    mapping: &ColumnMapping,
+    existing: &HashSet<String>,
+) -> Result<TradeImportResponse, AppError> {
+    let records = parse_csv(csv).map_err(AppError::InvalidInput)?;
+    let Some((header, rows)) = records.split_first() else {
+        return Err(AppError::InvalidInput("CSV file is empty".to_string()));
+    };
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(AppError::InvalidInput(format!(
+            "CSV has {} rows; at most {} can be imported at once",
+            rows.len(),
+            MAX_IMPORT_ROWS
+        )));
+    }
+
+    let headers: Vec<String> = header.iter().map(|h| h.trim().to_string()).collect();
+    let mut response = TradeImportResponse {
+        headers,
+        total_rows: rows.len(),
+        valid: Vec::new(),
+        errors: Vec::new(),
+        duplicates: 0,
+        imported: 0,
+        committed: false,
+    };
+
+    let columns = match ResolvedColumns::resolve(&response.headers, mapping) {
+        Ok(columns) => columns,
+        Err(errors) => {
+            response.errors = errors;
+            return Ok(response);
+        }
+    };
+
+    let mut seen = HashSet::new();
+    for (i, record) in rows.iter().enumerate() {
+        match parse_row(i + 1, record, &columns) {
+            Ok(trade) => {
+                let key = dedupe_key(&trade);
+                if existing.contains(&key) || !seen.insert(key) {
+                    response.duplicates += 1;
+                } else {
+                    response.valid.push(trade);
+                }
+            }
+            Err(errors) => response.errors.extend(errors),
+        }
+    }
+
+    Ok(response)
+}
+
+/// A trade reduced to what the statistics need
+#[derive(Debug, Clone)]
+struct Fill {
+    timestamp: i64,
+    pair: String,
+    side: TradeSide,
+    amount: f64,
+    price: f64,
+    fee: f64,
+}
+
+/// Per-pair realized PnL using the average-cost method.
+///
+/// Buy fees are added to the cost basis and sell fees are deducted from the
+/// proceeds. Sells beyond the tracked position (coins bought elsewhere) add
+/// volume but no PnL, since their cost is unknown.
+fn compute_pair_stats(fills: &[Fill]) -> Vec<PairStats> {
+    let mut by_pair: BTreeMap<&str, Vec<&Fill>> = BTreeMap::new();
+    for fill in fills {
+        by_pair.entry(fill.pair.as_str()).or_default().push(fill);
+    }
+
+    by_pair
+        .into_iter()
+        .map(|(pair, mut fills)| {
+            fills.sort_by_key(|f| f.timestamp);
+            let mut stats = PairStats {
+                pair: pair.to_string(),
+                trades: fills.len(),
+                volume: 0.0,
+                fees: 0.0,
+                realized_pnl: 0.0,
+                open_position: 0.0,
+                average_cost: 0.0,
+            };
+            let mut cost_basis = 0.0;
+
+            for fill in fills {
+                stats.volume += fill.amount * fill.price;
+                stats.fees += fill.fee;
+                match fill.side {
+                    TradeSide::Buy => {
+                        stats.open_position += fill.amount;
+                        cost_basis += fill.amount * fill.price + fill.fee;
+                    }
+                    TradeSide::Sell => {
+                        let matched = fill.amount.min(stats.open_position);
+                        if matched > POSITION_EPSILON {
+                            let average = cost_basis / stats.open_position;
+                            stats.realized_pnl += matched * (fill.price - average);
+                            cost_basis -= matched * average;
+                            stats.open_position -= matched;
+                        }
+                        stats.realized_pnl -= fill.fee;
+                    }
+                }
+                if stats.open_position <= POSITION_EPSILON {
+                    stats.open_position = 0.0;
+                    cost_basis = 0.0;
+                }
+            }
+
+            if stats.open_position > 0.0 {
+                stats.average_cost = cost_basis / stats.open_position;
+            }
+            stats
+        })
+        .collect()
+}
+
+/// Rank of a symbol as a quote currency; lower is more quote-like
+fn quote_rank(symbol: &str) -> usize {
+    QUOTE_PRIORITY
+        .iter()
+        .position(|q| q.eq_ignore_ascii_case(symbol))
+        .unwrap_or(QUOTE_PRIORITY.len())
+}
+
+/// Express a swap as a trade on a pair: selling into a more quote-like token
+/// is a sell of the input, anything else a buy of the output.
+fn swap_to_fill(
+    timestamp: i64,
+    input: (&str, f64),
+    output: (&str, f64),
+) -> Option<Fill> {
+    let ((in_symbol, in_amount), (out_symbol, out_amount)) = (input, output);
+    if in_amount <= 0.0 || out_amount <= 0.0 {
+        return None;
+    }
+    let fill = if quote_rank(out_symbol) < quote_rank(in_symbol) {
+        Fill {
+            timestamp,
+            pair: format!("{}/{}", in_symbol, out_symbol),
+            side: TradeSide::Sell,
+            amount: in_amount,
+            price: out_amount / in_amount,
+            fee: 0.0,
+        }
+    } else {
+        Fill {
+            timestamp,
+            pair: format!("{}/{}", out_symbol, in_symbol),
+            side: TradeSide::Buy,
+            amount: out_amount,
+            price: in_amount / out_amount,
+            fee: 0.0,
+        }
+    };
+    Some(fill)
+}
+
+/// Service for trade imports and trade statistics
+pub struct TradeImportService {
+    db: DbPool,
+    jupiter: Arc<JupiterClient>,
+}
+
+impl TradeImportService {
+    pub fn new(db: DbPool, jupiter: Arc<JupiterClient>) -> Self {
+        Self { db, jupiter }
+    }
+
+    /// Validate an import and, when `request.commit` is set, store the valid rows.
+    ///
+    /// Rows with errors are never stored; committing imports the rows that
+    /// passed, so the client should show the preview first.
+    #[instrument(skip(self, request), fields(bytes = request.csv.len(), commit = request.commit))]
+    pub async fn import(
+        &self,
+        user_id: i64,
+        request: &TradeImportRequest,
+    ) -> Result<TradeImportResponse, AppError> {
+        let existing = ImportedTradeRepository::dedupe_keys(&self.db, user_id).await?;
+        let mut response = validate(&request.csv, &request.mapping, &existing)?;
+        if !request.commit {
+            return Ok(response);
+        }
+
+        let rows: Vec<ImportedTradeForCreate> = response
+            .valid
+            .iter()
+            .map(|trade| ImportedTradeForCreate {
+                traded_at: Utc.timestamp_opt(trade.timestamp, 0).single().unwrap_or_default(),
+                base: trade.base.clone(),
+                quote: trade.quote.clone(),
+                side: trade.side.to_string(),
+                amount: trade.amount,
+                price: trade.price,
+                fee: trade.fee,
+                dedupe_key: dedupe_key(trade),
+            })
+            .collect();
+
+        response.imported = ImportedTradeRepository::insert_many(&self.db, user_id, &rows).await?;
+        response.committed = true;
+        info!(
+            "User {} imported {} trades ({} duplicates, {} row errors)",
+            user_id,
+            response.imported,
+            response.duplicates,
+            response.errors.len()
+        );
+        Ok(response)
+    }
+
+    /// Trade statistics over imported trades and confirmed on-chain swaps.
+    ///
+    /// Swaps whose mints are not in the Jupiter token list are left out.
+    pub async fn stats(&self, user_id: i64) -> Result<TradeStatsResponse, AppError> {
+        let imported = ImportedTradeRepository::find_by_user(&self.db, user_id).await?;
+        let swaps = SwapRepository::find_by_user(&self.db, user_id, None).await?;
+
+        let tokens: HashMap<String, (String, u8)> = self
+            .jupiter
+            .get_all_tokens()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|t| (t.address, (t.symbol.to_uppercase(), t.decimals)))
+            .collect();
+
+        let mut fills: Vec<Fill> = imported
+            .iter()
+            .map(|t| Fill {
+                timestamp: t.traded_at.timestamp(),
+                pair: format!("{}/{}", t.base, t.quote),
+                side: if t.side == "sell" { TradeSide::Sell } else { TradeSide::Buy },
+                amount: t.amount,
+                price: t.price,
+                fee: t.fee,
+            })
+            .collect();
+
+        let mut onchain_trades = 0;
+        for swap in swaps.iter().filter(|s| s.status == SwapStatus::Confirmed) {
+            let (Some((in_symbol, in_decimals)), Some((out_symbol, out_decimals))) =
+                (tokens.get(&swap.input_mint), tokens.get(&swap.output_mint))
+            else {
+                continue;
+            };
+            let input = (in_symbol.as_str(), swap.input_amount as f64 / 10f64.powi(*in_decimals as i32));
+            let output = (out_symbol.as_str(), swap.output_amount as f64 / 10f64.powi(*out_decimals as i32));
+            let timestamp = swap.confirmed_at.unwrap_or(swap.created_at).timestamp();
+            if let Some(fill) = swap_to_fill(timestamp, input, output) {
+                fills.push(fill);
+                onchain_trades += 1;
+            }
+        }
+
+        Ok(TradeStatsResponse {
+            onchain_trades,
+            imported_trades: imported.len(),
+            pairs: compute_pair_stats(&fills),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> HashSet<String> {
+        HashSet::new()
+    }
+
+    #[test]
+    fn test_parse_csv_quoted_fields() {
+        let text = "a,b,c\r\n\"1,234.50\",\"say \"\"hi\"\"\",\"multi\nline\"\n\n x ,,\n";
+        let records = parse_csv(text).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1], vec!["1,234.50", "say \"hi\"", "multi\nline"]);
+        assert_eq!(records[2], vec![" x ", "", ""]);
+    }
+
+    #[test]
+    fn test_parse_csv_edge_cases() {
+        // BOM, no trailing newline, whitespace before an opening quote
+        let records = parse_csv("\u{feff}h1,h2\n1, \"2,5\"").unwrap();
+        assert_eq!(records[0], vec!["h1", "h2"]);
+        assert_eq!(records[1], vec!["1", "2,5"]);
+
+        assert!(parse_csv("h1\n\"unterminated").is_err());
+        assert!(parse_csv("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_timestamp_formats() {
+        let expected = 1_709_294_400; // 2024-03-01 12:00:00 UTC
+        for value in [
+            "2024-03-01T12:00:00Z",
+            "2024-03-01T14:00:00+02:00",
+            "2024-03-01 12:00:00",
+            "2024-03-01T12:00:00.000",
+            "2024-03-01 12:00",
+            "03/01/2024 12:00:00",
+            "03/01/2024 12:00",
+            "1709294400",
+            "1709294400000",
+        ] {
+            assert_eq!(parse_timestamp(value), Ok(expected), "{}", value);
+        }
+        assert_eq!(parse_timestamp("2024-03-01"), Ok(expected - 12 * 3600));
+        assert_eq!(parse_timestamp("03/01/2024"), Ok(expected - 12 * 3600));
+        assert!(parse_timestamp("01.03.2024").is_err());
+        assert!(parse_timestamp("13/45/2024").is_err());
+        assert!(parse_timestamp("").is_err());
+    }
+
+    #[test]
+    fn test_parse_number_separators() {
+        assert_eq!(parse_number("1,234.50"), Ok(1234.5));
+        assert_eq!(parse_number(" $1,234,567 "), Ok(1_234_567.0));
+        assert_eq!(parse_number("0.000001"), Ok(0.000001));
+        assert!(parse_number("").is_err());
+        assert!(parse_number("12abc").is_err());
+        assert!(parse_number("NaN").is_err());
+    }
+
+    #[test]
+    fn test_parse_pair_and_side() {
+        assert_eq!(parse_pair("sol/usdc"), Ok(("SOL".to_string(), "USDC".to_string())));
+        assert_eq!(parse_pair("SOL-USDC"), Ok(("SOL".to_string(), "USDC".to_string())));
+        assert_eq!(parse_pair("JUP_SOL"), Ok(("JUP".to_string(), "SOL".to_string())));
+        assert!(parse_pair("SOLUSDC").is_err());
+        assert!(parse_pair("SOL/").is_err());
+        assert!(parse_pair("A/B/C").is_err());
+
+        assert_eq!(parse_side("BUY"), Ok(TradeSide::Buy));
+        assert_eq!(parse_side(" s "), Ok(TradeSide::Sell));
+        assert!(parse_side("hold").is_err());
+    }
+
+    #[test]
+    fn test_validate_reports_row_errors() {
+        let csv = "timestamp,pair,side,amount,price,fee\n\
+                   2024-03-01,SOL/USDC,buy,\"1,000\",$100.50,0.5\n\
+                   yesterday,SOLUSDC,buy,-1,100,\n\
+                   2024-03-02,SOL/USDC,sell,10\n";
+        let response = validate(csv, &ColumnMapping::default(), &keys()).unwrap();
+
+        assert_eq!(response.total_rows, 3);
+        assert_eq!(response.valid.len(), 1);
+        assert_eq!(response.valid[0].amount, 1000.0);
+        assert_eq!(response.valid[0].price, 100.5);
+        assert_eq!(response.valid[0].fee, 0.5);
+
+        let row2: Vec<_> = response.errors.iter().filter(|e| e.row == 2).collect();
+        let fields: Vec<_> = row2.iter().filter_map(|e| e.field.as_deref()).collect();
+        assert_eq!(fields, vec!["timestamp", "pair", "amount"]);
+
+        // Short row: missing price column
+        assert!(response.errors.iter().any(|e| e.row == 3 && e.field.as_deref() == Some("price")));
+        assert!(!response.committed);
+    }
+
+    #[test]
+    fn test_validate_with_custom_mapping() {
+        let csv = "Date(UTC),Market,Type,Filled,Avg Price\n\
+                   2024-03-01 08:30:00,SOL-USDT,SELL,2.5,150\n";
+        let mapping = ColumnMapping {
+            timestamp: "date(utc)".to_string(),
+            pair: "Market".to_string(),
+            side: "Type".to_string(),
+            amount: "Filled".to_string(),
+            price: "Avg Price".to_string(),
+            fee: None,
+        };
+        let response = validate(csv, &mapping, &keys()).unwrap();
+        assert!(response.errors.is_empty());
+        assert_eq!(response.valid[0].quote, "USDT");
+        assert_eq!(response.valid[0].side, TradeSide::Sell);
+
+        // Default mapping reports each missing header as a row 0 error
+        let response = validate(csv, &ColumnMapping::default(), &keys()).unwrap();
+        assert_eq!(response.headers[4], "Avg Price");
+        assert_eq!(response.errors.len(), 6);
+        assert!(response.errors.iter().all(|e| e.row == 0));
+        assert!(response.valid.is_empty());
+    }
+
+    #[test]
+    fn test_validate_detects_duplicates() {
+        let csv = "timestamp,pair,side,amount,price\n\
+                   2024-03-01,SOL/USDC,buy,1,100\n\
+                   1709251200,sol-usdc,B,\"1.0\",100.00\n\
+                   2024-03-02,SOL/USDC,buy,1,100\n";
+        let mapping = ColumnMapping { fee: None, ..Default::default() };
+
+        // Same trade written differently in one file
+        let response = validate(csv, &mapping, &keys()).unwrap();
+        assert_eq!(response.valid.len(), 2);
+        assert_eq!(response.duplicates, 1);
+
+        // Trades already imported are skipped on re-import
+        let existing: HashSet<String> = response.valid.iter().map(dedupe_key).collect();
+        let response = validate(csv, &mapping, &existing).unwrap();
+        assert!(response.valid.is_empty());
+        assert_eq!(response.duplicates, 3);
+    }
+
+    #[test]
+    fn test_validate_rejects_unusable_files() {
+        assert!(validate("", &ColumnMapping::default(), &keys()).is_err());
+        assert!(validate("a,b\n\"open", &ColumnMapping::default(), &keys()).is_err());
+
+        let mut csv = "timestamp,pair,side,amount,price\n".to_string();
+        for _ in 0..=MAX_IMPORT_ROWS {
+            csv.push_str("2024-03-01,SOL/USDC,buy,1,100\n");
+        }
+        assert!(validate(&csv, &ColumnMapping::default(), &keys()).is_err());
+    }
+
+    fn fill(timestamp: i64, side: TradeSide, amount: f64, price: f64, fee: f64) -> Fill {
+        Fill { timestamp, pair: "SOL/USDC".to_string(), side, amount, price, fee }
+    }
+
+    #[test]
+    fn test_average_cost_pnl() {
+        let stats = compute_pair_stats(&[
+            fill(3, TradeSide::Sell, 3.0, 130.0, 1.0),
+            fill(1, TradeSide::Buy, 2.0, 100.0, 0.0),
+            fill(2, TradeSide::Buy, 2.0, 120.0, 0.0),
+        ]);
+        let sol = &stats[0];
+        assert_eq!(sol.trades, 3);
+        // Average cost 110, sold 3 at 130, minus 1 fee
+        assert!((sol.realized_pnl - 59.0).abs() < 1e-9);
+        assert!((sol.open_position - 1.0).abs() < 1e-9);
+        assert!((sol.average_cost - 110.0).abs() < 1e-9);
+        assert!((sol.volume - 830.0).abs() < 1e-9);
+        assert_eq!(sol.fees, 1.0);
+    }
+
+    #[test]
+    fn test_oversell_has_no_pnl_for_unknown_cost() {
+        let stats = compute_pair_stats(&[
+            fill(1, TradeSide::Buy, 1.0, 100.0, 0.0),
+            fill(2, TradeSide::Sell, 3.0, 150.0, 0.0),
+        ]);
+        assert!((stats[0].realized_pnl - 50.0).abs() < 1e-9);
+        assert_eq!(stats[0].open_position, 0.0);
+        assert_eq!(stats[0].average_cost, 0.0);
+    }
+
+    #[test]
+    fn test_swap_to_fill_picks_quote() {
+        let sell = swap_to_fill(1, ("SOL", 2.0), ("USDC", 300.0)).unwrap();
+        assert_eq!(sell.pair, "SOL/USDC");
+        assert_eq!(sell.side, TradeSide::Sell);
+        assert_eq!(sell.price, 150.0);
+
+        let buy = swap_to_fill(1, ("SOL", 1.0), ("JUP", 200.0)).unwrap();
+        assert_eq!(buy.pair, "JUP/SOL");
+        assert_eq!(buy.side, TradeSide::Buy);
+        assert_eq!(buy.amount, 200.0);
+
+        assert!(swap_to_fill(1, ("SOL", 0.0), ("USDC", 1.0)).is_none());
+    }
+}
@@ -51,16 +51,22 @@
 //!                   → Database → Transaction Records
 //! ```
 
+use crate::services::portfolio::PortfolioService;
 use lib_core::AppError;
 use lib_solana::SolanaState;
 use lib_core::DbPool;
+use shared::dto::transactions::TransactionVersion;
 use solana_sdk::{
+    message::VersionedMessage,
     pubkey::Pubkey,
-    transaction::Transaction,
+    transaction::{Transaction, VersionedTransaction},
 };
 use std::str::FromStr;
 use std::sync::Arc;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
+
+/// Base fee per signature, used when the fee of a submitted swap can't be looked up
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
 
 /// Transaction submission result.
 #[derive(Debug, Clone, serde::Serialize)]
@@ -82,6 +88,9 @@ pub struct TransactionSummary {
     pub block_time: Option<i64>,
     /// Transaction status ("Success" or "Failed")
     pub status: String,
+    /// Memo(s) attached via the SPL Memo program, as returned by the RPC
+    /// (`[len] text`, multiple memos joined by `"; "`)
+    pub memo: Option<String>,
 }
 
 /// Transaction history response.
@@ -99,6 +108,9 @@ pub struct SwapTransactionSubmitRequest {
     /// Base64-encoded signed transaction
     #[serde(rename = "signedTransaction")]
     pub signed_transaction: String,
+    /// Message format of `signed_transaction`
+    #[serde(default)]
+    pub version: TransactionVersion,
     /// Input token mint address
     #[serde(rename = "inputMint")]
     pub input_mint: String,
@@ -190,9 +202,11 @@ impl TransactionService {
     ///
     /// # Notes
     ///
+    /// - Transaction is decoded as legacy or v0 according to `request.version`
     /// - Transaction is submitted to Solana RPC with `send_transaction`
     /// - Signature is returned immediately (transaction is pending)
     /// - Swap is recorded in database with "pending" status
+    /// - Its network fee is looked up before sending and stored with it
     /// - Transaction may still fail during processing (check status on-chain)
     #[instrument(skip(self), fields(user_id = %user_id, input_mint = %request.input_mint, output_mint = %request.output_mint))]
     pub async fn submit_swap_transaction(
@@ -209,14 +223,40 @@ impl TransactionService {
             .decode(&request.signed_transaction)
             .map_err(|e| AppError::InvalidInput(format!("Invalid base64 transaction: {}", e)))?;
 
-        let transaction: Transaction = bincode::deserialize(&tx_bytes)
-            .map_err(|e| AppError::InvalidInput(format!("Invalid transaction format: {}", e)))?;
+        // v0 transactions may load accounts from lookup tables; the legacy format can't express them
+        let transaction = match request.version {
+            TransactionVersion::Legacy => bincode::deserialize::<Transaction>(&tx_bytes)
+                .map(VersionedTransaction::from)
+                .map_err(|e| AppError::InvalidInput(format!("Invalid transaction format: {}", e)))?,
+            TransactionVersion::V0 => {
+                let transaction: VersionedTransaction = bincode::deserialize(&tx_bytes)
+                    .map_err(|e| AppError::InvalidInput(format!("Invalid transaction format: {}", e)))?;
+                if !matches!(transaction.message, VersionedMessage::V0(_)) {
+                    return Err(AppError::InvalidInput("Transaction is not a v0 transaction".to_string()));
+                }
+                transaction
+            }
+        };
+
+        // Looked up before sending, while the blockhash is surely still known
+        let network_fee_lamports = match self
+            .solana
+            .rpc
+            .get_fee_for_message(&transaction.message)
+            .await
+        {
+            Ok(fee) => fee,
+            Err(e) => {
+                debug!(error = %e, "Falling back to the base fee");
+                LAMPORTS_PER_SIGNATURE * transaction.message.header().num_required_signatures as u64
+            }
+        };
 
         // Submit transaction to Solana
         let signature = self
             .solana
             .rpc
-            .send_transaction(&transaction)
+            .send_versioned_transaction(&transaction)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to submit transaction: {}", e)))?;
 
@@ -224,7 +264,17 @@ impl TransactionService {
         use lib_core::model::store::swap_repository::SwapRepository;
         let user_id_int = user_id.parse::<i64>()
             .map_err(|_| AppError::InvalidInput("Invalid user ID".to_string()))?;
-        
+
+        // Valued at submission so positions get a cost basis once it confirms
+        let value_usd = PortfolioService::new(self.db.clone(), Arc::clone(&self.solana))
+            .swap_value_usd(
+                &request.input_mint,
+                request.input_amount,
+                &request.output_mint,
+                request.output_amount,
+            )
+            .await;
+
         SwapRepository::create(
             &self.db,
             user_id_int,
@@ -235,10 +285,16 @@ impl TransactionService {
             request.output_amount,
             request.price_impact,
             request.slippage_bps,
+            value_usd,
         )
         .await
         .map_err(|e| AppError::Internal(format!("Failed to record swap: {}", e)))?;
 
+        // Only the monthly report's fee total depends on it
+        if let Err(e) = SwapRepository::set_network_fee(&self.db, &signature.to_string(), network_fee_lamports as i64).await {
+            warn!(error = %e, %signature, "Failed to record the swap's network fee");
+        }
+
         debug!("Swap transaction submitted: {} for user {}", signature, user_id);
 
         Ok(TransactionSubmitResult {
@@ -317,6 +373,7 @@ impl TransactionService {
                 } else {
                     "Failed".to_string()
                 },
+                memo: sig_info.memo.clone(),
             };
             transactions.push(tx_summary);
         }
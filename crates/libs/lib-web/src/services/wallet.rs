@@ -10,7 +10,7 @@
 //! ## Features
 //!
 //! - **Balance Queries**: Get SOL balance for a wallet address
-//! - **Token Balances**: Get SPL token balances for a wallet
+//! - **Token Balances**: Get SPL and Token-2022 token balances for a wallet
 //! - **Wallet Info**: Get comprehensive wallet information including SOL and tokens
 //! - **Error Handling**: Comprehensive error handling with user-friendly messages
 //!
@@ -54,6 +54,7 @@
 
 use lib_core::AppError;
 use lib_solana::SolanaState;
+use shared::dto::tokens::{TokenProgram, TransferFee};
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -81,6 +82,15 @@ pub struct TokenBalance {
     pub balance: f64,
     /// UI amount string
     pub ui_amount: String,
+    /// Balance in base units
+    pub amount: u64,
+    /// Mint decimals
+    pub decimals: u8,
+    /// Token program owning the account (`spl` or `token2022`)
+    pub program: TokenProgram,
+    /// Transfer fee for the current epoch (Token-2022 transfer-fee mints only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_fee: Option<TransferFee>,
 }
 
 /// Comprehensive wallet information.
@@ -227,11 +237,17 @@ impl WalletService {
 
         let balances: Vec<TokenBalance> = token_accounts
             .into_iter()
+            // Empty accounts linger after a token was sold or sent away
+            .filter(|account| account.amount > 0)
             .map(|account| TokenBalance {
                 mint: account.mint,
                 symbol: account.token_symbol,
                 balance: account.ui_amount,
                 ui_amount: account.ui_amount.to_string(),
+                amount: account.amount,
+                decimals: account.decimals,
+                program: account.program,
+                transfer_fee: account.transfer_fee,
             })
             .collect();
 
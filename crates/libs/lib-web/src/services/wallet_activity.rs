@@ -0,0 +1,326 @@
+//! # Wallet Activity Watcher
+//!
+//! Background job that turns on-chain activity of users' wallets into webhook
+//! events. Only users with at least one webhook are watched.
+//!
+//! ## Each pass
+//!
+//! ```text
+//! users with webhooks ──> SOL balance ──> balance went up?          ──> incoming_transfer
+//!                                     └─> crossed a webhook's line? ──> balance_threshold
+//!                     └─> pending swaps ──> signature status ──> confirmed ──> swap_confirmed
+//!                                                             └─> failed / expired ──> marked failed
+//! ```
+//!
+//! Balances are remembered in memory, so the first pass after startup only
+//! records a baseline and never fires balance events.
+//!
+//! Balance changes are also stored as deposits and withdrawals for the
+//! monthly report, unless the user created a swap since the previous
+//! observation (or shortly before it, while that swap could still land): the
+//! change is then most likely the swap's own.
+//!
+//! ## Configuration
+//!
+//! - `WALLET_ACTIVITY_INTERVAL_SECS` - Poll interval (default: 30)
+
+use crate::services::webhooks::WebhookService;
+use lib_core::model::store::models::{Swap, SwapStatus};
+use lib_core::model::store::{SwapRepository, UserRepository, WalletTransferRepository, WebhookRepository};
+use lib_core::{AppError, DbPool};
+use lib_solana::client::SolanaClient;
+use shared::dto::webhooks::WebhookEventType;
+use solana_sdk::pubkey::Pubkey;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::Duration;
+use tracing::{debug, info, instrument, warn};
+
+/// Default poll interval in seconds
+const DEFAULT_INTERVAL_SECS: u64 = 30;
+
+/// Recent swaps checked for confirmation per user and pass
+const PENDING_SWAPS_CHECKED: usize = 20;
+
+/// A swap the cluster still doesn't know after this long will never land,
+/// since its blockhash has expired
+const PENDING_SWAP_TIMEOUT_SECS: i64 = 180;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Direction in which a balance crossed a threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crossing {
+    Above,
+    Below,
+}
+
+impl Crossing {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Crossing::Above => "above",
+            Crossing::Below => "below",
+        }
+    }
+}
+
+/// Whether a balance moving from `previous` to `current` crossed `threshold`.
+///
+/// Reaching the threshold exactly counts as being above it, so a balance
+/// sitting on the line fires once and not on every pass.
+pub fn crossed_threshold(previous: u64, current: u64, threshold: u64) -> Option<Crossing> {
+    match (previous >= threshold, current >= threshold) {
+        (false, true) => Some(Crossing::Above),
+        (true, false) => Some(Crossing::Below),
+        _ => None,
+    }
+}
+
+/// Lamports received between two observations, if the balance went up
+pub fn incoming_lamports(previous: u64, current: u64) -> Option<u64> {
+    current.checked_sub(previous).filter(|received| *received > 0)
+}
+
+/// Signed balance change between two observations, if there was one
+pub fn balance_change(previous: u64, current: u64) -> Option<i64> {
+    let change = current as i128 - previous as i128;
+    (change != 0).then(|| change.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+}
+
+fn sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL
+}
+
+/// Watches wallets of users with webhooks and raises webhook events.
+pub struct WalletActivityWatcher {
+    db: DbPool,
+    rpc: Arc<SolanaClient>,
+    webhooks: Arc<WebhookService>,
+    /// Last seen SOL balance per wallet, and when it was seen
+    balances: Mutex<HashMap<String, (u64, DateTime<Utc>)>>,
+}
+
+impl WalletActivityWatcher {
+    /// Create a new watcher.
+    ///
+    /// # Arguments
+    /// * `db` - Database pool with users, swaps and webhooks
+    /// * `rpc` - Solana RPC client used to read balances and signature statuses
+    /// * `webhooks` - Service that delivers the raised events
+    pub fn new(db: DbPool, rpc: Arc<SolanaClient>, webhooks: Arc<WebhookService>) -> Self {
+        Self {
+            db,
+            rpc,
+            webhooks,
+            balances: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run the watcher loop until the process exits.
+    pub async fn start(self: Arc<Self>) {
+        let interval_secs = std::env::var("WALLET_ACTIVITY_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_INTERVAL_SECS);
+
+        info!(interval_secs, "Wallet activity watcher started");
+
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.check_all().await {
+                warn!(error = %e, "Wallet activity pass failed");
+            }
+        }
+    }
+
+    /// Check every watched user once.
+    pub async fn check_all(&self) -> Result<(), AppError> {
+        let user_ids = WebhookRepository::find_user_ids(&self.db).await.map_err(db_error)?;
+        for user_id in user_ids {
+            if let Err(e) = self.check_user(user_id).await {
+                warn!(user_id, error = %e, "Wallet activity check failed");
+            }
+        }
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn check_user(&self, user_id: i64) -> Result<(), AppError> {
+        let Some(wallet) = UserRepository::find_by_id(&self.db, user_id)
+            .await
+            .map_err(db_error)?
+            .and_then(|user| user.wallet_address)
+        else {
+            return Ok(());
+        };
+
+        self.check_balance(user_id, &wallet).await?;
+        self.check_pending_swaps(user_id, &wallet).await
+    }
+
+    async fn check_balance(&self, user_id: i64, wallet: &str) -> Result<(), AppError> {
+        let pubkey = Pubkey::from_str(wallet)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid wallet address: {}", e)))?;
+        let current = self
+            .rpc
+            .get_account(&pubkey)
+            .await
+            .map(|account| account.lamports)
+            // An account that was never funded doesn't exist yet
+            .unwrap_or(0);
+
+        let now = Utc::now();
+        let previous = self.balances.lock().await.insert(wallet.to_string(), (current, now));
+        let Some((previous, previous_at)) = previous else {
+            debug!(wallet, lamports = current, "Recorded baseline balance");
+            return Ok(());
+        };
+
+        if let Some(lamports) = balance_change(previous, current) {
+            self.record_transfer(user_id, wallet, lamports, previous_at, now).await?;
+        }
+
+        if let Some(received) = incoming_lamports(previous, current) {
+            let data = serde_json::json!({
+                "amount_sol": sol(received),
+                "balance_sol": sol(current),
+            });
+            self.webhooks
+                .notify(user_id, wallet, WebhookEventType::IncomingTransfer, data, |_| true)
+                .await?;
+        }
+
+        let webhooks = WebhookRepository::find_by_user(&self.db, user_id).await.map_err(db_error)?;
+        for webhook in webhooks {
+            let Some(threshold) = webhook.balance_threshold_lamports.and_then(|t| u64::try_from(t).ok()) else {
+                continue;
+            };
+            if let Some(crossing) = crossed_threshold(previous, current, threshold) {
+                let data = serde_json::json!({
+                    "direction": crossing.as_str(),
+                    "threshold_sol": sol(threshold),
+                    "balance_sol": sol(current),
+                });
+                let webhook_id = webhook.id;
+                self.webhooks
+                    .notify(user_id, wallet, WebhookEventType::BalanceThreshold, data, |w| w.id == webhook_id)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Store a balance change as a deposit or withdrawal, unless a swap may
+    /// explain it
+    async fn record_transfer(
+        &self,
+        user_id: i64,
+        wallet: &str,
+        lamports: i64,
+        previous_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let since = previous_at - chrono::Duration::seconds(PENDING_SWAP_TIMEOUT_SECS);
+        let swaps = SwapRepository::find_created_between(&self.db, user_id, since, now)
+            .await
+            .map_err(db_error)?;
+        if !swaps.is_empty() {
+            debug!(wallet, lamports, "Balance change left to the user's swaps");
+            return Ok(());
+        }
+        WalletTransferRepository::create(&self.db, user_id, wallet, lamports, now)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn check_pending_swaps(&self, user_id: i64, wallet: &str) -> Result<(), AppError> {
+        let swaps = SwapRepository::find_by_user(&self.db, user_id, Some(PENDING_SWAPS_CHECKED))
+            .await
+            .map_err(db_error)?;
+
+        for swap in swaps.into_iter().filter(|s| s.status == SwapStatus::Pending) {
+            let status = self
+                .rpc
+                .get_signature_status(&swap.signature)
+                .await
+                .map_err(|e| AppError::Rpc(e.to_string()))?;
+
+            match status {
+                Some(Ok(())) => {
+                    SwapRepository::update_status(&self.db, &swap.signature, SwapStatus::Confirmed, None)
+                        .await
+                        .map_err(db_error)?;
+                    self.webhooks
+                        .notify(user_id, wallet, WebhookEventType::SwapConfirmed, swap_data(&swap), |_| true)
+                        .await?;
+                }
+                Some(Err(error)) => {
+                    SwapRepository::update_status(&self.db, &swap.signature, SwapStatus::Failed, Some(&error))
+                        .await
+                        .map_err(db_error)?;
+                }
+                None if (chrono::Utc::now() - swap.created_at).num_seconds() > PENDING_SWAP_TIMEOUT_SECS => {
+                    SwapRepository::update_status(
+                        &self.db,
+                        &swap.signature,
+                        SwapStatus::Failed,
+                        Some("Transaction expired before it landed"),
+                    )
+                    .await
+                    .map_err(db_error)?;
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn swap_data(swap: &Swap) -> serde_json::Value {
+    serde_json::json!({
+        "signature": swap.signature,
+        "input_mint": swap.input_mint,
+        "output_mint": swap.output_mint,
+        "input_amount": swap.input_amount,
+        "output_amount": swap.output_amount,
+    })
+}
+
+fn db_error(e: sqlx::Error) -> AppError {
+    AppError::Internal(format!("Wallet activity database error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_crossing() {
+        let line = 1_000;
+        assert_eq!(crossed_threshold(900, 1_000, line), Some(Crossing::Above));
+        assert_eq!(crossed_threshold(1_000, 999, line), Some(Crossing::Below));
+        assert_eq!(crossed_threshold(1_000, 1_500, line), None);
+        assert_eq!(crossed_threshold(100, 900, line), None);
+    }
+
+    #[test]
+    fn test_incoming_lamports() {
+        assert_eq!(incoming_lamports(100, 250), Some(150));
+        assert_eq!(incoming_lamports(250, 100), None);
+        assert_eq!(incoming_lamports(250, 250), None);
+    }
+
+    #[test]
+    fn test_balance_change() {
+        assert_eq!(balance_change(100, 250), Some(150));
+        assert_eq!(balance_change(250, 100), Some(-150));
+        assert_eq!(balance_change(250, 250), None);
+        assert_eq!(balance_change(0, u64::MAX), Some(i64::MAX));
+    }
+}
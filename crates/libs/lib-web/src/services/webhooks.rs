@@ -0,0 +1,871 @@
+//! # Webhook Service
+//!
+//! Registers outgoing webhooks and delivers wallet activity events to them.
+//!
+//! ## Delivery
+//!
+//! Events come from [`crate::services::wallet_activity`] (incoming transfers,
+//! confirmed swaps, balance thresholds) or the "send test event" button. For
+//! every webhook subscribed to the event, [`WebhookDispatcher`] POSTs a
+//! [`WebhookPayload`] signed with the webhook's secret (see
+//! [`shared::dto::webhooks`] for the headers) and retries failures with
+//! exponential backoff:
+//!
+//! ```text
+//! attempt 1 ── fail ──> wait 1s ──> attempt 2 ── fail ──> wait 2s ──> ... attempt 5 ──> give up
+//! ```
+//!
+//! Network errors, timeouts, 408, 429 and 5xx answers are retried; any other
+//! status means the receiver rejected the payload and retrying won't help.
+//! Each event ends up as one row in the webhook's delivery log, of which the
+//! newest [`DELIVERY_LOG_SIZE`] are kept.
+//!
+//! Events are delivered in the background so a slow receiver never holds up
+//! the activity watcher.
+//!
+//! ## Targets
+//!
+//! Webhook URLs are user input, so the server must not become a way into its
+//! own network. Receivers have to resolve to public addresses only: loopback,
+//! private, link-local, unspecified and similar ranges are refused when the
+//! webhook is registered and again before every delivery, and the dispatcher's
+//! resolver refuses them on connect so a name can't be rebound in between.
+//! Local receivers (development, tests) need
+//! [`WebhookConfig::allow_private_targets`], set by
+//! `WEBHOOK_ALLOW_PRIVATE_TARGETS=true`.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use hmac::{Hmac, Mac};
+use lib_core::model::store::models::{Webhook, WebhookDelivery};
+use lib_core::model::store::{UserRepository, WebhookRepository};
+use lib_core::{AppError, DbPool};
+use reqwest::StatusCode;
+use sha2::Sha256;
+use shared::dto::webhooks::{
+    CreateWebhookRequest, CreateWebhookResponse, WebhookDeliveriesResponse, WebhookDeliveryInfo, WebhookEventType,
+    WebhookInfo, WebhookListResponse, WebhookPayload, MAX_WEBHOOKS_PER_USER,
+};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, instrument, warn};
+use uuid::Uuid;
+
+/// Header carrying `sha256=<hex HMAC>` of `"{timestamp}.{body}"`
+pub const SIGNATURE_HEADER: &str = "X-Xforce-Signature";
+
+/// Header carrying the Unix seconds the signature was made at
+pub const TIMESTAMP_HEADER: &str = "X-Xforce-Timestamp";
+
+/// Header carrying the event name
+pub const EVENT_HEADER: &str = "X-Xforce-Event";
+
+/// Delivery log rows kept per webhook
+pub const DELIVERY_LOG_SIZE: i64 = 50;
+
+/// How long one attempt may take before it counts as failed
+const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest accepted webhook URL
+const MAX_URL_LEN: usize = 2048;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// `sha256=` + hex HMAC-SHA256 of `"{timestamp}.{body}"` keyed with `secret`
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    let hex: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+/// A new random signing secret
+pub fn new_secret() -> String {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(Uuid::new_v4().as_bytes());
+    bytes[16..].copy_from_slice(Uuid::new_v4().as_bytes());
+    format!("whsec_{}", URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// How often and how patiently to retry a delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts including the first one
+    pub max_attempts: u32,
+    /// Wait before the second attempt; doubles after every failure
+    pub base_delay: Duration,
+    /// Longest wait between two attempts
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A single attempt, for test events the user is waiting on
+    pub fn once() -> Self {
+        Self { max_attempts: 1, ..Self::default() }
+    }
+
+    /// Wait before attempt number `attempt` (1-based); zero for the first
+    pub fn delay_before(&self, attempt: u32) -> Duration {
+        if attempt <= 1 {
+            return Duration::ZERO;
+        }
+        let factor = 1u32.checked_shl(attempt - 2).unwrap_or(u32::MAX);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// Who webhooks may be delivered to, and how patiently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WebhookConfig {
+    pub retry: RetryPolicy,
+    /// Deliver to loopback, private and link-local addresses too.
+    /// Only for local development and tests.
+    pub allow_private_targets: bool,
+}
+
+impl WebhookConfig {
+    /// Default retries; private targets only with `WEBHOOK_ALLOW_PRIVATE_TARGETS=true`
+    pub fn from_env() -> Self {
+        let allow_private_targets = std::env::var("WEBHOOK_ALLOW_PRIVATE_TARGETS")
+            .is_ok_and(|value| value.trim().eq_ignore_ascii_case("true") || value.trim() == "1");
+        Self { allow_private_targets, ..Self::default() }
+    }
+}
+
+/// Whether `ip` is a public address webhooks may be delivered to
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                // 0.0.0.0/8 "this network"
+                || a == 0
+                // 100.64.0.0/10 carrier-grade NAT
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // fc00::/7 unique local
+                || (first & 0xfe00) == 0xfc00
+                // fe80::/10 link-local
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Resolve `host`, failing unless every address it resolves to is public.
+///
+/// All addresses must pass, otherwise a name could hide an internal address
+/// next to a public one.
+async fn resolve_public(host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+    let lower = host.to_ascii_lowercase();
+    if lower == "localhost" || lower.ends_with(".localhost") {
+        return Err(format!("Webhook host {} is not a public address", host));
+    }
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("Could not resolve webhook host {}: {}", host, e))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("Could not resolve webhook host {}", host));
+    }
+    if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(format!("Webhook host {} resolves to non-public address {}", host, addr.ip()));
+    }
+    Ok(addrs)
+}
+
+/// Check that `url` points at a public host, resolving its name
+pub async fn check_public_target(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    let host = parsed.host_str().ok_or_else(|| "Webhook URL must have a host".to_string())?;
+    // IPv6 literals come bracketed
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    resolve_public(host, parsed.port_or_known_default().unwrap_or(443)).await.map(|_| ())
+}
+
+/// Resolver used by the dispatcher's client, so a name that passed
+/// [`check_public_target`] can't be rebound to an internal address on connect
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            // reqwest fills in the port itself
+            let addrs: reqwest::dns::Addrs = Box::new(resolve_public(name.as_str(), 0).await?.into_iter());
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(addrs)
+        })
+    }
+}
+
+/// Whether an attempt that ended with `status` (`None`: no answer) is worth repeating
+pub fn is_retryable(status: Option<StatusCode>) -> bool {
+    match status {
+        None => true,
+        Some(status) => {
+            status.is_server_error()
+                || status == StatusCode::TOO_MANY_REQUESTS
+                || status == StatusCode::REQUEST_TIMEOUT
+        }
+    }
+}
+
+/// Events a webhook row is subscribed to
+pub fn parse_events(events: &str) -> Vec<WebhookEventType> {
+    events.split(',').filter_map(|name| WebhookEventType::parse(name.trim())).collect()
+}
+
+/// Whether `webhook` should receive `event`. Test events go to the webhook they were sent for.
+pub fn wants(webhook: &Webhook, event: WebhookEventType) -> bool {
+    event == WebhookEventType::Test || parse_events(&webhook.events).contains(&event)
+}
+
+/// Check a registration and turn it into the stored
+/// `(url, events, balance_threshold_lamports)`.
+///
+/// Only checks the URL's shape; whether its host is public needs a DNS lookup
+/// and is left to [`WebhookDispatcher::check_target`].
+pub fn validate_request(request: &CreateWebhookRequest) -> Result<(String, String, Option<i64>), AppError> {
+    let url = request.url.trim();
+    if url.len() > MAX_URL_LEN {
+        return Err(AppError::InvalidInput("Webhook URL is too long".to_string()));
+    }
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid webhook URL: {}", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(AppError::InvalidInput("Webhook URL must be http(s) with a host".to_string()));
+    }
+
+    let mut events: Vec<WebhookEventType> = Vec::new();
+    for event in &request.events {
+        if !WebhookEventType::SUBSCRIBABLE.contains(event) {
+            return Err(AppError::InvalidInput(format!("Can't subscribe to {} events", event.as_str())));
+        }
+        if !events.contains(event) {
+            events.push(*event);
+        }
+    }
+    if events.is_empty() {
+        return Err(AppError::InvalidInput("Pick at least one event".to_string()));
+    }
+
+    let threshold = match request.balance_threshold_sol {
+        Some(sol) if !(sol.is_finite() && sol > 0.0) => {
+            return Err(AppError::InvalidInput("Balance threshold must be a positive SOL amount".to_string()));
+        }
+        Some(sol) => Some((sol * LAMPORTS_PER_SOL).round() as i64),
+        None => None,
+    };
+    if events.contains(&WebhookEventType::BalanceThreshold) && threshold.is_none() {
+        return Err(AppError::InvalidInput(
+            "Balance threshold events need a threshold".to_string(),
+        ));
+    }
+
+    let events = events.iter().map(|e| e.as_str()).collect::<Vec<_>>().join(",");
+    Ok((parsed.to_string(), events, threshold))
+}
+
+/// Public view of a stored webhook
+pub fn webhook_info(webhook: &Webhook) -> WebhookInfo {
+    WebhookInfo {
+        id: webhook.id,
+        url: webhook.url.clone(),
+        events: parse_events(&webhook.events),
+        balance_threshold_sol: webhook.balance_threshold_lamports.map(|l| l as f64 / LAMPORTS_PER_SOL),
+        created_at: webhook.created_at.to_rfc3339(),
+    }
+}
+
+/// Public view of a delivery log row
+pub fn delivery_info(delivery: &WebhookDelivery) -> WebhookDeliveryInfo {
+    WebhookDeliveryInfo {
+        id: delivery.id,
+        event_id: delivery.event_id.clone(),
+        event: WebhookEventType::parse(&delivery.event).unwrap_or(WebhookEventType::Test),
+        delivered: delivery.delivered,
+        attempts: delivery.attempts.max(0) as u32,
+        response_status: delivery.response_status.and_then(|s| u16::try_from(s).ok()),
+        error: delivery.error.clone(),
+        created_at: delivery.created_at.to_rfc3339(),
+    }
+}
+
+/// Result of delivering one payload, after retries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryOutcome {
+    pub delivered: bool,
+    pub attempts: u32,
+    /// Status of the last attempt, if the receiver answered
+    pub response_status: Option<u16>,
+    /// Why the last attempt failed
+    pub error: Option<String>,
+}
+
+/// Signs and POSTs payloads, retrying with backoff
+pub struct WebhookDispatcher {
+    client: reqwest::Client,
+    retry: RetryPolicy,
+    allow_private_targets: bool,
+}
+
+impl WebhookDispatcher {
+    pub fn new(config: WebhookConfig) -> Self {
+        let mut builder = reqwest::Client::builder()
+            .timeout(ATTEMPT_TIMEOUT)
+            // A redirect would re-send the signed body somewhere the user didn't register
+            .redirect(reqwest::redirect::Policy::none());
+        if !config.allow_private_targets {
+            builder = builder.dns_resolver(Arc::new(PublicResolver));
+        }
+        Self {
+            client: builder.build().unwrap_or_default(),
+            retry: config.retry,
+            allow_private_targets: config.allow_private_targets,
+        }
+    }
+
+    /// Check that `url` may be delivered to under this dispatcher's config
+    pub async fn check_target(&self, url: &str) -> Result<(), String> {
+        if self.allow_private_targets {
+            return Ok(());
+        }
+        check_public_target(url).await
+    }
+
+    /// Deliver with the dispatcher's retry policy
+    pub async fn deliver(&self, url: &str, secret: &str, payload: &WebhookPayload) -> DeliveryOutcome {
+        self.deliver_with(url, secret, payload, self.retry).await
+    }
+
+    /// Deliver `payload` to `url`, making up to `retry.max_attempts` attempts.
+    ///
+    /// Every attempt is signed afresh, so receivers can reject old timestamps
+    /// without rejecting late retries. A URL that doesn't pass
+    /// [`Self::check_target`] (any more) isn't attempted at all.
+    #[instrument(skip(self, secret, payload), fields(event = payload.event.as_str(), event_id = %payload.id))]
+    pub async fn deliver_with(
+        &self,
+        url: &str,
+        secret: &str,
+        payload: &WebhookPayload,
+        retry: RetryPolicy,
+    ) -> DeliveryOutcome {
+        let body = match serde_json::to_vec(payload) {
+            Ok(body) => body,
+            Err(e) => {
+                return DeliveryOutcome {
+                    delivered: false,
+                    attempts: 0,
+                    response_status: None,
+                    error: Some(format!("Failed to encode payload: {}", e)),
+                };
+            }
+        };
+
+        if let Err(e) = self.check_target(url).await {
+            warn!(error = %e, "Webhook target refused");
+            return DeliveryOutcome { delivered: false, attempts: 0, response_status: None, error: Some(e) };
+        }
+
+        let mut outcome = DeliveryOutcome { delivered: false, attempts: 0, response_status: None, error: None };
+        for attempt in 1..=retry.max_attempts.max(1) {
+            tokio::time::sleep(retry.delay_before(attempt)).await;
+            outcome.attempts = attempt;
+
+            let timestamp = chrono::Utc::now().timestamp();
+            let result = self
+                .client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, payload.event.as_str())
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(SIGNATURE_HEADER, sign_payload(secret, timestamp, &body))
+                .body(body.clone())
+                .send()
+                .await;
+
+            let status = match result {
+                Ok(response) => {
+                    let status = response.status();
+                    outcome.response_status = Some(status.as_u16());
+                    if status.is_success() {
+                        outcome.delivered = true;
+                        outcome.error = None;
+                        debug!(attempt, status = status.as_u16(), "Webhook delivered");
+                        return outcome;
+                    }
+                    outcome.error = Some(format!("HTTP {}", status));
+                    Some(status)
+                }
+                Err(e) => {
+                    outcome.response_status = None;
+                    outcome.error = Some(if e.is_timeout() {
+                        "Timed out".to_string()
+                    } else {
+                        format!("Request failed: {}", e)
+                    });
+                    None
+                }
+            };
+
+            if !is_retryable(status) {
+                break;
+            }
+            debug!(attempt, error = ?outcome.error, "Webhook attempt failed");
+        }
+
+        warn!(attempts = outcome.attempts, error = ?outcome.error, "Webhook delivery failed");
+        outcome
+    }
+}
+
+/// Service for registering webhooks and fanning events out to them.
+///
+/// Held in the server state and shared with the activity watcher.
+pub struct WebhookService {
+    db: DbPool,
+    dispatcher: Arc<WebhookDispatcher>,
+}
+
+impl WebhookService {
+    pub fn new(db: DbPool, dispatcher: WebhookDispatcher) -> Self {
+        Self { db, dispatcher: Arc::new(dispatcher) }
+    }
+
+    /// Register a webhook.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CreateWebhookResponse)` - The webhook and its signing secret (only returned here)
+    /// * `Err(AppError::InvalidInput)` - Bad or non-public URL, bad events, or the per-user limit is reached
+    #[instrument(skip(self, request))]
+    pub async fn create(&self, user_id: i64, request: CreateWebhookRequest) -> Result<CreateWebhookResponse, AppError> {
+        let (url, events, threshold) = validate_request(&request)?;
+        self.dispatcher.check_target(&url).await.map_err(AppError::InvalidInput)?;
+        let existing = WebhookRepository::find_by_user(&self.db, user_id).await.map_err(db_error)?;
+        if existing.len() >= MAX_WEBHOOKS_PER_USER {
+            return Err(AppError::InvalidInput(format!(
+                "At most {} webhooks per account",
+                MAX_WEBHOOKS_PER_USER
+            )));
+        }
+
+        let secret = new_secret();
+        let webhook = WebhookRepository::create(&self.db, user_id, &url, &secret, &events, threshold)
+            .await
+            .map_err(db_error)?;
+        debug!(webhook_id = webhook.id, events = %webhook.events, "Webhook registered");
+        Ok(CreateWebhookResponse { webhook: webhook_info(&webhook), secret })
+    }
+
+    /// The user's webhooks, oldest first
+    pub async fn list(&self, user_id: i64) -> Result<WebhookListResponse, AppError> {
+        let webhooks = WebhookRepository::find_by_user(&self.db, user_id).await.map_err(db_error)?;
+        Ok(WebhookListResponse { webhooks: webhooks.iter().map(webhook_info).collect() })
+    }
+
+    /// Remove a webhook and its delivery log
+    pub async fn delete(&self, user_id: i64, id: i64) -> Result<(), AppError> {
+        if WebhookRepository::delete(&self.db, id, user_id).await.map_err(db_error)? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound("Webhook not found".to_string()))
+        }
+    }
+
+    /// Delivery log of one of the user's webhooks, newest first
+    pub async fn deliveries(&self, user_id: i64, id: i64) -> Result<WebhookDeliveriesResponse, AppError> {
+        let webhook = self.find(user_id, id).await?;
+        let deliveries = WebhookRepository::find_deliveries(&self.db, webhook.id, DELIVERY_LOG_SIZE)
+            .await
+            .map_err(db_error)?;
+        Ok(WebhookDeliveriesResponse { deliveries: deliveries.iter().map(delivery_info).collect() })
+    }
+
+    /// Send a test event and wait for the single attempt's outcome
+    #[instrument(skip(self))]
+    pub async fn send_test(&self, user_id: i64, id: i64) -> Result<WebhookDeliveryInfo, AppError> {
+        let webhook = self.find(user_id, id).await?;
+        let wallet = UserRepository::find_by_id(&self.db, user_id)
+            .await
+            .map_err(db_error)?
+            .and_then(|user| user.wallet_address)
+            .unwrap_or_default();
+        let payload = new_payload(
+            WebhookEventType::Test,
+            &wallet,
+            serde_json::json!({ "message": "Test event from XForce Terminal" }),
+        );
+        let delivery = deliver_and_record(&self.db, &self.dispatcher, &webhook, &payload, RetryPolicy::once()).await?;
+        Ok(delivery_info(&delivery))
+    }
+
+    /// Deliver `event` to every webhook of `user_id` subscribed to it, in the background.
+    ///
+    /// `applies` narrows the webhooks further, e.g. to those whose balance
+    /// threshold was crossed.
+    pub async fn notify(
+        &self,
+        user_id: i64,
+        wallet: &str,
+        event: WebhookEventType,
+        data: serde_json::Value,
+        applies: impl Fn(&Webhook) -> bool,
+    ) -> Result<usize, AppError> {
+        let webhooks = WebhookRepository::find_by_user(&self.db, user_id).await.map_err(db_error)?;
+        let payload = new_payload(event, wallet, data);
+        let mut sent = 0;
+        for webhook in webhooks.into_iter().filter(|w| wants(w, event) && applies(w)) {
+            let db = self.db.clone();
+            let dispatcher = Arc::clone(&self.dispatcher);
+            let payload = payload.clone();
+            let retry = dispatcher.retry;
+            tokio::spawn(async move {
+                if let Err(e) = deliver_and_record(&db, &dispatcher, &webhook, &payload, retry).await {
+                    error!(webhook_id = webhook.id, "Failed to log webhook delivery: {}", e);
+                }
+            });
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    async fn find(&self, user_id: i64, id: i64) -> Result<Webhook, AppError> {
+        WebhookRepository::find_for_user(&self.db, id, user_id)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| AppError::NotFound("Webhook not found".to_string()))
+    }
+}
+
+/// A payload with a fresh event id
+fn new_payload(event: WebhookEventType, wallet: &str, data: serde_json::Value) -> WebhookPayload {
+    WebhookPayload {
+        id: Uuid::new_v4().to_string(),
+        event,
+        wallet: wallet.to_string(),
+        timestamp: chrono::Utc::now().timestamp(),
+        data,
+    }
+}
+
+/// Deliver one payload and log the outcome
+async fn deliver_and_record(
+    db: &DbPool,
+    dispatcher: &WebhookDispatcher,
+    webhook: &Webhook,
+    payload: &WebhookPayload,
+    retry: RetryPolicy,
+) -> Result<WebhookDelivery, AppError> {
+    let outcome = dispatcher.deliver_with(&webhook.url, &webhook.secret, payload, retry).await;
+    WebhookRepository::record_delivery(
+        db,
+        webhook.id,
+        &payload.id,
+        payload.event.as_str(),
+        outcome.delivered,
+        outcome.attempts as i64,
+        outcome.response_status.map(i64::from),
+        outcome.error.as_deref(),
+        DELIVERY_LOG_SIZE,
+    )
+    .await
+    .map_err(db_error)
+}
+
+fn db_error(e: sqlx::Error) -> AppError {
+    AppError::Internal(format!("Webhook database error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::HeaderMap, routing::post, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Headers and body of one received POST
+    type Received = (HeaderMap, Vec<u8>);
+
+    /// Local receiver answering with `statuses` in turn (the last one repeats)
+    /// and recording what it was sent
+    #[derive(Clone)]
+    struct MockReceiver {
+        statuses: Arc<Vec<u16>>,
+        hits: Arc<AtomicUsize>,
+        requests: Arc<Mutex<Vec<Received>>>,
+    }
+
+    async fn receive(State(mock): State<MockReceiver>, headers: HeaderMap, body: axum::body::Bytes) -> StatusCode {
+        let hit = mock.hits.fetch_add(1, Ordering::SeqCst);
+        mock.requests.lock().unwrap().push((headers, body.to_vec()));
+        let status = mock.statuses.get(hit).or(mock.statuses.last()).copied().unwrap_or(200);
+        StatusCode::from_u16(status).unwrap()
+    }
+
+    async fn start_receiver(statuses: &[u16]) -> (String, MockReceiver) {
+        let mock = MockReceiver {
+            statuses: Arc::new(statuses.to_vec()),
+            hits: Arc::new(AtomicUsize::new(0)),
+            requests: Arc::new(Mutex::new(Vec::new())),
+        };
+        let app = Router::new().route("/hook", post(receive)).with_state(mock.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/hook", addr), mock)
+    }
+
+    fn fast_retry(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(5),
+            max_delay: Duration::from_millis(20),
+        }
+    }
+
+    /// Dispatcher allowed to reach the local mock receivers
+    fn local_dispatcher(max_attempts: u32) -> WebhookDispatcher {
+        WebhookDispatcher::new(WebhookConfig { retry: fast_retry(max_attempts), allow_private_targets: true })
+    }
+
+    fn payload(event: WebhookEventType) -> WebhookPayload {
+        new_payload(event, "WalletAddress111", serde_json::json!({ "lamports": 1_500_000_000u64 }))
+    }
+
+    fn webhook(events: &str, threshold: Option<i64>) -> Webhook {
+        Webhook {
+            id: 1,
+            user_id: 1,
+            url: "https://bot.example/hook".to_string(),
+            secret: "s3cret".to_string(),
+            events: events.to_string(),
+            balance_threshold_lamports: threshold,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_signature_known_answer() {
+        // HMAC-SHA256(key = "s3cret", msg = "1700000000.{\"a\":1}")
+        assert_eq!(
+            sign_payload("s3cret", 1_700_000_000, br#"{"a":1}"#),
+            "sha256=1698a50bc74d1ff1db85c4e0a5297c2ad9fdba245d5737cdb789e4cc6e098940"
+        );
+        assert_ne!(sign_payload("s3cret", 1_700_000_001, br#"{"a":1}"#), sign_payload("s3cret", 1_700_000_000, br#"{"a":1}"#));
+        assert_ne!(sign_payload("other", 1_700_000_000, br#"{"a":1}"#), sign_payload("s3cret", 1_700_000_000, br#"{"a":1}"#));
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        let retry = RetryPolicy::default();
+        let delays: Vec<u64> = (1..=8).map(|attempt| retry.delay_before(attempt).as_secs()).collect();
+        assert_eq!(delays, vec![0, 1, 2, 4, 8, 16, 32, 60]);
+        assert_eq!(retry.delay_before(200), retry.max_delay);
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        assert!(is_retryable(None));
+        assert!(is_retryable(Some(StatusCode::INTERNAL_SERVER_ERROR)));
+        assert!(is_retryable(Some(StatusCode::TOO_MANY_REQUESTS)));
+        assert!(!is_retryable(Some(StatusCode::BAD_REQUEST)));
+        assert!(!is_retryable(Some(StatusCode::GONE)));
+    }
+
+    #[test]
+    fn test_event_filtering() {
+        let hook = webhook("incoming_transfer,balance_threshold", Some(1));
+        assert!(wants(&hook, WebhookEventType::IncomingTransfer));
+        assert!(wants(&hook, WebhookEventType::BalanceThreshold));
+        assert!(!wants(&hook, WebhookEventType::SwapConfirmed));
+        assert!(wants(&hook, WebhookEventType::Test));
+        assert!(!wants(&webhook("", None), WebhookEventType::SwapConfirmed));
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let request = |url: &str, events: Vec<WebhookEventType>, threshold: Option<f64>| CreateWebhookRequest {
+            url: url.to_string(),
+            events,
+            balance_threshold_sol: threshold,
+        };
+        let (url, events, threshold) = validate_request(&request(
+            " https://bot.example/hook ",
+            vec![WebhookEventType::SwapConfirmed, WebhookEventType::BalanceThreshold, WebhookEventType::SwapConfirmed],
+            Some(1.5),
+        ))
+        .unwrap();
+        assert_eq!(url, "https://bot.example/hook");
+        assert_eq!(events, "swap_confirmed,balance_threshold");
+        assert_eq!(threshold, Some(1_500_000_000));
+
+        assert!(validate_request(&request("ftp://bot.example", vec![WebhookEventType::SwapConfirmed], None)).is_err());
+        assert!(validate_request(&request("not a url", vec![WebhookEventType::SwapConfirmed], None)).is_err());
+        assert!(validate_request(&request("https://bot.example", vec![], None)).is_err());
+        assert!(validate_request(&request("https://bot.example", vec![WebhookEventType::Test], None)).is_err());
+        assert!(validate_request(&request("https://bot.example", vec![WebhookEventType::BalanceThreshold], None)).is_err());
+        assert!(validate_request(&request("https://bot.example", vec![WebhookEventType::BalanceThreshold], Some(-1.0))).is_err());
+    }
+
+    #[test]
+    fn test_public_ips() {
+        for ip in ["93.184.215.14", "1.1.1.1", "2606:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{} should be public", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "100.64.0.1",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{} should not be public", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_public_targets_are_refused() {
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://localhost/hook",
+            "http://api.localhost/hook",
+            "http://10.0.0.5/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/hook",
+            "http://0.0.0.0/hook",
+        ] {
+            assert!(check_public_target(url).await.is_err(), "{} should be refused", url);
+        }
+        assert!(check_public_target("https://93.184.215.14/hook").await.is_ok());
+
+        let dispatcher = WebhookDispatcher::new(WebhookConfig::default());
+        assert!(dispatcher.check_target("http://127.0.0.1/hook").await.is_err());
+        assert!(local_dispatcher(1).check_target("http://127.0.0.1/hook").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_private_receiver_is_not_delivered_to_without_opt_in() {
+        let (url, mock) = start_receiver(&[200]).await;
+        let outcome = WebhookDispatcher::new(WebhookConfig { retry: fast_retry(3), allow_private_targets: false })
+            .deliver(&url, "s3cret", &payload(WebhookEventType::Test))
+            .await;
+        assert!(!outcome.delivered);
+        assert_eq!(outcome.attempts, 0);
+        assert!(outcome.error.unwrap().contains("non-public"));
+        assert_eq!(mock.hits.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_is_signed() {
+        let (url, mock) = start_receiver(&[200]).await;
+        let dispatcher = local_dispatcher(3);
+        let payload = payload(WebhookEventType::IncomingTransfer);
+
+        let outcome = dispatcher.deliver(&url, "s3cret", &payload).await;
+        assert_eq!(outcome, DeliveryOutcome { delivered: true, attempts: 1, response_status: Some(200), error: None });
+
+        let requests = mock.requests.lock().unwrap();
+        let (headers, body) = &requests[0];
+        let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+        assert_eq!(headers[SIGNATURE_HEADER].to_str().unwrap(), sign_payload("s3cret", timestamp, body));
+        assert_eq!(headers[EVENT_HEADER], "incoming_transfer");
+        assert_eq!(serde_json::from_slice::<WebhookPayload>(body).unwrap(), payload);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_retried_with_the_same_event() {
+        let (url, mock) = start_receiver(&[503, 500, 200]).await;
+        let dispatcher = local_dispatcher(5);
+
+        let outcome = dispatcher.deliver(&url, "s3cret", &payload(WebhookEventType::SwapConfirmed)).await;
+        assert!(outcome.delivered);
+        assert_eq!(outcome.attempts, 3);
+        assert_eq!(mock.hits.load(Ordering::SeqCst), 3);
+
+        let requests = mock.requests.lock().unwrap();
+        let ids: Vec<String> = requests
+            .iter()
+            .map(|(_, body)| serde_json::from_slice::<WebhookPayload>(body).unwrap().id)
+            .collect();
+        assert!(ids.iter().all(|id| id == &ids[0]));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let (url, mock) = start_receiver(&[500]).await;
+        let outcome = local_dispatcher(4)
+            .deliver(&url, "s3cret", &payload(WebhookEventType::Test))
+            .await;
+        assert_eq!(
+            outcome,
+            DeliveryOutcome {
+                delivered: false,
+                attempts: 4,
+                response_status: Some(500),
+                error: Some("HTTP 500 Internal Server Error".to_string()),
+            }
+        );
+        assert_eq!(mock.hits.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let (url, mock) = start_receiver(&[410]).await;
+        let outcome = local_dispatcher(5)
+            .deliver(&url, "s3cret", &payload(WebhookEventType::Test))
+            .await;
+        assert!(!outcome.delivered);
+        assert_eq!(outcome.attempts, 1);
+        assert_eq!(mock.hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_receiver_is_retried() {
+        // Bind and drop a listener so the port refuses connections
+        let addr = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let outcome = local_dispatcher(2)
+            .deliver(&format!("http://{}/hook", addr), "s3cret", &payload(WebhookEventType::Test))
+            .await;
+        assert!(!outcome.delivered);
+        assert_eq!(outcome.attempts, 2);
+        assert_eq!(outcome.response_status, None);
+        assert!(outcome.error.unwrap().starts_with("Request failed"));
+    }
+}
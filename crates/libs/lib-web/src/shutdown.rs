@@ -0,0 +1,223 @@
+//! # Graceful Shutdown
+//!
+//! On SIGINT or SIGTERM the server stops accepting connections and drains
+//! the ones it has before exiting:
+//!
+//! 1. [`Shutdown::trigger`] flips the switch every long-lived stream watches:
+//!    price WebSockets send a `1012 Service Restart` close frame and chat
+//!    subscriptions a final `closing` event, both with
+//!    [`SERVER_RESTARTING_REASON`], and end
+//! 2. The listener is closed, and in-flight HTTP requests (e.g. a swap
+//!    submission) are given up to `ServerConfig::shutdown_timeout` to finish
+//! 3. The database pool is closed, which waits for the connections still
+//!    checked out so their writes land
+//!
+//! Requests still running when the timeout expires are cut off.
+
+use axum::Router;
+use std::future::IntoFuture;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+pub use shared::dto::system::SERVER_RESTARTING_REASON;
+
+/// Switch flipped once when the server starts shutting down
+///
+/// Clones share the switch; anything holding one can wait for it with
+/// [`Shutdown::triggered`].
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self { tx: Arc::new(watch::channel(false).0) }
+    }
+
+    /// Start shutting down; later calls do nothing
+    pub fn trigger(&self) {
+        self.tx.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    /// Wait until [`Shutdown::trigger`] is called; returns at once if it was
+    pub async fn triggered(&self) {
+        let mut rx = self.tx.subscribe();
+        // The sender lives in `self`, so this can't fail
+        let _ = rx.wait_for(|triggered| *triggered).await;
+    }
+}
+
+/// Wait for SIGINT (Ctrl+C) or, on Unix, SIGTERM
+pub async fn os_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!(error = %e, "Failed to listen for Ctrl+C");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received Ctrl+C"),
+        _ = terminate => info!("Received SIGTERM"),
+    }
+}
+
+/// Serve `app` until `shutdown` is triggered, then drain for up to `drain_timeout`.
+///
+/// # Returns
+///
+/// `true` if every connection finished within the timeout, `false` if some
+/// were cut off.
+///
+/// # Errors
+///
+/// Returns an error if the server fails while accepting connections.
+pub async fn serve_with_drain(
+    listener: TcpListener,
+    app: Router,
+    shutdown: Shutdown,
+    drain_timeout: Duration,
+) -> std::io::Result<bool> {
+    let server = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown({
+            let shutdown = shutdown.clone();
+            async move { shutdown.triggered().await }
+        })
+        .into_future();
+    tokio::pin!(server);
+
+    tokio::select! {
+        result = &mut server => return result.map(|_| true),
+        _ = shutdown.triggered() => {}
+    }
+
+    info!(timeout_secs = drain_timeout.as_secs_f64(), "Stopped accepting connections, draining in-flight requests");
+    match tokio::time::timeout(drain_timeout, server).await {
+        Ok(result) => result.map(|_| true),
+        Err(_) => {
+            warn!(timeout_secs = drain_timeout.as_secs_f64(), "Drain timed out, cutting off the remaining requests");
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio::sync::Notify;
+
+    /// Server with a `/slow` route that takes `delay`, and a notify fired
+    /// when a request to it has started
+    async fn start(delay: Duration, drain_timeout: Duration) -> (SocketAddr, Shutdown, Arc<Notify>, tokio::task::JoinHandle<std::io::Result<bool>>) {
+        let started = Arc::new(Notify::new());
+        let app = Router::new().route(
+            "/slow",
+            get({
+                let started = Arc::clone(&started);
+                move || async move {
+                    started.notify_one();
+                    tokio::time::sleep(delay).await;
+                    "done"
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = Shutdown::new();
+        let server = tokio::spawn(serve_with_drain(listener, app, shutdown.clone(), drain_timeout));
+        (addr, shutdown, started, server)
+    }
+
+    /// Raw HTTP/1.1 GET, returning whatever the server sent before closing
+    async fn get_slow(addr: SocketAddr) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /slow HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response).await;
+        response
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_request_completes_during_drain() {
+        let (addr, shutdown, started, server) = start(Duration::from_millis(300), Duration::from_secs(5)).await;
+
+        let request = tokio::spawn(get_slow(addr));
+        started.notified().await;
+        shutdown.trigger();
+
+        let response = request.await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.ends_with("done"), "{}", response);
+        assert!(server.await.unwrap().unwrap());
+
+        // The listener is gone
+        assert!(TcpStream::connect(addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_drain_gives_up_after_timeout() {
+        let (addr, shutdown, started, server) = start(Duration::from_secs(30), Duration::from_millis(100)).await;
+
+        let request = tokio::spawn(get_slow(addr));
+        started.notified().await;
+        shutdown.trigger();
+
+        assert!(!server.await.unwrap().unwrap());
+        assert!(!request.is_finished());
+        request.abort();
+    }
+
+    #[tokio::test]
+    async fn test_triggered_returns_once_triggered() {
+        let shutdown = Shutdown::new();
+        assert!(!shutdown.is_triggered());
+
+        let waiter = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.triggered().await }
+        });
+        shutdown.trigger();
+        shutdown.trigger();
+        waiter.await.unwrap();
+
+        assert!(shutdown.is_triggered());
+        // Late waiters don't block
+        shutdown.triggered().await;
+    }
+}
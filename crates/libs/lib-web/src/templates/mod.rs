@@ -0,0 +1,10 @@
+//! # HTML Templates
+//!
+//! Server-rendered pages for people without the terminal, written with
+//! [`maud`] so user data is escaped by default, and printable documents.
+//!
+//! - [`share`] - Public share link pages (chart and portfolio snapshots)
+//! - [`monthly_report`] - Monochrome monthly account report (PDF)
+
+pub mod share;
+pub mod monthly_report;
@@ -0,0 +1,558 @@
+//! # Monthly Report Template
+//!
+//! The PDF behind `GET /api/reports/monthly`: plain black text in a
+//! monospaced face on A4, meant to be printed and filed.
+//!
+//! The report is first laid out as pages of fixed-width text lines
+//! ([`layout`]), which is also what the golden-file test compares; the PDF
+//! only places those lines. PDF bytes themselves aren't reproducible (the
+//! writer stamps random document ids and the creation time), the text is.
+//!
+//! Long tables continue on the next page under a repeated header, and every
+//! page ends with the account, the month and "Page n of m".
+
+use crate::services::reports::{MonthlyReport, ReportAmount, ReportHolding, ReportSnapshot};
+use lib_core::AppError;
+use lib_core::model::store::models::SwapStatus;
+use printpdf::{BuiltinFont, Mm, PdfDocument};
+
+/// Characters per line
+pub const LINE_WIDTH: usize = 80;
+/// Lines per page, footer included
+pub const LINES_PER_PAGE: usize = 56;
+/// Body lines per page; the rest is a blank line and the footer
+const BODY_LINES: usize = LINES_PER_PAGE - 2;
+
+const PAGE_WIDTH_MM: f32 = 210.0;
+const PAGE_HEIGHT_MM: f32 = 297.0;
+/// 80 Courier characters at 10pt are 169mm wide
+const MARGIN_LEFT_MM: f32 = 20.0;
+const MARGIN_TOP_MM: f32 = 18.0;
+const FONT_SIZE_PT: f32 = 10.0;
+const LINE_HEIGHT_MM: f32 = 4.6;
+
+/// Column widths of the swaps table: date, sold, bought, value, status
+const SWAP_COLUMNS: [usize; 5] = [16, 19, 19, 13, 9];
+/// Column widths of the holdings table: token, opening, closing, price, value
+const HOLDING_COLUMNS: [usize; 5] = [10, 18, 18, 14, 16];
+/// Column widths of the transfers table: date, direction, amount, value
+const TRANSFER_COLUMNS: [usize; 4] = [16, 12, 24, 24];
+
+/// One line of a page
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportLine {
+    pub text: String,
+    pub bold: bool,
+}
+
+impl ReportLine {
+    fn plain(text: impl Into<String>) -> Self {
+        Self { text: text.into(), bold: false }
+    }
+
+    fn bold(text: impl Into<String>) -> Self {
+        Self { text: text.into(), bold: true }
+    }
+
+    fn blank() -> Self {
+        Self::plain("")
+    }
+}
+
+/// Lines of one page, footer included
+pub type ReportPage = Vec<ReportLine>;
+
+/// Fills pages, repeating the current table's header after a page break
+struct Pager {
+    pages: Vec<ReportPage>,
+    page: ReportPage,
+    /// Heading and column header lines of the table being written
+    table: Option<Vec<ReportLine>>,
+}
+
+impl Pager {
+    fn new() -> Self {
+        Self { pages: Vec::new(), page: Vec::new(), table: None }
+    }
+
+    fn push(&mut self, line: ReportLine) {
+        if self.page.len() == BODY_LINES {
+            self.break_page();
+        }
+        self.page.push(line);
+    }
+
+    fn break_page(&mut self) {
+        self.pages.push(std::mem::take(&mut self.page));
+        if let Some(header) = &self.table {
+            self.page.extend(header.iter().cloned());
+        }
+    }
+
+    /// Start a section, on a new page unless `keep` more lines fit on this one
+    fn section(&mut self, title: &str, keep: usize) {
+        self.table = None;
+        if !self.page.is_empty() {
+            if self.page.len() + 2 + keep > BODY_LINES {
+                self.break_page();
+            } else {
+                self.push(ReportLine::blank());
+            }
+        }
+        self.push(ReportLine::bold(title));
+        self.push(ReportLine::plain(rule()));
+    }
+
+    /// Start a table under the current section with its column header
+    fn table(&mut self, title: &str, columns: &[(&str, usize, Align)]) {
+        let header = columns
+            .iter()
+            .map(|&(name, width, align)| (name.to_string(), width, align))
+            .collect::<Vec<_>>();
+        let header = vec![ReportLine::bold(format!("{} (continued)", title)), ReportLine::plain(row(&header))];
+        self.push(header[1].clone());
+        self.table = Some(header);
+    }
+
+    fn finish(mut self, footer: impl Fn(usize, usize) -> String) -> Vec<ReportPage> {
+        if !self.page.is_empty() {
+            self.pages.push(self.page);
+        }
+        let total = self.pages.len();
+        for (i, page) in self.pages.iter_mut().enumerate() {
+            page.resize(BODY_LINES, ReportLine::blank());
+            page.push(ReportLine::blank());
+            page.push(ReportLine::plain(footer(i + 1, total)));
+        }
+        self.pages
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Align {
+    Left,
+    Right,
+}
+
+/// Cells padded to their widths and joined by a space; overlong cells are cut
+fn row(cells: &[(String, usize, Align)]) -> String {
+    let line = cells
+        .iter()
+        .map(|(text, width, align)| {
+            let text = fit(text, *width);
+            match align {
+                Align::Left => format!("{:<width$}", text, width = width),
+                Align::Right => format!("{:>width$}", text, width = width),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    line.trim_end().to_string()
+}
+
+fn fit(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(width.saturating_sub(2)).collect();
+    format!("{}..", cut)
+}
+
+fn rule() -> String {
+    "-".repeat(LINE_WIDTH)
+}
+
+/// A label on the left and its value flush right
+fn label_value(label: &str, value: &str) -> ReportLine {
+    let width = LINE_WIDTH - 2 - label.len();
+    ReportLine::plain(format!("  {}{:>width$}", label, value, width = width))
+}
+
+/// Digits grouped by thousands: `1234567.891` with 2 decimals is `1,234,567.89`
+pub fn group_thousands(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (whole, fraction) = formatted.split_once('.').map_or((formatted.as_str(), None), |(w, f)| (w, Some(f)));
+    let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    // A value that rounds to zero isn't negative
+    let negative = value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0');
+    let sign = if negative { "-" } else { "" };
+    match fraction {
+        Some(fraction) => format!("{}{}.{}", sign, grouped, fraction),
+        None => format!("{}{}", sign, grouped),
+    }
+}
+
+/// `$1,234.56`, `-$1,234.56`
+pub fn usd(value: f64) -> String {
+    let amount = group_thousands(value, 2);
+    match amount.strip_prefix('-') {
+        Some(amount) => format!("-${}", amount),
+        None => format!("${}", amount),
+    }
+}
+
+/// `+$1,234.56`, `-$1,234.56`; zero has no sign
+pub fn signed_usd(value: f64) -> String {
+    let formatted = usd(value);
+    if formatted.starts_with('-') || formatted == "$0.00" {
+        formatted
+    } else {
+        format!("+{}", formatted)
+    }
+}
+
+/// A token quantity with as many decimals as its size calls for
+pub fn quantity(value: f64) -> String {
+    let decimals = match value.abs() {
+        v if v >= 1_000.0 => 2,
+        v if v >= 1.0 => 4,
+        _ => 6,
+    };
+    group_thousands(value, decimals)
+}
+
+fn amount(amount: &ReportAmount) -> String {
+    format!("{} {}", quantity(amount.quantity), amount.symbol)
+}
+
+/// Per-token prices can be tiny; keep significant digits
+fn price(value: f64) -> String {
+    if value.abs() >= 1.0 {
+        usd(value)
+    } else {
+        format!("${:.8}", value)
+    }
+}
+
+fn optional_usd(value: Option<f64>) -> String {
+    value.map_or_else(|| "n/a".to_string(), usd)
+}
+
+fn status(status: &SwapStatus) -> &'static str {
+    match status {
+        SwapStatus::Pending => "pending",
+        SwapStatus::Confirmed => "confirmed",
+        SwapStatus::Failed => "failed",
+    }
+}
+
+/// Lay the report out as pages of text.
+pub fn layout(report: &MonthlyReport) -> Vec<ReportPage> {
+    let mut pager = Pager::new();
+    let period_end = if report.is_partial() { report.generated_at } else { report.month.end() };
+
+    pager.push(ReportLine::bold("XFORCE TERMINAL - MONTHLY ACCOUNT REPORT"));
+    pager.push(ReportLine::plain(rule()));
+    pager.push(label_value("Account", &report.account));
+    pager.push(label_value("Period", &report.month.label()));
+    pager.push(label_value(
+        "Covering (UTC)",
+        &format!(
+            "{} to {}",
+            report.month.start().format("%Y-%m-%d %H:%M"),
+            period_end.format("%Y-%m-%d %H:%M"),
+        ),
+    ));
+    pager.push(label_value("Generated (UTC)", &report.generated_at.format("%Y-%m-%d %H:%M").to_string()));
+    if report.is_partial() {
+        pager.push(ReportLine::plain("  The month isn't over yet; figures run up to when the report was generated."));
+    }
+
+    summary(&mut pager, report);
+    holdings(&mut pager, &report.opening, &report.closing);
+    swaps(&mut pager, report);
+    transfers(&mut pager, report);
+    notes(&mut pager, report);
+
+    let footer_left = format!("{} - {}", report.account, report.month.label());
+    pager.finish(|page, total| {
+        let right = format!("Page {} of {}", page, total);
+        let width = LINE_WIDTH - right.len();
+        format!("{:<width$}{}", fit(&footer_left, width - 1), right, width = width)
+    })
+}
+
+fn summary(pager: &mut Pager, report: &MonthlyReport) {
+    pager.section("SUMMARY", 8);
+    pager.push(label_value("Portfolio value at start", &usd(report.opening.value_usd)));
+    pager.push(label_value("Portfolio value at end", &usd(report.closing.value_usd)));
+    pager.push(label_value("Change", &signed_usd(report.closing.value_usd - report.opening.value_usd)));
+    pager.push(label_value("Realized PnL", &signed_usd(report.realized_pnl_usd)));
+    pager.push(label_value(
+        "Network fees paid",
+        &format!("{} SOL ({})", group_thousands(report.fees_sol, 6), usd(report.fees_usd)),
+    ));
+
+    let (deposits, withdrawals): (Vec<_>, Vec<_>) = report.transfers.iter().partition(|t| t.amount_sol > 0.0);
+    let total = |transfers: &[&crate::services::reports::ReportTransfer]| {
+        let sol: f64 = transfers.iter().map(|t| t.amount_sol.abs()).sum();
+        let value: f64 = transfers.iter().filter_map(|t| t.value_usd).map(f64::abs).sum();
+        format!("{} SOL ({})", quantity(sol), usd(value))
+    };
+    pager.push(label_value(&format!("Deposits ({})", deposits.len()), &total(&deposits)));
+    pager.push(label_value(&format!("Withdrawals ({})", withdrawals.len()), &total(&withdrawals)));
+
+    let count = |wanted: SwapStatus| report.swaps.iter().filter(|s| s.status == wanted).count();
+    pager.push(label_value(
+        "Swaps",
+        &format!(
+            "{} confirmed, {} failed, {} pending",
+            count(SwapStatus::Confirmed),
+            count(SwapStatus::Failed),
+            count(SwapStatus::Pending),
+        ),
+    ));
+}
+
+fn holdings(pager: &mut Pager, opening: &ReportSnapshot, closing: &ReportSnapshot) {
+    const TITLE: &str = "HOLDINGS";
+    pager.section(TITLE, 2);
+    let [token, open, close, mark, value] = HOLDING_COLUMNS;
+    pager.table(TITLE, &[
+        ("Token", token, Align::Left),
+        ("At start", open, Align::Right),
+        ("At end", close, Align::Right),
+        ("Price at end", mark, Align::Right),
+        ("Value at end", value, Align::Right),
+    ]);
+
+    // Closing order first, then what was sold out during the month
+    let mut symbols: Vec<&str> = closing.holdings.iter().map(|h| h.amount.symbol.as_str()).collect();
+    for holding in &opening.holdings {
+        if !symbols.contains(&holding.amount.symbol.as_str()) {
+            symbols.push(&holding.amount.symbol);
+        }
+    }
+    if symbols.is_empty() {
+        pager.push(ReportLine::plain("  No holdings from swaps."));
+    }
+    let find = |snapshot: &'_ ReportSnapshot, symbol: &str| -> Option<ReportHolding> {
+        snapshot.holdings.iter().find(|h| h.amount.symbol == symbol).cloned()
+    };
+    for symbol in symbols {
+        let start = find(opening, symbol);
+        let end = find(closing, symbol);
+        let held = |holding: &Option<ReportHolding>| holding.as_ref().map_or_else(|| "-".to_string(), |h| quantity(h.amount.quantity));
+        pager.push(ReportLine::plain(row(&[
+            (symbol.to_string(), token, Align::Left),
+            (held(&start), open, Align::Right),
+            (held(&end), close, Align::Right),
+            (end.as_ref().and_then(|h| h.price_usd).map_or_else(|| "n/a".to_string(), price), mark, Align::Right),
+            (
+                match &end {
+                    Some(h) => optional_usd(h.value_usd),
+                    None => usd(0.0),
+                },
+                value,
+                Align::Right,
+            ),
+        ])));
+    }
+    pager.table = None;
+}
+
+fn swaps(pager: &mut Pager, report: &MonthlyReport) {
+    const TITLE: &str = "SWAPS";
+    pager.section(TITLE, 2);
+    let [date, sold, bought, value, state] = SWAP_COLUMNS;
+    pager.table(TITLE, &[
+        ("Date (UTC)", date, Align::Left),
+        ("Sold", sold, Align::Right),
+        ("Bought", bought, Align::Right),
+        ("Value", value, Align::Right),
+        ("Status", state, Align::Left),
+    ]);
+    if report.swaps.is_empty() {
+        pager.push(ReportLine::plain("  No swaps this month."));
+    }
+    for swap in &report.swaps {
+        pager.push(ReportLine::plain(row(&[
+            (swap.created_at.format("%Y-%m-%d %H:%M").to_string(), date, Align::Left),
+            (amount(&swap.sold), sold, Align::Right),
+            (amount(&swap.bought), bought, Align::Right),
+            (optional_usd(swap.value_usd), value, Align::Right),
+            (status(&swap.status).to_string(), state, Align::Left),
+        ])));
+    }
+    pager.table = None;
+}
+
+fn transfers(pager: &mut Pager, report: &MonthlyReport) {
+    const TITLE: &str = "DEPOSITS AND WITHDRAWALS";
+    pager.section(TITLE, 2);
+    let [date, direction, sol, value] = TRANSFER_COLUMNS;
+    pager.table(TITLE, &[
+        ("Date (UTC)", date, Align::Left),
+        ("Direction", direction, Align::Left),
+        ("Amount (SOL)", sol, Align::Right),
+        ("Value", value, Align::Right),
+    ]);
+    if report.transfers.is_empty() {
+        pager.push(ReportLine::plain("  None recorded this month."));
+    }
+    for transfer in &report.transfers {
+        let kind = if transfer.amount_sol > 0.0 { "deposit" } else { "withdrawal" };
+        pager.push(ReportLine::plain(row(&[
+            (transfer.observed_at.format("%Y-%m-%d %H:%M").to_string(), date, Align::Left),
+            (kind.to_string(), direction, Align::Left),
+            (quantity(transfer.amount_sol.abs()), sol, Align::Right),
+            (optional_usd(transfer.value_usd.map(f64::abs)), value, Align::Right),
+        ])));
+    }
+    pager.table = None;
+}
+
+fn notes(pager: &mut Pager, report: &MonthlyReport) {
+    let mut lines = vec![
+        "Holdings and realized PnL replay the swaps made in the terminal, at",
+        "weighted-average cost. Tokens are valued at the price of their last swap",
+        "before that moment; \"n/a\" marks a token or swap without one.",
+        "Deposits and withdrawals are SOL balance changes seen outside of swaps.",
+    ];
+    if report.incomplete {
+        lines.push("Some swaps had no USD value or sold tokens acquired outside the terminal,");
+        lines.push("so cost basis and realized PnL don't cover everything.");
+    }
+    pager.section("NOTES", lines.len());
+    for line in lines {
+        pager.push(ReportLine::plain(format!("  {}", line)));
+    }
+}
+
+/// The text of every page, pages separated by a form feed line
+pub fn text_layer(pages: &[ReportPage]) -> String {
+    pages
+        .iter()
+        .map(|page| page.iter().map(|line| format!("{}\n", line.text)).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\u{c}\n")
+}
+
+/// Render the report as a PDF.
+pub fn render_pdf(report: &MonthlyReport) -> Result<Vec<u8>, AppError> {
+    let pdf_error = |e: printpdf::Error| AppError::Internal(format!("Failed to render report: {}", e));
+    let pages = layout(report);
+    let title = format!("Monthly report {} - {}", report.month.label(), report.account);
+
+    let (doc, first_page, first_layer) =
+        PdfDocument::new(&title, Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Report");
+    let regular = doc.add_builtin_font(BuiltinFont::Courier).map_err(pdf_error)?;
+    let bold = doc.add_builtin_font(BuiltinFont::CourierBold).map_err(pdf_error)?;
+
+    for (i, lines) in pages.iter().enumerate() {
+        let (page, layer) = if i == 0 {
+            (first_page, first_layer)
+        } else {
+            doc.add_page(Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Report")
+        };
+        let layer = doc.get_page(page).get_layer(layer);
+        for (n, line) in lines.iter().enumerate().filter(|(_, line)| !line.text.is_empty()) {
+            let font = if line.bold { &bold } else { &regular };
+            let y = PAGE_HEIGHT_MM - MARGIN_TOP_MM - n as f32 * LINE_HEIGHT_MM;
+            layer.use_text(line.text.as_str(), FONT_SIZE_PT, Mm(MARGIN_LEFT_MM), Mm(y), font);
+        }
+    }
+
+    doc.save_to_bytes().map_err(pdf_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reports::build_report;
+    use crate::services::reports::tests::{at, swap, tokens, BONK_MINT, USDC_MINT};
+    use crate::services::reports::SOL_MINT;
+    use lib_core::model::store::models::WalletTransfer;
+    use shared::dto::reports::ReportMonth;
+
+    const GOLDEN: &str = include_str!("golden/monthly_report.txt");
+    const GOLDEN_PATH: &str = "src/templates/golden/monthly_report.txt";
+
+    /// March 2025 with enough swaps to run over several pages
+    fn fixture() -> MonthlyReport {
+        let confirmed = SwapStatus::Confirmed;
+        let mut ledger = vec![
+            swap(1, at(2, 3, 9), USDC_MINT, 2_500.0, SOL_MINT, 20.0, Some(2_500.0), confirmed.clone()),
+            swap(2, at(2, 17, 15), USDC_MINT, 300.0, BONK_MINT, 12_000_000.0, Some(300.0), confirmed.clone()),
+        ];
+        let mut swaps = Vec::new();
+        for i in 0..70u32 {
+            let id = 10 + i as i64;
+            let created_at = at(3, 1 + i % 28, 8 + i % 12);
+            let sol_price = 120.0 + (i % 9) as f64 * 2.5;
+            let swap = match i % 5 {
+                0 => swap(id, created_at, SOL_MINT, 0.5, USDC_MINT, 0.5 * sol_price, Some(0.5 * sol_price), confirmed.clone()),
+                1 => swap(id, created_at, USDC_MINT, 40.0, SOL_MINT, 40.0 / sol_price, Some(40.0), confirmed.clone()),
+                2 => swap(id, created_at, BONK_MINT, 250_000.0, USDC_MINT, 6.25, Some(6.25), confirmed.clone()),
+                3 => swap(id, created_at, SOL_MINT, 1.25, USDC_MINT, 1.25 * sol_price, None, SwapStatus::Failed),
+                _ => swap(id, created_at, SOL_MINT, 0.1, BONK_MINT, 480_000.0, Some(0.1 * sol_price), confirmed.clone()),
+            };
+            swaps.push(swap);
+        }
+        swaps.sort_by_key(|s| (s.created_at, s.id));
+        ledger.extend(swaps.iter().filter(|s| s.status == SwapStatus::Confirmed).cloned());
+        let transfers = [(2, 5_000_000_000), (11, -1_250_000_000), (27, 300_000_000)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (day, lamports))| WalletTransfer {
+                id: i as i64 + 1,
+                user_id: 1,
+                wallet: "wallet".to_string(),
+                lamports,
+                observed_at: at(3, day, 18),
+            })
+            .collect::<Vec<_>>();
+
+        let march = ReportMonth::new(2025, 3).unwrap();
+        build_report("alice".to_string(), march, at(4, 1, 6), &ledger, &swaps, &transfers, &tokens())
+    }
+
+    #[test]
+    fn test_text_layer_matches_golden_file() {
+        let text = text_layer(&layout(&fixture()));
+        if std::env::var("UPDATE_GOLDEN").is_ok() {
+            std::fs::write(GOLDEN_PATH, &text).unwrap();
+            return;
+        }
+        assert_eq!(text, GOLDEN, "rerun with UPDATE_GOLDEN=1 to accept the new layout");
+    }
+
+    #[test]
+    fn test_pages_are_full_width_and_numbered() {
+        let pages = layout(&fixture());
+        assert!(pages.len() >= 3);
+        for (i, page) in pages.iter().enumerate() {
+            assert_eq!(page.len(), LINES_PER_PAGE);
+            assert!(page.iter().all(|line| line.text.chars().count() <= LINE_WIDTH && line.text.is_ascii()));
+            assert!(page[LINES_PER_PAGE - 1].text.ends_with(&format!("Page {} of {}", i + 1, pages.len())));
+        }
+        // The swaps table continues under its header
+        assert_eq!(pages[1][0].text, "SWAPS (continued)");
+        assert!(pages[1][1].text.starts_with("Date (UTC)"));
+    }
+
+    #[test]
+    fn test_number_formatting() {
+        assert_eq!(group_thousands(1_234_567.891, 2), "1,234,567.89");
+        assert_eq!(group_thousands(-999.5, 0), "-1,000");
+        assert_eq!(group_thousands(-0.001, 2), "0.00");
+        assert_eq!(usd(-1_500.0), "-$1,500.00");
+        assert_eq!(signed_usd(12.345), "+$12.35");
+        assert_eq!(signed_usd(0.0), "$0.00");
+        assert_eq!(quantity(12_000_000.0), "12,000,000.00");
+        assert_eq!(quantity(0.5), "0.500000");
+        assert_eq!(fit("12,000,000.00 BONK and more", 19), "12,000,000.00 BON..");
+    }
+
+    #[test]
+    fn test_render_pdf() {
+        let pdf = render_pdf(&fixture()).unwrap();
+        assert!(pdf.starts_with(b"%PDF-"));
+    }
+}
@@ -0,0 +1,217 @@
+//! # Share Page Template
+//!
+//! The page behind `GET /share/{slug}`. Charts are drawn as an inline SVG
+//! built from the candles stored with the link, so the page needs no
+//! JavaScript and renders the same after the live candles are gone.
+
+use chrono::{DateTime, Utc};
+use maud::{html, Markup, PreEscaped, DOCTYPE};
+use shared::dto::share::{ChartShare, PortfolioShare, SharePayload};
+
+/// Chart image size in SVG user units
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 320.0;
+/// Room on the right for price labels
+const PRICE_AXIS_WIDTH: f64 = 70.0;
+
+const STYLE: &str = r#"
+body { background: #000; color: #fff; font-family: "JetBrains Mono", monospace; margin: 0; padding: 24px; }
+main { max-width: 860px; margin: 0 auto; }
+h1 { color: #cc0000; font-size: 20px; margin: 0 0 4px; }
+.meta { color: #969696; font-size: 12px; margin: 0 0 16px; }
+svg { background: #0a0a0a; border: 1px solid #333; width: 100%; height: auto; }
+table { border-collapse: collapse; width: 100%; margin-top: 16px; }
+td, th { border-bottom: 1px solid #333; padding: 6px 8px; text-align: left; }
+.bar { background: #cc0000; height: 10px; }
+footer { color: #969696; font-size: 11px; margin-top: 24px; }
+"#;
+
+/// Page for a share link
+pub fn share_page(payload: &SharePayload, expires_at: Option<DateTime<Utc>>) -> Markup {
+    let title = match payload {
+        SharePayload::Chart(chart) => format!("{} · {} chart", chart.symbol, chart.timeframe.label()),
+        SharePayload::Portfolio(_) => "Portfolio allocation".to_string(),
+    };
+
+    layout(&title, html! {
+        h1 { (title) }
+        p.meta {
+            "Read-only snapshot"
+            @if let Some(expires_at) = expires_at {
+                " · expires " (expires_at.format("%Y-%m-%d %H:%M UTC").to_string())
+            }
+        }
+        @match payload {
+            SharePayload::Chart(chart) => { (chart_section(chart)) }
+            SharePayload::Portfolio(portfolio) => { (portfolio_section(portfolio)) }
+        }
+    })
+}
+
+/// Page for an unknown or expired link
+pub fn not_found_page() -> Markup {
+    layout("Link not found", html! {
+        h1 { "Link not found" }
+        p.meta { "This share link doesn't exist or has expired." }
+    })
+}
+
+fn layout(title: &str, content: Markup) -> Markup {
+    html! {
+        (DOCTYPE)
+        html lang="en" {
+            head {
+                meta charset="utf-8";
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                meta name="robots" content="noindex";
+                title { (title) " · XForce Terminal" }
+                style { (PreEscaped(STYLE)) }
+            }
+            body {
+                main {
+                    (content)
+                    footer { "Shared from XForce Terminal" }
+                }
+            }
+        }
+    }
+}
+
+fn chart_section(chart: &ChartShare) -> Markup {
+    html! {
+        (candle_chart_svg(chart))
+        @if !chart.indicators.is_empty() {
+            p.meta { "Indicators: " (chart.indicators.join(", ")) }
+        }
+        @if let (Some(first), Some(last)) = (chart.candles.first(), chart.candles.last()) {
+            table {
+                tr { th { "Open" } th { "High" } th { "Low" } th { "Close" } }
+                tr {
+                    td { (format_price(first.open)) }
+                    td { (format_price(chart.candles.iter().map(|c| c.high).fold(f64::MIN, f64::max))) }
+                    td { (format_price(chart.candles.iter().map(|c| c.low).fold(f64::MAX, f64::min))) }
+                    td { (format_price(last.close)) }
+                }
+            }
+        }
+    }
+}
+
+/// Candlestick chart of the stored candles with annotation lines
+fn candle_chart_svg(chart: &ChartShare) -> Markup {
+    let prices = chart
+        .candles
+        .iter()
+        .flat_map(|c| [c.high, c.low])
+        .chain(chart.annotations.iter().map(|a| a.price));
+    let (min, max) = prices.fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p), hi.max(p)));
+    // Flat series still get a visible band
+    let (min, max) = if max > min { (min, max) } else { (min - 1.0, max + 1.0) };
+    let plot_width = CHART_WIDTH - PRICE_AXIS_WIDTH;
+    let y = |price: f64| CHART_HEIGHT - (price - min) / (max - min) * (CHART_HEIGHT - 20.0) - 10.0;
+    let slot = plot_width / chart.candles.len().max(1) as f64;
+    let body_width = (slot * 0.7).max(1.0);
+
+    html! {
+        svg viewBox=(format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)) role="img" aria-label=(format!("{} candles", chart.symbol)) {
+            @for (i, candle) in chart.candles.iter().enumerate() {
+                @let center = slot * (i as f64 + 0.5);
+                @let color = if candle.close >= candle.open { "#00ff00" } else { "#ff0000" };
+                @let top = y(candle.open.max(candle.close));
+                @let height = (y(candle.open.min(candle.close)) - top).max(1.0);
+                line x1=(fmt(center)) x2=(fmt(center)) y1=(fmt(y(candle.high))) y2=(fmt(y(candle.low))) stroke=(color) {}
+                rect.candle x=(fmt(center - body_width / 2.0)) y=(fmt(top)) width=(fmt(body_width)) height=(fmt(height)) fill=(color) {}
+            }
+            @for annotation in &chart.annotations {
+                @let level = fmt(y(annotation.price));
+                line x1="0" x2=(fmt(plot_width)) y1=(level) y2=(level) stroke="#ffaa00" stroke-dasharray="4 4" {}
+                text x="4" y=(fmt(y(annotation.price) - 4.0)) fill="#ffaa00" font-size="11" { (annotation.label) }
+            }
+            text x=(fmt(plot_width + 6.0)) y="16" fill="#969696" font-size="11" { (format_price(max)) }
+            text x=(fmt(plot_width + 6.0)) y=(fmt(CHART_HEIGHT - 6.0)) fill="#969696" font-size="11" { (format_price(min)) }
+        }
+    }
+}
+
+fn portfolio_section(portfolio: &PortfolioShare) -> Markup {
+    html! {
+        @if let Some(total) = portfolio.total_value {
+            p { "Total value: " (format!("${:.2}", total)) }
+        }
+        table {
+            tr {
+                th { "Token" }
+                th { "Allocation" }
+                th {}
+                @if portfolio.total_value.is_some() { th { "Value" } }
+            }
+            @for allocation in &portfolio.allocations {
+                tr {
+                    td { (allocation.symbol) }
+                    td { (format!("{:.1}%", allocation.allocation_pct)) }
+                    td {
+                        div.bar style=(format!("width: {:.1}%", allocation.allocation_pct.clamp(0.0, 100.0))) {}
+                    }
+                    @if portfolio.total_value.is_some() {
+                        td { (allocation.value.map(|v| format!("${:.2}", v)).unwrap_or_default()) }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn fmt(value: f64) -> String {
+    format!("{:.1}", value)
+}
+
+fn format_price(price: f64) -> String {
+    if price >= 1.0 {
+        format!("${:.2}", price)
+    } else {
+        format!("${:.6}", price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::dto::market::{Timeframe, OHLC};
+    use shared::dto::share::{ChartAnnotation, ShareAllocation};
+
+    fn chart() -> ChartShare {
+        ChartShare {
+            symbol: "SOL<script>".to_string(),
+            timeframe: Timeframe::OneHour,
+            indicators: vec![],
+            annotations: vec![ChartAnnotation { label: "Entry".to_string(), price: 100.0 }],
+            from: 0,
+            to: 7200,
+            candles: (0..3)
+                .map(|i| OHLC { timestamp: i * 3600, open: 99.0, high: 103.0, low: 98.0, close: 101.0, volume: 1.0 })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_chart_page_draws_every_candle() {
+        let html = share_page(&SharePayload::Chart(chart()), None).into_string();
+        assert_eq!(html.matches("class=\"candle\"").count(), 3);
+        assert!(html.contains("Entry"));
+        // User text is escaped
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("SOL&lt;script&gt;"));
+    }
+
+    #[test]
+    fn test_hidden_values_leave_no_value_column() {
+        let portfolio = PortfolioShare {
+            total_value: None,
+            allocations: vec![ShareAllocation { symbol: "SOL".to_string(), allocation_pct: 100.0, value: None }],
+        };
+        let html = share_page(&SharePayload::Portfolio(portfolio), None).into_string();
+        assert!(html.contains("100.0%"));
+        assert!(!html.contains("Value"));
+        assert!(!html.contains('$'));
+    }
+}
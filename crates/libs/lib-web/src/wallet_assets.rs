@@ -0,0 +1,229 @@
+//! # Embedded Wallet Assets
+//!
+//! Serves the wallet-web bundle from the backend binary, so one process
+//! serves both the API and the wallet helper on the same origin.
+//!
+//! ## Building
+//!
+//! The bundle is embedded with the `embedded-wallet` feature. Build wallet-web
+//! for the `/wallet` mount first, then the backend:
+//!
+//! ```bash
+//! cd wallet-web && trunk build --release --public-url /wallet/
+//! cargo build --release -p backend --features embedded-wallet
+//! ```
+//!
+//! ## Routes
+//!
+//! - `GET /wallet`, `GET /wallet/` - `index.html`
+//! - `GET /wallet/{*path}` - the file at `path`; paths without a file
+//!   extension that match no file are client-side routes and get `index.html`
+//!
+//! ## Caching
+//!
+//! Trunk puts a content hash in the names of the files it generates, so those
+//! are cached for a year. `index.html` is always revalidated, since it points
+//! at the current hashes. Other files (copied assets) are cached for an hour.
+//!
+//! ## Same Origin
+//!
+//! With the bundle embedded the wallet runs on the API's origin: its CORS
+//! origins are dropped from the defaults and `/api/health` reports the mount
+//! path, which the terminal uses to open the wallet on its current server.
+
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use std::borrow::Cow;
+
+/// Path the wallet is served under
+pub const MOUNT_PATH: &str = "/wallet";
+
+const INDEX: &str = "index.html";
+const CACHE_HASHED: &str = "public, max-age=31536000, immutable";
+const CACHE_OTHER: &str = "public, max-age=3600";
+const CACHE_INDEX: &str = "no-cache";
+
+/// Looks a file of the bundle up by its path relative to the dist root
+pub type AssetLookup = fn(&str) -> Option<Cow<'static, [u8]>>;
+
+#[cfg(feature = "embedded-wallet")]
+#[derive(rust_embed::RustEmbed)]
+#[folder = "../../../wallet-web/dist/"]
+struct WalletDist;
+
+#[cfg(feature = "embedded-wallet")]
+const EMBEDDED: Option<AssetLookup> = Some(|path| WalletDist::get(path).map(|file| file.data));
+
+#[cfg(not(feature = "embedded-wallet"))]
+const EMBEDDED: Option<AssetLookup> = None;
+
+/// Where the embedded wallet is served, `None` when the binary has no bundle
+pub fn mount_path() -> Option<&'static str> {
+    EMBEDDED.map(|_| MOUNT_PATH)
+}
+
+/// Routes of the embedded bundle; empty when the binary has none
+pub fn embedded_router<S: Clone + Send + Sync + 'static>() -> Router<S> {
+    match EMBEDDED {
+        Some(lookup) => router(lookup),
+        None => Router::new(),
+    }
+}
+
+/// Routes serving the bundle behind `lookup` under [`MOUNT_PATH`]
+pub fn router<S: Clone + Send + Sync + 'static>(lookup: AssetLookup) -> Router<S> {
+    let index = move || async move { serve(lookup, "") };
+    Router::new()
+        .route(MOUNT_PATH, get(index))
+        .route(&format!("{}/", MOUNT_PATH), get(index))
+        .route(
+            &format!("{}/{{*path}}", MOUNT_PATH),
+            get(move |Path(path): Path<String>| async move { serve(lookup, &path) }),
+        )
+}
+
+/// Response for `path` under the mount, with SPA fallback
+fn serve(lookup: AssetLookup, path: &str) -> Response {
+    let path = path.trim_start_matches('/');
+    let path = if path.is_empty() { INDEX } else { path };
+
+    if let Some(contents) = lookup(path) {
+        let mime = mime_guess::from_path(path).first_or_octet_stream();
+        return (
+            [
+                (header::CONTENT_TYPE, mime.essence_str().to_string()),
+                (header::CACHE_CONTROL, cache_control(path).to_string()),
+            ],
+            contents.into_owned(),
+        )
+            .into_response();
+    }
+
+    // A missing file is a 404; anything else is a route of the app itself
+    let last_segment = path.rsplit('/').next().unwrap_or(path);
+    if last_segment.contains('.') {
+        return (StatusCode::NOT_FOUND, "Not found").into_response();
+    }
+    match lookup(INDEX) {
+        Some(index) => (
+            [
+                (header::CONTENT_TYPE, "text/html".to_string()),
+                (header::CACHE_CONTROL, CACHE_INDEX.to_string()),
+            ],
+            index.into_owned(),
+        )
+            .into_response(),
+        None => (StatusCode::NOT_FOUND, "Wallet bundle is missing index.html").into_response(),
+    }
+}
+
+fn cache_control(path: &str) -> &'static str {
+    if path == INDEX {
+        CACHE_INDEX
+    } else if is_hashed(path) {
+        CACHE_HASHED
+    } else {
+        CACHE_OTHER
+    }
+}
+
+/// Whether trunk put a content hash in the file name, as in
+/// `wallet_web-4b1c0e9f2a7d3c58_bg.wasm` or `app-9f2a7d3c584b1c0e.css`
+fn is_hashed(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let stem = name.split('.').next().unwrap_or(name);
+    let Some((_, hash)) = stem.rsplit_once('-') else {
+        return false;
+    };
+    let hash = hash.strip_suffix("_bg").unwrap_or(hash);
+    hash.len() >= 16 && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    const JS: &str = "wallet_web-4b1c0e9f2a7d3c58.js";
+
+    fn bundle(path: &str) -> Option<Cow<'static, [u8]>> {
+        let contents: &'static [u8] = match path {
+            "index.html" => b"<!DOCTYPE html><title>wallet</title>",
+            "wallet_web-4b1c0e9f2a7d3c58.js" => b"export default {}",
+            "wallet_web-4b1c0e9f2a7d3c58_bg.wasm" => b"\0asm",
+            "assets/logo.svg" => b"<svg/>",
+            _ => return None,
+        };
+        Some(Cow::Borrowed(contents))
+    }
+
+    async fn get(uri: &str) -> (StatusCode, Option<String>, Option<String>, Vec<u8>) {
+        let response = router::<()>(bundle)
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let header = |name| response.headers().get(name).map(|v| v.to_str().unwrap().to_string());
+        let (content_type, cache) = (header(header::CONTENT_TYPE), header(header::CACHE_CONTROL));
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec();
+        (status, content_type, cache, body)
+    }
+
+    #[tokio::test]
+    async fn test_serves_index() {
+        for uri in ["/wallet", "/wallet/", "/wallet/index.html"] {
+            let (status, content_type, cache, body) = get(uri).await;
+            assert_eq!(status, StatusCode::OK, "{}", uri);
+            assert_eq!(content_type.as_deref(), Some("text/html"));
+            assert_eq!(cache.as_deref(), Some(CACHE_INDEX));
+            assert!(body.starts_with(b"<!DOCTYPE html>"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serves_hashed_assets_with_long_cache() {
+        let (status, content_type, cache, body) = get(&format!("/wallet/{}", JS)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type.as_deref(), Some("text/javascript"));
+        assert_eq!(cache.as_deref(), Some(CACHE_HASHED));
+        assert_eq!(body, b"export default {}");
+
+        let (_, content_type, cache, _) = get("/wallet/wallet_web-4b1c0e9f2a7d3c58_bg.wasm").await;
+        assert_eq!(content_type.as_deref(), Some("application/wasm"));
+        assert_eq!(cache.as_deref(), Some(CACHE_HASHED));
+
+        let (_, content_type, cache, _) = get("/wallet/assets/logo.svg").await;
+        assert_eq!(content_type.as_deref(), Some("image/svg+xml"));
+        assert_eq!(cache.as_deref(), Some(CACHE_OTHER));
+    }
+
+    #[tokio::test]
+    async fn test_client_routes_fall_back_to_index() {
+        for uri in ["/wallet/status", "/wallet/sign-transaction?tx=abc", "/wallet/wallet-setup/"] {
+            let (status, _, cache, body) = get(uri).await;
+            assert_eq!(status, StatusCode::OK, "{}", uri);
+            assert_eq!(cache.as_deref(), Some(CACHE_INDEX));
+            assert!(body.starts_with(b"<!DOCTYPE html>"));
+        }
+
+        // Missing files stay 404 so a stale hash isn't answered with HTML
+        let (status, _, _, _) = get("/wallet/wallet_web-0000000000000000.js").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_is_hashed() {
+        assert!(is_hashed(JS));
+        assert!(is_hashed("nested/app-9f2a7d3c584b1c0e.css"));
+        assert!(!is_hashed("assets/logo.svg"));
+        assert!(!is_hashed("wallet-setup.js"));
+        assert!(!is_hashed(INDEX));
+    }
+}
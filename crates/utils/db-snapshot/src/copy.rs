@@ -0,0 +1,519 @@
+//! # Snapshot Copy
+//!
+//! Copies a SQLite database table by table, in batches ordered by rowid, with
+//! each value passed through its column's [`Rule`]. Rowids and every
+//! non-personal column are copied as they are, so foreign keys, row counts,
+//! timestamps and amounts match the source.
+//!
+//! Each batch is committed in one transaction, which makes the highest rowid
+//! already in the target exactly where an interrupted run picks up again.
+//! Indexes, views and triggers are created once the data is in.
+
+use crate::pseudonym::Pseudonymizer;
+use crate::rules::{self, Rule, Unclassified};
+use serde::Serialize;
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::{Connection, Row, SqlitePool, TypeInfo, ValueRef};
+
+/// Rows copied per transaction unless told otherwise
+pub const DEFAULT_BATCH_SIZE: usize = 5_000;
+
+/// A source table and the rules of its columns
+#[derive(Debug, Clone)]
+pub struct TablePlan {
+    pub name: String,
+    create_sql: String,
+    columns: Vec<(String, Rule)>,
+}
+
+/// What was copied into one table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TableReport {
+    pub table: String,
+    /// Rows copied by this run
+    pub copied: u64,
+    /// Rows a previous, interrupted run had already copied
+    pub resumed: u64,
+}
+
+/// A value as SQLite stores it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+/// Tables of the source with the rule of every column
+///
+/// Fails, before anything is copied, if any text column has no rule.
+pub async fn plan(source: &SqlitePool) -> anyhow::Result<Vec<TablePlan>> {
+    let tables: Vec<(String, String)> = sqlx::query_as(
+        "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )
+    .fetch_all(source)
+    .await?;
+
+    let mut plans = Vec::with_capacity(tables.len());
+    let mut unclassified: Vec<Unclassified> = Vec::new();
+    for (name, create_sql) in tables {
+        let info = sqlx::query(&format!("PRAGMA table_info({})", quote(&name)))
+            .fetch_all(source)
+            .await?;
+        let mut columns = Vec::with_capacity(info.len());
+        for column in info {
+            let column_name: String = column.try_get("name")?;
+            let declared: String = column.try_get("type")?;
+            match rules::rule_for(&name, &column_name, &declared) {
+                Ok(rule) => columns.push((column_name, rule)),
+                Err(e) => unclassified.push(e),
+            }
+        }
+        plans.push(TablePlan {
+            name,
+            create_sql,
+            columns,
+        });
+    }
+
+    if !unclassified.is_empty() {
+        let list: Vec<String> = unclassified.iter().map(ToString::to_string).collect();
+        anyhow::bail!(
+            "refusing to copy unclassified columns:\n  {}",
+            list.join("\n  ")
+        );
+    }
+    Ok(plans)
+}
+
+/// Copy every table of `plans`, then the indexes, views and triggers
+///
+/// `progress` is called after each committed batch.
+pub async fn copy_all(
+    source: &SqlitePool,
+    target: &SqlitePool,
+    plans: &[TablePlan],
+    pseudonymizer: &Pseudonymizer,
+    batch_size: usize,
+    progress: &mut dyn FnMut(&TableReport),
+) -> anyhow::Result<Vec<TableReport>> {
+    let mut conn = target.acquire().await?;
+    // Tables are copied in name order, not in the order their keys need
+    sqlx::query("PRAGMA foreign_keys = OFF")
+        .execute(&mut *conn)
+        .await?;
+
+    let mut reports = Vec::with_capacity(plans.len());
+    for plan in plans {
+        reports.push(
+            copy_table(
+                source,
+                &mut conn,
+                plan,
+                pseudonymizer,
+                batch_size.max(1),
+                progress,
+            )
+            .await?,
+        );
+    }
+
+    let objects: Vec<(String, String, String)> = sqlx::query_as(
+        "SELECT type, name, sql FROM sqlite_master \
+         WHERE type IN ('index', 'view', 'trigger') AND sql IS NOT NULL AND name NOT LIKE 'sqlite_%' \
+         ORDER BY type = 'trigger', name",
+    )
+    .fetch_all(source)
+    .await?;
+    for (kind, name, sql) in objects {
+        if !exists(&mut conn, &kind, &name).await? {
+            sqlx::query(&sql).execute(&mut *conn).await?;
+        }
+    }
+
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&mut *conn)
+        .await?;
+    Ok(reports)
+}
+
+async fn copy_table(
+    source: &SqlitePool,
+    target: &mut SqliteConnection,
+    plan: &TablePlan,
+    pseudonymizer: &Pseudonymizer,
+    batch_size: usize,
+    progress: &mut dyn FnMut(&TableReport),
+) -> anyhow::Result<TableReport> {
+    let table = quote(&plan.name);
+    if !exists(target, "table", &plan.name).await? {
+        sqlx::query(&plan.create_sql).execute(&mut *target).await?;
+    }
+
+    let (resumed, mut last_rowid): (i64, Option<i64>) =
+        sqlx::query_as(&format!("SELECT COUNT(*), MAX(rowid) FROM {}", table))
+            .fetch_one(&mut *target)
+            .await?;
+    let mut report = TableReport {
+        table: plan.name.clone(),
+        copied: 0,
+        resumed: resumed as u64,
+    };
+
+    let columns: Vec<String> = plan.columns.iter().map(|(name, _)| quote(name)).collect();
+    let select = format!(
+        "SELECT rowid, {} FROM {} WHERE rowid > ? ORDER BY rowid LIMIT ?",
+        columns.join(", "),
+        table
+    );
+    let insert = format!(
+        "INSERT INTO {} (rowid, {}) VALUES ({})",
+        table,
+        columns.join(", "),
+        vec!["?"; columns.len() + 1].join(", ")
+    );
+
+    loop {
+        let rows = sqlx::query(&select)
+            .bind(last_rowid.unwrap_or(i64::MIN))
+            .bind(batch_size as i64)
+            .fetch_all(source)
+            .await?;
+        let Some(last) = rows.last() else {
+            break;
+        };
+        last_rowid = Some(last.try_get(0)?);
+
+        let mut tx = target.begin().await?;
+        for row in &rows {
+            let mut query = sqlx::query(&insert).bind(row.try_get::<i64, _>(0)?);
+            for (index, (_, rule)) in plan.columns.iter().enumerate() {
+                query = match anonymize(read_value(row, index + 1)?, *rule, pseudonymizer) {
+                    Value::Null => query.bind(None::<i64>),
+                    Value::Integer(v) => query.bind(v),
+                    Value::Real(v) => query.bind(v),
+                    Value::Text(v) => query.bind(v),
+                    Value::Blob(v) => query.bind(v),
+                };
+            }
+            query.execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        report.copied += rows.len() as u64;
+        progress(&report);
+    }
+    Ok(report)
+}
+
+/// Pass a value through its column's rule
+///
+/// Numbers and blobs in a personal-data column are treated as text, rather
+/// than trusted to be harmless.
+fn anonymize(value: Value, rule: Rule, pseudonymizer: &Pseudonymizer) -> Value {
+    let text = match (&value, rule) {
+        (_, Rule::Keep) | (Value::Null, _) => return value,
+        (Value::Text(s), _) => s.clone(),
+        (Value::Integer(v), _) => v.to_string(),
+        (Value::Real(v), _) => v.to_string(),
+        (Value::Blob(b), _) => String::from_utf8_lossy(b).into_owned(),
+    };
+    Value::Text(pseudonymizer.apply(rule, &text))
+}
+
+/// Column `index` of `row`, as stored
+pub fn read_value(row: &SqliteRow, index: usize) -> anyhow::Result<Value> {
+    let raw = row.try_get_raw(index)?;
+    if raw.is_null() {
+        return Ok(Value::Null);
+    }
+    // The storage class of the value, not the column's declared type
+    let storage = raw.type_info().name().to_string();
+    Ok(match storage.as_str() {
+        "INTEGER" => Value::Integer(row.try_get(index)?),
+        "REAL" => Value::Real(row.try_get(index)?),
+        "BLOB" => Value::Blob(row.try_get(index)?),
+        _ => Value::Text(row.try_get(index)?),
+    })
+}
+
+async fn exists(conn: &mut SqliteConnection, kind: &str, name: &str) -> anyhow::Result<bool> {
+    let found: Option<i64> =
+        sqlx::query_scalar("SELECT 1 FROM sqlite_master WHERE type = ? AND name = ?")
+            .bind(kind)
+            .bind(name)
+            .fetch_optional(conn)
+            .await?;
+    Ok(found.is_some())
+}
+
+/// Quote an identifier for SQL
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pseudonym::DEV_PASSWORD;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const ALICE_WALLET: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+    const BOB_WALLET: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const SIGNATURE: &str =
+        "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+
+    /// Every personal value seeded into the source
+    const PII: &[&str] = &[
+        "alice_real",
+        "bob.smith",
+        "alice@corp.io",
+        "bob@home.net",
+        "$argon2id$v=19$m=19456,t=2,p=1$realsalt$realhash",
+        ALICE_WALLET,
+        BOB_WALLET,
+        SIGNATURE,
+        "setup-token-1234",
+        "call me on 555-0100",
+        "insufficient funds in",
+        "https://alice.example.org/hook",
+        "whsec_live_secret",
+        "passport-scan",
+        "jti-of-alice",
+        "reset-hash-of-bob",
+        "share-slug-alice",
+        "Alice's moonbag",
+    ];
+
+    async fn pool() -> SqlitePool {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database")
+    }
+
+    async fn seeded_source() -> SqlitePool {
+        let pool = pool().await;
+        sqlx::migrate!("../../../migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        let statements = [
+            format!(
+                "INSERT INTO users (id, username, email, password_hash, wallet_address, wallet_setup_token, created_at) VALUES \
+                 (1, 'alice_real', 'alice@corp.io', '{}', '{}', 'setup-token-1234', '2024-03-01 10:00:00'), \
+                 (2, 'bob.smith', 'bob@home.net', '{}', '{}', NULL, '2024-03-02 11:00:00')",
+                PII[4], ALICE_WALLET, PII[4], BOB_WALLET
+            ),
+            "INSERT INTO friendships (sender_id, receiver_id, status) VALUES (1, 2, 'accepted')".to_string(),
+            "INSERT INTO direct_messages (sender_id, receiver_id, conversation_id, text, version, timestamp) VALUES \
+             (1, 2, '1_2', 'call me on 555-0100', 'v1', '2024-03-03T09:00:00Z'), \
+             (2, 1, '1_2', 'ok', 'v2', '2024-03-03T09:01:00Z'), \
+             (1, 2, '1_2', 'see you', 'v3', '2024-03-03T09:02:00Z')"
+                .to_string(),
+            format!(
+                "INSERT INTO swaps (user_id, signature, input_mint, output_mint, input_amount, output_amount, status, error_message) VALUES \
+                 (1, '{}', 'So11111111111111111111111111111111111111112', 'EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v', 1500000000, 210000000, 'confirmed', NULL), \
+                 (2, 'failed-sig', 'So11111111111111111111111111111111111111112', 'EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v', 5, 0, 'failed', 'insufficient funds in {}')",
+                SIGNATURE, BOB_WALLET
+            ),
+            "INSERT INTO streamed_symbols (symbol, mint, added_by) VALUES ('BONK', 'DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263', 'alice_real')".to_string(),
+            "INSERT INTO revoked_tokens (jti, user_id, expires_at) VALUES ('jti-of-alice', 1, '2030-01-01 00:00:00')".to_string(),
+            "INSERT INTO revoked_sessions (user_id, revoked_before, kept_jti) VALUES (1, '2024-03-04 00:00:00', 'jti-of-alice')".to_string(),
+            "INSERT INTO password_reset_tokens (token_hash, user_id, expires_at) VALUES ('reset-hash-of-bob', 2, '2030-01-01 00:00:00')".to_string(),
+            format!(
+                "INSERT INTO share_links (slug, user_id, kind, payload) VALUES ('share-slug-alice', 1, 'position', '{{\"wallet\":\"{}\",\"label\":\"Alice''s moonbag\",\"pnl\":12.5}}')",
+                ALICE_WALLET
+            ),
+            "INSERT INTO webhooks (id, user_id, url, secret, events) VALUES (1, 1, 'https://alice.example.org/hook', 'whsec_live_secret', 'swap.confirmed')".to_string(),
+            "INSERT INTO webhook_deliveries (webhook_id, event_id, event, delivered, attempts, error) VALUES (1, 'evt-1', 'swap.confirmed', 0, 3, 'connection refused')".to_string(),
+            "INSERT INTO chat_attachments (id, uploader_id, conversation_id, filename, content_type, size) VALUES ('att-1', 1, '1_2', 'passport-scan.png', 'image/png', 2048)".to_string(),
+            "INSERT INTO positions (user_id, mint, quantity, cost_basis_usd) VALUES (1, 'So11111111111111111111111111111111111111112', 1500000000, 210.0)".to_string(),
+            "INSERT INTO token_unlocks (mint, symbol, unlock_at, amount, source, updated_by) VALUES ('JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN', 'JUP', '2025-01-31 00:00:00', 1000.0, 'admin', 'bob.smith')".to_string(),
+        ];
+        for statement in statements {
+            sqlx::query(&statement)
+                .execute(&pool)
+                .await
+                .expect(&statement);
+        }
+        pool
+    }
+
+    async fn snapshot(
+        source: &SqlitePool,
+        target: &SqlitePool,
+        batch_size: usize,
+    ) -> Vec<TableReport> {
+        let hash = lib_auth::hash_password(DEV_PASSWORD).unwrap();
+        let pseudonymizer = Pseudonymizer::new([7; 32], hash);
+        let plans = plan(source).await.unwrap();
+        copy_all(
+            source,
+            target,
+            &plans,
+            &pseudonymizer,
+            batch_size,
+            &mut |_| {},
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Every value of every table, as text
+    async fn dump(pool: &SqlitePool) -> Vec<(String, Vec<String>)> {
+        let tables: Vec<String> = sqlx::query_scalar(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap();
+        let mut out = Vec::new();
+        for table in tables {
+            let rows = sqlx::query(&format!("SELECT * FROM {} ORDER BY rowid", quote(&table)))
+                .fetch_all(pool)
+                .await
+                .unwrap();
+            let mut values = Vec::new();
+            for row in &rows {
+                for index in 0..row.len() {
+                    values.push(match read_value(row, index).unwrap() {
+                        Value::Null => "NULL".to_string(),
+                        Value::Integer(v) => v.to_string(),
+                        Value::Real(v) => v.to_string(),
+                        Value::Text(v) => v,
+                        Value::Blob(v) => String::from_utf8_lossy(&v).into_owned(),
+                    });
+                }
+            }
+            out.push((table, values));
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn test_no_seeded_pii_survives() {
+        let source = seeded_source().await;
+        let target = pool().await;
+        let reports = snapshot(&source, &target, 2).await;
+
+        for (table, values) in dump(&target).await {
+            for value in values {
+                for pii in PII {
+                    assert!(
+                        !value.contains(pii),
+                        "{} leaked into {}: {}",
+                        pii,
+                        table,
+                        value
+                    );
+                }
+            }
+        }
+
+        // Same shape: every row, under the same IDs
+        let source_counts: Vec<_> = dump(&source)
+            .await
+            .into_iter()
+            .map(|(t, v)| (t, v.len()))
+            .collect();
+        let target_counts: Vec<_> = dump(&target)
+            .await
+            .into_iter()
+            .map(|(t, v)| (t, v.len()))
+            .collect();
+        assert_eq!(source_counts, target_counts);
+        let users = reports.iter().find(|r| r.table == "users").unwrap();
+        assert_eq!((users.copied, users.resumed), (2, 0));
+        let amounts: Vec<(i64, i64)> =
+            sqlx::query_as("SELECT user_id, input_amount FROM swaps ORDER BY id")
+                .fetch_all(&target)
+                .await
+                .unwrap();
+        assert_eq!(amounts, vec![(1, 1_500_000_000), (2, 5)]);
+
+        // Accounts sign in with the dev password
+        let hash: String = sqlx::query_scalar("SELECT password_hash FROM users WHERE id = 1")
+            .fetch_one(&target)
+            .await
+            .unwrap();
+        assert!(lib_auth::verify_password(DEV_PASSWORD, &hash).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_mapping_holds_across_tables() {
+        let source = seeded_source().await;
+        let target = pool().await;
+        snapshot(&source, &target, DEFAULT_BATCH_SIZE).await;
+
+        let (username, wallet): (String, String) =
+            sqlx::query_as("SELECT username, wallet_address FROM users WHERE id = 1")
+                .fetch_one(&target)
+                .await
+                .unwrap();
+        let added_by: String = sqlx::query_scalar("SELECT added_by FROM streamed_symbols")
+            .fetch_one(&target)
+            .await
+            .unwrap();
+        assert_eq!(added_by, username);
+
+        let payload: String = sqlx::query_scalar("SELECT payload FROM share_links")
+            .fetch_one(&target)
+            .await
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(payload["wallet"], wallet);
+        assert_eq!(payload["pnl"], 12.5);
+
+        let (jti, kept_jti): (String, String) = sqlx::query_as(
+            "SELECT t.jti, s.kept_jti FROM revoked_tokens t JOIN revoked_sessions s ON s.user_id = t.user_id",
+        )
+        .fetch_one(&target)
+        .await
+        .unwrap();
+        assert_eq!(jti, kept_jti);
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_snapshot_resumes() {
+        let source = seeded_source().await;
+        let complete = pool().await;
+        snapshot(&source, &complete, DEFAULT_BATCH_SIZE).await;
+
+        // As if the run died after the first batch of messages
+        let target = pool().await;
+        snapshot(&source, &target, 1).await;
+        sqlx::query("DELETE FROM direct_messages WHERE rowid > 1")
+            .execute(&target)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM users")
+            .execute(&target)
+            .await
+            .unwrap();
+
+        let reports = snapshot(&source, &target, 1).await;
+        let messages = reports
+            .iter()
+            .find(|r| r.table == "direct_messages")
+            .unwrap();
+        assert_eq!((messages.copied, messages.resumed), (2, 1));
+        assert_eq!(dump(&target).await.len(), dump(&complete).await.len());
+        for ((table, resumed), (_, expected)) in
+            dump(&target).await.into_iter().zip(dump(&complete).await)
+        {
+            // Only the password hashes differ, by their salt
+            if table != "users" {
+                assert_eq!(resumed, expected, "{} differs after resuming", table);
+            }
+        }
+
+        // A finished snapshot has nothing left to copy
+        let reports = snapshot(&source, &target, 1).await;
+        assert!(reports.iter().all(|r| r.copied == 0));
+    }
+}
@@ -0,0 +1,40 @@
+//! # DB Snapshot
+//!
+//! Copies a production SQLite database into a dev database with the personal
+//! data replaced, for reproducing bugs and load testing against realistic
+//! data without handling anyone's real details.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! cargo run --package db-snapshot -- sqlite:///backups/prod.db sqlite://./data/dev.db
+//! ```
+//!
+//! The source is opened read-only. The schema is copied from the source, so
+//! the target needs no migrations first.
+//!
+//! ## What Changes
+//!
+//! - Usernames become `trader_<hex>` handles, emails `user_<hex>@example.com`
+//! - Wallet addresses and signatures become generated ones; the same wallet
+//!   gets the same stand-in everywhere, including inside share link payloads
+//! - Every password hash becomes the hash of [`pseudonym::DEV_PASSWORD`]
+//! - Messages and error texts become filler of the same length, tokens and
+//!   secrets opaque values of the same length
+//!
+//! IDs, amounts, timestamps and row counts are kept. See [`rules`] for the
+//! rule of every column; a text column without one stops the snapshot before
+//! anything is copied.
+//!
+//! ## Resuming
+//!
+//! Rows are copied in batches, each in its own transaction. Running the same
+//! command again after an interruption carries on after the last batch. The
+//! key the stand-ins are derived from is kept in `<target>.snapshot.json`
+//! until then, and deleted once the snapshot completes, so the result can't
+//! be mapped back to production.
+
+pub mod copy;
+pub mod pseudonym;
+pub mod rules;
+pub mod state;
@@ -0,0 +1,167 @@
+//! # db-snapshot
+//!
+//! See the library docs for what is copied and how, or run `db-snapshot help`.
+
+use db_snapshot::copy::{self, DEFAULT_BATCH_SIZE};
+use db_snapshot::pseudonym::{Pseudonymizer, DEV_PASSWORD};
+use db_snapshot::state::{self, SnapshotState};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+const USAGE: &str = "\
+Usage: db-snapshot <SOURCE_URL> <TARGET_URL> [--batch-size <rows>]
+
+Copies the SQLite database at SOURCE_URL into TARGET_URL with personal data
+replaced. Run the same command again to resume an interrupted snapshot.
+
+Options:
+  --batch-size <rows>   Rows per transaction (default: 5000)";
+
+struct Args {
+    source: String,
+    target: String,
+    batch_size: usize,
+}
+
+fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut positional = Vec::new();
+    let mut batch_size = DEFAULT_BATCH_SIZE;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "help" | "--help" | "-h" => return Ok(None),
+            "--batch-size" => {
+                let value = args.next().ok_or("--batch-size needs a number of rows")?;
+                batch_size = value
+                    .parse()
+                    .ok()
+                    .filter(|rows| *rows > 0)
+                    .ok_or_else(|| format!("invalid batch size: {}", value))?;
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option: {}", flag)),
+            _ => positional.push(arg),
+        }
+    }
+    match <[String; 2]>::try_from(positional) {
+        Ok([source, target]) => Ok(Some(Args {
+            source,
+            target,
+            batch_size,
+        })),
+        Err(_) => Err("expected a source and a target database URL".to_string()),
+    }
+}
+
+async fn open(options: SqliteConnectOptions) -> anyhow::Result<SqlitePool> {
+    Ok(SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await?)
+}
+
+async fn run(args: &Args) -> anyhow::Result<()> {
+    let source_options = SqliteConnectOptions::from_str(&args.source)?.read_only(true);
+    let target_options = SqliteConnectOptions::from_str(&args.target)?
+        .create_if_missing(true)
+        .foreign_keys(false);
+    let target_path = target_options.get_filename().to_path_buf();
+    if target_path == source_options.get_filename() {
+        anyhow::bail!("the source and target are the same database");
+    }
+
+    let source = open(source_options).await?;
+    let target = open(target_options).await?;
+
+    let state_path = state::path_for(&target_path);
+    let snapshot = match state::load(&state_path)? {
+        Some(snapshot) if snapshot.source != args.source => anyhow::bail!(
+            "{} holds an unfinished snapshot of {}; finish it or delete the target and {}",
+            target_path.display(),
+            snapshot.source,
+            state_path.display()
+        ),
+        Some(snapshot) => {
+            println!("Resuming the snapshot into {}", target_path.display());
+            snapshot
+        }
+        None => {
+            let tables: i64 =
+                sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'")
+                    .fetch_one(&target)
+                    .await?;
+            if tables > 0 {
+                anyhow::bail!(
+                    "{} already has tables; snapshots go into a new database",
+                    target_path.display()
+                );
+            }
+            let snapshot = SnapshotState::new(&args.source);
+            state::save(&state_path, &snapshot)?;
+            snapshot
+        }
+    };
+
+    // Fails on unclassified columns before anything is written
+    let plans = copy::plan(&source).await?;
+    let password_hash = lib_auth::hash_password(DEV_PASSWORD).map_err(anyhow::Error::msg)?;
+    let pseudonymizer = Pseudonymizer::new(snapshot.key, password_hash);
+
+    let reports = copy::copy_all(
+        &source,
+        &target,
+        &plans,
+        &pseudonymizer,
+        args.batch_size,
+        &mut |report| {
+            println!(
+                "  {}: {} rows",
+                report.table,
+                report.resumed + report.copied
+            );
+        },
+    )
+    .await?;
+
+    // Without the key, nothing maps the stand-ins back
+    std::fs::remove_file(&state_path)?;
+
+    println!("\nSnapshot complete: {}", target_path.display());
+    for report in &reports {
+        if report.resumed > 0 {
+            println!(
+                "  {:<24} {:>10} rows ({} from the earlier run)",
+                report.table,
+                report.copied + report.resumed,
+                report.resumed
+            );
+        } else {
+            println!("  {:<24} {:>10} rows", report.table, report.copied);
+        }
+    }
+    println!("\nEvery account's password is now: {}", DEV_PASSWORD);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {:#}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! # Pseudonyms
+//!
+//! Replacements are keyed hashes of the originals. The same wallet maps to
+//! the same generated address in every table and every batch, so joins and
+//! "same owner" checks behave as in production. The key is random per
+//! snapshot and is thrown away when the snapshot completes, after which
+//! nothing maps a replacement back to the original, not even a guess.
+
+use crate::rules::Rule;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt::Write;
+
+/// Password every anonymized account signs in with
+pub const DEV_PASSWORD: &str = "snapshot-dev-password";
+
+/// Domain of the generated email addresses
+const EMAIL_DOMAIN: &str = "example.com";
+
+/// Host of the generated webhook URLs
+const URL_HOST: &str = "hooks.example.com";
+
+/// Replacement for free text, repeated and cut to length
+const FILLER: &str =
+    "lorem ipsum dolor sit amet consectetur adipiscing elit sed do eiusmod tempor ";
+
+/// Maps personal data to stand-ins
+pub struct Pseudonymizer {
+    key: [u8; 32],
+    password_hash: String,
+}
+
+impl Pseudonymizer {
+    /// `password_hash` goes in place of every password hash; see [`DEV_PASSWORD`]
+    pub fn new(key: [u8; 32], password_hash: String) -> Self {
+        Self { key, password_hash }
+    }
+
+    /// Replacement for `value` under `rule`
+    pub fn apply(&self, rule: Rule, value: &str) -> String {
+        match rule {
+            Rule::Keep => value.to_string(),
+            Rule::Username => format!("trader_{}", self.hex("username", value, 12)),
+            Rule::Email => format!("user_{}@{}", self.hex("email", value, 12), EMAIL_DOMAIN),
+            Rule::Wallet => self.wallet(value),
+            Rule::Signature => {
+                let mut bytes = self.digest("signature", value).to_vec();
+                bytes.extend(self.digest("signature#1", value));
+                bs58::encode(bytes).into_string()
+            }
+            Rule::PasswordHash => self.password_hash.clone(),
+            Rule::Secret => self.hex("secret", value, value.len()),
+            Rule::Text => filler(value.chars().count()),
+            Rule::Filename => {
+                let stem = format!("file_{}", self.hex("filename", value, 8));
+                match value.rsplit_once('.') {
+                    Some((_, ext))
+                        if !ext.is_empty()
+                            && ext.len() <= 8
+                            && ext.chars().all(|c| c.is_ascii_alphanumeric()) =>
+                    {
+                        format!("{}.{}", stem, ext)
+                    }
+                    _ => stem,
+                }
+            }
+            Rule::Url => format!("https://{}/{}", URL_HOST, self.hex("url", value, 16)),
+            Rule::Json => match serde_json::from_str(value) {
+                Ok(json) => self.json(json).to_string(),
+                Err(_) => filler(value.chars().count()),
+            },
+        }
+    }
+
+    /// A generated address in place of a wallet address
+    fn wallet(&self, value: &str) -> String {
+        bs58::encode(self.digest("wallet", value)).into_string()
+    }
+
+    /// Map the wallet addresses in a JSON document and blank its other strings
+    ///
+    /// Object keys are kept, as they name fields, unless they are addresses.
+    fn json(&self, value: serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+        match value {
+            Value::String(s) if is_address(&s) => Value::String(self.wallet(&s)),
+            Value::String(s) => Value::String(filler(s.chars().count())),
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|item| self.json(item)).collect())
+            }
+            Value::Object(fields) => Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let key = if is_address(&key) {
+                            self.wallet(&key)
+                        } else {
+                            key
+                        };
+                        (key, self.json(value))
+                    })
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    /// Keyed hash of `value`, separated by `kind` so a username and an email
+    /// that happen to be equal don't map alike
+    fn digest(&self, kind: &str, value: &str) -> [u8; 32] {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.key)
+            .expect("HMAC takes keys of any length");
+        mac.update(kind.as_bytes());
+        mac.update(&[0]);
+        mac.update(value.as_bytes());
+        mac.finalize().into_bytes().into()
+    }
+
+    /// `len` hex digits derived from `value`
+    fn hex(&self, kind: &str, value: &str, len: usize) -> String {
+        let mut out = String::with_capacity(len + 64);
+        let mut block = 0u32;
+        while out.len() < len {
+            for byte in self.digest(&format!("{}#{}", kind, block), value) {
+                let _ = write!(out, "{:02x}", byte);
+            }
+            block += 1;
+        }
+        out.truncate(len);
+        out
+    }
+}
+
+/// Whether `value` is a Solana address
+fn is_address(value: &str) -> bool {
+    (32..=44).contains(&value.len())
+        && bs58::decode(value)
+            .into_vec()
+            .is_ok_and(|bytes| bytes.len() == 32)
+}
+
+fn filler(len: usize) -> String {
+    FILLER.chars().cycle().take(len).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+
+    fn pseudonymizer(key: u8) -> Pseudonymizer {
+        Pseudonymizer::new([key; 32], "dev-hash".to_string())
+    }
+
+    #[test]
+    fn test_mapping_is_consistent_per_key() {
+        let p = pseudonymizer(1);
+        let wallet = p.apply(Rule::Wallet, WALLET);
+        assert_eq!(wallet, p.apply(Rule::Wallet, WALLET));
+        assert!(is_address(&wallet));
+        assert_ne!(wallet, WALLET);
+        // Another snapshot's key gives other stand-ins
+        assert_ne!(wallet, pseudonymizer(2).apply(Rule::Wallet, WALLET));
+
+        // The same text under another rule maps elsewhere
+        assert_ne!(
+            p.apply(Rule::Username, "alice"),
+            p.apply(Rule::Secret, "alice")
+        );
+        assert!(p
+            .apply(Rule::Email, "alice@corp.io")
+            .ends_with("@example.com"));
+        assert_eq!(p.apply(Rule::PasswordHash, "$argon2id$..."), "dev-hash");
+    }
+
+    #[test]
+    fn test_shape_is_kept() {
+        let p = pseudonymizer(1);
+        assert_eq!(
+            p.apply(
+                Rule::Secret,
+                "abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789x"
+            )
+            .len(),
+            65
+        );
+        assert_eq!(p.apply(Rule::Text, "héllo wörld").chars().count(), 11);
+        assert!(p
+            .apply(Rule::Filename, "alice passport.pdf")
+            .ends_with(".pdf"));
+        assert_eq!(
+            bs58::decode(p.apply(Rule::Signature, "sig"))
+                .into_vec()
+                .unwrap()
+                .len(),
+            64
+        );
+
+        let payload = format!(
+            r#"{{"owner":"{}","label":"Alice's bag","value":12.5}}"#,
+            WALLET
+        );
+        let mapped: serde_json::Value =
+            serde_json::from_str(&p.apply(Rule::Json, &payload)).unwrap();
+        assert_eq!(mapped["owner"], p.apply(Rule::Wallet, WALLET));
+        assert_eq!(mapped["label"].as_str().unwrap().len(), "Alice's bag".len());
+        assert_eq!(mapped["value"], 12.5);
+    }
+}
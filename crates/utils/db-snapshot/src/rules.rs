@@ -0,0 +1,247 @@
+//! # Column Rules
+//!
+//! What happens to each column on the way into the snapshot. Every text
+//! column of every table must be listed: a column added by a later migration
+//! stops the snapshot until someone decides whether it holds personal data.
+//! Columns of other types (ids, amounts, timestamps, flags) are copied as they
+//! are, which is what keeps row counts, orderings and totals realistic.
+
+use std::fmt;
+
+/// Transformation applied to one column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// Not personal data; copied unchanged
+    Keep,
+    /// A username, replaced by a generated handle
+    Username,
+    /// An email address, replaced by one at example.com
+    Email,
+    /// A wallet address, replaced by a generated one
+    Wallet,
+    /// A transaction signature, replaced by a generated one
+    Signature,
+    /// A password hash, replaced by the hash of the dev password
+    PasswordHash,
+    /// A token or secret; replaced by an opaque value of the same length
+    Secret,
+    /// Free text written by a user; replaced by filler of the same length
+    Text,
+    /// A user's file name; the extension is kept
+    Filename,
+    /// A URL the user configured
+    Url,
+    /// JSON; wallet addresses are mapped and other strings replaced by filler
+    Json,
+}
+
+/// Rules of every text column, by table
+///
+/// Keep this in step with `migrations/`.
+const RULES: &[(&str, &[(&str, Rule)])] = &[
+    (
+        "_sqlx_migrations",
+        &[("description", Rule::Keep), ("checksum", Rule::Keep)],
+    ),
+    (
+        "users",
+        &[
+            ("username", Rule::Username),
+            ("email", Rule::Email),
+            ("password_hash", Rule::PasswordHash),
+            ("wallet_address", Rule::Wallet),
+            ("wallet_setup_token", Rule::Secret),
+        ],
+    ),
+    ("friendships", &[("status", Rule::Keep)]),
+    // Conversation IDs are made of the two user IDs
+    (
+        "conversation_state",
+        &[
+            ("conversation_id", Rule::Keep),
+            ("last_version", Rule::Keep),
+        ],
+    ),
+    (
+        "direct_messages",
+        &[
+            ("conversation_id", Rule::Keep),
+            ("text", Rule::Text),
+            ("version", Rule::Keep),
+            ("timestamp", Rule::Keep),
+        ],
+    ),
+    (
+        "swaps",
+        &[
+            ("signature", Rule::Signature),
+            ("input_mint", Rule::Keep),
+            ("output_mint", Rule::Keep),
+            ("status", Rule::Keep),
+            // May quote the wallet or its balances
+            ("error_message", Rule::Text),
+        ],
+    ),
+    // Public on-chain data about our own programs
+    (
+        "program_versions",
+        &[
+            ("program_id", Rule::Keep),
+            ("programdata_address", Rule::Keep),
+            ("data_hash", Rule::Keep),
+            ("upgrade_authority", Rule::Keep),
+        ],
+    ),
+    (
+        "streamed_symbols",
+        &[
+            ("symbol", Rule::Keep),
+            ("mint", Rule::Keep),
+            ("added_by", Rule::Username),
+        ],
+    ),
+    // The dedupe key is built from the trade's own fields
+    (
+        "imported_trades",
+        &[
+            ("base", Rule::Keep),
+            ("quote", Rule::Keep),
+            ("side", Rule::Keep),
+            ("source", Rule::Keep),
+            ("dedupe_key", Rule::Keep),
+        ],
+    ),
+    ("revoked_tokens", &[("jti", Rule::Secret)]),
+    ("password_reset_tokens", &[("token_hash", Rule::Secret)]),
+    ("revoked_sessions", &[("kept_jti", Rule::Secret)]),
+    (
+        "share_links",
+        &[
+            ("slug", Rule::Secret),
+            ("kind", Rule::Keep),
+            ("payload", Rule::Json),
+        ],
+    ),
+    (
+        "webhooks",
+        &[
+            ("url", Rule::Url),
+            ("secret", Rule::Secret),
+            ("events", Rule::Keep),
+        ],
+    ),
+    (
+        "api_keys",
+        &[
+            ("key_hash", Rule::Secret),
+            ("prefix", Rule::Secret),
+            ("label", Rule::Text),
+            ("scopes", Rule::Keep),
+        ],
+    ),
+    (
+        "webhook_deliveries",
+        &[
+            ("event_id", Rule::Keep),
+            ("event", Rule::Keep),
+            ("error", Rule::Text),
+        ],
+    ),
+    // Attachment IDs are random and name the stored file
+    (
+        "chat_attachments",
+        &[
+            ("id", Rule::Keep),
+            ("conversation_id", Rule::Keep),
+            ("filename", Rule::Filename),
+            ("content_type", Rule::Keep),
+            ("message_version", Rule::Keep),
+        ],
+    ),
+    ("positions", &[("mint", Rule::Keep)]),
+    (
+        "token_unlocks",
+        &[
+            ("mint", Rule::Keep),
+            ("symbol", Rule::Keep),
+            ("allocation", Rule::Keep),
+            ("source", Rule::Keep),
+            ("updated_by", Rule::Username),
+        ],
+    ),
+    ("wallet_transfers", &[("wallet", Rule::Wallet)]),
+];
+
+/// A text column nobody has classified yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unclassified {
+    pub table: String,
+    pub column: String,
+}
+
+impl fmt::Display for Unclassified {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "column {}.{} has no anonymization rule; add it to db-snapshot's rules before taking a snapshot",
+            self.table, self.column
+        )
+    }
+}
+
+impl std::error::Error for Unclassified {}
+
+/// Rule for a column with the given declared type
+///
+/// Untyped columns count as text, since SQLite will store anything in them.
+pub fn rule_for(table: &str, column: &str, declared_type: &str) -> Result<Rule, Unclassified> {
+    let listed = RULES
+        .iter()
+        .find(|(name, _)| *name == table)
+        .and_then(|(_, columns)| columns.iter().find(|(name, _)| *name == column))
+        .map(|(_, rule)| *rule);
+    match listed {
+        Some(rule) => Ok(rule),
+        None if !may_hold_text(declared_type) => Ok(Rule::Keep),
+        None => Err(Unclassified {
+            table: table.to_string(),
+            column: column.to_string(),
+        }),
+    }
+}
+
+/// Whether SQLite gives the declared type text or blob affinity
+fn may_hold_text(declared_type: &str) -> bool {
+    let declared = declared_type.to_uppercase();
+    if declared.contains("INT") {
+        return false;
+    }
+    // Dates are stored as text but are timestamps, not personal data
+    if [
+        "REAL", "FLOA", "DOUB", "BOOL", "DATE", "TIME", "NUMERIC", "DECIMAL",
+    ]
+    .iter()
+    .any(|t| declared.contains(t))
+    {
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlisted_text_columns_are_refused() {
+        assert_eq!(rule_for("users", "email", "TEXT"), Ok(Rule::Email));
+        assert_eq!(rule_for("users", "id", "INTEGER"), Ok(Rule::Keep));
+        assert_eq!(rule_for("swaps", "created_at", "DATETIME"), Ok(Rule::Keep));
+        assert_eq!(rule_for("swaps", "price_impact", "REAL"), Ok(Rule::Keep));
+
+        let err = rule_for("users", "phone_number", "TEXT").unwrap_err();
+        assert_eq!(err.column, "phone_number");
+        assert!(rule_for("new_table", "notes", "").is_err());
+        assert!(rule_for("new_table", "avatar", "BLOB").is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! # Snapshot State
+//!
+//! The state file sits next to the target database while a snapshot is in
+//! progress. It names the source, so a resumed run can't mix two databases,
+//! and holds the pseudonym key, so a resumed run maps values exactly as the
+//! interrupted one did.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// An unfinished snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotState {
+    pub source: String,
+    pub key: [u8; 32],
+}
+
+impl SnapshotState {
+    /// A new snapshot of `source` with a fresh random key
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            key: rand::random(),
+        }
+    }
+}
+
+/// State file of the snapshot into the database at `target`
+pub fn path_for(target: &Path) -> PathBuf {
+    let mut name = target.as_os_str().to_owned();
+    name.push(".snapshot.json");
+    PathBuf::from(name)
+}
+
+/// Read the state file, if a snapshot is in progress
+pub fn load(path: &Path) -> anyhow::Result<Option<SnapshotState>> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Write the state file; readable by the owner only, as the key undoes the anonymization
+pub fn save(path: &Path, state: &SnapshotState) -> anyhow::Result<()> {
+    let bytes = serde_json::to_vec(state)?;
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    std::fs::write(path, &bytes)?;
+    Ok(())
+}
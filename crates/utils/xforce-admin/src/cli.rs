@@ -0,0 +1,345 @@
+//! # Argument Parsing
+//!
+//! Hand-rolled, like the rest of the workspace's binaries. Flags may appear
+//! anywhere on the command line; everything else is positional.
+//!
+//! ```text
+//! xforce-admin [--yes] [--json] [--dry-run] <group> <command> [args]
+//! ```
+
+use std::fmt;
+
+/// Usage text printed by `help` and on parse errors
+pub const USAGE: &str = "\
+Usage: xforce-admin [--yes] [--json] [--dry-run] <command>
+
+Users:
+  users list
+  users deactivate <user>
+  users activate <user>
+  users reset-password <user> [--password <password>]
+  users delete <user>
+  users delete-all
+
+Tokens:
+  tokens revoke <user>              End all of a user's sessions
+
+Price stream symbols (applied when the server next starts):
+  symbols list
+  symbols add <SYMBOL> <MINT> [--require-pyth]
+  symbols remove <SYMBOL>
+
+Contract plugins (config file, applied when the server next starts):
+  plugins list
+  plugins set <plugin> <PROGRAM_ID> [--cluster <localnet|devnet|mainnet>] [--rpc-url <url>]
+  plugins enable <plugin>
+  plugins disable <plugin>
+  plugins remove <plugin>
+
+Background jobs:
+  jobs run <revocation-cleanup|password-reset-cleanup|share-link-cleanup>
+
+Stats and maintenance:
+  stats
+  db prune                          Run every cleanup job
+  db vacuum
+
+<user> is a user ID, email address or username.
+<plugin> is a contract plugin ID such as batch-swap-router.
+
+Options:
+  --yes       Don't ask before destructive commands
+  --json      Print machine-readable JSON
+  --dry-run   Report what a command would change without changing it";
+
+/// Global flags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Options {
+    pub yes: bool,
+    pub json: bool,
+    pub dry_run: bool,
+}
+
+/// How a command names a user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserRef {
+    Id(i64),
+    Email(String),
+    Username(String),
+}
+
+impl UserRef {
+    fn parse(value: &str) -> Self {
+        if let Ok(id) = value.parse() {
+            Self::Id(id)
+        } else if value.contains('@') {
+            Self::Email(value.to_string())
+        } else {
+            Self::Username(value.to_string())
+        }
+    }
+}
+
+impl fmt::Display for UserRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "#{}", id),
+            Self::Email(value) | Self::Username(value) => f.write_str(value),
+        }
+    }
+}
+
+/// Cleanup jobs the server otherwise runs on a timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Job {
+    RevocationCleanup,
+    PasswordResetCleanup,
+    ShareLinkCleanup,
+}
+
+impl Job {
+    pub const ALL: [Job; 3] = [Job::RevocationCleanup, Job::PasswordResetCleanup, Job::ShareLinkCleanup];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::RevocationCleanup => "revocation-cleanup",
+            Self::PasswordResetCleanup => "password-reset-cleanup",
+            Self::ShareLinkCleanup => "share-link-cleanup",
+        }
+    }
+}
+
+/// A parsed `plugins` subcommand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginCommand {
+    List,
+    Set { plugin: String, program_id: String, cluster: Option<String>, rpc_url: Option<String> },
+    SetEnabled { plugin: String, enabled: bool },
+    Remove(String),
+}
+
+/// A parsed subcommand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    UsersList,
+    UsersSetActive { user: UserRef, active: bool },
+    UsersResetPassword { user: UserRef, password: Option<String> },
+    UsersDelete(UserRef),
+    UsersDeleteAll,
+    TokensRevoke(UserRef),
+    SymbolsList,
+    SymbolsAdd { symbol: String, mint: String, require_pyth: bool },
+    SymbolsRemove(String),
+    Plugins(PluginCommand),
+    JobsRun(Job),
+    Stats,
+    DbPrune,
+    DbVacuum,
+}
+
+impl Command {
+    /// Whether the command changes the database or plugin config, and so accepts `--dry-run`
+    pub fn writes(&self) -> bool {
+        !matches!(
+            self,
+            Self::Help | Self::UsersList | Self::SymbolsList | Self::Plugins(PluginCommand::List) | Self::Stats
+        )
+    }
+
+    /// Whether the command deletes data, and so asks first unless `--yes`
+    pub fn needs_confirmation(&self) -> bool {
+        matches!(
+            self,
+            Self::UsersDelete(_)
+                | Self::UsersDeleteAll
+                | Self::SymbolsRemove(_)
+                | Self::Plugins(PluginCommand::Remove(_))
+                | Self::DbPrune
+        )
+    }
+}
+
+/// Options plus the command they apply to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub options: Options,
+    pub command: Command,
+}
+
+/// Parse the arguments after the program name
+pub fn parse<I, S>(args: I) -> Result<Invocation, String>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut options = Options::default();
+    let mut password = None;
+    let mut require_pyth = false;
+    let mut cluster = None;
+    let mut rpc_url = None;
+    let mut positional = Vec::new();
+
+    let mut args = args.into_iter().map(Into::into);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--yes" | "-y" => options.yes = true,
+            "--json" => options.json = true,
+            "--dry-run" => options.dry_run = true,
+            "--require-pyth" => require_pyth = true,
+            "--password" => {
+                password = Some(args.next().ok_or("--password needs a value")?);
+            }
+            "--cluster" => {
+                cluster = Some(args.next().ok_or("--cluster needs a value")?);
+            }
+            "--rpc-url" => {
+                rpc_url = Some(args.next().ok_or("--rpc-url needs a value")?);
+            }
+            "--help" | "-h" => positional.insert(0, "help".to_string()),
+            flag if flag.starts_with('-') && flag.parse::<i64>().is_err() => {
+                return Err(format!("Unknown option {}", flag));
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let words: Vec<&str> = positional.iter().map(String::as_str).collect();
+    let command = match words.as_slice() {
+        [] | ["help", ..] => Command::Help,
+        ["users", "list"] => Command::UsersList,
+        ["users", "deactivate", user] => Command::UsersSetActive { user: UserRef::parse(user), active: false },
+        ["users", "activate", user] => Command::UsersSetActive { user: UserRef::parse(user), active: true },
+        ["users", "reset-password", user] => Command::UsersResetPassword {
+            user: UserRef::parse(user),
+            password: password.take(),
+        },
+        ["users", "delete", user] => Command::UsersDelete(UserRef::parse(user)),
+        ["users", "delete-all"] => Command::UsersDeleteAll,
+        ["tokens", "revoke", user] => Command::TokensRevoke(UserRef::parse(user)),
+        ["symbols", "list"] => Command::SymbolsList,
+        ["symbols", "add", symbol, mint] => Command::SymbolsAdd {
+            symbol: symbol.to_string(),
+            mint: mint.to_string(),
+            require_pyth: std::mem::take(&mut require_pyth),
+        },
+        ["symbols", "remove", symbol] => Command::SymbolsRemove(symbol.to_string()),
+        ["plugins", "list"] => Command::Plugins(PluginCommand::List),
+        ["plugins", "set", plugin, program_id] => Command::Plugins(PluginCommand::Set {
+            plugin: plugin.to_string(),
+            program_id: program_id.to_string(),
+            cluster: cluster.take(),
+            rpc_url: rpc_url.take(),
+        }),
+        ["plugins", "enable", plugin] => {
+            Command::Plugins(PluginCommand::SetEnabled { plugin: plugin.to_string(), enabled: true })
+        }
+        ["plugins", "disable", plugin] => {
+            Command::Plugins(PluginCommand::SetEnabled { plugin: plugin.to_string(), enabled: false })
+        }
+        ["plugins", "remove", plugin] => Command::Plugins(PluginCommand::Remove(plugin.to_string())),
+        ["jobs", "run", job] => Command::JobsRun(
+            Job::ALL
+                .into_iter()
+                .find(|j| j.name() == *job)
+                .ok_or_else(|| format!("Unknown job {}", job))?,
+        ),
+        ["stats"] => Command::Stats,
+        ["db", "prune"] => Command::DbPrune,
+        ["db", "vacuum"] => Command::DbVacuum,
+        _ => return Err(format!("Unknown command: {}", words.join(" "))),
+    };
+
+    if password.is_some() {
+        return Err("--password only applies to users reset-password".to_string());
+    }
+    if require_pyth {
+        return Err("--require-pyth only applies to symbols add".to_string());
+    }
+    if cluster.is_some() || rpc_url.is_some() {
+        return Err("--cluster and --rpc-url only apply to plugins set".to_string());
+    }
+    if options.dry_run && !command.writes() {
+        return Err("--dry-run only applies to commands that change something".to_string());
+    }
+
+    Ok(Invocation { options, command })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands_and_user_refs() {
+        let cases = [
+            ("users list", Command::UsersList),
+            ("users deactivate 42", Command::UsersSetActive { user: UserRef::Id(42), active: false }),
+            ("tokens revoke alice@example.com", Command::TokensRevoke(UserRef::Email("alice@example.com".into()))),
+            ("users delete alice", Command::UsersDelete(UserRef::Username("alice".into()))),
+            ("jobs run share-link-cleanup", Command::JobsRun(Job::ShareLinkCleanup)),
+            ("db vacuum", Command::DbVacuum),
+            ("", Command::Help),
+        ];
+        for (line, expected) in cases {
+            let invocation = parse(line.split_whitespace()).unwrap();
+            assert_eq!(invocation.command, expected, "{}", line);
+            assert_eq!(invocation.options, Options::default());
+        }
+    }
+
+    #[test]
+    fn test_flags_anywhere_and_values() {
+        let invocation = parse(["--json", "users", "reset-password", "bob", "--password", "hunter22", "--yes"]).unwrap();
+        assert_eq!(invocation.options, Options { yes: true, json: true, dry_run: false });
+        assert_eq!(
+            invocation.command,
+            Command::UsersResetPassword { user: UserRef::Username("bob".into()), password: Some("hunter22".into()) }
+        );
+
+        let invocation = parse(["symbols", "add", "jup", "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN", "--require-pyth"]).unwrap();
+        assert!(matches!(invocation.command, Command::SymbolsAdd { require_pyth: true, .. }));
+
+        let invocation = parse(["plugins", "set", "batch-swap-router", "Prog111", "--cluster", "devnet"]).unwrap();
+        assert_eq!(
+            invocation.command,
+            Command::Plugins(PluginCommand::Set {
+                plugin: "batch-swap-router".into(),
+                program_id: "Prog111".into(),
+                cluster: Some("devnet".into()),
+                rpc_url: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for line in [
+            "users",
+            "users delete",
+            "users purge bob",
+            "jobs run nightly",
+            "users list --force",
+            "users reset-password bob --password",
+            "users delete bob --password x",
+            "symbols list --require-pyth",
+            "plugins enable batch-swap-router --cluster devnet",
+            "plugins set batch-swap-router",
+        ] {
+            assert!(parse(line.split_whitespace()).is_err(), "{} should not parse", line);
+        }
+    }
+
+    #[test]
+    fn test_dry_run_only_on_writing_commands() {
+        assert!(parse(["stats", "--dry-run"]).is_err());
+        assert!(parse(["users", "list", "--dry-run"]).is_err());
+        assert!(parse(["plugins", "list", "--dry-run"]).is_err());
+        assert!(parse(["plugins", "disable", "batch-swap-router", "--dry-run"]).unwrap().command.writes());
+
+        let invocation = parse(["--dry-run", "db", "prune"]).unwrap();
+        assert!(invocation.options.dry_run);
+        assert!(invocation.command.writes() && invocation.command.needs_confirmation());
+        assert!(!Command::UsersSetActive { user: UserRef::Id(1), active: false }.needs_confirmation());
+    }
+}
@@ -0,0 +1,619 @@
+//! # Command Execution
+//!
+//! Every command goes through the lib-core repositories (or, for `plugins`,
+//! lib-solana's [`PluginLoader`] config file) and returns an [`Output`], which
+//! `main` prints either as text or as JSON.
+//!
+//! With `--dry-run`, writing commands look up what they would change and
+//! report it without touching the database or the plugin config. Commands that delete data ask
+//! through the `confirm` callback first unless `--yes` (or `--dry-run`) is
+//! given.
+
+use crate::cli::{Command, Invocation, Job, PluginCommand, UserRef, USAGE};
+use anyhow::{anyhow, bail, Context};
+use chrono::{DateTime, Utc};
+use lib_core::model::store::models::{StreamedSymbol, User, UserForUpdate};
+use lib_core::model::store::{
+    MaintenanceRepository, PasswordResetRepository, RevokedTokenRepository, ShareLinkRepository,
+    StreamedSymbolRepository, UserRepository,
+};
+use lib_core::DbPool;
+use lib_solana::contracts::{Cluster, ContractConfig};
+use lib_solana::PluginLoader;
+use rand::distr::{Alphanumeric, SampleString};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// Length of passwords generated by `users reset-password`
+const GENERATED_PASSWORD_LEN: usize = 20;
+
+/// Recorded as `added_by` for symbols added from the command line
+const ADDED_BY: &str = "xforce-admin";
+
+/// Result of one command
+#[derive(Debug, Clone, Serialize)]
+pub struct Output {
+    /// One-line summary
+    pub message: String,
+    pub dry_run: bool,
+    /// Whether the command was declined at the confirmation prompt
+    pub cancelled: bool,
+    /// Structured result for `--json`
+    pub data: Value,
+    /// Extra lines printed after the summary in text mode
+    #[serde(skip)]
+    pub details: Vec<String>,
+}
+
+impl Output {
+    fn new(message: impl Into<String>, data: Value) -> Self {
+        Self { message: message.into(), dry_run: false, cancelled: false, data, details: Vec::new() }
+    }
+
+    fn with_details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Render for `--json` or as plain text
+    pub fn render(&self, json: bool) -> String {
+        if json {
+            return serde_json::to_string_pretty(self).unwrap_or_default();
+        }
+        let mut text = if self.dry_run { format!("[dry run] {}", self.message) } else { self.message.clone() };
+        for line in &self.details {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text
+    }
+}
+
+/// User fields shown by the CLI; never includes the password hash
+#[derive(Debug, Clone, Serialize)]
+pub struct UserRow {
+    pub id: i64,
+    pub username: String,
+    pub email: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_login: Option<DateTime<Utc>>,
+    pub wallet_address: Option<String>,
+}
+
+impl From<&User> for UserRow {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username.clone(),
+            email: user.email.clone(),
+            is_active: user.is_active,
+            created_at: user.created_at,
+            last_login: user.last_login,
+            wallet_address: user.wallet_address.clone(),
+        }
+    }
+}
+
+impl UserRow {
+    fn line(&self) -> String {
+        format!(
+            "{:>6}  {:<20} {:<32} {:<8} {}",
+            self.id,
+            self.username,
+            self.email,
+            if self.is_active { "active" } else { "inactive" },
+            self.wallet_address.as_deref().unwrap_or("-"),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SymbolRow {
+    symbol: String,
+    mint: String,
+    require_pyth: bool,
+    added_by: Option<String>,
+}
+
+impl From<&StreamedSymbol> for SymbolRow {
+    fn from(symbol: &StreamedSymbol) -> Self {
+        Self {
+            symbol: symbol.symbol.clone(),
+            mint: symbol.mint.clone(),
+            require_pyth: symbol.require_pyth,
+            added_by: symbol.added_by.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PluginRow {
+    plugin: String,
+    program_id: String,
+    enabled: bool,
+    cluster: Option<String>,
+    rpc_url: Option<String>,
+}
+
+impl PluginRow {
+    fn new(plugin: &str, config: &ContractConfig) -> Self {
+        Self {
+            plugin: plugin.to_string(),
+            program_id: config.program_id.to_string(),
+            enabled: config.enabled,
+            cluster: config.cluster.as_ref().map(|c| c.as_str().to_string()),
+            rpc_url: config.rpc_url.clone(),
+        }
+    }
+
+    fn line(&self) -> String {
+        format!(
+            "{:<20} {:<44} {:<8} {:<8} {}",
+            self.plugin,
+            self.program_id,
+            if self.enabled { "enabled" } else { "disabled" },
+            self.cluster.as_deref().unwrap_or("default"),
+            self.rpc_url.as_deref().unwrap_or("default"),
+        )
+    }
+}
+
+/// Run a parsed command against `pool`
+///
+/// `confirm` is asked before deleting anything; returning `false` cancels
+/// the command.
+pub async fn run(
+    pool: &DbPool,
+    invocation: &Invocation,
+    confirm: &mut dyn FnMut(&str) -> bool,
+) -> anyhow::Result<Output> {
+    let dry_run = invocation.options.dry_run;
+    let ask = |prompt: String, confirm: &mut dyn FnMut(&str) -> bool| {
+        dry_run || invocation.options.yes || !invocation.command.needs_confirmation() || confirm(&prompt)
+    };
+
+    let mut output = match &invocation.command {
+        Command::Help => Output::new(USAGE, json!({ "usage": USAGE })),
+
+        Command::UsersList => {
+            let users: Vec<UserRow> = UserRepository::list(pool).await?.iter().map(UserRow::from).collect();
+            let details = users.iter().map(UserRow::line).collect();
+            Output::new(format!("{} user(s)", users.len()), json!({ "users": users })).with_details(details)
+        }
+
+        Command::UsersSetActive { user, active } => {
+            let user = resolve_user(pool, user).await?;
+            let verb = if *active { "Activated" } else { "Deactivated" };
+            let user = if dry_run {
+                user
+            } else {
+                let updated = UserRepository::update(pool, user.id, UserForUpdate::new().is_active(*active)).await?;
+                if !*active {
+                    // Log them out everywhere, not just at their next login
+                    RevokedTokenRepository::revoke_sessions(pool, updated.id, Utc::now(), None).await?;
+                }
+                updated
+            };
+            Output::new(format!("{} {}", verb, user.username), json!({ "user": UserRow::from(&user) }))
+        }
+
+        Command::UsersResetPassword { user, password } => {
+            let user = resolve_user(pool, user).await?;
+            let generated = password.is_none();
+            let password = password
+                .clone()
+                .unwrap_or_else(|| Alphanumeric.sample_string(&mut rand::rng(), GENERATED_PASSWORD_LEN));
+            // Hashed even on a dry run so a too-short password is still reported
+            let hash = lib_auth::hash_password(&password).map_err(|e| anyhow!(e))?;
+            if !dry_run {
+                UserRepository::update_password(pool, user.id, &hash).await?;
+                RevokedTokenRepository::revoke_sessions(pool, user.id, Utc::now(), None).await?;
+            }
+            // Only echo a password the operator didn't type, and only when it was set
+            let shown = (generated && !dry_run).then_some(password);
+            let details = shown.iter().map(|p| format!("New password: {}", p)).collect();
+            Output::new(
+                format!("Reset the password of {} and ended their sessions", user.username),
+                json!({ "user": UserRow::from(&user), "password": shown }),
+            )
+            .with_details(details)
+        }
+
+        Command::UsersDelete(user) => {
+            let user = resolve_user(pool, user).await?;
+            let row = UserRow::from(&user);
+            if !ask(format!("Delete user {} ({}) and everything they own?", user.username, user.email), confirm) {
+                return Ok(cancelled());
+            }
+            if !dry_run {
+                UserRepository::delete(pool, user.id).await?;
+            }
+            Output::new(format!("Deleted user {}", user.username), json!({ "user": row, "deleted": 1 }))
+        }
+
+        Command::UsersDeleteAll => {
+            let count = UserRepository::list(pool).await?.len() as u64;
+            if count == 0 {
+                Output::new("No users to delete", json!({ "deleted": 0 }))
+            } else {
+                if !ask(format!("Delete all {} user(s)? This cannot be undone.", count), confirm) {
+                    return Ok(cancelled());
+                }
+                let deleted = if dry_run { count } else { UserRepository::delete_all(pool).await? };
+                Output::new(format!("Deleted {} user(s)", deleted), json!({ "deleted": deleted }))
+            }
+        }
+
+        Command::TokensRevoke(user) => {
+            let user = resolve_user(pool, user).await?;
+            if !dry_run {
+                RevokedTokenRepository::revoke_sessions(pool, user.id, Utc::now(), None).await?;
+            }
+            Output::new(
+                format!("Revoked every session of {}", user.username),
+                json!({ "user": UserRow::from(&user) }),
+            )
+        }
+
+        Command::SymbolsList => {
+            let symbols: Vec<SymbolRow> =
+                StreamedSymbolRepository::list(pool).await?.iter().map(SymbolRow::from).collect();
+            let details = symbols
+                .iter()
+                .map(|s| format!("{:<10} {}{}", s.symbol, s.mint, if s.require_pyth { "  (pyth)" } else { "" }))
+                .collect();
+            Output::new(format!("{} streamed symbol(s)", symbols.len()), json!({ "symbols": symbols }))
+                .with_details(details)
+        }
+
+        Command::SymbolsAdd { symbol, mint, require_pyth } => {
+            let symbol = normalize_symbol(symbol)?;
+            validate_mint(mint)?;
+            if StreamedSymbolRepository::find(pool, &symbol).await?.is_some() {
+                bail!("{} is already streamed", symbol);
+            }
+            let row = if dry_run {
+                SymbolRow { symbol: symbol.clone(), mint: mint.clone(), require_pyth: *require_pyth, added_by: Some(ADDED_BY.into()) }
+            } else {
+                let added = StreamedSymbolRepository::insert(pool, &symbol, mint, *require_pyth, Some(ADDED_BY)).await?;
+                SymbolRow::from(&added)
+            };
+            Output::new(format!("Added {} (streams after the next server restart)", symbol), json!({ "symbol": row }))
+        }
+
+        Command::SymbolsRemove(symbol) => {
+            let symbol = normalize_symbol(symbol)?;
+            let existing = StreamedSymbolRepository::find(pool, &symbol)
+                .await?
+                .ok_or_else(|| anyhow!("{} is not streamed", symbol))?;
+            if !ask(format!("Stop streaming {}?", symbol), confirm) {
+                return Ok(cancelled());
+            }
+            if !dry_run {
+                StreamedSymbolRepository::delete(pool, &symbol).await?;
+            }
+            Output::new(
+                format!("Removed {} (takes effect after the next server restart)", symbol),
+                json!({ "symbol": SymbolRow::from(&existing) }),
+            )
+        }
+
+        Command::Plugins(command) => {
+            if let PluginCommand::Remove(plugin) = command {
+                if !ask(format!("Remove the {} plugin config?", plugin), confirm) {
+                    return Ok(cancelled());
+                }
+            }
+            run_plugins(&PluginLoader::config_path(), command, dry_run)?
+        }
+
+        Command::JobsRun(job) => {
+            let deleted = run_job(pool, *job, dry_run).await?;
+            Output::new(
+                format!("{}: deleted {} expired row(s)", job.name(), deleted),
+                json!({ "job": job.name(), "deleted": deleted }),
+            )
+        }
+
+        Command::Stats => {
+            let counts = MaintenanceRepository::table_counts(pool).await?;
+            let size = MaintenanceRepository::database_size(pool).await?;
+            let details = counts.iter().map(|(table, rows)| format!("{:<24} {:>10}", table, rows)).collect();
+            let tables: serde_json::Map<String, Value> =
+                counts.iter().map(|(table, rows)| (table.to_string(), json!(rows))).collect();
+            Output::new(format!("Database size: {} bytes", size), json!({ "database_bytes": size, "tables": tables }))
+                .with_details(details)
+        }
+
+        Command::DbPrune => {
+            if !ask("Delete all expired revocations, reset tokens and share links?".to_string(), confirm) {
+                return Ok(cancelled());
+            }
+            let mut jobs = serde_json::Map::new();
+            let mut details = Vec::new();
+            let mut total = 0;
+            for job in Job::ALL {
+                let deleted = run_job(pool, job, dry_run).await?;
+                total += deleted;
+                jobs.insert(job.name().to_string(), json!(deleted));
+                details.push(format!("{:<24} {:>10}", job.name(), deleted));
+            }
+            Output::new(format!("Pruned {} expired row(s)", total), json!({ "deleted": total, "jobs": jobs }))
+                .with_details(details)
+        }
+
+        Command::DbVacuum => {
+            let before = MaintenanceRepository::database_size(pool).await?;
+            let after = if dry_run {
+                before
+            } else {
+                MaintenanceRepository::vacuum(pool).await?;
+                MaintenanceRepository::database_size(pool).await?
+            };
+            Output::new(
+                format!("Vacuumed the database: {} -> {} bytes", before, after),
+                json!({ "bytes_before": before, "bytes_after": after }),
+            )
+        }
+    };
+
+    output.dry_run = dry_run;
+    Ok(output)
+}
+
+/// Run a `plugins` command against the plugin config file at `path`
+fn run_plugins(path: &Path, command: &PluginCommand, dry_run: bool) -> anyhow::Result<Output> {
+    let mut contracts = PluginLoader::read_config(path)?;
+    let file = path.display().to_string();
+
+    let output = match command {
+        PluginCommand::List => {
+            let mut plugins: Vec<PluginRow> =
+                contracts.iter().map(|(plugin, config)| PluginRow::new(plugin, config)).collect();
+            plugins.sort_by(|a, b| a.plugin.cmp(&b.plugin));
+            let details = plugins.iter().map(PluginRow::line).collect();
+            return Ok(Output::new(
+                format!("{} configured plugin(s) in {}", plugins.len(), file),
+                json!({ "file": file, "plugins": plugins }),
+            )
+            .with_details(details));
+        }
+
+        PluginCommand::Set { plugin, program_id, cluster, rpc_url } => {
+            if !PluginLoader::SUPPORTED_PLUGINS.contains(&plugin.as_str()) {
+                bail!("Unknown plugin {} (supported: {})", plugin, PluginLoader::SUPPORTED_PLUGINS.join(", "));
+            }
+            let program_id = program_id.parse().map_err(|_| anyhow!("{} is not a valid program ID", program_id))?;
+            let cluster = match cluster {
+                Some(name) => Some(Cluster::from_name(name).ok_or_else(|| anyhow!("Unknown cluster {}", name))?),
+                None => None,
+            };
+            if let Some(url) = rpc_url {
+                if !(url.starts_with("http://") || url.starts_with("https://")) {
+                    bail!("RPC URL must start with http:// or https://");
+                }
+            }
+            // Re-pointing a plugin doesn't re-enable one that was switched off
+            let enabled = contracts.get(plugin).is_none_or(|existing| existing.enabled);
+            let config = ContractConfig { program_id, enabled, cluster, rpc_url: rpc_url.clone() };
+            let row = PluginRow::new(plugin, &config);
+            contracts.insert(plugin.clone(), config);
+            Output::new(format!("Configured {} (applies after the next server restart)", plugin), json!({ "plugin": row }))
+        }
+
+        PluginCommand::SetEnabled { plugin, enabled } => {
+            let config = contracts
+                .get_mut(plugin)
+                .ok_or_else(|| anyhow!("{} is not configured; use plugins set first", plugin))?;
+            config.enabled = *enabled;
+            let verb = if *enabled { "Enabled" } else { "Disabled" };
+            Output::new(
+                format!("{} {} (applies after the next server restart)", verb, plugin),
+                json!({ "plugin": PluginRow::new(plugin, config) }),
+            )
+        }
+
+        PluginCommand::Remove(plugin) => {
+            let config = contracts.remove(plugin).ok_or_else(|| anyhow!("{} is not configured", plugin))?;
+            Output::new(
+                format!("Removed the {} config (the server falls back to its defaults after the next restart)", plugin),
+                json!({ "plugin": PluginRow::new(plugin, &config) }),
+            )
+        }
+    };
+
+    if !dry_run {
+        PluginLoader::write_config(path, &contracts)?;
+    }
+    Ok(output)
+}
+
+/// Delete (or with `dry_run`, count) the rows a cleanup job would remove
+async fn run_job(pool: &DbPool, job: Job, dry_run: bool) -> anyhow::Result<u64> {
+    let now = Utc::now();
+    let rows = match (job, dry_run) {
+        (Job::RevocationCleanup, false) => RevokedTokenRepository::delete_expired(pool, now).await?,
+        (Job::RevocationCleanup, true) => RevokedTokenRepository::count_expired(pool, now).await?,
+        (Job::PasswordResetCleanup, false) => PasswordResetRepository::delete_expired(pool, now).await?,
+        (Job::PasswordResetCleanup, true) => PasswordResetRepository::count_expired(pool, now).await?,
+        (Job::ShareLinkCleanup, false) => ShareLinkRepository::delete_expired(pool, now).await?,
+        (Job::ShareLinkCleanup, true) => ShareLinkRepository::count_expired(pool, now).await?,
+    };
+    Ok(rows)
+}
+
+fn cancelled() -> Output {
+    Output { cancelled: true, ..Output::new("Cancelled", Value::Null) }
+}
+
+async fn resolve_user(pool: &DbPool, user: &UserRef) -> anyhow::Result<User> {
+    let found = match user {
+        UserRef::Id(id) => UserRepository::find_by_id(pool, *id).await,
+        UserRef::Email(email) => UserRepository::find_by_email(pool, email).await,
+        UserRef::Username(username) => UserRepository::find_by_username(pool, username).await,
+    };
+    found.context("Failed to look up user")?.ok_or_else(|| anyhow!("No user {}", user))
+}
+
+/// Same rules as the admin API: 1-16 ASCII letters or digits, uppercased
+fn normalize_symbol(symbol: &str) -> anyhow::Result<String> {
+    let symbol = symbol.trim().to_uppercase();
+    if symbol.is_empty() || symbol.len() > 16 || !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        bail!("Symbol must be 1-16 letters or digits");
+    }
+    Ok(symbol)
+}
+
+/// Solana addresses are 32-44 base58 characters
+fn validate_mint(mint: &str) -> anyhow::Result<()> {
+    const BASE58: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    if !(32..=44).contains(&mint.len()) || !mint.chars().all(|c| BASE58.contains(c)) {
+        bail!("{} is not a valid mint address", mint);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::parse;
+    use chrono::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::migrate!("../../../migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        for name in ["alice", "bob"] {
+            UserRepository::create(&pool, name, &format!("{}@example.com", name), "hash")
+                .await
+                .unwrap();
+        }
+        pool
+    }
+
+    async fn run_line(pool: &DbPool, line: &str, answer: bool) -> (anyhow::Result<Output>, Vec<String>) {
+        let mut prompts = Vec::new();
+        let invocation = parse(line.split_whitespace()).unwrap();
+        let result = run(pool, &invocation, &mut |prompt: &str| {
+            prompts.push(prompt.to_string());
+            answer
+        })
+        .await;
+        (result, prompts)
+    }
+
+    #[tokio::test]
+    async fn test_user_delete_confirms_and_dry_run_keeps_data() {
+        let pool = setup_test_db().await;
+
+        // Dry run reports the deletion without asking or deleting
+        let (output, prompts) = run_line(&pool, "users delete alice --dry-run --json", false).await;
+        let output = output.unwrap();
+        assert!(output.dry_run && prompts.is_empty());
+        assert_eq!(output.data["user"]["username"], "alice");
+        assert!(UserRepository::find_by_username(&pool, "alice").await.unwrap().is_some());
+
+        // Declining the prompt cancels
+        let (output, prompts) = run_line(&pool, "users delete alice", false).await;
+        assert!(output.unwrap().cancelled);
+        assert_eq!(prompts.len(), 1);
+        assert!(UserRepository::find_by_username(&pool, "alice").await.unwrap().is_some());
+
+        // --yes skips the prompt
+        let (output, prompts) = run_line(&pool, "--yes users delete alice@example.com", false).await;
+        assert_eq!(output.unwrap().data["deleted"], 1);
+        assert!(prompts.is_empty());
+        assert!(UserRepository::find_by_username(&pool, "alice").await.unwrap().is_none());
+
+        let (output, _) = run_line(&pool, "users list --json", false).await;
+        let users = output.unwrap().data["users"].clone();
+        assert_eq!(users.as_array().unwrap().len(), 1);
+        assert!(users[0].get("password_hash").is_none());
+
+        let (output, _) = run_line(&pool, "users delete nobody --yes", true).await;
+        assert!(output.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deactivate_reset_password_and_prune() {
+        let pool = setup_test_db().await;
+        let bob = UserRepository::find_by_username(&pool, "bob").await.unwrap().unwrap();
+
+        let (output, _) = run_line(&pool, "users deactivate bob --dry-run", false).await;
+        assert!(output.unwrap().render(false).starts_with("[dry run] Deactivated bob"));
+        assert!(UserRepository::find_by_id(&pool, bob.id).await.unwrap().unwrap().is_active);
+
+        run_line(&pool, &format!("users deactivate {}", bob.id), false).await.0.unwrap();
+        assert!(!UserRepository::find_by_id(&pool, bob.id).await.unwrap().unwrap().is_active);
+        assert!(RevokedTokenRepository::is_session_revoked(&pool, bob.id, Utc::now() - Duration::seconds(5), "jti")
+            .await
+            .unwrap());
+
+        let output = run_line(&pool, "users reset-password bob --json", false).await.0.unwrap();
+        let password = output.data["password"].as_str().unwrap().to_string();
+        assert_eq!(password.len(), GENERATED_PASSWORD_LEN);
+        let hash = UserRepository::find_by_id(&pool, bob.id).await.unwrap().unwrap().password_hash;
+        assert!(lib_auth::verify_password(&password, &hash).unwrap());
+
+        let now = Utc::now();
+        RevokedTokenRepository::revoke(&pool, "old", bob.id, now - Duration::hours(1)).await.unwrap();
+        RevokedTokenRepository::revoke(&pool, "live", bob.id, now + Duration::hours(1)).await.unwrap();
+
+        let output = run_line(&pool, "db prune --dry-run", false).await.0.unwrap();
+        assert_eq!(output.data["jobs"]["revocation-cleanup"], 1);
+        assert!(RevokedTokenRepository::is_revoked(&pool, "old").await.unwrap());
+
+        let output = run_line(&pool, "db prune", true).await.0.unwrap();
+        assert_eq!(output.data["deleted"], 1);
+        assert!(!RevokedTokenRepository::is_revoked(&pool, "old").await.unwrap());
+        assert!(RevokedTokenRepository::is_revoked(&pool, "live").await.unwrap());
+
+        let output = run_line(&pool, "stats --json", false).await.0.unwrap();
+        assert_eq!(output.data["tables"]["users"], 2);
+        assert_eq!(output.data["tables"]["revoked_tokens"], 1);
+    }
+
+    #[test]
+    fn test_plugins_edit_the_config_file() {
+        let path = std::env::temp_dir().join(format!("xforce-admin-plugins-{}.json", std::process::id()));
+        let plugins = |line: &str, dry_run: bool| match parse(line.split_whitespace()).unwrap().command {
+            Command::Plugins(command) => run_plugins(&path, &command, dry_run),
+            other => panic!("{:?} is not a plugins command", other),
+        };
+        let set = "plugins set batch-swap-router HS63bw1V1qTM5uWf92q3uaFdqogrc4SN9qUJSR8aqBMx --cluster devnet";
+
+        // Dry run reports the change without creating the file
+        let output = plugins(set, true).unwrap();
+        assert_eq!(output.data["plugin"]["cluster"], "devnet");
+        assert!(!path.exists());
+
+        plugins(set, false).unwrap();
+        plugins("plugins disable batch-swap-router", false).unwrap();
+        let config = &PluginLoader::read_config(&path).unwrap()["batch-swap-router"];
+        assert!(!config.enabled);
+        assert_eq!(config.program_id.to_string(), "HS63bw1V1qTM5uWf92q3uaFdqogrc4SN9qUJSR8aqBMx");
+
+        // Setting it again keeps it disabled
+        plugins(set, false).unwrap();
+        let output = plugins("plugins list", false).unwrap();
+        assert_eq!(output.data["plugins"][0]["enabled"], false);
+
+        assert!(plugins("plugins set limit-orders HS63bw1V1qTM5uWf92q3uaFdqogrc4SN9qUJSR8aqBMx", false).is_err());
+        assert!(plugins("plugins set batch-swap-router not-a-key", false).is_err());
+        assert!(plugins("plugins enable unknown", false).is_err());
+
+        plugins("plugins remove batch-swap-router", false).unwrap();
+        assert!(PluginLoader::read_config(&path).unwrap().is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
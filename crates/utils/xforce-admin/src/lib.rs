@@ -0,0 +1,29 @@
+//! # XForce Admin
+//!
+//! Operator CLI for the backend database: user management, session
+//! revocation, the price stream's symbol universe, contract plugin config,
+//! cleanup jobs, stats and maintenance. Replaces the one-off utility binaries such as `clear_users`.
+//!
+//! Commands run against the lib-core repositories, so they follow the same
+//! rules as the server (deactivating a user also ends their sessions, for
+//! example).
+//!
+//! ## Usage
+//!
+//! ```bash
+//! cargo run --package xforce-admin -- users list
+//! cargo run --package xforce-admin -- --dry-run db prune
+//! cargo run --package xforce-admin -- --yes --json users delete alice
+//! ```
+//!
+//! The database is the one in `DATABASE_URL` (see [`lib_core::create_pool`]).
+//! The running server doesn't watch the `streamed_symbols` table, so symbol
+//! changes made here apply from its next start; use the admin API to change
+//! the stream of a live server.
+//!
+//! `plugins` commands edit the contract plugin config file instead
+//! (`CONTRACT_PLUGINS_FILE`, see [`lib_solana::PluginLoader::config_path`]),
+//! which the server also reads only at startup.
+
+pub mod cli;
+pub mod commands;
@@ -0,0 +1,60 @@
+//! # xforce-admin
+//!
+//! See the library docs for the command list, or run `xforce-admin help`.
+
+use lib_core::create_pool;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+use xforce_admin::cli::{self, USAGE};
+use xforce_admin::commands;
+
+/// Ask a yes/no question on the terminal; anything but yes declines
+fn confirm(prompt: &str) -> bool {
+    print!("{} (yes/no): ", prompt);
+    if io::stdout().flush().is_err() {
+        return false;
+    }
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "yes" | "y")
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    dotenvy::dotenv().ok();
+
+    let invocation = match cli::parse(std::env::args().skip(1)) {
+        Ok(invocation) => invocation,
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = async {
+        let pool = create_pool().await?;
+        commands::run(&pool, &invocation, &mut confirm).await
+    }
+    .await;
+
+    match result {
+        Ok(output) => {
+            println!("{}", output.render(invocation.options.json));
+            if output.cancelled {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+        Err(error) => {
+            if invocation.options.json {
+                println!("{}", serde_json::json!({ "error": format!("{:#}", error) }));
+            } else {
+                eprintln!("error: {:#}", error);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,14 @@
+-- Create program_versions table for tracking deployed program upgrades
+CREATE TABLE IF NOT EXISTS program_versions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    program_id TEXT NOT NULL,
+    programdata_address TEXT NOT NULL,
+    deployed_slot BIGINT NOT NULL,
+    data_hash TEXT NOT NULL,
+    upgrade_authority TEXT,
+    observed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create indexes for efficient querying
+CREATE INDEX IF NOT EXISTS idx_program_versions_program_id ON program_versions(program_id);
+CREATE INDEX IF NOT EXISTS idx_program_versions_observed_at ON program_versions(observed_at DESC);
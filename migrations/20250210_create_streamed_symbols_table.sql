@@ -0,0 +1,9 @@
+-- Create streamed_symbols table for the price stream symbol universe
+CREATE TABLE IF NOT EXISTS streamed_symbols (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    symbol TEXT NOT NULL UNIQUE,
+    mint TEXT NOT NULL,
+    require_pyth BOOLEAN NOT NULL DEFAULT 0,
+    added_by TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,22 @@
+-- Trades imported from other platforms (CSV exports).
+-- Kept apart from `swaps` so they are never reconciled against the chain,
+-- but included in trade statistics.
+CREATE TABLE IF NOT EXISTS imported_trades (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    traded_at DATETIME NOT NULL,
+    base TEXT NOT NULL,
+    quote TEXT NOT NULL,
+    side TEXT NOT NULL CHECK(side IN ('buy', 'sell')),
+    amount REAL NOT NULL,
+    price REAL NOT NULL,
+    fee REAL NOT NULL DEFAULT 0,
+    source TEXT NOT NULL DEFAULT 'import',
+    -- Normalized row contents; re-importing the same export is a no-op
+    dedupe_key TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(user_id, dedupe_key),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_imported_trades_user ON imported_trades(user_id, traded_at);
@@ -0,0 +1,11 @@
+-- JWTs revoked by logout before their natural expiry.
+-- Rows are only needed until the token would have expired anyway; a
+-- background job deletes them after `expires_at`.
+CREATE TABLE IF NOT EXISTS revoked_tokens (
+    jti TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    expires_at DATETIME NOT NULL,
+    revoked_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_revoked_tokens_expires ON revoked_tokens(expires_at);
@@ -0,0 +1,3 @@
+-- Flag end-to-end encrypted conversations. The server only relays ciphertext for these,
+-- so features that read message text (such as AI summaries) must refuse them.
+ALTER TABLE conversation_state ADD COLUMN is_encrypted BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,9 @@
+-- Per-user session cutoff, written when a user changes their password.
+-- JWTs of the user issued at or before `revoked_before` are rejected, except
+-- `kept_jti` (the token handed back by the password change itself).
+-- One row per user; a later change overwrites it.
+CREATE TABLE IF NOT EXISTS revoked_sessions (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    revoked_before DATETIME NOT NULL,
+    kept_jti TEXT
+);
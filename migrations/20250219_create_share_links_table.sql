@@ -0,0 +1,14 @@
+-- Public read-only share links (chart setups and portfolio allocations).
+-- `payload` is the JSON snapshot (shared::dto::share::SharePayload) with chart
+-- candles copied in at creation. Links without `expires_at` never expire.
+CREATE TABLE IF NOT EXISTS share_links (
+    slug TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    expires_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_share_links_user ON share_links(user_id);
+CREATE INDEX IF NOT EXISTS idx_share_links_expires ON share_links(expires_at);
@@ -0,0 +1,6 @@
+-- Per-participant receipt markers: the newest direct_messages.id each participant has
+-- been delivered and has read. Unread counts are the messages past the read marker.
+ALTER TABLE conversation_state ADD COLUMN user1_last_read_message_id INTEGER;
+ALTER TABLE conversation_state ADD COLUMN user2_last_read_message_id INTEGER;
+ALTER TABLE conversation_state ADD COLUMN user1_last_delivered_message_id INTEGER;
+ALTER TABLE conversation_state ADD COLUMN user2_last_delivered_message_id INTEGER;
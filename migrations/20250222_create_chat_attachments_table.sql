@@ -0,0 +1,15 @@
+-- Files uploaded to direct message conversations. The bytes live on disk under
+-- CHAT_ATTACHMENTS_DIR, named by `id`. `message_version` is set once the upload
+-- is sent in a message; unsent uploads keep it NULL.
+CREATE TABLE IF NOT EXISTS chat_attachments (
+    id TEXT PRIMARY KEY,
+    uploader_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    conversation_id TEXT NOT NULL,
+    filename TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    message_version TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_attachments_conversation ON chat_attachments(conversation_id, message_version);
@@ -0,0 +1,26 @@
+-- Holdings acquired through swaps, per user and mint. Updated in the same
+-- transaction that marks a swap confirmed.
+--
+-- `quantity` is in base units like swap amounts; `cost_basis_usd` is the
+-- weighted-average cost of that quantity. `incomplete` is set once the
+-- numbers are known to be off: a sell larger than the tracked quantity (the
+-- excess was acquired outside the terminal) or a fill without a USD value.
+CREATE TABLE IF NOT EXISTS positions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    mint TEXT NOT NULL,
+    quantity BIGINT NOT NULL DEFAULT 0,
+    cost_basis_usd REAL NOT NULL DEFAULT 0,
+    realized_pnl_usd REAL NOT NULL DEFAULT 0,
+    incomplete BOOLEAN NOT NULL DEFAULT 0,
+    opened_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    closed_at DATETIME,
+    UNIQUE(user_id, mint),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_positions_user ON positions(user_id);
+
+-- USD value of a swap when it was submitted, used as the fill price of both legs
+ALTER TABLE swaps ADD COLUMN value_usd REAL;
@@ -0,0 +1,26 @@
+-- Scheduled token unlocks (vesting cliffs, team/investor releases).
+--
+-- Rows come from the unlocks dataset importer (`source = 'import'`) or are
+-- entered by admins (`source = 'admin'`). The importer never overwrites a
+-- row an admin edited or removed; removal only sets `removed` so the next
+-- import doesn't bring the row back.
+--
+-- `amount` and `circulating_supply` are in whole tokens. `allocation` is
+-- the dataset's bucket name (e.g. "Team") or empty.
+CREATE TABLE IF NOT EXISTS token_unlocks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mint TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    unlock_at DATETIME NOT NULL,
+    amount REAL NOT NULL,
+    circulating_supply REAL,
+    allocation TEXT NOT NULL DEFAULT '',
+    source TEXT NOT NULL,
+    updated_by TEXT,
+    removed BOOLEAN NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(mint, unlock_at, allocation)
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_unlocks_unlock_at ON token_unlocks(unlock_at);
@@ -0,0 +1,17 @@
+-- Network fee of a swap's transaction in lamports, estimated when it was
+-- submitted. Monthly reports add these up as the fees paid.
+ALTER TABLE swaps ADD COLUMN network_fee_lamports BIGINT;
+
+-- SOL balance changes of a user's wallet that no swap accounts for, as the
+-- wallet activity watcher observed them: deposits (`lamports` > 0) and
+-- withdrawals (`lamports` < 0). Changes seen while the user was swapping are
+-- left out, since the watcher can't tell them apart from the swap's own.
+CREATE TABLE IF NOT EXISTS wallet_transfers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    wallet TEXT NOT NULL,
+    lamports BIGINT NOT NULL,
+    observed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_transfers_user ON wallet_transfers(user_id, observed_at);
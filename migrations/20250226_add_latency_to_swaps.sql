@@ -0,0 +1,5 @@
+-- Time each stage of the swap pipeline took, as the terminal measured it:
+-- a JSON object of milliseconds per stage (`build_ms`, `revalidation_ms`,
+-- `signing_ms`, `submission_ms`, `acknowledgement_ms`). NULL for swaps
+-- submitted by clients that don't report it.
+ALTER TABLE swaps ADD COLUMN latency TEXT;
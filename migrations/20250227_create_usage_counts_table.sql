@@ -0,0 +1,15 @@
+-- Anonymous usage counts uploaded by terminals that opted in: one row per
+-- install, day and event (`screen.wallet`, `feature.swap`, `error.login`).
+-- Installs are identified by a random id only; nothing ties a row to a user.
+-- A re-upload of a day replaces its counts.
+CREATE TABLE IF NOT EXISTS usage_counts (
+    install_id TEXT NOT NULL,
+    day DATE NOT NULL,
+    event TEXT NOT NULL,
+    count BIGINT NOT NULL,
+    app_version TEXT NOT NULL,
+    received_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (install_id, day, event)
+);
+
+CREATE INDEX IF NOT EXISTS idx_usage_counts_day ON usage_counts(day, event);
@@ -0,0 +1,17 @@
+-- Per-user API keys for scripting against the backend.
+-- Only a SHA-256 hash of each key is stored; the key itself is returned once,
+-- when it is created. `prefix` is the key's first characters, shown so the
+-- user can tell their keys apart. `scopes` is a comma separated list
+-- (`read`, `write`).
+CREATE TABLE IF NOT EXISTS api_keys (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    key_hash TEXT NOT NULL UNIQUE,
+    prefix TEXT NOT NULL,
+    label TEXT NOT NULL,
+    scopes TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_user ON api_keys(user_id);
@@ -0,0 +1,140 @@
+//! # API Key Data Transfer Objects
+//!
+//! API keys let scripts call the backend on behalf of a user without the
+//! user's password, e.g. to pull swap history into a spreadsheet.
+//!
+//! ## Endpoints
+//!
+//! ```text
+//! POST   /api/keys        { label, scopes } → { api_key, key }
+//! GET    /api/keys                          → { api_keys }
+//! DELETE /api/keys/{id}
+//! ```
+//!
+//! Managing keys needs a login session; a request made with an API key is
+//! refused there.
+//!
+//! ## Use
+//!
+//! Send the key in the [`API_KEY_HEADER`] header instead of
+//! `Authorization: Bearer`:
+//!
+//! ```text
+//! curl -H "X-Api-Key: xfk_…" http://localhost:3001/api/swap/history
+//! ```
+//!
+//! A key with only the `read` scope may call `GET` endpoints; anything else
+//! is answered with 403 unless the key also has `write`. The key is only
+//! returned when it is created; the backend keeps a hash of it.
+
+use serde::{Deserialize, Serialize};
+
+/// Header carrying an API key
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// Start of every API key, so leaked keys are easy to recognize
+pub const API_KEY_PREFIX: &str = "xfk_";
+
+/// Most API keys one user may hold
+pub const MAX_API_KEYS_PER_USER: usize = 10;
+
+/// Longest accepted key label, in characters
+pub const MAX_API_KEY_LABEL_LEN: usize = 64;
+
+/// What an API key may do
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyScope {
+    /// `GET` endpoints; every key has it
+    Read,
+    /// Endpoints that change state (`POST`, `PUT`, `DELETE`)
+    Write,
+}
+
+impl ApiKeyScope {
+    /// Wire name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::Read => "read",
+            ApiKeyScope::Write => "write",
+        }
+    }
+
+    /// Parse a wire name
+    pub fn parse(name: &str) -> Option<Self> {
+        [Self::Read, Self::Write].into_iter().find(|scope| scope.as_str() == name)
+    }
+
+    /// Human readable name for settings screens
+    pub fn label(&self) -> &'static str {
+        match self {
+            ApiKeyScope::Read => "Read only",
+            ApiKeyScope::Write => "Read and write",
+        }
+    }
+}
+
+/// Create an API key
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CreateApiKeyRequest {
+    /// Name to tell the key apart, e.g. "Spreadsheet"
+    pub label: String,
+    /// Scopes beyond `read`; empty for a read-only key
+    #[serde(default)]
+    pub scopes: Vec<ApiKeyScope>,
+}
+
+/// An API key of the user (never includes the key itself)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiKeyInfo {
+    pub id: i64,
+    pub label: String,
+    /// First characters of the key
+    pub prefix: String,
+    pub scopes: Vec<ApiKeyScope>,
+    /// RFC 3339 creation time
+    pub created_at: String,
+    /// RFC 3339 time of the last request made with the key, to the minute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<String>,
+}
+
+impl ApiKeyInfo {
+    /// Whether the key may change state
+    pub fn can_write(&self) -> bool {
+        self.scopes.contains(&ApiKeyScope::Write)
+    }
+}
+
+/// Response to `POST /api/keys`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CreateApiKeyResponse {
+    pub api_key: ApiKeyInfo,
+    /// The key; shown once, store it with the script
+    pub key: String,
+}
+
+/// Response to `GET /api/keys`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiKeyListResponse {
+    pub api_keys: Vec<ApiKeyInfo>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_wire_names() {
+        for scope in [ApiKeyScope::Read, ApiKeyScope::Write] {
+            let json = serde_json::to_string(&scope).unwrap();
+            assert_eq!(json, format!("\"{}\"", scope.as_str()));
+            assert_eq!(ApiKeyScope::parse(scope.as_str()), Some(scope));
+        }
+        assert_eq!(ApiKeyScope::parse("admin"), None);
+
+        // Scopes may be left out for a read-only key
+        let request: CreateApiKeyRequest = serde_json::from_str(r#"{"label": "Spreadsheet"}"#).unwrap();
+        assert!(request.scopes.is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+//! # History Streaming Data Transfer Objects
+//!
+//! Lines of the NDJSON history streams, one JSON object per line:
+//!
+//! ```text
+//! GET /api/swap/history/stream?cursor=&from_ts=&to_ts=&input_token=&output_token=&status=
+//!
+//! {"record":{...}}
+//! ... HISTORY_CURSOR_INTERVAL records ...
+//! {"cursor":"1739872800000000000_42"}
+//! {"record":{...}}
+//! {"cursor":"1739869200000000000_17"}
+//! {"end":{}}
+//! ```
+//!
+//! ## Resuming
+//!
+//! A cursor covers every record sent before it. A client that loses the
+//! connection drops the records received since the last cursor and requests
+//! the stream again with `?cursor=` set to it; the server continues right
+//! after those records. A stream that stops without an `end` line was cut
+//! off, even if the connection closed cleanly.
+
+use serde::{Deserialize, Serialize};
+
+/// Records between two cursors (the last cursor may cover fewer)
+pub const HISTORY_CURSOR_INTERVAL: usize = 100;
+
+/// One line of a history stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryStreamLine<T> {
+    /// The next history record
+    Record(T),
+    /// Opaque position to resume from, covering all records sent so far
+    Cursor(String),
+    /// No more records
+    End {},
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_format() {
+        let lines = [
+            HistoryStreamLine::Record(7),
+            HistoryStreamLine::Cursor("1739872800000000000_42".to_string()),
+            HistoryStreamLine::End {},
+        ];
+        let json: Vec<String> = lines.iter().map(|line| serde_json::to_string(line).unwrap()).collect();
+        assert_eq!(json, vec![r#"{"record":7}"#, r#"{"cursor":"1739872800000000000_42"}"#, r#"{"end":{}}"#]);
+
+        for (line, json) in lines.iter().zip(&json) {
+            assert_eq!(&serde_json::from_str::<HistoryStreamLine<i32>>(json).unwrap(), line);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! # Swap Latency Data Transfer Objects
+//!
+//! Where the time goes between a user starting a swap and the RPC accepting
+//! its transaction. The terminal times each stage of its swap pipeline and
+//! reports the breakdown once the transaction is acknowledged; the backend
+//! keeps it with the swap record.
+//!
+//! ## Endpoints
+//!
+//! ```text
+//! PUT /api/transaction/{signature}/latency  SwapLatency → 204
+//! GET /api/admin/swap-latency?days=7        → SwapLatencySummary
+//! ```
+//!
+//! ## Stages
+//!
+//! ```text
+//! Swap click ─ build ─ revalidation ─┐  (user reviews the confirmation)
+//!                                    └ Confirm click ─ signing ─ submission ─ acknowledgement
+//! ```
+//!
+//! The transaction is built and its quote revalidated by simulation before
+//! the confirmation dialog opens, so those stages run from the Swap click.
+//! The time the user spends reading the dialog is not a stage.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A timed step of the swap pipeline, in pipeline order
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum LatencyStage {
+    /// Swap click until the backend returned the unsigned transaction
+    Build,
+    /// Unsigned transaction until its simulation answered
+    Revalidation,
+    /// Confirm click until the wallet produced the signature
+    Signing,
+    /// Signature until the submit request was sent
+    Submission,
+    /// Submit request sent until the RPC acknowledged the transaction
+    Acknowledgement,
+}
+
+impl LatencyStage {
+    pub const ALL: [LatencyStage; 5] = [
+        LatencyStage::Build,
+        LatencyStage::Revalidation,
+        LatencyStage::Signing,
+        LatencyStage::Submission,
+        LatencyStage::Acknowledgement,
+    ];
+
+    /// Wire name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LatencyStage::Build => "build",
+            LatencyStage::Revalidation => "revalidation",
+            LatencyStage::Signing => "signing",
+            LatencyStage::Submission => "submission",
+            LatencyStage::Acknowledgement => "acknowledgement",
+        }
+    }
+
+    /// Name shown to users
+    pub fn label(&self) -> &'static str {
+        match self {
+            LatencyStage::Build => "Build",
+            LatencyStage::Revalidation => "Quote revalidation",
+            LatencyStage::Signing => "Signing",
+            LatencyStage::Submission => "Submission",
+            LatencyStage::Acknowledgement => "RPC acknowledgement",
+        }
+    }
+}
+
+/// How long each stage of one swap took, in milliseconds
+///
+/// Body of `PUT /api/transaction/{signature}/latency`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SwapLatency {
+    pub build_ms: u64,
+    pub revalidation_ms: u64,
+    pub signing_ms: u64,
+    pub submission_ms: u64,
+    pub acknowledgement_ms: u64,
+}
+
+impl SwapLatency {
+    /// Milliseconds spent in `stage`
+    pub fn stage(&self, stage: LatencyStage) -> u64 {
+        match stage {
+            LatencyStage::Build => self.build_ms,
+            LatencyStage::Revalidation => self.revalidation_ms,
+            LatencyStage::Signing => self.signing_ms,
+            LatencyStage::Submission => self.submission_ms,
+            LatencyStage::Acknowledgement => self.acknowledgement_ms,
+        }
+    }
+
+    /// Every stage with its time, in pipeline order
+    pub fn stages(&self) -> impl Iterator<Item = (LatencyStage, u64)> + '_ {
+        LatencyStage::ALL.into_iter().map(|stage| (stage, self.stage(stage)))
+    }
+
+    /// Time spent in the pipeline, without the user's review
+    pub fn total_ms(&self) -> u64 {
+        self.stages().map(|(_, ms)| ms).sum()
+    }
+}
+
+/// Median, 95th percentile and maximum of a set of timings
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LatencyPercentiles {
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+impl LatencyPercentiles {
+    /// Nearest-rank percentiles of `samples` (any order); all zero when empty
+    pub fn of(samples: impl IntoIterator<Item = u64>) -> Self {
+        let mut sorted: Vec<u64> = samples.into_iter().collect();
+        sorted.sort_unstable();
+        Self {
+            p50_ms: nearest_rank(&sorted, 50),
+            p95_ms: nearest_rank(&sorted, 95),
+            max_ms: sorted.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// The smallest sample at or above `pct` percent of ascending `sorted`
+fn nearest_rank(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (pct.min(100) * sorted.len()).div_ceil(100);
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Percentiles per stage over a set of swaps
+///
+/// Response of `GET /api/admin/swap-latency`; the terminal's debug overlay
+/// shows the same summary of the swaps made this session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SwapLatencySummary {
+    /// Swaps summarized
+    pub swaps: usize,
+    /// Every stage, even with no swaps
+    pub stages: BTreeMap<LatencyStage, LatencyPercentiles>,
+    /// End-to-end pipeline time
+    pub total: LatencyPercentiles,
+}
+
+impl SwapLatencySummary {
+    pub fn of(latencies: &[SwapLatency]) -> Self {
+        Self {
+            swaps: latencies.len(),
+            stages: LatencyStage::ALL
+                .into_iter()
+                .map(|stage| (stage, LatencyPercentiles::of(latencies.iter().map(|l| l.stage(stage)))))
+                .collect(),
+            total: LatencyPercentiles::of(latencies.iter().map(SwapLatency::total_ms)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_rank_percentiles() {
+        let samples = LatencyPercentiles::of((1..=20).rev());
+        assert_eq!(samples, LatencyPercentiles { p50_ms: 10, p95_ms: 19, max_ms: 20 });
+
+        assert_eq!(LatencyPercentiles::of([42]), LatencyPercentiles { p50_ms: 42, p95_ms: 42, max_ms: 42 });
+        // One slow outlier in ten is the p95
+        let mut spiky = vec![100; 9];
+        spiky.push(5_000);
+        assert_eq!(LatencyPercentiles::of(spiky), LatencyPercentiles { p50_ms: 100, p95_ms: 5_000, max_ms: 5_000 });
+        assert_eq!(LatencyPercentiles::of([]), LatencyPercentiles::default());
+    }
+
+    #[test]
+    fn test_summary_per_stage_and_total() {
+        let latency = |build_ms, acknowledgement_ms| SwapLatency {
+            build_ms,
+            revalidation_ms: 300,
+            signing_ms: 5,
+            submission_ms: 1,
+            acknowledgement_ms,
+        };
+        let summary = SwapLatencySummary::of(&[latency(400, 900), latency(1_200, 700), latency(600, 2_500)]);
+
+        assert_eq!(summary.swaps, 3);
+        assert_eq!(summary.stages.len(), LatencyStage::ALL.len());
+        assert_eq!(summary.stages[&LatencyStage::Build], LatencyPercentiles { p50_ms: 600, p95_ms: 1_200, max_ms: 1_200 });
+        assert_eq!(summary.stages[&LatencyStage::Signing], LatencyPercentiles { p50_ms: 5, p95_ms: 5, max_ms: 5 });
+        assert_eq!(summary.stages[&LatencyStage::Acknowledgement].p50_ms, 900);
+        // Totals are 1606, 2206 and 3406
+        assert_eq!(summary.total, LatencyPercentiles { p50_ms: 2_206, p95_ms: 3_406, max_ms: 3_406 });
+
+        let empty = SwapLatencySummary::of(&[]);
+        assert_eq!(empty.swaps, 0);
+        assert!(empty.stages.values().all(|p| *p == LatencyPercentiles::default()));
+    }
+
+    #[test]
+    fn test_wire_format() {
+        let latency = SwapLatency { build_ms: 1, revalidation_ms: 2, signing_ms: 3, submission_ms: 4, acknowledgement_ms: 5 };
+        assert_eq!(
+            serde_json::to_string(&latency).unwrap(),
+            r#"{"build_ms":1,"revalidation_ms":2,"signing_ms":3,"submission_ms":4,"acknowledgement_ms":5}"#
+        );
+        assert_eq!(latency.total_ms(), 15);
+
+        let json = serde_json::to_value(SwapLatencySummary::of(&[latency])).unwrap();
+        assert_eq!(json["stages"]["acknowledgement"]["p95_ms"], 5);
+        assert!(LatencyStage::ALL.iter().all(|stage| json["stages"].get(stage.as_str()).is_some()));
+    }
+}
@@ -8,11 +8,23 @@
 //! - **OHLC data**: Candlestick chart data (Open, High, Low, Close, Volume)
 //! - **Timeframes**: Chart timeframe selection (1M, 5M, 1H, 1D, etc.)
 //! - **Market requests**: Requesting chart data from the API
+//! - **Depth ladders**: Effective swap price at increasing trade sizes
+//! - **Streamed symbols**: The backend's price stream universe
+//! - **Analytics**: Historical volatility and return correlations
+//! - **Token list**: Swappable tokens with Jupiter's verification tags
+//! - **Price stream**: Updates pushed on the `/api/ws/prices` WebSocket
 //!
 //! ## Endpoints Using These DTOs
 //!
 //! - `GET /api/market/prices` - Get current token prices
 //! - `GET /api/market/ohlc?symbol=SOL/USDC&timeframe=OneHour&limit=100` - Get OHLC chart data
+//! - `GET /api/market/candles/batch?symbols=SOL,BONK&timeframe=1h` - Candles for several symbols at once
+//! - `GET /api/market/depth?input=SOL&output=USDC` - Get route-quote depth ladder
+//! - `GET /api/market/streamed-symbols` - List symbols on the price stream
+//! - `GET /api/market/analytics?symbols=SOL,BONK&window=30` - Volatility and correlation matrix
+//! - `GET /api/market/tokens` - Swappable token list
+//! - `POST /api/admin/streamed-symbols` - Add a symbol to the price stream (admin)
+//! - `GET /api/ws/prices` - WebSocket price stream
 //!
 //! ## Wire Format
 //!
@@ -53,6 +65,7 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// OHLC (Open, High, Low, Close) candlestick data for charting.
 ///
@@ -86,7 +99,7 @@ use serde::{Deserialize, Serialize};
 ///   "volume": 125000.0
 /// }
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct OHLC {
     /// Unix timestamp in seconds (epoch time).
     ///
@@ -377,3 +390,486 @@ pub struct OHLCResponse {
     pub timeframe: Timeframe,
     pub data: Vec<OHLC>,
 }
+
+/// OHLC candles for several symbols at one timeframe.
+///
+/// Returned by `GET /api/market/candles/batch`. A symbol whose candles could
+/// not be fetched is listed in `errors` instead of failing the whole batch, so
+/// every requested symbol appears in exactly one of the two maps.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "timeframe": "1h",
+///   "candles": {
+///     "SOL": [{ "timestamp": 1704067200, "open": 100.5, "high": 102.0, "low": 100.0, "close": 101.25, "volume": 150000.0 }]
+///   },
+///   "errors": { "NEWCOIN": "No candles available for symbol: NEWCOIN" }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CandleBatchResponse {
+    pub timeframe: String,
+    /// Candles per symbol, oldest first
+    pub candles: BTreeMap<String, Vec<OHLC>>,
+    /// Why a symbol has no candles
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, String>,
+}
+
+/// One size rung of a depth ladder.
+///
+/// `effective_price` is output units received per input unit at this size.
+/// A rung whose quote failed carries `error` and no price fields, so a single
+/// unroutable size does not fail the whole ladder.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DepthRung {
+    /// Trade size in input token units (e.g. 10.0 = 10 SOL)
+    pub size: f64,
+    /// Output units per input unit
+    pub effective_price: Option<f64>,
+    /// Price impact reported by the route, in percent
+    pub price_impact_pct: Option<f64>,
+    /// Quote error for this rung, if any
+    pub error: Option<String>,
+}
+
+/// Depth ladder for a token pair, built from Jupiter route quotes.
+///
+/// Returned by `GET /api/market/depth`. Rungs are ordered by ascending size.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "input": "SOL",
+///   "output": "USDC",
+///   "rungs": [
+///     { "size": 0.1, "effective_price": 145.21, "price_impact_pct": 0.0, "error": null },
+///     { "size": 1000.0, "effective_price": null, "price_impact_pct": null, "error": "Jupiter quote failed: ..." }
+///   ],
+///   "timestamp": 1704067200
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DepthResponse {
+    pub input: String,
+    pub output: String,
+    pub rungs: Vec<DepthRung>,
+    /// Unix timestamp (seconds) the quotes were fetched at
+    pub timestamp: i64,
+}
+
+/// Symbol on the backend's price stream
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StreamedSymbolInfo {
+    pub symbol: String,
+    pub mint: String,
+    /// Whether a Pyth feed was required when the symbol was added
+    pub require_pyth: bool,
+}
+
+/// Response for `GET /api/market/streamed-symbols`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StreamedSymbolsResponse {
+    pub symbols: Vec<StreamedSymbolInfo>,
+}
+
+/// Request body for `POST /api/admin/streamed-symbols`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddStreamedSymbolRequest {
+    pub symbol: String,
+    /// Reject the symbol unless Pyth publishes a feed for it
+    #[serde(default)]
+    pub require_pyth: bool,
+}
+
+/// Token in the swappable token list
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenListItem {
+    pub symbol: String,
+    pub name: String,
+    pub mint: String,
+    pub decimals: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_uri: Option<String>,
+    /// Jupiter tags, e.g. `verified`, `strict`, `lst`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Jupiter has reviewed the token. Scam tokens copy well-known names and
+    /// symbols, so clients should flag or hide the rest.
+    #[serde(default)]
+    pub verified: bool,
+    /// Traded volume over the last 24h in USD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_volume: Option<f64>,
+}
+
+/// Response for `GET /api/market/tokens`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenListResponse {
+    pub tokens: Vec<TokenListItem>,
+    /// Served from the backend's response cache
+    #[serde(default)]
+    pub cached: bool,
+    /// Milliseconds since the list was fetched from Jupiter
+    #[serde(default)]
+    pub age_ms: u64,
+}
+
+/// Volatility of one symbol over the analytics window
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SymbolVolatility {
+    pub symbol: String,
+    /// Annualized volatility as a fraction (0.85 = 85%); `None` when history is insufficient
+    pub volatility: Option<f64>,
+    /// Daily returns the figure was computed from
+    pub observations: usize,
+    /// Fewer daily candles than the window needs, so nothing was computed
+    pub insufficient_history: bool,
+}
+
+/// Volatility and pairwise return correlations for a set of symbols.
+///
+/// Returned by `GET /api/market/analytics`. `correlations[i][j]` is the
+/// Pearson correlation of the daily log returns of `symbols[i]` and
+/// `symbols[j]`, or `None` when either lacks history or their returns don't
+/// overlap enough.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "window_days": 30,
+///   "symbols": [
+///     { "symbol": "SOL", "volatility": 0.72, "observations": 30, "insufficient_history": false },
+///     { "symbol": "BONK", "volatility": null, "observations": 12, "insufficient_history": true }
+///   ],
+///   "correlations": [[1.0, null], [null, null]],
+///   "timestamp": 1704067200
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MarketAnalyticsResponse {
+    pub window_days: usize,
+    pub symbols: Vec<SymbolVolatility>,
+    pub correlations: Vec<Vec<Option<f64>>>,
+    /// Unix timestamp (seconds) the statistics were computed at
+    pub timestamp: i64,
+}
+
+/// WebSocket envelope for a price update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceUpdateMessage {
+    #[serde(rename = "type")]
+    pub message_type: String,
+    pub data: PriceUpdateData,
+}
+
+impl PriceUpdateMessage {
+    /// Message type tag used on the wire
+    pub const TYPE: &'static str = "price_update";
+
+    /// Wrap an update in the WebSocket envelope
+    pub fn new(data: PriceUpdateData) -> Self {
+        Self {
+            message_type: Self::TYPE.to_string(),
+            data,
+        }
+    }
+}
+
+/// Price of one streamed symbol
+///
+/// `confidence` and `publish_time` come from oracle sources, and `sources`,
+/// `divergence_pct` and `divergent` from comparing the sources. They were
+/// added later: older servers don't send them and older clients ignore them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PriceUpdateData {
+    pub symbol: String,
+    pub mint: String,
+    pub price: f64,
+    pub source: String,
+    /// Unix timestamp (seconds) the server sent the update at
+    pub timestamp: u64,
+    /// Confidence interval in USD (`price ± confidence`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// Unix timestamp (seconds) the oracle published the price at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish_time: Option<i64>,
+    /// Price each source reported, by source name (`{"jupiter": .., "pyth": ..}`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sources: BTreeMap<String, f64>,
+    /// The sources disagree by more than the server's divergence threshold
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub divergent: bool,
+    /// Spread between the source prices in percent of their mid; absent with
+    /// fewer than two sources
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub divergence_pct: Option<f64>,
+}
+
+/// Client message limiting the price stream to some symbols
+///
+/// Sent by the terminal after it (re)connects. Until a connection receives
+/// one it streams every symbol, and an empty list goes back to that.
+///
+/// ```json
+/// {"type": "subscribe", "data": {"symbols": ["SOL", "BONK"]}}
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PriceSubscribeMessage {
+    #[serde(rename = "type")]
+    pub message_type: String,
+    pub data: PriceSubscription,
+}
+
+impl PriceSubscribeMessage {
+    /// Message type tag used on the wire
+    pub const TYPE: &'static str = "subscribe";
+
+    pub fn new(symbols: Vec<String>) -> Self {
+        Self {
+            message_type: Self::TYPE.to_string(),
+            data: PriceSubscription { symbols },
+        }
+    }
+}
+
+/// Symbols a price stream connection wants
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PriceSubscription {
+    #[serde(default)]
+    pub symbols: Vec<String>,
+}
+
+/// Swap a quote stream is for
+///
+/// Two clients asking for the same pair, amount and slippage share one
+/// upstream quote poller on the server.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct QuoteSubscription {
+    pub input_mint: String,
+    pub output_mint: String,
+    /// Input amount in base units
+    pub amount: u64,
+    /// Slippage tolerance in basis points
+    #[serde(rename = "slippage")]
+    pub slippage_bps: u16,
+}
+
+/// Client message starting a quote stream, replacing the connection's
+/// previous one
+///
+/// ```json
+/// {"type": "quote_subscribe", "data": {"input_mint": "So11..", "output_mint": "EPjF..", "amount": 1000000000, "slippage": 50}}
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuoteSubscribeMessage {
+    #[serde(rename = "type")]
+    pub message_type: String,
+    pub data: QuoteSubscription,
+}
+
+impl QuoteSubscribeMessage {
+    /// Message type tag used on the wire
+    pub const TYPE: &'static str = "quote_subscribe";
+
+    pub fn new(subscription: QuoteSubscription) -> Self {
+        Self {
+            message_type: Self::TYPE.to_string(),
+            data: subscription,
+        }
+    }
+}
+
+/// Client message ending the connection's quote stream
+///
+/// ```json
+/// {"type": "quote_unsubscribe"}
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuoteUnsubscribeMessage {
+    #[serde(rename = "type")]
+    pub message_type: String,
+}
+
+impl QuoteUnsubscribeMessage {
+    /// Message type tag used on the wire
+    pub const TYPE: &'static str = "quote_unsubscribe";
+
+    pub fn new() -> Self {
+        Self {
+            message_type: Self::TYPE.to_string(),
+        }
+    }
+}
+
+impl Default for QuoteUnsubscribeMessage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Latest quote for a [`QuoteSubscription`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuoteUpdate {
+    pub subscription: QuoteSubscription,
+    /// Input amount in base units, as quoted
+    pub in_amount: String,
+    /// Expected output amount in base units
+    pub out_amount: String,
+    pub price_impact_pct: f64,
+    /// When the server received the quote, in Unix milliseconds
+    pub quoted_at: i64,
+}
+
+/// Server message carrying a [`QuoteUpdate`]
+///
+/// Sent at most every couple of seconds per stream, and only when Jupiter
+/// answered; a client judges freshness by `quoted_at`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuoteUpdateMessage {
+    #[serde(rename = "type")]
+    pub message_type: String,
+    pub data: QuoteUpdate,
+}
+
+impl QuoteUpdateMessage {
+    /// Message type tag used on the wire
+    pub const TYPE: &'static str = "quote_update";
+
+    pub fn new(update: QuoteUpdate) -> Self {
+        Self {
+            message_type: Self::TYPE.to_string(),
+            data: update,
+        }
+    }
+}
+
+/// Candle of a streamed symbol, sent on every price tick while it forms and
+/// once more when its period closes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CandleUpdate {
+    pub symbol: String,
+    pub timeframe: Timeframe,
+    pub candle: OHLC,
+    /// `false` while the period is still running; the next update for the
+    /// same `candle.timestamp` supersedes it
+    pub is_closed: bool,
+}
+
+/// Server message carrying a [`CandleUpdate`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CandleUpdateMessage {
+    #[serde(rename = "type")]
+    pub message_type: String,
+    pub data: CandleUpdate,
+}
+
+impl CandleUpdateMessage {
+    /// Message type tag used on the wire
+    pub const TYPE: &'static str = "candle_update";
+
+    pub fn new(update: CandleUpdate) -> Self {
+        Self {
+            message_type: Self::TYPE.to_string(),
+            data: update,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_update_without_oracle_fields() {
+        // As sent before confidence and publish time existed
+        let old = r#"{"type":"price_update","data":{"symbol":"SOL","mint":"So11111111111111111111111111111111111111112","price":145.5,"source":"jupiter","timestamp":1704067200}}"#;
+        let message: PriceUpdateMessage = serde_json::from_str(old).unwrap();
+        assert_eq!(message.message_type, PriceUpdateMessage::TYPE);
+        assert_eq!(message.data.confidence, None);
+        assert_eq!(message.data.publish_time, None);
+
+        // Without them nothing new goes on the wire
+        assert_eq!(serde_json::to_string(&message).unwrap(), old);
+
+        let with_oracle = PriceUpdateData { confidence: Some(0.07), publish_time: Some(1704067199), ..message.data };
+        let json = serde_json::to_string(&PriceUpdateMessage::new(with_oracle.clone())).unwrap();
+        assert!(json.contains(r#""confidence":0.07,"publish_time":1704067199"#));
+        let parsed: PriceUpdateMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.data, with_oracle);
+    }
+
+    #[test]
+    fn test_price_update_with_sources() {
+        let old = r#"{"type":"price_update","data":{"symbol":"USDC","mint":"EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v","price":1.0,"source":"jupiter","timestamp":1704067200}}"#;
+        let message: PriceUpdateMessage = serde_json::from_str(old).unwrap();
+        assert!(message.data.sources.is_empty());
+        assert!(!message.data.divergent);
+        assert_eq!(message.data.divergence_pct, None);
+
+        let sources = BTreeMap::from([("jupiter".to_string(), 1.0), ("pyth".to_string(), 0.97)]);
+        let compared = PriceUpdateData { sources, divergent: true, divergence_pct: Some(3.05), ..message.data };
+        let json = serde_json::to_string(&PriceUpdateMessage::new(compared.clone())).unwrap();
+        assert!(json.contains(r#""sources":{"jupiter":1.0,"pyth":0.97},"divergent":true,"divergence_pct":3.05"#));
+        let parsed: PriceUpdateMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.data, compared);
+    }
+
+    #[test]
+    fn test_price_subscribe_message() {
+        let message = PriceSubscribeMessage::new(vec!["SOL".to_string(), "BONK".to_string()]);
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(json, r#"{"type":"subscribe","data":{"symbols":["SOL","BONK"]}}"#);
+        assert_eq!(serde_json::from_str::<PriceSubscribeMessage>(&json).unwrap(), message);
+
+        // A price update is not a subscription
+        let update = r#"{"type":"price_update","data":{"symbol":"SOL","mint":"m","price":1.0,"source":"jupiter","timestamp":1}}"#;
+        let parsed = serde_json::from_str::<PriceSubscribeMessage>(update);
+        assert!(parsed.map(|m| m.message_type != PriceSubscribeMessage::TYPE).unwrap_or(true));
+    }
+
+    #[test]
+    fn test_quote_stream_messages() {
+        let subscription = QuoteSubscription {
+            input_mint: "So11111111111111111111111111111111111111112".to_string(),
+            output_mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            amount: 1_000_000_000,
+            slippage_bps: 50,
+        };
+        let json = serde_json::to_string(&QuoteSubscribeMessage::new(subscription.clone())).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"quote_subscribe","data":{"input_mint":"So11111111111111111111111111111111111111112","output_mint":"EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v","amount":1000000000,"slippage":50}}"#
+        );
+        assert_eq!(serde_json::from_str::<QuoteSubscribeMessage>(&json).unwrap().data, subscription);
+        assert_eq!(serde_json::to_string(&QuoteUnsubscribeMessage::new()).unwrap(), r#"{"type":"quote_unsubscribe"}"#);
+
+        // Only the type tells it apart from a price subscription
+        let parsed = serde_json::from_str::<PriceSubscribeMessage>(&json);
+        assert!(parsed.map(|m| m.message_type != PriceSubscribeMessage::TYPE).unwrap_or(true));
+    }
+
+    #[test]
+    fn test_candle_update_message() {
+        let update = CandleUpdate {
+            symbol: "SOL".to_string(),
+            timeframe: Timeframe::OneHour,
+            candle: OHLC::new(1704067200, 100.0, 101.5, 99.5, 101.0, 0.0),
+            is_closed: false,
+        };
+        let json = serde_json::to_string(&CandleUpdateMessage::new(update.clone())).unwrap();
+        assert!(json.starts_with(r#"{"type":"candle_update","data":{"symbol":"SOL","timeframe":"OneHour","candle":{"#));
+        assert!(json.ends_with(r#""is_closed":false}}"#));
+        let parsed: CandleUpdateMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.data, update);
+
+        // Not mistaken for a price update
+        let parsed = serde_json::from_str::<PriceUpdateMessage>(&json);
+        assert!(parsed.map(|m| m.message_type != PriceUpdateMessage::TYPE).unwrap_or(true));
+    }
+}
@@ -75,6 +75,30 @@ pub struct Message {
     pub timestamp: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
+    /// Files uploaded to `POST /api/chat/attachments` before sending
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+}
+
+/// File attached to a message
+///
+/// Uploaded first with `POST /api/chat/attachments`, then referenced by `id`
+/// from the message; the server fills in the rest from what was uploaded.
+/// Downloaded from `GET /api/chat/attachments/{id}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Attachment {
+    pub id: String,
+    pub filename: String,
+    pub content_type: String,
+    /// Size in bytes
+    pub size: u64,
+}
+
+impl Attachment {
+    /// Whether clients can show the attachment inline
+    pub fn is_image(&self) -> bool {
+        self.content_type.starts_with("image/")
+    }
 }
 
 impl Message {
@@ -85,6 +109,7 @@ impl Message {
             author_id,
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -95,6 +120,7 @@ impl Message {
             author_id,
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: Some(version),
+            attachments: Vec::new(),
         }
     }
 }
@@ -110,6 +136,82 @@ pub struct ConversationInfo {
     pub unread_count: i32,
 }
 
+/// AI summary of the recent messages in a conversation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConversationSummaryResponse {
+    pub conversation_id: String,
+    /// Bullet points of what was discussed
+    pub summary: Vec<String>,
+    /// Things participants agreed or asked to do
+    pub action_items: Vec<String>,
+    /// Token symbols mentioned in the conversation
+    pub tokens: Vec<String>,
+    /// Number of messages the summary covers
+    pub messages_summarized: usize,
+    /// Older messages left out to fit the AI model's input limit
+    pub messages_omitted: usize,
+}
+
+/// How far a participant has got through a conversation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReceiptStatus {
+    Delivered,
+    Read,
+}
+
+/// Delivered or read receipt pushed on a conversation's Braid subscription
+///
+/// Covers every message sent to `user_id` up to and including `version`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MessageReceipt {
+    pub user_id: i64,
+    pub version: String,
+    pub status: ReceiptStatus,
+}
+
+/// Progress of an AI bot reply, pushed on the bot conversation's Braid
+/// subscription as `{"ai_stream": {...}}` while the reply is generated
+///
+/// The finished reply arrives afterwards as a regular message update.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AiStreamEvent {
+    /// Next piece of the reply text
+    Chunk { delta: String },
+    /// No more chunks will follow
+    End { outcome: AiStreamOutcome },
+}
+
+/// How an AI reply stream ended
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AiStreamOutcome {
+    Completed,
+    /// Stopped with `POST /api/chat/{conversation_id}/cancel`
+    Cancelled,
+    /// The AI provider failed partway through
+    Failed { error: String },
+}
+
+impl AiStreamOutcome {
+    /// Final text of a reply that got as far as `partial`
+    ///
+    /// Interrupted replies keep what was generated, marked with why they
+    /// stopped. `None` if nothing was generated.
+    pub fn finalize(&self, partial: &str) -> Option<String> {
+        let partial = partial.trim_end();
+        if partial.trim().is_empty() {
+            return None;
+        }
+        Some(match self {
+            AiStreamOutcome::Completed => partial.to_string(),
+            AiStreamOutcome::Cancelled => format!("{} [cancelled]", partial),
+            AiStreamOutcome::Failed { error } => format!("{} [response interrupted: {}]", partial, error),
+        })
+    }
+}
+
 /// Typing indicator request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypingRequest {
@@ -5,8 +5,24 @@
 //!
 //! ## Module Organization
 //!
+//! - [`api_keys`] - Per-user API keys for scripted access and their scopes
 //! - [`auth`] - Authentication, signup, login, and wallet auth DTOs
+//! - [`history`] - NDJSON lines of resumable history streams
+//! - [`latency`] - Per-stage timing of the swap pipeline and its percentiles
 //! - [`market`] - Market data, OHLC charts, and price information
+//! - [`onramp`] - Fiat on-ramp providers and their checkout URL templates
+//! - [`positions`] - Token positions from swaps, with cost basis and PnL
+//! - [`reports`] - Periods of downloadable account reports
+//! - [`share`] - Public read-only share links for charts and portfolios
+//! - [`simulation`] - Preflight simulation of swap transactions
+//! - [`staking`] - Stake accounts, rewards and unsigned staking transactions
+//! - [`system`] - System notices pushed to connected clients and backend health
+//! - [`telemetry`] - Opt-in anonymous usage counts and their event taxonomy
+//! - [`tokens`] - Token programs (SPL / Token-2022) and transfer fees
+//! - [`trades`] - Historical trade import and trade statistics
+//! - [`transactions`] - Confirmation status and message version of submitted transactions
+//! - [`unlocks`] - Scheduled token unlocks and their size relative to supply
+//! - [`webhooks`] - Outgoing wallet activity webhooks and their delivery log
 //!
 //! ## Serialization Format
 //!
@@ -47,10 +63,42 @@
 //! }
 //! ```
 
+pub mod api_keys;
 pub mod auth;
+pub mod history;
+pub mod latency;
 pub mod market;
 pub mod messaging;
+pub mod onramp;
+pub mod positions;
+pub mod reports;
+pub mod share;
+pub mod simulation;
+pub mod staking;
+pub mod system;
+pub mod telemetry;
+pub mod tokens;
+pub mod trades;
+pub mod transactions;
+pub mod unlocks;
+pub mod webhooks;
 
+pub use api_keys::*;
 pub use auth::*;
+pub use history::*;
+pub use latency::*;
 pub use market::*;
 pub use messaging::*;
+pub use onramp::*;
+pub use positions::*;
+pub use reports::*;
+pub use share::*;
+pub use simulation::*;
+pub use staking::*;
+pub use system::*;
+pub use telemetry::*;
+pub use tokens::*;
+pub use trades::*;
+pub use transactions::*;
+pub use unlocks::*;
+pub use webhooks::*;
@@ -0,0 +1,309 @@
+//! # Fiat On-Ramp Data Transfer Objects
+//!
+//! Third-party services that sell SOL for card or bank payments and send it
+//! to the user's wallet. The terminal only links out to them; no payment
+//! passes through our code.
+//!
+//! ```text
+//! GET /api/onramp/providers?region=US   → OnRampProvidersResponse
+//! ```
+//!
+//! ## URL Templates
+//!
+//! A provider's `url_template` is an `https://` URL whose query values may be
+//! placeholders, filled in by the terminal:
+//!
+//! - `{address}` - the wallet to receive the SOL (required)
+//! - `{amount}` - the fiat amount to buy (optional; its parameter is dropped
+//!   when the user leaves the amount empty)
+//!
+//! ```text
+//! https://buy.example.com/?currency=sol&wallet={address}&fiat_amount={amount}
+//! ```
+//!
+//! Templates are strict so configuration can't be used to build arbitrary
+//! links: a placeholder has to be a whole query value, anything else in
+//! braces is rejected, and the values substituted are checked to be a base58
+//! address and a plain positive number.
+
+use serde::{Deserialize, Serialize};
+
+/// Wallet address placeholder
+pub const ADDRESS_PLACEHOLDER: &str = "{address}";
+
+/// Fiat amount placeholder
+pub const AMOUNT_PLACEHOLDER: &str = "{amount}";
+
+/// An on-ramp provider as configured on the backend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OnRampProvider {
+    /// Stable identifier, e.g. `"moonpay"`
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Checkout URL with [`ADDRESS_PLACEHOLDER`] and optionally
+    /// [`AMOUNT_PLACEHOLDER`] query values
+    pub url_template: String,
+    /// ISO 3166-1 alpha-2 country codes served; empty means everywhere
+    #[serde(default)]
+    pub regions: Vec<String>,
+}
+
+/// Response for `GET /api/onramp/providers`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct OnRampProvidersResponse {
+    pub providers: Vec<OnRampProvider>,
+}
+
+/// Why a template or the values for it were refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OnRampTemplateError {
+    /// The template isn't an `https://` URL
+    NotHttps,
+    /// A brace outside a query value (host, path, parameter name, fragment)
+    PlaceholderOutsideQuery,
+    /// A query value holding something other than exactly one placeholder
+    MalformedPlaceholder(String),
+    /// A placeholder other than `{address}` and `{amount}`
+    UnknownPlaceholder(String),
+    /// `{address}` missing, or a placeholder used twice
+    PlaceholderCount(&'static str),
+    /// The wallet address isn't a base58 Solana address
+    InvalidAddress,
+    /// The amount isn't a finite positive number
+    InvalidAmount,
+}
+
+impl std::fmt::Display for OnRampTemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotHttps => write!(f, "URL template must start with https://"),
+            Self::PlaceholderOutsideQuery => write!(f, "placeholders are only allowed as query values"),
+            Self::MalformedPlaceholder(value) => write!(f, "malformed placeholder in query value '{}'", value),
+            Self::UnknownPlaceholder(name) => write!(f, "unknown placeholder {{{}}}", name),
+            Self::PlaceholderCount(placeholder) => write!(f, "{} must appear exactly once", placeholder),
+            Self::InvalidAddress => write!(f, "not a Solana wallet address"),
+            Self::InvalidAmount => write!(f, "amount must be a positive number"),
+        }
+    }
+}
+
+impl std::error::Error for OnRampTemplateError {}
+
+/// A template split at its query, checked once
+struct ParsedTemplate<'a> {
+    /// Scheme, host and path
+    base: &'a str,
+    query: Vec<(&'a str, &'a str)>,
+    /// Including the leading `#`
+    fragment: &'a str,
+}
+
+fn parse_template(template: &str) -> Result<ParsedTemplate<'_>, OnRampTemplateError> {
+    if !template.starts_with("https://") || template.len() == "https://".len() {
+        return Err(OnRampTemplateError::NotHttps);
+    }
+    let (rest, fragment) = match template.find('#') {
+        Some(i) => template.split_at(i),
+        None => (template, ""),
+    };
+    let (base, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let has_brace = |s: &str| s.contains(['{', '}']);
+    if has_brace(base) || has_brace(fragment) {
+        return Err(OnRampTemplateError::PlaceholderOutsideQuery);
+    }
+
+    let mut pairs = Vec::new();
+    let (mut addresses, mut amounts) = (0, 0);
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        if has_brace(key) {
+            return Err(OnRampTemplateError::PlaceholderOutsideQuery);
+        }
+        if has_brace(value) {
+            match value {
+                ADDRESS_PLACEHOLDER => addresses += 1,
+                AMOUNT_PLACEHOLDER => amounts += 1,
+                _ => {
+                    let name = value.strip_prefix('{').and_then(|v| v.strip_suffix('}'));
+                    return Err(match name {
+                        Some(name) if !has_brace(name) => OnRampTemplateError::UnknownPlaceholder(name.to_string()),
+                        _ => OnRampTemplateError::MalformedPlaceholder(value.to_string()),
+                    });
+                }
+            }
+        }
+        pairs.push((key, value));
+    }
+    if addresses != 1 {
+        return Err(OnRampTemplateError::PlaceholderCount(ADDRESS_PLACEHOLDER));
+    }
+    if amounts > 1 {
+        return Err(OnRampTemplateError::PlaceholderCount(AMOUNT_PLACEHOLDER));
+    }
+    Ok(ParsedTemplate { base, query: pairs, fragment })
+}
+
+/// Check a provider's URL template without filling it in
+pub fn validate_template(template: &str) -> Result<(), OnRampTemplateError> {
+    parse_template(template).map(|_| ())
+}
+
+/// Amount as sent to providers: whole numbers bare, otherwise two decimals
+fn format_amount(amount: f64) -> String {
+    if amount.fract() == 0.0 {
+        format!("{:.0}", amount)
+    } else {
+        format!("{:.2}", amount)
+    }
+}
+
+/// Fill in a URL template for `address`, buying `amount` when given.
+///
+/// Without an amount the `{amount}` parameter is left out, and the provider
+/// asks for one itself.
+pub fn fill_template(template: &str, address: &str, amount: Option<f64>) -> Result<String, OnRampTemplateError> {
+    let parsed = parse_template(template)?;
+    if !super::share::looks_like_address(address) {
+        return Err(OnRampTemplateError::InvalidAddress);
+    }
+    let amount = match amount {
+        Some(amount) if !(amount.is_finite() && amount > 0.0) => return Err(OnRampTemplateError::InvalidAmount),
+        Some(amount) => Some(format_amount(amount)),
+        None => None,
+    };
+
+    let query: Vec<String> = parsed
+        .query
+        .iter()
+        .filter_map(|&(key, value)| {
+            let value = match value {
+                ADDRESS_PLACEHOLDER => address,
+                AMOUNT_PLACEHOLDER => amount.as_deref()?,
+                _ => value,
+            };
+            Some(if value.is_empty() { key.to_string() } else { format!("{}={}", key, value) })
+        })
+        .collect();
+
+    let mut url = parsed.base.to_string();
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(&query.join("&"));
+    }
+    url.push_str(parsed.fragment);
+    Ok(url)
+}
+
+/// Whether `code` looks like an ISO 3166-1 alpha-2 country code
+pub fn is_region_code(code: &str) -> bool {
+    code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+impl OnRampProvider {
+    /// Serves users in `region` (case-insensitive); providers without a
+    /// region list serve everyone
+    pub fn supports_region(&self, region: &str) -> bool {
+        self.regions.is_empty() || self.regions.iter().any(|r| r.eq_ignore_ascii_case(region))
+    }
+
+    /// Checkout URL for `address`, see [`fill_template`]
+    pub fn checkout_url(&self, address: &str, amount: Option<f64>) -> Result<String, OnRampTemplateError> {
+        fill_template(&self.url_template, address, amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: &str = "8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL";
+    const TEMPLATE: &str = "https://buy.example.com/checkout?currency=sol&wallet={address}&fiat_amount={amount}";
+
+    #[test]
+    fn test_fill_template() {
+        assert_eq!(
+            fill_template(TEMPLATE, WALLET, Some(100.0)).unwrap(),
+            format!("https://buy.example.com/checkout?currency=sol&wallet={}&fiat_amount=100", WALLET)
+        );
+        assert!(fill_template(TEMPLATE, WALLET, Some(49.999)).unwrap().ends_with("&fiat_amount=50.00"));
+        assert!(fill_template(TEMPLATE, WALLET, Some(12.5)).unwrap().ends_with("&fiat_amount=12.50"));
+
+        // No amount: the parameter goes, the rest stays in order
+        assert_eq!(
+            fill_template(TEMPLATE, WALLET, None).unwrap(),
+            format!("https://buy.example.com/checkout?currency=sol&wallet={}", WALLET)
+        );
+        assert_eq!(
+            fill_template("https://ramp.example/?amount={amount}&to={address}#buy", WALLET, None).unwrap(),
+            format!("https://ramp.example/?to={}#buy", WALLET)
+        );
+    }
+
+    #[test]
+    fn test_templates_are_strict() {
+        use OnRampTemplateError::*;
+
+        assert_eq!(validate_template(TEMPLATE), Ok(()));
+        assert_eq!(validate_template("https://ramp.example/?flag&to={address}"), Ok(()));
+
+        assert_eq!(validate_template("http://ramp.example/?to={address}"), Err(NotHttps));
+        assert_eq!(validate_template("javascript:alert(1)//{address}"), Err(NotHttps));
+        assert_eq!(validate_template("https://"), Err(NotHttps));
+        // The address can't pick the host or path
+        assert_eq!(validate_template("https://{address}.ramp.example/"), Err(PlaceholderOutsideQuery));
+        assert_eq!(validate_template("https://ramp.example/{address}"), Err(PlaceholderOutsideQuery));
+        assert_eq!(validate_template("https://ramp.example/?{address}=1"), Err(PlaceholderOutsideQuery));
+        assert_eq!(validate_template("https://ramp.example/?to={address}#{amount}"), Err(PlaceholderOutsideQuery));
+        // Only whole values, only known names
+        assert_eq!(
+            validate_template("https://ramp.example/?to=sol:{address}"),
+            Err(MalformedPlaceholder("sol:{address}".to_string()))
+        );
+        assert_eq!(validate_template("https://ramp.example/?to={address}&x={{amount}}"), Err(MalformedPlaceholder("{{amount}}".to_string())));
+        assert_eq!(
+            validate_template("https://ramp.example/?to={address}&email={email}"),
+            Err(UnknownPlaceholder("email".to_string()))
+        );
+        assert_eq!(validate_template("https://ramp.example/?to=fixed"), Err(PlaceholderCount(ADDRESS_PLACEHOLDER)));
+        assert_eq!(
+            validate_template("https://ramp.example/?to={address}&again={address}"),
+            Err(PlaceholderCount(ADDRESS_PLACEHOLDER))
+        );
+        assert_eq!(
+            validate_template("https://ramp.example/?to={address}&a={amount}&b={amount}"),
+            Err(PlaceholderCount(AMOUNT_PLACEHOLDER))
+        );
+    }
+
+    #[test]
+    fn test_values_cant_inject() {
+        use OnRampTemplateError::*;
+
+        assert_eq!(fill_template(TEMPLATE, "evil&redirect=https://x.example", None), Err(InvalidAddress));
+        assert_eq!(fill_template(TEMPLATE, "", None), Err(InvalidAddress));
+        // Base58 has no 0, O, I or l
+        assert_eq!(fill_template(TEMPLATE, "0W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL", None), Err(InvalidAddress));
+        assert_eq!(fill_template(TEMPLATE, WALLET, Some(0.0)), Err(InvalidAmount));
+        assert_eq!(fill_template(TEMPLATE, WALLET, Some(-5.0)), Err(InvalidAmount));
+        assert_eq!(fill_template(TEMPLATE, WALLET, Some(f64::NAN)), Err(InvalidAmount));
+        assert_eq!(fill_template(TEMPLATE, WALLET, Some(f64::INFINITY)), Err(InvalidAmount));
+    }
+
+    #[test]
+    fn test_region_filter() {
+        let provider = |regions: &[&str]| OnRampProvider {
+            id: "ramp".to_string(),
+            name: "Ramp".to_string(),
+            url_template: TEMPLATE.to_string(),
+            regions: regions.iter().map(|r| r.to_string()).collect(),
+        };
+        assert!(provider(&[]).supports_region("NZ"));
+        assert!(provider(&["US", "GB"]).supports_region("gb"));
+        assert!(!provider(&["US", "GB"]).supports_region("DE"));
+
+        assert!(is_region_code("US"));
+        assert!(!is_region_code("USA"));
+        assert!(!is_region_code("U1"));
+    }
+}
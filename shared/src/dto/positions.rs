@@ -0,0 +1,52 @@
+//! # Position Data Transfer Objects
+//!
+//! Holdings the user acquired through swaps, with their weighted-average
+//! cost basis and PnL.
+//!
+//! ```text
+//! GET /api/portfolio/positions  → PositionsResponse
+//! ```
+//!
+//! Amounts are in whole tokens and values in USD. Tokens that arrived outside
+//! the terminal aren't tracked; a position whose numbers are known not to
+//! cover everything is marked `incomplete`.
+
+use serde::{Deserialize, Serialize};
+
+/// One token position, valued at the current price
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PositionItem {
+    pub mint: String,
+    /// `None` for mints missing from the token list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    pub quantity: f64,
+    /// Total cost of `quantity`
+    pub cost_basis_usd: f64,
+    /// Cost per token, `None` when nothing is held
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub average_cost_usd: Option<f64>,
+    /// `None` when no price (or no token list entry) is available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub market_value_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unrealized_pnl_usd: Option<f64>,
+    pub realized_pnl_usd: f64,
+    /// A sell exceeded the tracked quantity or a swap had no USD value
+    pub incomplete: bool,
+    /// Unix seconds; set for positions the user closed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<i64>,
+}
+
+/// Response for `GET /api/portfolio/positions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PositionsResponse {
+    /// Open positions first
+    pub positions: Vec<PositionItem>,
+    /// Sum over the positions with a price
+    pub total_unrealized_pnl_usd: f64,
+    pub total_realized_pnl_usd: f64,
+}
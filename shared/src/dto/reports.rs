@@ -0,0 +1,130 @@
+//! # Report Data Transfer Objects
+//!
+//! Periods of the downloadable account reports.
+//!
+//! ```text
+//! GET /api/reports/monthly?month=2025-03  → application/pdf
+//! ```
+//!
+//! Months are calendar months in UTC, written `YYYY-MM`.
+
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+/// A calendar month in UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReportMonth {
+    year: i32,
+    month: u32,
+}
+
+impl ReportMonth {
+    /// `None` unless `month` is 1 to 12 and the year is within chrono's range
+    pub fn new(year: i32, month: u32) -> Option<Self> {
+        let candidate = Self { year, month };
+        ((1..=12).contains(&month) && Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single().is_some())
+            .then_some(candidate)
+    }
+
+    /// The month `at` falls in
+    pub fn of(at: DateTime<Utc>) -> Self {
+        Self { year: at.year(), month: at.month() }
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    /// First instant of the month
+    pub fn start(&self) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(self.year, self.month, 1, 0, 0, 0)
+            .single()
+            .expect("validated in ReportMonth::new")
+    }
+
+    /// First instant of the following month, where the month ends (exclusive)
+    pub fn end(&self) -> DateTime<Utc> {
+        self.next().start()
+    }
+
+    pub fn previous(&self) -> Self {
+        match self.month {
+            1 => Self { year: self.year - 1, month: 12 },
+            month => Self { year: self.year, month: month - 1 },
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self.month {
+            12 => Self { year: self.year + 1, month: 1 },
+            month => Self { year: self.year, month: month + 1 },
+        }
+    }
+
+    /// "March 2025"
+    pub fn label(&self) -> String {
+        self.start().format("%B %Y").to_string()
+    }
+
+    /// The `count` months up to and including the month of `now`, latest first
+    pub fn recent(now: DateTime<Utc>, count: usize) -> Vec<Self> {
+        std::iter::successors(Some(Self::of(now)), |month| Some(month.previous()))
+            .take(count)
+            .collect()
+    }
+}
+
+impl fmt::Display for ReportMonth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+impl FromStr for ReportMonth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid month '{}', expected YYYY-MM", s);
+        let (year, month) = s.trim().split_once('-').ok_or_else(invalid)?;
+        if year.len() != 4 || month.len() != 2 {
+            return Err(invalid());
+        }
+        let year = year.parse::<i32>().map_err(|_| invalid())?;
+        let month = month.parse::<u32>().map_err(|_| invalid())?;
+        Self::new(year, month).ok_or_else(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let month: ReportMonth = "2025-03".parse().unwrap();
+        assert_eq!((month.year(), month.month()), (2025, 3));
+        assert_eq!(month.to_string(), "2025-03");
+        assert_eq!(month.label(), "March 2025");
+
+        for invalid in ["2025-13", "2025-00", "2025-3", "25-03", "2025/03", "March", ""] {
+            assert!(invalid.parse::<ReportMonth>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_bounds_cross_the_year() {
+        let december = ReportMonth::new(2024, 12).unwrap();
+        assert_eq!(december.start(), Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(december.end(), Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(december.next().previous(), december);
+
+        let now = Utc.with_ymd_and_hms(2025, 2, 14, 9, 30, 0).unwrap();
+        let months: Vec<String> = ReportMonth::recent(now, 3).iter().map(|m| m.to_string()).collect();
+        assert_eq!(months, vec!["2025-02", "2025-01", "2024-12"]);
+    }
+}
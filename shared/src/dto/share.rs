@@ -0,0 +1,226 @@
+//! # Share Link Data Transfer Objects
+//!
+//! Read-only snapshots of a chart or a portfolio allocation, published under a
+//! random slug for people who don't use the terminal.
+//!
+//! ## Flow
+//!
+//! ```text
+//! POST /api/share        { payload, expires_in_hours, anonymize }  → { slug, path, expires_at }
+//! GET  /share/{slug}                                               → HTML page (no auth)
+//! ```
+//!
+//! Chart snapshots reference a candle range; the backend copies those candles
+//! into the link when it is created, so the page keeps rendering after the
+//! candles age out of the live aggregator.
+//!
+//! Portfolio snapshots never carry wallet or mint addresses. The backend runs
+//! [`PortfolioShare::anonymized`] on every portfolio payload before storing it.
+
+use super::market::{Timeframe, OHLC};
+use serde::{Deserialize, Serialize};
+
+/// Longest expiry a share link may be created with (30 days)
+pub const MAX_SHARE_TTL_HOURS: i64 = 24 * 30;
+
+/// Label used for holdings whose symbol is an address
+pub const UNLABELED_HOLDING: &str = "Other";
+
+/// Snapshot stored behind a share link
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SharePayload {
+    Chart(ChartShare),
+    Portfolio(PortfolioShare),
+}
+
+impl SharePayload {
+    /// Kind name as stored alongside the payload
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SharePayload::Chart(_) => "chart",
+            SharePayload::Portfolio(_) => "portfolio",
+        }
+    }
+}
+
+/// Chart setup: symbol, timeframe, overlays and a candle range
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChartShare {
+    pub symbol: String,
+    pub timeframe: Timeframe,
+    /// Enabled indicator overlays, e.g. `"SMA 20"`
+    #[serde(default)]
+    pub indicators: Vec<String>,
+    /// Horizontal price lines drawn over the candles
+    #[serde(default)]
+    pub annotations: Vec<ChartAnnotation>,
+    /// First candle of the range (Unix seconds, inclusive)
+    pub from: i64,
+    /// Last candle of the range (Unix seconds, inclusive)
+    pub to: i64,
+    /// Candles of the range; filled in by the backend, ignored in requests
+    #[serde(default)]
+    pub candles: Vec<OHLC>,
+}
+
+/// Labelled price level on a shared chart
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChartAnnotation {
+    pub label: String,
+    pub price: f64,
+}
+
+/// Allocation of a portfolio by token
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PortfolioShare {
+    /// Total value in USD; `None` when values are hidden
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_value: Option<f64>,
+    pub allocations: Vec<ShareAllocation>,
+}
+
+/// One token's share of a portfolio
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ShareAllocation {
+    pub symbol: String,
+    /// Percent of the total value (0-100)
+    pub allocation_pct: f64,
+    /// Value in USD; `None` when values are hidden
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+}
+
+impl PortfolioShare {
+    /// Strip anything that could identify the wallet.
+    ///
+    /// Symbols that are really addresses (unlabelled mints) are merged into
+    /// one [`UNLABELED_HOLDING`] row, percentages are rounded to one decimal,
+    /// and with `hide_values` every USD amount is dropped so only the
+    /// allocation is left. Rows are sorted largest first.
+    pub fn anonymized(self, hide_values: bool) -> Self {
+        let mut allocations: Vec<ShareAllocation> = Vec::with_capacity(self.allocations.len());
+        for allocation in self.allocations {
+            let symbol = if looks_like_address(&allocation.symbol) {
+                UNLABELED_HOLDING.to_string()
+            } else {
+                allocation.symbol
+            };
+            match allocations.iter_mut().find(|a| a.symbol == symbol) {
+                Some(existing) => {
+                    existing.allocation_pct += allocation.allocation_pct;
+                    existing.value = match (existing.value, allocation.value) {
+                        (Some(a), Some(b)) => Some(a + b),
+                        (a, b) => a.or(b),
+                    };
+                }
+                None => allocations.push(ShareAllocation { symbol, ..allocation }),
+            }
+        }
+
+        for allocation in &mut allocations {
+            allocation.allocation_pct = (allocation.allocation_pct * 10.0).round() / 10.0;
+            if hide_values {
+                allocation.value = None;
+            }
+        }
+        allocations.sort_by(|a, b| b.allocation_pct.total_cmp(&a.allocation_pct));
+
+        Self {
+            total_value: if hide_values { None } else { self.total_value },
+            allocations,
+        }
+    }
+}
+
+/// Whether a symbol is really a base58 Solana address
+pub(crate) fn looks_like_address(symbol: &str) -> bool {
+    (32..=44).contains(&symbol.len())
+        && symbol.chars().all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'))
+}
+
+/// Request to publish a share link
+///
+/// Sent to `POST /api/share` (authenticated).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CreateShareRequest {
+    pub payload: SharePayload,
+    /// Hours until the link stops working (1 to [`MAX_SHARE_TTL_HOURS`]);
+    /// `None` keeps it until it is deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in_hours: Option<i64>,
+    /// Hide USD values in a portfolio snapshot, leaving only percentages
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
+/// A published share link
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CreateShareResponse {
+    pub slug: String,
+    /// Path of the public page, relative to the backend base URL
+    pub path: String,
+    /// RFC 3339 expiry time, if the link expires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allocation(symbol: &str, allocation_pct: f64, value: f64) -> ShareAllocation {
+        ShareAllocation { symbol: symbol.to_string(), allocation_pct, value: Some(value) }
+    }
+
+    fn portfolio() -> PortfolioShare {
+        PortfolioShare {
+            total_value: Some(1000.0),
+            allocations: vec![
+                allocation("SOL", 60.04, 600.4),
+                allocation("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", 25.0, 250.0),
+                allocation("USDC", 10.0, 100.0),
+                allocation("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263", 4.96, 49.6),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_anonymized_merges_addresses() {
+        let shared = portfolio().anonymized(false);
+        let symbols: Vec<&str> = shared.allocations.iter().map(|a| a.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["SOL", UNLABELED_HOLDING, "USDC"]);
+        assert_eq!(shared.allocations[0].allocation_pct, 60.0);
+        assert_eq!(shared.allocations[1].allocation_pct, 30.0);
+        assert_eq!(shared.allocations[1].value, Some(299.6));
+        assert_eq!(shared.total_value, Some(1000.0));
+    }
+
+    #[test]
+    fn test_anonymized_hides_values() {
+        let shared = portfolio().anonymized(true);
+        assert_eq!(shared.total_value, None);
+        assert!(shared.allocations.iter().all(|a| a.value.is_none()));
+
+        let json = serde_json::to_string(&SharePayload::Portfolio(shared)).unwrap();
+        assert!(!json.contains("value"));
+        assert!(!json.contains("EPjFWdd5"));
+    }
+
+    #[test]
+    fn test_short_symbols_are_not_addresses() {
+        assert!(!looks_like_address("SOL"));
+        assert!(!looks_like_address("JitoSOL"));
+        assert!(looks_like_address("So11111111111111111111111111111111111111112"));
+        // '0' isn't in the base58 alphabet
+        assert!(!looks_like_address("0000000000000000000000000000000000000000"));
+    }
+
+    #[test]
+    fn test_payload_is_tagged_by_kind() {
+        let payload = SharePayload::Portfolio(PortfolioShare { total_value: None, allocations: vec![] });
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["kind"], "portfolio");
+        assert_eq!(payload.kind(), "portfolio");
+    }
+}
@@ -0,0 +1,81 @@
+//! # Swap Simulation Data Transfer Objects
+//!
+//! Preflight check run before a swap is signed. The terminal sends the
+//! unsigned transaction it got from `POST /api/swap/execute`; the backend
+//! simulates it against the cluster and reports what it would do.
+//!
+//! ## Endpoint
+//!
+//! ```text
+//! POST /api/swap/simulate  { transaction, userPublicKey, inputMint, outputMint } → SimulateSwapResponse
+//! ```
+//!
+//! A transaction that would fail is still a 200 with `success: false`; the
+//! error and the tail of the program logs tell the user why. Fields are
+//! camelCase like the rest of the swap API.
+
+use serde::{Deserialize, Serialize};
+
+/// Program log lines kept in [`SimulateSwapResponse::log_tail`]
+pub const SIMULATION_LOG_TAIL: usize = 12;
+
+/// Unsigned swap transaction to simulate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateSwapRequest {
+    /// Base64-encoded unsigned transaction, as returned by `/api/swap/execute`
+    pub transaction: String,
+    /// Wallet that will sign the transaction
+    pub user_public_key: String,
+    pub input_mint: String,
+    pub output_mint: String,
+}
+
+/// Change of one token balance of the wallet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenBalanceChange {
+    pub mint: String,
+    pub decimals: u8,
+    /// Change in base units; negative when tokens leave the wallet
+    pub change: i64,
+}
+
+impl TokenBalanceChange {
+    /// Change in tokens
+    pub fn ui_change(&self) -> f64 {
+        self.change as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// What the swap would do if it were signed now
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateSwapResponse {
+    /// Whether the transaction would succeed
+    pub success: bool,
+    /// Readable reason the transaction would fail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Compute units consumed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compute_units: Option<u64>,
+    /// Network fee in lamports
+    pub network_fee_lamports: u64,
+    /// Change of the wallet's SOL balance in lamports, fee and rent included
+    pub sol_change_lamports: i64,
+    /// Changes of the wallet's token balances, SOL excluded
+    pub token_changes: Vec<TokenBalanceChange>,
+    /// Full program logs
+    pub logs: Vec<String>,
+    /// Last program log lines, where the failing instruction explains itself
+    pub log_tail: Vec<String>,
+}
+
+impl SimulateSwapResponse {
+    /// SOL leaving the wallet for the swap itself, network fee excluded
+    pub fn sol_sent_lamports(&self) -> u64 {
+        let spent = (-self.sol_change_lamports).max(0) as u64;
+        spent.saturating_sub(self.network_fee_lamports)
+    }
+}
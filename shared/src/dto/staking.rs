@@ -0,0 +1,261 @@
+//! # Staking Data Transfer Objects
+//!
+//! Native SOL staking: a wallet's stake accounts, the rewards they earned,
+//! and unsigned transactions that create, deactivate and withdraw them. The
+//! backend builds the transactions; the terminal signs them locally and
+//! submits them like swaps.
+//!
+//! ```text
+//! GET  /api/staking/info?address=…          → StakingInfo
+//! GET  /api/staking/rewards?address=…       → StakingRewardsResponse
+//! POST /api/staking/delegate   DelegateStakeRequest  → StakeTransactionResponse
+//! POST /api/staking/deactivate StakeAccountRequest   → StakeTransactionResponse
+//! POST /api/staking/withdraw   StakeAccountRequest   → StakeTransactionResponse
+//! ```
+//!
+//! ## Lifecycle
+//!
+//! A new stake account is activating until the next epoch starts, then
+//! active. Deactivated stake stops earning at once but stays locked in the
+//! account until the epoch ends (the cooldown); only then can it be
+//! withdrawn. When a lot of stake moves at once across the network, warmup
+//! and cooldown can take several epochs.
+
+use serde::{Deserialize, Serialize};
+
+/// Size of a stake account in bytes
+pub const STAKE_ACCOUNT_LEN: usize = 200;
+
+/// Lamports in one SOL
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Average slot time, for estimating when an epoch ends
+pub const SLOT_DURATION_MS: u64 = 400;
+
+/// Most epochs of rewards fetched at once
+pub const MAX_REWARD_EPOCHS: u64 = 10;
+
+/// Where a stake account is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StakeActivation {
+    /// Delegated this epoch; starts earning next epoch
+    Activating,
+    /// Delegated and earning
+    Active,
+    /// Deactivated this epoch; withdrawable next epoch
+    Deactivating,
+    /// Not delegated, or fully cooled down; withdrawable
+    Inactive,
+}
+
+impl StakeActivation {
+    /// State at `epoch` of stake delegated at `activation_epoch` and
+    /// deactivated at `deactivation_epoch`, if it was
+    ///
+    /// Warmup and cooldown are taken to finish at the next epoch boundary.
+    /// Stake deactivated in the epoch it was delegated never activates.
+    pub fn at_epoch(activation_epoch: u64, deactivation_epoch: Option<u64>, epoch: u64) -> Self {
+        match deactivation_epoch {
+            Some(deactivation) if deactivation == activation_epoch || epoch > deactivation => Self::Inactive,
+            Some(_) => Self::Deactivating,
+            // Genesis stake is recorded as activated at u64::MAX
+            None if epoch > activation_epoch || activation_epoch == u64::MAX => Self::Active,
+            None => Self::Activating,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Activating => "Activating",
+            Self::Active => "Active",
+            Self::Deactivating => "Deactivating",
+            Self::Inactive => "Inactive",
+        }
+    }
+}
+
+/// One stake account of a wallet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StakeAccountInfo {
+    /// Stake account address
+    pub address: String,
+    /// Balance, including the rent-exempt reserve
+    pub lamports: u64,
+    /// Part of the balance that can't be staked
+    pub rent_exempt_reserve: u64,
+    /// Vote account of the validator delegated to; None if never delegated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voter: Option<String>,
+    /// Delegated lamports; 0 if never delegated
+    pub delegated_lamports: u64,
+    /// Epoch the delegation started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activation_epoch: Option<u64>,
+    /// Epoch the stake was deactivated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deactivation_epoch: Option<u64>,
+    pub state: StakeActivation,
+}
+
+impl StakeAccountInfo {
+    /// The stake is (becoming) active and can be deactivated
+    pub fn can_deactivate(&self) -> bool {
+        matches!(self.state, StakeActivation::Activating | StakeActivation::Active)
+    }
+
+    /// The balance is unlocked and can be withdrawn
+    pub fn can_withdraw(&self) -> bool {
+        self.state == StakeActivation::Inactive
+    }
+}
+
+/// Response for `GET /api/staking/info`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StakingInfo {
+    /// Wallet whose stake accounts are listed (as withdraw authority)
+    pub address: String,
+    /// Current epoch
+    pub epoch: u64,
+    /// Slots elapsed in the current epoch
+    pub slot_index: u64,
+    /// Slots in the current epoch
+    pub slots_in_epoch: u64,
+    pub accounts: Vec<StakeAccountInfo>,
+    /// Smallest delegation the stake program accepts
+    pub minimum_delegation: u64,
+    /// Rent-exempt reserve a new stake account needs on top of the stake
+    pub rent_exempt_reserve: u64,
+}
+
+impl StakingInfo {
+    /// Estimated seconds until the current epoch ends
+    pub fn epoch_remaining_secs(&self) -> u64 {
+        self.slots_in_epoch.saturating_sub(self.slot_index) * SLOT_DURATION_MS / 1000
+    }
+
+    /// Lamports delegated across all accounts
+    pub fn total_delegated(&self) -> u64 {
+        self.accounts.iter().map(|account| account.delegated_lamports).sum()
+    }
+}
+
+/// Inflation reward paid to a stake account for one epoch
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StakeReward {
+    /// Epoch the reward was earned in (paid at the start of the next)
+    pub epoch: u64,
+    pub stake_account: String,
+    /// Reward in lamports
+    pub lamports: u64,
+    /// Account balance after the reward
+    pub post_balance: u64,
+    /// Validator commission at the time, in percent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commission: Option<u8>,
+}
+
+/// Response for `GET /api/staking/rewards`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StakingRewardsResponse {
+    pub address: String,
+    /// Newest epoch first
+    pub rewards: Vec<StakeReward>,
+}
+
+/// Request for `POST /api/staking/delegate`: a new stake account funded
+/// from `wallet` and delegated to `vote_account`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DelegateStakeRequest {
+    /// Fee payer, funder, and stake and withdraw authority
+    pub wallet: String,
+    /// Vote account of the validator
+    pub vote_account: String,
+    /// Lamports to stake; the rent-exempt reserve is added on top
+    pub lamports: u64,
+}
+
+/// Request for `POST /api/staking/deactivate` and `POST /api/staking/withdraw`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StakeAccountRequest {
+    /// Stake (deactivate) or withdraw (withdraw) authority of the account
+    pub wallet: String,
+    pub stake_account: String,
+}
+
+/// Unsigned staking transaction, to be signed by the wallet and submitted
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StakeTransactionResponse {
+    /// Base64-encoded (bincode) legacy transaction
+    pub transaction: String,
+    #[serde(default)]
+    pub version: super::transactions::TransactionVersion,
+    pub last_valid_block_height: u64,
+    /// Stake account created or acted on
+    pub stake_account: String,
+    /// Lamports leaving (delegate) or returning to (withdraw) the wallet
+    pub lamports: u64,
+}
+
+/// Check an amount to stake; returns the lamports to fund the new account with
+pub fn validate_stake_amount(lamports: u64, minimum_delegation: u64, rent_exempt_reserve: u64) -> Result<u64, String> {
+    if lamports == 0 {
+        return Err("Amount must be more than zero".to_string());
+    }
+    if lamports < minimum_delegation {
+        return Err(format!("The minimum stake is {} SOL", format_sol(minimum_delegation)));
+    }
+    lamports
+        .checked_add(rent_exempt_reserve)
+        .ok_or_else(|| "Amount is too large".to_string())
+}
+
+/// Lamports as SOL without trailing zeros, e.g. "1" or "0.002282"
+pub fn format_sol(lamports: u64) -> String {
+    let whole = lamports / LAMPORTS_PER_SOL;
+    let fraction = lamports % LAMPORTS_PER_SOL;
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:09}", fraction);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activation_at_epoch() {
+        assert_eq!(StakeActivation::at_epoch(100, None, 100), StakeActivation::Activating);
+        assert_eq!(StakeActivation::at_epoch(100, None, 101), StakeActivation::Active);
+        assert_eq!(StakeActivation::at_epoch(u64::MAX, None, 5), StakeActivation::Active);
+        assert_eq!(StakeActivation::at_epoch(100, Some(120), 120), StakeActivation::Deactivating);
+        assert_eq!(StakeActivation::at_epoch(100, Some(120), 121), StakeActivation::Inactive);
+        // Deactivated before it ever activated
+        assert_eq!(StakeActivation::at_epoch(100, Some(100), 100), StakeActivation::Inactive);
+    }
+
+    #[test]
+    fn test_validate_stake_amount() {
+        let reserve = 2_282_880;
+        assert_eq!(validate_stake_amount(1_000_000_000, 1_000_000_000, reserve), Ok(1_002_282_880));
+        assert!(validate_stake_amount(0, 1, reserve).is_err());
+        let below = validate_stake_amount(999_999_999, 1_000_000_000, reserve).unwrap_err();
+        assert!(below.contains("1 SOL"), "{}", below);
+        assert!(validate_stake_amount(u64::MAX, 1, reserve).is_err());
+    }
+
+    #[test]
+    fn test_format_sol() {
+        assert_eq!(format_sol(1_000_000_000), "1");
+        assert_eq!(format_sol(2_282_880), "0.00228288");
+        assert_eq!(format_sol(0), "0");
+    }
+
+    #[test]
+    fn test_epoch_remaining_secs() {
+        let info = StakingInfo { slot_index: 422_000, slots_in_epoch: 432_000, ..Default::default() };
+        assert_eq!(info.epoch_remaining_secs(), 4_000);
+    }
+}
@@ -0,0 +1,276 @@
+//! # System Data Transfer Objects
+//!
+//! Defines operator-facing notices that the backend pushes to connected clients,
+//! and the backend health report served by `GET /api/health`.
+//!
+//! ## Wire Format
+//!
+//! Notices are delivered over the price stream WebSocket (`/api/ws/prices`)
+//! alongside price updates, using the same `{type, data}` envelope:
+//!
+//! ```json
+//! {
+//!   "type": "system_notice",
+//!   "data": {
+//!     "level": "warning",
+//!     "title": "Program upgraded",
+//!     "message": "Batch swap router was redeployed at slot 251234567",
+//!     "timestamp": 1704067200
+//!   }
+//! }
+//! ```
+//!
+//! `category` lets clients mute kinds of notices; it is left out for
+//! [`NoticeCategory::System`], which is what notices without one are.
+//!
+//! A [`NoticeCategory::Maintenance`] notice also carries the window, so
+//! clients can quiesce ahead of it:
+//!
+//! ```json
+//! {
+//!   "type": "system_notice",
+//!   "data": {
+//!     "level": "warning",
+//!     "title": "Scheduled maintenance",
+//!     "message": "Database upgrade, back within 10 minutes",
+//!     "timestamp": 1704066000,
+//!     "category": "maintenance",
+//!     "maintenance": { "starts_at": 1704067200, "expected_duration_secs": 600 }
+//!   }
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Severity of a system notice
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NoticeLevel {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// What a system notice is about, for per-category notification preferences
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NoticeCategory {
+    /// Backend operations, e.g. a monitored program upgrade
+    #[default]
+    System,
+    /// Price sources disagreeing on a streamed symbol for a sustained period
+    PriceDivergence,
+    /// A held or favorite token has a scheduled unlock within
+    /// [`crate::dto::unlocks::UNLOCK_WARNING_DAYS`]; raised by the terminal
+    UnlockApproaching,
+    /// The backend is going down for scheduled maintenance
+    Maintenance,
+}
+
+impl NoticeCategory {
+    pub const ALL: [NoticeCategory; 4] = [
+        NoticeCategory::System,
+        NoticeCategory::PriceDivergence,
+        NoticeCategory::UnlockApproaching,
+        NoticeCategory::Maintenance,
+    ];
+
+    /// Human readable name for settings screens
+    pub fn label(&self) -> &'static str {
+        match self {
+            NoticeCategory::System => "System notices",
+            NoticeCategory::PriceDivergence => "Price source divergence",
+            NoticeCategory::UnlockApproaching => "Token unlock approaching",
+            NoticeCategory::Maintenance => "Scheduled maintenance",
+        }
+    }
+
+    fn is_system(&self) -> bool {
+        *self == NoticeCategory::System
+    }
+}
+
+/// System-wide notice raised by a backend job
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SystemNotice {
+    pub level: NoticeLevel,
+    pub title: String,
+    pub message: String,
+    /// Unix timestamp (seconds) when the notice was raised
+    pub timestamp: i64,
+    #[serde(default, skip_serializing_if = "NoticeCategory::is_system")]
+    pub category: NoticeCategory,
+    /// When the backend goes down, for maintenance notices
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintenance: Option<MaintenanceWindow>,
+}
+
+/// Scheduled downtime announced by a maintenance notice
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MaintenanceWindow {
+    /// Unix timestamp (seconds) the backend goes down at
+    pub starts_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_duration_secs: Option<u64>,
+}
+
+/// Request body for `POST /api/admin/maintenance`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MaintenanceNoticeRequest {
+    /// Unix timestamp (seconds) the backend goes down at
+    pub starts_at: i64,
+    #[serde(default)]
+    pub expected_duration_secs: Option<u64>,
+    /// What is being done, shown to users
+    pub message: String,
+}
+
+/// WebSocket envelope for a system notice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemNoticeMessage {
+    #[serde(rename = "type")]
+    pub message_type: String,
+    pub data: SystemNotice,
+}
+
+impl SystemNoticeMessage {
+    /// Message type tag used on the wire
+    pub const TYPE: &'static str = "system_notice";
+
+    /// Wrap a notice in the WebSocket envelope
+    pub fn new(notice: SystemNotice) -> Self {
+        Self {
+            message_type: Self::TYPE.to_string(),
+            data: notice,
+        }
+    }
+}
+
+/// Close reason the backend gives its streams when it shuts down.
+///
+/// Price WebSockets get it in a `1012 Service Restart` close frame and chat
+/// subscriptions as a final `{"closing": {"reason": ..}}` event. The server
+/// is expected back shortly, so clients should wait a few seconds before
+/// reconnecting rather than retry straight away.
+pub const SERVER_RESTARTING_REASON: &str = "server restarting";
+
+/// Health of the backend or one of its dependencies
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// Responding normally
+    Ok,
+    /// Usable, but a non-critical dependency is failing
+    Degraded,
+    /// Not usable
+    Down,
+}
+
+/// Result of probing a single backend dependency
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SubsystemHealth {
+    /// Subsystem name (`database`, `solana_rpc`, `jupiter`, `pyth`, `websocket`)
+    pub name: String,
+    pub status: HealthStatus,
+    /// Probe round-trip time in milliseconds
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Counters of one backend response cache since startup
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacheStats {
+    /// Cache name (`prices`, `token_list`, `ohlc`)
+    pub name: String,
+    pub hits: u64,
+    pub misses: u64,
+    /// Keys currently held
+    pub entries: usize,
+    pub ttl_ms: u64,
+}
+
+impl CacheStats {
+    /// Share of lookups served from cache, `None` before the first lookup
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+/// Response for `GET /api/health`
+///
+/// ```json
+/// {
+///   "status": "degraded",
+///   "uptime_secs": 86400,
+///   "subsystems": [
+///     { "name": "database", "status": "ok", "latency_ms": 1 },
+///     { "name": "solana_rpc", "status": "down", "latency_ms": 2000, "error": "Timed out after 2s" },
+///     { "name": "jupiter", "status": "ok", "latency_ms": 143 },
+///     { "name": "pyth", "status": "ok", "latency_ms": 98 },
+///     { "name": "websocket", "status": "ok", "latency_ms": 0 }
+///   ],
+///   "caches": [
+///     { "name": "prices", "hits": 1840, "misses": 212, "entries": 3, "ttl_ms": 2000 }
+///   ],
+///   "timestamp": 1704067200
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HealthResponse {
+    pub status: HealthStatus,
+    pub uptime_secs: u64,
+    pub subsystems: Vec<SubsystemHealth>,
+    /// Hit/miss counters of the market response caches
+    #[serde(default)]
+    pub caches: Vec<CacheStats>,
+    /// Unix timestamp (seconds) of the check
+    pub timestamp: i64,
+    /// Path wallet-web is served under when the backend embeds it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_path: Option<String>,
+}
+
+impl HealthResponse {
+    /// Look up a subsystem by name
+    pub fn subsystem(&self, name: &str) -> Option<&SubsystemHealth> {
+        self.subsystems.iter().find(|s| s.name == name)
+    }
+
+    /// Subsystems that are not [`HealthStatus::Ok`]
+    pub fn degraded_subsystems(&self) -> impl Iterator<Item = &SubsystemHealth> {
+        self.subsystems.iter().filter(|s| s.status != HealthStatus::Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notice_category_defaults_to_system() {
+        // As sent before categories existed
+        let old = r#"{"type":"system_notice","data":{"level":"warning","title":"Program upgraded","message":"Redeployed","timestamp":1704067200}}"#;
+        let message: SystemNoticeMessage = serde_json::from_str(old).unwrap();
+        assert_eq!(message.data.category, NoticeCategory::System);
+        assert_eq!(serde_json::to_string(&message).unwrap(), old);
+
+        let divergence = SystemNotice { category: NoticeCategory::PriceDivergence, ..message.data };
+        let json = serde_json::to_string(&divergence).unwrap();
+        assert!(json.ends_with(r#""category":"price_divergence"}"#));
+        assert_eq!(serde_json::from_str::<SystemNotice>(&json).unwrap(), divergence);
+    }
+
+    #[test]
+    fn test_maintenance_notice_round_trip() {
+        let json = r#"{"level":"warning","title":"Scheduled maintenance","message":"Database upgrade","timestamp":1704066000,"category":"maintenance","maintenance":{"starts_at":1704067200,"expected_duration_secs":600}}"#;
+        let notice: SystemNotice = serde_json::from_str(json).unwrap();
+        assert_eq!(notice.category, NoticeCategory::Maintenance);
+        assert_eq!(
+            notice.maintenance,
+            Some(MaintenanceWindow { starts_at: 1_704_067_200, expected_duration_secs: Some(600) })
+        );
+        assert_eq!(serde_json::to_string(&notice).unwrap(), json);
+    }
+}
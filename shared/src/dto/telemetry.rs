@@ -0,0 +1,383 @@
+//! # Usage Analytics Data Transfer Objects
+//!
+//! Anonymous counts of which screens and features of the terminal are used,
+//! sent only by users who opted in under Settings > Privacy.
+//!
+//! ## Endpoints
+//!
+//! ```text
+//! POST   /api/telemetry/usage               UsageReport → 204
+//! DELETE /api/telemetry/usage/{install_id}  → 204
+//! ```
+//!
+//! Neither endpoint takes a login: reports are tied to a random install id
+//! rather than an account, and whoever holds the id can delete its reports.
+//!
+//! ## Taxonomy
+//!
+//! Every counter is a [`UsageEvent`]: a screen view, the use of a feature, or
+//! the category of an error. The set is closed and a report is nothing but
+//! per-day counts of these events, so it can't carry amounts, tokens,
+//! addresses or free text; the backend refuses event keys it doesn't know.
+//!
+//! ```json
+//! {
+//!   "install_id": "0f8fad5b-d9cb-469f-a165-70867728950e",
+//!   "app_version": "0.1.0",
+//!   "days": [{ "day": "2025-03-01", "counts": { "screen.wallet": 4, "feature.swap": 1 } }]
+//! }
+//! ```
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Most days a single report may cover
+pub const MAX_REPORT_DAYS: usize = 31;
+
+/// Highest count accepted for one event on one day
+pub const MAX_DAILY_COUNT: u64 = 100_000;
+
+/// Screen of the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UsageScreen {
+    Landing,
+    Auth,
+    Terminal,
+    PythFeed,
+    JupiterFeed,
+    Wallet,
+    Portfolio,
+    Transactions,
+    Tokens,
+    Messaging,
+    AiChat,
+    Settings,
+    LiveChart,
+    LiveAssets,
+    LiveTable,
+}
+
+impl UsageScreen {
+    pub const ALL: [UsageScreen; 15] = [
+        UsageScreen::Landing,
+        UsageScreen::Auth,
+        UsageScreen::Terminal,
+        UsageScreen::PythFeed,
+        UsageScreen::JupiterFeed,
+        UsageScreen::Wallet,
+        UsageScreen::Portfolio,
+        UsageScreen::Transactions,
+        UsageScreen::Tokens,
+        UsageScreen::Messaging,
+        UsageScreen::AiChat,
+        UsageScreen::Settings,
+        UsageScreen::LiveChart,
+        UsageScreen::LiveAssets,
+        UsageScreen::LiveTable,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UsageScreen::Landing => "landing",
+            UsageScreen::Auth => "auth",
+            UsageScreen::Terminal => "terminal",
+            UsageScreen::PythFeed => "pyth_feed",
+            UsageScreen::JupiterFeed => "jupiter_feed",
+            UsageScreen::Wallet => "wallet",
+            UsageScreen::Portfolio => "portfolio",
+            UsageScreen::Transactions => "transactions",
+            UsageScreen::Tokens => "tokens",
+            UsageScreen::Messaging => "messaging",
+            UsageScreen::AiChat => "ai_chat",
+            UsageScreen::Settings => "settings",
+            UsageScreen::LiveChart => "live_chart",
+            UsageScreen::LiveAssets => "live_assets",
+            UsageScreen::LiveTable => "live_table",
+        }
+    }
+}
+
+/// Feature used, counted once per completed action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UsageFeature {
+    /// Swap signed and submitted
+    Swap,
+    /// Transfer sent from the Wallet screen
+    Transfer,
+    /// "Buy SOL" dialog opened
+    BuySol,
+    /// Search palette opened
+    Search,
+    /// Command palette opened
+    CommandPalette,
+    /// Share link created for a chart or the portfolio
+    Share,
+    /// Trades imported from a CSV export
+    TradeImport,
+    /// Transactions or swap history exported to CSV
+    Export,
+    /// Rebalancing trades proposed
+    Rebalance,
+    /// Message sent to the AI assistant
+    AiChat,
+    /// Webhook registered
+    Webhook,
+    /// Screen opened in a window of its own
+    NewWindow,
+    /// Stake delegated, deactivated or withdrawn
+    Stake,
+}
+
+impl UsageFeature {
+    pub const ALL: [UsageFeature; 13] = [
+        UsageFeature::Swap,
+        UsageFeature::Transfer,
+        UsageFeature::BuySol,
+        UsageFeature::Search,
+        UsageFeature::CommandPalette,
+        UsageFeature::Share,
+        UsageFeature::TradeImport,
+        UsageFeature::Export,
+        UsageFeature::Rebalance,
+        UsageFeature::AiChat,
+        UsageFeature::Webhook,
+        UsageFeature::NewWindow,
+        UsageFeature::Stake,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UsageFeature::Swap => "swap",
+            UsageFeature::Transfer => "transfer",
+            UsageFeature::BuySol => "buy_sol",
+            UsageFeature::Search => "search",
+            UsageFeature::CommandPalette => "command_palette",
+            UsageFeature::Share => "share",
+            UsageFeature::TradeImport => "trade_import",
+            UsageFeature::Export => "export",
+            UsageFeature::Rebalance => "rebalance",
+            UsageFeature::AiChat => "ai_chat",
+            UsageFeature::Webhook => "webhook",
+            UsageFeature::NewWindow => "new_window",
+            UsageFeature::Stake => "stake",
+        }
+    }
+}
+
+/// Where an error the user saw came from; never its message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UsageErrorCategory {
+    /// Preparing or submitting a swap failed
+    Swap,
+    /// Preparing or sending a transfer failed
+    Transfer,
+    /// Connecting the wallet or loading its balances failed
+    Wallet,
+    /// Login or signup failed
+    Login,
+    /// A request to the backend was rejected or timed out
+    Backend,
+    /// A background task panicked or was cancelled
+    Task,
+}
+
+impl UsageErrorCategory {
+    pub const ALL: [UsageErrorCategory; 6] = [
+        UsageErrorCategory::Swap,
+        UsageErrorCategory::Transfer,
+        UsageErrorCategory::Wallet,
+        UsageErrorCategory::Login,
+        UsageErrorCategory::Backend,
+        UsageErrorCategory::Task,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UsageErrorCategory::Swap => "swap",
+            UsageErrorCategory::Transfer => "transfer",
+            UsageErrorCategory::Wallet => "wallet",
+            UsageErrorCategory::Login => "login",
+            UsageErrorCategory::Backend => "backend",
+            UsageErrorCategory::Task => "task",
+        }
+    }
+}
+
+/// Something counted by usage analytics
+///
+/// Serialized as its [key](UsageEvent::key), e.g. `"screen.wallet"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum UsageEvent {
+    ScreenView(UsageScreen),
+    Feature(UsageFeature),
+    Error(UsageErrorCategory),
+}
+
+impl UsageEvent {
+    /// Every event of the taxonomy
+    pub fn all() -> impl Iterator<Item = UsageEvent> {
+        let screens = UsageScreen::ALL.into_iter().map(UsageEvent::ScreenView);
+        let features = UsageFeature::ALL.into_iter().map(UsageEvent::Feature);
+        let errors = UsageErrorCategory::ALL.into_iter().map(UsageEvent::Error);
+        screens.chain(features).chain(errors)
+    }
+
+    /// Wire name: `screen.*`, `feature.*` or `error.*`
+    pub fn key(&self) -> String {
+        match self {
+            UsageEvent::ScreenView(screen) => format!("screen.{}", screen.as_str()),
+            UsageEvent::Feature(feature) => format!("feature.{}", feature.as_str()),
+            UsageEvent::Error(category) => format!("error.{}", category.as_str()),
+        }
+    }
+
+    /// The event named by `key`; None for anything outside the taxonomy
+    pub fn from_key(key: &str) -> Option<Self> {
+        let (kind, name) = key.split_once('.')?;
+        match kind {
+            "screen" => UsageScreen::ALL.into_iter().find(|s| s.as_str() == name).map(UsageEvent::ScreenView),
+            "feature" => UsageFeature::ALL.into_iter().find(|f| f.as_str() == name).map(UsageEvent::Feature),
+            "error" => UsageErrorCategory::ALL.into_iter().find(|c| c.as_str() == name).map(UsageEvent::Error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for UsageEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.key())
+    }
+}
+
+impl From<UsageEvent> for String {
+    fn from(event: UsageEvent) -> Self {
+        event.key()
+    }
+}
+
+impl TryFrom<String> for UsageEvent {
+    type Error = String;
+
+    fn try_from(key: String) -> Result<Self, String> {
+        UsageEvent::from_key(&key).ok_or_else(|| format!("unknown usage event {:?}", key))
+    }
+}
+
+/// Counts of one UTC day
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UsageDay {
+    pub day: NaiveDate,
+    pub counts: BTreeMap<UsageEvent, u64>,
+}
+
+/// Body of `POST /api/telemetry/usage`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UsageReport {
+    /// Random id of the installation, a UUID; not linked to any account
+    pub install_id: String,
+    pub app_version: String,
+    /// Completed days, oldest first
+    pub days: Vec<UsageDay>,
+}
+
+impl UsageReport {
+    /// Why the backend would refuse this report, if it would
+    ///
+    /// Event keys are already checked when the report is deserialized.
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_install_id(&self.install_id) {
+            return Err("install_id must be a UUID".to_string());
+        }
+        if self.app_version.is_empty() || self.app_version.len() > 32 {
+            return Err("app_version must be 1 to 32 characters".to_string());
+        }
+        if self.days.is_empty() || self.days.len() > MAX_REPORT_DAYS {
+            return Err(format!("a report covers 1 to {} days", MAX_REPORT_DAYS));
+        }
+        if self.days.windows(2).any(|pair| pair[0].day >= pair[1].day) {
+            return Err("days must be in order and not repeat".to_string());
+        }
+        if self.days.iter().flat_map(|day| day.counts.values()).any(|count| *count > MAX_DAILY_COUNT) {
+            return Err(format!("counts are at most {} a day", MAX_DAILY_COUNT));
+        }
+        Ok(())
+    }
+}
+
+/// Whether `id` looks like a hyphenated UUID, e.g. `0f8fad5b-d9cb-469f-a165-70867728950e`
+pub fn is_install_id(id: &str) -> bool {
+    let groups: Vec<&str> = id.split('-').collect();
+    groups.len() == 5
+        && groups.iter().zip([8, 4, 4, 4, 12]).all(|(group, len)| {
+            group.len() == len && group.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b.to_ascii_lowercase()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSTALL_ID: &str = "0f8fad5b-d9cb-469f-a165-70867728950e";
+
+    fn day(d: u32, counts: &[(UsageEvent, u64)]) -> UsageDay {
+        UsageDay { day: NaiveDate::from_ymd_opt(2025, 3, d).unwrap(), counts: counts.iter().copied().collect() }
+    }
+
+    #[test]
+    fn test_keys_round_trip_and_are_unique() {
+        let keys: std::collections::HashSet<String> = UsageEvent::all().map(|event| event.key()).collect();
+        assert_eq!(keys.len(), UsageScreen::ALL.len() + UsageFeature::ALL.len() + UsageErrorCategory::ALL.len());
+        for event in UsageEvent::all() {
+            assert_eq!(UsageEvent::from_key(&event.key()), Some(event));
+        }
+        assert_eq!(UsageEvent::ScreenView(UsageScreen::Wallet).key(), "screen.wallet");
+        assert_eq!(UsageEvent::from_key("feature.buy_sol"), Some(UsageEvent::Feature(UsageFeature::BuySol)));
+        assert_eq!(UsageEvent::from_key("screen.7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"), None);
+        assert_eq!(UsageEvent::from_key("wallet"), None);
+    }
+
+    #[test]
+    fn test_wire_format_refuses_unknown_events() {
+        let report = UsageReport {
+            install_id: INSTALL_ID.to_string(),
+            app_version: "0.1.0".to_string(),
+            days: vec![day(1, &[(UsageEvent::ScreenView(UsageScreen::Wallet), 4), (UsageEvent::Feature(UsageFeature::Swap), 1)])],
+        };
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            json,
+            r#"{"install_id":"0f8fad5b-d9cb-469f-a165-70867728950e","app_version":"0.1.0","days":[{"day":"2025-03-01","counts":{"screen.wallet":4,"feature.swap":1}}]}"#
+        );
+        assert_eq!(serde_json::from_str::<UsageReport>(&json).unwrap(), report);
+
+        let smuggled = json.replace("feature.swap", "note.bought 40 SOL");
+        assert!(serde_json::from_str::<UsageReport>(&smuggled).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let swap = UsageEvent::Feature(UsageFeature::Swap);
+        let report = |install_id: &str, days: Vec<UsageDay>| UsageReport {
+            install_id: install_id.to_string(),
+            app_version: "0.1.0".to_string(),
+            days,
+        };
+        assert_eq!(report(INSTALL_ID, vec![day(1, &[(swap, 2)]), day(2, &[])]).validate(), Ok(()));
+        assert_eq!(report(&INSTALL_ID.to_uppercase(), vec![day(1, &[])]).validate(), Ok(()));
+
+        assert!(report("alice@example.com", vec![day(1, &[])]).validate().is_err());
+        assert!(report("0f8fad5b-d9cb-469f-a165-70867728950", vec![day(1, &[])]).validate().is_err());
+        assert!(report("0f8fad5b-d9cb-469f-a165-7086772895zz", vec![day(1, &[])]).validate().is_err());
+        assert!(report(INSTALL_ID, vec![]).validate().is_err());
+        assert!(report(INSTALL_ID, vec![day(2, &[]), day(1, &[])]).validate().is_err());
+        assert!(report(INSTALL_ID, vec![day(1, &[]), day(1, &[])]).validate().is_err());
+        assert!(report(INSTALL_ID, vec![day(1, &[(swap, MAX_DAILY_COUNT + 1)])]).validate().is_err());
+        let too_long = (0..=MAX_REPORT_DAYS as i64)
+            .map(|offset| UsageDay { day: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap() + chrono::Duration::days(offset), counts: BTreeMap::new() })
+            .collect();
+        assert!(report(INSTALL_ID, too_long).validate().is_err());
+    }
+}
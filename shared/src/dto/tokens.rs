@@ -0,0 +1,122 @@
+//! # Token Data Transfer Objects
+//!
+//! Types describing SPL token holdings that both the backend and the terminal
+//! need to agree on.
+//!
+//! ## Token programs
+//!
+//! Token accounts belong either to the classic SPL Token program or to the
+//! Token-2022 (Token Extensions) program. Transfers must be sent to the program
+//! that owns the mint, so balances carry a [`TokenProgram`] marker:
+//!
+//! ```text
+//! { "mint": "...", "program": "token2022", "transfer_fee": { "basis_points": 50, "maximum_fee": 5000000 } }
+//! ```
+//!
+//! ## Transfer fees
+//!
+//! Token-2022 mints with the transfer-fee extension withhold part of every
+//! transfer from the recipient. [`TransferFee`] is the fee in effect for the
+//! current epoch and reproduces the on-chain calculation, so the terminal can
+//! show what the recipient will actually receive before anything is signed.
+
+use serde::{Deserialize, Serialize};
+
+/// Classic SPL Token program id
+pub const SPL_TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+/// Token-2022 (Token Extensions) program id
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// Program owning a token account and its mint
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenProgram {
+    /// Classic SPL Token program
+    #[default]
+    Spl,
+    /// Token-2022 (Token Extensions) program
+    #[serde(rename = "token2022")]
+    Token2022,
+}
+
+impl TokenProgram {
+    /// Program id as a base58 string
+    pub fn program_id(&self) -> &'static str {
+        match self {
+            TokenProgram::Spl => SPL_TOKEN_PROGRAM_ID,
+            TokenProgram::Token2022 => TOKEN_2022_PROGRAM_ID,
+        }
+    }
+
+    /// Program for a base58 program id, if it is a token program
+    pub fn from_program_id(program_id: &str) -> Option<Self> {
+        match program_id {
+            SPL_TOKEN_PROGRAM_ID => Some(TokenProgram::Spl),
+            TOKEN_2022_PROGRAM_ID => Some(TokenProgram::Token2022),
+            _ => None,
+        }
+    }
+}
+
+/// Transfer fee of a Token-2022 mint for the current epoch
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransferFee {
+    /// Fee rate in basis points (1/100 of a percent)
+    pub basis_points: u16,
+    /// Cap on the fee of a single transfer, in base units
+    pub maximum_fee: u64,
+}
+
+impl TransferFee {
+    /// Fee withheld from a transfer of `amount` base units.
+    ///
+    /// Same rounding as the Token-2022 program: the percentage is rounded up,
+    /// then capped at `maximum_fee`.
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        if self.basis_points == 0 || amount == 0 {
+            return 0;
+        }
+        let numerator = amount as u128 * self.basis_points as u128;
+        let fee = numerator.div_ceil(10_000);
+        fee.min(self.maximum_fee as u128) as u64
+    }
+
+    /// Base units the recipient ends up with when `amount` is sent
+    pub fn received(&self, amount: u64) -> u64 {
+        amount.saturating_sub(self.fee_for(amount))
+    }
+
+    /// Fee rate as a percentage, for display
+    pub fn percent(&self) -> f64 {
+        self.basis_points as f64 / 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_program_serialization() {
+        assert_eq!(serde_json::to_string(&TokenProgram::Token2022).unwrap(), "\"token2022\"");
+        assert_eq!(serde_json::to_string(&TokenProgram::Spl).unwrap(), "\"spl\"");
+        assert_eq!(TokenProgram::from_program_id(TOKEN_2022_PROGRAM_ID), Some(TokenProgram::Token2022));
+        assert_eq!(TokenProgram::from_program_id("11111111111111111111111111111111"), None);
+    }
+
+    #[test]
+    fn test_transfer_fee_rounds_up_and_caps() {
+        let fee = TransferFee { basis_points: 50, maximum_fee: 5_000 };
+
+        // 0.5% of 1_000_001 is 5000.005, rounded up then capped
+        assert_eq!(fee.fee_for(1_000_001), 5_000);
+        // 0.5% of 999 is 4.995, rounded up
+        assert_eq!(fee.fee_for(999), 5);
+        assert_eq!(fee.received(999), 994);
+        assert_eq!(fee.fee_for(0), 0);
+
+        let free = TransferFee { basis_points: 0, maximum_fee: 5_000 };
+        assert_eq!(free.received(1_000), 1_000);
+    }
+}
@@ -0,0 +1,164 @@
+//! # Trade Import Data Transfer Objects
+//!
+//! Types for importing historical trades from other platforms (CSV exports
+//! from centralized exchanges) and for the trade statistics that combine them
+//! with on-chain swaps.
+//!
+//! ## Import Flow
+//!
+//! ```text
+//! POST /api/swap/history/import  { csv, mapping, commit: false }  → preview (per-row errors)
+//! POST /api/swap/history/import  { csv, mapping, commit: true }   → rows stored, summary
+//! GET  /api/swap/stats                                            → PnL over swaps + imports
+//! ```
+//!
+//! ## CSV Columns
+//!
+//! | Field       | Default header | Accepted values                                        |
+//! |-------------|----------------|--------------------------------------------------------|
+//! | `timestamp` | `timestamp`    | RFC 3339, `YYYY-MM-DD[ HH:MM[:SS]]`, `MM/DD/YYYY[ HH:MM[:SS]]`, Unix seconds or milliseconds (UTC) |
+//! | `pair`      | `pair`         | `SOL/USDC`, `SOL-USDC` or `SOL_USDC`                   |
+//! | `side`      | `side`         | `buy` / `sell` (also `b` / `s`, any case)              |
+//! | `amount`    | `amount`       | Base asset quantity; thousands separators allowed      |
+//! | `price`     | `price`        | Quote per base; thousands separators and `$` allowed   |
+//! | `fee`       | `fee`          | Optional, in the quote asset; empty means 0            |
+//!
+//! Fields may be quoted (`"1,234.50"`), and quotes inside quoted fields are
+//! doubled as in RFC 4180. Exports with other header names are imported by
+//! sending a [`ColumnMapping`].
+
+use serde::{Deserialize, Serialize};
+
+/// Trade direction relative to the base asset
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+impl std::fmt::Display for TradeSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TradeSide::Buy => write!(f, "buy"),
+            TradeSide::Sell => write!(f, "sell"),
+        }
+    }
+}
+
+/// Where a trade came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeSource {
+    /// Swap executed through the terminal and confirmed on-chain
+    Onchain,
+    /// Imported from another platform; never reconciled against the chain
+    Import,
+}
+
+/// CSV header name for each trade field
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ColumnMapping {
+    pub timestamp: String,
+    pub pair: String,
+    pub side: String,
+    pub amount: String,
+    pub price: String,
+    /// Fee column; `None` when the export has no fees
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<String>,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            timestamp: "timestamp".to_string(),
+            pair: "pair".to_string(),
+            side: "side".to_string(),
+            amount: "amount".to_string(),
+            price: "price".to_string(),
+            fee: Some("fee".to_string()),
+        }
+    }
+}
+
+/// Request for `POST /api/swap/history/import`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeImportRequest {
+    /// Raw CSV text including the header row
+    pub csv: String,
+    #[serde(default)]
+    pub mapping: ColumnMapping,
+    /// Store the valid rows; `false` only validates (preview)
+    #[serde(default)]
+    pub commit: bool,
+}
+
+/// A validated trade row
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParsedTrade {
+    /// 1-based data row number (the header is row 0)
+    pub row: usize,
+    /// Unix timestamp (seconds, UTC)
+    pub timestamp: i64,
+    pub base: String,
+    pub quote: String,
+    pub side: TradeSide,
+    pub amount: f64,
+    pub price: f64,
+    pub fee: f64,
+}
+
+/// Validation error for one CSV row
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RowError {
+    /// 1-based data row number; 0 for header problems
+    pub row: usize,
+    /// Field that failed, if the error is field-specific
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Response for `POST /api/swap/history/import`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TradeImportResponse {
+    /// Header row of the uploaded CSV (for the column-mapping UI)
+    pub headers: Vec<String>,
+    /// Data rows in the file
+    pub total_rows: usize,
+    /// Rows that passed validation and are not duplicates
+    pub valid: Vec<ParsedTrade>,
+    pub errors: Vec<RowError>,
+    /// Rows skipped because they repeat an earlier row or an already imported trade
+    pub duplicates: usize,
+    /// Rows stored (always 0 for a preview)
+    pub imported: usize,
+    pub committed: bool,
+}
+
+/// Realized PnL for one trading pair (average-cost method)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PairStats {
+    /// `BASE/QUOTE`
+    pub pair: String,
+    pub trades: usize,
+    /// Quote-asset volume
+    pub volume: f64,
+    /// Fees paid in the quote asset
+    pub fees: f64,
+    /// Realized PnL in the quote asset, after fees
+    pub realized_pnl: f64,
+    /// Base asset still held from these trades
+    pub open_position: f64,
+    /// Average cost of the open position
+    pub average_cost: f64,
+}
+
+/// Response for `GET /api/swap/stats`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TradeStatsResponse {
+    pub onchain_trades: usize,
+    pub imported_trades: usize,
+    pub pairs: Vec<PairStats>,
+}
@@ -0,0 +1,127 @@
+//! # Transaction Status Data Transfer Objects
+//!
+//! Confirmation progress of a transaction the terminal submitted. The
+//! terminal watches each signature on chain and reports where it ended up, so
+//! the backend's swap record matches what the user saw.
+//!
+//! ## Endpoint
+//!
+//! ```text
+//! PUT /api/transaction/{signature}/status  { status, error } → 204
+//! ```
+//!
+//! ## Progression
+//!
+//! ```text
+//! pending → processed → confirmed → finalized
+//!        ↘ failed (with the on-chain error)
+//!        ↘ expired (the blockhash ran out before the transaction landed)
+//! ```
+//!
+//! ## Message Versions
+//!
+//! Swaps touching more accounts than a legacy message can address are built
+//! as v0 messages that load accounts from Address Lookup Tables. The
+//! [`TransactionVersion`] travels with the unsigned transaction and back with
+//! the signed one so neither side has to guess how to decode it.
+
+use serde::{Deserialize, Serialize};
+
+/// Where a submitted transaction is in its confirmation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionStatus {
+    /// Submitted, not seen by the cluster yet
+    Pending,
+    /// Included in a block on the node's fork
+    Processed,
+    /// Voted on by a supermajority
+    Confirmed,
+    /// Rooted; it can no longer be rolled back
+    Finalized,
+    /// Landed with an error
+    Failed,
+    /// Never landed and its blockhash is no longer valid
+    Expired,
+}
+
+impl TransactionStatus {
+    /// Wire name, also shown in the Transactions table
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionStatus::Pending => "pending",
+            TransactionStatus::Processed => "processed",
+            TransactionStatus::Confirmed => "confirmed",
+            TransactionStatus::Finalized => "finalized",
+            TransactionStatus::Failed => "failed",
+            TransactionStatus::Expired => "expired",
+        }
+    }
+
+    /// Whether the status can no longer change
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            TransactionStatus::Finalized | TransactionStatus::Failed | TransactionStatus::Expired
+        )
+    }
+}
+
+/// Message format of a serialized transaction
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionVersion {
+    /// Every account listed in the message; clients predating versions send these
+    #[default]
+    Legacy,
+    /// Versioned message that may load accounts from lookup tables
+    V0,
+}
+
+impl TransactionVersion {
+    /// Wire name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionVersion::Legacy => "legacy",
+            TransactionVersion::V0 => "v0",
+        }
+    }
+}
+
+/// Body of `PUT /api/transaction/{signature}/status`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransactionStatusUpdate {
+    pub status: TransactionStatus,
+    /// On-chain error when `status` is `failed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_wire_format() {
+        let update = TransactionStatusUpdate {
+            status: TransactionStatus::Failed,
+            error: Some("custom program error: 0x1771".to_string()),
+        };
+        let json = serde_json::to_string(&update).unwrap();
+        assert_eq!(json, r#"{"status":"failed","error":"custom program error: 0x1771"}"#);
+
+        let parsed: TransactionStatusUpdate = serde_json::from_str(r#"{"status":"finalized"}"#).unwrap();
+        assert_eq!(parsed.status, TransactionStatus::Finalized);
+        assert_eq!(parsed.status.as_str(), "finalized");
+        assert!(parsed.status.is_final());
+        assert!(!TransactionStatus::Confirmed.is_final());
+    }
+
+    #[test]
+    fn test_version_wire_format() {
+        assert_eq!(serde_json::to_string(&TransactionVersion::V0).unwrap(), r#""v0""#);
+        assert_eq!(serde_json::from_str::<TransactionVersion>(r#""legacy""#).unwrap(), TransactionVersion::Legacy);
+        assert_eq!(TransactionVersion::default(), TransactionVersion::Legacy);
+        assert_eq!(TransactionVersion::V0.as_str(), "v0");
+    }
+}
@@ -0,0 +1,142 @@
+//! # Token Unlock Data Transfer Objects
+//!
+//! Scheduled releases of locked tokens (vesting cliffs, team and investor
+//! unlocks), which tend to move the price of the token.
+//!
+//! ```text
+//! GET    /api/market/unlocks?days=90            → TokenUnlocksResponse
+//! POST   /api/admin/token-unlocks               TokenUnlockRequest → TokenUnlockInfo
+//! PUT    /api/admin/token-unlocks/{id}          TokenUnlockRequest → TokenUnlockInfo
+//! DELETE /api/admin/token-unlocks/{id}
+//! ```
+//!
+//! Amounts and supplies are in whole tokens; times are Unix seconds.
+
+use serde::{Deserialize, Serialize};
+
+/// Unlocks this close count as approaching: portfolio rows get a warning
+/// and the terminal raises an "unlock approaching" notification
+pub const UNLOCK_WARNING_DAYS: i64 = 7;
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Size of an unlock as a percentage of the circulating supply.
+///
+/// `None` without a usable supply; a bogus amount from the dataset shouldn't
+/// show up as a huge or negative unlock.
+pub fn relative_size_pct(amount: f64, circulating_supply: Option<f64>) -> Option<f64> {
+    let supply = circulating_supply.filter(|s| s.is_finite() && *s > 0.0)?;
+    (amount.is_finite() && amount >= 0.0).then(|| amount / supply * 100.0)
+}
+
+/// One scheduled unlock
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenUnlockInfo {
+    pub id: i64,
+    pub mint: String,
+    pub symbol: String,
+    /// Unix seconds
+    pub unlock_at: i64,
+    /// Tokens released
+    pub amount: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circulating_supply: Option<f64>,
+    /// `amount` relative to the circulating supply, in percent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pct_of_circulating: Option<f64>,
+    /// Allocation bucket, e.g. "Team"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocation: Option<String>,
+    /// `import` (unlocks dataset) or `admin`
+    pub source: String,
+}
+
+impl TokenUnlockInfo {
+    /// Whole days until the unlock, rounded up; 0 once it is due
+    pub fn days_until(&self, now: i64) -> i64 {
+        let secs = (self.unlock_at - now).max(0);
+        (secs + SECS_PER_DAY - 1) / SECS_PER_DAY
+    }
+
+    /// Still ahead and within [`UNLOCK_WARNING_DAYS`]
+    pub fn is_approaching(&self, now: i64) -> bool {
+        self.unlock_at > now && self.unlock_at - now <= UNLOCK_WARNING_DAYS * SECS_PER_DAY
+    }
+}
+
+/// Response for `GET /api/market/unlocks`, soonest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TokenUnlocksResponse {
+    pub unlocks: Vec<TokenUnlockInfo>,
+}
+
+/// Admin request to create or replace an unlock
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenUnlockRequest {
+    pub mint: String,
+    pub symbol: String,
+    /// Unix seconds
+    pub unlock_at: i64,
+    pub amount: f64,
+    #[serde(default)]
+    pub circulating_supply: Option<f64>,
+    #[serde(default)]
+    pub allocation: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unlock_at(unlock_at: i64) -> TokenUnlockInfo {
+        TokenUnlockInfo {
+            id: 1,
+            mint: "JUPmint".to_string(),
+            symbol: "JUP".to_string(),
+            unlock_at,
+            amount: 1_000.0,
+            circulating_supply: None,
+            pct_of_circulating: None,
+            allocation: None,
+            source: "import".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_relative_size_pct() {
+        assert_eq!(relative_size_pct(50_000.0, Some(1_000_000.0)), Some(5.0));
+        assert_eq!(relative_size_pct(0.0, Some(1_000_000.0)), Some(0.0));
+        // More than the circulating supply: heavy dilution, not an error
+        assert_eq!(relative_size_pct(2_000.0, Some(1_000.0)), Some(200.0));
+
+        assert_eq!(relative_size_pct(50_000.0, None), None);
+        assert_eq!(relative_size_pct(50_000.0, Some(0.0)), None);
+        assert_eq!(relative_size_pct(50_000.0, Some(-1.0)), None);
+        assert_eq!(relative_size_pct(50_000.0, Some(f64::NAN)), None);
+        assert_eq!(relative_size_pct(-5.0, Some(1_000.0)), None);
+        assert_eq!(relative_size_pct(f64::INFINITY, Some(1_000.0)), None);
+    }
+
+    #[test]
+    fn test_days_until_and_warning_window() {
+        let now = 1_700_000_000;
+        assert_eq!(unlock_at(now + 1).days_until(now), 1);
+        assert_eq!(unlock_at(now + SECS_PER_DAY).days_until(now), 1);
+        assert_eq!(unlock_at(now + SECS_PER_DAY + 1).days_until(now), 2);
+        assert_eq!(unlock_at(now - 10).days_until(now), 0);
+
+        assert!(unlock_at(now + 60).is_approaching(now));
+        assert!(unlock_at(now + UNLOCK_WARNING_DAYS * SECS_PER_DAY).is_approaching(now));
+        assert!(!unlock_at(now + UNLOCK_WARNING_DAYS * SECS_PER_DAY + 1).is_approaching(now));
+        // Already happened
+        assert!(!unlock_at(now).is_approaching(now));
+    }
+
+    #[test]
+    fn test_optional_fields_are_omitted() {
+        let json = serde_json::to_string(&unlock_at(1_700_000_000)).unwrap();
+        assert!(!json.contains("pct_of_circulating"));
+        assert!(!json.contains("allocation"));
+        assert_eq!(serde_json::from_str::<TokenUnlockInfo>(&json).unwrap(), unlock_at(1_700_000_000));
+    }
+}
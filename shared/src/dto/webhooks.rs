@@ -0,0 +1,180 @@
+//! # Webhook Data Transfer Objects
+//!
+//! Outgoing webhooks let external tools (bots, alerting) react to activity on
+//! the user's terminal wallet.
+//!
+//! ## Endpoints
+//!
+//! ```text
+//! POST   /api/webhooks                  { url, events, balance_threshold_sol } → { webhook, secret }
+//! GET    /api/webhooks                                                          → { webhooks }
+//! DELETE /api/webhooks/{id}
+//! GET    /api/webhooks/{id}/deliveries                                          → { deliveries }
+//! POST   /api/webhooks/{id}/test                                                → WebhookDeliveryInfo
+//! ```
+//!
+//! ## Delivery
+//!
+//! Each event is POSTed to the URL as a [`WebhookPayload`] with these headers:
+//!
+//! - `X-Xforce-Event` - Event type, e.g. `incoming_transfer`
+//! - `X-Xforce-Timestamp` - Unix seconds the delivery was signed at
+//! - `X-Xforce-Signature` - `sha256=` + hex HMAC-SHA256 of `"{timestamp}.{body}"`
+//!   keyed with the webhook secret
+//!
+//! The secret is only returned when the webhook is created. Receivers should
+//! recompute the signature, compare it in constant time, and reject stale
+//! timestamps so a captured delivery can't be replayed.
+
+use serde::{Deserialize, Serialize};
+
+/// Most webhooks one user may register
+pub const MAX_WEBHOOKS_PER_USER: usize = 10;
+
+/// Wallet activity a webhook can subscribe to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    /// SOL arrived in the wallet
+    IncomingTransfer,
+    /// A swap submitted through the terminal landed on chain
+    SwapConfirmed,
+    /// The SOL balance moved across the webhook's threshold
+    BalanceThreshold,
+    /// Sent by "send test event"; every webhook receives it
+    Test,
+}
+
+impl WebhookEventType {
+    /// Events a webhook can be registered for
+    pub const SUBSCRIBABLE: [WebhookEventType; 3] = [
+        WebhookEventType::IncomingTransfer,
+        WebhookEventType::SwapConfirmed,
+        WebhookEventType::BalanceThreshold,
+    ];
+
+    /// Wire name, also used in the `X-Xforce-Event` header
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::IncomingTransfer => "incoming_transfer",
+            WebhookEventType::SwapConfirmed => "swap_confirmed",
+            WebhookEventType::BalanceThreshold => "balance_threshold",
+            WebhookEventType::Test => "test",
+        }
+    }
+
+    /// Parse a wire name
+    pub fn parse(name: &str) -> Option<Self> {
+        [Self::IncomingTransfer, Self::SwapConfirmed, Self::BalanceThreshold, Self::Test]
+            .into_iter()
+            .find(|event| event.as_str() == name)
+    }
+
+    /// Human readable name for settings screens
+    pub fn label(&self) -> &'static str {
+        match self {
+            WebhookEventType::IncomingTransfer => "Incoming transfer",
+            WebhookEventType::SwapConfirmed => "Swap confirmed",
+            WebhookEventType::BalanceThreshold => "Balance threshold crossed",
+            WebhookEventType::Test => "Test event",
+        }
+    }
+}
+
+/// Register a webhook
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CreateWebhookRequest {
+    /// `http` or `https` URL that receives the POSTs
+    pub url: String,
+    /// At least one of [`WebhookEventType::SUBSCRIBABLE`]
+    pub events: Vec<WebhookEventType>,
+    /// SOL balance watched by `balance_threshold`; required for that event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_threshold_sol: Option<f64>,
+}
+
+/// A registered webhook (never includes the secret)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookInfo {
+    pub id: i64,
+    pub url: String,
+    pub events: Vec<WebhookEventType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_threshold_sol: Option<f64>,
+    /// RFC 3339 creation time
+    pub created_at: String,
+}
+
+/// Response to `POST /api/webhooks`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CreateWebhookResponse {
+    pub webhook: WebhookInfo,
+    /// Signing secret; shown once, store it with the receiver
+    pub secret: String,
+}
+
+/// Response to `GET /api/webhooks`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookListResponse {
+    pub webhooks: Vec<WebhookInfo>,
+}
+
+/// One delivered (or abandoned) event
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookDeliveryInfo {
+    pub id: i64,
+    /// Id of the event, also in the payload, for de-duplication by receivers
+    pub event_id: String,
+    pub event: WebhookEventType,
+    /// Got a 2xx answer within the allowed attempts
+    pub delivered: bool,
+    pub attempts: u32,
+    /// HTTP status of the last attempt, if the receiver answered at all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<u16>,
+    /// Why the last attempt failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// RFC 3339 time the event was raised
+    pub created_at: String,
+}
+
+/// Response to `GET /api/webhooks/{id}/deliveries`, newest first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookDeliveriesResponse {
+    pub deliveries: Vec<WebhookDeliveryInfo>,
+}
+
+/// Body POSTed to a webhook URL
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookPayload {
+    /// Unique per event; retries of one event carry the same id
+    pub id: String,
+    pub event: WebhookEventType,
+    /// Wallet address the event is about
+    pub wallet: String,
+    /// Unix seconds the event was raised
+    pub timestamp: i64,
+    /// Event specific fields, see [`WebhookEventType`]
+    pub data: serde_json::Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_type_wire_names() {
+        for event in [
+            WebhookEventType::IncomingTransfer,
+            WebhookEventType::SwapConfirmed,
+            WebhookEventType::BalanceThreshold,
+            WebhookEventType::Test,
+        ] {
+            let json = serde_json::to_string(&event).unwrap();
+            assert_eq!(json, format!("\"{}\"", event.as_str()));
+            assert_eq!(WebhookEventType::parse(event.as_str()), Some(event));
+        }
+        assert_eq!(WebhookEventType::parse("unknown"), None);
+    }
+}
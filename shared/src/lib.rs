@@ -8,6 +8,7 @@
 //! - **[`dto`]**: Data Transfer Objects for API communication
 //!   - **[`dto::auth`]**: Authentication and user management DTOs
 //!   - **[`dto::market`]**: Market data and charting DTOs
+//! - **[`validation`]**: Username, email and password rules for new accounts
 //! - **[`utils`]**: Shared utility functions
 //!   - **[`utils::format_address`]**: Format wallet addresses for display
 //!   - **[`utils::truncate_address`]**: Truncate addresses with ellipsis
@@ -61,6 +62,7 @@
 
 pub mod dto;
 pub mod utils;
+pub mod validation;
 
 // Re-export commonly used types for convenience
 // Note: Wildcard re-exports are used here since shared is a DTO library
@@ -0,0 +1,261 @@
+//! # Account Input Validation
+//!
+//! The username, email and password rules for new accounts, shared by the
+//! backend's signup handler and the terminal's signup form so both reject
+//! exactly the same input.
+//!
+//! Every validator returns all the rules the value breaks rather than the
+//! first one, so a form can show everything wrong with a field at once.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use shared::validation::{validate_signup, FIELD_PASSWORD};
+//!
+//! let errors = validate_signup("alice", "alice+sol@example.com", "password");
+//! assert_eq!(errors.len(), 1);
+//! assert_eq!(errors[0].field, FIELD_PASSWORD);
+//! assert_eq!(errors[0].message, "Password must contain a digit");
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Field name used for username errors
+pub const FIELD_USERNAME: &str = "username";
+/// Field name used for email errors
+pub const FIELD_EMAIL: &str = "email";
+/// Field name used for password errors
+pub const FIELD_PASSWORD: &str = "password";
+
+/// Shortest allowed username, in characters
+pub const USERNAME_MIN_LEN: usize = 3;
+/// Longest allowed username, in characters
+pub const USERNAME_MAX_LEN: usize = 32;
+/// Shortest allowed password, in characters
+pub const PASSWORD_MIN_LEN: usize = 8;
+
+/// One broken rule for one field
+///
+/// # JSON Example
+///
+/// ```json
+/// { "field": "username", "message": "Username must be at least 3 characters" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationError {
+    /// Field the rule applies to, e.g. [`FIELD_EMAIL`]
+    pub field: String,
+    /// Message to show next to the field
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self { field: field.to_string(), message: message.into() }
+    }
+}
+
+/// Body of a 400 response for input that failed validation
+///
+/// `error` holds the first message, so clients that read it like any other
+/// error response still get something to show; `fields` has all of them.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "error": "Username must be at least 3 characters",
+///   "fields": [
+///     { "field": "username", "message": "Username must be at least 3 characters" },
+///     { "field": "email", "message": "Invalid email format" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationErrorResponse {
+    pub error: String,
+    pub fields: Vec<ValidationError>,
+}
+
+impl From<Vec<ValidationError>> for ValidationErrorResponse {
+    fn from(fields: Vec<ValidationError>) -> Self {
+        let error = fields.first().map(|e| e.message.clone()).unwrap_or_default();
+        Self { error, fields }
+    }
+}
+
+/// Check a username.
+///
+/// 3 to 32 characters (not bytes, so non-Latin names aren't penalized), made
+/// of letters, digits, `_`, `-` and `.` in any script.
+pub fn validate_username(username: &str) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    if username.trim().is_empty() {
+        errors.push(ValidationError::new(FIELD_USERNAME, "Username is required"));
+        return errors;
+    }
+
+    let len = username.chars().count();
+    if len < USERNAME_MIN_LEN {
+        errors.push(ValidationError::new(
+            FIELD_USERNAME,
+            format!("Username must be at least {} characters", USERNAME_MIN_LEN),
+        ));
+    }
+    if len > USERNAME_MAX_LEN {
+        errors.push(ValidationError::new(
+            FIELD_USERNAME,
+            format!("Username must be at most {} characters", USERNAME_MAX_LEN),
+        ));
+    }
+    if !username.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+        errors.push(ValidationError::new(
+            FIELD_USERNAME,
+            "Username can only contain letters, digits, '_', '-' and '.'",
+        ));
+    }
+    errors
+}
+
+/// Check an email address.
+///
+/// Deliberately loose (real address grammar is huge): one `@`, a non-empty
+/// local part, and a dotted domain with no empty labels. Plus-addressing and
+/// other local-part punctuation pass.
+pub fn validate_email(email: &str) -> Vec<ValidationError> {
+    if email.trim().is_empty() {
+        return vec![ValidationError::new(FIELD_EMAIL, "Email is required")];
+    }
+
+    let valid = match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && domain.split('.').all(|label| !label.is_empty())
+                && !email.chars().any(char::is_whitespace)
+        }
+        None => false,
+    };
+    if valid {
+        Vec::new()
+    } else {
+        vec![ValidationError::new(FIELD_EMAIL, "Invalid email format")]
+    }
+}
+
+/// Check a new password: at least 8 characters with a letter and a digit.
+pub fn validate_password_strength(password: &str) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    if password.chars().count() < PASSWORD_MIN_LEN {
+        errors.push(ValidationError::new(
+            FIELD_PASSWORD,
+            format!("Password must be at least {} characters long", PASSWORD_MIN_LEN),
+        ));
+    }
+    if !password.chars().any(char::is_alphabetic) {
+        errors.push(ValidationError::new(FIELD_PASSWORD, "Password must contain a letter"));
+    }
+    if !password.chars().any(|c| c.is_ascii_digit()) {
+        errors.push(ValidationError::new(FIELD_PASSWORD, "Password must contain a digit"));
+    }
+    errors
+}
+
+/// Every rule a signup breaks, in form order
+pub fn validate_signup(username: &str, email: &str, password: &str) -> Vec<ValidationError> {
+    let mut errors = validate_username(username);
+    errors.extend(validate_email(email));
+    errors.extend(validate_password_strength(password));
+    errors
+}
+
+/// Messages for one field
+pub fn field_messages<'a>(errors: &'a [ValidationError], field: &'a str) -> impl Iterator<Item = &'a str> {
+    errors.iter().filter(move |e| e.field == field).map(|e| e.message.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_username_length_counts_characters() {
+        // Every script, at every length around the limits
+        for letter in ['a', 'é', 'ж', '日', 'ß', '٣'] {
+            for len in 0..=USERNAME_MAX_LEN + 2 {
+                let name: String = std::iter::repeat_n(letter, len).collect();
+                let valid = (USERNAME_MIN_LEN..=USERNAME_MAX_LEN).contains(&len);
+                assert_eq!(validate_username(&name).is_empty(), valid, "{:?}", name);
+            }
+        }
+        assert!(validate_username("ab").iter().any(|e| e.message == "Username must be at least 3 characters"));
+    }
+
+    #[test]
+    fn test_username_characters() {
+        for name in ["alice", "bob_99", "x-ray.fm", "Ünïcödé", "日本語ユーザー"] {
+            assert!(validate_username(name).is_empty(), "{}", name);
+        }
+        for name in ["al ice", "bob@home", "emoji😀", "tab\tname", "semi;colon", "   "] {
+            assert!(!validate_username(name).is_empty(), "{}", name);
+        }
+        // Short and with a bad character: both reported
+        assert_eq!(validate_username("a!").len(), 2);
+    }
+
+    #[test]
+    fn test_email() {
+        for email in [
+            "alice@example.com",
+            "alice+sol@example.com",
+            "alice+a+b@mail.example.co.uk",
+            "first.last@example.io",
+            "用户@例子.中国",
+        ] {
+            assert!(validate_email(email).is_empty(), "{}", email);
+        }
+        for email in [
+            "",
+            "invalid-email",
+            "@example.com",
+            "alice@",
+            "alice@localhost",
+            "alice@example..com",
+            "alice@.example.com",
+            "alice@example.com.",
+            "alice@@example.com",
+            "a@b@example.com",
+            "alice @example.com",
+        ] {
+            assert_eq!(validate_email(email).len(), 1, "{}", email);
+        }
+    }
+
+    #[test]
+    fn test_password_strength() {
+        assert!(validate_password_strength("TestPassword123!").is_empty());
+        assert!(validate_password_strength("pässwört1").is_empty());
+
+        let messages = |p| validate_password_strength(p).into_iter().map(|e| e.message).collect::<Vec<_>>();
+        assert_eq!(messages("short"), ["Password must be at least 8 characters long", "Password must contain a digit"]);
+        assert_eq!(messages("12345678"), ["Password must contain a letter"]);
+        assert_eq!(messages("passwords"), ["Password must contain a digit"]);
+        assert_eq!(validate_password_strength("").len(), 3);
+
+        // Length is in characters: seven multi-byte characters are still too short
+        assert_eq!(messages("日本語日本語1"), ["Password must be at least 8 characters long"]);
+    }
+
+    #[test]
+    fn test_signup_reports_every_field() {
+        let errors = validate_signup("ab", "nope", "short");
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, [FIELD_USERNAME, FIELD_EMAIL, FIELD_PASSWORD, FIELD_PASSWORD]);
+        assert_eq!(field_messages(&errors, FIELD_EMAIL).collect::<Vec<_>>(), ["Invalid email format"]);
+
+        let response = ValidationErrorResponse::from(errors);
+        assert_eq!(response.error, "Username must be at least 3 characters");
+        assert_eq!(response.fields.len(), 4);
+    }
+}
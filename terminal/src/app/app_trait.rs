@@ -6,7 +6,8 @@
 use std::sync::Arc;
 use parking_lot::RwLock;
 use crate::app::{
-    AppState, Screen, SwapTab, TokenPickerTarget, TokenInfo,
+    AppState, AssetSortColumn, Screen, SwapTab, TokenPickerTarget, TokenInfo, WindowView,
+    recovery::{RecoveryAction, RecoveryFlow},
     window_manager::WindowManager,
 };
 
@@ -26,31 +27,134 @@ pub trait AppLike {
     fn handle_signup_click(&mut self, username: String, email: String, password: String, confirm_password: String);
     fn handle_switch_to_login(&mut self);
     fn handle_switch_to_signup(&mut self);
+    fn handle_logout_click(&mut self);
+    fn handle_change_password_click(&mut self);
+    fn handle_update_profile_click(&mut self);
+    fn handle_session_refresh(&mut self);
+    
+    // Security methods
+    fn handle_grant_session(&mut self);
+    fn handle_revoke_grant(&mut self, grant_id: u64);
+    
+    // Webhook methods
+    fn handle_webhooks_refresh(&mut self);
+    fn handle_webhook_create(&mut self);
+    fn handle_webhook_delete(&mut self, id: i64);
+    fn handle_webhook_deliveries(&mut self, id: i64);
+    fn handle_webhook_test(&mut self, id: i64);
+    
+    // API key methods
+    fn handle_api_keys_refresh(&mut self);
+    fn handle_api_key_create(&mut self);
+    fn handle_api_key_revoke(&mut self, id: i64);
     
     // Navigation methods
     fn handle_screen_change(&mut self, screen: Screen);
     fn handle_swap_tab_change(&mut self, tab: SwapTab);
     fn next_screen(&mut self);
     fn previous_screen(&mut self);
+    /// Reload what `screen` shows, skipping the client caches
+    fn refresh(&mut self, screen: Screen);
+    /// Clear every client cache and reload everything
+    fn refresh_all(&mut self);
+    /// Screen shown in this window
+    fn current_screen(&self) -> Screen;
+    /// Screen-scoped state of this window (nav bar token, ...)
+    fn view(&self) -> WindowView;
+    fn set_view(&mut self, view: WindowView);
+    
+    // Search methods
+    fn handle_search_toggle(&mut self);
+    fn handle_search_query(&mut self, query: String);
+    fn handle_search_select(&mut self, target: crate::app::search::SearchTarget);
+    fn handle_command_palette_toggle(&mut self);
     
     // Swap methods
     fn handle_swap_execute_click(&mut self);
+    fn handle_swap_confirm(&mut self);
+    fn handle_swap_confirmation_cancel(&mut self);
     fn handle_token_select(&mut self, token: TokenInfo, target: TokenPickerTarget);
+    fn handle_token_favorite_toggle(&mut self, mint: String);
     fn trigger_quote_fetch(&mut self);
     fn fetch_token_list(&mut self);
     fn set_max_amount(&mut self);
     fn open_token_picker_internal(&self, state: &mut AppState, target: TokenPickerTarget);
     fn fetch_candles(&mut self, symbol: &str, timeframe: shared::dto::market::Timeframe);
+    fn fetch_depth(&mut self, input: &str, output: &str);
+    fn fetch_market_analytics(&mut self);
+    fn fetch_sparklines(&mut self);
+    fn check_backend_health(&mut self);
+    fn load_friends(&mut self);
+    fn handle_websocket_reconnect(&mut self);
+
+    // Live Assets methods
+    fn handle_asset_hover(&mut self, hovered: Option<String>);
+    fn handle_asset_focus(&mut self, symbol: String);
+    fn handle_token_detail_open(&mut self, symbol: String);
+    fn handle_token_detail_close(&mut self);
+    fn handle_asset_sort_click(&mut self, column: AssetSortColumn);
+    fn handle_asset_chart_open(&mut self, symbol: String);
     
     // Wallet methods
     fn handle_wallet_connect_click(&mut self);
     fn handle_wallet_generate_click(&mut self);
     fn handle_wallet_disconnect_click(&mut self);
+    fn handle_wallet_label_save(&mut self);
+    fn handle_send_review(&mut self);
+    fn handle_send_confirm(&mut self);
+    fn handle_airdrop_request(&mut self);
+    fn handle_staking_refresh(&mut self);
+    fn handle_stake_action(&mut self, action: crate::app::handlers::staking::StakeAction, stake_account: Option<String>);
+    fn handle_send_cancel(&mut self);
+    fn handle_recovery_action(&mut self, flow: RecoveryFlow, action: RecoveryAction);
+    fn handle_recovery_dismiss(&mut self, flow: RecoveryFlow);
+    fn handle_token_balances_refresh(&mut self);
+    fn handle_transactions_refresh(&mut self);
+    fn handle_transactions_export(&mut self);
+    fn handle_trade_import_pick_file(&mut self);
+    fn handle_trade_import_validate(&mut self);
+    fn handle_trade_import_commit(&mut self);
+    fn handle_trade_stats_refresh(&mut self);
+    fn handle_swap_history_refresh(&mut self);
+    fn handle_swap_history_export(&mut self);
+    fn handle_share_chart(&mut self);
+    fn handle_share_portfolio(&mut self);
+    fn handle_onramp_open(&mut self);
+    fn handle_onramp_buy(&mut self, provider_id: String);
+    fn handle_rebalance_save(&mut self);
+    fn handle_rebalance_propose(&mut self);
+    fn handle_rebalance_queue_all(&mut self);
+    fn handle_mnemonic_import(&mut self);
+    fn handle_derived_scan(&mut self);
+    fn handle_derived_activate(&mut self, index: u32);
+    fn handle_derived_label(&mut self, index: u32, label: String);
     
     // Settings methods
     fn handle_theme_color_change(&mut self, config: crate::ui::theme::ThemeConfig);
+    fn handle_theme_import(&mut self);
+    fn handle_theme_export(&mut self);
     fn handle_settings_save(&mut self);
+    fn handle_server_list_save(&mut self);
+    fn handle_connection_apply(&mut self);
     fn handle_settings_reset(&mut self);
     fn handle_settings_apply(&mut self);
+    fn handle_bandwidth_saver_toggle(&mut self, enabled: bool);
+    fn handle_stale_price_threshold_change(&mut self, secs: u64);
+    fn handle_idle_teardown_change(&mut self, mins: u64);
+    fn handle_notification_category_toggle(&mut self, category: shared::NoticeCategory, enabled: bool);
+    fn handle_key_capture(&mut self, action: Option<crate::app::keybindings::KeyAction>);
+    fn handle_key_binding_change(&mut self, action: crate::app::keybindings::KeyAction, combo: crate::app::keybindings::KeyCombo);
+    fn handle_key_bindings_reset(&mut self);
+    fn handle_locale_change(&mut self, locale: crate::ui::i18n::Locale);
+    fn handle_usage_analytics_toggle(&mut self, enabled: bool);
+    fn handle_usage_data_delete(&mut self);
+    fn handle_risk_thresholds_change(&mut self, thresholds: crate::app::RiskThresholds);
+    fn handle_indicator_config_change(&mut self, config: crate::ui::chart::indicators::IndicatorConfig);
+    fn handle_chart_action(&mut self, action: crate::ui::chart::annotations::ChartAction);
+
+    // AI chat methods
+    fn handle_ai_chat_connect(&mut self);
+    fn handle_ai_chat_send(&mut self, text: String);
+    fn handle_ai_chat_cancel(&mut self);
 }
 
@@ -0,0 +1,272 @@
+//! # Audit Trail
+//!
+//! Chronological record of what the app did on the user's behalf: logins,
+//! wallet connects, signed swaps, auto-signed transactions and changes to
+//! security settings. Shown as the timeline in Settings > Security.
+//!
+//! This is not the tracing log. Entries are structured, survive restarts and
+//! never hold secrets: keys, seeds and passwords are only ever referred to
+//! (by public key, signature or request id), and [`record`] masks anything in
+//! a summary that looks like key material.
+//!
+//! Entries are saved in the local database through the
+//! [`AuditRepository`], which keeps the last [`MAX_ENTRIES`]; the same
+//! entries are kept in memory.
+
+use chrono::{DateTime, Utc};
+use crate::core::store::AuditRepository;
+use serde::{Deserialize, Serialize};
+
+/// Entries kept in memory and in the database
+pub const MAX_ENTRIES: usize = 500;
+
+/// Shortest run of base58/hex characters treated as key material in summaries
+const SECRET_MIN_LEN: usize = 64;
+
+/// What kind of operation an entry records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditCategory {
+    /// Logins, logouts and account changes
+    Auth,
+    /// Wallets connected, generated, imported or switched, and transfers sent
+    Wallet,
+    /// Swaps signed from the confirmation dialog
+    Swap,
+    /// Transactions signed without a prompt under a session grant
+    AutoSign,
+    /// Signing session grants and other security settings
+    Security,
+}
+
+impl AuditCategory {
+    pub const ALL: [AuditCategory; 5] = [
+        AuditCategory::Auth,
+        AuditCategory::Wallet,
+        AuditCategory::Swap,
+        AuditCategory::AutoSign,
+        AuditCategory::Security,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AuditCategory::Auth => "Auth",
+            AuditCategory::Wallet => "Wallet",
+            AuditCategory::Swap => "Swap",
+            AuditCategory::AutoSign => "Auto-sign",
+            AuditCategory::Security => "Security",
+        }
+    }
+}
+
+/// Something an entry points at instead of embedding it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum AuditReference {
+    /// Transaction signature; the timeline links to its transaction
+    Transaction(String),
+    /// Backend request, signing grant or other record id
+    Request(String),
+    /// Wallet public key
+    Account(String),
+}
+
+impl AuditReference {
+    pub fn id(&self) -> &str {
+        match self {
+            AuditReference::Transaction(id) | AuditReference::Request(id) | AuditReference::Account(id) => id,
+        }
+    }
+}
+
+/// One operation in the audit trail
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    pub category: AuditCategory,
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<AuditReference>,
+}
+
+/// Bounded, append-only audit trail and the repository backing it
+///
+/// The default log has no repository and only lives in memory.
+#[derive(Debug, Clone, Default)]
+pub struct AuditLog {
+    repository: Option<AuditRepository>,
+    /// Oldest first
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Load the last [`MAX_ENTRIES`] entries of the saved trail
+    ///
+    /// A trail that can't be read starts empty; new entries are still saved.
+    pub fn open(repository: AuditRepository) -> Self {
+        let entries = repository.recent(MAX_ENTRIES).unwrap_or_else(|e| {
+            tracing::warn!("Failed to load audit trail: {}", e);
+            Vec::new()
+        });
+        tracing::info!(count = entries.len(), "Loaded audit trail");
+        Self { repository: Some(repository), entries }
+    }
+
+    /// Entries oldest first
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// Append an entry, dropping the oldest entries past [`MAX_ENTRIES`]
+    pub fn append(&mut self, entry: AuditEntry) {
+        if let Some(repository) = &self.repository {
+            if let Err(e) = repository.append(&entry, MAX_ENTRIES) {
+                tracing::error!("Failed to write audit entry: {}", e);
+            }
+        }
+        self.entries.push(entry);
+        let excess = self.entries.len().saturating_sub(MAX_ENTRIES);
+        self.entries.drain(..excess);
+    }
+}
+
+/// Add an operation to the audit trail
+///
+/// The single way handlers write to the trail. Secrets must never be passed
+/// in; refer to them with `references`. As a last line of defence, long
+/// runs of base58 or hex in `summary` are masked before anything is stored.
+pub fn record(
+    log: &mut AuditLog,
+    category: AuditCategory,
+    summary: impl Into<String>,
+    references: Vec<AuditReference>,
+) {
+    let summary = mask_secrets(&summary.into());
+    tracing::debug!(category = ?category, summary = %summary, "Audit entry recorded");
+    log.append(AuditEntry { at: Utc::now(), category, summary, references });
+}
+
+/// `summary` with anything that looks like a private key or seed replaced
+fn mask_secrets(summary: &str) -> String {
+    summary
+        .split(' ')
+        .map(|word| {
+            let token = word.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+            let encoded = token.chars().all(|c| c.is_ascii_alphanumeric() && !"0OIl".contains(c))
+                || token.chars().all(|c| c.is_ascii_hexdigit());
+            if token.len() >= SECRET_MIN_LEN && encoded {
+                word.replace(token, "[redacted]")
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Timeline filter (Settings > Security)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditFilter {
+    /// Only this category; None shows all
+    pub category: Option<AuditCategory>,
+    /// Case-insensitive text in the summary or a reference id
+    pub query: String,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        if self.category.is_some_and(|category| category != entry.category) {
+            return false;
+        }
+        let query = self.query.trim().to_lowercase();
+        query.is_empty()
+            || entry.summary.to_lowercase().contains(&query)
+            || entry.references.iter().any(|reference| reference.id().to_lowercase().contains(&query))
+    }
+}
+
+/// Entries passing `filter`, newest first
+pub fn timeline<'a>(entries: &'a [AuditEntry], filter: &'a AuditFilter) -> impl Iterator<Item = &'a AuditEntry> + 'a {
+    entries.iter().rev().filter(move |entry| filter.matches(entry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::store::LocalStore;
+
+    const SIGNATURE: &str = "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+
+    #[test]
+    fn test_record_persists_across_restarts() {
+        let store = LocalStore::open_in_memory().unwrap();
+        let mut log = AuditLog::open(store.audit());
+        record(&mut log, AuditCategory::Auth, "Logged in as alice", vec![]);
+        record(
+            &mut log,
+            AuditCategory::Swap,
+            "Signed swap of 1.5 SOL",
+            vec![AuditReference::Transaction(SIGNATURE.to_string())],
+        );
+
+        let reopened = AuditLog::open(store.audit());
+        assert_eq!(reopened.entries(), log.entries());
+        assert_eq!(reopened.entries()[1].references, vec![AuditReference::Transaction(SIGNATURE.to_string())]);
+    }
+
+    #[test]
+    fn test_record_masks_key_material() {
+        let mut log = AuditLog::default();
+        let secret = "4wBqpZM9k6Lq7p3oD8ypjuwMvPZbD1NVGvCpqULy3hJZdoQiu8xy4z8Zt5VkR3GdnsM4ztUdnvAxP1vgXo5H8s9T";
+        record(&mut log, AuditCategory::Wallet, format!("Imported key {}.", secret), vec![]);
+        record(&mut log, AuditCategory::Wallet, "Connected wallet 7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", vec![]);
+
+        assert_eq!(log.entries()[0].summary, "Imported key [redacted].");
+        // Public keys are short enough to keep
+        assert!(log.entries()[1].summary.ends_with("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"));
+    }
+
+    #[test]
+    fn test_log_is_bounded() {
+        let store = LocalStore::open_in_memory().unwrap();
+        let mut log = AuditLog::open(store.audit());
+        for i in 0..(MAX_ENTRIES * 2 + 10) {
+            record(&mut log, AuditCategory::Security, format!("change {}", i), vec![]);
+        }
+        assert_eq!(log.entries().len(), MAX_ENTRIES);
+        assert_eq!(log.entries()[0].summary, format!("change {}", MAX_ENTRIES + 10));
+
+        // The database keeps the same window
+        assert_eq!(store.audit().recent(usize::MAX).unwrap().len(), MAX_ENTRIES);
+        let reopened = AuditLog::open(store.audit());
+        assert_eq!(reopened.entries(), log.entries());
+    }
+
+    #[test]
+    fn test_timeline_filters_newest_first() {
+        let mut log = AuditLog::default();
+        record(&mut log, AuditCategory::Auth, "Logged in as alice", vec![]);
+        record(
+            &mut log,
+            AuditCategory::Swap,
+            "Signed swap of 1.5 SOL",
+            vec![AuditReference::Transaction(SIGNATURE.to_string())],
+        );
+        record(&mut log, AuditCategory::AutoSign, "Auto-signed DCA fill of 25 USDC", vec![]);
+        record(&mut log, AuditCategory::Swap, "Signed swap of 40 USDC", vec![]);
+
+        let summaries = |filter: &AuditFilter| -> Vec<String> {
+            timeline(log.entries(), filter).map(|entry| entry.summary.clone()).collect()
+        };
+
+        assert_eq!(summaries(&AuditFilter::default()).first().map(String::as_str), Some("Signed swap of 40 USDC"));
+        let swaps = AuditFilter { category: Some(AuditCategory::Swap), ..Default::default() };
+        assert_eq!(summaries(&swaps), vec!["Signed swap of 40 USDC", "Signed swap of 1.5 SOL"]);
+
+        // Text matches summaries and reference ids, ignoring case
+        let usdc = AuditFilter { query: " usdc ".to_string(), ..Default::default() };
+        assert_eq!(summaries(&usdc), vec!["Signed swap of 40 USDC", "Auto-signed DCA fill of 25 USDC"]);
+        let by_signature = AuditFilter { category: Some(AuditCategory::Swap), query: SIGNATURE[..12].to_lowercase() };
+        assert_eq!(summaries(&by_signature), vec!["Signed swap of 1.5 SOL"]);
+    }
+}
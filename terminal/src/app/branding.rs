@@ -0,0 +1,306 @@
+//! # Branding
+//!
+//! Landing and auth screen branding, read from `branding.toml` at startup so
+//! white-label deployments don't need a source change.
+//!
+//! ## File
+//!
+//! `./branding.toml` by default, or the path in `XTERMINAL_BRANDING`. Every
+//! key is optional; missing ones keep the built-in XFTerminal branding.
+//!
+//! ```toml
+//! title_accent = "AC"
+//! title = "ME Markets"
+//! tagline = "Trade with ACME"
+//! logo_text = "AC"
+//! accent_color = [0, 120, 255]
+//!
+//! [mesh]
+//! path = "acme-logo.json"   # relative to branding.toml, see crate::ui::cube
+//! size = 150.0
+//! speed_x = 0.5
+//! speed_y = 1.0
+//! edge_color = [255, 255, 255]
+//! edge_width = 4.0
+//! ```
+//!
+//! A file that can't be read or parsed, or a mesh that fails validation,
+//! falls back to the built-in branding as a whole; the Settings screen shows
+//! which branding is active and why a file was not used.
+
+use crate::ui::cube::{BrandMesh, MeshStyle};
+use egui::Color32;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Where the active branding came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum BrandingSource {
+    /// No branding file
+    BuiltIn,
+    /// Loaded from this file
+    File(PathBuf),
+    /// This file exists but was rejected
+    Fallback { path: PathBuf, error: String },
+}
+
+impl BrandingSource {
+    /// One-line description for the Settings screen
+    pub fn describe(&self) -> String {
+        match self {
+            Self::BuiltIn => "Built-in branding".to_string(),
+            Self::File(path) => format!("{}", path.display()),
+            Self::Fallback { path, .. } => format!("Built-in branding ({} was rejected)", path.display()),
+        }
+    }
+}
+
+/// Branding of the landing and auth screens
+#[derive(Debug, Clone, PartialEq)]
+pub struct Branding {
+    /// Leading part of the title, drawn in the accent color
+    pub title_accent: String,
+    /// Rest of the title, drawn in white
+    pub title: String,
+    pub tagline: String,
+    pub accent_color: Color32,
+    /// Text at the center of the rotating mesh
+    pub logo_text: String,
+    pub mesh: Arc<BrandMesh>,
+    pub mesh_size: f32,
+    pub speed_x: f32,
+    pub speed_y: f32,
+    pub edge_color: Color32,
+    pub edge_width: f32,
+    pub source: BrandingSource,
+}
+
+impl Default for Branding {
+    fn default() -> Self {
+        let style = MeshStyle::default();
+        Self {
+            title_accent: "XF".to_string(),
+            title: "Terminal".to_string(),
+            tagline: "Trade anywhere".to_string(),
+            accent_color: style.logo_color,
+            logo_text: style.logo_text,
+            mesh: Arc::new(BrandMesh::icosahedron()),
+            mesh_size: 150.0,
+            speed_x: style.speed_x,
+            speed_y: style.speed_y,
+            edge_color: style.edge_color,
+            edge_width: style.edge_width,
+            source: BrandingSource::BuiltIn,
+        }
+    }
+}
+
+impl Branding {
+    /// Style for [`crate::ui::cube::RotatingCube`]
+    pub fn mesh_style(&self) -> MeshStyle {
+        MeshStyle {
+            speed_y: self.speed_y,
+            speed_x: self.speed_x,
+            edge_color: self.edge_color,
+            edge_width: self.edge_width,
+            logo_text: self.logo_text.clone(),
+            logo_color: self.accent_color,
+        }
+    }
+}
+
+/// `branding.toml` as written; every key is optional
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct BrandingFile {
+    title_accent: Option<String>,
+    title: Option<String>,
+    tagline: Option<String>,
+    logo_text: Option<String>,
+    accent_color: Option<[u8; 3]>,
+    mesh: MeshSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct MeshSection {
+    path: Option<PathBuf>,
+    size: Option<f32>,
+    speed_x: Option<f32>,
+    speed_y: Option<f32>,
+    edge_color: Option<[u8; 3]>,
+    edge_width: Option<f32>,
+}
+
+/// Largest mesh size accepted, in points
+const MAX_MESH_SIZE: f32 = 600.0;
+
+/// Get branding file path
+pub fn get_branding_path() -> PathBuf {
+    std::env::var("XTERMINAL_BRANDING")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("./branding.toml"))
+}
+
+/// Load branding from [`get_branding_path`], falling back to the built-in branding
+pub fn load_branding() -> Branding {
+    load_branding_from(&get_branding_path())
+}
+
+/// Load branding from `path`, falling back to the built-in branding
+pub fn load_branding_from(path: &Path) -> Branding {
+    if !path.exists() {
+        return Branding::default();
+    }
+
+    let result = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| parse_branding(&content, path.parent().unwrap_or(Path::new("."))));
+
+    match result {
+        Ok(mut branding) => {
+            tracing::info!("Loaded branding from {:?}", path);
+            branding.source = BrandingSource::File(path.to_path_buf());
+            branding
+        }
+        Err(error) => {
+            tracing::warn!("Ignoring branding {:?}: {}. Using built-in branding.", path, error);
+            Branding {
+                source: BrandingSource::Fallback { path: path.to_path_buf(), error },
+                ..Branding::default()
+            }
+        }
+    }
+}
+
+/// Build branding from `branding.toml` contents; mesh paths are relative to `base_dir`
+fn parse_branding(content: &str, base_dir: &Path) -> Result<Branding, String> {
+    let file: BrandingFile = toml::from_str(content).map_err(|e| e.to_string())?;
+    let defaults = Branding::default();
+    let rgb = |c: Option<[u8; 3]>, default: Color32| c.map(|[r, g, b]| Color32::from_rgb(r, g, b)).unwrap_or(default);
+
+    let mesh = match &file.mesh.path {
+        Some(mesh_path) => {
+            let mesh_path = base_dir.join(mesh_path);
+            let json = std::fs::read_to_string(&mesh_path).map_err(|e| format!("{}: {}", mesh_path.display(), e))?;
+            let mesh = BrandMesh::from_json(&json).map_err(|e| format!("{}: {}", mesh_path.display(), e))?;
+            Arc::new(mesh)
+        }
+        None => defaults.mesh.clone(),
+    };
+
+    let mesh_size = file.mesh.size.unwrap_or(defaults.mesh_size);
+    if !(mesh_size.is_finite() && mesh_size > 0.0 && mesh_size <= MAX_MESH_SIZE) {
+        return Err(format!("mesh size must be between 0 and {}", MAX_MESH_SIZE));
+    }
+    let edge_width = file.mesh.edge_width.unwrap_or(defaults.edge_width);
+    if !(edge_width.is_finite() && edge_width > 0.0) {
+        return Err("mesh edge_width must be positive".to_string());
+    }
+    let speed = |s: Option<f32>, default: f32| match s {
+        Some(s) if !s.is_finite() => Err("mesh speeds must be finite".to_string()),
+        Some(s) => Ok(s),
+        None => Ok(default),
+    };
+
+    Ok(Branding {
+        title_accent: file.title_accent.unwrap_or(defaults.title_accent),
+        title: file.title.unwrap_or(defaults.title),
+        tagline: file.tagline.unwrap_or(defaults.tagline),
+        accent_color: rgb(file.accent_color, defaults.accent_color),
+        logo_text: file.logo_text.unwrap_or(defaults.logo_text),
+        mesh,
+        mesh_size,
+        speed_x: speed(file.mesh.speed_x, defaults.speed_x)?,
+        speed_y: speed(file.mesh.speed_y, defaults.speed_y)?,
+        edge_color: rgb(file.mesh.edge_color, defaults.edge_color),
+        edge_width,
+        source: BrandingSource::BuiltIn,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scratch directory for one test's branding files
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xterminal-branding-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_missing_file_uses_built_in_branding() {
+        let branding = load_branding_from(Path::new("/nonexistent/branding.toml"));
+        assert_eq!(branding, Branding::default());
+        assert_eq!(branding.source, BrandingSource::BuiltIn);
+    }
+
+    #[test]
+    fn test_load_branding_with_mesh() {
+        let dir = scratch_dir("mesh");
+        std::fs::write(
+            dir.join("logo.json"),
+            r#"{"vertices": [[0, 1, 0], [1, -1, 0], [-1, -1, 0]], "edges": [[0, 1], [1, 2], [2, 0]]}"#,
+        )
+        .unwrap();
+        let path = dir.join("branding.toml");
+        std::fs::write(
+            &path,
+            r#"
+title_accent = "AC"
+title = "ME Markets"
+tagline = "Trade with ACME"
+accent_color = [0, 120, 255]
+
+[mesh]
+path = "logo.json"
+speed_y = 0.25
+"#,
+        )
+        .unwrap();
+
+        let branding = load_branding_from(&path);
+        assert_eq!(branding.source, BrandingSource::File(path.clone()));
+        assert_eq!((branding.title_accent.as_str(), branding.title.as_str()), ("AC", "ME Markets"));
+        assert_eq!(branding.tagline, "Trade with ACME");
+        assert_eq!(branding.accent_color, Color32::from_rgb(0, 120, 255));
+        assert_eq!(branding.mesh.edges.len(), 3);
+        assert_eq!(branding.speed_y, 0.25);
+        // Unset keys keep the built-in values
+        assert_eq!(branding.logo_text, "XF");
+        assert_eq!(branding.mesh_style().logo_color, branding.accent_color);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_rejected_files_fall_back_to_built_in() {
+        let dir = scratch_dir("fallback");
+        std::fs::write(dir.join("flat.json"), r#"{"vertices": [[1, 1, 1], [1, 1, 1]], "edges": [[0, 1]]}"#).unwrap();
+
+        for (name, content) in [
+            ("syntax.toml", "title = "),
+            ("unknown.toml", "colour = [1, 2, 3]"),
+            ("missing-mesh.toml", "[mesh]\npath = \"nope.json\""),
+            ("flat-mesh.toml", "[mesh]\npath = \"flat.json\""),
+            ("huge.toml", "[mesh]\nsize = 5000.0"),
+        ] {
+            let path = dir.join(name);
+            std::fs::write(&path, content).unwrap();
+
+            let branding = load_branding_from(&path);
+            assert!(
+                matches!(&branding.source, BrandingSource::Fallback { path: p, .. } if *p == path),
+                "{} was not rejected",
+                name
+            );
+            assert_eq!(branding.title_accent, "XF");
+            assert_eq!(*branding.mesh, BrandMesh::icosahedron());
+        }
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,515 @@
+//! # Command Palette Commands
+//!
+//! Commands behind the command palette (Ctrl+K): a [`CommandRegistry`] of
+//! [`CommandSpec`]s, the fuzzy matcher that ranks them against what was typed,
+//! and argument parsing for commands such as `swap SOL USDC 5`.
+//!
+//! Parsing is kept apart from running: a command turns its arguments into a
+//! [`CommandAction`] and the palette widget runs it through the `AppLike`
+//! handlers, so a command behaves exactly like the button it stands in for.
+//!
+//! Built-in commands are global. Screens add their own at startup through
+//! `ui::screens::register_commands`, scoped with [`CommandSpec::on_screen`]
+//! so they are only offered while that screen is showing.
+
+use crate::app::state::{AppState, Screen, TokenInfo};
+use shared::dto::market::Timeframe;
+use std::sync::Arc;
+
+/// Suggestions shown in the palette
+pub const MAX_SUGGESTIONS: usize = 8;
+
+/// Timeframes the chart toolbars offer
+pub const CHART_TIMEFRAMES: [Timeframe; 6] = [
+    Timeframe::OneMinute,
+    Timeframe::FiveMinutes,
+    Timeframe::FifteenMinutes,
+    Timeframe::OneHour,
+    Timeframe::FourHours,
+    Timeframe::OneDay,
+];
+
+/// Argument synopsis of the swap command
+const SWAP_USAGE: &str = "Usage: swap <from> <to> [amount], e.g. swap SOL USDC 5";
+
+/// Longest token symbol the swap command accepts
+const MAX_SYMBOL_LEN: usize = 10;
+
+/// What running a command does
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandAction {
+    GoTo(Screen),
+    /// Fill the swap form and fetch a quote; the swap itself still needs the
+    /// Swap button so nothing is signed from the palette
+    Swap { input: String, output: String, amount: Option<f64> },
+    SetTimeframe(Timeframe),
+    ToggleDebugOverlay,
+    NewWindow,
+    /// Open the search palette, optionally with a query
+    Search(String),
+    ShareChart,
+    SharePortfolio,
+    /// Reload the current screen's data (F5)
+    Refresh,
+    /// Clear every client cache and reload everything
+    RefreshAll,
+    Logout,
+}
+
+type ParseFn = Arc<dyn Fn(&[&str]) -> Result<CommandAction, String> + Send + Sync>;
+
+/// A command the palette can run
+#[derive(Clone)]
+pub struct CommandSpec {
+    /// Words that invoke the command, e.g. `go to wallet`
+    pub name: String,
+    /// Other names that invoke it
+    pub aliases: Vec<String>,
+    /// Argument synopsis shown after the name, e.g. `<from> <to> [amount]`
+    pub args: &'static str,
+    pub description: String,
+    /// Screen the command belongs to; `None` for global commands
+    pub screen: Option<Screen>,
+    pub requires_auth: bool,
+    parse: ParseFn,
+}
+
+impl CommandSpec {
+    /// A command without arguments
+    pub fn action(name: &str, description: &str, action: CommandAction) -> Self {
+        let command = name.to_string();
+        Self::with_args(name, "", description, move |args| {
+            if args.is_empty() {
+                Ok(action.clone())
+            } else {
+                Err(format!("`{}` takes no arguments", command))
+            }
+        })
+    }
+
+    /// A command whose arguments (the words after its name) go through `parse`
+    pub fn with_args(
+        name: &str,
+        args: &'static str,
+        description: &str,
+        parse: impl Fn(&[&str]) -> Result<CommandAction, String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            aliases: Vec::new(),
+            args,
+            description: description.to_string(),
+            screen: None,
+            requires_auth: false,
+            parse: Arc::new(parse),
+        }
+    }
+
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.aliases.push(alias.to_string());
+        self
+    }
+
+    /// Only offer the command while `screen` is showing
+    pub fn on_screen(mut self, screen: Screen) -> Self {
+        self.screen = Some(screen);
+        self
+    }
+
+    /// Hide the command when logged out
+    pub fn requires_auth(mut self) -> Self {
+        self.requires_auth = true;
+        self
+    }
+
+    /// Action for the words after the command's name
+    pub fn parse(&self, args: &[&str]) -> Result<CommandAction, String> {
+        (self.parse)(args)
+    }
+
+    fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+}
+
+impl std::fmt::Debug for CommandSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandSpec")
+            .field("name", &self.name)
+            .field("screen", &self.screen)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A ranked match for the palette input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub name: String,
+    pub args: &'static str,
+    pub description: String,
+    pub score: u32,
+    /// What Enter runs, or why it can't run with the input as typed
+    pub action: Result<CommandAction, String>,
+}
+
+/// Commands on offer
+#[derive(Debug, Clone, Default)]
+pub struct CommandRegistry {
+    commands: Vec<CommandSpec>,
+}
+
+impl CommandRegistry {
+    /// Global commands: navigation, swap, windows, search and the debug overlay
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        for &screen in Screen::all() {
+            let keyword = screen_keyword(screen);
+            let mut command = CommandSpec::action(&format!("go to {}", keyword), screen.title(), CommandAction::GoTo(screen))
+                .alias(&format!("open {}", keyword));
+            if AppState::requires_auth(screen) {
+                command = command.requires_auth();
+            }
+            registry.register(command);
+        }
+        registry.register(
+            CommandSpec::with_args("swap", "<from> <to> [amount]", "Fill in the swap form and fetch a quote", parse_swap)
+                .requires_auth(),
+        );
+        registry.register(CommandSpec::action(
+            "toggle debug overlay",
+            "Show or hide frame timings and state",
+            CommandAction::ToggleDebugOverlay,
+        ));
+        registry.register(CommandSpec::action("new window", "Open this screen in a new window", CommandAction::NewWindow));
+        registry.register(
+            CommandSpec::with_args("search", "[text]", "Search tokens, transactions, messages and settings", |args| {
+                Ok(CommandAction::Search(args.join(" ")))
+            })
+            .requires_auth(),
+        );
+        registry.register(CommandSpec::action("refresh", "Reload this screen's data (F5)", CommandAction::Refresh).requires_auth());
+        registry.register(
+            CommandSpec::action("refresh all", "Clear cached data and reload everything", CommandAction::RefreshAll)
+                .requires_auth(),
+        );
+        registry.register(CommandSpec::action("logout", "Sign out of this session", CommandAction::Logout).requires_auth());
+        registry
+    }
+
+    /// Add a command; screens call this from `register_commands`
+    pub fn register(&mut self, command: CommandSpec) {
+        self.commands.push(command);
+    }
+
+    /// Commands offered on `screen`
+    pub fn available(&self, screen: Screen, is_authenticated: bool) -> impl Iterator<Item = &CommandSpec> {
+        self.commands.iter().filter(move |c| {
+            c.screen.is_none_or(|s| s == screen) && (!c.requires_auth || is_authenticated)
+        })
+    }
+
+    /// Commands matching `input`, best first
+    ///
+    /// Input that starts with a command's full name invokes it with the rest
+    /// as arguments and ranks above any fuzzy match; otherwise the whole input
+    /// is fuzzy-matched against the names. Empty input lists every command on
+    /// offer; anything else keeps the best [`MAX_SUGGESTIONS`].
+    pub fn suggest(&self, input: &str, screen: Screen, is_authenticated: bool) -> Vec<Suggestion> {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let query = words.join(" ");
+
+        let mut suggestions: Vec<Suggestion> = self
+            .available(screen, is_authenticated)
+            .filter_map(|command| {
+                let (score, args) = if words.is_empty() {
+                    (0, &[][..])
+                } else if let Some(length) = command.names().filter_map(|name| invoked_length(name, &words)).max() {
+                    // Longer names are more specific ("go to live table" over "go to")
+                    (1000 + length as u32, &words[length..])
+                } else {
+                    let score = command.names().filter_map(|name| fuzzy_score(name, &query)).max()?;
+                    (score, &[][..])
+                };
+                Some(Suggestion {
+                    name: command.name.clone(),
+                    args: command.args,
+                    description: command.description.clone(),
+                    score,
+                    action: command.parse(args),
+                })
+            })
+            .collect();
+
+        suggestions.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.name.len().cmp(&b.name.len()))
+                .then(a.name.cmp(&b.name))
+        });
+        if !words.is_empty() {
+            suggestions.truncate(MAX_SUGGESTIONS);
+        }
+        suggestions
+    }
+}
+
+/// Word count of `name` if `words` start with it
+fn invoked_length(name: &str, words: &[&str]) -> Option<usize> {
+    let name: Vec<&str> = name.split_whitespace().collect();
+    let matches = words.len() >= name.len() && name.iter().zip(words).all(|(n, w)| n.eq_ignore_ascii_case(w));
+    matches.then_some(name.len())
+}
+
+/// Short name of a screen for `go to ...`
+fn screen_keyword(screen: Screen) -> &'static str {
+    match screen {
+        Screen::Landing => "home",
+        Screen::Auth => "login",
+        Screen::Terminal => "terminal",
+        Screen::PythFeed => "pyth",
+        Screen::JupiterFeed => "jupiter",
+        Screen::Wallet => "wallet",
+        Screen::Portfolio => "portfolio",
+        Screen::Transactions => "transactions",
+        Screen::Tokens => "tokens",
+        Screen::Messaging => "messages",
+        Screen::AIChat => "ai chat",
+        Screen::Settings => "settings",
+        Screen::LiveChart => "chart",
+        Screen::LiveAssets => "assets",
+        Screen::LiveTable => "live table",
+    }
+}
+
+/// Score `candidate` against `query` as an in-order subsequence, ignoring case
+/// and spaces in the query. Matches at word starts and runs of consecutive
+/// characters score higher; gaps cost a little. `None` if it doesn't match.
+pub fn fuzzy_score(candidate: &str, query: &str) -> Option<u32> {
+    let query: Vec<char> = query.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+    let first = *query.first()?;
+    let text: Vec<char> = candidate.to_lowercase().chars().collect();
+
+    // Greedy from each place the first character occurs, keeping the best
+    text.iter()
+        .enumerate()
+        .filter(|&(_, &c)| c == first)
+        .filter_map(|(start, _)| score_from(&text, &query, start))
+        .max()
+}
+
+fn score_from(text: &[char], query: &[char], start: usize) -> Option<u32> {
+    let mut score: u32 = 0;
+    let mut matched = 0;
+    let mut previous: Option<usize> = None;
+    for (i, &c) in text.iter().enumerate().skip(start) {
+        if matched == query.len() {
+            break;
+        }
+        if c != query[matched] {
+            continue;
+        }
+        score += 1;
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 8;
+        }
+        match previous {
+            Some(p) if p + 1 == i => score += 5,
+            Some(p) => score = score.saturating_sub(((i - p - 1) as u32).min(3)),
+            None => {}
+        }
+        previous = Some(i);
+        matched += 1;
+    }
+    (matched == query.len()).then_some(score)
+}
+
+/// Arguments of `swap`: two symbols and an optional amount, in any order,
+/// with `to` / `for` / `into` / `->` allowed between them
+/// (`SOL USDC 5`, `5 SOL to USDC`)
+pub fn parse_swap(args: &[&str]) -> Result<CommandAction, String> {
+    let mut symbols: Vec<String> = Vec::new();
+    let mut amount = None;
+
+    for arg in args {
+        if matches!(arg.to_lowercase().as_str(), "to" | "for" | "into" | "->") {
+            continue;
+        }
+        if arg.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+            if amount.replace(parse_amount(arg)?).is_some() {
+                return Err("Give a single amount".to_string());
+            }
+        } else {
+            symbols.push(parse_symbol(arg)?);
+        }
+    }
+
+    match <[String; 2]>::try_from(symbols) {
+        Ok([input, output]) if input == output => Err("Pick two different tokens".to_string()),
+        Ok([input, output]) => Ok(CommandAction::Swap { input, output, amount }),
+        Err(_) => Err(SWAP_USAGE.to_string()),
+    }
+}
+
+/// A token symbol as typed (`sol`, `$BONK`), upper-cased
+pub fn parse_symbol(arg: &str) -> Result<String, String> {
+    let symbol = arg.trim_start_matches('$');
+    if symbol.is_empty() || symbol.len() > MAX_SYMBOL_LEN || !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("`{}` is not a token symbol", arg));
+    }
+    Ok(symbol.to_uppercase())
+}
+
+/// A positive, finite token amount
+pub fn parse_amount(arg: &str) -> Result<f64, String> {
+    let value: f64 = arg.parse().map_err(|_| format!("`{}` is not a valid amount", arg))?;
+    if !(value.is_finite() && value > 0.0) {
+        return Err("Amount must be greater than zero".to_string());
+    }
+    Ok(value)
+}
+
+/// Arguments of `set timeframe`: one of the chart toolbar labels (`15m`, `4h`, ...)
+pub fn parse_timeframe(args: &[&str]) -> Result<CommandAction, String> {
+    let labels = || CHART_TIMEFRAMES.iter().map(|t| t.label().to_lowercase()).collect::<Vec<_>>().join(", ");
+    let [arg] = args else {
+        return Err(format!("Usage: set timeframe <{}>", labels()));
+    };
+    CHART_TIMEFRAMES
+        .iter()
+        .find(|t| t.label().eq_ignore_ascii_case(arg))
+        .map(|&t| CommandAction::SetTimeframe(t))
+        .ok_or_else(|| format!("Unknown timeframe `{}` (use {})", arg, labels()))
+}
+
+/// Look up both swap symbols in the token list
+pub fn resolve_swap_tokens(tokens: &[TokenInfo], input: &str, output: &str) -> Result<(TokenInfo, TokenInfo), String> {
+    let find = |symbol: &str| {
+        tokens
+            .iter()
+            .find(|t| t.symbol.eq_ignore_ascii_case(symbol))
+            .cloned()
+            .ok_or_else(|| format!("Unknown token {}", symbol))
+    };
+    Ok((find(input)?, find(output)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(symbol: &str) -> TokenInfo {
+        TokenInfo {
+            symbol: symbol.to_string(),
+            name: symbol.to_string(),
+            mint: format!("{}Mint", symbol),
+            decimals: 6,
+            price: 0.0,
+            balance: 0.0,
+            change_24h: 0.0,
+            is_favorite: false,
+            verified: true,
+            daily_volume: None,
+        }
+    }
+
+    fn swap(input: &str, output: &str, amount: Option<f64>) -> Result<CommandAction, String> {
+        Ok(CommandAction::Swap { input: input.to_string(), output: output.to_string(), amount })
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("go to wallet", "gtw").is_some());
+        assert!(fuzzy_score("go to wallet", "GO WAL").is_some());
+        assert_eq!(fuzzy_score("go to wallet", "wz"), None);
+        assert_eq!(fuzzy_score("go to wallet", "  "), None);
+        // Order matters
+        assert_eq!(fuzzy_score("go to wallet", "wg"), None);
+
+        // Word starts beat mid-word matches, runs beat scattered letters
+        assert!(fuzzy_score("go to wallet", "w") > fuzzy_score("go to swap", "w"));
+        assert!(fuzzy_score("toggle debug overlay", "debug") > fuzzy_score("toggle debug overlay", "dbgov"));
+        assert!(fuzzy_score("set timeframe", "time") > fuzzy_score("toggle debug overlay", "time"));
+    }
+
+    #[test]
+    fn test_parse_swap() {
+        assert_eq!(parse_swap(&["SOL", "USDC", "5"]), swap("SOL", "USDC", Some(5.0)));
+        assert_eq!(parse_swap(&["sol", "usdc"]), swap("SOL", "USDC", None));
+        assert_eq!(parse_swap(&["0.5", "$bonk", "to", "SOL"]), swap("BONK", "SOL", Some(0.5)));
+        assert_eq!(parse_swap(&["SOL", "->", "USDC", ".25"]), swap("SOL", "USDC", Some(0.25)));
+    }
+
+    #[test]
+    fn test_parse_swap_rejects_bad_arguments() {
+        assert_eq!(parse_swap(&[]), Err(SWAP_USAGE.to_string()));
+        assert_eq!(parse_swap(&["SOL"]), Err(SWAP_USAGE.to_string()));
+        assert_eq!(parse_swap(&["SOL", "USDC", "BONK"]), Err(SWAP_USAGE.to_string()));
+        assert!(parse_swap(&["SOL", "sol", "1"]).is_err());
+        assert!(parse_swap(&["SOL", "USDC", "0"]).is_err());
+        assert!(parse_swap(&["SOL", "USDC", "5x"]).is_err());
+        assert!(parse_swap(&["SOL", "USDC", "1", "2"]).is_err());
+        assert!(parse_swap(&["SOL", "US-DC", "1"]).is_err());
+        // "nan" and "inf" are symbols, not amounts
+        assert_eq!(parse_swap(&["nan", "inf"]), swap("NAN", "INF", None));
+    }
+
+    #[test]
+    fn test_parse_timeframe() {
+        assert_eq!(parse_timeframe(&["15m"]), Ok(CommandAction::SetTimeframe(Timeframe::FifteenMinutes)));
+        assert_eq!(parse_timeframe(&["4H"]), Ok(CommandAction::SetTimeframe(Timeframe::FourHours)));
+        assert!(parse_timeframe(&["1w"]).is_err());
+        assert!(parse_timeframe(&[]).unwrap_err().starts_with("Usage"));
+    }
+
+    #[test]
+    fn test_invoking_a_command_parses_its_arguments() {
+        let registry = CommandRegistry::builtin();
+        let top = &registry.suggest("swap SOL USDC 5", Screen::Terminal, true)[0];
+        assert_eq!(top.name, "swap");
+        assert_eq!(top.action, swap("SOL", "USDC", Some(5.0)));
+
+        let top = &registry.suggest("Go To Wallet", Screen::Terminal, true)[0];
+        assert_eq!(top.action, Ok(CommandAction::GoTo(Screen::Wallet)));
+        let top = &registry.suggest("open live table", Screen::Terminal, true)[0];
+        assert_eq!(top.action, Ok(CommandAction::GoTo(Screen::LiveTable)));
+
+        // A fuzzy match runs without arguments
+        let top = &registry.suggest("dbg", Screen::Terminal, true)[0];
+        assert_eq!(top.action, Ok(CommandAction::ToggleDebugOverlay));
+        assert!(registry.suggest("swap", Screen::Terminal, true)[0].action.is_err());
+    }
+
+    #[test]
+    fn test_refresh_commands() {
+        let registry = CommandRegistry::builtin();
+        assert_eq!(registry.suggest("refresh", Screen::Wallet, true)[0].action, Ok(CommandAction::Refresh));
+        assert_eq!(registry.suggest("refresh all", Screen::Wallet, true)[0].action, Ok(CommandAction::RefreshAll));
+        // Nothing to reload before logging in
+        assert!(registry.available(Screen::Auth, false).all(|c| !c.name.starts_with("refresh")));
+    }
+
+    #[test]
+    fn test_scoped_and_protected_commands() {
+        let mut registry = CommandRegistry::builtin();
+        registry.register(CommandSpec::with_args("set timeframe", "<timeframe>", "", parse_timeframe).on_screen(Screen::LiveChart));
+
+        let offered = |screen, auth| registry.available(screen, auth).map(|c| c.name.clone()).collect::<Vec<_>>();
+        assert!(offered(Screen::LiveChart, true).contains(&"set timeframe".to_string()));
+        assert!(!offered(Screen::Wallet, true).contains(&"set timeframe".to_string()));
+
+        let logged_out = offered(Screen::Auth, false);
+        assert!(logged_out.contains(&"go to chart".to_string()));
+        assert!(!logged_out.contains(&"go to wallet".to_string()));
+        assert!(!logged_out.contains(&"swap".to_string()));
+        assert_eq!(registry.suggest("", Screen::Auth, false).len(), logged_out.len());
+        assert!(registry.suggest("go", Screen::Auth, false).len() <= MAX_SUGGESTIONS);
+    }
+
+    #[test]
+    fn test_resolve_swap_tokens() {
+        let tokens = vec![token("SOL"), token("USDC")];
+        let (input, output) = resolve_swap_tokens(&tokens, "usdc", "SOL").unwrap();
+        assert_eq!((input.mint.as_str(), output.mint.as_str()), ("USDCMint", "SOLMint"));
+        assert_eq!(resolve_swap_tokens(&tokens, "SOL", "BONK").unwrap_err(), "Unknown token BONK");
+    }
+}
@@ -0,0 +1,167 @@
+//! # Copy-On-Write State Fields
+//!
+//! The UI renders from a clone of [`AppState`](crate::app::AppState) taken
+//! each frame, so that handlers can lock the state for writing while a screen
+//! draws. The large collections in it (candles, token lists, swap and wallet
+//! history, chat messages) are held in a [`CowArc`]: cloning one only bumps a
+//! reference count, and the snapshot shares its buffer with the live state.
+//!
+//! Mutation goes through `DerefMut`, which copies the buffer first if a
+//! snapshot still holds it ([`Arc::make_mut`]). Snapshots are dropped at the
+//! end of each frame, so a write between frames normally finds the buffer
+//! unshared and changes it in place.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// Shared value, copied on the first write while shared
+#[derive(Default)]
+pub struct CowArc<T>(Arc<T>);
+
+impl<T> CowArc<T> {
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// Whether both hold the same buffer
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        Arc::ptr_eq(&a.0, &b.0)
+    }
+}
+
+impl<T> Clone for CowArc<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Deref for CowArc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Clone> DerefMut for CowArc<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        Arc::make_mut(&mut self.0)
+    }
+}
+
+impl<T> From<T> for CowArc<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<A, T: FromIterator<A>> FromIterator<A> for CowArc<T> {
+    fn from_iter<I: IntoIterator<Item = A>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CowArc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: PartialEq> PartialEq for CowArc<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CowArc<T>
+where
+    &'a T: IntoIterator,
+{
+    type Item = <&'a T as IntoIterator>::Item;
+    type IntoIter = <&'a T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        (&*self.0).into_iter()
+    }
+}
+
+/// Heap bytes of the element buffer, not counting what the elements own
+pub fn buffer_bytes<T>(items: &[T]) -> usize {
+    std::mem::size_of_val(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone_shares_until_written() {
+        let mut live = CowArc::new(vec![1, 2, 3]);
+        let snapshot = live.clone();
+        assert!(CowArc::ptr_eq(&live, &snapshot));
+
+        live.push(4);
+        assert!(!CowArc::ptr_eq(&live, &snapshot));
+        assert_eq!(*snapshot, vec![1, 2, 3]);
+        assert_eq!(*live, vec![1, 2, 3, 4]);
+
+        // Unshared: written in place
+        let before = live.as_ptr();
+        live[0] = 10;
+        assert_eq!(live.as_ptr(), before);
+        assert_eq!((&live).into_iter().sum::<i32>(), 19);
+    }
+
+    #[test]
+    fn test_render_snapshot_shares_large_collections() {
+        use crate::app::state::{TokenInfo, TransactionItem};
+        use shared::dto::messaging::Message;
+        use shared::dto::OHLC;
+
+        let mut state = crate::app::App::new().state.read().clone();
+        state.terminal.swap.token_list = (0..10_000)
+            .map(|i| TokenInfo {
+                symbol: format!("TKN{}", i),
+                name: format!("Token {}", i),
+                mint: format!("mint{}", i),
+                decimals: 9,
+                price: 1.0,
+                balance: 0.0,
+                change_24h: 0.0,
+                is_favorite: false,
+                verified: true,
+                daily_volume: None,
+            })
+            .collect();
+        state.terminal.sol_candles = (0..2_000).map(|i| OHLC::new(i * 60, 1.0, 1.0, 1.0, 1.0, 0.0)).collect();
+        state.transactions = (0..5_000)
+            .map(|i| TransactionItem {
+                signature: format!("sig{}", i),
+                timestamp: i,
+                tx_type: "Transfer".to_string(),
+                status: "confirmed".to_string(),
+                amount: String::new(),
+                memo: None,
+                wallet: None,
+                latency: None,
+            })
+            .collect();
+        for conversation in 0..50 {
+            let messages = (0..100).map(|i| Message::new(format!("message {}", i), "alice".to_string(), 7)).collect();
+            state.messaging.messages.insert(format!("{}:7", conversation), messages);
+        }
+
+        let snapshot = state.clone();
+        assert!(CowArc::ptr_eq(&snapshot.terminal.swap.token_list, &state.terminal.swap.token_list));
+        assert!(CowArc::ptr_eq(&snapshot.terminal.sol_candles, &state.terminal.sol_candles));
+        assert!(CowArc::ptr_eq(&snapshot.transactions, &state.transactions));
+        assert!(CowArc::ptr_eq(&snapshot.messaging.messages, &state.messaging.messages));
+        // Over a megabyte of element buffers alone that a deep clone would copy
+        assert!(snapshot.shared_bytes() > 1024 * 1024, "{} bytes shared", snapshot.shared_bytes());
+
+        // A write after the frame leaves the snapshot untouched
+        state.transactions.clear();
+        assert_eq!(snapshot.transactions.len(), 5_000);
+    }
+}
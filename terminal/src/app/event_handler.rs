@@ -5,8 +5,15 @@
 //! This module processes `AppEvent` messages received from async tasks (network requests,
 //! blockchain operations, etc.) and updates the application state in a thread-safe manner.
 
+use crate::app::audit::{self, AuditCategory};
+use crate::app::recovery::RecoveryFlow;
 use crate::app::{App, AppEvent, Screen};
 use crate::app::state::{AuthState, PriceData};
+use crate::app::usage;
+use shared::dto::telemetry::{UsageErrorCategory, UsageEvent, UsageFeature};
+
+/// Shortest gap between two "request timed out" notifications
+const TIMEOUT_NOTICE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
 
 /// Trait for event handling implementation
 pub(crate) trait AppEventHandler {
@@ -36,30 +43,183 @@ impl AppEventHandler for App {
             AppEvent::WalletStatusChecked(result) => {
                 self.handle_wallet_status_checked(result);
             }
-            AppEvent::PricesUpdated(mut new_prices) => {
-                self.handle_prices_updated(&mut new_prices);
+            AppEvent::PricesUpdated(new_prices) => {
+                self.handle_prices_updated(new_prices);
             }
             AppEvent::PriceUpdated(new_price) => {
                 self.handle_price_updated(new_price);
             }
-            AppEvent::SwapQuoteResult(result) => {
-                self.handle_swap_quote_result(result);
+            AppEvent::PricesChanged => {
+                self.handle_prices_changed(true);
+            }
+            AppEvent::SwapQuoteResult(generation, result) => {
+                self.handle_swap_quote_result(generation, result);
+            }
+            AppEvent::QuoteUpdated(update) => {
+                crate::app::handlers::quote_stream::handle_quote_update(&mut self.state.write(), update);
+            }
+            AppEvent::RebalanceQuoteResult { generation, index, result } => {
+                self.handle_rebalance_quote_result(generation, index, result);
+            }
+            AppEvent::SwapPrepared(result) => {
+                self.handle_swap_prepared(result);
             }
             AppEvent::TokenListResult(result) => {
                 self.handle_token_list_result(result);
             }
-            AppEvent::SwapHistoryResult(result) => {
-                self.handle_swap_history_result(result);
+            AppEvent::StreamedSymbolsResult(result) => {
+                self.handle_streamed_symbols_result(result);
+            }
+            AppEvent::SwapHistoryResult(filters, result) => {
+                self.handle_swap_history_result(filters, result);
+            }
+            AppEvent::SwapHistoryExportProgress(count) => {
+                self.handle_swap_history_export_progress(count);
+            }
+            AppEvent::SwapHistoryExportFinished(result) => {
+                self.handle_swap_history_export_finished(result);
+            }
+            AppEvent::TokenBalancesUpdated(balances) => {
+                self.handle_token_balances_updated(balances);
+            }
+            AppEvent::SolBalanceUpdated { address, balance } => {
+                self.handle_sol_balance_updated(address, balance);
+            }
+            AppEvent::TransferPrepared(result) => {
+                crate::app::handlers::send::apply_transfer_prepared(&mut self.state.write(), result);
+            }
+            AppEvent::TransferResult(result) => {
+                self.handle_transfer_result(result);
+            }
+            AppEvent::AirdropResult(result) => {
+                self.handle_airdrop_result(result);
+            }
+            AppEvent::StakingInfoResult { address, result } => {
+                crate::app::handlers::staking::apply_staking_info(&mut self.state.write(), &address, result);
+            }
+            AppEvent::StakingRewardsResult { address, result } => {
+                crate::app::handlers::staking::apply_staking_rewards(&mut self.state.write(), &address, result);
+            }
+            AppEvent::StakeActionResult { action, result } => {
+                self.handle_stake_action_result(action, result);
             }
             AppEvent::CandlesResult(result) => {
                 self.handle_candles_result(result);
             }
+            AppEvent::CandleUpdated(symbol, timeframe, candle, is_closed) => {
+                crate::app::handlers::candles::apply_candle_update(&mut self.state.write(), &symbol, timeframe, candle, is_closed);
+            }
+            AppEvent::TokenDetailResult { symbol, prefetch, result } => {
+                self.handle_token_detail_result(symbol, prefetch, result);
+            }
+            AppEvent::DepthResult(result) => {
+                self.handle_depth_result(result);
+            }
+            AppEvent::MarketAnalyticsResult(result) => {
+                self.handle_market_analytics_result(result);
+            }
+            AppEvent::SparklinesResult(result) => {
+                self.handle_sparklines_result(result);
+            }
+            AppEvent::HealthResult(result) => {
+                self.handle_health_result(result);
+            }
+            AppEvent::TokenUnlocksResult(result) => {
+                self.handle_token_unlocks_result(result);
+            }
+            AppEvent::TaskFailed(task, error) => {
+                self.handle_task_failed(task, error);
+            }
+            AppEvent::ServerSwitched(switch) => {
+                self.handle_server_switched(switch);
+            }
+            AppEvent::ApiFailure(error) => {
+                self.handle_api_failure(error);
+            }
+            AppEvent::SearchRemoteResult(query, result) => {
+                self.handle_search_remote_result(query, result);
+            }
+            AppEvent::TradeImportResult(result) => {
+                self.handle_trade_import_result(result);
+            }
+            AppEvent::TradeStatsResult(result) => {
+                self.handle_trade_stats_result(result);
+            }
+            AppEvent::ShareLinkResult(result) => {
+                self.handle_share_link_result(result);
+            }
+            AppEvent::OnRampProvidersResult(result) => {
+                self.handle_onramp_providers_result(result);
+            }
+            AppEvent::UsageDataDeleted(result) => {
+                crate::app::handlers::settings::apply_usage_data_deleted(&mut self.state.write(), result);
+            }
+            AppEvent::WebhooksResult(result) => {
+                self.handle_webhooks_result(result);
+            }
+            AppEvent::WebhookCreated(result) => {
+                self.handle_webhook_created(result);
+            }
+            AppEvent::WebhookDeleted(id, result) => {
+                self.handle_webhook_deleted(id, result);
+            }
+            AppEvent::WebhookDeliveriesResult(id, result) => {
+                self.handle_webhook_deliveries_result(id, result);
+            }
+            AppEvent::WebhookTestResult(id, result) => {
+                self.handle_webhook_test_result(id, result);
+            }
+            AppEvent::ApiKeysResult(result) => {
+                self.handle_api_keys_result(result);
+            }
+            AppEvent::ApiKeyCreated(result) => {
+                self.handle_api_key_created(result);
+            }
+            AppEvent::ApiKeyRevoked(id, result) => {
+                self.handle_api_key_revoked(id, result);
+            }
+            AppEvent::AIChatMessages(conversation_id, messages) => {
+                self.handle_ai_chat_messages(conversation_id, messages);
+            }
+            AppEvent::AIChatChunk(conversation_id, delta) => {
+                self.handle_ai_chat_chunk(conversation_id, delta);
+            }
+            AppEvent::AIChatStreamEnded(conversation_id, outcome) => {
+                self.handle_ai_chat_stream_ended(conversation_id, outcome);
+            }
+            AppEvent::InstanceRequest(request) => {
+                self.handle_instance_request(request);
+            }
+            AppEvent::LogoutResult(result) => {
+                self.handle_logout_result(result);
+            }
+            AppEvent::ProfileUpdateResult(result) => {
+                self.handle_profile_update_result(result);
+            }
+            AppEvent::SessionRefreshResult { from_token, result } => {
+                self.handle_session_refresh_result(from_token, result);
+            }
+            AppEvent::TransactionHistoryResult(result) => {
+                self.handle_transaction_history_result(result);
+            }
+            AppEvent::TransactionStatusChanged { signature, status, error } => {
+                self.handle_transaction_status_changed(signature, status, error);
+            }
+            AppEvent::RecoverableError { flow, error } => {
+                self.handle_recoverable_error(flow, error);
+            }
             AppEvent::Loading(msg) => {
                 self.handle_loading(msg);
             }
+            AppEvent::SystemNotice(notice) => {
+                self.handle_system_notice(notice);
+            }
             AppEvent::WebSocketStatusUpdate(status) => {
                 self.handle_websocket_status_update(status);
             }
+            AppEvent::WebSocketStateChanged { state, attempts, retry_in, error } => {
+                self.handle_websocket_state_changed(state, attempts, retry_in, error);
+            }
         }
     }
 }
@@ -67,6 +227,10 @@ impl AppEventHandler for App {
 impl App {
     fn handle_websocket_status_update(&mut self, status: crate::app::WebSocketStatus) {
         let mut state = self.state.write();
+        // Late report from a stream closed by the idle teardown
+        if state.idle.is_low_power() {
+            return;
+        }
         let old_state = state.websocket_status.state.clone();
         let old_connected = state.websocket_connected;
         let old_message_count = state.websocket_status.messages_received;
@@ -103,6 +267,40 @@ impl App {
         }
     }
 
+    fn handle_websocket_state_changed(
+        &mut self,
+        new_state: crate::app::WebSocketState,
+        attempts: u64,
+        retry_in: Option<std::time::Duration>,
+        error: Option<String>,
+    ) {
+        use crate::app::WebSocketState;
+
+        let mut state = self.state.write();
+        if state.idle.is_low_power() {
+            return;
+        }
+        let status = &mut state.websocket_status;
+        let old_state = std::mem::replace(&mut status.state, new_state.clone());
+        status.connection_attempts = attempts;
+        status.next_retry = retry_in.map(|delay| std::time::Instant::now() + delay);
+        if new_state == WebSocketState::Connected {
+            status.last_connected = Some(std::time::Instant::now());
+            status.last_error = None;
+        } else if error.is_some() {
+            status.last_error = error;
+        }
+        state.websocket_connected = new_state == WebSocketState::Connected;
+        state.needs_immediate_repaint = true;
+        if matches!(new_state, WebSocketState::Reconnecting | WebSocketState::Disabled) {
+            crate::app::handlers::maintenance::handle_backend_unreachable(&mut state);
+        }
+
+        if old_state != new_state {
+            tracing::info!(old_state = ?old_state, new_state = ?new_state, attempts, "WebSocket state transition");
+        }
+    }
+
     fn handle_login_result(&mut self, result: Result<shared::AuthResponse, String>) {
         tracing::info!(event = "LoginResult", success = result.is_ok(), "Processing login result");
 
@@ -130,27 +328,36 @@ impl App {
                         username: auth_response.user.username.clone(),
                     });
                 }
+                audit::record(
+                    &mut state.security.trail,
+                    AuditCategory::Auth,
+                    format!("Logged in as {}", auth_response.user.username),
+                    vec![],
+                );
+
+                // Refresh dependency health so features are gated from the first frame
+                crate::app::tasks::health::check_health(self.state.clone(), self.event_tx.clone());
+                // Unread counts for the Messaging badge
+                crate::app::tasks::messaging::load_friends(self.state.clone());
                 
                 // Start WebSocket connection for real-time price updates (only once)
                 // Delay connection slightly to allow UI to initialize first
                 if !state.websocket_connected {
                     state.websocket_connected = true;
-                    let event_tx_clone = self.event_tx.clone();
-                    let app_state_clone = self.state.clone();
-                    tokio::spawn(async move {
-                        // Small delay to ensure UI is initialized before attempting connection
-                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                        crate::services::api::websocket::connect_price_stream(event_tx_clone, Some(app_state_clone)).await;
-                    });
+                    state.price_stream_task = Some(crate::services::api::websocket::spawn_price_stream(
+                        self.event_tx.clone(),
+                        self.state.clone(),
+                    ));
                     tracing::info!("Scheduled WebSocket price stream connection (delayed for UI initialization)");
                 }
                 
                 // Only switch to Terminal screen if wallet is connected
                 if has_wallet {
                     state.current_screen = Screen::Terminal;
-                    // Fetch initial candles for SOL chart
+                    // Fetch initial candles for the chart
                     let timeframe = state.terminal.chart_timeframe;
-                    let needs_initial_prices = state.terminal.prices.is_empty();
+                    let chart_symbol = state.terminal.chart_symbol.clone();
+                    let needs_initial_prices = state.terminal.prices.load().is_empty();
                     drop(state);
                     
                     // Fetch initial prices immediately if we have none (don't wait for WebSocket)
@@ -159,13 +366,14 @@ impl App {
                         crate::app::tasks::market::fetch_prices(self.state.clone(), self.event_tx.clone());
                     }
                     
-                    self.fetch_candles("SOL", timeframe);
+                    self.fetch_candles(&chart_symbol, timeframe);
                 } else {
                     // No wallet - stay on Auth screen
                     state.current_screen = Screen::Auth;
                 }
             }
             Err(err) => {
+                usage::capture(UsageEvent::Error(UsageErrorCategory::Login));
                 if let AuthState::Login { error, .. } = &mut state.auth {
                     *error = Some(err);
                 }
@@ -183,9 +391,10 @@ impl App {
                 let token = auth_response.token.clone();
                 let should_open_wallet = auth_response.wallet_setup_required == Some(true);
                 let has_wallet = auth_response.user.wallet_address.is_some();
+                let wallet_base = crate::app::handlers::auth::wallet_web_url(&state);
                 let wallet_url = if should_open_wallet {
                     auth_response.wallet_setup_token.as_ref().map(|token| {
-                        format!("http://localhost:8080/?token={}", token)
+                        format!("{}/?token={}", wallet_base, token)
                     })
                 } else {
                     None
@@ -219,6 +428,12 @@ impl App {
                         username: auth_response.user.username.clone(),
                     });
                 }
+                audit::record(
+                    &mut state.security.trail,
+                    AuditCategory::Auth,
+                    format!("Signed up as {}", auth_response.user.username),
+                    vec![],
+                );
                 if let Some(creds) = polling_creds {
                     state.polling_credentials = Some(creds);
                 }
@@ -227,13 +442,10 @@ impl App {
                 // Delay connection slightly to allow UI to initialize first
                 if !state.websocket_connected {
                     state.websocket_connected = true;
-                    let event_tx_clone = self.event_tx.clone();
-                    let app_state_clone = self.state.clone();
-                    tokio::spawn(async move {
-                        // Small delay to ensure UI is initialized before attempting connection
-                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                        crate::services::api::websocket::connect_price_stream(event_tx_clone, Some(app_state_clone)).await;
-                    });
+                    state.price_stream_task = Some(crate::services::api::websocket::spawn_price_stream(
+                        self.event_tx.clone(),
+                        self.state.clone(),
+                    ));
                     tracing::info!("Scheduled WebSocket price stream connection (delayed for UI initialization)");
                 }
 
@@ -254,7 +466,7 @@ impl App {
                             tracing::error!("Failed to open wallet-web: {}", e);
                             let mut state = self.state.write();
                             if let AuthState::Signup { error, .. } = &mut state.auth {
-                                *error = Some("Failed to open wallet connection page. Please visit http://localhost:8080 manually.".to_string());
+                                *error = Some(format!("Failed to open wallet connection page. Please visit {} manually.", wallet_base));
                             }
                             drop(state);
                         } else {
@@ -270,6 +482,7 @@ impl App {
                 }
             }
             Err(err) => {
+                usage::capture(UsageEvent::Error(UsageErrorCategory::Login));
                 if let AuthState::Signup { error, .. } = &mut state.auth {
                     *error = Some(err);
                 }
@@ -311,147 +524,220 @@ impl App {
         }
     }
 
-    fn handle_prices_updated(&mut self, new_prices: &mut Vec<PriceData>) {
+    /// Batch of prices delivered through the event channel (tests, legacy senders)
+    fn handle_prices_updated(&mut self, new_prices: Vec<PriceData>) {
         tracing::debug!(event = "PricesUpdated", count = new_prices.len(), "Processing price update");
+        let store = self.state.read().terminal.prices.clone();
+        let changed = store.apply(&new_prices);
+        self.handle_prices_changed(changed);
+    }
+
+    fn handle_token_balances_updated(&mut self, balances: Vec<crate::app::state::TokenBalance>) {
+        tracing::debug!(event = "TokenBalancesUpdated", count = balances.len(), "Processing token balance update");
         let mut state = self.state.write();
-        let mut any_changes = false;
-        
-        // Store previous prices before updating
-        for new_price in new_prices.iter_mut() {
-            // Find matching existing price by symbol
-            if let Some(existing) = state.terminal.prices.iter().find(|p| p.symbol == new_price.symbol) {
-                // Store current price as previous price
-                new_price.previous_price = Some(existing.price);
-                // Check if price actually changed
-                if (existing.price - new_price.price).abs() > 0.0001 {
-                    any_changes = true;
-                }
-            } else {
-                // New token
-                any_changes = true;
+        match state.wallet.as_mut() {
+            Some(wallet) => wallet.token_balances = balances,
+            None => {
+                tracing::debug!("Ignoring token balances - no wallet connected");
+                return;
             }
         }
-        
-        state.terminal.prices = new_prices.clone();
-        state.terminal.last_price_update = std::time::Instant::now();
-        
-        // CRITICAL: Set immediate repaint flag for real-time updates
-        if any_changes {
-            state.needs_immediate_repaint = true;
-            state.last_price_update_time = std::time::Instant::now();
+        crate::app::handlers::recovery::resolve(&mut state, RecoveryFlow::Wallet);
+
+        let snapshots = crate::app::handlers::portfolio::recompute_portfolio(&mut state);
+        state.needs_immediate_repaint = true;
+        drop(state);
+        Self::persist_portfolio_snapshots(snapshots);
+    }
+
+    fn handle_sol_balance_updated(&mut self, address: String, balance: f64) {
+        tracing::debug!(event = "SolBalanceUpdated", address = %address, balance, "Processing SOL balance update");
+        let mut state = self.state.write();
+        match state.wallet.as_mut() {
+            Some(wallet) if wallet.address == address => wallet.sol_balance = balance,
+            _ => {
+                tracing::debug!("Ignoring SOL balance - wallet changed since the request");
+                return;
+            }
+        }
+        crate::app::handlers::recovery::resolve(&mut state, RecoveryFlow::Wallet);
+
+        let snapshots = crate::app::handlers::portfolio::recompute_portfolio(&mut state);
+        state.needs_immediate_repaint = true;
+        drop(state);
+        Self::persist_portfolio_snapshots(snapshots);
+    }
+
+    fn handle_transfer_result(&mut self, result: Result<String, String>) {
+        tracing::info!(event = "TransferResult", success = result.is_ok(), "Processing transfer result");
+        usage::capture(match result {
+            Ok(_) => UsageEvent::Feature(UsageFeature::Transfer),
+            Err(_) => UsageEvent::Error(UsageErrorCategory::Transfer),
+        });
+        crate::app::handlers::send::apply_transfer_result(&mut self.state.write(), result);
+    }
+
+    fn handle_airdrop_result(&mut self, result: Result<String, String>) {
+        tracing::info!(event = "AirdropResult", success = result.is_ok(), "Processing airdrop result");
+        let confirmed = result.is_ok();
+        crate::app::handlers::airdrop::apply_airdrop_result(&mut self.state.write(), result, std::time::Instant::now());
+        if confirmed {
+            crate::app::handlers::wallet::refresh_balances(self.state.clone(), self.event_tx.clone());
         }
     }
 
+    fn handle_stake_action_result(&mut self, action: crate::app::handlers::staking::StakeAction, result: Result<String, String>) {
+        tracing::info!(event = "StakeActionResult", ?action, success = result.is_ok(), "Processing staking result");
+        if result.is_ok() {
+            usage::capture(UsageEvent::Feature(UsageFeature::Stake));
+        }
+        crate::app::handlers::staking::apply_stake_action_result(&mut self.state.write(), action, result);
+    }
+
+    /// Save portfolio history (called after the state lock is released)
+    fn persist_portfolio_snapshots(snapshots: Option<Vec<crate::app::state::PortfolioSnapshot>>) {
+        if let Some(snapshots) = snapshots {
+            if let Err(e) = crate::app::handlers::portfolio::save_snapshots(&snapshots) {
+                tracing::warn!("Failed to save portfolio history: {}", e);
+            }
+        }
+    }
+
+    /// Single price delivered through the event channel (WebSocket without app state)
     fn handle_price_updated(&mut self, new_price: PriceData) {
-        tracing::info!(
+        tracing::debug!(
             event = "PriceUpdated",
             symbol = %new_price.symbol,
             price = new_price.price,
-            source = ?new_price.source,
-            "EVENT HANDLER: Processing PriceUpdated event from WebSocket"
+            "Processing PriceUpdated event"
         );
-        let is_sol = new_price.symbol == "SOL";
+        let store = self.state.read().terminal.prices.clone();
+        store.apply(std::slice::from_ref(&new_price));
+        // Every streamed update triggers a repaint, even if the price is unchanged
+        self.handle_prices_changed(true);
+    }
+
+    /// React to a write to the price store.
+    ///
+    /// The prices themselves are already published; this only updates the
+    /// repaint flags, revalues the portfolio and kicks off the first SOL candle
+    /// fetch once a SOL price is known.
+    fn handle_prices_changed(&mut self, changed: bool) {
         let mut state = self.state.write();
-        let price_count_before = state.terminal.prices.len();
-        let is_new_token = !state.terminal.prices.iter().any(|p| p.symbol == new_price.symbol);
-        
-        tracing::debug!(
-            symbol = %new_price.symbol,
-            price_count_before = price_count_before,
-            is_new_token = is_new_token,
-            "Checking if token exists in price list"
-        );
-        
-        // Find existing price and update it
-        let _price_changed = if let Some(existing) = state.terminal.prices.iter_mut().find(|p| p.symbol == new_price.symbol) {
-            // Store current price as previous price
-            let old_price = existing.price;
-            let price_changed = (existing.price - new_price.price).abs() > 0.0001; // Significant change
-            existing.previous_price = Some(existing.price);
-            existing.price = new_price.price;
-            existing.change_24h = new_price.change_24h;
-            tracing::info!(
-                symbol = %new_price.symbol,
-                old_price = old_price,
-                new_price = new_price.price,
-                price_change = new_price.price - old_price,
-                "Updated existing token price in state - UI should reflect this change"
-            );
-            price_changed
-        } else {
-            // New token, add it to the list
-            state.terminal.prices.push(new_price.clone());
-            tracing::info!(
-                symbol = %new_price.symbol,
-                price = new_price.price,
-                price_count_after = state.terminal.prices.len(),
-                "Added new token to price list - UI should display this new token"
-            );
-            true // New token counts as a change
-        };
-        
-        // CRITICAL: Always set immediate repaint flag for instant Bloomberg-style updates
-        // Every WebSocket price update should trigger immediate UI refresh (<10ms latency)
-        // Set these flags BEFORE releasing the lock to ensure they're visible immediately
-        state.needs_immediate_repaint = true;
-        state.last_price_update_time = std::time::Instant::now();
-        state.terminal.last_price_update = std::time::Instant::now();
-        
-        // Log repaint trigger for debugging
-        tracing::debug!(
-            symbol = %new_price.symbol,
-            needs_immediate_repaint = state.needs_immediate_repaint,
-            "Repaint flag set - UI will update immediately on next frame"
-        );
-        
-        let price_count_after = state.terminal.prices.len();
-        tracing::info!(
-            symbol = %new_price.symbol,
-            price_count_before = price_count_before,
-            price_count_after = price_count_after,
-            total_tokens = price_count_after,
-            needs_immediate_repaint = state.needs_immediate_repaint,
-            "Price list state updated - ready for UI rendering"
-        );
-        
-        // If this is SOL and we don't have candles yet, fetch them
-        let should_fetch = is_sol && state.terminal.sol_candles.is_empty() && !state.terminal.chart_loading;
-        let timeframe = if should_fetch {
-            Some(state.terminal.chart_timeframe)
-        } else {
-            None
-        };
+        let now = std::time::Instant::now();
+        state.terminal.last_price_update = now;
+
+        // CRITICAL: Set immediate repaint flag for real-time updates
+        if changed {
+            state.needs_immediate_repaint = true;
+            state.last_price_update_time = now;
+        }
+
+        // Start the flash of every price that moved since the last event
+        let snapshot = state.terminal.prices.load();
+        let terminal = &mut state.terminal;
+        for price in snapshot.moved_since(terminal.price_flash_version) {
+            terminal.price_flashes.insert(price.symbol.clone(), now);
+        }
+        terminal.price_flash_version = snapshot.version;
+
+        let snapshots = crate::app::handlers::portfolio::recompute_portfolio(&mut state);
+        crate::app::handlers::annotations::check_annotation_alerts(&mut state, chrono::Utc::now().timestamp());
+
+        // If we have a price for the chart's symbol but no candles yet, fetch them
+        let symbol = state.terminal.chart_symbol.clone();
+        let has_price = state.terminal.prices.load().get(&symbol).is_some();
+        let should_fetch = has_price && state.terminal.sol_candles.is_empty() && !state.terminal.chart_loading;
+        let timeframe = should_fetch.then_some(state.terminal.chart_timeframe);
         drop(state);
-        
+        Self::persist_portfolio_snapshots(snapshots);
+
         if let Some(tf) = timeframe {
-            tracing::debug!(symbol = "SOL", timeframe = ?tf, "Triggering candle fetch for SOL");
-            self.fetch_candles("SOL", tf);
+            tracing::debug!(symbol = %symbol, timeframe = ?tf, "Triggering candle fetch for chart");
+            self.fetch_candles(&symbol, tf);
         }
     }
 
-    fn handle_swap_quote_result(&mut self, result: Result<crate::app::state::SwapQuote, String>) {
-        tracing::info!(event = "SwapQuoteResult", success = result.is_ok(), "Processing swap quote result");
+    fn handle_swap_quote_result(&mut self, generation: u64, result: Result<crate::app::state::SwapQuote, String>) {
+        tracing::info!(event = "SwapQuoteResult", generation, success = result.is_ok(), "Processing swap quote result");
         let mut state = self.state.write();
+        let swap = &mut state.terminal.swap;
+        if generation < swap.quote_generation {
+            // Answer to an amount or pair that has since changed
+            tracing::debug!(generation, current = swap.quote_generation, "Dropping stale swap quote");
+            return;
+        }
+        swap.quote_debounce = None;
+        swap.quote_loading = false;
         match result {
             Ok(quote) => {
-                state.terminal.swap.quote = Some(quote);
-                state.terminal.swap.quote_loading = false;
+                swap.quote = Some(quote);
+                swap.quote_updated_at = Some(std::time::Instant::now());
             }
             Err(_err) => {
-                // Failed to fetch quote - clear it and stop loading
-                state.terminal.swap.quote = None;
-                state.terminal.swap.quote_loading = false;
+                // Failed to fetch quote - clear it
+                swap.quote = None;
             }
         }
     }
 
+    fn handle_rebalance_quote_result(&mut self, generation: u64, index: usize, result: Result<crate::app::state::SwapQuote, String>) {
+        tracing::debug!(event = "RebalanceQuoteResult", generation, index, success = result.is_ok(), "Processing rebalance quote");
+        let mut state = self.state.write();
+        let rebalance = &mut state.portfolio.rebalance;
+        if generation != rebalance.generation {
+            return;
+        }
+        if let Some(proposal) = rebalance.proposals.get_mut(index) {
+            proposal.quote = Some(result);
+        }
+    }
+
+    fn handle_swap_prepared(&mut self, result: Result<Box<crate::app::state::SwapConfirmation>, String>) {
+        tracing::info!(event = "SwapPrepared", success = result.is_ok(), "Processing swap simulation");
+        let mut state = self.state.write();
+        state.terminal.swap.preparing = false;
+        match result {
+            Ok(confirmation) => {
+                // A simulation that fails for lack of rent gets a card next to
+                // the blocked dialog; any other outcome means the swap got through
+                let simulation_error = confirmation.error().map(str::to_string);
+                if !simulation_error.is_some_and(|err| crate::app::handlers::recovery::raise(&mut state, RecoveryFlow::Swap, err)) {
+                    crate::app::handlers::recovery::resolve(&mut state, RecoveryFlow::Swap);
+                }
+                state.terminal.swap.confirmation = Some(*confirmation);
+            }
+            Err(err) => {
+                let message = format!("Swap failed: {}", err);
+                if !crate::app::handlers::recovery::raise(&mut state, RecoveryFlow::Swap, message.clone()) {
+                    state.pending_notifications.push(("error".to_string(), message));
+                }
+            }
+        }
+    }
+
+    fn handle_recoverable_error(&mut self, flow: RecoveryFlow, error: String) {
+        tracing::info!(event = "RecoverableError", ?flow, error = %error, "Processing recoverable error");
+        usage::capture(UsageEvent::Error(match flow {
+            RecoveryFlow::Wallet => UsageErrorCategory::Wallet,
+            RecoveryFlow::Swap => UsageErrorCategory::Swap,
+        }));
+        let mut state = self.state.write();
+        if !crate::app::handlers::recovery::raise(&mut state, flow, error.clone()) {
+            state.pending_notifications.push(("error".to_string(), error));
+        }
+    }
+
     fn handle_token_list_result(&mut self, result: Result<Vec<crate::app::state::TokenInfo>, String>) {
         let count = result.as_ref().map(|t| t.len()).unwrap_or(0);
         tracing::info!(event = "TokenListResult", success = result.is_ok(), count = count, "Processing token list result");
         let mut state = self.state.write();
         match result {
-            Ok(tokens) => {
-                state.terminal.swap.token_list = tokens;
+            Ok(mut tokens) => {
+                for token in &mut tokens {
+                    token.is_favorite = state.settings.tokens.is_favorite(&token.mint);
+                }
+                state.terminal.swap.token_list = tokens.into();
             }
             Err(_err) => {
                 // Failed to fetch token list - keep existing
@@ -459,24 +745,71 @@ impl App {
         }
     }
 
-    fn handle_swap_history_result(&mut self, result: Result<Vec<crate::app::state::SwapHistoryItem>, String>) {
-        let count = result.as_ref().map(|h| h.len()).unwrap_or(0);
+    fn handle_streamed_symbols_result(&mut self, result: Result<Vec<String>, String>) {
+        match result {
+            Ok(symbols) => {
+                tracing::info!(event = "StreamedSymbolsResult", count = symbols.len(), "Backend price stream symbols loaded");
+                self.state.write().terminal.streamed_symbols = symbols;
+            }
+            Err(err) => {
+                // Keep the fallback list; the next price fetch retries
+                tracing::warn!(event = "StreamedSymbolsResult", error = %err, "Failed to fetch streamed symbols");
+            }
+        }
+    }
+
+    fn handle_swap_history_result(
+        &mut self,
+        filters: crate::app::state::SwapHistoryFilters,
+        result: Result<crate::app::events::SwapHistoryPage, String>,
+    ) {
+        let count = result.as_ref().map(|p| p.items.len()).unwrap_or(0);
         tracing::info!(event = "SwapHistoryResult", success = result.is_ok(), count = count, "Processing swap history result");
         let mut state = self.state.write();
+        let swap = &mut state.terminal.swap;
+        swap.history_loading = false;
+        // The filters changed while this page was in flight
+        if swap.history_filters != filters {
+            return;
+        }
         match result {
-            Ok(history) => {
-                state.terminal.swap.swap_history = history;
+            Ok(page) => {
+                swap.swap_history = page.items.into();
+                swap.history_total = page.total;
             }
-            Err(_err) => {
-                // Failed to fetch history - keep existing
+            Err(err) => {
+                // Keep the previous page visible under the error
+                swap.history_error = Some(err);
             }
         }
     }
 
+    fn handle_swap_history_export_progress(&mut self, count: u64) {
+        // Progress arriving after the export finished must not bring it back
+        if let Some(written) = self.state.write().terminal.swap.history_export.as_mut() {
+            *written = (*written).max(count);
+        }
+    }
+
+    fn handle_swap_history_export_finished(&mut self, result: Result<(std::path::PathBuf, u64), String>) {
+        tracing::info!(event = "SwapHistoryExportFinished", success = result.is_ok(), "Swap history export finished");
+        let mut state = self.state.write();
+        state.terminal.swap.history_export = None;
+        let notification = match result {
+            Ok((path, count)) => {
+                usage::capture(UsageEvent::Feature(UsageFeature::Export));
+                ("success".to_string(), format!("Exported {} swaps to {}", count, path.display()))
+            }
+            Err(err) => ("error".to_string(), format!("Swap history export failed: {}", err)),
+        };
+        state.pending_notifications.push(notification);
+    }
+
     fn handle_candles_result(&mut self, result: Result<Vec<shared::dto::OHLC>, String>) {
         let count = result.as_ref().map(|c| c.len()).unwrap_or(0);
         let mut state = self.state.write();
         let timeframe = state.terminal.chart_timeframe;
+        let symbol = state.terminal.chart_symbol.clone();
         let timeframe_str = match timeframe {
             shared::dto::market::Timeframe::OneMinute => "1m",
             shared::dto::market::Timeframe::FiveMinutes => "5m",
@@ -491,7 +824,7 @@ impl App {
             event = "CandlesResult",
             success = result.is_ok(),
             count = count,
-            symbol = "SOL",
+            symbol = %symbol,
             timeframe = %timeframe_str,
             "Processing candles result"
         );
@@ -500,24 +833,29 @@ impl App {
             Ok(candles) => {
                 if candles.is_empty() {
                     tracing::warn!(
-                        symbol = "SOL",
+                        symbol = %symbol,
                         timeframe = %timeframe_str,
                         "Received empty candle list"
                     );
                 } else {
                     tracing::debug!(
-                        symbol = "SOL",
+                        symbol = %symbol,
                         timeframe = %timeframe_str,
                         count = candles.len(),
                         "Candles loaded successfully"
                     );
                 }
-                state.terminal.sol_candles = candles;
+                state.terminal.sol_candles = candles.into();
+                state.terminal.sol_candle_forming = false;
+                state.terminal.chart_data_version += 1;
                 state.terminal.chart_loading = false;
+                let config = state.settings.indicators;
+                let terminal = &mut state.terminal;
+                terminal.chart_indicators.update(&terminal.sol_candles, &config);
             }
             Err(err) => {
                 tracing::warn!(
-                    symbol = "SOL",
+                    symbol = %symbol,
                     timeframe = %timeframe_str,
                     error = %err,
                     "Failed to fetch candles"
@@ -528,6 +866,678 @@ impl App {
         }
     }
 
+    fn handle_token_detail_result(
+        &mut self,
+        symbol: String,
+        prefetch: bool,
+        result: Result<crate::app::preload::TokenDetail, String>,
+    ) {
+        tracing::debug!(event = "TokenDetailResult", symbol = %symbol, prefetch, success = result.is_ok(), "Processing token detail");
+        let mut state = self.state.write();
+        let live_assets = &mut state.live_assets;
+        if prefetch {
+            live_assets.prefetch.finish(&symbol);
+        }
+        let selected = live_assets.selected.as_deref() == Some(symbol.as_str());
+        match result {
+            Ok(detail) => {
+                live_assets.details.insert(&symbol, detail, std::time::Instant::now());
+                if selected {
+                    live_assets.detail_loading = false;
+                    live_assets.detail_error = None;
+                }
+            }
+            // A failed prefetch only matters if the user is waiting on it
+            Err(err) if selected => {
+                live_assets.detail_loading = false;
+                live_assets.detail_error = Some(err);
+            }
+            Err(_) => {}
+        }
+    }
+
+    fn handle_health_result(&mut self, result: Result<shared::dto::system::HealthResponse, String>) {
+        let mut state = self.state.write();
+        let health = &mut state.backend_health;
+        health.last_checked = Some(std::time::Instant::now());
+        match result {
+            Ok(report) => {
+                tracing::debug!(event = "HealthResult", status = ?report.status, "Backend health updated");
+                health.report = Some(report);
+                health.error = None;
+                drop(state);
+                // Readiness is back: leave maintenance, if in it
+                crate::app::handlers::maintenance::handle_backend_ready(self.state.clone(), self.event_tx.clone());
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Backend health check failed");
+                health.error = Some(err);
+                crate::app::handlers::maintenance::handle_backend_unreachable(&mut state);
+            }
+        }
+    }
+
+    fn handle_search_remote_result(&mut self, query: String, result: Result<Vec<crate::app::search::SearchResult>, String>) {
+        let mut state = self.state.write();
+        match result {
+            Ok(results) => {
+                crate::app::handlers::search::apply_remote_results(&mut state, &query, results);
+            }
+            Err(err) => {
+                // Local results stay; the lookup is best effort
+                tracing::warn!(event = "SearchRemoteResult", error = %err, "Backend transaction search failed");
+                if state.search.remote_pending.as_deref() == Some(query.as_str()) {
+                    state.search.remote_pending = None;
+                }
+            }
+        }
+    }
+
+    fn handle_trade_import_result(&mut self, result: Result<shared::dto::trades::TradeImportResponse, String>) {
+        use crate::app::handlers::trade_import;
+        use crate::app::state::TradeImportStep;
+
+        let committed = {
+            let mut state = self.state.write();
+            let import = &mut state.trade_import;
+            import.loading = false;
+            match result {
+                Ok(response) => {
+                    tracing::debug!(
+                        event = "TradeImportResult",
+                        valid = response.valid.len(),
+                        errors = response.errors.len(),
+                        imported = response.imported,
+                        "Trade import response"
+                    );
+                    import.step = trade_import::next_step(&response);
+                    // First pass over an unfamiliar export: pre-fill the mapping step
+                    if import.step == TradeImportStep::Mapping
+                        && import.mapping == shared::dto::trades::ColumnMapping::default()
+                    {
+                        import.mapping = trade_import::guess_mapping(&response.headers);
+                    }
+                    let committed = response.committed;
+                    let message = format!("Imported {} trades", response.imported);
+                    import.preview = Some(response);
+                    if committed {
+                        state.pending_notifications.push(("success".to_string(), message));
+                    }
+                    committed
+                }
+                Err(err) => {
+                    tracing::warn!(error = %err, "Trade import failed");
+                    import.error = Some(err);
+                    false
+                }
+            }
+        };
+
+        if committed {
+            usage::capture(UsageEvent::Feature(UsageFeature::TradeImport));
+            trade_import::handle_trade_stats_refresh(self.state.clone(), self.event_tx.clone());
+        }
+    }
+
+    fn handle_trade_stats_result(&mut self, result: Result<shared::dto::trades::TradeStatsResponse, String>) {
+        let mut state = self.state.write();
+        state.trade_import.stats_loading = false;
+        match result {
+            Ok(stats) => {
+                tracing::debug!(event = "TradeStatsResult", pairs = stats.pairs.len(), "Trade stats loaded");
+                state.trade_import.stats = Some(stats);
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to fetch trade stats");
+            }
+        }
+    }
+
+    fn handle_server_switched(&mut self, switch: crate::services::api::failover::ServerSwitch) {
+        use crate::services::api::websocket;
+
+        let mut state = self.state.write();
+        let message = if switch.to_primary {
+            format!("Primary server is back - reconnected to {}", switch.to)
+        } else {
+            format!("Switched to backup server ({})", switch.to)
+        };
+        let level = if switch.to_primary { "info" } else { "warning" };
+        state.pending_notifications.push((level.to_string(), message));
+
+        // Move the price stream to the new host; the JWT stays valid since
+        // every server shares the same database
+        if state.price_stream_task.is_some() {
+            websocket::restart_price_stream(&mut state, self.event_tx.clone(), self.state.clone());
+        }
+        drop(state);
+
+        crate::app::tasks::health::check_health(self.state.clone(), self.event_tx.clone());
+    }
+
+    fn handle_onramp_providers_result(&mut self, result: Result<shared::dto::onramp::OnRampProvidersResponse, String>) {
+        crate::app::handlers::onramp::apply_onramp_providers(&mut self.state.write().onramp, result);
+    }
+
+    fn handle_api_failure(&mut self, error: crate::services::api::ApiError) {
+        use crate::services::api::ApiError;
+
+        if !matches!(error, ApiError::Unauthorized) {
+            usage::capture(UsageEvent::Error(UsageErrorCategory::Backend));
+        }
+        let mut state = self.state.write();
+        match error {
+            ApiError::Unauthorized => {
+                if crate::app::handlers::auth::handle_session_expired(&mut state) {
+                    tracing::warn!(event = "ApiFailure", "Session token rejected, logged out");
+                }
+            }
+            ApiError::Timeout => {
+                // A stalled backend times out every request in flight; one toast is enough
+                let now = std::time::Instant::now();
+                let health = &mut state.backend_health;
+                if health.last_timeout_notice.is_some_and(|at| now.duration_since(at) < TIMEOUT_NOTICE_INTERVAL) {
+                    return;
+                }
+                health.last_timeout_notice = Some(now);
+                state
+                    .pending_notifications
+                    .push(("warning".to_string(), "Request timed out - check your connection and try again".to_string()));
+            }
+            other => tracing::debug!(event = "ApiFailure", error = %other, "Ignoring API failure"),
+        }
+    }
+
+    fn handle_instance_request(&mut self, request: crate::services::protocol_handler::Request) {
+        use crate::services::protocol_handler::{parse_link, Request};
+
+        let mut state = self.state.write();
+        state.link_prompt.raise_window = true;
+        let Request::Open(url) = request else {
+            return;
+        };
+        match parse_link(&url) {
+            Ok(link) => {
+                tracing::info!(event = "InstanceRequest", link = ?link, "Link opened, waiting for confirmation");
+                // A newer link replaces one that was never answered
+                state.link_prompt.pending = Some((url, link));
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Ignored link");
+                state.pending_notifications.push(("error".to_string(), format!("Ignored link: {}", err)));
+            }
+        }
+    }
+
+    fn handle_share_link_result(&mut self, result: Result<shared::dto::share::CreateShareResponse, String>) {
+        let mut state = self.state.write();
+        state.share.pending = false;
+        match result {
+            Ok(response) => {
+                tracing::info!(event = "ShareLinkResult", slug = %response.slug, "Share link created");
+                usage::capture(UsageEvent::Feature(UsageFeature::Share));
+                // Copied to the clipboard by the screen that asked for it on its next frame
+                let base_url = state.api_client.as_ref().map(|c| c.base_url()).unwrap_or_default();
+                state.share.link = Some(format!("{}{}", base_url, response.path));
+                state.share.copied = false;
+                let message = match response.expires_at {
+                    Some(_) => format!(
+                        "Share link copied to clipboard (expires in {}h)",
+                        state.share.expires_in_hours.unwrap_or_default()
+                    ),
+                    None => "Share link copied to clipboard".to_string(),
+                };
+                state.pending_notifications.push(("success".to_string(), message));
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to create share link");
+                state.pending_notifications.push(("error".to_string(), format!("Share failed: {}", err)));
+            }
+        }
+    }
+
+    fn handle_webhooks_result(&mut self, result: Result<Vec<shared::dto::webhooks::WebhookInfo>, String>) {
+        let mut state = self.state.write();
+        let webhooks = &mut state.webhooks;
+        webhooks.loading = false;
+        // Set on failure too, so the section doesn't refetch every frame
+        webhooks.loaded = true;
+        match result {
+            Ok(list) => {
+                tracing::debug!(event = "WebhooksResult", count = list.len(), "Webhooks loaded");
+                if webhooks.selected.is_some_and(|id| !list.iter().any(|w| w.id == id)) {
+                    webhooks.selected = None;
+                    webhooks.deliveries.clear();
+                }
+                webhooks.webhooks = list;
+                webhooks.error = None;
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to fetch webhooks");
+                webhooks.error = Some(err);
+            }
+        }
+    }
+
+    fn handle_webhook_created(&mut self, result: Result<shared::dto::webhooks::CreateWebhookResponse, String>) {
+        let mut state = self.state.write();
+        let webhooks = &mut state.webhooks;
+        webhooks.creating = false;
+        match result {
+            Ok(response) => {
+                tracing::info!(event = "WebhookCreated", id = response.webhook.id, "Webhook registered");
+                usage::capture(UsageEvent::Feature(UsageFeature::Webhook));
+                webhooks.new_secret = Some((response.webhook.id, response.secret));
+                webhooks.webhooks.push(response.webhook);
+                webhooks.url_input.clear();
+                webhooks.threshold_input.clear();
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to register webhook");
+                webhooks.error = Some(err);
+            }
+        }
+    }
+
+    fn handle_webhook_deleted(&mut self, id: i64, result: Result<(), String>) {
+        let mut state = self.state.write();
+        match result {
+            Ok(()) => {
+                let webhooks = &mut state.webhooks;
+                webhooks.webhooks.retain(|w| w.id != id);
+                if webhooks.selected == Some(id) {
+                    webhooks.selected = None;
+                    webhooks.deliveries.clear();
+                }
+                if webhooks.new_secret.as_ref().is_some_and(|(secret_id, _)| *secret_id == id) {
+                    webhooks.new_secret = None;
+                }
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, id, "Failed to delete webhook");
+                state.pending_notifications.push(("error".to_string(), format!("Delete failed: {}", err)));
+            }
+        }
+    }
+
+    fn handle_webhook_deliveries_result(
+        &mut self,
+        id: i64,
+        result: Result<Vec<shared::dto::webhooks::WebhookDeliveryInfo>, String>,
+    ) {
+        let mut state = self.state.write();
+        let webhooks = &mut state.webhooks;
+        // Another webhook was opened while this log was loading
+        if webhooks.selected != Some(id) {
+            return;
+        }
+        webhooks.deliveries_loading = false;
+        match result {
+            Ok(deliveries) => webhooks.deliveries = deliveries,
+            Err(err) => {
+                tracing::warn!(error = %err, id, "Failed to fetch webhook deliveries");
+                webhooks.error = Some(err);
+            }
+        }
+    }
+
+    fn handle_webhook_test_result(&mut self, id: i64, result: Result<shared::dto::webhooks::WebhookDeliveryInfo, String>) {
+        let mut state = self.state.write();
+        state.webhooks.testing = None;
+        let notification = match result {
+            Ok(delivery) => {
+                tracing::info!(event = "WebhookTestResult", id, delivered = delivery.delivered, "Test event sent");
+                let notification = if delivery.delivered {
+                    ("success".to_string(), "Test event delivered".to_string())
+                } else {
+                    let reason = delivery.error.clone().unwrap_or_else(|| "no answer".to_string());
+                    ("warning".to_string(), format!("Test event not delivered: {}", reason))
+                };
+                if state.webhooks.selected == Some(id) {
+                    state.webhooks.deliveries.insert(0, delivery);
+                }
+                notification
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, id, "Failed to send test event");
+                ("error".to_string(), format!("Test event failed: {}", err))
+            }
+        };
+        state.pending_notifications.push(notification);
+    }
+
+    fn handle_api_keys_result(&mut self, result: Result<Vec<shared::dto::api_keys::ApiKeyInfo>, String>) {
+        let mut state = self.state.write();
+        let api_keys = &mut state.api_keys;
+        api_keys.loading = false;
+        // Set on failure too, so the section doesn't refetch every frame
+        api_keys.loaded = true;
+        match result {
+            Ok(list) => {
+                tracing::debug!(event = "ApiKeysResult", count = list.len(), "API keys loaded");
+                api_keys.keys = list;
+                api_keys.error = None;
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to fetch API keys");
+                api_keys.error = Some(err);
+            }
+        }
+    }
+
+    fn handle_api_key_created(&mut self, result: Result<shared::dto::api_keys::CreateApiKeyResponse, String>) {
+        let mut state = self.state.write();
+        let api_keys = &mut state.api_keys;
+        api_keys.creating = false;
+        match result {
+            Ok(response) => {
+                tracing::info!(event = "ApiKeyCreated", id = response.api_key.id, "API key created");
+                api_keys.new_key = Some((response.api_key.id, response.key));
+                api_keys.keys.push(response.api_key);
+                api_keys.label_input.clear();
+                api_keys.write_input = false;
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to create API key");
+                api_keys.error = Some(err);
+            }
+        }
+    }
+
+    fn handle_api_key_revoked(&mut self, id: i64, result: Result<(), String>) {
+        let mut state = self.state.write();
+        state.api_keys.revoking = None;
+        match result {
+            Ok(()) => {
+                let api_keys = &mut state.api_keys;
+                api_keys.keys.retain(|key| key.id != id);
+                if api_keys.new_key.as_ref().is_some_and(|(key_id, _)| *key_id == id) {
+                    api_keys.new_key = None;
+                }
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, id, "Failed to revoke API key");
+                state.pending_notifications.push(("error".to_string(), format!("Revoke failed: {}", err)));
+            }
+        }
+    }
+
+    fn handle_ai_chat_messages(&mut self, conversation_id: String, messages: Vec<shared::dto::messaging::Message>) {
+        let mut state = self.state.write();
+        // Events from a subscription for a previous login
+        if state.ai_chat.conversation_id.as_ref() != Some(&conversation_id) {
+            return;
+        }
+        let current_user_id = state.current_user.as_ref().map(|u| u.id);
+        tracing::debug!(conversation_id = %conversation_id, total_messages = messages.len(), "AI chat messages updated");
+        state.ai_chat.set_messages(messages, current_user_id);
+    }
+
+    fn handle_ai_chat_chunk(&mut self, conversation_id: String, delta: String) {
+        let mut state = self.state.write();
+        if state.ai_chat.conversation_id.as_ref() != Some(&conversation_id) {
+            return;
+        }
+        state.ai_chat.push_chunk(&delta);
+        state.needs_immediate_repaint = true;
+    }
+
+    fn handle_ai_chat_stream_ended(&mut self, conversation_id: String, outcome: shared::dto::messaging::AiStreamOutcome) {
+        let mut state = self.state.write();
+        if state.ai_chat.conversation_id.as_ref() != Some(&conversation_id) {
+            return;
+        }
+        if let shared::dto::messaging::AiStreamOutcome::Failed { error } = &outcome {
+            tracing::warn!(conversation_id = %conversation_id, error = %error, "AI reply interrupted");
+        }
+        state.ai_chat.finish_stream(&outcome);
+    }
+
+    fn handle_logout_result(&mut self, result: Result<(), String>) {
+        if let Err(err) = result {
+            tracing::warn!(error = %err, "Backend logout failed");
+            self.state.write().pending_notifications.push((
+                "warning".into(),
+                format!("Logged out locally, but the server could not revoke the session: {}", err),
+            ));
+        }
+    }
+
+    fn handle_profile_update_result(&mut self, result: Result<crate::app::events::ProfileUpdate, String>) {
+        use crate::app::events::ProfileUpdate;
+
+        let mut state = self.state.write();
+        state.settings.account.pending = false;
+        let status = match result {
+            Ok(ProfileUpdate::Password(auth_response)) => {
+                tracing::info!(event = "ProfileUpdateResult", "Password changed");
+                // The old token was revoked along with every other session
+                state.auth_token = Some(auth_response.token);
+                let form = &mut state.settings.account;
+                form.current_password.clear();
+                form.new_password.clear();
+                form.confirm_password.clear();
+                audit::record(
+                    &mut state.security.trail,
+                    AuditCategory::Auth,
+                    "Password changed; other sessions signed out",
+                    vec![],
+                );
+                (false, auth_response.message)
+            }
+            Ok(ProfileUpdate::Profile(user)) => {
+                tracing::info!(event = "ProfileUpdateResult", "Email updated");
+                state.settings.account.email = user.email.clone();
+                audit::record(&mut state.security.trail, AuditCategory::Auth, "Account email changed", vec![]);
+                (false, format!("Email updated to {}", user.email))
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Account update failed");
+                (true, err)
+            }
+        };
+        let level = if status.0 { "error" } else { "success" };
+        state.pending_notifications.push((level.to_string(), status.1.clone()));
+        state.settings.account.status = Some(status);
+    }
+
+    fn handle_session_refresh_result(&mut self, from_token: String, result: Result<shared::AuthResponse, String>) {
+        let mut state = self.state.write();
+        state.session.refreshing = false;
+        // Logged out, logged in again or changed password meanwhile
+        if state.auth_token.as_deref() != Some(from_token.as_str()) {
+            return;
+        }
+        match result {
+            Ok(auth_response) => {
+                tracing::info!(event = "SessionRefreshResult", "Session refreshed");
+                // The old token is revoked now
+                state.auth_token = Some(auth_response.token);
+                state.session.refresh_error = None;
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Session refresh failed");
+                // Warn once per failure streak, retries keep going quietly
+                if state.session.refresh_error.is_none() {
+                    state.pending_notifications.push((
+                        "warning".into(),
+                        format!(
+                            "Couldn't extend your session ({}). Log in again before it expires to keep trading.",
+                            err
+                        ),
+                    ));
+                }
+                state.session.refresh_error = Some(err);
+            }
+        }
+    }
+
+    fn handle_transaction_history_result(&mut self, result: Result<Vec<crate::app::state::TransactionItem>, String>) {
+        match result {
+            Ok(fetched) => {
+                tracing::debug!(event = "TransactionHistoryResult", count = fetched.len(), "Processing transaction history");
+                let mut state = self.state.write();
+                state.transactions = crate::app::handlers::transactions::merge_history(&state.transactions, fetched).into();
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to fetch transaction history");
+            }
+        }
+    }
+
+    fn handle_transaction_status_changed(
+        &mut self,
+        signature: String,
+        status: shared::dto::transactions::TransactionStatus,
+        error: Option<String>,
+    ) {
+        use shared::dto::transactions::TransactionStatus;
+
+        tracing::debug!(event = "TransactionStatusChanged", signature = %signature, status = status.as_str(), "Processing transaction status");
+        let mut state = self.state.write();
+        crate::app::handlers::transactions::apply_status_change(&mut state.transactions, &signature, status);
+
+        let short = &signature[..8.min(signature.len())];
+        let notification = match status {
+            TransactionStatus::Finalized => Some(("success", format!("Transaction {} finalized", short))),
+            TransactionStatus::Failed => Some((
+                "error",
+                format!("Transaction {} failed: {}", short, error.as_deref().unwrap_or("unknown error")),
+            )),
+            TransactionStatus::Expired => Some(("warning", format!("Transaction {} expired before it landed", short))),
+            _ => None,
+        };
+        if let Some((level, message)) = notification {
+            state.pending_notifications.push((level.to_string(), message));
+        }
+
+        // A landed transfer moved the balances shown on the Wallet screen
+        let tx_type = state.transactions.iter().find(|item| item.signature == signature).map(|item| item.tx_type.clone());
+        let transfer_landed =
+            status == TransactionStatus::Finalized && tx_type.as_deref() == Some(crate::app::handlers::send::TRANSFER_TX_TYPE);
+        // Stake accounts are read at confirmed commitment
+        let stake_landed = matches!(status, TransactionStatus::Confirmed | TransactionStatus::Finalized)
+            && tx_type.as_deref() == Some(crate::app::handlers::staking::STAKE_TX_TYPE);
+        drop(state);
+        if transfer_landed || stake_landed {
+            crate::app::handlers::wallet::refresh_balances(self.state.clone(), self.event_tx.clone());
+        }
+        if stake_landed {
+            crate::app::tasks::staking::load_staking(self.state.clone(), self.event_tx.clone());
+        }
+    }
+
+    fn handle_depth_result(&mut self, result: Result<shared::dto::market::DepthResponse, String>) {
+        let mut state = self.state.write();
+        state.terminal.depth_loading = false;
+        match result {
+            Ok(depth) => {
+                tracing::debug!(
+                    input = %depth.input,
+                    output = %depth.output,
+                    rungs = depth.rungs.len(),
+                    "Depth ladder loaded"
+                );
+                state.terminal.depth = Some(depth);
+                state.terminal.depth_error = None;
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to fetch depth ladder");
+                // Keep the last ladder on screen, flagged with the error
+                state.terminal.depth_error = Some(err);
+            }
+        }
+    }
+
+    fn handle_market_analytics_result(&mut self, result: Result<shared::dto::market::MarketAnalyticsResponse, String>) {
+        let mut state = self.state.write();
+        state.live_assets.analytics_loading = false;
+        match result {
+            Ok(analytics) => {
+                tracing::debug!(
+                    symbols = analytics.symbols.len(),
+                    window = analytics.window_days,
+                    "Market analytics loaded"
+                );
+                state.live_assets.analytics = Some(analytics);
+                state.live_assets.analytics_error = None;
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to fetch market analytics");
+                state.live_assets.analytics_error = Some(err);
+            }
+        }
+    }
+
+    fn handle_sparklines_result(&mut self, result: Result<shared::dto::market::CandleBatchResponse, String>) {
+        let mut state = self.state.write();
+        state.live_assets.sparklines_loading = false;
+        match result {
+            Ok(batch) => {
+                if !batch.errors.is_empty() {
+                    // Usually symbols too new to have candles yet
+                    tracing::debug!(errors = ?batch.errors, "Some mini charts have no candles");
+                }
+                crate::app::handlers::live_assets::apply_sparklines(&mut state, batch);
+            }
+            Err(err) => {
+                // The rows render without a chart; the next fetch tries again
+                tracing::warn!(error = %err, "Failed to fetch mini chart candles");
+            }
+        }
+    }
+
+    fn handle_token_unlocks_result(&mut self, result: Result<shared::TokenUnlocksResponse, String>) {
+        tracing::debug!(event = "TokenUnlocksResult", success = result.is_ok(), "Processing token unlocks");
+        let now = chrono::Utc::now().timestamp();
+        let notices = crate::app::handlers::unlocks::apply_token_unlocks(&mut self.state.write(), result, now);
+        for notice in notices {
+            self.handle_system_notice(notice);
+        }
+    }
+
+    fn handle_task_failed(&mut self, task: crate::app::tasks::guard::GuardedTask, error: String) {
+        usage::capture(UsageEvent::Error(UsageErrorCategory::Task));
+        // Reloads cancelled by the idle teardown are started again on resume
+        let idle = self.state.read().idle.power() != crate::app::idle::PowerState::Active;
+        if task.is_user_initiated() && !idle {
+            self.state
+                .write()
+                .pending_notifications
+                .push(("error".to_string(), format!("{} failed ({}), please try again", task.label(), error)));
+        } else {
+            // The next poll starts it again now that its flag is cleared
+            tracing::warn!(event = "TaskFailed", task = task.name(), error = %error, "Background task failed, retrying on next poll");
+        }
+    }
+
+    fn handle_system_notice(&mut self, notice: shared::SystemNotice) {
+        tracing::warn!(
+            event = "SystemNotice",
+            level = ?notice.level,
+            title = %notice.title,
+            message = %notice.message,
+            "Received system notice from backend"
+        );
+        let mut state = self.state.write();
+        if state.system_notices.iter().any(|n| n == &notice) {
+            return;
+        }
+        // Muting the category hides the toast, not the countdown and freeze
+        crate::app::handlers::maintenance::handle_maintenance_notice(&mut state, &notice);
+        if !state.settings.notifications.is_enabled(notice.category) {
+            tracing::debug!(category = ?notice.category, "System notice category is muted");
+            return;
+        }
+        let level = match notice.level {
+            shared::NoticeLevel::Info => "info",
+            shared::NoticeLevel::Warning => "warning",
+            shared::NoticeLevel::Critical => "error",
+        };
+        state.pending_notifications.push((level.to_string(), notice.title.clone()));
+        state.system_notices.push(notice);
+    }
+
     fn handle_loading(&mut self, msg: String) {
         tracing::debug!(event = "Loading", message = %msg, "Processing loading status");
         let mut state = self.state.write();
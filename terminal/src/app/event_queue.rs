@@ -0,0 +1,257 @@
+//! # Event Queue
+//!
+//! The channel from background tasks to the UI thread is bounded
+//! ([`capacity`], 1024 events unless `TERMINAL_EVENT_QUEUE_CAPACITY` says
+//! otherwise), so a runaway sender can't grow it without limit. What happens
+//! to an event that doesn't fit depends on what it carries:
+//!
+//! - **Price events** are coalesced. [`send_price`] never waits: a nudge
+//!   that doesn't fit is redundant with the ones already queued (the price
+//!   store holds the data), and on the receiving side [`drain`] keeps only
+//!   the newest `PriceUpdated` per symbol.
+//! - **Stream events** (status reports and the like, sent over and over by
+//!   long-running tasks) go through [`send_or_drop`], which waits up to
+//!   [`SEND_TIMEOUT`] for room and then drops the event with a warning.
+//! - **Results** of one-off requests are sent with a plain `send().await`
+//!   and wait as long as it takes: dropping a login or swap result would
+//!   leave its screen waiting forever.
+//!
+//! Dropped and coalesced events are counted for the debug overlay.
+
+use crate::app::events::AppEvent;
+use crate::app::state::PriceData;
+use async_channel::{Receiver, Sender, TrySendError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Channel capacity when none is configured
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// Longest a stream event waits for room before it is dropped
+pub const SEND_TIMEOUT: Duration = Duration::from_millis(250);
+
+static CAPACITY: Lazy<usize> = Lazy::new(|| {
+    std::env::var("TERMINAL_EVENT_QUEUE_CAPACITY")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&capacity| capacity > 0)
+        .unwrap_or(DEFAULT_CAPACITY)
+});
+
+/// Capacity of the app's event channel
+pub fn capacity() -> usize {
+    *CAPACITY
+}
+
+/// Create the app's event channel
+pub fn channel() -> (Sender<AppEvent>, Receiver<AppEvent>) {
+    async_channel::bounded(capacity())
+}
+
+/// Send a price event without waiting
+///
+/// A `PricesChanged` nudge that doesn't fit is counted as coalesced: the
+/// events ahead of it already make the UI repaint from the price store. A
+/// `PriceUpdated` carries its price, so it falls back to [`send_or_drop`].
+pub async fn send_price(tx: &Sender<AppEvent>, event: AppEvent) -> bool {
+    match tx.try_send(event) {
+        Ok(()) => true,
+        Err(TrySendError::Full(AppEvent::PricesChanged)) => {
+            crate::debug::metrics::record_events_coalesced(1);
+            true
+        }
+        Err(TrySendError::Full(event)) => send_or_drop(tx, event).await,
+        Err(TrySendError::Closed(_)) => false,
+    }
+}
+
+/// Send a stream event, dropping it if the queue stays full for [`SEND_TIMEOUT`]
+///
+/// Returns false when the event was dropped or the channel is closed.
+pub async fn send_or_drop(tx: &Sender<AppEvent>, event: AppEvent) -> bool {
+    let name = event_name(&event);
+    match tokio::time::timeout(SEND_TIMEOUT, tx.send(event)).await {
+        Ok(result) => result.is_ok(),
+        Err(_) => {
+            record_drop(&name);
+            false
+        }
+    }
+}
+
+/// Send without waiting, dropping the event with a warning if the queue is full
+///
+/// For senders that can't await (drop handlers, OS callbacks).
+pub fn try_send_or_drop(tx: &Sender<AppEvent>, event: AppEvent) -> bool {
+    match tx.try_send(event) {
+        Ok(()) => true,
+        Err(TrySendError::Full(event)) => {
+            record_drop(&event_name(&event));
+            false
+        }
+        Err(TrySendError::Closed(_)) => false,
+    }
+}
+
+fn record_drop(name: &str) {
+    let message = format!("Event queue full - dropped {}", name);
+    tracing::warn!(event = %name, capacity = capacity(), "{}", message);
+    crate::debug::record_warning(message, Some("app::event_queue".to_string()));
+    crate::debug::metrics::record_events_dropped(1);
+}
+
+/// Variant name of an event, for logs
+fn event_name(event: &AppEvent) -> String {
+    let debug = format!("{:?}", event);
+    let end = debug.find(|c: char| !c.is_alphanumeric()).unwrap_or(debug.len());
+    debug[..end].to_string()
+}
+
+/// Price updates pending in one drain, newest per symbol
+#[derive(Debug, Default)]
+pub struct CoalescedPrices {
+    prices: Vec<PriceData>,
+    index: HashMap<String, usize>,
+    coalesced: usize,
+}
+
+impl CoalescedPrices {
+    /// Add an update, replacing an older one for the same symbol
+    pub fn push(&mut self, price: PriceData) {
+        match self.index.get(&price.symbol) {
+            Some(&i) => {
+                self.prices[i] = price;
+                self.coalesced += 1;
+            }
+            None => {
+                self.index.insert(price.symbol.clone(), self.prices.len());
+                self.prices.push(price);
+            }
+        }
+    }
+
+    /// Updates replaced by a newer one for their symbol
+    pub fn coalesced(&self) -> usize {
+        self.coalesced
+    }
+
+    /// The newest update per symbol, in order of each symbol's first update
+    pub fn into_prices(self) -> Vec<PriceData> {
+        self.prices
+    }
+}
+
+/// Events taken off the channel in one tick, sorted by priority
+#[derive(Debug, Default)]
+pub struct Drained {
+    /// Events received, before coalescing
+    pub received: usize,
+    pub prices: CoalescedPrices,
+    /// Any number of `PricesChanged` nudges collapse into this
+    pub prices_changed: bool,
+    pub other: Vec<AppEvent>,
+}
+
+/// Take up to `max` events off the channel without waiting
+///
+/// The limit keeps a tick bounded while senders refill the queue as fast as
+/// it drains; the rest waits for the next frame.
+pub fn drain(rx: &Receiver<AppEvent>, max: usize) -> Drained {
+    let mut drained = Drained::default();
+    while drained.received < max {
+        let Ok(event) = rx.try_recv() else {
+            break;
+        };
+        drained.received += 1;
+        match event {
+            AppEvent::PricesChanged => {
+                if drained.prices_changed {
+                    crate::debug::metrics::record_events_coalesced(1);
+                }
+                drained.prices_changed = true;
+            }
+            AppEvent::PriceUpdated(price) => drained.prices.push(price),
+            other => drained.other.push(other),
+        }
+    }
+    crate::debug::metrics::record_events_coalesced(drained.prices.coalesced());
+    drained
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(symbol: &str, value: f64) -> PriceData {
+        PriceData {
+            symbol: symbol.to_string(),
+            price: value,
+            change_24h: 0.0,
+            previous_price: None,
+            source: Some("test".to_string()),
+            confidence: None,
+            publish_time: None,
+            sources: Default::default(),
+            divergent: false,
+            divergence_pct: None,
+        }
+    }
+
+    #[test]
+    fn test_burst_keeps_newest_price_per_symbol() {
+        let (tx, rx) = async_channel::bounded(256);
+        for i in 0..50 {
+            tx.try_send(AppEvent::PriceUpdated(price("SOL", 100.0 + i as f64))).unwrap();
+            if i % 10 == 0 {
+                tx.try_send(AppEvent::PriceUpdated(price("BONK", i as f64))).unwrap();
+                tx.try_send(AppEvent::PricesChanged).unwrap();
+            }
+        }
+        tx.try_send(AppEvent::Loading("done".to_string())).unwrap();
+
+        let drained = drain(&rx, 1024);
+        assert_eq!(drained.received, 50 + 5 + 5 + 1);
+        assert!(drained.prices_changed);
+        assert_eq!(drained.other.len(), 1);
+        assert_eq!(drained.prices.coalesced(), 49 + 4);
+
+        let prices = drained.prices.into_prices();
+        assert_eq!(prices.len(), 2);
+        assert_eq!((prices[0].symbol.as_str(), prices[0].price), ("SOL", 149.0));
+        assert_eq!((prices[1].symbol.as_str(), prices[1].price), ("BONK", 40.0));
+    }
+
+    #[test]
+    fn test_drain_is_bounded() {
+        let (tx, rx) = async_channel::bounded(64);
+        for i in 0..64 {
+            tx.try_send(AppEvent::PriceUpdated(price("SOL", i as f64))).unwrap();
+        }
+
+        let first = drain(&rx, 40);
+        assert_eq!(first.received, 40);
+        assert_eq!(first.prices.into_prices()[0].price, 39.0);
+
+        let rest = drain(&rx, 40);
+        assert_eq!(rest.received, 24);
+        assert_eq!(rest.prices.into_prices()[0].price, 63.0);
+    }
+
+    #[tokio::test]
+    async fn test_overflow_policy() {
+        let (tx, rx) = async_channel::bounded(2);
+        assert!(send_or_drop(&tx, AppEvent::Loading("a".to_string())).await);
+        assert!(send_price(&tx, AppEvent::PricesChanged).await);
+
+        // Full: a nudge is coalesced away, a stream event is dropped after the timeout
+        assert!(send_price(&tx, AppEvent::PricesChanged).await);
+        assert!(!send_or_drop(&tx, AppEvent::Loading("b".to_string())).await);
+        assert!(!try_send_or_drop(&tx, AppEvent::Loading("c".to_string())));
+        assert_eq!(rx.len(), 2);
+
+        // Room again
+        rx.try_recv().unwrap();
+        assert!(send_or_drop(&tx, AppEvent::Loading("d".to_string())).await);
+    }
+}
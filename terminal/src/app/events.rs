@@ -2,7 +2,7 @@
 //!
 //! Event types for async task communication between background tasks and the main thread.
 
-use crate::app::state::{PriceData, SwapQuote, TokenInfo, SwapHistoryItem};
+use crate::app::state::{PriceData, SwapQuote, TokenInfo, SwapHistoryFilters, SwapHistoryItem, TokenBalance};
 
 /// Async task results sent to main thread
 #[derive(Debug, Clone)]
@@ -11,23 +11,170 @@ pub enum AppEvent {
     LoginResult(Result<shared::AuthResponse, String>),
     /// Signup completed
     SignupResult(Result<shared::AuthResponse, String>),
+    /// Backend token revocation finished (the local session is already gone)
+    LogoutResult(Result<(), String>),
+    /// Password change or profile update finished
+    ProfileUpdateResult(Result<ProfileUpdate, String>),
+    /// Session refresh finished; `from_token` is the token that was refreshed
+    SessionRefreshResult {
+        from_token: String,
+        result: Result<shared::AuthResponse, String>,
+    },
     /// Wallet connection status checked
     WalletStatusChecked(Result<shared::AuthResponse, String>),
     /// Prices updated (batch)
     PricesUpdated(Vec<PriceData>),
     /// Single price updated (from WebSocket stream)
     PriceUpdated(PriceData),
-    /// Swap quote received
-    SwapQuoteResult(Result<SwapQuote, String>),
+    /// Price store was written directly; only a repaint nudge
+    PricesChanged,
+    /// Swap quote received, tagged with the request generation it answers
+    SwapQuoteResult(u64, Result<SwapQuote, String>),
+    /// Streamed quote received over the price stream
+    QuoteUpdated(shared::dto::market::QuoteUpdate),
+    /// Quote for the `index`th swap of the rebalance proposal `generation`
+    RebalanceQuoteResult {
+        generation: u64,
+        index: usize,
+        result: Result<SwapQuote, String>,
+    },
+    /// Swap transaction built and simulated, ready for the confirmation dialog
+    SwapPrepared(Result<Box<crate::app::state::SwapConfirmation>, String>),
     /// Token list received
     TokenListResult(Result<Vec<TokenInfo>, String>),
-    /// Swap history received
-    SwapHistoryResult(Result<Vec<SwapHistoryItem>, String>),
+    /// Backend price stream symbol list received
+    StreamedSymbolsResult(Result<Vec<String>, String>),
+    /// Swap history page received, tagged with the filters it was fetched for
+    SwapHistoryResult(SwapHistoryFilters, Result<SwapHistoryPage, String>),
+    /// Swaps written so far by the running history export
+    SwapHistoryExportProgress(u64),
+    /// History export done: the file and its row count
+    SwapHistoryExportFinished(Result<(std::path::PathBuf, u64), String>),
+    /// Wallet SPL token balances refreshed
+    TokenBalancesUpdated(Vec<TokenBalance>),
+    /// SOL balance of the wallet at `address` re-read
+    SolBalanceUpdated { address: String, balance: f64 },
+    /// Transfer from the Send panel built and priced, ready for the confirmation dialog
+    TransferPrepared(Result<Box<crate::app::state::TransferConfirmation>, String>),
+    /// Transfer submitted: its signature, or why it wasn't sent
+    TransferResult(Result<String, String>),
+    /// Devnet airdrop confirmed: its signature, or why it failed
+    AirdropResult(Result<String, String>),
+    /// Stake accounts of the wallet at `address` loaded
+    StakingInfoResult { address: String, result: Result<shared::dto::staking::StakingInfo, String> },
+    /// Staking rewards of the wallet at `address` loaded
+    StakingRewardsResult { address: String, result: Result<shared::dto::staking::StakingRewardsResponse, String> },
+    /// Staking transaction submitted: its signature, or why it wasn't sent
+    StakeActionResult { action: crate::app::handlers::staking::StakeAction, result: Result<String, String> },
     /// Candles (OHLC data) received
     CandlesResult(Result<Vec<shared::dto::OHLC>, String>),
+    /// Streamed candle of `symbol` at `timeframe`; `is_closed` is `false`
+    /// while it is still forming
+    CandleUpdated(String, shared::dto::market::Timeframe, shared::dto::OHLC, bool),
+    /// Token detail loaded for Live Assets, by hover prefetch or by opening it
+    TokenDetailResult {
+        symbol: String,
+        prefetch: bool,
+        result: Result<crate::app::preload::TokenDetail, String>,
+    },
+    /// A tracked transaction moved on in its confirmation; `error` is set
+    /// when it failed
+    TransactionStatusChanged {
+        signature: String,
+        status: shared::dto::transactions::TransactionStatus,
+        error: Option<String>,
+    },
+    /// Wallet transaction history received
+    TransactionHistoryResult(Result<Vec<crate::app::state::TransactionItem>, String>),
+    /// Depth ladder received
+    DepthResult(Result<shared::dto::market::DepthResponse, String>),
+    /// Volatility and correlation analytics received
+    MarketAnalyticsResult(Result<shared::dto::market::MarketAnalyticsResponse, String>),
+    /// Candles for the Live Assets mini charts received
+    SparklinesResult(Result<shared::dto::market::CandleBatchResponse, String>),
+    /// Backend transaction lookup for a search query finished
+    SearchRemoteResult(String, Result<Vec<crate::app::search::SearchResult>, String>),
+    /// Trade import validated or committed
+    TradeImportResult(Result<shared::dto::trades::TradeImportResponse, String>),
+    /// Trade statistics received
+    TradeStatsResult(Result<shared::dto::trades::TradeStatsResponse, String>),
+    /// Share link created
+    ShareLinkResult(Result<shared::dto::share::CreateShareResponse, String>),
+    /// On-ramp providers for the "Buy SOL" dialog received
+    OnRampProvidersResult(Result<shared::dto::onramp::OnRampProvidersResponse, String>),
+    /// Backend answered "Delete my data" in Settings > Privacy
+    UsageDataDeleted(Result<(), String>),
+    /// Webhook list received
+    WebhooksResult(Result<Vec<shared::dto::webhooks::WebhookInfo>, String>),
+    /// Webhook registered
+    WebhookCreated(Result<shared::dto::webhooks::CreateWebhookResponse, String>),
+    /// Webhook deleted
+    WebhookDeleted(i64, Result<(), String>),
+    /// Delivery log of a webhook received
+    WebhookDeliveriesResult(i64, Result<Vec<shared::dto::webhooks::WebhookDeliveryInfo>, String>),
+    /// Test event sent; the delivery says whether the receiver accepted it
+    WebhookTestResult(i64, Result<shared::dto::webhooks::WebhookDeliveryInfo, String>),
+    /// API key list received
+    ApiKeysResult(Result<Vec<shared::dto::api_keys::ApiKeyInfo>, String>),
+    /// API key created; the response carries the key
+    ApiKeyCreated(Result<shared::dto::api_keys::CreateApiKeyResponse, String>),
+    /// API key revoked
+    ApiKeyRevoked(i64, Result<(), String>),
+    /// AI chat subscription delivered the conversation's messages
+    AIChatMessages(String, Vec<shared::dto::messaging::Message>),
+    /// Next piece of the AI reply being streamed (conversation id, delta)
+    AIChatChunk(String, String),
+    /// The AI reply stream ended, or the subscription carrying it dropped
+    AIChatStreamEnded(String, shared::dto::messaging::AiStreamOutcome),
+    /// Request from a later launch (or our own command line) to open a link
+    InstanceRequest(crate::services::protocol_handler::Request),
+    /// API client moved to another backend server
+    ServerSwitched(crate::services::api::failover::ServerSwitch),
+    /// API request failed in a way the whole app reacts to: the session
+    /// token was rejected, or the backend stopped answering in time
+    ApiFailure(crate::services::api::ApiError),
+    /// Backend health report received
+    HealthResult(Result<shared::dto::system::HealthResponse, String>),
+    /// Upcoming token unlocks received
+    TokenUnlocksResult(Result<shared::TokenUnlocksResponse, String>),
+    /// Wallet or RPC failure in a flow that may get a recovery card
+    RecoverableError {
+        flow: crate::app::recovery::RecoveryFlow,
+        error: String,
+    },
+    /// Guarded task panicked or was cancelled; its in-progress flags are
+    /// already reset
+    TaskFailed(crate::app::tasks::guard::GuardedTask, String),
     /// Loading state
     Loading(String),
+    /// System notice pushed by the backend
+    SystemNotice(shared::SystemNotice),
     /// WebSocket status update
     WebSocketStatusUpdate(crate::app::WebSocketStatus),
+    /// Price stream connection moved to another state; `attempts` counts
+    /// failures since the last connect and `retry_in` is the backoff before
+    /// the next one
+    WebSocketStateChanged {
+        state: crate::app::WebSocketState,
+        attempts: u64,
+        retry_in: Option<std::time::Duration>,
+        error: Option<String>,
+    },
 }
 
+/// Successful account update from the Settings screen
+#[derive(Debug, Clone)]
+pub enum ProfileUpdate {
+    /// Password changed; the other sessions were revoked and this is the token to keep
+    Password(shared::AuthResponse),
+    /// Email updated
+    Profile(shared::UserInfo),
+}
+
+/// One page of swap history
+#[derive(Debug, Clone)]
+pub struct SwapHistoryPage {
+    pub items: Vec<SwapHistoryItem>,
+    /// Swaps matching the filters across all pages
+    pub total: usize,
+}
@@ -0,0 +1,214 @@
+//! # Feature Gates
+//!
+//! Maps the backend health report to the terminal features that depend on each
+//! subsystem, so the UI can degrade immediately instead of spinning through
+//! request timeouts.
+//!
+//! ## Gate Table
+//!
+//! | Subsystem    | Feature         | When down   |
+//! |--------------|-----------------|-------------|
+//! | `database`   | `Account`       | unavailable |
+//! | `solana_rpc` | `SwapExecution` | unavailable |
+//! | `jupiter`    | `SwapQuotes`    | unavailable |
+//! | `jupiter`    | `SwapExecution` | unavailable |
+//! | `jupiter`    | `LivePrices`    | degraded    |
+//! | `pyth`       | `OraclePrices`  | degraded    |
+//! | `websocket`  | `LivePrices`    | degraded    |
+//! | `websocket`  | `Charts`        | degraded    |
+//!
+//! A subsystem the backend reports as `degraded` (rather than `down`) never
+//! makes a feature unavailable, and when several rows hit the same feature the
+//! worst gate wins.
+
+use shared::dto::system::{HealthResponse, HealthStatus};
+use std::collections::HashMap;
+
+/// Terminal feature that depends on a backend subsystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// Login, profile and history
+    Account,
+    /// Swap quote requests
+    SwapQuotes,
+    /// Building and submitting swaps
+    SwapExecution,
+    /// Jupiter prices over the price stream
+    LivePrices,
+    /// Pyth oracle prices
+    OraclePrices,
+    /// Live candle updates
+    Charts,
+}
+
+impl Feature {
+    /// Feature whose gate applies to prices from `source`
+    pub fn for_price_source(source: &str) -> Self {
+        if source.eq_ignore_ascii_case("pyth") {
+            Feature::OraclePrices
+        } else {
+            Feature::LivePrices
+        }
+    }
+}
+
+/// Whether a feature can be used right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Gate {
+    #[default]
+    Available,
+    /// Usable, but results may be stale or slow
+    Degraded(&'static str),
+    /// Don't attempt it; the reason is shown instead
+    Unavailable(&'static str),
+}
+
+impl Gate {
+    /// Feature can be attempted (available or degraded)
+    pub fn is_usable(&self) -> bool {
+        !matches!(self, Gate::Unavailable(_))
+    }
+
+    /// User-facing reason when not fully available
+    pub fn reason(&self) -> Option<&'static str> {
+        match self {
+            Gate::Available => None,
+            Gate::Degraded(reason) | Gate::Unavailable(reason) => Some(reason),
+        }
+    }
+
+    fn severity(&self) -> u8 {
+        match self {
+            Gate::Available => 0,
+            Gate::Degraded(_) => 1,
+            Gate::Unavailable(_) => 2,
+        }
+    }
+}
+
+/// What a failing subsystem does to a feature
+#[derive(Debug, Clone, Copy)]
+enum Effect {
+    Degrade,
+    Disable,
+}
+
+/// Subsystem, affected feature, effect when the subsystem is down, reason
+const GATE_TABLE: &[(&str, Feature, Effect, &str)] = &[
+    ("database", Feature::Account, Effect::Disable, "Account features unavailable - backend database is down"),
+    ("solana_rpc", Feature::SwapExecution, Effect::Disable, "Swaps unavailable - Solana RPC is not responding"),
+    ("jupiter", Feature::SwapQuotes, Effect::Disable, "Quotes unavailable - Jupiter is not responding"),
+    ("jupiter", Feature::SwapExecution, Effect::Disable, "Swaps unavailable - Jupiter is not responding"),
+    ("jupiter", Feature::LivePrices, Effect::Degrade, "Jupiter prices may be stale"),
+    ("pyth", Feature::OraclePrices, Effect::Degrade, "Pyth oracle degraded - prices may be stale"),
+    ("websocket", Feature::LivePrices, Effect::Degrade, "Price stream stalled - prices may be stale"),
+    ("websocket", Feature::Charts, Effect::Degrade, "Price stream stalled - candles are not updating"),
+];
+
+/// Feature gates derived from one health report
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeatureGates {
+    gates: HashMap<Feature, Gate>,
+}
+
+impl FeatureGates {
+    /// Apply [`GATE_TABLE`] to a health report
+    pub fn from_report(report: &HealthResponse) -> Self {
+        let mut gates: HashMap<Feature, Gate> = HashMap::new();
+
+        for &(subsystem, feature, effect, reason) in GATE_TABLE {
+            let Some(health) = report.subsystem(subsystem) else {
+                continue;
+            };
+            let gate = match (health.status, effect) {
+                (HealthStatus::Ok, _) => continue,
+                (HealthStatus::Down, Effect::Disable) => Gate::Unavailable(reason),
+                _ => Gate::Degraded(reason),
+            };
+            let entry = gates.entry(feature).or_default();
+            if gate.severity() > entry.severity() {
+                *entry = gate;
+            }
+        }
+
+        Self { gates }
+    }
+
+    /// Gate for a feature; features without a failing dependency are available
+    pub fn get(&self, feature: Feature) -> Gate {
+        self.gates.get(&feature).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::dto::system::SubsystemHealth;
+
+    fn report(statuses: &[(&str, HealthStatus)]) -> HealthResponse {
+        HealthResponse {
+            status: HealthStatus::Ok,
+            uptime_secs: 0,
+            subsystems: statuses
+                .iter()
+                .map(|(name, status)| SubsystemHealth {
+                    name: name.to_string(),
+                    status: *status,
+                    latency_ms: 0,
+                    error: None,
+                })
+                .collect(),
+            caches: Vec::new(),
+            timestamp: 0,
+            wallet_path: None,
+        }
+    }
+
+    #[test]
+    fn test_healthy_report_gates_nothing() {
+        let gates = FeatureGates::from_report(&report(&[
+            ("database", HealthStatus::Ok),
+            ("jupiter", HealthStatus::Ok),
+            ("pyth", HealthStatus::Ok),
+        ]));
+        assert_eq!(gates, FeatureGates::default());
+        assert_eq!(gates.get(Feature::SwapQuotes), Gate::Available);
+    }
+
+    #[test]
+    fn test_jupiter_down_disables_quotes() {
+        let gates = FeatureGates::from_report(&report(&[("jupiter", HealthStatus::Down)]));
+        assert!(!gates.get(Feature::SwapQuotes).is_usable());
+        assert!(!gates.get(Feature::SwapExecution).is_usable());
+        assert!(matches!(gates.get(Feature::LivePrices), Gate::Degraded(_)));
+        assert_eq!(gates.get(Feature::OraclePrices), Gate::Available);
+    }
+
+    #[test]
+    fn test_pyth_down_only_degrades_oracle_prices() {
+        let gates = FeatureGates::from_report(&report(&[("pyth", HealthStatus::Down)]));
+        assert!(matches!(gates.get(Feature::OraclePrices), Gate::Degraded(_)));
+        assert_eq!(gates.get(Feature::for_price_source("pyth")), gates.get(Feature::OraclePrices));
+        assert_eq!(gates.get(Feature::for_price_source("jupiter")), Gate::Available);
+    }
+
+    #[test]
+    fn test_degraded_subsystem_never_disables() {
+        let gates = FeatureGates::from_report(&report(&[("jupiter", HealthStatus::Degraded)]));
+        assert!(matches!(gates.get(Feature::SwapQuotes), Gate::Degraded(_)));
+    }
+
+    #[test]
+    fn test_worst_gate_wins() {
+        let gates = FeatureGates::from_report(&report(&[
+            ("websocket", HealthStatus::Down),
+            ("solana_rpc", HealthStatus::Down),
+            ("jupiter", HealthStatus::Down),
+        ]));
+        assert_eq!(
+            gates.get(Feature::SwapExecution).reason(),
+            Some("Swaps unavailable - Solana RPC is not responding")
+        );
+        assert!(matches!(gates.get(Feature::LivePrices), Gate::Degraded(_)));
+    }
+}
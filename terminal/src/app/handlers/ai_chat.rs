@@ -0,0 +1,140 @@
+//! # AI Chat Handlers
+//!
+//! Handlers for the AI Assistant screen: subscribing to the bot conversation,
+//! sending messages and cancelling a reply while it streams in.
+//!
+//! Subscription updates are turned into [`AppEvent::AIChatMessages`],
+//! [`AppEvent::AIChatChunk`] and [`AppEvent::AIChatStreamEnded`] events and
+//! applied to [`crate::app::state::AIChatState`] on the UI thread.
+
+use crate::app::events::AppEvent;
+use crate::app::state::AppState;
+use crate::app::usage;
+use crate::services::braid_client::{BraidClient, BraidEvent};
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::messaging::{AiStreamEvent, AiStreamOutcome, Message};
+use shared::dto::telemetry::{UsageEvent, UsageFeature};
+use std::sync::Arc;
+
+/// Start the AI conversation subscription the first time the screen is shown
+///
+/// The bot is user 0, so the conversation id is always `"0:{user_id}"`.
+///
+/// Internal handler function - use [`crate::app::App::handle_ai_chat_connect`] instead.
+pub(crate) fn handle_ai_chat_connect(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (conversation_id, token) = {
+        let mut state = state.write();
+        if state.ai_chat.conversation_id.is_some() {
+            return;
+        }
+        let (Some(user_id), Some(token)) = (state.current_user.as_ref().map(|u| u.id), state.auth_token.clone()) else {
+            return;
+        };
+        let conversation_id = format!("0:{}", user_id);
+        state.ai_chat.conversation_id = Some(conversation_id.clone());
+        (conversation_id, token)
+    };
+
+    tokio::spawn(async move {
+        let mut braid_client = BraidClient::new(conversation_id.clone(), token);
+        let mut rx = match braid_client.subscribe().await {
+            Ok(rx) => rx,
+            Err(e) => {
+                tracing::error!(conversation_id = %conversation_id, error = %e, "Failed to subscribe to AI conversation");
+                state.write().ai_chat.subscribed = false;
+                return;
+            }
+        };
+        state.write().ai_chat.subscribed = true;
+        tracing::info!(conversation_id = %conversation_id, "AI chat subscription established");
+
+        while let Some(event) = rx.recv().await {
+            let event = match event {
+                BraidEvent::Messages(messages, _version) => AppEvent::AIChatMessages(conversation_id.clone(), messages),
+                BraidEvent::AiStream(AiStreamEvent::Chunk { delta }) => AppEvent::AIChatChunk(conversation_id.clone(), delta),
+                BraidEvent::AiStream(AiStreamEvent::End { outcome }) => {
+                    AppEvent::AIChatStreamEnded(conversation_id.clone(), outcome)
+                }
+                // The bot conversation has no receipts
+                BraidEvent::Receipt(_) => continue,
+            };
+            if event_tx.send(event).await.is_err() {
+                return;
+            }
+        }
+
+        tracing::warn!(conversation_id = %conversation_id, "AI chat subscription ended");
+        state.write().ai_chat.subscribed = false;
+        // A reply streaming in when the connection dropped will never get its end event
+        let outcome = AiStreamOutcome::Failed { error: "connection lost".to_string() };
+        let _ = event_tx.send(AppEvent::AIChatStreamEnded(conversation_id, outcome)).await;
+    });
+}
+
+/// Send a message to the AI bot
+///
+/// Internal handler function - use [`crate::app::App::handle_ai_chat_send`] instead.
+pub(crate) fn handle_ai_chat_send(state: Arc<RwLock<AppState>>, text: String) {
+    let (conversation_id, token, message) = {
+        let mut state = state.write();
+        let (Some(conversation_id), Some(token)) = (state.ai_chat.conversation_id.clone(), state.auth_token.clone())
+        else {
+            tracing::warn!("Cannot send AI chat message - not connected");
+            return;
+        };
+        let (author, author_id) = state
+            .current_user
+            .as_ref()
+            .map(|u| (u.username.clone(), u.id))
+            .unwrap_or_else(|| ("You".to_string(), 0));
+        state.ai_chat.ai_typing = true;
+        // Leftovers of a cancelled reply must not swallow the next one
+        state.ai_chat.streaming = None;
+        state.ai_chat.message_input.clear();
+        (conversation_id, token, Message::new(text, author, author_id))
+    };
+    usage::capture(UsageEvent::Feature(UsageFeature::AiChat));
+
+    tokio::spawn(async move {
+        let mut braid_client = BraidClient::new(conversation_id.clone(), token);
+        if let Err(e) = braid_client.send_message(message).await {
+            tracing::error!(conversation_id = %conversation_id, error = %e, "Failed to send message to AI bot");
+            let mut state = state.write();
+            state.ai_chat.ai_typing = false;
+            state.pending_notifications.push(("error".to_string(), format!("Failed to send message: {}", e)));
+        }
+    });
+}
+
+/// Handle Cancel while the AI is replying
+///
+/// The text received so far stays in the conversation, marked as cancelled,
+/// and the backend is told to stop generating.
+///
+/// Internal handler function - use [`crate::app::App::handle_ai_chat_cancel`] instead.
+pub(crate) fn handle_ai_chat_cancel(state: Arc<RwLock<AppState>>) {
+    let (conversation_id, token, api_client) = {
+        let mut state = state.write();
+        if !state.ai_chat.cancel_stream() {
+            return;
+        }
+        let (Some(conversation_id), Some(token), Some(api_client)) = (
+            state.ai_chat.conversation_id.clone(),
+            state.auth_token.clone(),
+            state.api_client.clone(),
+        ) else {
+            return;
+        };
+        (conversation_id, token, api_client)
+    };
+
+    tokio::spawn(async move {
+        match api_client.cancel_ai_reply(&token, &conversation_id).await {
+            Ok(true) => tracing::info!(conversation_id = %conversation_id, "AI reply cancelled"),
+            // Finished before the request got there
+            Ok(false) => tracing::debug!(conversation_id = %conversation_id, "No AI reply to cancel"),
+            Err(e) => tracing::warn!(conversation_id = %conversation_id, error = %e, "Failed to cancel AI reply"),
+        }
+    });
+}
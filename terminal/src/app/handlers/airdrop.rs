@@ -0,0 +1,154 @@
+//! # Airdrop Handlers
+//!
+//! On devnet the Wallet screen offers an airdrop of 1 SOL from the cluster's
+//! faucet, so a freshly generated wallet can pay fees without the Solana
+//! CLI. [`request_airdrop`](crate::app::tasks::airdrop::request_airdrop)
+//! asks for it and waits until it is confirmed; the balances are then
+//! re-read, and the airdrop is listed with the other transactions.
+//!
+//! The faucet limits how often a wallet or IP may ask. When it refuses, the
+//! button stays disabled for [`RATE_LIMIT_COOLDOWN`].
+
+use crate::app::state::AppState;
+use crate::core::config::SolanaNetwork;
+use crate::services::token_transfer::{format_token_amount, SOL_DECIMALS};
+use std::time::{Duration, Instant};
+
+/// Lamports asked for per airdrop (1 SOL)
+pub const AIRDROP_LAMPORTS: u64 = 1_000_000_000;
+
+/// Activity feed type of an airdrop
+pub const AIRDROP_TX_TYPE: &str = "Airdrop";
+
+/// How long the button stays disabled after the faucet rate-limited a request
+pub const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(10 * 60);
+
+/// Why the airdrop button is disabled, or `None` if it can be pressed
+pub fn airdrop_blocked(state: &AppState, now: Instant) -> Option<String> {
+    if state.config.network != SolanaNetwork::Devnet {
+        return Some("Airdrops are only available on devnet".to_string());
+    }
+    if state.wallet.is_none() {
+        return Some("Connect a wallet first".to_string());
+    }
+    if state.airdrop.requesting {
+        return Some("Waiting for the airdrop to confirm".to_string());
+    }
+    let remaining = state.airdrop.cooldown_until.and_then(|until| until.checked_duration_since(now));
+    if let Some(remaining) = remaining.filter(|remaining| !remaining.is_zero()) {
+        return Some(format!("The faucet is rate limiting airdrops; try again in {}", format_wait(remaining)));
+    }
+    None
+}
+
+/// Readable message for a failed airdrop, and whether the faucet rate-limited it
+///
+/// The public faucet answers HTTP 429, or an RPC error saying the airdrop
+/// limit was reached or the faucet ran dry.
+pub fn describe_airdrop_error(error: &str) -> (String, bool) {
+    let lower = error.to_ascii_lowercase();
+    let rate_limited = ["429", "too many requests", "rate limit", "airdrop limit", "run dry"]
+        .iter()
+        .any(|marker| lower.contains(marker));
+    if rate_limited {
+        let message = "The devnet faucet refused the airdrop: too many requests from this wallet or network. \
+                       Try again later, or use https://faucet.solana.com";
+        (message.to_string(), true)
+    } else {
+        (format!("Airdrop failed: {}", error), false)
+    }
+}
+
+/// Show the outcome of an airdrop
+///
+/// Internal handler function - called from the event handler on `AppEvent::AirdropResult`.
+pub(crate) fn apply_airdrop_result(state: &mut AppState, result: Result<String, String>, now: Instant) {
+    state.airdrop.requesting = false;
+    let notification = match result {
+        Ok(signature) => {
+            state.airdrop.cooldown_until = None;
+            let amount = format_token_amount(AIRDROP_LAMPORTS, SOL_DECIMALS);
+            ("success", format!("Airdrop of {} SOL confirmed: {}", amount, shared::format_address(&signature, 8, 8)))
+        }
+        Err(error) => {
+            let (message, rate_limited) = describe_airdrop_error(&error);
+            if rate_limited {
+                state.airdrop.cooldown_until = Some(now + RATE_LIMIT_COOLDOWN);
+            }
+            ("error", message)
+        }
+    };
+    state.pending_notifications.push((notification.0.to_string(), notification.1));
+}
+
+/// "4 min", or "30 s" under a minute
+fn format_wait(wait: Duration) -> String {
+    let secs = wait.as_secs();
+    if secs >= 60 {
+        format!("{} min", secs.div_ceil(60))
+    } else {
+        format!("{} s", secs.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::WalletState;
+
+    fn devnet_state() -> AppState {
+        let mut state = crate::app::App::new().state.read().clone();
+        state.config.network = SolanaNetwork::Devnet;
+        state.wallet = Some(WalletState {
+            address: "8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL".to_string(),
+            sol_balance: 0.0,
+            token_balances: Vec::new(),
+        });
+        state
+    }
+
+    #[test]
+    fn test_airdrop_only_on_devnet_with_a_wallet() {
+        let now = Instant::now();
+        let mut state = devnet_state();
+        assert_eq!(airdrop_blocked(&state, now), None);
+
+        state.config.network = SolanaNetwork::Mainnet;
+        assert!(airdrop_blocked(&state, now).unwrap().contains("devnet"));
+
+        state.config.network = SolanaNetwork::Devnet;
+        state.wallet = None;
+        assert!(airdrop_blocked(&state, now).is_some());
+    }
+
+    #[test]
+    fn test_rate_limit_starts_a_cooldown() {
+        let now = Instant::now();
+        let mut state = devnet_state();
+        state.airdrop.requesting = true;
+
+        let error = "RPC error: HTTP status client error (429 Too Many Requests) for url (https://api.devnet.solana.com/)";
+        apply_airdrop_result(&mut state, Err(error.to_string()), now);
+        assert!(!state.airdrop.requesting);
+        assert!(state.pending_notifications.last().unwrap().1.contains("faucet"));
+        assert!(airdrop_blocked(&state, now + Duration::from_secs(60)).unwrap().contains("9 min"));
+        assert_eq!(airdrop_blocked(&state, now + RATE_LIMIT_COOLDOWN), None);
+
+        // Other failures can be retried right away
+        state.airdrop.cooldown_until = None;
+        apply_airdrop_result(&mut state, Err("RPC error: connection refused".to_string()), now);
+        assert_eq!(airdrop_blocked(&state, now), None);
+    }
+
+    #[test]
+    fn test_describe_airdrop_error() {
+        let (_, rate_limited) = describe_airdrop_error(
+            "RPC error: RPC response error -32600: airdrop request failed. This can happen when the rate limit is reached.",
+        );
+        assert!(rate_limited);
+        assert!(describe_airdrop_error("You've either reached your airdrop limit today or the airdrop faucet has run dry").1);
+        let (message, rate_limited) = describe_airdrop_error("Airdrop not confirmed");
+        assert!(!rate_limited);
+        assert_eq!(message, "Airdrop failed: Airdrop not confirmed");
+    }
+}
@@ -0,0 +1,94 @@
+//! # Chart Annotation Handlers
+//!
+//! Applies the chart's drawing toolbar and context menu actions, and checks
+//! the alerts derived from drawings on every price update.
+//!
+//! Fired alerts go through the usual toast notifications. Drawings are saved
+//! whenever they change, including when a one-shot alert disarms, so a
+//! restart doesn't fire it again.
+
+use crate::app::state::AppState;
+use crate::core::store::{store, Document};
+use crate::ui::chart::annotations::{ChartAction, ChartAnnotations, DrawingTool};
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Load chart drawings and their alerts
+pub fn load_annotations() -> ChartAnnotations {
+    store().settings().load_or_default(Document::Annotations)
+}
+
+fn save_annotations(annotations: &ChartAnnotations) {
+    if let Err(e) = store().settings().save(Document::Annotations, annotations) {
+        tracing::error!("Failed to save chart annotations: {}", e);
+    }
+}
+
+/// Apply a drawing tool pick, chart click or context menu choice
+///
+/// Internal handler function - use [`crate::app::App::handle_chart_action`] instead.
+pub(crate) fn handle_chart_action(state: Arc<RwLock<AppState>>, action: ChartAction) {
+    let annotations = {
+        let mut state = state.write();
+        let terminal = &mut state.terminal;
+        match action {
+            ChartAction::SelectTool(tool) => {
+                terminal.chart_tool = tool;
+                return;
+            }
+            ChartAction::Click { symbol, point } => {
+                let tool = terminal.chart_tool;
+                terminal.chart_tool = tool.click(&mut terminal.chart_annotations, symbol, point);
+                if tool == DrawingTool::Pointer || terminal.chart_tool != DrawingTool::Pointer {
+                    // Nothing drawn yet
+                    return;
+                }
+            }
+            ChartAction::SetAlert { id, rearm } => terminal.chart_annotations.set_alert(id, rearm),
+            ChartAction::Delete(id) => {
+                terminal.chart_annotations.remove(id);
+                if terminal.chart_tool == DrawingTool::Move(id) {
+                    terminal.chart_tool = DrawingTool::Pointer;
+                }
+            }
+        }
+        terminal.chart_annotations.clone()
+    };
+    save_annotations(&annotations);
+}
+
+/// Check drawing alerts against the latest prices, queueing a notification
+/// for each one that fires
+pub(crate) fn check_annotation_alerts(state: &mut AppState, now: i64) {
+    let mut symbols: Vec<String> = state
+        .terminal
+        .chart_annotations
+        .items
+        .iter()
+        .filter(|annotation| annotation.alert.is_some())
+        .map(|annotation| annotation.symbol.clone())
+        .collect();
+    // Each symbol's alerts must see a price once per update
+    symbols.sort();
+    symbols.dedup();
+    if symbols.is_empty() {
+        return;
+    }
+
+    let prices = state.terminal.prices.load();
+    let mut fired = Vec::new();
+    for symbol in &symbols {
+        if let Some(price) = prices.get(symbol) {
+            fired.extend(state.terminal.chart_annotations.check(symbol, price.price, now));
+        }
+    }
+    if fired.is_empty() {
+        return;
+    }
+
+    for firing in &fired {
+        tracing::info!(symbol = %firing.symbol, annotation = firing.annotation_id, "Chart alert fired");
+        state.pending_notifications.push(("warning".to_string(), firing.message()));
+    }
+    save_annotations(&state.terminal.chart_annotations);
+}
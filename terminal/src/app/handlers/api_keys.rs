@@ -0,0 +1,129 @@
+//! # API Key Handlers
+//!
+//! Handlers for Settings > API Keys: creating keys for scripts and revoking
+//! them. The key itself is only shown once, right after it is created.
+
+use crate::app::events::AppEvent;
+use crate::app::state::{ApiKeysState, AppState};
+use crate::app::tasks::guard::{spawn_guarded, GuardedTask};
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::api_keys::{ApiKeyScope, CreateApiKeyRequest, MAX_API_KEY_LABEL_LEN};
+use std::sync::Arc;
+
+/// Build a create request from the Settings form
+pub fn parse_api_key_form(form: &ApiKeysState) -> Result<CreateApiKeyRequest, String> {
+    let label = form.label_input.trim();
+    if label.is_empty() {
+        return Err("Give the key a label".to_string());
+    }
+    if label.chars().count() > MAX_API_KEY_LABEL_LEN {
+        return Err(format!("Key labels are at most {} characters", MAX_API_KEY_LABEL_LEN));
+    }
+
+    let scopes = if form.write_input { vec![ApiKeyScope::Write] } else { Vec::new() };
+    Ok(CreateApiKeyRequest { label: label.to_string(), scopes })
+}
+
+/// Handle Refresh in Settings > API Keys (also the first time the section is shown)
+///
+/// Internal handler function - use [`crate::app::App::handle_api_keys_refresh`] instead.
+pub(crate) fn handle_api_keys_refresh(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (jwt_token, api_client) = {
+        let mut state = state.write();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api_client.clone()) else {
+            return;
+        };
+        if state.api_keys.loading {
+            return;
+        }
+        state.api_keys.loading = true;
+        (jwt_token, api_client)
+    };
+
+    let tx = event_tx.clone();
+    spawn_guarded(GuardedTask::ApiKeys, state, event_tx, async move {
+        let result = api_client.list_api_keys(&jwt_token).await.map_err(|e| e.to_string()).map(|response| response.api_keys);
+        let _ = tx.send(AppEvent::ApiKeysResult(result)).await;
+    });
+}
+
+/// Handle Create Key
+///
+/// Internal handler function - use [`crate::app::App::handle_api_key_create`] instead.
+pub(crate) fn handle_api_key_create(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (request, jwt_token, api_client) = {
+        let mut state = state.write();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api_client.clone()) else {
+            return;
+        };
+        if state.api_keys.creating {
+            return;
+        }
+        match parse_api_key_form(&state.api_keys) {
+            Ok(request) => {
+                state.api_keys.creating = true;
+                state.api_keys.error = None;
+                (request, jwt_token, api_client)
+            }
+            Err(err) => {
+                state.api_keys.error = Some(err);
+                return;
+            }
+        }
+    };
+
+    let tx = event_tx.clone();
+    spawn_guarded(GuardedTask::ApiKeyCreate, state, event_tx, async move {
+        let result = api_client.create_api_key(&jwt_token, &request).await.map_err(|e| e.to_string());
+        let _ = tx.send(AppEvent::ApiKeyCreated(result)).await;
+    });
+}
+
+/// Handle Revoke on a key row
+///
+/// Internal handler function - use [`crate::app::App::handle_api_key_revoke`] instead.
+pub(crate) fn handle_api_key_revoke(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, id: i64) {
+    let (jwt_token, api_client) = {
+        let mut state = state.write();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api_client.clone()) else {
+            return;
+        };
+        if state.api_keys.revoking.is_some() {
+            return;
+        }
+        state.api_keys.revoking = Some(id);
+        (jwt_token, api_client)
+    };
+
+    tokio::spawn(async move {
+        let result = api_client.revoke_api_key(&jwt_token, id).await.map_err(|e| e.to_string());
+        let _ = event_tx.send(AppEvent::ApiKeyRevoked(id, result)).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(label: &str, write: bool) -> ApiKeysState {
+        ApiKeysState {
+            label_input: label.to_string(),
+            write_input: write,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_api_key_form() {
+        let request = parse_api_key_form(&form(" Spreadsheet ", false)).unwrap();
+        assert_eq!(request.label, "Spreadsheet");
+        assert!(request.scopes.is_empty());
+
+        let request = parse_api_key_form(&form("Bot", true)).unwrap();
+        assert_eq!(request.scopes, vec![ApiKeyScope::Write]);
+
+        assert!(parse_api_key_form(&form("  ", false)).is_err());
+        assert!(parse_api_key_form(&form(&"x".repeat(MAX_API_KEY_LABEL_LEN + 1), false)).is_err());
+    }
+}
@@ -1,14 +1,33 @@
 //! # Authentication Handlers
 //!
-//! Handlers for login, signup, and authentication-related actions.
+//! Handlers for login, signup, account updates and other authentication-related actions.
 
-use crate::app::state::{AppState, AuthState, LoginField, SignupField};
-use crate::app::events::AppEvent;
+use crate::app::audit::{self, AuditCategory};
+use crate::app::state::{AppState, AuthState, LoginField, Screen, SignupField};
+use crate::app::events::{AppEvent, ProfileUpdate};
 use crate::core::service::ApiService;
+use crate::services::session;
 use async_channel::Sender;
 use parking_lot::RwLock;
+use shared::validation::{validate_signup, ValidationError};
 use std::sync::Arc;
 
+/// Field name for the signup form's "Confirm Password" errors
+pub const FIELD_CONFIRM_PASSWORD: &str = "confirm_password";
+
+/// Standalone wallet-web (trunk serve), used unless the backend embeds the wallet
+const WALLET_WEB_URL: &str = "http://localhost:8080";
+
+/// Where wallet-web is reached: under the active server when it embeds the
+/// bundle (reported by `/api/health`), otherwise the standalone dev server
+pub(crate) fn wallet_web_url(state: &AppState) -> String {
+    let embedded = state.backend_health.report.as_ref().and_then(|r| r.wallet_path.as_deref());
+    match (embedded, &state.api_client) {
+        (Some(path), Some(client)) => format!("{}{}", client.base_url().trim_end_matches('/'), path),
+        _ => WALLET_WEB_URL.to_string(),
+    }
+}
+
 /// Handle login button click
 ///
 /// Internal handler function - use [`crate::app::App::handle_login_click`] instead.
@@ -26,7 +45,7 @@ pub(crate) fn handle_login_click(
         return;
     }
 
-    let api_client = match state.read().api_client.as_ref() {
+    let api_client = match state.read().api.as_ref() {
         Some(client) => client.clone(),
         None => {
             let mut state = state.write();
@@ -40,7 +59,7 @@ pub(crate) fn handle_login_click(
     let tx = event_tx.clone();
     tokio::spawn(async move {
         let _ = tx.send(AppEvent::Loading("Logging in...".to_string())).await;
-        let result = api_client.login(username, password).await;
+        let result = api_client.login(username, password).await.map_err(|e| e.to_string());
         let _ = tx.send(AppEvent::LoginResult(result)).await;
     });
 
@@ -50,6 +69,18 @@ pub(crate) fn handle_login_click(
     }
 }
 
+/// Everything wrong with the signup form, by field
+///
+/// The backend applies the same [`shared::validation`] rules, so a form that
+/// passes here only fails server-side on a taken username or email.
+pub fn signup_form_errors(username: &str, email: &str, password: &str, confirm_password: &str) -> Vec<ValidationError> {
+    let mut errors = validate_signup(username, email, password);
+    if password != confirm_password {
+        errors.push(ValidationError::new(FIELD_CONFIRM_PASSWORD, "Passwords don't match"));
+    }
+    errors
+}
+
 /// Handle signup button click
 ///
 /// Internal handler function - use [`crate::app::App::handle_signup_click`] instead.
@@ -69,15 +100,16 @@ pub(crate) fn handle_signup_click(
         return;
     }
 
-    if password != confirm_password {
+    // The form shows each message under its field
+    if !signup_form_errors(&username, &email, &password, &confirm_password).is_empty() {
         let mut state = state.write();
         if let AuthState::Signup { error, .. } = &mut state.auth {
-            *error = Some("Passwords don't match".to_string());
+            *error = Some("Fix the errors above to sign up".to_string());
         }
         return;
     }
 
-    let api_client = match state.read().api_client.as_ref() {
+    let api_client = match state.read().api.as_ref() {
         Some(client) => client.clone(),
         None => {
             let mut state = state.write();
@@ -91,7 +123,7 @@ pub(crate) fn handle_signup_click(
     let tx = event_tx.clone();
     tokio::spawn(async move {
         let _ = tx.send(AppEvent::Loading("Signing up...".to_string())).await;
-        let result = api_client.signup(username, email, password).await;
+        let result = api_client.signup(username, email, password).await.map_err(|e| e.to_string());
         let _ = tx.send(AppEvent::SignupResult(result)).await;
     });
 
@@ -129,3 +161,309 @@ pub(crate) fn handle_switch_to_signup(state: Arc<RwLock<AppState>>) {
     };
 }
 
+
+/// Handle the nav bar Logout button
+///
+/// The local session is cleared immediately; the backend is asked to revoke
+/// the token in the background, so logging out works offline too.
+///
+/// Internal handler function - use [`crate::app::App::handle_logout_click`] instead.
+pub(crate) fn handle_logout_click(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (jwt_token, api_client) = {
+        let mut state = state.write();
+        let session = (state.auth_token.clone(), state.api.clone());
+        if let Some(user) = state.current_user.clone() {
+            audit::record(&mut state.security.trail, AuditCategory::Auth, format!("Logged out {}", user.username), vec![]);
+        }
+        clear_session(&mut state);
+        session
+    };
+
+    if let (Some(jwt_token), Some(api_client)) = (jwt_token, api_client) {
+        tokio::spawn(async move {
+            let result = api_client.logout(&jwt_token).await.map_err(|e| e.to_string());
+            let _ = event_tx.send(AppEvent::LogoutResult(result)).await;
+        });
+    }
+}
+
+/// Log out after the backend rejected the session token
+///
+/// Unlike [`handle_logout_click`] there is nothing to revoke, the token is
+/// already dead; the login form explains why the user is back there. Returns
+/// whether a session was cleared: requests in flight when it ended all come
+/// back 401, and only the first should do anything.
+pub(crate) fn handle_session_expired(state: &mut AppState) -> bool {
+    if state.auth_token.is_none() {
+        return false;
+    }
+    let username = state.current_user.as_ref().map(|user| user.username.clone()).unwrap_or_default();
+    audit::record(&mut state.security.trail, AuditCategory::Auth, format!("Session expired for {}", username), vec![]);
+    clear_session(state);
+    if let AuthState::Login { error, username: login_username, .. } = &mut state.auth {
+        *error = Some(crate::services::api::ApiError::Unauthorized.to_string());
+        *login_username = username;
+    }
+    true
+}
+
+/// Handle Change Password button click (Settings > Account)
+///
+/// Internal handler function - use [`crate::app::App::handle_change_password_click`] instead.
+pub(crate) fn handle_change_password_click(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (current_password, new_password, jwt_token, api_client) = {
+        let mut state = state.write();
+        let form = &state.settings.account;
+        let error = if form.current_password.is_empty() || form.new_password.is_empty() {
+            Some("Current and new password required")
+        } else if form.new_password != form.confirm_password {
+            Some("New passwords do not match")
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            state.settings.account.status = Some((true, error.to_string()));
+            return;
+        }
+        let Some(session) = account_session(&mut state) else { return };
+        let form = &state.settings.account;
+        (form.current_password.clone(), form.new_password.clone(), session.0, session.1)
+    };
+
+    tokio::spawn(async move {
+        let result = api_client
+            .change_password(&jwt_token, current_password, new_password)
+            .await
+            .map_err(|e| e.to_string())
+            .map(ProfileUpdate::Password);
+        let _ = event_tx.send(AppEvent::ProfileUpdateResult(result)).await;
+    });
+}
+
+/// Handle Update Email button click (Settings > Account)
+///
+/// Internal handler function - use [`crate::app::App::handle_update_profile_click`] instead.
+pub(crate) fn handle_update_profile_click(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (email, jwt_token, api_client) = {
+        let mut state = state.write();
+        let email = state.settings.account.email.trim().to_string();
+        if email.is_empty() {
+            state.settings.account.status = Some((true, "Email required".to_string()));
+            return;
+        }
+        let Some((jwt_token, api_client)) = account_session(&mut state) else { return };
+        (email, jwt_token, api_client)
+    };
+
+    tokio::spawn(async move {
+        let result = api_client
+            .update_profile(&jwt_token, email)
+            .await
+            .map_err(|e| e.to_string())
+            .map(ProfileUpdate::Profile);
+        let _ = event_tx.send(AppEvent::ProfileUpdateResult(result)).await;
+    });
+}
+
+/// Refresh the login token once it is close to expiry
+///
+/// Called every frame; does nothing unless the user is active and
+/// [`session::refresh_due`] says it's time, so an idle terminal lets its
+/// session lapse instead of keeping it alive forever.
+///
+/// Internal handler function - use [`crate::app::App::keep_session_alive`] instead.
+pub(crate) fn handle_session_keep_alive(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, active: bool) {
+    if !active {
+        return;
+    }
+    let due = {
+        let state = state.read();
+        !state.session.refreshing
+            && state.session_expires_at().is_some_and(|expires_at| {
+                session::refresh_due(expires_at, chrono::Utc::now().timestamp(), state.session.last_refresh_attempt)
+            })
+    };
+    if due {
+        handle_session_refresh(state, event_tx);
+    }
+}
+
+/// Handle the Refresh Session button (Settings > Security)
+///
+/// Internal handler function - use [`crate::app::App::handle_session_refresh`] instead.
+pub(crate) fn handle_session_refresh(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (from_token, api_client) = {
+        let mut state = state.write();
+        if state.session.refreshing {
+            return;
+        }
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api.clone()) else {
+            return;
+        };
+        state.session.refreshing = true;
+        state.session.last_refresh_attempt = Some(chrono::Utc::now().timestamp());
+        (jwt_token, api_client)
+    };
+
+    tokio::spawn(async move {
+        let result = api_client.refresh_session(&from_token).await.map_err(|e| e.to_string());
+        let _ = event_tx.send(AppEvent::SessionRefreshResult { from_token, result }).await;
+    });
+}
+
+/// Token and client for an account request, marking the form as pending
+fn account_session(state: &mut AppState) -> Option<(String, Arc<dyn ApiService>)> {
+    let form = &mut state.settings.account;
+    if form.pending {
+        return None;
+    }
+    match (state.auth_token.clone(), state.api.clone()) {
+        (Some(jwt_token), Some(api_client)) => {
+            form.pending = true;
+            form.status = None;
+            Some((jwt_token, api_client))
+        }
+        _ => {
+            form.status = Some((true, "Not logged in".to_string()));
+            None
+        }
+    }
+}
+
+/// Forget everything tied to the logged-in user and return to the login form
+fn clear_session(state: &mut AppState) {
+    crate::services::api::websocket::disconnect_price_stream(state);
+    state.auth_token = None;
+    state.session = Default::default();
+    state.current_user = None;
+    state.polling_credentials = None;
+    state.transactions.clear();
+    state.terminal.swap.confirmation = None;
+    state.trade_import = Default::default();
+    state.settings.account = Default::default();
+    // Unattended signing must not outlive the session that granted it
+    if let Some(wallet_service) = state.wallet_service.as_mut() {
+        wallet_service.revoke_all_grants();
+    }
+    // The audit trail outlives sessions
+    let trail = std::mem::take(&mut state.security.trail);
+    state.security = crate::app::state::SecurityState { trail, ..Default::default() };
+    state.webhooks = Default::default();
+    state.api_keys = Default::default();
+    state.auth = AuthState::Login {
+        username: String::new(),
+        password: String::new(),
+        error: None,
+        active_field: LoginField::Username,
+    };
+    state.current_screen = Screen::Auth;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mock::MockApiService;
+    use crate::services::api::ApiError;
+
+    #[test]
+    fn test_session_expired_returns_to_login_once() {
+        let mut state = crate::app::App::new().state.read().clone();
+        state.security.trail = crate::app::audit::AuditLog::default();
+        state.auth_token = Some("expired".to_string());
+        state.current_user = Some(crate::app::state::CurrentUser { id: 7, username: "alice".to_string() });
+        state.current_screen = Screen::Terminal;
+
+        assert!(handle_session_expired(&mut state));
+        assert!(state.auth_token.is_none());
+        assert!(state.current_user.is_none());
+        assert_eq!(state.current_screen, Screen::Auth);
+        match &state.auth {
+            AuthState::Login { username, error, .. } => {
+                assert_eq!(username, "alice");
+                assert_eq!(error.as_deref(), Some("Session expired, please log in again"));
+            }
+            other => panic!("expected the login form, got {:?}", other),
+        }
+        let entries = state.security.trail.entries().len();
+        assert_eq!(state.security.trail.entries()[entries - 1].summary, "Session expired for alice");
+
+        // Later 401s from requests sent before the logout change nothing
+        assert!(!handle_session_expired(&mut state));
+        assert_eq!(state.security.trail.entries().len(), entries);
+    }
+
+    #[tokio::test]
+    async fn test_login_click_logs_in_through_the_api() {
+        let api = Arc::new(MockApiService::new());
+        api.respond::<Result<shared::AuthResponse, ApiError>>(
+            "login",
+            Ok(shared::AuthResponse {
+                user: shared::UserInfo {
+                    id: "7".to_string(),
+                    username: "alice".to_string(),
+                    email: "alice@example.com".to_string(),
+                    created_at: "2024-01-01T00:00:00Z".to_string(),
+                    wallet_address: Some("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU".to_string()),
+                },
+                token: "jwt-alice".to_string(),
+                message: "Login successful".to_string(),
+                wallet_setup_required: None,
+                wallet_setup_token: None,
+            }),
+        );
+        let mut app = crate::app::App::new();
+        app.state.write().api = Some(Arc::clone(&api) as Arc<dyn ApiService>);
+
+        // Nothing is sent without both fields
+        app.handle_login_click("alice".to_string(), String::new());
+        assert!(api.calls().is_empty());
+
+        app.handle_login_click("alice".to_string(), "hunter2hunter".to_string());
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                if let Ok(AppEvent::LoginResult(result)) = app.event_rx.recv().await {
+                    return result;
+                }
+            }
+        })
+        .await
+        .expect("login never returned");
+        assert_eq!(api.calls_to("login"), [vec![r#""alice""#.to_string(), r#""hunter2hunter""#.to_string()]]);
+
+        app.handle_event(AppEvent::LoginResult(result));
+        let state = app.state.read();
+        assert_eq!(state.auth_token.as_deref(), Some("jwt-alice"));
+        assert_eq!(state.current_user.as_ref().map(|user| user.id), Some(7));
+        assert_eq!(state.current_screen, Screen::Terminal);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_login_stays_on_the_form() {
+        let api = Arc::new(MockApiService::new());
+        api.respond::<Result<shared::AuthResponse, ApiError>>(
+            "login",
+            Err(ApiError::Api { status: 401, message: "Invalid credentials".to_string() }),
+        );
+        let mut state = crate::app::App::new().state.read().clone();
+        state.api = Some(Arc::clone(&api) as Arc<dyn ApiService>);
+        let state = Arc::new(RwLock::new(state));
+        let (tx, rx) = async_channel::unbounded();
+
+        handle_login_click(Arc::clone(&state), tx, "alice".to_string(), "wrong-password".to_string());
+        assert!(matches!(rx.recv().await, Ok(AppEvent::Loading(_))));
+        match rx.recv().await {
+            Ok(AppEvent::LoginResult(Err(error))) => assert_eq!(error, "Invalid credentials"),
+            other => panic!("expected a failed LoginResult, got {:?}", other),
+        }
+        assert!(state.read().auth_token.is_none());
+    }
+
+    #[test]
+    fn test_signup_form_errors() {
+        assert!(signup_form_errors("alice", "alice+sol@example.com", "hunter2hunter", "hunter2hunter").is_empty());
+
+        let errors = signup_form_errors("alice", "alice@example", "hunter2hunter", "hunter2");
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, [shared::validation::FIELD_EMAIL, FIELD_CONFIRM_PASSWORD]);
+    }
+}
@@ -0,0 +1,128 @@
+//! # Streamed Candle Handlers
+//!
+//! Keep the Live Chart's candles moving between REST loads: the price stream
+//! sends the forming candle of every timeframe on each tick and the final one
+//! when its period closes, and the updates for the chart's symbol and
+//! timeframe are merged into `sol_candles`.
+
+use crate::app::state::AppState;
+use crate::app::tasks::market::CHART_CANDLES;
+use shared::dto::market::Timeframe;
+use shared::dto::OHLC;
+
+/// Merge a streamed candle into the chart's candles
+///
+/// Internal handler function - called from the event handler on
+/// `AppEvent::CandleUpdated`. Updates for another symbol or timeframe are
+/// discarded, as are those arriving while candles load: after a timeframe
+/// switch the stream keeps sending the old timeframe's candles, and the new
+/// ones can't be placed until the REST load is back.
+pub(crate) fn apply_candle_update(state: &mut AppState, symbol: &str, timeframe: Timeframe, candle: OHLC, is_closed: bool) {
+    let terminal = &mut state.terminal;
+    if !symbol.eq_ignore_ascii_case(&terminal.chart_symbol) || timeframe != terminal.chart_timeframe || terminal.chart_loading {
+        return;
+    }
+    if !merge_candle(&mut terminal.sol_candles, candle) {
+        return;
+    }
+    terminal.sol_candle_forming = !is_closed;
+    terminal.chart_data_version += 1;
+    let config = state.settings.indicators;
+    let terminal = &mut state.terminal;
+    terminal.chart_indicators.update(&terminal.sol_candles, &config);
+}
+
+/// Replace the last candle if `candle` is for the same period, append it if
+/// it starts a new one
+///
+/// Nothing is merged into an empty chart (the first load fills it) and
+/// candles older than the last one are stale.
+///
+/// # Returns
+/// `true` if `candles` changed
+fn merge_candle(candles: &mut Vec<OHLC>, candle: OHLC) -> bool {
+    let Some(last) = candles.last_mut() else {
+        return false;
+    };
+    if candle.timestamp == last.timestamp {
+        *last = candle;
+    } else if candle.timestamp > last.timestamp {
+        candles.push(candle);
+        if candles.len() > CHART_CANDLES {
+            candles.remove(0);
+        }
+    } else {
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(timestamp: i64, close: f64) -> OHLC {
+        OHLC::new(timestamp, 100.0, close.max(100.0), close.min(100.0), close, 0.0)
+    }
+
+    fn chart() -> AppState {
+        let mut state = crate::app::App::new().state.read().clone();
+        state.terminal.chart_timeframe = Timeframe::OneHour;
+        state.terminal.sol_candles = vec![candle(0, 101.0), candle(3600, 102.0)].into();
+        state
+    }
+
+    #[test]
+    fn test_forming_candle_replaces_the_last_and_closed_ones_stay() {
+        let mut state = chart();
+
+        apply_candle_update(&mut state, "SOL", Timeframe::OneHour, candle(3600, 103.0), false);
+        assert_eq!(state.terminal.sol_candles.len(), 2);
+        assert_eq!(state.terminal.sol_candles[1].close, 103.0);
+        assert!(state.terminal.sol_candle_forming);
+
+        apply_candle_update(&mut state, "SOL", Timeframe::OneHour, candle(3600, 104.0), true);
+        assert_eq!(state.terminal.sol_candles[1].close, 104.0);
+        assert!(!state.terminal.sol_candle_forming);
+
+        // The next period starts a new candle after the closed one
+        apply_candle_update(&mut state, "SOL", Timeframe::OneHour, candle(7200, 99.0), false);
+        let closes: Vec<f64> = state.terminal.sol_candles.iter().map(|c| c.close).collect();
+        assert_eq!(closes, vec![101.0, 104.0, 99.0]);
+        assert!(state.terminal.sol_candle_forming);
+
+        // A late update for an earlier period is stale
+        apply_candle_update(&mut state, "SOL", Timeframe::OneHour, candle(3600, 90.0), true);
+        assert_eq!(state.terminal.sol_candles[1].close, 104.0);
+        assert!(state.terminal.sol_candle_forming);
+    }
+
+    #[test]
+    fn test_updates_for_another_chart_are_discarded() {
+        let mut state = chart();
+        let before = state.terminal.sol_candles.clone();
+
+        apply_candle_update(&mut state, "BONK", Timeframe::OneHour, candle(7200, 1.0), false);
+        // Still streamed for the timeframe the chart just switched away from
+        apply_candle_update(&mut state, "SOL", Timeframe::OneMinute, candle(7200, 1.0), false);
+        // Switched, but the new timeframe's candles are still loading
+        state.terminal.chart_loading = true;
+        apply_candle_update(&mut state, "SOL", Timeframe::OneHour, candle(7200, 1.0), false);
+        assert_eq!(state.terminal.sol_candles, before);
+
+        state.terminal.chart_loading = false;
+        state.terminal.sol_candles.clear();
+        apply_candle_update(&mut state, "SOL", Timeframe::OneHour, candle(7200, 1.0), false);
+        assert!(state.terminal.sol_candles.is_empty());
+        assert!(!state.terminal.sol_candle_forming);
+    }
+
+    #[test]
+    fn test_appended_candles_keep_the_chart_length() {
+        let mut candles: Vec<OHLC> = (0..CHART_CANDLES as i64).map(|i| candle(i * 60, 100.0)).collect();
+        assert!(merge_candle(&mut candles, candle(CHART_CANDLES as i64 * 60, 101.0)));
+        assert_eq!(candles.len(), CHART_CANDLES);
+        assert_eq!(candles[0].timestamp, 60);
+        assert_eq!(candles.last().unwrap().close, 101.0);
+    }
+}
@@ -0,0 +1,52 @@
+//! # Command Palette Handlers
+//!
+//! Opening and closing the command palette. Matching and parsing live in
+//! [`crate::app::commands`]; the palette widget runs the chosen action.
+
+use crate::app::state::AppState;
+use crate::app::usage;
+use parking_lot::RwLock;
+use shared::dto::telemetry::{UsageEvent, UsageFeature};
+use std::sync::Arc;
+
+/// Open or close the command palette; opening starts from empty input
+///
+/// Internal handler function - use [`crate::app::App::handle_command_palette_toggle`] instead.
+pub(crate) fn handle_command_palette_toggle(state: Arc<RwLock<AppState>>) {
+    let mut state = state.write();
+    state.palette.open = !state.palette.open;
+    if state.palette.open {
+        usage::capture(UsageEvent::Feature(UsageFeature::CommandPalette));
+        // One overlay owns the keyboard at a time
+        state.search.open = false;
+        let palette = &mut state.palette;
+        palette.input.clear();
+        palette.selected = 0;
+        palette.error = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::handlers::search::handle_search_toggle;
+    use crate::app::App;
+
+    #[test]
+    fn test_palettes_close_each_other() {
+        let state = Arc::new(RwLock::new(App::new().state.read().clone()));
+        state.write().palette.input = "swap".to_string();
+
+        handle_command_palette_toggle(state.clone());
+        assert!(state.read().palette.open);
+        assert!(state.read().palette.input.is_empty());
+
+        handle_search_toggle(state.clone());
+        assert!(state.read().search.open);
+        assert!(!state.read().palette.open);
+
+        handle_command_palette_toggle(state.clone());
+        assert!(state.read().palette.open);
+        assert!(!state.read().search.open);
+    }
+}
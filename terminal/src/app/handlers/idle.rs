@@ -0,0 +1,151 @@
+//! # Idle Teardown Handlers
+//!
+//! Carry out the transitions [`IdleMonitor`](crate::app::idle::IdleMonitor)
+//! asks for.
+//!
+//! Teardown aborts the price stream and the supervised reloads and prefetches
+//! (their [`TaskGuard`](crate::app::tasks::guard)s reset the in-progress
+//! flags), then drops the HTTP pool and the wallet's RPC client. All of it
+//! happens under one state write, so no task can start in between; events
+//! from tasks that were already past their last await still arrive, and the
+//! websocket status ones are ignored until the resume.
+
+use crate::app::events::AppEvent;
+use crate::app::idle::{teardown_after, IdleTransition, WindowSignals};
+use crate::app::state::{AppState, WebSocketState};
+use crate::app::tasks;
+use crate::services::api::websocket;
+use async_channel::Sender;
+use parking_lot::RwLock;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Feed one frame's window signals to the idle monitor
+///
+/// Internal handler function - use [`crate::app::App::idle_tick`] instead.
+pub(crate) fn handle_idle_tick(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, signals: WindowSignals) {
+    let now = Instant::now();
+    let transition = {
+        let mut state = state.write();
+        let limit = teardown_after(state.settings.network.idle_teardown_mins);
+        let stream_connected = matches!(state.websocket_status.state, WebSocketState::Connected);
+        state.idle.settle(stream_connected, now);
+        state.idle.observe(signals, limit, now)
+    };
+
+    match transition {
+        Some(IdleTransition::TearDown) => tear_down(&mut state.write()),
+        Some(IdleTransition::Resume) => resume(state, event_tx),
+        None => {}
+    }
+}
+
+/// Close connections and stop background work
+pub(crate) fn tear_down(state: &mut AppState) {
+    websocket::disconnect_price_stream(state);
+    let reloads = state.refresh_tasks.cancel_except(&[]);
+    let prefetches = state.live_assets.prefetch.cancel_except(&[]);
+    if let Some(api_client) = &state.api_client {
+        api_client.close_connections();
+    }
+    if let Some(wallet_service) = state.wallet_service.as_mut() {
+        wallet_service.reset_rpc_client();
+    }
+    tracing::info!(reloads, prefetches, "Idle: connections closed, entering low-power mode");
+}
+
+/// Reopen connections and reload what the current screen shows
+pub(crate) fn resume(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let screen = {
+        let mut app_state = state.write();
+        if let Some(api_client) = &app_state.api_client {
+            api_client.open_connections();
+        }
+        // The stream runs once logged in
+        if app_state.auth_token.is_some() {
+            websocket::restart_price_stream(&mut app_state, event_tx.clone(), state.clone());
+        }
+        app_state.current_screen
+    };
+    tracing::info!("Activity after idle teardown - reconnecting");
+
+    tasks::health::check_health(state.clone(), event_tx.clone());
+    super::refresh::refresh_targets(&state, &event_tx, super::refresh::RefreshTarget::for_screen(screen));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::idle::PowerState;
+    use std::time::Duration;
+
+    fn test_state() -> Arc<RwLock<AppState>> {
+        let mut state = crate::app::App::new().state.read().clone();
+        state.settings.network.idle_teardown_mins = 1;
+        state.auth_token = Some("token".to_string());
+        Arc::new(RwLock::new(state))
+    }
+
+    fn away() -> WindowSignals {
+        WindowSignals { focused: false, minimized: true, interacted: false }
+    }
+
+    #[tokio::test]
+    async fn test_idle_teardown_and_reconnect() {
+        let state = test_state();
+        let (tx, _rx) = async_channel::unbounded();
+        {
+            let mut app_state = state.write();
+            websocket::restart_price_stream(&mut app_state, tx.clone(), state.clone());
+            app_state.live_assets.prefetch.spawn("SOL", std::future::pending());
+            // Last activity a minute ago
+            app_state.idle = crate::app::idle::IdleMonitor::new(Instant::now() - Duration::from_secs(61));
+        }
+
+        // Unfocused and untouched past the limit
+        handle_idle_tick(state.clone(), tx.clone(), away());
+        {
+            let app_state = state.read();
+            assert_eq!(app_state.idle.power(), PowerState::LowPower);
+            assert!(app_state.price_stream_task.is_none());
+            assert!(!app_state.websocket_connected);
+            assert_eq!(app_state.live_assets.prefetch.in_flight(), 0);
+            assert!(!app_state.api_client.as_ref().unwrap().has_connections());
+        }
+
+        // Staying away changes nothing
+        handle_idle_tick(state.clone(), tx.clone(), away());
+        assert!(state.read().price_stream_task.is_none());
+
+        // The next interaction brings everything back
+        let clicked = WindowSignals { focused: true, minimized: false, interacted: true };
+        handle_idle_tick(state.clone(), tx.clone(), clicked);
+        {
+            let app_state = state.read();
+            assert_eq!(app_state.idle.power(), PowerState::Reconnecting);
+            assert!(app_state.price_stream_task.is_some());
+            assert!(app_state.api_client.as_ref().unwrap().has_connections());
+        }
+
+        // Connected again: back to normal
+        state.write().websocket_status.state = WebSocketState::Connected;
+        handle_idle_tick(state.clone(), tx, clicked);
+        assert_eq!(state.read().idle.power(), PowerState::Active);
+        websocket::disconnect_price_stream(&mut state.write());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_teardown_keeps_connections() {
+        let state = test_state();
+        let (tx, _rx) = async_channel::unbounded();
+        {
+            let mut app_state = state.write();
+            app_state.settings.network.idle_teardown_mins = 0;
+            app_state.idle = crate::app::idle::IdleMonitor::new(Instant::now() - Duration::from_secs(120));
+        }
+        handle_idle_tick(state.clone(), tx, away());
+        let app_state = state.read();
+        assert_eq!(app_state.idle.power(), PowerState::Active);
+        assert!(app_state.api_client.as_ref().unwrap().has_connections());
+    }
+}
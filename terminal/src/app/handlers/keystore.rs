@@ -0,0 +1,357 @@
+//! # Derived Account Handlers
+//!
+//! Handlers for importing a mnemonic into the encrypted keystore, scanning its
+//! derived accounts, and activating an index as the connected wallet.
+//!
+//! Every operation that needs the seed consumes the password input from state
+//! and runs the (slow, Argon2-bound) unlock on a blocking thread. The seed is
+//! only decrypted inside [`Keystore::with_seed`].
+
+use crate::app::audit::{self, AuditCategory, AuditReference};
+use crate::app::events::AppEvent;
+use crate::app::state::{AppState, WalletState};
+use crate::services::keystore::{get_keystore_path, Keystore, DEFAULT_SCAN_COUNT};
+use async_channel::Sender;
+use parking_lot::RwLock;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use std::str::FromStr;
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+/// Load the keystore from disk, if one has been imported
+pub fn load_keystore() -> Option<Keystore> {
+    let path = get_keystore_path();
+    if !path.exists() {
+        return None;
+    }
+
+    match Keystore::load_from_file(&path) {
+        Ok(keystore) => {
+            tracing::info!(accounts = keystore.accounts.len(), "Loaded keystore from {:?}", path);
+            Some(keystore)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to load keystore from {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Number of indexes to derive and scan (`DERIVED_SCAN_COUNT`, default 5)
+fn scan_count() -> u32 {
+    std::env::var("DERIVED_SCAN_COUNT")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_SCAN_COUNT)
+}
+
+/// Take the password out of the input field so it is not kept in state
+fn take_password(state: &mut AppState) -> Zeroizing<String> {
+    Zeroizing::new(std::mem::take(&mut state.derived_accounts.password_input))
+}
+
+fn notify(event_tx: &Sender<AppEvent>, message: String) {
+    let tx = event_tx.clone();
+    tokio::spawn(async move {
+        let _ = tx.send(AppEvent::Loading(message)).await;
+    });
+}
+
+/// Fetch SOL balances for `(index, address)` pairs. Failed lookups are skipped.
+fn fetch_balances(rpc_url: &str, accounts: &[(u32, String)]) -> Vec<(u32, f64)> {
+    let rpc_client = RpcClient::new(rpc_url.to_string());
+    accounts
+        .iter()
+        .filter_map(|(index, address)| {
+            let pubkey = Pubkey::from_str(address).ok()?;
+            match rpc_client.get_balance(&pubkey) {
+                Ok(lamports) => Some((*index, lamports as f64 / 1_000_000_000.0)),
+                Err(e) => {
+                    tracing::warn!(index, "Failed to get balance for derived account: {}", e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Derive missing indexes, save the keystore and refresh balances.
+///
+/// Shared by import and scan; runs on a blocking thread.
+fn derive_and_scan(
+    mut keystore: Keystore,
+    password: Zeroizing<String>,
+    count: u32,
+    rpc_url: String,
+) -> Result<(Keystore, Vec<(u32, f64)>), String> {
+    keystore.ensure_derived(&password, count).map_err(|e| e.to_string())?;
+    drop(password);
+    keystore.save_to_file(get_keystore_path()).map_err(|e| e.to_string())?;
+
+    let accounts: Vec<(u32, String)> = keystore
+        .accounts
+        .iter()
+        .map(|a| (a.index, a.address.clone()))
+        .collect();
+    let balances = fetch_balances(&rpc_url, &accounts);
+    Ok((keystore, balances))
+}
+
+fn spawn_scan(
+    state: Arc<RwLock<AppState>>,
+    event_tx: Sender<AppEvent>,
+    job: impl FnOnce() -> Result<(Keystore, Vec<(u32, f64)>), String> + Send + 'static,
+    done_message: &'static str,
+    audited: bool,
+) {
+    state.write().derived_accounts.scanning = true;
+
+    tokio::spawn(async move {
+        let result = match tokio::task::spawn_blocking(job).await {
+            Ok(result) => result,
+            Err(e) => Err(format!("Task join error: {}", e)),
+        };
+
+        let message = {
+            let mut state = state.write();
+            let derived = &mut state.derived_accounts;
+            derived.scanning = false;
+            match result {
+                Ok((keystore, balances)) => {
+                    let count = keystore.accounts.len();
+                    // The keystore's first address stands in for the seed itself
+                    let first_address = keystore.accounts.first().map(|account| account.address.clone());
+                    derived.keystore = Some(keystore);
+                    derived.balances.extend(balances);
+                    if audited {
+                        audit::record(
+                            &mut state.security.trail,
+                            AuditCategory::Wallet,
+                            format!("{} into the encrypted keystore", done_message),
+                            first_address.map(AuditReference::Account).into_iter().collect(),
+                        );
+                    }
+                    format!("{} ({} accounts)", done_message, count)
+                }
+                Err(e) => format!("Derived accounts: {}", e),
+            }
+        };
+        let _ = event_tx.send(AppEvent::Loading(message)).await;
+    });
+}
+
+/// Handle mnemonic import from the Wallet screen
+///
+/// Internal handler function - use [`crate::app::App::handle_mnemonic_import`] instead.
+pub(crate) fn handle_mnemonic_import(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (phrase, passphrase, password, rpc_url) = {
+        let mut state = state.write();
+        if state.derived_accounts.scanning {
+            return;
+        }
+        let derived = &mut state.derived_accounts;
+        let phrase = Zeroizing::new(std::mem::take(&mut derived.mnemonic_input));
+        let passphrase = Zeroizing::new(std::mem::take(&mut derived.passphrase_input));
+        (phrase, passphrase, take_password(&mut state), state.config.rpc_endpoint())
+    };
+
+    let count = scan_count();
+    spawn_scan(
+        state,
+        event_tx,
+        move || {
+            let keystore = Keystore::import_mnemonic(&phrase, &passphrase, &password).map_err(|e| e.to_string())?;
+            drop((phrase, passphrase));
+            derive_and_scan(keystore, password, count, rpc_url)
+        },
+        "Seed imported",
+        true,
+    );
+}
+
+/// Handle scan of the derived account indexes
+///
+/// Internal handler function - use [`crate::app::App::handle_derived_scan`] instead.
+pub(crate) fn handle_derived_scan(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (keystore, password, rpc_url) = {
+        let mut state = state.write();
+        if state.derived_accounts.scanning {
+            return;
+        }
+        let Some(keystore) = state.derived_accounts.keystore.clone() else {
+            return;
+        };
+        (keystore, take_password(&mut state), state.config.rpc_endpoint())
+    };
+
+    let count = scan_count();
+    spawn_scan(state, event_tx, move || derive_and_scan(keystore, password, count, rpc_url), "Derived accounts scanned", false);
+}
+
+/// Connect a derived keypair as the active wallet
+fn apply_activation(state: &mut AppState, index: u32, keypair: Keypair, balance: f64, rpc_url: &str) -> Option<Vec<crate::app::state::PortfolioSnapshot>> {
+    let address = keypair.pubkey().to_string();
+    state.wallet_service = Some(crate::services::wallet::WalletService::from_keypair(rpc_url, keypair));
+    audit::record(
+        &mut state.security.trail,
+        AuditCategory::Wallet,
+        format!("Switched to derived account #{}", index),
+        vec![AuditReference::Account(address.clone())],
+    );
+    state.wallet = Some(WalletState {
+        address,
+        sol_balance: balance,
+        token_balances: Vec::new(),
+    });
+    state.derived_accounts.active_index = Some(index);
+    state.derived_accounts.balances.insert(index, balance);
+    super::portfolio::recompute_portfolio(state)
+}
+
+/// Handle activation of a derived account index as the connected wallet
+///
+/// Internal handler function - use [`crate::app::App::handle_derived_activate`] instead.
+pub(crate) fn handle_derived_activate(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, index: u32) {
+    let (keystore, password) = {
+        let mut state = state.write();
+        if state.derived_accounts.scanning {
+            return;
+        }
+        let Some(keystore) = state.derived_accounts.keystore.clone() else {
+            return;
+        };
+        (keystore, take_password(&mut state))
+    };
+
+    if password.is_empty() {
+        notify(&event_tx, "Enter the keystore password to activate an account".to_string());
+        return;
+    }
+
+    let rpc_url = {
+        let mut state = state.write();
+        state.derived_accounts.scanning = true;
+        state.config.rpc_endpoint()
+    };
+
+    tokio::spawn(async move {
+        let _ = event_tx.send(AppEvent::Loading(format!("Unlocking account #{}...", index))).await;
+
+        let rpc_url_for_task = rpc_url.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut keystore = keystore;
+            let keypair = keystore.activate(&password, index).map_err(|e| e.to_string())?;
+            drop(password);
+            keystore.save_to_file(get_keystore_path()).map_err(|e| e.to_string())?;
+
+            let address = keypair.pubkey().to_string();
+            let balance = fetch_balances(&rpc_url_for_task, &[(index, address)])
+                .first()
+                .map(|(_, balance)| *balance)
+                .ok_or_else(|| "Failed to get balance".to_string())?;
+            Ok::<_, String>((keystore, keypair, balance))
+        })
+        .await
+        .unwrap_or_else(|e| Err(format!("Task join error: {}", e)));
+
+        let (message, snapshots) = {
+            let mut state = state.write();
+            state.derived_accounts.scanning = false;
+            match result {
+                Ok((keystore, keypair, balance)) => {
+                    let address = keypair.pubkey().to_string();
+                    state.derived_accounts.keystore = Some(keystore);
+                    let snapshots = apply_activation(&mut state, index, keypair, balance, &rpc_url);
+                    (format!("Wallet connected: {} (account #{})", address, index), snapshots)
+                }
+                Err(e) => (format!("Failed to activate account #{}: {}", index, e), None),
+            }
+        }; // Drop the lock guard before await
+        if let Some(snapshots) = snapshots {
+            if let Err(e) = super::portfolio::save_snapshots(&snapshots) {
+                tracing::warn!("Failed to save portfolio history: {}", e);
+            }
+        }
+        let _ = event_tx.send(AppEvent::Loading(message)).await;
+        super::wallet::handle_token_balances_refresh(state, event_tx);
+    });
+}
+
+/// Handle label change for a derived account index
+///
+/// Internal handler function - use [`crate::app::App::handle_derived_label`] instead.
+pub(crate) fn handle_derived_label(state: Arc<RwLock<AppState>>, index: u32, label: String) {
+    let mut state = state.write();
+    state.derived_accounts.label_edit = None;
+    let Some(keystore) = state.derived_accounts.keystore.as_mut() else {
+        return;
+    };
+    if keystore.set_label(index, &label) {
+        if let Err(e) = keystore.save_to_file(get_keystore_path()) {
+            state
+                .pending_notifications
+                .push(("error".to_string(), format!("Failed to save keystore: {}", e)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_state() -> AppState {
+        let app = crate::app::App::new();
+        let mut state = app.state.read().clone();
+        // Keep test entries out of the real audit file
+        state.security.trail = crate::app::audit::AuditLog::default();
+        state
+    }
+
+    #[test]
+    fn test_take_password_clears_input() {
+        let mut state = test_state();
+        state.derived_accounts.password_input = "secret".to_string();
+
+        let password = take_password(&mut state);
+        assert_eq!(password.as_str(), "secret");
+        assert!(state.derived_accounts.password_input.is_empty());
+    }
+
+    #[test]
+    fn test_apply_activation_connects_wallet() {
+        let mut state = test_state();
+        let keypair = Keypair::new();
+        let address = keypair.pubkey().to_string();
+
+        apply_activation(&mut state, 3, keypair, 1.5, "http://localhost:8899");
+
+        assert!(state.wallet_service.is_some());
+        let wallet = state.wallet.as_ref().unwrap();
+        assert_eq!(wallet.address, address);
+        assert_eq!(wallet.sol_balance, 1.5);
+        assert_eq!(state.derived_accounts.active_index, Some(3));
+        assert_eq!(state.derived_accounts.balances.get(&3), Some(&1.5));
+
+        // The trail points at the account, never at its key
+        let entry = state.security.trail.entries().last().unwrap();
+        assert_eq!(entry.references, vec![AuditReference::Account(address)]);
+        assert_eq!(entry.summary, "Switched to derived account #3");
+    }
+
+    #[test]
+    fn test_activated_keypair_matches_derived_address() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let mut keystore = Keystore::import_mnemonic(phrase, "", "pw").unwrap();
+        keystore.ensure_derived("pw", 2).unwrap();
+
+        let keypair = keystore.activate("pw", 1).unwrap();
+        let expected = keystore.account(1).unwrap().address.clone();
+
+        let mut state = test_state();
+        apply_activation(&mut state, 1, keypair, 0.0, "http://localhost:8899");
+        assert_eq!(state.wallet.unwrap().address, expected);
+    }
+}
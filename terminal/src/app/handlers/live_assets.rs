@@ -0,0 +1,262 @@
+//! # Live Assets Handlers
+//!
+//! Hover intent, sorting, mini charts and the token detail view of the Live
+//! Assets tab. The handlers only update state and report which token should
+//! be fetched; the App methods start the fetches (see [`crate::app::preload`]).
+
+use crate::app::state::{AppState, AssetSort, AssetSortColumn, PriceData};
+use crate::app::tasks::market::SPARKLINE_CANDLES;
+use crate::core::store::{store, Document};
+use parking_lot::RwLock;
+use shared::dto::market::{CandleBatchResponse, Timeframe};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Load the saved sort of the price list
+pub fn load_asset_sort() -> AssetSort {
+    store().settings().load_or_default(Document::LiveAssets)
+}
+
+/// Sort the price list by `column`, or flip the direction if it already is,
+/// and save it
+///
+/// A new column starts with the largest first, except the symbol which
+/// starts at A.
+pub(crate) fn handle_asset_sort_click(state: Arc<RwLock<AppState>>, column: AssetSortColumn) {
+    let sort = {
+        let mut state = state.write();
+        let sort = &mut state.settings.asset_sort;
+        if sort.column == column {
+            sort.descending = !sort.descending;
+        } else {
+            *sort = AssetSort { column, descending: column != AssetSortColumn::Symbol };
+        }
+        // The clicked order applies right away, even if a row is hovered
+        state.live_assets.row_order.clear();
+        state.settings.asset_sort
+    };
+    if let Err(e) = store().settings().save(Document::LiveAssets, &sort) {
+        tracing::error!("Failed to save Live Assets sort: {}", e);
+    }
+}
+
+/// Symbols of `prices` in `sort` order, ties broken by symbol
+///
+/// Symbols without candles count as no volume.
+pub(crate) fn sorted_symbols(prices: &[PriceData], volumes: &BTreeMap<String, f64>, sort: AssetSort) -> Vec<String> {
+    let volume = |price: &PriceData| volumes.get(&price.symbol).copied().unwrap_or(0.0);
+    let mut rows: Vec<&PriceData> = prices.iter().collect();
+    rows.sort_by(|a, b| {
+        let key = match sort.column {
+            AssetSortColumn::Symbol => Ordering::Equal,
+            AssetSortColumn::Price => a.price.total_cmp(&b.price),
+            AssetSortColumn::Change24h => a.change_24h.total_cmp(&b.change_24h),
+            AssetSortColumn::Volume24h => volume(a).total_cmp(&volume(b)),
+        };
+        let ordering = key.then_with(|| a.symbol.cmp(&b.symbol));
+        if sort.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    rows.into_iter().map(|price| price.symbol.clone()).collect()
+}
+
+/// Row order of the price list this frame
+///
+/// Follows the sort, except while a row is hovered: then the last order is
+/// kept (new symbols go at the end, gone ones drop out) so streamed prices
+/// don't move the row under the pointer.
+pub(crate) fn asset_row_order(state: &AppState) -> Vec<String> {
+    let prices = state.terminal.prices.load();
+    let live_assets = &state.live_assets;
+    let sorted = sorted_symbols(&prices, &live_assets.volumes, state.settings.asset_sort);
+    let previous = &live_assets.row_order;
+    if live_assets.hover_intent.hovered().is_none() || previous.is_empty() {
+        return sorted;
+    }
+    let mut order: Vec<String> = previous.iter().filter(|symbol| prices.get(symbol).is_some()).cloned().collect();
+    order.extend(sorted.into_iter().filter(|symbol| !previous.contains(symbol)));
+    order
+}
+
+/// Store the mini chart candles: the last [`SPARKLINE_CANDLES`] closes of
+/// each symbol and their volume
+pub(crate) fn apply_sparklines(state: &mut AppState, batch: CandleBatchResponse) {
+    let live_assets = &mut state.live_assets;
+    live_assets.sparklines.clear();
+    live_assets.volumes.clear();
+    for (symbol, candles) in batch.candles {
+        let recent = &candles[candles.len().saturating_sub(SPARKLINE_CANDLES)..];
+        live_assets.volumes.insert(symbol.clone(), recent.iter().map(|c| c.volume).sum());
+        live_assets.sparklines.insert(symbol, recent.iter().map(|c| c.close).collect());
+    }
+}
+
+/// Show `symbol` on the Live Chart
+///
+/// Returns the timeframe to load its candles at, `None` when the chart
+/// already has them.
+pub(crate) fn handle_asset_chart_open(state: Arc<RwLock<AppState>>, symbol: &str) -> Option<Timeframe> {
+    let mut state = state.write();
+    let terminal = &mut state.terminal;
+    if terminal.chart_symbol == symbol && !terminal.sol_candles.is_empty() {
+        return None;
+    }
+    terminal.chart_symbol = symbol.to_string();
+    terminal.sol_candles.clear();
+    terminal.sol_candle_forming = false;
+    terminal.chart_data_version += 1;
+    terminal.chart_loading = true;
+    Some(terminal.chart_timeframe)
+}
+
+/// Record the row under the pointer this frame.
+///
+/// Prefetches for rows the pointer has left are cancelled; the token being
+/// opened keeps its fetch. Returns the token to prefetch once hover intent
+/// fires.
+pub(crate) fn handle_asset_hover(state: Arc<RwLock<AppState>>, hovered: Option<&str>, now: Instant) -> Option<String> {
+    let mut state = state.write();
+    let live_assets = &mut state.live_assets;
+    if live_assets.hover_intent.hovered() != hovered {
+        let keep: Vec<&str> = hovered.into_iter().chain(live_assets.selected.as_deref()).collect();
+        let cancelled = live_assets.prefetch.cancel_except(&keep);
+        if cancelled > 0 {
+            tracing::debug!(cancelled, "Cancelled token detail prefetches");
+        }
+    }
+    live_assets.hover_intent.update(hovered, now)
+}
+
+/// Keyboard focus moved to a row: prefetch it right away
+pub(crate) fn handle_asset_focus(state: Arc<RwLock<AppState>>, symbol: &str, now: Instant) -> Option<String> {
+    let mut state = state.write();
+    let live_assets = &mut state.live_assets;
+    let keep: Vec<&str> = std::iter::once(symbol).chain(live_assets.selected.as_deref()).collect();
+    live_assets.prefetch.cancel_except(&keep);
+    live_assets.hover_intent.focus(symbol, now)
+}
+
+/// Open the detail view for `symbol`.
+///
+/// Returns `true` when the detail has to be fetched: it is neither cached
+/// nor already being prefetched.
+pub(crate) fn handle_token_detail_open(state: Arc<RwLock<AppState>>, symbol: &str, now: Instant) -> bool {
+    let mut state = state.write();
+    let live_assets = &mut state.live_assets;
+    live_assets.selected = Some(symbol.to_string());
+    live_assets.detail_error = None;
+    if live_assets.details.get_fresh(symbol, now).is_some() {
+        live_assets.detail_loading = false;
+        return false;
+    }
+    live_assets.detail_loading = true;
+    !live_assets.prefetch.is_running(symbol)
+}
+
+pub(crate) fn handle_token_detail_close(state: Arc<RwLock<AppState>>) {
+    let mut state = state.write();
+    let live_assets = &mut state.live_assets;
+    live_assets.selected = None;
+    live_assets.detail_loading = false;
+    live_assets.detail_error = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::PriceStore;
+    use shared::dto::OHLC;
+
+    fn price(symbol: &str, price: f64, change_24h: f64) -> PriceData {
+        PriceData {
+            symbol: symbol.to_string(),
+            price,
+            change_24h,
+            previous_price: None,
+            source: None,
+            confidence: None,
+            publish_time: None,
+            sources: BTreeMap::new(),
+            divergent: false,
+            divergence_pct: None,
+        }
+    }
+
+    fn prices() -> Vec<PriceData> {
+        vec![price("SOL", 150.0, 2.0), price("BONK", 0.00002, 9.5), price("JUP", 0.9, 2.0), price("USDC", 1.0, 0.0)]
+    }
+
+    #[test]
+    fn test_sorted_symbols() {
+        let prices = prices();
+        let volumes = BTreeMap::from([("SOL".to_string(), 900.0), ("JUP".to_string(), 40.0)]);
+        let sort = |column, descending| sorted_symbols(&prices, &volumes, AssetSort { column, descending });
+
+        assert_eq!(sort(AssetSortColumn::Symbol, false), ["BONK", "JUP", "SOL", "USDC"]);
+        assert_eq!(sort(AssetSortColumn::Symbol, true), ["USDC", "SOL", "JUP", "BONK"]);
+        assert_eq!(sort(AssetSortColumn::Price, true), ["SOL", "USDC", "JUP", "BONK"]);
+        // JUP and SOL tie on change; the symbol keeps them in a fixed order
+        assert_eq!(sort(AssetSortColumn::Change24h, false), ["USDC", "JUP", "SOL", "BONK"]);
+        assert_eq!(sort(AssetSortColumn::Volume24h, true), ["SOL", "JUP", "USDC", "BONK"]);
+    }
+
+    #[test]
+    fn test_row_order_holds_while_a_row_is_hovered() {
+        let mut state = crate::app::App::new().state.read().clone();
+        state.terminal.prices = Arc::new(PriceStore::new(prices()));
+        state.settings.asset_sort = AssetSort { column: AssetSortColumn::Price, descending: true };
+        state.live_assets.row_order = asset_row_order(&state);
+        assert_eq!(state.live_assets.row_order, ["SOL", "USDC", "JUP", "BONK"]);
+
+        // JUP overtakes SOL and ETH appears while the pointer rests on a row
+        state.terminal.prices.apply(&[price("JUP", 200.0, 2.0), price("ETH", 3000.0, 1.0)]);
+        state.live_assets.hover_intent.update(Some("USDC"), Instant::now());
+        assert_eq!(asset_row_order(&state), ["SOL", "USDC", "JUP", "BONK", "ETH"]);
+
+        // Once the pointer leaves, rows follow the sort again
+        state.live_assets.hover_intent.update(None, Instant::now());
+        assert_eq!(asset_row_order(&state), ["ETH", "JUP", "SOL", "USDC", "BONK"]);
+    }
+
+    #[test]
+    fn test_apply_sparklines_keeps_the_last_day() {
+        let mut state = crate::app::App::new().state.read().clone();
+        let candles = (0..48).map(|i| OHLC::new(i * 3600, 1.0, 1.0, 1.0, i as f64, 2.0)).collect();
+        let batch = CandleBatchResponse {
+            timeframe: "1h".to_string(),
+            candles: BTreeMap::from([("SOL".to_string(), candles), ("NEW".to_string(), vec![])]),
+            errors: BTreeMap::new(),
+        };
+        apply_sparklines(&mut state, batch);
+
+        let closes = &state.live_assets.sparklines["SOL"];
+        assert_eq!(closes.len(), SPARKLINE_CANDLES);
+        assert_eq!(closes.first(), Some(&24.0));
+        assert_eq!(state.live_assets.volumes["SOL"], 2.0 * SPARKLINE_CANDLES as f64);
+        assert_eq!(state.live_assets.volumes["NEW"], 0.0);
+    }
+
+    #[test]
+    fn test_asset_chart_open_loads_the_symbol_once() {
+        let state = crate::app::App::new().state;
+        state.write().terminal.chart_timeframe = Timeframe::FifteenMinutes;
+
+        assert_eq!(handle_asset_chart_open(state.clone(), "BONK"), Some(Timeframe::FifteenMinutes));
+        {
+            let state = state.read();
+            assert_eq!(state.terminal.chart_symbol, "BONK");
+            assert!(state.terminal.chart_loading);
+        }
+
+        // Already loaded: just navigate
+        state.write().terminal.sol_candles = vec![OHLC::new(0, 1.0, 1.0, 1.0, 1.0, 0.0)].into();
+        assert_eq!(handle_asset_chart_open(state.clone(), "BONK"), None);
+        assert_eq!(handle_asset_chart_open(state.clone(), "SOL"), Some(Timeframe::FifteenMinutes));
+        assert!(state.read().terminal.sol_candles.is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! # Maintenance Handlers
+//!
+//! Feed the [maintenance state machine](crate::app::maintenance) from
+//! notices, the clock, health checks and the price stream, and carry out its
+//! transitions: notifications on the way in, a re-sync of balances and
+//! history on the way out.
+//!
+//! Swaps and scheduler signing ask [`trade_block_reason`] and
+//! [`scheduler_pause_reason`] before they start.
+
+use crate::app::events::AppEvent;
+use crate::app::maintenance::{MaintenancePhase, MaintenanceTransition};
+use crate::app::state::{AppState, WebSocketState};
+use crate::services::api::websocket;
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::SystemNotice;
+use std::sync::Arc;
+
+use super::refresh::RefreshTarget;
+
+/// Take in a maintenance notice from the backend
+///
+/// Applies even when maintenance notices are muted; muting only drops the
+/// notification.
+pub(crate) fn handle_maintenance_notice(state: &mut AppState, notice: &SystemNotice) {
+    let Some(window) = notice.maintenance else {
+        return;
+    };
+    let now = chrono::Utc::now().timestamp();
+    tracing::info!(starts_at = window.starts_at, duration = ?window.expected_duration_secs, "Maintenance announced");
+    let transition = state.maintenance.schedule(window, notice.message.clone(), now);
+    if let Some(transition) = transition {
+        notify(state, transition);
+    }
+}
+
+/// Advance the state machine on the clock and the price stream
+///
+/// Internal handler function - called from [`crate::app::App::on_tick`].
+pub(crate) fn handle_maintenance_tick(state: &mut AppState) {
+    if state.maintenance.phase() == MaintenancePhase::Normal {
+        return;
+    }
+    let now = chrono::Utc::now().timestamp();
+    let stream_connected = matches!(state.websocket_status.state, WebSocketState::Connected);
+    let transition = match state.maintenance.settle(stream_connected, now) {
+        Some(transition) => Some(transition),
+        None => state.maintenance.tick(now),
+    };
+    if let Some(transition) = transition {
+        notify(state, transition);
+    }
+}
+
+/// A health check or the price stream failed
+///
+/// Returns true when the failure is the announced maintenance, so the caller
+/// shouldn't report it as a connection error.
+pub(crate) fn handle_backend_unreachable(state: &mut AppState) -> bool {
+    let now = chrono::Utc::now().timestamp();
+    if let Some(transition) = state.maintenance.backend_unreachable(now) {
+        notify(state, transition);
+    }
+    state.maintenance.is_down()
+}
+
+/// A health check passed: leave maintenance and re-sync
+pub(crate) fn handle_backend_ready(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let transition = {
+        let mut app_state = state.write();
+        let now = chrono::Utc::now().timestamp();
+        let transition = app_state.maintenance.backend_ready(now);
+        if let Some(transition) = transition {
+            notify(&mut app_state, transition);
+        }
+        transition
+    };
+    if transition == Some(MaintenanceTransition::Resync) {
+        resync(state, event_tx);
+    }
+}
+
+/// Reload what may have changed while the backend was down
+fn resync(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let screen = {
+        let mut app_state = state.write();
+        // Don't wait out the stream's backoff, which grew during the outage
+        if app_state.auth_token.is_some() {
+            websocket::restart_price_stream(&mut app_state, event_tx.clone(), state.clone());
+        }
+        app_state.current_screen
+    };
+    tracing::info!("Backend back from maintenance - re-syncing");
+
+    let mut targets = vec![RefreshTarget::WalletBalances, RefreshTarget::TransactionHistory];
+    for &target in RefreshTarget::for_screen(screen) {
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+    super::refresh::refresh_targets(&state, &event_tx, &targets);
+    super::swap::handle_swap_history_refresh(state, event_tx);
+}
+
+fn notify(state: &mut AppState, transition: MaintenanceTransition) {
+    let (level, message) = match transition {
+        MaintenanceTransition::Freeze => (
+            "warning",
+            "Maintenance starts in under 2 minutes - new swaps are paused".to_string(),
+        ),
+        MaintenanceTransition::WentDown => (
+            "warning",
+            format!("Backend is down for scheduled maintenance: {}", state.maintenance.message()),
+        ),
+        MaintenanceTransition::Resync => ("info", "Maintenance over - re-syncing balances and history".to_string()),
+        // Windows that never happened end quietly
+        MaintenanceTransition::Cleared => return,
+    };
+    tracing::info!(?transition, "{}", message);
+    state.pending_notifications.push((level.to_string(), message));
+    state.needs_immediate_repaint = true;
+}
+
+/// Why a new swap can't start now, if it can't
+pub fn trade_block_reason(state: &AppState) -> Option<&'static str> {
+    match state.maintenance.phase() {
+        MaintenancePhase::Imminent => Some("Swaps are paused: maintenance starts in under 2 minutes"),
+        MaintenancePhase::Down => Some("Swaps are paused: the backend is down for maintenance"),
+        _ => None,
+    }
+}
+
+/// Why schedulers can't sign now, if they can't
+pub fn scheduler_pause_reason(state: &AppState) -> Option<&'static str> {
+    state
+        .maintenance
+        .pauses_schedulers()
+        .then_some("Schedulers are paused for scheduled maintenance")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::maintenance::TRADE_FREEZE;
+    use shared::{MaintenanceWindow, NoticeCategory, NoticeLevel};
+
+    fn test_state() -> AppState {
+        crate::app::App::new().state.read().clone()
+    }
+
+    fn notice(starts_in: i64) -> SystemNotice {
+        let now = chrono::Utc::now().timestamp();
+        SystemNotice {
+            level: NoticeLevel::Warning,
+            title: "Scheduled maintenance".to_string(),
+            message: "Database upgrade".to_string(),
+            timestamp: now,
+            category: NoticeCategory::Maintenance,
+            maintenance: Some(MaintenanceWindow { starts_at: now + starts_in, expected_duration_secs: Some(600) }),
+        }
+    }
+
+    #[test]
+    fn test_notice_blocks_trades_in_the_freeze() {
+        let mut state = test_state();
+        handle_maintenance_notice(&mut state, &notice(3600));
+        assert_eq!(state.maintenance.phase(), MaintenancePhase::Scheduled);
+        assert_eq!(trade_block_reason(&state), None);
+        assert!(state.pending_notifications.is_empty());
+
+        // Before the freeze an outage is a connection error
+        assert!(!handle_backend_unreachable(&mut state));
+
+        let mut state = test_state();
+        handle_maintenance_notice(&mut state, &notice(TRADE_FREEZE / 2));
+        assert!(trade_block_reason(&state).is_some());
+        assert!(scheduler_pause_reason(&state).is_some());
+        assert_eq!(state.pending_notifications.len(), 1);
+
+        assert!(handle_backend_unreachable(&mut state));
+        assert!(trade_block_reason(&state).unwrap().contains("down for maintenance"));
+    }
+
+    #[test]
+    fn test_notices_without_a_window_are_ignored() {
+        let mut state = test_state();
+        let mut plain = notice(60);
+        plain.maintenance = None;
+        handle_maintenance_notice(&mut state, &plain);
+        assert_eq!(state.maintenance.phase(), MaintenancePhase::Normal);
+    }
+}
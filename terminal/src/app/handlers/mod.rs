@@ -2,9 +2,35 @@
 //!
 //! Event handlers organized by domain for better modularity and testability.
 
+pub mod ai_chat;
+pub mod airdrop;
+pub mod annotations;
+pub mod api_keys;
 pub mod auth;
+pub mod candles;
+pub mod commands;
+pub mod idle;
+pub mod keystore;
+pub mod live_assets;
+pub mod maintenance;
 pub mod navigation;
+pub mod onramp;
+pub mod portfolio;
+pub mod quote_stream;
+pub mod rebalance;
+pub mod recovery;
+pub mod refresh;
+pub mod risk;
+pub mod search;
+pub mod security;
+pub mod send;
+pub mod share;
+pub mod staking;
 pub mod swap;
+pub mod trade_import;
+pub mod transactions;
+pub mod unlocks;
 pub mod wallet;
+pub mod webhooks;
 pub mod settings;
 
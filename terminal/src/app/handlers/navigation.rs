@@ -11,6 +11,7 @@ use std::sync::Arc;
 /// Internal handler function - use [`crate::app::App::handle_screen_change`] instead.
 pub(crate) fn handle_screen_change(state: Arc<RwLock<AppState>>, screen: Screen) {
     let mut state = state.write();
+    let previous = state.current_screen;
     
     // Check if screen requires authentication
     if AppState::requires_auth(screen) && !state.is_authenticated() {
@@ -20,6 +21,11 @@ pub(crate) fn handle_screen_change(state: Arc<RwLock<AppState>>, screen: Screen)
     } else {
         state.current_screen = screen;
     }
+
+    // A search highlight only applies to the screen it navigated to
+    if state.current_screen != previous {
+        state.search.highlight = None;
+    }
 }
 
 /// Handle swap tab change
@@ -30,57 +36,49 @@ pub(crate) fn handle_swap_tab_change(state: Arc<RwLock<AppState>>, tab: SwapTab)
     state.terminal.swap.active_tab = tab;
 }
 
-/// Navigate to next screen in Tab order (skips protected screens if not authenticated)
+/// Screen after (or before) `current` in Tab order
 ///
-/// Internal handler function - use [`crate::app::App::next_screen`] instead.
-pub(crate) fn next_screen(state: Arc<RwLock<AppState>>) {
-    let mut state = match state.try_write() {
-        Some(guard) => guard,
-        None => {
-            tracing::warn!("Skipped screen navigation - state locked");
-            return;
-        }
-    };
-
-    // Get screens excluding Messaging and Settings (only accessible via nav bar)
-    let all_screens = Screen::all();
-    let screens: Vec<Screen> = all_screens
+/// Messaging and Settings are left out (they are reached from the nav bar);
+/// protected screens are skipped when not authenticated, falling back to Auth
+/// if nothing else is reachable. Used by the main window and by each
+/// secondary window for its own screen.
+pub fn adjacent_screen(current: Screen, forward: bool, is_authenticated: bool) -> Screen {
+    let screens: Vec<Screen> = Screen::all()
         .iter()
         .copied()
         .filter(|&s| s != Screen::Messaging && s != Screen::Settings)
         .collect();
-    
-    let current_idx = screens
-        .iter()
-        .position(|&s| s == state.current_screen)
-        .unwrap_or(0);
-    
-    let is_authenticated = state.is_authenticated();
-    
-    // Find next screen, skipping protected screens if not authenticated
-    let mut next_idx = (current_idx + 1) % screens.len();
-    let mut attempts = 0;
-    while attempts < screens.len() {
-        let screen = screens[next_idx];
-        // Allow if it's a public screen or user is authenticated
-        if !AppState::requires_auth(screen) || is_authenticated {
-            state.current_screen = screen;
-            return;
-        }
-        next_idx = (next_idx + 1) % screens.len();
-        attempts += 1;
-    }
-    
-    // Fallback: if all screens are protected and not authenticated, go to Auth
-    if !is_authenticated {
-        state.current_screen = Screen::Auth;
-    }
+    let len = screens.len();
+    let current_idx = screens.iter().position(|&s| s == current).unwrap_or(0);
+
+    (1..=len)
+        .map(|step| {
+            let idx = if forward {
+                (current_idx + step) % len
+            } else {
+                (current_idx + len - step % len) % len
+            };
+            screens[idx]
+        })
+        .find(|&screen| !AppState::requires_auth(screen) || is_authenticated)
+        .unwrap_or(Screen::Auth)
+}
+
+/// Navigate to next screen in Tab order (skips protected screens if not authenticated)
+///
+/// Internal handler function - use [`crate::app::App::next_screen`] instead.
+pub(crate) fn next_screen(state: Arc<RwLock<AppState>>) {
+    step_screen(state, true);
 }
 
 /// Navigate to previous screen in Tab order (skips protected screens if not authenticated)
 ///
 /// Internal handler function - use [`crate::app::App::previous_screen`] instead.
 pub(crate) fn previous_screen(state: Arc<RwLock<AppState>>) {
+    step_screen(state, false);
+}
+
+fn step_screen(state: Arc<RwLock<AppState>>, forward: bool) {
     let mut state = match state.try_write() {
         Some(guard) => guard,
         None => {
@@ -89,46 +87,30 @@ pub(crate) fn previous_screen(state: Arc<RwLock<AppState>>) {
         }
     };
 
-    // Get screens excluding Messaging and Settings (only accessible via nav bar)
-    let all_screens = Screen::all();
-    let screens: Vec<Screen> = all_screens
-        .iter()
-        .copied()
-        .filter(|&s| s != Screen::Messaging && s != Screen::Settings)
-        .collect();
-    
-    let current_idx = screens
-        .iter()
-        .position(|&s| s == state.current_screen)
-        .unwrap_or(0);
-    
     let is_authenticated = state.is_authenticated();
-    
-    // Find previous screen, skipping protected screens if not authenticated
-    let mut prev_idx = if current_idx == 0 {
-        screens.len() - 1
-    } else {
-        current_idx - 1
-    };
-    let mut attempts = 0;
-    while attempts < screens.len() {
-        let screen = screens[prev_idx];
-        // Allow if it's a public screen or user is authenticated
-        if !AppState::requires_auth(screen) || is_authenticated {
-            state.current_screen = screen;
-            return;
-        }
-        prev_idx = if prev_idx == 0 {
-            screens.len() - 1
-        } else {
-            prev_idx - 1
-        };
-        attempts += 1;
+    state.current_screen = adjacent_screen(state.current_screen, forward, is_authenticated);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjacent_screen_wraps_and_skips_nav_bar_screens() {
+        assert_eq!(adjacent_screen(Screen::Tokens, true, true), Screen::AIChat);
+        assert_eq!(adjacent_screen(Screen::AIChat, false, true), Screen::Tokens);
+        assert_eq!(adjacent_screen(Screen::AIChat, true, true), Screen::LiveChart);
+        assert_eq!(adjacent_screen(Screen::LiveTable, true, true), Screen::Landing);
+        assert_eq!(adjacent_screen(Screen::Landing, false, true), Screen::LiveTable);
     }
-    
-    // Fallback: if all screens are protected and not authenticated, go to Auth
-    if !is_authenticated {
-        state.current_screen = Screen::Auth;
+
+    #[test]
+    fn test_adjacent_screen_skips_protected_when_logged_out() {
+        assert_eq!(adjacent_screen(Screen::Auth, true, false), Screen::LiveChart);
+        for &screen in Screen::all() {
+            for forward in [true, false] {
+                assert!(!AppState::requires_auth(adjacent_screen(screen, forward, false)));
+            }
+        }
     }
 }
-
@@ -0,0 +1,206 @@
+//! # On-Ramp Handlers
+//!
+//! The Wallet screen's "Buy SOL" dialog: lists the backend's on-ramp
+//! providers for the chosen region and opens a provider's checkout in the
+//! browser with the active wallet filled in.
+//!
+//! Nothing is paid through the terminal. Once the provider sends the SOL,
+//! the wallet's account subscription picks up the new balance.
+
+use crate::app::events::AppEvent;
+use crate::app::state::{AppState, OnRampState};
+use crate::app::usage;
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::onramp::{OnRampProvider, OnRampProvidersResponse};
+use shared::dto::telemetry::{UsageEvent, UsageFeature};
+use std::sync::Arc;
+
+/// Amount typed into the dialog; empty means "let the provider ask"
+pub fn parse_amount(input: &str) -> Result<Option<f64>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    match input.parse::<f64>() {
+        Ok(amount) if amount.is_finite() && amount > 0.0 => Ok(Some(amount)),
+        _ => Err("Amount must be a positive number".to_string()),
+    }
+}
+
+/// Regions offered in the dialog's picker, sorted
+pub fn region_choices(providers: &[OnRampProvider]) -> Vec<String> {
+    let mut regions: Vec<String> = providers.iter().flat_map(|p| p.regions.iter().cloned()).collect();
+    regions.sort();
+    regions.dedup();
+    regions
+}
+
+/// Providers serving the picked region, all of them without one
+pub fn visible_providers(onramp: &OnRampState) -> impl Iterator<Item = &OnRampProvider> {
+    onramp
+        .providers
+        .iter()
+        .filter(|p| onramp.region.is_empty() || p.supports_region(&onramp.region))
+}
+
+/// Checkout URL of `provider_id` for the wallet at `address`
+pub fn checkout_url(onramp: &OnRampState, provider_id: &str, address: &str) -> Result<String, String> {
+    let provider = onramp
+        .providers
+        .iter()
+        .find(|p| p.id == provider_id)
+        .ok_or_else(|| "Provider is no longer available".to_string())?;
+    let amount = parse_amount(&onramp.amount)?;
+    provider
+        .checkout_url(address, amount)
+        .map_err(|e| format!("Can't open {}: {}", provider.name, e))
+}
+
+/// Handle the "Buy SOL" button: open the dialog and load the providers
+///
+/// Internal handler function - use [`crate::app::App::handle_onramp_open`] instead.
+pub(crate) fn handle_onramp_open(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    usage::capture(UsageEvent::Feature(UsageFeature::BuySol));
+    let api_client = {
+        let mut state = state.write();
+        let onramp = &mut state.onramp;
+        onramp.open = true;
+        onramp.opened = None;
+        onramp.error = None;
+        if onramp.loading {
+            return;
+        }
+        let Some(api_client) = state.api_client.clone() else {
+            state.onramp.error = Some("Not connected to a server".to_string());
+            return;
+        };
+        state.onramp.loading = true;
+        api_client
+    };
+
+    tokio::spawn(async move {
+        let result = api_client.get_onramp_providers().await.map_err(|e| e.to_string());
+        let _ = event_tx.send(AppEvent::OnRampProvidersResult(result)).await;
+    });
+}
+
+/// Store the fetched providers
+pub(crate) fn apply_onramp_providers(onramp: &mut OnRampState, result: Result<OnRampProvidersResponse, String>) {
+    onramp.loading = false;
+    match result {
+        Ok(response) => {
+            onramp.providers = response.providers;
+            onramp.error = None;
+            // A region no provider lists any more would hide them all
+            if !onramp.region.is_empty() && !region_choices(&onramp.providers).contains(&onramp.region) {
+                onramp.region.clear();
+            }
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "Failed to fetch on-ramp providers");
+            onramp.error = Some(err);
+        }
+    }
+}
+
+/// Handle a provider's "Buy" button: open its checkout in the browser
+///
+/// Internal handler function - use [`crate::app::App::handle_onramp_buy`] instead.
+pub(crate) fn handle_onramp_buy(state: Arc<RwLock<AppState>>, provider_id: String) {
+    let url = {
+        let mut state = state.write();
+        let Some(address) = state.wallet.as_ref().map(|w| w.address.clone()) else {
+            state.onramp.error = Some("Connect a wallet first".to_string());
+            return;
+        };
+        match checkout_url(&state.onramp, &provider_id, &address) {
+            Ok(url) => url,
+            Err(err) => {
+                state.onramp.error = Some(err);
+                return;
+            }
+        }
+    };
+
+    // Lock released: the browser can take a moment to come up
+    let result = open::that(&url);
+    let mut state = state.write();
+    match result {
+        Ok(()) => {
+            tracing::info!(provider = %provider_id, "Opened on-ramp checkout");
+            let name = state.onramp.providers.iter().find(|p| p.id == provider_id).map(|p| p.name.clone());
+            state.onramp.opened = name;
+            state.onramp.error = None;
+        }
+        Err(e) => {
+            tracing::error!(provider = %provider_id, error = %e, "Failed to open on-ramp checkout");
+            state.onramp.error = Some(format!("Couldn't open the browser, visit {} manually", url));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: &str = "8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL";
+
+    fn provider(id: &str, regions: &[&str]) -> OnRampProvider {
+        OnRampProvider {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            url_template: format!("https://{}.example/?wallet={{address}}&usd={{amount}}", id),
+            regions: regions.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    fn onramp() -> OnRampState {
+        OnRampState {
+            providers: vec![provider("global", &[]), provider("us", &["US"]), provider("eu", &["DE", "FR"])],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount(""), Ok(None));
+        assert_eq!(parse_amount(" 150 "), Ok(Some(150.0)));
+        assert!(parse_amount("0").is_err());
+        assert!(parse_amount("-20").is_err());
+        assert!(parse_amount("100&x=1").is_err());
+    }
+
+    #[test]
+    fn test_region_filtering() {
+        let mut onramp = onramp();
+        assert_eq!(region_choices(&onramp.providers), ["DE", "FR", "US"]);
+
+        let ids = |onramp: &OnRampState| visible_providers(onramp).map(|p| p.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&onramp), ["global", "us", "eu"]);
+        onramp.region = "FR".to_string();
+        assert_eq!(ids(&onramp), ["global", "eu"]);
+
+        // Refetched list without France: back to everything
+        apply_onramp_providers(&mut onramp, Ok(OnRampProvidersResponse { providers: vec![provider("us", &["US"])] }));
+        assert!(onramp.region.is_empty());
+        assert_eq!(ids(&onramp), ["us"]);
+    }
+
+    #[test]
+    fn test_checkout_url() {
+        let mut onramp = onramp();
+        onramp.amount = "75".to_string();
+        assert_eq!(
+            checkout_url(&onramp, "eu", WALLET).unwrap(),
+            format!("https://eu.example/?wallet={}&usd=75", WALLET)
+        );
+
+        onramp.amount.clear();
+        assert_eq!(checkout_url(&onramp, "eu", WALLET).unwrap(), format!("https://eu.example/?wallet={}", WALLET));
+
+        onramp.amount = "lots".to_string();
+        assert!(checkout_url(&onramp, "eu", WALLET).is_err());
+        assert!(checkout_url(&onramp, "gone", WALLET).is_err());
+    }
+}
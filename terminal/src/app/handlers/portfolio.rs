@@ -0,0 +1,403 @@
+//! # Portfolio Handlers
+//!
+//! Portfolio valuation from wallet balances and live prices, plus the daily
+//! snapshot history used for the portfolio sparkline.
+//!
+//! Balances of every wallet connected during the session are kept, so the
+//! Portfolio screen can show one wallet or all of them combined.
+//!
+//! The screen's "Monthly report" menu downloads the backend's printable PDF
+//! report for a month and saves it where the user picks.
+
+use crate::app::state::{AppState, PortfolioHolding, PortfolioSnapshot, PortfolioState, PriceData, WalletState};
+use crate::core::store::{store, StoreError};
+use parking_lot::RwLock;
+use shared::dto::reports::ReportMonth;
+use std::sync::Arc;
+
+/// Number of daily snapshots kept for the history sparkline
+pub const SNAPSHOT_HISTORY_DAYS: usize = 30;
+
+/// Minimum interval between snapshot writes for the current day
+const SNAPSHOT_SAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Load portfolio snapshot history from the local database
+pub fn load_snapshots() -> Vec<PortfolioSnapshot> {
+    match store().portfolio_history().load() {
+        Ok(snapshots) => {
+            tracing::info!(count = snapshots.len(), "Loaded portfolio history");
+            snapshots
+        }
+        Err(e) => {
+            tracing::warn!("Failed to load portfolio history: {}. Starting empty.", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Save portfolio snapshot history to the local database
+pub fn save_snapshots(snapshots: &[PortfolioSnapshot]) -> Result<(), StoreError> {
+    store().portfolio_history().replace(snapshots)?;
+    tracing::debug!(count = snapshots.len(), "Saved portfolio history");
+    Ok(())
+}
+
+/// Build holdings and totals from a wallet and the current price list
+pub fn compute_portfolio(wallet: &WalletState, prices: &[PriceData]) -> (Vec<PortfolioHolding>, f64, f64) {
+    compute_combined_portfolio(&[wallet], prices)
+}
+
+/// Build holdings and totals summed over `wallets`
+///
+/// Each holding lists how much of it every wallet holds, so the combined
+/// view can show whose it is.
+pub fn compute_combined_portfolio(wallets: &[&WalletState], prices: &[PriceData]) -> (Vec<PortfolioHolding>, f64, f64) {
+    let find_price = |symbol: &str| prices.iter().find(|p| p.symbol == symbol);
+
+    // Per symbol, in first-seen order: amount, cached USD value, amount per wallet
+    let mut positions: Vec<(String, f64, f64, Vec<(String, f64)>)> = Vec::new();
+    for wallet in wallets {
+        let balances = std::iter::once(("SOL", wallet.sol_balance, 0.0)).chain(
+            wallet
+                .token_balances
+                .iter()
+                .filter(|balance| balance.symbol != "SOL")
+                .map(|balance| (balance.symbol.as_str(), balance.amount, balance.usd_value)),
+        );
+        for (symbol, amount, usd_value) in balances {
+            if amount <= 0.0 {
+                continue;
+            }
+            let share = (wallet.address.clone(), amount);
+            match positions.iter_mut().find(|position| position.0 == symbol) {
+                Some(position) => {
+                    position.1 += amount;
+                    position.2 += usd_value;
+                    position.3.push(share);
+                }
+                None => positions.push((symbol.to_string(), amount, usd_value, vec![share])),
+            }
+        }
+    }
+
+    let mut holdings: Vec<PortfolioHolding> = positions
+        .into_iter()
+        .map(|(symbol, amount, usd_value, wallets)| PortfolioHolding {
+            wallets,
+            ..build_holding(&symbol, amount, find_price(&symbol), usd_value)
+        })
+        .collect();
+
+    let total_value: f64 = holdings.iter().map(|h| h.value).sum();
+    let change_24h_value: f64 = holdings.iter().map(|h| h.change_24h_value).sum();
+
+    if total_value > 0.0 {
+        for holding in holdings.iter_mut() {
+            holding.allocation_pct = holding.value / total_value * 100.0;
+        }
+    }
+
+    holdings.sort_by(|a, b| b.value.partial_cmp(&a.value).unwrap_or(std::cmp::Ordering::Equal));
+
+    (holdings, total_value, change_24h_value)
+}
+
+/// Build a single holding, falling back to the cached USD value when no live price exists
+fn build_holding(symbol: &str, amount: f64, price: Option<&PriceData>, fallback_value: f64) -> PortfolioHolding {
+    let (value, change_24h_pct) = match price {
+        Some(p) => (amount * p.price, p.change_24h),
+        None => (fallback_value, 0.0),
+    };
+
+    // Value 24h ago = value / (1 + change%), so the delta is the difference
+    let change_24h_value = if change_24h_pct > -100.0 {
+        value - value / (1.0 + change_24h_pct / 100.0)
+    } else {
+        0.0
+    };
+
+    PortfolioHolding {
+        symbol: symbol.to_string(),
+        amount,
+        price: price.map(|p| p.price),
+        value,
+        allocation_pct: 0.0,
+        change_24h_pct,
+        change_24h_value,
+        wallets: Vec::new(),
+    }
+}
+
+/// Record today's total value, returning true when a new day was appended
+pub fn record_snapshot(snapshots: &mut Vec<PortfolioSnapshot>, date: &str, total_value: f64) -> bool {
+    let appended = match snapshots.last_mut() {
+        Some(last) if last.date == date => {
+            last.total_value = total_value;
+            false
+        }
+        _ => {
+            snapshots.push(PortfolioSnapshot {
+                date: date.to_string(),
+                total_value,
+            });
+            true
+        }
+    };
+
+    if snapshots.len() > SNAPSHOT_HISTORY_DAYS {
+        let excess = snapshots.len() - SNAPSHOT_HISTORY_DAYS;
+        snapshots.drain(..excess);
+    }
+
+    appended
+}
+
+/// Holdings and totals the Portfolio screen shows for its wallet filter
+///
+/// The connected wallet alone is what [`recompute_portfolio`] keeps in
+/// `state.portfolio`; other wallets come from their last known balances.
+pub fn visible_portfolio(state: &AppState) -> (Vec<PortfolioHolding>, f64, f64) {
+    let portfolio = &state.portfolio;
+    let wallets: Vec<&WalletState> = portfolio
+        .wallets
+        .values()
+        .filter(|wallet| portfolio.wallet_filter.matches(Some(&wallet.address)))
+        .collect();
+    let only_connected = match (wallets.as_slice(), &state.wallet) {
+        ([wallet], Some(connected)) => wallet.address == connected.address,
+        _ => false,
+    };
+    if only_connected {
+        return (portfolio.holdings.clone(), portfolio.total_value, portfolio.change_24h_value);
+    }
+    compute_combined_portfolio(&wallets, &state.terminal.prices.load())
+}
+
+/// 24h change as a percentage of the value a day ago
+pub fn change_24h_pct(total_value: f64, change_24h_value: f64) -> f64 {
+    let previous_value = total_value - change_24h_value;
+    if previous_value > 0.0 {
+        change_24h_value / previous_value * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Recompute portfolio state from the current wallet and prices
+///
+/// Returns the snapshot history when it should be persisted. The caller is expected
+/// to release the state lock before writing it with [`save_snapshots`].
+pub(crate) fn recompute_portfolio(state: &mut AppState) -> Option<Vec<PortfolioSnapshot>> {
+    let Some(wallet) = &state.wallet else {
+        let snapshots = std::mem::take(&mut state.portfolio.snapshots);
+        let last_snapshot_save = state.portfolio.last_snapshot_save;
+        let rebalance = std::mem::take(&mut state.portfolio.rebalance);
+        state.portfolio = PortfolioState {
+            snapshots,
+            last_snapshot_save,
+            rebalance,
+            ..PortfolioState::default()
+        };
+        return None;
+    };
+
+    let (holdings, total_value, change_24h_value) = compute_portfolio(wallet, &state.terminal.prices.load());
+
+    let portfolio = &mut state.portfolio;
+    portfolio.wallets.insert(wallet.address.clone(), wallet.clone());
+    portfolio.holdings = holdings;
+    portfolio.total_value = total_value;
+    portfolio.change_24h_value = change_24h_value;
+    portfolio.change_24h_pct = change_24h_pct(total_value, change_24h_value);
+
+    // Don't record an empty valuation before prices have arrived
+    if total_value <= 0.0 {
+        return None;
+    }
+
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let appended = record_snapshot(&mut portfolio.snapshots, &today, total_value);
+    let save_due = portfolio
+        .last_snapshot_save
+        .map(|t| t.elapsed() >= SNAPSHOT_SAVE_INTERVAL)
+        .unwrap_or(true);
+
+    if appended || save_due {
+        portfolio.last_snapshot_save = Some(std::time::Instant::now());
+        Some(portfolio.snapshots.clone())
+    } else {
+        None
+    }
+}
+
+/// Ask where to save the monthly report for `month`, then download it there
+pub(crate) fn handle_monthly_report_download(state: Arc<RwLock<AppState>>, month: ReportMonth) {
+    let (api_client, token) = {
+        let state = state.read();
+        let (Some(api_client), Some(token)) = (state.api_client.clone(), state.auth_token.clone()) else {
+            return;
+        };
+        if state.portfolio.report_pending {
+            return;
+        }
+        (api_client, token)
+    };
+
+    let Some(path) = rfd::FileDialog::new()
+        .set_title(format!("Save report for {}", month.label()))
+        .set_file_name(format!("xforce-report-{}.pdf", month))
+        .add_filter("PDF", &["pdf"])
+        .save_file()
+    else {
+        return;
+    };
+    state.write().portfolio.report_pending = true;
+
+    tokio::spawn(async move {
+        let result = match api_client.download_monthly_report(&token, month).await {
+            Ok(bytes) => tokio::fs::write(&path, bytes).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        let notification = match result {
+            Ok(()) => ("success".to_string(), format!("Saved {} report to {}", month.label(), path.display())),
+            Err(e) => ("error".to_string(), format!("Couldn't download the {} report: {}", month.label(), e)),
+        };
+        let mut state = state.write();
+        state.portfolio.report_pending = false;
+        state.pending_notifications.push(notification);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::TokenBalance;
+
+    fn price(symbol: &str, price: f64, change_24h: f64) -> PriceData {
+        PriceData {
+            symbol: symbol.to_string(),
+            price,
+            change_24h,
+            previous_price: None,
+            source: None,
+            confidence: None,
+            publish_time: None,
+            sources: Default::default(),
+            divergent: false,
+            divergence_pct: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_portfolio_values_and_allocation() {
+        let wallet = WalletState {
+            address: "addr".to_string(),
+            sol_balance: 2.0,
+            token_balances: vec![TokenBalance {
+                symbol: "USDC".to_string(),
+                amount: 100.0,
+                usd_value: 100.0,
+                ..Default::default()
+            }],
+        };
+        let prices = vec![price("SOL", 150.0, 0.0), price("USDC", 1.0, 0.0)];
+
+        let (holdings, total, change) = compute_portfolio(&wallet, &prices);
+
+        assert_eq!(total, 400.0);
+        assert_eq!(change, 0.0);
+        assert_eq!(holdings[0].symbol, "SOL");
+        assert!((holdings[0].allocation_pct - 75.0).abs() < 1e-9);
+        assert!((holdings[1].allocation_pct - 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compute_portfolio_24h_change_and_fallback() {
+        let wallet = WalletState {
+            address: "addr".to_string(),
+            sol_balance: 1.0,
+            token_balances: vec![TokenBalance {
+                symbol: "XYZ".to_string(),
+                amount: 5.0,
+                usd_value: 10.0,
+                ..Default::default()
+            }],
+        };
+        // SOL went from 100 to 110 (+10%)
+        let prices = vec![price("SOL", 110.0, 10.0)];
+
+        let (holdings, total, change) = compute_portfolio(&wallet, &prices);
+
+        assert!((total - 120.0).abs() < 1e-9);
+        assert!((change - 10.0).abs() < 1e-9);
+        let xyz = holdings.iter().find(|h| h.symbol == "XYZ").expect("XYZ holding");
+        assert!(xyz.price.is_none());
+        assert_eq!(xyz.value, 10.0);
+    }
+
+    #[test]
+    fn test_combined_portfolio_sums_wallets() {
+        let usdc = |amount: f64| TokenBalance {
+            symbol: "USDC".to_string(),
+            amount,
+            usd_value: amount,
+            ..Default::default()
+        };
+        let trading = WalletState { address: "trading".to_string(), sol_balance: 1.0, token_balances: vec![usdc(50.0)] };
+        let savings = WalletState { address: "savings".to_string(), sol_balance: 3.0, token_balances: vec![usdc(0.0)] };
+        let prices = vec![price("SOL", 100.0, 0.0), price("USDC", 1.0, 0.0)];
+
+        let (holdings, total, _) = compute_combined_portfolio(&[&trading, &savings], &prices);
+        assert_eq!(total, 450.0);
+        assert_eq!(holdings[0].symbol, "SOL");
+        assert_eq!(holdings[0].amount, 4.0);
+        assert_eq!(holdings[0].wallets, vec![("trading".to_string(), 1.0), ("savings".to_string(), 3.0)]);
+        // An empty balance doesn't put the wallet on the holding
+        assert_eq!(holdings[1].wallets, vec![("trading".to_string(), 50.0)]);
+
+        // A single wallet is the same as the plain valuation
+        let (single, single_total, _) = compute_combined_portfolio(&[&savings], &prices);
+        let (plain, plain_total, _) = compute_portfolio(&savings, &prices);
+        assert_eq!(single_total, plain_total);
+        assert_eq!(single.len(), plain.len());
+    }
+
+    #[test]
+    fn test_visible_portfolio_follows_filter() {
+        use crate::app::wallet_identity::WalletFilter;
+
+        let mut state = crate::app::App::new().state.read().clone();
+        state.terminal.prices.replace(vec![price("SOL", 100.0, 0.0)]);
+        for (address, sol_balance) in [("old", 2.0), ("new", 1.0)] {
+            state.wallet = Some(WalletState { address: address.to_string(), sol_balance, token_balances: Vec::new() });
+            let _ = recompute_portfolio(&mut state);
+        }
+        // The stored valuation is the connected wallet only
+        assert_eq!(state.portfolio.total_value, 100.0);
+        assert_eq!(visible_portfolio(&state).1, 300.0);
+
+        state.portfolio.wallet_filter = WalletFilter::Only("old".to_string());
+        assert_eq!(visible_portfolio(&state).1, 200.0);
+
+        // Disconnecting forgets every wallet
+        state.wallet = None;
+        let _ = recompute_portfolio(&mut state);
+        assert!(state.portfolio.wallets.is_empty());
+    }
+
+    #[test]
+    fn test_record_snapshot_updates_same_day_and_caps_history() {
+        let mut snapshots = Vec::new();
+        assert!(record_snapshot(&mut snapshots, "2025-01-01", 100.0));
+        assert!(!record_snapshot(&mut snapshots, "2025-01-01", 120.0));
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].total_value, 120.0);
+
+        for day in 2..=40 {
+            record_snapshot(&mut snapshots, &format!("2025-01-{:02}", day), day as f64);
+        }
+        assert_eq!(snapshots.len(), SNAPSHOT_HISTORY_DAYS);
+        assert_eq!(snapshots.last().map(|s| s.date.as_str()), Some("2025-01-40"));
+    }
+}
@@ -0,0 +1,189 @@
+//! # Quote Stream Handlers
+//!
+//! Keep the backend's quote stream in step with the swap panel: while the
+//! panel is open with a valid amount (or a swap waits in the confirmation
+//! dialog), the price stream connection subscribes to quotes for exactly that
+//! swap, and streamed quotes replace the one on display.
+//!
+//! The subscription follows the typed amount only once the debounced REST
+//! quote for it is back, so typing "123" doesn't start three upstream pollers.
+
+use crate::app::state::{AppState, SwapConfirmation, SwapState};
+use crate::app::tasks::swap::{amount_lamports, swap_quote};
+use crate::app::{Feature, Screen};
+use shared::dto::market::{QuoteSubscribeMessage, QuoteSubscription, QuoteUnsubscribeMessage, QuoteUpdate};
+use std::time::{Duration, Instant};
+
+/// Age after which a streamed quote is shown as stale; the backend sends one
+/// every 2s while Jupiter answers
+pub(crate) const QUOTE_STALE_AFTER: Duration = Duration::from_secs(6);
+
+/// How current the displayed quote is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum QuoteFreshness {
+    /// Streaming, and the last quote is recent
+    Live(Duration),
+    /// Streaming, but no quote for [`QUOTE_STALE_AFTER`]
+    Stale(Duration),
+    /// Fetched once, not streaming
+    Fetched(Duration),
+}
+
+/// Quote stream the swap panel wants right now
+fn desired_subscription(state: &AppState) -> Option<QuoteSubscription> {
+    let swap = &state.terminal.swap;
+    if let Some(confirmation) = &swap.confirmation {
+        return Some(confirmation_subscription(confirmation));
+    }
+    if state.current_screen != Screen::Terminal || !state.terminal.swap_panel_open || state.idle.is_low_power() {
+        return None;
+    }
+    if !state.backend_health.gates().get(Feature::SwapQuotes).is_usable() || swap.input_mint == swap.output_mint {
+        return None;
+    }
+    Some(QuoteSubscription {
+        input_mint: swap.input_mint.clone(),
+        output_mint: swap.output_mint.clone(),
+        amount: amount_lamports(&swap.amount)?,
+        slippage_bps: swap.slippage_bps,
+    })
+}
+
+fn confirmation_subscription(confirmation: &SwapConfirmation) -> QuoteSubscription {
+    QuoteSubscription {
+        input_mint: confirmation.input_mint.clone(),
+        output_mint: confirmation.output_mint.clone(),
+        amount: confirmation.amount,
+        slippage_bps: confirmation.slippage_bps,
+    }
+}
+
+/// Subscribe, resubscribe or unsubscribe to match the swap panel
+///
+/// Internal handler function - called from [`crate::app::App::on_tick`].
+/// While disconnected only the wanted subscription is recorded; the price
+/// stream sends it when it connects.
+pub(crate) fn sync_quote_stream(state: &mut AppState) {
+    if state.terminal.swap.quote_debounce.is_some() {
+        return;
+    }
+    let desired = desired_subscription(state);
+    if desired == state.terminal.swap.quote_stream {
+        return;
+    }
+    let frame = match &desired {
+        Some(subscription) => serde_json::to_string(&QuoteSubscribeMessage::new(subscription.clone())),
+        None => serde_json::to_string(&QuoteUnsubscribeMessage::new()),
+    };
+    if let (Some(outbox), Ok(frame)) = (&state.price_stream_outbox, frame) {
+        let _ = outbox.try_send(frame);
+    }
+    tracing::debug!(subscription = ?desired, "Quote stream changed");
+    state.terminal.swap.quote_stream = desired;
+}
+
+/// Show a streamed quote, if it is for the swap on screen
+///
+/// A swap waiting in the confirmation dialog takes the quote too, so the
+/// expected output recorded on confirm is the latest one.
+pub(crate) fn handle_quote_update(state: &mut AppState, update: QuoteUpdate) {
+    let swap = &mut state.terminal.swap;
+    if swap.quote_stream.as_ref() != Some(&update.subscription) {
+        // From the stream before the last change
+        return;
+    }
+    let quote = swap_quote(&update.in_amount, &update.out_amount, update.price_impact_pct);
+    if let Some(confirmation) = swap.confirmation.as_mut() {
+        if confirmation_subscription(confirmation) == update.subscription {
+            confirmation.quote = quote.clone();
+        }
+    }
+    let panel_matches = swap.input_mint == update.subscription.input_mint
+        && swap.output_mint == update.subscription.output_mint
+        && swap.slippage_bps == update.subscription.slippage_bps
+        && amount_lamports(&swap.amount) == Some(update.subscription.amount);
+    if panel_matches {
+        swap.quote = Some(quote);
+        swap.quote_loading = false;
+    }
+    swap.quote_updated_at = Some(Instant::now());
+}
+
+/// Freshness of the displayed quote, for the indicator next to it
+pub(crate) fn quote_freshness(swap: &SwapState, now: Instant) -> Option<QuoteFreshness> {
+    swap.quote.as_ref()?;
+    let age = now.saturating_duration_since(swap.quote_updated_at?);
+    Some(match swap.quote_stream {
+        None => QuoteFreshness::Fetched(age),
+        Some(_) if age < QUOTE_STALE_AFTER => QuoteFreshness::Live(age),
+        Some(_) => QuoteFreshness::Stale(age),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn panel_state() -> (AppState, async_channel::Receiver<String>) {
+        let mut state = crate::app::App::new().state.read().clone();
+        let (outbox, frames) = async_channel::unbounded();
+        state.price_stream_outbox = Some(outbox);
+        state.current_screen = Screen::Terminal;
+        state.terminal.swap_panel_open = true;
+        state.terminal.swap.amount = "1.5".to_string();
+        (state, frames)
+    }
+
+    fn update(state: &AppState, out_amount: &str) -> QuoteUpdate {
+        QuoteUpdate {
+            subscription: state.terminal.swap.quote_stream.clone().unwrap(),
+            in_amount: "1500000000".to_string(),
+            out_amount: out_amount.to_string(),
+            price_impact_pct: 0.2,
+            quoted_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_stream_follows_the_swap_panel() {
+        let (mut state, frames) = panel_state();
+        sync_quote_stream(&mut state);
+        let frame: QuoteSubscribeMessage = serde_json::from_str(&frames.try_recv().unwrap()).unwrap();
+        assert_eq!(frame.data.amount, 1_500_000_000);
+        assert_eq!(state.terminal.swap.quote_stream, Some(frame.data));
+
+        // Nothing is sent while nothing changes
+        sync_quote_stream(&mut state);
+        assert!(frames.try_recv().is_err());
+
+        state.terminal.swap.amount = "2".to_string();
+        sync_quote_stream(&mut state);
+        let frame: QuoteSubscribeMessage = serde_json::from_str(&frames.try_recv().unwrap()).unwrap();
+        assert_eq!(frame.data.amount, 2_000_000_000);
+
+        state.terminal.swap_panel_open = false;
+        sync_quote_stream(&mut state);
+        let frame: QuoteUnsubscribeMessage = serde_json::from_str(&frames.try_recv().unwrap()).unwrap();
+        assert_eq!(frame.message_type, QuoteUnsubscribeMessage::TYPE);
+        assert_eq!(state.terminal.swap.quote_stream, None);
+    }
+
+    #[test]
+    fn test_streamed_quotes_replace_the_displayed_one() {
+        let (mut state, _frames) = panel_state();
+        sync_quote_stream(&mut state);
+        let first = update(&state, "210000000000");
+        handle_quote_update(&mut state, first.clone());
+        let quote = state.terminal.swap.quote.clone().unwrap();
+        assert_eq!(quote.output_amount, 210.0);
+        assert!(matches!(quote_freshness(&state.terminal.swap, Instant::now()), Some(QuoteFreshness::Live(_))));
+        let later = Instant::now() + QUOTE_STALE_AFTER;
+        assert!(matches!(quote_freshness(&state.terminal.swap, later), Some(QuoteFreshness::Stale(_))));
+
+        // A quote from the stream before the amount changed is ignored
+        state.terminal.swap.amount = "3".to_string();
+        sync_quote_stream(&mut state);
+        handle_quote_update(&mut state, QuoteUpdate { out_amount: "1".to_string(), ..first });
+        assert_eq!(state.terminal.swap.quote.as_ref().unwrap().output_amount, 210.0);
+    }
+}
@@ -0,0 +1,472 @@
+//! # Rebalance Handlers
+//!
+//! Target allocations per profile and the swaps that bring the portfolio back
+//! to them.
+//!
+//! [`propose_rebalance`] compares the holdings' USD weights against the
+//! targets. Assets within the minimum trade size of their target are left
+//! alone; the rest are paired largest surplus against largest shortfall, so
+//! every swap settles at least one side and `n` unbalanced assets need at most
+//! `n - 1` swaps. Amounts are rounded down to about a cent of the input token.
+//!
+//! A proposal only fetches quotes. "Queue all" puts its swaps on the swap
+//! panel's queue, where each one is reviewed, simulated and signed through the
+//! normal swap flow; nothing is executed from here.
+
+use crate::app::events::AppEvent;
+use crate::app::state::{
+    AppState, PortfolioHolding, QueuedSwap, RebalancePreferences, RebalanceProfile, RebalanceProposal, RebalanceSwap,
+    SwapQuote, TargetAllocation,
+};
+use crate::app::usage;
+use crate::core::service::ApiService;
+use crate::core::store::{store, Document};
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::telemetry::{UsageEvent, UsageFeature};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Profile used while nobody is logged in
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Targets may miss 100% by this much (rounding of typed percentages)
+const TARGET_SUM_TOLERANCE_PCT: f64 = 0.01;
+
+/// Wrapped SOL mint, what quotes for SOL are asked with
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Load the target allocations of every profile
+pub fn load_rebalance_preferences() -> RebalancePreferences {
+    store().settings().load_or_default(Document::Rebalance)
+}
+
+fn save_rebalance_preferences(preferences: &RebalancePreferences) -> Result<(), String> {
+    store().settings().save(Document::Rebalance, preferences).map_err(|e| e.to_string())
+}
+
+/// Profile the targets are kept under: the logged-in user
+pub fn profile_key(state: &AppState) -> String {
+    state
+        .current_user
+        .as_ref()
+        .map(|user| user.username.clone())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// Check that targets name each asset once and add up to 100%
+pub fn validate_targets(targets: &[TargetAllocation]) -> Result<(), String> {
+    if targets.is_empty() {
+        return Err("Add at least one target allocation".to_string());
+    }
+    for (i, target) in targets.iter().enumerate() {
+        if target.symbol.trim().is_empty() {
+            return Err("Every target needs a token symbol".to_string());
+        }
+        if !target.pct.is_finite() || target.pct < 0.0 {
+            return Err(format!("Target for {} must be between 0% and 100%", target.symbol));
+        }
+        if targets[..i].iter().any(|other| other.symbol.eq_ignore_ascii_case(&target.symbol)) {
+            return Err(format!("{} has more than one target", target.symbol));
+        }
+    }
+    let sum: f64 = targets.iter().map(|t| t.pct).sum();
+    if (sum - 100.0).abs() > TARGET_SUM_TOLERANCE_PCT {
+        return Err(format!("Targets add up to {:.2}%, not 100%", sum));
+    }
+    Ok(())
+}
+
+/// Swaps that bring `holdings` to `targets`, largest first
+///
+/// Targets are matched to holdings by symbol, ignoring case; a target for an
+/// asset not held is bought from scratch and a holding without a target is
+/// sold off. Drift and trades smaller than `min_trade_usd` are left alone.
+/// Holdings without a live price are valued at their cached USD value.
+pub fn propose_rebalance(holdings: &[PortfolioHolding], targets: &[TargetAllocation], min_trade_usd: f64) -> Vec<RebalanceSwap> {
+    let total: f64 = holdings.iter().map(|h| h.value).sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+
+    // USD above target per asset; negative when below it
+    let mut excess: BTreeMap<String, f64> = BTreeMap::new();
+    let mut unit_prices: BTreeMap<String, f64> = BTreeMap::new();
+    for holding in holdings {
+        *excess.entry(holding.symbol.clone()).or_default() += holding.value;
+        let unit_price = holding.price.unwrap_or(holding.value / holding.amount);
+        if unit_price.is_finite() && unit_price > 0.0 {
+            unit_prices.insert(holding.symbol.clone(), unit_price);
+        }
+    }
+    for target in targets {
+        let symbol = holdings
+            .iter()
+            .find(|h| h.symbol.eq_ignore_ascii_case(&target.symbol))
+            .map(|h| h.symbol.clone())
+            .unwrap_or_else(|| target.symbol.trim().to_string());
+        *excess.entry(symbol).or_default() -= total * target.pct / 100.0;
+    }
+
+    let min_trade = min_trade_usd.max(0.0);
+    let mut sellers: Vec<(String, f64)> = excess
+        .iter()
+        .filter(|(symbol, usd)| **usd >= min_trade && unit_prices.contains_key(*symbol))
+        .map(|(symbol, usd)| (symbol.clone(), *usd))
+        .collect();
+    let mut buyers: Vec<(String, f64)> = excess
+        .iter()
+        .filter(|(_, usd)| -**usd >= min_trade)
+        .map(|(symbol, usd)| (symbol.clone(), -usd))
+        .collect();
+    let by_size = |a: &(String, f64), b: &(String, f64)| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal);
+    sellers.sort_by(by_size);
+    buyers.sort_by(by_size);
+
+    let mut swaps = Vec::new();
+    let (mut s, mut b) = (0, 0);
+    while s < sellers.len() && b < buyers.len() {
+        let usd = sellers[s].1.min(buyers[b].1);
+        let unit_price = unit_prices[&sellers[s].0];
+        let input_amount = round_amount(usd / unit_price, unit_price);
+        if input_amount * unit_price >= min_trade && input_amount > 0.0 {
+            swaps.push(RebalanceSwap {
+                input_symbol: sellers[s].0.clone(),
+                output_symbol: buyers[b].0.clone(),
+                input_amount,
+                usd_value: input_amount * unit_price,
+            });
+        }
+        sellers[s].1 -= usd;
+        buyers[b].1 -= usd;
+        if sellers[s].1 < min_trade.max(f64::EPSILON) {
+            s += 1;
+        }
+        if buyers[b].1 < min_trade.max(f64::EPSILON) {
+            b += 1;
+        }
+    }
+
+    swaps.sort_by(|a, b| b.usd_value.partial_cmp(&a.usd_value).unwrap_or(std::cmp::Ordering::Equal));
+    swaps
+}
+
+/// Round `amount` down to the decimal where one step is worth about a cent
+/// at `unit_price` (at most 9 decimals, at least whole tokens)
+pub fn round_amount(amount: f64, unit_price: f64) -> f64 {
+    let decimals = (unit_price * 100.0).log10().ceil().clamp(0.0, 9.0) as i32;
+    let scale = 10f64.powi(decimals);
+    // The epsilon keeps 0.3 from flooring to 0.29999
+    ((amount * scale) + 1e-9).floor() / scale
+}
+
+/// Load the current profile's saved targets into the form when the profile
+/// changed (login, logout, first render)
+pub(crate) fn sync_profile(state: &mut AppState) {
+    let profile = profile_key(state);
+    let rebalance = &mut state.portfolio.rebalance;
+    if rebalance.profile.as_deref() == Some(profile.as_str()) {
+        return;
+    }
+    let saved = rebalance.preferences.profiles.get(&profile).cloned().unwrap_or_default();
+    rebalance.targets = saved.targets;
+    rebalance.min_trade_usd = saved.min_trade_usd;
+    rebalance.profile = Some(profile);
+    rebalance.proposals.clear();
+    rebalance.status = None;
+}
+
+/// Handle "Save targets": validate the form and store it for the profile
+///
+/// Internal handler function - use [`crate::app::App::handle_rebalance_save`] instead.
+pub(crate) fn handle_rebalance_save(state: Arc<RwLock<AppState>>) {
+    let preferences = {
+        let mut state = state.write();
+        let profile = profile_key(&state);
+        let rebalance = &mut state.portfolio.rebalance;
+        if let Err(e) = validate_targets(&rebalance.targets) {
+            rebalance.status = Some((true, e));
+            return;
+        }
+        rebalance.preferences.profiles.insert(
+            profile,
+            RebalanceProfile {
+                targets: rebalance.targets.clone(),
+                min_trade_usd: rebalance.min_trade_usd,
+            },
+        );
+        rebalance.preferences.clone()
+    };
+
+    let result = save_rebalance_preferences(&preferences);
+    if let Err(e) = &result {
+        tracing::error!("Failed to save rebalance targets: {}", e);
+    }
+    state.write().portfolio.rebalance.status = Some(match result {
+        Ok(()) => (false, "Targets saved".to_string()),
+        Err(e) => (true, format!("Failed to save targets: {}", e)),
+    });
+}
+
+/// Handle "Propose": compute the swaps and fetch a quote for each
+///
+/// Internal handler function - use [`crate::app::App::handle_rebalance_propose`] instead.
+pub(crate) fn handle_rebalance_propose(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let mut state = state.write();
+    if let Err(e) = validate_targets(&state.portfolio.rebalance.targets) {
+        state.portfolio.rebalance.status = Some((true, e));
+        return;
+    }
+    let portfolio = &state.portfolio;
+    let swaps = propose_rebalance(&portfolio.holdings, &portfolio.rebalance.targets, portfolio.rebalance.min_trade_usd);
+
+    usage::capture(UsageEvent::Feature(UsageFeature::Rebalance));
+    let quotes: Vec<_> = swaps.iter().map(|swap| quote_request(&state, swap)).collect();
+    let api_client = state.api_client.clone();
+    let rebalance = &mut state.portfolio.rebalance;
+    rebalance.generation += 1;
+    let generation = rebalance.generation;
+    rebalance.status = Some((
+        false,
+        match swaps.len() {
+            0 => "Portfolio is within the minimum trade size of its targets".to_string(),
+            1 => "1 swap proposed".to_string(),
+            n => format!("{} swaps proposed", n),
+        },
+    ));
+    rebalance.proposals = swaps
+        .into_iter()
+        .zip(&quotes)
+        .map(|(swap, request)| RebalanceProposal {
+            swap,
+            quote: request.as_ref().err().map(|e| Err(e.clone())),
+        })
+        .collect();
+
+    let Some(api_client) = api_client else {
+        for proposal in rebalance.proposals.iter_mut().filter(|p| p.quote.is_none()) {
+            proposal.quote = Some(Err("Not connected to a server".to_string()));
+        }
+        return;
+    };
+    for (index, request) in quotes.into_iter().enumerate() {
+        let Ok(request) = request else { continue };
+        let api_client = Arc::clone(&api_client);
+        let event_tx = event_tx.clone();
+        tokio::spawn(async move {
+            let result = api_client
+                .get_swap_quote(&request.input_mint, &request.output_mint, request.amount, request.slippage_bps)
+                .await
+                .map_err(|e| e.to_string())
+                .map(|quote| SwapQuote {
+                    input_amount: quote.in_amount.parse().unwrap_or(0.0) / 10f64.powi(request.input_decimals as i32),
+                    output_amount: quote.out_amount.parse().unwrap_or(0.0) / 10f64.powi(request.output_decimals as i32),
+                    price_impact: quote.price_impact_pct,
+                    estimated_fee: 0.000005, // Base network fee, as on the swap panel
+                });
+            let _ = event_tx.send(AppEvent::RebalanceQuoteResult { generation, index, result }).await;
+        });
+    }
+}
+
+/// Handle "Queue all": put the proposed swaps on the swap panel's queue
+///
+/// Internal handler function - use [`crate::app::App::handle_rebalance_queue_all`] instead.
+pub(crate) fn handle_rebalance_queue_all(state: Arc<RwLock<AppState>>) {
+    let mut state = state.write();
+    let queued: Vec<QueuedSwap> = state
+        .portfolio
+        .rebalance
+        .proposals
+        .iter()
+        .map(|proposal| QueuedSwap::from(&proposal.swap))
+        .collect();
+    if queued.is_empty() {
+        return;
+    }
+    let count = queued.len();
+    state.terminal.swap.queue.extend(queued);
+    state.terminal.swap_panel_open = true;
+    state.pending_notifications.push((
+        "info".to_string(),
+        format!("Queued {} swaps for review on the Terminal swap panel", count),
+    ));
+}
+
+/// What a quote for one proposed swap is asked with
+struct QuoteRequest {
+    input_mint: String,
+    output_mint: String,
+    input_decimals: u8,
+    output_decimals: u8,
+    /// Input amount in base units
+    amount: u64,
+    slippage_bps: u16,
+}
+
+fn quote_request(state: &AppState, swap: &RebalanceSwap) -> Result<QuoteRequest, String> {
+    let (input_mint, input_decimals) =
+        resolve_mint(state, &swap.input_symbol).ok_or_else(|| format!("Unknown mint for {}", swap.input_symbol))?;
+    let (output_mint, output_decimals) =
+        resolve_mint(state, &swap.output_symbol).ok_or_else(|| format!("Unknown mint for {}", swap.output_symbol))?;
+    Ok(QuoteRequest {
+        input_mint,
+        output_mint,
+        input_decimals,
+        output_decimals,
+        amount: (swap.input_amount * 10f64.powi(input_decimals as i32)).round() as u64,
+        slippage_bps: state.terminal.swap.slippage_bps,
+    })
+}
+
+/// Mint and decimals of a symbol, from the wallet's accounts or the token list
+fn resolve_mint(state: &AppState, symbol: &str) -> Option<(String, u8)> {
+    if symbol.eq_ignore_ascii_case("SOL") {
+        return Some((WSOL_MINT.to_string(), 9));
+    }
+    let held = state
+        .wallet
+        .as_ref()
+        .and_then(|wallet| wallet.token_balances.iter().find(|b| b.symbol.eq_ignore_ascii_case(symbol)))
+        .filter(|balance| !balance.mint.is_empty())
+        .map(|balance| (balance.mint.clone(), balance.decimals));
+    held.or_else(|| {
+        state
+            .terminal
+            .swap
+            .token_list
+            .iter()
+            .find(|token| token.symbol.eq_ignore_ascii_case(symbol))
+            .map(|token| (token.mint.clone(), token.decimals))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(symbol: &str, amount: f64, price: f64) -> PortfolioHolding {
+        PortfolioHolding {
+            symbol: symbol.to_string(),
+            amount,
+            price: Some(price),
+            value: amount * price,
+            allocation_pct: 0.0,
+            change_24h_pct: 0.0,
+            change_24h_value: 0.0,
+            wallets: Vec::new(),
+        }
+    }
+
+    fn targets(pcts: &[(&str, f64)]) -> Vec<TargetAllocation> {
+        pcts.iter()
+            .map(|(symbol, pct)| TargetAllocation {
+                symbol: symbol.to_string(),
+                pct: *pct,
+            })
+            .collect()
+    }
+
+    /// USD value per symbol after applying the swaps at the holdings' prices
+    fn values_after(holdings: &[PortfolioHolding], swaps: &[RebalanceSwap]) -> BTreeMap<String, f64> {
+        let mut values: BTreeMap<String, f64> = holdings.iter().map(|h| (h.symbol.clone(), h.value)).collect();
+        for swap in swaps {
+            *values.entry(swap.input_symbol.clone()).or_default() -= swap.usd_value;
+            *values.entry(swap.output_symbol.clone()).or_default() += swap.usd_value;
+        }
+        values
+    }
+
+    #[test]
+    fn test_propose_rebalance_moves_surplus_to_shortfall() {
+        // $1000: SOL 60%, USDC 20%, JUP 20% against 50 / 30 / 20
+        let holdings = vec![holding("SOL", 4.0, 150.0), holding("USDC", 200.0, 1.0), holding("JUP", 400.0, 0.5)];
+        let swaps = propose_rebalance(&holdings, &targets(&[("SOL", 50.0), ("usdc", 30.0), ("JUP", 20.0)]), 10.0);
+
+        assert_eq!(swaps.len(), 1);
+        assert_eq!((swaps[0].input_symbol.as_str(), swaps[0].output_symbol.as_str()), ("SOL", "USDC"));
+        assert!((swaps[0].input_amount - 0.66666).abs() < 1e-9);
+        assert!((swaps[0].usd_value - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_min_trade_size_leaves_small_drift_alone() {
+        // SOL is $5 over target, USDC $5 under
+        let holdings = vec![holding("SOL", 505.0 / 150.0, 150.0), holding("USDC", 495.0, 1.0)];
+        let targets = targets(&[("SOL", 50.0), ("USDC", 50.0)]);
+
+        assert!(propose_rebalance(&holdings, &targets, 10.0).is_empty());
+        assert_eq!(propose_rebalance(&holdings, &targets, 1.0).len(), 1);
+    }
+
+    #[test]
+    fn test_swap_set_is_minimal() {
+        // Two assets over target, three under: at most four swaps
+        let holdings = vec![holding("SOL", 4.0, 150.0), holding("BONK", 20_000_000.0, 0.00002)];
+        let targets = targets(&[("SOL", 25.0), ("BONK", 5.0), ("USDC", 40.0), ("JUP", 20.0), ("PYTH", 10.0)]);
+        let swaps = propose_rebalance(&holdings, &targets, 1.0);
+
+        assert!(swaps.len() <= 4, "{:?}", swaps);
+        let after = values_after(&holdings, &swaps);
+        for target in &targets {
+            let want = 1000.0 * target.pct / 100.0;
+            assert!((after[&target.symbol] - want).abs() < 1.0, "{}: {} vs {}", target.symbol, after[&target.symbol], want);
+        }
+        assert!(swaps.windows(2).all(|w| w[0].usd_value >= w[1].usd_value));
+    }
+
+    #[test]
+    fn test_unheld_target_is_bought_and_untargeted_holding_sold() {
+        let holdings = vec![holding("SOL", 1.0, 100.0), holding("DUST", 50.0, 1.0)];
+        let swaps = propose_rebalance(&holdings, &targets(&[("SOL", 50.0), ("JUP", 50.0)]), 1.0);
+        let after = values_after(&holdings, &swaps);
+
+        assert!(after["DUST"].abs() < 0.01);
+        assert!((after["JUP"] - 75.0).abs() < 0.01);
+        assert_eq!(swaps.len(), 2);
+    }
+
+    #[test]
+    fn test_degenerate_portfolios() {
+        let targets = targets(&[("SOL", 50.0), ("USDC", 50.0)]);
+
+        // Nothing held, or nothing worth anything
+        assert!(propose_rebalance(&[], &targets, 10.0).is_empty());
+        assert!(propose_rebalance(&[holding("SOL", 0.0, 150.0)], &targets, 10.0).is_empty());
+
+        // A single asset already at its 100% target needs nothing
+        let single = vec![holding("SOL", 2.0, 150.0)];
+        assert!(propose_rebalance(&single, &[TargetAllocation { symbol: "SOL".to_string(), pct: 100.0 }], 10.0).is_empty());
+
+        // Splitting a single asset in half sells half of it
+        let swaps = propose_rebalance(&single, &targets, 10.0);
+        assert_eq!(swaps.len(), 1);
+        assert_eq!(swaps[0].input_amount, 1.0);
+        assert_eq!(swaps[0].output_symbol, "USDC");
+    }
+
+    #[test]
+    fn test_round_amount() {
+        // $150 SOL: 5 decimals is ~$0.0015 a step
+        assert_eq!(round_amount(0.123456789, 150.0), 0.12345);
+        // $1 stablecoin: cents
+        assert_eq!(round_amount(12.3456, 1.0), 12.34);
+        assert_eq!(round_amount(0.3, 1.0), 0.3);
+        // Sub-cent memecoin: whole tokens
+        assert_eq!(round_amount(1234.9, 0.00002), 1234.0);
+    }
+
+    #[test]
+    fn test_validate_targets() {
+        assert!(validate_targets(&targets(&[("SOL", 50.0), ("USDC", 30.0), ("JUP", 20.0)])).is_ok());
+        assert!(validate_targets(&targets(&[("SOL", 33.33), ("USDC", 33.33), ("JUP", 33.34)])).is_ok());
+        assert!(validate_targets(&[]).is_err());
+        assert_eq!(
+            validate_targets(&targets(&[("SOL", 50.0), ("USDC", 40.0)])),
+            Err("Targets add up to 90.00%, not 100%".to_string())
+        );
+        assert!(validate_targets(&targets(&[("SOL", 50.0), ("sol", 50.0)])).is_err());
+        assert!(validate_targets(&targets(&[("SOL", 120.0), ("USDC", -20.0)])).is_err());
+        assert!(validate_targets(&targets(&[("", 100.0)])).is_err());
+    }
+}
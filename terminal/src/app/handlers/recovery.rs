@@ -0,0 +1,249 @@
+//! # Recovery Card Handlers
+//!
+//! Raise and close the [recovery cards](crate::app::recovery) of the Wallet
+//! and Swap flows, and carry out the action the user picks on one.
+//!
+//! "Switch RPC endpoint" means something different per flow: the wallet
+//! talks to its RPC node directly, so it moves between the configured
+//! endpoint and the cluster's public one for the rest of the session; swaps
+//! go through the backend, so the API client's failover moves on to the next
+//! server in the list, whose RPC node is a different one.
+
+use crate::app::events::AppEvent;
+use crate::app::recovery::{self, RecoveryAction, RecoveryFlow, RecoveryStats};
+use crate::app::search::{SearchTarget, SettingsSection};
+use crate::app::state::AppState;
+use crate::app::tasks;
+use crate::core::store::{store, Document};
+use async_channel::Sender;
+use parking_lot::RwLock;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Load recovery statistics, starting empty when there are none
+pub fn load_stats() -> RecoveryStats {
+    store().settings().load_or_default(Document::RecoveryStats)
+}
+
+/// Show a recovery card for `message` if it is an error we know how to help with
+///
+/// # Returns
+///
+/// False when the message isn't classified; the caller shows it as a plain
+/// notification instead.
+pub(crate) fn raise(state: &mut AppState, flow: RecoveryFlow, message: String) -> bool {
+    let Some(kind) = recovery::classify(&message) else {
+        return false;
+    };
+    tracing::info!(?flow, ?kind, error = %message, "Showing recovery card");
+    state.recovery.raise(flow, kind, message);
+    state.needs_immediate_repaint = true;
+    true
+}
+
+/// The flow just succeeded; close its card and credit the action that fixed it
+pub(crate) fn resolve(state: &mut AppState, flow: RecoveryFlow) {
+    if state.recovery.resolve(flow, Instant::now()) {
+        if let Err(e) = store().settings().save(Document::RecoveryStats, &state.recovery.stats) {
+            tracing::warn!("Failed to save recovery statistics: {}", e);
+        }
+    }
+}
+
+/// Close a card without acting on it
+///
+/// Internal handler function - use [`crate::app::App::handle_recovery_dismiss`] instead.
+pub(crate) fn handle_recovery_dismiss(state: Arc<RwLock<AppState>>, flow: RecoveryFlow) {
+    state.write().recovery.dismiss(flow);
+}
+
+/// Carry out an action from a recovery card
+///
+/// Internal handler function - use [`crate::app::App::handle_recovery_action`] instead.
+pub(crate) fn handle_recovery_action(
+    state: Arc<RwLock<AppState>>,
+    event_tx: Sender<AppEvent>,
+    flow: RecoveryFlow,
+    action: RecoveryAction,
+) {
+    {
+        let mut app_state = state.write();
+        let Some(card) = app_state.recovery.card_mut(flow) else {
+            return;
+        };
+        // An earlier action that neither fixed nor failed anything yet is
+        // superseded without counting against it
+        card.pending = Some((action, Instant::now()));
+        tracing::info!(?flow, kind = ?card.kind, ?action, "Recovery action");
+    }
+
+    match action {
+        RecoveryAction::RetryWithBackoff => schedule_retry(state, event_tx, flow),
+        RecoveryAction::SwitchRpcEndpoint => {
+            let switched = match flow {
+                RecoveryFlow::Wallet => switch_wallet_rpc(&mut state.write()),
+                RecoveryFlow::Swap => switch_backend(&mut state.write()),
+            };
+            if switched {
+                retry(state, event_tx, flow);
+            } else {
+                super::search::handle_search_select(state, SearchTarget::Setting(SettingsSection::Connection));
+            }
+        }
+        RecoveryAction::OpenNetworkSettings => {
+            super::search::handle_search_select(state, SearchTarget::Setting(SettingsSection::Connection));
+        }
+        RecoveryAction::BuySol => super::onramp::handle_onramp_open(state, event_tx),
+    }
+}
+
+/// Run the failed operation again after the card's next backoff delay
+fn schedule_retry(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, flow: RecoveryFlow) {
+    let delay = {
+        let mut app_state = state.write();
+        let Some(card) = app_state.recovery.card_mut(flow) else {
+            return;
+        };
+        let delay = recovery::retry_delay(card.retries);
+        card.retries += 1;
+        card.retry_at = Some(Instant::now() + delay);
+        delay
+    };
+
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        // Dismissed, resolved, or replaced by a newer card while waiting
+        let still_waiting = state
+            .read()
+            .recovery
+            .card(flow)
+            .and_then(|card| card.retry_at)
+            .is_some_and(|at| at <= Instant::now());
+        if still_waiting {
+            if let Some(card) = state.write().recovery.card_mut(flow) {
+                card.retry_at = None;
+            }
+            retry(state, event_tx, flow);
+        }
+    });
+}
+
+/// Start the operation the flow failed on
+fn retry(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, flow: RecoveryFlow) {
+    match flow {
+        RecoveryFlow::Wallet => {
+            let (sol, tokens) = {
+                let app_state = state.read();
+                (
+                    super::wallet::sol_balance_task(&app_state, event_tx.clone()),
+                    super::wallet::token_balances_task(&app_state, event_tx.clone()),
+                )
+            };
+            match (sol, tokens) {
+                (Some(sol), Some(tokens)) => {
+                    tokio::spawn(sol);
+                    tokio::spawn(tokens);
+                }
+                // The connect itself failed
+                _ => super::wallet::handle_wallet_connect_click(state, event_tx),
+            }
+        }
+        RecoveryFlow::Swap => tasks::swap::prepare_swap(state, event_tx),
+    }
+}
+
+/// Move the wallet to the other RPC endpoint of the cluster for this session
+///
+/// Returns false when there is no other endpoint to move to.
+fn switch_wallet_rpc(state: &mut AppState) -> bool {
+    let current = state.config.rpc_endpoint();
+    // Captured once so switching back finds the configured endpoint again;
+    // recaptured after the connection settings change
+    if !state.recovery.rpc_endpoints.contains(&current) {
+        state.recovery.rpc_endpoints = rpc_candidates(&state.config);
+    }
+    let endpoints = &state.recovery.rpc_endpoints;
+    if endpoints.len() < 2 {
+        state.pending_notifications.push((
+            "info".to_string(),
+            "No other RPC endpoint to switch to - set one under Connection".to_string(),
+        ));
+        return false;
+    }
+    let index = endpoints.iter().position(|url| *url == current).unwrap_or(0);
+    let next = endpoints[(index + 1) % endpoints.len()].clone();
+
+    tracing::info!(from = %current, to = %next, "Switching wallet RPC endpoint");
+    // Not saved: the configured endpoint is used again on the next launch
+    state.config.rpc_url = Some(next.clone());
+    super::wallet::rebuild_wallet_service(state, &next);
+    state
+        .pending_notifications
+        .push(("info".to_string(), format!("Wallet RPC switched to {} for this session", next)));
+    true
+}
+
+/// RPC endpoints the wallet can move between: the configured one, then the
+/// cluster's public one
+fn rpc_candidates(config: &crate::core::config::TerminalConfig) -> Vec<String> {
+    let mut candidates = vec![config.rpc_endpoint()];
+    let public = config.network.default_rpc_url().to_string();
+    if !candidates.contains(&public) {
+        candidates.push(public);
+    }
+    candidates
+}
+
+/// Move the API client to the next backend server
+///
+/// Returns false when only one server is configured.
+fn switch_backend(state: &mut AppState) -> bool {
+    let switched = state.api_client.as_ref().and_then(|api_client| api_client.failover().rotate()).is_some();
+    if !switched {
+        state.pending_notifications.push((
+            "info".to_string(),
+            "Only one backend server is configured - add standbys under Servers".to_string(),
+        ));
+    }
+    // The switch itself is announced by the ServerSwitched event
+    switched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::recovery::ErrorKind;
+    use crate::app::App;
+
+    fn test_state() -> AppState {
+        App::new().state.read().clone()
+    }
+
+    #[test]
+    fn test_raise_only_for_known_errors() {
+        let mut state = test_state();
+        assert!(!raise(&mut state, RecoveryFlow::Swap, "Swap failed: Slippage tolerance exceeded".to_string()));
+        assert!(state.recovery.cards.is_empty());
+
+        assert!(raise(&mut state, RecoveryFlow::Swap, "Swap failed: 429 Too Many Requests".to_string()));
+        assert_eq!(state.recovery.card(RecoveryFlow::Swap).unwrap().kind, ErrorKind::RateLimited);
+    }
+
+    #[test]
+    fn test_switch_wallet_rpc_toggles_endpoints() {
+        let mut state = test_state();
+        let public = state.config.network.default_rpc_url().to_string();
+        state.config.rpc_url = Some("https://rpc.example.com".to_string());
+
+        assert!(switch_wallet_rpc(&mut state));
+        assert_eq!(state.config.rpc_endpoint(), public);
+        assert!(switch_wallet_rpc(&mut state));
+        assert_eq!(state.config.rpc_endpoint(), "https://rpc.example.com");
+
+        // Nothing but the public endpoint
+        let mut state = test_state();
+        state.config.rpc_url = None;
+        assert!(!switch_wallet_rpc(&mut state));
+        assert_eq!(state.config.rpc_endpoint(), public);
+    }
+}
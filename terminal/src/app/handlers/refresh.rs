@@ -0,0 +1,219 @@
+//! # Refresh Handlers
+//!
+//! F5 (Cmd/Ctrl+R) reloads what the current screen shows, skipping the
+//! client-side caches and throttles that would otherwise answer from memory.
+//! "Refresh all" in the command palette drops all of them and reloads
+//! everything.
+//!
+//! | Screen               | Reloads                                     |
+//! |----------------------|---------------------------------------------|
+//! | Transactions         | transaction history                         |
+//! | Wallet, Portfolio    | SOL balance and token accounts              |
+//! | Tokens               | token list, SOL balance and token accounts  |
+//! | Terminal, Live Chart | candles of the current timeframe, depth     |
+//! | Live Assets          | analytics and token details                 |
+//!
+//! Reloads run under a [`TaskSupervisor`] keyed by [`RefreshTarget`], so
+//! pressing F5 again while one is in flight doesn't send a second request.
+//! A reload that panics still frees its slot; see [`tasks::guard`].
+
+use crate::app::events::AppEvent;
+use crate::app::preload::{SpawnOutcome, TaskSupervisor};
+use crate::app::state::{AppState, Screen};
+use crate::app::tasks;
+use crate::app::tasks::guard::{guarded, GuardedTask};
+use async_channel::Sender;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Data a refresh can reload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshTarget {
+    TransactionHistory,
+    /// SOL balance and token accounts of the connected wallet
+    WalletBalances,
+    TokenList,
+    /// SOL candles of the chart's current timeframe
+    Candles,
+    /// Depth ladder below the chart; only its throttle is reset, the screen
+    /// asks again on the next frame
+    Depth,
+    /// Live Assets analytics and the cached token details
+    MarketAnalytics,
+}
+
+impl RefreshTarget {
+    pub const ALL: [RefreshTarget; 6] = [
+        RefreshTarget::TransactionHistory,
+        RefreshTarget::WalletBalances,
+        RefreshTarget::TokenList,
+        RefreshTarget::Candles,
+        RefreshTarget::Depth,
+        RefreshTarget::MarketAnalytics,
+    ];
+
+    /// Supervisor key of the target's reload
+    pub fn key(self) -> &'static str {
+        match self {
+            RefreshTarget::TransactionHistory => "transaction_history",
+            RefreshTarget::WalletBalances => "wallet_balances",
+            RefreshTarget::TokenList => "token_list",
+            RefreshTarget::Candles => "candles",
+            RefreshTarget::Depth => "depth",
+            RefreshTarget::MarketAnalytics => "market_analytics",
+        }
+    }
+
+    /// What refreshing `screen` reloads; empty for screens without fetched data
+    pub fn for_screen(screen: Screen) -> &'static [RefreshTarget] {
+        match screen {
+            Screen::Transactions => &[RefreshTarget::TransactionHistory],
+            Screen::Wallet | Screen::Portfolio => &[RefreshTarget::WalletBalances],
+            Screen::Tokens => &[RefreshTarget::TokenList, RefreshTarget::WalletBalances],
+            Screen::Terminal | Screen::LiveChart => &[RefreshTarget::Candles, RefreshTarget::Depth],
+            Screen::LiveAssets => &[RefreshTarget::MarketAnalytics],
+            _ => &[],
+        }
+    }
+}
+
+/// Supervisor for forced reloads, with room for every target at once
+pub(crate) fn refresh_supervisor() -> TaskSupervisor {
+    TaskSupervisor::new(RefreshTarget::ALL.len())
+}
+
+/// Drop what the client keeps for `target`, so the next fetch goes to the backend
+pub(crate) fn invalidate(state: &mut AppState, target: RefreshTarget) {
+    match target {
+        RefreshTarget::Depth => state.terminal.last_depth_fetch = None,
+        RefreshTarget::MarketAnalytics => {
+            state.live_assets.last_analytics_fetch = None;
+            state.live_assets.last_sparkline_fetch = None;
+            state.live_assets.details.clear();
+        }
+        // Fetched on demand and never cached
+        RefreshTarget::TransactionHistory
+        | RefreshTarget::WalletBalances
+        | RefreshTarget::TokenList
+        | RefreshTarget::Candles => {}
+    }
+}
+
+/// Handle F5 on `screen`: invalidate and reload what it shows
+///
+/// Returns the number of reloads started; targets already reloading are left
+/// to finish.
+///
+/// Internal handler function - use [`crate::app::App::refresh`] instead.
+pub(crate) fn handle_refresh(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, screen: Screen) -> usize {
+    let targets = RefreshTarget::for_screen(screen);
+    if targets.is_empty() {
+        tracing::debug!(screen = ?screen, "Nothing to refresh on this screen");
+        return 0;
+    }
+    let started = refresh_targets(&state, &event_tx, targets);
+    if started > 0 {
+        state
+            .write()
+            .pending_notifications
+            .push(("info".to_string(), format!("Refreshing {}", screen.title())));
+    }
+    started
+}
+
+/// Handle "Refresh all": clear every client cache and reload everything
+///
+/// Internal handler function - use [`crate::app::App::refresh_all`] instead.
+pub(crate) fn handle_refresh_all(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) -> usize {
+    let started = refresh_targets(&state, &event_tx, &RefreshTarget::ALL);
+    state
+        .write()
+        .pending_notifications
+        .push(("info".to_string(), "Caches cleared, reloading everything".to_string()));
+    started
+}
+
+/// Invalidate and reload `targets`, returning the number of reloads started
+pub(crate) fn refresh_targets(state: &Arc<RwLock<AppState>>, event_tx: &Sender<AppEvent>, targets: &[RefreshTarget]) -> usize {
+    let mut guard = state.write();
+    let mut started = 0;
+    for &target in targets {
+        invalidate(&mut guard, target);
+        if guard.refresh_tasks.is_running(target.key()) {
+            tracing::debug!(refresh = target.key(), "Refresh already in flight");
+            continue;
+        }
+        let Some(task) = reload_task(&mut guard, event_tx.clone(), target) else {
+            continue;
+        };
+
+        let key = target.key();
+        let supervised = {
+            let state = Arc::clone(state);
+            let work = {
+                let state = Arc::clone(&state);
+                async move {
+                    task.await;
+                    state.write().refresh_tasks.finish(key);
+                }
+            };
+            guarded(GuardedTask::Refresh(target), state, event_tx.clone(), work)
+        };
+        if guard.refresh_tasks.spawn(key, supervised) == SpawnOutcome::Started {
+            tracing::debug!(refresh = key, "Refresh started");
+            started += 1;
+        }
+    }
+    started
+}
+
+/// Fetch reloading `target`, `None` when there is nothing to fetch (no
+/// wallet, no API client, or the screen fetches by itself)
+fn reload_task(state: &mut AppState, event_tx: Sender<AppEvent>, target: RefreshTarget) -> Option<BoxFuture<'static, ()>> {
+    use super::{transactions, wallet};
+
+    match target {
+        RefreshTarget::TransactionHistory => transactions::history_task(state, event_tx).map(FutureExt::boxed),
+        RefreshTarget::WalletBalances => {
+            let sol = wallet::sol_balance_task(state, event_tx.clone());
+            let tokens = wallet::token_balances_task(state, event_tx);
+            if sol.is_none() && tokens.is_none() {
+                return None;
+            }
+            Some(
+                async move {
+                    let sol = async move {
+                        if let Some(sol) = sol {
+                            sol.await;
+                        }
+                    };
+                    let tokens = async move {
+                        if let Some(tokens) = tokens {
+                            tokens.await;
+                        }
+                    };
+                    futures::join!(sol, tokens);
+                }
+                .boxed(),
+            )
+        }
+        RefreshTarget::TokenList => tasks::market::token_list_task(state, event_tx).map(FutureExt::boxed),
+        RefreshTarget::Candles => {
+            let timeframe = state.terminal.chart_timeframe;
+            let task = tasks::market::candles_task(state, event_tx, state.terminal.chart_symbol.clone(), timeframe)?;
+            state.terminal.chart_loading = true;
+            Some(task.boxed())
+        }
+        RefreshTarget::Depth => None,
+        RefreshTarget::MarketAnalytics => {
+            // The screen asks for analytics every frame; only the open token needs a fetch
+            let symbol = state.live_assets.selected.clone()?;
+            let api_client = state.api.clone()?;
+            state.live_assets.detail_loading = true;
+            state.live_assets.detail_error = None;
+            Some(tasks::market::load_token_detail(api_client, event_tx, symbol, false).boxed())
+        }
+    }
+}
@@ -0,0 +1,390 @@
+//! # Risk Handlers
+//!
+//! Exposure analysis of the portfolio: how concentrated it is and how hard
+//! its positions would be to exit.
+//!
+//! [`compute_exposure`] weighs each holding against the total value and against
+//! its token's 24h traded volume, and [`risk_warnings`] turns the metrics into
+//! badges once they cross the [`RiskThresholds`] set under Settings > Risk.
+//! Concentration is measured two ways:
+//!
+//! - **Largest position**: the share of the single biggest asset
+//! - **Herfindahl index**: the sum of squared percentage weights, from near 0
+//!   for many equal positions to 10,000 for everything in one asset
+//!
+//! The swap confirmation dialog asks [`swap_concentration_warning`] whether the
+//! pending swap would push its output asset past the position limit.
+
+use crate::app::state::{AppState, PortfolioHolding, RiskThresholds, TokenInfo};
+use crate::core::store::{store, Document};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Herfindahl index of a portfolio held in a single asset
+pub const MAX_HHI: f64 = 10_000.0;
+
+/// One holding's share of the portfolio and of its market
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionExposure {
+    pub symbol: String,
+    pub value: f64,
+    /// Share of total portfolio value in percent (0-100)
+    pub weight_pct: f64,
+    /// Token's traded volume over the last 24h in USD, when known
+    pub daily_volume: Option<f64>,
+    /// Position value divided by the daily volume
+    pub volume_multiple: Option<f64>,
+}
+
+/// Concentration and liquidity metrics of a portfolio
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExposureMetrics {
+    pub total_value: f64,
+    /// Positions largest first
+    pub positions: Vec<PositionExposure>,
+    /// Herfindahl index over the percentage weights (0-10,000)
+    pub hhi: f64,
+}
+
+impl ExposureMetrics {
+    /// Largest position, if anything is held
+    pub fn largest(&self) -> Option<&PositionExposure> {
+        self.positions.first()
+    }
+}
+
+/// What a warning is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskKind {
+    /// One asset holds too much of the portfolio
+    Concentration,
+    /// The portfolio as a whole is spread over too few assets
+    Diversification,
+    /// A position is large compared to what its token trades in a day
+    Liquidity,
+}
+
+impl RiskKind {
+    /// Short label for the badge
+    pub fn badge(&self) -> &'static str {
+        match self {
+            RiskKind::Concentration => "Concentrated",
+            RiskKind::Diversification => "Undiversified",
+            RiskKind::Liquidity => "Illiquid",
+        }
+    }
+}
+
+/// A crossed risk threshold with its explanation
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskWarning {
+    pub kind: RiskKind,
+    /// Asset the warning is about; `None` for portfolio-wide warnings
+    pub symbol: Option<String>,
+    pub explanation: String,
+}
+
+/// 24h volumes by uppercase symbol from the token list
+///
+/// Scam tokens reuse well-known symbols, so a verified token's volume wins
+/// over an unverified one with the same symbol.
+pub fn daily_volumes(tokens: &[TokenInfo]) -> HashMap<String, f64> {
+    let mut volumes: HashMap<String, (bool, f64)> = HashMap::new();
+    for token in tokens {
+        let Some(volume) = token.daily_volume.filter(|v| v.is_finite() && *v >= 0.0) else {
+            continue;
+        };
+        let symbol = token.symbol.to_uppercase();
+        match volumes.get(&symbol) {
+            Some((verified, _)) if *verified || !token.verified => {}
+            _ => {
+                volumes.insert(symbol, (token.verified, volume));
+            }
+        }
+    }
+    volumes.into_iter().map(|(symbol, (_, volume))| (symbol, volume)).collect()
+}
+
+/// Weigh each holding against the portfolio and its token's 24h volume
+///
+/// `volumes` is keyed by uppercase symbol, as built by [`daily_volumes`].
+/// Holdings worth nothing are left out; a token with zero volume has an
+/// infinite volume multiple.
+pub fn compute_exposure(holdings: &[PortfolioHolding], volumes: &HashMap<String, f64>) -> ExposureMetrics {
+    let total_value: f64 = holdings.iter().map(|h| h.value).filter(|v| *v > 0.0).sum();
+    if total_value <= 0.0 {
+        return ExposureMetrics::default();
+    }
+
+    let mut positions: Vec<PositionExposure> = holdings
+        .iter()
+        .filter(|h| h.value > 0.0)
+        .map(|holding| {
+            let daily_volume = volumes.get(&holding.symbol.to_uppercase()).copied();
+            let volume_multiple = daily_volume.map(|volume| {
+                if volume > 0.0 {
+                    holding.value / volume
+                } else {
+                    f64::INFINITY
+                }
+            });
+            PositionExposure {
+                symbol: holding.symbol.clone(),
+                value: holding.value,
+                weight_pct: holding.value / total_value * 100.0,
+                daily_volume,
+                volume_multiple,
+            }
+        })
+        .collect();
+    positions.sort_by(|a, b| b.value.partial_cmp(&a.value).unwrap_or(std::cmp::Ordering::Equal));
+
+    let hhi = positions.iter().map(|p| p.weight_pct * p.weight_pct).sum();
+
+    ExposureMetrics { total_value, positions, hhi }
+}
+
+/// Warnings for every threshold the metrics cross, portfolio-wide ones first
+pub fn risk_warnings(metrics: &ExposureMetrics, thresholds: &RiskThresholds) -> Vec<RiskWarning> {
+    let mut warnings = Vec::new();
+
+    if metrics.hhi > thresholds.max_hhi {
+        warnings.push(RiskWarning {
+            kind: RiskKind::Diversification,
+            symbol: None,
+            explanation: format!(
+                "Herfindahl index is {:.0} (limit {:.0}); that is like holding only {:.1} equally sized assets",
+                metrics.hhi,
+                thresholds.max_hhi,
+                MAX_HHI / metrics.hhi
+            ),
+        });
+    }
+
+    for position in &metrics.positions {
+        if position.weight_pct > thresholds.max_position_pct {
+            warnings.push(RiskWarning {
+                kind: RiskKind::Concentration,
+                symbol: Some(position.symbol.clone()),
+                explanation: format!(
+                    "{} is {:.1}% of your portfolio (limit {:.0}%)",
+                    position.symbol, position.weight_pct, thresholds.max_position_pct
+                ),
+            });
+        }
+        if let Some(multiple) = position.volume_multiple.filter(|m| *m > thresholds.max_volume_multiple) {
+            warnings.push(RiskWarning {
+                kind: RiskKind::Liquidity,
+                symbol: Some(position.symbol.clone()),
+                explanation: liquidity_explanation(&position.symbol, multiple),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// "your BONK position is 3x its daily volume", or a percentage below 1x
+fn liquidity_explanation(symbol: &str, multiple: f64) -> String {
+    if multiple.is_infinite() {
+        format!("Your {} position has no trading volume in the last 24h; it may not be possible to sell", symbol)
+    } else if multiple >= 1.0 {
+        format!(
+            "Your {} position is {:.1}x its daily volume; selling it would take days and move the price",
+            symbol, multiple
+        )
+    } else {
+        format!(
+            "Your {} position is {:.0}% of its daily volume; selling it at once would move the price",
+            symbol,
+            multiple * 100.0
+        )
+    }
+}
+
+/// Warn when swapping `usd_value` of `input_symbol` into `output_symbol` would
+/// leave the output asset past the position limit
+///
+/// The swap is assumed to keep its USD value; fees and price impact are
+/// ignored. No warning when the output's share doesn't grow, so swapping out
+/// of an already concentrated asset stays quiet.
+pub fn swap_concentration_warning(
+    holdings: &[PortfolioHolding],
+    input_symbol: &str,
+    output_symbol: &str,
+    usd_value: f64,
+    thresholds: &RiskThresholds,
+) -> Option<RiskWarning> {
+    let total: f64 = holdings.iter().map(|h| h.value).filter(|v| *v > 0.0).sum();
+    if total <= 0.0 || !usd_value.is_finite() || usd_value <= 0.0 {
+        return None;
+    }
+    let value_of = |symbol: &str| {
+        holdings
+            .iter()
+            .filter(|h| h.symbol.eq_ignore_ascii_case(symbol) && h.value > 0.0)
+            .map(|h| h.value)
+            .sum::<f64>()
+    };
+
+    // Can't sell more than is held
+    let moved = usd_value.min(value_of(input_symbol));
+    let before_pct = value_of(output_symbol) / total * 100.0;
+    let after_pct = (value_of(output_symbol) + moved) / total * 100.0;
+    if after_pct <= thresholds.max_position_pct || after_pct <= before_pct {
+        return None;
+    }
+
+    Some(RiskWarning {
+        kind: RiskKind::Concentration,
+        symbol: Some(output_symbol.to_string()),
+        explanation: format!(
+            "After this swap {} would be {:.1}% of your portfolio, past your {:.0}% limit",
+            output_symbol, after_pct, thresholds.max_position_pct
+        ),
+    })
+}
+
+/// Load risk warning thresholds
+pub fn load_risk_thresholds() -> RiskThresholds {
+    store().settings().load_or_default(Document::Risk)
+}
+
+fn save_risk_thresholds(thresholds: &RiskThresholds) {
+    if let Err(e) = store().settings().save(Document::Risk, thresholds) {
+        tracing::error!("Failed to save risk thresholds: {}", e);
+    }
+}
+
+/// Handle a change under Settings > Risk: clamp the thresholds and save them
+///
+/// Internal handler function - use [`crate::app::App::handle_risk_thresholds_change`] instead.
+pub(crate) fn handle_risk_thresholds_change(state: Arc<RwLock<AppState>>, thresholds: RiskThresholds) {
+    let thresholds = RiskThresholds {
+        max_position_pct: thresholds.max_position_pct.clamp(1.0, 100.0),
+        max_hhi: thresholds.max_hhi.clamp(1.0, MAX_HHI),
+        max_volume_multiple: thresholds.max_volume_multiple.max(0.001),
+    };
+    state.write().settings.risk = thresholds.clone();
+    save_risk_thresholds(&thresholds);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(symbol: &str, value: f64) -> PortfolioHolding {
+        PortfolioHolding {
+            symbol: symbol.to_string(),
+            amount: value,
+            price: Some(1.0),
+            value,
+            allocation_pct: 0.0,
+            change_24h_pct: 0.0,
+            change_24h_value: 0.0,
+            wallets: Vec::new(),
+        }
+    }
+
+    fn token(symbol: &str, verified: bool, daily_volume: Option<f64>) -> TokenInfo {
+        TokenInfo {
+            symbol: symbol.to_string(),
+            name: symbol.to_string(),
+            mint: format!("{}Mint", symbol),
+            decimals: 6,
+            price: 0.0,
+            balance: 0.0,
+            change_24h: 0.0,
+            is_favorite: false,
+            verified,
+            daily_volume,
+        }
+    }
+
+    #[test]
+    fn test_compute_exposure_weights_and_hhi() {
+        let holdings = vec![holding("USDC", 250.0), holding("SOL", 750.0), holding("DUST", 0.0)];
+
+        let metrics = compute_exposure(&holdings, &HashMap::new());
+
+        assert_eq!(metrics.total_value, 1000.0);
+        assert_eq!(metrics.positions.len(), 2);
+        let largest = metrics.largest().expect("largest position");
+        assert_eq!(largest.symbol, "SOL");
+        assert!((largest.weight_pct - 75.0).abs() < 1e-9);
+        // 75² + 25²
+        assert!((metrics.hhi - 6250.0).abs() < 1e-6);
+        assert!(largest.volume_multiple.is_none());
+
+        let single = compute_exposure(&[holding("SOL", 10.0)], &HashMap::new());
+        assert!((single.hhi - MAX_HHI).abs() < 1e-6);
+        assert_eq!(compute_exposure(&[], &HashMap::new()), ExposureMetrics::default());
+    }
+
+    #[test]
+    fn test_volume_multiple_and_liquidity_warning() {
+        let holdings = vec![holding("BONK", 3000.0), holding("SOL", 3000.0), holding("USDC", 3000.0)];
+        let volumes = HashMap::from([("BONK".to_string(), 1000.0), ("SOL".to_string(), 1e9)]);
+
+        let metrics = compute_exposure(&holdings, &volumes);
+        let bonk = metrics.positions.iter().find(|p| p.symbol == "BONK").expect("BONK position");
+        assert_eq!(bonk.volume_multiple, Some(3.0));
+
+        let warnings = risk_warnings(&metrics, &RiskThresholds::default());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, RiskKind::Liquidity);
+        assert_eq!(warnings[0].symbol.as_deref(), Some("BONK"));
+        assert!(warnings[0].explanation.contains("BONK position is 3.0x its daily volume"));
+    }
+
+    #[test]
+    fn test_concentration_warnings_follow_thresholds() {
+        let holdings = vec![holding("JUP", 950.0), holding("USDC", 50.0)];
+        let metrics = compute_exposure(&holdings, &HashMap::new());
+
+        let warnings = risk_warnings(&metrics, &RiskThresholds::default());
+        let kinds: Vec<RiskKind> = warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(kinds, vec![RiskKind::Diversification, RiskKind::Concentration]);
+        assert!(warnings[1].explanation.contains("JUP is 95.0%"));
+
+        let relaxed = RiskThresholds { max_position_pct: 96.0, max_hhi: MAX_HHI, ..RiskThresholds::default() };
+        assert!(risk_warnings(&metrics, &relaxed).is_empty());
+    }
+
+    #[test]
+    fn test_daily_volumes_prefer_verified_tokens() {
+        let tokens = vec![
+            token("BONK", false, Some(5e9)),
+            token("bonk", true, Some(2e6)),
+            token("WIF", true, None),
+            token("FAKE", false, Some(10.0)),
+        ];
+
+        let volumes = daily_volumes(&tokens);
+        assert_eq!(volumes.get("BONK"), Some(&2e6));
+        assert_eq!(volumes.get("FAKE"), Some(&10.0));
+        assert!(!volumes.contains_key("WIF"));
+    }
+
+    #[test]
+    fn test_swap_concentration_warning() {
+        let holdings = vec![holding("SOL", 400.0), holding("USDC", 400.0), holding("BONK", 200.0)];
+        let thresholds = RiskThresholds::default();
+
+        // BONK 20% -> 55%
+        let warning = swap_concentration_warning(&holdings, "USDC", "BONK", 350.0, &thresholds).expect("warning");
+        assert_eq!(warning.symbol.as_deref(), Some("BONK"));
+        assert!(warning.explanation.contains("55.0%"));
+
+        // BONK 20% -> 40% stays under the limit
+        assert!(swap_concentration_warning(&holdings, "USDC", "BONK", 200.0, &thresholds).is_none());
+        // Only what is held can be sold: 400 USDC at most, SOL ends at 80%
+        let capped = swap_concentration_warning(&holdings, "usdc", "SOL", 10_000.0, &thresholds).expect("warning");
+        assert!(capped.explanation.contains("80.0%"));
+        // Selling out of a concentrated asset never warns
+        let concentrated = vec![holding("SOL", 900.0), holding("USDC", 100.0)];
+        assert!(swap_concentration_warning(&concentrated, "SOL", "USDC", 100.0, &thresholds).is_none());
+        assert!(swap_concentration_warning(&[], "SOL", "USDC", 100.0, &thresholds).is_none());
+    }
+}
@@ -0,0 +1,211 @@
+//! # Search Handlers
+//!
+//! Drives the search palette: local providers run on every keystroke, and
+//! queries long enough to be a signature fragment also search the backend
+//! swap history. Selecting a result navigates to its screen and leaves a
+//! highlight for that screen to outline.
+
+use crate::app::events::AppEvent;
+use crate::app::handlers::navigation;
+use crate::app::search::{self, SearchDomain, SearchResult, SearchTarget, MAX_RESULTS_PER_DOMAIN, REMOTE_MIN_QUERY_LEN};
+use crate::app::state::AppState;
+use crate::app::usage;
+use crate::services::api::swap::{SwapHistoryItem, SwapHistoryQuery};
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::telemetry::{UsageEvent, UsageFeature};
+use std::sync::Arc;
+
+/// Open or close the palette; opening starts from an empty query
+///
+/// Internal handler function - use [`crate::app::App::handle_search_toggle`] instead.
+pub(crate) fn handle_search_toggle(state: Arc<RwLock<AppState>>) {
+    let mut state = state.write();
+    state.search.open = !state.search.open;
+    if state.search.open {
+        usage::capture(UsageEvent::Feature(UsageFeature::Search));
+        // One overlay owns the keyboard at a time
+        state.palette.open = false;
+        let search = &mut state.search;
+        search.query.clear();
+        search.groups.clear();
+        search.selected = 0;
+        search.remote_pending = None;
+    }
+}
+
+/// Re-run the search for an edited query
+///
+/// Internal handler function - use [`crate::app::App::handle_search_query`] instead.
+pub(crate) fn handle_search_query(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, query: String) {
+    let remote = {
+        let mut state = state.write();
+        let groups = search::search(&state, &query);
+        let remote = (query.trim().chars().count() >= REMOTE_MIN_QUERY_LEN)
+            .then(|| state.auth_token.clone().zip(state.api_client.clone()))
+            .flatten();
+
+        let search = &mut state.search;
+        search.groups = groups;
+        search.selected = 0;
+        search.remote_pending = remote.as_ref().map(|_| query.clone());
+        search.query = query.clone();
+        remote
+    };
+
+    let Some((jwt_token, api_client)) = remote else {
+        return;
+    };
+    let fragment = query.trim().to_string();
+    let request = SwapHistoryQuery {
+        limit: MAX_RESULTS_PER_DOMAIN,
+        signature: Some(fragment.clone()),
+        ..Default::default()
+    };
+    tokio::spawn(async move {
+        let result = api_client
+            .get_swap_history(&jwt_token, &request)
+            .await
+            .map_err(|e| e.to_string())
+            .map(|response| remote_results(&response.swaps, &fragment));
+        let _ = event_tx.send(AppEvent::SearchRemoteResult(query, result)).await;
+    });
+}
+
+/// Backend swap history matches as transaction results
+fn remote_results(swaps: &[SwapHistoryItem], fragment: &str) -> Vec<SearchResult> {
+    let query = fragment.to_lowercase();
+    swaps
+        .iter()
+        .map(|swap| SearchResult {
+            title: shared::format_address(&swap.signature, 8, 8),
+            detail: format!("Swap · {} · {}", swap.status, swap.created_at.get(..10).unwrap_or(&swap.created_at)),
+            // The backend match is case-sensitive, so this always scores
+            score: search::match_score(&swap.signature, &query).unwrap_or(40),
+            target: SearchTarget::Transaction { signature: swap.signature.clone() },
+        })
+        .collect()
+}
+
+/// Merge backend results if the query hasn't changed since they were requested
+pub(crate) fn apply_remote_results(state: &mut AppState, query: &str, results: Vec<SearchResult>) {
+    if state.search.remote_pending.as_deref() != Some(query) {
+        return;
+    }
+    state.search.remote_pending = None;
+    search::merge_results(&mut state.search.groups, SearchDomain::Transactions, results);
+}
+
+/// Navigate to a result and highlight it there
+///
+/// Internal handler function - use [`crate::app::App::handle_search_select`] instead.
+pub(crate) fn handle_search_select(state: Arc<RwLock<AppState>>, target: SearchTarget) {
+    navigation::handle_screen_change(state.clone(), target.screen());
+
+    let mut state = state.write();
+    if let SearchTarget::Message { conversation_id, .. } = &target {
+        let peer = search::conversation_peer(&state, conversation_id);
+        state.messaging.active_conversation_id = Some(conversation_id.clone());
+        state.messaging.selected_user_id = peer;
+    }
+    let search = &mut state.search;
+    search.open = false;
+    search.remote_pending = None;
+    search.highlight = Some(target);
+    search.highlight_seq += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::search::SettingsSection;
+    use crate::app::state::{CurrentUser, Screen};
+    use crate::app::App;
+
+    fn test_state() -> Arc<RwLock<AppState>> {
+        Arc::new(RwLock::new(App::new().state.read().clone()))
+    }
+
+    fn swap(signature: &str) -> SwapHistoryItem {
+        SwapHistoryItem {
+            id: 1,
+            signature: signature.to_string(),
+            input_mint: String::new(),
+            output_mint: String::new(),
+            input_amount: 0,
+            output_amount: 0,
+            status: "confirmed".to_string(),
+            created_at: "2025-02-18T10:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_toggle_resets_query() {
+        let state = test_state();
+        state.write().search.query = "old".to_string();
+
+        handle_search_toggle(state.clone());
+        assert!(state.read().search.open);
+        assert!(state.read().search.query.is_empty());
+
+        handle_search_toggle(state.clone());
+        assert!(!state.read().search.open);
+    }
+
+    #[test]
+    fn test_remote_results() {
+        let results = remote_results(&[swap("4xKpQzr9")], "kpq");
+        assert_eq!(results[0].detail, "Swap · confirmed · 2025-02-18");
+        assert_eq!(results[0].score, 40);
+        assert_eq!(results[0].target, SearchTarget::Transaction { signature: "4xKpQzr9".to_string() });
+    }
+
+    #[test]
+    fn test_stale_remote_results_are_dropped() {
+        let state = test_state();
+        let mut state = state.write();
+        state.search.remote_pending = Some("4xKp".to_string());
+
+        apply_remote_results(&mut state, "4xK", remote_results(&[swap("4xKpQzr9")], "4xK"));
+        assert!(state.search.groups.is_empty());
+
+        apply_remote_results(&mut state, "4xKp", remote_results(&[swap("4xKpQzr9")], "4xKp"));
+        assert_eq!(state.search.groups[0].domain, SearchDomain::Transactions);
+        assert_eq!(state.search.remote_pending, None);
+    }
+
+    #[test]
+    fn test_select_navigates_and_highlights() {
+        let state = test_state();
+        {
+            let mut state = state.write();
+            state.auth_token = Some("token".to_string());
+            state.current_user = Some(CurrentUser { id: 9, username: "me".to_string() });
+            state.search.open = true;
+        }
+
+        let target = SearchTarget::Message { conversation_id: "3:9".to_string(), index: 2 };
+        handle_search_select(state.clone(), target.clone());
+        {
+            let state = state.read();
+            assert_eq!(state.current_screen, Screen::Messaging);
+            assert_eq!(state.messaging.active_conversation_id.as_deref(), Some("3:9"));
+            assert_eq!(state.messaging.selected_user_id, Some(3));
+            assert!(!state.search.open);
+            assert_eq!(state.search.highlight, Some(target));
+            assert_eq!(state.search.highlight_seq, 1);
+        }
+
+        handle_search_select(state.clone(), SearchTarget::Setting(SettingsSection::Security));
+        assert_eq!(state.read().current_screen, Screen::Settings);
+        assert_eq!(state.read().search.highlight_seq, 2);
+    }
+
+    #[test]
+    fn test_select_protected_screen_when_logged_out() {
+        let state = test_state();
+        handle_search_select(state.clone(), SearchTarget::Transaction { signature: "abc".to_string() });
+        // The navigation guard still applies
+        assert_eq!(state.read().current_screen, Screen::Auth);
+    }
+}
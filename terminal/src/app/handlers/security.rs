@@ -0,0 +1,389 @@
+//! # Security Handlers
+//!
+//! Handlers for session signer grants (Settings > Security) and the auto-sign
+//! entry point used by schedulers such as DCA and TWAP.
+//!
+//! Granting a session requires the keystore password, checked by unlocking
+//! the seed on a blocking thread. Every auto-sign attempt, allowed or denied,
+//! is written to the audit log shown next to the grants.
+
+use crate::app::audit::{self, AuditCategory, AuditReference};
+use crate::app::state::{AppState, SecurityState, SignerAuditEntry};
+use crate::services::signer_policy::SessionGrant;
+use crate::services::wallet::WalletTransaction;
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::str::FromStr;
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+/// Audit entries kept in memory (oldest dropped first)
+pub const MAX_AUDIT_ENTRIES: usize = 200;
+
+/// Longest session a grant may cover (7 days)
+pub const MAX_GRANT_MINUTES: i64 = 7 * 24 * 60;
+
+/// Split a comma or whitespace separated list of public keys, rejecting
+/// anything that is not a valid key
+fn parse_pubkey_list(input: &str, what: &str) -> Result<Vec<String>, String> {
+    let mut keys: Vec<String> = Vec::new();
+    for item in input.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty()) {
+        Pubkey::from_str(item).map_err(|_| format!("Invalid {}: {}", what, item))?;
+        if !keys.iter().any(|k| k == item) {
+            keys.push(item.to_string());
+        }
+    }
+    if keys.is_empty() {
+        return Err(format!("At least one {} is required", what));
+    }
+    Ok(keys)
+}
+
+/// Build a grant from the Settings form. The id is assigned by the wallet service.
+pub fn parse_grant_form(form: &SecurityState, now: DateTime<Utc>) -> Result<SessionGrant, String> {
+    let label = form.label_input.trim();
+    if label.is_empty() {
+        return Err("Grant label required".to_string());
+    }
+
+    let input_mint = form.input_mint_input.trim();
+    Pubkey::from_str(input_mint).map_err(|_| "Invalid input mint".to_string())?;
+
+    let max_amount = form
+        .max_amount_input
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|amount| *amount > 0)
+        .ok_or_else(|| "Max amount must be a positive whole number of base units".to_string())?;
+
+    let minutes = form
+        .expiry_minutes_input
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|minutes| (1..=MAX_GRANT_MINUTES).contains(minutes))
+        .ok_or_else(|| format!("Expiry must be between 1 and {} minutes", MAX_GRANT_MINUTES))?;
+
+    Ok(SessionGrant {
+        id: 0,
+        label: label.to_string(),
+        input_mint: input_mint.to_string(),
+        max_amount,
+        allowed_programs: parse_pubkey_list(&form.allowed_programs_input, "program")?,
+        allowed_mints: parse_pubkey_list(&form.allowed_mints_input, "destination mint")?,
+        created_at: now,
+        expires_at: now + chrono::Duration::minutes(minutes),
+    })
+}
+
+/// Append to the audit log, dropping the oldest entries past the cap
+fn record_audit(security: &mut SecurityState, entry: SignerAuditEntry) {
+    security.audit_log.push(entry);
+    let excess = security.audit_log.len().saturating_sub(MAX_AUDIT_ENTRIES);
+    security.audit_log.drain(..excess);
+}
+
+/// Copy the wallet service's grants into state for the Settings screen
+fn sync_grants(state: &mut AppState) {
+    state.security.grants = state
+        .wallet_service
+        .as_ref()
+        .map(|wallet_service| wallet_service.grants().to_vec())
+        .unwrap_or_default();
+}
+
+fn notify(state: &mut AppState, level: &str, message: String) {
+    state.pending_notifications.push((level.to_string(), message));
+}
+
+/// Handle Grant button click (Settings > Security)
+///
+/// Internal handler function - use [`crate::app::App::handle_grant_session`] instead.
+pub(crate) fn handle_grant_session(state: Arc<RwLock<AppState>>) {
+    let (grant, keystore, password) = {
+        let mut state = state.write();
+        if state.security.granting {
+            return;
+        }
+        let grant = match parse_grant_form(&state.security, Utc::now()) {
+            Ok(grant) => grant,
+            Err(e) => {
+                notify(&mut state, "error", e);
+                return;
+            }
+        };
+        // Only a keystore-derived wallet has a password to protect the grant
+        let derived_wallet = state.derived_accounts.active_index.is_some() && state.wallet_service.is_some();
+        let Some(keystore) = state.derived_accounts.keystore.clone().filter(|_| derived_wallet) else {
+            notify(&mut state, "error", "Activate a keystore account before granting a signing session".to_string());
+            return;
+        };
+        let password = Zeroizing::new(std::mem::take(&mut state.security.password_input));
+        if password.is_empty() {
+            notify(&mut state, "error", "Enter the keystore password to grant a signing session".to_string());
+            return;
+        }
+        state.security.granting = true;
+        (grant, keystore, password)
+    };
+
+    tokio::spawn(async move {
+        let unlocked = tokio::task::spawn_blocking(move || {
+            keystore.with_seed(&password, |_| Ok(())).map_err(|e| e.to_string())
+        })
+        .await
+        .unwrap_or_else(|e| Err(format!("Task join error: {}", e)));
+
+        let mut state = state.write();
+        state.security.granting = false;
+        let result = unlocked.and_then(|()| {
+            state
+                .wallet_service
+                .as_mut()
+                .map(|wallet_service| wallet_service.grant_session(grant))
+                .ok_or_else(|| "Wallet disconnected".to_string())
+        });
+        match result {
+            Ok(grant) => {
+                tracing::info!(
+                    grant_id = grant.id,
+                    label = %grant.label,
+                    max_amount = grant.max_amount,
+                    expires_at = %grant.expires_at,
+                    "Signing session granted"
+                );
+                sync_grants(&mut state);
+                audit::record(
+                    &mut state.security.trail,
+                    AuditCategory::Security,
+                    format!(
+                        "Granted signing session \"{}\": up to {} per transaction until {}",
+                        grant.label,
+                        grant.max_amount,
+                        grant.expires_at.format("%Y-%m-%d %H:%M UTC")
+                    ),
+                    vec![AuditReference::Request(format!("grant #{}", grant.id))],
+                );
+                let message = format!("Signing session \"{}\" granted until {}", grant.label, grant.expires_at.format("%H:%M UTC"));
+                notify(&mut state, "success", message);
+            }
+            Err(e) => notify(&mut state, "error", format!("Failed to grant signing session: {}", e)),
+        }
+    });
+}
+
+/// Handle Revoke button click (Settings > Security)
+///
+/// Internal handler function - use [`crate::app::App::handle_revoke_grant`] instead.
+pub(crate) fn handle_revoke_grant(state: Arc<RwLock<AppState>>, grant_id: u64) {
+    let mut state = state.write();
+    let revoked = state
+        .wallet_service
+        .as_mut()
+        .is_some_and(|wallet_service| wallet_service.revoke_grant(grant_id));
+    sync_grants(&mut state);
+    if revoked {
+        tracing::info!(grant_id, "Signing session revoked");
+        audit::record(
+            &mut state.security.trail,
+            AuditCategory::Security,
+            "Revoked signing session",
+            vec![AuditReference::Request(format!("grant #{}", grant_id))],
+        );
+        notify(&mut state, "info", format!("Signing session #{} revoked", grant_id));
+    }
+}
+
+/// Sign `transaction` without prompting, if an active grant covers it.
+///
+/// This is the only way schedulers should sign. What is spent and where it
+/// goes is read from the transaction's instructions, not declared by the
+/// scheduler. The attempt is recorded in the audit log with the grant that
+/// matched, or the reason it was denied. Nothing is signed around a
+/// maintenance window.
+pub fn auto_sign(state: &Arc<RwLock<AppState>>, transaction: &mut WalletTransaction) -> Result<Signature, String> {
+    let mut state = state.write();
+    // For the audit log; a transaction it can't be read from is logged as "?"
+    let request = state.wallet_service.as_ref().and_then(|wallet_service| wallet_service.sign_request(transaction).ok());
+    let result = if let Some(reason) = super::maintenance::scheduler_pause_reason(&state) {
+        Err(reason.to_string())
+    } else {
+        match state.wallet_service.as_mut() {
+            Some(wallet_service) => wallet_service.auto_sign_transaction(transaction).map_err(|e| e.to_string()),
+            None => Err("Wallet service not available".to_string()),
+        }
+    };
+
+    let (input_mint, amount, destination_mint) = match request {
+        Some(request) => (request.input_mint, request.amount, request.destination_mint),
+        None => ("?".to_string(), 0, "?".to_string()),
+    };
+    let entry = SignerAuditEntry {
+        at: Utc::now(),
+        grant: result.as_ref().ok().map(|(_, grant)| (grant.id, grant.label.clone())),
+        input_mint,
+        amount,
+        destination_mint,
+        outcome: result.as_ref().map(|(signature, _)| signature.to_string()).map_err(Clone::clone),
+    };
+    match &entry.outcome {
+        Ok(signature) => tracing::info!(grant = ?entry.grant, %signature, amount, "Auto-signed transaction"),
+        Err(reason) => tracing::warn!(%reason, amount, "Auto-sign denied"),
+    }
+    if let (Ok(signature), Some((grant_id, label))) = (&entry.outcome, &entry.grant) {
+        audit::record(
+            &mut state.security.trail,
+            AuditCategory::AutoSign,
+            format!("Auto-signed {} of {} under \"{}\"", amount, shared::format_address(&entry.input_mint, 4, 4), label),
+            vec![
+                AuditReference::Transaction(signature.clone()),
+                AuditReference::Request(format!("grant #{}", grant_id)),
+            ],
+        );
+    }
+    record_audit(&mut state.security, entry);
+    // Expired grants were pruned during evaluation
+    sync_grants(&mut state);
+
+    result.map(|(signature, _)| signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::signer_policy::JUPITER_PROGRAM;
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const SOL: &str = "So11111111111111111111111111111111111111112";
+    const RECIPIENT: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+
+    fn test_state() -> AppState {
+        let app = crate::app::App::new();
+        let mut state = app.state.read().clone();
+        // Keep test entries out of the real audit file
+        state.security.trail = crate::app::audit::AuditLog::default();
+        state
+    }
+
+    fn filled_form() -> SecurityState {
+        SecurityState {
+            label_input: " SOL DCA ".to_string(),
+            input_mint_input: USDC.to_string(),
+            max_amount_input: "25000000".to_string(),
+            allowed_mints_input: format!("{}, {}", SOL, SOL),
+            allowed_programs_input: JUPITER_PROGRAM.to_string(),
+            expiry_minutes_input: "90".to_string(),
+            ..SecurityState::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_grant_form() {
+        let now = Utc::now();
+        let grant = parse_grant_form(&filled_form(), now).unwrap();
+
+        assert_eq!(grant.label, "SOL DCA");
+        assert_eq!(grant.input_mint, USDC);
+        assert_eq!(grant.max_amount, 25_000_000);
+        assert_eq!(grant.allowed_mints, vec![SOL.to_string()]);
+        assert_eq!(grant.allowed_programs, vec![JUPITER_PROGRAM.to_string()]);
+        assert_eq!(grant.expires_at - now, chrono::Duration::minutes(90));
+    }
+
+    #[test]
+    fn test_parse_grant_form_rejects_bad_input() {
+        let now = Utc::now();
+        let cases: [(fn(&mut SecurityState), &str); 6] = [
+            (|f| f.label_input = "  ".to_string(), "label"),
+            (|f| f.input_mint_input = "USDC".to_string(), "input mint"),
+            (|f| f.max_amount_input = "0".to_string(), "Max amount"),
+            (|f| f.allowed_mints_input = "not-a-mint".to_string(), "destination mint"),
+            (|f| f.allowed_programs_input.clear(), "program"),
+            (|f| f.expiry_minutes_input = (MAX_GRANT_MINUTES + 1).to_string(), "Expiry"),
+        ];
+        for (break_form, expected) in cases {
+            let mut form = filled_form();
+            break_form(&mut form);
+            let err = parse_grant_form(&form, now).unwrap_err();
+            assert!(err.contains(expected), "{:?} should mention {:?}", err, expected);
+        }
+    }
+
+    #[test]
+    fn test_default_form_programs_parse() {
+        let form = SecurityState::default();
+        let programs = parse_pubkey_list(&form.allowed_programs_input, "program").unwrap();
+        assert_eq!(programs.len(), crate::services::signer_policy::DEFAULT_ALLOWED_PROGRAMS.len());
+    }
+
+    #[test]
+    fn test_audit_log_is_capped() {
+        let mut security = SecurityState::default();
+        for amount in 0..(MAX_AUDIT_ENTRIES as u64 + 5) {
+            record_audit(&mut security, SignerAuditEntry {
+                at: Utc::now(),
+                grant: None,
+                input_mint: USDC.to_string(),
+                amount,
+                destination_mint: SOL.to_string(),
+                outcome: Err("denied".to_string()),
+            });
+        }
+        assert_eq!(security.audit_log.len(), MAX_AUDIT_ENTRIES);
+        assert_eq!(security.audit_log[0].amount, 5);
+    }
+
+    #[test]
+    fn test_denied_auto_sign_is_audited() {
+        let mut state = test_state();
+        let mut wallet_service = crate::services::wallet::WalletService::new("http://localhost:8899");
+        wallet_service.generate_new_keypair();
+        let transfer = wallet_service
+            .build_token_transfer(RECIPIENT, USDC, shared::dto::tokens::TokenProgram::Spl, 1_000, 6, None)
+            .unwrap();
+        state.wallet_service = Some(wallet_service);
+        let state = Arc::new(RwLock::new(state));
+
+        // Amount and mints come from the transaction
+        let result = auto_sign(&state, &mut WalletTransaction::Legacy(transfer));
+        assert!(result.unwrap_err().contains("no signing session has been granted"));
+        {
+            let state = state.read();
+            let entry = state.security.audit_log.last().unwrap();
+            assert_eq!(entry.grant, None);
+            assert_eq!((entry.input_mint.as_str(), entry.amount), (USDC, 1_000));
+            assert!(entry.outcome.is_err());
+        }
+
+        // A transaction nothing can be read from is still logged
+        let result = auto_sign(&state, &mut WalletTransaction::Legacy(Default::default()));
+        assert!(result.unwrap_err().contains("no transfer from this wallet"));
+        let state = state.read();
+        assert_eq!(state.security.audit_log.last().unwrap().input_mint, "?");
+        // Denied attempts stay in the auto-sign log only
+        assert!(state.security.trail.entries().is_empty());
+    }
+
+    #[test]
+    fn test_revoke_grant_updates_state() {
+        let mut state = test_state();
+        let mut wallet_service = crate::services::wallet::WalletService::new("http://localhost:8899");
+        let grant = wallet_service.grant_session(parse_grant_form(&filled_form(), Utc::now()).unwrap());
+        state.wallet_service = Some(wallet_service);
+        sync_grants(&mut state);
+        assert_eq!(state.security.grants.len(), 1);
+        let state = Arc::new(RwLock::new(state));
+
+        handle_revoke_grant(state.clone(), grant.id);
+
+        let state = state.read();
+        assert!(state.security.grants.is_empty());
+        assert!(state.wallet_service.as_ref().unwrap().grants().is_empty());
+        let entry = state.security.trail.entries().last().unwrap();
+        assert_eq!(entry.category, AuditCategory::Security);
+        assert_eq!(entry.references, vec![AuditReference::Request(format!("grant #{}", grant.id))]);
+    }
+}
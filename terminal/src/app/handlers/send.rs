@@ -0,0 +1,348 @@
+//! # Send Handlers
+//!
+//! The Wallet screen's Send panel moves SOL or a held SPL token to another
+//! address. Review checks the form against the wallet's balances, then
+//! [`prepare_transfer`](crate::app::tasks::transfer::prepare_transfer) builds
+//! the transfer locally and looks up its exact fee. The confirmation dialog
+//! shows the lamports involved, and
+//! [`confirm_transfer`](crate::app::tasks::transfer::confirm_transfer) signs
+//! it and submits it through the same backend path as swaps, so it lands in
+//! the history.
+//!
+//! An optional memo (an invoice number, say) is checked with the rest of the
+//! form, attached as an SPL Memo instruction and shown in the confirmation.
+//!
+//! Sending to an address that holds no SOL gets a warning: it may be a typo,
+//! and SOL sent there must cover the account's rent or the runtime rejects
+//! the transfer.
+
+use crate::app::state::{AppState, SendState, TransferConfirmation, WalletState};
+use crate::services::memo::validate_memo;
+use crate::services::token_transfer::{format_token_amount, parse_token_amount, TransferError, TransferPreview, SOL_DECIMALS};
+use parking_lot::RwLock;
+use shared::dto::tokens::TokenProgram;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Activity feed type of a transfer sent from the Wallet screen
+pub const TRANSFER_TX_TYPE: &str = "Transfer";
+
+/// Network fee of a transaction with one signature, in lamports
+///
+/// The panel's estimate and what Max keeps back; the dialog shows the fee
+/// the RPC quoted for the actual transaction.
+pub const BASE_FEE_LAMPORTS: u64 = 5_000;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Transfer described by the Send panel, checked against the wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferDraft {
+    pub recipient: String,
+    /// Token mint; `None` for SOL
+    pub mint: Option<String>,
+    pub symbol: String,
+    pub decimals: u8,
+    /// Program owning the token account (unused for SOL)
+    pub program: TokenProgram,
+    /// Base units leaving the wallet
+    pub amount: u64,
+    /// Base units reaching the recipient, after a transfer fee
+    pub received: u64,
+    /// Validated memo to attach
+    pub memo: Option<String>,
+}
+
+/// The wallet's SOL balance in lamports
+pub fn sol_lamports(wallet: &WalletState) -> u64 {
+    (wallet.sol_balance * LAMPORTS_PER_SOL).round() as u64
+}
+
+/// Recipient typed into the panel, if it is a Solana address other than the wallet's own
+pub fn validate_recipient(input: &str, own_address: &str) -> Result<String, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Enter the recipient's address".to_string());
+    }
+    if Pubkey::from_str(input).is_err() {
+        return Err(format!("{} is not a Solana address", shared::format_address(input, 4, 4)));
+    }
+    if input == own_address {
+        return Err("That is this wallet's own address".to_string());
+    }
+    Ok(input.to_string())
+}
+
+/// Check the panel's inputs against the wallet's balances
+///
+/// Sending SOL keeps [`BASE_FEE_LAMPORTS`] back for the fee; sending a token
+/// needs at least that much SOL.
+pub fn draft_transfer(form: &SendState, wallet: &WalletState) -> Result<TransferDraft, String> {
+    let recipient = validate_recipient(&form.recipient, &wallet.address)?;
+    let memo = match form.memo.trim() {
+        "" => None,
+        memo => Some(validate_memo(memo).map_err(|e| e.to_string())?.to_string()),
+    };
+    let lamports = sol_lamports(wallet);
+
+    let (symbol, decimals, program, balance, transfer_fee) = match &form.mint {
+        None => ("SOL".to_string(), SOL_DECIMALS, TokenProgram::Spl, lamports.saturating_sub(BASE_FEE_LAMPORTS), None),
+        Some(mint) => {
+            let token = wallet
+                .token_balances
+                .iter()
+                .find(|balance| &balance.mint == mint)
+                .ok_or_else(|| "This wallet no longer holds that token".to_string())?;
+            if lamports < BASE_FEE_LAMPORTS {
+                return Err("Not enough SOL to pay the network fee".to_string());
+            }
+            (token.symbol.clone(), token.decimals, token.program, token.raw_amount, token.transfer_fee)
+        }
+    };
+
+    let amount = parse_token_amount(&form.amount, decimals)
+        .ok_or_else(|| format!("Enter an amount of {} with at most {} decimals", symbol, decimals))?;
+    let preview = TransferPreview::new(amount, balance, decimals, transfer_fee).map_err(|e| match e {
+        TransferError::InsufficientBalance { balance, .. } if form.mint.is_none() => {
+            format!("Only {} SOL can be sent after the network fee", format_token_amount(balance, decimals))
+        }
+        TransferError::InsufficientBalance { balance, .. } => {
+            format!("Only {} {} available", format_token_amount(balance, decimals), symbol)
+        }
+        other => other.to_string(),
+    })?;
+
+    Ok(TransferDraft {
+        recipient,
+        mint: form.mint.clone(),
+        symbol,
+        decimals,
+        program,
+        amount: preview.amount,
+        received: preview.received,
+        memo,
+    })
+}
+
+/// What Max fills in: the whole token balance, or the SOL balance less the fee
+pub fn max_amount(mint: Option<&str>, wallet: &WalletState) -> Option<String> {
+    match mint {
+        None => Some(format_token_amount(sol_lamports(wallet).saturating_sub(BASE_FEE_LAMPORTS), SOL_DECIMALS)),
+        Some(mint) => wallet
+            .token_balances
+            .iter()
+            .find(|balance| balance.mint == mint)
+            .map(|balance| format_token_amount(balance.raw_amount, balance.decimals)),
+    }
+}
+
+/// Warning about the recipient for the confirmation dialog
+pub fn recipient_warning(confirmation: &TransferConfirmation) -> Option<String> {
+    if confirmation.recipient_lamports > 0 {
+        return None;
+    }
+    let minimum = format_token_amount(confirmation.rent_exempt_minimum, SOL_DECIMALS);
+    Some(if confirmation.below_rent_minimum() {
+        format!(
+            "The recipient holds no SOL. A new account needs at least {} SOL for rent, so this transfer would be rejected.",
+            minimum
+        )
+    } else {
+        "The recipient holds no SOL. Check the address; an unused account is often a typo.".to_string()
+    })
+}
+
+/// Show the built transfer in the confirmation dialog, or why it couldn't be built
+pub(crate) fn apply_transfer_prepared(state: &mut AppState, result: Result<Box<TransferConfirmation>, String>) {
+    let send = &mut state.send;
+    send.preparing = false;
+    match result {
+        Ok(confirmation) => {
+            send.error = None;
+            send.confirmation = Some(*confirmation);
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "Failed to prepare transfer");
+            send.error = Some(err);
+        }
+    }
+}
+
+/// Notify how the submitted transfer went; a sent one clears the form
+pub(crate) fn apply_transfer_result(state: &mut AppState, result: Result<String, String>) {
+    state.send.sending = false;
+    let notification = match result {
+        Ok(signature) => {
+            state.send.recipient.clear();
+            state.send.amount.clear();
+            state.send.memo.clear();
+            state.send.error = None;
+            ("success", format!("Transfer sent: {}", shared::format_address(&signature, 8, 8)))
+        }
+        Err(err) => {
+            state.send.error = Some(err.clone());
+            ("error", format!("Transfer failed: {}", err))
+        }
+    };
+    state.pending_notifications.push((notification.0.to_string(), notification.1));
+}
+
+/// Close the confirmation dialog without signing
+///
+/// Internal handler function - use [`crate::app::App::handle_send_cancel`] instead.
+pub(crate) fn handle_send_cancel(state: Arc<RwLock<AppState>>) {
+    state.write().send.confirmation = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::TokenBalance;
+    use crate::services::wallet::WalletTransaction;
+    use shared::dto::tokens::TransferFee;
+
+    const OWN: &str = "8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL";
+    const RECIPIENT: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn wallet(sol_balance: f64) -> WalletState {
+        WalletState {
+            address: OWN.to_string(),
+            sol_balance,
+            token_balances: vec![TokenBalance {
+                symbol: "USDC".to_string(),
+                amount: 2.5,
+                mint: USDC.to_string(),
+                decimals: 6,
+                raw_amount: 2_500_000,
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn form(mint: Option<&str>, amount: &str) -> SendState {
+        SendState {
+            recipient: format!(" {} ", RECIPIENT),
+            mint: mint.map(str::to_string),
+            amount: amount.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn confirmation(amount: u64, recipient_lamports: u64) -> TransferConfirmation {
+        TransferConfirmation {
+            transaction: WalletTransaction::Legacy(Default::default()),
+            recipient: RECIPIENT.to_string(),
+            mint: None,
+            symbol: "SOL".to_string(),
+            decimals: SOL_DECIMALS,
+            amount,
+            received: amount,
+            memo: None,
+            fee_lamports: 5_000,
+            recipient_lamports,
+            rent_exempt_minimum: 890_880,
+            token_account_rent: None,
+            wallet: OWN.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_recipient() {
+        assert_eq!(validate_recipient(&format!("{}\n", RECIPIENT), OWN), Ok(RECIPIENT.to_string()));
+        assert!(validate_recipient("", OWN).is_err());
+        assert!(validate_recipient("not-an-address", OWN).is_err());
+        // Base58, but 0, O, I and l aren't in its alphabet
+        assert!(validate_recipient("0xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", OWN).is_err());
+        // Too short to be 32 bytes
+        assert!(validate_recipient("7xKXtg2CW87d97TXJSDpbD5jBkhe", OWN).is_err());
+        assert_eq!(validate_recipient(OWN, OWN), Err("That is this wallet's own address".to_string()));
+    }
+
+    #[test]
+    fn test_draft_sol_transfer_keeps_the_fee() {
+        let draft = draft_transfer(&form(None, "0.5"), &wallet(1.0)).unwrap();
+        assert_eq!(draft.recipient, RECIPIENT);
+        assert_eq!((draft.amount, draft.received, draft.decimals), (500_000_000, 500_000_000, 9));
+        assert_eq!(draft.mint, None);
+
+        let max = max_amount(None, &wallet(1.0)).unwrap();
+        assert_eq!(max, "0.999995");
+        assert_eq!(draft_transfer(&form(None, &max), &wallet(1.0)).unwrap().amount, 999_995_000);
+        assert_eq!(
+            draft_transfer(&form(None, "1"), &wallet(1.0)),
+            Err("Only 0.999995 SOL can be sent after the network fee".to_string())
+        );
+        assert!(draft_transfer(&form(None, "0"), &wallet(1.0)).is_err());
+        assert!(draft_transfer(&form(None, "0.0000000001"), &wallet(1.0)).is_err());
+    }
+
+    #[test]
+    fn test_draft_transfer_memo() {
+        assert_eq!(draft_transfer(&form(None, "0.5"), &wallet(1.0)).unwrap().memo, None);
+
+        let mut with_memo = form(Some(USDC), "1");
+        with_memo.memo = "  invoice 42 ".to_string();
+        assert_eq!(draft_transfer(&with_memo, &wallet(0.01)).unwrap().memo.as_deref(), Some("invoice 42"));
+
+        with_memo.memo = "a".repeat(crate::services::memo::MAX_MEMO_BYTES + 1);
+        assert_eq!(draft_transfer(&with_memo, &wallet(0.01)), Err("Memo is 257 bytes (max 256)".to_string()));
+    }
+
+    #[test]
+    fn test_draft_token_transfer() {
+        let draft = draft_transfer(&form(Some(USDC), "2.5"), &wallet(0.01)).unwrap();
+        assert_eq!((draft.symbol.as_str(), draft.amount, draft.decimals), ("USDC", 2_500_000, 6));
+        assert_eq!(max_amount(Some(USDC), &wallet(0.01)).as_deref(), Some("2.5"));
+
+        assert_eq!(draft_transfer(&form(Some(USDC), "3"), &wallet(0.01)), Err("Only 2.5 USDC available".to_string()));
+        assert!(draft_transfer(&form(Some(USDC), "1"), &wallet(0.0)).is_err());
+        assert!(draft_transfer(&form(Some("GoneMint1111"), "1"), &wallet(0.01)).is_err());
+        assert_eq!(max_amount(Some("GoneMint1111"), &wallet(0.01)), None);
+
+        // A transfer fee is withheld from what arrives
+        let mut fee_wallet = wallet(0.01);
+        fee_wallet.token_balances[0].transfer_fee = Some(TransferFee { basis_points: 100, maximum_fee: 1_000_000 });
+        let draft = draft_transfer(&form(Some(USDC), "1"), &fee_wallet).unwrap();
+        assert_eq!((draft.amount, draft.received), (1_000_000, 990_000));
+    }
+
+    #[test]
+    fn test_recipient_warnings() {
+        assert_eq!(recipient_warning(&confirmation(1_000_000_000, 42)), None);
+
+        let unfunded = confirmation(1_000_000_000, 0);
+        assert!(unfunded.can_send());
+        assert!(recipient_warning(&unfunded).unwrap().contains("holds no SOL"));
+        assert_eq!(unfunded.total_lamports(), 1_000_005_000);
+
+        let dust = confirmation(1_000, 0);
+        assert!(!dust.can_send());
+        assert!(recipient_warning(&dust).unwrap().contains("0.00089088 SOL for rent"));
+
+        // Tokens to an empty wallet only warn; the sender pays the token account rent
+        let mut token = confirmation(1_000, 0);
+        token.mint = Some(USDC.to_string());
+        token.token_account_rent = Some(2_039_280);
+        assert!(token.can_send());
+        assert_eq!(token.total_lamports(), 2_044_280);
+    }
+
+    #[test]
+    fn test_transfer_result_clears_the_form() {
+        let mut state = crate::app::App::new().state.read().clone();
+        state.send = form(None, "0.5");
+        state.send.memo = "invoice 42".to_string();
+        state.send.sending = true;
+        apply_transfer_result(&mut state, Ok("5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW".to_string()));
+        assert!(!state.send.sending);
+        assert!(state.send.recipient.is_empty() && state.send.amount.is_empty() && state.send.memo.is_empty());
+        assert_eq!(state.pending_notifications.last().unwrap().0, "success");
+
+        state.send = form(None, "0.5");
+        apply_transfer_result(&mut state, Err("Blockhash not found".to_string()));
+        assert_eq!(state.send.amount, "0.5");
+        assert_eq!(state.send.error.as_deref(), Some("Blockhash not found"));
+    }
+}
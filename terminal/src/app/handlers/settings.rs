@@ -1,38 +1,458 @@
 //! # Settings Handlers
 //!
-//! Handlers for settings-related actions including theme customization and persistence.
+//! Handlers for settings-related actions including theme customization
+//! (presets, theme file import and export),
+//! the backend server list, the connection settings, token picker favorites,
+//! network usage, keyboard shortcuts, the UI language, usage analytics consent,
+//! and persistence.
 
+use crate::app::events::AppEvent;
+use crate::core::config::{
+    parse_slippage, validate_http_url, validate_ws_url, TerminalConfig, FIELD_API_URL, FIELD_RPC_URL, FIELD_SLIPPAGE,
+    FIELD_WS_URL,
+};
+use crate::core::store::{store, Document, StoreError};
+use crate::services::api::failover::DEFAULT_SERVER;
+use async_channel::Sender;
+use shared::validation::ValidationError;
+use crate::ui::chart::indicators::IndicatorConfig;
+use crate::ui::i18n::{self, Locale};
 use crate::ui::theme::ThemeConfig;
 use parking_lot::RwLock;
 use std::sync::Arc;
-use crate::app::AppState;
+use crate::app::usage;
+use crate::app::keybindings::{KeyAction, KeyBindings, KeyCaptureForm, KeyCombo};
+use crate::app::{AppState, ConnectionForm, NetworkPreferences, NotificationPreferences, PrivacyForm, TokenPreferences};
 
-/// Get default config file path
-pub fn get_config_path() -> std::path::PathBuf {
-    std::path::PathBuf::from("./xterminal-config.json")
+/// Load theme settings from the local database
+pub fn load_settings() -> ThemeConfig {
+    store().settings().load_or_default(Document::Theme)
 }
 
-/// Load settings from file
-pub fn load_settings() -> ThemeConfig {
-    let path = get_config_path();
-    match ThemeConfig::load_from_file(&path) {
-        Ok(config) => {
-            tracing::info!("Loaded theme configuration from {:?}", path);
-            config
+/// Save theme settings to the local database
+pub fn save_settings(config: &ThemeConfig) -> Result<(), StoreError> {
+    store().settings().save(Document::Theme, config)?;
+    tracing::info!("Saved theme configuration");
+    Ok(())
+}
+
+/// Load chart indicator configuration
+pub fn load_indicator_config() -> IndicatorConfig {
+    store().settings().load_or_default(Document::Indicators)
+}
+
+/// Handle chart indicator toggle/period change
+///
+/// Recomputes the indicator series for the loaded candles and persists the
+/// selection immediately (it is not part of the theme save/reset flow).
+pub fn handle_indicator_config_change(state: Arc<RwLock<AppState>>, config: IndicatorConfig) {
+    {
+        let mut app_state = state.write();
+        app_state.settings.indicators = config;
+        let terminal = &mut app_state.terminal;
+        terminal.chart_indicators.update(&terminal.sol_candles, &config);
+    }
+
+    if let Err(e) = store().settings().save(Document::Indicators, &config) {
+        tracing::error!("Failed to save indicator config: {}", e);
+    }
+}
+
+/// Load token picker favorites and recents
+pub fn load_token_preferences() -> TokenPreferences {
+    store().settings().load_or_default(Document::Tokens)
+}
+
+/// Save token picker favorites and recents
+pub fn save_token_preferences(preferences: &TokenPreferences) {
+    if let Err(e) = store().settings().save(Document::Tokens, preferences) {
+        tracing::error!("Failed to save token preferences: {}", e);
+    }
+}
+
+/// Load network usage preferences
+pub fn load_network_preferences() -> NetworkPreferences {
+    store().settings().load_or_default(Document::Network)
+}
+
+/// Turn bandwidth saver on or off and save it.
+///
+/// Turning it on also stops the hover prefetches already running.
+pub fn handle_bandwidth_saver_toggle(state: Arc<RwLock<AppState>>, enabled: bool) {
+    let preferences = {
+        let mut state = state.write();
+        state.settings.network.bandwidth_saver = enabled;
+        if enabled {
+            state.live_assets.prefetch.cancel_except(&[]);
+        }
+        state.settings.network.clone()
+    };
+    save_network_preferences(&preferences);
+}
+
+/// Set the age after which oracle prices are flagged as stale and save it.
+///
+/// Clamped to at least one second.
+pub fn handle_stale_price_threshold_change(state: Arc<RwLock<AppState>>, secs: u64) {
+    let preferences = {
+        let mut state = state.write();
+        state.settings.network.stale_price_secs = secs.max(1);
+        state.settings.network.clone()
+    };
+    save_network_preferences(&preferences);
+}
+
+/// Set the idle time before connections are closed and save it; 0 disables
+/// idle teardown.
+pub fn handle_idle_teardown_change(state: Arc<RwLock<AppState>>, mins: u64) {
+    let preferences = {
+        let mut state = state.write();
+        state.settings.network.idle_teardown_mins = mins;
+        state.settings.network.clone()
+    };
+    save_network_preferences(&preferences);
+}
+
+fn save_network_preferences(preferences: &NetworkPreferences) {
+    if let Err(e) = store().settings().save(Document::Network, preferences) {
+        tracing::error!("Failed to save network preferences: {}", e);
+    }
+}
+
+/// Load the muted notice categories
+pub fn load_notification_preferences() -> NotificationPreferences {
+    store().settings().load_or_default(Document::Notifications)
+}
+
+/// Show or mute a category of backend notices and save it.
+///
+/// Banners already shown for a muted category are dismissed too.
+pub fn handle_notification_category_toggle(state: Arc<RwLock<AppState>>, category: shared::NoticeCategory, enabled: bool) {
+    let preferences = {
+        let mut state = state.write();
+        state.settings.notifications.set_enabled(category, enabled);
+        if !enabled {
+            state.system_notices.retain(|notice| notice.category != category);
+        }
+        state.settings.notifications.clone()
+    };
+
+    if let Err(e) = store().settings().save(Document::Notifications, &preferences) {
+        tracing::error!("Failed to save notification preferences: {}", e);
+    }
+}
+
+/// Load the keyboard shortcuts
+pub fn load_key_bindings() -> KeyBindings {
+    store().settings().load_or_default(Document::KeyBindings)
+}
+
+/// Start waiting for the next key press to rebind `action`; None stops
+/// waiting without changing anything
+pub fn handle_key_capture(state: Arc<RwLock<AppState>>, action: Option<KeyAction>) {
+    let mut state = state.write();
+    state.settings.key_capture = KeyCaptureForm { action, error: None };
+}
+
+/// Bind `action` to the captured key and save it.
+///
+/// A shortcut that is already taken is refused and the editor keeps waiting
+/// for another key.
+pub fn handle_key_binding_change(state: Arc<RwLock<AppState>>, action: KeyAction, combo: KeyCombo) {
+    let bindings = {
+        let mut state = state.write();
+        if let Err(taken_by) = state.settings.key_bindings.set(action, combo) {
+            state.settings.key_capture.error = Some(format!("{} is already used by {}", combo, taken_by));
+            return;
+        }
+        state.settings.key_capture = KeyCaptureForm::default();
+        state.settings.key_bindings.clone()
+    };
+    tracing::info!(action = ?action, shortcut = %combo, "Shortcut rebound");
+    save_key_bindings(&bindings);
+}
+
+/// Put every shortcut back to its default and save it
+pub fn handle_key_bindings_reset(state: Arc<RwLock<AppState>>) {
+    {
+        let mut state = state.write();
+        state.settings.key_bindings = KeyBindings::default();
+        state.settings.key_capture = KeyCaptureForm::default();
+    }
+    save_key_bindings(&KeyBindings::default());
+}
+
+fn save_key_bindings(bindings: &KeyBindings) {
+    if let Err(e) = store().settings().save(Document::KeyBindings, bindings) {
+        tracing::error!("Failed to save keyboard shortcuts: {}", e);
+    }
+}
+
+/// Load the UI language
+pub fn load_locale() -> Locale {
+    store().settings().load_or_default(Document::Language)
+}
+
+/// Switch the UI to `locale` and save it; takes effect on the next frame
+pub fn handle_locale_change(state: Arc<RwLock<AppState>>, locale: Locale) {
+    state.write().settings.locale = locale;
+    i18n::set_locale(locale);
+    tracing::info!(locale = locale.code(), "UI language changed");
+    if let Err(e) = store().settings().save(Document::Language, &locale) {
+        tracing::error!("Failed to save the UI language: {}", e);
+    }
+}
+
+/// Privacy section as saved: consent, install id and the pending upload
+pub fn load_privacy_form() -> PrivacyForm {
+    let usage = usage::load_preferences();
+    let preview = usage::preview(&usage, chrono::Utc::now().date_naive());
+    PrivacyForm { usage, preview, ..Default::default() }
+}
+
+/// Re-read the JSON the next usage upload would send
+pub fn refresh_usage_preview(state: &mut AppState) {
+    state.settings.privacy.preview = usage::preview(&state.settings.privacy.usage, chrono::Utc::now().date_naive());
+}
+
+/// Turn usage analytics on or off and save it.
+///
+/// Turning them on creates the install id if there is none yet; turning
+/// them off forgets the counts not uploaded yet.
+pub fn handle_usage_analytics_toggle(state: Arc<RwLock<AppState>>, enabled: bool) {
+    let preferences = {
+        let mut state = state.write();
+        let privacy = &mut state.settings.privacy;
+        privacy.usage.enabled = enabled;
+        if enabled {
+            privacy.usage.install_id();
+        }
+        privacy.status = None;
+        usage::recorder().set_enabled(enabled);
+        refresh_usage_preview(&mut state);
+        state.settings.privacy.usage.clone()
+    };
+    tracing::info!(enabled, "Usage analytics preference changed");
+    usage::save_preferences(&preferences);
+}
+
+/// Ask the backend to delete the counts uploaded under the install id
+///
+/// Result arrives as [`AppEvent::UsageDataDeleted`].
+pub fn handle_usage_data_delete(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (install_id, api_client) = {
+        let mut state = state.write();
+        if state.settings.privacy.deleting {
+            return;
+        }
+        let Some(install_id) = state.settings.privacy.usage.install_id.clone() else {
+            state.settings.privacy.status = Some((false, "Nothing was ever uploaded".to_string()));
+            return;
+        };
+        let Some(api_client) = state.api_client.clone() else {
+            state.settings.privacy.status = Some((true, "Not connected to the backend".to_string()));
+            return;
+        };
+        state.settings.privacy.deleting = true;
+        state.settings.privacy.status = None;
+        (install_id, api_client)
+    };
+
+    tokio::spawn(async move {
+        let result = api_client.delete_usage(&install_id).await.map_err(|e| e.to_string());
+        let _ = event_tx.send(AppEvent::UsageDataDeleted(result)).await;
+    });
+}
+
+/// Apply the backend's answer to "Delete my data"
+///
+/// Once the uploaded counts are gone, local ones are forgotten too and the
+/// install id is replaced, so later uploads can't be linked to the old ones.
+pub fn apply_usage_data_deleted(state: &mut AppState, result: Result<(), String>) {
+    state.settings.privacy.deleting = false;
+    match result {
+        Ok(()) => {
+            usage::recorder().forget();
+            let privacy = &mut state.settings.privacy;
+            privacy.usage.install_id = None;
+            if privacy.usage.enabled {
+                privacy.usage.install_id();
+            }
+            privacy.status = Some((false, "Your usage data was deleted".to_string()));
+            usage::save_preferences(&privacy.usage);
+            refresh_usage_preview(state);
         }
         Err(e) => {
-            tracing::warn!("Failed to load theme config from {:?}: {}. Using defaults.", path, e);
-            ThemeConfig::default()
+            tracing::warn!("Failed to delete usage data: {}", e);
+            state.settings.privacy.status = Some((true, format!("Deletion failed: {}", e)));
         }
     }
 }
 
-/// Save settings to file
-pub fn save_settings(config: &ThemeConfig) -> Result<(), Box<dyn std::error::Error>> {
-    let path = get_config_path();
-    config.save_to_file(&path)?;
-    tracing::info!("Saved theme configuration to {:?}", path);
-    Ok(())
+/// Load the backend server list, primary first
+///
+/// Without a saved list this is the default server; an `api_url` from
+/// [`TerminalConfig`] is put in front by [`promote_server`].
+pub fn load_server_list() -> Vec<String> {
+    match store().settings().load::<Vec<String>>(Document::Servers) {
+        Ok(Some(servers)) if !servers.is_empty() => {
+            tracing::info!(count = servers.len(), "Loaded backend server list");
+            servers
+        }
+        Ok(_) => vec![DEFAULT_SERVER.to_string()],
+        Err(e) => {
+            tracing::warn!("Failed to load server list: {}. Using default server.", e);
+            vec![DEFAULT_SERVER.to_string()]
+        }
+    }
+}
+
+/// Make `primary` the first server, keeping the rest as backups
+pub fn promote_server(servers: &mut Vec<String>, primary: &str) {
+    servers.retain(|s| s.trim_end_matches('/') != primary);
+    servers.insert(0, primary.to_string());
+}
+
+/// Check a backend URL entered in Settings, returning it without a trailing slash
+pub fn validate_server_url(url: &str) -> Result<String, String> {
+    validate_http_url(url)
+}
+
+/// Handle saving the server list from Settings > Servers
+///
+/// Validates every entry, writes the list, and hands it to the API client,
+/// which stays on the active server if it is still listed.
+pub fn handle_server_list_save(state: Arc<RwLock<AppState>>) {
+    let mut app_state = state.write();
+    let servers: Result<Vec<String>, String> = app_state
+        .settings
+        .servers
+        .entries
+        .iter()
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| validate_server_url(s))
+        .collect();
+
+    let servers = match servers {
+        Ok(servers) if servers.is_empty() => Err("Add at least one server".to_string()),
+        other => other,
+    };
+    let servers = match servers {
+        Ok(servers) => servers,
+        Err(e) => {
+            app_state.settings.servers.status = Some((true, e));
+            return;
+        }
+    };
+
+    if let Err(e) = store().settings().save(Document::Servers, &servers) {
+        tracing::error!("Failed to save server list: {}", e);
+        app_state.settings.servers.status = Some((true, format!("Failed to save: {}", e)));
+        return;
+    }
+
+    if let Some(api_client) = &app_state.api_client {
+        api_client.failover().set_servers(servers.clone());
+    }
+    tracing::info!(count = servers.len(), "Saved backend server list");
+    app_state.settings.servers.entries = servers;
+    app_state.settings.servers.status = Some((false, "Server list saved".to_string()));
+}
+
+/// Read the Settings > Connection form, collecting every rejected field
+pub fn connection_from_form(form: &ConnectionForm) -> Result<TerminalConfig, Vec<ValidationError>> {
+    let mut errors = Vec::new();
+    let mut optional_url = |field: &str, value: &str, validate: fn(&str) -> Result<String, String>| {
+        if value.trim().is_empty() {
+            return None;
+        }
+        validate(value).map_err(|e| errors.push(ValidationError::new(field, e))).ok()
+    };
+    let rpc_url = optional_url(FIELD_RPC_URL, &form.rpc_url, validate_http_url);
+    let api_url = optional_url(FIELD_API_URL, &form.api_url, validate_http_url);
+    let ws_url = optional_url(FIELD_WS_URL, &form.ws_url, validate_ws_url);
+    let default_slippage_bps = parse_slippage(&form.default_slippage_bps)
+        .map_err(|e| errors.push(ValidationError::new(FIELD_SLIPPAGE, e)))
+        .unwrap_or_default();
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(TerminalConfig { network: form.network, rpc_url, api_url, ws_url, default_slippage_bps })
+}
+
+/// Handle "Apply & Reconnect" in Settings > Connection
+///
+/// Validates and saves the form, then rebuilds what depends on it: the
+/// wallet's RPC client (keeping its keypair), the primary backend, and the
+/// price stream. Switching network drops prices and candles from the old one
+/// before everything is reloaded.
+pub fn handle_connection_apply(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let mut app_state = state.write();
+    let config = match connection_from_form(&app_state.settings.connection) {
+        Ok(config) => config,
+        Err(errors) => {
+            let form = &mut app_state.settings.connection;
+            form.status = Some((true, "Fix the highlighted fields".to_string()));
+            form.errors = errors;
+            return;
+        }
+    };
+    app_state.settings.connection.errors.clear();
+
+    if let Err(e) = config.save() {
+        tracing::error!("Failed to save connection settings: {}", e);
+        app_state.settings.connection.status = Some((true, format!("Failed to save: {}", e)));
+        return;
+    }
+
+    let previous = std::mem::replace(&mut app_state.config, config.clone());
+    if previous.network != config.network {
+        tracing::info!(from = ?previous.network, to = ?config.network, "Switching network");
+        clear_network_data(&mut app_state);
+    }
+    if previous.default_slippage_bps == app_state.terminal.swap.slippage_bps {
+        app_state.terminal.swap.slippage_bps = config.default_slippage_bps;
+    }
+
+    if previous.rpc_endpoint() != config.rpc_endpoint() {
+        super::wallet::rebuild_wallet_service(&mut app_state, &config.rpc_endpoint());
+    }
+
+    if let Some(api_url) = &config.api_url {
+        promote_server(&mut app_state.settings.servers.entries, api_url);
+        if let Some(api_client) = &app_state.api_client {
+            api_client.failover().set_servers(app_state.settings.servers.entries.clone());
+        }
+    }
+
+    if app_state.websocket_connected {
+        crate::services::api::websocket::restart_price_stream(&mut app_state, event_tx.clone(), state.clone());
+    }
+    app_state.settings.connection = ConnectionForm::new(&config);
+    app_state.settings.connection.status = Some((false, format!("Connected to {}", config.network.label())));
+    drop(app_state);
+
+    super::refresh::refresh_targets(&state, &event_tx, &super::refresh::RefreshTarget::ALL);
+}
+
+/// Drop prices, candles and quotes that belong to the previous network
+fn clear_network_data(state: &mut AppState) {
+    let terminal = &mut state.terminal;
+    terminal.prices.replace(Vec::new());
+    terminal.chart_data.clear();
+    terminal.sol_candles.clear();
+    terminal.chart_data_version += 1;
+    terminal.chart_indicators = crate::ui::chart::indicators::IndicatorSeries::new(state.settings.indicators);
+    terminal.streamed_symbols.clear();
+    terminal.depth = None;
+    terminal.depth_error = None;
+    terminal.last_depth_fetch = None;
+    terminal.swap.quote = None;
+    if let Some(wallet) = &mut state.wallet {
+        wallet.sol_balance = 0.0;
+        wallet.token_balances.clear();
+    }
 }
 
 /// Handle theme color change
@@ -42,6 +462,63 @@ pub fn handle_theme_color_change(state: Arc<RwLock<AppState>>, config: ThemeConf
     app_state.settings.unsaved_changes = true;
 }
 
+/// Ask for a theme file and make it the current theme (unsaved, like a
+/// color change).
+///
+/// A file that isn't a theme leaves the current theme alone and says why in
+/// a notification.
+pub fn handle_theme_import(state: Arc<RwLock<AppState>>) {
+    let Some(path) = rfd::FileDialog::new()
+        .set_title("Import theme")
+        .add_filter("Theme", &["json"])
+        .pick_file()
+    else {
+        return;
+    };
+
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let result = std::fs::read_to_string(&path).map_err(|e| e.to_string());
+    let mut state = state.write();
+    match result {
+        Ok(json) => import_theme(&mut state, &name, &json),
+        Err(e) => state.pending_notifications.push(("error".to_string(), format!("Couldn't read {}: {}", name, e))),
+    }
+}
+
+/// Apply an imported theme file, or report why it can't be
+fn import_theme(state: &mut AppState, name: &str, json: &str) {
+    match ThemeConfig::from_json(json) {
+        Ok(config) => {
+            state.settings.theme_config = config;
+            state.settings.unsaved_changes = true;
+            state.pending_notifications.push(("success".to_string(), format!("Imported theme from {}; save to keep it", name)));
+        }
+        Err(e) => {
+            tracing::warn!(file = %name, error = %e, "Theme import rejected");
+            state.pending_notifications.push(("error".to_string(), format!("Couldn't import {}: {}", name, e)));
+        }
+    }
+}
+
+/// Ask where to save the current theme as a shareable JSON file
+pub fn handle_theme_export(state: Arc<RwLock<AppState>>) {
+    let Some(path) = rfd::FileDialog::new()
+        .set_title("Export theme")
+        .set_file_name("xforce-theme.json")
+        .add_filter("Theme", &["json"])
+        .save_file()
+    else {
+        return;
+    };
+
+    let mut state = state.write();
+    let notification = match std::fs::write(&path, state.settings.theme_config.to_json()) {
+        Ok(()) => ("success".to_string(), format!("Exported theme to {}", path.display())),
+        Err(e) => ("error".to_string(), format!("Couldn't export the theme: {}", e)),
+    };
+    state.pending_notifications.push(notification);
+}
+
 /// Handle settings save
 pub fn handle_settings_save(state: Arc<RwLock<AppState>>) {
     let app_state = state.write();
@@ -75,3 +552,64 @@ pub fn handle_settings_apply(_state: Arc<RwLock<AppState>>) {
     // The state already has the config, UI will read it and apply
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_theme_import_keeps_the_current_theme() {
+        let mut state = crate::app::App::new().state.read().clone();
+        let before = state.settings.theme_config.clone();
+
+        import_theme(&mut state, "broken.json", r#"{"background": "black"}"#);
+        assert_eq!(state.settings.theme_config, before);
+        assert!(!state.settings.unsaved_changes);
+        assert_eq!(state.pending_notifications.last().map(|(kind, _)| kind.as_str()), Some("error"));
+
+        let light = crate::ui::theme::ThemePreset::Light.config();
+        import_theme(&mut state, "light.json", &light.to_json());
+        assert_eq!(state.settings.theme_config, light);
+        assert!(state.settings.unsaved_changes);
+    }
+
+    #[test]
+    fn test_validate_server_url() {
+        assert_eq!(validate_server_url(" https://backup.example.com/ ").unwrap(), "https://backup.example.com");
+        assert_eq!(validate_server_url("http://10.0.0.2:3001").unwrap(), "http://10.0.0.2:3001");
+        assert!(validate_server_url("backup.example.com").is_err());
+        assert!(validate_server_url("ftp://backup").is_err());
+        assert!(validate_server_url("http://").is_err());
+        assert!(validate_server_url("http://bad host").is_err());
+    }
+
+    #[test]
+    fn test_connection_form_rejects_each_bad_field() {
+        let mut form = ConnectionForm::new(&TerminalConfig::default());
+        form.rpc_url = "rpc.example.com".to_string();
+        form.ws_url = "https://prices.example.com".to_string();
+        form.default_slippage_bps = "0.5".to_string();
+        let fields: Vec<String> = connection_from_form(&form).unwrap_err().into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, [FIELD_RPC_URL, FIELD_WS_URL, FIELD_SLIPPAGE]);
+
+        form.network = crate::core::config::SolanaNetwork::Mainnet;
+        form.rpc_url = " ".to_string();
+        form.api_url = "https://api.example.com/".to_string();
+        form.ws_url.clear();
+        form.default_slippage_bps = "100".to_string();
+        let config = connection_from_form(&form).unwrap();
+        assert_eq!(config.rpc_url, None);
+        assert_eq!(config.rpc_endpoint(), "https://api.mainnet-beta.solana.com");
+        assert_eq!(config.api_url.as_deref(), Some("https://api.example.com"));
+        assert_eq!(config.default_slippage_bps, 100);
+    }
+
+    #[test]
+    fn test_promote_server() {
+        let mut servers = vec!["http://a:3001".to_string(), "http://b:3001/".to_string()];
+        promote_server(&mut servers, "http://b:3001");
+        assert_eq!(servers, ["http://b:3001", "http://a:3001"]);
+        promote_server(&mut servers, "http://c:3001");
+        assert_eq!(servers, ["http://c:3001", "http://b:3001", "http://a:3001"]);
+    }
+}
@@ -0,0 +1,161 @@
+//! # Share Link Handlers
+//!
+//! Builds read-only snapshots of the Live Chart and Portfolio screens and
+//! publishes them through `POST /api/share`. The resulting URL is copied to
+//! the clipboard by the screen's Share menu.
+
+use crate::app::events::AppEvent;
+use crate::app::state::{AppState, PortfolioState, TerminalState};
+use crate::ui::chart::indicators::IndicatorConfig;
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::share::{ChartShare, CreateShareRequest, PortfolioShare, ShareAllocation, SharePayload};
+use std::sync::Arc;
+
+/// Names of the enabled indicator overlays, e.g. `"SMA 20"`
+pub fn indicator_names(config: &IndicatorConfig) -> Vec<String> {
+    [
+        ("SMA", config.sma_enabled, config.sma_period),
+        ("EMA", config.ema_enabled, config.ema_period),
+        ("RSI", config.rsi_enabled, config.rsi_period),
+    ]
+    .into_iter()
+    .filter(|(_, enabled, _)| *enabled)
+    .map(|(name, _, period)| format!("{} {}", name, period))
+    .collect()
+}
+
+/// Snapshot of the chart as currently shown (`None` before candles load)
+///
+/// Only the candle range is sent; the backend fills in its own candles.
+pub fn chart_snapshot(terminal: &TerminalState, indicators: &IndicatorConfig) -> Option<ChartShare> {
+    let from = terminal.sol_candles.first()?.timestamp;
+    let to = terminal.sol_candles.last()?.timestamp;
+    Some(ChartShare {
+        symbol: terminal.chart_symbol.clone(),
+        timeframe: terminal.chart_timeframe,
+        indicators: indicator_names(indicators),
+        annotations: Vec::new(),
+        from,
+        to,
+        candles: Vec::new(),
+    })
+}
+
+/// Snapshot of the portfolio allocation (`None` without valued holdings)
+pub fn portfolio_snapshot(portfolio: &PortfolioState) -> Option<PortfolioShare> {
+    let allocations: Vec<ShareAllocation> = portfolio
+        .holdings
+        .iter()
+        .filter(|h| h.value > 0.0)
+        .map(|h| ShareAllocation {
+            symbol: h.symbol.clone(),
+            allocation_pct: h.allocation_pct,
+            value: Some(h.value),
+        })
+        .collect();
+    if allocations.is_empty() {
+        return None;
+    }
+    Some(PortfolioShare {
+        total_value: Some(portfolio.total_value),
+        allocations,
+    })
+}
+
+/// Handle "Share…" on the Live Chart screen
+///
+/// Internal handler function - use [`crate::app::App::handle_share_chart`] instead.
+pub(crate) fn handle_share_chart(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let payload = {
+        let state = state.read();
+        chart_snapshot(&state.terminal, &state.settings.indicators).map(SharePayload::Chart)
+    };
+    send_share(state, event_tx, payload, "No candles loaded yet");
+}
+
+/// Handle "Share…" on the Portfolio screen
+///
+/// Internal handler function - use [`crate::app::App::handle_share_portfolio`] instead.
+pub(crate) fn handle_share_portfolio(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let payload = portfolio_snapshot(&state.read().portfolio).map(SharePayload::Portfolio);
+    send_share(state, event_tx, payload, "No priced holdings to share");
+}
+
+fn send_share(
+    state: Arc<RwLock<AppState>>,
+    event_tx: Sender<AppEvent>,
+    payload: Option<SharePayload>,
+    empty_message: &str,
+) {
+    let (request, jwt_token, api_client) = {
+        let mut state = state.write();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api_client.clone()) else {
+            return;
+        };
+        if state.share.pending {
+            return;
+        }
+        let Some(payload) = payload else {
+            state.pending_notifications.push(("warning".to_string(), empty_message.to_string()));
+            return;
+        };
+        state.share.pending = true;
+        let request = CreateShareRequest {
+            payload,
+            expires_in_hours: state.share.expires_in_hours,
+            anonymize: state.share.anonymize,
+        };
+        (request, jwt_token, api_client)
+    };
+
+    tokio::spawn(async move {
+        let result = api_client.create_share_link(&jwt_token, &request).await.map_err(|e| e.to_string());
+        let _ = event_tx.send(AppEvent::ShareLinkResult(result)).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::PortfolioHolding;
+
+    fn holding(symbol: &str, value: f64, allocation_pct: f64) -> PortfolioHolding {
+        PortfolioHolding {
+            symbol: symbol.to_string(),
+            amount: 1.0,
+            price: Some(value),
+            value,
+            allocation_pct,
+            highlight_seq: 0,
+        }
+    }
+
+    #[test]
+    fn test_indicator_names() {
+        let config = IndicatorConfig {
+            sma_enabled: true,
+            sma_period: 20,
+            ema_enabled: false,
+            ema_period: 50,
+            rsi_enabled: true,
+            rsi_period: 14,
+        };
+        assert_eq!(indicator_names(&config), vec!["SMA 20".to_string(), "RSI 14".to_string()]);
+    }
+
+    #[test]
+    fn test_portfolio_snapshot_skips_unpriced_holdings() {
+        let portfolio = PortfolioState {
+            holdings: vec![holding("SOL", 75.0, 75.0), holding("BONK", 25.0, 25.0), holding("DUST", 0.0, 0.0)],
+            total_value: 100.0,
+            ..Default::default()
+        };
+        let snapshot = portfolio_snapshot(&portfolio).unwrap();
+        assert_eq!(snapshot.total_value, Some(100.0));
+        let symbols: Vec<&str> = snapshot.allocations.iter().map(|a| a.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["SOL", "BONK"]);
+
+        assert!(portfolio_snapshot(&PortfolioState::default()).is_none());
+    }
+}
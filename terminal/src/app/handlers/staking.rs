@@ -0,0 +1,255 @@
+//! # Staking Handlers
+//!
+//! The Wallet screen's Staking panel lists the wallet's stake accounts and
+//! the rewards they earned, and stakes SOL with a validator. The backend
+//! builds each staking transaction unsigned;
+//! [`run_stake_action`](crate::app::tasks::staking::run_stake_action) checks
+//! that it only touches the system and stake programs with the wallet as fee
+//! payer, signs it locally and submits it like a swap.
+//!
+//! ## Lifecycle
+//!
+//! ```text
+//! Stake       → new stake account, activating until the epoch ends, then active
+//! Deactivate  → stops earning now; the SOL stays locked until the epoch ends
+//! Withdraw    → once inactive, the whole balance returns to the wallet
+//! ```
+//!
+//! Deactivating asks for confirmation first, with a warning about when the
+//! SOL becomes withdrawable.
+
+use crate::app::handlers::send::{sol_lamports, BASE_FEE_LAMPORTS};
+use crate::app::state::{AppState, StakingState, WalletState};
+use crate::services::token_transfer::{format_token_amount, parse_token_amount, SOL_DECIMALS};
+use crate::services::wallet::WalletTransaction;
+use shared::dto::staking::{
+    format_sol, validate_stake_amount, DelegateStakeRequest, StakingInfo, StakingRewardsResponse,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Activity feed type of staking transactions
+pub const STAKE_TX_TYPE: &str = "Stake";
+
+/// Completed epochs of rewards shown
+pub const REWARD_EPOCHS: u64 = 5;
+
+/// Programs a staking transaction from the backend may call
+const STAKING_PROGRAMS: [Pubkey; 2] = [
+    Pubkey::from_str_const("11111111111111111111111111111111"),
+    Pubkey::from_str_const("Stake11111111111111111111111111111111111111"),
+];
+
+/// What a staking transaction does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StakeAction {
+    Delegate,
+    Deactivate,
+    Withdraw,
+}
+
+impl StakeAction {
+    /// Past tense, for notifications and the activity feed
+    pub fn done(self) -> &'static str {
+        match self {
+            StakeAction::Delegate => "Staked",
+            StakeAction::Deactivate => "Deactivated",
+            StakeAction::Withdraw => "Withdrew",
+        }
+    }
+}
+
+/// Stake request described by the panel's form, checked against the wallet
+///
+/// The account is funded with the stake plus its rent-exempt reserve, and
+/// the wallet must also cover the fee.
+pub fn draft_delegation(staking: &StakingState, wallet: &WalletState) -> Result<DelegateStakeRequest, String> {
+    let info = staking.info.as_ref().ok_or("Staking info hasn't loaded yet")?;
+
+    let vote_account = staking.vote_account.trim();
+    if vote_account.is_empty() {
+        return Err("Enter the vote account of a validator".to_string());
+    }
+    Pubkey::from_str(vote_account).map_err(|_| "The validator vote account isn't a Solana address".to_string())?;
+
+    let lamports = parse_token_amount(&staking.amount, SOL_DECIMALS)
+        .ok_or_else(|| format!("Enter an amount in SOL, with at most {} decimals", SOL_DECIMALS))?;
+    let funded = validate_stake_amount(lamports, info.minimum_delegation, info.rent_exempt_reserve)?;
+    let needed = funded.saturating_add(BASE_FEE_LAMPORTS);
+    if needed > sol_lamports(wallet) {
+        return Err(format!(
+            "Not enough SOL: staking {} SOL needs {} SOL, including the {} SOL rent-exempt reserve and the fee",
+            format_token_amount(lamports, SOL_DECIMALS),
+            format_token_amount(needed, SOL_DECIMALS),
+            format_sol(info.rent_exempt_reserve),
+        ));
+    }
+
+    Ok(DelegateStakeRequest { wallet: wallet.address.clone(), vote_account: vote_account.to_string(), lamports })
+}
+
+/// Check that a transaction the backend built for `wallet` is a staking
+/// transaction: the wallet pays the fee and only the system and stake
+/// programs are called
+pub fn check_stake_transaction(transaction: &WalletTransaction, wallet: &str) -> Result<(), String> {
+    let WalletTransaction::Legacy(transaction) = transaction else {
+        return Err("Unexpected versioned staking transaction".to_string());
+    };
+    let message = &transaction.message;
+    if message.account_keys.first().map(ToString::to_string).as_deref() != Some(wallet) {
+        return Err("The staking transaction isn't paid by this wallet".to_string());
+    }
+    if message.header.num_required_signatures != 1 {
+        return Err("The staking transaction asks for other signers".to_string());
+    }
+    for instruction in &message.instructions {
+        let program = message.account_keys.get(instruction.program_id_index as usize);
+        if !program.is_some_and(|program| STAKING_PROGRAMS.contains(program)) {
+            return Err("The staking transaction calls a program other than the system and stake programs".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Text of the deactivation warning, from the current epoch's progress
+pub fn cooldown_warning(info: &StakingInfo) -> String {
+    format!(
+        "Deactivated stake stops earning rewards right away, but the SOL stays locked in the stake account \
+         until epoch {} ends (in about {}). It can be withdrawn from epoch {}. When a lot of stake is \
+         deactivating across the network, the cooldown can last several epochs. Reactivating means \
+         creating a new stake account and waiting for it to warm up again.",
+        info.epoch,
+        format_duration(info.epoch_remaining_secs()),
+        info.epoch + 1,
+    )
+}
+
+/// Store fetched stake accounts, if they're still for the active wallet
+pub(crate) fn apply_staking_info(state: &mut AppState, address: &str, result: Result<StakingInfo, String>) {
+    if state.staking.address.as_deref() != Some(address) {
+        return;
+    }
+    state.staking.loading = false;
+    match result {
+        Ok(info) => {
+            state.staking.info = Some(info);
+            state.staking.error = None;
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "Failed to fetch staking info");
+            state.staking.error = Some(err);
+        }
+    }
+}
+
+/// Store fetched rewards, if they're still for the active wallet
+///
+/// A failure only leaves the rewards table empty.
+pub(crate) fn apply_staking_rewards(state: &mut AppState, address: &str, result: Result<StakingRewardsResponse, String>) {
+    if state.staking.address.as_deref() != Some(address) {
+        return;
+    }
+    match result {
+        Ok(response) => state.staking.rewards = response.rewards,
+        Err(err) => tracing::warn!(error = %err, "Failed to fetch staking rewards"),
+    }
+}
+
+/// Show the outcome of a staking transaction
+///
+/// Internal handler function - called from the event handler on `AppEvent::StakeActionResult`.
+pub(crate) fn apply_stake_action_result(state: &mut AppState, action: StakeAction, result: Result<String, String>) {
+    state.staking.submitting = None;
+    let notification = match result {
+        Ok(signature) => {
+            if action == StakeAction::Delegate {
+                state.staking.amount.clear();
+            }
+            state.staking.form_error = None;
+            ("success", format!("{}: {}", action.done(), shared::format_address(&signature, 8, 8)))
+        }
+        Err(err) => {
+            if action == StakeAction::Delegate {
+                state.staking.form_error = Some(err.clone());
+            }
+            ("error", format!("Staking transaction failed: {}", err))
+        }
+    };
+    state.pending_notifications.push((notification.0.to_string(), notification.1));
+}
+
+/// "about 3 h 20 min", or minutes under an hour
+fn format_duration(secs: u64) -> String {
+    let minutes = secs.div_ceil(60).max(1);
+    if minutes >= 60 {
+        format!("{} h {} min", minutes / 60, minutes % 60)
+    } else {
+        format!("{} min", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::transaction::Transaction;
+
+    const WALLET: &str = "8W6QginkhTTxoP2deQjq7rZ9YMwN5FH9JYuLfSKuJKAL";
+    const VOTE: &str = "CertusDeBmqN8ZawdkxK5kFGMwBXdudvWHYwtNgNhvLu";
+
+    fn wallet(sol_balance: f64) -> WalletState {
+        WalletState { address: WALLET.to_string(), sol_balance, token_balances: Vec::new() }
+    }
+
+    fn staking(amount: &str) -> StakingState {
+        StakingState {
+            info: Some(StakingInfo {
+                epoch: 700,
+                slot_index: 420_000,
+                slots_in_epoch: 432_000,
+                minimum_delegation: 1_000_000_000,
+                rent_exempt_reserve: 2_282_880,
+                ..Default::default()
+            }),
+            vote_account: VOTE.to_string(),
+            amount: amount.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_draft_delegation_checks_minimum_and_reserve() {
+        let request = draft_delegation(&staking("1.5"), &wallet(2.0)).unwrap();
+        assert_eq!(request.lamports, 1_500_000_000);
+        assert_eq!(request.vote_account, VOTE);
+
+        assert!(draft_delegation(&staking("0.5"), &wallet(2.0)).unwrap_err().contains("minimum stake is 1 SOL"));
+        // 1 SOL plus the reserve and fee doesn't fit in exactly 1 SOL
+        assert!(draft_delegation(&staking("1"), &wallet(1.0)).unwrap_err().contains("rent-exempt reserve"));
+
+        let mut bad_vote = staking("1");
+        bad_vote.vote_account = "validator".to_string();
+        assert!(draft_delegation(&bad_vote, &wallet(2.0)).is_err());
+        assert!(draft_delegation(&StakingState::default(), &wallet(2.0)).is_err());
+    }
+
+    #[test]
+    fn test_check_stake_transaction() {
+        let payer = Pubkey::from_str(WALLET).unwrap();
+        let build = |program: Pubkey| {
+            let instruction = Instruction::new_with_bytes(program, &[5, 0, 0, 0], vec![AccountMeta::new(payer, true)]);
+            WalletTransaction::Legacy(Transaction::new_with_payer(&[instruction], Some(&payer)))
+        };
+        assert!(check_stake_transaction(&build(STAKING_PROGRAMS[1]), WALLET).is_ok());
+        assert!(check_stake_transaction(&build(STAKING_PROGRAMS[1]), VOTE).is_err());
+        let token_program = Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+        assert!(check_stake_transaction(&build(token_program), WALLET).is_err());
+    }
+
+    #[test]
+    fn test_cooldown_warning_names_the_epochs() {
+        let warning = cooldown_warning(staking("1").info.as_ref().unwrap());
+        assert!(warning.contains("until epoch 700 ends (in about 1 h 20 min)"), "{}", warning);
+        assert!(warning.contains("withdrawn from epoch 701"));
+    }
+}
@@ -1,13 +1,31 @@
 //! # Swap Handlers
 //!
-//! Handlers for swap-related actions including token selection and swap execution.
+//! Handlers for swap-related actions including token selection, the swap
+//! confirmation dialog, the filtered swap history and its CSV export.
 
-use crate::app::state::{AppState, TokenInfo, TokenPickerTarget};
-use crate::app::events::AppEvent;
+use crate::app::price_store::PriceSnapshot;
+use crate::app::state::{AppState, PriceData, SwapConfirmation, SwapHistoryFilters, SwapHistoryItem, TokenInfo, TokenPickerTarget};
+use crate::app::events::{AppEvent, SwapHistoryPage};
+use crate::app::handlers::transactions::csv_field;
+use crate::app::tasks::guard::{spawn_guarded, GuardedTask};
+use crate::core::service::ApiService;
+use crate::services::api::swap::{SwapHistoryItem as ApiSwapHistoryItem, SwapHistoryQuery};
 use async_channel::Sender;
+use chrono::NaiveDate;
 use parking_lot::RwLock;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+/// Rows per swap history page
+pub const HISTORY_PAGE_SIZE: usize = 25;
+
+/// Wrapped SOL mint; Jupiter wraps and unwraps it inside the swap, so it
+/// shows up as a change of the wallet's SOL balance
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
 /// Open token picker popup
 ///
 /// Internal handler function - use [`crate::app::App::open_token_picker_internal`] instead.
@@ -18,19 +36,26 @@ pub(crate) fn open_token_picker(state: Arc<RwLock<AppState>>, target: TokenPicke
     state.terminal.swap.token_filter.clear();
     state.terminal.swap.selected_token_index = 0;
 
-    // Populate token list from prices (TODO: fetch from API)
+    // Keep the fetched token list; fall back to the price feed until it arrives
+    if !state.terminal.swap.token_list.is_empty() {
+        return;
+    }
     state.terminal.swap.token_list = state
         .terminal
         .prices
+        .load()
         .iter()
         .map(|price| TokenInfo {
             symbol: price.symbol.clone(),
             name: price.symbol.clone(), // TODO: Get full name from API
             mint: "placeholder_mint".to_string(), // TODO: Get from API
+            decimals: 9, // TODO: Get from API
             price: price.price,
             balance: 0.0, // TODO: Get from wallet
             change_24h: price.change_24h,
             is_favorite: false,
+            verified: true, // Priced symbols are the backend's curated stream
+            daily_volume: None,
         })
         .collect();
 }
@@ -44,7 +69,7 @@ pub(crate) fn handle_token_select(
     token: TokenInfo,
     target: TokenPickerTarget,
 ) {
-    {
+    let preferences = {
         let mut state = state.write();
         match target {
             TokenPickerTarget::Input => {
@@ -56,11 +81,88 @@ pub(crate) fn handle_token_select(
                 state.terminal.swap.output_mint = token.mint.clone();
             }
         }
-        state.terminal.swap.show_token_picker = false;
-    }
+        // The old quote is for the other pair; never show it against this one
+        state.terminal.swap.reset_quote();
+        state.terminal.swap.close_token_picker();
+        state.settings.tokens.push_recent(&token.mint);
+        state.settings.tokens.clone()
+    };
+    super::settings::save_token_preferences(&preferences);
     // Note: Quote fetch will be triggered by the caller or via on_tick
 }
 
+/// Star or unstar a token in the picker
+///
+/// Internal handler function - use [`crate::app::App::handle_token_favorite_toggle`] instead.
+pub(crate) fn handle_token_favorite_toggle(state: Arc<RwLock<AppState>>, mint: String) {
+    let preferences = {
+        let mut state = state.write();
+        let favorite = state.settings.tokens.toggle_favorite(&mint);
+        for token in state.terminal.swap.token_list.iter_mut().filter(|t| t.mint == mint) {
+            token.is_favorite = favorite;
+        }
+        state.settings.tokens.clone()
+    };
+    super::settings::save_token_preferences(&preferences);
+}
+
+/// Indices of the tokens matching a picker search, best match first.
+///
+/// Matching is case-insensitive on symbol, name and mint. An exact symbol
+/// beats a symbol prefix, which beats a name or mint prefix, which beat a
+/// match anywhere in the symbol or name; favorites lead within each tier.
+/// An empty query lists every token, favorites first.
+pub fn rank_tokens(tokens: &[TokenInfo], query: &str) -> Vec<usize> {
+    let query = query.trim();
+    let mut ranked: Vec<(u8, usize)> = tokens
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, token)| match_tier(token, query).map(|tier| (tier, idx)))
+        .collect();
+    ranked.sort_by(|(tier_a, a), (tier_b, b)| {
+        let (a, b) = (&tokens[*a], &tokens[*b]);
+        tier_a
+            .cmp(tier_b)
+            .then(b.is_favorite.cmp(&a.is_favorite))
+            .then(a.symbol.len().cmp(&b.symbol.len()))
+            .then_with(|| a.symbol.cmp(&b.symbol))
+    });
+    ranked.into_iter().map(|(_, idx)| idx).collect()
+}
+
+/// Match tier of a token for a search, lower is better; `None` if it doesn't match
+fn match_tier(token: &TokenInfo, query: &str) -> Option<u8> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    if token.symbol.eq_ignore_ascii_case(query) {
+        Some(0)
+    } else if starts_with_ignore_case(&token.symbol, query) {
+        Some(1)
+    } else if starts_with_ignore_case(&token.name, query) || starts_with_ignore_case(&token.mint, query) {
+        Some(2)
+    } else if contains_ignore_case(&token.symbol, query) || contains_ignore_case(&token.name, query) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+// Byte-wise so ranking thousands of tokens per frame doesn't allocate
+fn starts_with_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack
+        .as_bytes()
+        .get(..needle.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .any(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
 /// Set max amount from wallet balance
 ///
 /// Internal handler function - use [`crate::app::App::set_max_amount`] instead.
@@ -71,3 +173,599 @@ pub(crate) fn set_max_amount(state: Arc<RwLock<AppState>>) {
     state.terminal.swap.amount = "100.0".to_string();
 }
 
+/// One-line outcome of a simulated swap for the confirmation dialog:
+/// "You will send 1 SOL, receive ~145.23 USDC, network fee 0.000005 SOL".
+///
+/// `None` unless the simulation succeeded.
+pub fn confirmation_summary(confirmation: &SwapConfirmation) -> Option<String> {
+    let simulation = confirmation.simulation.as_ref().ok().filter(|simulation| simulation.success)?;
+    let token_change = |mint: &str| {
+        simulation
+            .token_changes
+            .iter()
+            .find(|change| change.mint == mint)
+            .map_or(0.0, |change| change.ui_change())
+    };
+
+    let sent = if confirmation.input_mint == WSOL_MINT {
+        simulation.sol_sent_lamports() as f64 / LAMPORTS_PER_SOL
+    } else {
+        -token_change(&confirmation.input_mint)
+    };
+    let received = if confirmation.output_mint == WSOL_MINT {
+        (simulation.sol_change_lamports + simulation.network_fee_lamports as i64).max(0) as f64 / LAMPORTS_PER_SOL
+    } else {
+        token_change(&confirmation.output_mint)
+    };
+
+    Some(format!(
+        "You will send {} {}, receive ~{} {}, network fee {} SOL",
+        format_amount(sent),
+        confirmation.input_symbol,
+        format_amount(received),
+        confirmation.output_symbol,
+        format_amount(simulation.network_fee_lamports as f64 / LAMPORTS_PER_SOL),
+    ))
+}
+
+/// USD value of a swap's input for the confirmation dialog
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsdEstimate {
+    pub usd: f64,
+    /// Source of the price the estimate uses
+    pub source: Option<String>,
+    /// Age in seconds of the oracle price, when it is older than the stale threshold
+    pub stale_secs: Option<i64>,
+}
+
+/// Value the swap's input at the latest price of its token
+///
+/// `None` without a price for the input token.
+pub fn usd_estimate(confirmation: &SwapConfirmation, prices: &PriceSnapshot, now: i64, stale_after_secs: u64) -> Option<UsdEstimate> {
+    let price = prices.get(&confirmation.input_symbol.to_uppercase())?;
+    Some(UsdEstimate {
+        usd: confirmation.ui_amount * price.price,
+        source: price.source.clone(),
+        stale_secs: price.is_stale(now, stale_after_secs).then(|| price.age_secs(now)).flatten(),
+    })
+}
+
+/// Tokens of the swap whose price sources disagree, with the spread in percent
+///
+/// Input first, then output; the spread is `None` when the backend flagged
+/// the price without reporting it and the sources can't be compared here.
+pub fn pair_divergence(confirmation: &SwapConfirmation, prices: &PriceSnapshot) -> Vec<(String, Option<f64>)> {
+    [&confirmation.input_symbol, &confirmation.output_symbol]
+        .into_iter()
+        .filter_map(|symbol| {
+            let price = prices.get(&symbol.to_uppercase()).filter(|price| price.divergent)?;
+            let pct = price.divergence_pct.or_else(|| PriceData::spread_pct(&price.sources));
+            Some((symbol.clone(), pct))
+        })
+        .collect()
+}
+
+/// Up to 9 decimals without trailing zeros
+fn format_amount(amount: f64) -> String {
+    let text = format!("{:.9}", amount);
+    match text.trim_end_matches('0').trim_end_matches('.') {
+        "-0" => "0".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// Close the confirmation dialog without signing
+///
+/// Internal handler function - use [`crate::app::App::handle_swap_confirmation_cancel`] instead.
+pub(crate) fn handle_swap_confirmation_cancel(state: Arc<RwLock<AppState>>) {
+    state.write().terminal.swap.confirmation = None;
+}
+
+/// Build the history API query for a set of filters.
+///
+/// The To date is inclusive here but the API's `to_ts` is exclusive, so it
+/// becomes midnight of the following day.
+pub fn history_query(filters: &SwapHistoryFilters) -> Result<SwapHistoryQuery, String> {
+    let date = |text: &str, label: &str| -> Result<Option<NaiveDate>, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| format!("{} date must be YYYY-MM-DD", label))
+    };
+    let from = date(&filters.from_date, "From")?;
+    let to = date(&filters.to_date, "To")?;
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err("From date is after To date".to_string());
+        }
+    }
+    let midnight = |day: NaiveDate| day.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc().timestamp());
+
+    Ok(SwapHistoryQuery {
+        limit: HISTORY_PAGE_SIZE,
+        offset: filters.page * HISTORY_PAGE_SIZE,
+        from_ts: from.and_then(midnight),
+        to_ts: to.and_then(|day| day.succ_opt()).and_then(midnight),
+        input_token: filters.input_mint.clone(),
+        output_token: filters.output_mint.clone(),
+        status: filters.status.clone(),
+        signature: None,
+    })
+}
+
+/// Number of history pages for a total count (always at least one)
+pub fn history_page_count(total: usize) -> usize {
+    total.div_ceil(HISTORY_PAGE_SIZE).max(1)
+}
+
+/// Convert an API history row, resolving symbols and decimals from the token list.
+///
+/// Mints missing from the list show a shortened address and raw amounts.
+fn history_item(item: &ApiSwapHistoryItem, tokens: &[TokenInfo]) -> SwapHistoryItem {
+    let token = |mint: &str| tokens.iter().find(|t| t.mint == mint);
+    let symbol = |mint: &str| token(mint).map_or_else(|| shared::truncate_address(mint), |t| t.symbol.clone());
+    let amount = |raw: i64, mint: &str| {
+        let decimals = token(mint).map_or(0, |t| t.decimals);
+        raw as f64 / 10f64.powi(decimals as i32)
+    };
+
+    SwapHistoryItem {
+        signature: item.signature.clone(),
+        timestamp: chrono::DateTime::parse_from_rfc3339(&item.created_at)
+            .map(|dt| dt.timestamp())
+            .unwrap_or(0),
+        input_symbol: symbol(&item.input_mint),
+        output_symbol: symbol(&item.output_mint),
+        input_amount: amount(item.input_amount, &item.input_mint),
+        output_amount: amount(item.output_amount, &item.output_mint),
+        status: item.status.clone(),
+    }
+}
+
+/// Fetch the swap history page for the current filters
+///
+/// Internal handler function - use [`crate::app::App::handle_swap_history_refresh`] instead.
+pub(crate) fn handle_swap_history_refresh(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (filters, query, jwt_token, api_client, tokens) = {
+        let mut state = state.write();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api.clone()) else {
+            return;
+        };
+        let filters = state.terminal.swap.history_filters.clone();
+        let query = match history_query(&filters) {
+            Ok(query) => query,
+            Err(err) => {
+                state.terminal.swap.history_error = Some(err);
+                return;
+            }
+        };
+        state.terminal.swap.history_loading = true;
+        state.terminal.swap.history_error = None;
+        (filters, query, jwt_token, api_client, state.terminal.swap.token_list.clone())
+    };
+
+    let tx = event_tx.clone();
+    spawn_guarded(GuardedTask::SwapHistory, state, event_tx, async move {
+        let result = api_client.get_swap_history(&jwt_token, &query).await.map_err(|e| e.to_string()).map(|response| SwapHistoryPage {
+            items: response.swaps.iter().map(|item| history_item(item, &tokens)).collect(),
+            total: usize::try_from(response.total_count).unwrap_or(0),
+        });
+        let _ = tx.send(AppEvent::SwapHistoryResult(filters, result)).await;
+    });
+}
+
+/// Where the swap history export is written
+pub fn swap_export_path() -> PathBuf {
+    PathBuf::from("./xterminal-swaps.csv")
+}
+
+/// Header line of the swap history export
+pub const SWAP_CSV_HEADER: &str = "signature,time,sold,sold_amount,bought,bought_amount,status\n";
+
+/// Render swaps as CSV rows, without the header
+pub fn swap_csv_rows(items: &[SwapHistoryItem]) -> String {
+    let mut csv = String::new();
+    for item in items {
+        let time = chrono::DateTime::from_timestamp(item.timestamp, 0)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default();
+        let row = [
+            csv_field(&item.signature),
+            time,
+            csv_field(&item.input_symbol),
+            item.input_amount.to_string(),
+            csv_field(&item.output_symbol),
+            item.output_amount.to_string(),
+            csv_field(&item.status),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Write the swaps matching `query` to `path` as they stream in
+///
+/// Rows go straight to the file, one batch at a time, so the export never
+/// holds the history in memory. `on_progress` gets the running row count.
+async fn export_swap_history(
+    api_client: &dyn ApiService,
+    jwt_token: &str,
+    query: &SwapHistoryQuery,
+    tokens: &[TokenInfo],
+    path: &Path,
+    on_progress: &(dyn Fn(u64) + Sync),
+) -> Result<u64, String> {
+    let file = std::fs::File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut writer = std::io::BufWriter::new(file);
+    writer.write_all(SWAP_CSV_HEADER.as_bytes()).map_err(|e| e.to_string())?;
+
+    let mut written = 0;
+    let mut on_batch = |batch: &[ApiSwapHistoryItem]| {
+        let items: Vec<_> = batch.iter().map(|item| history_item(item, tokens)).collect();
+        writer.write_all(swap_csv_rows(&items).as_bytes()).map_err(|e| e.to_string())?;
+        written += batch.len() as u64;
+        on_progress(written);
+        Ok(())
+    };
+    let count = api_client.stream_history(jwt_token, query, &mut on_batch).await?;
+    writer.flush().map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+/// Export every swap matching the current filters (not just the page shown) to CSV
+///
+/// Internal handler function - use [`crate::app::App::handle_swap_history_export`] instead.
+pub(crate) fn handle_swap_history_export(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (query, jwt_token, api_client, tokens) = {
+        let mut state = state.write();
+        if state.terminal.swap.history_export.is_some() {
+            return;
+        }
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api.clone()) else {
+            return;
+        };
+        let query = match history_query(&state.terminal.swap.history_filters) {
+            Ok(query) => query,
+            Err(err) => {
+                state.terminal.swap.history_error = Some(err);
+                return;
+            }
+        };
+        state.terminal.swap.history_export = Some(0);
+        (query, jwt_token, api_client, state.terminal.swap.token_list.clone())
+    };
+
+    tokio::spawn(async move {
+        let path = swap_export_path();
+        let progress_tx = event_tx.clone();
+        let on_progress = move |count| {
+            let _ = progress_tx.try_send(AppEvent::SwapHistoryExportProgress(count));
+        };
+        let result = export_swap_history(api_client.as_ref(), &jwt_token, &query, &tokens, &path, &on_progress).await;
+        if result.is_err() {
+            // Don't leave a truncated export that looks complete
+            let _ = std::fs::remove_file(&path);
+        }
+        let _ = event_tx.send(AppEvent::SwapHistoryExportFinished(result.map(|count| (path, count)))).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::{TokenPreferences, MAX_RECENT_TOKENS};
+    use shared::dto::simulation::{SimulateSwapResponse, TokenBalanceChange};
+
+    fn token(symbol: &str, mint: &str, decimals: u8) -> TokenInfo {
+        TokenInfo {
+            symbol: symbol.to_string(),
+            name: symbol.to_string(),
+            mint: mint.to_string(),
+            decimals,
+            price: 0.0,
+            balance: 0.0,
+            change_24h: 0.0,
+            is_favorite: false,
+            verified: true,
+            daily_volume: None,
+        }
+    }
+
+    fn confirmation(input_mint: &str, output_mint: &str, simulation: SimulateSwapResponse) -> SwapConfirmation {
+        SwapConfirmation {
+            transaction: crate::services::wallet::WalletTransaction::Legacy(solana_sdk::transaction::Transaction::default()),
+            input_mint: input_mint.to_string(),
+            output_mint: output_mint.to_string(),
+            input_symbol: "IN".to_string(),
+            output_symbol: "OUT".to_string(),
+            ui_amount: 1.0,
+            amount: 1_000_000_000,
+            quote: crate::app::state::SwapQuote {
+                input_amount: 1.0,
+                output_amount: 145.0,
+                price_impact: 0.01,
+                estimated_fee: 0.000005,
+            },
+            slippage_bps: 50,
+            memo: String::new(),
+            wallet: "alice".to_string(),
+            simulation: Ok(simulation),
+            timer: crate::app::latency::SwapTimer::start(),
+        }
+    }
+
+    fn simulation(sol_change_lamports: i64, token_changes: Vec<TokenBalanceChange>) -> SimulateSwapResponse {
+        SimulateSwapResponse {
+            success: true,
+            error: None,
+            compute_units: Some(120_000),
+            network_fee_lamports: 5_000,
+            sol_change_lamports,
+            token_changes,
+            logs: Vec::new(),
+            log_tail: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_confirmation_summary_sol_to_token() {
+        let usdc = TokenBalanceChange { mint: "USDCMINT".to_string(), decimals: 6, change: 145_230_000 };
+        let confirmation = confirmation(WSOL_MINT, "USDCMINT", simulation(-1_000_005_000, vec![usdc]));
+
+        assert_eq!(
+            confirmation_summary(&confirmation).unwrap(),
+            "You will send 1 IN, receive ~145.23 OUT, network fee 0.000005 SOL"
+        );
+        assert!(confirmation.can_execute());
+    }
+
+    #[test]
+    fn test_confirmation_summary_token_to_sol() {
+        let usdc = TokenBalanceChange { mint: "USDCMINT".to_string(), decimals: 6, change: -20_000_000 };
+        let confirmation = confirmation("USDCMINT", WSOL_MINT, simulation(99_995_000, vec![usdc]));
+
+        assert_eq!(
+            confirmation_summary(&confirmation).unwrap(),
+            "You will send 20 IN, receive ~0.1 OUT, network fee 0.000005 SOL"
+        );
+    }
+
+    #[test]
+    fn test_failed_simulation_blocks_execution() {
+        let failed = SimulateSwapResponse {
+            success: false,
+            error: Some("Slippage tolerance exceeded".to_string()),
+            log_tail: vec!["Program log: Error Code: SlippageToleranceExceeded".to_string()],
+            ..simulation(0, Vec::new())
+        };
+        let blocked = confirmation(WSOL_MINT, "USDCMINT", failed);
+        assert!(!blocked.can_execute());
+        assert_eq!(blocked.error(), Some("Slippage tolerance exceeded"));
+        assert_eq!(blocked.log_tail().len(), 1);
+        assert!(confirmation_summary(&blocked).is_none());
+
+        // An unreachable simulation blocks too
+        let unavailable = SwapConfirmation { simulation: Err("Network error".to_string()), ..blocked };
+        assert!(!unavailable.can_execute());
+        assert_eq!(unavailable.error(), Some("Network error"));
+        assert!(unavailable.log_tail().is_empty());
+    }
+
+    #[test]
+    fn test_usd_estimate_flags_stale_oracle_price() {
+        let now = 1_704_067_200;
+        let sol = |publish_time: Option<i64>| crate::app::state::PriceData {
+            symbol: "IN".to_string(),
+            price: 150.0,
+            change_24h: 0.0,
+            previous_price: None,
+            source: Some("pyth".to_string()),
+            confidence: Some(0.08),
+            publish_time,
+            sources: Default::default(),
+            divergent: false,
+            divergence_pct: None,
+        };
+        let confirmation = confirmation(WSOL_MINT, "USDCMINT", simulation(0, Vec::new()));
+        let estimate = |price| {
+            let store = crate::app::price_store::PriceStore::new(vec![price]);
+            usd_estimate(&confirmation, &store.load(), now, 30)
+        };
+
+        let fresh = estimate(sol(Some(now - 5))).unwrap();
+        assert_eq!(fresh.usd, 150.0);
+        assert_eq!(fresh.stale_secs, None);
+
+        let stale = estimate(sol(Some(now - 95))).unwrap();
+        assert_eq!(stale.stale_secs, Some(95));
+        assert_eq!(stale.source.as_deref(), Some("pyth"));
+
+        // Aggregator prices have no publish time to judge
+        assert_eq!(estimate(sol(None)).unwrap().stale_secs, None);
+
+        let store = crate::app::price_store::PriceStore::new(Vec::new());
+        assert!(usd_estimate(&confirmation, &store.load(), now, 30).is_none());
+    }
+
+    #[test]
+    fn test_pair_divergence_names_disagreeing_tokens() {
+        let price = |symbol: &str, sources: &[(&str, f64)], divergence_pct: Option<f64>| PriceData {
+            symbol: symbol.to_string(),
+            price: sources[0].1,
+            change_24h: 0.0,
+            previous_price: None,
+            source: Some("jupiter".to_string()),
+            confidence: None,
+            publish_time: None,
+            sources: sources.iter().map(|(source, price)| (source.to_string(), *price)).collect(),
+            divergent: sources.len() > 1,
+            divergence_pct,
+        };
+        let confirmation = confirmation(WSOL_MINT, "USDCMINT", simulation(0, Vec::new()));
+        let divergence = |prices| {
+            let store = crate::app::price_store::PriceStore::new(prices);
+            pair_divergence(&confirmation, &store.load())
+        };
+
+        assert!(divergence(vec![price("IN", &[("jupiter", 150.0)], None), price("OUT", &[("jupiter", 1.0)], None)]).is_empty());
+
+        // The streamed percentage wins; REST prices are measured from their sources
+        let both = divergence(vec![
+            price("IN", &[("jupiter", 150.0), ("pyth", 140.0)], Some(6.9)),
+            price("OUT", &[("jupiter", 1.0), ("pyth", 0.97)], None),
+        ]);
+        assert_eq!(both.len(), 2);
+        assert_eq!(both[0], ("IN".to_string(), Some(6.9)));
+        assert_eq!(both[1].0, "OUT");
+        assert!((both[1].1.unwrap() - 3.0457).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_history_query_combines_filters() {
+        let filters = SwapHistoryFilters {
+            from_date: "2024-03-01".to_string(),
+            to_date: " 2024-03-02 ".to_string(),
+            input_mint: Some("SOLMINT".to_string()),
+            output_mint: Some("USDCMINT".to_string()),
+            status: Some("failed".to_string()),
+            page: 2,
+        };
+
+        let query = history_query(&filters).unwrap();
+        assert_eq!(query.limit, HISTORY_PAGE_SIZE);
+        assert_eq!(query.offset, 2 * HISTORY_PAGE_SIZE);
+        assert_eq!(query.from_ts, Some(1_709_251_200));
+        // Inclusive To date: the bound is the start of the next day
+        assert_eq!(query.to_ts, Some(1_709_424_000));
+        assert_eq!(query.input_token.as_deref(), Some("SOLMINT"));
+        assert_eq!(query.status.as_deref(), Some("failed"));
+
+        let open = history_query(&SwapHistoryFilters::default()).unwrap();
+        assert_eq!((open.offset, open.from_ts, open.to_ts, open.input_token), (0, None, None, None));
+    }
+
+    #[test]
+    fn test_history_query_rejects_bad_dates() {
+        let bad_format = SwapHistoryFilters { from_date: "03/01/2024".to_string(), ..Default::default() };
+        assert!(history_query(&bad_format).is_err());
+
+        let reversed = SwapHistoryFilters {
+            from_date: "2024-03-02".to_string(),
+            to_date: "2024-03-01".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(history_query(&reversed).unwrap_err(), "From date is after To date");
+    }
+
+    #[test]
+    fn test_history_page_count() {
+        assert_eq!(history_page_count(0), 1);
+        assert_eq!(history_page_count(HISTORY_PAGE_SIZE), 1);
+        assert_eq!(history_page_count(HISTORY_PAGE_SIZE + 1), 2);
+    }
+
+    #[test]
+    fn test_history_item_resolves_tokens() {
+        let tokens = [token("SOL", "SOLMINT", 9), token("USDC", "USDCMINT", 6)];
+        let item = ApiSwapHistoryItem {
+            id: 1,
+            signature: "sig".to_string(),
+            input_mint: "SOLMINT".to_string(),
+            output_mint: "USDCMINT".to_string(),
+            input_amount: 1_500_000_000,
+            output_amount: 225_000_000,
+            status: "confirmed".to_string(),
+            created_at: "2024-03-01T00:00:00+00:00".to_string(),
+        };
+
+        let converted = history_item(&item, &tokens);
+        assert_eq!(converted.input_symbol, "SOL");
+        assert_eq!(converted.input_amount, 1.5);
+        assert_eq!(converted.output_amount, 225.0);
+        assert_eq!(converted.timestamp, 1_709_251_200);
+
+        // Unknown mints keep raw amounts
+        let converted = history_item(&item, &[]);
+        assert_eq!(converted.input_amount, 1_500_000_000.0);
+        assert_ne!(converted.input_symbol, "SOL");
+    }
+
+    #[test]
+    fn test_rank_tokens_prefers_prefix_matches() {
+        let mut tokens = vec![
+            token("WSOL", "So11111111111111111111111111111111111111112", 9),
+            token("SOLA", "SoLAmint", 9),
+            token("SOL", "So11111111111111111111111111111111111111111", 9),
+            token("JSOLX", "JsoLmint", 9),
+            token("BONK", "DezXmint", 5),
+            token("MSOL", "mSoLmint", 9),
+        ];
+        tokens[4].name = "Bonk Solana".to_string();
+        tokens[5].is_favorite = true;
+
+        let symbols = |query: &str| -> Vec<&str> {
+            rank_tokens(&tokens, query).into_iter().map(|i| tokens[i].symbol.as_str()).collect()
+        };
+
+        assert_eq!(symbols("sol"), vec!["SOL", "SOLA", "MSOL", "BONK", "WSOL", "JSOLX"]);
+        assert_eq!(symbols("dezx"), vec!["BONK"]);
+        assert_eq!(symbols("bonk s"), vec!["BONK"]);
+        assert!(symbols("xyz").is_empty());
+        // Empty query lists everything, favorites first
+        assert_eq!(symbols(" ")[0], "MSOL");
+        assert_eq!(symbols("").len(), tokens.len());
+    }
+
+    #[test]
+    fn test_token_preferences_recents_and_favorites() {
+        let mut preferences = TokenPreferences::default();
+        for i in 0..12 {
+            preferences.push_recent(&format!("MINT{}", i));
+        }
+        preferences.push_recent("MINT5");
+        assert_eq!(preferences.recents.len(), MAX_RECENT_TOKENS);
+        assert_eq!(preferences.recents[0], "MINT5");
+        assert_eq!(preferences.recents[1], "MINT11");
+        assert!(!preferences.recents.contains(&"MINT1".to_string()));
+        assert_eq!(preferences.recents.iter().filter(|m| *m == "MINT5").count(), 1);
+
+        assert!(preferences.toggle_favorite("MINT3"));
+        assert!(preferences.is_favorite("MINT3"));
+        assert!(!preferences.toggle_favorite("MINT3"));
+        assert!(preferences.favorites.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_export_writes_rows_as_batches_arrive() {
+        use crate::services::api::history_stream::mock_server::{body_from, flaky_server, RECORDS};
+
+        // The first connection drops in the middle of the third batch
+        let cut_at = body_from(0, 100).find("\"sig230\"").unwrap();
+        let (url, _) = flaky_server(1, cut_at).await;
+        let api_client = crate::services::api::ApiClient::with_servers(vec![url]);
+        let tokens = vec![token("SOL", "SOL", 9), token("USDC", "USDC", 6)];
+        let path = std::env::temp_dir().join(format!("xterminal-swaps-{}.csv", std::process::id()));
+        let progress = parking_lot::Mutex::new(Vec::new());
+
+        let count = export_swap_history(&api_client, "jwt", &SwapHistoryQuery::default(), &tokens, &path, &|n| {
+            progress.lock().push(n)
+        })
+        .await
+        .unwrap();
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(count, RECORDS as u64);
+        // One update per batch; the dropped batch is only counted once it arrives whole
+        assert_eq!(*progress.lock(), vec![100, 200, 250]);
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), RECORDS + 1);
+        assert_eq!(format!("{}\n", lines[0]), SWAP_CSV_HEADER);
+        assert_eq!(lines[1], "sig0,2025-02-18T10:00:00+00:00,SOL,1,USDC,145,confirmed");
+        assert!(lines[RECORDS].starts_with("sig249,"));
+    }
+}
@@ -0,0 +1,203 @@
+//! # Trade Import Handlers
+//!
+//! Drives the Transactions screen's import wizard: pick a CSV export from
+//! another platform, validate it against a column mapping, then commit the
+//! valid rows. Also loads the trade statistics shown below the history.
+
+use crate::app::events::AppEvent;
+use crate::app::state::{AppState, TradeImportStep};
+use crate::app::tasks::guard::{spawn_guarded, GuardedTask};
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::trades::{ColumnMapping, TradeImportRequest, TradeImportResponse};
+use std::sync::Arc;
+
+/// Header names other platforms use for each field, in order of preference
+const HEADER_ALIASES: &[(&str, &[&str])] = &[
+    ("timestamp", &["timestamp", "date(utc)", "date", "time", "datetime", "created at", "executed at"]),
+    ("pair", &["pair", "market", "symbol", "instrument", "product"]),
+    ("side", &["side", "type", "direction", "action"]),
+    ("amount", &["amount", "quantity", "qty", "filled", "size", "executed"]),
+    ("price", &["price", "avg price", "average price", "fill price", "rate"]),
+    ("fee", &["fee", "fees", "commission"]),
+];
+
+/// Guess a column mapping from the header row.
+///
+/// Fields without a recognizable header keep their default name so the
+/// mapping step shows them as unmatched; an unmatched fee column is dropped.
+pub fn guess_mapping(headers: &[String]) -> ColumnMapping {
+    let find = |field: &str| -> Option<String> {
+        let aliases = HEADER_ALIASES.iter().find(|(f, _)| *f == field)?.1;
+        aliases.iter().find_map(|alias| {
+            headers
+                .iter()
+                .find(|h| h.trim().eq_ignore_ascii_case(alias))
+                .cloned()
+        })
+    };
+
+    let defaults = ColumnMapping::default();
+    ColumnMapping {
+        timestamp: find("timestamp").unwrap_or(defaults.timestamp),
+        pair: find("pair").unwrap_or(defaults.pair),
+        side: find("side").unwrap_or(defaults.side),
+        amount: find("amount").unwrap_or(defaults.amount),
+        price: find("price").unwrap_or(defaults.price),
+        fee: find("fee"),
+    }
+}
+
+/// Wizard step to show for a backend response
+pub fn next_step(response: &TradeImportResponse) -> TradeImportStep {
+    if response.committed {
+        TradeImportStep::Done
+    } else if response.errors.iter().any(|e| e.row == 0) {
+        TradeImportStep::Mapping
+    } else {
+        TradeImportStep::Review
+    }
+}
+
+/// Handle the "Choose CSV" button: read the file and validate it
+///
+/// Internal handler function - use [`crate::app::App::handle_trade_import_pick_file`] instead.
+pub(crate) fn handle_trade_import_pick_file(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let Some(path) = rfd::FileDialog::new()
+        .set_title("Import trades")
+        .add_filter("CSV", &["csv", "txt"])
+        .pick_file()
+    else {
+        return;
+    };
+
+    {
+        let mut state = state.write();
+        let import = &mut state.trade_import;
+        match std::fs::read_to_string(&path) {
+            Ok(csv) => {
+                import.file_name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+                import.csv = csv;
+                import.mapping = ColumnMapping::default();
+                import.preview = None;
+                import.error = None;
+            }
+            Err(e) => {
+                import.error = Some(format!("Failed to read {}: {}", path.display(), e));
+                return;
+            }
+        }
+    }
+
+    handle_trade_import_validate(state, event_tx);
+}
+
+/// Handle validation with the current column mapping (nothing is stored)
+///
+/// Internal handler function - use [`crate::app::App::handle_trade_import_validate`] instead.
+pub(crate) fn handle_trade_import_validate(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    send_import(state, event_tx, false);
+}
+
+/// Handle the final import of the validated rows
+///
+/// Internal handler function - use [`crate::app::App::handle_trade_import_commit`] instead.
+pub(crate) fn handle_trade_import_commit(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    send_import(state, event_tx, true);
+}
+
+fn send_import(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, commit: bool) {
+    let (request, jwt_token, api_client) = {
+        let mut state = state.write();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api.clone()) else {
+            return;
+        };
+        let import = &mut state.trade_import;
+        if import.csv.is_empty() || import.loading {
+            return;
+        }
+        import.loading = true;
+        import.error = None;
+        let request = TradeImportRequest {
+            csv: import.csv.clone(),
+            mapping: import.mapping.clone(),
+            commit,
+        };
+        (request, jwt_token, api_client)
+    };
+
+    let tx = event_tx.clone();
+    spawn_guarded(GuardedTask::TradeImport, state, event_tx, async move {
+        let result = api_client.import_trades(&jwt_token, &request).await.map_err(|e| e.to_string());
+        let _ = tx.send(AppEvent::TradeImportResult(result)).await;
+    });
+}
+
+/// Handle trade statistics refresh
+///
+/// Internal handler function - use [`crate::app::App::handle_trade_stats_refresh`] instead.
+pub(crate) fn handle_trade_stats_refresh(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (jwt_token, api_client) = {
+        let mut state = state.write();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api.clone()) else {
+            return;
+        };
+        state.trade_import.stats_loading = true;
+        (jwt_token, api_client)
+    };
+
+    let tx = event_tx.clone();
+    spawn_guarded(GuardedTask::TradeStats, state, event_tx, async move {
+        let result = api_client.get_trade_stats(&jwt_token).await.map_err(|e| e.to_string());
+        let _ = tx.send(AppEvent::TradeStatsResult(result)).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::dto::trades::RowError;
+
+    fn headers(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    fn response(error_rows: &[usize], committed: bool) -> TradeImportResponse {
+        TradeImportResponse {
+            headers: Vec::new(),
+            total_rows: 1,
+            valid: Vec::new(),
+            errors: error_rows
+                .iter()
+                .map(|&row| RowError { row, field: None, message: String::new() })
+                .collect(),
+            duplicates: 0,
+            imported: 0,
+            committed,
+        }
+    }
+
+    #[test]
+    fn test_guess_mapping_from_exchange_headers() {
+        let mapping = guess_mapping(&headers(&["Date(UTC)", "Market", "Type", "Price", "Amount", "Fee"]));
+        assert_eq!(mapping.timestamp, "Date(UTC)");
+        assert_eq!(mapping.pair, "Market");
+        assert_eq!(mapping.side, "Type");
+        assert_eq!(mapping.fee.as_deref(), Some("Fee"));
+
+        // Unknown headers keep the default name; no fee column means no fee
+        let mapping = guess_mapping(&headers(&["when", "Symbol", "Side", "Qty", "Fill Price"]));
+        assert_eq!(mapping.timestamp, "timestamp");
+        assert_eq!(mapping.amount, "Qty");
+        assert_eq!(mapping.price, "Fill Price");
+        assert_eq!(mapping.fee, None);
+    }
+
+    #[test]
+    fn test_next_step() {
+        assert_eq!(next_step(&response(&[], false)), TradeImportStep::Review);
+        assert_eq!(next_step(&response(&[3], false)), TradeImportStep::Review);
+        assert_eq!(next_step(&response(&[0], false)), TradeImportStep::Mapping);
+        assert_eq!(next_step(&response(&[], true)), TradeImportStep::Done);
+    }
+}
@@ -0,0 +1,240 @@
+//! # Transaction History Handlers
+//!
+//! Loads the connected wallet's activity from the backend (including memos on
+//! transactions received from other wallets) and exports it to CSV.
+
+use crate::app::events::AppEvent;
+use crate::app::state::{AppState, TransactionItem};
+use crate::app::usage;
+use crate::services::api::wallet::TransactionSummary;
+use crate::services::memo;
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::telemetry::{UsageEvent, UsageFeature};
+use shared::dto::transactions::TransactionStatus;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Number of transactions requested per refresh
+const HISTORY_LIMIT: usize = 50;
+
+/// Get CSV export file path
+pub fn get_export_path() -> std::path::PathBuf {
+    std::path::PathBuf::from("./xterminal-transactions.csv")
+}
+
+/// Convert a backend history entry of `wallet` into an activity feed item
+pub fn summary_to_item(summary: &TransactionSummary, wallet: &str) -> TransactionItem {
+    let memos = summary
+        .memo
+        .as_deref()
+        .map(memo::decode_history_memo)
+        .unwrap_or_default();
+
+    TransactionItem {
+        signature: summary.signature.clone(),
+        timestamp: summary.block_time.unwrap_or(0),
+        tx_type: "Transaction".to_string(),
+        status: match summary.status.as_str() {
+            "Success" => "confirmed".to_string(),
+            "Failed" => "failed".to_string(),
+            other => other.to_lowercase(),
+        },
+        amount: String::new(),
+        memo: (!memos.is_empty()).then(|| memos.join("; ")),
+        wallet: Some(wallet.to_string()),
+        latency: None,
+    }
+}
+
+/// Merge fetched history into the feed.
+///
+/// Fetched entries win, but locally recorded details (swap type, amount and
+/// pipeline latency) are kept, and local entries the RPC has not indexed yet
+/// stay on top.
+pub fn merge_history(existing: &[TransactionItem], fetched: Vec<TransactionItem>) -> Vec<TransactionItem> {
+    let mut merged: Vec<TransactionItem> = existing
+        .iter()
+        .filter(|local| !fetched.iter().any(|f| f.signature == local.signature))
+        .cloned()
+        .collect();
+
+    merged.extend(fetched.into_iter().map(|mut item| {
+        if let Some(local) = existing.iter().find(|l| l.signature == item.signature) {
+            item.tx_type = local.tx_type.clone();
+            item.amount = local.amount.clone();
+            item.memo = item.memo.or_else(|| local.memo.clone());
+            item.wallet = item.wallet.or_else(|| local.wallet.clone());
+            item.latency = item.latency.or(local.latency);
+            // History reports success as confirmed; keep what tracking saw
+            if local.status == TransactionStatus::Finalized.as_str() && item.status == "confirmed" {
+                item.status = local.status.clone();
+            }
+        }
+        item
+    }));
+    merged
+}
+
+/// Show a tracked status change on the matching activity row
+///
+/// Returns whether the row was found; a final status is never replaced by
+/// an earlier step that arrives late.
+pub fn apply_status_change(items: &mut [TransactionItem], signature: &str, status: TransactionStatus) -> bool {
+    let Some(item) = items.iter_mut().find(|item| item.signature == signature) else {
+        return false;
+    };
+    let settled = ["finalized", "failed", "expired"].contains(&item.status.as_str());
+    if !settled || status.is_final() {
+        item.status = status.as_str().to_string();
+    }
+    true
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render transactions as CSV (RFC 4180 quoting)
+pub fn to_csv(items: &[TransactionItem]) -> String {
+    let mut csv = String::from("signature,time,type,status,amount,memo\n");
+    for item in items {
+        let time = chrono::DateTime::from_timestamp(item.timestamp, 0)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default();
+        let row = [
+            csv_field(&item.signature),
+            time,
+            csv_field(&item.tx_type),
+            csv_field(&item.status),
+            csv_field(&item.amount),
+            csv_field(item.memo.as_deref().unwrap_or("")),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Handle transaction history refresh
+///
+/// Internal handler function - use [`crate::app::App::handle_transactions_refresh`] instead.
+pub(crate) fn handle_transactions_refresh(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let task = history_task(&state.read(), event_tx);
+    if let Some(task) = task {
+        tokio::spawn(task);
+    }
+}
+
+/// Task loading the connected wallet's history, `None` without a wallet
+pub(crate) fn history_task(state: &AppState, event_tx: Sender<AppEvent>) -> Option<impl Future<Output = ()> + Send + 'static> {
+    let (Some(wallet), Some(api_client)) = (state.wallet.as_ref(), state.api.clone()) else {
+        return None;
+    };
+    let address = wallet.address.clone();
+
+    Some(async move {
+        let result = api_client
+            .get_transaction_history(&address, HISTORY_LIMIT)
+            .await
+            .map_err(|e| e.to_string())
+            .map(|history| {
+                history
+                    .transactions
+                    .iter()
+                    .map(|summary| summary_to_item(summary, &history.address))
+                    .collect()
+            });
+        let _ = event_tx.send(AppEvent::TransactionHistoryResult(result)).await;
+    })
+}
+
+/// Handle transaction history CSV export
+///
+/// Internal handler function - use [`crate::app::App::handle_transactions_export`] instead.
+pub(crate) fn handle_transactions_export(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let csv = to_csv(&state.read().transactions);
+    let path = get_export_path();
+
+    let message = match std::fs::write(&path, csv) {
+        Ok(()) => {
+            usage::capture(UsageEvent::Feature(UsageFeature::Export));
+            format!("NOTIFY_SUCCESS:Exported transactions to {}", path.display())
+        }
+        Err(e) => format!("NOTIFY_ERROR:Failed to export transactions: {}", e),
+    };
+    tokio::spawn(async move {
+        let _ = event_tx.send(AppEvent::Loading(message)).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(signature: &str, memo: Option<&str>) -> TransactionItem {
+        TransactionItem {
+            signature: signature.to_string(),
+            timestamp: 1_700_000_000,
+            tx_type: "Swap".to_string(),
+            status: "pending".to_string(),
+            amount: "1 → 150".to_string(),
+            memo: memo.map(str::to_string),
+            wallet: Some("alice".to_string()),
+            latency: None,
+        }
+    }
+
+    #[test]
+    fn test_summary_decodes_external_memo() {
+        let summary = TransactionSummary {
+            signature: "abc".to_string(),
+            slot: 1,
+            block_time: Some(1_700_000_000),
+            status: "Success".to_string(),
+            memo: Some("[10] invoice 42".to_string()),
+        };
+        let item = summary_to_item(&summary, "alice");
+        assert_eq!(item.memo.as_deref(), Some("invoice 42"));
+        assert_eq!(item.status, "confirmed");
+        assert_eq!(item.wallet.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn test_merge_keeps_local_details_and_unindexed_entries() {
+        let latency = shared::dto::latency::SwapLatency { build_ms: 700, ..Default::default() };
+        let existing = vec![
+            item("pending", Some("mine")),
+            TransactionItem { latency: Some(latency), ..item("swap", Some("invoice 42")) },
+        ];
+        let fetched = vec![TransactionItem {
+            tx_type: "Transaction".to_string(),
+            status: "confirmed".to_string(),
+            amount: String::new(),
+            memo: None,
+            ..item("swap", None)
+        }];
+
+        let merged = merge_history(&existing, fetched);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].signature, "pending");
+        assert_eq!(merged[1].status, "confirmed");
+        assert_eq!(merged[1].tx_type, "Swap");
+        assert_eq!(merged[1].memo.as_deref(), Some("invoice 42"));
+        assert_eq!(merged[1].latency, Some(latency));
+    }
+
+    #[test]
+    fn test_csv_export_includes_escaped_memo() {
+        let csv = to_csv(&[item("sig1", Some("invoice 42, \"Q3\"")), item("sig2", None)]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "signature,time,type,status,amount,memo");
+        assert!(lines[1].ends_with(",\"invoice 42, \"\"Q3\"\"\""));
+        assert!(lines[2].ends_with(",pending,1 → 150,"));
+    }
+}
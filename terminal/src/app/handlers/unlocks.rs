@@ -0,0 +1,219 @@
+//! # Token Unlock Handlers
+//!
+//! Upcoming token unlocks from the backend, and the "unlock approaching"
+//! notices for tokens the user holds or starred.
+//!
+//! The unlock list is refetched with the health poll. Each fetch re-checks
+//! which unlocks of held or favorite tokens are now within
+//! [`shared::UNLOCK_WARNING_DAYS`] and announces every one of them once per
+//! session, as a [`shared::NoticeCategory::UnlockApproaching`] notice that
+//! the user can mute in Settings.
+
+use crate::app::state::AppState;
+use shared::{NoticeCategory, NoticeLevel, SystemNotice, TokenUnlockInfo, TokenUnlocksResponse};
+use std::collections::HashSet;
+
+/// Days of unlocks fetched; the token detail timeline shows all of them
+pub const UNLOCK_FETCH_DAYS: i64 = 90;
+
+/// Compact token amount (950, 12.5K, 53.5M, 2.1B)
+pub fn format_unlock_amount(amount: f64) -> String {
+    if amount >= 1e9 {
+        format!("{:.1}B", amount / 1e9)
+    } else if amount >= 1e6 {
+        format!("{:.1}M", amount / 1e6)
+    } else if amount >= 1e3 {
+        format!("{:.1}K", amount / 1e3)
+    } else {
+        format!("{:.0}", amount)
+    }
+}
+
+/// Mints whose unlocks the user is notified about: non-empty wallet balances
+/// and favorites
+fn watched_mints(state: &AppState) -> HashSet<String> {
+    let held = state
+        .wallet
+        .iter()
+        .flat_map(|w| &w.token_balances)
+        .filter(|b| b.amount > 0.0)
+        .map(|b| b.mint.clone());
+    held.chain(state.settings.tokens.favorites.iter().cloned()).collect()
+}
+
+/// Approaching unlocks of `watched` mints that haven't been announced
+pub fn due_unlock_notices<'a>(
+    unlocks: &'a [TokenUnlockInfo],
+    watched: &HashSet<String>,
+    notified: &HashSet<i64>,
+    now: i64,
+) -> Vec<&'a TokenUnlockInfo> {
+    unlocks
+        .iter()
+        .filter(|u| u.is_approaching(now))
+        .filter(|u| watched.contains(&u.mint))
+        .filter(|u| !notified.contains(&u.id))
+        .collect()
+}
+
+/// Notice announcing an approaching unlock
+pub fn unlock_notice(unlock: &TokenUnlockInfo, now: i64) -> SystemNotice {
+    let days = unlock.days_until(now);
+    let when = chrono::DateTime::from_timestamp(unlock.unlock_at, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+    let mut details = Vec::new();
+    if let Some(pct) = unlock.pct_of_circulating {
+        details.push(format!("{:.2}% of circulating supply", pct));
+    }
+    if let Some(allocation) = &unlock.allocation {
+        details.push(allocation.clone());
+    }
+    let details = if details.is_empty() { String::new() } else { format!(" ({})", details.join(", ")) };
+
+    SystemNotice {
+        level: NoticeLevel::Warning,
+        title: format!("{} unlock in {} day{}", unlock.symbol, days, if days == 1 { "" } else { "s" }),
+        message: format!(
+            "{} {}{} unlocks on {}",
+            format_unlock_amount(unlock.amount),
+            unlock.symbol,
+            details,
+            when
+        ),
+        timestamp: now,
+        category: NoticeCategory::UnlockApproaching,
+        maintenance: None,
+    }
+}
+
+/// Store a fetched unlock list and return the notices now due.
+///
+/// Due unlocks are marked announced whether or not their category is muted,
+/// so unmuting later doesn't replay old ones.
+pub(crate) fn apply_token_unlocks(
+    state: &mut AppState,
+    result: Result<TokenUnlocksResponse, String>,
+    now: i64,
+) -> Vec<SystemNotice> {
+    match result {
+        Ok(response) => {
+            state.token_unlocks.upcoming = response.unlocks;
+            state.token_unlocks.error = None;
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "Failed to fetch token unlocks");
+            state.token_unlocks.error = Some(err);
+            return Vec::new();
+        }
+    }
+
+    let watched = watched_mints(state);
+    let unlocks = &state.token_unlocks;
+    let notices: Vec<(i64, SystemNotice)> = due_unlock_notices(&unlocks.upcoming, &watched, &unlocks.notified, now)
+        .into_iter()
+        .map(|u| (u.id, unlock_notice(u, now)))
+        .collect();
+    state.token_unlocks.notified.extend(notices.iter().map(|(id, _)| *id));
+    notices.into_iter().map(|(_, notice)| notice).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::{TokenBalance, WalletState};
+
+    const NOW: i64 = 1_740_000_000;
+    const DAY: i64 = 86_400;
+
+    fn unlock(id: i64, mint: &str, in_secs: i64) -> TokenUnlockInfo {
+        TokenUnlockInfo {
+            id,
+            mint: mint.to_string(),
+            symbol: mint.trim_end_matches("mint").to_uppercase(),
+            unlock_at: NOW + in_secs,
+            amount: 53_472_222.0,
+            circulating_supply: Some(1_350_000_000.0),
+            pct_of_circulating: Some(3.961),
+            allocation: Some("Team".to_string()),
+            source: "import".to_string(),
+        }
+    }
+
+    fn test_state() -> AppState {
+        let mut state = crate::app::App::new().state.read().clone();
+        state.settings.tokens.favorites = vec!["PYTHmint".to_string()];
+        state.wallet = Some(WalletState {
+            address: "wallet".to_string(),
+            sol_balance: 1.0,
+            token_balances: vec![
+                TokenBalance { symbol: "JUP".to_string(), mint: "JUPmint".to_string(), amount: 10.0, ..Default::default() },
+                // Emptied account: not held any more
+                TokenBalance { symbol: "WIF".to_string(), mint: "WIFmint".to_string(), amount: 0.0, ..Default::default() },
+            ],
+        });
+        state
+    }
+
+    #[test]
+    fn test_due_notices_cover_held_and_favorite_tokens_in_window() {
+        let unlocks = [
+            unlock(1, "JUPmint", 3 * DAY),
+            unlock(2, "PYTHmint", 7 * DAY),
+            unlock(3, "WIFmint", DAY),
+            unlock(4, "BONKmint", DAY),
+            unlock(5, "JUPmint", 8 * DAY),
+            unlock(6, "JUPmint", -60),
+        ];
+        let state = test_state();
+        let watched = watched_mints(&state);
+
+        let due: Vec<i64> = due_unlock_notices(&unlocks, &watched, &HashSet::new(), NOW).iter().map(|u| u.id).collect();
+        assert_eq!(due, [1, 2]);
+
+        let notified = HashSet::from([1]);
+        let due: Vec<i64> = due_unlock_notices(&unlocks, &watched, &notified, NOW).iter().map(|u| u.id).collect();
+        assert_eq!(due, [2]);
+    }
+
+    #[test]
+    fn test_apply_announces_each_unlock_once() {
+        let mut state = test_state();
+        let response = || TokenUnlocksResponse { unlocks: vec![unlock(1, "JUPmint", 3 * DAY), unlock(5, "JUPmint", 8 * DAY)] };
+
+        let notices = apply_token_unlocks(&mut state, Ok(response()), NOW);
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].category, NoticeCategory::UnlockApproaching);
+        assert_eq!(notices[0].title, "JUP unlock in 3 days");
+        assert_eq!(notices[0].message, "53.5M JUP (3.96% of circulating supply, Team) unlocks on 2025-02-22 21:20 UTC");
+        assert_eq!(state.token_unlocks.upcoming.len(), 2);
+
+        // Refetch: nothing new, until the second unlock enters the window
+        assert!(apply_token_unlocks(&mut state, Ok(response()), NOW + DAY / 2).is_empty());
+        let notices = apply_token_unlocks(&mut state, Ok(response()), NOW + 2 * DAY);
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].title, "JUP unlock in 6 days");
+
+        // A failed fetch keeps the list
+        assert!(apply_token_unlocks(&mut state, Err("timeout".to_string()), NOW).is_empty());
+        assert_eq!(state.token_unlocks.upcoming.len(), 2);
+        assert_eq!(state.token_unlocks.error.as_deref(), Some("timeout"));
+    }
+
+    #[test]
+    fn test_approaching_lookup_by_symbol() {
+        let mut state = test_state();
+        state.token_unlocks.upcoming = vec![unlock(5, "JUPmint", 8 * DAY), unlock(1, "JUPmint", 9 * DAY)];
+        assert!(state.token_unlocks.approaching("jup", NOW).is_none());
+        assert_eq!(state.token_unlocks.approaching("JUP", NOW + 2 * DAY).map(|u| u.id), Some(5));
+        assert_eq!(state.token_unlocks.for_symbol("Jup").count(), 2);
+    }
+
+    #[test]
+    fn test_format_unlock_amount() {
+        assert_eq!(format_unlock_amount(950.0), "950");
+        assert_eq!(format_unlock_amount(12_500.0), "12.5K");
+        assert_eq!(format_unlock_amount(53_472_222.0), "53.5M");
+        assert_eq!(format_unlock_amount(2_100_000_000.0), "2.1B");
+    }
+}
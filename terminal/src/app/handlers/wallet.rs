@@ -1,11 +1,21 @@
 //! # Wallet Handlers
 //!
-//! Handlers for wallet connection, generation, and disconnection.
+//! Handlers for wallet connection, generation, and disconnection, for
+//! loading the connected wallet's SOL and token balances, and for the
+//! per-wallet labels and colors.
 
-use crate::app::state::{AppState, WalletState};
+use crate::app::audit::{self, AuditCategory, AuditReference};
+use crate::app::state::{AppState, TokenBalance, WalletState};
+use crate::app::wallet_identity::WalletIdentities;
 use crate::app::events::AppEvent;
+use crate::app::recovery::RecoveryFlow;
+use crate::core::service::ApiService;
+use crate::core::store::store;
+use crate::services::api::wallet::TokenBalance as ApiTokenBalance;
 use async_channel::Sender;
 use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
@@ -30,8 +40,7 @@ pub(crate) fn handle_wallet_connect_click(
     let tx = event_tx.clone();
     
     // Load keypair synchronously before spawning async task
-    let rpc_url = std::env::var("SOLANA_RPC_URL")
-        .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+    let rpc_url = state.read().config.rpc_endpoint();
     let mut wallet_service = crate::services::wallet::WalletService::new(&rpc_url);
     
     let keypair_result = wallet_service.load_keypair_from_file(&path);
@@ -74,7 +83,7 @@ pub(crate) fn handle_wallet_connect_click(
                 Ok(balance) => {
                     // Reconstruct wallet_service from keypair
                     let wallet_service = crate::services::wallet::WalletService::from_keypair(&rpc_url_clone, keypair);
-                    {
+                    let snapshots = {
                         let mut state = state_clone.write();
                         state.wallet_service = Some(wallet_service);
                         state.wallet = Some(WalletState {
@@ -82,11 +91,27 @@ pub(crate) fn handle_wallet_connect_click(
                             sol_balance: balance,
                             token_balances: Vec::new(),
                         });
-                    } // Drop the lock guard before await
+                        audit::record(
+                            &mut state.security.trail,
+                            AuditCategory::Wallet,
+                            "Connected wallet from keypair file",
+                            vec![AuditReference::Account(pubkey_clone.clone())],
+                        );
+                        super::recovery::resolve(&mut state, RecoveryFlow::Wallet);
+                        super::portfolio::recompute_portfolio(&mut state)
+                    }; // Drop the lock guard before await
+                    if let Some(snapshots) = snapshots {
+                        if let Err(e) = super::portfolio::save_snapshots(&snapshots) {
+                            tracing::warn!("Failed to save portfolio history: {}", e);
+                        }
+                    }
                     let _ = tx.send(AppEvent::Loading(format!("Wallet connected: {}", pubkey_clone))).await;
+                    super::transactions::handle_transactions_refresh(state_clone.clone(), tx.clone());
+                    handle_token_balances_refresh(state_clone.clone(), tx.clone());
                 }
                 Err(e) => {
-                    let _ = tx.send(AppEvent::Loading(format!("Failed to get balance: {}", e))).await;
+                    let error = format!("Failed to get balance: {}", e);
+                    let _ = tx.send(AppEvent::RecoverableError { flow: RecoveryFlow::Wallet, error }).await;
                 }
             }
         });
@@ -109,8 +134,7 @@ pub(crate) fn handle_wallet_generate_click(
     let tx = event_tx.clone();
     
     // Generate keypair synchronously before spawning async task
-    let rpc_url = std::env::var("SOLANA_RPC_URL")
-        .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+    let rpc_url = state.read().config.rpc_endpoint();
     let mut wallet_service = crate::services::wallet::WalletService::new(&rpc_url);
     let pubkey = wallet_service.generate_new_keypair();
     let keypair = wallet_service.take_keypair();
@@ -141,7 +165,7 @@ pub(crate) fn handle_wallet_generate_click(
                 Ok(balance) => {
                     // Reconstruct wallet_service from keypair
                     let wallet_service = crate::services::wallet::WalletService::from_keypair(&rpc_url_clone, keypair);
-                    {
+                    let snapshots = {
                         let mut state = state_clone.write();
                         state.wallet_service = Some(wallet_service);
                         state.wallet = Some(WalletState {
@@ -149,7 +173,19 @@ pub(crate) fn handle_wallet_generate_click(
                             sol_balance: balance,
                             token_balances: Vec::new(),
                         });
-                    } // Drop the lock guard before await
+                        audit::record(
+                            &mut state.security.trail,
+                            AuditCategory::Wallet,
+                            "Generated new wallet",
+                            vec![AuditReference::Account(pubkey_clone.clone())],
+                        );
+                        super::portfolio::recompute_portfolio(&mut state)
+                    }; // Drop the lock guard before await
+                    if let Some(snapshots) = snapshots {
+                        if let Err(e) = super::portfolio::save_snapshots(&snapshots) {
+                            tracing::warn!("Failed to save portfolio history: {}", e);
+                        }
+                    }
                     let _ = tx.send(AppEvent::Loading(format!("Wallet generated: {}", pubkey_clone))).await;
                 }
                 Err(e) => {
@@ -160,6 +196,16 @@ pub(crate) fn handle_wallet_generate_click(
     }
 }
 
+/// Point the wallet at another RPC endpoint, keeping its keypair
+pub(crate) fn rebuild_wallet_service(state: &mut AppState, rpc_url: &str) {
+    if let Some(mut wallet_service) = state.wallet_service.take() {
+        state.wallet_service = Some(match wallet_service.take_keypair() {
+            Some(keypair) => crate::services::wallet::WalletService::from_keypair(rpc_url, keypair),
+            None => crate::services::wallet::WalletService::new(rpc_url),
+        });
+    }
+}
+
 /// Handle wallet disconnect button click
 ///
 /// Internal handler function - use [`crate::app::App::handle_wallet_disconnect_click`] instead.
@@ -168,7 +214,253 @@ pub(crate) fn handle_wallet_disconnect_click(state: Arc<RwLock<AppState>>) {
     if let Some(ref mut wallet_service) = state.wallet_service {
         wallet_service.disconnect();
     }
+    if let Some(address) = state.wallet.as_ref().map(|wallet| wallet.address.clone()) {
+        audit::record(
+            &mut state.security.trail,
+            AuditCategory::Wallet,
+            "Disconnected wallet",
+            vec![AuditReference::Account(address)],
+        );
+    }
     state.wallet_service = None;
     state.wallet = None;
+    let _ = super::portfolio::recompute_portfolio(&mut state);
+}
+
+/// Load wallet labels and colors from the local database
+pub fn load_wallet_identities() -> WalletIdentities {
+    match store().wallets().load() {
+        Ok(identities) => identities,
+        Err(e) => {
+            tracing::warn!("Failed to load wallet labels: {}. Using defaults.", e);
+            WalletIdentities::default()
+        }
+    }
+}
+
+/// Handle Save on the wallet label editor
+///
+/// Internal handler function - use [`crate::app::App::handle_wallet_label_save`] instead.
+pub(crate) fn handle_wallet_label_save(state: Arc<RwLock<AppState>>) {
+    let mut state = state.write();
+    let Some(edit) = state.wallet_label_edit.take() else {
+        return;
+    };
+    state.wallet_identities.set(&edit.address, &edit.label, edit.color);
+
+    let notification = match store().wallets().replace(&state.wallet_identities) {
+        Ok(()) => ("success".to_string(), format!("Wallet shown as \"{}\"", state.wallet_identities.label(&edit.address))),
+        Err(e) => {
+            tracing::error!("Failed to save wallet labels: {}", e);
+            ("error".to_string(), format!("Failed to save wallet label: {}", e))
+        }
+    };
+    state.pending_notifications.push(notification);
+}
+
+
+/// Handle Refresh on the wallet's token balances (also run after connecting)
+///
+/// Balances come from the backend and cover both the SPL Token and the
+/// Token-2022 program. Symbols and USD values are filled in from the swap
+/// token list when it knows the mint.
+///
+/// Internal handler function - use [`crate::app::App::handle_token_balances_refresh`] instead.
+pub(crate) fn handle_token_balances_refresh(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let task = token_balances_task(&state.read(), event_tx);
+    if let Some(task) = task {
+        tokio::spawn(task);
+    }
+}
+
+/// Re-read the connected wallet's SOL and token balances, after a transfer landed
+pub(crate) fn refresh_balances(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (sol, tokens) = {
+        let state = state.read();
+        (sol_balance_task(&state, event_tx.clone()), token_balances_task(&state, event_tx))
+    };
+    if let Some(sol) = sol {
+        tokio::spawn(sol);
+    }
+    if let Some(tokens) = tokens {
+        tokio::spawn(tokens);
+    }
+}
+
+/// Task loading the connected wallet's token balances, `None` without a wallet
+pub(crate) fn token_balances_task(
+    state: &AppState,
+    event_tx: Sender<AppEvent>,
+) -> Option<impl Future<Output = ()> + Send + 'static> {
+    let (Some(wallet), Some(api_client)) = (state.wallet.as_ref(), state.api.clone()) else {
+        return None;
+    };
+    let address = wallet.address.clone();
+    let known_tokens: HashMap<String, (String, f64)> = state
+        .terminal
+        .swap
+        .token_list
+        .iter()
+        .map(|token| (token.mint.clone(), (token.symbol.clone(), token.price)))
+        .collect();
+
+    Some(async move {
+        match api_client.get_token_balances(&address).await {
+            Ok(balances) => {
+                let balances = balances
+                    .into_iter()
+                    .map(|balance| to_wallet_balance(balance, &known_tokens))
+                    .collect();
+                let _ = event_tx.send(AppEvent::TokenBalancesUpdated(balances)).await;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to fetch token balances: {}", e);
+                let error = format!("Failed to load token balances: {}", e);
+                let _ = event_tx.send(AppEvent::RecoverableError { flow: RecoveryFlow::Wallet, error }).await;
+            }
+        }
+    })
+}
+
+/// Where [`sol_balance_task`] reads the SOL balance from
+enum BalanceSource {
+    /// RPC node of the connected wallet
+    Rpc(String),
+    /// Mock backend of the offline demo, whose wallet has no RPC node
+    Offline(Arc<dyn ApiService>),
+}
+
+/// Task re-reading the connected wallet's SOL balance from its RPC node (the
+/// mock backend in the offline demo), `None` without a wallet
+pub(crate) fn sol_balance_task(
+    state: &AppState,
+    event_tx: Sender<AppEvent>,
+) -> Option<impl Future<Output = ()> + Send + 'static> {
+    let address = state.wallet.as_ref()?.address.clone();
+    let source = match state.api.clone().filter(|_| state.offline) {
+        Some(api) => BalanceSource::Offline(api),
+        None => BalanceSource::Rpc(state.wallet_service.as_ref()?.rpc_client().url()),
+    };
+
+    Some(async move {
+        let pubkey = address.clone();
+        let balance = match source {
+            BalanceSource::Offline(api) => {
+                api.get_wallet_balance(&address).await.map(|b| b.balance_sol).map_err(|e| e.to_string())
+            }
+            BalanceSource::Rpc(rpc_url) => tokio::task::spawn_blocking(move || {
+                let pubkey = Pubkey::from_str(&pubkey).map_err(|e| format!("Invalid pubkey: {}", e))?;
+                RpcClient::new(rpc_url)
+                    .get_balance(&pubkey)
+                    .map(|lamports| lamports as f64 / 1_000_000_000.0)
+                    .map_err(|e| e.to_string())
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result),
+        };
+
+        match balance {
+            Ok(balance) => {
+                let _ = event_tx.send(AppEvent::SolBalanceUpdated { address, balance }).await;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to fetch SOL balance: {}", e);
+                let error = format!("Failed to get balance: {}", e);
+                let _ = event_tx.send(AppEvent::RecoverableError { flow: RecoveryFlow::Wallet, error }).await;
+            }
+        }
+    })
+}
+
+/// Convert a backend balance, naming it after the token list or a shortened mint
+fn to_wallet_balance(balance: ApiTokenBalance, known_tokens: &HashMap<String, (String, f64)>) -> TokenBalance {
+    let known = known_tokens.get(&balance.mint);
+    let symbol = balance
+        .symbol
+        .clone()
+        .or_else(|| known.map(|(symbol, _)| symbol.clone()))
+        .unwrap_or_else(|| format!("{}...", balance.mint.chars().take(6).collect::<String>()));
+    let usd_value = known.map(|(_, price)| price * balance.balance).unwrap_or(0.0);
+
+    TokenBalance {
+        symbol,
+        amount: balance.balance,
+        usd_value,
+        mint: balance.mint,
+        decimals: balance.decimals,
+        raw_amount: balance.amount,
+        program: balance.program,
+        transfer_fee: balance.transfer_fee,
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::dto::tokens::{TokenProgram, TransferFee};
+
+    fn api_balance(mint: &str, symbol: Option<&str>, program: TokenProgram) -> ApiTokenBalance {
+        ApiTokenBalance {
+            mint: mint.to_string(),
+            symbol: symbol.map(str::to_string),
+            balance: 2.5,
+            ui_amount: "2.5".to_string(),
+            amount: 2_500_000,
+            decimals: 6,
+            program,
+            transfer_fee: None,
+        }
+    }
+
+    #[test]
+    fn test_to_wallet_balance() {
+        let known = HashMap::from([("MintUSDC".to_string(), ("USDC".to_string(), 1.0))]);
+
+        let balance = to_wallet_balance(api_balance("MintUSDC", None, TokenProgram::Spl), &known);
+        assert_eq!(balance.symbol, "USDC");
+        assert_eq!(balance.usd_value, 2.5);
+        assert_eq!(balance.raw_amount, 2_500_000);
+
+        let mut fee_token = api_balance("FeeMint11111", None, TokenProgram::Token2022);
+        fee_token.transfer_fee = Some(TransferFee { basis_points: 50, maximum_fee: 1_000 });
+        let balance = to_wallet_balance(fee_token, &known);
+        assert_eq!(balance.symbol, "FeeMin...");
+        assert_eq!(balance.usd_value, 0.0);
+        assert_eq!(balance.program, TokenProgram::Token2022);
+        assert!(balance.transfer_fee.is_some());
+    }
+
+    #[test]
+    fn test_older_backend_balances_default_to_spl() {
+        let json = r#"{"mint": "MintUSDC", "symbol": null, "balance": 1.0, "ui_amount": "1"}"#;
+        let balance: ApiTokenBalance = serde_json::from_str(json).unwrap();
+        assert_eq!(balance.program, TokenProgram::Spl);
+        assert!(balance.transfer_fee.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_token_balances_feed_the_portfolio() {
+        use crate::services::offline::OfflineApi;
+
+        let mut state = crate::app::App::new().state.read().clone();
+        state.api = Some(Arc::new(OfflineApi::new()) as Arc<dyn ApiService>);
+        state.wallet = Some(WalletState { address: "demo".to_string(), sol_balance: 25.0, token_balances: Vec::new() });
+        let (tx, rx) = async_channel::unbounded();
+
+        token_balances_task(&state, tx).expect("wallet is connected").await;
+        let Ok(AppEvent::TokenBalancesUpdated(balances)) = rx.try_recv() else {
+            panic!("expected a token balance update");
+        };
+
+        // What the TokenBalancesUpdated handler does with them
+        if let Some(wallet) = state.wallet.as_mut() {
+            wallet.token_balances = balances;
+        }
+        let _ = crate::app::handlers::portfolio::recompute_portfolio(&mut state);
+        let symbols: Vec<&str> = state.portfolio.holdings.iter().map(|h| h.symbol.as_str()).collect();
+        assert!(symbols.contains(&"SOL"));
+        assert!(symbols.contains(&"USDC"));
+        assert!(symbols.contains(&"BONK"));
+    }
+}
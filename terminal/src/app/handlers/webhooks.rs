@@ -0,0 +1,199 @@
+//! # Webhook Handlers
+//!
+//! Handlers for Settings > Webhooks: registering webhooks for wallet activity,
+//! removing them, reading their delivery logs and sending test events.
+//! Delivery itself happens on the backend; the terminal only manages it.
+
+use crate::app::events::AppEvent;
+use crate::app::state::{AppState, WebhooksState};
+use crate::app::tasks::guard::{spawn_guarded, GuardedTask};
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::webhooks::{CreateWebhookRequest, WebhookEventType};
+use std::sync::Arc;
+
+/// Build a registration from the Settings form
+pub fn parse_webhook_form(form: &WebhooksState) -> Result<CreateWebhookRequest, String> {
+    let url = form.url_input.trim();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err("Webhook URL must start with https:// or http://".to_string());
+    }
+    if form.events_input.is_empty() {
+        return Err("Pick at least one event".to_string());
+    }
+
+    let threshold = form.threshold_input.trim();
+    let balance_threshold_sol = if form.events_input.contains(&WebhookEventType::BalanceThreshold) {
+        let sol = threshold
+            .parse::<f64>()
+            .ok()
+            .filter(|sol| sol.is_finite() && *sol > 0.0)
+            .ok_or_else(|| "Balance threshold must be a positive SOL amount".to_string())?;
+        Some(sol)
+    } else {
+        None
+    };
+
+    Ok(CreateWebhookRequest {
+        url: url.to_string(),
+        events: form.events_input.clone(),
+        balance_threshold_sol,
+    })
+}
+
+/// Handle Refresh in Settings > Webhooks (also the first time the section is shown)
+///
+/// Internal handler function - use [`crate::app::App::handle_webhooks_refresh`] instead.
+pub(crate) fn handle_webhooks_refresh(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (jwt_token, api_client) = {
+        let mut state = state.write();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api_client.clone()) else {
+            return;
+        };
+        if state.webhooks.loading {
+            return;
+        }
+        state.webhooks.loading = true;
+        (jwt_token, api_client)
+    };
+
+    let tx = event_tx.clone();
+    spawn_guarded(GuardedTask::Webhooks, state, event_tx, async move {
+        let result = api_client.list_webhooks(&jwt_token).await.map_err(|e| e.to_string()).map(|response| response.webhooks);
+        let _ = tx.send(AppEvent::WebhooksResult(result)).await;
+    });
+}
+
+/// Handle Add Webhook
+///
+/// Internal handler function - use [`crate::app::App::handle_webhook_create`] instead.
+pub(crate) fn handle_webhook_create(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (request, jwt_token, api_client) = {
+        let mut state = state.write();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api_client.clone()) else {
+            return;
+        };
+        if state.webhooks.creating {
+            return;
+        }
+        match parse_webhook_form(&state.webhooks) {
+            Ok(request) => {
+                state.webhooks.creating = true;
+                state.webhooks.error = None;
+                (request, jwt_token, api_client)
+            }
+            Err(err) => {
+                state.webhooks.error = Some(err);
+                return;
+            }
+        }
+    };
+
+    let tx = event_tx.clone();
+    spawn_guarded(GuardedTask::WebhookCreate, state, event_tx, async move {
+        let result = api_client.create_webhook(&jwt_token, &request).await.map_err(|e| e.to_string());
+        let _ = tx.send(AppEvent::WebhookCreated(result)).await;
+    });
+}
+
+/// Handle Delete on a webhook row
+///
+/// Internal handler function - use [`crate::app::App::handle_webhook_delete`] instead.
+pub(crate) fn handle_webhook_delete(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, id: i64) {
+    let (jwt_token, api_client) = {
+        let state = state.read();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api_client.clone()) else {
+            return;
+        };
+        (jwt_token, api_client)
+    };
+
+    tokio::spawn(async move {
+        let result = api_client.delete_webhook(&jwt_token, id).await.map_err(|e| e.to_string());
+        let _ = event_tx.send(AppEvent::WebhookDeleted(id, result)).await;
+    });
+}
+
+/// Show (and reload) the delivery log of a webhook
+///
+/// Internal handler function - use [`crate::app::App::handle_webhook_deliveries`] instead.
+pub(crate) fn handle_webhook_deliveries(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, id: i64) {
+    let (jwt_token, api_client) = {
+        let mut state = state.write();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api_client.clone()) else {
+            return;
+        };
+        if state.webhooks.selected != Some(id) {
+            state.webhooks.deliveries.clear();
+        }
+        state.webhooks.selected = Some(id);
+        state.webhooks.deliveries_loading = true;
+        (jwt_token, api_client)
+    };
+
+    let tx = event_tx.clone();
+    spawn_guarded(GuardedTask::WebhookDeliveries, state, event_tx, async move {
+        let result = api_client
+            .get_webhook_deliveries(&jwt_token, id)
+            .await
+            .map_err(|e| e.to_string())
+            .map(|response| response.deliveries);
+        let _ = tx.send(AppEvent::WebhookDeliveriesResult(id, result)).await;
+    });
+}
+
+/// Handle Send Test Event on a webhook row
+///
+/// Internal handler function - use [`crate::app::App::handle_webhook_test`] instead.
+pub(crate) fn handle_webhook_test(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, id: i64) {
+    let (jwt_token, api_client) = {
+        let mut state = state.write();
+        let (Some(jwt_token), Some(api_client)) = (state.auth_token.clone(), state.api_client.clone()) else {
+            return;
+        };
+        if state.webhooks.testing.is_some() {
+            return;
+        }
+        state.webhooks.testing = Some(id);
+        (jwt_token, api_client)
+    };
+
+    tokio::spawn(async move {
+        let result = api_client.send_test_webhook(&jwt_token, id).await.map_err(|e| e.to_string());
+        let _ = event_tx.send(AppEvent::WebhookTestResult(id, result)).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(url: &str, events: Vec<WebhookEventType>, threshold: &str) -> WebhooksState {
+        WebhooksState {
+            url_input: url.to_string(),
+            events_input: events,
+            threshold_input: threshold.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_webhook_form() {
+        let request = parse_webhook_form(&form(
+            " https://bot.example/hook ",
+            vec![WebhookEventType::SwapConfirmed, WebhookEventType::BalanceThreshold],
+            "2.5",
+        ))
+        .unwrap();
+        assert_eq!(request.url, "https://bot.example/hook");
+        assert_eq!(request.balance_threshold_sol, Some(2.5));
+
+        // The threshold only matters when its event is picked
+        let request = parse_webhook_form(&form("https://bot.example", vec![WebhookEventType::IncomingTransfer], "junk")).unwrap();
+        assert_eq!(request.balance_threshold_sol, None);
+
+        assert!(parse_webhook_form(&form("bot.example", vec![WebhookEventType::IncomingTransfer], "")).is_err());
+        assert!(parse_webhook_form(&form("https://bot.example", vec![], "")).is_err());
+        assert!(parse_webhook_form(&form("https://bot.example", vec![WebhookEventType::BalanceThreshold], "0")).is_err());
+    }
+}
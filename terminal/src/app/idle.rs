@@ -0,0 +1,198 @@
+//! # Idle Teardown
+//!
+//! A terminal left minimized overnight would otherwise hold its websocket,
+//! HTTP and RPC connections open for hours; some corporate proxies cut such
+//! connections without telling either end, leaving them half-open.
+//!
+//! [`IdleMonitor`] watches the window's focus and input each frame. Once the
+//! window has been unfocused or minimized with no interaction for the
+//! configured time (Settings > Servers), it asks for a teardown and the app
+//! enters [`PowerState::LowPower`]: connections closed, background polling
+//! paused, repaints slowed. The next interaction asks for a resume; the
+//! status bar shows "Reconnecting" until the price stream is back.
+
+use std::time::{Duration, Instant};
+
+/// Default idle time before teardown, in minutes
+pub const DEFAULT_IDLE_TEARDOWN_MINS: u64 = 15;
+
+/// Longest "Reconnecting" is shown when the price stream doesn't come back
+pub const RECONNECT_STATUS_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Whether the app is holding its connections open
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PowerState {
+    #[default]
+    Active,
+    /// Connections closed and background polling paused
+    LowPower,
+    /// Back from low power, waiting for the price stream
+    Reconnecting,
+}
+
+/// What the window reported this frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WindowSignals {
+    pub focused: bool,
+    pub minimized: bool,
+    /// Any key, click, scroll or pointer movement
+    pub interacted: bool,
+}
+
+/// Change the app has to carry out after [`IdleMonitor::observe`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleTransition {
+    /// Close connections and pause polling
+    TearDown,
+    /// Reconnect everything
+    Resume,
+}
+
+/// Tracks activity and the current [`PowerState`]
+#[derive(Debug, Clone)]
+pub struct IdleMonitor {
+    power: PowerState,
+    last_activity: Instant,
+    reconnecting_since: Option<Instant>,
+    was_focused: bool,
+}
+
+impl IdleMonitor {
+    pub fn new(now: Instant) -> Self {
+        Self { power: PowerState::Active, last_activity: now, reconnecting_since: None, was_focused: true }
+    }
+
+    pub fn power(&self) -> PowerState {
+        self.power
+    }
+
+    /// Connections are closed; background work should wait
+    pub fn is_low_power(&self) -> bool {
+        self.power == PowerState::LowPower
+    }
+
+    /// Feed one frame's window signals
+    ///
+    /// `teardown_after` of `None` disables teardown. Regaining focus counts
+    /// as an interaction.
+    pub fn observe(&mut self, signals: WindowSignals, teardown_after: Option<Duration>, now: Instant) -> Option<IdleTransition> {
+        let regained_focus = signals.focused && !self.was_focused;
+        self.was_focused = signals.focused;
+
+        if signals.interacted || regained_focus {
+            self.last_activity = now;
+            if self.power == PowerState::LowPower {
+                self.power = PowerState::Reconnecting;
+                self.reconnecting_since = Some(now);
+                return Some(IdleTransition::Resume);
+            }
+            return None;
+        }
+
+        let away = !signals.focused || signals.minimized;
+        let idle_for = now.saturating_duration_since(self.last_activity);
+        match teardown_after {
+            Some(limit) if away && self.power != PowerState::LowPower && idle_for >= limit => {
+                self.power = PowerState::LowPower;
+                self.reconnecting_since = None;
+                Some(IdleTransition::TearDown)
+            }
+            _ => None,
+        }
+    }
+
+    /// Leave "Reconnecting" once the price stream is up or after
+    /// [`RECONNECT_STATUS_TIMEOUT`]
+    pub fn settle(&mut self, stream_connected: bool, now: Instant) {
+        let Some(since) = self.reconnecting_since else {
+            return;
+        };
+        if stream_connected || now.saturating_duration_since(since) >= RECONNECT_STATUS_TIMEOUT {
+            self.power = PowerState::Active;
+            self.reconnecting_since = None;
+        }
+    }
+}
+
+impl Default for IdleMonitor {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+/// Idle time before teardown for a setting in minutes; 0 disables it
+pub fn teardown_after(mins: u64) -> Option<Duration> {
+    (mins > 0).then(|| Duration::from_secs(mins * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: Option<Duration> = Some(Duration::from_secs(60));
+
+    fn away() -> WindowSignals {
+        WindowSignals { focused: false, minimized: true, interacted: false }
+    }
+
+    #[test]
+    fn test_tears_down_only_when_away_and_idle() {
+        let start = Instant::now();
+        let mut monitor = IdleMonitor::new(start);
+
+        // Focused but untouched: never torn down
+        let focused = WindowSignals { focused: true, ..Default::default() };
+        assert_eq!(monitor.observe(focused, LIMIT, start + Duration::from_secs(600)), None);
+
+        // Unfocused, not yet long enough since the last activity
+        let mut monitor = IdleMonitor::new(start);
+        assert_eq!(monitor.observe(away(), LIMIT, start + Duration::from_secs(59)), None);
+        assert_eq!(monitor.observe(away(), LIMIT, start + Duration::from_secs(60)), Some(IdleTransition::TearDown));
+        assert!(monitor.is_low_power());
+        // Only once
+        assert_eq!(monitor.observe(away(), LIMIT, start + Duration::from_secs(120)), None);
+
+        // Disabled
+        let mut monitor = IdleMonitor::new(start);
+        assert_eq!(monitor.observe(away(), None, start + Duration::from_secs(86_400)), None);
+    }
+
+    #[test]
+    fn test_interaction_resumes_and_settles() {
+        let start = Instant::now();
+        let mut monitor = IdleMonitor::new(start);
+        monitor.observe(away(), LIMIT, start + Duration::from_secs(60));
+
+        // Focus coming back is enough
+        let back = WindowSignals { focused: true, minimized: false, interacted: false };
+        let t = start + Duration::from_secs(3600);
+        assert_eq!(monitor.observe(back, LIMIT, t), Some(IdleTransition::Resume));
+        assert_eq!(monitor.power(), PowerState::Reconnecting);
+
+        monitor.settle(false, t + Duration::from_secs(1));
+        assert_eq!(monitor.power(), PowerState::Reconnecting);
+        monitor.settle(true, t + Duration::from_secs(2));
+        assert_eq!(monitor.power(), PowerState::Active);
+
+        // Idle time counts from the resume, not from before the teardown
+        assert_eq!(monitor.observe(away(), LIMIT, t + Duration::from_secs(30)), None);
+    }
+
+    #[test]
+    fn test_reconnecting_times_out() {
+        let start = Instant::now();
+        let mut monitor = IdleMonitor::new(start);
+        monitor.observe(away(), LIMIT, start + Duration::from_secs(60));
+        let clicked = WindowSignals { focused: false, minimized: false, interacted: true };
+        assert_eq!(monitor.observe(clicked, LIMIT, start + Duration::from_secs(61)), Some(IdleTransition::Resume));
+
+        monitor.settle(false, start + Duration::from_secs(61) + RECONNECT_STATUS_TIMEOUT);
+        assert_eq!(monitor.power(), PowerState::Active);
+    }
+
+    #[test]
+    fn test_teardown_setting() {
+        assert_eq!(teardown_after(0), None);
+        assert_eq!(teardown_after(15), Some(Duration::from_secs(900)));
+    }
+}
@@ -0,0 +1,318 @@
+//! # Keyboard Shortcuts
+//!
+//! Named [`KeyAction`]s and the [`KeyBindings`] that map them to key
+//! combinations, edited in Settings > Shortcuts and saved as the
+//! `key_bindings` settings document. Only bindings that differ from the
+//! defaults are saved, so a changed default reaches everyone who never
+//! rebound that action.
+//!
+//! [`pressed`] is the one place shortcuts are matched: `main.rs` runs the
+//! window-level actions (new window, fullscreen) and `ui::render` and the
+//! secondary windows run the rest.
+
+use crate::app::state::SettingsState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Something a keyboard shortcut does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    NextScreen,
+    PrevScreen,
+    NewWindow,
+    ToggleDebug,
+    ToggleFullscreen,
+    OpenCommandPalette,
+    FocusSwapAmount,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 7] = [
+        KeyAction::NextScreen,
+        KeyAction::PrevScreen,
+        KeyAction::NewWindow,
+        KeyAction::ToggleDebug,
+        KeyAction::ToggleFullscreen,
+        KeyAction::OpenCommandPalette,
+        KeyAction::FocusSwapAmount,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            KeyAction::NextScreen => "Next screen",
+            KeyAction::PrevScreen => "Previous screen",
+            KeyAction::NewWindow => "New window",
+            KeyAction::ToggleDebug => "Toggle debug overlay",
+            KeyAction::ToggleFullscreen => "Toggle fullscreen",
+            KeyAction::OpenCommandPalette => "Command palette",
+            KeyAction::FocusSwapAmount => "Focus swap amount",
+        }
+    }
+
+    /// Shortcut used until the user rebinds the action
+    pub fn default_combo(self) -> KeyCombo {
+        match self {
+            KeyAction::NextScreen => KeyCombo::new(egui::Key::Tab),
+            KeyAction::PrevScreen => KeyCombo::new(egui::Key::Tab).shift(),
+            KeyAction::NewWindow => KeyCombo::new(egui::Key::N).ctrl(),
+            KeyAction::ToggleDebug => KeyCombo::new(egui::Key::D).ctrl(),
+            KeyAction::ToggleFullscreen => KeyCombo::new(egui::Key::F11),
+            KeyAction::OpenCommandPalette => KeyCombo::new(egui::Key::K).ctrl(),
+            KeyAction::FocusSwapAmount => KeyCombo::new(egui::Key::S).ctrl().shift(),
+        }
+    }
+}
+
+/// Shortcuts that can't be rebound, with what they do
+pub const RESERVED: [(KeyCombo, &str); 3] = [
+    (KeyCombo::new(egui::Key::F).ctrl(), "Search"),
+    (KeyCombo::new(egui::Key::F5), "Refresh"),
+    (KeyCombo::new(egui::Key::R).ctrl(), "Refresh"),
+];
+
+/// A key with the modifiers held down with it, written like `Ctrl+Shift+S`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyCombo {
+    pub key: egui::Key,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyCombo {
+    pub const fn new(key: egui::Key) -> Self {
+        Self { key, ctrl: false, shift: false, alt: false }
+    }
+
+    pub const fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub const fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub const fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Whether the combination was pressed this frame. Modifiers must match
+    /// exactly, so `Tab` doesn't also fire on `Shift+Tab`.
+    pub fn pressed(&self, input: &egui::InputState) -> bool {
+        let modifiers = input.modifiers;
+        input.key_pressed(self.key)
+            && modifiers.ctrl == self.ctrl
+            && modifiers.shift == self.shift
+            && modifiers.alt == self.alt
+    }
+
+    /// First key pressed this frame with its modifiers, for "press a key to
+    /// rebind"
+    pub fn captured(input: &egui::InputState) -> Option<Self> {
+        input.events.iter().find_map(|event| match event {
+            egui::Event::Key { key, pressed: true, repeat: false, modifiers, .. } => Some(Self {
+                key: *key,
+                ctrl: modifiers.ctrl,
+                shift: modifiers.shift,
+                alt: modifiers.alt,
+            }),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        write!(f, "{}", self.key.name())
+    }
+}
+
+impl FromStr for KeyCombo {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (modifiers, key) = match s.rsplit_once('+') {
+            Some((modifiers, key)) => (Some(modifiers), key),
+            None => (None, s),
+        };
+        let key = egui::Key::from_name(key).ok_or_else(|| format!("unknown key '{}' in '{}'", key, s))?;
+        let mut combo = KeyCombo::new(key);
+        for modifier in modifiers.into_iter().flat_map(|m| m.split('+')) {
+            combo = match modifier {
+                "Ctrl" => combo.ctrl(),
+                "Shift" => combo.shift(),
+                "Alt" => combo.alt(),
+                other => return Err(format!("unknown modifier '{}' in '{}'", other, s)),
+            };
+        }
+        Ok(combo)
+    }
+}
+
+impl Serialize for KeyCombo {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyCombo {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Shortcut of every [`KeyAction`], see the [module docs](self)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    /// Actions bound to something other than their default
+    overrides: BTreeMap<KeyAction, KeyCombo>,
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: KeyAction) -> KeyCombo {
+        self.overrides.get(&action).copied().unwrap_or_else(|| action.default_combo())
+    }
+
+    /// Bind `action` to `combo`, unless something else already uses it
+    ///
+    /// # Returns
+    /// What `combo` is taken by, when it is
+    pub fn set(&mut self, action: KeyAction, combo: KeyCombo) -> Result<(), String> {
+        if let Some(taken_by) = self.conflict(action, combo) {
+            return Err(taken_by);
+        }
+        if combo == action.default_combo() {
+            self.overrides.remove(&action);
+        } else {
+            self.overrides.insert(action, combo);
+        }
+        Ok(())
+    }
+
+    /// Action or reserved shortcut other than `action` that `combo` triggers
+    pub fn conflict(&self, action: KeyAction, combo: KeyCombo) -> Option<String> {
+        if let Some((_, name)) = RESERVED.iter().find(|(reserved, _)| *reserved == combo) {
+            return Some(name.to_string());
+        }
+        KeyAction::ALL
+            .into_iter()
+            .find(|&other| other != action && self.get(other) == combo)
+            .map(|other| other.label().to_string())
+    }
+
+    /// Actions sharing a shortcut with an earlier one (possible when a saved
+    /// binding collides with a newer default)
+    pub fn conflicts(&self) -> Vec<KeyAction> {
+        KeyAction::ALL
+            .iter()
+            .enumerate()
+            .filter(|&(i, &action)| KeyAction::ALL[..i].iter().any(|&earlier| self.get(earlier) == self.get(action)))
+            .map(|(_, &action)| action)
+            .collect()
+    }
+}
+
+/// Settings > Shortcuts while rebinding
+#[derive(Debug, Clone, Default)]
+pub struct KeyCaptureForm {
+    /// Action waiting for its new shortcut
+    pub action: Option<KeyAction>,
+    /// Why the last key pressed was refused
+    pub error: Option<String>,
+}
+
+/// Actions whose shortcut was pressed this frame, in [`KeyAction::ALL`] order
+///
+/// Nothing fires while Settings is capturing a shortcut: that key press
+/// belongs to the editor.
+pub fn pressed(ctx: &egui::Context, settings: &SettingsState) -> Vec<KeyAction> {
+    if settings.key_capture.action.is_some() {
+        return Vec::new();
+    }
+    ctx.input(|input| {
+        KeyAction::ALL
+            .into_iter()
+            .filter(|&action| settings.key_bindings.get(action).pressed(input))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combo_text_round_trip() {
+        for action in KeyAction::ALL {
+            let combo = action.default_combo();
+            assert_eq!(combo.to_string().parse::<KeyCombo>(), Ok(combo));
+        }
+        let combo = KeyCombo::new(egui::Key::P).ctrl().alt().shift();
+        assert_eq!(combo.to_string(), "Ctrl+Alt+Shift+P");
+        assert_eq!("Shift+Ctrl+P".parse::<KeyCombo>(), Ok(KeyCombo::new(egui::Key::P).ctrl().shift()));
+        assert!("Hyper+P".parse::<KeyCombo>().is_err());
+        assert!("Ctrl+NotAKey".parse::<KeyCombo>().is_err());
+    }
+
+    #[test]
+    fn test_bindings_serialization_round_trip() {
+        let mut bindings = KeyBindings::default();
+        bindings.set(KeyAction::NewWindow, KeyCombo::new(egui::Key::W).ctrl().shift()).unwrap();
+        bindings.set(KeyAction::ToggleFullscreen, KeyCombo::new(egui::Key::F12)).unwrap();
+
+        let json = serde_json::to_string(&bindings).unwrap();
+        assert_eq!(json, r#"{"overrides":{"new_window":"Ctrl+Shift+W","toggle_fullscreen":"F12"}}"#);
+        let loaded: KeyBindings = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, bindings);
+        assert_eq!(loaded.get(KeyAction::NewWindow), KeyCombo::new(egui::Key::W).ctrl().shift());
+        // Actions never rebound keep following the defaults
+        assert_eq!(loaded.get(KeyAction::ToggleDebug), KeyAction::ToggleDebug.default_combo());
+
+        // Rebinding to the default drops the override
+        bindings.set(KeyAction::NewWindow, KeyAction::NewWindow.default_combo()).unwrap();
+        bindings.set(KeyAction::ToggleFullscreen, KeyAction::ToggleFullscreen.default_combo()).unwrap();
+        assert_eq!(bindings, KeyBindings::default());
+        assert_eq!(serde_json::from_str::<KeyBindings>("{}").unwrap(), KeyBindings::default());
+    }
+
+    #[test]
+    fn test_conflicting_bindings_are_refused() {
+        let mut bindings = KeyBindings::default();
+        assert!(bindings.conflicts().is_empty());
+
+        let taken = KeyAction::NewWindow.default_combo();
+        assert_eq!(bindings.set(KeyAction::ToggleDebug, taken), Err("New window".to_string()));
+        assert_eq!(bindings.get(KeyAction::ToggleDebug), KeyAction::ToggleDebug.default_combo());
+
+        let search = KeyCombo::new(egui::Key::F).ctrl();
+        assert_eq!(bindings.set(KeyAction::ToggleDebug, search), Err("Search".to_string()));
+
+        // Rebinding an action to its own shortcut isn't a conflict
+        assert_eq!(bindings.conflict(KeyAction::NewWindow, taken), None);
+        // Modifiers are part of the shortcut
+        assert!(bindings.set(KeyAction::ToggleDebug, KeyCombo::new(egui::Key::N).ctrl().alt()).is_ok());
+    }
+
+    #[test]
+    fn test_saved_bindings_colliding_with_defaults_are_listed() {
+        let bindings: KeyBindings = serde_json::from_str(r#"{"overrides":{"focus_swap_amount":"Ctrl+K"}}"#).unwrap();
+        assert_eq!(bindings.conflicts(), vec![KeyAction::FocusSwapAmount]);
+    }
+}
@@ -0,0 +1,283 @@
+//! # Swap Latency
+//!
+//! Times each stage of the swap pipeline against a budget. A [`SwapTimer`]
+//! starts on the Swap click and rides along in the
+//! [`SwapConfirmation`](crate::app::state::SwapConfirmation) to the Confirm
+//! click, collecting a timestamp at each checkpoint:
+//!
+//! ```text
+//! Swap click → build received → revalidated   (dialog open, not timed)
+//! Confirm click → signed → submit sent → acknowledged
+//! ```
+//!
+//! Once the RPC acknowledged the transaction the timestamps become a
+//! [`SwapLatency`] breakdown. It is kept with the swap's row in the
+//! Transactions screen, reported to the backend, and added to this session's
+//! percentiles in the debug overlay. Stages over their [`LatencyBudgets`] are
+//! logged as warnings with the swap's trace id.
+//!
+//! ## Budgets
+//!
+//! Defaults can be overridden per stage with `TERMINAL_LATENCY_BUDGETS`, for
+//! example `TERMINAL_LATENCY_BUDGETS=build=1500,acknowledgement=1000`.
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use shared::dto::latency::{LatencyStage, SwapLatency, SwapLatencySummary};
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Breakdowns kept for the debug overlay's percentiles
+pub const SESSION_HISTORY_LEN: usize = 200;
+
+/// Environment variable overriding stage budgets
+const BUDGETS_ENV: &str = "TERMINAL_LATENCY_BUDGETS";
+
+/// A point in the pipeline where a timestamp is taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checkpoint {
+    /// The backend returned the unsigned transaction
+    BuildReceived,
+    /// The simulation of that transaction answered
+    Revalidated,
+    /// The user clicked Confirm in the dialog
+    ConfirmClicked,
+    /// The wallet produced the signature
+    Signed,
+    /// The submit request is about to go out
+    SubmitSent,
+    /// The backend returned the signature the RPC accepted
+    Acknowledged,
+}
+
+impl Checkpoint {
+    const COUNT: usize = 6;
+}
+
+/// Timestamps of one swap's trip through the pipeline
+#[derive(Debug, Clone)]
+pub struct SwapTimer {
+    /// Ties the swap's log lines together, from Swap click to acknowledgement
+    pub trace_id: String,
+    started: Instant,
+    checkpoints: [Option<Instant>; Checkpoint::COUNT],
+}
+
+impl SwapTimer {
+    /// Start timing at the Swap click
+    pub fn start() -> Self {
+        Self::start_at(Instant::now())
+    }
+
+    pub fn start_at(started: Instant) -> Self {
+        Self {
+            trace_id: uuid::Uuid::new_v4().to_string(),
+            started,
+            checkpoints: [None; Checkpoint::COUNT],
+        }
+    }
+
+    /// Take the timestamp of `checkpoint` now
+    pub fn mark(&mut self, checkpoint: Checkpoint) {
+        self.mark_at(checkpoint, Instant::now());
+    }
+
+    /// Take the timestamp of `checkpoint`; a repeated mark replaces the earlier one
+    pub fn mark_at(&mut self, checkpoint: Checkpoint, at: Instant) {
+        self.checkpoints[checkpoint as usize] = Some(at);
+    }
+
+    fn at(&self, checkpoint: Checkpoint) -> Option<Instant> {
+        self.checkpoints[checkpoint as usize]
+    }
+
+    /// Time spent in each stage; None until every checkpoint was reached
+    pub fn breakdown(&self) -> Option<SwapLatency> {
+        let ms = |from: Instant, to: Instant| to.saturating_duration_since(from).as_millis() as u64;
+        let built = self.at(Checkpoint::BuildReceived)?;
+        let revalidated = self.at(Checkpoint::Revalidated)?;
+        let confirmed = self.at(Checkpoint::ConfirmClicked)?;
+        let signed = self.at(Checkpoint::Signed)?;
+        let sent = self.at(Checkpoint::SubmitSent)?;
+        let acknowledged = self.at(Checkpoint::Acknowledged)?;
+        Some(SwapLatency {
+            build_ms: ms(self.started, built),
+            revalidation_ms: ms(built, revalidated),
+            signing_ms: ms(confirmed, signed),
+            submission_ms: ms(signed, sent),
+            acknowledgement_ms: ms(sent, acknowledged),
+        })
+    }
+}
+
+/// Longest each stage should take, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyBudgets(SwapLatency);
+
+impl Default for LatencyBudgets {
+    fn default() -> Self {
+        Self(SwapLatency {
+            build_ms: 2_000,
+            revalidation_ms: 1_500,
+            signing_ms: 500,
+            submission_ms: 100,
+            acknowledgement_ms: 2_000,
+        })
+    }
+}
+
+impl LatencyBudgets {
+    /// Defaults with the `stage=ms` pairs of `spec` applied
+    ///
+    /// Entries that don't parse are skipped with a warning.
+    pub fn parse(spec: &str) -> Self {
+        let mut budgets = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let parsed = entry.split_once('=').and_then(|(name, ms)| {
+                let stage = LatencyStage::ALL.into_iter().find(|stage| stage.as_str() == name.trim())?;
+                Some((stage, ms.trim().parse::<u64>().ok()?))
+            });
+            match parsed {
+                Some((stage, ms)) => *budgets.slot(stage) = ms,
+                None => tracing::warn!(entry, "Ignoring malformed {} entry", BUDGETS_ENV),
+            }
+        }
+        budgets
+    }
+
+    /// Budgets from `TERMINAL_LATENCY_BUDGETS`, or the defaults
+    pub fn from_env() -> Self {
+        std::env::var(BUDGETS_ENV).map(|spec| Self::parse(&spec)).unwrap_or_default()
+    }
+
+    pub fn budget(&self, stage: LatencyStage) -> u64 {
+        self.0.stage(stage)
+    }
+
+    fn slot(&mut self, stage: LatencyStage) -> &mut u64 {
+        match stage {
+            LatencyStage::Build => &mut self.0.build_ms,
+            LatencyStage::Revalidation => &mut self.0.revalidation_ms,
+            LatencyStage::Signing => &mut self.0.signing_ms,
+            LatencyStage::Submission => &mut self.0.submission_ms,
+            LatencyStage::Acknowledgement => &mut self.0.acknowledgement_ms,
+        }
+    }
+
+    /// Stages of `latency` that took longer than their budget
+    pub fn exceeded(&self, latency: &SwapLatency) -> Vec<LatencyStage> {
+        latency
+            .stages()
+            .filter(|(stage, ms)| *ms > self.budget(*stage))
+            .map(|(stage, _)| stage)
+            .collect()
+    }
+}
+
+static BUDGETS: Lazy<LatencyBudgets> = Lazy::new(LatencyBudgets::from_env);
+
+/// Budgets in effect, read from the environment on first use
+pub fn budgets() -> &'static LatencyBudgets {
+    &BUDGETS
+}
+
+static SESSION: Lazy<Mutex<VecDeque<SwapLatency>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(SESSION_HISTORY_LEN)));
+
+/// Add a finished swap to this session's percentiles and warn about stages
+/// over budget
+pub fn record(latency: &SwapLatency, trace_id: &str, signature: &str) {
+    {
+        let mut session = SESSION.lock();
+        if session.len() == SESSION_HISTORY_LEN {
+            session.pop_front();
+        }
+        session.push_back(*latency);
+    }
+
+    let budgets = budgets();
+    for stage in budgets.exceeded(latency) {
+        tracing::warn!(
+            trace_id,
+            signature,
+            stage = stage.as_str(),
+            elapsed_ms = latency.stage(stage),
+            budget_ms = budgets.budget(stage),
+            total_ms = latency.total_ms(),
+            "Swap stage over its latency budget"
+        );
+    }
+}
+
+/// Percentiles of the swaps acknowledged this session
+pub fn session_summary() -> SwapLatencySummary {
+    let mut session = SESSION.lock();
+    SwapLatencySummary::of(session.make_contiguous())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn timer_with(offsets_ms: [u64; Checkpoint::COUNT]) -> SwapTimer {
+        let start = Instant::now();
+        let mut timer = SwapTimer::start_at(start);
+        let checkpoints = [
+            Checkpoint::BuildReceived,
+            Checkpoint::Revalidated,
+            Checkpoint::ConfirmClicked,
+            Checkpoint::Signed,
+            Checkpoint::SubmitSent,
+            Checkpoint::Acknowledged,
+        ];
+        for (checkpoint, ms) in checkpoints.into_iter().zip(offsets_ms) {
+            timer.mark_at(checkpoint, start + Duration::from_millis(ms));
+        }
+        timer
+    }
+
+    #[test]
+    fn test_breakdown_skips_the_review_time() {
+        // Built at 800ms, revalidated at 1100ms, then 30s in the dialog
+        let timer = timer_with([800, 1_100, 31_100, 31_140, 31_142, 32_042]);
+        assert_eq!(
+            timer.breakdown(),
+            Some(SwapLatency {
+                build_ms: 800,
+                revalidation_ms: 300,
+                signing_ms: 40,
+                submission_ms: 2,
+                acknowledgement_ms: 900,
+            })
+        );
+        assert_eq!(timer.breakdown().unwrap().total_ms(), 2_042);
+    }
+
+    #[test]
+    fn test_breakdown_needs_every_checkpoint() {
+        let mut timer = SwapTimer::start();
+        timer.mark(Checkpoint::BuildReceived);
+        timer.mark(Checkpoint::Revalidated);
+        timer.mark(Checkpoint::ConfirmClicked);
+        timer.mark(Checkpoint::Signed);
+        timer.mark(Checkpoint::SubmitSent);
+        assert_eq!(timer.breakdown(), None);
+
+        timer.mark(Checkpoint::Acknowledged);
+        assert!(timer.breakdown().is_some());
+        assert_ne!(timer.trace_id, SwapTimer::start().trace_id);
+    }
+
+    #[test]
+    fn test_budgets_parse_and_flag_slow_stages() {
+        let budgets = LatencyBudgets::parse(" signing=100, acknowledgement = 800,bogus=5,build=fast,");
+        assert_eq!(budgets.budget(LatencyStage::Signing), 100);
+        assert_eq!(budgets.budget(LatencyStage::Acknowledgement), 800);
+        assert_eq!(budgets.budget(LatencyStage::Build), LatencyBudgets::default().budget(LatencyStage::Build));
+
+        let latency = timer_with([800, 1_100, 5_000, 5_150, 5_151, 6_051]).breakdown().unwrap();
+        assert_eq!(budgets.exceeded(&latency), vec![LatencyStage::Signing, LatencyStage::Acknowledgement]);
+        assert!(LatencyBudgets::default().exceeded(&latency).is_empty());
+    }
+}
@@ -0,0 +1,310 @@
+//! # Scheduled Maintenance
+//!
+//! The backend announces maintenance with a [`SystemNotice`] carrying a
+//! [`MaintenanceWindow`]. Rather than run into the outage, the terminal
+//! quiesces ahead of it:
+//!
+//! ```text
+//! Normal ──notice──> Scheduled ──T-2min──> Imminent ──backend down──> Down
+//!    ^                   │                    │                         │
+//!    │                   └──── window passes without an outage ─────────┤ ready
+//!    │                                                                  v
+//!    └────────────── price stream back (or timeout) ─────────────── Recovering
+//! ```
+//!
+//! - **Scheduled**: a countdown in the status bar; everything still works.
+//! - **Imminent**: within [`TRADE_FREEZE`] of the start, new swaps are refused
+//!   and schedulers (DCA, TWAP) can't sign.
+//! - **Down**: the backend stopped answering once the window was due. Shown
+//!   as "Maintenance" rather than a connection error; health is polled more
+//!   often so the terminal notices the readiness check passing again.
+//! - **Recovering**: the backend is back. Balances and history are re-synced
+//!   and schedulers stay paused until the price stream reconnects.
+//!
+//! Times are Unix seconds, as on the wire.
+//!
+//! [`SystemNotice`]: shared::SystemNotice
+//! [`MaintenanceWindow`]: shared::MaintenanceWindow
+
+use shared::MaintenanceWindow;
+
+/// How long before the start new swaps are refused, in seconds
+pub const TRADE_FREEZE: i64 = 120;
+
+/// How long after the start a window that never took the backend down is
+/// given up on, in seconds
+pub const MISSED_WINDOW_GRACE: i64 = 15 * 60;
+
+/// Longest "Recovering" lasts when the price stream doesn't come back, in seconds
+pub const RECOVERY_TIMEOUT: i64 = 30;
+
+/// Where the terminal is relative to a maintenance window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MaintenancePhase {
+    #[default]
+    Normal,
+    /// Announced; counting down
+    Scheduled,
+    /// Within [`TRADE_FREEZE`] of the start
+    Imminent,
+    /// The backend is down for the window
+    Down,
+    /// The backend is back; re-syncing
+    Recovering,
+}
+
+/// Change the app has to carry out after a state machine step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceTransition {
+    /// Entered the freeze before the window
+    Freeze,
+    /// The backend went down for the window
+    WentDown,
+    /// The backend is ready again: re-sync balances and history
+    Resync,
+    /// The window is over (or was called off)
+    Cleared,
+}
+
+/// Maintenance state machine
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceState {
+    phase: MaintenancePhase,
+    window: Option<MaintenanceWindow>,
+    /// Message of the notice, for the status bar tooltip
+    message: String,
+    recovering_since: Option<i64>,
+}
+
+impl MaintenanceState {
+    pub fn phase(&self) -> MaintenancePhase {
+        self.phase
+    }
+
+    pub fn window(&self) -> Option<&MaintenanceWindow> {
+        self.window.as_ref()
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Seconds until the window starts, while counting down
+    pub fn countdown(&self, now: i64) -> Option<i64> {
+        match (self.phase, &self.window) {
+            (MaintenancePhase::Scheduled | MaintenancePhase::Imminent, Some(window)) => {
+                Some((window.starts_at - now).max(0))
+            }
+            _ => None,
+        }
+    }
+
+    /// New swaps and orders are refused
+    pub fn blocks_new_trades(&self) -> bool {
+        matches!(self.phase, MaintenancePhase::Imminent | MaintenancePhase::Down)
+    }
+
+    /// Schedulers may not sign; they also wait out the re-sync
+    pub fn pauses_schedulers(&self) -> bool {
+        matches!(
+            self.phase,
+            MaintenancePhase::Imminent | MaintenancePhase::Down | MaintenancePhase::Recovering
+        )
+    }
+
+    /// The backend is down for maintenance rather than unreachable
+    pub fn is_down(&self) -> bool {
+        self.phase == MaintenancePhase::Down
+    }
+
+    /// Take in an announced window
+    ///
+    /// A repeated notice moves the start; one whose window is already over is
+    /// ignored. An outage in progress is not affected.
+    pub fn schedule(&mut self, window: MaintenanceWindow, message: String, now: i64) -> Option<MaintenanceTransition> {
+        if matches!(self.phase, MaintenancePhase::Down | MaintenancePhase::Recovering) {
+            return None;
+        }
+        if now >= window.starts_at + MISSED_WINDOW_GRACE {
+            return None;
+        }
+        let was_frozen = self.phase == MaintenancePhase::Imminent;
+        self.window = Some(window);
+        self.message = message;
+        // Judged again, as the start may have moved out of the freeze
+        self.phase = MaintenancePhase::Scheduled;
+        match self.tick(now) {
+            Some(MaintenanceTransition::Freeze) if was_frozen => None,
+            transition => transition,
+        }
+    }
+
+    /// Advance on the clock alone
+    pub fn tick(&mut self, now: i64) -> Option<MaintenanceTransition> {
+        match self.phase {
+            MaintenancePhase::Scheduled | MaintenancePhase::Imminent => {
+                let starts_at = self.window.as_ref()?.starts_at;
+                if now >= starts_at + MISSED_WINDOW_GRACE {
+                    self.clear();
+                    return Some(MaintenanceTransition::Cleared);
+                }
+                if self.phase == MaintenancePhase::Scheduled && now >= starts_at - TRADE_FREEZE {
+                    self.phase = MaintenancePhase::Imminent;
+                    return Some(MaintenanceTransition::Freeze);
+                }
+                None
+            }
+            MaintenancePhase::Recovering => self.settle(false, now),
+            MaintenancePhase::Normal | MaintenancePhase::Down => None,
+        }
+    }
+
+    /// The backend stopped answering
+    ///
+    /// Only the freeze counts this as maintenance; before it, or without a
+    /// window, it is an ordinary connection error.
+    pub fn backend_unreachable(&mut self, now: i64) -> Option<MaintenanceTransition> {
+        if let Some(transition) = self.tick(now) {
+            if transition == MaintenanceTransition::Cleared {
+                return Some(transition);
+            }
+        }
+        if self.phase == MaintenancePhase::Imminent {
+            self.phase = MaintenancePhase::Down;
+            return Some(MaintenanceTransition::WentDown);
+        }
+        None
+    }
+
+    /// The backend's readiness check passed
+    pub fn backend_ready(&mut self, now: i64) -> Option<MaintenanceTransition> {
+        if self.phase == MaintenancePhase::Down {
+            self.phase = MaintenancePhase::Recovering;
+            self.recovering_since = Some(now);
+            return Some(MaintenanceTransition::Resync);
+        }
+        None
+    }
+
+    /// Leave "Recovering" once the price stream is up or after [`RECOVERY_TIMEOUT`]
+    pub fn settle(&mut self, stream_connected: bool, now: i64) -> Option<MaintenanceTransition> {
+        let since = self.recovering_since?;
+        if stream_connected || now - since >= RECOVERY_TIMEOUT {
+            self.clear();
+            return Some(MaintenanceTransition::Cleared);
+        }
+        None
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1_704_067_200;
+
+    fn window() -> MaintenanceWindow {
+        MaintenanceWindow { starts_at: START, expected_duration_secs: Some(600) }
+    }
+
+    fn scheduled() -> MaintenanceState {
+        let mut state = MaintenanceState::default();
+        state.schedule(window(), "Database upgrade".to_string(), START - 600);
+        state
+    }
+
+    #[test]
+    fn test_countdown_then_freeze() {
+        let mut state = scheduled();
+        assert_eq!(state.phase(), MaintenancePhase::Scheduled);
+        assert_eq!(state.countdown(START - 600), Some(600));
+        assert!(!state.blocks_new_trades());
+        assert!(!state.pauses_schedulers());
+
+        assert_eq!(state.tick(START - TRADE_FREEZE - 1), None);
+        assert_eq!(state.tick(START - TRADE_FREEZE), Some(MaintenanceTransition::Freeze));
+        assert_eq!(state.phase(), MaintenancePhase::Imminent);
+        assert!(state.blocks_new_trades());
+        assert!(state.pauses_schedulers());
+        // Only once
+        assert_eq!(state.tick(START - 60), None);
+    }
+
+    #[test]
+    fn test_outage_is_maintenance_only_within_the_freeze() {
+        // Without a window it's a connection error
+        let mut state = MaintenanceState::default();
+        assert_eq!(state.backend_unreachable(START), None);
+
+        // Before the freeze too
+        let mut state = scheduled();
+        assert_eq!(state.backend_unreachable(START - 300), None);
+        assert_eq!(state.phase(), MaintenancePhase::Scheduled);
+
+        // The freeze is entered on the way
+        assert_eq!(state.backend_unreachable(START - 30), Some(MaintenanceTransition::WentDown));
+        assert!(state.is_down());
+        assert_eq!(state.countdown(START), None);
+    }
+
+    #[test]
+    fn test_recovery_resyncs_then_settles() {
+        let mut state = scheduled();
+        state.backend_unreachable(START);
+        assert_eq!(state.backend_ready(START + 400), Some(MaintenanceTransition::Resync));
+        assert_eq!(state.phase(), MaintenancePhase::Recovering);
+        assert!(!state.blocks_new_trades());
+        assert!(state.pauses_schedulers());
+        // A second passing check changes nothing
+        assert_eq!(state.backend_ready(START + 410), None);
+
+        assert_eq!(state.settle(false, START + 401), None);
+        assert_eq!(state.settle(true, START + 402), Some(MaintenanceTransition::Cleared));
+        assert_eq!(state.phase(), MaintenancePhase::Normal);
+        assert!(state.window().is_none());
+
+        // Or the timeout
+        let mut state = scheduled();
+        state.backend_unreachable(START);
+        state.backend_ready(START + 400);
+        assert_eq!(state.tick(START + 400 + RECOVERY_TIMEOUT), Some(MaintenanceTransition::Cleared));
+    }
+
+    #[test]
+    fn test_windows_that_never_happen_are_cleared() {
+        let mut state = scheduled();
+        state.tick(START - 60);
+        assert_eq!(state.tick(START + MISSED_WINDOW_GRACE), Some(MaintenanceTransition::Cleared));
+        assert!(!state.blocks_new_trades());
+
+        // A notice for a window already over is ignored
+        let mut state = MaintenanceState::default();
+        assert_eq!(state.schedule(window(), String::new(), START + MISSED_WINDOW_GRACE), None);
+        assert_eq!(state.phase(), MaintenancePhase::Normal);
+    }
+
+    #[test]
+    fn test_rescheduling() {
+        // Announced late: straight into the freeze
+        let mut state = MaintenanceState::default();
+        assert_eq!(state.schedule(window(), String::new(), START - 60), Some(MaintenanceTransition::Freeze));
+        assert_eq!(state.schedule(window(), String::new(), START - 50), None);
+
+        // Postponed: trading again until the new freeze
+        let postponed = MaintenanceWindow { starts_at: START + 1800, expected_duration_secs: None };
+        assert_eq!(state.schedule(postponed, String::new(), START - 40), None);
+        assert_eq!(state.phase(), MaintenancePhase::Scheduled);
+        assert!(!state.blocks_new_trades());
+        state.schedule(window(), String::new(), START - 30);
+
+        // A notice during the outage doesn't reset it
+        state.backend_unreachable(START);
+        let later = MaintenanceWindow { starts_at: START + 3600, expected_duration_secs: None };
+        assert_eq!(state.schedule(later, String::new(), START + 60), None);
+        assert!(state.is_down());
+    }
+}
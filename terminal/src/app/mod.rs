@@ -24,13 +24,13 @@
 //! │  └──────────────────────────────────────────────────────┘   │
 //! └───────────────────────┬─────────────────────────────────────┘
 //!                         │ async_channel
-//!                         │ (unbounded)
+//!                         │ (bounded)   
 //! ┌───────────────────────▼─────────────────────────────────────┐
 //! │              Async Task Threads (Tokio)                    │
 //! │  ┌──────────────────────────────────────────────────────┐   │
 //! │  │  Tasks Module                                         │   │
 //! │  │  - fetch_prices() - market data                      │   │
-//! │  │  - execute_swap() - swap execution                   │   │
+//! │  │  - prepare_swap() / confirm_swap() - swap execution  │   │
 //! │  │  - fetch_token_list() - token metadata               │   │
 //! │  └────────────┬─────────────────────────────────────────┘   │
 //! │               │                                              │
@@ -63,7 +63,7 @@
 //!
 //! // Async task: Write state updates
 //! let mut state = app.state.write(); // Exclusive write lock
-//! state.terminal.prices = new_prices;
+//! state.terminal.sol_candles = candles.into();
 //! drop(state); // Lock released immediately
 //! ```
 //!
@@ -114,7 +114,8 @@
 //!
 //! - **Main Thread**: Single-threaded (egui requirement), handles all UI rendering
 //! - **Async Tasks**: Multi-threaded (Tokio runtime), handles network I/O
-//! - **Communication**: Via `async_channel::unbounded` (lock-free, async)
+//! - **Communication**: Via a bounded `async_channel` (lock-free, async); see
+//!   [`event_queue`] for what happens when it fills up
 //! - **State Access**: `Arc<RwLock<AppState>>` ensures thread-safe access
 //!
 //! ## Related Modules
@@ -123,27 +124,57 @@
 //! - [`events`]: Event enum for async communication
 //! - [`handlers`]: User action handlers
 //! - [`tasks`]: Async background tasks
+//! - [`search`]: Search providers behind the search palette
+//! - [`commands`]: Command registry and parsing behind the command palette
+//! - [`branding`]: Landing screen branding from `branding.toml`
+//! - [`wallet_identity`]: Per-wallet labels and chip colors
+//! - [`idle`]: Low-power mode for a terminal left unattended
+//! - [`recovery`]: Recovery cards for common wallet and RPC failures
+//! - [`event_queue`]: Capacity and overflow policy of the event channel
+//! - [`latency`]: Stage timing and latency budgets of the swap pipeline
 
 mod state;
 mod events;
-mod handlers;
+pub(crate) mod handlers;
 mod tasks;
 mod event_handler;
 mod window_manager;
 mod window_app;
 mod viewport;
 mod app_trait;
+mod price_store;
+mod feature_gates;
+mod cow_arc;
+pub mod audit;
+pub mod preload;
+pub mod search;
+pub mod commands;
+pub mod branding;
+pub mod wallet_identity;
+pub mod idle;
+pub mod maintenance;
+pub mod recovery;
+pub mod event_queue;
+pub mod latency;
+pub mod usage;
+pub mod keybindings;
 
 pub use state::*;
 pub use events::AppEvent;
-pub use window_manager::WindowManager;
+pub use window_manager::{WindowId, WindowLayout, WindowManager};
 pub use window_app::WindowApp;
-pub use viewport::show_deferred_viewport;
+pub use viewport::{show_deferred_viewport, sync_window_geometry};
 pub use app_trait::AppLike;
+pub use price_store::{PriceSnapshot, PriceStore};
+pub use feature_gates::{Feature, FeatureGates, Gate};
+pub use cow_arc::CowArc;
+pub use handlers::swap::{history_page_count, HISTORY_PAGE_SIZE};
+pub use handlers::refresh::RefreshTarget;
+pub(crate) use tasks::market::DEPTH_REFRESH_INTERVAL;
 
 use std::sync::Arc;
 use parking_lot::RwLock;
-use async_channel::{Sender, Receiver, unbounded};
+use async_channel::{Sender, Receiver};
 use crate::core::service::ApiService;
 
 /// Main application orchestrator that coordinates UI rendering, async tasks, and state management.
@@ -156,7 +187,7 @@ use crate::core::service::ApiService;
 /// # Architecture
 ///
 /// The application follows an event-driven pattern where async tasks send results
-/// back to the main thread via `AppEvent` messages through an unbounded channel.
+/// back to the main thread via `AppEvent` messages through a bounded channel.
 ///
 /// # Thread Safety
 ///
@@ -223,6 +254,10 @@ impl App {
     /// - Event channel for async task communication
     /// - Initial token list fetch task
     ///
+    /// Started with `--offline` or `TERMINAL_OFFLINE=1`, the backend and the
+    /// wallet are the mocks of [`crate::services::offline`] instead, and the
+    /// app opens signed in on the Terminal screen.
+    ///
     /// # Returns
     ///
     /// A new [`App`] instance ready for use in the egui update loop.
@@ -249,19 +284,58 @@ impl App {
     /// assert_eq!(state.current_screen, Screen::Landing);
     /// ```
     pub fn new() -> Self {
-        // Create API client
-        let api_client = Arc::new(crate::services::api::ApiClient::new());
+        let config = crate::core::config::TerminalConfig::load();
+
+        // Create API client over the configured servers (primary first)
+        let mut servers = handlers::settings::load_server_list();
+        if let Some(api_url) = &config.api_url {
+            handlers::settings::promote_server(&mut servers, api_url);
+        }
+        let offline = crate::core::config::offline_requested(std::env::args(), |name| std::env::var(name).ok());
+        let api_client = (!offline).then(|| Arc::new(crate::services::api::ApiClient::with_servers(servers.clone())));
+        let offline_api = offline.then(|| Arc::new(crate::services::offline::OfflineApi::new()));
+        let api = match (&api_client, &offline_api) {
+            (Some(api_client), _) => Some(Arc::clone(api_client) as Arc<dyn ApiService>),
+            (None, offline_api) => offline_api.clone().map(|api| api as Arc<dyn ApiService>),
+        };
+
+        // The offline demo starts signed in, with a throwaway wallet connected
+        let offline_wallet = offline_api.map(|api| {
+            Box::new(crate::services::offline::OfflineWallet::new(api)) as Box<dyn crate::core::service::WalletService>
+        });
+        let wallet = offline_wallet.as_ref().and_then(|w| w.get_public_key()).map(|address| WalletState {
+            address,
+            sol_balance: 0.0,
+            token_balances: Vec::new(),
+        });
+        if offline {
+            tracing::warn!("Starting the offline demo - prices, quotes and swaps are simulated");
+        }
 
-        // Load settings from file
+        // Load settings from the local database
         let theme_config = handlers::settings::load_settings();
         let settings = crate::app::state::SettingsState {
             theme_config,
-            config_path: handlers::settings::get_config_path().to_string_lossy().to_string(),
+            config_path: crate::core::store::database_path().to_string_lossy().to_string(),
             unsaved_changes: false,
+            indicators: handlers::settings::load_indicator_config(),
+            account: Default::default(),
+            servers: crate::app::state::ServerListForm::new(servers),
+            connection: crate::app::state::ConnectionForm::new(&config),
+            tokens: handlers::settings::load_token_preferences(),
+            network: handlers::settings::load_network_preferences(),
+            risk: handlers::risk::load_risk_thresholds(),
+            notifications: handlers::settings::load_notification_preferences(),
+            privacy: handlers::settings::load_privacy_form(),
+            asset_sort: handlers::live_assets::load_asset_sort(),
+            key_bindings: handlers::settings::load_key_bindings(),
+            key_capture: Default::default(),
+            locale: handlers::settings::load_locale(),
         };
+        crate::ui::i18n::set_locale(settings.locale);
 
         let state = AppState {
-            current_screen: Screen::Landing,
+            current_screen: if offline { Screen::Terminal } else { Screen::Landing },
             auth: AuthState::Login {
                 username: String::new(),
                 password: String::new(),
@@ -269,41 +343,112 @@ impl App {
                 active_field: LoginField::Username,
             },
             terminal: TerminalState {
-                swap: SwapState::default(),
-                prices: Vec::new(), // Start empty, will be populated from websocket
-                chart_data: Vec::new(),
-                sol_candles: Vec::new(), // Will be populated from API
+                swap: SwapState {
+                    slippage_bps: config.default_slippage_bps,
+                    ..SwapState::default()
+                },
+                prices: Arc::new(PriceStore::default()), // Start empty, will be populated from websocket
+                chart_data: CowArc::default(),
+                chart_symbol: crate::app::state::DEFAULT_CHART_SYMBOL.to_string(),
+                sol_candles: CowArc::default(), // Will be populated from API
                 chart_timeframe: shared::dto::market::Timeframe::OneHour,
                 chart_loading: false,
+                sol_candle_forming: false,
+                chart_data_version: 0,
+                chart_indicators: crate::ui::chart::indicators::IndicatorSeries::new(settings.indicators),
+                chart_annotations: handlers::annotations::load_annotations(),
+                chart_tool: Default::default(),
                 active_chart: None, // Will use real OHLC data instead
                 last_price_update: std::time::Instant::now(),
+                price_flashes: std::collections::HashMap::new(),
+                price_flash_version: 0,
                 fetching_prices: false,
+                streamed_symbols: Vec::new(),
                 swap_panel_open: false,
+                depth: None,
+                depth_loading: false,
+                depth_error: None,
+                last_depth_fetch: None,
             },
-            wallet: None,
-            transactions: Vec::new(),
-            auth_token: None,
-            current_user: None,
-            api_client: Some(api_client),
+            wallet,
+            wallet_identities: handlers::wallet::load_wallet_identities(),
+            wallet_label_edit: None,
+            recovery: crate::app::recovery::RecoveryState {
+                stats: handlers::recovery::load_stats(),
+                ..Default::default()
+            },
+            transactions: CowArc::default(),
+            transactions_wallet: Default::default(),
+            auth_token: offline.then(crate::services::offline::demo_token),
+            session: crate::app::state::SessionState::default(),
+            current_user: offline.then(|| CurrentUser {
+                id: 0,
+                username: crate::services::offline::DEMO_USERNAME.to_string(),
+            }),
+            config,
+            api_client: api_client.clone(),
+            api,
+            offline,
             wallet_service: None, // Will be initialized when user connects wallet
+            offline_wallet,
             polling_credentials: None,
             pending_notifications: Vec::new(),
+            system_notices: Vec::new(),
             websocket_connected: false,
             websocket_status: crate::app::state::WebSocketStatus::default(),
+            price_stream_task: None,
+            price_stream_outbox: None,
+            idle: Default::default(),
+            maintenance: Default::default(),
+            backend_health: crate::app::state::BackendHealthState::default(),
             messaging: crate::app::state::MessagingState::default(),
             ai_chat: crate::app::state::AIChatState::default(),
             settings,
+            portfolio: crate::app::state::PortfolioState {
+                snapshots: handlers::portfolio::load_snapshots(),
+                rebalance: crate::app::state::RebalanceState {
+                    preferences: handlers::rebalance::load_rebalance_preferences(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            token_unlocks: crate::app::state::TokenUnlockState::default(),
+            live_assets: crate::app::state::LiveAssetsState::default(),
+            derived_accounts: crate::app::state::DerivedAccountsState {
+                keystore: handlers::keystore::load_keystore(),
+                ..Default::default()
+            },
+            trade_import: crate::app::state::TradeImportState::default(),
+            security: crate::app::state::SecurityState {
+                trail: audit::AuditLog::open(crate::core::store::store().audit()),
+                ..Default::default()
+            },
+            search: crate::app::state::SearchState::default(),
+            palette: crate::app::state::CommandPaletteState {
+                registry: std::sync::Arc::new(crate::ui::screens::register_commands(commands::CommandRegistry::builtin())),
+                ..Default::default()
+            },
+            share: crate::app::state::ShareState::default(),
+            webhooks: crate::app::state::WebhooksState::default(),
+            api_keys: crate::app::state::ApiKeysState::default(),
+            onramp: crate::app::state::OnRampState::default(),
+            send: crate::app::state::SendState::default(),
+            receive: crate::app::state::ReceiveState::default(),
+            airdrop: crate::app::state::AirdropState::default(),
+            staking: crate::app::state::StakingState::default(),
+            link_prompt: crate::app::state::LinkPromptState::default(),
+            refresh_tasks: handlers::refresh::refresh_supervisor(),
             debug_overlay_visible: std::env::var("TERMINAL_DEBUG_UI")
                 .map(|v| v == "1")
                 .unwrap_or(false),
             needs_immediate_repaint: false,
             last_price_update_time: std::time::Instant::now(),
-            nav_bar_selected_token: Some("SOL".to_string()), // Default to SOL
-            nav_bar_show_token_picker: false,
+            root_view: WindowView::default(),
+            branding: branding::load_branding(),
         };
 
         // Create event channel
-        let (event_tx, event_rx) = unbounded();
+        let (event_tx, event_rx) = event_queue::channel();
 
         // Create window manager
         let window_manager = Arc::new(RwLock::new(WindowManager::new()));
@@ -318,6 +463,16 @@ impl App {
         
         // Fetch initial token list
         tasks::market::fetch_token_list(app.state.clone(), app.event_tx.clone());
+        tasks::market::fetch_streamed_symbols(app.state.clone(), app.event_tx.clone());
+
+        // Poll backend health for the status bar
+        if let Some(api_client) = &api_client {
+            tasks::health::start_health_polling(app.state.clone(), app.event_tx.clone());
+            tasks::health::watch_server_switches(api_client.failover().switches(), app.event_tx.clone());
+            tasks::health::watch_api_failures(api_client.failures(), app.event_tx.clone());
+        } else {
+            handlers::wallet::refresh_balances(app.state.clone(), app.event_tx.clone());
+        }
         
         tracing::info!("App state initialized - Event channel created, token list fetch started");
         tracing::debug!("WebSocket connection will be started after successful login");
@@ -337,6 +492,11 @@ impl App {
                 change_24h: 5.2,
                 previous_price: None,
                 source: Some("jupiter".to_string()),
+                confidence: None,
+                publish_time: None,
+                sources: Default::default(),
+                divergent: false,
+                divergence_pct: None,
             },
             PriceData {
                 symbol: "USDC".to_string(),
@@ -344,6 +504,11 @@ impl App {
                 change_24h: 0.0,
                 previous_price: None,
                 source: Some("jupiter".to_string()),
+                confidence: None,
+                publish_time: None,
+                sources: Default::default(),
+                divergent: false,
+                divergence_pct: None,
             },
             PriceData {
                 symbol: "BTC".to_string(),
@@ -351,6 +516,11 @@ impl App {
                 change_24h: 3.1,
                 previous_price: None,
                 source: Some("pyth".to_string()),
+                confidence: None,
+                publish_time: None,
+                sources: Default::default(),
+                divergent: false,
+                divergence_pct: None,
             },
             PriceData {
                 symbol: "ETH".to_string(),
@@ -358,6 +528,11 @@ impl App {
                 change_24h: -1.5,
                 previous_price: None,
                 source: Some("pyth".to_string()),
+                confidence: None,
+                publish_time: None,
+                sources: Default::default(),
+                divergent: false,
+                divergence_pct: None,
             },
         ]
     }
@@ -423,36 +598,32 @@ impl App {
         // Update watchdog heartbeat to detect freezes
         crate::debug::update_heartbeat();
 
+        // Channel depth before draining, for the debug overlay
+        crate::debug::record_event_queue_depth(self.event_rx.len());
+
         // Process any pending async events - CRITICAL for instant Bloomberg-style updates
         // Process all events immediately without delays to ensure <10ms latency
         let event_processing_start = std::time::Instant::now();
-        let mut events_processed = 0u32;
-        let mut price_updated_events = 0u32;
         
-        // Process all available events in the channel (non-blocking)
+        // Process the events in the channel (non-blocking), at most one
+        // channel's worth per tick
         // CRITICAL: Prioritize PriceUpdated events for instant processing
         // This ensures WebSocket price updates are handled immediately with <10ms latency
-        let mut price_events = Vec::new();
-        let mut other_events = Vec::new();
+        let drained = event_queue::drain(&self.event_rx, event_queue::capacity());
+        let events_processed = drained.received;
+        let price_updated_events = drained.received - drained.other.len();
         
-        // Separate events by priority
-        while let Ok(event) = self.event_rx.try_recv() {
-            events_processed += 1;
-            if matches!(event, AppEvent::PriceUpdated(_)) {
-                price_updated_events += 1;
-                price_events.push(event);
-            } else {
-                other_events.push(event);
-            }
+        // Process price update events FIRST for minimal latency; a burst for
+        // one symbol is applied once, with its newest price
+        for price in drained.prices.into_prices() {
+            self.handle_event(AppEvent::PriceUpdated(price));
         }
-        
-        // Process price update events FIRST for minimal latency
-        for event in price_events {
-            self.handle_event(event);
+        if drained.prices_changed {
+            self.handle_event(AppEvent::PricesChanged);
         }
         
         // Then process other events
-        for event in other_events {
+        for event in drained.other {
             self.handle_event(event);
         }
         
@@ -460,6 +631,7 @@ impl App {
         if events_processed > 0 {
             let processing_time = event_processing_start.elapsed();
             let processing_time_us = processing_time.as_micros();
+            crate::debug::record_event_latency(processing_time);
             
             tracing::debug!(
                 events_processed = events_processed,
@@ -479,13 +651,20 @@ impl App {
             }
         }
 
+        // Maintenance countdown and settling after a re-sync
+        handlers::maintenance::handle_maintenance_tick(&mut self.state.write());
+
+        // Keep the quote stream on the swap that is on screen
+        handlers::quote_stream::sync_quote_stream(&mut self.state.write());
+
         // Fallback to REST API if WebSocket is disabled or disconnected for too long
         // Also fetch initial prices if we have none yet
         let should_fallback = {
             let state = self.state.read();
-            let auth_ok = state.auth_token.is_some();
+            // Nothing is fetched in low-power mode or from a backend down for maintenance
+            let auth_ok = state.auth_token.is_some() && !state.idle.is_low_power() && !state.maintenance.is_down();
             let on_terminal_screen = state.current_screen == Screen::Terminal;
-            let has_no_prices = state.terminal.prices.is_empty();
+            let has_no_prices = state.terminal.prices.load().is_empty();
             let ws_disabled = matches!(state.websocket_status.state, crate::app::WebSocketState::Disabled);
             let ws_disconnected_too_long = !state.websocket_connected 
                 && state.terminal.last_price_update.elapsed().as_secs() >= 5; // Reduced from 10s to 5s
@@ -493,14 +672,19 @@ impl App {
             let ws_failing = matches!(state.websocket_status.state, crate::app::WebSocketState::Reconnecting)
                 && state.websocket_status.connection_attempts >= 3; // After 3 failed attempts, use REST API
             
-            auth_ok && on_terminal_screen && (has_no_prices || ws_disabled || ws_disconnected_too_long || no_recent_updates || ws_failing)
+            // The offline demo has no stream; its walk is polled on every screen
+            let offline_due = state.offline && state.terminal.last_price_update.elapsed() >= crate::services::offline::PRICE_STEP;
+
+            auth_ok && (offline_due || (on_terminal_screen && (has_no_prices || ws_disabled || ws_disconnected_too_long || no_recent_updates || ws_failing)))
         };
         
-        if should_fallback {
+        if should_fallback && self.state.read().offline {
+            tasks::market::fetch_prices(self.state.clone(), self.event_tx.clone());
+        } else if should_fallback {
             let state = self.state.read();
             let ws_state = state.websocket_status.state.clone();
             let last_update_secs = state.terminal.last_price_update.elapsed().as_secs();
-            let price_count = state.terminal.prices.len();
+            let price_count = state.terminal.prices.load().len();
             let connection_attempts = state.websocket_status.connection_attempts;
             drop(state);
             
@@ -558,7 +742,7 @@ impl App {
                     // Get API client
                     let api_client = {
                         let state = state_arc.read();
-                        state.api_client.clone()
+                        state.api.clone()
                     };
 
                     if let Some(api_client) = api_client {
@@ -576,7 +760,7 @@ impl App {
                             }
                             Err(e) => {
                                 tracing::debug!("Wallet status check failed: {}", e);
-                                let _ = event_tx.send(AppEvent::WalletStatusChecked(Err(e))).await;
+                                let _ = event_tx.send(AppEvent::WalletStatusChecked(Err(e.to_string()))).await;
                             }
                         }
                     }
@@ -609,19 +793,112 @@ impl App {
         handlers::auth::handle_switch_to_signup(self.state.clone());
     }
 
+    /// Handle Logout button click
+    pub fn handle_logout_click(&mut self) {
+        handlers::auth::handle_logout_click(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Handle Change Password button click (Settings > Account)
+    pub fn handle_change_password_click(&mut self) {
+        handlers::auth::handle_change_password_click(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Handle Update Email button click (Settings > Account)
+    pub fn handle_update_profile_click(&mut self) {
+        handlers::auth::handle_update_profile_click(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Refresh the login token now (Settings > Security)
+    pub fn handle_session_refresh(&mut self) {
+        handlers::auth::handle_session_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Enter or leave low-power mode from this frame's window focus and input
+    pub fn idle_tick(&mut self, signals: idle::WindowSignals) {
+        handlers::idle::handle_idle_tick(self.state.clone(), self.event_tx.clone(), signals);
+    }
+
+    /// Refresh the login token shortly before it expires, while the user is active
+    pub fn keep_session_alive(&mut self, active: bool) {
+        handlers::auth::handle_session_keep_alive(self.state.clone(), self.event_tx.clone(), active);
+    }
+
+    /// Handle Grant button click (Settings > Security)
+    pub fn handle_grant_session(&mut self) {
+        handlers::security::handle_grant_session(self.state.clone());
+    }
+
+    /// Handle Revoke button click for a signing session grant
+    pub fn handle_revoke_grant(&mut self, grant_id: u64) {
+        handlers::security::handle_revoke_grant(self.state.clone(), grant_id);
+    }
+
+    /// Reload the registered webhooks (Settings > Webhooks)
+    pub fn handle_webhooks_refresh(&mut self) {
+        handlers::webhooks::handle_webhooks_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Register a webhook from the Settings form
+    pub fn handle_webhook_create(&mut self) {
+        handlers::webhooks::handle_webhook_create(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Delete a webhook and its delivery log
+    pub fn handle_webhook_delete(&mut self, id: i64) {
+        handlers::webhooks::handle_webhook_delete(self.state.clone(), self.event_tx.clone(), id);
+    }
+
+    /// Show the delivery log of a webhook
+    pub fn handle_webhook_deliveries(&mut self, id: i64) {
+        handlers::webhooks::handle_webhook_deliveries(self.state.clone(), self.event_tx.clone(), id);
+    }
+
+    /// Send a test event to a webhook
+    pub fn handle_webhook_test(&mut self, id: i64) {
+        handlers::webhooks::handle_webhook_test(self.state.clone(), self.event_tx.clone(), id);
+    }
+
+    /// Reload the user's API keys (Settings > API Keys)
+    pub fn handle_api_keys_refresh(&mut self) {
+        handlers::api_keys::handle_api_keys_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Create an API key from the Settings form
+    pub fn handle_api_key_create(&mut self) {
+        handlers::api_keys::handle_api_key_create(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Revoke an API key
+    pub fn handle_api_key_revoke(&mut self, id: i64) {
+        handlers::api_keys::handle_api_key_revoke(self.state.clone(), self.event_tx.clone(), id);
+    }
+
     /// Handle screen change
     pub fn handle_screen_change(&mut self, screen: Screen) {
         handlers::navigation::handle_screen_change(self.state.clone(), screen);
     }
 
-    /// Handle swap tab change
+    /// Handle swap tab change (opening History loads the current page)
     pub fn handle_swap_tab_change(&mut self, tab: SwapTab) {
         handlers::navigation::handle_swap_tab_change(self.state.clone(), tab);
+        if tab == SwapTab::History {
+            self.handle_swap_history_refresh();
+        }
     }
 
-    /// Handle swap execute button click
+    /// Handle swap execute button click (builds and simulates the swap for confirmation)
     pub fn handle_swap_execute_click(&mut self) {
-        tasks::swap::execute_swap(self.state.clone(), self.event_tx.clone());
+        tasks::swap::prepare_swap(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Sign and submit the swap in the confirmation dialog
+    pub fn handle_swap_confirm(&mut self) {
+        tasks::swap::confirm_swap(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Close the swap confirmation dialog without signing
+    pub fn handle_swap_confirmation_cancel(&mut self) {
+        handlers::swap::handle_swap_confirmation_cancel(self.state.clone());
     }
 
     /// Handle token selection from picker
@@ -631,6 +908,11 @@ impl App {
         tasks::swap::trigger_quote_fetch(self.state.clone(), self.event_tx.clone());
     }
 
+    /// Handle the favorite star on a token picker row
+    pub fn handle_token_favorite_toggle(&mut self, mint: String) {
+        handlers::swap::handle_token_favorite_toggle(self.state.clone(), mint);
+    }
+
     /// Handle wallet connect button click
     pub fn handle_wallet_connect_click(&mut self) {
         handlers::wallet::handle_wallet_connect_click(self.state.clone(), self.event_tx.clone());
@@ -646,6 +928,187 @@ impl App {
         handlers::wallet::handle_wallet_disconnect_click(self.state.clone());
     }
 
+    /// Save the label and color being edited for a wallet
+    pub fn handle_wallet_label_save(&mut self) {
+        handlers::wallet::handle_wallet_label_save(self.state.clone());
+    }
+
+    /// Build the transfer in the Send panel and open its confirmation
+    pub fn handle_send_review(&mut self) {
+        tasks::transfer::prepare_transfer(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Sign and submit the transfer in the confirmation dialog
+    pub fn handle_send_confirm(&mut self) {
+        tasks::transfer::confirm_transfer(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Ask the devnet faucet for SOL for the connected wallet
+    pub fn handle_airdrop_request(&mut self) {
+        tasks::airdrop::request_airdrop(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Reload the connected wallet's stake accounts and rewards
+    pub fn handle_staking_refresh(&mut self) {
+        tasks::staking::load_staking(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Stake from the Staking panel's form, or deactivate or withdraw `stake_account`
+    pub fn handle_stake_action(&mut self, action: handlers::staking::StakeAction, stake_account: Option<String>) {
+        tasks::staking::run_stake_action(self.state.clone(), self.event_tx.clone(), action, stake_account);
+    }
+
+    /// Close the transfer confirmation dialog without signing
+    pub fn handle_send_cancel(&mut self) {
+        handlers::send::handle_send_cancel(self.state.clone());
+    }
+
+    /// Carry out an action picked on a recovery card
+    pub fn handle_recovery_action(&mut self, flow: recovery::RecoveryFlow, action: recovery::RecoveryAction) {
+        handlers::recovery::handle_recovery_action(self.state.clone(), self.event_tx.clone(), flow, action);
+    }
+
+    /// Close a recovery card
+    pub fn handle_recovery_dismiss(&mut self, flow: recovery::RecoveryFlow) {
+        handlers::recovery::handle_recovery_dismiss(self.state.clone(), flow);
+    }
+
+    /// Reload the connected wallet's SPL and Token-2022 balances
+    pub fn handle_token_balances_refresh(&mut self) {
+        handlers::wallet::handle_token_balances_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Reload the connected wallet's transaction history
+    pub fn handle_transactions_refresh(&mut self) {
+        handlers::transactions::handle_transactions_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Reload what `screen` shows (F5), skipping the client caches
+    pub fn refresh(&mut self, screen: Screen) {
+        handlers::refresh::handle_refresh(self.state.clone(), self.event_tx.clone(), screen);
+    }
+
+    /// Clear every client cache and reload everything
+    pub fn refresh_all(&mut self) {
+        handlers::refresh::handle_refresh_all(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Export the transaction history to CSV
+    pub fn handle_transactions_export(&mut self) {
+        handlers::transactions::handle_transactions_export(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Pick a CSV of trades from another platform and validate it
+    pub fn handle_trade_import_pick_file(&mut self) {
+        handlers::trade_import::handle_trade_import_pick_file(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Re-validate the selected CSV with the current column mapping
+    pub fn handle_trade_import_validate(&mut self) {
+        handlers::trade_import::handle_trade_import_validate(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Import the validated trades
+    pub fn handle_trade_import_commit(&mut self) {
+        handlers::trade_import::handle_trade_import_commit(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Reload trade statistics (swaps and imported trades)
+    pub fn handle_trade_stats_refresh(&mut self) {
+        handlers::trade_import::handle_trade_stats_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Fetch the swap history page for the current filters
+    pub fn handle_swap_history_refresh(&mut self) {
+        handlers::swap::handle_swap_history_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Export every swap matching the history filters to CSV, streaming it
+    /// from the backend
+    pub fn handle_swap_history_export(&mut self) {
+        handlers::swap::handle_swap_history_export(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Publish the Live Chart as a read-only share link
+    pub fn handle_share_chart(&mut self) {
+        handlers::share::handle_share_chart(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Publish the portfolio allocation as a read-only share link
+    pub fn handle_share_portfolio(&mut self) {
+        handlers::share::handle_share_portfolio(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Open the "Buy SOL" dialog and load the on-ramp providers
+    pub fn handle_onramp_open(&mut self) {
+        handlers::onramp::handle_onramp_open(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Open an on-ramp provider's checkout for the active wallet
+    pub fn handle_onramp_buy(&mut self, provider_id: String) {
+        handlers::onramp::handle_onramp_buy(self.state.clone(), provider_id);
+    }
+
+    /// Save the rebalance targets being edited for the current profile
+    pub fn handle_rebalance_save(&mut self) {
+        handlers::rebalance::handle_rebalance_save(self.state.clone());
+    }
+
+    /// Propose the swaps that bring the portfolio to its targets and quote them
+    pub fn handle_rebalance_propose(&mut self) {
+        handlers::rebalance::handle_rebalance_propose(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Queue the proposed rebalance swaps on the swap panel for review
+    pub fn handle_rebalance_queue_all(&mut self) {
+        handlers::rebalance::handle_rebalance_queue_all(self.state.clone());
+    }
+
+    /// Open or close the search palette
+    pub fn handle_search_toggle(&mut self) {
+        handlers::search::handle_search_toggle(self.state.clone());
+    }
+
+    /// Search for an edited palette query
+    pub fn handle_search_query(&mut self, query: String) {
+        handlers::search::handle_search_query(self.state.clone(), self.event_tx.clone(), query);
+    }
+
+    /// Navigate to a search result and highlight it
+    pub fn handle_search_select(&mut self, target: search::SearchTarget) {
+        handlers::search::handle_search_select(self.state.clone(), target);
+    }
+
+    /// Open or close the command palette
+    pub fn handle_command_palette_toggle(&mut self) {
+        handlers::commands::handle_command_palette_toggle(self.state.clone());
+    }
+
+    /// Import the mnemonic entered on the Wallet screen into the keystore
+    pub fn handle_mnemonic_import(&mut self) {
+        handlers::keystore::handle_mnemonic_import(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Derive and scan balances of the keystore's account indexes
+    pub fn handle_derived_scan(&mut self) {
+        handlers::keystore::handle_derived_scan(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Activate a derived account index as the connected wallet
+    pub fn handle_derived_activate(&mut self, index: u32) {
+        handlers::keystore::handle_derived_activate(self.state.clone(), self.event_tx.clone(), index);
+    }
+
+    /// Set the label of a derived account index
+    pub fn handle_derived_label(&mut self, index: u32, label: String) {
+        handlers::keystore::handle_derived_label(self.state.clone(), index, label);
+    }
+
+    /// Change the chart indicator selection/periods
+    pub fn handle_indicator_config_change(&mut self, config: crate::ui::chart::indicators::IndicatorConfig) {
+        handlers::settings::handle_indicator_config_change(self.state.clone(), config);
+    }
+
     /// Trigger async swap quote fetch with debouncing
     pub fn trigger_quote_fetch(&mut self) {
         tasks::swap::trigger_quote_fetch(self.state.clone(), self.event_tx.clone());
@@ -661,11 +1124,31 @@ impl App {
         handlers::settings::handle_theme_color_change(self.state.clone(), config);
     }
 
+    /// Import a theme file picked in a native dialog
+    pub fn handle_theme_import(&mut self) {
+        handlers::settings::handle_theme_import(self.state.clone());
+    }
+
+    /// Export the current theme to a file picked in a native dialog
+    pub fn handle_theme_export(&mut self) {
+        handlers::settings::handle_theme_export(self.state.clone());
+    }
+
     /// Handle settings save
     pub fn handle_settings_save(&mut self) {
         handlers::settings::handle_settings_save(self.state.clone());
     }
 
+    /// Save and apply the backend server list
+    pub fn handle_server_list_save(&mut self) {
+        handlers::settings::handle_server_list_save(self.state.clone());
+    }
+
+    /// Apply Settings > Connection and reconnect the services
+    pub fn handle_connection_apply(&mut self) {
+        handlers::settings::handle_connection_apply(self.state.clone(), self.event_tx.clone());
+    }
+
     /// Handle settings reset to defaults
     pub fn handle_settings_reset(&mut self) {
         handlers::settings::handle_settings_reset(self.state.clone());
@@ -676,6 +1159,129 @@ impl App {
         handlers::settings::handle_settings_apply(self.state.clone());
     }
 
+    /// Live Assets row under the pointer this frame; prefetches its detail once hover intent fires
+    pub fn handle_asset_hover(&mut self, hovered: Option<String>) {
+        let intent = handlers::live_assets::handle_asset_hover(self.state.clone(), hovered.as_deref(), std::time::Instant::now());
+        if let Some(symbol) = intent {
+            tasks::market::prefetch_token_detail(self.state.clone(), self.event_tx.clone(), symbol);
+        }
+    }
+
+    /// Live Assets row got keyboard focus; prefetches its detail right away
+    pub fn handle_asset_focus(&mut self, symbol: String) {
+        let intent = handlers::live_assets::handle_asset_focus(self.state.clone(), &symbol, std::time::Instant::now());
+        if let Some(symbol) = intent {
+            tasks::market::prefetch_token_detail(self.state.clone(), self.event_tx.clone(), symbol);
+        }
+    }
+
+    /// Open the Live Assets detail view, from the cache when it has the token
+    pub fn handle_token_detail_open(&mut self, symbol: String) {
+        if handlers::live_assets::handle_token_detail_open(self.state.clone(), &symbol, std::time::Instant::now()) {
+            tasks::market::fetch_token_detail(self.state.clone(), self.event_tx.clone(), symbol);
+        }
+    }
+
+    /// Close the Live Assets detail view
+    pub fn handle_token_detail_close(&mut self) {
+        handlers::live_assets::handle_token_detail_close(self.state.clone());
+    }
+
+    /// Sort the Live Assets price list by a column, or flip its direction
+    pub fn handle_asset_sort_click(&mut self, column: AssetSortColumn) {
+        handlers::live_assets::handle_asset_sort_click(self.state.clone(), column);
+    }
+
+    /// Open the Live Chart on a Live Assets row's symbol
+    pub fn handle_asset_chart_open(&mut self, symbol: String) {
+        let timeframe = handlers::live_assets::handle_asset_chart_open(self.state.clone(), &symbol);
+        self.handle_screen_change(Screen::LiveChart);
+        if let Some(timeframe) = timeframe {
+            self.fetch_candles(&symbol, timeframe);
+        }
+    }
+
+    /// Turn bandwidth saver mode (no hover prefetching) on or off
+    pub fn handle_bandwidth_saver_toggle(&mut self, enabled: bool) {
+        handlers::settings::handle_bandwidth_saver_toggle(self.state.clone(), enabled);
+    }
+
+    /// Set the age after which oracle prices are shown as stale
+    pub fn handle_stale_price_threshold_change(&mut self, secs: u64) {
+        handlers::settings::handle_stale_price_threshold_change(self.state.clone(), secs);
+    }
+
+    /// Set the idle time before connections are closed (0 = never)
+    pub fn handle_idle_teardown_change(&mut self, mins: u64) {
+        handlers::settings::handle_idle_teardown_change(self.state.clone(), mins);
+    }
+
+    /// Apply a chart drawing tool, click or annotation menu choice
+    pub fn handle_chart_action(&mut self, action: crate::ui::chart::annotations::ChartAction) {
+        handlers::annotations::handle_chart_action(self.state.clone(), action);
+    }
+
+    /// Subscribe to the AI conversation (once per login)
+    pub fn handle_ai_chat_connect(&mut self) {
+        handlers::ai_chat::handle_ai_chat_connect(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Send a message to the AI assistant
+    pub fn handle_ai_chat_send(&mut self, text: String) {
+        handlers::ai_chat::handle_ai_chat_send(self.state.clone(), text);
+    }
+
+    /// Stop the AI reply in progress
+    pub fn handle_ai_chat_cancel(&mut self) {
+        handlers::ai_chat::handle_ai_chat_cancel(self.state.clone());
+    }
+
+    /// Show or mute a category of backend notices
+    pub fn handle_notification_category_toggle(&mut self, category: shared::NoticeCategory, enabled: bool) {
+        handlers::settings::handle_notification_category_toggle(self.state.clone(), category, enabled);
+    }
+
+    /// Wait for the next key press to rebind a shortcut; None stops waiting
+    pub fn handle_key_capture(&mut self, action: Option<keybindings::KeyAction>) {
+        handlers::settings::handle_key_capture(self.state.clone(), action);
+    }
+
+    /// Bind a shortcut to the captured key, unless it is taken
+    pub fn handle_key_binding_change(&mut self, action: keybindings::KeyAction, combo: keybindings::KeyCombo) {
+        handlers::settings::handle_key_binding_change(self.state.clone(), action, combo);
+    }
+
+    /// Restore the default keyboard shortcuts
+    pub fn handle_key_bindings_reset(&mut self) {
+        handlers::settings::handle_key_bindings_reset(self.state.clone());
+    }
+
+    /// Switch the UI language
+    pub fn handle_locale_change(&mut self, locale: crate::ui::i18n::Locale) {
+        handlers::settings::handle_locale_change(self.state.clone(), locale);
+    }
+
+    /// Opt in to or out of anonymous usage analytics
+    pub fn handle_usage_analytics_toggle(&mut self, enabled: bool) {
+        handlers::settings::handle_usage_analytics_toggle(self.state.clone(), enabled);
+    }
+
+    /// Delete the uploaded usage counts ("Delete my data")
+    pub fn handle_usage_data_delete(&mut self) {
+        handlers::settings::handle_usage_data_delete(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Reconnect the price stream after it gave up
+    pub fn handle_websocket_reconnect(&mut self) {
+        let mut state = self.state.write();
+        crate::services::api::websocket::restart_price_stream(&mut state, self.event_tx.clone(), self.state.clone());
+    }
+
+    /// Set the portfolio concentration and liquidity warning thresholds
+    pub fn handle_risk_thresholds_change(&mut self, thresholds: RiskThresholds) {
+        handlers::risk::handle_risk_thresholds_change(self.state.clone(), thresholds);
+    }
+
     /// Fetch candles for a symbol and timeframe
     pub fn fetch_candles(&mut self, symbol: &str, timeframe: shared::dto::market::Timeframe) {
         let timeframe_str = match timeframe {
@@ -695,6 +1301,31 @@ impl App {
         tasks::market::fetch_candles(self.state.clone(), self.event_tx.clone(), symbol.to_string(), timeframe);
     }
 
+    /// Fetch the depth ladder for a token pair (throttled)
+    pub fn fetch_depth(&mut self, input: &str, output: &str) {
+        tasks::market::fetch_depth(self.state.clone(), self.event_tx.clone(), input.to_string(), output.to_string());
+    }
+
+    /// Fetch volatility and correlations for the live assets (throttled)
+    pub fn fetch_market_analytics(&mut self) {
+        tasks::market::fetch_market_analytics(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Fetch candles for the Live Assets mini charts in one request (throttled)
+    pub fn fetch_sparklines(&mut self) {
+        tasks::market::fetch_sparklines(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Run a backend health check now instead of waiting for the next poll
+    pub fn check_backend_health(&mut self) {
+        tasks::health::check_health(self.state.clone(), self.event_tx.clone());
+    }
+
+    /// Reload friends, friend requests and unread counts
+    pub fn load_friends(&mut self) {
+        tasks::messaging::load_friends(self.state.clone());
+    }
+
     /// Open token picker popup
     pub fn open_token_picker_internal(&self, _state: &mut AppState, target: TokenPickerTarget) {
         handlers::swap::open_token_picker(self.state.clone(), target);
@@ -707,8 +1338,7 @@ impl App {
 
     /// Connect wallet from keypair file
     pub async fn connect_wallet_from_file(&self, path: &str) -> Result<String, String> {
-        let rpc_url = std::env::var("SOLANA_RPC_URL")
-            .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+        let rpc_url = self.state.read().config.rpc_endpoint();
 
         let mut wallet_service = crate::services::wallet::WalletService::new(&rpc_url);
 
@@ -737,8 +1367,7 @@ impl App {
 
     /// Generate a new wallet
     pub async fn generate_wallet(&self) -> Result<String, String> {
-        let rpc_url = std::env::var("SOLANA_RPC_URL")
-            .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+        let rpc_url = self.state.read().config.rpc_endpoint();
 
         let mut wallet_service = crate::services::wallet::WalletService::new(&rpc_url);
         let pubkey = wallet_service.generate_new_keypair();
@@ -766,6 +1395,7 @@ impl App {
             wallet_service.disconnect();
         }
         state.wallet_service = None;
+        state.offline_wallet = None;
         state.wallet = None;
     }
 
@@ -800,85 +1430,433 @@ impl AppLike for App {
         self.handle_switch_to_signup();
     }
     
-    fn handle_screen_change(&mut self, screen: Screen) {
-        self.handle_screen_change(screen);
+    fn handle_logout_click(&mut self) {
+        self.handle_logout_click();
     }
     
-    fn handle_swap_tab_change(&mut self, tab: SwapTab) {
-        self.handle_swap_tab_change(tab);
+    fn handle_change_password_click(&mut self) {
+        self.handle_change_password_click();
     }
     
-    fn next_screen(&mut self) {
-        self.next_screen();
+    fn handle_update_profile_click(&mut self) {
+        self.handle_update_profile_click();
     }
     
-    fn previous_screen(&mut self) {
-        self.previous_screen();
+    fn handle_session_refresh(&mut self) {
+        self.handle_session_refresh();
     }
     
-    fn handle_swap_execute_click(&mut self) {
-        self.handle_swap_execute_click();
+    fn handle_grant_session(&mut self) {
+        self.handle_grant_session();
     }
     
-    fn handle_token_select(&mut self, token: TokenInfo, target: TokenPickerTarget) {
-        self.handle_token_select(token, target);
+    fn handle_revoke_grant(&mut self, grant_id: u64) {
+        self.handle_revoke_grant(grant_id);
     }
     
-    fn handle_wallet_connect_click(&mut self) {
-        self.handle_wallet_connect_click();
+    fn handle_webhooks_refresh(&mut self) {
+        self.handle_webhooks_refresh();
     }
     
-    fn handle_wallet_generate_click(&mut self) {
-        self.handle_wallet_generate_click();
+    fn handle_webhook_create(&mut self) {
+        self.handle_webhook_create();
     }
     
-    fn handle_wallet_disconnect_click(&mut self) {
-        self.handle_wallet_disconnect_click();
+    fn handle_webhook_delete(&mut self, id: i64) {
+        self.handle_webhook_delete(id);
     }
     
-    fn trigger_quote_fetch(&mut self) {
-        self.trigger_quote_fetch();
+    fn handle_webhook_deliveries(&mut self, id: i64) {
+        self.handle_webhook_deliveries(id);
     }
     
-    fn fetch_token_list(&mut self) {
-        self.fetch_token_list();
+    fn handle_webhook_test(&mut self, id: i64) {
+        self.handle_webhook_test(id);
     }
     
-    fn handle_theme_color_change(&mut self, config: crate::ui::theme::ThemeConfig) {
-        self.handle_theme_color_change(config);
+    fn handle_api_keys_refresh(&mut self) {
+        self.handle_api_keys_refresh();
     }
     
-    fn handle_settings_save(&mut self) {
-        self.handle_settings_save();
+    fn handle_api_key_create(&mut self) {
+        self.handle_api_key_create();
     }
     
-    fn handle_settings_reset(&mut self) {
-        self.handle_settings_reset();
+    fn handle_api_key_revoke(&mut self, id: i64) {
+        self.handle_api_key_revoke(id);
     }
     
-    fn handle_settings_apply(&mut self) {
-        self.handle_settings_apply();
+    fn handle_screen_change(&mut self, screen: Screen) {
+        self.handle_screen_change(screen);
     }
     
-    fn fetch_candles(&mut self, symbol: &str, timeframe: shared::dto::market::Timeframe) {
-        self.fetch_candles(symbol, timeframe);
+    fn handle_swap_tab_change(&mut self, tab: SwapTab) {
+        self.handle_swap_tab_change(tab);
     }
     
-    fn open_token_picker_internal(&self, state: &mut AppState, target: TokenPickerTarget) {
-        self.open_token_picker_internal(state, target);
+    fn next_screen(&mut self) {
+        self.next_screen();
     }
     
-    fn set_max_amount(&mut self) {
-        self.set_max_amount();
+    fn previous_screen(&mut self) {
+        self.previous_screen();
     }
-}
 
-// Include tests from the old app.rs
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn refresh(&mut self, screen: Screen) {
+        self.refresh(screen);
+    }
 
-    // ========== Screen Tests ==========
+    fn refresh_all(&mut self) {
+        self.refresh_all();
+    }
+
+    fn current_screen(&self) -> Screen {
+        self.state.read().current_screen
+    }
+
+    fn view(&self) -> WindowView {
+        self.state.read().root_view.clone()
+    }
+
+    fn set_view(&mut self, view: WindowView) {
+        self.state.write().root_view = view;
+    }
+    
+    fn handle_swap_execute_click(&mut self) {
+        self.handle_swap_execute_click();
+    }
+
+    fn handle_swap_confirm(&mut self) {
+        self.handle_swap_confirm();
+    }
+
+    fn handle_swap_confirmation_cancel(&mut self) {
+        self.handle_swap_confirmation_cancel();
+    }
+    
+    fn handle_token_select(&mut self, token: TokenInfo, target: TokenPickerTarget) {
+        self.handle_token_select(token, target);
+    }
+
+    fn handle_token_favorite_toggle(&mut self, mint: String) {
+        self.handle_token_favorite_toggle(mint);
+    }
+    
+    fn handle_wallet_connect_click(&mut self) {
+        self.handle_wallet_connect_click();
+    }
+    
+    fn handle_wallet_generate_click(&mut self) {
+        self.handle_wallet_generate_click();
+    }
+    
+    fn handle_wallet_disconnect_click(&mut self) {
+        self.handle_wallet_disconnect_click();
+    }
+
+    fn handle_wallet_label_save(&mut self) {
+        self.handle_wallet_label_save();
+    }
+
+    fn handle_send_review(&mut self) {
+        self.handle_send_review();
+    }
+
+    fn handle_send_confirm(&mut self) {
+        self.handle_send_confirm();
+    }
+
+    fn handle_airdrop_request(&mut self) {
+        self.handle_airdrop_request();
+    }
+
+    fn handle_staking_refresh(&mut self) {
+        self.handle_staking_refresh();
+    }
+
+    fn handle_stake_action(&mut self, action: handlers::staking::StakeAction, stake_account: Option<String>) {
+        self.handle_stake_action(action, stake_account);
+    }
+
+    fn handle_send_cancel(&mut self) {
+        self.handle_send_cancel();
+    }
+
+    fn handle_recovery_action(&mut self, flow: recovery::RecoveryFlow, action: recovery::RecoveryAction) {
+        self.handle_recovery_action(flow, action);
+    }
+
+    fn handle_recovery_dismiss(&mut self, flow: recovery::RecoveryFlow) {
+        self.handle_recovery_dismiss(flow);
+    }
+
+    fn handle_token_balances_refresh(&mut self) {
+        self.handle_token_balances_refresh();
+    }
+
+    fn handle_transactions_refresh(&mut self) {
+        self.handle_transactions_refresh();
+    }
+
+    fn handle_transactions_export(&mut self) {
+        self.handle_transactions_export();
+    }
+
+    fn handle_trade_import_pick_file(&mut self) {
+        self.handle_trade_import_pick_file();
+    }
+
+    fn handle_trade_import_validate(&mut self) {
+        self.handle_trade_import_validate();
+    }
+
+    fn handle_trade_import_commit(&mut self) {
+        self.handle_trade_import_commit();
+    }
+
+    fn handle_trade_stats_refresh(&mut self) {
+        self.handle_trade_stats_refresh();
+    }
+
+    fn handle_swap_history_refresh(&mut self) {
+        self.handle_swap_history_refresh();
+    }
+
+    fn handle_swap_history_export(&mut self) {
+        self.handle_swap_history_export();
+    }
+
+    fn handle_share_chart(&mut self) {
+        self.handle_share_chart();
+    }
+
+    fn handle_share_portfolio(&mut self) {
+        self.handle_share_portfolio();
+    }
+
+    fn handle_onramp_open(&mut self) {
+        self.handle_onramp_open();
+    }
+
+    fn handle_onramp_buy(&mut self, provider_id: String) {
+        self.handle_onramp_buy(provider_id);
+    }
+
+    fn handle_rebalance_save(&mut self) {
+        self.handle_rebalance_save();
+    }
+
+    fn handle_rebalance_propose(&mut self) {
+        self.handle_rebalance_propose();
+    }
+
+    fn handle_rebalance_queue_all(&mut self) {
+        self.handle_rebalance_queue_all();
+    }
+
+    fn handle_search_toggle(&mut self) {
+        self.handle_search_toggle();
+    }
+
+    fn handle_search_query(&mut self, query: String) {
+        self.handle_search_query(query);
+    }
+
+    fn handle_search_select(&mut self, target: search::SearchTarget) {
+        self.handle_search_select(target);
+    }
+
+    fn handle_command_palette_toggle(&mut self) {
+        self.handle_command_palette_toggle();
+    }
+
+    fn handle_mnemonic_import(&mut self) {
+        self.handle_mnemonic_import();
+    }
+
+    fn handle_derived_scan(&mut self) {
+        self.handle_derived_scan();
+    }
+
+    fn handle_derived_activate(&mut self, index: u32) {
+        self.handle_derived_activate(index);
+    }
+
+    fn handle_derived_label(&mut self, index: u32, label: String) {
+        self.handle_derived_label(index, label);
+    }
+
+    fn handle_indicator_config_change(&mut self, config: crate::ui::chart::indicators::IndicatorConfig) {
+        self.handle_indicator_config_change(config);
+    }
+    
+    fn trigger_quote_fetch(&mut self) {
+        self.trigger_quote_fetch();
+    }
+    
+    fn fetch_token_list(&mut self) {
+        self.fetch_token_list();
+    }
+    
+    fn handle_theme_color_change(&mut self, config: crate::ui::theme::ThemeConfig) {
+        self.handle_theme_color_change(config);
+    }
+
+    fn handle_theme_import(&mut self) {
+        self.handle_theme_import();
+    }
+
+    fn handle_theme_export(&mut self) {
+        self.handle_theme_export();
+    }
+    
+    fn handle_settings_save(&mut self) {
+        self.handle_settings_save();
+    }
+
+    fn handle_server_list_save(&mut self) {
+        self.handle_server_list_save();
+    }
+
+    fn handle_connection_apply(&mut self) {
+        self.handle_connection_apply();
+    }
+    
+    fn handle_settings_reset(&mut self) {
+        self.handle_settings_reset();
+    }
+    
+    fn handle_settings_apply(&mut self) {
+        self.handle_settings_apply();
+    }
+    
+    fn fetch_candles(&mut self, symbol: &str, timeframe: shared::dto::market::Timeframe) {
+        self.fetch_candles(symbol, timeframe);
+    }
+
+    fn handle_asset_hover(&mut self, hovered: Option<String>) {
+        self.handle_asset_hover(hovered);
+    }
+
+    fn handle_asset_focus(&mut self, symbol: String) {
+        self.handle_asset_focus(symbol);
+    }
+
+    fn handle_token_detail_open(&mut self, symbol: String) {
+        self.handle_token_detail_open(symbol);
+    }
+
+    fn handle_token_detail_close(&mut self) {
+        self.handle_token_detail_close();
+    }
+
+    fn handle_asset_sort_click(&mut self, column: AssetSortColumn) {
+        self.handle_asset_sort_click(column);
+    }
+
+    fn handle_asset_chart_open(&mut self, symbol: String) {
+        self.handle_asset_chart_open(symbol);
+    }
+
+    fn handle_bandwidth_saver_toggle(&mut self, enabled: bool) {
+        self.handle_bandwidth_saver_toggle(enabled);
+    }
+
+    fn handle_stale_price_threshold_change(&mut self, secs: u64) {
+        self.handle_stale_price_threshold_change(secs);
+    }
+
+    fn handle_idle_teardown_change(&mut self, mins: u64) {
+        self.handle_idle_teardown_change(mins);
+    }
+
+    fn handle_notification_category_toggle(&mut self, category: shared::NoticeCategory, enabled: bool) {
+        self.handle_notification_category_toggle(category, enabled);
+    }
+
+    fn handle_key_capture(&mut self, action: Option<keybindings::KeyAction>) {
+        self.handle_key_capture(action);
+    }
+
+    fn handle_key_binding_change(&mut self, action: keybindings::KeyAction, combo: keybindings::KeyCombo) {
+        self.handle_key_binding_change(action, combo);
+    }
+
+    fn handle_key_bindings_reset(&mut self) {
+        self.handle_key_bindings_reset();
+    }
+
+    fn handle_locale_change(&mut self, locale: crate::ui::i18n::Locale) {
+        self.handle_locale_change(locale);
+    }
+
+    fn handle_usage_analytics_toggle(&mut self, enabled: bool) {
+        self.handle_usage_analytics_toggle(enabled);
+    }
+
+    fn handle_usage_data_delete(&mut self) {
+        self.handle_usage_data_delete();
+    }
+
+    fn handle_chart_action(&mut self, action: crate::ui::chart::annotations::ChartAction) {
+        self.handle_chart_action(action);
+    }
+
+    fn handle_ai_chat_connect(&mut self) {
+        self.handle_ai_chat_connect();
+    }
+
+    fn handle_ai_chat_send(&mut self, text: String) {
+        self.handle_ai_chat_send(text);
+    }
+
+    fn handle_ai_chat_cancel(&mut self) {
+        self.handle_ai_chat_cancel();
+    }
+
+    fn handle_risk_thresholds_change(&mut self, thresholds: RiskThresholds) {
+        self.handle_risk_thresholds_change(thresholds);
+    }
+
+    fn handle_websocket_reconnect(&mut self) {
+        self.handle_websocket_reconnect();
+    }
+    
+    fn fetch_depth(&mut self, input: &str, output: &str) {
+        self.fetch_depth(input, output);
+    }
+    
+    fn fetch_market_analytics(&mut self) {
+        self.fetch_market_analytics();
+    }
+    
+    fn fetch_sparklines(&mut self) {
+        self.fetch_sparklines();
+    }
+    
+    fn check_backend_health(&mut self) {
+        self.check_backend_health();
+    }
+    
+    fn load_friends(&mut self) {
+        self.load_friends();
+    }
+    
+    fn open_token_picker_internal(&self, state: &mut AppState, target: TokenPickerTarget) {
+        self.open_token_picker_internal(state, target);
+    }
+    
+    fn set_max_amount(&mut self) {
+        self.set_max_amount();
+    }
+}
+
+// Include tests from the old app.rs
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== Screen Tests ==========
 
     #[test]
     fn test_screen_all_returns_correct_order() {
@@ -1021,7 +1999,7 @@ mod tests {
         assert_eq!(state.terminal.swap.output_token, "USDC");
         assert_eq!(state.terminal.swap.amount, "");
         assert!(state.terminal.swap.quote.is_none());
-        assert!(!state.terminal.prices.is_empty());
+        assert!(!state.terminal.prices.load().is_empty());
         assert_eq!(state.terminal.chart_data.len(), 0);
     }
 
@@ -1031,10 +2009,11 @@ mod tests {
         let state = app.state.read();
 
         // Should have at least SOL, USDC, BTC, ETH
-        assert!(state.terminal.prices.len() >= 4);
+        let prices = state.terminal.prices.load();
+        assert!(prices.len() >= 4);
 
         // Find SOL price
-        let sol_price = state.terminal.prices.iter().find(|p| p.symbol == "SOL");
+        let sol_price = prices.get("SOL");
         assert!(sol_price.is_some(), "SOL price should be in initial prices");
 
         let sol = sol_price.expect("SOL price should exist in test");
@@ -1042,7 +2021,7 @@ mod tests {
         assert!(sol.previous_price.is_none()); // Initial state
 
         // Find USDC price (should be stable ~1.0)
-        let usdc_price = state.terminal.prices.iter().find(|p| p.symbol == "USDC");
+        let usdc_price = prices.get("USDC");
         assert!(usdc_price.is_some(), "USDC price should be in initial prices");
 
         let usdc = usdc_price.expect("USDC price should exist in test");
@@ -1067,6 +2046,64 @@ mod tests {
         assert_eq!(quote.estimated_fee, 0.25);
     }
 
+    #[tokio::test]
+    async fn test_newest_quote_wins_out_of_order() {
+        let mut app = App::new();
+        let quote = |output_amount: f64| SwapQuote {
+            input_amount: 0.0,
+            output_amount,
+            price_impact: 0.1,
+            estimated_fee: 0.000005,
+        };
+
+        // Typing "1", "12", "123" issues three requests
+        let mut generations = Vec::new();
+        for amount in ["1", "12", "123"] {
+            app.state.write().terminal.swap.amount = amount.to_string();
+            app.trigger_quote_fetch();
+            generations.push(app.state.read().terminal.swap.quote_generation);
+        }
+        assert!(generations.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(app.state.read().terminal.swap.quote_loading);
+
+        // "123" answers first, then the slower "1" and "12" requests land
+        app.handle_event(AppEvent::SwapQuoteResult(generations[2], Ok(quote(123.0))));
+        app.handle_event(AppEvent::SwapQuoteResult(generations[0], Ok(quote(1.0))));
+        app.handle_event(AppEvent::SwapQuoteResult(generations[1], Err("timeout".to_string())));
+
+        let state = app.state.read();
+        assert_eq!(state.terminal.swap.quote.as_ref().map(|q| q.output_amount), Some(123.0));
+        assert!(!state.terminal.swap.quote_loading);
+    }
+
+    #[tokio::test]
+    async fn test_pair_change_clears_quote() {
+        let mut app = App::new();
+        let generation = {
+            let mut state = app.state.write();
+            state.terminal.swap.quote = Some(SwapQuote {
+                input_amount: 1.0,
+                output_amount: 150.0,
+                price_impact: 0.1,
+                estimated_fee: 0.000005,
+            });
+            state.terminal.swap.quote_generation
+        };
+
+        // What handle_token_select does when either side of the pair changes
+        app.state.write().terminal.swap.reset_quote();
+        assert!(app.state.read().terminal.swap.quote.is_none());
+
+        // A quote for the old pair arriving late is ignored
+        app.handle_event(AppEvent::SwapQuoteResult(generation, Ok(SwapQuote {
+            input_amount: 1.0,
+            output_amount: 150.0,
+            price_impact: 0.1,
+            estimated_fee: 0.000005,
+        })));
+        assert!(app.state.read().terminal.swap.quote.is_none());
+    }
+
     // ========== PriceData Tests ==========
 
     #[test]
@@ -1077,6 +2114,11 @@ mod tests {
             change_24h: 5.0,
             previous_price: Some(145.0),
             source: Some("jupiter".to_string()),
+            confidence: None,
+            publish_time: None,
+            sources: Default::default(),
+            divergent: false,
+            divergence_pct: None,
         };
 
         assert_eq!(price_data.symbol, "SOL");
@@ -1093,6 +2135,11 @@ mod tests {
             change_24h: -2.5,
             previous_price: None,
             source: Some("pyth".to_string()),
+            confidence: None,
+            publish_time: None,
+            sources: Default::default(),
+            divergent: false,
+            divergence_pct: None,
         };
 
         assert_eq!(price_data.symbol, "BTC");
@@ -1101,6 +2148,34 @@ mod tests {
         assert!(price_data.previous_price.is_none());
     }
 
+    #[test]
+    fn test_price_data_sources_tooltip() {
+        let price_data = PriceData {
+            symbol: "USDC".to_string(),
+            price: 0.97,
+            change_24h: 0.0,
+            previous_price: None,
+            source: Some("pyth".to_string()),
+            confidence: None,
+            publish_time: None,
+            sources: [("pyth".to_string(), 0.97), ("jupiter".to_string(), 1.0)].into_iter().collect(),
+            divergent: true,
+            divergence_pct: None,
+        };
+
+        assert_eq!(
+            price_data.sources_tooltip(),
+            "Price sources disagree\njupiter: $1.0000\npyth: $0.9700"
+        );
+
+        let measured = PriceData { divergence_pct: PriceData::spread_pct(&price_data.sources), ..price_data };
+        assert_eq!(
+            measured.sources_tooltip(),
+            "Price sources disagree by 3.05%\njupiter: $1.0000\npyth: $0.9700"
+        );
+        assert_eq!(PriceData::spread_pct(&[("pyth".to_string(), 0.97)].into_iter().collect()), None);
+    }
+
     // ========== Wallet State Tests ==========
 
     #[test]
@@ -1121,6 +2196,7 @@ mod tests {
                     symbol: "USDC".to_string(),
                     amount: 1000.0,
                     usd_value: 1000.0,
+                    ..Default::default()
                 },
             ],
         };
@@ -1150,6 +2226,9 @@ mod tests {
             tx_type: "Swap".to_string(),
             status: "Confirmed".to_string(),
             amount: "10.5 SOL".to_string(),
+            memo: None,
+            wallet: None,
+            latency: None,
         };
 
         assert_eq!(tx.signature, "5J7B...");
@@ -1188,15 +2267,20 @@ mod tests {
         // Set initial prices
         {
             let mut state = app.state.write();
-            state.terminal.prices = vec![
+            state.terminal.prices.replace(vec![
                 PriceData {
                     symbol: "SOL".to_string(),
                     price: 145.0,
                     change_24h: 5.0,
                     previous_price: None,
                     source: Some("jupiter".to_string()),
+                    confidence: None,
+                    publish_time: None,
+                    sources: Default::default(),
+                    divergent: false,
+                    divergence_pct: None,
                 },
-            ];
+            ]);
         }
 
         // Simulate price update
@@ -1207,6 +2291,11 @@ mod tests {
                 change_24h: 5.0,
                 previous_price: None, // Will be set by the update logic
                 source: Some("jupiter".to_string()),
+                confidence: None,
+                publish_time: None,
+                sources: Default::default(),
+                divergent: false,
+                divergence_pct: None,
             },
         ];
 
@@ -1214,8 +2303,9 @@ mod tests {
 
         // Check that previous price was stored
         let state = app.state.read();
-        assert_eq!(state.terminal.prices[0].price, 150.0);
-        assert_eq!(state.terminal.prices[0].previous_price, Some(145.0));
+        let prices = state.terminal.prices.load();
+        assert_eq!(prices[0].price, 150.0);
+        assert_eq!(prices[0].previous_price, Some(145.0));
     }
 
     #[tokio::test]
@@ -1262,6 +2352,311 @@ mod tests {
     }
 
     #[tokio::test]
+    async fn test_logout_clears_session_and_returns_to_auth() {
+        let mut app = App::new();
+        {
+            let mut state = app.state.write();
+            state.auth_token = Some("jwt-token-here".to_string());
+            state.current_screen = Screen::Terminal;
+            state.websocket_connected = true;
+            state.price_stream_task = Some(tokio::spawn(std::future::pending::<()>()).abort_handle());
+        }
+        let task = app.state.read().price_stream_task.clone().unwrap();
+
+        app.handle_logout_click();
+
+        let state = app.state.read();
+        assert!(state.auth_token.is_none());
+        assert!(state.current_user.is_none());
+        assert!(!state.websocket_connected);
+        assert!(state.price_stream_task.is_none());
+        assert_eq!(state.current_screen, Screen::Auth);
+        assert!(matches!(state.auth, AuthState::Login { error: None, .. }));
+        drop(state);
+
+        // The aborted stream task stops the next time the scheduler gets to it
+        for _ in 0..10 {
+            if task.is_finished() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert!(task.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_password_change_keeps_fresh_token() {
+        let mut app = App::new();
+        {
+            let mut state = app.state.write();
+            state.auth_token = Some("old-token".to_string());
+            let form = &mut state.settings.account;
+            form.current_password = "OldPassword123!".to_string();
+            form.new_password = "NewPassword456!".to_string();
+            form.confirm_password = "Mismatch456!".to_string();
+        }
+
+        // Mismatched confirmation never reaches the backend
+        app.handle_change_password_click();
+        {
+            let state = app.state.read();
+            assert!(!state.settings.account.pending);
+            assert_eq!(state.settings.account.status, Some((true, "New passwords do not match".to_string())));
+        }
+
+        app.state.write().settings.account.pending = true;
+        app.handle_event(AppEvent::ProfileUpdateResult(Ok(crate::app::events::ProfileUpdate::Password(
+            shared::AuthResponse {
+                user: shared::UserInfo {
+                    id: "1".to_string(),
+                    username: "testuser".to_string(),
+                    email: "test@example.com".to_string(),
+                    created_at: "2025-02-18T00:00:00Z".to_string(),
+                    wallet_address: None,
+                },
+                token: "fresh-token".to_string(),
+                message: "Password changed".to_string(),
+                wallet_setup_required: None,
+                wallet_setup_token: None,
+            },
+        ))));
+
+        let state = app.state.read();
+        assert_eq!(state.auth_token, Some("fresh-token".to_string()));
+        assert!(!state.settings.account.pending);
+        assert!(state.settings.account.current_password.is_empty());
+        assert!(state.settings.account.new_password.is_empty());
+        assert_eq!(state.settings.account.status, Some((false, "Password changed".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_session_refresh_result_only_applies_to_current_token() {
+        let mut app = App::new();
+        let refreshed = |token: &str| shared::AuthResponse {
+            user: shared::UserInfo {
+                id: "1".to_string(),
+                username: "testuser".to_string(),
+                email: "test@example.com".to_string(),
+                created_at: "2025-02-18T00:00:00Z".to_string(),
+                wallet_address: None,
+            },
+            token: token.to_string(),
+            message: "Session refreshed".to_string(),
+            wallet_setup_required: None,
+            wallet_setup_token: None,
+        };
+        {
+            let mut state = app.state.write();
+            state.auth_token = Some("current-token".to_string());
+            state.session.refreshing = true;
+        }
+
+        // A refresh started before a re-login must not overwrite the new token
+        app.handle_event(AppEvent::SessionRefreshResult {
+            from_token: "earlier-token".to_string(),
+            result: Ok(refreshed("stale-token")),
+        });
+        assert_eq!(app.state.read().auth_token, Some("current-token".to_string()));
+        assert!(!app.state.read().session.refreshing);
+
+        // Failures warn once and are remembered for the Security settings
+        for _ in 0..2 {
+            app.handle_event(AppEvent::SessionRefreshResult {
+                from_token: "current-token".to_string(),
+                result: Err("Network error".to_string()),
+            });
+        }
+        {
+            let state = app.state.read();
+            assert_eq!(state.session.refresh_error, Some("Network error".to_string()));
+            assert_eq!(state.pending_notifications.iter().filter(|(level, _)| level == "warning").count(), 1);
+        }
+
+        app.handle_event(AppEvent::SessionRefreshResult {
+            from_token: "current-token".to_string(),
+            result: Ok(refreshed("fresh-token")),
+        });
+        let state = app.state.read();
+        assert_eq!(state.auth_token, Some("fresh-token".to_string()));
+        assert_eq!(state.session.refresh_error, None);
+    }
+
+    // ========== Live Assets Preload Tests ==========
+
+    fn preload_app() -> App {
+        let app = App::new();
+        app.state.write().settings.network.bandwidth_saver = false;
+        app
+    }
+
+    fn sol_detail() -> preload::TokenDetail {
+        let candle = shared::dto::market::OHLC {
+            timestamp: 0,
+            open: 100.0,
+            high: 110.0,
+            low: 95.0,
+            close: 105.0,
+            volume: 1_000.0,
+        };
+        preload::TokenDetail::new(preload::PRELOAD_TIMEFRAME, vec![candle])
+    }
+
+    #[tokio::test]
+    async fn test_hover_intent_prefetches_and_cancels_on_move() {
+        let app = preload_app();
+        let start = std::time::Instant::now();
+        let hover = |symbol: Option<&str>, at: std::time::Duration| {
+            handlers::live_assets::handle_asset_hover(app.state.clone(), symbol, start + at)
+        };
+
+        // Passing over a row starts nothing
+        assert_eq!(hover(Some("SOL"), std::time::Duration::ZERO), None);
+        assert_eq!(hover(Some("SOL"), std::time::Duration::from_millis(299)), None);
+        let intent = hover(Some("SOL"), preload::HOVER_INTENT_DELAY);
+        assert_eq!(intent, Some("SOL".to_string()));
+        tasks::market::prefetch_token_detail(app.state.clone(), app.event_tx.clone(), "SOL".to_string());
+        assert!(app.state.read().live_assets.prefetch.is_running("SOL"));
+
+        // Moving to the next row cancels the prefetch left behind
+        assert_eq!(hover(Some("JUP"), std::time::Duration::from_millis(400)), None);
+        assert_eq!(app.state.read().live_assets.prefetch.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_hover_keeps_the_fetch_of_the_opened_token() {
+        let mut app = preload_app();
+        app.handle_asset_focus("SOL".to_string());
+        assert!(app.state.read().live_assets.prefetch.is_running("SOL"));
+
+        // Opening while the prefetch runs waits for it instead of fetching again
+        app.handle_token_detail_open("SOL".to_string());
+        assert!(app.state.read().live_assets.detail_loading);
+
+        handlers::live_assets::handle_asset_hover(app.state.clone(), Some("JUP"), std::time::Instant::now());
+        assert!(app.state.read().live_assets.prefetch.is_running("SOL"));
+    }
+
+    #[test]
+    fn test_prefetched_detail_opens_from_cache() {
+        let mut app = preload_app();
+        app.handle_event(AppEvent::TokenDetailResult {
+            symbol: "SOL".to_string(),
+            prefetch: true,
+            result: Ok(sol_detail()),
+        });
+        // A failed prefetch nobody is waiting on stays silent
+        app.handle_event(AppEvent::TokenDetailResult {
+            symbol: "JUP".to_string(),
+            prefetch: true,
+            result: Err("timeout".to_string()),
+        });
+
+        app.handle_token_detail_open("SOL".to_string());
+        let state = app.state.read();
+        let live_assets = &state.live_assets;
+        assert_eq!(live_assets.selected, Some("SOL".to_string()));
+        assert!(!live_assets.detail_loading);
+        assert_eq!(live_assets.detail_error, None);
+        assert_eq!(live_assets.details.get("SOL"), Some(&sol_detail()));
+        assert!(live_assets.details.get("JUP").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_bandwidth_saver_disables_prefetch() {
+        let mut app = preload_app();
+        app.state.write().settings.network.bandwidth_saver = true;
+
+        app.handle_asset_focus("SOL".to_string());
+        assert_eq!(app.state.read().live_assets.prefetch.in_flight(), 0);
+
+        // Opening a token still loads it
+        app.handle_token_detail_open("SOL".to_string());
+        assert!(app.state.read().live_assets.detail_loading);
+        app.handle_event(AppEvent::TokenDetailResult {
+            symbol: "SOL".to_string(),
+            prefetch: false,
+            result: Err("offline".to_string()),
+        });
+        let state = app.state.read();
+        assert!(!state.live_assets.detail_loading);
+        assert_eq!(state.live_assets.detail_error, Some("offline".to_string()));
+    }
+
+    #[test]
+    fn test_tracked_transactions_update_independently() {
+        use shared::dto::transactions::TransactionStatus;
+
+        let mut app = App::new();
+        {
+            let mut state = app.state.write();
+            for signature in ["sigA", "sigB"] {
+                state.transactions.push(TransactionItem {
+                    signature: signature.to_string(),
+                    timestamp: 0,
+                    tx_type: "Swap".to_string(),
+                    status: "pending".to_string(),
+                    amount: String::new(),
+                    memo: None,
+                    wallet: None,
+                    latency: None,
+                });
+            }
+        }
+        let mut changed = |signature: &str, status, error: Option<&str>| {
+            app.handle_event(AppEvent::TransactionStatusChanged {
+                signature: signature.to_string(),
+                status,
+                error: error.map(str::to_string),
+            });
+        };
+
+        changed("sigA", TransactionStatus::Processed, None);
+        changed("sigB", TransactionStatus::Confirmed, None);
+        changed("sigA", TransactionStatus::Failed, Some("custom program error: 0x1"));
+        changed("sigB", TransactionStatus::Finalized, None);
+        // A late intermediate step doesn't undo the outcome
+        changed("sigA", TransactionStatus::Confirmed, None);
+
+        let state = app.state.read();
+        let status = |signature: &str| {
+            state.transactions.iter().find(|tx| tx.signature == signature).unwrap().status.clone()
+        };
+        assert_eq!(status("sigA"), "failed");
+        assert_eq!(status("sigB"), "finalized");
+
+        // Only outcomes are announced
+        let levels: Vec<&str> = state.pending_notifications.iter().map(|(level, _)| level.as_str()).collect();
+        assert_eq!(levels, vec!["error", "success"]);
+        assert!(state.pending_notifications[0].1.contains("0x1"));
+    }
+
+    #[test]
+    fn test_expired_transaction_warns() {
+        use shared::dto::transactions::TransactionStatus;
+
+        let mut app = App::new();
+        app.state.write().transactions.push(TransactionItem {
+            signature: "sigC".to_string(),
+            timestamp: 0,
+            tx_type: "Swap".to_string(),
+            status: "pending".to_string(),
+            amount: String::new(),
+            memo: None,
+            wallet: None,
+            latency: None,
+        });
+        app.handle_event(AppEvent::TransactionStatusChanged {
+            signature: "sigC".to_string(),
+            status: TransactionStatus::Expired,
+            error: None,
+        });
+
+        let state = app.state.read();
+        assert_eq!(state.transactions[0].status, "expired");
+        assert_eq!(state.pending_notifications.last().unwrap().0, "warning");
+    }
+
+        #[tokio::test]
     async fn test_app_event_loading_updates_error_field() {
         let mut app = App::new();
 
@@ -1276,6 +2671,240 @@ mod tests {
         }
     }
 
+    // ========== Refresh Tests ==========
+
+    fn refresh_app() -> App {
+        let app = App::new();
+        app.state.write().wallet = Some(WalletState {
+            address: "11111111111111111111111111111111".to_string(),
+            sol_balance: 1.0,
+            token_balances: Vec::new(),
+        });
+        app
+    }
+
+    fn refreshing(app: &App) -> Vec<&'static str> {
+        let state = app.state.read();
+        RefreshTarget::ALL
+            .iter()
+            .map(|target| target.key())
+            .filter(|key| state.refresh_tasks.is_running(key))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_refresh_reloads_what_each_screen_shows() {
+        for (screen, expected) in [
+            (Screen::Transactions, vec!["transaction_history"]),
+            (Screen::Wallet, vec!["wallet_balances"]),
+            (Screen::Tokens, vec!["wallet_balances", "token_list"]),
+            (Screen::LiveChart, vec!["candles"]),
+            (Screen::Settings, vec![]),
+        ] {
+            let mut app = refresh_app();
+            app.refresh(screen);
+            assert_eq!(refreshing(&app), expected, "{:?}", screen);
+        }
+
+        // Candles reload for the timeframe on screen
+        let mut app = refresh_app();
+        app.state.write().terminal.chart_timeframe = shared::dto::market::Timeframe::FourHours;
+        app.refresh(Screen::LiveChart);
+        assert!(app.state.read().terminal.chart_loading);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_in_flight_is_not_repeated() {
+        let state = refresh_app().state;
+        let (event_tx, _event_rx) = async_channel::unbounded();
+
+        assert_eq!(handlers::refresh::handle_refresh(state.clone(), event_tx.clone(), Screen::Transactions), 1);
+        // Pressing F5 again while the history loads starts nothing new
+        assert_eq!(handlers::refresh::handle_refresh(state.clone(), event_tx.clone(), Screen::Transactions), 0);
+        assert_eq!(state.read().pending_notifications.len(), 1);
+
+        // Once it finishes the next press reloads again
+        state.write().refresh_tasks.finish(RefreshTarget::TransactionHistory.key());
+        assert_eq!(handlers::refresh::handle_refresh(state.clone(), event_tx, Screen::Transactions), 1);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_without_wallet_starts_nothing() {
+        let mut app = App::new();
+        app.refresh(Screen::Transactions);
+        app.refresh(Screen::Wallet);
+        assert!(refreshing(&app).is_empty());
+        assert!(app.state.read().pending_notifications.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_invalidates_client_caches() {
+        let mut app = refresh_app();
+        let now = std::time::Instant::now();
+        {
+            let mut state = app.state.write();
+            state.terminal.last_depth_fetch = Some(now);
+            state.live_assets.last_analytics_fetch = Some((vec!["SOL".to_string()], 30, now));
+            state.live_assets.details.insert("SOL", sol_detail(), now);
+        }
+
+        // The chart's refresh leaves Live Assets' cache alone
+        app.refresh(Screen::Terminal);
+        {
+            let state = app.state.read();
+            assert!(state.terminal.last_depth_fetch.is_none());
+            assert!(state.live_assets.last_analytics_fetch.is_some());
+            assert_eq!(state.live_assets.details.len(), 1);
+        }
+
+        // Refreshing Live Assets refetches the open token instead of serving it from the cache
+        app.state.write().live_assets.selected = Some("SOL".to_string());
+        app.refresh(Screen::LiveAssets);
+        let state = app.state.read();
+        assert!(state.live_assets.last_analytics_fetch.is_none());
+        assert!(state.live_assets.details.is_empty());
+        assert!(state.live_assets.detail_loading);
+        assert!(state.refresh_tasks.is_running(RefreshTarget::MarketAnalytics.key()));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_all_clears_every_cache() {
+        let mut app = refresh_app();
+        let now = std::time::Instant::now();
+        {
+            let mut state = app.state.write();
+            state.terminal.last_depth_fetch = Some(now);
+            state.live_assets.last_analytics_fetch = Some((vec!["SOL".to_string()], 30, now));
+            state.live_assets.details.insert("JUP", sol_detail(), now);
+        }
+        app.refresh(Screen::Transactions);
+
+        app.refresh_all();
+        let state = app.state.read();
+        assert!(state.terminal.last_depth_fetch.is_none());
+        assert!(state.live_assets.last_analytics_fetch.is_none());
+        assert!(state.live_assets.details.is_empty());
+        drop(state);
+        // The history reload already running is kept, not doubled
+        assert_eq!(refreshing(&app), vec!["transaction_history", "wallet_balances", "token_list", "candles"]);
+    }
+
+    // ========== Rebalance Tests ==========
+
+    fn rebalance_app() -> App {
+        let app = App::new();
+        {
+            let mut state = app.state.write();
+            state.portfolio.holdings = vec![
+                PortfolioHolding {
+                    symbol: "SOL".to_string(),
+                    amount: 4.0,
+                    price: Some(150.0),
+                    value: 600.0,
+                    allocation_pct: 75.0,
+                    change_24h_pct: 0.0,
+                    change_24h_value: 0.0,
+                    wallets: Vec::new(),
+                },
+                PortfolioHolding {
+                    symbol: "USDC".to_string(),
+                    amount: 200.0,
+                    price: Some(1.0),
+                    value: 200.0,
+                    allocation_pct: 25.0,
+                    change_24h_pct: 0.0,
+                    change_24h_value: 0.0,
+                    wallets: Vec::new(),
+                },
+            ];
+            let rebalance = &mut state.portfolio.rebalance;
+            rebalance.targets = vec![
+                TargetAllocation { symbol: "SOL".to_string(), pct: 50.0 },
+                TargetAllocation { symbol: "USDC".to_string(), pct: 50.0 },
+            ];
+            rebalance.min_trade_usd = DEFAULT_MIN_TRADE_USD;
+        }
+        app
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_proposal_is_only_queued_for_review() {
+        let mut app = rebalance_app();
+        app.handle_rebalance_propose();
+        {
+            let state = app.state.read();
+            let proposals = &state.portfolio.rebalance.proposals;
+            assert_eq!(proposals.len(), 1);
+            assert_eq!(proposals[0].swap.input_symbol, "SOL");
+            assert_eq!(proposals[0].swap.input_amount, 1.33333);
+            // Nothing knows USDC's mint yet, so there is nothing to quote
+            assert!(matches!(&proposals[0].quote, Some(Err(e)) if e.contains("USDC")));
+        }
+
+        app.handle_rebalance_queue_all();
+        let state = app.state.read();
+        assert_eq!(state.terminal.swap.queue.len(), 1);
+        assert_eq!(state.terminal.swap.queue[0].amount, 1.33333);
+        assert!(state.terminal.swap_panel_open);
+        // Queuing never starts building or signing a swap
+        assert!(!state.terminal.swap.preparing);
+        assert!(state.terminal.swap.confirmation.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_rejects_targets_off_100_percent() {
+        let mut app = rebalance_app();
+        app.state.write().portfolio.rebalance.targets[1].pct = 40.0;
+        app.handle_rebalance_propose();
+
+        let state = app.state.read();
+        assert!(state.portfolio.rebalance.proposals.is_empty());
+        assert!(matches!(&state.portfolio.rebalance.status, Some((true, _))));
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_drops_quotes_of_older_proposals() {
+        let mut app = rebalance_app();
+        app.handle_rebalance_propose();
+        let generation = app.state.read().portfolio.rebalance.generation;
+        let quote = SwapQuote {
+            input_amount: 1.33333,
+            output_amount: 199.9,
+            price_impact: 0.05,
+            estimated_fee: 0.000005,
+        };
+
+        app.handle_event(AppEvent::RebalanceQuoteResult { generation: generation - 1, index: 0, result: Ok(quote.clone()) });
+        assert!(matches!(&app.state.read().portfolio.rebalance.proposals[0].quote, Some(Err(_))));
+
+        app.handle_event(AppEvent::RebalanceQuoteResult { generation, index: 0, result: Ok(quote) });
+        assert!(matches!(&app.state.read().portfolio.rebalance.proposals[0].quote, Some(Ok(q)) if q.output_amount == 199.9));
+    }
+
+    #[test]
+    fn test_rebalance_targets_follow_the_profile() {
+        let app = App::new();
+        let mut state = app.state.write();
+        state.portfolio.rebalance.preferences.profiles.insert(
+            "alice".to_string(),
+            RebalanceProfile {
+                targets: vec![TargetAllocation { symbol: "SOL".to_string(), pct: 100.0 }],
+                min_trade_usd: 25.0,
+            },
+        );
+
+        state.current_user = Some(CurrentUser { id: 1, username: "alice".to_string() });
+        handlers::rebalance::sync_profile(&mut state);
+        assert_eq!(state.portfolio.rebalance.targets.len(), 1);
+        assert_eq!(state.portfolio.rebalance.min_trade_usd, 25.0);
+
+        state.current_user = None;
+        handlers::rebalance::sync_profile(&mut state);
+        assert_eq!(state.portfolio.rebalance.profile.as_deref(), Some(handlers::rebalance::DEFAULT_PROFILE));
+        assert!(state.portfolio.rebalance.targets.is_empty());
+        assert_eq!(state.portfolio.rebalance.min_trade_usd, DEFAULT_MIN_TRADE_USD);
+    }
+
     // ========== Integration Tests ==========
 
     #[test]
@@ -1290,7 +2919,7 @@ mod tests {
         assert!(state.wallet.is_none());
         assert_eq!(state.transactions.len(), 0);
         assert!(state.api_client.is_some());
-        assert!(!state.terminal.prices.is_empty());
+        assert!(!state.terminal.prices.load().is_empty());
     }
 
     #[test]
@@ -0,0 +1,367 @@
+//! # Token Detail Preloading
+//!
+//! Opening a token in Live Assets needs its candles and stats, which take a
+//! round trip to load. Hovering a row is a good predictor of the click, so the
+//! data is fetched in the background once the pointer rests on a row for
+//! [`HOVER_INTENT_DELAY`] (or the row gets keyboard focus) and kept in a
+//! [`TokenDetailCache`]; the click then renders straight from the cache.
+//!
+//! ## Bounding the work
+//!
+//! - [`TaskSupervisor`] keys prefetches per token and runs at most
+//!   [`MAX_PREFETCH_IN_FLIGHT`] at a time
+//! - Moving the hover to another row cancels the prefetches for the rows left
+//!   behind; an explicit open is never cancelled by hovering
+//! - Entries older than [`DETAIL_CACHE_TTL`] are refetched, and the cache keeps
+//!   at most [`DETAIL_CACHE_CAPACITY`] tokens
+//! - The bandwidth saver setting turns hover prefetching off entirely
+
+use shared::dto::market::{Timeframe, OHLC};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::task::AbortHandle;
+
+/// How long the pointer must rest on a row before its data is prefetched
+pub const HOVER_INTENT_DELAY: Duration = Duration::from_millis(300);
+
+/// Most prefetches running at once
+pub const MAX_PREFETCH_IN_FLIGHT: usize = 2;
+
+/// Age after which cached token details are fetched again
+pub const DETAIL_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Most tokens kept in the detail cache
+pub const DETAIL_CACHE_CAPACITY: usize = 32;
+
+/// Timeframe prefetched, the terminal chart's default
+pub const PRELOAD_TIMEFRAME: Timeframe = Timeframe::OneHour;
+
+/// Candles requested per token
+pub const PRELOAD_CANDLES: usize = 100;
+
+/// Tracks how long the pointer has rested on one row
+#[derive(Debug, Clone, Default)]
+pub struct HoverIntent {
+    /// Row under the pointer and since when
+    hovered: Option<(String, Instant)>,
+    /// Row the intent already fired for, so it fires once per hover
+    fired: Option<String>,
+}
+
+impl HoverIntent {
+    /// Record the row under the pointer this frame (`None` when it's over no row).
+    ///
+    /// Returns the row's key the first frame it has been hovered for at least
+    /// [`HOVER_INTENT_DELAY`].
+    pub fn update(&mut self, hovered: Option<&str>, now: Instant) -> Option<String> {
+        let Some(key) = hovered else {
+            self.hovered = None;
+            self.fired = None;
+            return None;
+        };
+        match &self.hovered {
+            Some((current, _)) if current == key => {}
+            _ => {
+                self.hovered = Some((key.to_string(), now));
+                self.fired = None;
+            }
+        }
+        let (_, since) = self.hovered.as_ref()?;
+        if self.fired.is_none() && now.duration_since(*since) >= HOVER_INTENT_DELAY {
+            self.fired = Some(key.to_string());
+            return self.fired.clone();
+        }
+        None
+    }
+
+    /// Keyboard focus is explicit intent: fire at once
+    pub fn focus(&mut self, key: &str, now: Instant) -> Option<String> {
+        if self.fired.as_deref() == Some(key) {
+            return None;
+        }
+        self.hovered = Some((key.to_string(), now));
+        self.fired = Some(key.to_string());
+        self.fired.clone()
+    }
+
+    /// Row the pointer currently rests on
+    pub fn hovered(&self) -> Option<&str> {
+        self.hovered.as_ref().map(|(key, _)| key.as_str())
+    }
+}
+
+/// Whether a supervised task was started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnOutcome {
+    Started,
+    /// A task for the key is already running
+    AlreadyRunning,
+    /// The in-flight limit is reached
+    AtCapacity,
+}
+
+/// Background tasks keyed by name (a token, a refresh target), with an
+/// in-flight limit.
+///
+/// Finished tasks must be reported with [`TaskSupervisor::finish`] so their
+/// slot frees up; aborted ones are forgotten right away.
+#[derive(Debug, Clone)]
+pub struct TaskSupervisor {
+    tasks: HashMap<String, AbortHandle>,
+    limit: usize,
+}
+
+impl Default for TaskSupervisor {
+    fn default() -> Self {
+        Self::new(MAX_PREFETCH_IN_FLIGHT)
+    }
+}
+
+impl TaskSupervisor {
+    pub fn new(limit: usize) -> Self {
+        Self { tasks: HashMap::new(), limit }
+    }
+
+    /// Spawn `task` for `key` unless one is running or the limit is reached
+    pub fn spawn<F>(&mut self, key: &str, task: F) -> SpawnOutcome
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        if self.tasks.contains_key(key) {
+            return SpawnOutcome::AlreadyRunning;
+        }
+        if self.tasks.len() >= self.limit {
+            return SpawnOutcome::AtCapacity;
+        }
+        self.tasks.insert(key.to_string(), tokio::spawn(task).abort_handle());
+        SpawnOutcome::Started
+    }
+
+    /// Forget a task that completed
+    pub fn finish(&mut self, key: &str) {
+        self.tasks.remove(key);
+    }
+
+    /// Abort every task except those for the `keep` keys
+    pub fn cancel_except(&mut self, keep: &[&str]) -> usize {
+        let before = self.tasks.len();
+        self.tasks.retain(|key, handle| {
+            let kept = keep.contains(&key.as_str());
+            if !kept {
+                handle.abort();
+            }
+            kept
+        });
+        before - self.tasks.len()
+    }
+
+    pub fn is_running(&self, key: &str) -> bool {
+        self.tasks.contains_key(key)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.tasks.len()
+    }
+}
+
+/// Summary of a token over the loaded candles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenStats {
+    pub high: f64,
+    pub low: f64,
+    /// Change from the first open to the last close, in percent
+    pub change_pct: f64,
+    pub volume: f64,
+}
+
+impl TokenStats {
+    /// Stats over `candles` (oldest first); `None` when there are none
+    pub fn from_candles(candles: &[OHLC]) -> Option<Self> {
+        let (first, last) = (candles.first()?, candles.last()?);
+        let change_pct = if first.open > 0.0 { (last.close - first.open) / first.open * 100.0 } else { 0.0 };
+        Some(Self {
+            high: candles.iter().map(|c| c.high).fold(f64::MIN, f64::max),
+            low: candles.iter().map(|c| c.low).fold(f64::MAX, f64::min),
+            change_pct,
+            volume: candles.iter().map(|c| c.volume).sum(),
+        })
+    }
+}
+
+/// Everything the token detail view shows
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenDetail {
+    pub timeframe: Timeframe,
+    pub candles: Vec<OHLC>,
+    pub stats: Option<TokenStats>,
+}
+
+impl TokenDetail {
+    pub fn new(timeframe: Timeframe, candles: Vec<OHLC>) -> Self {
+        let stats = TokenStats::from_candles(&candles);
+        Self { timeframe, candles, stats }
+    }
+}
+
+/// Recently loaded token details, by token key
+#[derive(Debug, Clone, Default)]
+pub struct TokenDetailCache {
+    entries: HashMap<String, (TokenDetail, Instant)>,
+}
+
+impl TokenDetailCache {
+    /// Detail for `key` if it was loaded less than [`DETAIL_CACHE_TTL`] ago
+    pub fn get_fresh(&self, key: &str, now: Instant) -> Option<&TokenDetail> {
+        self.entries
+            .get(key)
+            .filter(|(_, loaded_at)| now.duration_since(*loaded_at) < DETAIL_CACHE_TTL)
+            .map(|(detail, _)| detail)
+    }
+
+    /// Detail for `key` however old, to show while a refresh loads
+    pub fn get(&self, key: &str) -> Option<&TokenDetail> {
+        self.entries.get(key).map(|(detail, _)| detail)
+    }
+
+    /// Store a detail, evicting the oldest entry when full
+    pub fn insert(&mut self, key: &str, detail: TokenDetail, now: Instant) {
+        if !self.entries.contains_key(key) && self.entries.len() >= DETAIL_CACHE_CAPACITY {
+            let oldest = self.entries.iter().min_by_key(|(_, (_, at))| *at).map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(key.to_string(), (detail, now));
+    }
+
+    /// Forget every entry, so the next open loads from the backend
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(timestamp: i64, open: f64, close: f64) -> OHLC {
+        OHLC {
+            timestamp,
+            open,
+            high: open.max(close) + 1.0,
+            low: open.min(close) - 1.0,
+            close,
+            volume: 10.0,
+        }
+    }
+
+    #[test]
+    fn test_hover_intent_fires_once_after_delay() {
+        let mut intent = HoverIntent::default();
+        let start = Instant::now();
+
+        assert_eq!(intent.update(Some("SOL"), start), None);
+        assert_eq!(intent.update(Some("SOL"), start + Duration::from_millis(299)), None);
+        assert_eq!(intent.update(Some("SOL"), start + HOVER_INTENT_DELAY), Some("SOL".to_string()));
+        // Resting longer doesn't fire again
+        assert_eq!(intent.update(Some("SOL"), start + Duration::from_secs(2)), None);
+    }
+
+    #[test]
+    fn test_hover_intent_restarts_when_pointer_moves() {
+        let mut intent = HoverIntent::default();
+        let start = Instant::now();
+
+        intent.update(Some("SOL"), start);
+        // Passing over BONK on the way down doesn't count towards its delay
+        assert_eq!(intent.update(Some("BONK"), start + Duration::from_millis(250)), None);
+        assert_eq!(intent.update(Some("BONK"), start + Duration::from_millis(400)), None);
+        assert_eq!(intent.update(Some("BONK"), start + Duration::from_millis(550)), Some("BONK".to_string()));
+
+        // Leaving the list and coming back starts over
+        assert_eq!(intent.update(None, start + Duration::from_millis(600)), None);
+        assert_eq!(intent.hovered(), None);
+        assert_eq!(intent.update(Some("BONK"), start + Duration::from_millis(700)), None);
+    }
+
+    #[test]
+    fn test_keyboard_focus_fires_immediately() {
+        let mut intent = HoverIntent::default();
+        let now = Instant::now();
+        assert_eq!(intent.focus("JUP", now), Some("JUP".to_string()));
+        assert_eq!(intent.focus("JUP", now), None);
+        assert_eq!(intent.update(Some("JUP"), now + Duration::from_secs(1)), None);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_limits_and_cancels() {
+        let mut supervisor = TaskSupervisor::new(2);
+        let pending = || std::future::pending::<()>();
+
+        assert_eq!(supervisor.spawn("SOL", pending()), SpawnOutcome::Started);
+        assert_eq!(supervisor.spawn("SOL", pending()), SpawnOutcome::AlreadyRunning);
+        assert_eq!(supervisor.spawn("JUP", pending()), SpawnOutcome::Started);
+        assert_eq!(supervisor.spawn("BONK", pending()), SpawnOutcome::AtCapacity);
+
+        // Hover moved on to JUP: everything else goes
+        assert_eq!(supervisor.cancel_except(&["JUP"]), 1);
+        assert!(supervisor.is_running("JUP") && !supervisor.is_running("SOL"));
+        assert_eq!(supervisor.spawn("BONK", pending()), SpawnOutcome::Started);
+
+        supervisor.finish("JUP");
+        assert_eq!(supervisor.in_flight(), 1);
+        assert_eq!(supervisor.cancel_except(&[]), 1);
+        assert_eq!(supervisor.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_prefetch_never_completes() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let mut supervisor = TaskSupervisor::default();
+        supervisor.spawn("SOL", async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let _ = tx.send(());
+        });
+        supervisor.cancel_except(&[]);
+        // The sender is dropped with the aborted task instead of sending
+        assert!(rx.await.is_err());
+    }
+
+    #[test]
+    fn test_detail_cache_freshness_and_eviction() {
+        let mut cache = TokenDetailCache::default();
+        let now = Instant::now();
+        let detail = TokenDetail::new(PRELOAD_TIMEFRAME, vec![candle(0, 100.0, 110.0), candle(3600, 110.0, 120.0)]);
+
+        cache.insert("SOL", detail.clone(), now);
+        assert_eq!(cache.get_fresh("SOL", now + Duration::from_secs(59)), Some(&detail));
+        assert_eq!(cache.get_fresh("SOL", now + DETAIL_CACHE_TTL), None);
+        assert!(cache.get("SOL").is_some());
+
+        for i in 0..DETAIL_CACHE_CAPACITY {
+            cache.insert(&format!("T{}", i), detail.clone(), now + Duration::from_secs(1 + i as u64));
+        }
+        assert_eq!(cache.len(), DETAIL_CACHE_CAPACITY);
+        // SOL was the oldest
+        assert!(cache.get("SOL").is_none());
+    }
+
+    #[test]
+    fn test_token_stats() {
+        let stats = TokenStats::from_candles(&[candle(0, 100.0, 90.0), candle(3600, 90.0, 120.0)]).unwrap();
+        assert_eq!(stats.high, 121.0);
+        assert_eq!(stats.low, 89.0);
+        assert!((stats.change_pct - 20.0).abs() < 1e-9);
+        assert_eq!(stats.volume, 20.0);
+        assert!(TokenStats::from_candles(&[]).is_none());
+    }
+}
@@ -0,0 +1,410 @@
+//! # Lock-Free Price Store
+//!
+//! Holds the latest price per symbol outside the `AppState` lock.
+//!
+//! Writers (WebSocket stream, REST fallback) publish a new immutable
+//! [`PriceSnapshot`] via [`ArcSwap`]; readers (render path, portfolio valuation)
+//! load the current snapshot wait-free and never block a writer. The event
+//! channel only carries an [`AppEvent::PricesChanged`](crate::app::AppEvent::PricesChanged)
+//! nudge so the main loop can schedule a repaint.
+//!
+//! ## Concurrent Writers
+//!
+//! Updates go through [`ArcSwap::rcu`]: each writer copies the current
+//! snapshot, applies its delta and swaps only if nobody else published in the
+//! meantime, retrying otherwise. Two writers racing on the same snapshot
+//! therefore never overwrite each other's updates.
+
+use super::state::PriceData;
+use arc_swap::ArcSwap;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Minimum price move treated as a change (matches the old event handler)
+const PRICE_EPSILON: f64 = 0.0001;
+
+/// Immutable set of prices, in first-seen order
+#[derive(Debug, Clone, Default)]
+pub struct PriceSnapshot {
+    prices: Vec<PriceData>,
+    index: HashMap<String, usize>,
+    /// Version at which each price last moved, parallel to `prices`
+    moved: Vec<u64>,
+    /// Incremented on every published update
+    pub version: u64,
+}
+
+impl PriceSnapshot {
+    fn new(prices: Vec<PriceData>) -> Self {
+        let mut snapshot = Self::default();
+        for price in prices {
+            snapshot.upsert(price);
+        }
+        snapshot
+    }
+
+    /// Price for a symbol
+    pub fn get(&self, symbol: &str) -> Option<&PriceData> {
+        self.index.get(symbol).map(|&i| &self.prices[i])
+    }
+
+    /// Prices that moved (or first appeared) in an update published after
+    /// `version`; prices loaded by [`PriceStore::replace`] never count as moved
+    pub fn moved_since(&self, version: u64) -> impl Iterator<Item = &PriceData> {
+        self.prices
+            .iter()
+            .zip(&self.moved)
+            .filter(move |(_, &moved)| moved > version)
+            .map(|(price, _)| price)
+    }
+
+    /// Insert or update a price, recording the old price as `previous_price`.
+    /// Returns true if the price is new or moved by more than [`PRICE_EPSILON`].
+    fn upsert(&mut self, mut price: PriceData) -> bool {
+        match self.index.get(&price.symbol) {
+            Some(&i) => {
+                let existing = &mut self.prices[i];
+                let changed = (existing.price - price.price).abs() > PRICE_EPSILON;
+                price.previous_price = Some(existing.price);
+                if price.source.is_none() {
+                    price.source = existing.source.take();
+                }
+                *existing = price;
+                changed
+            }
+            None => {
+                self.index.insert(price.symbol.clone(), self.prices.len());
+                self.prices.push(price);
+                self.moved.push(0);
+                true
+            }
+        }
+    }
+}
+
+impl std::ops::Deref for PriceSnapshot {
+    type Target = [PriceData];
+
+    fn deref(&self) -> &[PriceData] {
+        &self.prices
+    }
+}
+
+/// Shared, lock-free price store
+pub struct PriceStore {
+    current: ArcSwap<PriceSnapshot>,
+}
+
+impl PriceStore {
+    pub fn new(prices: Vec<PriceData>) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(PriceSnapshot::new(prices)),
+        }
+    }
+
+    /// Current snapshot (wait-free)
+    pub fn load(&self) -> Arc<PriceSnapshot> {
+        self.current.load_full()
+    }
+
+    /// Apply a batch of price updates. Returns true if any price changed.
+    pub fn apply(&self, updates: &[PriceData]) -> bool {
+        if updates.is_empty() {
+            return false;
+        }
+
+        let mut changed = false;
+        self.current.rcu(|current| {
+            let mut next = PriceSnapshot::clone(current);
+            // rcu may retry the closure, so only the last run's result counts
+            changed = false;
+            next.version += 1;
+            for price in updates {
+                if next.upsert(price.clone()) {
+                    changed = true;
+                    let i = next.index[&price.symbol];
+                    next.moved[i] = next.version;
+                }
+            }
+            next
+        });
+        changed
+    }
+
+    /// Replace all prices (drops symbols not in `prices`)
+    pub fn replace(&self, prices: Vec<PriceData>) {
+        let fresh = PriceSnapshot::new(prices);
+        // Same rcu as `apply`, so a racing writer can't publish the same version
+        self.current.rcu(|current| PriceSnapshot {
+            version: current.version + 1,
+            ..fresh.clone()
+        });
+    }
+}
+
+impl Default for PriceStore {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl std::fmt::Debug for PriceStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let snapshot = self.current.load();
+        f.debug_struct("PriceStore")
+            .field("symbols", &snapshot.len())
+            .field("version", &snapshot.version)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn price(symbol: &str, value: f64) -> PriceData {
+        PriceData {
+            symbol: symbol.to_string(),
+            price: value,
+            change_24h: 0.0,
+            previous_price: None,
+            source: Some("test".to_string()),
+            confidence: None,
+            publish_time: None,
+            sources: Default::default(),
+            divergent: false,
+            divergence_pct: None,
+        }
+    }
+
+    #[test]
+    fn test_apply_tracks_previous_price_and_changes() {
+        let store = PriceStore::default();
+        assert!(store.apply(&[price("SOL", 145.0)]));
+        assert!(store.apply(&[price("SOL", 150.0)]));
+        assert!(!store.apply(&[price("SOL", 150.0)]));
+
+        let snapshot = store.load();
+        let sol = snapshot.get("SOL").unwrap();
+        assert_eq!(sol.price, 150.0);
+        assert_eq!(sol.previous_price, Some(150.0));
+        assert_eq!(snapshot.version, 3);
+    }
+
+    #[test]
+    fn test_readers_keep_their_snapshot() {
+        let store = PriceStore::new(vec![price("SOL", 1.0), price("BTC", 2.0)]);
+        let before = store.load();
+        store.apply(&[price("SOL", 5.0), price("ETH", 3.0)]);
+
+        assert_eq!(before.get("SOL").unwrap().price, 1.0);
+        assert!(before.get("ETH").is_none());
+
+        let after = store.load();
+        let symbols: Vec<&str> = after.iter().map(|p| p.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["SOL", "BTC", "ETH"]);
+    }
+
+    #[test]
+    fn test_replace_drops_missing_symbols() {
+        let store = PriceStore::new(vec![price("SOL", 1.0), price("BTC", 2.0)]);
+        store.replace(vec![price("ETH", 3.0)]);
+        let snapshot = store.load();
+        assert_eq!(snapshot.len(), 1);
+        assert!(snapshot.get("SOL").is_none());
+        assert_eq!(snapshot.get("ETH").unwrap().price, 3.0);
+    }
+
+    #[test]
+    fn test_moved_since_lists_prices_that_moved_after_a_version() {
+        let store = PriceStore::new(vec![price("SOL", 1.0), price("BTC", 2.0)]);
+        assert_eq!(store.load().moved_since(0).count(), 0);
+
+        store.apply(&[price("SOL", 1.5), price("BTC", 2.0)]);
+        let seen = store.load().version;
+        let symbols: Vec<String> = store.load().moved_since(0).map(|p| p.symbol.clone()).collect();
+        assert_eq!(symbols, vec!["SOL"]);
+
+        store.apply(&[price("BTC", 2.5), price("ETH", 3.0)]);
+        let snapshot = store.load();
+        let symbols: Vec<&str> = snapshot.moved_since(seen).map(|p| p.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["BTC", "ETH"]);
+    }
+
+    #[test]
+    fn test_concurrent_writers_do_not_lose_updates() {
+        const UPDATES: usize = 2_000;
+        let store = Arc::new(PriceStore::default());
+
+        // WebSocket-style writer: one symbol per update
+        let ws = {
+            let store = store.clone();
+            thread::spawn(move || {
+                for i in 0..UPDATES {
+                    store.apply(&[price(&format!("WS{}", i % 50), i as f64)]);
+                }
+            })
+        };
+        // REST-style writer: batches touching a disjoint symbol set
+        let rest = {
+            let store = store.clone();
+            thread::spawn(move || {
+                for i in 0..UPDATES {
+                    store.apply(&[price("REST_A", i as f64), price("REST_B", i as f64)]);
+                }
+            })
+        };
+        ws.join().unwrap();
+        rest.join().unwrap();
+
+        let snapshot = store.load();
+        assert_eq!(snapshot.version, (UPDATES * 2) as u64);
+        assert_eq!(snapshot.len(), 52);
+        assert_eq!(snapshot.get("REST_A").unwrap().price, (UPDATES - 1) as f64);
+        for s in 0..50 {
+            let last = (UPDATES - 50 + s) as f64;
+            assert_eq!(snapshot.get(&format!("WS{}", s)).unwrap().price, last);
+        }
+    }
+
+    #[test]
+    fn test_replace_racing_apply_bumps_the_version_once_each() {
+        const UPDATES: usize = 1_000;
+        let store = Arc::new(PriceStore::default());
+
+        let replacer = {
+            let store = store.clone();
+            thread::spawn(move || {
+                for i in 0..UPDATES {
+                    store.replace(vec![price("SOL", i as f64)]);
+                }
+            })
+        };
+        for i in 0..UPDATES {
+            store.apply(&[price("SOL", i as f64)]);
+        }
+        replacer.join().unwrap();
+
+        assert_eq!(store.load().version, (UPDATES * 2) as u64);
+    }
+
+    /// Frame-time jitter of a simulated 60 FPS render loop while a writer
+    /// publishes 500 updates/sec, comparing the old `RwLock<Vec<PriceData>>`
+    /// with the price store.
+    ///
+    /// Run with `cargo test --release -p terminal price_store -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_frame_jitter_at_500_updates_per_sec() {
+        use parking_lot::RwLock;
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+        use std::time::{Duration, Instant};
+
+        const SYMBOLS: usize = 200;
+        const UPDATE_INTERVAL: Duration = Duration::from_micros(2_000); // 500 updates/sec
+        const FRAME: Duration = Duration::from_micros(16_667); // 60 FPS
+        const FRAMES: usize = 300;
+        /// Price reads per frame (ticker, watchlist, portfolio, chart ...)
+        const READS_PER_FRAME: usize = 8;
+        let initial: Vec<PriceData> = (0..SYMBOLS).map(|i| price(&format!("T{}", i), 1.0)).collect();
+
+        struct Report {
+            updates_per_sec: f64,
+            p50: Duration,
+            p99: Duration,
+            max: Duration,
+            jitter: Duration,
+        }
+
+        fn run<W, R>(write: W, read: R) -> Report
+        where
+            W: Fn(usize) + Send + 'static,
+            R: Fn() -> f64,
+        {
+            let stop = Arc::new(AtomicBool::new(false));
+            let published = Arc::new(AtomicUsize::new(0));
+            let writer = {
+                let stop = stop.clone();
+                let published = published.clone();
+                thread::spawn(move || {
+                    // Paced on an absolute schedule so slow writes don't lower the rate
+                    let mut next = Instant::now();
+                    while !stop.load(Ordering::Relaxed) {
+                        write(published.fetch_add(1, Ordering::Relaxed));
+                        next += UPDATE_INTERVAL;
+                        if let Some(wait) = next.checked_duration_since(Instant::now()) {
+                            thread::sleep(wait);
+                        }
+                    }
+                })
+            };
+
+            let start = Instant::now();
+            let mut frames = Vec::with_capacity(FRAMES);
+            let mut next_frame = start;
+            for _ in 0..FRAMES {
+                let t = Instant::now();
+                for _ in 0..READS_PER_FRAME {
+                    std::hint::black_box(read());
+                }
+                frames.push(t.elapsed());
+                next_frame += FRAME;
+                if let Some(wait) = next_frame.checked_duration_since(Instant::now()) {
+                    thread::sleep(wait);
+                }
+            }
+            let elapsed = start.elapsed();
+            stop.store(true, Ordering::Relaxed);
+            writer.join().unwrap();
+
+            let mean = frames.iter().sum::<Duration>().as_secs_f64() / frames.len() as f64;
+            let variance = frames
+                .iter()
+                .map(|f| (f.as_secs_f64() - mean).powi(2))
+                .sum::<f64>()
+                / frames.len() as f64;
+            frames.sort();
+            let pct = |p: f64| frames[((frames.len() - 1) as f64 * p) as usize];
+            Report {
+                updates_per_sec: published.load(Ordering::Relaxed) as f64 / elapsed.as_secs_f64(),
+                p50: pct(0.5),
+                p99: pct(0.99),
+                max: *frames.last().unwrap(),
+                jitter: Duration::from_secs_f64(variance.sqrt()),
+            }
+        }
+
+        // Writer does the same per-update work as the old handler (find + update under lock)
+        let locked = Arc::new(RwLock::new(initial.clone()));
+        let writer_lock = locked.clone();
+        let before = run(
+            move |i| {
+                let mut prices = writer_lock.write();
+                if let Some(p) = prices.iter_mut().find(|p| p.symbol == format!("T{}", i % SYMBOLS)) {
+                    p.previous_price = Some(p.price);
+                    p.price = i as f64;
+                }
+                thread::sleep(Duration::from_micros(50)); // portfolio recompute etc. under the lock
+            },
+            || locked.read().iter().map(|p| p.price).sum(),
+        );
+
+        let store = Arc::new(PriceStore::new(initial));
+        let writer_store = store.clone();
+        let after = run(
+            move |i| {
+                writer_store.apply(&[price(&format!("T{}", i % SYMBOLS), i as f64)]);
+            },
+            || store.load().iter().map(|p| p.price).sum(),
+        );
+
+        println!("frame time at 60 FPS (updates/sec, p50 / p99 / max, jitter = std dev)");
+        for (name, r) in [("RwLock<Vec>", before), ("PriceStore ", after)] {
+            println!(
+                "  {}: {:.0}/s, {:?} / {:?} / {:?}, jitter {:?}",
+                name, r.updates_per_sec, r.p50, r.p99, r.max, r.jitter
+            );
+        }
+    }
+}
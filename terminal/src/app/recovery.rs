@@ -0,0 +1,421 @@
+//! # RPC Error Recovery
+//!
+//! Turns raw wallet and RPC failures ("429 Too Many Requests", "failed to get
+//! recent blockhash", ...) into a recovery card: what went wrong in plain
+//! words and a few buttons that usually fix it.
+//!
+//! [`classify`] matches an error message against [`PATTERNS`], one table for
+//! the Wallet and Swap flows alike. Each [`ErrorKind`] has a fixed list of
+//! [`RecoveryAction`]s; [`RecoveryStats`] counts how often each action was
+//! followed by a success in the same flow, and [`RecoveryStats::ranked`]
+//! puts the actions that worked best first. The counts are saved in the
+//! local database.
+//!
+//! Messages that match nothing stay plain error notifications.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// First automatic retry delay; doubled for every retry of the same card
+pub const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+/// Longest automatic retry delay
+pub const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// How long after an action a success still counts as its doing
+pub const RESOLUTION_WINDOW: Duration = Duration::from_secs(120);
+
+/// Where the error came from; each flow shows at most one card
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RecoveryFlow {
+    /// Connecting the wallet and loading its balances
+    Wallet,
+    /// Preparing and submitting a swap
+    Swap,
+}
+
+/// What went wrong, in terms the user can act on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ErrorKind {
+    /// The RPC node is throttling requests
+    RateLimited,
+    /// The node is behind the cluster or unhealthy
+    NodeBehind,
+    /// The endpoint can't be reached at all
+    NetworkUnreachable,
+    /// Not enough SOL to cover fees or keep an account rent-exempt
+    InsufficientRent,
+}
+
+impl ErrorKind {
+    pub fn title(&self) -> &'static str {
+        match self {
+            ErrorKind::RateLimited => "RPC node is rate limiting",
+            ErrorKind::NodeBehind => "RPC node is behind",
+            ErrorKind::NetworkUnreachable => "Network unreachable",
+            ErrorKind::InsufficientRent => "Not enough SOL for rent",
+        }
+    }
+
+    pub fn explanation(&self) -> &'static str {
+        match self {
+            ErrorKind::RateLimited => {
+                "The RPC endpoint refused the request because too many were sent. Public endpoints throttle hard; waiting a little or using another endpoint usually helps."
+            }
+            ErrorKind::NodeBehind => {
+                "The RPC node hasn't caught up with the cluster, so it couldn't provide a recent blockhash or account state. Another endpoint, or a retry in a few seconds, usually works."
+            }
+            ErrorKind::NetworkUnreachable => {
+                "The RPC endpoint could not be reached. Check your connection and the endpoint address in the network settings."
+            }
+            ErrorKind::InsufficientRent => {
+                "The wallet doesn't hold enough SOL to pay the fee and keep the new token account rent-exempt (about 0.002 SOL per account)."
+            }
+        }
+    }
+
+    /// Actions offered for this kind, most likely fix first
+    pub fn actions(&self) -> &'static [RecoveryAction] {
+        match self {
+            ErrorKind::RateLimited => &[RecoveryAction::RetryWithBackoff, RecoveryAction::SwitchRpcEndpoint],
+            ErrorKind::NodeBehind => &[RecoveryAction::SwitchRpcEndpoint, RecoveryAction::RetryWithBackoff],
+            ErrorKind::NetworkUnreachable => &[
+                RecoveryAction::RetryWithBackoff,
+                RecoveryAction::OpenNetworkSettings,
+                RecoveryAction::SwitchRpcEndpoint,
+            ],
+            ErrorKind::InsufficientRent => &[RecoveryAction::BuySol, RecoveryAction::RetryWithBackoff],
+        }
+    }
+}
+
+/// A button on the recovery card
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RecoveryAction {
+    /// Move to the next RPC endpoint (or backend server, for swaps)
+    SwitchRpcEndpoint,
+    /// Run the failed operation again after a growing delay
+    RetryWithBackoff,
+    /// Jump to Settings > Connection
+    OpenNetworkSettings,
+    /// Open the "Buy SOL" dialog
+    BuySol,
+}
+
+impl RecoveryAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RecoveryAction::SwitchRpcEndpoint => "Switch RPC endpoint",
+            RecoveryAction::RetryWithBackoff => "Retry",
+            RecoveryAction::OpenNetworkSettings => "Network settings",
+            RecoveryAction::BuySol => "Buy SOL",
+        }
+    }
+}
+
+/// Message fragments and the kind they indicate, checked in order
+///
+/// Matching is case-insensitive. More specific fragments come first: an
+/// RPC error that wraps a connection failure is a network problem, not a
+/// node problem.
+pub const PATTERNS: &[(&str, ErrorKind)] = &[
+    // Rate limiting: HTTP 429 and the JSON-RPC codes providers use for it
+    ("too many requests", ErrorKind::RateLimited),
+    ("rate limit", ErrorKind::RateLimited),
+    ("-32429", ErrorKind::RateLimited),
+    // Rent and balance
+    ("insufficient funds for rent", ErrorKind::InsufficientRent),
+    ("insufficientfundsforrent", ErrorKind::InsufficientRent),
+    ("no record of a prior credit", ErrorKind::InsufficientRent),
+    ("insufficient lamports", ErrorKind::InsufficientRent),
+    ("insufficient funds for fee", ErrorKind::InsufficientRent),
+    // Transport failures
+    ("error trying to connect", ErrorKind::NetworkUnreachable),
+    ("dns error", ErrorKind::NetworkUnreachable),
+    ("connection refused", ErrorKind::NetworkUnreachable),
+    ("connection reset", ErrorKind::NetworkUnreachable),
+    ("network is unreachable", ErrorKind::NetworkUnreachable),
+    ("timed out", ErrorKind::NetworkUnreachable),
+    ("error sending request", ErrorKind::NetworkUnreachable),
+    // Lagging or unhealthy node
+    ("node is behind", ErrorKind::NodeBehind),
+    ("node is unhealthy", ErrorKind::NodeBehind),
+    ("nodeunhealthy", ErrorKind::NodeBehind),
+    ("-32005", ErrorKind::NodeBehind),
+    ("minimum context slot has not been reached", ErrorKind::NodeBehind),
+    ("blockhash not found", ErrorKind::NodeBehind),
+    ("failed to get recent blockhash", ErrorKind::NodeBehind),
+];
+
+/// Kind of a wallet or RPC error message, `None` when it isn't one we can help with
+pub fn classify(message: &str) -> Option<ErrorKind> {
+    let message = message.to_ascii_lowercase();
+    PATTERNS
+        .iter()
+        .find(|(fragment, _)| message.contains(fragment))
+        .map(|(_, kind)| *kind)
+}
+
+/// Delay before the `attempt`th automatic retry (0-based)
+pub fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY.saturating_mul(1 << attempt.min(5)).min(RETRY_MAX_DELAY)
+}
+
+/// Recovery card shown in a flow
+#[derive(Debug, Clone)]
+pub struct RecoveryCard {
+    pub flow: RecoveryFlow,
+    pub kind: ErrorKind,
+    /// The original message, behind "Details"
+    pub message: String,
+    /// Retries run for this card so far
+    pub retries: u32,
+    /// Last action taken and when, until the flow succeeds or fails again
+    pub pending: Option<(RecoveryAction, Instant)>,
+    /// Retry scheduled for this time
+    pub retry_at: Option<Instant>,
+}
+
+impl RecoveryCard {
+    pub fn new(flow: RecoveryFlow, kind: ErrorKind, message: String) -> Self {
+        Self { flow, kind, message, retries: 0, pending: None, retry_at: None }
+    }
+}
+
+/// How often an action was tried and how often the flow succeeded after it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionOutcome {
+    pub tried: u32,
+    pub resolved: u32,
+}
+
+impl ActionOutcome {
+    /// Resolution rate with one imagined success and failure, so an action
+    /// tried once isn't ranked as a certainty
+    fn score(&self) -> f64 {
+        (self.resolved as f64 + 1.0) / (self.tried as f64 + 2.0)
+    }
+}
+
+/// Outcomes of every action per error kind
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryStats {
+    outcomes: BTreeMap<ErrorKind, BTreeMap<RecoveryAction, ActionOutcome>>,
+}
+
+impl RecoveryStats {
+    pub fn outcome(&self, kind: ErrorKind, action: RecoveryAction) -> ActionOutcome {
+        self.outcomes.get(&kind).and_then(|actions| actions.get(&action)).copied().unwrap_or_default()
+    }
+
+    /// Count an action as tried; `resolved` when the flow succeeded after it
+    pub fn record(&mut self, kind: ErrorKind, action: RecoveryAction, resolved: bool) {
+        let outcome = self.outcomes.entry(kind).or_default().entry(action).or_default();
+        outcome.tried += 1;
+        if resolved {
+            outcome.resolved += 1;
+        }
+    }
+
+    /// The actions for `kind`, best resolution rate first
+    ///
+    /// Ties keep the order of [`ErrorKind::actions`].
+    pub fn ranked(&self, kind: ErrorKind) -> Vec<RecoveryAction> {
+        let mut actions = kind.actions().to_vec();
+        actions.sort_by(|a, b| {
+            self.outcome(kind, *b)
+                .score()
+                .partial_cmp(&self.outcome(kind, *a).score())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        actions
+    }
+}
+
+/// Open recovery cards and the action statistics
+#[derive(Debug, Clone, Default)]
+pub struct RecoveryState {
+    pub cards: Vec<RecoveryCard>,
+    pub stats: RecoveryStats,
+    /// The card whose raw message is expanded
+    pub details_open: Option<RecoveryFlow>,
+    /// Endpoints "Switch RPC endpoint" cycles the wallet through
+    pub rpc_endpoints: Vec<String>,
+}
+
+impl RecoveryState {
+    pub fn card(&self, flow: RecoveryFlow) -> Option<&RecoveryCard> {
+        self.cards.iter().find(|card| card.flow == flow)
+    }
+
+    pub fn card_mut(&mut self, flow: RecoveryFlow) -> Option<&mut RecoveryCard> {
+        self.cards.iter_mut().find(|card| card.flow == flow)
+    }
+
+    /// Show a card for `flow`
+    ///
+    /// An action still pending on the previous card of the flow didn't help
+    /// and is recorded as such. The retry count carries over while the kind
+    /// stays the same, so repeated failures back off further.
+    pub fn raise(&mut self, flow: RecoveryFlow, kind: ErrorKind, message: String) {
+        let previous = self.cards.iter().position(|card| card.flow == flow).map(|i| self.cards.remove(i));
+        let mut card = RecoveryCard::new(flow, kind, message);
+        if let Some(previous) = previous {
+            if let Some((action, _)) = previous.pending {
+                self.stats.record(previous.kind, action, false);
+            }
+            if previous.kind == kind {
+                card.retries = previous.retries;
+            }
+        }
+        self.cards.push(card);
+    }
+
+    /// The flow succeeded: close its card, crediting the pending action
+    ///
+    /// Returns true when an action was credited, so the caller can save the
+    /// statistics.
+    pub fn resolve(&mut self, flow: RecoveryFlow, now: Instant) -> bool {
+        let Some(index) = self.cards.iter().position(|card| card.flow == flow) else {
+            return false;
+        };
+        let card = self.cards.remove(index);
+        if self.details_open == Some(flow) {
+            self.details_open = None;
+        }
+        match card.pending {
+            Some((action, at)) if now.saturating_duration_since(at) <= RESOLUTION_WINDOW => {
+                self.stats.record(card.kind, action, true);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Close a card without counting its pending action either way
+    pub fn dismiss(&mut self, flow: RecoveryFlow) {
+        self.cards.retain(|card| card.flow != flow);
+        if self.details_open == Some(flow) {
+            self.details_open = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Messages captured from the wallet and swap flows against public endpoints
+    const CAPTURED: &[(&str, Option<ErrorKind>)] = &[
+        (
+            "Failed to get balance: HTTP status client error (429 Too Many Requests) for url (https://api.mainnet-beta.solana.com/)",
+            Some(ErrorKind::RateLimited),
+        ),
+        (
+            "RPC response error -32429: Too many requests for a specific RPC call, contact your app developer or support@rpcpool.com.",
+            Some(ErrorKind::RateLimited),
+        ),
+        (
+            "Submit failed: failed to get recent blockhash: RPC response error -32005: Node is behind by 163 slots [NodeUnhealthy]",
+            Some(ErrorKind::NodeBehind),
+        ),
+        ("Swap failed: failed to get recent blockhash: Node is unhealthy", Some(ErrorKind::NodeBehind)),
+        (
+            "RPC response error -32016: Minimum context slot has not been reached",
+            Some(ErrorKind::NodeBehind),
+        ),
+        ("Transaction simulation failed: Blockhash not found", Some(ErrorKind::NodeBehind)),
+        (
+            "failed to get recent blockhash: error sending request for url (https://api.devnet.solana.com/): error trying to connect: dns error: failed to lookup address information: Name or service not known",
+            Some(ErrorKind::NetworkUnreachable),
+        ),
+        (
+            "Failed to get balance: error sending request for url (http://127.0.0.1:8899/): error trying to connect: tcp connect error: Connection refused (os error 111)",
+            Some(ErrorKind::NetworkUnreachable),
+        ),
+        ("Network error: operation timed out", Some(ErrorKind::NetworkUnreachable)),
+        (
+            "Transaction simulation failed: Transaction results in an account (1) with insufficient funds for rent",
+            Some(ErrorKind::InsufficientRent),
+        ),
+        (
+            "Transaction simulation failed: Attempt to debit an account but found no record of a prior credit.",
+            Some(ErrorKind::InsufficientRent),
+        ),
+        ("Program log: Error: insufficient lamports 1500000, need 2039280", Some(ErrorKind::InsufficientRent)),
+        ("Signing error: Wallet service not available", None),
+        ("Slippage tolerance exceeded", None),
+    ];
+
+    #[test]
+    fn test_classify_captured_errors() {
+        for (message, expected) in CAPTURED {
+            assert_eq!(classify(message), *expected, "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_captured_errors_get_actions() {
+        let stats = RecoveryStats::default();
+        let actions = |message: &str| stats.ranked(classify(message).unwrap());
+
+        assert_eq!(actions(CAPTURED[0].0)[0], RecoveryAction::RetryWithBackoff);
+        assert_eq!(actions(CAPTURED[2].0)[0], RecoveryAction::SwitchRpcEndpoint);
+        assert!(actions(CAPTURED[6].0).contains(&RecoveryAction::OpenNetworkSettings));
+        assert_eq!(actions(CAPTURED[9].0)[0], RecoveryAction::BuySol);
+    }
+
+    #[test]
+    fn test_ranking_follows_outcomes() {
+        let mut stats = RecoveryStats::default();
+        let kind = ErrorKind::RateLimited;
+        assert_eq!(stats.ranked(kind), vec![RecoveryAction::RetryWithBackoff, RecoveryAction::SwitchRpcEndpoint]);
+
+        // Retrying keeps failing, switching keeps working
+        for _ in 0..3 {
+            stats.record(kind, RecoveryAction::RetryWithBackoff, false);
+            stats.record(kind, RecoveryAction::SwitchRpcEndpoint, true);
+        }
+        assert_eq!(stats.ranked(kind), vec![RecoveryAction::SwitchRpcEndpoint, RecoveryAction::RetryWithBackoff]);
+        assert_eq!(stats.outcome(kind, RecoveryAction::SwitchRpcEndpoint), ActionOutcome { tried: 3, resolved: 3 });
+    }
+
+    #[test]
+    fn test_resolution_tracking() {
+        let start = Instant::now();
+        let mut recovery = RecoveryState::default();
+        let flow = RecoveryFlow::Wallet;
+
+        recovery.raise(flow, ErrorKind::NodeBehind, "Node is behind".to_string());
+        recovery.card_mut(flow).unwrap().pending = Some((RecoveryAction::RetryWithBackoff, start));
+        // Failed again: the retry didn't help, and the next one backs off further
+        recovery.card_mut(flow).unwrap().retries = 1;
+        recovery.raise(flow, ErrorKind::NodeBehind, "Node is behind".to_string());
+        assert_eq!(recovery.card(flow).unwrap().retries, 1);
+        assert_eq!(
+            recovery.stats.outcome(ErrorKind::NodeBehind, RecoveryAction::RetryWithBackoff),
+            ActionOutcome { tried: 1, resolved: 0 }
+        );
+
+        recovery.card_mut(flow).unwrap().pending = Some((RecoveryAction::SwitchRpcEndpoint, start));
+        assert!(recovery.resolve(flow, start + Duration::from_secs(5)));
+        assert!(recovery.card(flow).is_none());
+        assert_eq!(
+            recovery.stats.outcome(ErrorKind::NodeBehind, RecoveryAction::SwitchRpcEndpoint),
+            ActionOutcome { tried: 1, resolved: 1 }
+        );
+
+        // A success long after the action isn't credited to it
+        recovery.raise(flow, ErrorKind::RateLimited, "429".to_string());
+        recovery.card_mut(flow).unwrap().pending = Some((RecoveryAction::RetryWithBackoff, start));
+        assert!(!recovery.resolve(flow, start + RESOLUTION_WINDOW + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        assert_eq!(retry_delay(0), Duration::from_secs(2));
+        assert_eq!(retry_delay(2), Duration::from_secs(8));
+        assert_eq!(retry_delay(10), RETRY_MAX_DELAY);
+    }
+}
@@ -0,0 +1,667 @@
+//! # App-wide Search
+//!
+//! Data providers behind the search palette (Ctrl+F). Each domain implements
+//! [`SearchProvider`] over what the app already holds in memory:
+//!
+//! - **Tokens**: token list and every symbol in the price store
+//! - **Transactions**: loaded wallet history and swap history pages
+//! - **Messages**: conversations received since login
+//! - **Settings**: sections of the Settings screen, matched by keyword
+//!
+//! The wallet history only covers the latest page, so transaction queries are
+//! also sent to the backend swap history search once they are long enough to
+//! be a useful signature fragment (see `handlers::search`); those results are
+//! merged in with [`merge_results`] when they arrive.
+//!
+//! Groups are ranked by their best match, so a query that is an exact token
+//! symbol puts tokens first while a signature fragment puts transactions first.
+
+use crate::app::state::{AppState, Screen};
+use std::cmp::Reverse;
+
+/// Results shown per domain
+pub const MAX_RESULTS_PER_DOMAIN: usize = 5;
+
+/// Query length before the backend transaction lookup starts
+pub const REMOTE_MIN_QUERY_LEN: usize = 4;
+
+/// Search domains, in tie-break order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SearchDomain {
+    Tokens,
+    Transactions,
+    Messages,
+    Settings,
+}
+
+impl SearchDomain {
+    /// Group heading in the palette
+    pub fn title(&self) -> &'static str {
+        match self {
+            SearchDomain::Tokens => "Tokens",
+            SearchDomain::Transactions => "Transactions",
+            SearchDomain::Messages => "Messages",
+            SearchDomain::Settings => "Settings",
+        }
+    }
+}
+
+/// Sections of the Settings screen a result can jump to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsSection {
+    Theme,
+    Actions,
+    Account,
+    Security,
+    Webhooks,
+    ApiKeys,
+    Servers,
+    Connection,
+    Risk,
+    Notifications,
+    Shortcuts,
+    Language,
+    Privacy,
+}
+
+impl SettingsSection {
+    pub fn all() -> &'static [SettingsSection] {
+        &[
+            SettingsSection::Theme,
+            SettingsSection::Actions,
+            SettingsSection::Account,
+            SettingsSection::Security,
+            SettingsSection::Webhooks,
+            SettingsSection::ApiKeys,
+            SettingsSection::Servers,
+            SettingsSection::Connection,
+            SettingsSection::Risk,
+            SettingsSection::Notifications,
+            SettingsSection::Shortcuts,
+            SettingsSection::Language,
+            SettingsSection::Privacy,
+        ]
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            SettingsSection::Theme => "Theme Colors",
+            SettingsSection::Actions => "Save / Reset Settings",
+            SettingsSection::Account => "Account",
+            SettingsSection::Security => "Security",
+            SettingsSection::Webhooks => "Webhooks",
+            SettingsSection::ApiKeys => "API Keys",
+            SettingsSection::Servers => "Servers",
+            SettingsSection::Connection => "Connection",
+            SettingsSection::Risk => "Risk",
+            SettingsSection::Notifications => "Notifications",
+            SettingsSection::Shortcuts => "Keyboard Shortcuts",
+            SettingsSection::Language => "Language",
+            SettingsSection::Privacy => "Privacy",
+        }
+    }
+
+    /// Words that should find this section besides its title
+    fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            SettingsSection::Theme => &["theme", "colors", "colours", "appearance", "primary", "border", "gray"],
+            SettingsSection::Actions => &["save", "reset", "defaults", "apply"],
+            SettingsSection::Account => &["password", "email", "profile"],
+            SettingsSection::Security => &["signing", "session", "grant", "auto-sign", "audit"],
+            SettingsSection::Webhooks => &["webhook", "automation", "bot", "delivery", "notify"],
+            SettingsSection::ApiKeys => &["api key", "script", "automation", "access", "revoke"],
+            SettingsSection::Servers => &["server", "backend", "failover", "backup", "primary", "standby"],
+            SettingsSection::Connection => &["network", "mainnet", "devnet", "testnet", "rpc", "websocket", "slippage"],
+            SettingsSection::Risk => &["concentration", "exposure", "liquidity", "herfindahl", "warning", "limit"],
+            SettingsSection::Notifications => &["notice", "alert", "mute", "divergence", "banner", "toast"],
+            SettingsSection::Shortcuts => &["hotkey", "keybinding", "keys", "rebind", "fullscreen"],
+            SettingsSection::Language => &["locale", "translation", "idioma", "español", "spanish", "decimal"],
+            SettingsSection::Privacy => &["analytics", "telemetry", "usage", "statistics", "delete my data", "opt-in"],
+        }
+    }
+
+    /// Account, Security, Webhooks and API Keys are only rendered for a logged-in user
+    pub fn requires_auth(&self) -> bool {
+        matches!(
+            self,
+            SettingsSection::Account | SettingsSection::Security | SettingsSection::Webhooks | SettingsSection::ApiKeys
+        )
+    }
+}
+
+/// Where selecting a result goes, and what gets highlighted there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchTarget {
+    /// Row in the terminal price list
+    Token { symbol: String },
+    /// Transaction by signature
+    Transaction { signature: String },
+    /// Message by position in its conversation
+    Message { conversation_id: String, index: usize },
+    Setting(SettingsSection),
+}
+
+impl SearchTarget {
+    /// Screen that shows the target
+    pub fn screen(&self) -> Screen {
+        match self {
+            SearchTarget::Token { .. } => Screen::Terminal,
+            SearchTarget::Transaction { .. } => Screen::Transactions,
+            SearchTarget::Message { .. } => Screen::Messaging,
+            SearchTarget::Setting(_) => Screen::Settings,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub title: String,
+    pub detail: String,
+    /// Match quality from [`match_score`]
+    pub score: u32,
+    pub target: SearchTarget,
+}
+
+/// Results for one domain, best first
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchGroup {
+    pub domain: SearchDomain,
+    pub results: Vec<SearchResult>,
+}
+
+impl SearchGroup {
+    fn best_score(&self) -> u32 {
+        self.results.iter().map(|r| r.score).max().unwrap_or(0)
+    }
+}
+
+/// A searchable domain.
+pub trait SearchProvider {
+    fn domain(&self) -> SearchDomain;
+
+    /// Shortest query worth running; shorter queries skip this provider
+    fn min_query_len(&self) -> usize {
+        1
+    }
+
+    /// Matches for `query`, which is trimmed and lowercased. Order and count
+    /// don't matter; [`search`] sorts and truncates.
+    fn search(&self, state: &AppState, query: &str) -> Vec<SearchResult>;
+}
+
+/// Registered providers
+pub fn providers() -> [&'static dyn SearchProvider; 4] {
+    [&TokenSearch, &TransactionSearch, &MessageSearch, &SettingsSearch]
+}
+
+/// Score `text` against a lowercased query: exact match, prefix, word prefix,
+/// then substring. `None` if it doesn't match.
+pub fn match_score(text: &str, query: &str) -> Option<u32> {
+    if query.is_empty() {
+        return None;
+    }
+    let text = text.to_lowercase();
+    if text == query {
+        Some(100)
+    } else if text.starts_with(query) {
+        Some(80)
+    } else if text.split(|c: char| !c.is_alphanumeric()).any(|word| word.starts_with(query)) {
+        Some(60)
+    } else if text.contains(query) {
+        Some(40)
+    } else {
+        None
+    }
+}
+
+fn best_score<'a>(texts: impl IntoIterator<Item = &'a str>, query: &str) -> Option<u32> {
+    texts.into_iter().filter_map(|text| match_score(text, query)).max()
+}
+
+/// Run every provider and rank the groups
+pub fn search(state: &AppState, query: &str) -> Vec<SearchGroup> {
+    let query = query.trim().to_lowercase();
+    let length = query.chars().count();
+    let mut groups: Vec<SearchGroup> = providers()
+        .iter()
+        .filter(|provider| length > 0 && length >= provider.min_query_len())
+        .map(|provider| SearchGroup {
+            domain: provider.domain(),
+            results: provider.search(state, &query),
+        })
+        .collect();
+    for group in &mut groups {
+        sort_results(&mut group.results);
+    }
+    rank_groups(&mut groups);
+    groups
+}
+
+/// Add late results (the backend lookup) to a domain, skipping targets already listed
+pub fn merge_results(groups: &mut Vec<SearchGroup>, domain: SearchDomain, results: Vec<SearchResult>) {
+    let index = match groups.iter().position(|g| g.domain == domain) {
+        Some(index) => index,
+        None => {
+            groups.push(SearchGroup { domain, results: Vec::new() });
+            groups.len() - 1
+        }
+    };
+    let group = &mut groups[index];
+    for result in results {
+        if !group.results.iter().any(|r| r.target == result.target) {
+            group.results.push(result);
+        }
+    }
+    sort_results(&mut group.results);
+    rank_groups(groups);
+}
+
+/// Number of results across all groups
+pub fn result_count(groups: &[SearchGroup]) -> usize {
+    groups.iter().map(|g| g.results.len()).sum()
+}
+
+/// Result at a position in display order (groups in order, then their results)
+pub fn result_at(groups: &[SearchGroup], index: usize) -> Option<&SearchResult> {
+    groups.iter().flat_map(|g| g.results.iter()).nth(index)
+}
+
+fn sort_results(results: &mut Vec<SearchResult>) {
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.title.len().cmp(&b.title.len()))
+            .then(a.title.cmp(&b.title))
+    });
+    results.truncate(MAX_RESULTS_PER_DOMAIN);
+}
+
+fn rank_groups(groups: &mut Vec<SearchGroup>) {
+    groups.retain(|g| !g.results.is_empty());
+    groups.sort_by_key(|g| (Reverse(g.best_score()), g.domain));
+}
+
+/// Token list entries and priced symbols
+pub struct TokenSearch;
+
+impl SearchProvider for TokenSearch {
+    fn domain(&self) -> SearchDomain {
+        SearchDomain::Tokens
+    }
+
+    fn search(&self, state: &AppState, query: &str) -> Vec<SearchResult> {
+        let prices = state.terminal.prices.load();
+        let mut results: Vec<SearchResult> = state
+            .terminal
+            .swap
+            .token_list
+            .iter()
+            .filter_map(|token| {
+                let score = best_score([token.symbol.as_str(), token.name.as_str()], query)?;
+                Some(SearchResult {
+                    title: token.symbol.clone(),
+                    detail: token.name.clone(),
+                    score,
+                    target: SearchTarget::Token { symbol: token.symbol.clone() },
+                })
+            })
+            .collect();
+
+        // Streamed symbols that aren't in the token list
+        for price in prices.iter() {
+            if results.iter().any(|r| r.title == price.symbol) {
+                continue;
+            }
+            if let Some(score) = match_score(&price.symbol, query) {
+                results.push(SearchResult {
+                    title: price.symbol.clone(),
+                    detail: format!("${:.4}", price.price),
+                    score,
+                    target: SearchTarget::Token { symbol: price.symbol.clone() },
+                });
+            }
+        }
+        results
+    }
+}
+
+/// Wallet transactions and swap history, by signature fragment or memo
+pub struct TransactionSearch;
+
+impl SearchProvider for TransactionSearch {
+    fn domain(&self) -> SearchDomain {
+        SearchDomain::Transactions
+    }
+
+    /// Two characters of base58 match nearly every signature
+    fn min_query_len(&self) -> usize {
+        3
+    }
+
+    fn search(&self, state: &AppState, query: &str) -> Vec<SearchResult> {
+        let mut results: Vec<SearchResult> = state
+            .transactions
+            .iter()
+            .filter_map(|tx| {
+                let score = best_score([tx.signature.as_str(), tx.memo.as_deref().unwrap_or("")], query)?;
+                Some(SearchResult {
+                    title: shared::format_address(&tx.signature, 8, 8),
+                    detail: format!("{} · {}", tx.tx_type, tx.status),
+                    score,
+                    target: SearchTarget::Transaction { signature: tx.signature.clone() },
+                })
+            })
+            .collect();
+
+        for swap in &state.terminal.swap.swap_history {
+            if results.iter().any(|r| matches!(&r.target, SearchTarget::Transaction { signature } if *signature == swap.signature)) {
+                continue;
+            }
+            if let Some(score) = match_score(&swap.signature, query) {
+                results.push(SearchResult {
+                    title: shared::format_address(&swap.signature, 8, 8),
+                    detail: format!("Swap {} → {} · {}", swap.input_symbol, swap.output_symbol, swap.status),
+                    score,
+                    target: SearchTarget::Transaction { signature: swap.signature.clone() },
+                });
+            }
+        }
+        results
+    }
+}
+
+/// Messages in conversations received this session
+pub struct MessageSearch;
+
+impl SearchProvider for MessageSearch {
+    fn domain(&self) -> SearchDomain {
+        SearchDomain::Messages
+    }
+
+    fn min_query_len(&self) -> usize {
+        2
+    }
+
+    fn search(&self, state: &AppState, query: &str) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+        for (conversation_id, messages) in &state.messaging.messages {
+            let peer = conversation_peer(state, conversation_id)
+                .and_then(|id| state.messaging.friends.iter().find(|f| f.user_id == id))
+                .map(|f| f.username.as_str());
+            for (index, message) in messages.iter().enumerate() {
+                let Some(score) = match_score(&message.text, query) else {
+                    continue;
+                };
+                let detail = match peer {
+                    Some(peer) if peer != message.author => format!("{} · with {}", message.author, peer),
+                    _ => message.author.clone(),
+                };
+                results.push(SearchResult {
+                    title: snippet(&message.text, query, 60),
+                    detail,
+                    score,
+                    target: SearchTarget::Message { conversation_id: conversation_id.clone(), index },
+                });
+            }
+        }
+        results
+    }
+}
+
+/// Sections of the Settings screen
+pub struct SettingsSearch;
+
+impl SearchProvider for SettingsSearch {
+    fn domain(&self) -> SearchDomain {
+        SearchDomain::Settings
+    }
+
+    fn search(&self, state: &AppState, query: &str) -> Vec<SearchResult> {
+        SettingsSection::all()
+            .iter()
+            .filter(|section| !section.requires_auth() || state.is_authenticated())
+            .filter_map(|section| {
+                let score = best_score(
+                    std::iter::once(section.title()).chain(section.keywords().iter().copied()),
+                    query,
+                )?;
+                Some(SearchResult {
+                    title: section.title().to_string(),
+                    detail: "Settings".to_string(),
+                    score,
+                    target: SearchTarget::Setting(*section),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The other participant of a `min:max` conversation ID
+pub fn conversation_peer(state: &AppState, conversation_id: &str) -> Option<i64> {
+    let me = state.current_user.as_ref()?.id;
+    let (a, b) = conversation_id.split_once(':')?;
+    let (a, b) = (a.parse::<i64>().ok()?, b.parse::<i64>().ok()?);
+    if a == me {
+        Some(b)
+    } else if b == me {
+        Some(a)
+    } else {
+        None
+    }
+}
+
+/// Up to `max` characters of `text` around the first match of `query`
+fn snippet(text: &str, query: &str, max: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= max {
+        return text.to_string();
+    }
+    // Char offset of the match; lowercasing can change byte lengths
+    let lower: Vec<char> = text.to_lowercase().chars().collect();
+    let needle: Vec<char> = query.chars().collect();
+    let at = lower
+        .windows(needle.len().max(1))
+        .position(|w| w == needle.as_slice())
+        .unwrap_or(0)
+        .min(chars.len());
+    let start = at.saturating_sub(max / 3).min(chars.len() - max);
+    let body: String = chars[start..start + max].iter().collect();
+    format!(
+        "{}{}{}",
+        if start > 0 { "…" } else { "" },
+        body,
+        if start + max < chars.len() { "…" } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::{CurrentUser, PriceData, SwapHistoryItem, TokenInfo, TransactionItem};
+    use crate::app::App;
+    use shared::dto::messaging::{Friend, Message};
+
+    fn test_state() -> AppState {
+        App::new().state.read().clone()
+    }
+
+    fn token(symbol: &str, name: &str) -> TokenInfo {
+        TokenInfo {
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            mint: format!("{}Mint", symbol),
+            decimals: 6,
+            price: 0.0,
+            balance: 0.0,
+            change_24h: 0.0,
+            is_favorite: false,
+            verified: true,
+            daily_volume: None,
+        }
+    }
+
+    fn titles(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_match_score_order() {
+        assert_eq!(match_score("SOL", "sol"), Some(100));
+        assert_eq!(match_score("Solana", "sol"), Some(80));
+        assert_eq!(match_score("Wrapped Solana", "sol"), Some(60));
+        assert_eq!(match_score("mSOL", "sol"), Some(40));
+        assert_eq!(match_score("USDC", "sol"), None);
+        assert_eq!(match_score("USDC", ""), None);
+    }
+
+    #[test]
+    fn test_token_provider() {
+        let mut state = test_state();
+        state.terminal.swap.token_list = vec![token("SOL", "Solana"), token("MSOL", "Marinade staked SOL"), token("USDC", "USD Coin")].into();
+        state.terminal.prices.replace(vec![PriceData {
+            symbol: "SOLX".to_string(),
+            price: 2.5,
+            change_24h: 0.0,
+            previous_price: None,
+            source: None,
+            confidence: None,
+            publish_time: None,
+            sources: Default::default(),
+            divergent: false,
+            divergence_pct: None,
+        }]);
+
+        let results = TokenSearch.search(&state, "sol");
+        let mut found = titles(&results);
+        found.sort();
+        assert_eq!(found, vec!["MSOL", "SOL", "SOLX"]);
+        let sol = results.iter().find(|r| r.title == "SOL").unwrap();
+        assert_eq!(sol.score, 100);
+        assert_eq!(sol.target, SearchTarget::Token { symbol: "SOL".to_string() });
+    }
+
+    #[test]
+    fn test_transaction_provider() {
+        let mut state = test_state();
+        state.transactions = vec![TransactionItem {
+            signature: "5VERv8NMvzbJMEkV8xnrLk".to_string(),
+            timestamp: 0,
+            tx_type: "Transfer".to_string(),
+            status: "confirmed".to_string(),
+            amount: String::new(),
+            memo: Some("rent payment".to_string()),
+            wallet: None,
+            latency: None,
+        }]
+        .into();
+        state.terminal.swap.swap_history = vec![SwapHistoryItem {
+            signature: "3abcMzbJMEk".to_string(),
+            timestamp: 0,
+            input_symbol: "SOL".to_string(),
+            output_symbol: "USDC".to_string(),
+            input_amount: 1.0,
+            output_amount: 150.0,
+            status: "confirmed".to_string(),
+        }]
+        .into();
+
+        // Signature fragments match case-insensitively in both sources
+        let results = TransactionSearch.search(&state, "zbjmek");
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().any(|r| r.detail.starts_with("Swap SOL → USDC")));
+
+        // Memos are searchable too
+        let results = TransactionSearch.search(&state, "rent");
+        assert_eq!(results[0].target, SearchTarget::Transaction { signature: "5VERv8NMvzbJMEkV8xnrLk".to_string() });
+
+        // Too short to be worth matching signatures
+        assert!(search(&state, "zb").iter().all(|g| g.domain != SearchDomain::Transactions));
+    }
+
+    #[test]
+    fn test_message_provider() {
+        let mut state = test_state();
+        state.current_user = Some(CurrentUser { id: 1, username: "me".to_string() });
+        state.messaging.friends = vec![Friend {
+            id: 1,
+            user_id: 7,
+            username: "alice".to_string(),
+            friendship_id: 1,
+            unread_count: 0,
+            last_message_at: None,
+            last_message_preview: None,
+        }];
+        state.messaging.messages.insert(
+            "1:7".to_string(),
+            vec![
+                Message::new("gm".to_string(), "me".to_string(), 1),
+                Message::new("Swapped into BONK at the lows".to_string(), "me".to_string(), 1),
+            ],
+        );
+
+        let results = MessageSearch.search(&state, "bonk");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].detail, "me · with alice");
+        assert_eq!(results[0].target, SearchTarget::Message { conversation_id: "1:7".to_string(), index: 1 });
+        assert_eq!(conversation_peer(&state, "1:7"), Some(7));
+        assert_eq!(conversation_peer(&state, "3:7"), None);
+    }
+
+    #[test]
+    fn test_settings_provider_hides_account_when_logged_out() {
+        let state = test_state();
+        assert!(!state.is_authenticated());
+
+        assert!(SettingsSearch.search(&state, "password").is_empty());
+        let results = SettingsSearch.search(&state, "colors");
+        assert_eq!(results[0].target, SearchTarget::Setting(SettingsSection::Theme));
+    }
+
+    #[test]
+    fn test_groups_ranked_by_best_match() {
+        let mut state = test_state();
+        state.terminal.swap.token_list = vec![token("RESET", "Reset Protocol Token"), token("RESETX", "Resets")].into();
+
+        // Exact token symbol and exact settings keyword tie; domain order breaks it
+        let groups = search(&state, "  Reset ");
+        assert_eq!(groups[0].domain, SearchDomain::Tokens);
+        assert_eq!(titles(&groups[0].results), vec!["RESET", "RESETX"]);
+        assert_eq!(groups[1].domain, SearchDomain::Settings);
+        assert_eq!(result_count(&groups), 3);
+        assert_eq!(result_at(&groups, 2).map(|r| r.target.clone()), Some(SearchTarget::Setting(SettingsSection::Actions)));
+        assert!(search(&state, "   ").is_empty());
+    }
+
+    #[test]
+    fn test_merge_results_dedupes_and_reranks() {
+        let mut groups = vec![SearchGroup {
+            domain: SearchDomain::Settings,
+            results: vec![SearchResult {
+                title: "Security".to_string(),
+                detail: String::new(),
+                score: 40,
+                target: SearchTarget::Setting(SettingsSection::Security),
+            }],
+        }];
+        let remote = |signature: &str, score| SearchResult {
+            title: signature.to_string(),
+            detail: String::new(),
+            score,
+            target: SearchTarget::Transaction { signature: signature.to_string() },
+        };
+
+        merge_results(&mut groups, SearchDomain::Transactions, vec![remote("abc", 80), remote("abc", 80)]);
+        assert_eq!(groups[0].domain, SearchDomain::Transactions);
+        assert_eq!(groups[0].results.len(), 1);
+        assert_eq!(groups[1].domain, SearchDomain::Settings);
+    }
+
+    #[test]
+    fn test_snippet_centers_on_match() {
+        let text = format!("{} needle {}", "a".repeat(80), "b".repeat(80));
+        let cut = snippet(&text, "needle", 30);
+        assert!(cut.contains("needle"));
+        assert!(cut.starts_with('…') && cut.ends_with('…'));
+        assert_eq!(snippet("short", "x", 30), "short");
+    }
+}
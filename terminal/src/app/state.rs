@@ -3,10 +3,13 @@
 //! All state-related types for the application, including screens, authentication,
 //! terminal state, wallet state, and transaction state.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use super::cow_arc::CowArc;
+
 /// Application screens
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum Screen {
     /// Landing screen (splash/welcome)
     Landing,
@@ -20,6 +23,8 @@ pub enum Screen {
     JupiterFeed,
     /// Wallet management screen
     Wallet,
+    /// Portfolio overview with holdings, allocation, and P&L
+    Portfolio,
     /// Transaction history screen
     Transactions,
     /// SPL Token management screen
@@ -48,6 +53,7 @@ impl Screen {
             Screen::PythFeed,
             Screen::JupiterFeed,
             Screen::Wallet,
+            Screen::Portfolio,
             Screen::Transactions,
             Screen::Tokens,
             Screen::Messaging,
@@ -68,6 +74,7 @@ impl Screen {
             Screen::PythFeed => "Pyth Network Feed",
             Screen::JupiterFeed => "Jupiter WebSocket Feed",
             Screen::Wallet => "Wallet Management",
+            Screen::Portfolio => "Portfolio",
             Screen::Transactions => "Transaction History",
             Screen::Tokens => "SPL Tokens",
             Screen::Messaging => "Messaging",
@@ -101,20 +108,28 @@ pub enum AuthState {
     },
 }
 
-/// Active field in login form
+/// Active field in login form, in Tab order
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LoginField {
     Username,
     Password,
+    /// Login button
+    Submit,
+    /// Switch to signup button
+    SwitchForm,
 }
 
-/// Active field in signup form
+/// Active field in signup form, in Tab order
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SignupField {
     Username,
     Email,
     Password,
     ConfirmPassword,
+    /// Signup button
+    Submit,
+    /// Switch to login button
+    SwitchForm,
 }
 
 /// Active tab in terminal screen
@@ -163,6 +178,24 @@ pub enum SwapField {
     TokenSearch,
 }
 
+/// Focusable field of the swap panel, in Tab order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapField {
+    /// "From" token button
+    InputToken,
+    /// "To" token button
+    OutputToken,
+    Amount,
+    /// Max amount button
+    Max,
+    /// Advanced options header
+    Advanced,
+    /// Memo, while the advanced options are open
+    Memo,
+    /// Execute button, while it is enabled
+    Execute,
+}
+
 /// Target for token picker popup
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TokenPickerTarget {
@@ -178,10 +211,16 @@ pub struct TokenInfo {
     pub symbol: String,
     pub name: String,
     pub mint: String,
+    /// Mint decimals, used to turn raw amounts into UI amounts
+    pub decimals: u8,
     pub price: f64,
     pub balance: f64,
     pub change_24h: f64,
     pub is_favorite: bool,
+    /// Reviewed by Jupiter; unverified tokens may impersonate real ones
+    pub verified: bool,
+    /// Traded volume over the last 24h in USD, when known
+    pub daily_volume: Option<f64>,
 }
 
 /// Swap history item
@@ -196,6 +235,21 @@ pub struct SwapHistoryItem {
     pub status: String,
 }
 
+/// Swap history filters, as edited on the History tab
+///
+/// Dates are `YYYY-MM-DD` in UTC; both ends are inclusive and an empty
+/// string leaves that end open. Mints and status of `None` mean "any".
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SwapHistoryFilters {
+    pub from_date: String,
+    pub to_date: String,
+    pub input_mint: Option<String>,
+    pub output_mint: Option<String>,
+    pub status: Option<String>,
+    /// Zero-based page index
+    pub page: usize,
+}
+
 /// Comprehensive swap state
 #[derive(Debug, Clone)]
 pub struct SwapState {
@@ -224,15 +278,66 @@ pub struct SwapState {
     /// Token picker is for input or output
     pub token_picker_for: TokenPickerTarget,
     /// Available tokens for selection
-    pub token_list: Vec<TokenInfo>,
+    pub token_list: CowArc<Vec<TokenInfo>>,
     /// Filter text for token search
     pub token_filter: String,
     /// Selected index in token picker
     pub selected_token_index: usize,
-    /// Swap transaction history
-    pub swap_history: Vec<SwapHistoryItem>,
-    /// Last quote fetch timestamp
-    pub last_quote_fetch: std::time::Instant,
+    /// List tokens Jupiter hasn't verified in the picker
+    pub show_unverified_tokens: bool,
+    /// Swap transaction history (the current page)
+    pub swap_history: CowArc<Vec<SwapHistoryItem>>,
+    /// Filters and page for the history tab
+    pub history_filters: SwapHistoryFilters,
+    /// Swaps matching the filters across all pages
+    pub history_total: usize,
+    /// History page is being fetched
+    pub history_loading: bool,
+    /// Last history fetch error
+    pub history_error: Option<String>,
+    /// Swaps written by the running CSV export, `None` when not exporting
+    pub history_export: Option<u64>,
+    /// Bumped for every quote request; results tagged with an older
+    /// generation are stale and dropped
+    pub quote_generation: u64,
+    /// Quote request waiting out the debounce delay
+    pub quote_debounce: Option<tokio::task::AbortHandle>,
+    /// Quote stream the backend was asked for; `None` when not streaming
+    pub quote_stream: Option<shared::dto::market::QuoteSubscription>,
+    /// When the displayed quote arrived, for its freshness indicator
+    pub quote_updated_at: Option<std::time::Instant>,
+    /// Optional on-chain memo attached to the swap (advanced option)
+    pub memo: String,
+    /// Swap transaction is being built and simulated
+    pub preparing: bool,
+    /// Simulated swap waiting in the confirmation dialog
+    pub confirmation: Option<SwapConfirmation>,
+    /// Swaps queued for review (e.g. by the rebalancer), oldest first
+    pub queue: Vec<QueuedSwap>,
+    /// Move keyboard focus to this field when the panel next draws
+    pub focus_field: Option<SwapField>,
+}
+
+/// Swap waiting on the swap panel until it is reviewed and executed by hand
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedSwap {
+    pub input_symbol: String,
+    pub output_symbol: String,
+    /// Amount of the input token
+    pub amount: f64,
+    /// USD value of `amount` when it was queued
+    pub usd_value: f64,
+}
+
+impl From<&RebalanceSwap> for QueuedSwap {
+    fn from(swap: &RebalanceSwap) -> Self {
+        Self {
+            input_symbol: swap.input_symbol.clone(),
+            output_symbol: swap.output_symbol.clone(),
+            amount: swap.input_amount,
+            usd_value: swap.usd_value,
+        }
+    }
 }
 
 /// WebSocket connection status details
@@ -240,16 +345,21 @@ pub struct SwapState {
 pub struct WebSocketStatus {
     /// Connection state
     pub state: WebSocketState,
-    /// Number of connection attempts
+    /// Failed connection attempts since the last successful connect
     pub connection_attempts: u64,
+    /// When the next reconnect attempt is due, while reconnecting
+    pub next_retry: Option<std::time::Instant>,
     /// Last error message (if any)
     pub last_error: Option<String>,
     /// Last successful connection time
     pub last_connected: Option<std::time::Instant>,
     /// Total messages received
     pub messages_received: u64,
-    /// Last message time
+    /// Last price update time
     pub last_message: Option<std::time::Instant>,
+    /// Last frame of any kind, pings included; the stream reconnects when
+    /// this gets older than [`crate::services::api::websocket::STALL_TIMEOUT`]
+    pub last_message_at: Option<std::time::Instant>,
 }
 
 /// WebSocket connection state
@@ -263,19 +373,44 @@ pub enum WebSocketState {
     Connected,
     /// Connection failed, retrying
     Reconnecting,
-    /// Permanently disabled (max retries reached)
+    /// Given up after too many failed attempts, until the user reconnects
     Disabled,
 }
 
+/// Result of the periodic backend health poll
+#[derive(Debug, Clone, Default)]
+pub struct BackendHealthState {
+    /// Last health report received
+    pub report: Option<shared::dto::system::HealthResponse>,
+    /// Error from the last poll (backend unreachable)
+    pub error: Option<String>,
+    /// When the last poll finished
+    pub last_checked: Option<std::time::Instant>,
+    /// When the user was last told a request timed out
+    pub last_timeout_notice: Option<std::time::Instant>,
+}
+
+impl BackendHealthState {
+    /// Feature gates for the last report; nothing is gated before the first one
+    pub fn gates(&self) -> crate::app::FeatureGates {
+        self.report
+            .as_ref()
+            .map(crate::app::FeatureGates::from_report)
+            .unwrap_or_default()
+    }
+}
+
 impl Default for WebSocketStatus {
     fn default() -> Self {
         Self {
             state: WebSocketState::Disconnected,
             connection_attempts: 0,
+            next_retry: None,
             last_error: None,
             last_connected: None,
             messages_received: 0,
             last_message: None,
+            last_message_at: None,
         }
     }
 }
@@ -290,43 +425,114 @@ impl Default for SwapState {
             output_token: "USDC".to_string(),
             output_mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
             amount: String::new(),
-            slippage_bps: 50, // 0.5% default
+            slippage_bps: crate::core::config::DEFAULT_SLIPPAGE_BPS,
             quote: None,
             quote_loading: false,
             show_token_picker: false,
             token_picker_for: TokenPickerTarget::Input,
-            token_list: Vec::new(),
+            token_list: CowArc::default(),
             token_filter: String::new(),
             selected_token_index: 0,
-            swap_history: Vec::new(),
-            last_quote_fetch: std::time::Instant::now(),
+            show_unverified_tokens: false,
+            swap_history: CowArc::default(),
+            history_filters: SwapHistoryFilters::default(),
+            history_total: 0,
+            history_loading: false,
+            history_error: None,
+            history_export: None,
+            quote_generation: 0,
+            quote_debounce: None,
+            quote_stream: None,
+            quote_updated_at: None,
+            memo: String::new(),
+            preparing: false,
+            confirmation: None,
+            queue: Vec::new(),
+            focus_field: None,
         }
     }
 }
 
+impl SwapState {
+    /// Cancel the pending quote request and invalidate any in flight.
+    /// Returns the generation for the next request.
+    pub fn next_quote_generation(&mut self) -> u64 {
+        if let Some(pending) = self.quote_debounce.take() {
+            pending.abort();
+        }
+        self.quote_generation += 1;
+        self.quote_generation
+    }
+
+    /// Drop the displayed quote and every request for it, e.g. when the pair changes
+    pub fn reset_quote(&mut self) {
+        self.next_quote_generation();
+        self.quote = None;
+        self.quote_loading = false;
+        self.quote_updated_at = None;
+    }
+
+    /// Close the token picker and give focus back to the button that opened it
+    pub fn close_token_picker(&mut self) {
+        self.show_token_picker = false;
+        self.focus_field = Some(match self.token_picker_for {
+            TokenPickerTarget::Input => SwapField::InputToken,
+            TokenPickerTarget::Output => SwapField::OutputToken,
+        });
+    }
+}
+
 /// Terminal screen state (trading view)
 #[derive(Debug, Clone)]
 pub struct TerminalState {
     /// Comprehensive swap state
     pub swap: SwapState,
-    /// Price data for all tokens
-    pub prices: Vec<PriceData>,
+    /// Price data for all tokens (lock-free; shared with the WebSocket and REST writers)
+    pub prices: Arc<crate::app::PriceStore>,
     /// Chart data (OHLC candles) - real data from API
-    pub chart_data: Vec<shared::dto::OHLC>,
-    /// SOL candles for main chart
-    pub sol_candles: Vec<shared::dto::OHLC>,
+    pub chart_data: CowArc<Vec<shared::dto::OHLC>>,
+    /// Symbol the charts show
+    pub chart_symbol: String,
+    /// Candles of `chart_symbol` for the main chart
+    pub sol_candles: CowArc<Vec<shared::dto::OHLC>>,
     /// Selected chart timeframe
     pub chart_timeframe: shared::dto::market::Timeframe,
     /// Chart loading state
     pub chart_loading: bool,
+    /// Whether the last of `sol_candles` is still forming (streamed, its
+    /// period not yet closed)
+    pub sol_candle_forming: bool,
+    /// Bumped whenever `sol_candles` or `sol_candle_forming` change; the
+    /// chart rebuilds its cached geometry only when it moves
+    pub chart_data_version: u64,
+    /// Indicator values for `sol_candles`
+    pub chart_indicators: crate::ui::chart::indicators::IndicatorSeries,
+    /// Levels and trendlines drawn on charts, with their alerts
+    pub chart_annotations: crate::ui::chart::annotations::ChartAnnotations,
+    /// Drawing tool picked in the chart toolbar
+    pub chart_tool: crate::ui::chart::annotations::DrawingTool,
     /// Active chart for display (legacy, may be removed)
     pub active_chart: Option<crate::ui::chart::ChartData>,
     /// Last price update timestamp
     pub last_price_update: std::time::Instant,
+    /// When each symbol's price last moved, driving the per-cell flash
+    pub price_flashes: std::collections::HashMap<String, std::time::Instant>,
+    /// Price store version up to which moves are in `price_flashes`
+    pub price_flash_version: u64,
     /// Flag to prevent concurrent price fetches (prevents task pileup)
     pub fetching_prices: bool,
+    /// Symbols the backend price stream tracks (empty until fetched)
+    pub streamed_symbols: Vec<String>,
     /// Swap panel visibility (collapsible)
     pub swap_panel_open: bool,
+    /// SOL/USDC depth ladder from route quotes
+    pub depth: Option<shared::dto::market::DepthResponse>,
+    /// Depth fetch in flight
+    pub depth_loading: bool,
+    /// Last depth fetch error
+    pub depth_error: Option<String>,
+    /// When the depth ladder was last requested
+    pub last_depth_fetch: Option<std::time::Instant>,
 }
 
 /// Swap quote information
@@ -338,6 +544,51 @@ pub struct SwapQuote {
     pub estimated_fee: f64,
 }
 
+/// Swap built and simulated, waiting for the user to confirm it
+#[derive(Debug, Clone)]
+pub struct SwapConfirmation {
+    /// Unsigned transaction with the memo attached; signed only on confirm
+    pub transaction: crate::services::wallet::WalletTransaction,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub input_symbol: String,
+    pub output_symbol: String,
+    /// Input amount as entered
+    pub ui_amount: f64,
+    /// Input amount in base units
+    pub amount: u64,
+    pub quote: SwapQuote,
+    pub slippage_bps: u16,
+    pub memo: String,
+    /// Address of the wallet that will sign
+    pub wallet: String,
+    /// Simulation outcome; `Err` when the simulation couldn't be run at all
+    pub simulation: Result<shared::dto::simulation::SimulateSwapResponse, String>,
+    /// Pipeline timestamps since the Swap click
+    pub timer: crate::app::latency::SwapTimer,
+}
+
+impl SwapConfirmation {
+    /// Only a swap that simulated successfully may be signed
+    pub fn can_execute(&self) -> bool {
+        matches!(&self.simulation, Ok(simulation) if simulation.success)
+    }
+
+    /// Why the swap can't be executed
+    pub fn error(&self) -> Option<&str> {
+        match &self.simulation {
+            Ok(simulation) if simulation.success => None,
+            Ok(simulation) => Some(simulation.error.as_deref().unwrap_or("Simulation failed")),
+            Err(err) => Some(err),
+        }
+    }
+
+    /// Last program log lines of the simulation
+    pub fn log_tail(&self) -> &[String] {
+        self.simulation.as_ref().map(|simulation| simulation.log_tail.as_slice()).unwrap_or_default()
+    }
+}
+
 /// Price data for a single token
 #[derive(Debug, Clone)]
 pub struct PriceData {
@@ -348,6 +599,58 @@ pub struct PriceData {
     pub previous_price: Option<f64>,
     /// Price source (e.g., "pyth", "jupiter")
     pub source: Option<String>,
+    /// Oracle confidence interval in USD (`price ± confidence`)
+    pub confidence: Option<f64>,
+    /// Unix timestamp (seconds) the oracle published the price at
+    pub publish_time: Option<i64>,
+    /// Price each source reported, when the backend compared several
+    pub sources: BTreeMap<String, f64>,
+    /// Whether the sources disagree by more than the backend's threshold
+    pub divergent: bool,
+    /// Spread between the sources as a percentage of their midpoint
+    pub divergence_pct: Option<f64>,
+}
+
+impl PriceData {
+    /// Seconds since the oracle published the price, `None` without a publish time
+    pub fn age_secs(&self, now: i64) -> Option<i64> {
+        self.publish_time.map(|published| (now - published).max(0))
+    }
+
+    /// Whether the oracle price is older than `stale_after_secs`
+    ///
+    /// Prices without a publish time (aggregator quotes) are never stale here.
+    pub fn is_stale(&self, now: i64, stale_after_secs: u64) -> bool {
+        self.age_secs(now).is_some_and(|age| age as u64 > stale_after_secs)
+    }
+
+    /// One line per source ("pyth: $150.0000"), for the divergence tooltip
+    pub fn sources_tooltip(&self) -> String {
+        let lines: Vec<String> = self
+            .sources
+            .iter()
+            .map(|(source, price)| format!("{}: ${:.4}", source, price))
+            .collect();
+        match self.divergence_pct {
+            Some(pct) => format!("Price sources disagree by {:.2}%\n{}", pct, lines.join("\n")),
+            None => format!("Price sources disagree\n{}", lines.join("\n")),
+        }
+    }
+
+    /// Spread between `sources` as a percentage of their midpoint, the way the
+    /// backend measures it; `None` with fewer than two sources
+    ///
+    /// REST prices only carry the per-source prices, so the terminal works the
+    /// percentage out itself.
+    pub fn spread_pct(sources: &BTreeMap<String, f64>) -> Option<f64> {
+        if sources.len() < 2 {
+            return None;
+        }
+        let low = sources.values().copied().fold(f64::INFINITY, f64::min);
+        let high = sources.values().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mid = (low + high) / 2.0;
+        (mid > 0.0).then(|| (high - low) / mid * 100.0)
+    }
 }
 
 /// Global application state
@@ -360,40 +663,129 @@ pub struct AppState {
     pub terminal: TerminalState,
     /// Wallet balances and info
     pub wallet: Option<WalletState>,
+    /// Label and chip color per wallet address
+    pub wallet_identities: crate::app::wallet_identity::WalletIdentities,
+    /// Recovery cards of the Wallet and Swap flows
+    pub recovery: crate::app::recovery::RecoveryState,
+    /// Label being edited on the Wallet screen
+    pub wallet_label_edit: Option<WalletLabelEdit>,
     /// Transaction history
-    pub transactions: Vec<TransactionItem>,
+    pub transactions: CowArc<Vec<TransactionItem>>,
+    /// Wallets the Transactions screen shows
+    pub transactions_wallet: crate::app::wallet_identity::WalletFilter,
     /// JWT token (once logged in)
     pub auth_token: Option<String>,
+    /// Refresh bookkeeping for `auth_token`
+    pub session: SessionState,
     /// Current user info (from JWT)
     pub current_user: Option<CurrentUser>,
-    /// API client
+    /// Network and endpoints the services were built from
+    pub config: crate::core::config::TerminalConfig,
+    /// HTTP client of the backend, for what [`crate::core::service::ApiService`]
+    /// doesn't cover: server failover, pooled connections and the endpoints
+    /// of single screens (messaging, webhooks, ...)
     pub api_client: Option<Arc<crate::services::api::ApiClient>>,
+    /// Backend the auth, market, balance and swap tasks go through:
+    /// `api_client`, the offline demo's [`crate::services::offline::OfflineApi`],
+    /// or a mock in tests
+    pub api: Option<Arc<dyn crate::core::service::ApiService>>,
+    /// Started with `--offline` / `TERMINAL_OFFLINE=1`: mock services, no backend
+    pub offline: bool,
     /// Wallet service for signing transactions
     pub wallet_service: Option<crate::services::wallet::WalletService>,
+    /// Signs swaps in place of `wallet_service` in the offline demo
+    pub offline_wallet: Option<Box<dyn crate::core::service::WalletService>>,
     /// Credentials for polling wallet status (username, password)
     pub polling_credentials: Option<(String, String)>,
     /// Pending notifications to display (level, message)
     pub pending_notifications: Vec<(String, String)>,
+    /// Backend system notices shown as banners until dismissed
+    pub system_notices: Vec<shared::SystemNotice>,
     /// WebSocket connection status for price stream
     pub websocket_connected: bool,
     /// WebSocket connection status details
     pub websocket_status: WebSocketStatus,
+    /// Running price stream task, aborted on logout
+    pub price_stream_task: Option<tokio::task::AbortHandle>,
+    /// Frames to send on the price stream's current connection; `None` while disconnected
+    pub price_stream_outbox: Option<async_channel::Sender<String>>,
+    /// Activity tracking for idle teardown
+    pub idle: crate::app::idle::IdleMonitor,
+    /// Announced maintenance window and how far into it we are
+    pub maintenance: crate::app::maintenance::MaintenanceState,
+    /// Backend health from the periodic `/api/health` poll
+    pub backend_health: BackendHealthState,
     /// Messaging state
     pub messaging: MessagingState,
     /// AI Chat state
     pub ai_chat: AIChatState,
     /// Settings state (theme configuration, etc.)
     pub settings: SettingsState,
+    /// Portfolio valuation derived from wallet balances and live prices
+    pub portfolio: PortfolioState,
+    /// Upcoming token unlocks (token detail timeline, portfolio warnings)
+    pub token_unlocks: TokenUnlockState,
+    /// Live Assets screen tab and the volatility/correlation analytics
+    pub live_assets: LiveAssetsState,
+    /// Accounts derived from the imported mnemonic seed
+    pub derived_accounts: DerivedAccountsState,
+    /// Trade import wizard and trade statistics (Transactions screen)
+    pub trade_import: TradeImportState,
+    /// Session signer grants, the auto-sign audit log and the audit trail (Settings > Security)
+    pub security: SecurityState,
+    /// Search palette (Ctrl+F) and the item its last result points at
+    pub search: SearchState,
+    /// Command palette (Ctrl+K)
+    pub palette: CommandPaletteState,
+    /// Share link options and the last created link (Live Chart, Portfolio)
+    pub share: ShareState,
+    /// Wallet activity webhooks and their delivery logs (Settings > Webhooks)
+    pub webhooks: WebhooksState,
+    /// API keys for scripted access (Settings > API Keys)
+    pub api_keys: ApiKeysState,
+    /// "Buy SOL" dialog (Wallet screen)
+    pub onramp: OnRampState,
+    /// Send panel and its confirmation dialog (Wallet screen)
+    pub send: SendState,
+    /// Receive panel (Wallet screen)
+    pub receive: ReceiveState,
+    /// Devnet airdrop button (Wallet screen)
+    pub airdrop: AirdropState,
+    /// Staking panel (Wallet screen)
+    pub staking: StakingState,
+    /// `xforce://` link waiting for confirmation
+    pub link_prompt: LinkPromptState,
+    /// Forced reloads in flight, keyed per [`crate::app::RefreshTarget`]
+    pub refresh_tasks: crate::app::preload::TaskSupervisor,
     /// Debug overlay visibility (toggled with Ctrl+D)
     pub debug_overlay_visible: bool,
     /// Flag to request immediate repaint (set when price updates arrive)
     pub needs_immediate_repaint: bool,
     /// Timestamp of last price update for flash effect tracking
     pub last_price_update_time: std::time::Instant,
+    /// Screen-scoped state of the main window (secondary windows keep theirs
+    /// in [`crate::app::WindowManager`])
+    pub root_view: WindowView,
+    /// Landing and auth screen branding, loaded once at startup
+    pub branding: crate::app::branding::Branding,
+}
+
+/// Screen-scoped state each window keeps for itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowView {
     /// Navigation bar: Selected token symbol (defaults to SOL)
-    pub nav_bar_selected_token: Option<String>,
+    pub selected_token: Option<String>,
     /// Navigation bar: Show token picker dropdown
-    pub nav_bar_show_token_picker: bool,
+    pub show_token_picker: bool,
+}
+
+impl Default for WindowView {
+    fn default() -> Self {
+        Self {
+            selected_token: Some("SOL".to_string()),
+            show_token_picker: false,
+        }
+    }
 }
 
 impl AppState {
@@ -402,9 +794,36 @@ impl AppState {
         self.auth_token.is_some()
     }
 
+    /// Unix seconds when the auth token expires, if logged in
+    pub fn session_expires_at(&self) -> Option<i64> {
+        self.auth_token.as_deref().and_then(crate::services::session::token_expiry)
+    }
+
+    /// Wallet swaps are signed with: the connected wallet, or the offline demo's
+    pub fn signer(&self) -> Option<&dyn crate::core::service::WalletService> {
+        match &self.offline_wallet {
+            Some(wallet) => Some(wallet.as_ref()),
+            None => self.wallet_service.as_ref().map(|wallet| wallet as &dyn crate::core::service::WalletService),
+        }
+    }
+
+    /// Bytes of the copy-on-write collections: what a render snapshot shares
+    /// with this state instead of copying (element buffers only)
+    pub fn shared_bytes(&self) -> usize {
+        use super::cow_arc::buffer_bytes;
+        let terminal = &self.terminal;
+        buffer_bytes(&terminal.chart_data)
+            + buffer_bytes(&terminal.sol_candles)
+            + buffer_bytes(&terminal.swap.token_list)
+            + buffer_bytes(&terminal.swap.swap_history)
+            + buffer_bytes(&self.transactions)
+            + buffer_bytes(&self.ai_chat.messages)
+            + self.messaging.messages.values().map(|messages| buffer_bytes(messages)).sum::<usize>()
+    }
+
     /// Check if a screen requires authentication
     pub fn requires_auth(screen: Screen) -> bool {
-        matches!(screen, Screen::Terminal | Screen::PythFeed | Screen::JupiterFeed | Screen::Wallet | Screen::Transactions | Screen::Tokens | Screen::Messaging | Screen::AIChat)
+        matches!(screen, Screen::Terminal | Screen::PythFeed | Screen::JupiterFeed | Screen::Wallet | Screen::Portfolio | Screen::Transactions | Screen::Tokens | Screen::Messaging | Screen::AIChat)
     }
 }
 
@@ -416,25 +835,58 @@ impl Clone for AppState {
             auth: self.auth.clone(),
             terminal: self.terminal.clone(),
             wallet: self.wallet.clone(),
+            wallet_identities: self.wallet_identities.clone(),
+            recovery: self.recovery.clone(),
+            wallet_label_edit: self.wallet_label_edit.clone(),
             transactions: self.transactions.clone(),
+            transactions_wallet: self.transactions_wallet.clone(),
             auth_token: self.auth_token.clone(),
+            session: self.session.clone(),
             current_user: self.current_user.clone(),
+            config: self.config.clone(),
             api_client: self.api_client.clone(),
+            api: self.api.clone(),
+            offline: self.offline,
             // IMPORTANT: wallet_service is intentionally NOT cloned (contains Keypair secret)
             // Rendering doesn't need access to signing capabilities anyway
             wallet_service: None,
+            offline_wallet: None,
             polling_credentials: self.polling_credentials.clone(),
             pending_notifications: self.pending_notifications.clone(),
+            system_notices: self.system_notices.clone(),
             websocket_connected: self.websocket_connected,
             websocket_status: self.websocket_status.clone(),
+            price_stream_task: self.price_stream_task.clone(),
+            price_stream_outbox: self.price_stream_outbox.clone(),
+            idle: self.idle.clone(),
+            maintenance: self.maintenance.clone(),
+            backend_health: self.backend_health.clone(),
             messaging: self.messaging.clone(),
             ai_chat: self.ai_chat.clone(),
             settings: self.settings.clone(),
+            portfolio: self.portfolio.clone(),
+            token_unlocks: self.token_unlocks.clone(),
+            live_assets: self.live_assets.clone(),
+            derived_accounts: self.derived_accounts.clone(),
+            trade_import: self.trade_import.clone(),
+            security: self.security.clone(),
+            search: self.search.clone(),
+            palette: self.palette.clone(),
+            share: self.share.clone(),
+            webhooks: self.webhooks.clone(),
+            api_keys: self.api_keys.clone(),
+            onramp: self.onramp.clone(),
+            send: self.send.clone(),
+            receive: self.receive.clone(),
+            airdrop: self.airdrop.clone(),
+            staking: self.staking.clone(),
+            link_prompt: self.link_prompt.clone(),
+            refresh_tasks: self.refresh_tasks.clone(),
             debug_overlay_visible: self.debug_overlay_visible,
             needs_immediate_repaint: self.needs_immediate_repaint,
             last_price_update_time: self.last_price_update_time,
-            nav_bar_selected_token: self.nav_bar_selected_token.clone(),
-            nav_bar_show_token_picker: self.nav_bar_show_token_picker,
+            root_view: self.root_view.clone(),
+            branding: self.branding.clone(),
         }
     }
 }
@@ -447,12 +899,706 @@ pub struct WalletState {
     pub token_balances: Vec<TokenBalance>,
 }
 
-/// Token balance in wallet
+/// Label and color picked for a wallet, saved with "Save"
 #[derive(Debug, Clone)]
+pub struct WalletLabelEdit {
+    pub address: String,
+    pub label: String,
+    pub color: [u8; 3],
+}
+
+/// Token balance in wallet
+#[derive(Debug, Clone, Default)]
 pub struct TokenBalance {
     pub symbol: String,
     pub amount: f64,
     pub usd_value: f64,
+    pub mint: String,
+    pub decimals: u8,
+    /// Balance in base units, what transfers are built from
+    pub raw_amount: u64,
+    /// Token program owning the account; transfers must go through it
+    pub program: shared::dto::tokens::TokenProgram,
+    /// Transfer fee for the current epoch (Token-2022 transfer-fee mints)
+    pub transfer_fee: Option<shared::dto::tokens::TransferFee>,
+}
+
+/// Single asset line in the portfolio view
+#[derive(Debug, Clone)]
+pub struct PortfolioHolding {
+    pub symbol: String,
+    pub amount: f64,
+    /// Current price used for valuation (None if no live price is known)
+    pub price: Option<f64>,
+    /// Current USD value of the holding
+    pub value: f64,
+    /// Share of total portfolio value (0-100)
+    pub allocation_pct: f64,
+    /// 24h price change of the asset in percent
+    pub change_24h_pct: f64,
+    /// 24h change of the holding in USD
+    pub change_24h_value: f64,
+    /// Amount held by each wallet (address, amount), in the combined view
+    pub wallets: Vec<(String, f64)>,
+}
+
+/// Daily total portfolio value, persisted for the history sparkline
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PortfolioSnapshot {
+    /// UTC date in `YYYY-MM-DD` format
+    pub date: String,
+    /// Total portfolio value in USD
+    pub total_value: f64,
+}
+
+/// Portfolio state (recomputed on price and balance updates)
+#[derive(Debug, Clone, Default)]
+pub struct PortfolioState {
+    /// Holdings sorted by value (largest first)
+    pub holdings: Vec<PortfolioHolding>,
+    /// Total portfolio value in USD
+    pub total_value: f64,
+    /// Total 24h change in USD
+    pub change_24h_value: f64,
+    /// Total 24h change in percent
+    pub change_24h_pct: f64,
+    /// Daily snapshots for the last 30 days (oldest first)
+    pub snapshots: Vec<PortfolioSnapshot>,
+    /// Last time the snapshot history was saved
+    pub last_snapshot_save: Option<std::time::Instant>,
+    /// Target allocations and the last rebalance proposal
+    pub rebalance: RebalanceState,
+    /// Last known balances of every wallet connected this session, by address
+    pub wallets: std::collections::BTreeMap<String, WalletState>,
+    /// Wallets the Portfolio screen shows
+    pub wallet_filter: crate::app::wallet_identity::WalletFilter,
+    /// Month picked for the monthly report (`None` is the current month)
+    pub report_month: Option<shared::dto::reports::ReportMonth>,
+    /// A monthly report download is in flight
+    pub report_pending: bool,
+}
+
+/// Upcoming token unlocks from `GET /api/market/unlocks`, refreshed by the health poll
+#[derive(Debug, Clone, Default)]
+pub struct TokenUnlockState {
+    /// Soonest first
+    pub upcoming: Vec<shared::TokenUnlockInfo>,
+    /// Unlocks already announced with an "unlock approaching" notice this session
+    pub notified: std::collections::HashSet<i64>,
+    /// Error of the last fetch; `upcoming` keeps the previous list
+    pub error: Option<String>,
+}
+
+impl TokenUnlockState {
+    /// Upcoming unlocks of `symbol`, soonest first
+    pub fn for_symbol<'a>(&'a self, symbol: &'a str) -> impl Iterator<Item = &'a shared::TokenUnlockInfo> + 'a {
+        self.upcoming.iter().filter(move |u| u.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    /// Soonest unlock of `symbol` within [`shared::UNLOCK_WARNING_DAYS`]
+    pub fn approaching(&self, symbol: &str, now: i64) -> Option<&shared::TokenUnlockInfo> {
+        self.for_symbol(symbol).find(|u| u.is_approaching(now))
+    }
+}
+
+/// Target share of one asset in the portfolio
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TargetAllocation {
+    pub symbol: String,
+    /// Share of the total value in percent (0-100)
+    pub pct: f64,
+}
+
+/// Smallest drift or trade the rebalancer acts on unless configured otherwise
+pub const DEFAULT_MIN_TRADE_USD: f64 = 10.0;
+
+/// Rebalancing targets of one profile
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RebalanceProfile {
+    #[serde(default)]
+    pub targets: Vec<TargetAllocation>,
+    /// Drift and trades below this USD value are left alone
+    #[serde(default = "default_min_trade_usd")]
+    pub min_trade_usd: f64,
+}
+
+fn default_min_trade_usd() -> f64 {
+    DEFAULT_MIN_TRADE_USD
+}
+
+impl Default for RebalanceProfile {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            min_trade_usd: DEFAULT_MIN_TRADE_USD,
+        }
+    }
+}
+
+/// Rebalancing targets by profile (username), saved in the local database
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RebalancePreferences {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, RebalanceProfile>,
+}
+
+/// One swap of a rebalance proposal
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebalanceSwap {
+    pub input_symbol: String,
+    pub output_symbol: String,
+    /// Amount of the input token to sell, rounded down
+    pub input_amount: f64,
+    /// USD value of `input_amount` at the current price
+    pub usd_value: f64,
+}
+
+/// Proposed swap and the quote fetched for it
+#[derive(Debug, Clone)]
+pub struct RebalanceProposal {
+    pub swap: RebalanceSwap,
+    /// `None` while the quote is loading
+    pub quote: Option<Result<SwapQuote, String>>,
+}
+
+/// Rebalancer section of the Portfolio screen
+#[derive(Debug, Clone, Default)]
+pub struct RebalanceState {
+    /// Saved targets of every profile
+    pub preferences: RebalancePreferences,
+    /// Profile whose targets the form holds
+    pub profile: Option<String>,
+    /// Targets being edited
+    pub targets: Vec<TargetAllocation>,
+    pub min_trade_usd: f64,
+    /// Symbol typed into the "Add" field
+    pub new_symbol: String,
+    /// Swaps of the last proposal, largest first
+    pub proposals: Vec<RebalanceProposal>,
+    /// Bumped for every proposal; quotes for an older one are dropped
+    pub generation: u64,
+    /// Outcome of the last save or proposal: (is_error, message)
+    pub status: Option<(bool, String)>,
+}
+
+/// Live Assets screen tabs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LiveAssetsTab {
+    #[default]
+    Prices,
+    Analytics,
+}
+
+/// Column the Live Assets price list is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum AssetSortColumn {
+    #[default]
+    Symbol,
+    Price,
+    Change24h,
+    Volume24h,
+}
+
+impl AssetSortColumn {
+    pub const ALL: [AssetSortColumn; 4] = [
+        AssetSortColumn::Symbol,
+        AssetSortColumn::Price,
+        AssetSortColumn::Change24h,
+        AssetSortColumn::Volume24h,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AssetSortColumn::Symbol => "Symbol",
+            AssetSortColumn::Price => "Price",
+            AssetSortColumn::Change24h => "24h",
+            AssetSortColumn::Volume24h => "Volume",
+        }
+    }
+}
+
+/// Sort of the Live Assets price list, saved in the local database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct AssetSort {
+    pub column: AssetSortColumn,
+    /// Largest (or last alphabetically) first
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// Live Assets screen state
+#[derive(Debug, Clone)]
+pub struct LiveAssetsState {
+    pub tab: LiveAssetsTab,
+    /// Analytics window in days
+    pub window_days: usize,
+    /// Last analytics response
+    pub analytics: Option<shared::dto::market::MarketAnalyticsResponse>,
+    /// Analytics fetch in flight
+    pub analytics_loading: bool,
+    /// Last analytics fetch error
+    pub analytics_error: Option<String>,
+    /// Symbols and window of the last request, and when it was sent
+    pub last_analytics_fetch: Option<(Vec<String>, usize, std::time::Instant)>,
+    /// Token open in the detail panel
+    pub selected: Option<String>,
+    /// Detail of the open token is loading (not yet cached)
+    pub detail_loading: bool,
+    /// Last detail fetch error for the open token
+    pub detail_error: Option<String>,
+    /// Hover dwell tracking for prefetching
+    pub hover_intent: crate::app::preload::HoverIntent,
+    /// Hover prefetches in flight, keyed per token
+    pub prefetch: crate::app::preload::TaskSupervisor,
+    /// Loaded token details (candles and stats)
+    pub details: crate::app::preload::TokenDetailCache,
+    /// Hourly closes per symbol for the mini charts, oldest first
+    pub sparklines: BTreeMap<String, Vec<f64>>,
+    /// Volume per symbol over the mini chart's candles
+    pub volumes: BTreeMap<String, f64>,
+    /// Symbols in the order the price list last showed them; kept while a
+    /// row is hovered so streamed prices don't move rows under the pointer
+    pub row_order: Vec<String>,
+    /// Mini chart fetch in flight
+    pub sparklines_loading: bool,
+    /// Symbols of the last mini chart request, and when it was sent
+    pub last_sparkline_fetch: Option<(Vec<String>, std::time::Instant)>,
+}
+
+impl Default for LiveAssetsState {
+    fn default() -> Self {
+        Self {
+            tab: LiveAssetsTab::default(),
+            window_days: 30,
+            analytics: None,
+            analytics_loading: false,
+            analytics_error: None,
+            last_analytics_fetch: None,
+            selected: None,
+            detail_loading: false,
+            detail_error: None,
+            hover_intent: Default::default(),
+            prefetch: Default::default(),
+            details: Default::default(),
+            sparklines: BTreeMap::new(),
+            volumes: BTreeMap::new(),
+            row_order: Vec::new(),
+            sparklines_loading: false,
+            last_sparkline_fetch: None,
+        }
+    }
+}
+
+/// Derived accounts of the imported seed (Wallet screen)
+///
+/// Holds only the encrypted keystore and public data. The text inputs are
+/// cleared by the handlers as soon as they are consumed.
+#[derive(Debug, Clone, Default)]
+pub struct DerivedAccountsState {
+    /// Loaded keystore (seed stays encrypted)
+    pub keystore: Option<crate::services::keystore::Keystore>,
+    /// SOL balance per account index from the last scan
+    pub balances: std::collections::HashMap<u32, f64>,
+    /// Index currently connected as the active wallet
+    pub active_index: Option<u32>,
+    /// Mnemonic phrase input (import form)
+    pub mnemonic_input: String,
+    /// Optional BIP39 passphrase input (import form)
+    pub passphrase_input: String,
+    /// Keystore password input
+    pub password_input: String,
+    /// Label being edited (index, text)
+    pub label_edit: Option<(u32, String)>,
+    /// True while deriving or fetching balances
+    pub scanning: bool,
+}
+
+/// Auto-sign attempt recorded for the security audit log
+#[derive(Debug, Clone)]
+pub struct SignerAuditEntry {
+    pub at: chrono::DateTime<chrono::Utc>,
+    /// Grant that allowed the signature (id, label); None when denied
+    pub grant: Option<(u64, String)>,
+    pub input_mint: String,
+    pub amount: u64,
+    pub destination_mint: String,
+    /// Signature on success, denial reason otherwise
+    pub outcome: Result<String, String>,
+}
+
+/// Session signer grants (Settings > Security)
+///
+/// `grants` mirrors the grants held by the wallet service so the screen can
+/// list them without touching the keypair.
+#[derive(Debug, Clone)]
+pub struct SecurityState {
+    pub grants: Vec<crate::services::signer_policy::SessionGrant>,
+    /// Newest last, capped at [`crate::app::handlers::security::MAX_AUDIT_ENTRIES`]
+    pub audit_log: Vec<SignerAuditEntry>,
+    /// Grant form inputs
+    pub label_input: String,
+    pub input_mint_input: String,
+    /// Per-transaction cap in the input mint's base units
+    pub max_amount_input: String,
+    /// Comma or whitespace separated
+    pub allowed_mints_input: String,
+    /// Comma or whitespace separated
+    pub allowed_programs_input: String,
+    pub expiry_minutes_input: String,
+    /// Keystore password, cleared as soon as it is consumed
+    pub password_input: String,
+    /// True while the keystore is being unlocked
+    pub granting: bool,
+    /// Everything done on the user's behalf, written through [`crate::app::audit::record`]
+    pub trail: crate::app::audit::AuditLog,
+    /// Timeline filter
+    pub trail_filter: crate::app::audit::AuditFilter,
+}
+
+impl Default for SecurityState {
+    fn default() -> Self {
+        Self {
+            grants: Vec::new(),
+            audit_log: Vec::new(),
+            label_input: String::new(),
+            input_mint_input: String::new(),
+            max_amount_input: String::new(),
+            allowed_mints_input: String::new(),
+            allowed_programs_input: crate::services::signer_policy::DEFAULT_ALLOWED_PROGRAMS.join(", "),
+            expiry_minutes_input: "60".to_string(),
+            password_input: String::new(),
+            granting: false,
+            trail: crate::app::audit::AuditLog::default(),
+            trail_filter: crate::app::audit::AuditFilter::default(),
+        }
+    }
+}
+
+/// Search palette state
+#[derive(Debug, Clone, Default)]
+pub struct SearchState {
+    pub open: bool,
+    pub query: String,
+    /// Ranked results for `query`
+    pub groups: Vec<crate::app::search::SearchGroup>,
+    /// Index into the results in display order
+    pub selected: usize,
+    /// Query the backend transaction lookup is running for
+    pub remote_pending: Option<String>,
+    /// Item the last selected result points at; the target screen outlines it
+    pub highlight: Option<crate::app::search::SearchTarget>,
+    /// Bumped on every selection so screens scroll to the highlight once
+    pub highlight_seq: u64,
+}
+
+/// Command palette state
+#[derive(Debug, Clone, Default)]
+pub struct CommandPaletteState {
+    pub open: bool,
+    pub input: String,
+    /// Index into the suggestions for `input`
+    pub selected: usize,
+    /// Why the last Enter couldn't run the selected command
+    pub error: Option<String>,
+    /// Built-in commands plus the ones screens registered at startup
+    pub registry: std::sync::Arc<crate::app::commands::CommandRegistry>,
+}
+
+/// Step of the trade import wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TradeImportStep {
+    /// Wizard closed
+    #[default]
+    Closed,
+    /// Waiting for a CSV file
+    SelectFile,
+    /// Headers did not match; user maps columns
+    Mapping,
+    /// Validated; showing row errors and the rows to import
+    Review,
+    /// Import committed; showing the summary
+    Done,
+}
+
+/// Trade import wizard (CSV exports from other platforms)
+///
+/// Imported trades only feed the trade statistics; they never become
+/// [`TransactionItem`]s, so they stay out of on-chain history.
+#[derive(Debug, Clone, Default)]
+pub struct TradeImportState {
+    pub step: TradeImportStep,
+    /// Name of the selected file
+    pub file_name: Option<String>,
+    /// Contents of the selected file
+    pub csv: String,
+    /// Column mapping sent with each validation
+    pub mapping: shared::dto::trades::ColumnMapping,
+    /// Last validation or commit response
+    pub preview: Option<shared::dto::trades::TradeImportResponse>,
+    /// True while a request is in flight
+    pub loading: bool,
+    pub error: Option<String>,
+    /// Realized PnL per pair over swaps and imports
+    pub stats: Option<shared::dto::trades::TradeStatsResponse>,
+    pub stats_loading: bool,
+}
+
+impl TradeImportState {
+    /// Start a new import, keeping the loaded statistics
+    pub fn open_wizard(&mut self) {
+        *self = Self {
+            step: TradeImportStep::SelectFile,
+            stats: self.stats.take(),
+            stats_loading: self.stats_loading,
+            ..Default::default()
+        };
+    }
+
+    /// Close the wizard and drop the file contents
+    pub fn close_wizard(&mut self) {
+        *self = Self {
+            stats: self.stats.take(),
+            stats_loading: self.stats_loading,
+            ..Default::default()
+        };
+    }
+}
+
+/// Read-only share links for the Live Chart and Portfolio screens
+#[derive(Debug, Clone)]
+pub struct ShareState {
+    /// Link lifetime in hours; `None` never expires
+    pub expires_in_hours: Option<i64>,
+    /// Leave USD values out of portfolio snapshots
+    pub anonymize: bool,
+    /// True while a link is being created
+    pub pending: bool,
+    /// Full URL of the last created link
+    pub link: Option<String>,
+    /// Whether `link` has been put on the clipboard yet
+    pub copied: bool,
+}
+
+impl Default for ShareState {
+    fn default() -> Self {
+        Self {
+            expires_in_hours: Some(24),
+            anonymize: true,
+            pending: false,
+            link: None,
+            copied: false,
+        }
+    }
+}
+
+/// Fiat on-ramp providers offered by the "Buy SOL" dialog
+#[derive(Debug, Clone, Default)]
+pub struct OnRampState {
+    pub open: bool,
+    pub loading: bool,
+    /// Every configured provider; the dialog filters them by `region`
+    pub providers: Vec<shared::dto::onramp::OnRampProvider>,
+    /// Country code picked in the dialog; empty shows all providers
+    pub region: String,
+    /// Fiat amount to buy; empty lets the provider ask
+    pub amount: String,
+    /// Provider whose checkout was opened, for the "balance will update" hint
+    pub opened: Option<String>,
+    /// Failed fetch or checkout URL
+    pub error: Option<String>,
+}
+
+/// Send panel on the Wallet screen
+#[derive(Debug, Clone, Default)]
+pub struct SendState {
+    pub recipient: String,
+    /// Mint of the token to send; `None` sends SOL
+    pub mint: Option<String>,
+    /// Amount as typed, in tokens
+    pub amount: String,
+    /// SPL Memo to attach, as typed; empty attaches none
+    pub memo: String,
+    /// Why the last Review was refused
+    pub error: Option<String>,
+    /// Building the transfer and looking up its fee
+    pub preparing: bool,
+    /// Transfer waiting in the confirmation dialog
+    pub confirmation: Option<TransferConfirmation>,
+    /// Signed transfer being submitted
+    pub sending: bool,
+}
+
+/// Receive panel on the Wallet screen
+#[derive(Debug, Clone, Default)]
+pub struct ReceiveState {
+    /// SOL to request, as typed; empty shares just the address
+    pub amount: String,
+}
+
+/// Devnet airdrop button on the Wallet screen
+#[derive(Debug, Clone, Default)]
+pub struct AirdropState {
+    /// Airdrop requested and not yet confirmed
+    pub requesting: bool,
+    /// The faucet rate-limited the last request; the button waits until then
+    pub cooldown_until: Option<std::time::Instant>,
+}
+
+/// Staking panel on the Wallet screen
+#[derive(Debug, Clone, Default)]
+pub struct StakingState {
+    /// Wallet the stake accounts were loaded for; a different active wallet reloads them
+    pub address: Option<String>,
+    pub info: Option<shared::dto::staking::StakingInfo>,
+    /// Rewards over the last few epochs, newest first
+    pub rewards: Vec<shared::dto::staking::StakeReward>,
+    pub loading: bool,
+    /// Loading the stake accounts failed
+    pub error: Option<String>,
+    /// Validator vote account, as typed
+    pub vote_account: String,
+    /// SOL to stake, as typed
+    pub amount: String,
+    /// Why the stake form can't be submitted
+    pub form_error: Option<String>,
+    /// Staking transaction being built, signed or submitted
+    pub submitting: Option<crate::app::handlers::staking::StakeAction>,
+    /// Stake account whose deactivation waits for confirmation
+    pub confirm_deactivate: Option<String>,
+}
+
+/// Transfer built on the Wallet screen, waiting for the user to confirm it
+#[derive(Debug, Clone)]
+pub struct TransferConfirmation {
+    /// Unsigned transfer; signed only on confirm
+    pub transaction: crate::services::wallet::WalletTransaction,
+    pub recipient: String,
+    /// Token mint; `None` for SOL
+    pub mint: Option<String>,
+    pub symbol: String,
+    pub decimals: u8,
+    /// Base units leaving the wallet (lamports for SOL)
+    pub amount: u64,
+    /// Base units reaching the recipient, after a Token-2022 transfer fee
+    pub received: u64,
+    /// Memo attached to the transaction
+    pub memo: Option<String>,
+    /// Network fee of the transaction, in lamports
+    pub fee_lamports: u64,
+    /// SOL the recipient holds, in lamports
+    pub recipient_lamports: u64,
+    /// Least a plain account must hold to exist, in lamports
+    pub rent_exempt_minimum: u64,
+    /// Rent for the recipient's token account, when the transfer creates it
+    pub token_account_rent: Option<u64>,
+    /// Address of the wallet that will sign
+    pub wallet: String,
+}
+
+impl TransferConfirmation {
+    /// SOL sent to an empty account must at least make it rent exempt;
+    /// the runtime rejects anything less
+    pub fn below_rent_minimum(&self) -> bool {
+        self.mint.is_none() && self.recipient_lamports == 0 && self.amount < self.rent_exempt_minimum
+    }
+
+    /// Only a transfer the runtime would accept may be signed
+    pub fn can_send(&self) -> bool {
+        !self.below_rent_minimum()
+    }
+
+    /// Lamports leaving the wallet: the fee, any token account rent, and the amount when sending SOL
+    pub fn total_lamports(&self) -> u64 {
+        let sol = if self.mint.is_none() { self.amount } else { 0 };
+        sol + self.fee_lamports + self.token_account_rent.unwrap_or(0)
+    }
+}
+
+/// Outgoing webhooks registered with the backend (Settings > Webhooks)
+#[derive(Debug, Clone)]
+pub struct WebhooksState {
+    pub webhooks: Vec<shared::dto::webhooks::WebhookInfo>,
+    /// A list was fetched (or failed) since login
+    pub loaded: bool,
+    pub loading: bool,
+    /// Add form inputs
+    pub url_input: String,
+    pub events_input: Vec<shared::dto::webhooks::WebhookEventType>,
+    /// SOL amount for `balance_threshold`
+    pub threshold_input: String,
+    /// True while a webhook is being created
+    pub creating: bool,
+    /// Signing secret of the webhook just created; the backend never returns it again
+    pub new_secret: Option<(i64, String)>,
+    /// Webhook whose delivery log is shown
+    pub selected: Option<i64>,
+    /// Delivery log of `selected`, newest first
+    pub deliveries: Vec<shared::dto::webhooks::WebhookDeliveryInfo>,
+    pub deliveries_loading: bool,
+    /// Webhook a test event is being sent to
+    pub testing: Option<i64>,
+    /// Last failed request
+    pub error: Option<String>,
+}
+
+impl Default for WebhooksState {
+    fn default() -> Self {
+        Self {
+            webhooks: Vec::new(),
+            loaded: false,
+            loading: false,
+            url_input: String::new(),
+            events_input: vec![shared::dto::webhooks::WebhookEventType::IncomingTransfer],
+            threshold_input: String::new(),
+            creating: false,
+            new_secret: None,
+            selected: None,
+            deliveries: Vec::new(),
+            deliveries_loading: false,
+            testing: None,
+            error: None,
+        }
+    }
+}
+
+/// API keys of the user (Settings > API Keys)
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeysState {
+    pub keys: Vec<shared::dto::api_keys::ApiKeyInfo>,
+    /// A list was fetched (or failed) since login
+    pub loaded: bool,
+    pub loading: bool,
+    /// Create form inputs
+    pub label_input: String,
+    pub write_input: bool,
+    /// True while a key is being created
+    pub creating: bool,
+    /// Key just created; the backend never returns it again
+    pub new_key: Option<(i64, String)>,
+    /// Key a revoke request is in flight for
+    pub revoking: Option<i64>,
+    /// Last failed request
+    pub error: Option<String>,
+}
+
+/// An opened `xforce://` link; nothing happens until it is confirmed
+#[derive(Debug, Clone, Default)]
+pub struct LinkPromptState {
+    /// Link as received, and what it opens
+    pub pending: Option<(String, crate::services::protocol_handler::DeepLink)>,
+    /// Bring the main window to the front on the next frame
+    pub raise_window: bool,
+}
+
+/// Keep-alive of the login session (see [`crate::services::session`])
+#[derive(Debug, Clone, Default)]
+pub struct SessionState {
+    /// A `/api/auth/refresh` request is in flight
+    pub refreshing: bool,
+    /// Unix seconds of the last refresh attempt, to space out retries
+    pub last_refresh_attempt: Option<i64>,
+    /// Why the last refresh failed; cleared by the next success
+    pub refresh_error: Option<String>,
 }
 
 /// Transaction history item
@@ -463,6 +1609,12 @@ pub struct TransactionItem {
     pub tx_type: String,
     pub status: String,
     pub amount: String,
+    /// Decoded on-chain memo(s), if any
+    pub memo: Option<String>,
+    /// Address of the wallet the transaction belongs to
+    pub wallet: Option<String>,
+    /// Time each pipeline stage took, for swaps submitted this session
+    pub latency: Option<shared::dto::latency::SwapLatency>,
 }
 
 /// Current user information
@@ -484,7 +1636,7 @@ pub struct MessagingState {
     /// Currently active conversation ID
     pub active_conversation_id: Option<String>,
     /// Messages by conversation ID
-    pub messages: std::collections::HashMap<String, Vec<shared::dto::messaging::Message>>,
+    pub messages: CowArc<std::collections::HashMap<String, Vec<shared::dto::messaging::Message>>>,
     /// Selected user ID for conversation
     pub selected_user_id: Option<i64>,
     /// Search query for finding users
@@ -495,6 +1647,86 @@ pub struct MessagingState {
     pub typing_indicators: std::collections::HashMap<String, (i64, String)>,
     /// Current message input text
     pub message_input: String,
+    /// Latest AI summary by conversation ID
+    pub summaries: std::collections::HashMap<String, shared::dto::messaging::ConversationSummaryResponse>,
+    /// Conversation whose summary is being generated
+    pub summary_pending: Option<String>,
+    /// Unread messages by conversation ID (conversations with none are absent)
+    pub unread_counts: std::collections::HashMap<String, u32>,
+    /// The other participant's receipts by conversation ID
+    pub receipts: std::collections::HashMap<String, ConversationReceipts>,
+    /// Uploaded attachments waiting to be sent, by conversation ID
+    pub pending_attachments: std::collections::HashMap<String, Vec<shared::dto::messaging::Attachment>>,
+    /// Conversation whose attachment is being uploaded
+    pub attachment_uploading: Option<String>,
+    /// Inline image previews by attachment ID
+    pub attachment_previews: std::collections::HashMap<String, crate::ui::widgets::attachment_preview::AttachmentPreview>,
+}
+
+impl MessagingState {
+    /// Conversation ID of two users, smaller ID first as the backend expects
+    pub fn conversation_id(user_a: i64, user_b: i64) -> String {
+        format!("{}:{}", user_a.min(user_b), user_a.max(user_b))
+    }
+    
+    /// Replace friends and requests with a fresh list, taking its unread counts
+    pub fn apply_friends_list(&mut self, list: shared::dto::messaging::FriendsListResponse, current_user_id: Option<i64>) {
+        if let Some(me) = current_user_id {
+            self.unread_counts = list.friends.iter()
+                .filter(|friend| friend.unread_count > 0)
+                .map(|friend| (Self::conversation_id(me, friend.user_id), friend.unread_count as u32))
+                .collect();
+        }
+        self.friends = list.friends;
+        self.incoming_requests = list.incoming_requests;
+        self.outgoing_requests = list.outgoing_requests;
+    }
+    
+    /// Unread messages across all conversations, for the nav bar badge
+    pub fn total_unread(&self) -> u32 {
+        self.unread_counts.values().sum()
+    }
+}
+
+/// How far the other participant has got through a conversation
+#[derive(Debug, Clone, Default)]
+pub struct ConversationReceipts {
+    /// Newest message version delivered to them
+    pub delivered: Option<String>,
+    /// Newest message version they have read
+    pub read: Option<String>,
+}
+
+impl ConversationReceipts {
+    /// Record a receipt from the subscription
+    pub fn apply(&mut self, receipt: shared::dto::messaging::MessageReceipt) {
+        match receipt.status {
+            shared::dto::messaging::ReceiptStatus::Delivered => self.delivered = Some(receipt.version),
+            shared::dto::messaging::ReceiptStatus::Read => self.read = Some(receipt.version),
+        }
+    }
+    
+    /// Receipt status of `messages[index]`
+    ///
+    /// A receipt covers its message and everything before it; read implies
+    /// delivered. `None` while neither marker has reached the message.
+    pub fn status_of(
+        &self,
+        messages: &[shared::dto::messaging::Message],
+        index: usize,
+    ) -> Option<shared::dto::messaging::ReceiptStatus> {
+        let position = |version: &Option<String>| {
+            let version = version.as_deref()?;
+            messages.iter().position(|m| m.version.as_deref() == Some(version))
+        };
+        if position(&self.read).is_some_and(|read| index <= read) {
+            Some(shared::dto::messaging::ReceiptStatus::Read)
+        } else if position(&self.delivered).is_some_and(|delivered| index <= delivered) {
+            Some(shared::dto::messaging::ReceiptStatus::Delivered)
+        } else {
+            None
+        }
+    }
 }
 
 impl Default for MessagingState {
@@ -504,12 +1736,19 @@ impl Default for MessagingState {
             incoming_requests: vec![],
             outgoing_requests: vec![],
             active_conversation_id: None,
-            messages: std::collections::HashMap::new(),
+            messages: CowArc::default(),
             selected_user_id: None,
             search_query: String::new(),
             search_results: vec![],
             typing_indicators: std::collections::HashMap::new(),
             message_input: String::new(),
+            summaries: std::collections::HashMap::new(),
+            summary_pending: None,
+            unread_counts: std::collections::HashMap::new(),
+            receipts: std::collections::HashMap::new(),
+            pending_attachments: std::collections::HashMap::new(),
+            attachment_uploading: None,
+            attachment_previews: std::collections::HashMap::new(),
         }
     }
 }
@@ -519,18 +1758,272 @@ impl Default for MessagingState {
 pub struct SettingsState {
     /// Current theme configuration
     pub theme_config: crate::ui::theme::ThemeConfig,
-    /// Path to config file
+    /// Database the settings are saved in
     pub config_path: String,
     /// Whether there are unsaved changes
     pub unsaved_changes: bool,
+    /// Chart indicator selection and periods
+    pub indicators: crate::ui::chart::indicators::IndicatorConfig,
+    /// Account section forms (password and email)
+    pub account: AccountFormState,
+    /// Backend server list being edited (Settings > Servers)
+    pub servers: ServerListForm,
+    /// Network and endpoints being edited (Settings > Connection)
+    pub connection: ConnectionForm,
+    /// Favorite and recently picked tokens
+    pub tokens: TokenPreferences,
+    /// Network usage preferences
+    pub network: NetworkPreferences,
+    /// Limits past which the Portfolio screen warns about concentration
+    pub risk: RiskThresholds,
+    /// Which categories of backend notices are shown
+    pub notifications: NotificationPreferences,
+    /// Usage analytics consent (Settings > Privacy)
+    pub privacy: PrivacyForm,
+    /// Sort of the Live Assets price list
+    pub asset_sort: AssetSort,
+    /// Keyboard shortcuts (Settings > Shortcuts)
+    pub key_bindings: crate::app::keybindings::KeyBindings,
+    /// Shortcut being rebound
+    pub key_capture: crate::app::keybindings::KeyCaptureForm,
+    /// Language of the UI (Settings > Language)
+    pub locale: crate::ui::i18n::Locale,
+}
+
+/// Symbol the charts open on
+pub const DEFAULT_CHART_SYMBOL: &str = "SOL";
+
+/// Oracle prices older than this are shown as stale unless configured otherwise
+pub const DEFAULT_STALE_PRICE_SECS: u64 = 30;
+
+/// Network usage and price feed preferences, saved in the local database
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NetworkPreferences {
+    /// Only fetch what is on screen: no prefetching on hover
+    #[serde(default)]
+    pub bandwidth_saver: bool,
+    /// Age in seconds after which an oracle price is flagged as stale
+    #[serde(default = "default_stale_price_secs")]
+    pub stale_price_secs: u64,
+    /// Minutes unfocused and untouched before connections are closed; 0 never
+    #[serde(default = "default_idle_teardown_mins")]
+    pub idle_teardown_mins: u64,
+}
+
+fn default_stale_price_secs() -> u64 {
+    DEFAULT_STALE_PRICE_SECS
+}
+
+fn default_idle_teardown_mins() -> u64 {
+    crate::app::idle::DEFAULT_IDLE_TEARDOWN_MINS
+}
+
+impl Default for NetworkPreferences {
+    fn default() -> Self {
+        Self {
+            bandwidth_saver: false,
+            stale_price_secs: DEFAULT_STALE_PRICE_SECS,
+            idle_teardown_mins: crate::app::idle::DEFAULT_IDLE_TEARDOWN_MINS,
+        }
+    }
+}
+
+/// Backend notice categories the user muted, saved in the local database
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NotificationPreferences {
+    /// Categories whose notices are neither toasted nor bannered
+    #[serde(default)]
+    pub muted: Vec<shared::NoticeCategory>,
+}
+
+impl NotificationPreferences {
+    /// Whether notices of `category` should be shown
+    pub fn is_enabled(&self, category: shared::NoticeCategory) -> bool {
+        !self.muted.contains(&category)
+    }
+
+    /// Show or mute notices of `category`
+    pub fn set_enabled(&mut self, category: shared::NoticeCategory, enabled: bool) {
+        self.muted.retain(|muted| *muted != category);
+        if !enabled {
+            self.muted.push(category);
+        }
+    }
+}
+
+/// Largest share of the portfolio one asset may hold before a warning, in percent
+pub const DEFAULT_MAX_POSITION_PCT: f64 = 50.0;
+
+/// Herfindahl index (0-10,000) above which the portfolio counts as undiversified
+pub const DEFAULT_MAX_HHI: f64 = 5000.0;
+
+/// Position value as a multiple of the token's 24h volume past which it counts as illiquid
+pub const DEFAULT_MAX_VOLUME_MULTIPLE: f64 = 0.1;
+
+/// Portfolio risk warning thresholds (Settings > Risk), saved in the local database
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RiskThresholds {
+    /// Largest share of total value in one asset, in percent (0-100)
+    #[serde(default = "default_max_position_pct")]
+    pub max_position_pct: f64,
+    /// Largest Herfindahl index, 10,000 being everything in one asset
+    #[serde(default = "default_max_hhi")]
+    pub max_hhi: f64,
+    /// Largest position value relative to the token's 24h volume
+    #[serde(default = "default_max_volume_multiple")]
+    pub max_volume_multiple: f64,
+}
+
+fn default_max_position_pct() -> f64 {
+    DEFAULT_MAX_POSITION_PCT
+}
+
+fn default_max_hhi() -> f64 {
+    DEFAULT_MAX_HHI
+}
+
+fn default_max_volume_multiple() -> f64 {
+    DEFAULT_MAX_VOLUME_MULTIPLE
+}
+
+impl Default for RiskThresholds {
+    fn default() -> Self {
+        Self {
+            max_position_pct: DEFAULT_MAX_POSITION_PCT,
+            max_hhi: DEFAULT_MAX_HHI,
+            max_volume_multiple: DEFAULT_MAX_VOLUME_MULTIPLE,
+        }
+    }
+}
+
+/// Most recently picked tokens kept
+pub const MAX_RECENT_TOKENS: usize = 10;
+
+/// Token picker favorites and recents, saved in the local database
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TokenPreferences {
+    /// Favorite token mints
+    #[serde(default)]
+    pub favorites: Vec<String>,
+    /// Last picked token mints, most recent first
+    #[serde(default)]
+    pub recents: Vec<String>,
+}
+
+impl TokenPreferences {
+    pub fn is_favorite(&self, mint: &str) -> bool {
+        self.favorites.iter().any(|m| m == mint)
+    }
+
+    /// Star or unstar a mint, returning whether it is now a favorite
+    pub fn toggle_favorite(&mut self, mint: &str) -> bool {
+        if self.is_favorite(mint) {
+            self.favorites.retain(|m| m != mint);
+            false
+        } else {
+            self.favorites.push(mint.to_string());
+            true
+        }
+    }
+
+    /// Move a mint to the front of the recents, dropping the oldest past [`MAX_RECENT_TOKENS`]
+    pub fn push_recent(&mut self, mint: &str) {
+        self.recents.retain(|m| m != mint);
+        self.recents.insert(0, mint.to_string());
+        self.recents.truncate(MAX_RECENT_TOKENS);
+    }
+}
+
+/// Servers section of the Settings screen
+#[derive(Debug, Clone, Default)]
+pub struct ServerListForm {
+    /// Server URLs, primary first
+    pub entries: Vec<String>,
+    /// URL typed into the "Add" field
+    pub new_entry: String,
+    /// Outcome of the last save: (is_error, message)
+    pub status: Option<(bool, String)>,
+}
+
+impl ServerListForm {
+    pub fn new(entries: Vec<String>) -> Self {
+        Self { entries, ..Default::default() }
+    }
+}
+
+/// Connection section of the Settings screen
+///
+/// URLs are kept as typed; an empty one means "use the default".
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionForm {
+    pub network: crate::core::config::SolanaNetwork,
+    pub rpc_url: String,
+    pub api_url: String,
+    pub ws_url: String,
+    pub default_slippage_bps: String,
+    /// Fields rejected by the last apply
+    pub errors: Vec<shared::validation::ValidationError>,
+    /// Outcome of the last apply: (is_error, message)
+    pub status: Option<(bool, String)>,
+}
+
+impl ConnectionForm {
+    pub fn new(config: &crate::core::config::TerminalConfig) -> Self {
+        Self {
+            network: config.network,
+            rpc_url: config.rpc_url.clone().unwrap_or_default(),
+            api_url: config.api_url.clone().unwrap_or_default(),
+            ws_url: config.ws_url.clone().unwrap_or_default(),
+            default_slippage_bps: config.default_slippage_bps.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Privacy section of the Settings screen
+#[derive(Debug, Clone, Default)]
+pub struct PrivacyForm {
+    pub usage: crate::app::usage::UsagePreferences,
+    /// Pretty-printed JSON of the next upload; None when nothing is pending
+    pub preview: Option<String>,
+    /// A request to delete the uploaded counts is in flight
+    pub deleting: bool,
+    /// Outcome of the last deletion: (is_error, message)
+    pub status: Option<(bool, String)>,
+}
+
+/// Account section of the Settings screen
+#[derive(Debug, Clone, Default)]
+pub struct AccountFormState {
+    pub current_password: String,
+    pub new_password: String,
+    pub confirm_password: String,
+    pub email: String,
+    /// A password or profile request is in flight
+    pub pending: bool,
+    /// Outcome of the last request: (is_error, message)
+    pub status: Option<(bool, String)>,
 }
 
 impl Default for SettingsState {
     fn default() -> Self {
         Self {
             theme_config: crate::ui::theme::ThemeConfig::default(),
-            config_path: "./xterminal-config.json".to_string(),
+            config_path: crate::core::store::database_path().to_string_lossy().to_string(),
             unsaved_changes: false,
+            indicators: crate::ui::chart::indicators::IndicatorConfig::default(),
+            account: AccountFormState::default(),
+            servers: ServerListForm::default(),
+            connection: ConnectionForm::new(&crate::core::config::TerminalConfig::default()),
+            tokens: TokenPreferences::default(),
+            network: NetworkPreferences::default(),
+            risk: RiskThresholds::default(),
+            notifications: NotificationPreferences::default(),
+            privacy: PrivacyForm::default(),
+            asset_sort: AssetSort::default(),
+            key_bindings: Default::default(),
+            key_capture: Default::default(),
+            locale: Default::default(),
         }
     }
 }
@@ -541,24 +2034,228 @@ pub struct AIChatState {
     /// Conversation ID for AI chat (uses special format: "ai:{user_id}")
     pub conversation_id: Option<String>,
     /// Messages in the AI conversation
-    pub messages: Vec<shared::dto::messaging::Message>,
+    pub messages: CowArc<Vec<shared::dto::messaging::Message>>,
     /// Current message input text
     pub message_input: String,
     /// Whether the AI is currently typing/responding
     pub ai_typing: bool,
     /// Whether we're subscribed to conversation updates
     pub subscribed: bool,
+    /// Reply currently streaming in, until the bot posts it
+    pub streaming: Option<StreamingReply>,
 }
 
 impl Default for AIChatState {
     fn default() -> Self {
         Self {
             conversation_id: None,
-            messages: vec![],
+            messages: CowArc::default(),
             message_input: String::new(),
             ai_typing: false,
             subscribed: false,
+            streaming: None,
         }
     }
 }
 
+/// AI reply assembled from streamed chunks
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamingReply {
+    /// Text received so far
+    pub text: String,
+    /// Stopped with the Cancel button; chunks still in flight are dropped
+    pub cancelled: bool,
+}
+
+impl AIChatState {
+    /// Author shown on replies finalized locally, before the bot's own copy arrives
+    const LOCAL_REPLY_AUTHOR: &'static str = "AI Assistant";
+
+    /// Whether a reply is being waited for or streamed in
+    pub fn is_replying(&self) -> bool {
+        self.ai_typing || self.streaming.as_ref().is_some_and(|reply| !reply.cancelled)
+    }
+
+    /// Append the next chunk of the reply being streamed
+    pub fn push_chunk(&mut self, delta: &str) {
+        let reply = self.streaming.get_or_insert_with(StreamingReply::default);
+        if !reply.cancelled {
+            reply.text.push_str(delta);
+        }
+    }
+
+    /// Replace the conversation with the latest list from the subscription
+    ///
+    /// Once the bot's reply shows up in the list, the streamed copy is dropped.
+    pub fn set_messages(&mut self, messages: Vec<shared::dto::messaging::Message>, current_user_id: Option<i64>) {
+        let replied = messages.last().is_some_and(|message| Some(message.author_id) != current_user_id);
+        if replied {
+            self.ai_typing = false;
+            self.streaming = None;
+        }
+        self.messages = messages.into();
+    }
+
+    /// The bot stopped streaming; keep whatever arrived as a local message
+    ///
+    /// Interrupted replies keep their partial text with an error suffix. The
+    /// bot posts its own copy as well, which replaces this one with the next
+    /// message update.
+    pub fn finish_stream(&mut self, outcome: &shared::dto::messaging::AiStreamOutcome) {
+        self.ai_typing = false;
+        let Some(reply) = self.streaming.take() else {
+            return;
+        };
+        // A cancelled reply was already finalized when Cancel was pressed
+        if !reply.cancelled {
+            self.push_local_reply(outcome.finalize(&reply.text));
+        }
+    }
+
+    /// Stop the reply in progress, keeping the text received so far
+    ///
+    /// Returns whether there was a reply to stop.
+    pub fn cancel_stream(&mut self) -> bool {
+        if !self.is_replying() {
+            return false;
+        }
+        self.ai_typing = false;
+        let reply = self.streaming.get_or_insert_with(StreamingReply::default);
+        reply.cancelled = true;
+        let text = shared::dto::messaging::AiStreamOutcome::Cancelled.finalize(&reply.text);
+        self.push_local_reply(text);
+        true
+    }
+
+    fn push_local_reply(&mut self, text: Option<String>) {
+        if let Some(text) = text {
+            self.messages.push(shared::dto::messaging::Message::new(
+                text,
+                Self::LOCAL_REPLY_AUTHOR.to_string(),
+                0,
+            ));
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::dto::messaging::{AiStreamOutcome, Message, MessageReceipt, ReceiptStatus};
+
+    #[test]
+    fn test_receipt_status_covers_earlier_messages() {
+        let messages: Vec<Message> = ["v1", "v2", "v3"]
+            .into_iter()
+            .map(|v| Message::with_version("hi".into(), "alice".into(), 1, v.into()))
+            .collect();
+        let mut receipts = ConversationReceipts::default();
+        assert_eq!(receipts.status_of(&messages, 0), None);
+
+        receipts.apply(MessageReceipt { user_id: 2, version: "v2".into(), status: ReceiptStatus::Delivered });
+        receipts.apply(MessageReceipt { user_id: 2, version: "v1".into(), status: ReceiptStatus::Read });
+        assert_eq!(receipts.status_of(&messages, 0), Some(ReceiptStatus::Read));
+        assert_eq!(receipts.status_of(&messages, 1), Some(ReceiptStatus::Delivered));
+        assert_eq!(receipts.status_of(&messages, 2), None);
+    }
+
+    #[test]
+    fn test_ai_chat_assembles_streamed_reply() {
+        let mut chat = AIChatState { ai_typing: true, ..Default::default() };
+        chat.set_messages(vec![Message::new("price of SOL?".into(), "alice".into(), 5)], Some(5));
+        assert!(chat.ai_typing);
+
+        for delta in ["SOL is ", "trading ", "at $142"] {
+            chat.push_chunk(delta);
+        }
+        assert_eq!(chat.streaming.as_ref().map(|reply| reply.text.as_str()), Some("SOL is trading at $142"));
+        assert!(chat.is_replying());
+
+        // The bot's posted reply takes over from the streamed copy
+        let mut messages = chat.messages.to_vec();
+        messages.push(Message::new("SOL is trading at $142".into(), "DeepSeek AI".into(), 0));
+        chat.set_messages(messages, Some(5));
+        assert_eq!(chat.streaming, None);
+        assert!(!chat.ai_typing);
+        assert_eq!(chat.messages.len(), 2);
+    }
+
+    #[test]
+    fn test_ai_chat_keeps_interrupted_reply() {
+        let mut chat = AIChatState { ai_typing: true, ..Default::default() };
+        chat.push_chunk("Jupiter routes ");
+        chat.finish_stream(&AiStreamOutcome::Failed { error: "connection lost".into() });
+
+        assert_eq!(chat.streaming, None);
+        assert!(!chat.ai_typing);
+        assert_eq!(
+            chat.messages.last().map(|message| message.text.as_str()),
+            Some("Jupiter routes [response interrupted: connection lost]")
+        );
+    }
+
+    #[test]
+    fn test_ai_chat_cancel_drops_later_chunks() {
+        let mut chat = AIChatState::default();
+        assert!(!chat.cancel_stream());
+
+        chat.ai_typing = true;
+        chat.push_chunk("Staking yields ");
+        assert!(chat.cancel_stream());
+        assert!(!chat.is_replying());
+        assert_eq!(chat.messages.last().map(|message| message.text.as_str()), Some("Staking yields [cancelled]"));
+
+        // Chunks already on the wire and the bot's end event change nothing
+        chat.push_chunk("around 7%");
+        chat.finish_stream(&AiStreamOutcome::Cancelled);
+        assert_eq!(chat.messages.len(), 1);
+        assert_eq!(chat.streaming, None);
+    }
+
+    #[test]
+    fn test_friends_list_sets_unread_counts() {
+        let friend = |user_id: i64, unread_count: i32| shared::dto::messaging::Friend {
+            id: user_id,
+            user_id,
+            username: format!("user{}", user_id),
+            friendship_id: user_id,
+            unread_count,
+            last_message_at: None,
+            last_message_preview: None,
+        };
+        let mut messaging = MessagingState::default();
+        messaging.apply_friends_list(
+            shared::dto::messaging::FriendsListResponse {
+                friends: vec![friend(2, 3), friend(7, 0), friend(9, 1)],
+                incoming_requests: vec![],
+                outgoing_requests: vec![],
+            },
+            Some(5),
+        );
+
+        assert_eq!(messaging.unread_counts.get("2:5"), Some(&3));
+        assert_eq!(messaging.unread_counts.get("5:9"), Some(&1));
+        assert!(!messaging.unread_counts.contains_key("5:7"));
+        assert_eq!(messaging.total_unread(), 4);
+    }
+
+    #[test]
+    fn test_notification_preferences_mute_categories() {
+        use shared::NoticeCategory;
+
+        let mut preferences = NotificationPreferences::default();
+        assert!(NoticeCategory::ALL.iter().all(|&category| preferences.is_enabled(category)));
+
+        preferences.set_enabled(NoticeCategory::PriceDivergence, false);
+        preferences.set_enabled(NoticeCategory::PriceDivergence, false);
+        assert_eq!(preferences.muted, vec![NoticeCategory::PriceDivergence]);
+        assert!(preferences.is_enabled(NoticeCategory::System));
+
+        let saved: NotificationPreferences = serde_json::from_str(r#"{"muted":["price_divergence"]}"#).unwrap();
+        assert_eq!(saved, preferences);
+
+        preferences.set_enabled(NoticeCategory::PriceDivergence, true);
+        assert!(preferences.muted.is_empty());
+    }
+}
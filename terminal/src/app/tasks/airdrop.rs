@@ -0,0 +1,113 @@
+//! # Airdrop Task
+//!
+//! Asks the devnet faucet for SOL and polls until the airdrop is confirmed.
+//! See [`crate::app::handlers::airdrop`] for the flow.
+
+use crate::app::events::AppEvent;
+use crate::app::handlers::airdrop::{airdrop_blocked, AIRDROP_LAMPORTS, AIRDROP_TX_TYPE};
+use crate::app::handlers::transactions::apply_status_change;
+use crate::app::state::{AppState, TransactionItem};
+use crate::app::tasks::guard::{spawn_guarded, GuardedTask};
+use crate::app::tasks::tx_status::signature_status;
+use crate::services::token_transfer::{format_token_amount, SOL_DECIMALS};
+use crate::services::wallet::request_airdrop_to;
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::transactions::TransactionStatus;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Time between confirmation polls
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Give up waiting for confirmation after this long
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Request an airdrop of [`AIRDROP_LAMPORTS`] to the connected wallet
+///
+/// Internal task function - spawns async task to request and confirm the airdrop and send the result via event channel.
+pub(crate) fn request_airdrop(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (address, rpc_url) = {
+        let mut state_guard = state.write();
+        if let Some(reason) = airdrop_blocked(&state_guard, Instant::now()) {
+            tracing::debug!(%reason, "Airdrop not requested");
+            return;
+        }
+        let (Some(wallet), Some(wallet_service)) = (&state_guard.wallet, &state_guard.wallet_service) else {
+            return;
+        };
+        let request = (wallet.address.clone(), wallet_service.rpc_client().url());
+        state_guard.airdrop.requesting = true;
+        request
+    };
+
+    let state_clone = state.clone();
+    spawn_guarded(GuardedTask::Airdrop, state, event_tx.clone(), async move {
+        let rpc = Arc::new(RpcClient::new(rpc_url));
+        let result = async {
+            let recipient = Pubkey::from_str(&address).map_err(|e| format!("Invalid wallet address: {}", e))?;
+            let signature = {
+                let rpc = Arc::clone(&rpc);
+                tokio::task::spawn_blocking(move || request_airdrop_to(&rpc, &recipient, AIRDROP_LAMPORTS))
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .map_err(|e| e.to_string())?
+                    .to_string()
+            };
+            tracing::info!(%signature, %address, "Airdrop requested");
+
+            state_clone.write().transactions.insert(0, TransactionItem {
+                signature: signature.clone(),
+                timestamp: chrono::Utc::now().timestamp(),
+                tx_type: AIRDROP_TX_TYPE.to_string(),
+                status: TransactionStatus::Pending.as_str().to_string(),
+                amount: format!("+{} SOL from the devnet faucet", format_token_amount(AIRDROP_LAMPORTS, SOL_DECIMALS)),
+                memo: None,
+                wallet: Some(address.clone()),
+                latency: None,
+            });
+
+            let status = wait_for_confirmation(rpc, signature.clone()).await;
+            apply_status_change(&mut state_clone.write().transactions, &signature, status);
+            match status {
+                TransactionStatus::Confirmed => Ok(signature),
+                TransactionStatus::Failed => Err("The airdrop transaction failed".to_string()),
+                _ => Err(format!(
+                    "Airdrop not confirmed after {} seconds; it may still land",
+                    CONFIRM_TIMEOUT.as_secs()
+                )),
+            }
+        }
+        .await;
+
+        let _ = event_tx.send(AppEvent::AirdropResult(result)).await;
+    });
+}
+
+/// Poll `signature` until it is confirmed or failed, or [`CONFIRM_TIMEOUT`]
+/// passes (reported as still pending)
+async fn wait_for_confirmation(rpc: Arc<RpcClient>, signature: String) -> TransactionStatus {
+    let started = Instant::now();
+    while started.elapsed() < CONFIRM_TIMEOUT {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let rpc = Arc::clone(&rpc);
+        let sig = signature.clone();
+        let polled = tokio::task::spawn_blocking(move || signature_status(&rpc, &sig)).await;
+        match polled {
+            Ok(Ok(Some((TransactionStatus::Confirmed | TransactionStatus::Finalized, _)))) => {
+                return TransactionStatus::Confirmed;
+            }
+            Ok(Ok(Some((TransactionStatus::Failed, error)))) => {
+                tracing::warn!(%signature, error = error.as_deref().unwrap_or("unknown"), "Airdrop transaction failed");
+                return TransactionStatus::Failed;
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => tracing::debug!(%signature, error = %e, "Airdrop status poll failed"),
+            Err(e) => tracing::debug!(%signature, error = %e, "Airdrop status task failed"),
+        }
+    }
+    TransactionStatus::Pending
+}
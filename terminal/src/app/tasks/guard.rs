@@ -0,0 +1,411 @@
+//! # Task Guards
+//!
+//! Spawned tasks set an in-progress flag (`fetching_prices`,
+//! `history_loading`, ...) before their first await and clear it when the
+//! result comes back. A panic or cancellation in between leaves the flag set
+//! for good, and the feature behind it disabled: a stuck `fetching_prices`
+//! turns off the REST price fallback for the rest of the session.
+//!
+//! [`spawn_guarded`] runs such a task under a [`TaskGuard`]. If the task
+//! doesn't run to completion, the guard resets the flags of its
+//! [`GuardedTask`], records the failure in the error aggregator and sends
+//! [`AppEvent::TaskFailed`]. The event handler tells the user about failed
+//! user-initiated tasks; background ones are retried by their next poll.
+
+use crate::app::events::AppEvent;
+use crate::app::handlers::refresh::RefreshTarget;
+use crate::app::state::AppState;
+use async_channel::Sender;
+use futures::FutureExt;
+use parking_lot::RwLock;
+use std::any::Any;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// Task run under a [`TaskGuard`], naming the flags it resets on failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardedTask {
+    /// REST price fallback (`terminal.fetching_prices`)
+    Prices,
+    /// Depth ladder below the chart
+    Depth,
+    /// Live Assets volatility and correlations
+    MarketAnalytics,
+    /// Live Assets mini charts
+    Sparklines,
+    /// Token detail opened from Live Assets
+    TokenDetail,
+    SwapHistory,
+    Webhooks,
+    WebhookCreate,
+    WebhookDeliveries,
+    ApiKeys,
+    ApiKeyCreate,
+    TradeImport,
+    TradeStats,
+    /// Send panel building a transfer (`send.preparing`)
+    TransferPrepare,
+    /// Transfer confirmed in its dialog (`send.sending`)
+    Transfer,
+    /// Devnet airdrop waiting to confirm (`airdrop.requesting`)
+    Airdrop,
+    /// Staking transaction being built, signed and submitted (`staking.submitting`)
+    Stake,
+    /// F5 reload; also frees its slot in the refresh supervisor
+    Refresh(RefreshTarget),
+}
+
+impl GuardedTask {
+    /// Name in logs and the error aggregator
+    pub fn name(self) -> &'static str {
+        match self {
+            GuardedTask::Prices => "price_fetch",
+            GuardedTask::Depth => "depth_fetch",
+            GuardedTask::MarketAnalytics => "market_analytics",
+            GuardedTask::Sparklines => "sparklines",
+            GuardedTask::TokenDetail => "token_detail",
+            GuardedTask::SwapHistory => "swap_history",
+            GuardedTask::Webhooks => "webhooks",
+            GuardedTask::WebhookCreate => "webhook_create",
+            GuardedTask::WebhookDeliveries => "webhook_deliveries",
+            GuardedTask::ApiKeys => "api_keys",
+            GuardedTask::ApiKeyCreate => "api_key_create",
+            GuardedTask::TradeImport => "trade_import",
+            GuardedTask::TradeStats => "trade_stats",
+            GuardedTask::TransferPrepare => "transfer_prepare",
+            GuardedTask::Transfer => "transfer",
+            GuardedTask::Airdrop => "airdrop",
+            GuardedTask::Stake => "stake",
+            GuardedTask::Refresh(target) => target.key(),
+        }
+    }
+
+    /// What failed, as shown in the notification
+    pub fn label(self) -> &'static str {
+        match self {
+            GuardedTask::Prices => "Price update",
+            GuardedTask::Depth => "Depth ladder",
+            GuardedTask::MarketAnalytics => "Market analytics",
+            GuardedTask::Sparklines => "Mini charts",
+            GuardedTask::TokenDetail => "Loading the token detail",
+            GuardedTask::SwapHistory => "Loading the swap history",
+            GuardedTask::Webhooks => "Loading webhooks",
+            GuardedTask::WebhookCreate => "Adding the webhook",
+            GuardedTask::WebhookDeliveries => "Loading webhook deliveries",
+            GuardedTask::ApiKeys => "Loading API keys",
+            GuardedTask::ApiKeyCreate => "Creating the API key",
+            GuardedTask::TradeImport => "Trade import",
+            GuardedTask::TradeStats => "Loading trade statistics",
+            GuardedTask::TransferPrepare => "Preparing the transfer",
+            GuardedTask::Transfer => "Sending the transfer",
+            GuardedTask::Airdrop => "Requesting the airdrop",
+            GuardedTask::Stake => "Submitting the staking transaction",
+            GuardedTask::Refresh(_) => "Refresh",
+        }
+    }
+
+    /// Started by the user, who waits for the result; background tasks are
+    /// started again by their poll
+    pub fn is_user_initiated(self) -> bool {
+        !matches!(
+            self,
+            GuardedTask::Prices | GuardedTask::Depth | GuardedTask::MarketAnalytics | GuardedTask::Sparklines
+        )
+    }
+
+    /// Clear the in-progress flags the task would have cleared itself
+    pub fn reset(self, state: &mut AppState) {
+        match self {
+            GuardedTask::Prices => state.terminal.fetching_prices = false,
+            GuardedTask::Depth => state.terminal.depth_loading = false,
+            GuardedTask::MarketAnalytics => state.live_assets.analytics_loading = false,
+            GuardedTask::Sparklines => state.live_assets.sparklines_loading = false,
+            GuardedTask::TokenDetail => state.live_assets.detail_loading = false,
+            GuardedTask::SwapHistory => state.terminal.swap.history_loading = false,
+            GuardedTask::Webhooks => state.webhooks.loading = false,
+            GuardedTask::WebhookCreate => state.webhooks.creating = false,
+            GuardedTask::WebhookDeliveries => state.webhooks.deliveries_loading = false,
+            GuardedTask::ApiKeys => state.api_keys.loading = false,
+            GuardedTask::ApiKeyCreate => state.api_keys.creating = false,
+            GuardedTask::TradeImport => state.trade_import.loading = false,
+            GuardedTask::TradeStats => state.trade_import.stats_loading = false,
+            GuardedTask::TransferPrepare => state.send.preparing = false,
+            GuardedTask::Transfer => state.send.sending = false,
+            GuardedTask::Airdrop => state.airdrop.requesting = false,
+            GuardedTask::Stake => state.staking.submitting = None,
+            GuardedTask::Refresh(target) => {
+                state.refresh_tasks.finish(target.key());
+                match target {
+                    RefreshTarget::Candles => state.terminal.chart_loading = false,
+                    RefreshTarget::MarketAnalytics => state.live_assets.detail_loading = false,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Resets a task's flags when it is dropped before [`TaskGuard::complete`]:
+/// on panic (after the payload was caught) and on cancellation
+pub(crate) struct TaskGuard {
+    task: GuardedTask,
+    state: Arc<RwLock<AppState>>,
+    event_tx: Sender<AppEvent>,
+    panic: Option<String>,
+    completed: bool,
+}
+
+impl TaskGuard {
+    pub(crate) fn new(task: GuardedTask, state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) -> Self {
+        Self { task, state, event_tx, panic: None, completed: false }
+    }
+
+    /// The task ran to the end and cleared its flags itself
+    pub(crate) fn complete(mut self) {
+        self.completed = true;
+    }
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        if self.completed {
+            return;
+        }
+        self.task.reset(&mut self.state.write());
+
+        let name = self.task.name();
+        let error = match self.panic.take() {
+            Some(message) => {
+                crate::debug::record_panic(message.clone(), Some(name.to_string()));
+                format!("panicked: {}", message)
+            }
+            None => {
+                crate::debug::record_warning("Task cancelled before completing".to_string(), Some(name.to_string()));
+                "cancelled".to_string()
+            }
+        };
+        tracing::error!(task = name, error = %error, "Guarded task failed, in-progress flags reset");
+        // Unbounded channel; only fails once the app is shutting down
+        crate::app::event_queue::try_send_or_drop(&self.event_tx, AppEvent::TaskFailed(self.task, error));
+    }
+}
+
+/// Run `future` under a [`TaskGuard`] for `task`
+pub(crate) async fn guarded<F>(task: GuardedTask, state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, future: F)
+where
+    F: Future<Output = ()>,
+{
+    let mut guard = TaskGuard::new(task, state, event_tx);
+    // parking_lot locks don't poison, so state written before the panic
+    // stays usable; only the flags need fixing
+    match AssertUnwindSafe(future).catch_unwind().await {
+        Ok(()) => guard.complete(),
+        Err(payload) => guard.panic = Some(panic_message(payload.as_ref())),
+    }
+}
+
+/// Spawn `future` under a [`TaskGuard`] for `task`
+pub(crate) fn spawn_guarded<F>(
+    task: GuardedTask,
+    state: Arc<RwLock<AppState>>,
+    event_tx: Sender<AppEvent>,
+    future: F,
+) -> JoinHandle<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(guarded(task, state, event_tx, future))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::tasks::market::prices_task;
+    use crate::core::service::ApiService;
+    use crate::services::api::{
+        ApiError, PriceResponse, SwapExecuteResponse, SwapHistoryItem, SwapHistoryQuery, SwapHistoryResponse,
+        SwapQuoteResponse, TokenBalance, TokenListItem, TransactionHistory, WalletBalance,
+    };
+    use shared::AuthResponse;
+    use shared::dto::transactions::TransactionVersion;
+
+    /// API whose every call panics, standing in for a bug in response handling
+    struct PanickingApi;
+
+    fn exploded() -> ! {
+        panic!("mock API exploded")
+    }
+
+    #[async_trait::async_trait]
+    impl ApiService for PanickingApi {
+        async fn login(&self, _email_or_username: String, _password: String) -> Result<AuthResponse, ApiError> {
+            exploded()
+        }
+
+        async fn signup(&self, _username: String, _email: String, _password: String) -> Result<AuthResponse, ApiError> {
+            exploded()
+        }
+
+        async fn logout(&self, _jwt_token: &str) -> Result<(), ApiError> {
+            exploded()
+        }
+
+        async fn refresh_session(&self, _jwt_token: &str) -> Result<AuthResponse, ApiError> {
+            exploded()
+        }
+
+        async fn change_password(&self, _jwt_token: &str, _current_password: String, _new_password: String) -> Result<AuthResponse, ApiError> {
+            exploded()
+        }
+
+        async fn update_profile(&self, _jwt_token: &str, _email: String) -> Result<shared::UserInfo, ApiError> {
+            exploded()
+        }
+
+        async fn get_prices(&self, _symbols: &[&str]) -> Result<PriceResponse, ApiError> {
+            exploded()
+        }
+
+        async fn get_wallet_balance(&self, _address: &str) -> Result<WalletBalance, ApiError> {
+            exploded()
+        }
+
+        async fn get_transaction_history(&self, _address: &str, _limit: usize) -> Result<TransactionHistory, ApiError> {
+            exploded()
+        }
+
+        async fn get_swap_quote(&self, _input_mint: &str, _output_mint: &str, _amount: u64, _slippage_bps: u16) -> Result<SwapQuoteResponse, ApiError> {
+            exploded()
+        }
+
+        async fn execute_swap(&self, _input_mint: &str, _output_mint: &str, _amount: u64, _slippage_bps: u16, _user_pubkey: &str, _jwt_token: &str) -> Result<SwapExecuteResponse, ApiError> {
+            exploded()
+        }
+
+        async fn simulate_swap(&self, _request: &shared::dto::simulation::SimulateSwapRequest, _jwt_token: &str) -> Result<shared::dto::simulation::SimulateSwapResponse, ApiError> {
+            exploded()
+        }
+
+        async fn submit_transaction(&self, _signed_transaction: String, _version: TransactionVersion, _input_mint: String, _output_mint: String, _input_amount: i64, _output_amount: i64, _price_impact: Option<f64>, _slippage_bps: Option<i32>, _jwt_token: &str) -> Result<crate::services::api::TransactionSubmitResponse, ApiError> {
+            exploded()
+        }
+
+        async fn update_transaction_status(&self, _signature: &str, _update: &shared::dto::transactions::TransactionStatusUpdate, _jwt_token: &str) -> Result<(), ApiError> {
+            exploded()
+        }
+
+        async fn record_transaction_latency(&self, _signature: &str, _latency: &shared::dto::latency::SwapLatency, _jwt_token: &str) -> Result<(), ApiError> {
+            exploded()
+        }
+
+        async fn get_token_balances(&self, _address: &str) -> Result<Vec<TokenBalance>, ApiError> {
+            exploded()
+        }
+
+        async fn get_token_list(&self) -> Result<Vec<TokenListItem>, ApiError> {
+            exploded()
+        }
+
+        async fn get_swap_history(&self, _jwt_token: &str, _query: &SwapHistoryQuery) -> Result<SwapHistoryResponse, ApiError> {
+            exploded()
+        }
+
+        async fn stream_history(&self, _jwt_token: &str, _query: &SwapHistoryQuery, _on_batch: &mut (dyn for<'b> FnMut(&'b [SwapHistoryItem]) -> Result<(), String> + Send)) -> Result<u64, String> {
+            exploded()
+        }
+
+        async fn import_trades(&self, _jwt_token: &str, _request: &shared::dto::trades::TradeImportRequest) -> Result<shared::dto::trades::TradeImportResponse, ApiError> {
+            exploded()
+        }
+
+        async fn get_trade_stats(&self, _jwt_token: &str) -> Result<shared::dto::trades::TradeStatsResponse, ApiError> {
+            exploded()
+        }
+
+        async fn get_candles(&self, _symbol: &str, _timeframe: &str, _limit: usize) -> Result<Vec<shared::dto::market::OHLC>, ApiError> {
+            exploded()
+        }
+
+        async fn get_candles_batch(&self, _symbols: &[String], _timeframe: &str) -> Result<shared::dto::market::CandleBatchResponse, ApiError> {
+            exploded()
+        }
+
+        async fn get_streamed_symbols(&self) -> Result<shared::dto::market::StreamedSymbolsResponse, ApiError> {
+            exploded()
+        }
+
+        async fn get_token_unlocks(&self, _days: i64) -> Result<shared::dto::unlocks::TokenUnlocksResponse, ApiError> {
+            exploded()
+        }
+
+        async fn get_depth(&self, _input: &str, _output: &str) -> Result<shared::dto::market::DepthResponse, ApiError> {
+            exploded()
+        }
+
+        async fn get_market_analytics(&self, _symbols: &[String], _window: usize) -> Result<shared::dto::market::MarketAnalyticsResponse, ApiError> {
+            exploded()
+        }
+
+        async fn get_health(&self) -> Result<shared::dto::system::HealthResponse, ApiError> {
+            exploded()
+        }
+    }
+
+    fn test_state() -> Arc<RwLock<AppState>> {
+        Arc::new(RwLock::new(crate::app::App::new().state.read().clone()))
+    }
+
+    #[tokio::test]
+    async fn test_panicking_price_fetch_resets_flag_and_reports() {
+        let state = test_state();
+        state.write().terminal.fetching_prices = true;
+        let (tx, rx) = async_channel::unbounded();
+
+        let task = prices_task(Arc::new(PanickingApi), vec!["SOL".to_string()], Arc::clone(&state), tx.clone());
+        spawn_guarded(GuardedTask::Prices, Arc::clone(&state), tx, task).await.unwrap();
+
+        assert!(!state.read().terminal.fetching_prices);
+        match rx.try_recv() {
+            Ok(AppEvent::TaskFailed(GuardedTask::Prices, error)) => assert_eq!(error, "panicked: mock API exploded"),
+            other => panic!("expected TaskFailed, got {:?}", other),
+        }
+        assert!(!GuardedTask::Prices.is_user_initiated());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_task_resets_flag_and_reports() {
+        let state = test_state();
+        state.write().terminal.swap.history_loading = true;
+        let (tx, rx) = async_channel::unbounded();
+
+        let handle = spawn_guarded(GuardedTask::SwapHistory, Arc::clone(&state), tx, std::future::pending());
+        handle.abort();
+        assert!(handle.await.unwrap_err().is_cancelled());
+
+        assert!(!state.read().terminal.swap.history_loading);
+        match rx.try_recv() {
+            Ok(AppEvent::TaskFailed(GuardedTask::SwapHistory, error)) => assert_eq!(error, "cancelled"),
+            other => panic!("expected TaskFailed, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_completed_task_reports_nothing() {
+        let state = test_state();
+        let (tx, rx) = async_channel::unbounded();
+        spawn_guarded(GuardedTask::Depth, Arc::clone(&state), tx, async {}).await.unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! # Backend Health Polling
+//!
+//! Polls `GET /api/health` so the status bar reflects whether the backend and
+//! its dependencies are actually up, rather than whether we hold a JWT. The
+//! report also drives [`crate::app::FeatureGates`].
+//!
+//! While the API client is on a standby server, each poll also probes the
+//! primary so the client can move back once it is stable.
+//!
+//! Polls are skipped while the app is in low-power mode.
+//!
+//! The poll also refreshes the price stream's symbols and, less often, the
+//! upcoming token unlocks and, if the user opted in, uploads usage counts.
+//!
+//! While the backend is down for announced maintenance, health is checked
+//! every [`MAINTENANCE_POLL_INTERVAL`] instead, and nothing else is fetched;
+//! the first passing check ends the maintenance.
+//!
+//! Two watchers forward what the API client reports on its own channels:
+//! server switches, and rejected sessions or timed-out requests.
+
+use crate::app::events::AppEvent;
+use crate::app::state::AppState;
+use crate::core::service::ApiService;
+use crate::services::api::failover::ServerSwitch;
+use crate::services::api::ApiError;
+use async_channel::Sender;
+use parking_lot::RwLock;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Time between backend health checks
+pub(crate) const HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Time between health checks while the backend is down for maintenance
+pub(crate) const MAINTENANCE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Health polls between token unlock refreshes (about ten minutes)
+const UNLOCK_REFRESH_POLLS: u64 = 20;
+
+/// Health polls between usage analytics uploads (about an hour)
+const USAGE_UPLOAD_POLLS: u64 = 120;
+
+/// Run a single health check
+///
+/// Internal task function - used after login and when the user asks for a
+/// check from the status bar.
+pub(crate) fn check_health(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    tokio::spawn(async move {
+        let Some(api_client) = state.read().api_client.clone() else {
+            return;
+        };
+        let result = api_client.get_health().await.map_err(|e| e.to_string());
+        let _ = event_tx.send(AppEvent::HealthResult(result)).await;
+    });
+}
+
+/// Start polling backend health
+///
+/// Internal task function - checks immediately, then every [`HEALTH_POLL_INTERVAL`]
+/// until the event channel is closed.
+pub(crate) fn start_health_polling(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    tokio::spawn(async move {
+        let mut polls = 0u64;
+        let mut first = true;
+
+        loop {
+            if !first {
+                let delay = if state.read().maintenance.is_down() {
+                    MAINTENANCE_POLL_INTERVAL
+                } else {
+                    HEALTH_POLL_INTERVAL
+                };
+                tokio::time::sleep(delay).await;
+            }
+            first = false;
+
+            let (api_client, maintenance) = {
+                let state = state.read();
+                if state.idle.is_low_power() {
+                    continue;
+                }
+                (state.api_client.clone(), state.maintenance.is_down())
+            };
+            let Some(api_client) = api_client else {
+                continue;
+            };
+            if maintenance {
+                // Only readiness matters until the backend is back
+                let result = api_client.get_health().await.map_err(|e| e.to_string());
+                if event_tx.send(AppEvent::HealthResult(result)).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+            polls += 1;
+            // Sticky failover: return to the primary once it has been healthy for a while
+            api_client.failover().check_primary().await;
+
+            let result = api_client.get_health().await.map_err(|e| e.to_string());
+            if event_tx.send(AppEvent::HealthResult(result)).await.is_err() {
+                tracing::debug!("Event channel closed - stopping health polling");
+                break;
+            }
+
+            // Pick up symbols an admin added or removed since the last poll
+            if let Ok(response) = api_client.get_streamed_symbols().await {
+                let symbols = response.symbols.into_iter().map(|s| s.symbol).collect();
+                let _ = event_tx.send(AppEvent::StreamedSymbolsResult(Ok(symbols))).await;
+            }
+
+            // Unlocks change rarely, but "approaching" is re-judged on every refresh
+            if polls % UNLOCK_REFRESH_POLLS == 1 {
+                let result = api_client
+                    .get_token_unlocks(crate::app::handlers::unlocks::UNLOCK_FETCH_DAYS)
+                    .await
+                    .map_err(|e| e.to_string());
+                let _ = event_tx.send(AppEvent::TokenUnlocksResult(result)).await;
+            }
+
+            if polls % USAGE_UPLOAD_POLLS == 1 {
+                super::usage::upload_usage(&state, &api_client).await;
+            }
+        }
+    });
+}
+
+/// Forward API client server switches to the app
+///
+/// Internal task function - runs until either channel is closed.
+pub(crate) fn watch_server_switches(switches: async_channel::Receiver<ServerSwitch>, event_tx: Sender<AppEvent>) {
+    tokio::spawn(async move {
+        while let Ok(switch) = switches.recv().await {
+            if event_tx.send(AppEvent::ServerSwitched(switch)).await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Forward API client failures (expired session, timeouts) to the app
+///
+/// Internal task function - runs until either channel is closed.
+pub(crate) fn watch_api_failures(failures: async_channel::Receiver<ApiError>, event_tx: Sender<AppEvent>) {
+    tokio::spawn(async move {
+        while let Ok(failure) = failures.recv().await {
+            if event_tx.send(AppEvent::ApiFailure(failure)).await.is_err() {
+                break;
+            }
+        }
+    });
+}
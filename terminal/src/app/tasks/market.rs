@@ -1,16 +1,22 @@
 //! # Market Data Tasks
 //!
-//! Async tasks for fetching market data including prices and token lists.
+//! Async tasks for fetching market data including prices, token lists and the
+//! backend's streamed symbol list.
 
 use crate::app::state::{AppState, PriceData, TokenInfo};
 use crate::app::events::AppEvent;
+use crate::app::tasks::guard::{spawn_guarded, GuardedTask};
 use crate::core::service::ApiService;
 use async_channel::Sender;
 use parking_lot::RwLock;
+use std::future::Future;
 use std::sync::Arc;
 use tokio::spawn;
 use tracing::{info, debug, warn};
 
+/// Symbols requested before the backend's streamed symbol list has loaded
+const FALLBACK_SYMBOLS: [&str; 7] = ["SOL", "USDC", "BTC", "ETH", "USDT", "JUP", "RAY"];
+
 /// Fetch prices from backend API
 ///
 /// Internal task function - spawns async task to fetch prices and send results via event channel.
@@ -26,56 +32,80 @@ pub(crate) fn fetch_prices(
         if state.terminal.fetching_prices {
             return;
         }
+        let Some(api_client) = state.api.clone() else {
+            return;
+        };
 
         state.terminal.fetching_prices = true;
         state.terminal.last_price_update = std::time::Instant::now();
-        state.api_client.clone()
+        let symbols = if state.terminal.streamed_symbols.is_empty() {
+            FALLBACK_SYMBOLS.iter().map(|s| s.to_string()).collect()
+        } else {
+            state.terminal.streamed_symbols.clone()
+        };
+        (api_client, symbols)
     }; // Lock released here
 
-    if let Some(api_client) = should_fetch {
-        let state_arc = Arc::clone(&state);
+    let (api_client, symbols) = should_fetch;
+    let task = prices_task(api_client, symbols, Arc::clone(&state), event_tx.clone());
+    spawn_guarded(GuardedTask::Prices, state, event_tx, task);
+}
 
-        spawn(async move {
-            let symbols = ["SOL", "USDC", "BTC", "ETH", "USDT", "JUP", "RAY"];
-            let result = api_client.get_prices(&symbols).await;
-
-            // Always reset fetching flag when done
-            // CRITICAL: Release lock immediately to prevent deadlock with main thread
-            {
-                let mut state = state_arc.write();
-                state.terminal.fetching_prices = false;
-                // Lock released here automatically
-            }
+/// Task fetching `symbols` over REST into the price store; clears
+/// `fetching_prices` when the request returns
+pub(crate) async fn prices_task<A: ApiService + ?Sized>(
+    api_client: Arc<A>,
+    symbols: Vec<String>,
+    state: Arc<RwLock<AppState>>,
+    event_tx: Sender<AppEvent>,
+) {
+    let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+    let result = api_client.get_prices(&symbols).await;
+
+    // Always reset fetching flag when done
+    // CRITICAL: Release lock immediately to prevent deadlock with main thread
+    let store = {
+        let mut state = state.write();
+        state.terminal.fetching_prices = false;
+        state.terminal.prices.clone()
+        // Lock released here automatically
+    };
 
-            match result {
-                Ok(response) => {
-                    let prices: Vec<PriceData> = response
-                        .prices
-                        .iter()
-                        .map(|(symbol, data)| PriceData {
-                            symbol: symbol.clone(),
-                            price: data.price,
-                            change_24h: data.change_24h.unwrap_or(0.0),
-                            previous_price: None, // Will be set when event is processed
-                            source: Some(data.source.clone()),
-                        })
-                        .collect();
-                    tracing::info!(
-                        price_count = prices.len(),
-                        symbols = ?prices.iter().map(|p| p.symbol.clone()).collect::<Vec<_>>(),
-                        "REST API: Fetched prices successfully - sending to event channel"
-                    );
-                    let _ = event_tx.send(AppEvent::PricesUpdated(prices)).await;
-                }
-                Err(e) => {
-                    tracing::error!(
-                        error = %e,
-                        "REST API: Failed to fetch prices - will retry on next fallback trigger"
-                    );
-                    // Silently fail - keep showing last known prices
-                }
+    match result {
+        Ok(response) => {
+            let prices: Vec<PriceData> = response
+                .prices
+                .iter()
+                .map(|(symbol, data)| PriceData {
+                    symbol: symbol.clone(),
+                    price: data.price,
+                    change_24h: data.change_24h.unwrap_or(0.0),
+                    previous_price: None, // Set by the price store
+                    source: Some(data.source.clone()),
+                    confidence: data.confidence,
+                    publish_time: data.publish_time,
+                    sources: data.sources.clone(),
+                    divergent: data.divergent,
+                    divergence_pct: PriceData::spread_pct(&data.sources),
+                })
+                .collect();
+            tracing::info!(
+                price_count = prices.len(),
+                symbols = ?prices.iter().map(|p| p.symbol.clone()).collect::<Vec<_>>(),
+                "REST API: Fetched prices successfully - publishing to price store"
+            );
+            // Merge rather than replace so symbols only streamed over the WebSocket survive
+            if store.apply(&prices) {
+                crate::app::event_queue::send_price(&event_tx, AppEvent::PricesChanged).await;
             }
-        });
+        }
+        Err(e) => {
+            tracing::error!(
+                error = %e,
+                "REST API: Failed to fetch prices - will retry on next fallback trigger"
+            );
+            // Silently fail - keep showing last known prices
+        }
     }
 }
 
@@ -86,39 +116,201 @@ pub(crate) fn fetch_token_list(
     state: Arc<RwLock<AppState>>,
     event_tx: Sender<AppEvent>,
 ) {
-    let api_client = {
-        let state = state.read();
-        state.api_client.clone()
-    };
+    let task = token_list_task(&state.read(), event_tx);
+    if let Some(task) = task {
+        spawn(task);
+    }
+}
+
+/// Task loading the token list, `None` without an API client
+pub(crate) fn token_list_task(
+    state: &AppState,
+    event_tx: Sender<AppEvent>,
+) -> Option<impl Future<Output = ()> + Send + 'static> {
+    let api_client = state.api.clone()?;
+
+    Some(async move {
+        let result = api_client.get_token_list().await;
+
+        match result {
+            Ok(token_list) => {
+                let tokens: Vec<TokenInfo> = token_list
+                    .iter()
+                    .map(|token| TokenInfo {
+                        symbol: token.symbol.clone(),
+                        name: token.name.clone(),
+                        mint: token.mint.clone(),
+                        decimals: token.decimals,
+                        price: 0.0, // Price will be populated from price feed
+                        balance: 0.0,
+                        change_24h: 0.0,
+                        is_favorite: false,
+                        verified: token.verified,
+                        daily_volume: token.daily_volume,
+                    })
+                    .collect();
+                let _ = event_tx.send(AppEvent::TokenListResult(Ok(tokens))).await;
+            }
+            Err(e) => {
+                let _ = event_tx.send(AppEvent::TokenListResult(Err(e.to_string()))).await;
+            }
+        }
+    })
+}
+
+/// Fetch the symbol list the backend price stream tracks
+///
+/// Internal task function - the result replaces the fallback symbols used by
+/// [`fetch_prices`].
+pub(crate) fn fetch_streamed_symbols(
+    state: Arc<RwLock<AppState>>,
+    event_tx: Sender<AppEvent>,
+) {
+    let api_client = state.read().api.clone();
 
     if let Some(api_client) = api_client {
         spawn(async move {
-            let result = api_client.get_token_list().await;
-
-            match result {
-                Ok(token_list) => {
-                    let tokens: Vec<TokenInfo> = token_list
-                        .iter()
-                        .map(|token| TokenInfo {
-                            symbol: token.symbol.clone(),
-                            name: token.name.clone(),
-                            mint: token.mint.clone(),
-                            price: 0.0, // Price will be populated from price feed
-                            balance: 0.0,
-                            change_24h: 0.0,
-                            is_favorite: false,
-                        })
-                        .collect();
-                    let _ = event_tx.send(AppEvent::TokenListResult(Ok(tokens))).await;
-                }
-                Err(e) => {
-                    let _ = event_tx.send(AppEvent::TokenListResult(Err(e))).await;
-                }
-            }
+            let result = api_client
+                .get_streamed_symbols()
+                .await
+                .map_err(|e| e.to_string())
+                .map(|response| response.symbols.into_iter().map(|s| s.symbol).collect());
+            let _ = event_tx.send(AppEvent::StreamedSymbolsResult(result)).await;
         });
     }
 }
 
+/// Minimum time between depth ladder requests (matches the backend cache TTL)
+pub(crate) const DEPTH_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Fetch the route-quote depth ladder for a token pair.
+///
+/// Internal task function - skips the request if one is in flight or the last
+/// one was less than [`DEPTH_REFRESH_INTERVAL`] ago.
+pub(crate) fn fetch_depth(
+    state: Arc<RwLock<AppState>>,
+    event_tx: Sender<AppEvent>,
+    input: String,
+    output: String,
+) {
+    let api_client = {
+        let mut state = state.write();
+        let recent = state
+            .terminal
+            .last_depth_fetch
+            .is_some_and(|t| t.elapsed() < DEPTH_REFRESH_INTERVAL);
+        if state.terminal.depth_loading || recent {
+            return;
+        }
+        let Some(api_client) = state.api.clone() else {
+            return;
+        };
+        state.terminal.depth_loading = true;
+        state.terminal.last_depth_fetch = Some(std::time::Instant::now());
+        api_client
+    };
+
+    debug!(input = %input, output = %output, "Fetching depth ladder");
+    let tx = event_tx.clone();
+    spawn_guarded(GuardedTask::Depth, state, event_tx, async move {
+        let result = api_client.get_depth(&input, &output).await.map_err(|e| e.to_string());
+        let _ = tx.send(AppEvent::DepthResult(result)).await;
+    });
+}
+
+/// Minimum time between identical analytics requests (matches the backend cache TTL)
+pub(crate) const ANALYTICS_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Most symbols in one analytics request (the backend's limit)
+pub(crate) const MAX_ANALYTICS_SYMBOLS: usize = 20;
+
+/// Fetch volatility and correlations for the live price symbols over the
+/// selected window.
+///
+/// Internal task function - skips the request if one is in flight or the same
+/// symbols and window were requested less than [`ANALYTICS_REFRESH_INTERVAL`] ago.
+pub(crate) fn fetch_market_analytics(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (api_client, symbols, window) = {
+        let mut state = state.write();
+        let mut symbols: Vec<String> = state.terminal.prices.load().iter().map(|p| p.symbol.clone()).collect();
+        symbols.sort();
+        symbols.dedup();
+        symbols.truncate(MAX_ANALYTICS_SYMBOLS);
+
+        let live_assets = &state.live_assets;
+        let window = live_assets.window_days;
+        let recent = live_assets.last_analytics_fetch.as_ref().is_some_and(|(last_symbols, last_window, at)| {
+            *last_symbols == symbols && *last_window == window && at.elapsed() < ANALYTICS_REFRESH_INTERVAL
+        });
+        if live_assets.analytics_loading || recent || symbols.is_empty() {
+            return;
+        }
+        let Some(api_client) = state.api.clone() else {
+            return;
+        };
+        state.live_assets.analytics_loading = true;
+        state.live_assets.last_analytics_fetch = Some((symbols.clone(), window, std::time::Instant::now()));
+        (api_client, symbols, window)
+    };
+
+    debug!(count = symbols.len(), window = window, "Fetching market analytics");
+    let tx = event_tx.clone();
+    spawn_guarded(GuardedTask::MarketAnalytics, state, event_tx, async move {
+        let result = api_client.get_market_analytics(&symbols, window).await.map_err(|e| e.to_string());
+        let _ = tx.send(AppEvent::MarketAnalyticsResult(result)).await;
+    });
+}
+
+/// Minimum time between identical mini chart requests
+pub(crate) const SPARKLINE_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Timeframe of the Live Assets mini charts
+pub(crate) const SPARKLINE_TIMEFRAME: &str = "1h";
+
+/// Candles in a mini chart, and summed into the Live Assets volume: one day
+pub(crate) const SPARKLINE_CANDLES: usize = 24;
+
+/// Fetch the candles behind the Live Assets mini charts, all symbols in one
+/// request.
+///
+/// Internal task function - skips the request if one is in flight or the same
+/// symbols were requested less than [`SPARKLINE_REFRESH_INTERVAL`] ago.
+pub(crate) fn fetch_sparklines(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (api_client, symbols) = {
+        let mut state = state.write();
+        let mut symbols: Vec<String> = state.terminal.prices.load().iter().map(|p| p.symbol.clone()).collect();
+        symbols.sort();
+        symbols.dedup();
+        // Same per-request limit as analytics
+        symbols.truncate(MAX_ANALYTICS_SYMBOLS);
+
+        let live_assets = &state.live_assets;
+        let recent = live_assets
+            .last_sparkline_fetch
+            .as_ref()
+            .is_some_and(|(last_symbols, at)| *last_symbols == symbols && at.elapsed() < SPARKLINE_REFRESH_INTERVAL);
+        if live_assets.sparklines_loading || recent || symbols.is_empty() || state.idle.is_low_power() {
+            return;
+        }
+        let Some(api_client) = state.api.clone() else {
+            return;
+        };
+        state.live_assets.sparklines_loading = true;
+        state.live_assets.last_sparkline_fetch = Some((symbols.clone(), std::time::Instant::now()));
+        (api_client, symbols)
+    };
+
+    debug!(count = symbols.len(), "Fetching mini chart candles");
+    let tx = event_tx.clone();
+    spawn_guarded(GuardedTask::Sparklines, state, event_tx, async move {
+        let result = api_client
+            .get_candles_batch(&symbols, SPARKLINE_TIMEFRAME)
+            .await
+            .map_err(|e| e.to_string());
+        let _ = tx.send(AppEvent::SparklinesResult(result)).await;
+    });
+}
+
 /// Fetch OHLC candlestick data for a token.
 ///
 /// Internal task function - spawns async task to fetch candles and send results via event channel.
@@ -128,56 +320,195 @@ pub(crate) fn fetch_candles(
     symbol: String,
     timeframe: shared::dto::market::Timeframe,
 ) {
-    let api_client = {
-        let state = state.read();
-        state.api_client.clone()
+    let task = candles_task(&state.read(), event_tx, symbol, timeframe);
+    if let Some(task) = task {
+        spawn(task);
+    }
+}
+
+/// Candles loaded for a chart, and kept as streamed ones are appended
+pub(crate) const CHART_CANDLES: usize = 100;
+
+/// Task loading candles for a symbol and timeframe, `None` without an API client
+pub(crate) fn candles_task(
+    state: &AppState,
+    event_tx: Sender<AppEvent>,
+    symbol: String,
+    timeframe: shared::dto::market::Timeframe,
+) -> Option<impl Future<Output = ()> + Send + 'static> {
+    let api_client = state.api.clone()?;
+
+    let timeframe_str = match timeframe {
+        shared::dto::market::Timeframe::OneMinute => "1m",
+        shared::dto::market::Timeframe::FiveMinutes => "5m",
+        shared::dto::market::Timeframe::FifteenMinutes => "15m",
+        shared::dto::market::Timeframe::OneHour => "1h",
+        shared::dto::market::Timeframe::FourHours => "4h",
+        shared::dto::market::Timeframe::OneDay => "1d",
+        shared::dto::market::Timeframe::OneWeek => "1w", // Not supported by backend, but handle gracefully
     };
 
+    info!(
+        symbol = %symbol,
+        timeframe = %timeframe_str,
+        limit = CHART_CANDLES,
+        "Fetching candles from API"
+    );
+
+    Some(async move {
+        let start = std::time::Instant::now();
+        let result = api_client.get_candles(&symbol, timeframe_str, CHART_CANDLES).await.map_err(|e| e.to_string());
+        let duration = start.elapsed();
+
+        match &result {
+            Ok(candles) => {
+                debug!(
+                    symbol = %symbol,
+                    timeframe = %timeframe_str,
+                    count = candles.len(),
+                    duration_ms = duration.as_millis(),
+                    "Candles fetched successfully"
+                );
+            }
+            Err(e) => {
+                warn!(
+                    symbol = %symbol,
+                    timeframe = %timeframe_str,
+                    error = %e,
+                    duration_ms = duration.as_millis(),
+                    "Failed to fetch candles"
+                );
+            }
+        }
+
+        let _ = event_tx.send(AppEvent::CandlesResult(result)).await;
+    })
+}
+
+/// Prefetch a token's detail after hover intent, into the detail cache.
+///
+/// Skipped in bandwidth saver mode, when the cache is still fresh, and when
+/// the supervisor is at its in-flight limit.
+pub(crate) fn prefetch_token_detail(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, symbol: String) {
+    let mut state = state.write();
+    if state.settings.network.bandwidth_saver {
+        return;
+    }
+    let Some(api_client) = state.api.clone() else {
+        return;
+    };
+    let live_assets = &mut state.live_assets;
+    if live_assets.details.get_fresh(&symbol, std::time::Instant::now()).is_some() {
+        return;
+    }
+
+    let task = load_token_detail(api_client, event_tx, symbol.clone(), true);
+    let outcome = live_assets.prefetch.spawn(&symbol, task);
+    debug!(symbol = %symbol, outcome = ?outcome, "Token detail prefetch");
+}
+
+/// Load a token's detail for the detail view, outside the prefetch limit
+pub(crate) fn fetch_token_detail(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>, symbol: String) {
+    let api_client = state.read().api.clone();
     if let Some(api_client) = api_client {
-        let timeframe_str = match timeframe {
-            shared::dto::market::Timeframe::OneMinute => "1m",
-            shared::dto::market::Timeframe::FiveMinutes => "5m",
-            shared::dto::market::Timeframe::FifteenMinutes => "15m",
-            shared::dto::market::Timeframe::OneHour => "1h",
-            shared::dto::market::Timeframe::FourHours => "4h",
-            shared::dto::market::Timeframe::OneDay => "1d",
-            shared::dto::market::Timeframe::OneWeek => "1w", // Not supported by backend, but handle gracefully
-        };
-        
-        info!(
-            symbol = %symbol,
-            timeframe = %timeframe_str,
-            limit = 100,
-            "Fetching candles from API"
-        );
-        
-        spawn(async move {
-            let start = std::time::Instant::now();
-            let result = api_client.get_candles(&symbol, timeframe_str, 100).await;
-            let duration = start.elapsed();
-            
-            match &result {
-                Ok(candles) => {
-                    debug!(
-                        symbol = %symbol,
-                        timeframe = %timeframe_str,
-                        count = candles.len(),
-                        duration_ms = duration.as_millis(),
-                        "Candles fetched successfully"
-                    );
-                }
-                Err(e) => {
-                    warn!(
-                        symbol = %symbol,
-                        timeframe = %timeframe_str,
-                        error = %e,
-                        duration_ms = duration.as_millis(),
-                        "Failed to fetch candles"
-                    );
-                }
+        let task = load_token_detail(api_client, event_tx.clone(), symbol, false);
+        spawn_guarded(GuardedTask::TokenDetail, state, event_tx, task);
+    }
+}
+
+/// Load a token's detail and report it as [`AppEvent::TokenDetailResult`]
+pub(crate) async fn load_token_detail(
+    api_client: Arc<dyn ApiService>,
+    event_tx: Sender<AppEvent>,
+    symbol: String,
+    prefetch: bool,
+) {
+    use crate::app::preload::{TokenDetail, PRELOAD_CANDLES, PRELOAD_TIMEFRAME};
+
+    let result = api_client
+        .get_candles(&symbol, "1h", PRELOAD_CANDLES)
+        .await
+        .map_err(|e| e.to_string())
+        .map(|candles| TokenDetail::new(PRELOAD_TIMEFRAME, candles));
+    if let Err(e) = &result {
+        warn!(symbol = %symbol, prefetch, error = %e, "Failed to load token detail");
+    }
+    let _ = event_tx.send(AppEvent::TokenDetailResult { symbol, prefetch, result }).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mock::MockApiService;
+    use crate::services::api::ApiError;
+    use std::time::Duration;
+
+    fn test_state(api: &Arc<MockApiService>) -> Arc<RwLock<AppState>> {
+        let mut state = crate::app::App::new().state.read().clone();
+        state.api = Some(Arc::clone(api) as Arc<dyn ApiService>);
+        Arc::new(RwLock::new(state))
+    }
+
+    /// Wait for the price fetch in flight to return
+    async fn prices_fetched(state: &Arc<RwLock<AppState>>) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while state.read().terminal.fetching_prices {
+                tokio::task::yield_now().await;
             }
-            
-            let _ = event_tx.send(AppEvent::CandlesResult(result)).await;
-        });
+        })
+        .await
+        .expect("price fetch never returned");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_prices_error_keeps_last_prices() {
+        let api = Arc::new(MockApiService::new());
+        api.respond::<Result<crate::services::api::PriceResponse, ApiError>>("get_prices", Err(ApiError::Timeout));
+        let state = test_state(&api);
+        state.write().terminal.prices.apply(&[PriceData {
+            symbol: "SOL".to_string(),
+            price: 145.0,
+            change_24h: 0.0,
+            previous_price: None,
+            source: Some("jupiter".to_string()),
+            confidence: None,
+            publish_time: None,
+            sources: Default::default(),
+            divergent: false,
+            divergence_pct: None,
+        }]);
+        let (tx, rx) = async_channel::unbounded();
+
+        fetch_prices(Arc::clone(&state), tx);
+        prices_fetched(&state).await;
+
+        assert_eq!(api.calls_to("get_prices"), [vec![format!("{:?}", FALLBACK_SYMBOLS)]]);
+        let prices = state.read().terminal.prices.load();
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices[0].price, 145.0);
+        // Failures stay quiet: no refresh, no error toast
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_prices_skips_while_in_flight_or_without_backend() {
+        let api = Arc::new(MockApiService::new());
+        let state = test_state(&api);
+        let (tx, _rx) = async_channel::unbounded();
+
+        state.write().terminal.fetching_prices = true;
+        fetch_prices(Arc::clone(&state), tx.clone());
+        tokio::task::yield_now().await;
+        assert!(api.calls().is_empty());
+
+        // Without a backend the flag must not get stuck, or no fetch ever runs again
+        {
+            let mut state = state.write();
+            state.terminal.fetching_prices = false;
+            state.api = None;
+        }
+        fetch_prices(Arc::clone(&state), tx);
+        assert!(!state.read().terminal.fetching_prices);
+        assert!(api.calls().is_empty());
     }
 }
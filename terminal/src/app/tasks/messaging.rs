@@ -0,0 +1,35 @@
+//! # Messaging Tasks
+//!
+//! Background loading of the friends list, which also carries each
+//! conversation's unread count for the Messaging badge in the nav bar.
+
+use crate::app::state::AppState;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Load friends, friend requests and unread counts
+///
+/// Internal task function - used after login so the nav bar badge is right
+/// before the Messaging screen is first opened, and by that screen itself.
+pub(crate) fn load_friends(state: Arc<RwLock<AppState>>) {
+    let (api_client, token) = {
+        let state = state.read();
+        let (Some(api_client), Some(token)) = (state.api_client.clone(), state.auth_token.clone()) else {
+            return;
+        };
+        (api_client, token)
+    };
+
+    tokio::spawn(async move {
+        match api_client.get_friends(&token).await {
+            Ok(friends_list) => {
+                let mut state = state.write();
+                let current_user_id = state.current_user.as_ref().map(|u| u.id);
+                state.messaging.apply_friends_list(friends_list, current_user_id);
+            }
+            Err(e) => {
+                eprintln!("Failed to load friends: {}", e);
+            }
+        }
+    });
+}
@@ -1,7 +1,22 @@
 //! # Async Tasks
 //!
-//! Async task spawning for market data, swap operations, and other background tasks.
+//! Async task spawning for market data, swap operations, wallet transfers,
+//! devnet airdrops, staking, transaction status tracking, backend health polling,
+//! usage analytics uploads, friends and unread counts, and other background
+//! tasks.
+//!
+//! Tasks that set an in-progress flag before awaiting are spawned through
+//! [`guard::spawn_guarded`], which clears the flag again if they panic or
+//! are cancelled.
 
+pub mod airdrop;
+pub mod guard;
+pub mod health;
 pub mod market;
+pub mod messaging;
+pub mod staking;
 pub mod swap;
+pub mod transfer;
+pub mod tx_status;
+pub mod usage;
 
@@ -0,0 +1,198 @@
+//! # Staking Tasks
+//!
+//! Async tasks behind the Wallet screen's Staking panel: loading the stake
+//! accounts and rewards, and getting a staking transaction built by the
+//! backend, then signing and submitting it. See
+//! [`crate::app::handlers::staking`] for the flow.
+
+use crate::app::audit::{self, AuditCategory, AuditReference};
+use crate::app::events::AppEvent;
+use crate::app::handlers::staking::{check_stake_transaction, draft_delegation, StakeAction, REWARD_EPOCHS, STAKE_TX_TYPE};
+use crate::app::state::{AppState, TransactionItem};
+use crate::app::tasks::guard::{spawn_guarded, GuardedTask};
+use crate::app::tasks::transfer::SOL_MINT;
+use crate::core::service::ApiService;
+use crate::services::token_transfer::{format_token_amount, SOL_DECIMALS};
+use crate::services::wallet::WalletTransaction;
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::staking::{DelegateStakeRequest, StakeAccountRequest};
+use shared::dto::transactions::TransactionStatus;
+use std::sync::Arc;
+
+/// Load the active wallet's stake accounts and rewards
+///
+/// Internal task function - spawns async task to fetch both and send results via event channel.
+pub(crate) fn load_staking(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (address, api_client) = {
+        let mut state_guard = state.write();
+        let Some(address) = state_guard.wallet.as_ref().map(|wallet| wallet.address.clone()) else {
+            return;
+        };
+        let Some(api_client) = state_guard.api_client.clone() else {
+            state_guard.staking.error = Some("Not connected to a server".to_string());
+            return;
+        };
+        let staking = &mut state_guard.staking;
+        if staking.address.as_deref() != Some(address.as_str()) {
+            // Another wallet's accounts must not show under this one
+            staking.info = None;
+            staking.rewards.clear();
+            staking.confirm_deactivate = None;
+            staking.address = Some(address.clone());
+        } else if staking.loading {
+            return;
+        }
+        staking.loading = true;
+        staking.error = None;
+        (address, api_client)
+    };
+
+    tokio::spawn(async move {
+        let (info, rewards) = tokio::join!(
+            api_client.get_staking_info(&address),
+            api_client.get_staking_rewards(&address, REWARD_EPOCHS),
+        );
+        let _ = event_tx
+            .send(AppEvent::StakingInfoResult { address: address.clone(), result: info.map_err(|e| e.to_string()) })
+            .await;
+        let _ = event_tx
+            .send(AppEvent::StakingRewardsResult { address, result: rewards.map_err(|e| e.to_string()) })
+            .await;
+    });
+}
+
+/// What to ask the backend to build
+enum StakeRequest {
+    Delegate(DelegateStakeRequest),
+    Deactivate(StakeAccountRequest),
+    Withdraw(StakeAccountRequest),
+}
+
+/// Have the backend build a staking transaction, then sign and submit it
+///
+/// `stake_account` is the account to deactivate or withdraw; staking takes
+/// its request from the panel's form. The backend records the transaction
+/// through the swap submit path with SOL on both sides.
+///
+/// Internal task function - spawns async task to run the action and send the result via event channel.
+pub(crate) fn run_stake_action(
+    state: Arc<RwLock<AppState>>,
+    event_tx: Sender<AppEvent>,
+    action: StakeAction,
+    stake_account: Option<String>,
+) {
+    let (request, wallet, auth_token, api_client) = {
+        let mut guard = state.write();
+        let state_guard = &mut *guard;
+        if state_guard.staking.submitting.is_some() {
+            return;
+        }
+        let Some(wallet_state) = state_guard.wallet.as_ref() else {
+            state_guard.staking.form_error = Some("Connect a wallet first".to_string());
+            return;
+        };
+        let (Some(auth_token), Some(api_client)) = (state_guard.auth_token.clone(), state_guard.api_client.clone()) else {
+            state_guard.staking.form_error = Some("Log in to stake".to_string());
+            return;
+        };
+        let wallet = wallet_state.address.clone();
+        let account_request = |stake_account: Option<String>| StakeAccountRequest {
+            wallet: wallet.clone(),
+            stake_account: stake_account.unwrap_or_default(),
+        };
+        let request = match action {
+            StakeAction::Delegate => match draft_delegation(&state_guard.staking, wallet_state) {
+                Ok(request) => StakeRequest::Delegate(request),
+                Err(e) => {
+                    state_guard.staking.form_error = Some(e);
+                    return;
+                }
+            },
+            StakeAction::Deactivate => StakeRequest::Deactivate(account_request(stake_account)),
+            StakeAction::Withdraw => StakeRequest::Withdraw(account_request(stake_account)),
+        };
+        state_guard.staking.form_error = None;
+        state_guard.staking.confirm_deactivate = None;
+        state_guard.staking.submitting = Some(action);
+        (request, wallet, auth_token, api_client)
+    };
+
+    let state_clone = state.clone();
+    spawn_guarded(GuardedTask::Stake, state, event_tx.clone(), async move {
+        let result = async {
+            let built = match &request {
+                StakeRequest::Delegate(request) => api_client.build_stake_delegate(request).await,
+                StakeRequest::Deactivate(request) => api_client.build_stake_deactivate(request).await,
+                StakeRequest::Withdraw(request) => api_client.build_stake_withdraw(request).await,
+            }
+            .map_err(|e| e.to_string())?;
+
+            let mut transaction = WalletTransaction::decode(&built.transaction, built.version)?;
+            check_stake_transaction(&transaction, &wallet)?;
+            // Lock released before any .await
+            let signed = {
+                let state = state_clone.read();
+                let wallet_service = state.wallet_service.as_ref().ok_or("Wallet service not available")?;
+                wallet_service.sign(&mut transaction).map_err(|e| format!("Signing failed: {}", e))?;
+                transaction.encode()?
+            };
+
+            let response = api_client
+                .submit_transaction(
+                    signed,
+                    transaction.version(),
+                    SOL_MINT.to_string(),
+                    SOL_MINT.to_string(),
+                    built.lamports as i64,
+                    built.lamports as i64,
+                    None,
+                    None,
+                    &auth_token,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok::<_, String>((response.signature, built, transaction.recent_blockhash()))
+        }
+        .await;
+
+        let result = result.map(|(signature, built, blockhash)| {
+            let stake_account = shared::format_address(&built.stake_account, 4, 4);
+            let sol = format_token_amount(built.lamports, SOL_DECIMALS);
+            let description = match &request {
+                StakeRequest::Delegate(request) => format!(
+                    "{} SOL → {} (validator {})",
+                    sol,
+                    stake_account,
+                    shared::format_address(&request.vote_account, 4, 4)
+                ),
+                StakeRequest::Deactivate(_) => format!("Deactivate {}", stake_account),
+                StakeRequest::Withdraw(_) => format!("{} SOL ← {}", sol, stake_account),
+            };
+            tracing::info!(%signature, "Staking transaction submitted: {}", description);
+            {
+                let mut state = state_clone.write();
+                state.transactions.insert(0, TransactionItem {
+                    signature: signature.clone(),
+                    timestamp: chrono::Utc::now().timestamp(),
+                    tx_type: STAKE_TX_TYPE.to_string(),
+                    status: TransactionStatus::Pending.as_str().to_string(),
+                    amount: description.clone(),
+                    memo: None,
+                    wallet: Some(wallet),
+                    latency: None,
+                });
+                audit::record(
+                    &mut state.security.trail,
+                    AuditCategory::Wallet,
+                    format!("Signed staking transaction: {}", description),
+                    vec![AuditReference::Transaction(signature.clone())],
+                );
+            }
+            super::tx_status::track_transaction(state_clone, event_tx.clone(), signature.clone(), blockhash);
+            signature
+        });
+
+        let _ = event_tx.send(AppEvent::StakeActionResult { action, result }).await;
+    });
+}
@@ -1,97 +1,127 @@
 //! # Swap Tasks
 //!
-//! Async tasks for swap operations including quote fetching and swap execution.
+//! Async tasks for swap operations including quote fetching, simulation and swap execution.
 
-use crate::app::state::{AppState, SwapQuote};
+use crate::app::audit::{self, AuditCategory, AuditReference};
+use crate::app::state::{AppState, SwapConfirmation, SwapQuote};
 use crate::app::events::AppEvent;
-use crate::core::service::ApiService;
+use crate::app::latency::{self, Checkpoint, SwapTimer};
+use crate::app::recovery::RecoveryFlow;
+use crate::app::usage;
+use crate::app::Feature;
+use crate::services::wallet::WalletTransaction;
 use async_channel::Sender;
 use parking_lot::RwLock;
+use shared::dto::simulation::SimulateSwapRequest;
+use shared::dto::telemetry::{UsageEvent, UsageFeature};
+use shared::dto::transactions::TransactionStatus;
 use std::sync::Arc;
 use tokio::spawn;
 
+/// Wait after the last edit before asking for a quote
+const QUOTE_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Amount typed into the swap panel, in base units (9 decimals); `None`
+/// unless it is a positive number
+pub(crate) fn amount_lamports(amount: &str) -> Option<u64> {
+    match amount.trim().parse::<f64>() {
+        Ok(amount) if amount > 0.0 => Some((amount * 1_000_000_000.0) as u64),
+        _ => None,
+    }
+}
+
+/// Quote as shown in the swap panel, from base-unit amounts
+pub(crate) fn swap_quote(in_amount: &str, out_amount: &str, price_impact_pct: f64) -> SwapQuote {
+    SwapQuote {
+        input_amount: in_amount.parse().unwrap_or(0.0) / 1_000_000_000.0,
+        output_amount: out_amount.parse().unwrap_or(0.0) / 1_000_000_000.0,
+        price_impact: price_impact_pct,
+        estimated_fee: 0.000005, // TODO: Calculate from routes
+    }
+}
+
 /// Trigger async swap quote fetch with debouncing
 ///
+/// Each call replaces the previous one: its pending request is cancelled and
+/// any request already in flight is outdated by the new generation, so typing
+/// "1", "12", "123" only ever shows the quote for "123".
+///
 /// Internal task function - spawns async task to fetch swap quote and send results via event channel.
 pub(crate) fn trigger_quote_fetch(
     state: Arc<RwLock<AppState>>,
     event_tx: Sender<AppEvent>,
 ) {
-    let state_guard = state.read();
+    let mut state_guard = state.write();
+    let swap = &mut state_guard.terminal.swap;
+    let generation = swap.next_quote_generation();
 
     // Only fetch if we have a valid amount
-    let amount_str = &state_guard.terminal.swap.amount;
-    if amount_str.is_empty() {
+    let Some(amount_lamports) = amount_lamports(&swap.amount) else {
+        // Nothing to quote, and the old quote no longer matches the amount
+        swap.quote = None;
+        swap.quote_loading = false;
         return;
-    }
-
-    // Parse amount to lamports (assuming 9 decimals for SOL)
-    let amount_f64: f64 = match amount_str.parse() {
-        Ok(amt) => amt,
-        Err(_) => return, // Invalid number, skip
     };
 
-    // Convert to lamports (9 decimals)
-    let amount_lamports = (amount_f64 * 1_000_000_000.0) as u64;
-
-    // Debounce: only fetch if 500ms elapsed since last fetch
-    if state_guard.terminal.swap.last_quote_fetch.elapsed().as_millis() < 500 {
+    // Don't wait on a timeout when the backend already reports Jupiter down
+    if !state_guard.backend_health.gates().get(Feature::SwapQuotes).is_usable() {
+        tracing::debug!("Skipping quote fetch - quotes are gated by backend health");
+        state_guard.terminal.swap.quote_loading = false;
         return;
     }
 
     let input_mint = state_guard.terminal.swap.input_mint.clone();
     let output_mint = state_guard.terminal.swap.output_mint.clone();
     let slippage_bps = state_guard.terminal.swap.slippage_bps;
-    let api_client = match &state_guard.api_client {
+    let api_client = match &state_guard.api {
         Some(client) => client.clone(),
         None => return,
     };
 
-    drop(state_guard); // Release lock
-
-    // Update last fetch time
-    {
-        let mut state = state.write();
-        state.terminal.swap.quote_loading = true;
-        state.terminal.swap.last_quote_fetch = std::time::Instant::now();
-    }
-
-    spawn(async move {
-        match api_client.get_swap_quote(&input_mint, &output_mint, amount_lamports, slippage_bps).await {
-            Ok(quote_response) => {
-                // Convert API response to our SwapQuote
-                let input_amount: f64 = quote_response.in_amount.parse().unwrap_or(0.0) / 1_000_000_000.0;
-                let output_amount: f64 = quote_response.out_amount.parse().unwrap_or(0.0) / 1_000_000_000.0;
-
-                let quote = SwapQuote {
-                    input_amount,
-                    output_amount,
-                    price_impact: quote_response.price_impact_pct,
-                    estimated_fee: 0.000005, // TODO: Calculate from routes
-                };
-
-                let _ = event_tx.send(AppEvent::SwapQuoteResult(Ok(quote))).await;
-            }
-            Err(e) => {
-                let _ = event_tx.send(AppEvent::SwapQuoteResult(Err(e))).await;
-            }
-        }
+    let task = spawn(async move {
+        tokio::time::sleep(QUOTE_DEBOUNCE).await;
+        let result = api_client
+            .get_swap_quote(&input_mint, &output_mint, amount_lamports, slippage_bps)
+            .await
+            .map_err(|e| e.to_string())
+            .map(|quote| swap_quote(&quote.in_amount, &quote.out_amount, quote.price_impact_pct));
+        let _ = event_tx.send(AppEvent::SwapQuoteResult(generation, result)).await;
     });
+
+    let swap = &mut state_guard.terminal.swap;
+    swap.quote_loading = true;
+    swap.quote_debounce = Some(task.abort_handle());
 }
 
-/// Execute swap transaction
+/// Build and simulate a swap for the confirmation dialog
 ///
-/// Internal task function - spawns async task to execute swap and send results via event channel.
-pub(crate) fn execute_swap(
+/// Nothing is signed here: the unsigned transaction (memo attached) is
+/// simulated by the backend and handed to the dialog with the result via
+/// [`AppEvent::SwapPrepared`]. [`confirm_swap`] signs and submits it. The
+/// swap's [`SwapTimer`] starts here and travels with it.
+///
+/// Internal task function - spawns async task to prepare swap and send results via event channel.
+pub(crate) fn prepare_swap(
     state: Arc<RwLock<AppState>>,
     event_tx: Sender<AppEvent>,
 ) {
+    let clicked_at = std::time::Instant::now();
     // Get necessary data for swap
-    let (quote, input_mint, output_mint, amount_str, slippage_bps, wallet_pubkey, auth_token, api_client) = {
+    let (quote, input_mint, output_mint, input_symbol, output_symbol, amount_str, slippage_bps, wallet_pubkey, auth_token, api_client, memo) = {
         let state_guard = state.read();
+        if state_guard.terminal.swap.preparing {
+            return;
+        }
+        if let Some(reason) = crate::app::handlers::maintenance::trade_block_reason(&state_guard) {
+            let tx = event_tx.clone();
+            tokio::spawn(async move {
+                let _ = tx.send(AppEvent::Loading(format!("NOTIFY_WARNING:{}", reason))).await;
+            });
+            return;
+        }
         
         // Check if wallet is connected
-        let wallet_pubkey = match state_guard.wallet_service.as_ref().and_then(|ws| ws.get_public_key()) {
+        let wallet_pubkey = match state_guard.signer().and_then(|ws| ws.get_public_key()) {
             Some(pk) => pk,
             None => {
                 let tx = event_tx.clone();
@@ -125,9 +155,19 @@ pub(crate) fn execute_swap(
                 return;
             }
         };
+        if let Err(reason) = crate::services::session::check_long_operation(
+            crate::services::session::token_expiry(&auth_token),
+            chrono::Utc::now().timestamp(),
+        ) {
+            let tx = event_tx.clone();
+            tokio::spawn(async move {
+                let _ = tx.send(AppEvent::Loading(format!("ERROR: {}", reason))).await;
+            });
+            return;
+        }
 
         // Get API client
-        let api_client = match &state_guard.api_client {
+        let api_client = match &state_guard.api {
             Some(client) => client.clone(),
             None => {
                 let tx = event_tx.clone();
@@ -138,15 +178,30 @@ pub(crate) fn execute_swap(
             }
         };
 
+        // Reject a bad memo before asking the backend for a transaction
+        let memo = state_guard.terminal.swap.memo.trim().to_string();
+        if !memo.is_empty() {
+            if let Err(e) = crate::services::memo::validate_memo(&memo) {
+                let tx = event_tx.clone();
+                tokio::spawn(async move {
+                    let _ = tx.send(AppEvent::Loading(format!("ERROR: Invalid memo - {}", e))).await;
+                });
+                return;
+            }
+        }
+
         (
             quote,
             state_guard.terminal.swap.input_mint.clone(),
             state_guard.terminal.swap.output_mint.clone(),
+            state_guard.terminal.swap.input_token.clone(),
+            state_guard.terminal.swap.output_token.clone(),
             state_guard.terminal.swap.amount.clone(),
             state_guard.terminal.swap.slippage_bps,
             wallet_pubkey,
             auth_token,
             api_client,
+            memo,
         )
     };
 
@@ -163,70 +218,133 @@ pub(crate) fn execute_swap(
     };
     let amount_lamports = (amount_f64 * 1_000_000_000.0) as u64;
 
-    // Clone state reference for async task
-    let state_clone = state.clone();
+    state.write().terminal.swap.preparing = true;
+    let mut timer = SwapTimer::start_at(clicked_at);
 
-    // Spawn async task to execute swap
     spawn(async move {
-        eprintln!("Starting swap execution...");
+        eprintln!("Preparing swap... (trace {})", timer.trace_id);
         eprintln!("  Input: {} {} ({})", amount_f64, input_mint, amount_lamports);
         eprintln!("  Output: {} (expected)", quote.output_amount);
         eprintln!("  Slippage: {} bps", slippage_bps);
 
-        // Step 1: Get unsigned transaction from backend
-        let swap_response = match api_client
-            .execute_swap(
-                &input_mint,
-                &output_mint,
-                amount_lamports,
-                slippage_bps,
-                &wallet_pubkey,
-                &auth_token,
-            )
-            .await
-        {
-            Ok(resp) => {
-                eprintln!("Received unsigned transaction from backend");
-                resp
+        let result = async {
+            // Step 1: Get unsigned transaction from backend
+            let swap_response = api_client
+                .execute_swap(
+                    &input_mint,
+                    &output_mint,
+                    amount_lamports,
+                    slippage_bps,
+                    &wallet_pubkey,
+                    &auth_token,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            timer.mark(Checkpoint::BuildReceived);
+            eprintln!("Received unsigned transaction from backend");
+
+            // Step 2: Deserialize transaction from base64, legacy or v0
+            let mut transaction = WalletTransaction::decode(&swap_response.transaction, swap_response.version)?;
+            eprintln!("  Instructions: {} ({})", transaction.instruction_count(), transaction.version().as_str());
+
+            // Step 2b: Attach memo (must happen before simulating and signing)
+            if !memo.is_empty() {
+                transaction
+                    .append_memo(&memo)
+                    .map_err(|e| format!("Memo failed: {}", e))?;
+                eprintln!("  Memo attached: {}", memo);
             }
-            Err(e) => {
-                eprintln!("Failed to get transaction: {}", e);
-                let _ = event_tx.send(AppEvent::Loading(format!("Swap failed: {}", e))).await;
-                return;
+
+            // Step 3: Simulate exactly what will be signed
+            let request = SimulateSwapRequest {
+                transaction: transaction.encode()?,
+                user_public_key: wallet_pubkey.clone(),
+                input_mint: input_mint.clone(),
+                output_mint: output_mint.clone(),
+            };
+            let simulation = api_client.simulate_swap(&request, &auth_token).await.map_err(|e| e.to_string());
+            timer.mark(Checkpoint::Revalidated);
+            match &simulation {
+                Ok(simulation) if simulation.success => eprintln!("Simulation succeeded"),
+                Ok(simulation) => eprintln!("Simulation failed: {:?}", simulation.error),
+                Err(e) => eprintln!("Simulation unavailable: {}", e),
             }
-        };
 
-        // Step 2: Deserialize transaction from base64
-        use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
-        use solana_sdk::transaction::Transaction;
+            Ok::<_, String>(Box::new(SwapConfirmation {
+                transaction,
+                input_mint,
+                output_mint,
+                input_symbol,
+                output_symbol,
+                ui_amount: amount_f64,
+                amount: amount_lamports,
+                quote,
+                slippage_bps,
+                memo,
+                wallet: wallet_pubkey,
+                simulation,
+                timer,
+            }))
+        }
+        .await;
 
-        let tx_bytes = match BASE64.decode(&swap_response.transaction) {
-            Ok(bytes) => bytes,
-            Err(e) => {
-                eprintln!("Failed to decode transaction: {}", e);
-                let _ = event_tx.send(AppEvent::Loading(format!("Decode failed: {}", e))).await;
-                return;
-            }
-        };
+        let _ = event_tx.send(AppEvent::SwapPrepared(result)).await;
+    });
+}
 
-        let mut transaction: Transaction = match bincode::deserialize(&tx_bytes) {
-            Ok(tx) => tx,
-            Err(e) => {
-                eprintln!("Failed to deserialize transaction: {}", e);
-                let _ = event_tx.send(AppEvent::Loading(format!("Deserialize failed: {}", e))).await;
-                return;
-            }
+/// Sign and submit the swap waiting in the confirmation dialog
+///
+/// Does nothing unless its simulation succeeded. Once the RPC acknowledged
+/// the transaction, the swap's [stage timings](crate::app::latency) are kept
+/// with its activity row and reported to the backend.
+///
+/// Internal task function - spawns async task to execute swap and send results via event channel.
+pub(crate) fn confirm_swap(
+    state: Arc<RwLock<AppState>>,
+    event_tx: Sender<AppEvent>,
+) {
+    let confirmed_at = std::time::Instant::now();
+    let (confirmation, auth_token, api_client, offline) = {
+        let mut state_guard = state.write();
+        let (Some(auth_token), Some(api_client)) = (state_guard.auth_token.clone(), state_guard.api.clone()) else {
+            return;
+        };
+        // The confirmation stays open, to send once maintenance is over
+        if let Some(reason) = crate::app::handlers::maintenance::trade_block_reason(&state_guard) {
+            state_guard.pending_notifications.push(("warning".to_string(), reason.to_string()));
+            return;
+        }
+        let Some(confirmation) = state_guard.terminal.swap.confirmation.take_if(|c| c.can_execute()) else {
+            return;
         };
+        (confirmation, auth_token, api_client, state_guard.offline)
+    };
+    let SwapConfirmation {
+        mut transaction,
+        input_mint,
+        output_mint,
+        ui_amount: amount_f64,
+        amount: amount_lamports,
+        quote,
+        slippage_bps,
+        memo,
+        wallet,
+        mut timer,
+        ..
+    } = confirmation;
+    timer.mark_at(Checkpoint::ConfirmClicked, confirmed_at);
 
-        eprintln!("Transaction deserialized successfully");
-        eprintln!("  Instructions: {}", transaction.message.instructions.len());
+    // Clone state reference for async task
+    let state_clone = state.clone();
 
-        // Step 3: Sign transaction with wallet
+    // Spawn async task to execute swap
+    spawn(async move {
+        // Step 1: Sign transaction with wallet
         let sign_result = {
             let state_write = state_clone.write();
-            match &state_write.wallet_service {
+            match state_write.signer() {
                 Some(wallet_service) => {
-                    wallet_service.sign_transaction(&mut transaction)
+                    wallet_service.sign(&mut transaction)
                 }
                 None => {
                     Err(crate::services::wallet::WalletError::SigningError("Wallet service not available".to_string()))
@@ -237,6 +355,7 @@ pub(crate) fn execute_swap(
 
         let _signature = match sign_result {
             Ok(sig) => {
+                timer.mark(Checkpoint::Signed);
                 eprintln!("Transaction signed successfully");
                 eprintln!("  Signature: {}", sig);
                 sig
@@ -248,23 +367,24 @@ pub(crate) fn execute_swap(
             }
         };
 
-        // Step 4: Serialize signed transaction back to base64
-        let signed_bytes = match bincode::serialize(&transaction) {
-            Ok(bytes) => bytes,
+        // Step 2: Serialize signed transaction back to base64
+        let signed_b64 = match transaction.encode() {
+            Ok(encoded) => encoded,
             Err(e) => {
                 eprintln!("Failed to serialize signed transaction: {}", e);
-                let _ = event_tx.send(AppEvent::Loading(format!("Serialize failed: {}", e))).await;
+                let _ = event_tx.send(AppEvent::Loading(e)).await;
                 return;
             }
         };
-        let signed_b64 = BASE64.encode(&signed_bytes);
 
         eprintln!("Signed transaction serialized");
 
-        // Step 5: Submit signed transaction to backend
+        // Step 3: Submit signed transaction to backend
+        timer.mark(Checkpoint::SubmitSent);
         let submit_result = api_client
             .submit_transaction(
                 signed_b64,
+                transaction.version(),
                 input_mint.clone(),
                 output_mint.clone(),
                 amount_lamports as i64,
@@ -277,6 +397,11 @@ pub(crate) fn execute_swap(
 
         match submit_result {
             Ok(response) => {
+                timer.mark(Checkpoint::Acknowledged);
+                let breakdown = timer.breakdown();
+                if let Some(breakdown) = &breakdown {
+                    latency::record(breakdown, &timer.trace_id, &response.signature);
+                }
                 eprintln!("Swap executed successfully!");
                 eprintln!("  Transaction signature: {}", response.signature);
                 eprintln!("  Explorer: https://explorer.solana.com/tx/{}?cluster=devnet", response.signature);
@@ -285,17 +410,197 @@ pub(crate) fn execute_swap(
                 let success_msg = format!("Swap successful! Signature: {}", response.signature);
                 let _ = event_tx.send(AppEvent::Loading(success_msg)).await;
                 
-                // Also trigger a trade confirmation notification
-                let trade_msg = format!("Trade Confirmed: {} → {} | Sig: {}", 
+                // Also trigger a trade notification; tracking reports when it settles
+                let trade_msg = format!("Trade Submitted: {} → {} | Sig: {}", 
                     input_mint, output_mint, response.signature);
                 let _ = event_tx.send(AppEvent::Loading(format!("NOTIFY_SUCCESS:{}", trade_msg))).await;
+
+                // Show the swap in the activity feed right away (history refresh fills in the rest)
+                {
+                    let mut state = state_clone.write();
+                    state.transactions.insert(0, crate::app::state::TransactionItem {
+                        signature: response.signature.clone(),
+                        timestamp: chrono::Utc::now().timestamp(),
+                        tx_type: "Swap".to_string(),
+                        status: TransactionStatus::Pending.as_str().to_string(),
+                        amount: format!("{} → {:.6}", amount_f64, quote.output_amount),
+                        memo: (!memo.is_empty()).then(|| memo.clone()),
+                        wallet: Some(wallet.clone()),
+                        latency: breakdown,
+                    });
+                    state.terminal.swap.memo.clear();
+                    crate::app::handlers::recovery::resolve(&mut state, RecoveryFlow::Swap);
+                    usage::capture(UsageEvent::Feature(UsageFeature::Swap));
+                    audit::record(
+                        &mut state.security.trail,
+                        AuditCategory::Swap,
+                        format!(
+                            "Signed swap of {} {} for {:.6} {}",
+                            amount_f64,
+                            shared::format_address(&input_mint, 4, 4),
+                            quote.output_amount,
+                            shared::format_address(&output_mint, 4, 4),
+                        ),
+                        vec![AuditReference::Transaction(response.signature.clone())],
+                    );
+                }
+
+                if offline {
+                    // The demo backend settles swaps as it accepts them
+                    let settled = AppEvent::TransactionStatusChanged {
+                        signature: response.signature.clone(),
+                        status: TransactionStatus::Finalized,
+                        error: None,
+                    };
+                    let _ = event_tx.send(settled).await;
+                    crate::app::handlers::wallet::refresh_balances(state_clone, event_tx);
+                } else {
+                    super::tx_status::track_transaction(
+                        state_clone,
+                        event_tx,
+                        response.signature.clone(),
+                        transaction.recent_blockhash(),
+                    );
+                }
+
+                // Only feeds the admin summary; the swap went through either way
+                if let Some(breakdown) = breakdown {
+                    if let Err(e) = api_client.record_transaction_latency(&response.signature, &breakdown, &auth_token).await {
+                        tracing::debug!(trace_id = %timer.trace_id, "Failed to report swap latency: {}", e);
+                    }
+                }
             }
             Err(e) => {
                 eprintln!("Failed to submit transaction: {}", e);
-                let error_msg = format!("Submit failed: {}", e);
-                let _ = event_tx.send(AppEvent::Loading(format!("NOTIFY_ERROR:{}", error_msg))).await;
+                let error = format!("Submit failed: {}", e);
+                let _ = event_tx.send(AppEvent::RecoverableError { flow: RecoveryFlow::Swap, error }).await;
             }
         }
     });
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mock::MockApiService;
+    use crate::core::service::{ApiService, WalletService};
+    use crate::services::api::{ApiError, SwapExecuteResponse, TransactionSubmitResponse};
+    use crate::services::offline::{OfflineApi, OfflineWallet};
+    use shared::dto::simulation::SimulateSwapResponse;
+    use shared::dto::transactions::TransactionVersion;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::transaction::Transaction;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    const SOL: &str = "So11111111111111111111111111111111111111112";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    /// First event `pick` takes, skipping the rest
+    async fn next_event<T>(rx: &async_channel::Receiver<AppEvent>, mut pick: impl FnMut(AppEvent) -> Option<T>) -> T {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(found) = pick(rx.recv().await.expect("event channel closed")) {
+                    return found;
+                }
+            }
+        })
+        .await
+        .expect("event never arrived")
+    }
+
+    #[tokio::test]
+    async fn test_swap_is_prepared_signed_and_submitted() {
+        let wallet = OfflineWallet::new(Arc::new(OfflineApi::new()));
+        let owner = wallet.get_public_key().unwrap();
+        let unsigned = Transaction::new_with_payer(&[], Some(&Pubkey::from_str(&owner).unwrap()));
+
+        let api = Arc::new(MockApiService::new());
+        api.respond::<Result<SwapExecuteResponse, ApiError>>(
+            "execute_swap",
+            Ok(SwapExecuteResponse {
+                transaction: WalletTransaction::Legacy(unsigned).encode().unwrap(),
+                version: TransactionVersion::Legacy,
+                last_valid_block_height: 0,
+                input_mint: SOL.to_string(),
+                output_mint: USDC.to_string(),
+                in_amount: "1000000000".to_string(),
+                out_amount: "145000000000".to_string(),
+                price_impact_pct: 0.01,
+            }),
+        )
+        .respond::<Result<SimulateSwapResponse, ApiError>>(
+            "simulate_swap",
+            Ok(SimulateSwapResponse {
+                success: true,
+                error: None,
+                compute_units: None,
+                network_fee_lamports: 5_000,
+                sol_change_lamports: -1_000_005_000,
+                token_changes: Vec::new(),
+                logs: Vec::new(),
+                log_tail: Vec::new(),
+            }),
+        )
+        .respond::<Result<TransactionSubmitResponse, ApiError>>(
+            "submit_transaction",
+            Ok(TransactionSubmitResponse { signature: "5ig".to_string(), status: "pending".to_string() }),
+        );
+
+        let state = {
+            let mut state = crate::app::App::new().state.read().clone();
+            state.api = Some(Arc::clone(&api) as Arc<dyn ApiService>);
+            state.offline_wallet = Some(Box::new(wallet));
+            state.auth_token = Some(crate::services::offline::demo_token());
+            let swap = &mut state.terminal.swap;
+            swap.input_mint = SOL.to_string();
+            swap.output_mint = USDC.to_string();
+            swap.amount = "1".to_string();
+            swap.slippage_bps = 50;
+            swap.quote = Some(swap_quote("1000000000", "145000000000", 0.01));
+            Arc::new(RwLock::new(state))
+        };
+        let (tx, rx) = async_channel::unbounded();
+
+        prepare_swap(Arc::clone(&state), tx.clone());
+        let confirmation = next_event(&rx, |event| match event {
+            AppEvent::SwapPrepared(result) => Some(result),
+            _ => None,
+        })
+        .await
+        .unwrap();
+        assert!(confirmation.can_execute());
+        // One SOL, for the connected wallet
+        let execute = &api.calls_to("execute_swap")[0];
+        assert_eq!(execute[2], "1000000000");
+        assert_eq!(execute[4], format!("{:?}", owner));
+
+        {
+            let mut state = state.write();
+            state.terminal.swap.preparing = false;
+            state.terminal.swap.confirmation = Some(*confirmation);
+        }
+        confirm_swap(Arc::clone(&state), tx);
+        let message = next_event(&rx, |event| match event {
+            AppEvent::Loading(message) if message.starts_with("Swap successful") => Some(message),
+            _ => None,
+        })
+        .await;
+        assert_eq!(message, "Swap successful! Signature: 5ig");
+
+        // What went to the backend is what the wallet signed
+        let submitted = &api.calls_to("submit_transaction")[0];
+        match WalletTransaction::decode(submitted[0].trim_matches('"'), TransactionVersion::Legacy).unwrap() {
+            WalletTransaction::Legacy(transaction) => assert!(transaction.is_signed()),
+            other => panic!("expected a legacy transaction, got {:?}", other.version()),
+        }
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while state.read().transactions.first().is_none_or(|row| row.signature != "5ig") {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("swap never reached the activity feed");
+    }
+}
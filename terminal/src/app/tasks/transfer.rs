@@ -0,0 +1,236 @@
+//! # Transfer Tasks
+//!
+//! Async tasks behind the Wallet screen's Send panel: building a SOL or token
+//! transfer and looking up what it costs, then signing and submitting it.
+//! See [`crate::app::handlers::send`] for the flow.
+
+use crate::app::audit::{self, AuditCategory, AuditReference};
+use crate::app::events::AppEvent;
+use crate::app::handlers::send::{self, TransferDraft, TRANSFER_TX_TYPE};
+use crate::app::state::{AppState, TransferConfirmation};
+use crate::app::tasks::guard::{spawn_guarded, GuardedTask};
+use crate::core::service::ApiService;
+use crate::services::token_transfer::{associated_token_address, format_token_amount};
+use crate::services::wallet::WalletTransaction;
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::transactions::TransactionStatus;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Mint the backend records SOL transfers under (wrapped SOL)
+pub(crate) const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Size of a token account, for its rent
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Build the transfer described by the Send panel and open the confirmation
+///
+/// The transaction is built locally; the RPC is asked for its exact fee,
+/// the recipient's balance and the rent a new account needs.
+///
+/// Internal task function - spawns async task to prepare the transfer and send results via event channel.
+pub(crate) fn prepare_transfer(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (draft, transaction, wallet, rpc_url) = {
+        let mut guard = state.write();
+        // Reborrowed so the form and the wallet can be borrowed separately
+        let state_guard = &mut *guard;
+        if state_guard.send.preparing || state_guard.send.sending {
+            return;
+        }
+        let Some(wallet_state) = state_guard.wallet.as_ref() else {
+            state_guard.send.error = Some("Connect a wallet first".to_string());
+            return;
+        };
+        let draft = match send::draft_transfer(&state_guard.send, wallet_state) {
+            Ok(draft) => draft,
+            Err(e) => {
+                state_guard.send.error = Some(e);
+                return;
+            }
+        };
+        let wallet = wallet_state.address.clone();
+        let Some(wallet_service) = state_guard.wallet_service.as_ref() else {
+            state_guard.send.error = Some("Wallet service not available".to_string());
+            return;
+        };
+        let memo = draft.memo.as_deref();
+        let built = match &draft.mint {
+            None => wallet_service.build_sol_transfer(&draft.recipient, draft.amount, memo),
+            Some(mint) => {
+                wallet_service.build_token_transfer(&draft.recipient, mint, draft.program, draft.amount, draft.decimals, memo)
+            }
+        };
+        let transaction = match built {
+            Ok(transaction) => transaction,
+            Err(e) => {
+                state_guard.send.error = Some(e.to_string());
+                return;
+            }
+        };
+        let rpc_url = wallet_service.rpc_client().url();
+        state_guard.send.error = None;
+        state_guard.send.preparing = true;
+        (draft, transaction, wallet, rpc_url)
+    };
+
+    spawn_guarded(GuardedTask::TransferPrepare, state, event_tx.clone(), async move {
+        let result = tokio::task::spawn_blocking(move || inspect_transfer(&rpc_url, draft, transaction, wallet))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result);
+        let _ = event_tx.send(AppEvent::TransferPrepared(result)).await;
+    });
+}
+
+/// Look up the fee, the recipient's balance and any rent for a built transfer
+fn inspect_transfer(
+    rpc_url: &str,
+    draft: TransferDraft,
+    mut transaction: solana_sdk::transaction::Transaction,
+    wallet: String,
+) -> Result<Box<TransferConfirmation>, String> {
+    let rpc = RpcClient::new(rpc_url.to_string());
+    let recipient = Pubkey::from_str(&draft.recipient).map_err(|e| e.to_string())?;
+
+    // The fee is quoted for a message with a live blockhash; signing refreshes it
+    transaction.message.recent_blockhash = rpc
+        .get_latest_blockhash()
+        .map_err(|e| format!("Failed to get blockhash: {}", e))?;
+    let fee_lamports = rpc
+        .get_fee_for_message(&transaction.message)
+        .map_err(|e| format!("Failed to estimate the fee: {}", e))?;
+    let recipient_lamports = rpc
+        .get_balance(&recipient)
+        .map_err(|e| format!("Failed to read the recipient's balance: {}", e))?;
+    let rent_exempt_minimum = rpc
+        .get_minimum_balance_for_rent_exemption(0)
+        .map_err(|e| format!("Failed to read the rent minimum: {}", e))?;
+
+    let token_account_rent = match &draft.mint {
+        None => None,
+        Some(mint) => {
+            let mint = Pubkey::from_str(mint).map_err(|e| e.to_string())?;
+            let token_account = associated_token_address(&recipient, &mint, draft.program);
+            let exists = rpc
+                .get_balance(&token_account)
+                .map_err(|e| format!("Failed to read the recipient's token account: {}", e))?
+                > 0;
+            if exists {
+                None
+            } else {
+                Some(
+                    rpc.get_minimum_balance_for_rent_exemption(TOKEN_ACCOUNT_LEN)
+                        .map_err(|e| format!("Failed to read the rent minimum: {}", e))?,
+                )
+            }
+        }
+    };
+
+    Ok(Box::new(TransferConfirmation {
+        transaction: WalletTransaction::Legacy(transaction),
+        recipient: draft.recipient,
+        mint: draft.mint,
+        symbol: draft.symbol,
+        decimals: draft.decimals,
+        amount: draft.amount,
+        received: draft.received,
+        memo: draft.memo,
+        fee_lamports,
+        recipient_lamports,
+        rent_exempt_minimum,
+        token_account_rent,
+        wallet,
+    }))
+}
+
+/// Sign and submit the transfer waiting in the confirmation dialog
+///
+/// The backend records it through the swap submit path, with the same mint
+/// on both sides, so it shows up in the history like any other transaction.
+///
+/// Internal task function - spawns async task to send the transfer and send results via event channel.
+pub(crate) fn confirm_transfer(state: Arc<RwLock<AppState>>, event_tx: Sender<AppEvent>) {
+    let (confirmation, auth_token, api_client) = {
+        let mut state_guard = state.write();
+        if state_guard.send.sending {
+            return;
+        }
+        let (Some(auth_token), Some(api_client)) = (state_guard.auth_token.clone(), state_guard.api_client.clone()) else {
+            state_guard.send.error = Some("Log in to send transfers".to_string());
+            return;
+        };
+        let Some(confirmation) = state_guard.send.confirmation.take_if(|c| c.can_send()) else {
+            return;
+        };
+        state_guard.send.sending = true;
+        (confirmation, auth_token, api_client)
+    };
+    let TransferConfirmation { mut transaction, recipient, mint, symbol, decimals, amount, received, memo, wallet, .. } =
+        confirmation;
+
+    let state_clone = state.clone();
+    spawn_guarded(GuardedTask::Transfer, state, event_tx.clone(), async move {
+        let result = async {
+            // Lock released before any .await
+            let signed = {
+                let state = state_clone.read();
+                let wallet_service = state.wallet_service.as_ref().ok_or("Wallet service not available")?;
+                wallet_service.sign(&mut transaction).map_err(|e| format!("Signing failed: {}", e))?;
+                transaction.encode()?
+            };
+
+            let recorded_mint = mint.clone().unwrap_or_else(|| SOL_MINT.to_string());
+            let response = api_client
+                .submit_transaction(
+                    signed,
+                    transaction.version(),
+                    recorded_mint.clone(),
+                    recorded_mint,
+                    amount as i64,
+                    received as i64,
+                    None,
+                    None,
+                    &auth_token,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok::<_, String>(response.signature)
+        }
+        .await;
+
+        if let Ok(signature) = &result {
+            let ui_amount = format_token_amount(amount, decimals);
+            tracing::info!(%signature, %recipient, "Transfer of {} {} submitted", ui_amount, symbol);
+            {
+                let mut state = state_clone.write();
+                state.transactions.insert(0, crate::app::state::TransactionItem {
+                    signature: signature.clone(),
+                    timestamp: chrono::Utc::now().timestamp(),
+                    tx_type: TRANSFER_TX_TYPE.to_string(),
+                    status: TransactionStatus::Pending.as_str().to_string(),
+                    amount: format!("{} {} → {}", ui_amount, symbol, shared::format_address(&recipient, 4, 4)),
+                    memo,
+                    wallet: Some(wallet),
+                    latency: None,
+                });
+                audit::record(
+                    &mut state.security.trail,
+                    AuditCategory::Wallet,
+                    format!("Signed transfer of {} {} to {}", ui_amount, symbol, shared::format_address(&recipient, 4, 4)),
+                    vec![AuditReference::Transaction(signature.clone())],
+                );
+            }
+            super::tx_status::track_transaction(
+                state_clone,
+                event_tx.clone(),
+                signature.clone(),
+                transaction.recent_blockhash(),
+            );
+        }
+
+        let _ = event_tx.send(AppEvent::TransferResult(result)).await;
+    });
+}
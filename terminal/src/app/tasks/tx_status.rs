@@ -0,0 +1,260 @@
+//! # Transaction Status Tasks
+//!
+//! Follows submitted transactions until they settle. Each signature gets its
+//! own task polling `getSignatureStatuses`, so any number of swaps can be in
+//! flight without one waiting on another. Every step is reported as
+//! [`AppEvent::TransactionStatusChanged`]:
+//!
+//! ```text
+//! pending → processed → confirmed → finalized
+//!        ↘ failed     (landed with an error)
+//!        ↘ expired    (blockhash ran out before it landed)
+//! ```
+//!
+//! The final status is also sent to the backend so the swap history matches.
+//! When tracking times out, the last observed status is reported instead; a
+//! transaction never seen is only reported expired once its blockhash is
+//! provably no longer valid.
+
+use crate::app::events::AppEvent;
+use crate::app::state::AppState;
+use async_channel::Sender;
+use parking_lot::RwLock;
+use shared::dto::transactions::{TransactionStatus, TransactionStatusUpdate};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Time between two status polls of one signature
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Give up on a signature after this long. A blockhash expires after about
+/// 150 blocks (~1 minute), so this only hits when the RPC stops answering.
+const TRACK_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Start following `signature` until it is finalized, failed or expired
+///
+/// `blockhash` is the recent blockhash the transaction was signed with; once
+/// it is no longer valid a transaction that hasn't landed never will.
+///
+/// Internal task function - spawns async task to poll the status and send changes via event channel.
+pub(crate) fn track_transaction(
+    state: Arc<RwLock<AppState>>,
+    event_tx: Sender<AppEvent>,
+    signature: String,
+    blockhash: Hash,
+) {
+    let (rpc_url, api_client, auth_token) = {
+        let state_guard = state.read();
+        let Some(wallet_service) = &state_guard.wallet_service else {
+            tracing::debug!(%signature, "Not tracking transaction - no wallet connected");
+            return;
+        };
+        (
+            wallet_service.rpc_client().url(),
+            state_guard.api.clone(),
+            state_guard.auth_token.clone(),
+        )
+    };
+    let rpc = Arc::new(RpcClient::new(rpc_url));
+
+    tokio::spawn(async move {
+        let started = Instant::now();
+        let mut current = TransactionStatus::Pending;
+        let mut error = None;
+
+        while !current.is_final() {
+            if started.elapsed() > TRACK_TIMEOUT {
+                tracing::warn!(%signature, status = current.as_str(), "Stopped tracking transaction - timed out");
+                // A transaction never seen gets one last chance to prove it expired
+                let last_poll = if current == TransactionStatus::Pending {
+                    let rpc = Arc::clone(&rpc);
+                    let sig = signature.clone();
+                    tokio::task::spawn_blocking(move || poll_status(&rpc, &sig, &blockhash))
+                        .await
+                        .ok()
+                        .and_then(Result::ok)
+                        .flatten()
+                } else {
+                    None
+                };
+                let Some((status, status_error)) = timeout_outcome(current, error.take(), last_poll) else {
+                    return;
+                };
+                if status != current {
+                    let event = AppEvent::TransactionStatusChanged {
+                        signature: signature.clone(),
+                        status,
+                        error: status_error.clone(),
+                    };
+                    if event_tx.send(event).await.is_err() {
+                        return;
+                    }
+                }
+                current = status;
+                error = status_error;
+                break;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let rpc = Arc::clone(&rpc);
+            let sig = signature.clone();
+            let polled = tokio::task::spawn_blocking(move || poll_status(&rpc, &sig, &blockhash)).await;
+            let (status, status_error) = match polled {
+                Ok(Ok(Some(update))) => update,
+                Ok(Ok(None)) => continue,
+                Ok(Err(e)) => {
+                    tracing::debug!(%signature, error = %e, "Transaction status poll failed");
+                    continue;
+                }
+                Err(e) => {
+                    tracing::debug!(%signature, error = %e, "Transaction status task failed");
+                    continue;
+                }
+            };
+            if status == current {
+                continue;
+            }
+
+            tracing::debug!(%signature, from = current.as_str(), to = status.as_str(), "Transaction status changed");
+            current = status;
+            error = status_error;
+            let event = AppEvent::TransactionStatusChanged {
+                signature: signature.clone(),
+                status,
+                error: error.clone(),
+            };
+            if event_tx.send(event).await.is_err() {
+                return;
+            }
+        }
+
+        if let (Some(api_client), Some(auth_token)) = (api_client, auth_token) {
+            let update = TransactionStatusUpdate { status: current, error };
+            if let Err(e) = api_client.update_transaction_status(&signature, &update, &auth_token).await {
+                tracing::warn!(%signature, error = %e, "Failed to report transaction status to backend");
+            }
+        }
+    });
+}
+
+/// Status to report once tracking timed out with `current` last observed.
+///
+/// A transaction the cluster reported keeps that status. One never seen
+/// takes the result of `last_poll`, which is only expired when the blockhash
+/// is no longer valid; `None` (RPC still not answering) reports nothing.
+fn timeout_outcome(
+    current: TransactionStatus,
+    error: Option<String>,
+    last_poll: Option<(TransactionStatus, Option<String>)>,
+) -> Option<(TransactionStatus, Option<String>)> {
+    if current != TransactionStatus::Pending {
+        return Some((current, error));
+    }
+    last_poll
+}
+
+/// One poll: the current status, `None` while the transaction is still
+/// unknown to the cluster and its blockhash is valid
+fn poll_status(
+    rpc: &RpcClient,
+    signature: &str,
+    blockhash: &Hash,
+) -> Result<Option<(TransactionStatus, Option<String>)>, String> {
+    if let Some(update) = signature_status(rpc, signature)? {
+        return Ok(Some(update));
+    }
+    if rpc.is_blockhash_valid(blockhash, rpc.commitment()).map_err(|e| e.to_string())? {
+        return Ok(None);
+    }
+    // It may have landed between the two calls
+    Ok(Some(signature_status(rpc, signature)?.unwrap_or((TransactionStatus::Expired, None))))
+}
+
+/// Status of `signature` and its error, `None` while the cluster doesn't know it
+pub(crate) fn signature_status(
+    rpc: &RpcClient,
+    signature: &str,
+) -> Result<Option<(TransactionStatus, Option<String>)>, String> {
+    rpc.send::<serde_json::Value>(
+        RpcRequest::GetSignatureStatuses,
+        serde_json::json!([[signature], { "searchTransactionHistory": false }]),
+    )
+    .map(|response| status_from_rpc(&response["value"][0]))
+    .map_err(|e| e.to_string())
+}
+
+/// Status of one entry of a `getSignatureStatuses` response, `None` when the
+/// signature isn't known (the entry is `null`)
+fn status_from_rpc(entry: &serde_json::Value) -> Option<(TransactionStatus, Option<String>)> {
+    if entry.is_null() {
+        return None;
+    }
+    if let Some(err) = entry.get("err").filter(|err| !err.is_null()) {
+        return Some((TransactionStatus::Failed, Some(err.to_string())));
+    }
+    let status = match entry["confirmationStatus"].as_str() {
+        Some("finalized") => TransactionStatus::Finalized,
+        Some("confirmed") => TransactionStatus::Confirmed,
+        Some("processed") => TransactionStatus::Processed,
+        // Older nodes only report confirmations, `null` once rooted
+        _ if entry["confirmations"].is_null() => TransactionStatus::Finalized,
+        _ => TransactionStatus::Processed,
+    };
+    Some((status, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_status_from_rpc() {
+        assert_eq!(status_from_rpc(&serde_json::Value::Null), None);
+
+        for (reported, expected) in [
+            ("processed", TransactionStatus::Processed),
+            ("confirmed", TransactionStatus::Confirmed),
+            ("finalized", TransactionStatus::Finalized),
+        ] {
+            let entry = json!({ "slot": 1, "confirmations": 3, "err": null, "confirmationStatus": reported });
+            assert_eq!(status_from_rpc(&entry), Some((expected, None)));
+        }
+
+        let failed = json!({
+            "slot": 1,
+            "confirmations": 0,
+            "err": { "InstructionError": [2, { "Custom": 6001 }] },
+            "confirmationStatus": "confirmed"
+        });
+        let (status, error) = status_from_rpc(&failed).unwrap();
+        assert_eq!(status, TransactionStatus::Failed);
+        assert!(error.unwrap().contains("6001"));
+
+        let rooted = json!({ "slot": 1, "confirmations": null, "err": null });
+        assert_eq!(status_from_rpc(&rooted), Some((TransactionStatus::Finalized, None)));
+    }
+
+    #[test]
+    fn test_timeout_keeps_the_last_observed_status() {
+        // Seen as confirmed before the RPC went quiet: never reported expired
+        assert_eq!(
+            timeout_outcome(TransactionStatus::Confirmed, None, None),
+            Some((TransactionStatus::Confirmed, None))
+        );
+        assert_eq!(
+            timeout_outcome(TransactionStatus::Processed, None, Some((TransactionStatus::Expired, None))),
+            Some((TransactionStatus::Processed, None))
+        );
+
+        // Never seen: expired only when the last poll proved the blockhash invalid
+        assert_eq!(
+            timeout_outcome(TransactionStatus::Pending, None, Some((TransactionStatus::Expired, None))),
+            Some((TransactionStatus::Expired, None))
+        );
+        assert_eq!(timeout_outcome(TransactionStatus::Pending, None, None), None);
+    }
+}
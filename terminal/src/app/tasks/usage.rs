@@ -0,0 +1,40 @@
+//! # Usage Upload
+//!
+//! Sends the completed days of usage analytics to the backend. Run by the
+//! health poll about once an hour; see [`crate::app::usage`].
+
+use crate::app::handlers::settings::refresh_usage_preview;
+use crate::app::state::AppState;
+use crate::app::usage;
+use crate::services::api::ApiClient;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Upload the counts of completed days, if the user opted in
+///
+/// Uploaded days are forgotten locally. A failed upload keeps them for the
+/// next attempt; the backend replaces days it already has, so sending one
+/// twice is harmless.
+pub(crate) async fn upload_usage(state: &Arc<RwLock<AppState>>, api_client: &ApiClient) {
+    let install_id = {
+        let state = state.read();
+        let preferences = &state.settings.privacy.usage;
+        match (&preferences.install_id, preferences.enabled) {
+            (Some(install_id), true) => install_id.clone(),
+            _ => return,
+        }
+    };
+
+    let today = chrono::Utc::now().date_naive();
+    if let Some(report) = usage::recorder().pending_report(&install_id, today) {
+        match api_client.upload_usage(&report).await {
+            Ok(()) => {
+                tracing::debug!(days = report.days.len(), "Usage counts uploaded");
+                usage::recorder().mark_uploaded(&report);
+            }
+            Err(e) => tracing::debug!("Usage upload failed, retrying later: {}", e),
+        }
+    }
+    // A new day may have completed since the preview was taken
+    refresh_usage_preview(&mut state.write());
+}
@@ -0,0 +1,261 @@
+//! # Usage Analytics
+//!
+//! Opt-in, anonymous counts of which screens and features are used and which
+//! kinds of errors come up. Off until the user turns it on under
+//! Settings > Privacy.
+//!
+//! Every capture site goes through [`capture`], the one place consent is
+//! checked: while analytics are off it returns before touching anything, and
+//! turning them off forgets the counts not yet uploaded. Events come from the
+//! closed [`UsageEvent`] taxonomy, so amounts, tokens, addresses and free text
+//! can't be recorded.
+//!
+//! ## Flow
+//!
+//! ```text
+//! capture(event) → daily count in the local database (UTC days)
+//! health poll, hourly → POST /api/telemetry/usage with completed days
+//!                     → uploaded days forgotten locally
+//! Settings > Privacy → the next upload's exact JSON; "Delete my data"
+//!                     → DELETE /api/telemetry/usage/{install_id}
+//! ```
+//!
+//! Reports carry a random install id, created when analytics are first
+//! enabled and replaced when the user deletes their data.
+
+use crate::app::state::Screen;
+use crate::core::store::{store, Document, UsageRepository};
+use chrono::{NaiveDate, Utc};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use shared::dto::telemetry::{UsageEvent, UsageReport, UsageScreen, MAX_REPORT_DAYS};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Consent and install id, saved in the local database
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct UsagePreferences {
+    /// The user opted in
+    pub enabled: bool,
+    /// Random id reports are uploaded under; None until first needed
+    pub install_id: Option<String>,
+}
+
+impl UsagePreferences {
+    /// Install id, created on first use
+    pub fn install_id(&mut self) -> &str {
+        self.install_id.get_or_insert_with(|| uuid::Uuid::new_v4().to_string())
+    }
+}
+
+/// Load the usage analytics consent
+pub fn load_preferences() -> UsagePreferences {
+    store().settings().load_or_default(Document::Usage)
+}
+
+/// Save the usage analytics consent
+pub fn save_preferences(preferences: &UsagePreferences) {
+    if let Err(e) = store().settings().save(Document::Usage, preferences) {
+        tracing::error!("Failed to save usage analytics preferences: {}", e);
+    }
+}
+
+/// Counts events into daily buckets while enabled
+pub struct UsageRecorder {
+    enabled: AtomicBool,
+    /// Screen of the last view counted, so a screen shown for many frames
+    /// counts once
+    last_screen: Mutex<Option<UsageScreen>>,
+    counts: UsageRepository,
+}
+
+impl UsageRecorder {
+    pub fn new(counts: UsageRepository, enabled: bool) -> Self {
+        Self { enabled: AtomicBool::new(enabled), last_screen: Mutex::new(None), counts }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Count `event` on `day`; does nothing while disabled
+    pub fn capture(&self, event: UsageEvent, day: NaiveDate) {
+        if !self.is_enabled() {
+            return;
+        }
+        if let UsageEvent::ScreenView(screen) = event {
+            if self.last_screen.lock().replace(screen) == Some(screen) {
+                return;
+            }
+        }
+        if let Err(e) = self.counts.increment(day, event) {
+            tracing::debug!("Failed to count usage event {}: {}", event, e);
+        }
+    }
+
+    /// Start or stop counting; stopping forgets the counts not yet uploaded
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        *self.last_screen.lock() = None;
+        if !enabled {
+            self.forget();
+        }
+    }
+
+    /// Forget the counts not yet uploaded
+    pub fn forget(&self) {
+        if let Err(e) = self.counts.clear() {
+            tracing::error!("Failed to clear usage counts: {}", e);
+        }
+    }
+
+    /// The report the next upload would send: days before `today`, oldest
+    /// first. None while disabled or when there is nothing to send.
+    pub fn pending_report(&self, install_id: &str, today: NaiveDate) -> Option<UsageReport> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let days = match self.counts.days_before(today, MAX_REPORT_DAYS) {
+            Ok(days) => days,
+            Err(e) => {
+                tracing::warn!("Failed to read usage counts: {}", e);
+                return None;
+            }
+        };
+        if days.is_empty() {
+            return None;
+        }
+        Some(UsageReport {
+            install_id: install_id.to_string(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            days,
+        })
+    }
+
+    /// Forget the days of a report the backend accepted
+    pub fn mark_uploaded(&self, report: &UsageReport) {
+        let Some(last) = report.days.last() else {
+            return;
+        };
+        if let Err(e) = self.counts.clear_through(last.day) {
+            tracing::warn!("Failed to clear uploaded usage counts: {}", e);
+        }
+    }
+}
+
+static RECORDER: Lazy<UsageRecorder> =
+    Lazy::new(|| UsageRecorder::new(store().usage(), load_preferences().enabled));
+
+/// The app-wide recorder, enabled according to the saved consent
+pub fn recorder() -> &'static UsageRecorder {
+    &RECORDER
+}
+
+/// Count `event` today, if the user opted in
+pub fn capture(event: UsageEvent) {
+    recorder().capture(event, Utc::now().date_naive());
+}
+
+/// Screen view event for `screen`
+pub fn screen_view(screen: Screen) -> UsageEvent {
+    UsageEvent::ScreenView(match screen {
+        Screen::Landing => UsageScreen::Landing,
+        Screen::Auth => UsageScreen::Auth,
+        Screen::Terminal => UsageScreen::Terminal,
+        Screen::PythFeed => UsageScreen::PythFeed,
+        Screen::JupiterFeed => UsageScreen::JupiterFeed,
+        Screen::Wallet => UsageScreen::Wallet,
+        Screen::Portfolio => UsageScreen::Portfolio,
+        Screen::Transactions => UsageScreen::Transactions,
+        Screen::Tokens => UsageScreen::Tokens,
+        Screen::Messaging => UsageScreen::Messaging,
+        Screen::AIChat => UsageScreen::AiChat,
+        Screen::Settings => UsageScreen::Settings,
+        Screen::LiveChart => UsageScreen::LiveChart,
+        Screen::LiveAssets => UsageScreen::LiveAssets,
+        Screen::LiveTable => UsageScreen::LiveTable,
+    })
+}
+
+/// Pretty-printed JSON of the next upload, for Settings > Privacy
+pub fn preview(preferences: &UsagePreferences, today: NaiveDate) -> Option<String> {
+    let install_id = preferences.install_id.as_deref()?;
+    let report = recorder().pending_report(install_id, today)?;
+    serde_json::to_string_pretty(&report).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::store::LocalStore;
+    use shared::dto::telemetry::{UsageErrorCategory, UsageFeature};
+
+    const INSTALL: &str = "0f8fad5b-d9cb-469f-a165-70867728950e";
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    fn recorder(enabled: bool) -> (UsageRecorder, UsageRepository) {
+        let counts = LocalStore::open_in_memory().unwrap().usage();
+        (UsageRecorder::new(counts.clone(), enabled), counts)
+    }
+
+    #[test]
+    fn test_nothing_is_captured_while_opted_out() {
+        let (recorder, counts) = recorder(false);
+        for event in UsageEvent::all() {
+            recorder.capture(event, day(1));
+        }
+        assert!(counts.days_before(day(2), MAX_REPORT_DAYS).unwrap().is_empty());
+        assert!(recorder.pending_report(INSTALL, day(2)).is_none());
+        assert!(!UsagePreferences::default().enabled);
+    }
+
+    #[test]
+    fn test_counts_are_bucketed_per_day_and_screens_deduplicated() {
+        let (recorder, _counts) = recorder(true);
+        let swap = UsageEvent::Feature(UsageFeature::Swap);
+        let wallet = screen_view(Screen::Wallet);
+        for _ in 0..3 {
+            recorder.capture(wallet, day(1));
+        }
+        recorder.capture(swap, day(1));
+        recorder.capture(swap, day(1));
+        recorder.capture(screen_view(Screen::Terminal), day(1));
+        recorder.capture(wallet, day(1));
+        recorder.capture(UsageEvent::Error(UsageErrorCategory::Login), day(2));
+
+        // Today isn't complete, so it isn't reported yet
+        let report = recorder.pending_report(INSTALL, day(2)).unwrap();
+        assert!(report.validate().is_ok());
+        assert_eq!(report.days.len(), 1);
+        assert_eq!(report.days[0].counts[&wallet], 2);
+        assert_eq!(report.days[0].counts[&swap], 2);
+
+        recorder.mark_uploaded(&report);
+        let report = recorder.pending_report(INSTALL, day(3)).unwrap();
+        assert_eq!(report.days.iter().map(|d| d.day).collect::<Vec<_>>(), vec![day(2)]);
+    }
+
+    #[test]
+    fn test_opting_out_forgets_counts_and_stops_capture() {
+        let (recorder, counts) = recorder(true);
+        recorder.capture(UsageEvent::Feature(UsageFeature::Export), day(1));
+        recorder.set_enabled(false);
+        recorder.capture(UsageEvent::Feature(UsageFeature::Export), day(1));
+        assert!(counts.days_before(day(2), MAX_REPORT_DAYS).unwrap().is_empty());
+
+        recorder.set_enabled(true);
+        recorder.capture(UsageEvent::Feature(UsageFeature::Export), day(1));
+        assert_eq!(counts.days_before(day(2), MAX_REPORT_DAYS).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_install_id_is_created_once() {
+        let mut preferences = UsagePreferences::default();
+        let id = preferences.install_id().to_string();
+        assert!(shared::dto::telemetry::is_install_id(&id));
+        assert_eq!(preferences.install_id(), id);
+    }
+}
@@ -1,7 +1,9 @@
 //! # Viewport Rendering
 //!
 //! Handles rendering of secondary windows (deferred viewports) with full
-//! screen navigation support. Each window can independently cycle through screens.
+//! screen navigation support. Each window renders the screen stored for it in
+//! the [`WindowManager`] (not `AppState::current_screen`), and its nav bar and
+//! Tab keys only change that screen. Data such as prices stays shared.
 
 use eframe::egui;
 use std::sync::Arc;
@@ -10,66 +12,83 @@ use async_channel::Sender;
 use crate::app::{
     AppState, Screen,
     events::AppEvent,
-    window_manager::{WindowManager, WindowId},
+    keybindings::KeyAction,
+    window_manager::{self, WindowManager, WindowId},
     window_app::WindowApp,
 };
 
-/// Handle Tab key navigation for a window, updating the window's screen.
-pub fn handle_window_navigation(
+/// Keep a window's tracked geometry in step with its viewport.
+///
+/// Geometry restored from a layout file is applied instead on the first frame,
+/// clamped onto the monitor the viewport opened on.
+pub fn sync_window_geometry(
     ctx: &egui::Context,
     window_id: WindowId,
-    current_screen: Screen,
-    state: &Arc<RwLock<AppState>>,
     window_manager: &Arc<RwLock<WindowManager>>,
-    forward: bool,
-) -> Option<Screen> {
-    if !ctx.input(|i| {
-        let tab_pressed = i.key_pressed(egui::Key::Tab);
-        let shift = i.modifiers.shift;
-        tab_pressed && ((forward && !shift) || (!forward && shift))
-    }) {
-        return None;
+) {
+    let info = ctx.input(|i| i.viewport().clone());
+    let mut window_manager = window_manager.write();
+    let Some(window) = window_manager.get_window_mut(window_id) else {
+        return;
+    };
+
+    if window.restore_pending {
+        let (position, size) = match info.monitor_size {
+            Some(monitor) => window_manager::clamp_to_monitor(window.position, window.size, (monitor.x, monitor.y)),
+            // Some platforms never report the monitor; apply the layout as saved
+            None => (window.position, window.size),
+        };
+        if let Some((width, height)) = size {
+            ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(width, height)));
+        }
+        if let Some((x, y)) = position {
+            ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(egui::pos2(x, y)));
+        }
+        if window.is_fullscreen {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(true));
+        }
+        window.position = position;
+        window.size = size;
+        window.restore_pending = false;
+        return;
     }
 
-    let screens = Screen::all();
-    let current_idx = screens
-        .iter()
-        .position(|&s| s == current_screen)
-        .unwrap_or(0);
-    
-    let is_authenticated = {
-        let state = state.read();
-        state.is_authenticated()
-    };
-    
-    // Find next/previous screen, skipping protected screens if not authenticated
-    let mut new_idx = if forward {
-        (current_idx + 1) % screens.len()
-    } else {
-        if current_idx == 0 { screens.len() - 1 } else { current_idx - 1 }
-    };
-    
-    let mut attempts = 0;
-    while attempts < screens.len() {
-        let screen = screens[new_idx];
-        if !AppState::requires_auth(screen) || is_authenticated {
-            // Update window's screen
-            let mut window_manager = window_manager.write();
-            if let Some(window) = window_manager.get_window_mut(window_id) {
-                window.screen = screen;
-                window.title = format!("Terminal - {}", screen.title());
-            }
-            return Some(screen);
+    if let Some(fullscreen) = info.fullscreen {
+        window.is_fullscreen = fullscreen;
+    }
+    // Fullscreen and minimized rects aren't worth restoring
+    if window.is_fullscreen || info.minimized == Some(true) {
+        return;
+    }
+    if let Some(rect) = info.outer_rect {
+        window.position = Some((rect.min.x, rect.min.y));
+    }
+    if let Some(rect) = info.inner_rect {
+        window.size = Some((rect.width(), rect.height()));
+    }
+}
+
+/// Handle the screen navigation shortcuts (Tab / Shift+Tab by default) and
+/// the refresh shortcut for a window.
+///
+/// Key input is delivered per viewport, so this only moves (or refreshes) the
+/// window that has focus; the main window and other windows keep their screens.
+pub fn handle_window_navigation(ctx: &egui::Context, state: &AppState, window_app: &mut WindowApp) {
+    // The search palette owns the keyboard while it is open
+    if state.search.open {
+        return;
+    }
+    for action in crate::app::keybindings::pressed(ctx, &state.settings) {
+        match action {
+            KeyAction::NextScreen => window_app.next_screen(),
+            KeyAction::PrevScreen => window_app.previous_screen(),
+            _ => {}
         }
-        new_idx = if forward {
-            (new_idx + 1) % screens.len()
-        } else {
-            if new_idx == 0 { screens.len() - 1 } else { new_idx - 1 }
-        };
-        attempts += 1;
     }
-    
-    None
+    if state.is_authenticated() && crate::ui::refresh_pressed(ctx) {
+        let screen = window_app.current_screen();
+        window_app.refresh(screen);
+    }
 }
 
 /// Render a screen in a viewport window.
@@ -110,8 +129,11 @@ pub fn render_viewport_screen(
         Screen::Wallet => {
             crate::ui::screens::wallet::render(ui, state, window_app);
         },
+        Screen::Portfolio => {
+            crate::ui::screens::portfolio::render(ui, state, window_app);
+        },
         Screen::Transactions => {
-            crate::ui::screens::transactions::render(ui, state);
+            crate::ui::screens::transactions::render(ui, state, window_app);
         },
         Screen::Tokens => {
             crate::ui::screens::tokens::render(ui, state, window_app);
@@ -159,6 +181,13 @@ pub fn show_deferred_viewport(
             return;
         }
 
+        // Closing a secondary window drops it from the manager (and the saved layout)
+        if ctx.input(|i| i.viewport().close_requested()) {
+            window_manager.write().remove_window(viewport_id);
+            return;
+        }
+        sync_window_geometry(ctx, window_id, &window_manager);
+
         // Get current window screen from window manager
         let current_screen = {
             let window_manager = window_manager.read();
@@ -171,37 +200,6 @@ pub fn show_deferred_viewport(
         let title = format!("Terminal - {}", current_screen.title());
         ctx.send_viewport_cmd_to(viewport_id, egui::ViewportCommand::Title(title));
         
-        // Handle Tab key navigation (forward and backward)
-        let state_clone = state.clone();
-        let window_manager_clone = window_manager.clone();
-        handle_window_navigation(
-            ctx,
-            window_id,
-            current_screen,
-            &state_clone,
-            &window_manager_clone,
-            true,
-        );
-        
-        let state_clone = state.clone();
-        let window_manager_clone = window_manager.clone();
-        handle_window_navigation(
-            ctx,
-            window_id,
-            current_screen,
-            &state_clone,
-            &window_manager_clone,
-            false,
-        );
-        
-        // Get updated screen after potential navigation
-        let screen_to_render = {
-            let window_manager = window_manager.read();
-            window_manager.get_window(window_id)
-                .map(|w| w.screen)
-                .unwrap_or(Screen::Terminal)
-        };
-        
         // Read state for rendering
         let state_for_render = state.read().clone();
         
@@ -213,10 +211,23 @@ pub fn show_deferred_viewport(
             window_id,
         );
         
+        // Handle Tab key navigation (forward and backward) for this window only
+        handle_window_navigation(ctx, &state_for_render, &mut window_app);
+        
+        // Get updated screen after potential navigation
+        let screen_to_render = window_app.current_screen();
+        
         // Create a cube for screens that need it
-        let mut cube = crate::ui::cube::RotatingCube::new();
+        let mut cube = crate::ui::cube::RotatingCube::from_branding(&state_for_render.branding);
         
         egui::CentralPanel::default().show(ctx, |ui| {
+            // The nav bar in this window changes this window's screen
+            if state_for_render.is_authenticated() {
+                crate::ui::widgets::nav_bar::render_nav_bar(ui, &state_for_render, &mut window_app);
+                ui.add_space(5.0);
+                ui.separator();
+                ui.add_space(5.0);
+            }
             render_viewport_screen(ui, screen_to_render, &state_for_render, &mut window_app, &mut cube);
         });
     });
@@ -0,0 +1,167 @@
+//! # Wallet Identities
+//!
+//! A user-chosen label and color per wallet address. With several wallets in
+//! play (derived accounts, a keypair file, a generated wallet) the status
+//! bar, transaction rows, portfolio positions and the swap confirmation show
+//! a small colored chip naming the wallet they belong to.
+//!
+//! Identities are saved in the local database by the
+//! [`WalletRepository`](crate::core::store::WalletRepository), keyed by address.
+//! A wallet nobody has labelled yet is shown by its shortened address in a
+//! color picked from [`PALETTE`] by that address, so it keeps the same color
+//! from one session to the next.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Chip colors offered in the picker, and the defaults for unlabelled wallets
+pub const PALETTE: [[u8; 3]; 8] = [
+    [0x4e, 0x9a, 0xf1], // blue
+    [0x2e, 0xcc, 0x71], // green
+    [0xf3, 0x9c, 0x12], // orange
+    [0x9b, 0x59, 0xb6], // purple
+    [0x1a, 0xbc, 0x9c], // teal
+    [0xe9, 0x1e, 0x63], // pink
+    [0xf1, 0xc4, 0x0f], // yellow
+    [0x95, 0xa5, 0xa6], // grey
+];
+
+/// Longest label kept, in characters
+pub const MAX_LABEL_LEN: usize = 24;
+
+/// Label and chip color of one wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletIdentity {
+    pub label: String,
+    /// RGB
+    pub color: [u8; 3],
+}
+
+/// Identities of every labelled wallet, by address
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WalletIdentities {
+    wallets: BTreeMap<String, WalletIdentity>,
+}
+
+impl WalletIdentities {
+    pub fn get(&self, address: &str) -> Option<&WalletIdentity> {
+        self.wallets.get(address)
+    }
+
+    /// Label shown in chips: the user's label or the shortened address
+    pub fn label(&self, address: &str) -> String {
+        match self.wallets.get(address) {
+            Some(identity) => identity.label.clone(),
+            None => shared::format_address(address, 4, 4),
+        }
+    }
+
+    /// Chip color: the user's pick or the address's default
+    pub fn color(&self, address: &str) -> [u8; 3] {
+        self.wallets.get(address).map(|identity| identity.color).unwrap_or_else(|| default_color(address))
+    }
+
+    /// Set a wallet's label and color
+    ///
+    /// The label is trimmed and cut to [`MAX_LABEL_LEN`]; an empty label
+    /// forgets the wallet, which then goes back to its defaults.
+    pub fn set(&mut self, address: &str, label: &str, color: [u8; 3]) {
+        let label: String = label.trim().chars().take(MAX_LABEL_LEN).collect();
+        if label.is_empty() {
+            self.wallets.remove(address);
+        } else {
+            self.wallets.insert(address.to_string(), WalletIdentity { label, color });
+        }
+    }
+
+    /// Labelled wallets by address
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &WalletIdentity)> {
+        self.wallets.iter().map(|(address, identity)| (address.as_str(), identity))
+    }
+}
+
+impl FromIterator<(String, WalletIdentity)> for WalletIdentities {
+    fn from_iter<I: IntoIterator<Item = (String, WalletIdentity)>>(iter: I) -> Self {
+        Self { wallets: iter.into_iter().collect() }
+    }
+}
+
+/// Palette color for an unlabelled wallet, stable for a given address
+pub fn default_color(address: &str) -> [u8; 3] {
+    let hash = address.bytes().fold(0u32, |hash, byte| hash.wrapping_mul(31).wrapping_add(byte as u32));
+    PALETTE[hash as usize % PALETTE.len()]
+}
+
+/// Which wallets a list shows
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum WalletFilter {
+    /// Every wallet, rows told apart by their chips
+    #[default]
+    All,
+    /// Only the wallet with this address
+    Only(String),
+}
+
+impl WalletFilter {
+    /// Whether a row of `wallet` is shown; rows without one only in the combined view
+    pub fn matches(&self, wallet: Option<&str>) -> bool {
+        match self {
+            WalletFilter::All => true,
+            WalletFilter::Only(address) => wallet == Some(address.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+    const BOB: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    #[test]
+    fn test_unlabelled_wallet_defaults() {
+        let identities = WalletIdentities::default();
+        assert_eq!(identities.label(ALICE), "7xKX...gAsU");
+        assert_eq!(identities.color(ALICE), default_color(ALICE));
+        // Stable across calls and within the palette
+        assert_eq!(default_color(BOB), default_color(BOB));
+        assert!(PALETTE.contains(&default_color(BOB)));
+    }
+
+    #[test]
+    fn test_set_trims_and_clears() {
+        let mut identities = WalletIdentities::default();
+        identities.set(ALICE, "  Trading  ", PALETTE[3]);
+        assert_eq!(identities.label(ALICE), "Trading");
+        assert_eq!(identities.color(ALICE), PALETTE[3]);
+
+        identities.set(BOB, &"x".repeat(40), PALETTE[0]);
+        assert_eq!(identities.label(BOB).chars().count(), MAX_LABEL_LEN);
+
+        identities.set(ALICE, "   ", PALETTE[3]);
+        assert!(identities.get(ALICE).is_none());
+        assert_eq!(identities.color(ALICE), default_color(ALICE));
+        assert!(identities.get(BOB).is_some());
+    }
+
+    #[test]
+    fn test_round_trip_through_json() {
+        let mut identities = WalletIdentities::default();
+        identities.set(ALICE, "Cold storage", PALETTE[1]);
+        let json = serde_json::to_string(&identities).unwrap();
+        assert!(json.starts_with(&format!("{{\"{}\"", ALICE)));
+        assert_eq!(serde_json::from_str::<WalletIdentities>(&json).unwrap(), identities);
+    }
+
+    #[test]
+    fn test_filter() {
+        assert!(WalletFilter::All.matches(Some(ALICE)));
+        assert!(WalletFilter::All.matches(None));
+        let only = WalletFilter::Only(ALICE.to_string());
+        assert!(only.matches(Some(ALICE)));
+        assert!(!only.matches(Some(BOB)));
+        assert!(!only.matches(None));
+    }
+}
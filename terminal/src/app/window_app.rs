@@ -2,14 +2,16 @@
 //!
 //! Provides an App-like interface for secondary windows that allows screen renderers
 //! to work within deferred viewports. This wrapper delegates to the same handlers
-//! as the main App, but updates window-specific screen state.
+//! as the main App, but screen changes, Tab navigation and the nav bar's
+//! [`WindowView`] apply to its own window in the [`WindowManager`] only.
 
 use std::sync::Arc;
 use parking_lot::RwLock;
 use async_channel::Sender;
 use crate::app::{
-    AppState, Screen, SwapTab, TokenPickerTarget, TokenInfo,
+    AppState, Screen, SwapTab, TokenPickerTarget, TokenInfo, WindowView,
     events::AppEvent,
+    recovery::{RecoveryAction, RecoveryFlow},
     window_manager::{WindowManager, WindowId},
 };
 
@@ -40,19 +42,46 @@ impl WindowApp {
         }
     }
 
-    /// Handle screen change - updates the window's screen instead of global state.
+    /// Handle screen change - updates this window's screen only.
+    ///
+    /// Protected screens redirect this window to Auth when logged out; the
+    /// main window and other windows keep what they show.
     pub fn handle_screen_change(&mut self, screen: Screen) {
+        let screen = if AppState::requires_auth(screen) && !self.state.read().is_authenticated() {
+            tracing::info!("Access denied: {} requires authentication, redirecting window to Auth", screen.title());
+            Screen::Auth
+        } else {
+            screen
+        };
+        self.window_manager.write().set_window_screen(self.window_id, screen);
+    }
+
+    /// Move this window to the next (or previous) screen in Tab order
+    fn step_screen(&mut self, forward: bool) {
         use crate::app::handlers::navigation;
-        
-        // Update window's screen
-        let mut window_manager = self.window_manager.write();
-        if let Some(window) = window_manager.get_window_mut(self.window_id) {
-            window.screen = screen;
-            window.title = format!("Terminal - {}", screen.title());
-        }
-        
-        // Also update global state for consistency (some handlers might check it)
-        navigation::handle_screen_change(self.state.clone(), screen);
+
+        let is_authenticated = self.state.read().is_authenticated();
+        let screen = navigation::adjacent_screen(self.current_screen(), forward, is_authenticated);
+        self.window_manager.write().set_window_screen(self.window_id, screen);
+    }
+
+    /// Navigate this window to the next screen in Tab order
+    pub fn next_screen(&mut self) {
+        self.step_screen(true);
+    }
+
+    /// Navigate this window to the previous screen in Tab order
+    pub fn previous_screen(&mut self) {
+        self.step_screen(false);
+    }
+
+    /// Screen shown in this window
+    pub fn current_screen(&self) -> Screen {
+        self.window_manager
+            .read()
+            .get_window(self.window_id)
+            .map(|w| w.screen)
+            .unwrap_or(Screen::Terminal)
     }
 }
 
@@ -78,14 +107,97 @@ impl WindowApp {
         auth::handle_switch_to_signup(self.state.clone());
     }
 
+    pub fn handle_logout_click(&mut self) {
+        use crate::app::handlers::auth;
+        auth::handle_logout_click(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_change_password_click(&mut self) {
+        use crate::app::handlers::auth;
+        auth::handle_change_password_click(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_update_profile_click(&mut self) {
+        use crate::app::handlers::auth;
+        auth::handle_update_profile_click(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_session_refresh(&mut self) {
+        use crate::app::handlers::auth;
+        auth::handle_session_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_grant_session(&mut self) {
+        use crate::app::handlers::security;
+        security::handle_grant_session(self.state.clone());
+    }
+
+    pub fn handle_revoke_grant(&mut self, grant_id: u64) {
+        use crate::app::handlers::security;
+        security::handle_revoke_grant(self.state.clone(), grant_id);
+    }
+
+    pub fn handle_webhooks_refresh(&mut self) {
+        use crate::app::handlers::webhooks;
+        webhooks::handle_webhooks_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_webhook_create(&mut self) {
+        use crate::app::handlers::webhooks;
+        webhooks::handle_webhook_create(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_webhook_delete(&mut self, id: i64) {
+        use crate::app::handlers::webhooks;
+        webhooks::handle_webhook_delete(self.state.clone(), self.event_tx.clone(), id);
+    }
+
+    pub fn handle_webhook_deliveries(&mut self, id: i64) {
+        use crate::app::handlers::webhooks;
+        webhooks::handle_webhook_deliveries(self.state.clone(), self.event_tx.clone(), id);
+    }
+
+    pub fn handle_webhook_test(&mut self, id: i64) {
+        use crate::app::handlers::webhooks;
+        webhooks::handle_webhook_test(self.state.clone(), self.event_tx.clone(), id);
+    }
+
+    pub fn handle_api_keys_refresh(&mut self) {
+        use crate::app::handlers::api_keys;
+        api_keys::handle_api_keys_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_api_key_create(&mut self) {
+        use crate::app::handlers::api_keys;
+        api_keys::handle_api_key_create(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_api_key_revoke(&mut self, id: i64) {
+        use crate::app::handlers::api_keys;
+        api_keys::handle_api_key_revoke(self.state.clone(), self.event_tx.clone(), id);
+    }
+
     pub fn handle_swap_tab_change(&mut self, tab: SwapTab) {
         use crate::app::handlers::navigation;
         navigation::handle_swap_tab_change(self.state.clone(), tab);
+        if tab == SwapTab::History {
+            self.handle_swap_history_refresh();
+        }
     }
 
     pub fn handle_swap_execute_click(&mut self) {
         use crate::app::tasks::swap;
-        swap::execute_swap(self.state.clone(), self.event_tx.clone());
+        swap::prepare_swap(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_swap_confirm(&mut self) {
+        use crate::app::tasks::swap;
+        swap::confirm_swap(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_swap_confirmation_cancel(&mut self) {
+        use crate::app::handlers::swap;
+        swap::handle_swap_confirmation_cancel(self.state.clone());
     }
 
     pub fn handle_token_select(&mut self, token: TokenInfo, target: TokenPickerTarget) {
@@ -93,6 +205,11 @@ impl WindowApp {
         swap::handle_token_select(self.state.clone(), self.event_tx.clone(), token, target);
     }
 
+    pub fn handle_token_favorite_toggle(&mut self, mint: String) {
+        use crate::app::handlers::swap;
+        swap::handle_token_favorite_toggle(self.state.clone(), mint);
+    }
+
     pub fn handle_wallet_connect_click(&mut self) {
         use crate::app::handlers::wallet;
         wallet::handle_wallet_connect_click(self.state.clone(), self.event_tx.clone());
@@ -108,6 +225,181 @@ impl WindowApp {
         wallet::handle_wallet_disconnect_click(self.state.clone());
     }
 
+    pub fn handle_wallet_label_save(&mut self) {
+        use crate::app::handlers::wallet;
+        wallet::handle_wallet_label_save(self.state.clone());
+    }
+
+    pub fn handle_send_review(&mut self) {
+        use crate::app::tasks::transfer;
+        transfer::prepare_transfer(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_send_confirm(&mut self) {
+        use crate::app::tasks::transfer;
+        transfer::confirm_transfer(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_airdrop_request(&mut self) {
+        use crate::app::tasks::airdrop;
+        airdrop::request_airdrop(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_staking_refresh(&mut self) {
+        use crate::app::tasks::staking;
+        staking::load_staking(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_stake_action(&mut self, action: crate::app::handlers::staking::StakeAction, stake_account: Option<String>) {
+        use crate::app::tasks::staking;
+        staking::run_stake_action(self.state.clone(), self.event_tx.clone(), action, stake_account);
+    }
+
+    pub fn handle_send_cancel(&mut self) {
+        use crate::app::handlers::send;
+        send::handle_send_cancel(self.state.clone());
+    }
+
+    pub fn handle_recovery_action(&mut self, flow: RecoveryFlow, action: RecoveryAction) {
+        use crate::app::handlers::recovery;
+        recovery::handle_recovery_action(self.state.clone(), self.event_tx.clone(), flow, action);
+    }
+
+    pub fn handle_recovery_dismiss(&mut self, flow: RecoveryFlow) {
+        use crate::app::handlers::recovery;
+        recovery::handle_recovery_dismiss(self.state.clone(), flow);
+    }
+
+    pub fn handle_token_balances_refresh(&mut self) {
+        use crate::app::handlers::wallet;
+        wallet::handle_token_balances_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_transactions_refresh(&mut self) {
+        use crate::app::handlers::transactions;
+        transactions::handle_transactions_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn refresh(&mut self, screen: Screen) {
+        use crate::app::handlers::refresh;
+        refresh::handle_refresh(self.state.clone(), self.event_tx.clone(), screen);
+    }
+
+    pub fn refresh_all(&mut self) {
+        use crate::app::handlers::refresh;
+        refresh::handle_refresh_all(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_transactions_export(&mut self) {
+        use crate::app::handlers::transactions;
+        transactions::handle_transactions_export(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_trade_import_pick_file(&mut self) {
+        use crate::app::handlers::trade_import;
+        trade_import::handle_trade_import_pick_file(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_trade_import_validate(&mut self) {
+        use crate::app::handlers::trade_import;
+        trade_import::handle_trade_import_validate(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_trade_import_commit(&mut self) {
+        use crate::app::handlers::trade_import;
+        trade_import::handle_trade_import_commit(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_trade_stats_refresh(&mut self) {
+        use crate::app::handlers::trade_import;
+        trade_import::handle_trade_stats_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_swap_history_refresh(&mut self) {
+        use crate::app::handlers::swap;
+        swap::handle_swap_history_refresh(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_swap_history_export(&mut self) {
+        use crate::app::handlers::swap;
+        swap::handle_swap_history_export(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_share_chart(&mut self) {
+        use crate::app::handlers::share;
+        share::handle_share_chart(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_share_portfolio(&mut self) {
+        use crate::app::handlers::share;
+        share::handle_share_portfolio(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_onramp_open(&mut self) {
+        use crate::app::handlers::onramp;
+        onramp::handle_onramp_open(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_onramp_buy(&mut self, provider_id: String) {
+        use crate::app::handlers::onramp;
+        onramp::handle_onramp_buy(self.state.clone(), provider_id);
+    }
+
+    pub fn handle_rebalance_save(&mut self) {
+        use crate::app::handlers::rebalance;
+        rebalance::handle_rebalance_save(self.state.clone());
+    }
+
+    pub fn handle_rebalance_propose(&mut self) {
+        use crate::app::handlers::rebalance;
+        rebalance::handle_rebalance_propose(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_rebalance_queue_all(&mut self) {
+        use crate::app::handlers::rebalance;
+        rebalance::handle_rebalance_queue_all(self.state.clone());
+    }
+
+    pub fn handle_search_toggle(&mut self) {
+        use crate::app::handlers::search;
+        search::handle_search_toggle(self.state.clone());
+    }
+
+    pub fn handle_search_query(&mut self, query: String) {
+        use crate::app::handlers::search;
+        search::handle_search_query(self.state.clone(), self.event_tx.clone(), query);
+    }
+
+    pub fn handle_search_select(&mut self, target: crate::app::search::SearchTarget) {
+        use crate::app::handlers::search;
+        search::handle_search_select(self.state.clone(), target);
+    }
+
+    pub fn handle_command_palette_toggle(&mut self) {
+        use crate::app::handlers::commands;
+        commands::handle_command_palette_toggle(self.state.clone());
+    }
+
+    pub fn handle_mnemonic_import(&mut self) {
+        use crate::app::handlers::keystore;
+        keystore::handle_mnemonic_import(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_derived_scan(&mut self) {
+        use crate::app::handlers::keystore;
+        keystore::handle_derived_scan(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_derived_activate(&mut self, index: u32) {
+        use crate::app::handlers::keystore;
+        keystore::handle_derived_activate(self.state.clone(), self.event_tx.clone(), index);
+    }
+
+    pub fn handle_derived_label(&mut self, index: u32, label: String) {
+        use crate::app::handlers::keystore;
+        keystore::handle_derived_label(self.state.clone(), index, label);
+    }
+
     pub fn trigger_quote_fetch(&mut self) {
         use crate::app::tasks::swap;
         swap::trigger_quote_fetch(self.state.clone(), self.event_tx.clone());
@@ -123,11 +415,36 @@ impl WindowApp {
         settings::handle_theme_color_change(self.state.clone(), config);
     }
 
+    pub fn handle_theme_import(&mut self) {
+        use crate::app::handlers::settings;
+        settings::handle_theme_import(self.state.clone());
+    }
+
+    pub fn handle_theme_export(&mut self) {
+        use crate::app::handlers::settings;
+        settings::handle_theme_export(self.state.clone());
+    }
+
     pub fn handle_settings_save(&mut self) {
         use crate::app::handlers::settings;
         settings::handle_settings_save(self.state.clone());
     }
 
+    pub fn handle_server_list_save(&mut self) {
+        use crate::app::handlers::settings;
+        settings::handle_server_list_save(self.state.clone());
+    }
+
+    pub fn handle_connection_apply(&mut self) {
+        use crate::app::handlers::settings;
+        settings::handle_connection_apply(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_indicator_config_change(&mut self, config: crate::ui::chart::indicators::IndicatorConfig) {
+        use crate::app::handlers::settings;
+        settings::handle_indicator_config_change(self.state.clone(), config);
+    }
+
     pub fn handle_settings_reset(&mut self) {
         use crate::app::handlers::settings;
         settings::handle_settings_reset(self.state.clone());
@@ -143,6 +460,154 @@ impl WindowApp {
         tasks::market::fetch_candles(self.state.clone(), self.event_tx.clone(), symbol.to_string(), timeframe);
     }
 
+    pub fn handle_asset_hover(&mut self, hovered: Option<String>) {
+        use crate::app::{handlers, tasks};
+        let intent = handlers::live_assets::handle_asset_hover(self.state.clone(), hovered.as_deref(), std::time::Instant::now());
+        if let Some(symbol) = intent {
+            tasks::market::prefetch_token_detail(self.state.clone(), self.event_tx.clone(), symbol);
+        }
+    }
+
+    pub fn handle_asset_focus(&mut self, symbol: String) {
+        use crate::app::{handlers, tasks};
+        let intent = handlers::live_assets::handle_asset_focus(self.state.clone(), &symbol, std::time::Instant::now());
+        if let Some(symbol) = intent {
+            tasks::market::prefetch_token_detail(self.state.clone(), self.event_tx.clone(), symbol);
+        }
+    }
+
+    pub fn handle_token_detail_open(&mut self, symbol: String) {
+        use crate::app::{handlers, tasks};
+        if handlers::live_assets::handle_token_detail_open(self.state.clone(), &symbol, std::time::Instant::now()) {
+            tasks::market::fetch_token_detail(self.state.clone(), self.event_tx.clone(), symbol);
+        }
+    }
+
+    pub fn handle_token_detail_close(&mut self) {
+        use crate::app::handlers::live_assets;
+        live_assets::handle_token_detail_close(self.state.clone());
+    }
+
+    pub fn handle_asset_sort_click(&mut self, column: crate::app::AssetSortColumn) {
+        use crate::app::handlers::live_assets;
+        live_assets::handle_asset_sort_click(self.state.clone(), column);
+    }
+
+    pub fn handle_asset_chart_open(&mut self, symbol: String) {
+        use crate::app::handlers::live_assets;
+        let timeframe = live_assets::handle_asset_chart_open(self.state.clone(), &symbol);
+        self.handle_screen_change(Screen::LiveChart);
+        if let Some(timeframe) = timeframe {
+            self.fetch_candles(&symbol, timeframe);
+        }
+    }
+
+    pub fn handle_bandwidth_saver_toggle(&mut self, enabled: bool) {
+        use crate::app::handlers::settings;
+        settings::handle_bandwidth_saver_toggle(self.state.clone(), enabled);
+    }
+
+    pub fn handle_stale_price_threshold_change(&mut self, secs: u64) {
+        use crate::app::handlers::settings;
+        settings::handle_stale_price_threshold_change(self.state.clone(), secs);
+    }
+
+    pub fn handle_idle_teardown_change(&mut self, mins: u64) {
+        use crate::app::handlers::settings;
+        settings::handle_idle_teardown_change(self.state.clone(), mins);
+    }
+
+    pub fn handle_notification_category_toggle(&mut self, category: shared::NoticeCategory, enabled: bool) {
+        use crate::app::handlers::settings;
+        settings::handle_notification_category_toggle(self.state.clone(), category, enabled);
+    }
+
+    pub fn handle_key_capture(&mut self, action: Option<crate::app::keybindings::KeyAction>) {
+        use crate::app::handlers::settings;
+        settings::handle_key_capture(self.state.clone(), action);
+    }
+
+    pub fn handle_key_binding_change(&mut self, action: crate::app::keybindings::KeyAction, combo: crate::app::keybindings::KeyCombo) {
+        use crate::app::handlers::settings;
+        settings::handle_key_binding_change(self.state.clone(), action, combo);
+    }
+
+    pub fn handle_key_bindings_reset(&mut self) {
+        use crate::app::handlers::settings;
+        settings::handle_key_bindings_reset(self.state.clone());
+    }
+
+    pub fn handle_locale_change(&mut self, locale: crate::ui::i18n::Locale) {
+        use crate::app::handlers::settings;
+        settings::handle_locale_change(self.state.clone(), locale);
+    }
+
+    pub fn handle_usage_analytics_toggle(&mut self, enabled: bool) {
+        use crate::app::handlers::settings;
+        settings::handle_usage_analytics_toggle(self.state.clone(), enabled);
+    }
+
+    pub fn handle_usage_data_delete(&mut self) {
+        use crate::app::handlers::settings;
+        settings::handle_usage_data_delete(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_chart_action(&mut self, action: crate::ui::chart::annotations::ChartAction) {
+        use crate::app::handlers::annotations;
+        annotations::handle_chart_action(self.state.clone(), action);
+    }
+
+    pub fn handle_ai_chat_connect(&mut self) {
+        use crate::app::handlers::ai_chat;
+        ai_chat::handle_ai_chat_connect(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn handle_ai_chat_send(&mut self, text: String) {
+        use crate::app::handlers::ai_chat;
+        ai_chat::handle_ai_chat_send(self.state.clone(), text);
+    }
+
+    pub fn handle_ai_chat_cancel(&mut self) {
+        use crate::app::handlers::ai_chat;
+        ai_chat::handle_ai_chat_cancel(self.state.clone());
+    }
+
+    pub fn handle_risk_thresholds_change(&mut self, thresholds: crate::app::RiskThresholds) {
+        use crate::app::handlers::risk;
+        risk::handle_risk_thresholds_change(self.state.clone(), thresholds);
+    }
+
+    pub fn fetch_depth(&mut self, input: &str, output: &str) {
+        use crate::app::tasks;
+        tasks::market::fetch_depth(self.state.clone(), self.event_tx.clone(), input.to_string(), output.to_string());
+    }
+
+    pub fn fetch_market_analytics(&mut self) {
+        use crate::app::tasks;
+        tasks::market::fetch_market_analytics(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn fetch_sparklines(&mut self) {
+        use crate::app::tasks;
+        tasks::market::fetch_sparklines(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn check_backend_health(&mut self) {
+        use crate::app::tasks;
+        tasks::health::check_health(self.state.clone(), self.event_tx.clone());
+    }
+
+    pub fn load_friends(&mut self) {
+        use crate::app::tasks;
+        tasks::messaging::load_friends(self.state.clone());
+    }
+
+    pub fn handle_websocket_reconnect(&mut self) {
+        use crate::services::api::websocket;
+        let mut state = self.state.write();
+        websocket::restart_price_stream(&mut state, self.event_tx.clone(), self.state.clone());
+    }
+
     pub fn open_token_picker_internal(&self, _state: &mut AppState, target: TokenPickerTarget) {
         use crate::app::handlers::swap;
         swap::open_token_picker(self.state.clone(), target);
@@ -179,6 +644,62 @@ impl crate::app::app_trait::AppLike for WindowApp {
         self.handle_switch_to_signup();
     }
     
+    fn handle_logout_click(&mut self) {
+        self.handle_logout_click();
+    }
+    
+    fn handle_change_password_click(&mut self) {
+        self.handle_change_password_click();
+    }
+    
+    fn handle_update_profile_click(&mut self) {
+        self.handle_update_profile_click();
+    }
+    
+    fn handle_session_refresh(&mut self) {
+        self.handle_session_refresh();
+    }
+    
+    fn handle_grant_session(&mut self) {
+        self.handle_grant_session();
+    }
+    
+    fn handle_revoke_grant(&mut self, grant_id: u64) {
+        self.handle_revoke_grant(grant_id);
+    }
+    
+    fn handle_webhooks_refresh(&mut self) {
+        self.handle_webhooks_refresh();
+    }
+    
+    fn handle_webhook_create(&mut self) {
+        self.handle_webhook_create();
+    }
+    
+    fn handle_webhook_delete(&mut self, id: i64) {
+        self.handle_webhook_delete(id);
+    }
+    
+    fn handle_webhook_deliveries(&mut self, id: i64) {
+        self.handle_webhook_deliveries(id);
+    }
+    
+    fn handle_webhook_test(&mut self, id: i64) {
+        self.handle_webhook_test(id);
+    }
+    
+    fn handle_api_keys_refresh(&mut self) {
+        self.handle_api_keys_refresh();
+    }
+    
+    fn handle_api_key_create(&mut self) {
+        self.handle_api_key_create();
+    }
+    
+    fn handle_api_key_revoke(&mut self, id: i64) {
+        self.handle_api_key_revoke(id);
+    }
+    
     fn handle_screen_change(&mut self, screen: Screen) {
         self.handle_screen_change(screen);
     }
@@ -188,22 +709,58 @@ impl crate::app::app_trait::AppLike for WindowApp {
     }
     
     fn next_screen(&mut self) {
-        use crate::app::handlers::navigation;
-        navigation::next_screen(self.state.clone());
+        self.next_screen();
     }
     
     fn previous_screen(&mut self) {
-        use crate::app::handlers::navigation;
-        navigation::previous_screen(self.state.clone());
+        self.previous_screen();
+    }
+
+    fn refresh(&mut self, screen: Screen) {
+        self.refresh(screen);
+    }
+
+    fn refresh_all(&mut self) {
+        self.refresh_all();
+    }
+    
+    fn current_screen(&self) -> Screen {
+        self.current_screen()
+    }
+    
+    fn view(&self) -> WindowView {
+        self.window_manager
+            .read()
+            .get_window(self.window_id)
+            .map(|w| w.view.clone())
+            .unwrap_or_default()
+    }
+    
+    fn set_view(&mut self, view: WindowView) {
+        if let Some(window) = self.window_manager.write().get_window_mut(self.window_id) {
+            window.view = view;
+        }
     }
     
     fn handle_swap_execute_click(&mut self) {
         self.handle_swap_execute_click();
     }
+
+    fn handle_swap_confirm(&mut self) {
+        self.handle_swap_confirm();
+    }
+
+    fn handle_swap_confirmation_cancel(&mut self) {
+        self.handle_swap_confirmation_cancel();
+    }
     
     fn handle_token_select(&mut self, token: TokenInfo, target: TokenPickerTarget) {
         self.handle_token_select(token, target);
     }
+
+    fn handle_token_favorite_toggle(&mut self, mint: String) {
+        self.handle_token_favorite_toggle(mint);
+    }
     
     fn handle_wallet_connect_click(&mut self) {
         self.handle_wallet_connect_click();
@@ -216,6 +773,142 @@ impl crate::app::app_trait::AppLike for WindowApp {
     fn handle_wallet_disconnect_click(&mut self) {
         self.handle_wallet_disconnect_click();
     }
+
+    fn handle_wallet_label_save(&mut self) {
+        self.handle_wallet_label_save();
+    }
+
+    fn handle_send_review(&mut self) {
+        self.handle_send_review();
+    }
+
+    fn handle_send_confirm(&mut self) {
+        self.handle_send_confirm();
+    }
+
+    fn handle_airdrop_request(&mut self) {
+        self.handle_airdrop_request();
+    }
+
+    fn handle_staking_refresh(&mut self) {
+        self.handle_staking_refresh();
+    }
+
+    fn handle_stake_action(&mut self, action: crate::app::handlers::staking::StakeAction, stake_account: Option<String>) {
+        self.handle_stake_action(action, stake_account);
+    }
+
+    fn handle_send_cancel(&mut self) {
+        self.handle_send_cancel();
+    }
+
+    fn handle_recovery_action(&mut self, flow: RecoveryFlow, action: RecoveryAction) {
+        self.handle_recovery_action(flow, action);
+    }
+
+    fn handle_recovery_dismiss(&mut self, flow: RecoveryFlow) {
+        self.handle_recovery_dismiss(flow);
+    }
+
+    fn handle_token_balances_refresh(&mut self) {
+        self.handle_token_balances_refresh();
+    }
+
+    fn handle_transactions_refresh(&mut self) {
+        self.handle_transactions_refresh();
+    }
+
+    fn handle_transactions_export(&mut self) {
+        self.handle_transactions_export();
+    }
+
+    fn handle_trade_import_pick_file(&mut self) {
+        self.handle_trade_import_pick_file();
+    }
+
+    fn handle_trade_import_validate(&mut self) {
+        self.handle_trade_import_validate();
+    }
+
+    fn handle_trade_import_commit(&mut self) {
+        self.handle_trade_import_commit();
+    }
+
+    fn handle_trade_stats_refresh(&mut self) {
+        self.handle_trade_stats_refresh();
+    }
+
+    fn handle_swap_history_refresh(&mut self) {
+        self.handle_swap_history_refresh();
+    }
+
+    fn handle_swap_history_export(&mut self) {
+        self.handle_swap_history_export();
+    }
+
+    fn handle_share_chart(&mut self) {
+        self.handle_share_chart();
+    }
+
+    fn handle_share_portfolio(&mut self) {
+        self.handle_share_portfolio();
+    }
+
+    fn handle_onramp_open(&mut self) {
+        self.handle_onramp_open();
+    }
+
+    fn handle_onramp_buy(&mut self, provider_id: String) {
+        self.handle_onramp_buy(provider_id);
+    }
+
+    fn handle_rebalance_save(&mut self) {
+        self.handle_rebalance_save();
+    }
+
+    fn handle_rebalance_propose(&mut self) {
+        self.handle_rebalance_propose();
+    }
+
+    fn handle_rebalance_queue_all(&mut self) {
+        self.handle_rebalance_queue_all();
+    }
+
+    fn handle_search_toggle(&mut self) {
+        self.handle_search_toggle();
+    }
+
+    fn handle_search_query(&mut self, query: String) {
+        self.handle_search_query(query);
+    }
+
+    fn handle_search_select(&mut self, target: crate::app::search::SearchTarget) {
+        self.handle_search_select(target);
+    }
+    
+    fn handle_command_palette_toggle(&mut self) {
+        self.handle_command_palette_toggle();
+    }
+    
+    fn handle_mnemonic_import(&mut self) {
+        self.handle_mnemonic_import();
+    }
+    
+    fn handle_derived_scan(&mut self) {
+        self.handle_derived_scan();
+    }
+    
+    fn handle_derived_activate(&mut self, index: u32) {
+        self.handle_derived_activate(index);
+    }
+    
+    fn handle_derived_label(&mut self, index: u32, label: String) {
+        self.handle_derived_label(index, label);
+    }
+    
+    fn handle_indicator_config_change(&mut self, config: crate::ui::chart::indicators::IndicatorConfig) {
+        self.handle_indicator_config_change(config);
+    }
     
     fn trigger_quote_fetch(&mut self) {
         self.trigger_quote_fetch();
@@ -228,10 +921,26 @@ impl crate::app::app_trait::AppLike for WindowApp {
     fn handle_theme_color_change(&mut self, config: crate::ui::theme::ThemeConfig) {
         self.handle_theme_color_change(config);
     }
+
+    fn handle_theme_import(&mut self) {
+        self.handle_theme_import();
+    }
+
+    fn handle_theme_export(&mut self) {
+        self.handle_theme_export();
+    }
     
     fn handle_settings_save(&mut self) {
         self.handle_settings_save();
     }
+
+    fn handle_server_list_save(&mut self) {
+        self.handle_server_list_save();
+    }
+
+    fn handle_connection_apply(&mut self) {
+        self.handle_connection_apply();
+    }
     
     fn handle_settings_reset(&mut self) {
         self.handle_settings_reset();
@@ -244,6 +953,114 @@ impl crate::app::app_trait::AppLike for WindowApp {
     fn fetch_candles(&mut self, symbol: &str, timeframe: shared::dto::market::Timeframe) {
         self.fetch_candles(symbol, timeframe);
     }
+
+    fn handle_asset_hover(&mut self, hovered: Option<String>) {
+        self.handle_asset_hover(hovered);
+    }
+
+    fn handle_asset_focus(&mut self, symbol: String) {
+        self.handle_asset_focus(symbol);
+    }
+
+    fn handle_token_detail_open(&mut self, symbol: String) {
+        self.handle_token_detail_open(symbol);
+    }
+
+    fn handle_token_detail_close(&mut self) {
+        self.handle_token_detail_close();
+    }
+
+    fn handle_asset_sort_click(&mut self, column: crate::app::AssetSortColumn) {
+        self.handle_asset_sort_click(column);
+    }
+
+    fn handle_asset_chart_open(&mut self, symbol: String) {
+        self.handle_asset_chart_open(symbol);
+    }
+
+    fn handle_bandwidth_saver_toggle(&mut self, enabled: bool) {
+        self.handle_bandwidth_saver_toggle(enabled);
+    }
+
+    fn handle_stale_price_threshold_change(&mut self, secs: u64) {
+        self.handle_stale_price_threshold_change(secs);
+    }
+
+    fn handle_idle_teardown_change(&mut self, mins: u64) {
+        self.handle_idle_teardown_change(mins);
+    }
+
+    fn handle_notification_category_toggle(&mut self, category: shared::NoticeCategory, enabled: bool) {
+        self.handle_notification_category_toggle(category, enabled);
+    }
+
+    fn handle_key_capture(&mut self, action: Option<crate::app::keybindings::KeyAction>) {
+        self.handle_key_capture(action);
+    }
+
+    fn handle_key_binding_change(&mut self, action: crate::app::keybindings::KeyAction, combo: crate::app::keybindings::KeyCombo) {
+        self.handle_key_binding_change(action, combo);
+    }
+
+    fn handle_key_bindings_reset(&mut self) {
+        self.handle_key_bindings_reset();
+    }
+
+    fn handle_locale_change(&mut self, locale: crate::ui::i18n::Locale) {
+        self.handle_locale_change(locale);
+    }
+
+    fn handle_usage_analytics_toggle(&mut self, enabled: bool) {
+        self.handle_usage_analytics_toggle(enabled);
+    }
+
+    fn handle_usage_data_delete(&mut self) {
+        self.handle_usage_data_delete();
+    }
+
+    fn handle_chart_action(&mut self, action: crate::ui::chart::annotations::ChartAction) {
+        self.handle_chart_action(action);
+    }
+
+    fn handle_ai_chat_connect(&mut self) {
+        self.handle_ai_chat_connect();
+    }
+
+    fn handle_ai_chat_send(&mut self, text: String) {
+        self.handle_ai_chat_send(text);
+    }
+
+    fn handle_ai_chat_cancel(&mut self) {
+        self.handle_ai_chat_cancel();
+    }
+
+    fn handle_risk_thresholds_change(&mut self, thresholds: crate::app::RiskThresholds) {
+        self.handle_risk_thresholds_change(thresholds);
+    }
+    
+    fn fetch_depth(&mut self, input: &str, output: &str) {
+        self.fetch_depth(input, output);
+    }
+    
+    fn fetch_market_analytics(&mut self) {
+        self.fetch_market_analytics();
+    }
+    
+    fn fetch_sparklines(&mut self) {
+        self.fetch_sparklines();
+    }
+    
+    fn check_backend_health(&mut self) {
+        self.check_backend_health();
+    }
+    
+    fn load_friends(&mut self) {
+        self.load_friends();
+    }
+
+    fn handle_websocket_reconnect(&mut self) {
+        self.handle_websocket_reconnect();
+    }
     
     fn open_token_picker_internal(&self, state: &mut AppState, target: TokenPickerTarget) {
         self.open_token_picker_internal(state, target);
@@ -7,13 +7,28 @@
 //!
 //! - **Main Window**: Root viewport (ViewportId::ROOT) - cannot be closed
 //! - **Secondary Windows**: Deferred viewports created on demand
-//! - **Window State**: Each window has independent screen, position, size, and fullscreen state
+//! - **Window State**: Each window has independent screen, position, size, and fullscreen state,
+//!   plus a [`crate::app::WindowView`] for screen-scoped UI state (the main window's lives in
+//!   `AppState::root_view`)
 //! - **Shared App State**: All windows share the same `Arc<RwLock<AppState>>` for data synchronization
+//! - **Layout Persistence**: The window list is saved in the local database on shutdown and
+//!   restored on the next launch
 
 use egui::ViewportId;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use crate::core::store::{Document, SettingsRepository, StoreError};
 use std::sync::atomic::{AtomicU64, Ordering};
 
+/// Layout format version; layouts saved by any other version are ignored
+pub const LAYOUT_VERSION: u32 = 1;
+
+/// An (x, y) position or (width, height) size in points
+type Point = (f32, f32);
+
+/// Smallest window size a restored layout may set
+const MIN_RESTORED_SIZE: (f32, f32) = (600.0, 400.0);
+
 /// Unique identifier for a window/viewport
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct WindowId(pub u64);
@@ -27,6 +42,8 @@ pub struct WindowState {
     pub viewport_id: ViewportId,
     /// Current screen displayed in this window
     pub screen: crate::app::Screen,
+    /// Screen-scoped state (nav bar token, ...); dropped with the window
+    pub view: crate::app::WindowView,
     /// Window title
     pub title: String,
     /// Whether this window is fullscreened
@@ -35,6 +52,87 @@ pub struct WindowState {
     pub position: Option<(f32, f32)>,
     /// Window size (width, height)
     pub size: Option<(f32, f32)>,
+    /// Saved geometry still has to be applied to the viewport
+    pub restore_pending: bool,
+}
+
+/// One window in a saved layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedWindow {
+    pub screen: crate::app::Screen,
+    pub title: String,
+    pub size: Option<(f32, f32)>,
+    pub position: Option<(f32, f32)>,
+    pub is_fullscreen: bool,
+}
+
+impl SavedWindow {
+    fn from_state(window: &WindowState) -> Self {
+        Self {
+            screen: window.screen,
+            title: window.title.clone(),
+            size: window.size,
+            position: window.position,
+            is_fullscreen: window.is_fullscreen,
+        }
+    }
+}
+
+/// Window arrangement persisted across restarts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowLayout {
+    /// Format version, compared against [`LAYOUT_VERSION`] on load
+    pub version: u32,
+    /// Main window geometry (its screen comes from the app state instead)
+    pub root: Option<SavedWindow>,
+    /// Secondary windows in the order they were opened
+    pub windows: Vec<SavedWindow>,
+}
+
+impl WindowLayout {
+    /// Load the saved layout
+    ///
+    /// None when nothing was saved yet; corrupted or version-mismatched
+    /// layouts are errors so the caller can log them and fall back to one window.
+    pub fn load(settings: &SettingsRepository) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let Some(layout) = settings.load::<WindowLayout>(Document::Layout)? else {
+            return Ok(None);
+        };
+        if layout.version != LAYOUT_VERSION {
+            return Err(format!("unsupported layout version {} (expected {})", layout.version, LAYOUT_VERSION).into());
+        }
+        Ok(Some(layout))
+    }
+
+    /// Replace the saved layout
+    pub fn save(&self, settings: &SettingsRepository) -> Result<(), StoreError> {
+        settings.save(Document::Layout, self)
+    }
+}
+
+/// Fit a saved window onto a monitor of the given size
+///
+/// The size is capped to the monitor and the position is moved so the whole
+/// window is visible; a window left on an unplugged monitor lands back on this one.
+pub fn clamp_to_monitor(
+    position: Option<Point>,
+    size: Option<Point>,
+    monitor: Point,
+) -> (Option<Point>, Option<Point>) {
+    let size = size.map(|(w, h)| {
+        (
+            w.max(MIN_RESTORED_SIZE.0).min(monitor.0),
+            h.max(MIN_RESTORED_SIZE.1).min(monitor.1),
+        )
+    });
+    let (width, height) = size.unwrap_or(MIN_RESTORED_SIZE);
+    let position = position.map(|(x, y)| {
+        (
+            x.clamp(0.0, (monitor.0 - width).max(0.0)),
+            y.clamp(0.0, (monitor.1 - height).max(0.0)),
+        )
+    });
+    (position, size)
 }
 
 /// Window manager that tracks all open windows
@@ -65,10 +163,12 @@ impl WindowManager {
             id: window_id,
             viewport_id,
             screen,
+            view: crate::app::WindowView::default(),
             title: "Solana DeFi Trading Terminal".to_string(),
             is_fullscreen: false,
             position: None,
             size: None,
+            restore_pending: false,
         };
         
         self.windows.insert(window_id, window_state);
@@ -92,10 +192,12 @@ impl WindowManager {
             id: window_id,
             viewport_id,
             screen,
+            view: crate::app::WindowView::default(),
             title: title.clone(),
             is_fullscreen: false,
             position: None,
             size: None,
+            restore_pending: false,
         };
         
         self.windows.insert(window_id, window_state);
@@ -188,6 +290,60 @@ impl WindowManager {
     pub fn is_managed(&self, viewport_id: ViewportId) -> bool {
         self.viewport_to_window.contains_key(&viewport_id)
     }
+
+    /// Snapshot the current window arrangement
+    pub fn layout(&self) -> WindowLayout {
+        let mut secondary: Vec<&WindowState> = self.windows.values().filter(|w| w.id.0 != 0).collect();
+        secondary.sort_by_key(|w| w.id.0);
+
+        WindowLayout {
+            version: LAYOUT_VERSION,
+            root: self.windows.get(&WindowId(0)).map(SavedWindow::from_state),
+            windows: secondary.into_iter().map(SavedWindow::from_state).collect(),
+        }
+    }
+
+    /// Recreate the windows of a saved layout
+    ///
+    /// Call after [`Self::register_root`]. Geometry is applied by each viewport
+    /// once it knows its monitor size (see `restore_pending`).
+    pub fn restore_layout(&mut self, layout: &WindowLayout) {
+        if let (Some(saved), Some(root)) = (&layout.root, self.windows.get_mut(&WindowId(0))) {
+            root.size = saved.size;
+            root.position = saved.position;
+            root.is_fullscreen = saved.is_fullscreen;
+            root.restore_pending = true;
+        }
+
+        for (index, saved) in layout.windows.iter().enumerate() {
+            let viewport_id = ViewportId::from_hash_of(("restored_window", index));
+            let window_id = self.create_window(viewport_id, saved.screen, Some(saved.title.clone()));
+            if let Some(window) = self.windows.get_mut(&window_id) {
+                window.size = saved.size;
+                window.position = saved.position;
+                window.is_fullscreen = saved.is_fullscreen;
+                window.restore_pending = true;
+            }
+        }
+    }
+
+    /// Close every secondary window and forget the main window's geometry
+    pub fn reset_layout(&mut self) {
+        self.windows.retain(|id, _| id.0 == 0);
+        self.viewport_to_window.retain(|_, id| id.0 == 0);
+        if let Some(root) = self.windows.get_mut(&WindowId(0)) {
+            root.position = None;
+            root.size = None;
+            root.restore_pending = false;
+        }
+    }
+
+    /// Save the current arrangement in the local database
+    pub fn save_layout(&self) -> Result<(), StoreError> {
+        self.layout().save(&crate::core::store::store().settings())?;
+        tracing::info!("Saved window layout ({} secondary windows)", self.window_count());
+        Ok(())
+    }
 }
 
 impl Default for WindowManager {
@@ -196,3 +352,111 @@ impl Default for WindowManager {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Screen;
+
+    fn arranged() -> WindowManager {
+        let mut manager = WindowManager::new();
+        manager.register_root(ViewportId::ROOT, Screen::Terminal);
+        for (name, screen) in [("chart", Screen::LiveChart), ("txs", Screen::Transactions)] {
+            let window_id = manager.create_window(ViewportId::from_hash_of(name), screen, None);
+            let window = manager.get_window_mut(window_id).unwrap();
+            window.position = Some((100.0, 50.0));
+            window.size = Some((900.0, 600.0));
+        }
+        manager
+    }
+
+    #[test]
+    fn test_layout_round_trip() {
+        let settings = crate::core::store::LocalStore::open_in_memory().unwrap().settings();
+        let layout = arranged().layout();
+        layout.save(&settings).unwrap();
+        let loaded = WindowLayout::load(&settings).unwrap().unwrap();
+        assert_eq!(loaded, layout);
+
+        let mut restored = WindowManager::new();
+        restored.register_root(ViewportId::ROOT, Screen::Terminal);
+        restored.restore_layout(&loaded);
+        assert_eq!(restored.window_count(), 2);
+        let screens: Vec<Screen> = restored.layout().windows.iter().map(|w| w.screen).collect();
+        assert_eq!(screens, vec![Screen::LiveChart, Screen::Transactions]);
+        assert!(restored.all_windows().iter().filter(|w| w.id.0 != 0).all(|w| w.restore_pending));
+    }
+
+    #[test]
+    fn test_bad_layouts_are_rejected() {
+        let settings = crate::core::store::LocalStore::open_in_memory().unwrap().settings();
+        assert!(WindowLayout::load(&settings).unwrap().is_none());
+
+        settings.save(Document::Layout, &serde_json::json!({ "version": 1, "windows": "chart" })).unwrap();
+        assert!(WindowLayout::load(&settings).is_err());
+
+        let mut layout = arranged().layout();
+        layout.version = LAYOUT_VERSION + 1;
+        layout.save(&settings).unwrap();
+        assert!(WindowLayout::load(&settings).is_err());
+    }
+
+    #[test]
+    fn test_clamp_to_monitor() {
+        let monitor = (1920.0, 1080.0);
+        // Left on a monitor that is no longer plugged in
+        assert_eq!(
+            clamp_to_monitor(Some((2500.0, 300.0)), Some((1000.0, 700.0)), monitor),
+            (Some((920.0, 300.0)), Some((1000.0, 700.0)))
+        );
+        // Larger than the monitor and above its top edge
+        assert_eq!(
+            clamp_to_monitor(Some((10.0, -40.0)), Some((2560.0, 1440.0)), monitor),
+            (Some((0.0, 0.0)), Some((1920.0, 1080.0)))
+        );
+        assert_eq!(clamp_to_monitor(None, None, monitor), (None, None));
+    }
+
+    #[test]
+    fn test_windows_keep_their_own_screen() {
+        let mut manager = arranged();
+        let chart = manager.get_window_by_viewport(ViewportId::from_hash_of("chart")).unwrap().id;
+        let txs = manager.get_window_by_viewport(ViewportId::from_hash_of("txs")).unwrap().id;
+
+        manager.set_window_screen(chart, Screen::Portfolio);
+        manager.get_window_mut(chart).unwrap().view.selected_token = Some("BONK".to_string());
+
+        assert_eq!(manager.get_window(chart).unwrap().screen, Screen::Portfolio);
+        assert_eq!(manager.get_window(chart).unwrap().title, "Terminal - Portfolio");
+        assert_eq!(manager.get_window(txs).unwrap().screen, Screen::Transactions);
+        assert_eq!(manager.get_window(txs).unwrap().view, crate::app::WindowView::default());
+        assert_eq!(manager.get_window(WindowId(0)).unwrap().screen, Screen::Terminal);
+    }
+
+    #[test]
+    fn test_closing_a_window_drops_its_state() {
+        let mut manager = arranged();
+        let viewport = ViewportId::from_hash_of("chart");
+        let chart = manager.get_window_by_viewport(viewport).unwrap().id;
+        manager.get_window_mut(chart).unwrap().view.show_token_picker = true;
+
+        assert_eq!(manager.remove_window(viewport), Some(chart));
+        assert_eq!(manager.window_count(), 1);
+        assert!(manager.get_window(chart).is_none());
+        assert!(manager.get_window_by_viewport(viewport).is_none());
+        assert!(!manager.is_managed(viewport));
+        let screens: Vec<Screen> = manager.layout().windows.iter().map(|w| w.screen).collect();
+        assert_eq!(screens, vec![Screen::Transactions]);
+        // Closing it again is a no-op
+        assert_eq!(manager.remove_window(viewport), None);
+    }
+
+    #[test]
+    fn test_reset_layout_keeps_root() {
+        let mut manager = arranged();
+        manager.reset_layout();
+        assert_eq!(manager.window_count(), 0);
+        assert!(manager.is_managed(ViewportId::ROOT));
+        assert!(!manager.is_managed(ViewportId::from_hash_of("chart")));
+    }
+}
@@ -0,0 +1,359 @@
+//! # Terminal Configuration
+//!
+//! Where the terminal connects: the Solana network and its RPC endpoint,
+//! the backend API and its price stream, and the slippage new swaps start
+//! with.
+//!
+//! [`TerminalConfig::load`] runs once in [`crate::app::App::new`]. It reads
+//! the settings saved by Settings > Connection from the local database and
+//! then applies these environment variables, which win over the saved ones:
+//!
+//! | Variable               | Field                                    |
+//! |------------------------|------------------------------------------|
+//! | `SOLANA_NETWORK`       | `network` (`mainnet`, `devnet`, `testnet`) |
+//! | `SOLANA_RPC_URL`       | `rpc_url`                                |
+//! | `API_BASE_URL`         | `api_url`                                |
+//! | `XTERMINAL_WS_URL`     | `ws_url`                                 |
+//! | `DEFAULT_SLIPPAGE_BPS` | `default_slippage_bps`                   |
+//!
+//! Invalid values in either place are logged and ignored.
+//!
+//! `--offline` or `TERMINAL_OFFLINE=1` starts the offline demo instead, see
+//! [`offline_requested`].
+
+use serde::{Deserialize, Serialize};
+use shared::validation::ValidationError;
+use super::store::{store, Document, SettingsRepository};
+
+/// Field name used for RPC URL errors
+pub const FIELD_RPC_URL: &str = "rpc_url";
+/// Field name used for API URL errors
+pub const FIELD_API_URL: &str = "api_url";
+/// Field name used for price stream URL errors
+pub const FIELD_WS_URL: &str = "ws_url";
+/// Field name used for slippage errors
+pub const FIELD_SLIPPAGE: &str = "default_slippage_bps";
+
+/// Slippage new swaps start with unless configured otherwise (0.5%)
+pub const DEFAULT_SLIPPAGE_BPS: u16 = 50;
+/// Highest configurable default slippage (50%)
+pub const MAX_SLIPPAGE_BPS: u16 = 5_000;
+
+/// Solana cluster the wallet talks to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SolanaNetwork {
+    Mainnet,
+    #[default]
+    Devnet,
+    Testnet,
+}
+
+impl SolanaNetwork {
+    pub const ALL: [SolanaNetwork; 3] = [SolanaNetwork::Mainnet, SolanaNetwork::Devnet, SolanaNetwork::Testnet];
+
+    /// Name shown in Settings
+    pub fn label(self) -> &'static str {
+        match self {
+            SolanaNetwork::Mainnet => "Mainnet",
+            SolanaNetwork::Devnet => "Devnet",
+            SolanaNetwork::Testnet => "Testnet",
+        }
+    }
+
+    /// Public RPC endpoint of the cluster
+    pub fn default_rpc_url(self) -> &'static str {
+        match self {
+            SolanaNetwork::Mainnet => "https://api.mainnet-beta.solana.com",
+            SolanaNetwork::Devnet => "https://api.devnet.solana.com",
+            SolanaNetwork::Testnet => "https://api.testnet.solana.com",
+        }
+    }
+}
+
+impl std::str::FromStr for SolanaNetwork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mainnet" | "mainnet-beta" => Ok(SolanaNetwork::Mainnet),
+            "devnet" => Ok(SolanaNetwork::Devnet),
+            "testnet" => Ok(SolanaNetwork::Testnet),
+            other => Err(format!("Unknown network '{}', expected mainnet, devnet or testnet", other)),
+        }
+    }
+}
+
+/// Connection settings, see the [module docs](self) for where they come from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerminalConfig {
+    pub network: SolanaNetwork,
+    /// RPC endpoint; `None` uses the network's public endpoint
+    pub rpc_url: Option<String>,
+    /// Primary backend; `None` keeps the Settings > Servers list as it is
+    pub api_url: Option<String>,
+    /// Price stream endpoint; `None` derives it from the active backend
+    pub ws_url: Option<String>,
+    /// Slippage a new swap starts with, in basis points
+    pub default_slippage_bps: u16,
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self {
+            network: SolanaNetwork::default(),
+            rpc_url: None,
+            api_url: None,
+            ws_url: None,
+            default_slippage_bps: DEFAULT_SLIPPAGE_BPS,
+        }
+    }
+}
+
+impl TerminalConfig {
+    /// Read the saved settings, then apply the environment
+    pub fn load() -> Self {
+        let config = match Self::load_saved(&store().settings()) {
+            Ok(Some(config)) => {
+                tracing::info!("Loaded connection settings");
+                config
+            }
+            Ok(None) => Self::default(),
+            Err(e) => {
+                tracing::warn!("Failed to load connection settings: {}. Using defaults.", e);
+                Self::default()
+            }
+        };
+        config.with_overrides(|name| std::env::var(name).ok())
+    }
+
+    /// Read and validate the saved settings; None when nothing was saved
+    pub fn load_saved(settings: &SettingsRepository) -> Result<Option<Self>, String> {
+        let Some(config) = settings.load::<Self>(Document::Connection).map_err(|e| e.to_string())? else {
+            return Ok(None);
+        };
+        config.validate().map(Some).map_err(|errors| {
+            errors.into_iter().map(|e| e.message).collect::<Vec<_>>().join("; ")
+        })
+    }
+
+    /// Save the settings in the local database
+    pub fn save(&self) -> Result<(), String> {
+        store().settings().save(Document::Connection, self).map_err(|e| e.to_string())
+    }
+
+    /// Apply environment variables looked up with `var`, skipping invalid ones
+    pub fn with_overrides(mut self, var: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| var(name).filter(|v| !v.trim().is_empty());
+
+        if let Some(value) = var("SOLANA_NETWORK") {
+            match value.parse() {
+                Ok(network) => self.network = network,
+                Err(e) => tracing::warn!("Ignoring SOLANA_NETWORK: {}", e),
+            }
+        }
+        for (name, field, schemes) in [
+            ("SOLANA_RPC_URL", &mut self.rpc_url, HTTP_SCHEMES),
+            ("API_BASE_URL", &mut self.api_url, HTTP_SCHEMES),
+            ("XTERMINAL_WS_URL", &mut self.ws_url, WS_SCHEMES),
+        ] {
+            if let Some(value) = var(name) {
+                match validate_url(&value, schemes) {
+                    Ok(url) => *field = Some(url),
+                    Err(e) => tracing::warn!("Ignoring {}: {}", name, e),
+                }
+            }
+        }
+        if let Some(value) = var("DEFAULT_SLIPPAGE_BPS") {
+            match parse_slippage(&value) {
+                Ok(bps) => self.default_slippage_bps = bps,
+                Err(e) => tracing::warn!("Ignoring DEFAULT_SLIPPAGE_BPS: {}", e),
+            }
+        }
+        self
+    }
+
+    /// Every field that is out of range or malformed
+    pub fn validate(self) -> Result<Self, Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        for (field, url, schemes) in [
+            (FIELD_RPC_URL, &self.rpc_url, HTTP_SCHEMES),
+            (FIELD_API_URL, &self.api_url, HTTP_SCHEMES),
+            (FIELD_WS_URL, &self.ws_url, WS_SCHEMES),
+        ] {
+            if let Some(Err(e)) = url.as_deref().map(|url| validate_url(url, schemes)) {
+                errors.push(ValidationError::new(field, e));
+            }
+        }
+        if let Err(e) = parse_slippage(&self.default_slippage_bps.to_string()) {
+            errors.push(ValidationError::new(FIELD_SLIPPAGE, e));
+        }
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// RPC endpoint the wallet uses
+    pub fn rpc_endpoint(&self) -> String {
+        self.rpc_url.clone().unwrap_or_else(|| self.network.default_rpc_url().to_string())
+    }
+
+    /// Price stream endpoint, derived from `api_base` unless overridden
+    pub fn price_stream_url(&self, api_base: &str) -> String {
+        if let Some(url) = &self.ws_url {
+            return url.clone();
+        }
+        api_base
+            .replace("http://", "ws://")
+            .replace("https://", "wss://")
+            + "/api/ws/prices"
+    }
+}
+
+/// Command line flag starting the offline demo
+pub const OFFLINE_FLAG: &str = "--offline";
+
+/// Whether to start the offline demo (mock services, no backend): `args`
+/// contain [`OFFLINE_FLAG`] or `TERMINAL_OFFLINE`, looked up with `var`, is `1`
+pub fn offline_requested(args: impl IntoIterator<Item = String>, var: impl Fn(&str) -> Option<String>) -> bool {
+    args.into_iter().skip(1).any(|arg| arg == OFFLINE_FLAG)
+        || var("TERMINAL_OFFLINE").is_some_and(|v| v.trim() == "1")
+}
+
+const HTTP_SCHEMES: &[&str] = &["http://", "https://"];
+const WS_SCHEMES: &[&str] = &["ws://", "wss://"];
+
+/// Check an `http(s)://` URL, returning it without a trailing slash
+pub fn validate_http_url(url: &str) -> Result<String, String> {
+    validate_url(url, HTTP_SCHEMES)
+}
+
+/// Check a `ws(s)://` URL, returning it without a trailing slash
+pub fn validate_ws_url(url: &str) -> Result<String, String> {
+    validate_url(url, WS_SCHEMES)
+}
+
+fn validate_url(url: &str, schemes: &[&str]) -> Result<String, String> {
+    let url = url.trim().trim_end_matches('/');
+    let host = schemes
+        .iter()
+        .find_map(|scheme| url.strip_prefix(scheme))
+        .ok_or_else(|| format!("{} must start with {}", url, schemes.join(" or ")))?;
+    if host.is_empty() || host.contains(char::is_whitespace) {
+        return Err(format!("{} is not a valid server address", url));
+    }
+    Ok(url.to_string())
+}
+
+/// Parse a default slippage in basis points (1 to [`MAX_SLIPPAGE_BPS`])
+pub fn parse_slippage(value: &str) -> Result<u16, String> {
+    match value.trim().parse::<u16>() {
+        Ok(bps) if (1..=MAX_SLIPPAGE_BPS).contains(&bps) => Ok(bps),
+        _ => Err(format!("Slippage must be a whole number of basis points from 1 to {}", MAX_SLIPPAGE_BPS)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_environment_overrides_file() {
+        let file = TerminalConfig {
+            network: SolanaNetwork::Testnet,
+            rpc_url: Some("https://rpc.example.com".to_string()),
+            api_url: Some("http://file.example.com".to_string()),
+            ws_url: None,
+            default_slippage_bps: 75,
+        };
+        let config = file.clone().with_overrides(env(&[
+            ("SOLANA_NETWORK", "Mainnet-Beta"),
+            ("API_BASE_URL", "https://api.example.com/"),
+            ("DEFAULT_SLIPPAGE_BPS", "100"),
+        ]));
+        assert_eq!(config.network, SolanaNetwork::Mainnet);
+        assert_eq!(config.rpc_url, file.rpc_url);
+        assert_eq!(config.api_url.as_deref(), Some("https://api.example.com"));
+        assert_eq!(config.default_slippage_bps, 100);
+
+        // Nothing set: the file stands
+        assert_eq!(file.clone().with_overrides(env(&[])), file);
+    }
+
+    #[test]
+    fn test_invalid_environment_is_ignored() {
+        let config = TerminalConfig::default().with_overrides(env(&[
+            ("SOLANA_NETWORK", "localnet"),
+            ("SOLANA_RPC_URL", "api.devnet.solana.com"),
+            ("XTERMINAL_WS_URL", "http://not-a-socket.example.com"),
+            ("DEFAULT_SLIPPAGE_BPS", "0"),
+            ("API_BASE_URL", "  "),
+        ]));
+        assert_eq!(config, TerminalConfig::default());
+    }
+
+    #[test]
+    fn test_offline_requested() {
+        let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert!(offline_requested(args(&["terminal", "--offline"]), env(&[])));
+        assert!(offline_requested(args(&["terminal"]), env(&[("TERMINAL_OFFLINE", "1")])));
+        assert!(!offline_requested(args(&["terminal"]), env(&[("TERMINAL_OFFLINE", "0")])));
+        // The program name is not a flag
+        assert!(!offline_requested(args(&["--offline"]), env(&[])));
+    }
+
+    #[test]
+    fn test_endpoints() {
+        let mut config = TerminalConfig::default();
+        assert_eq!(config.rpc_endpoint(), "https://api.devnet.solana.com");
+        assert_eq!(config.price_stream_url("https://api.example.com"), "wss://api.example.com/api/ws/prices");
+
+        config.network = SolanaNetwork::Mainnet;
+        assert_eq!(config.rpc_endpoint(), "https://api.mainnet-beta.solana.com");
+        config.rpc_url = Some("https://rpc.example.com".to_string());
+        config.ws_url = Some("ws://127.0.0.1:9000/prices".to_string());
+        assert_eq!(config.rpc_endpoint(), "https://rpc.example.com");
+        assert_eq!(config.price_stream_url("https://api.example.com"), "ws://127.0.0.1:9000/prices");
+    }
+
+    #[test]
+    fn test_validate_reports_every_field() {
+        let config = TerminalConfig {
+            network: SolanaNetwork::Devnet,
+            rpc_url: Some("ftp://rpc".to_string()),
+            api_url: Some("http://".to_string()),
+            ws_url: Some("https://prices".to_string()),
+            default_slippage_bps: MAX_SLIPPAGE_BPS + 1,
+        };
+        let fields: Vec<String> = config.validate().unwrap_err().into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, [FIELD_RPC_URL, FIELD_API_URL, FIELD_WS_URL, FIELD_SLIPPAGE]);
+
+        assert!(TerminalConfig::default().validate().is_ok());
+        assert_eq!(parse_slippage(" 250 "), Ok(250));
+        assert!(parse_slippage("0.5").is_err());
+    }
+
+    #[test]
+    fn test_saved_settings_round_trip() {
+        let settings = crate::core::store::LocalStore::open_in_memory().unwrap().settings();
+        assert_eq!(TerminalConfig::load_saved(&settings), Ok(None));
+
+        // Missing fields take their defaults
+        settings.save(Document::Connection, &serde_json::json!({ "network": "mainnet" })).unwrap();
+        let config = TerminalConfig::load_saved(&settings).unwrap().unwrap();
+        assert_eq!(config.network, SolanaNetwork::Mainnet);
+        assert_eq!(config.default_slippage_bps, DEFAULT_SLIPPAGE_BPS);
+
+        settings.save(Document::Connection, &serde_json::json!({ "rpc_url": "rpc.example.com" })).unwrap();
+        assert!(TerminalConfig::load_saved(&settings).unwrap_err().contains("must start with http:// or https://"));
+    }
+}
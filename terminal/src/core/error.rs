@@ -31,10 +31,13 @@
 //! Common error types automatically convert to `AppError`:
 //!
 //! - `String` → `AppError::Api`
+//! - `ApiError` → `AppError::Validation` when the backend rejected the input
+//!   (400, 422), `AppError::Api` otherwise
 //! - `WalletError` → `AppError::Wallet`
 //!
 //! ## Related Types
 //!
+//! - [`crate::services::api::ApiError`]: API client errors
 //! - [`crate::services::wallet::WalletError`]: Wallet-specific errors
 
 use thiserror::Error;
@@ -190,6 +193,15 @@ impl From<&str> for AppError {
     }
 }
 
+impl From<crate::services::api::ApiError> for AppError {
+    fn from(err: crate::services::api::ApiError) -> Self {
+        match err.status_code() {
+            Some(400 | 422) => AppError::Validation(err.to_string()),
+            _ => AppError::Api(err.to_string()),
+        }
+    }
+}
+
 impl From<crate::services::wallet::WalletError> for AppError {
     fn from(err: crate::services::wallet::WalletError) -> Self {
         AppError::Wallet(err.to_string())
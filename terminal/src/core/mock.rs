@@ -0,0 +1,214 @@
+//! # Mock API Service
+//!
+//! [`MockApiService`] stands in for the backend in handler and task tests:
+//! each call answers with the next response programmed for its method and
+//! is recorded, so a test can check what was asked without any network.
+//!
+//! ```rust,ignore
+//! let api = Arc::new(MockApiService::new());
+//! api.respond("get_prices", Err(ApiError::Timeout));
+//! state.write().api = Some(api.clone());
+//! // ... run the task ...
+//! assert_eq!(api.calls_to("get_prices").len(), 1);
+//! ```
+
+use crate::core::service::ApiService;
+use crate::services::api::{
+    ApiError, PriceResponse, SwapExecuteResponse, SwapHistoryItem, SwapHistoryQuery, SwapHistoryResponse,
+    SwapQuoteResponse, TokenBalance, TokenListItem, TransactionHistory, TransactionSubmitResponse, WalletBalance,
+};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use shared::dto::transactions::TransactionVersion;
+use shared::AuthResponse;
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+
+/// Call made to a [`MockApiService`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockCall {
+    /// Name of the [`ApiService`] method
+    pub method: &'static str,
+    /// Its arguments, `Debug`-formatted in order (tokens and passwords included)
+    pub args: Vec<String>,
+}
+
+/// [`ApiService`] answering with programmed responses, see the [module docs](self)
+///
+/// A method without a response left fails with a 501 [`ApiError::Api`]
+/// naming it, so an unexpected call shows up in the test's assertion.
+#[derive(Default)]
+pub struct MockApiService {
+    responses: Mutex<HashMap<&'static str, VecDeque<Box<dyn Any + Send>>>>,
+    calls: Mutex<Vec<MockCall>>,
+}
+
+impl MockApiService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `response` for the next call to `method`
+    ///
+    /// Its type must be the method's return type, `Result<T, ApiError>`,
+    /// except for `stream_history`, which takes the swaps to stream as
+    /// `Result<Vec<SwapHistoryItem>, String>`.
+    pub fn respond<T: Send + 'static>(&self, method: &'static str, response: T) -> &Self {
+        self.responses.lock().entry(method).or_default().push_back(Box::new(response));
+        self
+    }
+
+    /// Every call so far, oldest first
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().clone()
+    }
+
+    /// Arguments of every call to `method` so far, oldest first
+    pub fn calls_to(&self, method: &str) -> Vec<Vec<String>> {
+        self.calls.lock().iter().filter(|call| call.method == method).map(|call| call.args.clone()).collect()
+    }
+
+    /// Record the call and take the next response programmed for it
+    fn answer<T: 'static>(&self, method: &'static str, args: Vec<String>) -> Option<T> {
+        self.calls.lock().push(MockCall { method, args });
+        let response = self.responses.lock().get_mut(method)?.pop_front()?;
+        match response.downcast::<T>() {
+            Ok(response) => Some(*response),
+            Err(_) => panic!("response programmed for {} has the wrong type", method),
+        }
+    }
+
+    fn call<T: 'static>(&self, method: &'static str, args: Vec<String>) -> Result<T, ApiError> {
+        self.answer(method, args)
+            .unwrap_or_else(|| Err(ApiError::Api { status: 501, message: format!("{} not mocked", method) }))
+    }
+}
+
+/// `Debug`-formatted arguments of a [`MockCall`]
+macro_rules! args {
+    ($($arg:expr),* $(,)?) => {
+        vec![$(format!("{:?}", $arg)),*]
+    };
+}
+
+#[async_trait]
+impl ApiService for MockApiService {
+    async fn login(&self, email_or_username: String, password: String) -> Result<AuthResponse, ApiError> {
+        self.call("login", args![email_or_username, password])
+    }
+
+    async fn signup(&self, username: String, email: String, password: String) -> Result<AuthResponse, ApiError> {
+        self.call("signup", args![username, email, password])
+    }
+
+    async fn logout(&self, jwt_token: &str) -> Result<(), ApiError> {
+        self.call("logout", args![jwt_token])
+    }
+
+    async fn refresh_session(&self, jwt_token: &str) -> Result<AuthResponse, ApiError> {
+        self.call("refresh_session", args![jwt_token])
+    }
+
+    async fn change_password(&self, jwt_token: &str, current_password: String, new_password: String) -> Result<AuthResponse, ApiError> {
+        self.call("change_password", args![jwt_token, current_password, new_password])
+    }
+
+    async fn update_profile(&self, jwt_token: &str, email: String) -> Result<shared::UserInfo, ApiError> {
+        self.call("update_profile", args![jwt_token, email])
+    }
+
+    async fn get_prices(&self, symbols: &[&str]) -> Result<PriceResponse, ApiError> {
+        self.call("get_prices", args![symbols])
+    }
+
+    async fn get_wallet_balance(&self, address: &str) -> Result<WalletBalance, ApiError> {
+        self.call("get_wallet_balance", args![address])
+    }
+
+    async fn get_transaction_history(&self, address: &str, limit: usize) -> Result<TransactionHistory, ApiError> {
+        self.call("get_transaction_history", args![address, limit])
+    }
+
+    async fn get_swap_quote(&self, input_mint: &str, output_mint: &str, amount: u64, slippage_bps: u16) -> Result<SwapQuoteResponse, ApiError> {
+        self.call("get_swap_quote", args![input_mint, output_mint, amount, slippage_bps])
+    }
+
+    async fn execute_swap(&self, input_mint: &str, output_mint: &str, amount: u64, slippage_bps: u16, user_pubkey: &str, jwt_token: &str) -> Result<SwapExecuteResponse, ApiError> {
+        self.call("execute_swap", args![input_mint, output_mint, amount, slippage_bps, user_pubkey, jwt_token])
+    }
+
+    async fn simulate_swap(&self, request: &shared::dto::simulation::SimulateSwapRequest, jwt_token: &str) -> Result<shared::dto::simulation::SimulateSwapResponse, ApiError> {
+        self.call("simulate_swap", args![request, jwt_token])
+    }
+
+    async fn submit_transaction(&self, signed_transaction: String, version: TransactionVersion, input_mint: String, output_mint: String, input_amount: i64, output_amount: i64, price_impact: Option<f64>, slippage_bps: Option<i32>, jwt_token: &str) -> Result<TransactionSubmitResponse, ApiError> {
+        self.call(
+            "submit_transaction",
+            args![signed_transaction, version, input_mint, output_mint, input_amount, output_amount, price_impact, slippage_bps, jwt_token],
+        )
+    }
+
+    async fn update_transaction_status(&self, signature: &str, update: &shared::dto::transactions::TransactionStatusUpdate, jwt_token: &str) -> Result<(), ApiError> {
+        self.call("update_transaction_status", args![signature, update, jwt_token])
+    }
+
+    async fn record_transaction_latency(&self, signature: &str, latency: &shared::dto::latency::SwapLatency, jwt_token: &str) -> Result<(), ApiError> {
+        self.call("record_transaction_latency", args![signature, latency, jwt_token])
+    }
+
+    async fn get_token_balances(&self, address: &str) -> Result<Vec<TokenBalance>, ApiError> {
+        self.call("get_token_balances", args![address])
+    }
+
+    async fn get_token_list(&self) -> Result<Vec<TokenListItem>, ApiError> {
+        self.call("get_token_list", args![])
+    }
+
+    async fn get_swap_history(&self, jwt_token: &str, query: &SwapHistoryQuery) -> Result<SwapHistoryResponse, ApiError> {
+        self.call("get_swap_history", args![jwt_token, query])
+    }
+
+    async fn stream_history(&self, jwt_token: &str, query: &SwapHistoryQuery, on_batch: &mut (dyn for<'b> FnMut(&'b [SwapHistoryItem]) -> Result<(), String> + Send)) -> Result<u64, String> {
+        let swaps: Vec<SwapHistoryItem> = self
+            .answer::<Result<Vec<SwapHistoryItem>, String>>("stream_history", args![jwt_token, query])
+            .unwrap_or_else(|| Err("stream_history not mocked".to_string()))?;
+        on_batch(&swaps)?;
+        Ok(swaps.len() as u64)
+    }
+
+    async fn import_trades(&self, jwt_token: &str, request: &shared::dto::trades::TradeImportRequest) -> Result<shared::dto::trades::TradeImportResponse, ApiError> {
+        self.call("import_trades", args![jwt_token, request])
+    }
+
+    async fn get_trade_stats(&self, jwt_token: &str) -> Result<shared::dto::trades::TradeStatsResponse, ApiError> {
+        self.call("get_trade_stats", args![jwt_token])
+    }
+
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: usize) -> Result<Vec<shared::dto::market::OHLC>, ApiError> {
+        self.call("get_candles", args![symbol, timeframe, limit])
+    }
+
+    async fn get_candles_batch(&self, symbols: &[String], timeframe: &str) -> Result<shared::dto::market::CandleBatchResponse, ApiError> {
+        self.call("get_candles_batch", args![symbols, timeframe])
+    }
+
+    async fn get_streamed_symbols(&self) -> Result<shared::dto::market::StreamedSymbolsResponse, ApiError> {
+        self.call("get_streamed_symbols", args![])
+    }
+
+    async fn get_token_unlocks(&self, days: i64) -> Result<shared::dto::unlocks::TokenUnlocksResponse, ApiError> {
+        self.call("get_token_unlocks", args![days])
+    }
+
+    async fn get_depth(&self, input: &str, output: &str) -> Result<shared::dto::market::DepthResponse, ApiError> {
+        self.call("get_depth", args![input, output])
+    }
+
+    async fn get_market_analytics(&self, symbols: &[String], window: usize) -> Result<shared::dto::market::MarketAnalyticsResponse, ApiError> {
+        self.call("get_market_analytics", args![symbols, window])
+    }
+
+    async fn get_health(&self) -> Result<shared::dto::system::HealthResponse, ApiError> {
+        self.call("get_health", args![])
+    }
+}
@@ -4,13 +4,18 @@
 //!
 //! This module provides foundational abstractions used throughout the terminal application:
 //!
+//! - **Configuration**: Where the terminal connects (see [`config`] module)
 //! - **Error Types**: Centralized error handling (see [`error`] module)
+//! - **Local Database**: Everything saved between sessions (see [`store`] module)
 //! - **Service Traits**: Dependency injection traits for better testability (see [`service`] module)
 //!
 //! ## Modules
 //!
+//! - **[`config`]**: Connection settings (`TerminalConfig`, `SolanaNetwork`)
 //! - **[`error`]**: Application error types (`AppError`, `Result<T>`)
+//! - **`mock`** (tests only): `MockApiService`, an `ApiService` with programmed responses
 //! - **[`service`]**: Service traits for dependency injection (`ApiService`, `WalletService`)
+//! - **[`store`]**: SQLite database and its repositories (`LocalStore`, `SettingsRepository`, ...)
 //!
 //! ## Error Handling
 //!
@@ -41,6 +46,9 @@
 //! let api: Arc<dyn ApiService> = Arc::new(MockApiService::new());
 //! ```
 //!
+//! Tasks reach the backend through `AppState::api`, so a test swaps the
+//! mock in there and runs the real handler.
+//!
 //! ## Re-exports
 //!
 //! Common types are re-exported for convenience:
@@ -49,8 +57,12 @@
 //! - [`ApiService`]: API service trait
 //! - [`WalletService`]: Wallet service trait
 
+pub mod config;
 pub mod error;
+#[cfg(test)]
+pub(crate) mod mock;
 pub mod service;
+pub mod store;
 
 // Re-export commonly used types for convenience
 // Note: These may be unused in the current implementation but are part of the public API
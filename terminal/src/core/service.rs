@@ -3,15 +3,17 @@
 //! Traits for dependency injection, enabling better testability and modularity.
 
 use shared::AuthResponse;
-use crate::services::api::{PriceResponse, SwapQuoteResponse, SwapExecuteResponse, SwapHistoryItem, TokenListItem, WalletBalance, TokenBalance, TransactionHistory};
-use crate::services::wallet::{WalletError, WalletService as WalletServiceImpl};
+use crate::services::api::{ApiError, PriceResponse, SwapQuoteResponse, SwapExecuteResponse, SwapHistoryItem, SwapHistoryQuery, SwapHistoryResponse, TokenListItem, WalletBalance, TokenBalance, TransactionHistory};
+use crate::services::wallet::{WalletError, WalletService as WalletServiceImpl, WalletTransaction};
+use shared::dto::transactions::TransactionVersion;
 use solana_sdk::signature::Signature;
-use solana_sdk::transaction::Transaction;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
 use async_trait::async_trait;
 
 /// Trait for API service operations
 /// 
 /// This trait allows for dependency injection and mocking in tests.
+/// Failures are [`ApiError`]s; event payloads carry their message.
 ///
 /// Note: This trait is exported for public API and testing purposes.
 /// It may appear unused but enables dependency injection patterns.
@@ -19,19 +21,31 @@ use async_trait::async_trait;
 #[allow(dead_code)] // Exported for dependency injection and testing
 pub trait ApiService: Send + Sync {
     /// Login with username/email and password
-    async fn login(&self, email_or_username: String, password: String) -> Result<AuthResponse, String>;
+    async fn login(&self, email_or_username: String, password: String) -> Result<AuthResponse, ApiError>;
     
     /// Sign up a new user
-    async fn signup(&self, username: String, email: String, password: String) -> Result<AuthResponse, String>;
+    async fn signup(&self, username: String, email: String, password: String) -> Result<AuthResponse, ApiError>;
+    
+    /// Revoke a JWT on the backend
+    async fn logout(&self, jwt_token: &str) -> Result<(), ApiError>;
+    
+    /// Exchange the JWT for a fresh one before it expires
+    async fn refresh_session(&self, jwt_token: &str) -> Result<AuthResponse, ApiError>;
+    
+    /// Change the password (revokes every other session)
+    async fn change_password(&self, jwt_token: &str, current_password: String, new_password: String) -> Result<AuthResponse, ApiError>;
+    
+    /// Update the account email
+    async fn update_profile(&self, jwt_token: &str, email: String) -> Result<shared::UserInfo, ApiError>;
     
     /// Get prices for multiple symbols
-    async fn get_prices(&self, symbols: &[&str]) -> Result<PriceResponse, String>;
+    async fn get_prices(&self, symbols: &[&str]) -> Result<PriceResponse, ApiError>;
     
     /// Get wallet SOL balance
-    async fn get_wallet_balance(&self, address: &str) -> Result<WalletBalance, String>;
+    async fn get_wallet_balance(&self, address: &str) -> Result<WalletBalance, ApiError>;
     
     /// Get transaction history for an address
-    async fn get_transaction_history(&self, address: &str, limit: usize) -> Result<TransactionHistory, String>;
+    async fn get_transaction_history(&self, address: &str, limit: usize) -> Result<TransactionHistory, ApiError>;
     
     /// Get swap quote from Jupiter
     async fn get_swap_quote(
@@ -40,7 +54,7 @@ pub trait ApiService: Send + Sync {
         output_mint: &str,
         amount: u64,
         slippage_bps: u16,
-    ) -> Result<SwapQuoteResponse, String>;
+    ) -> Result<SwapQuoteResponse, ApiError>;
     
     /// Execute swap and get unsigned transaction
     async fn execute_swap(
@@ -51,7 +65,14 @@ pub trait ApiService: Send + Sync {
         slippage_bps: u16,
         user_pubkey: &str,
         jwt_token: &str,
-    ) -> Result<SwapExecuteResponse, String>;
+    ) -> Result<SwapExecuteResponse, ApiError>;
+    
+    /// Simulate an unsigned swap transaction before signing
+    async fn simulate_swap(
+        &self,
+        request: &shared::dto::simulation::SimulateSwapRequest,
+        jwt_token: &str,
+    ) -> Result<shared::dto::simulation::SimulateSwapResponse, ApiError>;
     
     /// Submit signed transaction
     /// Submit a signed transaction to the backend
@@ -62,6 +83,7 @@ pub trait ApiService: Send + Sync {
     async fn submit_transaction(
         &self,
         signed_transaction: String,
+        version: TransactionVersion,
         input_mint: String,
         output_mint: String,
         input_amount: i64,
@@ -69,19 +91,74 @@ pub trait ApiService: Send + Sync {
         price_impact: Option<f64>,
         slippage_bps: Option<i32>,
         jwt_token: &str,
-    ) -> Result<crate::services::api::TransactionSubmitResponse, String>;
+    ) -> Result<crate::services::api::TransactionSubmitResponse, ApiError>;
+    
+    /// Report the confirmation outcome of a submitted swap transaction
+    async fn update_transaction_status(
+        &self,
+        signature: &str,
+        update: &shared::dto::transactions::TransactionStatusUpdate,
+        jwt_token: &str,
+    ) -> Result<(), ApiError>;
+    
+    /// Report how long each pipeline stage of a submitted swap took
+    async fn record_transaction_latency(
+        &self,
+        signature: &str,
+        latency: &shared::dto::latency::SwapLatency,
+        jwt_token: &str,
+    ) -> Result<(), ApiError>;
     
     /// Get SPL token balances for an address
-    async fn get_token_balances(&self, address: &str) -> Result<Vec<TokenBalance>, String>;
+    async fn get_token_balances(&self, address: &str) -> Result<Vec<TokenBalance>, ApiError>;
     
     /// Get list of available tokens
-    async fn get_token_list(&self) -> Result<Vec<TokenListItem>, String>;
+    async fn get_token_list(&self) -> Result<Vec<TokenListItem>, ApiError>;
     
-    /// Get swap history for authenticated user
-    async fn get_swap_history(&self, jwt_token: &str, limit: usize) -> Result<Vec<SwapHistoryItem>, String>;
+    /// Get a filtered page of swap history for authenticated user
+    async fn get_swap_history(&self, jwt_token: &str, query: &SwapHistoryQuery) -> Result<SwapHistoryResponse, ApiError>;
+    
+    /// Stream every swap matching the history filters, resuming after dropped
+    /// connections; `on_batch` gets the swaps in order, a batch at a time
+    ///
+    /// The error is a message rather than an [`ApiError`], since it may be
+    /// the one `on_batch` returned.
+    ///
+    /// The lifetime is spelled out because `async_trait` would otherwise name
+    /// the elided one, tying the callback to a single batch borrow.
+    async fn stream_history(
+        &self,
+        jwt_token: &str,
+        query: &SwapHistoryQuery,
+        on_batch: &mut (dyn for<'b> FnMut(&'b [SwapHistoryItem]) -> Result<(), String> + Send),
+    ) -> Result<u64, String>;
+    
+    /// Validate or commit a CSV trade import
+    async fn import_trades(&self, jwt_token: &str, request: &shared::dto::trades::TradeImportRequest) -> Result<shared::dto::trades::TradeImportResponse, ApiError>;
+    
+    /// Get trade statistics over swaps and imported trades
+    async fn get_trade_stats(&self, jwt_token: &str) -> Result<shared::dto::trades::TradeStatsResponse, ApiError>;
     
     /// Get OHLC candlestick data for a token
-    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: usize) -> Result<Vec<shared::dto::market::OHLC>, String>;
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: usize) -> Result<Vec<shared::dto::market::OHLC>, ApiError>;
+    
+    /// Get OHLC candlestick data for several tokens in one request
+    async fn get_candles_batch(&self, symbols: &[String], timeframe: &str) -> Result<shared::dto::market::CandleBatchResponse, ApiError>;
+    
+    /// Get the symbols tracked by the backend price stream
+    async fn get_streamed_symbols(&self) -> Result<shared::dto::market::StreamedSymbolsResponse, ApiError>;
+    
+    /// Get token unlocks scheduled in the next `days` days
+    async fn get_token_unlocks(&self, days: i64) -> Result<shared::dto::unlocks::TokenUnlocksResponse, ApiError>;
+    
+    /// Get the route-quote depth ladder for a token pair
+    async fn get_depth(&self, input: &str, output: &str) -> Result<shared::dto::market::DepthResponse, ApiError>;
+    
+    /// Get volatility and return correlations over a window of days
+    async fn get_market_analytics(&self, symbols: &[String], window: usize) -> Result<shared::dto::market::MarketAnalyticsResponse, ApiError>;
+    
+    /// Get the backend health report
+    async fn get_health(&self) -> Result<shared::dto::system::HealthResponse, ApiError>;
 }
 
 /// Trait for wallet service operations
@@ -98,6 +175,17 @@ pub trait WalletService: Send + Sync {
     
     /// Sign a transaction
     fn sign_transaction(&self, transaction: &mut Transaction) -> Result<Signature, WalletError>;
+
+    /// Sign a versioned transaction, legacy or v0
+    fn sign_versioned_transaction(&self, transaction: &mut VersionedTransaction) -> Result<Signature, WalletError>;
+
+    /// Sign a transaction from the backend in whichever format it came
+    fn sign(&self, transaction: &mut WalletTransaction) -> Result<Signature, WalletError> {
+        match transaction {
+            WalletTransaction::Legacy(transaction) => self.sign_transaction(transaction),
+            WalletTransaction::V0(transaction) => self.sign_versioned_transaction(transaction),
+        }
+    }
     
     /// Get wallet balance
     async fn get_balance(&self) -> Result<f64, WalletError>;
@@ -116,6 +204,10 @@ impl WalletService for WalletServiceImpl {
     fn sign_transaction(&self, transaction: &mut Transaction) -> Result<Signature, WalletError> {
         WalletServiceImpl::sign_transaction(self, transaction)
     }
+
+    fn sign_versioned_transaction(&self, transaction: &mut VersionedTransaction) -> Result<Signature, WalletError> {
+        WalletServiceImpl::sign_versioned_transaction(self, transaction)
+    }
     
     async fn get_balance(&self) -> Result<f64, WalletError> {
         WalletServiceImpl::get_balance(self).await
@@ -0,0 +1,93 @@
+//! # Audit Repository
+//!
+//! The [audit trail](crate::app::audit), one row per entry in the order they
+//! were recorded. Entries are kept as their JSON form so new reference kinds
+//! don't need a migration.
+
+use super::{LocalStore, StoreError};
+use crate::app::audit::AuditEntry;
+use rusqlite::params;
+
+/// Audit trail entries, see the [module docs](self)
+#[derive(Debug, Clone)]
+pub struct AuditRepository {
+    store: LocalStore,
+}
+
+impl AuditRepository {
+    pub(super) fn new(store: LocalStore) -> Self {
+        Self { store }
+    }
+
+    /// The last `limit` entries, oldest first
+    ///
+    /// Unreadable rows are skipped.
+    pub fn recent(&self, limit: usize) -> Result<Vec<AuditEntry>, StoreError> {
+        let rows: Vec<String> = self.store.read(|conn| {
+            let mut statement =
+                conn.prepare("SELECT entry FROM (SELECT id, entry FROM audit_entries ORDER BY id DESC LIMIT ?1) ORDER BY id")?;
+            let rows = statement.query_map([limit.min(i64::MAX as usize) as i64], |row| row.get(0))?;
+            Ok(rows.collect::<Result<_, _>>()?)
+        })?;
+
+        let entries: Vec<AuditEntry> = rows.iter().filter_map(|json| serde_json::from_str(json).ok()).collect();
+        if entries.len() < rows.len() {
+            tracing::warn!(skipped = rows.len() - entries.len(), "Skipped unreadable audit entries");
+        }
+        Ok(entries)
+    }
+
+    /// Add an entry, then drop all but the newest `keep`
+    pub fn append(&self, entry: &AuditEntry, keep: usize) -> Result<(), StoreError> {
+        let json = serde_json::to_string(entry)?;
+        self.store.write(|tx| {
+            insert(tx, entry, &json)?;
+            prune(tx, keep)?;
+            Ok(())
+        })
+    }
+}
+
+pub(super) fn insert(tx: &rusqlite::Transaction<'_>, entry: &AuditEntry, json: &str) -> rusqlite::Result<()> {
+    tx.execute("INSERT INTO audit_entries (at, entry) VALUES (?1, ?2)", params![entry.at.to_rfc3339(), json])?;
+    Ok(())
+}
+
+pub(super) fn prune(tx: &rusqlite::Transaction<'_>, keep: usize) -> rusqlite::Result<()> {
+    tx.execute(
+        "DELETE FROM audit_entries WHERE id NOT IN (SELECT id FROM audit_entries ORDER BY id DESC LIMIT ?1)",
+        [keep.min(i64::MAX as usize) as i64],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::audit::{AuditCategory, AuditReference};
+
+    fn entry(summary: &str) -> AuditEntry {
+        AuditEntry {
+            at: chrono::Utc::now(),
+            category: AuditCategory::Wallet,
+            summary: summary.to_string(),
+            references: vec![AuditReference::Account("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU".to_string())],
+        }
+    }
+
+    #[test]
+    fn test_entries_come_back_oldest_first_and_pruned() {
+        let audit = LocalStore::open_in_memory().unwrap().audit();
+        for i in 0..5 {
+            audit.append(&entry(&format!("change {}", i)), 3).unwrap();
+        }
+
+        let summaries: Vec<String> = audit.recent(10).unwrap().into_iter().map(|e| e.summary).collect();
+        assert_eq!(summaries, ["change 2", "change 3", "change 4"]);
+
+        let last = audit.recent(1).unwrap();
+        assert_eq!(last.len(), 1);
+        assert_eq!(last[0].summary, "change 4");
+        assert_eq!(last[0].references, entry("change 4").references);
+    }
+}
@@ -0,0 +1,232 @@
+//! # Legacy File Import
+//!
+//! Moves the per-area `./xterminal-*.json` files of earlier versions into the
+//! database. Each file is imported in its own transaction and then renamed
+//! to `<name>.bak`, so it is only ever imported once and stays around as a
+//! backup.
+//!
+//! Data already in the database wins: a file whose area was saved since
+//! (say, the rename failed after a crash) is renamed without being imported
+//! again. A file that can't be read or parsed is left where it is and
+//! reported, so nothing is lost; the area starts from its defaults.
+
+use super::{audit, portfolio, settings, wallets, Document, LocalStore, StoreError};
+use crate::app::audit::{AuditEntry, MAX_ENTRIES};
+use crate::app::state::PortfolioSnapshot;
+use crate::app::wallet_identity::WalletIdentities;
+use std::path::{Path, PathBuf};
+
+/// Wallet identities file
+const WALLETS_FILE: &str = "xterminal-wallets.json";
+/// Portfolio snapshot history file
+const PORTFOLIO_HISTORY_FILE: &str = "xterminal-portfolio-history.json";
+/// Audit trail, then its rotated backup
+const AUDIT_FILES: [&str; 2] = ["xterminal-audit.jsonl.1", "xterminal-audit.jsonl"];
+
+/// What [`import_legacy_files`] did
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// Files moved into the database and renamed
+    pub imported: Vec<PathBuf>,
+    /// Files left in place, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Import every legacy file found in `dir`
+pub fn import_legacy_files(store: &LocalStore, dir: &Path) -> ImportReport {
+    let mut report = ImportReport::default();
+
+    for document in Document::ALL {
+        let Some(file) = document.legacy_file() else {
+            continue;
+        };
+        import_files(&mut report, &[dir.join(file)], |contents| {
+            let value: serde_json::Value = serde_json::from_str(&contents[0])?;
+            store.write(|tx| {
+                let saved: bool = tx.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM documents WHERE key = ?1)",
+                    [document.key()],
+                    |row| row.get(0),
+                )?;
+                if !saved {
+                    settings::save_json(tx, document, &value.to_string())?;
+                }
+                Ok(())
+            })
+        });
+    }
+
+    import_files(&mut report, &[dir.join(WALLETS_FILE)], |contents| {
+        let identities: WalletIdentities = serde_json::from_str(&contents[0])?;
+        store.write(|tx| {
+            if is_empty(tx, "wallet_identities")? {
+                wallets::replace(tx, &identities)?;
+            }
+            Ok(())
+        })
+    });
+
+    import_files(&mut report, &[dir.join(PORTFOLIO_HISTORY_FILE)], |contents| {
+        let snapshots: Vec<PortfolioSnapshot> = serde_json::from_str(&contents[0])?;
+        store.write(|tx| {
+            if is_empty(tx, "portfolio_snapshots")? {
+                portfolio::replace(tx, &snapshots)?;
+            }
+            Ok(())
+        })
+    });
+
+    let audit_files: Vec<PathBuf> = AUDIT_FILES.iter().map(|file| dir.join(file)).collect();
+    import_files(&mut report, &audit_files, |contents| {
+        // Lines that don't parse were skipped by the old reader too
+        let entries: Vec<(AuditEntry, &str)> = contents
+            .iter()
+            .flat_map(|content| content.lines())
+            .filter_map(|line| serde_json::from_str(line).ok().map(|entry| (entry, line)))
+            .collect();
+        store.write(|tx| {
+            if is_empty(tx, "audit_entries")? {
+                for (entry, line) in &entries {
+                    audit::insert(tx, entry, line)?;
+                }
+                audit::prune(tx, MAX_ENTRIES)?;
+            }
+            Ok(())
+        })
+    });
+
+    if !report.imported.is_empty() {
+        tracing::info!(count = report.imported.len(), "Imported legacy settings files into the local database");
+    }
+    report
+}
+
+/// Import the files of one area that exist, renaming them once `import` committed
+///
+/// `import` gets the contents of the existing files in the order given.
+fn import_files(
+    report: &mut ImportReport,
+    paths: &[PathBuf],
+    import: impl FnOnce(&[String]) -> Result<(), StoreError>,
+) {
+    let mut found = Vec::new();
+    let mut contents = Vec::new();
+    for path in paths {
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                found.push(path.clone());
+                contents.push(content);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                tracing::warn!("Failed to read legacy file {:?}: {}", path, e);
+                report.failed.push((path.clone(), e.to_string()));
+                return;
+            }
+        }
+    }
+    if found.is_empty() {
+        return;
+    }
+
+    if let Err(e) = import(&contents) {
+        tracing::warn!("Failed to import {:?}: {}. Leaving it in place.", found, e);
+        report.failed.extend(found.into_iter().map(|path| (path, e.to_string())));
+        return;
+    }
+
+    for path in found {
+        let backup = backup_path(&path);
+        if let Err(e) = std::fs::rename(&path, &backup) {
+            tracing::warn!("Imported {:?} but could not rename it to {:?}: {}", path, backup, e);
+        }
+        tracing::info!("Imported {:?}", path);
+        report.imported.push(path);
+    }
+}
+
+fn is_empty(tx: &rusqlite::Transaction<'_>, table: &str) -> rusqlite::Result<bool> {
+    tx.query_row(&format!("SELECT NOT EXISTS(SELECT 1 FROM {})", table), [], |row| row.get(0))
+}
+
+/// `<path>.bak`
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::audit::AuditCategory;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xterminal-import-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn audit_line(summary: &str) -> String {
+        format!(r#"{{"at":"2025-03-01T12:00:00Z","category":"security","summary":"{}"}}"#, summary)
+    }
+
+    #[test]
+    fn test_import_moves_files_into_the_database() {
+        let dir = temp_dir("all");
+        std::fs::write(dir.join("xterminal-servers.json"), r#"["https://api.example.com"]"#).unwrap();
+        std::fs::write(dir.join("xterminal-network.json"), r#"{ "bandwidth_saver": true }"#).unwrap();
+        std::fs::write(
+            dir.join(WALLETS_FILE),
+            r#"{ "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU": { "label": "Trading", "color": [1, 2, 3] } }"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join(PORTFOLIO_HISTORY_FILE), r#"[{ "date": "2025-03-01", "total_value": 1200.0 }]"#).unwrap();
+        std::fs::write(dir.join(AUDIT_FILES[0]), format!("{}\n", audit_line("old"))).unwrap();
+        std::fs::write(dir.join(AUDIT_FILES[1]), format!("{}\nnot json\n{}\n", audit_line("newer"), audit_line("newest"))).unwrap();
+
+        let store = LocalStore::open_in_memory().unwrap();
+        let report = import_legacy_files(&store, &dir);
+        assert_eq!(report.imported.len(), 6);
+        assert!(report.failed.is_empty());
+
+        let servers: Option<Vec<String>> = store.settings().load(Document::Servers).unwrap();
+        assert_eq!(servers, Some(vec!["https://api.example.com".to_string()]));
+        let network: crate::app::NetworkPreferences = store.settings().load_or_default(Document::Network);
+        assert!(network.bandwidth_saver);
+        assert_eq!(store.wallets().load().unwrap().label("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"), "Trading");
+        assert_eq!(store.portfolio_history().load().unwrap().len(), 1);
+        let trail: Vec<String> = store.audit().recent(MAX_ENTRIES).unwrap().into_iter().map(|e| e.summary).collect();
+        assert_eq!(trail, ["old", "newer", "newest"]);
+        assert_eq!(store.audit().recent(1).unwrap()[0].category, AuditCategory::Security);
+
+        // Renamed to backups, so a second run has nothing to do
+        assert!(!dir.join("xterminal-servers.json").exists());
+        assert!(backup_path(&dir.join("xterminal-servers.json")).exists());
+        assert!(backup_path(&dir.join(AUDIT_FILES[0])).exists());
+        assert!(import_legacy_files(&store, &dir).imported.is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_import_keeps_newer_data_and_broken_files() {
+        let dir = temp_dir("conflict");
+        let store = LocalStore::open_in_memory().unwrap();
+        store.settings().save(Document::Servers, &["https://saved.example.com"]).unwrap();
+        std::fs::write(dir.join("xterminal-servers.json"), r#"["https://stale.example.com"]"#).unwrap();
+        std::fs::write(dir.join("xterminal-risk.json"), r#"{ "max_position_pct": "#).unwrap();
+
+        let report = import_legacy_files(&store, &dir);
+        assert_eq!(report.imported, vec![dir.join("xterminal-servers.json")]);
+        assert_eq!(report.failed.len(), 1);
+
+        let servers: Option<Vec<String>> = store.settings().load(Document::Servers).unwrap();
+        assert_eq!(servers, Some(vec!["https://saved.example.com".to_string()]));
+        assert!(!store.settings().contains(Document::Risk).unwrap());
+        assert!(dir.join("xterminal-risk.json").exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
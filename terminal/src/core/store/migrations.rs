@@ -0,0 +1,70 @@
+//! # Schema Migrations
+//!
+//! [`MIGRATIONS`] lists every schema change, oldest first. A database's
+//! `user_version` is the number already applied; [`migrate`] runs the rest
+//! in one transaction, so a failed upgrade leaves the old schema untouched.
+//!
+//! Migrations are never edited once released: a schema change is a new entry
+//! at the end.
+
+use super::StoreError;
+use rusqlite::{Connection, TransactionBehavior};
+
+/// Schema changes, oldest first
+const MIGRATIONS: &[&str] = &[
+    // 1: settings documents, wallet identities, portfolio history, audit trail
+    "CREATE TABLE documents (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+    CREATE TABLE wallet_identities (
+        address TEXT PRIMARY KEY,
+        label TEXT NOT NULL,
+        color INTEGER NOT NULL
+    );
+    CREATE TABLE portfolio_snapshots (
+        date TEXT PRIMARY KEY,
+        total_value REAL NOT NULL
+    );
+    CREATE TABLE audit_entries (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        at TEXT NOT NULL,
+        entry TEXT NOT NULL
+    );",
+    // 2: daily usage counts, kept only while usage analytics are enabled
+    "CREATE TABLE usage_counts (
+        day TEXT NOT NULL,
+        event TEXT NOT NULL,
+        count INTEGER NOT NULL,
+        PRIMARY KEY (day, event)
+    );",
+];
+
+/// Schema version of a fully migrated database
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Apply the migrations `conn` hasn't seen yet
+///
+/// # Returns
+///
+/// The version the database was at before
+///
+/// # Errors
+///
+/// [`StoreError::NewerSchema`] when a newer build already migrated the
+/// database; it is left alone rather than misread.
+pub(super) fn migrate(conn: &mut Connection) -> Result<u32, StoreError> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let current: u32 = tx.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if current > SCHEMA_VERSION {
+        return Err(StoreError::NewerSchema { found: current, supported: SCHEMA_VERSION });
+    }
+
+    for (applied, sql) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", applied as u32 + 1)?;
+    }
+    tx.commit()?;
+    Ok(current)
+}
@@ -0,0 +1,245 @@
+//! # Local Database
+//!
+//! Everything the terminal keeps between sessions lives in one SQLite
+//! database, `./xterminal.db`: settings and preferences, wallet identities,
+//! the portfolio history, the audit trail and, when enabled, usage counts.
+//! Each domain is reached through its own repository:
+//!
+//! | Repository                     | Holds                                              |
+//! |--------------------------------|----------------------------------------------------|
+//! | [`SettingsRepository`]         | One JSON document per settings area, see [`Document`] |
+//! | [`WalletRepository`]           | Wallet labels and chip colors                      |
+//! | [`PortfolioHistoryRepository`] | Daily portfolio value snapshots                    |
+//! | [`AuditRepository`]            | The audit trail shown in Settings > Security       |
+//! | [`UsageRepository`]            | Daily usage counts awaiting upload (opt-in)        |
+//!
+//! The schema is versioned with SQLite's `user_version`; opening a database
+//! runs the [`migrations`] it hasn't seen yet. Every write is a transaction,
+//! so a crash leaves the previous value in place instead of a half-written one.
+//!
+//! ## Concurrency
+//!
+//! A [`LocalStore`] is a cheap handle to one connection behind a mutex, held
+//! only for the length of a statement, so handlers and async tasks can write
+//! from any thread. The database runs in WAL mode with a busy timeout, so a
+//! second terminal on the same directory waits for a write instead of failing.
+//!
+//! ## Legacy Files
+//!
+//! Earlier versions kept each area in its own `./xterminal-*.json` file. On
+//! the first open of the app-wide [`store`], [`legacy::import_legacy_files`]
+//! copies them into the database and renames each to `*.bak`. The encrypted
+//! keystore stays in its own file, and CSV exports are written where the
+//! user asks.
+
+pub mod audit;
+pub mod legacy;
+pub mod migrations;
+pub mod portfolio;
+pub mod settings;
+pub mod usage;
+pub mod wallets;
+
+pub use audit::AuditRepository;
+pub use portfolio::PortfolioHistoryRepository;
+pub use settings::{Document, SettingsRepository};
+pub use usage::UsageRepository;
+pub use wallets::WalletRepository;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rusqlite::{Connection, Transaction, TransactionBehavior};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// How long a write waits for another process holding the database
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Get local database path
+pub fn database_path() -> PathBuf {
+    PathBuf::from("./xterminal.db")
+}
+
+static STORE: Lazy<LocalStore> = Lazy::new(|| {
+    let path = database_path();
+    match LocalStore::open(&path) {
+        Ok(store) => {
+            let dir = path.parent().unwrap_or(Path::new("."));
+            legacy::import_legacy_files(&store, dir);
+            store
+        }
+        Err(e) => {
+            tracing::error!("Failed to open local database {:?}: {}. Nothing will be saved this session.", path, e);
+            LocalStore::open_in_memory().expect("in-memory SQLite database")
+        }
+    }
+});
+
+/// The app-wide database at [`database_path`], opened on first use
+///
+/// Falls back to an in-memory database when the file can't be opened, so
+/// the app still starts with defaults.
+pub fn store() -> &'static LocalStore {
+    &STORE
+}
+
+/// Errors reading or writing the local database
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("unreadable stored value: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("database schema version {found} is newer than this build supports ({supported})")]
+    NewerSchema { found: u32, supported: u32 },
+}
+
+/// Handle to an open, migrated database; clones share the connection
+#[derive(Clone)]
+pub struct LocalStore {
+    conn: Arc<Mutex<Connection>>,
+    path: Option<PathBuf>,
+}
+
+impl std::fmt::Debug for LocalStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalStore").field("path", &self.path).finish_non_exhaustive()
+    }
+}
+
+impl LocalStore {
+    /// Open (or create) the database at `path` and bring its schema up to date
+    pub fn open(path: &Path) -> Result<Self, StoreError> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        Self::init(conn, Some(path.to_path_buf()))
+    }
+
+    /// Fresh database that lives as long as its handles, for tests
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        Self::init(Connection::open_in_memory()?, None)
+    }
+
+    fn init(mut conn: Connection, path: Option<PathBuf>) -> Result<Self, StoreError> {
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        let from = migrations::migrate(&mut conn)?;
+        if from != migrations::SCHEMA_VERSION {
+            tracing::info!(from, to = migrations::SCHEMA_VERSION, "Migrated local database {:?}", path);
+        }
+        Ok(Self { conn: Arc::new(Mutex::new(conn)), path })
+    }
+
+    /// File backing the database; None when in memory
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Migrations applied so far
+    pub fn schema_version(&self) -> Result<u32, StoreError> {
+        self.read(|conn| Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?))
+    }
+
+    pub fn settings(&self) -> SettingsRepository {
+        SettingsRepository::new(self.clone())
+    }
+
+    pub fn wallets(&self) -> WalletRepository {
+        WalletRepository::new(self.clone())
+    }
+
+    pub fn portfolio_history(&self) -> PortfolioHistoryRepository {
+        PortfolioHistoryRepository::new(self.clone())
+    }
+
+    pub fn audit(&self) -> AuditRepository {
+        AuditRepository::new(self.clone())
+    }
+
+    pub fn usage(&self) -> UsageRepository {
+        UsageRepository::new(self.clone())
+    }
+
+    /// Run queries against the connection
+    fn read<T>(&self, f: impl FnOnce(&Connection) -> Result<T, StoreError>) -> Result<T, StoreError> {
+        f(&self.conn.lock())
+    }
+
+    /// Run `f` in a transaction, committed only if it succeeds
+    ///
+    /// The transaction takes the write lock up front so two processes can't
+    /// both read and then fail to upgrade.
+    fn write<T>(&self, f: impl FnOnce(&Transaction<'_>) -> Result<T, StoreError>) -> Result<T, StoreError> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let value = f(&tx)?;
+        tx.commit()?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_migrates_once() {
+        let path = std::env::temp_dir().join(format!("xterminal-store-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let store = LocalStore::open(&path).unwrap();
+        assert_eq!(store.schema_version().unwrap(), migrations::SCHEMA_VERSION);
+        store.settings().save(Document::Servers, &["https://api.example.com"]).unwrap();
+        drop(store);
+
+        let reopened = LocalStore::open(&path).unwrap();
+        assert_eq!(reopened.schema_version().unwrap(), migrations::SCHEMA_VERSION);
+        let servers: Option<Vec<String>> = reopened.settings().load(Document::Servers).unwrap();
+        assert_eq!(servers, Some(vec!["https://api.example.com".to_string()]));
+        drop(reopened);
+
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[test]
+    fn test_newer_schema_is_refused() {
+        let path = std::env::temp_dir().join(format!("xterminal-store-newer-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.pragma_update(None, "user_version", migrations::SCHEMA_VERSION + 1).unwrap();
+        }
+        assert!(matches!(LocalStore::open(&path), Err(StoreError::NewerSchema { .. })));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_concurrent_writers() {
+        let store = LocalStore::open_in_memory().unwrap();
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let audit = store.audit();
+                std::thread::spawn(move || {
+                    for j in 0..25 {
+                        let entry = crate::app::audit::AuditEntry {
+                            at: chrono::Utc::now(),
+                            category: crate::app::audit::AuditCategory::Security,
+                            summary: format!("writer {} change {}", i, j),
+                            references: Vec::new(),
+                        };
+                        audit.append(&entry, usize::MAX).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(store.audit().recent(usize::MAX).unwrap().len(), 200);
+    }
+}
@@ -0,0 +1,64 @@
+//! # Portfolio History Repository
+//!
+//! The daily [portfolio snapshots](crate::app::state::PortfolioSnapshot)
+//! behind the portfolio sparkline, one row per UTC date.
+
+use super::{LocalStore, StoreError};
+use crate::app::state::PortfolioSnapshot;
+use rusqlite::params;
+
+/// Daily portfolio values, see the [module docs](self)
+#[derive(Debug, Clone)]
+pub struct PortfolioHistoryRepository {
+    store: LocalStore,
+}
+
+impl PortfolioHistoryRepository {
+    pub(super) fn new(store: LocalStore) -> Self {
+        Self { store }
+    }
+
+    /// Snapshots oldest first
+    pub fn load(&self) -> Result<Vec<PortfolioSnapshot>, StoreError> {
+        self.store.read(|conn| {
+            let mut statement = conn.prepare("SELECT date, total_value FROM portfolio_snapshots ORDER BY date")?;
+            let rows = statement.query_map([], |row| Ok(PortfolioSnapshot { date: row.get(0)?, total_value: row.get(1)? }))?;
+            Ok(rows.collect::<Result<_, _>>()?)
+        })
+    }
+
+    /// Replace the history with `snapshots`, dropping days no longer in it
+    pub fn replace(&self, snapshots: &[PortfolioSnapshot]) -> Result<(), StoreError> {
+        self.store.write(|tx| Ok(replace(tx, snapshots)?))
+    }
+}
+
+pub(super) fn replace(tx: &rusqlite::Transaction<'_>, snapshots: &[PortfolioSnapshot]) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM portfolio_snapshots", [])?;
+    let mut insert = tx.prepare("INSERT OR REPLACE INTO portfolio_snapshots (date, total_value) VALUES (?1, ?2)")?;
+    for snapshot in snapshots {
+        insert.execute(params![snapshot.date, snapshot.total_value])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(date: &str, total_value: f64) -> PortfolioSnapshot {
+        PortfolioSnapshot { date: date.to_string(), total_value }
+    }
+
+    #[test]
+    fn test_history_round_trip_in_date_order() {
+        let history = LocalStore::open_in_memory().unwrap().portfolio_history();
+        assert!(history.load().unwrap().is_empty());
+
+        history.replace(&[snapshot("2025-03-02", 1250.5), snapshot("2025-03-01", 1200.0)]).unwrap();
+        assert_eq!(history.load().unwrap(), vec![snapshot("2025-03-01", 1200.0), snapshot("2025-03-02", 1250.5)]);
+
+        history.replace(&[snapshot("2025-03-02", 1300.0), snapshot("2025-03-03", 1280.0)]).unwrap();
+        assert_eq!(history.load().unwrap(), vec![snapshot("2025-03-02", 1300.0), snapshot("2025-03-03", 1280.0)]);
+    }
+}
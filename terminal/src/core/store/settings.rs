@@ -0,0 +1,232 @@
+//! # Settings Repository
+//!
+//! Each settings area is one JSON [`Document`] in the `documents` table,
+//! read and written whole by the handler that owns it. Documents are stored
+//! as their serde form, so fields added later fall back to their defaults
+//! exactly as they did in the old per-area files.
+
+use super::{LocalStore, StoreError};
+use rusqlite::{params, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// A settings area stored as one document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Document {
+    /// Theme colors (Settings > Theme)
+    Theme,
+    /// Chart indicator selection
+    Indicators,
+    /// Token picker favorites and recents
+    Tokens,
+    /// Bandwidth saver and price feed preferences
+    Network,
+    /// Muted backend notice categories
+    Notifications,
+    /// Backend server list, primary first
+    Servers,
+    /// Settings > Connection
+    Connection,
+    /// Portfolio risk warning thresholds
+    Risk,
+    /// Rebalancing targets by profile
+    Rebalance,
+    /// Window arrangement restored on launch
+    Layout,
+    /// Chart drawings and their alerts
+    Annotations,
+    /// How often each recovery action fixed an error
+    RecoveryStats,
+    /// Executable last registered for `xforce://` links
+    ProtocolRegistration,
+    /// Usage analytics consent and install id (Settings > Privacy)
+    Usage,
+    /// Sort of the Live Assets price list
+    LiveAssets,
+    /// Keyboard shortcuts rebound in Settings > Shortcuts
+    KeyBindings,
+    /// Language of the UI (Settings > Language)
+    Language,
+}
+
+impl Document {
+    pub const ALL: [Document; 17] = [
+        Document::Theme,
+        Document::Indicators,
+        Document::Tokens,
+        Document::Network,
+        Document::Notifications,
+        Document::Servers,
+        Document::Connection,
+        Document::Risk,
+        Document::Rebalance,
+        Document::Layout,
+        Document::Annotations,
+        Document::RecoveryStats,
+        Document::ProtocolRegistration,
+        Document::Usage,
+        Document::LiveAssets,
+        Document::KeyBindings,
+        Document::Language,
+    ];
+
+    /// Row key in the `documents` table
+    pub fn key(self) -> &'static str {
+        match self {
+            Document::Theme => "theme",
+            Document::Indicators => "indicators",
+            Document::Tokens => "tokens",
+            Document::Network => "network",
+            Document::Notifications => "notifications",
+            Document::Servers => "servers",
+            Document::Connection => "connection",
+            Document::Risk => "risk",
+            Document::Rebalance => "rebalance",
+            Document::Layout => "layout",
+            Document::Annotations => "annotations",
+            Document::RecoveryStats => "recovery_stats",
+            Document::ProtocolRegistration => "protocol_registration",
+            Document::Usage => "usage",
+            Document::LiveAssets => "live_assets",
+            Document::KeyBindings => "key_bindings",
+            Document::Language => "language",
+        }
+    }
+
+    /// File the document was kept in before the database; None for
+    /// documents that never had one
+    pub fn legacy_file(self) -> Option<&'static str> {
+        match self {
+            Document::Theme => Some("xterminal-config.json"),
+            Document::Indicators => Some("xterminal-indicators.json"),
+            Document::Tokens => Some("xterminal-tokens.json"),
+            Document::Network => Some("xterminal-network.json"),
+            Document::Notifications => Some("xterminal-notifications.json"),
+            Document::Servers => Some("xterminal-servers.json"),
+            Document::Connection => Some("xterminal-connection.json"),
+            Document::Risk => Some("xterminal-risk.json"),
+            Document::Rebalance => Some("xterminal-rebalance.json"),
+            Document::Layout => Some("xterminal-layout.json"),
+            Document::Annotations => Some("xterminal-annotations.json"),
+            Document::RecoveryStats => Some("xterminal-recovery.json"),
+            Document::ProtocolRegistration => Some("xterminal-protocol.json"),
+            Document::Usage | Document::LiveAssets | Document::KeyBindings | Document::Language => None,
+        }
+    }
+}
+
+/// Settings documents, see the [module docs](self)
+#[derive(Debug, Clone)]
+pub struct SettingsRepository {
+    store: LocalStore,
+}
+
+impl SettingsRepository {
+    pub(super) fn new(store: LocalStore) -> Self {
+        Self { store }
+    }
+
+    /// Read a document; None when it was never saved
+    pub fn load<T: DeserializeOwned>(&self, document: Document) -> Result<Option<T>, StoreError> {
+        let json: Option<String> = self.store.read(|conn| {
+            Ok(conn
+                .query_row("SELECT value FROM documents WHERE key = ?1", [document.key()], |row| row.get(0))
+                .optional()?)
+        })?;
+        Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    /// Read a document, falling back to the default when it is missing or unreadable
+    pub fn load_or_default<T: DeserializeOwned + Default>(&self, document: Document) -> T {
+        match self.load(document) {
+            Ok(value) => value.unwrap_or_default(),
+            Err(e) => {
+                tracing::warn!("Failed to load {} settings: {}. Using defaults.", document.key(), e);
+                T::default()
+            }
+        }
+    }
+
+    /// Replace a document
+    pub fn save<T: Serialize + ?Sized>(&self, document: Document, value: &T) -> Result<(), StoreError> {
+        let json = serde_json::to_string(value)?;
+        self.store.write(|tx| Ok(save_json(tx, document, &json)?))
+    }
+
+    /// Forget a document, so it loads as never saved
+    pub fn remove(&self, document: Document) -> Result<(), StoreError> {
+        self.store.write(|tx| {
+            tx.execute("DELETE FROM documents WHERE key = ?1", [document.key()])?;
+            Ok(())
+        })
+    }
+
+    /// Whether a document was ever saved
+    pub fn contains(&self, document: Document) -> Result<bool, StoreError> {
+        self.store.read(|conn| {
+            Ok(conn.query_row("SELECT EXISTS(SELECT 1 FROM documents WHERE key = ?1)", [document.key()], |row| {
+                row.get(0)
+            })?)
+        })
+    }
+}
+
+/// Upsert a document's JSON inside a transaction
+pub(super) fn save_json(tx: &rusqlite::Transaction<'_>, document: Document, json: &str) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT INTO documents (key, value, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        params![document.key(), json, chrono::Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    struct Preferences {
+        enabled: bool,
+        limit: u32,
+    }
+
+    #[test]
+    fn test_documents_round_trip_and_replace() {
+        let settings = LocalStore::open_in_memory().unwrap().settings();
+        assert_eq!(settings.load::<Preferences>(Document::Network).unwrap(), None);
+        assert!(!settings.contains(Document::Network).unwrap());
+
+        settings.save(Document::Network, &Preferences { enabled: true, limit: 3 }).unwrap();
+        settings.save(Document::Network, &Preferences { enabled: false, limit: 7 }).unwrap();
+        assert_eq!(settings.load(Document::Network).unwrap(), Some(Preferences { enabled: false, limit: 7 }));
+        assert!(settings.contains(Document::Network).unwrap());
+        // Documents don't leak into each other
+        assert_eq!(settings.load::<Preferences>(Document::Risk).unwrap(), None);
+
+        settings.remove(Document::Network).unwrap();
+        assert_eq!(settings.load::<Preferences>(Document::Network).unwrap(), None);
+    }
+
+    #[test]
+    fn test_missing_fields_default_and_bad_documents_fall_back() {
+        let settings = LocalStore::open_in_memory().unwrap().settings();
+        settings.save(Document::Tokens, &serde_json::json!({ "limit": 4 })).unwrap();
+        assert_eq!(settings.load_or_default::<Preferences>(Document::Tokens), Preferences { enabled: false, limit: 4 });
+
+        settings.save(Document::Tokens, &serde_json::json!({ "limit": "four" })).unwrap();
+        assert!(settings.load::<Preferences>(Document::Tokens).is_err());
+        assert_eq!(settings.load_or_default::<Preferences>(Document::Tokens), Preferences::default());
+    }
+
+    #[test]
+    fn test_keys_and_legacy_files_are_unique() {
+        let keys: std::collections::HashSet<_> = Document::ALL.iter().map(|d| d.key()).collect();
+        let files: Vec<_> = Document::ALL.iter().filter_map(|d| d.legacy_file()).collect();
+        let unique_files: std::collections::HashSet<_> = files.iter().collect();
+        assert_eq!(keys.len(), Document::ALL.len());
+        assert_eq!(unique_files.len(), files.len());
+    }
+}
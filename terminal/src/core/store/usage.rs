@@ -0,0 +1,108 @@
+//! # Usage Count Repository
+//!
+//! Local daily counts of [`UsageEvent`]s, kept only while usage analytics
+//! are enabled. A day's counts stay here until the upload that includes
+//! them succeeds; see [`crate::app::usage`].
+
+use super::{LocalStore, StoreError};
+use chrono::NaiveDate;
+use rusqlite::params;
+use shared::dto::telemetry::{UsageDay, UsageEvent};
+use std::collections::BTreeMap;
+
+/// Daily usage counts, see the [module docs](self)
+#[derive(Debug, Clone)]
+pub struct UsageRepository {
+    store: LocalStore,
+}
+
+impl UsageRepository {
+    pub(super) fn new(store: LocalStore) -> Self {
+        Self { store }
+    }
+
+    /// Count one `event` on `day`
+    pub fn increment(&self, day: NaiveDate, event: UsageEvent) -> Result<(), StoreError> {
+        self.store.write(|tx| {
+            tx.execute(
+                "INSERT INTO usage_counts (day, event, count) VALUES (?1, ?2, 1)
+                 ON CONFLICT(day, event) DO UPDATE SET count = count + 1",
+                params![day.to_string(), event.key()],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Counts of the days before `day`, oldest first, at most `limit` days
+    ///
+    /// Events this build doesn't know, left by a newer one, are skipped.
+    pub fn days_before(&self, day: NaiveDate, limit: usize) -> Result<Vec<UsageDay>, StoreError> {
+        let rows: Vec<(String, String, u64)> = self.store.read(|conn| {
+            let mut statement =
+                conn.prepare("SELECT day, event, count FROM usage_counts WHERE day < ?1 ORDER BY day, event")?;
+            let rows = statement.query_map([day.to_string()], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            Ok(rows.collect::<Result<_, _>>()?)
+        })?;
+
+        let mut days: BTreeMap<NaiveDate, BTreeMap<UsageEvent, u64>> = BTreeMap::new();
+        for (day, event, count) in rows {
+            let (Ok(day), Some(event)) = (day.parse::<NaiveDate>(), UsageEvent::from_key(&event)) else {
+                continue;
+            };
+            days.entry(day).or_default().insert(event, count);
+        }
+        Ok(days.into_iter().take(limit).map(|(day, counts)| UsageDay { day, counts }).collect())
+    }
+
+    /// Forget the counts of `day` and every day before it
+    pub fn clear_through(&self, day: NaiveDate) -> Result<(), StoreError> {
+        self.store.write(|tx| {
+            tx.execute("DELETE FROM usage_counts WHERE day <= ?1", [day.to_string()])?;
+            Ok(())
+        })
+    }
+
+    /// Forget every count
+    pub fn clear(&self) -> Result<(), StoreError> {
+        self.store.write(|tx| {
+            tx.execute("DELETE FROM usage_counts", [])?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::dto::telemetry::{UsageFeature, UsageScreen};
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_counts_aggregate_per_day() {
+        let usage = LocalStore::open_in_memory().unwrap().usage();
+        let swap = UsageEvent::Feature(UsageFeature::Swap);
+        let wallet = UsageEvent::ScreenView(UsageScreen::Wallet);
+        usage.increment(day(1), swap).unwrap();
+        usage.increment(day(1), swap).unwrap();
+        usage.increment(day(1), wallet).unwrap();
+        usage.increment(day(2), swap).unwrap();
+        usage.increment(day(3), swap).unwrap();
+
+        let days = usage.days_before(day(3), 31).unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].day, day(1));
+        assert_eq!(days[0].counts, BTreeMap::from([(swap, 2), (wallet, 1)]));
+        assert_eq!(days[1].counts, BTreeMap::from([(swap, 1)]));
+        assert_eq!(usage.days_before(day(3), 1).unwrap().len(), 1);
+
+        usage.clear_through(day(1)).unwrap();
+        let left: Vec<NaiveDate> = usage.days_before(day(4), 31).unwrap().iter().map(|d| d.day).collect();
+        assert_eq!(left, vec![day(2), day(3)]);
+
+        usage.clear().unwrap();
+        assert!(usage.days_before(day(4), 31).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+//! # Wallet Repository
+//!
+//! [Wallet identities](crate::app::wallet_identity) by address, one row per
+//! labelled wallet. Colors are stored as `0xRRGGBB`.
+
+use super::{LocalStore, StoreError};
+use crate::app::wallet_identity::{WalletIdentities, WalletIdentity};
+use rusqlite::params;
+
+/// Wallet labels and colors, see the [module docs](self)
+#[derive(Debug, Clone)]
+pub struct WalletRepository {
+    store: LocalStore,
+}
+
+impl WalletRepository {
+    pub(super) fn new(store: LocalStore) -> Self {
+        Self { store }
+    }
+
+    /// Every labelled wallet
+    pub fn load(&self) -> Result<WalletIdentities, StoreError> {
+        self.store.read(|conn| {
+            let mut statement = conn.prepare("SELECT address, label, color FROM wallet_identities ORDER BY address")?;
+            let rows = statement.query_map([], |row| {
+                let color: u32 = row.get(2)?;
+                let [_, r, g, b] = color.to_be_bytes();
+                Ok((row.get(0)?, WalletIdentity { label: row.get(1)?, color: [r, g, b] }))
+            })?;
+            Ok(rows.collect::<Result<WalletIdentities, _>>()?)
+        })
+    }
+
+    /// Replace every stored identity with `identities`
+    pub fn replace(&self, identities: &WalletIdentities) -> Result<(), StoreError> {
+        self.store.write(|tx| Ok(replace(tx, identities)?))
+    }
+}
+
+pub(super) fn replace(tx: &rusqlite::Transaction<'_>, identities: &WalletIdentities) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM wallet_identities", [])?;
+    let mut insert = tx.prepare("INSERT INTO wallet_identities (address, label, color) VALUES (?1, ?2, ?3)")?;
+    for (address, identity) in identities.iter() {
+        let [r, g, b] = identity.color;
+        insert.execute(params![address, identity.label, u32::from_be_bytes([0, r, g, b])])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identities_round_trip() {
+        let wallets = LocalStore::open_in_memory().unwrap().wallets();
+        assert_eq!(wallets.load().unwrap(), WalletIdentities::default());
+
+        let mut identities = WalletIdentities::default();
+        identities.set("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", "Trading", [0xe9, 0x1e, 0x63]);
+        identities.set("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM", "Cold", [0x00, 0x00, 0xff]);
+        wallets.replace(&identities).unwrap();
+        assert_eq!(wallets.load().unwrap(), identities);
+
+        // Forgetting a wallet removes its row
+        identities.set("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM", "", [0, 0, 0]);
+        wallets.replace(&identities).unwrap();
+        let loaded = wallets.load().unwrap();
+        assert!(loaded.get("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM").is_none());
+        assert_eq!(loaded.label("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"), "Trading");
+    }
+}
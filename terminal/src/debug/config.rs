@@ -19,6 +19,10 @@ pub struct DebugConfig {
     pub enable_trace_ids: bool,
     /// Freeze detection threshold in milliseconds
     pub freeze_threshold_ms: u64,
+    /// Capture a stall report when the threshold is crossed
+    pub stall_reports_enabled: bool,
+    /// File stall reports are appended to, one JSON object per line
+    pub stall_report_file: PathBuf,
 }
 
 impl Default for DebugConfig {
@@ -41,6 +45,10 @@ impl Default for DebugConfig {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(1000), // Default 1 second
+            stall_reports_enabled: std::env::var("TERMINAL_STALL_REPORTS")
+                .map(|v| v == "1")
+                .unwrap_or(true),
+            stall_report_file: PathBuf::from("logs/stall-reports.jsonl"),
         }
     }
 }
@@ -59,7 +67,6 @@ impl DebugConfig {
             show_debug_ui: std::env::var("TERMINAL_DEBUG_UI")
                 .map(|v| v == "1")
                 .unwrap_or(cfg!(feature = "debug-mode")),
-            log_dir,
             enable_realtime_log: std::env::var("TERMINAL_DEBUG_REALTIME")
                 .map(|v| v == "1")
                 .unwrap_or(true),
@@ -70,6 +77,11 @@ impl DebugConfig {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(1000),
+            stall_reports_enabled: std::env::var("TERMINAL_STALL_REPORTS")
+                .map(|v| v == "1")
+                .unwrap_or(true),
+            stall_report_file: log_dir.join("stall-reports.jsonl"),
+            log_dir,
         }
     }
 
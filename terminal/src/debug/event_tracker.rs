@@ -17,6 +17,9 @@ static EVENT_HISTORY: Lazy<Mutex<EventHistory>> = Lazy::new(|| {
     Mutex::new(EventHistory::new(100)) // Keep last 100 events
 });
 
+/// Variant name and receipt time of the last event received
+static LAST_RECEIVED: Lazy<Mutex<Option<(String, Instant)>>> = Lazy::new(|| Mutex::new(None));
+
 /// Event tracking information
 #[derive(Debug, Clone)]
 pub struct EventInfo {
@@ -103,6 +106,10 @@ pub fn track_event_send(event_type: &str) -> u64 {
 pub fn track_event_receive(event_type: &str, event_id: Option<u64>) {
     let trace_id = super::trace_context::get_trace_id();
 
+    if let Ok(mut last) = LAST_RECEIVED.lock() {
+        *last = Some((event_name(event_type).to_string(), Instant::now()));
+    }
+
     if let Some(tid) = &trace_id {
         if let Some(eid) = event_id {
             tracing::debug!(
@@ -141,6 +148,19 @@ pub fn track_event_receive(event_type: &str, event_id: Option<u64>) {
     }
 }
 
+/// Variant name of the last event received and when it arrived
+pub fn last_received_event() -> Option<(String, Instant)> {
+    LAST_RECEIVED.lock().ok().and_then(|last| last.clone())
+}
+
+/// Variant name from an event's `Debug` output (`PricesUpdated([...])` → `PricesUpdated`)
+fn event_name(event_type: &str) -> &str {
+    let end = event_type
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(event_type.len());
+    &event_type[..end]
+}
+
 /// Get recent event history
 pub fn get_recent_events(count: usize) -> Vec<EventInfo> {
     EVENT_HISTORY
@@ -1,15 +1,61 @@
 //! Lock timing instrumentation for detecting contention and deadlocks
+//!
+//! Guards handed out by [`TracedRwLock`] are registered while they are held,
+//! so the watchdog can name the call sites holding a lock during a stall.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::ops::{Deref, DerefMut};
+use once_cell::sync::Lazy;
+
+/// Id of the next registered guard
+static NEXT_GUARD_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Guards currently held, by id
+static HELD_LOCKS: Lazy<Mutex<HashMap<u64, HeldLock>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A traced lock guard that hasn't been dropped yet
+#[derive(Debug, Clone)]
+pub struct HeldLock {
+    pub lock: &'static str,
+    pub caller: &'static str,
+    pub write: bool,
+    pub acquired_at: Instant,
+}
+
+/// Locks held right now, longest-held first
+pub fn held_locks() -> Vec<HeldLock> {
+    let mut locks: Vec<HeldLock> = HELD_LOCKS
+        .lock()
+        .map(|held| held.values().cloned().collect())
+        .unwrap_or_default();
+    locks.sort_by_key(|held| held.acquired_at);
+    locks
+}
+
+fn register_held(lock: &'static str, caller: &'static str, write: bool) -> u64 {
+    let id = NEXT_GUARD_ID.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut held) = HELD_LOCKS.lock() {
+        held.insert(id, HeldLock { lock, caller, write, acquired_at: Instant::now() });
+    }
+    id
+}
+
+fn release_held(id: u64) {
+    if let Ok(mut held) = HELD_LOCKS.lock() {
+        held.remove(&id);
+    }
+}
 
 /// Instrumented read guard that logs when lock is held too long
 pub struct TracedReadGuard<'a, T> {
     guard: RwLockReadGuard<'a, T>,
     lock_name: &'static str,
     acquired_at: Instant,
+    held_id: u64,
 }
 
 impl<'a, T> Deref for TracedReadGuard<'a, T> {
@@ -22,6 +68,7 @@ impl<'a, T> Deref for TracedReadGuard<'a, T> {
 
 impl<'a, T> Drop for TracedReadGuard<'a, T> {
     fn drop(&mut self) {
+        release_held(self.held_id);
         let held_duration = self.acquired_at.elapsed();
 
         // Warn if lock held for more than 50ms (blocking UI)
@@ -46,6 +93,7 @@ pub struct TracedWriteGuard<'a, T> {
     guard: RwLockWriteGuard<'a, T>,
     lock_name: &'static str,
     acquired_at: Instant,
+    held_id: u64,
 }
 
 impl<'a, T> Deref for TracedWriteGuard<'a, T> {
@@ -64,6 +112,7 @@ impl<'a, T> DerefMut for TracedWriteGuard<'a, T> {
 
 impl<'a, T> Drop for TracedWriteGuard<'a, T> {
     fn drop(&mut self) {
+        release_held(self.held_id);
         let held_duration = self.acquired_at.elapsed();
 
         // Warn if write lock held for more than 10ms (blocking readers)
@@ -130,6 +179,7 @@ impl<T> TracedRwLock<T> {
             guard,
             lock_name: self.name,
             acquired_at: Instant::now(),
+            held_id: register_held(self.name, caller, false),
         }
     }
 
@@ -165,6 +215,7 @@ impl<T> TracedRwLock<T> {
             guard,
             lock_name: self.name,
             acquired_at: Instant::now(),
+            held_id: register_held(self.name, caller, true),
         }
     }
 
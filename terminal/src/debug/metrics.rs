@@ -1,10 +1,201 @@
 //! Performance metrics collection
+//!
+//! Besides the per-frame breakdown, the last [`HISTORY_LEN`] frame times and
+//! event-processing latencies are kept in lock-free rings for the debug
+//! overlay's graphs, along with the event queue depth sampled each frame, the
+//! cost of the render path's state snapshot and of the candlestick chart's
+//! geometry updates.
 
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{OnceLock, Mutex};
 use std::time::{Duration, Instant};
 use sysinfo::System;
 
+/// Samples kept for each overlay graph (~10s of frames at 60 FPS)
+pub const HISTORY_LEN: usize = 600;
+
+/// Fixed-size ring of durations, written without locking
+///
+/// Samples are stored as whole microseconds. A reader racing a writer may see
+/// one slot from the next lap, which is fine for a graph.
+pub struct SampleRing<const N: usize> {
+    slots: [AtomicU32; N],
+    written: AtomicUsize,
+}
+
+impl<const N: usize> SampleRing<N> {
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { AtomicU32::new(0) }; N],
+            written: AtomicUsize::new(0),
+        }
+    }
+
+    /// Record a sample, overwriting the oldest once full
+    pub fn push(&self, sample: Duration) {
+        let micros = sample.as_micros().min(u32::MAX as u128) as u32;
+        let index = self.written.fetch_add(1, Ordering::Relaxed) % N;
+        self.slots[index].store(micros, Ordering::Relaxed);
+    }
+
+    /// Samples in milliseconds, oldest first
+    pub fn snapshot_ms(&self) -> Vec<f64> {
+        let written = self.written.load(Ordering::Relaxed);
+        let count = written.min(N);
+        (written - count..written)
+            .map(|i| self.slots[i % N].load(Ordering::Relaxed) as f64 / 1000.0)
+            .collect()
+    }
+}
+
+impl<const N: usize> Default for SampleRing<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Median, 95th percentile and maximum of a sample window
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Percentiles {
+    pub p50: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+impl Percentiles {
+    /// Summarize `samples` (any order); all zero when empty
+    pub fn of(samples: &[f64]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        Self {
+            p50: percentile(&sorted, 50.0),
+            p95: percentile(&sorted, 95.0),
+            max: sorted.last().copied().unwrap_or(0.0),
+        }
+    }
+}
+
+/// Nearest-rank percentile of ascending `sorted` samples; 0 when empty
+pub fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (pct.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Total time of recent frames
+static FRAME_TIMES: SampleRing<HISTORY_LEN> = SampleRing::new();
+/// Time `on_tick` spent handling events, for ticks that had any
+static EVENT_LATENCIES: SampleRing<HISTORY_LEN> = SampleRing::new();
+/// Events waiting in the channel at the last sample
+static QUEUED_EVENTS: AtomicUsize = AtomicUsize::new(0);
+/// Most events ever seen waiting at once
+static PEAK_QUEUED_EVENTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Time the render path spent taking its state snapshot
+static STATE_SNAPSHOT_TIMES: SampleRing<HISTORY_LEN> = SampleRing::new();
+/// Bytes the last snapshot shared with the live state instead of copying
+static STATE_SNAPSHOT_SHARED: AtomicUsize = AtomicUsize::new(0);
+
+/// Time the candlestick chart spent bringing its geometry up to date
+static CHART_GEOMETRY_TIMES: SampleRing<HISTORY_LEN> = SampleRing::new();
+/// Chart frames drawn from cached geometry, and all chart frames
+static CHART_FRAMES_CACHED: AtomicU64 = AtomicU64::new(0);
+static CHART_FRAMES: AtomicU64 = AtomicU64::new(0);
+/// Candles rebuilt by the last geometry update that rebuilt any
+static CHART_LAST_REBUILT: AtomicUsize = AtomicUsize::new(0);
+
+/// Events dropped because the event queue stayed full
+static DROPPED_EVENTS: AtomicU64 = AtomicU64::new(0);
+/// Price events merged into a newer one instead of being handled
+static COALESCED_EVENTS: AtomicU64 = AtomicU64::new(0);
+
+/// Record how long one tick spent processing events
+pub fn record_event_latency(latency: Duration) {
+    EVENT_LATENCIES.push(latency);
+}
+
+/// Record the event channel depth sampled at the start of a frame
+pub fn record_event_queue_depth(depth: usize) {
+    QUEUED_EVENTS.store(depth, Ordering::Relaxed);
+    PEAK_QUEUED_EVENTS.fetch_max(depth, Ordering::Relaxed);
+}
+
+/// Count events dropped by the event queue's overflow policy
+pub fn record_events_dropped(count: usize) {
+    DROPPED_EVENTS.fetch_add(count as u64, Ordering::Relaxed);
+}
+
+/// Count price events coalesced into a newer one
+pub fn record_events_coalesced(count: usize) {
+    COALESCED_EVENTS.fetch_add(count as u64, Ordering::Relaxed);
+}
+
+/// Events dropped and coalesced so far
+pub fn event_overflow_counts() -> (u64, u64) {
+    (DROPPED_EVENTS.load(Ordering::Relaxed), COALESCED_EVENTS.load(Ordering::Relaxed))
+}
+
+/// Recent frame times in milliseconds, oldest first
+pub fn frame_time_history() -> Vec<f64> {
+    FRAME_TIMES.snapshot_ms()
+}
+
+/// Recent event-processing latencies in milliseconds, oldest first
+pub fn event_latency_history() -> Vec<f64> {
+    EVENT_LATENCIES.snapshot_ms()
+}
+
+/// Record the render path's state snapshot for this frame
+pub fn record_state_snapshot(elapsed: Duration, shared_bytes: usize) {
+    STATE_SNAPSHOT_TIMES.push(elapsed);
+    STATE_SNAPSHOT_SHARED.store(shared_bytes, Ordering::Relaxed);
+}
+
+/// Recent state snapshot times in milliseconds, oldest first
+pub fn state_snapshot_history() -> Vec<f64> {
+    STATE_SNAPSHOT_TIMES.snapshot_ms()
+}
+
+/// Bytes the last state snapshot shared instead of copying
+pub fn state_snapshot_shared_bytes() -> usize {
+    STATE_SNAPSHOT_SHARED.load(Ordering::Relaxed)
+}
+
+/// Record one frame of the candlestick chart (geometry update plus the copy
+/// handed to the plot) and how many candles it rebuilt
+pub fn record_chart_geometry(elapsed: Duration, rebuilt: usize) {
+    CHART_GEOMETRY_TIMES.push(elapsed);
+    CHART_FRAMES.fetch_add(1, Ordering::Relaxed);
+    if rebuilt == 0 {
+        CHART_FRAMES_CACHED.fetch_add(1, Ordering::Relaxed);
+    } else {
+        CHART_LAST_REBUILT.store(rebuilt, Ordering::Relaxed);
+    }
+}
+
+/// Recent chart geometry update times in milliseconds, oldest first
+pub fn chart_geometry_history() -> Vec<f64> {
+    CHART_GEOMETRY_TIMES.snapshot_ms()
+}
+
+/// Chart frames drawn from cache, all chart frames, and the candles the
+/// last rebuild touched
+pub fn chart_geometry_counts() -> (u64, u64, usize) {
+    (
+        CHART_FRAMES_CACHED.load(Ordering::Relaxed),
+        CHART_FRAMES.load(Ordering::Relaxed),
+        CHART_LAST_REBUILT.load(Ordering::Relaxed),
+    )
+}
+
+/// Events queued at the last sample, and the peak so far
+pub fn event_queue_depth() -> (usize, usize) {
+    (QUEUED_EVENTS.load(Ordering::Relaxed), PEAK_QUEUED_EVENTS.load(Ordering::Relaxed))
+}
+
 /// Frame timing metrics
 #[derive(Debug, Clone)]
 pub struct FrameMetrics {
@@ -48,6 +239,7 @@ impl FrameMetrics {
         self.tick_time = tick;
         self.render_time = render;
         self.last_update = Instant::now();
+        FRAME_TIMES.push(total);
 
         // Add to history
         self.frame_history.push_back(total);
@@ -189,3 +381,54 @@ pub fn get_memory_metrics() -> Option<MemoryMetrics> {
         m.lock().ok().map(|guard| *guard)
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let samples: Vec<f64> = (1..=100).rev().map(f64::from).collect();
+        let stats = Percentiles::of(&samples);
+        assert_eq!(stats, Percentiles { p50: 50.0, p95: 95.0, max: 100.0 });
+
+        assert_eq!(Percentiles::of(&[]), Percentiles::default());
+        assert_eq!(Percentiles::of(&[7.5]), Percentiles { p50: 7.5, p95: 7.5, max: 7.5 });
+
+        // Nearest rank: a single outlier in 20 samples is the p95
+        let mut spiky = vec![16.0; 19];
+        spiky.push(120.0);
+        assert_eq!(Percentiles::of(&spiky).p95, 16.0);
+        spiky.push(120.0);
+        assert_eq!(Percentiles::of(&spiky).p95, 120.0);
+    }
+
+    #[test]
+    fn test_percentile_bounds() {
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 25.0), 1.0);
+        assert_eq!(percentile(&sorted, 26.0), 2.0);
+        assert_eq!(percentile(&sorted, 100.0), 4.0);
+        assert_eq!(percentile(&sorted, 250.0), 4.0);
+    }
+
+    #[test]
+    fn test_sample_ring_keeps_latest() {
+        let ring = SampleRing::<4>::new();
+        assert!(ring.snapshot_ms().is_empty());
+
+        for ms in 1..=3 {
+            ring.push(Duration::from_millis(ms));
+        }
+        assert_eq!(ring.snapshot_ms(), [1.0, 2.0, 3.0]);
+
+        for ms in 4..=6 {
+            ring.push(Duration::from_millis(ms));
+        }
+        assert_eq!(ring.snapshot_ms(), [3.0, 4.0, 5.0, 6.0]);
+
+        ring.push(Duration::from_micros(1500));
+        assert_eq!(ring.snapshot_ms(), [4.0, 5.0, 6.0, 1.5]);
+    }
+}
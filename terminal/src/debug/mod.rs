@@ -40,6 +40,9 @@
 //! - `RUST_LOG`: Log level filter (e.g., `terminal=debug,info`)
 //! - `TERMINAL_LOG_FILE`: Custom log file path (default: `logs/terminal-debug.log`)
 //! - `TERMINAL_DEBUG_UI`: Enable in-UI debug overlay (1=on, 0=off)
+//! - `TERMINAL_FREEZE_THRESHOLD`: Missed-heartbeat time (ms) that counts as a freeze
+//! - `TERMINAL_STALL_REPORTS`: Capture a stall report per freeze to `logs/stall-reports.jsonl` (1=on, 0=off)
+//! - `TERMINAL_LATENCY_BUDGETS`: Swap stage budgets in ms, e.g. `signing=300,acknowledgement=1500` (see [`crate::app::latency`])
 
 pub mod config;
 pub mod lock_tracer;
@@ -54,10 +57,10 @@ pub mod error_aggregator;
 pub use config::DebugConfig;
 pub use lock_tracer::{TracedRwLock, block_on_read, block_on_write};
 pub use logger::init as init_logger;
-pub use metrics::{FrameMetrics, record_frame_time, init_metrics, update_memory_metrics};
+pub use metrics::{FrameMetrics, record_frame_time, init_metrics, update_memory_metrics, record_event_latency, record_event_queue_depth};
 pub use task_tracker::{spawn_tracked, active_task_count, track_blocking};
 pub use trace_context::{TraceGuard, new_trace_id, set_trace_id, get_trace_id, clear_trace_id, with_trace_id, with_trace_id_async};
-pub use watchdog::{update_heartbeat, init_from_config as init_watchdog};
+pub use watchdog::{update_heartbeat, init_from_config as init_watchdog, recent_stall_reports, StallReport};
 pub use event_tracker::{track_event_send, track_event_receive, get_recent_events, pending_event_count, log_event_stats};
 pub use error_aggregator::{record_error, record_warning, record_panic, get_recent_errors, get_error_stats, total_error_count, log_error_stats, ErrorEntry, ErrorLevel};
 
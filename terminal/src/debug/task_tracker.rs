@@ -1,18 +1,63 @@
 //! Async task lifecycle tracking for debugging hung tasks and performance
 
+use std::collections::HashMap;
 use std::future::Future;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 use std::time::Instant;
+use once_cell::sync::Lazy;
 use tokio::task::JoinHandle;
 
 /// Global task counter
 static TASK_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+/// Id of the next spawned task
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Tracked tasks still running, by id
+static ACTIVE_TASKS: Lazy<Mutex<HashMap<u64, ActiveTask>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A tracked task that hasn't finished yet
+#[derive(Debug, Clone)]
+pub struct ActiveTask {
+    pub name: &'static str,
+    pub task_id: u64,
+    pub started_at: Instant,
+}
+
 /// Get current number of active tasks
 pub fn active_task_count() -> u64 {
     TASK_COUNTER.load(Ordering::Relaxed)
 }
 
+/// Tracked tasks running right now, oldest first
+pub fn active_tasks() -> Vec<ActiveTask> {
+    let mut tasks: Vec<ActiveTask> = ACTIVE_TASKS
+        .lock()
+        .map(|tasks| tasks.values().cloned().collect())
+        .unwrap_or_default();
+    tasks.sort_by_key(|task| task.started_at);
+    tasks
+}
+
+/// Register a spawned task; returns its id
+fn register_task(name: &'static str) -> u64 {
+    TASK_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let task_id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut tasks) = ACTIVE_TASKS.lock() {
+        tasks.insert(task_id, ActiveTask { name, task_id, started_at: Instant::now() });
+    }
+    task_id
+}
+
+/// Unregister a finished task
+fn finish_task(task_id: u64) {
+    if let Ok(mut tasks) = ACTIVE_TASKS.lock() {
+        tasks.remove(&task_id);
+    }
+    TASK_COUNTER.fetch_sub(1, Ordering::Relaxed);
+}
+
 /// Spawn an instrumented async task with lifecycle tracking
 ///
 /// # Arguments
@@ -36,7 +81,7 @@ where
     F: Future + Send + 'static,
     F::Output: Send + 'static,
 {
-    let task_id = TASK_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let task_id = register_task(name);
     let start = Instant::now();
 
     tracing::info!(
@@ -67,7 +112,7 @@ where
             );
         }
 
-        TASK_COUNTER.fetch_sub(1, Ordering::Relaxed);
+        finish_task(task_id);
         result
     })
 }
@@ -85,7 +130,7 @@ where
     F: Future + Send + 'static,
     F::Output: Send + 'static,
 {
-    let task_id = TASK_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let task_id = register_task(name);
     let start = Instant::now();
 
     tracing::info!(
@@ -123,7 +168,7 @@ where
             "Task completed"
         );
 
-        TASK_COUNTER.fetch_sub(1, Ordering::Relaxed);
+        finish_task(task_id);
         result
     })
 }
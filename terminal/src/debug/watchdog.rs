@@ -2,10 +2,22 @@
 //!
 //! Monitors the main thread's heartbeat and detects when the UI becomes
 //! unresponsive for longer than the configured threshold.
+//!
+//! When stall reports are enabled, each freeze is captured as a
+//! [`StallReport`]: the last event the UI received, the traced locks held,
+//! the pending event count and the tracked tasks still running. Reports are
+//! recorded with the error aggregator, appended to the stall report file
+//! (once when the stall is detected, again when it ends) and listed in the
+//! debug overlay.
 
 use super::config::DebugConfig;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
@@ -15,11 +27,187 @@ static LAST_HEARTBEAT: AtomicU64 = AtomicU64::new(0);
 /// Global watchdog enabled flag
 static WATCHDOG_ENABLED: AtomicBool = AtomicBool::new(false);
 
+/// Most stall reports kept for the debug overlay
+const MAX_STALL_REPORTS: usize = 20;
+
+/// Recent stall reports, oldest first
+static STALL_REPORTS: Lazy<Mutex<VecDeque<StallReport>>> = Lazy::new(|| {
+    Mutex::new(VecDeque::with_capacity(MAX_STALL_REPORTS))
+});
+
+/// A traced lock that was held when a stall was captured
+#[derive(Debug, Clone, Serialize)]
+pub struct LockSnapshot {
+    pub lock: String,
+    pub caller: String,
+    pub write: bool,
+    pub held_ms: u64,
+}
+
+/// A tracked task that was running when a stall was captured
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskSnapshot {
+    pub name: String,
+    pub task_id: u64,
+    pub running_ms: u64,
+}
+
+/// What the app was doing when the main thread stopped responding
+#[derive(Debug, Clone, Serialize)]
+pub struct StallReport {
+    /// Unix time (ms) of the last heartbeat before the stall
+    pub started_at_ms: u64,
+    /// How long the heartbeat was missed; grows until the stall ends
+    pub duration_ms: u64,
+    /// Whether the main thread has responded again
+    pub resolved: bool,
+    /// Variant of the last `AppEvent` received before the stall
+    pub last_event: Option<String>,
+    /// How long before the capture that event arrived
+    pub last_event_age_ms: Option<u64>,
+    pub held_locks: Vec<LockSnapshot>,
+    pub pending_events: usize,
+    pub active_tasks: Vec<TaskSnapshot>,
+}
+
+impl StallReport {
+    /// Snapshot the lock, event and task trackers
+    fn capture(started_at_ms: u64, duration_ms: u64) -> Self {
+        let last_event = super::event_tracker::last_received_event();
+        Self {
+            started_at_ms,
+            duration_ms,
+            resolved: false,
+            last_event_age_ms: last_event.as_ref().map(|(_, at)| at.elapsed().as_millis() as u64),
+            last_event: last_event.map(|(name, _)| name),
+            held_locks: super::lock_tracer::held_locks()
+                .into_iter()
+                .map(|held| LockSnapshot {
+                    lock: held.lock.to_string(),
+                    caller: held.caller.to_string(),
+                    write: held.write,
+                    held_ms: held.acquired_at.elapsed().as_millis() as u64,
+                })
+                .collect(),
+            pending_events: super::event_tracker::pending_event_count(),
+            active_tasks: super::task_tracker::active_tasks()
+                .into_iter()
+                .map(|task| TaskSnapshot {
+                    name: task.name.to_string(),
+                    task_id: task.task_id,
+                    running_ms: task.started_at.elapsed().as_millis() as u64,
+                })
+                .collect(),
+        }
+    }
+
+    /// One-line summary for the error aggregator and the overlay
+    pub fn summary(&self) -> String {
+        let mut summary = format!("UI stalled for {}ms", self.duration_ms);
+        if let Some(event) = &self.last_event {
+            summary.push_str(&format!(" after {}", event));
+        }
+        if !self.held_locks.is_empty() {
+            let callers: Vec<&str> = self.held_locks.iter().map(|held| held.caller.as_str()).collect();
+            summary.push_str(&format!("; locks held by {}", callers.join(", ")));
+        }
+        summary.push_str(&format!(
+            "; {} pending events, {} active tasks",
+            self.pending_events,
+            self.active_tasks.len()
+        ));
+        summary
+    }
+}
+
+/// Most recent stall reports, newest first
+pub fn recent_stall_reports(count: usize) -> Vec<StallReport> {
+    STALL_REPORTS
+        .lock()
+        .map(|reports| reports.iter().rev().take(count).cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Add a report, or replace the one for the same stall
+fn store_report(report: &StallReport) {
+    if let Ok(mut reports) = STALL_REPORTS.lock() {
+        if let Some(existing) = reports.iter_mut().rev().find(|r| r.started_at_ms == report.started_at_ms) {
+            *existing = report.clone();
+            return;
+        }
+        if reports.len() >= MAX_STALL_REPORTS {
+            reports.pop_front();
+        }
+        reports.push_back(report.clone());
+    }
+}
+
+/// Follows one stall at a time from detection to recovery
+struct StallRecorder {
+    threshold_ms: u64,
+    report_file: Option<PathBuf>,
+    current: Option<StallReport>,
+}
+
+impl StallRecorder {
+    fn new(threshold_ms: u64, report_file: Option<PathBuf>) -> Self {
+        Self { threshold_ms, report_file, current: None }
+    }
+
+    /// Feed one heartbeat check
+    ///
+    /// Returns the report when this check detected a new stall or saw one end.
+    fn observe(&mut self, last_heartbeat_ms: u64, now_ms: u64) -> Option<StallReport> {
+        let elapsed = now_ms.saturating_sub(last_heartbeat_ms);
+
+        if elapsed > self.threshold_ms {
+            if let Some(report) = &mut self.current {
+                report.duration_ms = elapsed;
+                store_report(report);
+                return None;
+            }
+            let report = StallReport::capture(last_heartbeat_ms, elapsed);
+            super::error_aggregator::record_error(report.summary(), Some("debug::watchdog".to_string()));
+            self.write(&report);
+            store_report(&report);
+            self.current = Some(report.clone());
+            return Some(report);
+        }
+
+        let mut report = self.current.take()?;
+        // The heartbeat that ended the stall
+        report.duration_ms = last_heartbeat_ms.saturating_sub(report.started_at_ms).max(report.duration_ms);
+        report.resolved = true;
+        self.write(&report);
+        store_report(&report);
+        Some(report)
+    }
+
+    /// Append a report to the stall report file
+    fn write(&self, report: &StallReport) {
+        let Some(path) = &self.report_file else {
+            return;
+        };
+        let result = (|| -> std::io::Result<()> {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let line = serde_json::to_string(report)?;
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", line)
+        })();
+        if let Err(e) = result {
+            tracing::warn!(path = %path.display(), "Failed to write stall report: {}", e);
+        }
+    }
+}
+
 /// Watchdog state
 pub struct Watchdog {
     threshold_ms: u64,
     check_interval_ms: u64,
     enabled: Arc<AtomicBool>,
+    stall_recorder: Option<StallRecorder>,
 }
 
 impl Watchdog {
@@ -30,9 +218,16 @@ impl Watchdog {
             threshold_ms,
             check_interval_ms,
             enabled: Arc::new(AtomicBool::new(true)),
+            stall_recorder: None,
         }
     }
 
+    /// Capture a stall report for each freeze, appending them to `report_file`
+    pub fn with_stall_reports(mut self, report_file: PathBuf) -> Self {
+        self.stall_recorder = Some(StallRecorder::new(self.threshold_ms, Some(report_file)));
+        self
+    }
+
     /// Start the watchdog monitoring in a background task
     pub fn start(self) {
         WATCHDOG_ENABLED.store(true, Ordering::Relaxed);
@@ -53,7 +248,7 @@ impl Watchdog {
     }
 
     /// Main monitoring loop
-    async fn monitor_loop(&self) {
+    async fn monitor_loop(mut self) {
         let mut last_warning_time = Instant::now();
         let mut consecutive_warnings = 0;
 
@@ -72,6 +267,10 @@ impl Watchdog {
             let now = current_time_ms();
             let elapsed = now.saturating_sub(last_heartbeat);
 
+            if let Some(recorder) = &mut self.stall_recorder {
+                recorder.observe(last_heartbeat, now);
+            }
+
             if elapsed > self.threshold_ms {
                 consecutive_warnings += 1;
 
@@ -143,7 +342,10 @@ pub fn init_from_config() {
     let config = DebugConfig::from_env();
 
     if config.freeze_threshold_ms > 0 {
-        let watchdog = Watchdog::new(config.freeze_threshold_ms);
+        let mut watchdog = Watchdog::new(config.freeze_threshold_ms);
+        if config.stall_reports_enabled {
+            watchdog = watchdog.with_stall_reports(config.stall_report_file);
+        }
         watchdog.start();
     } else {
         tracing::info!("Watchdog disabled (freeze_threshold_ms = 0)");
@@ -169,6 +371,54 @@ mod tests {
         assert!(heartbeat > 0);
     }
 
+    #[test]
+    fn test_missed_heartbeat_generates_report() {
+        let mut recorder = StallRecorder::new(100, None);
+        let heartbeat = current_time_ms();
+
+        // Heartbeat still fresh
+        assert!(recorder.observe(heartbeat, heartbeat + 50).is_none());
+
+        // No update_heartbeat for 250ms
+        let report = recorder.observe(heartbeat, heartbeat + 250).expect("stall report");
+        assert_eq!(report.started_at_ms, heartbeat);
+        assert_eq!(report.duration_ms, 250);
+        assert!(!report.resolved);
+        assert!(recent_stall_reports(MAX_STALL_REPORTS).iter().any(|r| r.started_at_ms == heartbeat));
+
+        // Still stalled: same report, longer
+        assert!(recorder.observe(heartbeat, heartbeat + 400).is_none());
+
+        // Heartbeat resumes
+        let resolved = recorder.observe(heartbeat + 450, heartbeat + 460).expect("resolved report");
+        assert!(resolved.resolved);
+        assert_eq!(resolved.duration_ms, 450);
+        let stored = recent_stall_reports(MAX_STALL_REPORTS)
+            .into_iter()
+            .find(|r| r.started_at_ms == heartbeat)
+            .unwrap();
+        assert!(stored.resolved);
+    }
+
+    #[test]
+    fn test_stall_report_file() {
+        let path = std::env::temp_dir().join(format!("stall-reports-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut recorder = StallRecorder::new(100, Some(path.clone()));
+        let heartbeat = current_time_ms() - 10_000;
+
+        recorder.observe(heartbeat, heartbeat + 500);
+        recorder.observe(heartbeat + 800, heartbeat + 810);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["resolved"], false);
+        assert_eq!(lines[1]["resolved"], true);
+        assert_eq!(lines[1]["duration_ms"], 800);
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn test_current_time() {
         let now = current_time_ms();
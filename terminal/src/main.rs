@@ -75,7 +75,9 @@
 
 use eframe::egui;
 use std::time::{Duration, Instant};
-use crate::app::{App, show_deferred_viewport};
+use crate::app::{App, AppEvent, show_deferred_viewport, sync_window_geometry};
+use crate::app::keybindings::KeyAction;
+use crate::protocol_handler::{self, Request, Startup};
 
 mod app;
 mod core;
@@ -105,6 +107,28 @@ async fn main() -> Result<(), eframe::Error> {
     tracing::info!("Terminal startup - Debug viewer should be tracking logs from this point");
     tracing::debug!("Main function entry point - Application initialization beginning");
 
+    // A link click launches us again; hand the link to the running instance if there is one
+    let link = protocol_handler::link_from_args(std::env::args());
+    let request = match &link {
+        Some(link) => Request::Open(link.clone()),
+        None => Request::Focus,
+    };
+    let instance_server = match protocol_handler::claim_or_forward(protocol_handler::instance_addr(), &request) {
+        Ok(Startup::Forwarded) => {
+            tracing::info!("Forwarded {:?} to the running instance, exiting", request);
+            return Ok(());
+        }
+        Ok(Startup::Primary(server)) => Some(server),
+        Ok(Startup::Standalone) => None,
+        Err(e) => {
+            tracing::warn!("Single-instance check failed, continuing without it: {}", e);
+            None
+        }
+    };
+    if let Err(e) = protocol_handler::ensure_registered() {
+        tracing::warn!("Failed to register {}:// links: {}", protocol_handler::SCHEME, e);
+    }
+
     // Create app state with error handling
     let app = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| App::new())) {
         Ok(app) => app,
@@ -126,6 +150,19 @@ async fn main() -> Result<(), eframe::Error> {
 
     tracing::info!("App state created successfully");
 
+    // Links from later launches, then our own, go through the same confirmation prompt
+    if let Some(server) = instance_server {
+        let event_tx = app.event_tx();
+        if let Err(e) = server.spawn(move |request| {
+            crate::app::event_queue::try_send_or_drop(&event_tx, AppEvent::InstanceRequest(request));
+        }) {
+            tracing::warn!("Failed to listen for links from other launches: {}", e);
+        }
+    }
+    if let Some(link) = link {
+        crate::app::event_queue::try_send_or_drop(&app.event_tx(), AppEvent::InstanceRequest(Request::Open(link)));
+    }
+
     // Native options for window - with title bar for window movement
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -176,14 +213,31 @@ async fn main() -> Result<(), eframe::Error> {
                 }
             }
             
+            // Reopen the windows from the last session; a bad saved layout just means one window
+            let mut viewport_fullscreen = std::collections::HashMap::new();
+            match crate::app::WindowLayout::load(&crate::core::store::store().settings()) {
+                Ok(Some(layout)) => {
+                    app.window_manager.write().restore_layout(&layout);
+                    if let Some(root) = &layout.root {
+                        viewport_fullscreen.insert(egui::ViewportId::ROOT, root.is_fullscreen);
+                    }
+                    tracing::info!("Restored window layout with {} secondary windows", layout.windows.len());
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!("Ignoring saved window layout: {}", e);
+                }
+            }
+            
             tracing::info!("Creating GuiApp instance...");
+            let cube = RotatingCube::from_branding(&app.state.read().branding);
             Ok(Box::new(GuiApp { 
                 app,
-                cube: RotatingCube::new(),
+                cube,
                 last_frame_time: Instant::now(),
                 notifications: crate::ui::widgets::notifications::NotificationManager::new(),
                 theme_applied: false,
-                viewport_fullscreen: std::collections::HashMap::new(),
+                viewport_fullscreen,
             }))
         }),
     );
@@ -214,6 +268,8 @@ struct GuiApp {
 
 impl eframe::App for GuiApp {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let frame_start = Instant::now();
+
         // Initialize fonts and apply theme on first update (after egui is fully initialized)
         // This prevents panic in egui 0.33's style.rs during initialization
         // Fonts must be initialized BEFORE theme application to ensure text styles exist
@@ -246,17 +302,19 @@ impl eframe::App for GuiApp {
         self.last_frame_time = now;
         self.cube.update(delta_time);
 
-        // Handle Ctrl+N to create new window
-        if ctx.input(|i| i.key_pressed(egui::Key::N) && i.modifiers.ctrl) {
-            let current_screen = {
-                let state = self.app.state.read();
-                state.current_screen
-            };
+        // Window-level shortcuts; ui::render handles the others
+        let (shortcuts, current_screen) = {
+            let state = self.app.state.read();
+            (crate::app::keybindings::pressed(ctx, &state.settings), state.current_screen)
+        };
+
+        // New window (Ctrl+N by default)
+        if shortcuts.contains(&KeyAction::NewWindow) {
             crate::ui::widgets::window_controls::create_new_window(&mut self.app, current_screen);
         }
         
-        // Handle F11 to toggle fullscreen for the focused viewport
-        if ctx.input(|i| i.key_pressed(egui::Key::F11)) {
+        // Toggle fullscreen for the focused viewport (F11 by default)
+        if shortcuts.contains(&KeyAction::ToggleFullscreen) {
             // Get the currently focused viewport (defaults to ROOT)
             // TODO: Implement proper focused viewport detection when egui API is clearer
             let focused_viewport = egui::ViewportId::ROOT;
@@ -280,18 +338,38 @@ impl eframe::App for GuiApp {
             );
         }
         
+        // Track the main window's geometry and save the layout when it closes
+        sync_window_geometry(ctx, crate::app::WindowId(0), &self.app.window_manager);
+        if ctx.input(|i| i.viewport().close_requested()) {
+            if let Err(e) = self.app.window_manager.read().save_layout() {
+                tracing::warn!("Failed to save window layout: {}", e);
+            }
+        }
+
         // Render all secondary windows
         self.render_secondary_windows(ctx);
 
         // Process async events on every frame (this processes events from event_rx)
+        let tick_start = Instant::now();
         self.app.on_tick();
+
+        // Extend the login session near expiry, but only while someone is using the terminal
+        self.app.keep_session_alive(ctx.input(|i| i.focused));
+
+        // Drop connections while the window is left alone, reconnect on return
+        let signals = ctx.input(|i| crate::app::idle::WindowSignals {
+            focused: i.focused,
+            minimized: i.viewport().minimized.unwrap_or(false),
+            interacted: !i.events.is_empty() || i.pointer.delta() != egui::Vec2::ZERO,
+        });
+        self.app.idle_tick(signals);
         
         // Process pending notifications from app state
         self.process_notifications();
 
         // CRITICAL: Check if immediate repaint is needed (real-time price updates)
         // Process this FIRST before any other checks to minimize latency
-        let (needs_immediate_repaint, is_receiving_updates, ws_connected) = {
+        let (needs_immediate_repaint, is_receiving_updates, ws_connected, low_power) = {
             let state = self.app.state.read();
             (
                 state.needs_immediate_repaint,
@@ -300,11 +378,15 @@ impl eframe::App for GuiApp {
                     && state.last_price_update_time.elapsed().as_millis() < 1000, // Updated in last second
                 state.websocket_connected 
                     && matches!(state.websocket_status.state, crate::app::WebSocketState::Connected),
+                state.idle.is_low_power(),
             )
         };
         
-        // CRITICAL: Immediate repaint for price updates (0ms delay for instant updates)
-        if needs_immediate_repaint {
+        if low_power {
+            // Nothing is streaming; wake only often enough to notice the user coming back
+            ctx.request_repaint_after(Duration::from_secs(1));
+        } else if needs_immediate_repaint {
+            // CRITICAL: Immediate repaint for price updates (0ms delay for instant updates)
             // Instant repaint - no delay for Bloomberg-style real-time updates
             ctx.request_repaint();
             // Clear the flag immediately
@@ -325,10 +407,18 @@ impl eframe::App for GuiApp {
         }
 
         // Render UI (pass frame for window controls, notifications for potential in-render notifications)
+        let render_start = Instant::now();
         ui::render(ctx, &mut self.app, &mut self.notifications, &mut self.cube, frame);
         
         // Show notifications (rendered on top of everything)
         self.notifications.show(ctx);
+
+        // Frame breakdown for the debug overlay
+        debug::record_frame_time(
+            tick_start - frame_start,
+            render_start - tick_start,
+            render_start.elapsed(),
+        );
     }
 }
 
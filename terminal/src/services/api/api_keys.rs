@@ -0,0 +1,72 @@
+//! # API Key Client
+//!
+//! HTTP client methods for managing the user's API keys.
+
+use super::client::{ApiClient, ApiError, SendVia};
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+use shared::dto::api_keys::{ApiKeyListResponse, CreateApiKeyRequest, CreateApiKeyResponse};
+
+impl ApiClient {
+
+    /// Create an API key; the response carries the key, which is never shown again
+    pub async fn create_api_key(
+        &self,
+        token: &str,
+        request: &CreateApiKeyRequest,
+    ) -> Result<CreateApiKeyResponse, ApiError> {
+        let url = format!("{}/api/keys", self.base_url());
+
+        let response = self.http()
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .json(request)
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        parse_response(response).await
+    }
+
+    /// List the user's API keys
+    pub async fn list_api_keys(&self, token: &str) -> Result<ApiKeyListResponse, ApiError> {
+        let url = format!("{}/api/keys", self.base_url());
+
+        let response = self.http()
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        parse_response(response).await
+    }
+
+    /// Revoke an API key; scripts using it are refused from then on
+    pub async fn revoke_api_key(&self, token: &str, id: i64) -> Result<(), ApiError> {
+        let url = format!("{}/api/keys/{}", self.base_url(), id);
+
+        let response = self.http()
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(ApiError::from_response(response, "API error").await)
+        }
+    }
+}
+
+async fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, ApiError> {
+    if response.status().is_success() {
+        response.json::<T>()
+            .await
+            .map_err(ApiError::parse)
+    } else {
+        Err(ApiError::from_response(response, "API error").await)
+    }
+}
@@ -1,9 +1,12 @@
 //! # Authentication Endpoints
 //!
-//! Handles user authentication (login and signup).
+//! Handles user authentication (login, signup and logout) and account updates.
 
-use shared::{AuthResponse, ErrorResponse, LoginRequest, SignupRequest};
-use super::client::ApiClient;
+use shared::{
+    AuthResponse, ChangePasswordRequest, ErrorResponse, LoginRequest, SignupRequest, UpdateProfileRequest,
+    UserInfo,
+};
+use super::client::{ApiClient, ApiError, SendVia};
 
 /// Login with username/email and password.
 #[tracing::instrument(skip(client, password), fields(email_or_username = %email_or_username))]
@@ -11,7 +14,7 @@ pub async fn login(
     client: &ApiClient,
     email_or_username: String,
     password: String,
-) -> Result<AuthResponse, String> {
+) -> Result<AuthResponse, ApiError> {
     tracing::info!("Attempting login");
     let start = std::time::Instant::now();
 
@@ -21,14 +24,14 @@ pub async fn login(
     };
 
     let response = client
-        .client
-        .post(format!("{}/api/auth/login", ApiClient::base_url()))
+        .http()
+        .post(format!("{}/api/auth/login", client.base_url()))
         .json(&request)
-        .send()
+        .send_via(client)
         .await
         .map_err(|e| {
             tracing::error!(error = %e, "Login network error");
-            format!("Network error: {}", e)
+            ApiError::from(e)
         })?;
 
     let status = response.status();
@@ -40,7 +43,7 @@ pub async fn login(
             .await
             .map_err(|e| {
                 tracing::error!(error = %e, "Login response parse error");
-                format!("Failed to parse response: {}", e)
+                ApiError::parse(e)
             });
 
         if result.is_ok() {
@@ -48,18 +51,15 @@ pub async fn login(
         }
         result
     } else {
-        let error = response
-            .json::<ErrorResponse>()
-            .await
-            .map_err(|e| format!("Failed to parse error: {}", e))?;
+        let error = rejection(response).await;
 
         tracing::warn!(
             status = status.as_u16(),
-            error = %error.error,
+            error = %error,
             duration_ms = duration.as_millis(),
             "Login failed"
         );
-        Err(error.error)
+        Err(error)
     }
 }
 
@@ -69,7 +69,7 @@ pub async fn signup(
     username: String,
     email: String,
     password: String,
-) -> Result<AuthResponse, String> {
+) -> Result<AuthResponse, ApiError> {
     let request = SignupRequest {
         username,
         email,
@@ -77,24 +77,121 @@ pub async fn signup(
     };
 
     let response = client
-        .client
-        .post(format!("{}/api/auth/signup", ApiClient::base_url()))
+        .http()
+        .post(format!("{}/api/auth/signup", client.base_url()))
         .json(&request)
-        .send()
+        .send_via(client)
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(ApiError::from)?;
 
     if response.status().is_success() {
         response
             .json::<AuthResponse>()
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e))
+            .map_err(ApiError::parse)
+    } else {
+        Err(rejection(response).await)
+    }
+}
+
+
+/// Log out: revoke `jwt_token` on the backend.
+///
+/// A token that is already expired or revoked counts as logged out.
+pub async fn logout(client: &ApiClient, jwt_token: &str) -> Result<(), ApiError> {
+    let response = client
+        .http()
+        .post(format!("{}/api/auth/logout", client.base_url()))
+        .header("Authorization", format!("Bearer {}", jwt_token))
+        .send_via(client)
+        .await
+        .map_err(ApiError::from)?;
+
+    let status = response.status();
+    if status.is_success() || status == reqwest::StatusCode::UNAUTHORIZED {
+        Ok(())
     } else {
-        let error = response
-            .json::<ErrorResponse>()
+        // The auth middleware answers with an empty body
+        Err(ApiError::from_response(response, "Logout failed").await)
+    }
+}
+
+/// Trade a still-valid JWT for a fresh one.
+///
+/// The old token is revoked by the backend, so callers switch to the returned
+/// one straight away.
+pub async fn refresh_session(client: &ApiClient, jwt_token: &str) -> Result<AuthResponse, ApiError> {
+    let response = client
+        .http()
+        .post(format!("{}/api/auth/refresh", client.base_url()))
+        .header("Authorization", format!("Bearer {}", jwt_token))
+        .send_via(client)
+        .await
+        .map_err(ApiError::from)?;
+
+    parse_account_response(response).await
+}
+
+/// Change the password of the logged-in user.
+///
+/// Every other session is revoked, so the returned response carries the
+/// token to keep using.
+pub async fn change_password(
+    client: &ApiClient,
+    jwt_token: &str,
+    current_password: String,
+    new_password: String,
+) -> Result<AuthResponse, ApiError> {
+    let request = ChangePasswordRequest {
+        current_password,
+        new_password,
+    };
+
+    let response = client
+        .http()
+        .put(format!("{}/api/auth/password", client.base_url()))
+        .header("Authorization", format!("Bearer {}", jwt_token))
+        .json(&request)
+        .send_via(client)
+        .await
+        .map_err(ApiError::from)?;
+
+    parse_account_response(response).await
+}
+
+/// Update the email address of the logged-in user.
+pub async fn update_profile(client: &ApiClient, jwt_token: &str, email: String) -> Result<UserInfo, ApiError> {
+    let response = client
+        .http()
+        .put(format!("{}/api/auth/profile", client.base_url()))
+        .header("Authorization", format!("Bearer {}", jwt_token))
+        .json(&UpdateProfileRequest { email })
+        .send_via(client)
+        .await
+        .map_err(ApiError::from)?;
+
+    parse_account_response(response).await
+}
+
+async fn parse_account_response<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T, ApiError> {
+    let status = response.status();
+    if status.is_success() {
+        response
+            .json::<T>()
             .await
-            .map_err(|e| format!("Failed to parse error: {}", e))?;
-        Err(error.error)
+            .map_err(ApiError::parse)
+    } else {
+        // A 401 comes from the auth middleware, with an empty body
+        Err(ApiError::from_response(response, "Request failed").await)
     }
 }
 
+/// Login or signup refused: the backend's message, also for a `401` (wrong
+/// credentials rather than an expired session)
+async fn rejection(response: reqwest::Response) -> ApiError {
+    let status = response.status();
+    match response.json::<ErrorResponse>().await {
+        Ok(error) => ApiError::rejected(status, error.error),
+        Err(e) => ApiError::parse(e),
+    }
+}
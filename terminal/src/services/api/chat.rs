@@ -0,0 +1,140 @@
+//! # Chat API Client
+//!
+//! HTTP client methods for conversation features outside the Braid message stream.
+
+use super::client::{ApiClient, ApiError, SendVia};
+use shared::dto::messaging::{Attachment, ConversationSummaryResponse};
+
+impl ApiClient {
+    
+    /// Ask the backend AI to summarize the latest `limit` messages of a conversation
+    /// (the backend default when `None`)
+    ///
+    /// Fails with the backend's explanation when summaries aren't possible,
+    /// e.g. for end-to-end encrypted conversations.
+    pub async fn summarize_conversation(
+        &self,
+        token: &str,
+        conversation_id: &str,
+        limit: Option<usize>,
+    ) -> Result<ConversationSummaryResponse, ApiError> {
+        let url = format!("{}/api/chat/{}/summary", self.base_url(), conversation_id);
+        
+        let mut request = self.http()
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token));
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        
+        let response = request
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+        
+        if response.status().is_success() {
+            response.json::<ConversationSummaryResponse>()
+                .await
+                .map_err(ApiError::parse)
+        } else {
+            Err(ApiError::from_response(response, "API error").await)
+        }
+    }
+    
+    /// Mark every message sent to the caller in a conversation as read
+    ///
+    /// The other participant sees a read receipt unless the conversation is
+    /// with the AI bot.
+    pub async fn mark_conversation_read(&self, token: &str, conversation_id: &str) -> Result<(), ApiError> {
+        let url = format!("{}/api/chat/conversations/{}/read", self.base_url(), conversation_id);
+        
+        let response = self.http()
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+        
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(ApiError::status(response.status(), "API error"))
+        }
+    }
+    
+    /// Stop the AI bot's reply in a conversation
+    ///
+    /// Returns `Ok(false)` if no reply was being generated, e.g. because it
+    /// finished before the request arrived.
+    pub async fn cancel_ai_reply(&self, token: &str, conversation_id: &str) -> Result<bool, ApiError> {
+        let url = format!("{}/api/chat/{}/cancel", self.base_url(), conversation_id);
+        
+        let response = self.http()
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+        
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status => Err(ApiError::status(status, "API error")),
+        }
+    }
+    
+    /// Upload a file to send in a conversation
+    ///
+    /// The returned attachment goes into the message's `attachments`. The
+    /// backend picks the content type from the file name and rejects files
+    /// over 5 MB or of types it doesn't accept.
+    pub async fn upload_attachment(
+        &self,
+        token: &str,
+        conversation_id: &str,
+        filename: String,
+        bytes: Vec<u8>,
+    ) -> Result<Attachment, ApiError> {
+        let url = format!("{}/api/chat/attachments", self.base_url());
+        let form = reqwest::multipart::Form::new()
+            .text("conversation_id", conversation_id.to_string())
+            .part("file", reqwest::multipart::Part::bytes(bytes).file_name(filename));
+        
+        let response = self.http()
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .multipart(form)
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+        
+        if response.status().is_success() {
+            response.json::<Attachment>()
+                .await
+                .map_err(ApiError::parse)
+        } else {
+            Err(ApiError::from_response(response, "API error").await)
+        }
+    }
+    
+    /// Download the contents of an attachment
+    pub async fn download_attachment(&self, token: &str, attachment_id: &str) -> Result<Vec<u8>, ApiError> {
+        let url = format!("{}/api/chat/attachments/{}", self.base_url(), attachment_id);
+        
+        let response = self.http()
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+        
+        if response.status().is_success() {
+            response.bytes()
+                .await
+                .map(|bytes| bytes.to_vec())
+                .map_err(ApiError::from)
+        } else {
+            Err(ApiError::from_response(response, "API error").await)
+        }
+    }
+}
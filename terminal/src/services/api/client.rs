@@ -1,61 +1,277 @@
 //! # API Client
 //!
 //! Main HTTP client for backend API communication.
+//!
+//! Every endpoint returns [`ApiError`], so callers can tell an unreachable
+//! backend from an expired session or a rejected request. The two failures
+//! the app reacts to globally, an authenticated request answered with `401`
+//! and a timeout, are also published on [`ApiClient::failures`].
 
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use shared::ErrorResponse;
+use std::sync::Arc;
 use crate::core::service::ApiService;
+use super::failover::{Failover, FailoverPolicy, HttpHealthProbe, DEFAULT_SERVER};
+
+/// Failures kept for the app until it picks them up; older ones are dropped
+const FAILURE_QUEUE: usize = 16;
+
+/// Error of a backend API call
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ApiError {
+    /// Backend unreachable: connection refused, DNS failure, reset connection
+    #[error("Network error: {0}")]
+    Network(#[source] Arc<reqwest::Error>),
+    /// No response within the client timeout
+    #[error("Request timed out")]
+    Timeout,
+    /// The session token was rejected (expired or revoked)
+    #[error("Session expired, please log in again")]
+    Unauthorized,
+    /// The backend answered with an error status
+    #[error("{message}")]
+    Api { status: u16, message: String },
+    /// The response body didn't match the expected shape
+    #[error("Failed to parse response: {0}")]
+    Parse(String),
+}
+
+impl ApiError {
+    /// Error for an unsuccessful `status`, e.g. "Failed to fetch prices: 500 Internal Server Error"
+    pub fn status(status: StatusCode, context: &str) -> Self {
+        if status == StatusCode::UNAUTHORIZED {
+            return ApiError::Unauthorized;
+        }
+        ApiError::Api { status: status.as_u16(), message: format!("{}: {}", context, status) }
+    }
+
+    /// Error for an unsuccessful response, with the backend's
+    /// [`ErrorResponse`] message when the body carries one
+    pub async fn from_response(response: Response, context: &str) -> Self {
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED {
+            return ApiError::Unauthorized;
+        }
+        match response.json::<ErrorResponse>().await {
+            Ok(body) => ApiError::rejected(status, body.error),
+            Err(_) => ApiError::status(status, context),
+        }
+    }
+
+    /// The backend refused the request with `message`; unlike
+    /// [`ApiError::status`], a `401` stays a message (wrong credentials at login)
+    pub fn rejected(status: StatusCode, message: String) -> Self {
+        ApiError::Api { status: status.as_u16(), message }
+    }
+
+    /// Response body that couldn't be decoded
+    pub fn parse(error: impl std::fmt::Display) -> Self {
+        ApiError::Parse(error.to_string())
+    }
+
+    /// HTTP status the backend answered with, if it answered
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            ApiError::Unauthorized => Some(401),
+            ApiError::Api { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
 
-/// Base URL for backend API server
-const API_BASE_URL: &str = "http://127.0.0.1:3001";
+    /// Worth trying again unchanged: the backend was unreachable, slow or failing
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ApiError::Network(_) | ApiError::Timeout => true,
+            ApiError::Api { status, .. } => *status >= 500,
+            ApiError::Unauthorized | ApiError::Parse(_) => false,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            ApiError::Timeout
+        } else if error.is_decode() {
+            ApiError::Parse(error.to_string())
+        } else {
+            ApiError::Network(Arc::new(error))
+        }
+    }
+}
 
 /// HTTP client for communicating with the backend API server.
 ///
 /// This client handles all REST API calls and maintains a connection pool
-/// for efficient HTTP/2 multiplexing.
+/// for efficient HTTP/2 multiplexing. Requests go to the active server of
+/// its [`Failover`] list.
+///
+/// The pool can be dropped with [`ApiClient::close_connections`] (idle
+/// teardown); the next request builds a fresh one.
 pub struct ApiClient {
-    pub(crate) client: Client,
+    client: parking_lot::Mutex<Option<Client>>,
+    failover: Arc<Failover>,
+    failure_tx: async_channel::Sender<ApiError>,
+    failure_rx: async_channel::Receiver<ApiError>,
 }
 
 impl ApiClient {
-    /// Create a new API client with default configuration.
+    /// Create a new API client for the configured backend (or [`DEFAULT_SERVER`]),
+    /// see [`TerminalConfig`](crate::core::config::TerminalConfig).
     ///
     /// The client is configured with a 10 second timeout to prevent freezing.
     pub fn new() -> Self {
-        // Create client with 10 second timeout to prevent freezing
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .unwrap_or_else(|_| Client::new());
+        let server = crate::core::config::TerminalConfig::load()
+            .api_url
+            .unwrap_or_else(|| DEFAULT_SERVER.to_string());
+        Self::with_servers(vec![server])
+    }
+
+    /// Create a client that fails over between `servers`, primary first.
+    pub fn with_servers(servers: Vec<String>) -> Self {
+        let client = parking_lot::Mutex::new(Some(build_http_client()));
+        let failover = Failover::new(servers, FailoverPolicy::default(), Arc::new(HttpHealthProbe::new()));
+
+        let (failure_tx, failure_rx) = async_channel::bounded(FAILURE_QUEUE);
+
+        Self { client, failover: Arc::new(failover), failure_tx, failure_rx }
+    }
+
+    /// HTTP client for a request, reconnecting after [`ApiClient::close_connections`]
+    pub(crate) fn http(&self) -> Client {
+        self.client.lock().get_or_insert_with(build_http_client).clone()
+    }
+
+    /// Drop the connection pool
+    ///
+    /// Requests in flight keep their connection until they finish; nothing is
+    /// reopened until the next request.
+    pub fn close_connections(&self) {
+        if self.client.lock().take().is_some() {
+            tracing::info!("API connection pool closed");
+        }
+    }
+
+    /// Build a new connection pool now rather than on the next request
+    pub fn open_connections(&self) {
+        self.http();
+    }
+
+    /// Whether a connection pool is currently open
+    pub fn has_connections(&self) -> bool {
+        self.client.lock().is_some()
+    }
+
+    /// Get the base URL of the active server.
+    pub(crate) fn base_url(&self) -> String {
+        self.failover.active_url()
+    }
+
+    /// Server list and failover state
+    pub fn failover(&self) -> &Arc<Failover> {
+        &self.failover
+    }
+
+    /// Session rejections and timeouts as they happen, see [`ApiClient::send`]
+    pub fn failures(&self) -> async_channel::Receiver<ApiError> {
+        self.failure_rx.clone()
+    }
+
+    /// Send a request, counting transport failures towards a failover.
+    ///
+    /// Connection errors, timeouts and gateway errors (502-504) count as
+    /// failures; any other response means the server is reachable. A `401`
+    /// to a request carrying a token and a timeout are published on
+    /// [`ApiClient::failures`].
+    pub(crate) async fn send(&self, request: RequestBuilder) -> reqwest::Result<Response> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        // Login answers 401 to wrong credentials; only a rejected token means the session is gone
+        let authenticated = request.headers().contains_key(reqwest::header::AUTHORIZATION);
+        let result = client.execute(request).await;
+
+        let failure = match &result {
+            Ok(response) if authenticated && response.status() == StatusCode::UNAUTHORIZED => Some(ApiError::Unauthorized),
+            Err(e) if e.is_timeout() => Some(ApiError::Timeout),
+            _ => None,
+        };
+        if let Some(failure) = failure {
+            // Nobody listening, or the app is behind: the caller still gets the error
+            let _ = self.failure_tx.try_send(failure);
+        }
 
-        Self { client }
+        let reachable = match &result {
+            Ok(response) => !matches!(response.status().as_u16(), 502..=504),
+            Err(e) => !(e.is_connect() || e.is_timeout()),
+        };
+
+        if reachable {
+            self.failover.record_success();
+        } else if self.failover.record_failure() {
+            let failover = Arc::clone(&self.failover);
+            tokio::spawn(async move {
+                failover.fail_over().await;
+            });
+        }
+        result
     }
+}
 
-    /// Get the base URL for API requests.
-    pub(crate) fn base_url() -> &'static str {
-        API_BASE_URL
+/// Client with 10 second timeout to prevent freezing
+fn build_http_client() -> Client {
+    Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+/// [`RequestBuilder::send`] through an [`ApiClient`], see [`ApiClient::send`]
+pub(crate) trait SendVia {
+    fn send_via(self, api: &ApiClient) -> impl std::future::Future<Output = reqwest::Result<Response>> + Send;
+}
+
+impl SendVia for RequestBuilder {
+    fn send_via(self, api: &ApiClient) -> impl std::future::Future<Output = reqwest::Result<Response>> + Send {
+        api.send(self)
     }
 }
 
 // Implement ApiService trait for ApiClient
 #[async_trait::async_trait]
 impl ApiService for ApiClient {
-    async fn login(&self, email_or_username: String, password: String) -> Result<shared::AuthResponse, String> {
+    async fn login(&self, email_or_username: String, password: String) -> Result<shared::AuthResponse, ApiError> {
         crate::services::api::auth::login(self, email_or_username, password).await
     }
     
-    async fn signup(&self, username: String, email: String, password: String) -> Result<shared::AuthResponse, String> {
+    async fn signup(&self, username: String, email: String, password: String) -> Result<shared::AuthResponse, ApiError> {
         crate::services::api::auth::signup(self, username, email, password).await
     }
     
-    async fn get_prices(&self, symbols: &[&str]) -> Result<crate::services::api::market::PriceResponse, String> {
+    async fn logout(&self, jwt_token: &str) -> Result<(), ApiError> {
+        crate::services::api::auth::logout(self, jwt_token).await
+    }
+    
+    async fn refresh_session(&self, jwt_token: &str) -> Result<shared::AuthResponse, ApiError> {
+        crate::services::api::auth::refresh_session(self, jwt_token).await
+    }
+    
+    async fn change_password(&self, jwt_token: &str, current_password: String, new_password: String) -> Result<shared::AuthResponse, ApiError> {
+        crate::services::api::auth::change_password(self, jwt_token, current_password, new_password).await
+    }
+    
+    async fn update_profile(&self, jwt_token: &str, email: String) -> Result<shared::UserInfo, ApiError> {
+        crate::services::api::auth::update_profile(self, jwt_token, email).await
+    }
+    
+    async fn get_prices(&self, symbols: &[&str]) -> Result<crate::services::api::market::PriceResponse, ApiError> {
         crate::services::api::market::get_prices(self, symbols).await
     }
     
-    async fn get_wallet_balance(&self, address: &str) -> Result<crate::services::api::wallet::WalletBalance, String> {
+    async fn get_wallet_balance(&self, address: &str) -> Result<crate::services::api::wallet::WalletBalance, ApiError> {
         crate::services::api::wallet::get_wallet_balance(self, address).await
     }
     
-    async fn get_transaction_history(&self, address: &str, limit: usize) -> Result<crate::services::api::wallet::TransactionHistory, String> {
+    async fn get_transaction_history(&self, address: &str, limit: usize) -> Result<crate::services::api::wallet::TransactionHistory, ApiError> {
         crate::services::api::wallet::get_transaction_history(self, address, limit).await
     }
     
@@ -65,7 +281,7 @@ impl ApiService for ApiClient {
         output_mint: &str,
         amount: u64,
         slippage_bps: u16,
-    ) -> Result<crate::services::api::swap::SwapQuoteResponse, String> {
+    ) -> Result<crate::services::api::swap::SwapQuoteResponse, ApiError> {
         crate::services::api::swap::get_swap_quote(self, input_mint, output_mint, amount, slippage_bps).await
     }
     
@@ -77,13 +293,22 @@ impl ApiService for ApiClient {
         slippage_bps: u16,
         user_pubkey: &str,
         jwt_token: &str,
-    ) -> Result<crate::services::api::swap::SwapExecuteResponse, String> {
+    ) -> Result<crate::services::api::swap::SwapExecuteResponse, ApiError> {
         crate::services::api::swap::execute_swap(self, input_mint, output_mint, amount, slippage_bps, user_pubkey, jwt_token).await
     }
     
+    async fn simulate_swap(
+        &self,
+        request: &shared::dto::simulation::SimulateSwapRequest,
+        jwt_token: &str,
+    ) -> Result<shared::dto::simulation::SimulateSwapResponse, ApiError> {
+        crate::services::api::swap::simulate_swap(self, request, jwt_token).await
+    }
+    
     async fn submit_transaction(
         &self,
         signed_transaction: String,
+        version: shared::dto::transactions::TransactionVersion,
         input_mint: String,
         output_mint: String,
         input_amount: i64,
@@ -91,24 +316,155 @@ impl ApiService for ApiClient {
         price_impact: Option<f64>,
         slippage_bps: Option<i32>,
         jwt_token: &str,
-    ) -> Result<crate::services::api::swap::TransactionSubmitResponse, String> {
-        crate::services::api::swap::submit_transaction(self, signed_transaction, input_mint, output_mint, input_amount, output_amount, price_impact, slippage_bps, jwt_token).await
+    ) -> Result<crate::services::api::swap::TransactionSubmitResponse, ApiError> {
+        crate::services::api::swap::submit_transaction(self, signed_transaction, version, input_mint, output_mint, input_amount, output_amount, price_impact, slippage_bps, jwt_token).await
+    }
+    
+    async fn update_transaction_status(
+        &self,
+        signature: &str,
+        update: &shared::dto::transactions::TransactionStatusUpdate,
+        jwt_token: &str,
+    ) -> Result<(), ApiError> {
+        crate::services::api::swap::update_transaction_status(self, signature, update, jwt_token).await
     }
     
-    async fn get_token_balances(&self, address: &str) -> Result<Vec<crate::services::api::wallet::TokenBalance>, String> {
+    async fn record_transaction_latency(
+        &self,
+        signature: &str,
+        latency: &shared::dto::latency::SwapLatency,
+        jwt_token: &str,
+    ) -> Result<(), ApiError> {
+        crate::services::api::swap::record_transaction_latency(self, signature, latency, jwt_token).await
+    }
+    
+    async fn get_token_balances(&self, address: &str) -> Result<Vec<crate::services::api::wallet::TokenBalance>, ApiError> {
         crate::services::api::wallet::get_token_balances(self, address).await
     }
     
-    async fn get_token_list(&self) -> Result<Vec<crate::services::api::market::TokenListItem>, String> {
+    async fn get_token_list(&self) -> Result<Vec<crate::services::api::market::TokenListItem>, ApiError> {
         crate::services::api::market::get_token_list(self).await
     }
     
-    async fn get_swap_history(&self, jwt_token: &str, limit: usize) -> Result<Vec<crate::services::api::swap::SwapHistoryItem>, String> {
-        crate::services::api::swap::get_swap_history(self, jwt_token, limit).await
+    async fn get_swap_history(&self, jwt_token: &str, query: &crate::services::api::swap::SwapHistoryQuery) -> Result<crate::services::api::swap::SwapHistoryResponse, ApiError> {
+        crate::services::api::swap::get_swap_history(self, jwt_token, query).await
     }
     
-    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: usize) -> Result<Vec<shared::dto::market::OHLC>, String> {
+    async fn stream_history(
+        &self,
+        jwt_token: &str,
+        query: &crate::services::api::swap::SwapHistoryQuery,
+        on_batch: &mut (dyn for<'b> FnMut(&'b [crate::services::api::swap::SwapHistoryItem]) -> Result<(), String> + Send),
+    ) -> Result<u64, String> {
+        crate::services::api::history_stream::stream_history(self, jwt_token, query, on_batch).await
+    }
+    
+    async fn import_trades(&self, jwt_token: &str, request: &shared::dto::trades::TradeImportRequest) -> Result<shared::dto::trades::TradeImportResponse, ApiError> {
+        crate::services::api::swap::import_trades(self, jwt_token, request).await
+    }
+    
+    async fn get_trade_stats(&self, jwt_token: &str) -> Result<shared::dto::trades::TradeStatsResponse, ApiError> {
+        crate::services::api::swap::get_trade_stats(self, jwt_token).await
+    }
+    
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: usize) -> Result<Vec<shared::dto::market::OHLC>, ApiError> {
         crate::services::api::market::get_candles(self, symbol, timeframe, limit).await
     }
+    
+    async fn get_candles_batch(&self, symbols: &[String], timeframe: &str) -> Result<shared::dto::market::CandleBatchResponse, ApiError> {
+        crate::services::api::market::get_candles_batch(self, symbols, timeframe).await
+    }
+    
+    async fn get_streamed_symbols(&self) -> Result<shared::dto::market::StreamedSymbolsResponse, ApiError> {
+        crate::services::api::market::get_streamed_symbols(self).await
+    }
+    
+    async fn get_token_unlocks(&self, days: i64) -> Result<shared::dto::unlocks::TokenUnlocksResponse, ApiError> {
+        crate::services::api::market::get_token_unlocks(self, days).await
+    }
+    
+    async fn get_depth(&self, input: &str, output: &str) -> Result<shared::dto::market::DepthResponse, ApiError> {
+        crate::services::api::market::get_depth(self, input, output).await
+    }
+    
+    async fn get_market_analytics(&self, symbols: &[String], window: usize) -> Result<shared::dto::market::MarketAnalyticsResponse, ApiError> {
+        crate::services::api::market::get_market_analytics(self, symbols, window).await
+    }
+    
+    async fn get_health(&self) -> Result<shared::dto::system::HealthResponse, ApiError> {
+        crate::services::api::system::get_health(self).await
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Server answering every request `401` with an [`ErrorResponse`] body
+    async fn rejecting_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut head = vec![0u8; 4096];
+                let _ = socket.read(&mut head).await;
+                let body = r#"{"error":"Invalid username or password"}"#;
+                let response = format!(
+                    "HTTP/1.1 401 Unauthorized\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_status_errors() {
+        let error = ApiError::status(StatusCode::BAD_GATEWAY, "Failed to fetch prices");
+        assert_eq!(error.to_string(), "Failed to fetch prices: 502 Bad Gateway");
+        assert_eq!(error.status_code(), Some(502));
+        assert!(error.is_transient());
+
+        let error = ApiError::status(StatusCode::UNPROCESSABLE_ENTITY, "Swap execution failed");
+        assert_eq!(error.status_code(), Some(422));
+        assert!(!error.is_transient());
+
+        // An expired token is its own variant, whatever the context
+        assert!(matches!(ApiError::status(StatusCode::UNAUTHORIZED, "Failed to fetch swap history"), ApiError::Unauthorized));
+        assert!(matches!(ApiError::rejected(StatusCode::UNAUTHORIZED, "Wrong password".to_string()), ApiError::Api { status: 401, .. }));
+
+        assert!(ApiError::Timeout.is_transient());
+        assert!(!ApiError::Unauthorized.is_transient());
+        assert_eq!(ApiError::parse("expected value").to_string(), "Failed to parse response: expected value");
+    }
+
+    #[test]
+    fn test_app_error_conversion() {
+        use crate::core::error::AppError;
+
+        let rejected = ApiError::rejected(StatusCode::BAD_REQUEST, "Amount must be positive".to_string());
+        assert!(matches!(AppError::from(rejected), AppError::Validation(m) if m == "Amount must be positive"));
+        assert!(matches!(AppError::from(ApiError::Timeout), AppError::Api(m) if m == "Request timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_only_rejected_tokens_are_published() {
+        let client = ApiClient::with_servers(vec![rejecting_server().await]);
+        let failures = client.failures();
+
+        // Wrong credentials: the backend's message, and the session is none of the app's business
+        let error = client.login("alice".to_string(), "hunter2".to_string()).await.unwrap_err();
+        assert_eq!(error.to_string(), "Invalid username or password");
+        assert!(failures.try_recv().is_err());
+
+        let error = client.refresh_session("expired-token").await.unwrap_err();
+        assert!(matches!(error, ApiError::Unauthorized));
+        assert!(matches!(failures.try_recv(), Ok(ApiError::Unauthorized)));
+        assert!(failures.try_recv().is_err());
+    }
+}
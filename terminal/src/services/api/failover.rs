@@ -0,0 +1,573 @@
+//! # Backend Failover
+//!
+//! Keeps [`ApiClient`](super::ApiClient) pointed at a healthy backend out of
+//! an ordered server list (primary first, then standbys).
+//!
+//! ## Behavior
+//!
+//! - Every request reports whether it reached the server. After
+//!   [`FailoverPolicy::failure_threshold`] consecutive transport failures the
+//!   active server is health-checked; if it is down, the standbys are probed in
+//!   list order and the first healthy one becomes active.
+//! - Health checks are spaced by [`FailoverPolicy::probe_cooldown`] so a dead
+//!   network doesn't turn every request into a round of probes.
+//! - While on a standby, the health poll also probes the primary; once it has
+//!   been healthy for [`FailoverPolicy::primary_stable_for`] the client moves
+//!   back to it.
+//!
+//! Session tokens stay valid across the switch because every server in the
+//! list shares the same database. Each switch is published as a
+//! [`ServerSwitch`] so the app can notify the user and move the price stream.
+//!
+//! The decisions live in [`FailoverTracker`], which takes the current time as
+//! an argument; [`Failover`] adds the probing through a [`HealthProbe`].
+
+use async_channel::{Receiver, Sender};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Backend used when no server list is configured
+pub const DEFAULT_SERVER: &str = "http://127.0.0.1:3001";
+
+/// Timeout for a single health probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Thresholds for failing over and returning to the primary
+#[derive(Debug, Clone, Copy)]
+pub struct FailoverPolicy {
+    /// Consecutive transport failures before the active server is checked
+    pub failure_threshold: u32,
+    /// Minimum time between health checks triggered by failures
+    pub probe_cooldown: Duration,
+    /// How long the primary must stay healthy before switching back to it
+    pub primary_stable_for: Duration,
+}
+
+impl Default for FailoverPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            probe_cooldown: Duration::from_secs(15),
+            primary_stable_for: Duration::from_secs(120),
+        }
+    }
+}
+
+/// Change of the active server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerSwitch {
+    pub from: String,
+    pub to: String,
+    /// True when `to` is the primary (first) server
+    pub to_primary: bool,
+}
+
+/// Checks whether a backend is up
+#[async_trait::async_trait]
+pub trait HealthProbe: Send + Sync {
+    async fn is_healthy(&self, base_url: &str) -> bool;
+}
+
+/// Probes `GET /api/health`; any success status counts as healthy
+///
+/// A degraded report still means the server can take requests, so the
+/// report body is not inspected.
+pub struct HttpHealthProbe {
+    client: reqwest::Client,
+}
+
+impl HttpHealthProbe {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(PROBE_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self { client }
+    }
+}
+
+impl Default for HttpHealthProbe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthProbe for HttpHealthProbe {
+    async fn is_healthy(&self, base_url: &str) -> bool {
+        match self.client.get(format!("{}/api/health", base_url)).send().await {
+            Ok(response) => response.status().is_success(),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Failover decisions: failure counting, probe cool-down, fallback order
+/// and the sticky return to the primary
+#[derive(Debug, Clone)]
+pub struct FailoverTracker {
+    servers: Vec<String>,
+    active: usize,
+    consecutive_failures: u32,
+    last_probe: Option<Instant>,
+    primary_healthy_since: Option<Instant>,
+    policy: FailoverPolicy,
+}
+
+impl FailoverTracker {
+    /// Tracker on the first server of `servers` ([`DEFAULT_SERVER`] if empty)
+    pub fn new(servers: Vec<String>, policy: FailoverPolicy) -> Self {
+        Self {
+            servers: normalize_servers(servers),
+            active: 0,
+            consecutive_failures: 0,
+            last_probe: None,
+            primary_healthy_since: None,
+            policy,
+        }
+    }
+
+    pub fn servers(&self) -> &[String] {
+        &self.servers
+    }
+
+    pub fn active_url(&self) -> &str {
+        &self.servers[self.active]
+    }
+
+    pub fn on_primary(&self) -> bool {
+        self.active == 0
+    }
+
+    /// A request reached the active server
+    pub fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+    }
+
+    /// A request failed to reach the active server
+    ///
+    /// # Returns
+    ///
+    /// True when the active server should be health-checked now.
+    pub fn record_failure(&mut self, now: Instant) -> bool {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.probe_due(now)
+    }
+
+    /// Whether enough failures piled up outside the cool-down to check the server
+    pub fn probe_due(&self, now: Instant) -> bool {
+        self.consecutive_failures >= self.policy.failure_threshold
+            && self
+                .last_probe
+                .is_none_or(|last| now.duration_since(last) >= self.policy.probe_cooldown)
+    }
+
+    /// Start a health check at `now` (starts the cool-down)
+    pub fn begin_probe(&mut self, now: Instant) {
+        self.last_probe = Some(now);
+    }
+
+    /// The active server answered its health check; the failures were transient
+    pub fn active_recovered(&mut self) {
+        self.consecutive_failures = 0;
+    }
+
+    /// Servers to try when the active one is down, in list order
+    pub fn fallback_order(&self) -> Vec<usize> {
+        (0..self.servers.len()).filter(|&i| i != self.active).collect()
+    }
+
+    /// The server after the active one in list order, wrapping to the primary
+    pub fn next_server(&self) -> Option<usize> {
+        (self.servers.len() > 1).then(|| (self.active + 1) % self.servers.len())
+    }
+
+    /// Make `index` the active server
+    pub fn switch_to(&mut self, index: usize) -> Option<ServerSwitch> {
+        if index == self.active || index >= self.servers.len() {
+            return None;
+        }
+        let from = self.active_url().to_string();
+        self.active = index;
+        self.consecutive_failures = 0;
+        self.primary_healthy_since = None;
+        Some(ServerSwitch {
+            from,
+            to: self.active_url().to_string(),
+            to_primary: index == 0,
+        })
+    }
+
+    /// Record a probe of the primary while on a standby
+    ///
+    /// # Returns
+    ///
+    /// True once the primary has been healthy for
+    /// [`FailoverPolicy::primary_stable_for`] without a failed probe.
+    pub fn record_primary_probe(&mut self, healthy: bool, now: Instant) -> bool {
+        if self.on_primary() {
+            return false;
+        }
+        if !healthy {
+            self.primary_healthy_since = None;
+            return false;
+        }
+        let since = *self.primary_healthy_since.get_or_insert(now);
+        now.duration_since(since) >= self.policy.primary_stable_for
+    }
+
+    /// Replace the server list, staying on the active server if it is still listed
+    pub fn set_servers(&mut self, servers: Vec<String>) -> Option<ServerSwitch> {
+        let from = self.active_url().to_string();
+        self.servers = normalize_servers(servers);
+        self.consecutive_failures = 0;
+        self.primary_healthy_since = None;
+        self.active = self.servers.iter().position(|s| *s == from).unwrap_or(0);
+        let to = self.active_url().to_string();
+        if to == from {
+            return None;
+        }
+        Some(ServerSwitch {
+            from,
+            to,
+            to_primary: self.active == 0,
+        })
+    }
+}
+
+/// Trim, drop trailing slashes and duplicates; fall back to [`DEFAULT_SERVER`]
+fn normalize_servers(servers: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(servers.len());
+    for server in servers {
+        let server = server.trim().trim_end_matches('/').to_string();
+        if !server.is_empty() && !normalized.contains(&server) {
+            normalized.push(server);
+        }
+    }
+    if normalized.is_empty() {
+        normalized.push(DEFAULT_SERVER.to_string());
+    }
+    normalized
+}
+
+/// Shared failover state of an [`ApiClient`](super::ApiClient)
+pub struct Failover {
+    tracker: Mutex<FailoverTracker>,
+    probe: Arc<dyn HealthProbe>,
+    /// Set while a failure-triggered check runs, so requests don't stack them
+    probing: AtomicBool,
+    switch_tx: Sender<ServerSwitch>,
+    switch_rx: Receiver<ServerSwitch>,
+}
+
+impl Failover {
+    pub fn new(servers: Vec<String>, policy: FailoverPolicy, probe: Arc<dyn HealthProbe>) -> Self {
+        let (switch_tx, switch_rx) = async_channel::unbounded();
+        Self {
+            tracker: Mutex::new(FailoverTracker::new(servers, policy)),
+            probe,
+            probing: AtomicBool::new(false),
+            switch_tx,
+            switch_rx,
+        }
+    }
+
+    /// Base URL of the active server
+    pub fn active_url(&self) -> String {
+        self.tracker.lock().active_url().to_string()
+    }
+
+    /// Configured servers, primary first
+    pub fn servers(&self) -> Vec<String> {
+        self.tracker.lock().servers().to_vec()
+    }
+
+    /// Switches as they happen
+    pub fn switches(&self) -> Receiver<ServerSwitch> {
+        self.switch_rx.clone()
+    }
+
+    pub fn record_success(&self) {
+        self.tracker.lock().record_success();
+    }
+
+    /// Count a transport failure; true when a health check is due
+    pub fn record_failure(&self) -> bool {
+        self.tracker.lock().record_failure(Instant::now())
+    }
+
+    /// Replace the server list (from Settings)
+    pub fn set_servers(&self, servers: Vec<String>) {
+        let switch = self.tracker.lock().set_servers(servers);
+        if let Some(switch) = switch {
+            self.publish(switch);
+        }
+    }
+
+    /// Move to the next server without a health check, when the user asks for it
+    ///
+    /// The active server can be up and still failing requests (e.g. its RPC
+    /// node is rate limited), which [`fail_over`](Self::fail_over) wouldn't
+    /// act on.
+    pub fn rotate(&self) -> Option<ServerSwitch> {
+        let switch = {
+            let mut tracker = self.tracker.lock();
+            tracker.next_server().and_then(|index| tracker.switch_to(index))
+        };
+        if let Some(switch) = &switch {
+            self.publish(switch.clone());
+        }
+        switch
+    }
+
+    /// Health-check the active server and move to the first healthy standby if it is down
+    pub async fn fail_over(&self) -> Option<ServerSwitch> {
+        if self.probing.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        let switch = self.run_fail_over().await;
+        self.probing.store(false, Ordering::Release);
+        switch
+    }
+
+    async fn run_fail_over(&self) -> Option<ServerSwitch> {
+        let (active, candidates) = {
+            let mut tracker = self.tracker.lock();
+            let now = Instant::now();
+            if !tracker.probe_due(now) {
+                return None;
+            }
+            tracker.begin_probe(now);
+            let candidates: Vec<(usize, String)> = tracker
+                .fallback_order()
+                .into_iter()
+                .map(|i| (i, tracker.servers()[i].clone()))
+                .collect();
+            (tracker.active_url().to_string(), candidates)
+        };
+
+        if self.probe.is_healthy(&active).await {
+            self.tracker.lock().active_recovered();
+            return None;
+        }
+        warn!(server = %active, "Active backend failed its health check");
+
+        for (index, url) in candidates {
+            if self.probe.is_healthy(&url).await {
+                let switch = {
+                    let mut tracker = self.tracker.lock();
+                    // Settings may have replaced the list while probing
+                    if tracker.active_url() != active || tracker.servers().get(index) != Some(&url) {
+                        return None;
+                    }
+                    tracker.switch_to(index)
+                };
+                if let Some(switch) = &switch {
+                    self.publish(switch.clone());
+                }
+                return switch;
+            }
+        }
+        warn!("No healthy backend found - staying on {}", active);
+        None
+    }
+
+    /// While on a standby, probe the primary and return to it once it has been stable
+    pub async fn check_primary(&self) -> Option<ServerSwitch> {
+        let primary = {
+            let tracker = self.tracker.lock();
+            if tracker.on_primary() {
+                return None;
+            }
+            tracker.servers()[0].clone()
+        };
+
+        let healthy = self.probe.is_healthy(&primary).await;
+        let switch = {
+            let mut tracker = self.tracker.lock();
+            if tracker.record_primary_probe(healthy, Instant::now()) {
+                tracker.switch_to(0)
+            } else {
+                None
+            }
+        };
+        if let Some(switch) = &switch {
+            self.publish(switch.clone());
+        }
+        switch
+    }
+
+    fn publish(&self, switch: ServerSwitch) {
+        info!(from = %switch.from, to = %switch.to, "Switched backend server");
+        let _ = self.switch_tx.try_send(switch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const PRIMARY: &str = "http://primary:3001";
+    const BACKUP: &str = "http://backup:3001";
+    const SPARE: &str = "http://spare:3001";
+
+    /// Transport stand-in: fixed health per URL, counting probes
+    #[derive(Default)]
+    struct MockProbe {
+        healthy: Mutex<HashMap<String, bool>>,
+        probes: Mutex<Vec<String>>,
+    }
+
+    impl MockProbe {
+        fn set(&self, url: &str, healthy: bool) {
+            self.healthy.lock().insert(url.to_string(), healthy);
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl HealthProbe for MockProbe {
+        async fn is_healthy(&self, base_url: &str) -> bool {
+            self.probes.lock().push(base_url.to_string());
+            self.healthy.lock().get(base_url).copied().unwrap_or(false)
+        }
+    }
+
+    fn servers() -> Vec<String> {
+        vec![PRIMARY.to_string(), BACKUP.to_string(), SPARE.to_string()]
+    }
+
+    fn policy() -> FailoverPolicy {
+        FailoverPolicy {
+            failure_threshold: 3,
+            probe_cooldown: Duration::from_secs(10),
+            primary_stable_for: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn test_failures_trigger_probe_after_threshold_and_cooldown() {
+        let mut tracker = FailoverTracker::new(servers(), policy());
+        let t0 = Instant::now();
+        assert!(!tracker.record_failure(t0));
+        assert!(!tracker.record_failure(t0));
+        assert!(tracker.record_failure(t0));
+
+        tracker.begin_probe(t0);
+        assert!(!tracker.record_failure(t0 + Duration::from_secs(5)));
+        assert!(tracker.record_failure(t0 + Duration::from_secs(10)));
+
+        // A success in between starts the count over
+        tracker.record_success();
+        assert!(!tracker.record_failure(t0 + Duration::from_secs(20)));
+    }
+
+    #[test]
+    fn test_fallback_order_skips_active() {
+        let mut tracker = FailoverTracker::new(servers(), policy());
+        assert_eq!(tracker.fallback_order(), vec![1, 2]);
+        let switch = tracker.switch_to(2).unwrap();
+        assert_eq!(switch.from, PRIMARY);
+        assert_eq!(switch.to, SPARE);
+        assert!(!switch.to_primary);
+        assert_eq!(tracker.fallback_order(), vec![0, 1]);
+        assert_eq!(tracker.switch_to(2), None);
+    }
+
+    #[test]
+    fn test_next_server_wraps() {
+        let mut tracker = FailoverTracker::new(servers(), policy());
+        assert_eq!(tracker.next_server(), Some(1));
+        tracker.switch_to(2);
+        assert_eq!(tracker.next_server(), Some(0));
+
+        let single = FailoverTracker::new(vec![PRIMARY.to_string()], policy());
+        assert_eq!(single.next_server(), None);
+    }
+
+    #[test]
+    fn test_primary_must_stay_healthy_before_returning() {
+        let mut tracker = FailoverTracker::new(servers(), policy());
+        let t0 = Instant::now();
+        assert!(!tracker.record_primary_probe(true, t0), "already on the primary");
+
+        tracker.switch_to(1);
+        assert!(!tracker.record_primary_probe(true, t0));
+        assert!(!tracker.record_primary_probe(true, t0 + Duration::from_secs(30)));
+        // A failed probe restarts the clock
+        assert!(!tracker.record_primary_probe(false, t0 + Duration::from_secs(45)));
+        assert!(!tracker.record_primary_probe(true, t0 + Duration::from_secs(60)));
+        assert!(tracker.record_primary_probe(true, t0 + Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn test_set_servers_keeps_active_when_listed() {
+        let mut tracker = FailoverTracker::new(servers(), policy());
+        tracker.switch_to(1);
+        assert_eq!(tracker.set_servers(vec![BACKUP.to_string(), format!("{}/", PRIMARY)]), None);
+        assert_eq!(tracker.active_url(), BACKUP);
+        assert!(tracker.on_primary());
+
+        let switch = tracker.set_servers(vec![SPARE.to_string()]).unwrap();
+        assert_eq!(switch.to, SPARE);
+        assert_eq!(FailoverTracker::new(vec![" ".to_string()], policy()).active_url(), DEFAULT_SERVER);
+    }
+
+    #[tokio::test]
+    async fn test_fail_over_to_first_healthy_standby() {
+        let probe = Arc::new(MockProbe::default());
+        probe.set(PRIMARY, false);
+        probe.set(BACKUP, false);
+        probe.set(SPARE, true);
+        let failover = Failover::new(servers(), policy(), probe.clone());
+        let switches = failover.switches();
+
+        for _ in 0..3 {
+            failover.record_failure();
+        }
+        let switch = failover.fail_over().await.unwrap();
+        assert_eq!(switch.to, SPARE);
+        assert_eq!(failover.active_url(), SPARE);
+        assert_eq!(switches.try_recv().unwrap(), switch);
+        assert_eq!(*probe.probes.lock(), vec![PRIMARY, BACKUP, SPARE]);
+    }
+
+    #[tokio::test]
+    async fn test_healthy_active_server_is_kept() {
+        let probe = Arc::new(MockProbe::default());
+        probe.set(PRIMARY, true);
+        probe.set(BACKUP, true);
+        let failover = Failover::new(servers(), policy(), probe.clone());
+
+        // Below the threshold nothing is probed
+        failover.record_failure();
+        assert_eq!(failover.fail_over().await, None);
+        assert!(probe.probes.lock().is_empty());
+
+        failover.record_failure();
+        failover.record_failure();
+        assert_eq!(failover.fail_over().await, None);
+        assert_eq!(failover.active_url(), PRIMARY);
+        assert_eq!(*probe.probes.lock(), vec![PRIMARY]);
+    }
+
+    #[tokio::test]
+    async fn test_check_primary_waits_for_stability() {
+        let probe = Arc::new(MockProbe::default());
+        probe.set(PRIMARY, false);
+        probe.set(BACKUP, true);
+        let failover = Failover::new(servers(), policy(), probe.clone());
+        for _ in 0..3 {
+            failover.record_failure();
+        }
+        failover.fail_over().await.unwrap();
+
+        probe.set(PRIMARY, true);
+        // First healthy probe only starts the stability window
+        assert_eq!(failover.check_primary().await, None);
+        assert_eq!(failover.active_url(), BACKUP);
+    }
+}
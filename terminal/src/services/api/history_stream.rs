@@ -0,0 +1,361 @@
+//! # History Streaming
+//!
+//! Resumable download of the whole swap history from
+//! `GET /api/swap/history/stream`, an NDJSON stream described in
+//! [`shared::dto::history`].
+//!
+//! Records are handed over in batches as each cursor arrives, so neither the
+//! response nor the history is ever held in memory as a whole. When the
+//! connection drops, the request is repeated from the last cursor and the
+//! records received after it are discarded (the server sends them again), so
+//! the caller sees every record exactly once.
+
+use super::client::{ApiClient, SendVia};
+use super::swap::{SwapHistoryItem, SwapHistoryQuery};
+use serde::Serialize;
+use shared::dto::history::HistoryStreamLine;
+use shared::ErrorResponse;
+use std::time::Duration;
+
+/// Reconnects in a row, without a cursor in between, before giving up
+pub const MAX_RESUME_ATTEMPTS: u32 = 5;
+
+/// Wait before the first reconnect; doubles with each further one
+const RESUME_DELAY: Duration = Duration::from_millis(250);
+
+/// Streams take longer than the client's default request timeout; a stream
+/// cut off by this one is resumed like any other
+const STREAM_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Splits a byte stream into lines
+///
+/// Chunks end wherever the network cut them, often inside a record; the
+/// unfinished line is kept until the rest of it arrives.
+#[derive(Debug, Default)]
+pub struct LineDecoder {
+    partial: Vec<u8>,
+}
+
+impl LineDecoder {
+    /// Add a chunk, returning the lines it completed (without `\n`)
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut rest = chunk;
+        while let Some(end) = rest.iter().position(|&b| b == b'\n') {
+            self.partial.extend_from_slice(&rest[..end]);
+            lines.push(String::from_utf8_lossy(&self.partial).into_owned());
+            self.partial.clear();
+            rest = &rest[end + 1..];
+        }
+        self.partial.extend_from_slice(rest);
+        lines
+    }
+
+    /// Bytes of an unfinished line
+    pub fn pending(&self) -> usize {
+        self.partial.len()
+    }
+}
+
+/// Query string of a stream request: the history filters and the cursor
+#[derive(Serialize)]
+struct StreamParams<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from_ts: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to_ts: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_token: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_token: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<&'a str>,
+}
+
+/// Why a stream request stopped early
+enum Interruption {
+    /// Connection trouble; resume from the last cursor
+    Dropped(String),
+    /// Resuming won't help
+    Failed(String),
+}
+
+/// Stream every swap matching `query`'s filters (its paging is ignored),
+/// newest first, handing them to `on_batch` a cursor's worth at a time.
+///
+/// Returns the number of swaps delivered. An error from `on_batch` stops the
+/// stream and is returned as is.
+pub async fn stream_history(
+    client: &ApiClient,
+    jwt_token: &str,
+    query: &SwapHistoryQuery,
+    on_batch: &mut (dyn FnMut(&[SwapHistoryItem]) -> Result<(), String> + Send),
+) -> Result<u64, String> {
+    let mut cursor = None;
+    let mut delivered = 0;
+    let mut attempts = 0;
+    loop {
+        let resumed_from = cursor.clone();
+        match stream_once(client, jwt_token, query, &mut cursor, &mut delivered, on_batch).await {
+            Ok(()) => return Ok(delivered),
+            Err(Interruption::Failed(error)) => return Err(error),
+            Err(Interruption::Dropped(error)) => {
+                // Any progress restarts the count
+                attempts = if cursor != resumed_from { 1 } else { attempts + 1 };
+                if attempts > MAX_RESUME_ATTEMPTS {
+                    return Err(format!("Swap history stream failed: {}", error));
+                }
+                let delay = RESUME_DELAY * 2u32.pow(attempts - 1);
+                tracing::warn!(
+                    delivered,
+                    attempt = attempts,
+                    cursor = ?cursor,
+                    error = %error,
+                    "Swap history stream interrupted, resuming in {:?}",
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// One request of [`stream_history`], from `cursor` on
+///
+/// Advances `cursor` and `delivered` as batches are handed over.
+async fn stream_once(
+    client: &ApiClient,
+    jwt_token: &str,
+    query: &SwapHistoryQuery,
+    cursor: &mut Option<String>,
+    delivered: &mut u64,
+    on_batch: &mut (dyn FnMut(&[SwapHistoryItem]) -> Result<(), String> + Send),
+) -> Result<(), Interruption> {
+    let params = StreamParams {
+        cursor: cursor.as_deref(),
+        from_ts: query.from_ts,
+        to_ts: query.to_ts,
+        input_token: query.input_token.as_deref(),
+        output_token: query.output_token.as_deref(),
+        status: query.status.as_deref(),
+        signature: query.signature.as_deref(),
+    };
+    let mut response = client
+        .http()
+        .get(format!("{}/api/swap/history/stream", client.base_url()))
+        .header("Authorization", format!("Bearer {}", jwt_token))
+        .query(&params)
+        .timeout(STREAM_TIMEOUT)
+        .send_via(client)
+        .await
+        .map_err(|e| Interruption::Dropped(format!("Network error: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error = match response.json::<ErrorResponse>().await {
+            Ok(error) => error.error,
+            Err(_) => format!("Failed to stream swap history: {}", status),
+        };
+        return Err(if status.is_server_error() { Interruption::Dropped(error) } else { Interruption::Failed(error) });
+    }
+
+    let mut decoder = LineDecoder::default();
+    // Records since the last cursor, held back until it arrives
+    let mut batch = Vec::new();
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => {
+                let error = format!("stream ended without its end line ({} bytes of a record pending)", decoder.pending());
+                return Err(Interruption::Dropped(error));
+            }
+            Err(e) => return Err(Interruption::Dropped(format!("Connection lost: {}", e))),
+        };
+        for line in decoder.push(&chunk) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line = serde_json::from_str::<HistoryStreamLine<SwapHistoryItem>>(&line)
+                .map_err(|e| Interruption::Failed(format!("Failed to parse history line: {}", e)))?;
+            match line {
+                HistoryStreamLine::Record(item) => batch.push(item),
+                HistoryStreamLine::Cursor(next) => {
+                    on_batch(&batch).map_err(Interruption::Failed)?;
+                    *delivered += batch.len() as u64;
+                    batch.clear();
+                    *cursor = Some(next);
+                }
+                HistoryStreamLine::End {} => {
+                    if !batch.is_empty() {
+                        on_batch(&batch).map_err(Interruption::Failed)?;
+                        *delivered += batch.len() as u64;
+                    }
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock_server {
+    //! History server that drops connections, for tests of streaming clients
+
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Swaps the server holds, `sig0` (newest) to `sig249`
+    pub(crate) const RECORDS: usize = 250;
+
+    fn record_line(i: usize) -> String {
+        let item = SwapHistoryItem {
+            id: i as i64,
+            signature: format!("sig{}", i),
+            input_mint: "SOL".to_string(),
+            output_mint: "USDC".to_string(),
+            input_amount: 1_000_000_000,
+            output_amount: 145_000_000,
+            status: "confirmed".to_string(),
+            created_at: "2025-02-18T10:00:00+00:00".to_string(),
+        };
+        serde_json::to_string(&HistoryStreamLine::Record(item)).unwrap() + "\n"
+    }
+
+    /// NDJSON body from record `start` on, with a cursor (`c{n}`, the records
+    /// it covers) every `interval` records and after the last
+    pub(crate) fn body_from(start: usize, interval: usize) -> String {
+        let mut body = String::new();
+        for i in start..RECORDS {
+            body.push_str(&record_line(i));
+            if (i + 1) % interval == 0 || i + 1 == RECORDS {
+                body.push_str(&format!("{{\"cursor\":\"c{}\"}}\n", i + 1));
+            }
+        }
+        body + "{\"end\":{}}\n"
+    }
+
+    /// History server whose first `drops` responses are cut off after
+    /// `cut_at` bytes, in the middle of a record. Bodies go out in small
+    /// pieces so records straddle chunks. Returns the base URL and the
+    /// cursors requests came with.
+    pub(crate) async fn flaky_server(drops: usize, cut_at: usize) -> (String, Arc<parking_lot::Mutex<Vec<Option<String>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let served = Arc::new(AtomicUsize::new(0));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut head = vec![0u8; 4096];
+                let n = socket.read(&mut head).await.unwrap();
+                let head = String::from_utf8_lossy(&head[..n]).to_string();
+                let cursor = head
+                    .split_whitespace()
+                    .nth(1)
+                    .and_then(|path| path.split("cursor=").nth(1))
+                    .map(|c| c.split('&').next().unwrap().to_string());
+                seen.lock().push(cursor.clone());
+                let start = cursor.map_or(0, |c| c.trim_start_matches('c').parse().unwrap());
+
+                let mut body = body_from(start, 100).into_bytes();
+                if served.fetch_add(1, Ordering::SeqCst) < drops {
+                    body.truncate(cut_at);
+                }
+                let head = "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nConnection: close\r\n\r\n";
+                socket.write_all(head.as_bytes()).await.unwrap();
+                for piece in body.chunks(37) {
+                    if socket.write_all(piece).await.is_err() {
+                        break;
+                    }
+                    socket.flush().await.unwrap();
+                }
+                let _ = socket.shutdown().await;
+            }
+        });
+        (url, requests)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock_server::{body_from, flaky_server, RECORDS};
+    use super::*;
+
+    async fn collect(client: &ApiClient) -> (Result<u64, String>, Vec<usize>, Vec<String>) {
+        let mut batches = Vec::new();
+        let mut signatures = Vec::new();
+        let mut on_batch = |batch: &[SwapHistoryItem]| {
+            batches.push(batch.len());
+            signatures.extend(batch.iter().map(|item| item.signature.clone()));
+            Ok(())
+        };
+        let result = stream_history(client, "jwt", &SwapHistoryQuery::default(), &mut on_batch).await;
+        (result, batches, signatures)
+    }
+
+    #[test]
+    fn test_line_decoder_keeps_partial_lines() {
+        let mut decoder = LineDecoder::default();
+        assert!(decoder.push(b"{\"rec").is_empty());
+        assert_eq!(decoder.pending(), 5);
+        assert_eq!(decoder.push(b"ord\":1}\n{\"cursor\":\"c1\"}\n{\"e"), vec!["{\"record\":1}", "{\"cursor\":\"c1\"}"]);
+        assert_eq!(decoder.push(b"nd\":{}}\n"), vec!["{\"end\":{}}"]);
+        assert_eq!(decoder.pending(), 0);
+        // A multi-byte character split across chunks survives
+        let text = "{\"memo\":\"café\"}\n".as_bytes();
+        assert!(decoder.push(&text[..13]).is_empty());
+        assert_eq!(decoder.push(&text[13..]), vec!["{\"memo\":\"café\"}"]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_delivers_batches_at_cursors() {
+        let (url, requests) = flaky_server(0, 0).await;
+        let client = ApiClient::with_servers(vec![url]);
+
+        let (result, batches, signatures) = collect(&client).await;
+
+        assert_eq!(result, Ok(RECORDS as u64));
+        assert_eq!(batches, vec![100, 100, 50]);
+        assert_eq!(signatures.first().map(String::as_str), Some("sig0"));
+        assert_eq!(signatures.last().map(String::as_str), Some("sig249"));
+        assert_eq!(*requests.lock(), vec![None]);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_stream_resumes_from_last_cursor() {
+        // Cut inside record 130, after the first cursor
+        let cut_at = body_from(0, 100).find("\"sig130\"").unwrap();
+        let (url, requests) = flaky_server(2, cut_at).await;
+        let client = ApiClient::with_servers(vec![url]);
+
+        let (result, batches, signatures) = collect(&client).await;
+
+        // Records after the cursor are dropped and come again: no gaps, no repeats
+        assert_eq!(result, Ok(RECORDS as u64));
+        assert_eq!(signatures, (0..RECORDS).map(|i| format!("sig{}", i)).collect::<Vec<_>>());
+        assert_eq!(batches, vec![100, 100, 50]);
+        // The second cut lands in record 230 of the resumed stream, past its first cursor
+        assert_eq!(*requests.lock(), vec![None, Some("c100".to_string()), Some("c200".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_gives_up_without_progress() {
+        // Every response is cut before its first cursor
+        let (url, requests) = flaky_server(usize::MAX, 50).await;
+        let client = ApiClient::with_servers(vec![url]);
+
+        let (result, batches, _) = collect(&client).await;
+
+        assert!(result.unwrap_err().contains("end line"));
+        assert!(batches.is_empty());
+        assert_eq!(requests.lock().len(), MAX_RESUME_ATTEMPTS as usize + 1);
+    }
+}
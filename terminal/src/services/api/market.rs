@@ -1,31 +1,31 @@
 //! # Market Data Endpoints
 //!
-//! Handles market data queries (prices, token lists).
+//! Handles market data queries (prices, token lists, candles, depth).
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use super::client::ApiClient;
+use std::collections::{BTreeMap, HashMap};
+use super::client::{ApiClient, ApiError, SendVia};
 
 /// Get Solana token prices.
 #[tracing::instrument(skip(client), fields(symbols = ?symbols))]
 pub async fn get_prices(
     client: &ApiClient,
     symbols: &[&str],
-) -> Result<PriceResponse, String> {
+) -> Result<PriceResponse, ApiError> {
     let start = std::time::Instant::now();
     let symbols_param = symbols.join(",");
-    let url = format!("{}/api/market/prices?symbols={}", ApiClient::base_url(), symbols_param);
+    let url = format!("{}/api/market/prices?symbols={}", client.base_url(), symbols_param);
 
     tracing::debug!("Fetching prices");
 
     let response = client
-        .client
+        .http()
         .get(&url)
-        .send()
+        .send_via(client)
         .await
         .map_err(|e| {
             tracing::error!(error = %e, "Price fetch network error");
-            format!("Network error: {}", e)
+            ApiError::from(e)
         })?;
 
     let duration = start.elapsed();
@@ -36,7 +36,7 @@ pub async fn get_prices(
             .await
             .map_err(|e| {
                 tracing::error!(error = %e, "Price response parse error");
-                format!("Failed to parse response: {}", e)
+                ApiError::parse(e)
             });
 
         if let Ok(ref prices) = result {
@@ -54,31 +54,31 @@ pub async fn get_prices(
             duration_ms = duration.as_millis(),
             "Price fetch failed"
         );
-        Err(format!("Failed to fetch prices: {}", status))
+        Err(ApiError::status(status, "Failed to fetch prices"))
     }
 }
 
 /// Get available token list for swapping.
 pub async fn get_token_list(
     client: &ApiClient,
-) -> Result<Vec<TokenListItem>, String> {
-    let url = format!("{}/api/market/tokens", ApiClient::base_url());
+) -> Result<Vec<TokenListItem>, ApiError> {
+    let url = format!("{}/api/market/tokens", client.base_url());
 
     let response = client
-        .client
+        .http()
         .get(&url)
-        .send()
+        .send_via(client)
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(ApiError::from)?;
 
     if response.status().is_success() {
         response
             .json::<TokenListResponse>()
             .await
             .map(|resp| resp.tokens)
-            .map_err(|e| format!("Failed to parse response: {}", e))
+            .map_err(ApiError::parse)
     } else {
-        Err(format!("Failed to fetch token list: {}", response.status()))
+        Err(ApiError::status(response.status(), "Failed to fetch token list"))
     }
 }
 
@@ -89,10 +89,10 @@ pub async fn get_candles(
     symbol: &str,
     timeframe: &str,
     limit: usize,
-) -> Result<Vec<shared::dto::market::OHLC>, String> {
+) -> Result<Vec<shared::dto::market::OHLC>, ApiError> {
     let url = format!(
         "{}/api/market/candles?symbol={}&timeframe={}&limit={}",
-        ApiClient::base_url(),
+        client.base_url(),
         symbol,
         timeframe,
         limit
@@ -108,9 +108,9 @@ pub async fn get_candles(
     );
 
     let response = client
-        .client
+        .http()
         .get(&url)
-        .send()
+        .send_via(client)
         .await
         .map_err(|e| {
             let duration = start.elapsed();
@@ -122,7 +122,7 @@ pub async fn get_candles(
                 duration_ms = duration.as_millis(),
                 "Candle fetch network error"
             );
-            format!("Network error: {}", e)
+            ApiError::from(e)
         })?;
 
     let status = response.status();
@@ -141,7 +141,7 @@ pub async fn get_candles(
                     duration_ms = duration.as_millis(),
                     "Candle response parse error"
                 );
-                format!("Failed to parse response: {}", e)
+                ApiError::parse(e)
             })?;
         
         tracing::debug!(
@@ -164,7 +164,177 @@ pub async fn get_candles(
             duration_ms = duration.as_millis(),
             "Candle fetch failed with non-success status"
         );
-        Err(format!("Failed to fetch candles: {}", status))
+        Err(ApiError::status(status, "Failed to fetch candles"))
+    }
+}
+
+/// Get OHLC candles for several symbols in one request.
+///
+/// The backend takes at most 20 symbols; a symbol it has no candles for is
+/// listed in the response's `errors` rather than failing the call.
+#[tracing::instrument(skip(client, symbols), fields(count = symbols.len(), timeframe = timeframe))]
+pub async fn get_candles_batch(
+    client: &ApiClient,
+    symbols: &[String],
+    timeframe: &str,
+) -> Result<shared::dto::market::CandleBatchResponse, ApiError> {
+    let url = format!(
+        "{}/api/market/candles/batch?symbols={}&timeframe={}",
+        client.base_url(),
+        symbols.join(","),
+        timeframe
+    );
+
+    let response = client
+        .http()
+        .get(&url)
+        .send_via(client)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Candle batch fetch network error");
+            ApiError::from(e)
+        })?;
+
+    let status = response.status();
+    if status.is_success() {
+        response
+            .json::<shared::dto::market::CandleBatchResponse>()
+            .await
+            .map_err(ApiError::parse)
+    } else {
+        tracing::warn!(status = status.as_u16(), "Candle batch fetch failed");
+        Err(ApiError::status(status, "Failed to fetch candles"))
+    }
+}
+
+/// Get the symbols the backend price stream is tracking.
+#[tracing::instrument(skip(client))]
+pub async fn get_streamed_symbols(
+    client: &ApiClient,
+) -> Result<shared::dto::market::StreamedSymbolsResponse, ApiError> {
+    let url = format!("{}/api/market/streamed-symbols", client.base_url());
+
+    let response = client
+        .http()
+        .get(&url)
+        .send_via(client)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Streamed symbols fetch network error");
+            ApiError::from(e)
+        })?;
+
+    let status = response.status();
+    if status.is_success() {
+        response
+            .json::<shared::dto::market::StreamedSymbolsResponse>()
+            .await
+            .map_err(ApiError::parse)
+    } else {
+        tracing::warn!(status = status.as_u16(), "Streamed symbols fetch failed");
+        Err(ApiError::status(status, "Failed to fetch streamed symbols"))
+    }
+}
+
+/// Get token unlocks scheduled in the next `days` days, soonest first.
+#[tracing::instrument(skip(client))]
+pub async fn get_token_unlocks(
+    client: &ApiClient,
+    days: i64,
+) -> Result<shared::dto::unlocks::TokenUnlocksResponse, ApiError> {
+    let url = format!("{}/api/market/unlocks?days={}", client.base_url(), days);
+
+    let response = client
+        .http()
+        .get(&url)
+        .send_via(client)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Token unlocks fetch network error");
+            ApiError::from(e)
+        })?;
+
+    let status = response.status();
+    if status.is_success() {
+        response
+            .json::<shared::dto::unlocks::TokenUnlocksResponse>()
+            .await
+            .map_err(ApiError::parse)
+    } else {
+        tracing::warn!(status = status.as_u16(), "Token unlocks fetch failed");
+        Err(ApiError::status(status, "Failed to fetch token unlocks"))
+    }
+}
+
+/// Get the route-quote depth ladder for a token pair.
+#[tracing::instrument(skip(client), fields(input = %input, output = %output))]
+pub async fn get_depth(
+    client: &ApiClient,
+    input: &str,
+    output: &str,
+) -> Result<shared::dto::market::DepthResponse, ApiError> {
+    let url = format!(
+        "{}/api/market/depth?input={}&output={}",
+        client.base_url(),
+        input,
+        output
+    );
+
+    let response = client
+        .http()
+        .get(&url)
+        .send_via(client)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Depth fetch network error");
+            ApiError::from(e)
+        })?;
+
+    let status = response.status();
+    if status.is_success() {
+        response
+            .json::<shared::dto::market::DepthResponse>()
+            .await
+            .map_err(ApiError::parse)
+    } else {
+        tracing::warn!(status = status.as_u16(), "Depth fetch failed");
+        Err(ApiError::status(status, "Failed to fetch depth"))
+    }
+}
+
+/// Get volatility and the return correlation matrix for a set of symbols.
+#[tracing::instrument(skip(client, symbols), fields(count = symbols.len(), window = window))]
+pub async fn get_market_analytics(
+    client: &ApiClient,
+    symbols: &[String],
+    window: usize,
+) -> Result<shared::dto::market::MarketAnalyticsResponse, ApiError> {
+    let url = format!(
+        "{}/api/market/analytics?symbols={}&window={}",
+        client.base_url(),
+        symbols.join(","),
+        window
+    );
+
+    let response = client
+        .http()
+        .get(&url)
+        .send_via(client)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Analytics fetch network error");
+            ApiError::from(e)
+        })?;
+
+    let status = response.status();
+    if status.is_success() {
+        response
+            .json::<shared::dto::market::MarketAnalyticsResponse>()
+            .await
+            .map_err(ApiError::parse)
+    } else {
+        tracing::warn!(status = status.as_u16(), "Analytics fetch failed");
+        Err(ApiError::status(status, "Failed to fetch analytics"))
     }
 }
 
@@ -179,6 +349,15 @@ pub struct PriceData {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub change_24h: Option<f64>,
     pub last_updated: u64,
+    /// When the oracle published the price; only oracle sources report it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish_time: Option<i64>,
+    /// Price each source reported, when the backend compared several
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sources: BTreeMap<String, f64>,
+    /// Whether the sources disagree by more than the backend's threshold
+    #[serde(default)]
+    pub divergent: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -186,17 +365,5 @@ pub struct PriceResponse {
     pub prices: HashMap<String, PriceData>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TokenListItem {
-    pub symbol: String,
-    pub name: String,
-    pub mint: String,
-    pub decimals: u8,
-    pub logo_uri: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TokenListResponse {
-    pub tokens: Vec<TokenListItem>,
-}
+pub use shared::dto::market::{TokenListItem, TokenListResponse};
 
@@ -8,24 +8,46 @@
 //! ```text
 //! api/
 //! ├── mod.rs      - Module exports and documentation
-//! ├── client.rs   - ApiClient struct and common functionality
+//! ├── client.rs   - ApiClient struct, ApiError and common functionality
+//! ├── api_keys.rs - API keys for scripted access
+//! ├── failover.rs - Server list, health probes and failover decisions
 //! ├── auth.rs     - Authentication endpoints (login, signup)
+//! ├── chat.rs     - Conversation summaries
 //! ├── market.rs   - Market data endpoints (prices, token list)
+//! ├── onramp.rs   - Fiat on-ramp providers
+//! ├── reports.rs  - Printable account reports (PDF)
+//! ├── share.rs    - Public share links
+//! ├── staking.rs  - Stake accounts, rewards and staking transactions
 //! ├── wallet.rs   - Wallet query endpoints (balance, tokens, transactions)
-//! └── swap.rs     - Swap endpoints (quote, execute, submit, history)
+//! ├── swap.rs     - Swap endpoints (quote, execute, submit, history)
+//! ├── telemetry.rs - Opt-in anonymous usage counts
+//! ├── history_stream.rs - Resumable NDJSON swap history stream
+//! ├── webhooks.rs - Wallet activity webhooks and their delivery logs
+//! └── system.rs   - Backend health report
 //! ```
 
+pub mod api_keys;
 pub mod auth;
+pub mod chat;
 pub mod client;
+pub mod failover;
 pub mod friends;
+pub mod history_stream;
 pub mod market;
+pub mod onramp;
+pub mod reports;
+pub mod share;
+pub mod staking;
 pub mod swap;
+pub mod system;
+pub mod telemetry;
 pub mod wallet;
+pub mod webhooks;
 pub mod websocket;
 
 // Re-export types for backward compatibility
 pub use auth::*;
-pub use client::ApiClient;
+pub use client::{ApiClient, ApiError};
 // pub use friends::*; // Unused for now
 pub use market::*;
 pub use swap::*;
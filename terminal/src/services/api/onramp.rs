@@ -0,0 +1,30 @@
+//! # On-Ramp API Client
+//!
+//! HTTP client method for the fiat on-ramp providers behind "Buy SOL".
+
+use super::client::{ApiClient, ApiError, SendVia};
+use shared::dto::onramp::OnRampProvidersResponse;
+
+impl ApiClient {
+
+    /// Every configured on-ramp provider
+    ///
+    /// Fetched unfiltered so the dialog can switch regions without asking again.
+    pub async fn get_onramp_providers(&self) -> Result<OnRampProvidersResponse, ApiError> {
+        let url = format!("{}/api/onramp/providers", self.base_url());
+
+        let response = self.http()
+            .get(&url)
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        if response.status().is_success() {
+            response.json::<OnRampProvidersResponse>()
+                .await
+                .map_err(ApiError::parse)
+        } else {
+            Err(ApiError::from_response(response, "Failed to fetch on-ramp providers").await)
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! # Reports API Client
+//!
+//! HTTP client method for downloading printable account reports.
+
+use super::client::{ApiClient, ApiError, SendVia};
+use shared::dto::reports::ReportMonth;
+
+impl ApiClient {
+    
+    /// Download the monthly report for `month` as PDF bytes
+    pub async fn download_monthly_report(&self, token: &str, month: ReportMonth) -> Result<Vec<u8>, ApiError> {
+        let url = format!("{}/api/reports/monthly?month={}", self.base_url(), month);
+        
+        let response = self.http()
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+        
+        if response.status().is_success() {
+            response.bytes()
+                .await
+                .map(|bytes| bytes.to_vec())
+                .map_err(ApiError::from)
+        } else {
+            Err(ApiError::from_response(response, "API error").await)
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! # Share API Client
+//!
+//! HTTP client method for publishing read-only share links.
+
+use super::client::{ApiClient, ApiError, SendVia};
+use shared::dto::share::{CreateShareRequest, CreateShareResponse};
+
+impl ApiClient {
+    
+    /// Publish a chart or portfolio snapshot
+    ///
+    /// The returned `path` is relative to the backend; prefix it with the
+    /// active server's base URL for a shareable URL.
+    pub async fn create_share_link(
+        &self,
+        token: &str,
+        request: &CreateShareRequest,
+    ) -> Result<CreateShareResponse, ApiError> {
+        let url = format!("{}/api/share", self.base_url());
+        
+        let response = self.http()
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .json(request)
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+        
+        if response.status().is_success() {
+            response.json::<CreateShareResponse>()
+                .await
+                .map_err(ApiError::parse)
+        } else {
+            Err(ApiError::from_response(response, "API error").await)
+        }
+    }
+}
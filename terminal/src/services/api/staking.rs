@@ -0,0 +1,90 @@
+//! # Staking API Client
+//!
+//! HTTP client methods for the Wallet screen's Staking panel: stake accounts,
+//! rewards, and the unsigned transactions that stake, deactivate and withdraw.
+
+use super::client::{ApiClient, ApiError, SendVia};
+use serde::Serialize;
+use shared::dto::staking::{
+    DelegateStakeRequest, StakeAccountRequest, StakeTransactionResponse, StakingInfo, StakingRewardsResponse,
+};
+
+impl ApiClient {
+
+    /// Stake accounts of `address` and the current epoch
+    pub async fn get_staking_info(&self, address: &str) -> Result<StakingInfo, ApiError> {
+        let url = format!("{}/api/staking/info?address={}", self.base_url(), address);
+
+        let response = self.http()
+            .get(&url)
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        if response.status().is_success() {
+            response.json::<StakingInfo>()
+                .await
+                .map_err(ApiError::parse)
+        } else {
+            Err(ApiError::from_response(response, "Failed to fetch staking info").await)
+        }
+    }
+
+    /// Rewards of the stake accounts of `address` over the last `epochs` epochs
+    pub async fn get_staking_rewards(&self, address: &str, epochs: u64) -> Result<StakingRewardsResponse, ApiError> {
+        let url = format!("{}/api/staking/rewards?address={}&epochs={}", self.base_url(), address, epochs);
+
+        let response = self.http()
+            .get(&url)
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        if response.status().is_success() {
+            response.json::<StakingRewardsResponse>()
+                .await
+                .map_err(ApiError::parse)
+        } else {
+            Err(ApiError::from_response(response, "Failed to fetch staking rewards").await)
+        }
+    }
+
+    /// Unsigned transaction creating a stake account and delegating it
+    pub async fn build_stake_delegate(&self, request: &DelegateStakeRequest) -> Result<StakeTransactionResponse, ApiError> {
+        self.post_stake_transaction("delegate", request).await
+    }
+
+    /// Unsigned transaction deactivating a stake account
+    pub async fn build_stake_deactivate(&self, request: &StakeAccountRequest) -> Result<StakeTransactionResponse, ApiError> {
+        self.post_stake_transaction("deactivate", request).await
+    }
+
+    /// Unsigned transaction withdrawing an inactive stake account
+    pub async fn build_stake_withdraw(&self, request: &StakeAccountRequest) -> Result<StakeTransactionResponse, ApiError> {
+        self.post_stake_transaction("withdraw", request).await
+    }
+
+    async fn post_stake_transaction(
+        &self,
+        action: &str,
+        request: &impl Serialize,
+    ) -> Result<StakeTransactionResponse, ApiError> {
+        let url = format!("{}/api/staking/{}", self.base_url(), action);
+
+        let response = self.http()
+            .post(&url)
+            .json(request)
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        if response.status().is_success() {
+            response.json::<StakeTransactionResponse>()
+                .await
+                .map_err(ApiError::parse)
+        } else {
+            let context = format!("Failed to build the {} transaction", action);
+            Err(ApiError::from_response(response, &context).await)
+        }
+    }
+}
@@ -1,10 +1,13 @@
 //! # Swap Endpoints
 //!
-//! Handles swap operations (quote, execute, submit, history).
+//! Handles swap operations (quote, execute, simulate, submit, history, trade imports).
 
 use serde::{Deserialize, Serialize};
-use shared::ErrorResponse;
-use super::client::ApiClient;
+use shared::dto::latency::SwapLatency;
+use shared::dto::simulation::{SimulateSwapRequest, SimulateSwapResponse};
+use shared::dto::trades::{TradeImportRequest, TradeImportResponse, TradeStatsResponse};
+use shared::dto::transactions::{TransactionStatusUpdate, TransactionVersion};
+use super::client::{ApiClient, ApiError, SendVia};
 
 /// Get swap quote from Jupiter.
 pub async fn get_swap_quote(
@@ -13,30 +16,26 @@ pub async fn get_swap_quote(
     output_mint: &str,
     amount: u64,
     slippage_bps: u16,
-) -> Result<SwapQuoteResponse, String> {
+) -> Result<SwapQuoteResponse, ApiError> {
     let url = format!(
         "{}/api/swap/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}",
-        ApiClient::base_url(), input_mint, output_mint, amount, slippage_bps
+        client.base_url(), input_mint, output_mint, amount, slippage_bps
     );
 
     let response = client
-        .client
+        .http()
         .get(&url)
-        .send()
+        .send_via(client)
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(ApiError::from)?;
 
     if response.status().is_success() {
         response
             .json::<SwapQuoteResponse>()
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e))
+            .map_err(ApiError::parse)
     } else {
-        let error = response
-            .json::<ErrorResponse>()
-            .await
-            .map_err(|e| format!("Failed to parse error: {}", e))?;
-        Err(error.error)
+        Err(ApiError::from_response(response, "Failed to get swap quote").await)
     }
 }
 
@@ -56,7 +55,7 @@ pub async fn execute_swap(
     slippage_bps: u16,
     user_public_key: &str,
     jwt_token: &str,
-) -> Result<SwapExecuteResponse, String> {
+) -> Result<SwapExecuteResponse, ApiError> {
     tracing::info!("Executing swap");
     let start = std::time::Instant::now();
 
@@ -69,15 +68,15 @@ pub async fn execute_swap(
     };
 
     let response = client
-        .client
-        .post(format!("{}/api/swap/execute", ApiClient::base_url()))
+        .http()
+        .post(format!("{}/api/swap/execute", client.base_url()))
         .header("Authorization", format!("Bearer {}", jwt_token))
         .json(&request)
-        .send()
+        .send_via(client)
         .await
         .map_err(|e| {
             tracing::error!(error = %e, "Swap execution network error");
-            format!("Network error: {}", e)
+            ApiError::from(e)
         })?;
 
     let duration = start.elapsed();
@@ -89,7 +88,7 @@ pub async fn execute_swap(
             .await
             .map_err(|e| {
                 tracing::error!(error = %e, "Swap response parse error");
-                format!("Failed to parse response: {}", e)
+                ApiError::parse(e)
             });
 
         if result.is_ok() {
@@ -97,25 +96,53 @@ pub async fn execute_swap(
         }
         result
     } else {
-        let error = response
-            .json::<ErrorResponse>()
-            .await
-            .map_err(|e| format!("Failed to parse error: {}", e))?;
+        let error = ApiError::from_response(response, "Swap execution failed").await;
 
         tracing::warn!(
             status = status.as_u16(),
-            error = %error.error,
+            error = %error,
             duration_ms = duration.as_millis(),
             "Swap execution failed"
         );
-        Err(error.error)
+        Err(error)
+    }
+}
+
+/// Simulate an unsigned swap transaction before it is signed.
+///
+/// A transaction that would fail is still `Ok`, with `success: false`.
+pub async fn simulate_swap(
+    client: &ApiClient,
+    request: &SimulateSwapRequest,
+    jwt_token: &str,
+) -> Result<SimulateSwapResponse, ApiError> {
+    let response = client
+        .http()
+        .post(format!("{}/api/swap/simulate", client.base_url()))
+        .header("Authorization", format!("Bearer {}", jwt_token))
+        .json(request)
+        .send_via(client)
+        .await
+        .map_err(ApiError::from)?;
+
+    if response.status().is_success() {
+        response
+            .json::<SimulateSwapResponse>()
+            .await
+            .map_err(ApiError::parse)
+    } else {
+        Err(ApiError::from_response(response, "Failed to simulate swap").await)
     }
 }
 
 /// Submit signed transaction.
+///
+/// `version` is the message format of `signed_transaction`, as returned by
+/// [`execute_swap`] (see [`SwapExecuteResponse::version`]).
 pub async fn submit_transaction(
     client: &ApiClient,
     signed_transaction: String,
+    version: TransactionVersion,
     input_mint: String,
     output_mint: String,
     input_amount: i64,
@@ -123,9 +150,10 @@ pub async fn submit_transaction(
     price_impact: Option<f64>,
     slippage_bps: Option<i32>,
     jwt_token: &str,
-) -> Result<TransactionSubmitResponse, String> {
+) -> Result<TransactionSubmitResponse, ApiError> {
     let request = TransactionSubmitRequest {
         signed_transaction,
+        version,
         input_mint,
         output_mint,
         input_amount,
@@ -135,52 +163,146 @@ pub async fn submit_transaction(
     };
 
     let response = client
-        .client
-        .post(format!("{}/api/transactions/submit", ApiClient::base_url()))
+        .http()
+        .post(format!("{}/api/transactions/submit", client.base_url()))
         .header("Authorization", format!("Bearer {}", jwt_token))
         .json(&request)
-        .send()
+        .send_via(client)
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(ApiError::from)?;
 
     if response.status().is_success() {
         response
             .json::<TransactionSubmitResponse>()
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e))
+            .map_err(ApiError::parse)
     } else {
-        let error = response
-            .json::<ErrorResponse>()
-            .await
-            .map_err(|e| format!("Failed to parse error: {}", e))?;
-        Err(error.error)
+        Err(ApiError::from_response(response, "Failed to submit transaction").await)
     }
 }
 
-/// Get swap history for user.
+/// Report where a submitted swap transaction ended up.
+pub async fn update_transaction_status(
+    client: &ApiClient,
+    signature: &str,
+    update: &TransactionStatusUpdate,
+    jwt_token: &str,
+) -> Result<(), ApiError> {
+    let response = client
+        .http()
+        .put(format!("{}/api/transaction/{}/status", client.base_url(), signature))
+        .header("Authorization", format!("Bearer {}", jwt_token))
+        .json(update)
+        .send_via(client)
+        .await
+        .map_err(ApiError::from)?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(ApiError::from_response(response, "Failed to update transaction status").await)
+    }
+}
+
+/// Report how long each stage of a submitted swap took.
+pub async fn record_transaction_latency(
+    client: &ApiClient,
+    signature: &str,
+    latency: &SwapLatency,
+    jwt_token: &str,
+) -> Result<(), ApiError> {
+    let response = client
+        .http()
+        .put(format!("{}/api/transaction/{}/latency", client.base_url(), signature))
+        .header("Authorization", format!("Bearer {}", jwt_token))
+        .json(latency)
+        .send_via(client)
+        .await
+        .map_err(ApiError::from)?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(ApiError::from_response(response, "Failed to record swap latency").await)
+    }
+}
+
+/// Get a filtered page of swap history for user.
 pub async fn get_swap_history(
     client: &ApiClient,
     jwt_token: &str,
-    limit: usize,
-) -> Result<Vec<SwapHistoryItem>, String> {
-    let url = format!("{}/api/swap/history?limit={}", ApiClient::base_url(), limit);
+    query: &SwapHistoryQuery,
+) -> Result<SwapHistoryResponse, ApiError> {
+    let url = format!("{}/api/swap/history", client.base_url());
 
     let response = client
-        .client
+        .http()
         .get(&url)
         .header("Authorization", format!("Bearer {}", jwt_token))
-        .send()
+        .query(query)
+        .send_via(client)
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(ApiError::from)?;
 
     if response.status().is_success() {
         response
             .json::<SwapHistoryResponse>()
             .await
-            .map(|resp| resp.swaps)
-            .map_err(|e| format!("Failed to parse response: {}", e))
+            .map_err(ApiError::parse)
+    } else {
+        Err(ApiError::from_response(response, "Failed to fetch swap history").await)
+    }
+}
+
+/// Validate or commit a CSV trade import.
+pub async fn import_trades(
+    client: &ApiClient,
+    jwt_token: &str,
+    request: &TradeImportRequest,
+) -> Result<TradeImportResponse, ApiError> {
+    let url = format!("{}/api/swap/history/import", client.base_url());
+
+    let response = client
+        .http()
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", jwt_token))
+        .json(request)
+        .send_via(client)
+        .await
+        .map_err(ApiError::from)?;
+
+    if response.status().is_success() {
+        response
+            .json::<TradeImportResponse>()
+            .await
+            .map_err(ApiError::parse)
     } else {
-        Err(format!("Failed to fetch swap history: {}", response.status()))
+        Err(ApiError::from_response(response, "Failed to import trades").await)
+    }
+}
+
+/// Get trade statistics (swaps and imported trades) for user.
+pub async fn get_trade_stats(
+    client: &ApiClient,
+    jwt_token: &str,
+) -> Result<TradeStatsResponse, ApiError> {
+    let url = format!("{}/api/swap/stats", client.base_url());
+
+    let response = client
+        .http()
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", jwt_token))
+        .send_via(client)
+        .await
+        .map_err(ApiError::from)?;
+
+    if response.status().is_success() {
+        response
+            .json::<TradeStatsResponse>()
+            .await
+            .map_err(ApiError::parse)
+    } else {
+        Err(ApiError::from_response(response, "Failed to fetch trade statistics").await)
     }
 }
 
@@ -230,6 +352,9 @@ pub struct SwapExecuteRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SwapExecuteResponse {
     pub transaction: String, // Base64-encoded unsigned transaction
+    /// Message format of `transaction`; older servers only build legacy ones
+    #[serde(default)]
+    pub version: TransactionVersion,
     #[serde(rename = "lastValidBlockHeight")]
     pub last_valid_block_height: u64,
     #[serde(rename = "inputMint")]
@@ -248,6 +373,7 @@ pub struct SwapExecuteResponse {
 pub struct TransactionSubmitRequest {
     #[serde(rename = "signedTransaction")]
     pub signed_transaction: String,
+    pub version: TransactionVersion,
     #[serde(rename = "inputMint")]
     pub input_mint: String,
     #[serde(rename = "outputMint")]
@@ -288,5 +414,31 @@ pub struct SwapHistoryItem {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SwapHistoryResponse {
     pub swaps: Vec<SwapHistoryItem>,
+    /// Swaps matching the filters across all pages
+    #[serde(rename = "totalCount", default)]
+    pub total_count: i64,
+}
+
+/// Filters and paging for `GET /api/swap/history`.
+///
+/// Timestamps are Unix seconds; `from_ts` is inclusive and `to_ts` exclusive.
+/// Unset fields are left out of the query string.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SwapHistoryQuery {
+    pub limit: usize,
+    pub offset: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_ts: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_ts: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Fragment of the transaction signature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
 
@@ -0,0 +1,36 @@
+//! # System API
+//!
+//! Backend health report (`GET /api/health`).
+
+use super::client::{ApiClient, ApiError, SendVia};
+
+/// Get the backend health report.
+///
+/// The backend answers `503` with a full report when it is down, so the body
+/// is parsed for any status that carries one.
+#[tracing::instrument(skip(client))]
+pub async fn get_health(client: &ApiClient) -> Result<shared::dto::system::HealthResponse, ApiError> {
+    let url = format!("{}/api/health", client.base_url());
+
+    let response = client
+        .http()
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(5))
+        .send_via(client)
+        .await
+        .map_err(|e| {
+            tracing::debug!(error = %e, "Health check network error");
+            ApiError::from(e)
+        })?;
+
+    let status = response.status();
+    if status.is_success() || status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+        response
+            .json::<shared::dto::system::HealthResponse>()
+            .await
+            .map_err(ApiError::parse)
+    } else {
+        tracing::warn!(status = status.as_u16(), "Health check failed");
+        Err(ApiError::status(status, "Health check failed"))
+    }
+}
@@ -0,0 +1,45 @@
+//! # Usage Telemetry API Client
+//!
+//! HTTP client methods for opt-in anonymous usage counts. Neither call sends
+//! the session token: reports belong to an install id, not the account.
+
+use super::client::{ApiClient, ApiError, SendVia};
+use shared::dto::telemetry::UsageReport;
+
+impl ApiClient {
+
+    /// Upload the daily counts of completed days
+    pub async fn upload_usage(&self, report: &UsageReport) -> Result<(), ApiError> {
+        let url = format!("{}/api/telemetry/usage", self.base_url());
+
+        let response = self.http()
+            .post(&url)
+            .json(report)
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(ApiError::from_response(response, "Failed to upload usage counts").await)
+        }
+    }
+
+    /// Delete everything the backend holds for `install_id`
+    pub async fn delete_usage(&self, install_id: &str) -> Result<(), ApiError> {
+        let url = format!("{}/api/telemetry/usage/{}", self.base_url(), install_id);
+
+        let response = self.http()
+            .delete(&url)
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(ApiError::from_response(response, "Failed to delete usage counts").await)
+        }
+    }
+}
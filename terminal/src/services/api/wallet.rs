@@ -3,29 +3,30 @@
 //! Handles wallet-related queries (balance, token balances, transaction history).
 
 use serde::{Deserialize, Serialize};
-use super::client::ApiClient;
+use super::client::{ApiClient, ApiError, SendVia};
+use shared::dto::tokens::{TokenProgram, TransferFee};
 
 /// Get wallet SOL balance.
 pub async fn get_wallet_balance(
     client: &ApiClient,
     address: &str,
-) -> Result<WalletBalance, String> {
-    let url = format!("{}/api/wallet/balance?address={}", ApiClient::base_url(), address);
+) -> Result<WalletBalance, ApiError> {
+    let url = format!("{}/api/wallet/balance?address={}", client.base_url(), address);
 
     let response = client
-        .client
+        .http()
         .get(&url)
-        .send()
+        .send_via(client)
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(ApiError::from)?;
 
     if response.status().is_success() {
         response
             .json::<WalletBalance>()
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e))
+            .map_err(ApiError::parse)
     } else {
-        Err(format!("Failed to fetch wallet balance: {}", response.status()))
+        Err(ApiError::status(response.status(), "Failed to fetch wallet balance"))
     }
 }
 
@@ -34,47 +35,47 @@ pub async fn get_transaction_history(
     client: &ApiClient,
     address: &str,
     limit: usize,
-) -> Result<TransactionHistory, String> {
-    let url = format!("{}/api/transactions?address={}&limit={}", ApiClient::base_url(), address, limit);
+) -> Result<TransactionHistory, ApiError> {
+    let url = format!("{}/api/transactions?address={}&limit={}", client.base_url(), address, limit);
 
     let response = client
-        .client
+        .http()
         .get(&url)
-        .send()
+        .send_via(client)
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(ApiError::from)?;
 
     if response.status().is_success() {
         response
             .json::<TransactionHistory>()
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e))
+            .map_err(ApiError::parse)
     } else {
-        Err(format!("Failed to fetch transactions: {}", response.status()))
+        Err(ApiError::status(response.status(), "Failed to fetch transactions"))
     }
 }
 
-/// Get SPL token balances for an address.
+/// Get SPL and Token-2022 token balances for an address.
 pub async fn get_token_balances(
     client: &ApiClient,
     address: &str,
-) -> Result<Vec<TokenBalance>, String> {
-    let url = format!("{}/api/wallet/tokens?address={}", ApiClient::base_url(), address);
+) -> Result<Vec<TokenBalance>, ApiError> {
+    let url = format!("{}/api/wallet/tokens?address={}", client.base_url(), address);
 
     let response = client
-        .client
+        .http()
         .get(&url)
-        .send()
+        .send_via(client)
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(ApiError::from)?;
 
     if response.status().is_success() {
         response
             .json::<Vec<TokenBalance>>()
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e))
+            .map_err(ApiError::parse)
     } else {
-        Err(format!("Failed to fetch token balances: {}", response.status()))
+        Err(ApiError::status(response.status(), "Failed to fetch token balances"))
     }
 }
 
@@ -93,6 +94,9 @@ pub struct TransactionSummary {
     pub slot: u64,
     pub block_time: Option<i64>,
     pub status: String,
+    /// Raw RPC memo field (`[len] text; ...`), see [`crate::services::memo::decode_history_memo`]
+    #[serde(default)]
+    pub memo: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,5 +111,16 @@ pub struct TokenBalance {
     pub symbol: Option<String>,
     pub balance: f64,
     pub ui_amount: String,
+    /// Balance in base units
+    #[serde(default)]
+    pub amount: u64,
+    #[serde(default)]
+    pub decimals: u8,
+    /// Program owning the token account; older backends only report classic SPL tokens
+    #[serde(default)]
+    pub program: TokenProgram,
+    /// Transfer fee for the current epoch, on Token-2022 mints that charge one
+    #[serde(default)]
+    pub transfer_fee: Option<TransferFee>,
 }
 
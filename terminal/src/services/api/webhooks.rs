@@ -0,0 +1,103 @@
+//! # Webhook API Client
+//!
+//! HTTP client methods for managing outgoing wallet activity webhooks.
+
+use super::client::{ApiClient, ApiError, SendVia};
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+use shared::dto::webhooks::{
+    CreateWebhookRequest, CreateWebhookResponse, WebhookDeliveriesResponse, WebhookDeliveryInfo,
+    WebhookListResponse,
+};
+
+impl ApiClient {
+
+    /// Register a webhook; the response carries the signing secret, which is never shown again
+    pub async fn create_webhook(
+        &self,
+        token: &str,
+        request: &CreateWebhookRequest,
+    ) -> Result<CreateWebhookResponse, ApiError> {
+        let url = format!("{}/api/webhooks", self.base_url());
+
+        let response = self.http()
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .json(request)
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        parse_response(response).await
+    }
+
+    /// List the user's webhooks
+    pub async fn list_webhooks(&self, token: &str) -> Result<WebhookListResponse, ApiError> {
+        let url = format!("{}/api/webhooks", self.base_url());
+
+        let response = self.http()
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        parse_response(response).await
+    }
+
+    /// Delete a webhook and its delivery log
+    pub async fn delete_webhook(&self, token: &str, id: i64) -> Result<(), ApiError> {
+        let url = format!("{}/api/webhooks/{}", self.base_url(), id);
+
+        let response = self.http()
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(ApiError::from_response(response, "API error").await)
+        }
+    }
+
+    /// Recent deliveries of a webhook, newest first
+    pub async fn get_webhook_deliveries(&self, token: &str, id: i64) -> Result<WebhookDeliveriesResponse, ApiError> {
+        let url = format!("{}/api/webhooks/{}/deliveries", self.base_url(), id);
+
+        let response = self.http()
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        parse_response(response).await
+    }
+
+    /// Send a test event; resolves once the backend's single attempt finished
+    pub async fn send_test_webhook(&self, token: &str, id: i64) -> Result<WebhookDeliveryInfo, ApiError> {
+        let url = format!("{}/api/webhooks/{}/test", self.base_url(), id);
+
+        let response = self.http()
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send_via(self)
+            .await
+            .map_err(ApiError::from)?;
+
+        parse_response(response).await
+    }
+}
+
+async fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, ApiError> {
+    if response.status().is_success() {
+        response.json::<T>()
+            .await
+            .map_err(ApiError::parse)
+    } else {
+        Err(ApiError::from_response(response, "API error").await)
+    }
+}
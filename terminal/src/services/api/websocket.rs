@@ -1,68 +1,181 @@
 //! # WebSocket Client for Real-Time Price Updates
 //!
 //! Handles WebSocket connection to backend for streaming price updates.
+//!
+//! Dropped connections are retried with jittered exponential backoff, from
+//! 250ms up to 30s. After [`MAX_CONNECTION_ATTEMPTS`] failures in a row the
+//! stream is disabled and the status bar offers a Reconnect button, which
+//! calls [`restart_price_stream`].
+//!
+//! The server pings every 10s, so a connection that delivers nothing at all
+//! for [`STALL_TIMEOUT`] is treated as dead and reconnected, instead of
+//! showing "Connected" while a NAT has silently dropped it.
+//!
+//! A server shutting down closes the connection with
+//! [`SERVER_RESTARTING_REASON`]; the next attempt then waits
+//! [`restart_delay`] rather than hitting the server while it's still down.
+//!
+//! The same connection carries the swap panel's quote stream. Frames queued
+//! on [`AppState::price_stream_outbox`] are sent as they come, and the quote
+//! subscription is sent again after every reconnect.
 
-use crate::app::{AppEvent, AppState, WebSocketState};
+use crate::app::{event_queue, AppEvent, AppState, WebSocketState};
 use crate::app::PriceData;
+use crate::core::config::TerminalConfig;
+use super::failover::DEFAULT_SERVER;
 use async_channel::Sender;
 use std::sync::Arc;
 use parking_lot::RwLock;
 use futures_util::{SinkExt, StreamExt};
-use serde::{Deserialize, Serialize};
+use shared::dto::market::{
+    CandleUpdateMessage, PriceSubscribeMessage, PriceUpdateMessage, QuoteSubscribeMessage, QuoteUpdateMessage,
+};
+use shared::dto::system::SERVER_RESTARTING_REASON;
+use std::collections::BTreeSet;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::{HeaderValue, AUTHORIZATION};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tracing::{debug, error, info, warn, trace};
 
-/// Price update message from WebSocket server
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct PriceUpdateMessage {
-    #[serde(rename = "type")]
-    pub message_type: String,
-    pub data: PriceUpdateData,
+/// WebSocket URL for price streaming: the configured `ws_url`, or the API
+/// client's active server
+pub(crate) fn price_stream_url(app_state: Option<&Arc<RwLock<AppState>>>) -> String {
+    match app_state {
+        Some(state) => {
+            let state = state.read();
+            let base_url = state.api_client.as_ref().map(|c| c.base_url()).unwrap_or_else(|| DEFAULT_SERVER.to_string());
+            state.config.price_stream_url(&base_url)
+        }
+        None => {
+            let config = TerminalConfig::load();
+            let base_url = config.api_url.clone().unwrap_or_else(|| DEFAULT_SERVER.to_string());
+            config.price_stream_url(&base_url)
+        }
+    }
 }
 
-/// Price update data payload
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct PriceUpdateData {
-    pub symbol: String,
-    pub mint: String,
-    pub price: f64,
-    pub source: String,
-    pub timestamp: u64,
+/// Global counter for total price update messages received
+pub static MESSAGE_COUNTER: AtomicU64 = AtomicU64::new(0);
+/// Counter for reconnection attempts
+pub static RECONNECT_COUNTER: AtomicU64 = AtomicU64::new(0);
+/// Consecutive failed attempts after which the stream stays off until the
+/// user presses Reconnect
+pub const MAX_CONNECTION_ATTEMPTS: u64 = 10;
+/// Delay before the first reconnect
+pub const BACKOFF_BASE: Duration = Duration::from_millis(250);
+/// Longest delay between reconnects
+pub const BACKOFF_CAP: Duration = Duration::from_secs(30);
+/// Silence after which a connection is assumed dead and reconnected
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+/// Least wait before reconnecting to a server that closed for a restart
+pub const RESTART_BACKOFF: Duration = Duration::from_secs(5);
+/// Flag to track if WebSocket is disabled due to repeated failures
+static WEBSOCKET_DISABLED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Delay before reconnect attempt `attempt` (counting from 1)
+///
+/// The delay doubles from [`BACKOFF_BASE`] up to [`BACKOFF_CAP`] and `jitter`
+/// (0..1) takes up to half of it off, so terminals dropped by the same server
+/// restart don't all come back in the same instant.
+pub fn backoff_delay(attempt: u64, jitter: f64) -> Duration {
+    let doublings = attempt.saturating_sub(1).min(31) as u32;
+    let ceiling = BACKOFF_BASE.saturating_mul(1u32 << doublings).min(BACKOFF_CAP);
+    ceiling.mul_f64(1.0 - 0.5 * jitter.clamp(0.0, 1.0))
 }
 
-/// WebSocket URL for price streaming
-fn price_stream_url() -> String {
-    let base_url = std::env::var("API_BASE_URL")
-        .unwrap_or_else(|_| "http://localhost:3001".to_string());
-    base_url
-        .replace("http://", "ws://")
-        .replace("https://", "wss://")
-        + "/api/ws/prices"
+/// Delay before reconnecting to a server that said it was restarting
+///
+/// Between [`RESTART_BACKOFF`] and twice that, spread by `jitter` (0..1) so
+/// the terminals it closed don't all reconnect at once when it's back.
+pub fn restart_delay(jitter: f64) -> Duration {
+    RESTART_BACKOFF + RESTART_BACKOFF.mul_f64(jitter.clamp(0.0, 1.0))
+}
+
+/// Whether a close frame's reason says the server is restarting
+pub fn is_restart_close(reason: &str) -> bool {
+    reason == SERVER_RESTARTING_REASON
+}
+
+/// How long to keep waiting for a frame on a connection last heard from at
+/// `last_message_at`; `None` once it has been silent for [`STALL_TIMEOUT`]
+pub fn stall_remaining(last_message_at: Instant, now: Instant) -> Option<Duration> {
+    STALL_TIMEOUT
+        .checked_sub(now.saturating_duration_since(last_message_at))
+        .filter(|remaining| !remaining.is_zero())
+}
+
+/// Report a connection state change to the UI
+async fn send_state(
+    event_tx: &Sender<AppEvent>,
+    state: WebSocketState,
+    attempts: u64,
+    retry_in: Option<Duration>,
+    error: Option<String>,
+) {
+    let _ = event_tx.send(AppEvent::WebSocketStateChanged { state, attempts, retry_in, error }).await;
+}
+
+/// Handshake request for `url`, authenticated with the session's JWT when logged in
+///
+/// The JWT is read again for every attempt, so a reconnect after a session
+/// refresh uses the new token.
+fn stream_request(
+    url: &str,
+    app_state: Option<&Arc<RwLock<AppState>>>,
+) -> Result<tokio_tungstenite::tungstenite::handshake::client::Request, tokio_tungstenite::tungstenite::Error> {
+    let mut request = url.into_client_request()?;
+    let jwt = app_state.and_then(|state| state.read().auth_token.clone());
+    if let Some(value) = jwt.and_then(|jwt| HeaderValue::from_str(&format!("Bearer {}", jwt)).ok()) {
+        request.headers_mut().insert(AUTHORIZATION, value);
+    }
+    Ok(request)
+}
+
+/// Subscription frames to send after (re)connecting: the price store's
+/// symbols, plus the swap panel's quote stream
+fn resubscribe_frames(app_state: Option<&Arc<RwLock<AppState>>>, symbols: &BTreeSet<String>) -> Vec<String> {
+    let mut frames = Vec::new();
+    if !symbols.is_empty() {
+        match serde_json::to_string(&PriceSubscribeMessage::new(symbols.iter().cloned().collect())) {
+            Ok(json) => frames.push(json),
+            Err(e) => error!(error = %e, "Failed to serialize price subscription"),
+        }
+    }
+    let quote_stream = app_state.and_then(|state| state.read().terminal.swap.quote_stream.clone());
+    if let Some(subscription) = quote_stream {
+        match serde_json::to_string(&QuoteSubscribeMessage::new(subscription)) {
+            Ok(json) => frames.push(json),
+            Err(e) => error!(error = %e, "Failed to serialize quote subscription"),
+        }
+    }
+    frames
+}
+
+/// Symbols to subscribe to after (re)connecting: what the price store holds
+fn active_symbols(app_state: Option<&Arc<RwLock<AppState>>>) -> BTreeSet<String> {
+    app_state
+        .map(|state| state.read().terminal.prices.load().iter().map(|price| price.symbol.clone()).collect())
+        .unwrap_or_default()
 }
 
 /// Connect to price stream WebSocket and forward updates to event channel.
 ///
 /// This function handles:
-/// - WebSocket connection establishment
-/// - Automatic reconnection on disconnect (with max retry limit)
+/// - WebSocket connection establishment, with the JWT when logged in
+/// - Reconnection with jittered exponential backoff ([`backoff_delay`]),
+///   reset by every successful connect
+/// - Resubscribing to the symbols streamed before a reconnect
+/// - Disabling the stream after [`MAX_CONNECTION_ATTEMPTS`] failures in a row
 /// - Message parsing and forwarding
-/// - Error handling and logging
+///
+/// Every state change is sent as [`AppEvent::WebSocketStateChanged`].
 ///
 /// # Arguments
 /// * `event_tx` - Channel sender for price update events
-///
-/// Global counter for total price update messages received
-pub static MESSAGE_COUNTER: AtomicU64 = AtomicU64::new(0);
-/// Counter for reconnection attempts
-pub static RECONNECT_COUNTER: AtomicU64 = AtomicU64::new(0);
-/// Maximum number of connection attempts before giving up
-const MAX_CONNECTION_ATTEMPTS: u64 = 5;
-/// Flag to track if WebSocket is disabled due to repeated failures
-static WEBSOCKET_DISABLED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
-
+/// * `app_state` - Shared state holding the JWT and the price store
 pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<Arc<RwLock<AppState>>>) {
     // Check if WebSocket is disabled
     if WEBSOCKET_DISABLED.load(Ordering::Relaxed) {
@@ -70,66 +183,29 @@ pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<
         return;
     }
 
-    let url = price_stream_url();
+    let url = price_stream_url(app_state.as_ref());
     info!(url = %url, "Connecting to price stream WebSocket");
     
     // Clone app_state for use in the loop (needed because it's moved into the connection handler)
     let app_state_for_loop = app_state.clone();
     
-    // Update status to Connecting
-    if let Some(state) = app_state_for_loop.as_ref() {
-        let mut ws_status = state.write().websocket_status.clone();
-        ws_status.state = WebSocketState::Connecting;
-        ws_status.connection_attempts += 1;
-        state.write().websocket_status = ws_status.clone();
-        let _ = event_tx.send(AppEvent::WebSocketStatusUpdate(ws_status)).await;
-    }
-    
-    let mut reconnect_delay = Duration::from_secs(1);
-    const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
-    let mut total_attempts = 0u64;
+    send_state(&event_tx, WebSocketState::Connecting, 0, None, None).await;
+
+    // Failed attempts since the last successful connect
+    let mut failures = 0u64;
+    // Symbols streamed so far, sent again as a subscription after a reconnect
+    let mut subscriptions = BTreeSet::new();
+    // Whether the last connection was closed by a restarting server
+    let mut server_restarting = false;
     
     loop {
-        total_attempts += 1;
         let attempt = RECONNECT_COUNTER.fetch_add(1, Ordering::Relaxed) + 1;
-        
-        // Update status to Reconnecting if not first attempt
-        if total_attempts > 1 {
-            if let Some(state) = app_state_for_loop.as_ref() {
-                let mut ws_status = state.write().websocket_status.clone();
-                ws_status.state = WebSocketState::Reconnecting;
-                ws_status.connection_attempts = total_attempts;
-                state.write().websocket_status = ws_status.clone();
-                let _ = event_tx.send(AppEvent::WebSocketStatusUpdate(ws_status)).await;
-            }
-        }
-        
-        // Check if we've exceeded max attempts
-        if total_attempts > MAX_CONNECTION_ATTEMPTS {
-            error!(
-                url = %url,
-                total_attempts = total_attempts,
-                "WebSocket connection failed after {} attempts. Disabling WebSocket to prevent UI blocking.",
-                MAX_CONNECTION_ATTEMPTS
-            );
-            WEBSOCKET_DISABLED.store(true, Ordering::Relaxed);
-            
-            // Update status to Disabled
-            if let Some(state) = app_state_for_loop.as_ref() {
-                let mut ws_status = state.write().websocket_status.clone();
-                ws_status.state = WebSocketState::Disabled;
-                state.write().websocket_status = ws_status.clone();
-                let _ = event_tx.send(AppEvent::WebSocketStatusUpdate(ws_status)).await;
-            }
-            
-            // Send notification to UI
-            let _ = event_tx.send(AppEvent::Loading(format!(
-                "NOTIFY_WARNING:WebSocket connection unavailable after {} attempts. Price updates disabled.",
-                MAX_CONNECTION_ATTEMPTS
-            ))).await;
-            return;
-        }
-        match connect_async(&url).await {
+
+        let connection = match stream_request(&url, app_state_for_loop.as_ref()) {
+            Ok(request) => connect_async(request).await,
+            Err(e) => Err(e),
+        };
+        let error_msg = match connection {
             Ok((ws_stream, response)) => {
                 info!(
                     url = %url,
@@ -137,32 +213,67 @@ pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<
                     attempt = attempt,
                     "WebSocket connection established successfully"
                 );
-                reconnect_delay = Duration::from_secs(1); // Reset delay on successful connection
+                failures = 0; // Reset backoff on successful connection
                 RECONNECT_COUNTER.store(0, Ordering::Relaxed); // Reset counter on success
+                send_state(&event_tx, WebSocketState::Connected, 0, None, None).await;
                 
-                // Update status to Connected
+                let (mut write, mut read) = ws_stream.split();
+
+                subscriptions.extend(active_symbols(app_state_for_loop.as_ref()));
+                // Frames queued from here on go out on this connection
+                let (outbox, outbox_rx) = async_channel::unbounded::<String>();
                 if let Some(state) = app_state_for_loop.as_ref() {
-                    let mut ws_status = state.write().websocket_status.clone();
-                    ws_status.state = WebSocketState::Connected;
-                    ws_status.last_connected = Some(std::time::Instant::now());
-                    ws_status.last_error = None;
-                    state.write().websocket_status = ws_status.clone();
-                    state.write().websocket_connected = true;
-                    let _ = event_tx.send(AppEvent::WebSocketStatusUpdate(ws_status)).await;
+                    state.write().price_stream_outbox = Some(outbox);
+                }
+                for json in resubscribe_frames(app_state_for_loop.as_ref(), &subscriptions) {
+                    if let Err(e) = write.send(Message::Text(json)).await {
+                        warn!(error = %e, "Failed to resubscribe to price stream");
+                    } else {
+                        info!(symbols = subscriptions.len(), "Resubscribed to price stream");
+                    }
                 }
-                
-                // Send connection success event to update UI state
-                // Note: We can't update state directly here, but the connection is established
-                // The UI should check websocket_connected flag which is set when connection starts
-                
-                let (mut write, mut read) = ws_stream.split();
                 
                 // Spawn task to handle incoming messages
                 let event_tx_clone = event_tx.clone();
                 let app_state_for_read = app_state_for_loop.clone();
+                // Prices are published straight to the lock-free store; the event
+                // channel only gets a nudge so the UI knows to repaint
+                let price_store = app_state_for_loop.as_ref().map(|s| s.read().terminal.prices.clone());
                 let read_task = tokio::spawn(async move {
                     let mut message_count = 0u64;
-                    while let Some(msg) = read.next().await {
+                    let mut seen = BTreeSet::new();
+                    let mut stalled = false;
+                    let mut restarting = false;
+                    let mut last_message_at = Instant::now();
+                    loop {
+                        let Some(remaining) = stall_remaining(last_message_at, Instant::now()) else {
+                            warn!(
+                                timeout_secs = STALL_TIMEOUT.as_secs(),
+                                message_count = message_count,
+                                "No WebSocket messages (including pings) within the stall timeout, reconnecting"
+                            );
+                            stalled = true;
+                            break;
+                        };
+                        let msg = tokio::select! {
+                            read = tokio::time::timeout(remaining, read.next()) => match read {
+                                Ok(Some(msg)) => msg,
+                                Ok(None) => break,
+                                // Checked again at the top of the loop
+                                Err(_) => continue,
+                            },
+                            Ok(frame) = outbox_rx.recv() => {
+                                if let Err(e) = write.send(Message::Text(frame)).await {
+                                    warn!(error = %e, "Failed to send frame on the price stream");
+                                    break;
+                                }
+                                continue;
+                            }
+                        };
+                        last_message_at = Instant::now();
+                        if let Some(state) = app_state_for_read.as_ref() {
+                            state.write().websocket_status.last_message_at = Some(last_message_at);
+                        }
                         match msg {
                             Ok(Message::Text(text)) => {
                                 debug!(
@@ -170,6 +281,37 @@ pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<
                                     message_preview = if text.len() > 200 { format!("{}...", &text[..200]) } else { text.clone() },
                                     "Received WebSocket text message"
                                 );
+                                if let Ok(quote) = serde_json::from_str::<QuoteUpdateMessage>(&text) {
+                                    if quote.message_type == QuoteUpdateMessage::TYPE {
+                                        // A newer one follows within seconds if this is dropped
+                                        event_queue::send_or_drop(&event_tx_clone, AppEvent::QuoteUpdated(quote.data)).await;
+                                        continue;
+                                    }
+                                }
+                                if let Ok(candle) = serde_json::from_str::<CandleUpdateMessage>(&text) {
+                                    if candle.message_type == CandleUpdateMessage::TYPE {
+                                        let update = candle.data;
+                                        let event = AppEvent::CandleUpdated(update.symbol, update.timeframe, update.candle, update.is_closed);
+                                        if update.is_closed {
+                                            if let Err(e) = event_tx_clone.send(event).await {
+                                                error!(error = %e, "Failed to send CandleUpdated event to event channel");
+                                            }
+                                        } else {
+                                            // The next tick supersedes a dropped forming candle
+                                            event_queue::send_or_drop(&event_tx_clone, event).await;
+                                        }
+                                        continue;
+                                    }
+                                }
+                                // System notices share the socket with price updates
+                                if let Ok(notice) = serde_json::from_str::<shared::SystemNoticeMessage>(&text) {
+                                    if notice.message_type == shared::SystemNoticeMessage::TYPE {
+                                        if let Err(e) = event_tx_clone.send(AppEvent::SystemNotice(notice.data)).await {
+                                            error!(error = %e, "Failed to send SystemNotice event to event channel");
+                                        }
+                                        continue;
+                                    }
+                                }
                                 match serde_json::from_str::<PriceUpdateMessage>(&text) {
                                     Ok(update) => {
                                         debug!(
@@ -178,8 +320,9 @@ pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<
                                             price = update.data.price,
                                             "Parsed WebSocket message successfully"
                                         );
-                                        if update.message_type == "price_update" {
+                                        if update.message_type == PriceUpdateMessage::TYPE {
                                             message_count += 1;
+                                            seen.insert(update.data.symbol.clone());
                                             let total_messages = MESSAGE_COUNTER.fetch_add(1, Ordering::Relaxed) + 1;
                                             
                                             let price_data = PriceData {
@@ -188,6 +331,11 @@ pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<
                                                 change_24h: 0.0, // 24h change not in stream
                                                 previous_price: None,
                                                 source: Some(update.data.source.clone()),
+                                                confidence: update.data.confidence,
+                                                publish_time: update.data.publish_time,
+                                                sources: update.data.sources.clone(),
+                                                divergent: update.data.divergent,
+                                                divergence_pct: update.data.divergence_pct,
                                             };
                                             
                                             info!(
@@ -211,13 +359,11 @@ pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<
                                                     new_count = ws_status.messages_received,
                                                     "Updated WebSocket status - message count incremented"
                                                 );
-                                                match event_tx_clone.send(AppEvent::WebSocketStatusUpdate(ws_status)).await {
-                                                    Ok(_) => {
-                                                        debug!("WebSocket status update event sent successfully");
-                                                    }
-                                                    Err(e) => {
-                                                        error!(error = %e, "Failed to send WebSocket status update event");
-                                                    }
+                                                // Sent per message, so it may be dropped when the queue is full
+                                                if event_queue::send_or_drop(&event_tx_clone, AppEvent::WebSocketStatusUpdate(ws_status)).await {
+                                                    debug!("WebSocket status update event sent successfully");
+                                                } else {
+                                                    debug!("WebSocket status update event not delivered");
                                                 }
                                             } else {
                                                 warn!("App state not available for WebSocket status update");
@@ -228,27 +374,31 @@ pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<
                                                 symbol = %price_data.symbol,
                                                 price = price_data.price,
                                                 timestamp = price_data.change_24h, // Using as placeholder for timestamp
-                                                "Sending PriceUpdated event to event channel for immediate processing"
+                                                "Publishing price update and notifying event channel"
                                             );
-                                            match event_tx_clone.send(AppEvent::PriceUpdated(price_data)).await {
-                                                Ok(_) => {
-                                                    // Log at debug level to avoid spam, but ensure we can track if needed
-                                                    debug!(
-                                                        symbol = %update.data.symbol,
-                                                        price = update.data.price,
-                                                        message_count = message_count,
-                                                        "PriceUpdated event sent successfully - will trigger immediate UI repaint"
-                                                    );
-                                                }
-                                                Err(e) => {
-                                                    error!(
-                                                        error = %e,
-                                                        symbol = %update.data.symbol,
-                                                        price = update.data.price,
-                                                        message_count = message_count,
-                                                        "CRITICAL: Failed to send PriceUpdated event to event channel - price update will be lost"
-                                                    );
+                                            let event = match price_store.as_ref() {
+                                                Some(store) => {
+                                                    store.apply(std::slice::from_ref(&price_data));
+                                                    AppEvent::PricesChanged
                                                 }
+                                                None => AppEvent::PriceUpdated(price_data),
+                                            };
+                                            // Never waits: a full queue coalesces the nudge
+                                            if event_queue::send_price(&event_tx_clone, event).await {
+                                                // Log at debug level to avoid spam, but ensure we can track if needed
+                                                debug!(
+                                                    symbol = %update.data.symbol,
+                                                    price = update.data.price,
+                                                    message_count = message_count,
+                                                    "Price event sent successfully - will trigger immediate UI repaint"
+                                                );
+                                            } else {
+                                                error!(
+                                                    symbol = %update.data.symbol,
+                                                    price = update.data.price,
+                                                    message_count = message_count,
+                                                    "CRITICAL: Failed to send price event to event channel - UI will not repaint for this update"
+                                                );
                                             }
                                         } else {
                                             debug!(
@@ -270,6 +420,7 @@ pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<
                             Ok(Message::Close(frame)) => {
                                 let close_code = frame.as_ref().map(|f| f.code);
                                 let close_reason = frame.as_ref().map(|f| f.reason.to_string());
+                                restarting = close_reason.as_deref().is_some_and(is_restart_close);
                                 info!(
                                     code = ?close_code,
                                     reason = ?close_reason,
@@ -307,18 +458,43 @@ pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<
                     }
                     info!(
                         message_count = message_count,
+                        stalled = stalled,
+                        restarting = restarting,
                         "WebSocket read task ended"
                     );
+                    (seen, stalled, restarting)
                 });
                 
-                // Wait for read task to complete (connection closed)
-                read_task.await.ok();
+                // Wait for read task to complete (connection closed). The guard
+                // stops it too if this task is aborted on logout.
+                let mut read_task = AbortOnDrop(read_task);
+                let (stalled, restarting) = match (&mut read_task.0).await {
+                    Ok((seen, stalled, restarting)) => {
+                        subscriptions.extend(seen);
+                        (stalled, restarting)
+                    }
+                    Err(_) => (false, false),
+                };
+                server_restarting = restarting;
+                if let Some(state) = app_state_for_loop.as_ref() {
+                    state.write().price_stream_outbox = None;
+                }
                 warn!(
                     attempt = attempt,
+                    stalled = stalled,
                     "WebSocket connection lost, reconnecting..."
                 );
+                if restarting {
+                    "Server restarting".to_string()
+                } else if stalled {
+                    format!("No messages for {}s", STALL_TIMEOUT.as_secs())
+                } else {
+                    "Connection lost".to_string()
+                }
             }
             Err(e) => {
+                failures += 1;
+                server_restarting = false;
                 let error_msg = format!("{}", e);
                 let error_description = if error_msg.contains("500") {
                     "HTTP error: 500 Internal Server Error"
@@ -330,37 +506,23 @@ pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<
                     url = %url,
                     error = %e,
                     attempt = attempt,
-                    total_attempts = total_attempts,
+                    failures = failures,
                     max_attempts = MAX_CONNECTION_ATTEMPTS,
-                    reconnect_delay_secs = reconnect_delay.as_secs(),
-                    "Failed to connect to price stream WebSocket, url: {}, error: {}, attempt: {}, reconnect_delay_secs: {}",
+                    "Failed to connect to price stream WebSocket, url: {}, error: {}, failures: {}",
                     url,
                     error_description,
-                    attempt,
-                    reconnect_delay.as_secs()
+                    failures
                 );
                 
-                // Update status with error
-                if let Some(state) = app_state_for_loop.as_ref() {
-                    let mut ws_status = state.write().websocket_status.clone();
-                    ws_status.last_error = Some(error_msg.clone());
-                    ws_status.state = if total_attempts >= MAX_CONNECTION_ATTEMPTS {
-                        WebSocketState::Disabled
-                    } else {
-                        WebSocketState::Reconnecting
-                    };
-                    state.write().websocket_status = ws_status.clone();
-                    let _ = event_tx.send(AppEvent::WebSocketStatusUpdate(ws_status)).await;
-                }
-                
                 // Check if we should give up
-                if total_attempts >= MAX_CONNECTION_ATTEMPTS {
+                if failures >= MAX_CONNECTION_ATTEMPTS {
                     error!(
                         url = %url,
-                        total_attempts = total_attempts,
+                        failures = failures,
                         "Maximum connection attempts reached. Disabling WebSocket."
                     );
                     WEBSOCKET_DISABLED.store(true, Ordering::Relaxed);
+                    send_state(&event_tx, WebSocketState::Disabled, failures, None, Some(error_msg)).await;
                     // Send notification to UI
                     let _ = event_tx.send(AppEvent::Loading(format!(
                         "NOTIFY_WARNING:WebSocket connection failed after {} attempts. Price updates disabled.",
@@ -368,33 +530,134 @@ pub async fn connect_price_stream(event_tx: Sender<AppEvent>, app_state: Option<
                     ))).await;
                     return;
                 }
+                error_msg
             }
-        }
+        };
         
-        // Exponential backoff for reconnection (only if we haven't exceeded max attempts)
-        if total_attempts < MAX_CONNECTION_ATTEMPTS {
-            info!(
-                attempt = attempt,
-                total_attempts = total_attempts,
-                max_attempts = MAX_CONNECTION_ATTEMPTS,
-                delay_secs = reconnect_delay.as_secs(),
-                "Reconnecting in {}s..., attempt: {}, delay_secs: {}",
-                reconnect_delay.as_secs(),
-                attempt,
-                reconnect_delay.as_secs()
-            );
-            sleep(reconnect_delay).await;
-            reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+        let delay = if server_restarting {
+            restart_delay(rand::random::<f64>())
         } else {
-            break;
-        }
+            backoff_delay(failures.max(1), rand::random::<f64>())
+        };
+        info!(
+            attempt = attempt,
+            failures = failures,
+            max_attempts = MAX_CONNECTION_ATTEMPTS,
+            delay_ms = delay.as_millis() as u64,
+            "Reconnecting price stream in {}ms",
+            delay.as_millis()
+        );
+        send_state(&event_tx, WebSocketState::Reconnecting, failures, Some(delay), Some(error_msg)).await;
+        sleep(delay).await;
+    }
+}
+
+/// Aborts the wrapped task when dropped
+struct AbortOnDrop<T>(tokio::task::JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
     }
 }
 
-/// Reset WebSocket disabled flag (for testing or manual retry)
-#[allow(dead_code)]
+/// Start the price stream in the background after a short delay (lets the UI
+/// initialize first).
+///
+/// # Returns
+///
+/// Handle that stops the stream, see [`disconnect_price_stream`].
+pub fn spawn_price_stream(event_tx: Sender<AppEvent>, app_state: Arc<RwLock<AppState>>) -> tokio::task::AbortHandle {
+    tokio::spawn(async move {
+        sleep(Duration::from_millis(500)).await;
+        connect_price_stream(event_tx, Some(app_state)).await;
+    })
+    .abort_handle()
+}
+
+/// Stop the price stream started by [`spawn_price_stream`], if any.
+pub fn disconnect_price_stream(state: &mut AppState) {
+    if let Some(task) = state.price_stream_task.take() {
+        task.abort();
+        info!("Price stream disconnected");
+    }
+    state.websocket_connected = false;
+    state.websocket_status = crate::app::state::WebSocketStatus::default();
+    state.price_stream_outbox = None;
+}
+
+/// Reset WebSocket disabled flag (manual retry, or after a server switch)
 pub fn reset_websocket_disabled() {
     WEBSOCKET_DISABLED.store(false, Ordering::Relaxed);
     RECONNECT_COUNTER.store(0, Ordering::Relaxed);
 }
 
+/// Drop the current price stream, if any, and connect again from scratch
+///
+/// Used by the Reconnect button once the stream gave up, and after a
+/// server switch.
+pub fn restart_price_stream(state: &mut AppState, event_tx: Sender<AppEvent>, app_state: Arc<RwLock<AppState>>) {
+    // The offline demo polls its mock instead
+    if state.offline {
+        return;
+    }
+    disconnect_price_stream(state);
+    reset_websocket_disabled();
+    state.websocket_connected = true;
+    state.price_stream_task = Some(spawn_price_stream(event_tx, app_state));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_schedule_doubles_up_to_cap() {
+        let schedule: Vec<u64> = (1..=10).map(|attempt| backoff_delay(attempt, 0.0).as_millis() as u64).collect();
+        assert_eq!(schedule, vec![250, 500, 1000, 2000, 4000, 8000, 16000, 30000, 30000, 30000]);
+        assert_eq!(backoff_delay(0, 0.0), BACKOFF_BASE);
+        assert_eq!(backoff_delay(u64::MAX, 0.0), BACKOFF_CAP);
+    }
+
+    #[test]
+    fn test_backoff_jitter_takes_off_up_to_half() {
+        for attempt in 1..=12 {
+            let full = backoff_delay(attempt, 0.0);
+            let least = backoff_delay(attempt, 1.0);
+            assert_eq!(least, full / 2);
+            let jittered = backoff_delay(attempt, 0.37);
+            assert!(jittered < full && jittered > least, "attempt {}: {:?}", attempt, jittered);
+        }
+        // Out-of-range jitter is clamped
+        assert_eq!(backoff_delay(3, 7.0), backoff_delay(3, 1.0));
+        assert_eq!(backoff_delay(3, -1.0), backoff_delay(3, 0.0));
+        assert!(backoff_delay(40, 0.0) <= BACKOFF_CAP);
+    }
+
+    #[test]
+    fn test_restart_delay_waits_at_least_the_restart_backoff() {
+        assert_eq!(restart_delay(0.0), RESTART_BACKOFF);
+        assert_eq!(restart_delay(1.0), RESTART_BACKOFF * 2);
+        assert!(restart_delay(0.5) > RESTART_BACKOFF && restart_delay(0.5) < RESTART_BACKOFF * 2);
+        assert_eq!(restart_delay(-3.0), RESTART_BACKOFF);
+        // Longer than the first few ordinary retries, which would hit a server still down
+        assert!(restart_delay(0.0) > backoff_delay(4, 0.0));
+
+        assert!(is_restart_close(SERVER_RESTARTING_REASON));
+        assert!(!is_restart_close(""));
+        assert!(!is_restart_close("going away"));
+    }
+
+    #[test]
+    fn test_stall_remaining_counts_down_to_timeout() {
+        let last = Instant::now();
+        assert_eq!(stall_remaining(last, last), Some(STALL_TIMEOUT));
+        assert_eq!(stall_remaining(last, last + Duration::from_secs(10)), Some(Duration::from_secs(20)));
+        assert_eq!(stall_remaining(last, last + STALL_TIMEOUT - Duration::from_millis(1)), Some(Duration::from_millis(1)));
+        assert_eq!(stall_remaining(last, last + STALL_TIMEOUT), None);
+        assert_eq!(stall_remaining(last, last + STALL_TIMEOUT * 2), None);
+        // A message stamped after `now` (clock read order) is not a stall
+        assert_eq!(stall_remaining(last + Duration::from_secs(1), last), Some(STALL_TIMEOUT));
+    }
+}
+
@@ -2,10 +2,21 @@
 //!
 //! Client for Braid HTTP protocol - handles SSE subscriptions and PUT requests for messaging.
 
-use shared::dto::messaging::Message;
+use shared::dto::messaging::{AiStreamEvent, Message, MessageReceipt};
 use tokio::sync::mpsc;
 use futures_util::StreamExt;
 
+/// Update received on a conversation subscription
+#[derive(Debug, Clone)]
+pub enum BraidEvent {
+    /// Messages and the version they bring the conversation to
+    Messages(Vec<Message>, String),
+    /// The other participant's delivered or read receipt
+    Receipt(MessageReceipt),
+    /// Part of an AI bot reply that is still being generated
+    AiStream(AiStreamEvent),
+}
+
 /// Braid client for a single conversation
 pub struct BraidClient {
     conversation_id: String,
@@ -25,8 +36,8 @@ impl BraidClient {
     }
 
     /// Subscribe to conversation updates via SSE
-    /// Returns a receiver channel that receives message updates
-    pub async fn subscribe(&mut self) -> Result<mpsc::Receiver<(Vec<Message>, String)>, String> {
+    /// Returns a receiver channel that receives message updates and receipts
+    pub async fn subscribe(&mut self) -> Result<mpsc::Receiver<BraidEvent>, String> {
         let (tx, rx) = mpsc::channel(100);
         let conversation_id = self.conversation_id.clone();
         let token = self.token.clone();
@@ -81,20 +92,8 @@ impl BraidClient {
                                 
                                 match serde_json::from_str::<serde_json::Value>(json_str) {
                                     Ok(event_data) => {
-                                        if let (Some(version), Some(messages_array)) = (
-                                            event_data.get("version").and_then(|v| v.as_str()),
-                                            event_data.get("messages").and_then(|m| m.as_array()),
-                                        ) {
-                                            let version = version.to_string();
-                                            
-                                            let messages: Vec<Message> = messages_array
-                                                .iter()
-                                                .filter_map(|m| {
-                                                    serde_json::from_value(m.clone()).ok()
-                                                })
-                                                .collect();
-                                            
-                                            if let Err(_) = tx.send((messages, version)).await {
+                                        for event in parse_event(&event_data) {
+                                            if tx.send(event).await.is_err() {
                                                 // Receiver dropped, stop subscription
                                                 return;
                                             }
@@ -203,3 +202,79 @@ impl BraidClient {
     }
 }
 
+/// Updates carried by one SSE event
+///
+/// The first event of a subscription is a snapshot that may list the other
+/// participant's current receipts under `receipts`; later events carry
+/// either messages, a single `receipt`, or an `ai_stream` chunk.
+fn parse_event(event_data: &serde_json::Value) -> Vec<BraidEvent> {
+    let mut events = Vec::new();
+    
+    if let (Some(version), Some(messages_array)) = (
+        event_data.get("version").and_then(|v| v.as_str()),
+        event_data.get("messages").and_then(|m| m.as_array()),
+    ) {
+        let messages: Vec<Message> = messages_array
+            .iter()
+            .filter_map(|m| serde_json::from_value(m.clone()).ok())
+            .collect();
+        events.push(BraidEvent::Messages(messages, version.to_string()));
+    }
+    
+    let receipts = event_data
+        .get("receipts")
+        .and_then(|r| r.as_array())
+        .into_iter()
+        .flatten()
+        .chain(event_data.get("receipt"));
+    for receipt in receipts {
+        if let Ok(receipt) = serde_json::from_value::<MessageReceipt>(receipt.clone()) {
+            events.push(BraidEvent::Receipt(receipt));
+        }
+    }
+    
+    if let Some(stream_event) = event_data.get("ai_stream") {
+        if let Ok(stream_event) = serde_json::from_value::<AiStreamEvent>(stream_event.clone()) {
+            events.push(BraidEvent::AiStream(stream_event));
+        }
+    }
+    
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::dto::messaging::{AiStreamOutcome, ReceiptStatus};
+
+    #[test]
+    fn test_parse_snapshot_and_receipt_events() {
+        let snapshot = serde_json::json!({
+            "version": "v2",
+            "messages": [{"text": "gm", "author": "bob", "author_id": 2, "timestamp": "2025-02-21T09:00:00+00:00", "version": "v2"}],
+            "receipts": [{"user_id": 2, "version": "v1", "status": "read"}],
+        });
+        let events = parse_event(&snapshot);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], BraidEvent::Messages(messages, version) if messages.len() == 1 && version == "v2"));
+        assert!(matches!(&events[1], BraidEvent::Receipt(r) if r.status == ReceiptStatus::Read && r.version == "v1"));
+
+        let receipt = serde_json::json!({"receipt": {"user_id": 2, "version": "v3", "status": "delivered"}});
+        let events = parse_event(&receipt);
+        assert!(matches!(&events[..], [BraidEvent::Receipt(r)] if r.status == ReceiptStatus::Delivered));
+    }
+
+    #[test]
+    fn test_parse_ai_stream_events() {
+        let chunk = serde_json::json!({"ai_stream": {"kind": "chunk", "delta": "SOL is "}});
+        let events = parse_event(&chunk);
+        assert!(matches!(&events[..], [BraidEvent::AiStream(AiStreamEvent::Chunk { delta })] if delta == "SOL is "));
+
+        let end = serde_json::json!({"ai_stream": {"kind": "end", "outcome": {"status": "failed", "error": "timeout"}}});
+        let events = parse_event(&end);
+        assert!(matches!(
+            &events[..],
+            [BraidEvent::AiStream(AiStreamEvent::End { outcome: AiStreamOutcome::Failed { error } })] if error == "timeout"
+        ));
+    }
+}
@@ -0,0 +1,476 @@
+//! # Encrypted Seed Keystore
+//!
+//! Stores an imported mnemonic seed encrypted at rest and derives Solana
+//! accounts from it on demand.
+//!
+//! ## Derivation
+//!
+//! Accounts use the standard Solana BIP44 path `m/44'/501'/{index}'/0'`, the
+//! same layout used by `solana-keygen` and most browser wallets, so indexes
+//! line up with accounts created elsewhere from the same phrase.
+//!
+//! ## Security Model
+//!
+//! - The seed is encrypted with AES-256-GCM-SIV under a key derived from the
+//!   user's password with Argon2id.
+//! - The decrypted seed only exists inside [`Keystore::with_seed`] and is
+//!   zeroized when the derivation call returns.
+//! - Derived public addresses (and user labels) are cached in the keystore so
+//!   the derived-accounts list can be shown without unlocking.
+//!
+//! ## File Format
+//!
+//! `{version, seed: {salt, nonce, ciphertext}, accounts: [...]}`, kept in its
+//! own file next to the app. Wallets from before the keystore are plain Solana
+//! keypair files and keep loading through
+//! [`WalletService::load_keypair_from_file`](crate::services::wallet::WalletService::load_keypair_from_file);
+//! handing one to [`Keystore::from_json`] is reported as such instead of as a
+//! corrupt keystore.
+
+use aes_gcm_siv::aead::{Aead, KeyInit};
+use aes_gcm_siv::{Aes256GcmSiv, Nonce};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use solana_derivation_path::DerivationPath;
+use solana_sdk::signature::{Keypair, Signer};
+use std::fmt;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// Current keystore file format version
+pub const KEYSTORE_VERSION: u32 = 1;
+
+/// Number of account indexes scanned by default
+pub const DEFAULT_SCAN_COUNT: u32 = 5;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// Keystore errors
+#[derive(Debug)]
+pub enum KeystoreError {
+    /// Mnemonic phrase is malformed
+    InvalidMnemonic(String),
+    /// Password did not decrypt the seed
+    WrongPassword,
+    /// Key derivation or encryption failure
+    Crypto(String),
+    /// Unknown or corrupt file format
+    Format(String),
+    /// Derived key does not match the cached address
+    AddressMismatch { index: u32, expected: String, derived: String },
+    /// File I/O error
+    IoError(std::io::Error),
+}
+
+impl fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeystoreError::InvalidMnemonic(msg) => write!(f, "Invalid mnemonic: {}", msg),
+            KeystoreError::WrongPassword => write!(f, "Incorrect keystore password"),
+            KeystoreError::Crypto(msg) => write!(f, "Keystore crypto error: {}", msg),
+            KeystoreError::Format(msg) => write!(f, "Keystore format error: {}", msg),
+            KeystoreError::AddressMismatch { index, expected, derived } => write!(
+                f,
+                "Account #{} derived {} but keystore expected {}",
+                index, derived, expected
+            ),
+            KeystoreError::IoError(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for KeystoreError {}
+
+impl From<std::io::Error> for KeystoreError {
+    fn from(err: std::io::Error) -> Self {
+        KeystoreError::IoError(err)
+    }
+}
+
+/// Encrypted seed blob (all fields base64)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EncryptedSeed {
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// Cached public info for a derived account
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DerivedAccount {
+    pub index: u32,
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Encrypted seed plus the derived-account cache
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Keystore {
+    pub version: u32,
+    pub seed: EncryptedSeed,
+    #[serde(default)]
+    pub accounts: Vec<DerivedAccount>,
+}
+
+/// Get keystore file path
+pub fn get_keystore_path() -> std::path::PathBuf {
+    std::path::PathBuf::from("./xterminal-keystore.json")
+}
+
+/// Derivation path for an account index (`m/44'/501'/{index}'/0'`)
+pub fn derivation_path(index: u32) -> DerivationPath {
+    DerivationPath::new_bip44(Some(index), Some(0))
+}
+
+/// Derive the keypair for an account index from a raw seed
+pub fn derive_keypair(seed: &[u8], index: u32) -> Result<Keypair, KeystoreError> {
+    solana_keypair::seed_derivable::keypair_from_seed_and_derivation_path(seed, Some(derivation_path(index)))
+        .map_err(|e| KeystoreError::Crypto(format!("Derivation failed for index {}: {}", index, e)))
+}
+
+/// Normalize a mnemonic (collapse whitespace, lowercase) and check its word count
+fn normalize_mnemonic(phrase: &str) -> Result<String, KeystoreError> {
+    let words: Vec<String> = phrase.split_whitespace().map(|w| w.to_lowercase()).collect();
+    if ![12, 15, 18, 21, 24].contains(&words.len()) {
+        return Err(KeystoreError::InvalidMnemonic(format!(
+            "expected 12, 15, 18, 21 or 24 words, got {}",
+            words.len()
+        )));
+    }
+    if words.iter().any(|w| !w.chars().all(|c| c.is_ascii_lowercase())) {
+        return Err(KeystoreError::InvalidMnemonic("words must contain only letters".to_string()));
+    }
+    Ok(words.join(" "))
+}
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; KEY_LEN]>, KeystoreError> {
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    argon2::Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, key.as_mut())
+        .map_err(|e| KeystoreError::Crypto(e.to_string()))?;
+    Ok(key)
+}
+
+fn decode_field(name: &str, value: &str) -> Result<Vec<u8>, KeystoreError> {
+    BASE64
+        .decode(value)
+        .map_err(|e| KeystoreError::Format(format!("Invalid {} encoding: {}", name, e)))
+}
+
+impl EncryptedSeed {
+    /// Encrypt a seed under a password
+    fn seal(seed: &[u8], password: &str) -> Result<Self, KeystoreError> {
+        let mut rng = rand::rng();
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut salt);
+        rng.fill(&mut nonce);
+
+        let key = derive_key(password, &salt)?;
+        let cipher = Aes256GcmSiv::new_from_slice(key.as_ref())
+            .map_err(|e| KeystoreError::Crypto(e.to_string()))?;
+        let ciphertext = cipher
+            .encrypt(&Nonce::from(nonce), seed)
+            .map_err(|e| KeystoreError::Crypto(e.to_string()))?;
+
+        Ok(Self {
+            salt: BASE64.encode(salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        })
+    }
+
+    /// Decrypt the seed. The result is zeroized on drop.
+    fn open(&self, password: &str) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+        let salt = decode_field("salt", &self.salt)?;
+        let nonce = decode_field("nonce", &self.nonce)?;
+        let ciphertext = decode_field("ciphertext", &self.ciphertext)?;
+        let nonce: [u8; NONCE_LEN] = nonce.try_into().map_err(|n: Vec<u8>| {
+            KeystoreError::Format(format!("Expected {}-byte nonce, got {}", NONCE_LEN, n.len()))
+        })?;
+
+        let key = derive_key(password, &salt)?;
+        let cipher = Aes256GcmSiv::new_from_slice(key.as_ref())
+            .map_err(|e| KeystoreError::Crypto(e.to_string()))?;
+        cipher
+            .decrypt(&Nonce::from(nonce), ciphertext.as_ref())
+            .map(Zeroizing::new)
+            .map_err(|_| KeystoreError::WrongPassword)
+    }
+}
+
+impl Keystore {
+    /// Create a keystore from a mnemonic phrase, caching the address of index 0.
+    ///
+    /// # Arguments
+    /// * `phrase` - BIP39 mnemonic
+    /// * `passphrase` - Optional BIP39 passphrase ("25th word"), empty if unused
+    /// * `password` - Password used to encrypt the seed at rest
+    pub fn import_mnemonic(phrase: &str, passphrase: &str, password: &str) -> Result<Self, KeystoreError> {
+        if password.is_empty() {
+            return Err(KeystoreError::Crypto("Keystore password must not be empty".to_string()));
+        }
+        let phrase = Zeroizing::new(normalize_mnemonic(phrase)?);
+        let seed = Zeroizing::new(solana_seed_phrase::generate_seed_from_seed_phrase_and_passphrase(
+            &phrase, passphrase,
+        ));
+
+        let address = derive_keypair(&seed, 0)?.pubkey().to_string();
+
+        Ok(Self {
+            version: KEYSTORE_VERSION,
+            seed: EncryptedSeed::seal(&seed, password)?,
+            accounts: vec![DerivedAccount {
+                index: 0,
+                address,
+                label: None,
+            }],
+        })
+    }
+
+    /// Parse a keystore file
+    pub fn from_json(content: &str) -> Result<Self, KeystoreError> {
+        let value: serde_json::Value = serde_json::from_str(content)
+            .map_err(|e| KeystoreError::Format(e.to_string()))?;
+        if value.is_array() || value.is_string() {
+            return Err(KeystoreError::Format(
+                "This is a keypair file, not a keystore - connect it as a wallet instead".to_string(),
+            ));
+        }
+
+        match value.get("version").and_then(|v| v.as_u64()) {
+            Some(version) if version == KEYSTORE_VERSION as u64 => {
+                serde_json::from_value(value).map_err(|e| KeystoreError::Format(e.to_string()))
+            }
+            Some(other) => Err(KeystoreError::Format(format!("Unsupported keystore version {}", other))),
+            None => Err(KeystoreError::Format("Missing keystore version".to_string())),
+        }
+    }
+
+    /// Load keystore from a file
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, KeystoreError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_json(&content)
+    }
+
+    /// Save keystore to a file (always in the current format)
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), KeystoreError> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| KeystoreError::Format(e.to_string()))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Run `f` with the decrypted seed. The seed is zeroized before this returns.
+    pub fn with_seed<T>(
+        &self,
+        password: &str,
+        f: impl FnOnce(&[u8]) -> Result<T, KeystoreError>,
+    ) -> Result<T, KeystoreError> {
+        let seed = self.seed.open(password)?;
+        f(&seed)
+    }
+
+    /// Cached account for an index, if it has been derived before
+    pub fn account(&self, index: u32) -> Option<&DerivedAccount> {
+        self.accounts.iter().find(|a| a.index == index)
+    }
+
+    /// Make sure indexes `0..count` are in the address cache.
+    ///
+    /// Unlocks the seed only when at least one index is missing, and derives
+    /// all missing indexes within a single unlock.
+    pub fn ensure_derived(&mut self, password: &str, count: u32) -> Result<(), KeystoreError> {
+        let missing: Vec<u32> = (0..count).filter(|i| self.account(*i).is_none()).collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let derived = self.with_seed(password, |seed| {
+            missing
+                .iter()
+                .map(|&index| {
+                    derive_keypair(seed, index).map(|kp| DerivedAccount {
+                        index,
+                        address: kp.pubkey().to_string(),
+                        label: None,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+
+        self.accounts.extend(derived);
+        self.accounts.sort_by_key(|a| a.index);
+        Ok(())
+    }
+
+    /// Derive the signing keypair for an index.
+    ///
+    /// When the index is cached, the derived address must match the cached one;
+    /// a mismatch means the keystore file was tampered with or corrupted.
+    pub fn activate(&mut self, password: &str, index: u32) -> Result<Keypair, KeystoreError> {
+        let keypair = self.with_seed(password, |seed| derive_keypair(seed, index))?;
+        let derived = keypair.pubkey().to_string();
+
+        match self.account(index) {
+            Some(cached) if cached.address != derived => Err(KeystoreError::AddressMismatch {
+                index,
+                expected: cached.address.clone(),
+                derived,
+            }),
+            Some(_) => Ok(keypair),
+            None => {
+                self.accounts.push(DerivedAccount {
+                    index,
+                    address: derived,
+                    label: None,
+                });
+                self.accounts.sort_by_key(|a| a.index);
+                Ok(keypair)
+            }
+        }
+    }
+
+    /// Set or clear the label for a cached index. Returns false if the index is unknown.
+    pub fn set_label(&mut self, index: u32, label: &str) -> bool {
+        match self.accounts.iter_mut().find(|a| a.index == index) {
+            Some(account) => {
+                let label = label.trim();
+                account.label = if label.is_empty() { None } else { Some(label.to_string()) };
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Well-known BIP39 test vector phrase
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const PASSWORD: &str = "correct horse battery staple";
+
+    #[test]
+    fn test_import_caches_index_zero_and_encrypts_seed() {
+        let keystore = Keystore::import_mnemonic(PHRASE, "", PASSWORD).unwrap();
+        assert_eq!(keystore.version, KEYSTORE_VERSION);
+        assert_eq!(keystore.accounts.len(), 1);
+
+        let seed = solana_seed_phrase::generate_seed_from_seed_phrase_and_passphrase(PHRASE, "");
+        let expected = derive_keypair(&seed, 0).unwrap().pubkey().to_string();
+        assert_eq!(keystore.accounts[0].address, expected);
+
+        let raw = BASE64.decode(&keystore.seed.ciphertext).unwrap();
+        assert!(!raw.windows(seed.len()).any(|w| w == seed.as_slice()));
+    }
+
+    #[test]
+    fn test_invalid_mnemonic_and_wrong_password() {
+        assert!(matches!(
+            Keystore::import_mnemonic("too few words", "", PASSWORD),
+            Err(KeystoreError::InvalidMnemonic(_))
+        ));
+
+        let mut keystore = Keystore::import_mnemonic(PHRASE, "", PASSWORD).unwrap();
+        assert!(matches!(keystore.activate("nope", 1), Err(KeystoreError::WrongPassword)));
+    }
+
+    #[test]
+    fn test_indexes_derive_distinct_addresses() {
+        let mut keystore = Keystore::import_mnemonic(PHRASE, "", PASSWORD).unwrap();
+        keystore.ensure_derived(PASSWORD, DEFAULT_SCAN_COUNT).unwrap();
+
+        let indexes: Vec<u32> = keystore.accounts.iter().map(|a| a.index).collect();
+        assert_eq!(indexes, vec![0, 1, 2, 3, 4]);
+
+        let addresses: std::collections::HashSet<&str> =
+            keystore.accounts.iter().map(|a| a.address.as_str()).collect();
+        assert_eq!(addresses.len(), 5);
+    }
+
+    #[test]
+    fn test_cached_indexes_do_not_unlock() {
+        let mut keystore = Keystore::import_mnemonic(PHRASE, "", PASSWORD).unwrap();
+        keystore.ensure_derived(PASSWORD, 3).unwrap();
+
+        // Everything is cached, so the (wrong) password is never used
+        assert!(keystore.ensure_derived("wrong", 3).is_ok());
+        // A new index requires unlocking
+        assert!(matches!(keystore.ensure_derived("wrong", 4), Err(KeystoreError::WrongPassword)));
+    }
+
+    #[test]
+    fn test_activation_matches_cache_and_detects_tampering() {
+        let mut keystore = Keystore::import_mnemonic(PHRASE, "", PASSWORD).unwrap();
+        keystore.ensure_derived(PASSWORD, 3).unwrap();
+
+        let keypair = keystore.activate(PASSWORD, 2).unwrap();
+        assert_eq!(keypair.pubkey().to_string(), keystore.account(2).unwrap().address);
+
+        // Activating an index beyond the scan adds it to the cache
+        keystore.activate(PASSWORD, 7).unwrap();
+        assert!(keystore.account(7).is_some());
+
+        keystore.accounts[1].address = Keypair::new().pubkey().to_string();
+        assert!(matches!(
+            keystore.activate(PASSWORD, 1),
+            Err(KeystoreError::AddressMismatch { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_labels_round_trip_through_json() {
+        let mut keystore = Keystore::import_mnemonic(PHRASE, "", PASSWORD).unwrap();
+        keystore.ensure_derived(PASSWORD, 2).unwrap();
+        assert!(keystore.set_label(1, "  Trading  "));
+        assert!(!keystore.set_label(9, "missing"));
+
+        let json = serde_json::to_string(&keystore).unwrap();
+        let loaded = Keystore::from_json(&json).unwrap();
+        assert_eq!(loaded, keystore);
+        assert_eq!(loaded.account(1).unwrap().label.as_deref(), Some("Trading"));
+    }
+
+    #[test]
+    fn test_keypair_files_still_load_next_to_a_keystore() {
+        use crate::services::wallet::WalletService;
+
+        // A wallet saved before the keystore existed: a bare 32-byte secret
+        let secret = [7u8; 32];
+        let path = std::env::temp_dir().join(format!("xterminal-keypair-{}.json", std::process::id()));
+        std::fs::write(&path, serde_json::to_string(&secret).unwrap()).unwrap();
+
+        // Importing a seed leaves the old file usable as a wallet...
+        let keystore = Keystore::import_mnemonic(PHRASE, "", PASSWORD).unwrap();
+        let mut wallet = WalletService::new("https://api.devnet.solana.com");
+        let loaded = wallet.load_keypair_from_file(&path);
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        loaded.unwrap();
+        assert_eq!(
+            wallet.get_public_key(),
+            Some(Keypair::new_from_array(secret).pubkey().to_string())
+        );
+
+        // ...and it is not mistaken for a keystore
+        match Keystore::from_json(&content) {
+            Err(KeystoreError::Format(msg)) => assert!(msg.contains("keypair file"), "{}", msg),
+            other => panic!("expected a format error, got {:?}", other),
+        }
+        let json = serde_json::to_string(&keystore).unwrap();
+        assert_eq!(Keystore::from_json(&json).unwrap(), keystore);
+
+        for unsupported in [serde_json::json!({ "version": 99 }), serde_json::json!({ "seed": keystore.seed })] {
+            assert!(matches!(
+                Keystore::from_json(&unsupported.to_string()),
+                Err(KeystoreError::Format(_))
+            ));
+        }
+    }
+}
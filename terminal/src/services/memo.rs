@@ -0,0 +1,358 @@
+//! # Transaction Memos
+//!
+//! Builds, validates and decodes SPL Memo program instructions so a free-text
+//! note ("invoice 42") can be attached to a transfer or swap and read back
+//! from history.
+//!
+//! ## Attaching a Memo
+//!
+//! Swap transactions arrive from the backend already compiled and unsigned.
+//! [`append_memo`] adds the memo program as a read-only account and pushes a
+//! memo instruction signed by the fee payer, so it must run before the
+//! transaction is signed. [`append_memo_versioned`] does the same for v0
+//! transactions, whose accounts loaded from lookup tables are left as is.
+//!
+//! ## Reading Memos Back
+//!
+//! - [`memos_in_message`] decodes memo instructions from a transaction we hold.
+//! - [`decode_history_memo`] parses the `memo` field returned by the
+//!   `getSignaturesForAddress` RPC, which renders each memo as `[len] text`
+//!   and joins multiple memos with `"; "`.
+
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::compiled_instruction::CompiledInstruction;
+use solana_sdk::message::{Message, MessageHeader, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::fmt;
+
+/// SPL Memo program (v2)
+pub const MEMO_PROGRAM_ID: Pubkey = Pubkey::from_str_const("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// SPL Memo program (v1), still seen in older history
+pub const MEMO_V1_PROGRAM_ID: Pubkey = Pubkey::from_str_const("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+
+/// Longest memo accepted, in bytes (leaves room for the swap route in one packet)
+pub const MAX_MEMO_BYTES: usize = 256;
+
+/// Maximum serialized transaction size (`solana_packet::PACKET_DATA_SIZE`)
+const PACKET_DATA_SIZE: usize = 1232;
+
+/// Memo validation / attachment errors
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemoError {
+    Empty,
+    TooLong { len: usize, max: usize },
+    InvalidUtf8,
+    ControlCharacter,
+    AlreadySigned,
+    TransactionTooLarge { size: usize },
+}
+
+impl fmt::Display for MemoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoError::Empty => write!(f, "Memo is empty"),
+            MemoError::TooLong { len, max } => write!(f, "Memo is {} bytes (max {})", len, max),
+            MemoError::InvalidUtf8 => write!(f, "Memo is not valid UTF-8"),
+            MemoError::ControlCharacter => write!(f, "Memo contains control characters"),
+            MemoError::AlreadySigned => write!(f, "Cannot add a memo to a signed transaction"),
+            MemoError::TransactionTooLarge { size } => write!(
+                f,
+                "Transaction with memo is {} bytes (max {}); shorten the memo",
+                size, PACKET_DATA_SIZE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MemoError {}
+
+/// Validate a memo typed by the user. Returns the trimmed memo.
+pub fn validate_memo(memo: &str) -> Result<&str, MemoError> {
+    let memo = memo.trim();
+    if memo.is_empty() {
+        return Err(MemoError::Empty);
+    }
+    if memo.len() > MAX_MEMO_BYTES {
+        return Err(MemoError::TooLong { len: memo.len(), max: MAX_MEMO_BYTES });
+    }
+    if memo.chars().any(char::is_control) {
+        return Err(MemoError::ControlCharacter);
+    }
+    Ok(memo)
+}
+
+/// Validate raw memo bytes (e.g. instruction data) as a memo
+pub fn validate_memo_bytes(data: &[u8]) -> Result<&str, MemoError> {
+    let memo = std::str::from_utf8(data).map_err(|_| MemoError::InvalidUtf8)?;
+    validate_memo(memo)
+}
+
+/// Build a memo instruction. Every account in `signers` must sign the transaction.
+pub fn memo_instruction(memo: &str, signers: &[Pubkey]) -> Result<Instruction, MemoError> {
+    let memo = validate_memo(memo)?;
+    Ok(Instruction {
+        program_id: MEMO_PROGRAM_ID,
+        accounts: signers.iter().map(|pk| AccountMeta::new_readonly(*pk, true)).collect(),
+        data: memo.as_bytes().to_vec(),
+    })
+}
+
+/// Append a memo instruction, signed by the fee payer, to an unsigned transaction.
+///
+/// The transaction is left untouched if the memo is rejected.
+pub fn append_memo(transaction: &mut Transaction, memo: &str) -> Result<(), MemoError> {
+    let memo = validate_memo(memo)?;
+    if transaction.signatures.iter().any(|s| *s != Signature::default()) {
+        return Err(MemoError::AlreadySigned);
+    }
+
+    let mut updated = transaction.clone();
+    let message = &mut updated.message;
+    push_memo(&mut message.account_keys, &mut message.header, &mut message.instructions, memo)?;
+
+    let size = bincode::serialized_size(&updated).map(|s| s as usize).unwrap_or(usize::MAX);
+    if size > PACKET_DATA_SIZE {
+        return Err(MemoError::TransactionTooLarge { size });
+    }
+
+    *transaction = updated;
+    Ok(())
+}
+
+/// [`append_memo`] for a versioned transaction, legacy or v0.
+///
+/// A v0 message keeps loading the same accounts from its lookup tables; only
+/// its listed keys gain the memo program.
+pub fn append_memo_versioned(transaction: &mut VersionedTransaction, memo: &str) -> Result<(), MemoError> {
+    let memo = validate_memo(memo)?;
+    if transaction.signatures.iter().any(|s| *s != Signature::default()) {
+        return Err(MemoError::AlreadySigned);
+    }
+
+    let mut updated = transaction.clone();
+    match &mut updated.message {
+        VersionedMessage::Legacy(message) => {
+            push_memo(&mut message.account_keys, &mut message.header, &mut message.instructions, memo)?
+        }
+        VersionedMessage::V0(message) => {
+            push_memo(&mut message.account_keys, &mut message.header, &mut message.instructions, memo)?
+        }
+    }
+
+    let size = bincode::serialized_size(&updated).map(|s| s as usize).unwrap_or(usize::MAX);
+    if size > PACKET_DATA_SIZE {
+        return Err(MemoError::TransactionTooLarge { size });
+    }
+
+    *transaction = updated;
+    Ok(())
+}
+
+/// Push a memo instruction signed by the fee payer, adding the memo program to the listed keys
+fn push_memo(
+    account_keys: &mut Vec<Pubkey>,
+    header: &mut MessageHeader,
+    instructions: &mut Vec<CompiledInstruction>,
+    memo: &str,
+) -> Result<(), MemoError> {
+    let program_index = match account_keys.iter().position(|k| *k == MEMO_PROGRAM_ID) {
+        Some(index) => index,
+        None => {
+            // Read-only unsigned accounts sit at the end of the key list; accounts
+            // loaded from lookup tables are indexed after it and move up by one
+            let listed = account_keys.len();
+            for instruction in instructions.iter_mut() {
+                for index in std::iter::once(&mut instruction.program_id_index).chain(instruction.accounts.iter_mut()) {
+                    if usize::from(*index) >= listed {
+                        *index = index.checked_add(1).ok_or(MemoError::TransactionTooLarge { size: usize::MAX })?;
+                    }
+                }
+            }
+            account_keys.push(MEMO_PROGRAM_ID);
+            header.num_readonly_unsigned_accounts += 1;
+            listed
+        }
+    };
+    let program_id_index = u8::try_from(program_index)
+        .map_err(|_| MemoError::TransactionTooLarge { size: usize::MAX })?;
+    instructions.push(CompiledInstruction {
+        program_id_index,
+        accounts: vec![0], // fee payer
+        data: memo.as_bytes().to_vec(),
+    });
+    Ok(())
+}
+
+/// Memos carried by a message's memo instructions (invalid ones are skipped)
+pub fn memos_in_message(message: &Message) -> Vec<String> {
+    message
+        .instructions
+        .iter()
+        .filter(|ix| {
+            message
+                .account_keys
+                .get(ix.program_id_index as usize)
+                .is_some_and(|k| *k == MEMO_PROGRAM_ID || *k == MEMO_V1_PROGRAM_ID)
+        })
+        .filter_map(|ix| std::str::from_utf8(&ix.data).ok())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Decode the `memo` field of a `getSignaturesForAddress` entry.
+///
+/// Each memo is rendered as `[len] text` with `len` in bytes, so the text
+/// itself may contain `"; "`. Anything that does not follow the format is
+/// returned as-is.
+pub fn decode_history_memo(raw: &str) -> Vec<String> {
+    let mut memos = Vec::new();
+    let mut rest = raw;
+
+    while !rest.is_empty() {
+        let parsed = rest
+            .strip_prefix('[')
+            .and_then(|s| s.split_once("] "))
+            .and_then(|(len, text)| Some((len.parse::<usize>().ok()?, text)))
+            .and_then(|(len, text)| Some((text.get(..len)?, &text[len..])));
+
+        match parsed {
+            Some((memo, remainder)) => {
+                memos.push(memo.to_string());
+                rest = remainder.strip_prefix("; ").unwrap_or(remainder);
+            }
+            None => {
+                memos.push(rest.to_string());
+                break;
+            }
+        }
+    }
+
+    memos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    fn unsigned_transfer(payer: &Keypair) -> Transaction {
+        let ix = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(Pubkey::new_unique(), false),
+            ],
+            data: vec![2, 0, 0, 0],
+        };
+        Transaction::new_unsigned(Message::new_with_blockhash(&[ix], Some(&payer.pubkey()), &Hash::default()))
+    }
+
+    #[test]
+    fn test_memo_instruction_construction() {
+        let signer = Pubkey::new_unique();
+        let ix = memo_instruction("  invoice 42 ", &[signer]).unwrap();
+
+        assert_eq!(ix.program_id, MEMO_PROGRAM_ID);
+        assert_eq!(ix.data, b"invoice 42");
+        assert_eq!(ix.accounts, vec![AccountMeta::new_readonly(signer, true)]);
+    }
+
+    #[test]
+    fn test_memo_length_enforcement() {
+        let max = "a".repeat(MAX_MEMO_BYTES);
+        assert_eq!(validate_memo(&max), Ok(max.as_str()));
+
+        // Multi-byte characters count by bytes, not chars
+        let too_long = "é".repeat(MAX_MEMO_BYTES / 2 + 1);
+        assert_eq!(
+            validate_memo(&too_long),
+            Err(MemoError::TooLong { len: MAX_MEMO_BYTES + 2, max: MAX_MEMO_BYTES })
+        );
+        assert_eq!(validate_memo("   "), Err(MemoError::Empty));
+        assert_eq!(validate_memo("line\nbreak"), Err(MemoError::ControlCharacter));
+        assert_eq!(validate_memo_bytes(&[0xff, 0xfe]), Err(MemoError::InvalidUtf8));
+    }
+
+    #[test]
+    fn test_append_memo_to_unsigned_transaction() {
+        let payer = Keypair::new();
+        let mut tx = unsigned_transfer(&payer);
+        let keys_before = tx.message.account_keys.len();
+
+        append_memo(&mut tx, "invoice 42").unwrap();
+
+        assert_eq!(tx.message.account_keys.len(), keys_before + 1);
+        assert_eq!(tx.message.header.num_readonly_unsigned_accounts, 2);
+        assert_eq!(memos_in_message(&tx.message), vec!["invoice 42"]);
+
+        // Still signable by the fee payer alone
+        tx.sign(&[&payer], Hash::default());
+        assert!(tx.verify().is_ok());
+    }
+
+    #[test]
+    fn test_append_memo_rejects_signed_or_oversized() {
+        let payer = Keypair::new();
+        let mut signed = unsigned_transfer(&payer);
+        signed.sign(&[&payer], Hash::default());
+        assert_eq!(append_memo(&mut signed, "late"), Err(MemoError::AlreadySigned));
+
+        let mut tx = unsigned_transfer(&payer);
+        let before = tx.clone();
+        for i in 0..3 {
+            append_memo(&mut tx, &format!("{}{}", i, "x".repeat(MAX_MEMO_BYTES - 1))).unwrap();
+        }
+        let err = append_memo(&mut tx, &"y".repeat(MAX_MEMO_BYTES)).unwrap_err();
+        assert!(matches!(err, MemoError::TransactionTooLarge { .. }));
+        assert_eq!(memos_in_message(&tx.message).len(), 3);
+        assert_ne!(tx, before);
+    }
+
+    #[test]
+    fn test_append_memo_to_v0_transaction() {
+        use solana_sdk::message::{v0, AddressLookupTableAccount};
+
+        let payer = Keypair::new();
+        let pool = Pubkey::new_unique();
+        let table = AddressLookupTableAccount { key: Pubkey::new_unique(), addresses: vec![pool] };
+        let ix = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![AccountMeta::new(payer.pubkey(), true), AccountMeta::new(pool, false)],
+            data: vec![1],
+        };
+        let message = v0::Message::try_compile(&payer.pubkey(), &[ix], &[table], Hash::default()).unwrap();
+        let mut tx = VersionedTransaction { signatures: vec![Signature::default()], message: VersionedMessage::V0(message) };
+
+        append_memo_versioned(&mut tx, "invoice 42").unwrap();
+
+        let VersionedMessage::V0(message) = &tx.message else { panic!("expected a v0 message") };
+        assert_eq!(message.account_keys.len(), 3);
+        assert_eq!(message.account_keys[2], MEMO_PROGRAM_ID);
+        // The pool is still the first loaded account, now after three listed keys
+        assert_eq!(message.instructions[0].accounts, vec![0, 3]);
+        assert_eq!(message.address_table_lookups[0].writable_indexes, vec![0]);
+        assert_eq!(message.instructions[1].data, b"invoice 42");
+
+        // Still signable by the fee payer alone
+        let signed = VersionedTransaction::try_new(tx.message.clone(), &[&payer]).unwrap();
+        assert!(signed.verify_with_results().iter().all(|ok| *ok));
+        assert_eq!(append_memo_versioned(&mut signed.clone(), "late"), Err(MemoError::AlreadySigned));
+    }
+
+    #[test]
+    fn test_decode_history_memo() {
+        assert_eq!(decode_history_memo("[10] invoice 42"), vec!["invoice 42"]);
+        // Separator inside a memo is kept thanks to the byte length prefix
+        assert_eq!(
+            decode_history_memo("[6] a; b c; [5] été"),
+            vec!["a; b c", "été"]
+        );
+        // Not in RPC format
+        assert_eq!(decode_history_memo("plain text"), vec!["plain text"]);
+        assert!(decode_history_memo("").is_empty());
+    }
+}
@@ -9,6 +9,13 @@
 //! services/
 //! ├── api.rs       - Backend HTTP API client
 //! │                  (authentication, market data, swaps)
+//! ├── keystore.rs  - Encrypted mnemonic seed and derived accounts
+//! ├── memo.rs      - SPL Memo instructions (attach, validate, decode)
+//! ├── offline.rs   - Mock backend and wallet of the offline demo (`--offline`)
+//! ├── protocol_handler/ - xforce:// links, single instance, OS registration
+//! ├── session.rs   - Login token expiry, warnings and refresh timing
+//! ├── signer_policy.rs - Session grants and the auto-sign policy engine
+//! ├── token_transfer.rs - SOL, SPL and Token-2022 transfers and transfer-fee previews
 //! └── wallet.rs    - Solana wallet service
 //!                    (keypair management, transaction signing)
 //! ```
@@ -56,24 +63,24 @@
 //!
 //! ```rust
 //! // Authentication
-//! api_client.login(username, password) -> Result<AuthResponse, String>
-//! api_client.signup(username, email, password) -> Result<AuthResponse, String>
+//! api_client.login(username, password) -> Result<AuthResponse, ApiError>
+//! api_client.signup(username, email, password) -> Result<AuthResponse, ApiError>
 //!
 //! // Market Data
-//! api_client.get_prices(&["SOL", "USDC"]) -> Result<PriceResponse, String>
-//! api_client.get_token_list() -> Result<Vec<TokenListItem>, String>
+//! api_client.get_prices(&["SOL", "USDC"]) -> Result<PriceResponse, ApiError>
+//! api_client.get_token_list() -> Result<Vec<TokenListItem>, ApiError>
 //!
 //! // Swaps
-//! api_client.get_swap_quote(input_mint, output_mint, amount, slippage) -> Result<SwapQuoteResponse, String>
-//! api_client.execute_swap(...) -> Result<SwapExecuteResponse, String>
-//! api_client.get_swap_history(jwt_token, limit) -> Result<Vec<SwapHistoryItem>, String>
+//! api_client.get_swap_quote(input_mint, output_mint, amount, slippage) -> Result<SwapQuoteResponse, ApiError>
+//! api_client.execute_swap(...) -> Result<SwapExecuteResponse, ApiError>
+//! api_client.get_swap_history(jwt_token, &query) -> Result<SwapHistoryResponse, ApiError>
 //!
 //! // Transactions
-//! api_client.submit_transaction(signed_tx, metadata, jwt_token) -> Result<TransactionSubmitResponse, String>
+//! api_client.submit_transaction(signed_tx, metadata, jwt_token) -> Result<TransactionSubmitResponse, ApiError>
 //!
 //! // Wallet
-//! api_client.get_wallet_balance(address) -> Result<WalletBalance, String>
-//! api_client.get_token_balances(address) -> Result<Vec<TokenBalance>, String>
+//! api_client.get_wallet_balance(address) -> Result<WalletBalance, ApiError>
+//! api_client.get_token_balances(address) -> Result<Vec<TokenBalance>, ApiError>
 //! ```
 //!
 //! ### Usage Pattern
@@ -116,6 +123,12 @@
 //!
 //! // Transaction Signing
 //! wallet_service.sign_transaction(&mut tx) -> Result<Signature, WalletError>
+//! wallet_service.sign_versioned_transaction(&mut versioned_tx) -> Result<Signature, WalletError>
+//! wallet_service.sign(&mut wallet_tx) -> Result<Signature, WalletError>  // Legacy or v0
+//!
+//! // Transfers (unsigned, for the Wallet screen's Send panel)
+//! wallet_service.build_sol_transfer(recipient, lamports, memo) -> Result<Transaction, WalletError>
+//! wallet_service.build_token_transfer(recipient, mint, program, amount, decimals, memo) -> Result<Transaction, WalletError>
 //!
 //! // Balance Queries
 //! wallet_service.get_balance() -> Result<f64, WalletError>
@@ -157,14 +170,19 @@
 //!
 //! 2. Get Unsigned Transaction
 //!    └─> ApiClient.execute_swap() ──> Backend ──> Jupiter
-//!        └─> Returns base64-encoded unsigned transaction
+//!        └─> Returns base64-encoded unsigned transaction and its version
+//!            (legacy, or v0 loading accounts from lookup tables)
+//!
+//! 3. Simulate and Confirm
+//!    └─> ApiClient.simulate_swap() ──> Backend ──> Solana RPC
+//!        └─> Expected balance changes shown; a failed simulation blocks signing
 //!
-//! 3. Sign Transaction Locally
-//!    └─> WalletService.sign_transaction()
+//! 4. Sign Transaction Locally
+//!    └─> WalletService.sign() (legacy or v0, as decoded into WalletTransaction)
 //!        └─> Uses local keypair (never sent to server)
 //!
-//! 4. Submit Signed Transaction
-//!    └─> ApiClient.submit_transaction() ──> Backend ──> Solana Network
+//! 5. Submit Signed Transaction
+//!    └─> ApiClient.submit_transaction() with the version ──> Backend ──> Solana Network
 //!        └─> Backend broadcasts and saves to database
 //! ```
 //!
@@ -180,10 +198,16 @@
 //!
 //! ### ApiClient Errors
 //!
-//! Returns `Result<T, String>` with user-friendly messages:
-//! - Network errors: "Network error: {details}"
-//! - Parse errors: "Failed to parse response: {details}"
-//! - API errors: Extracted from ErrorResponse body
+//! Returns `Result<T, ApiError>`; its `Display` is the user-facing message:
+//! - `ApiError::Network` - "Network error: {details}"
+//! - `ApiError::Timeout` - "Request timed out"
+//! - `ApiError::Unauthorized` - the session token was rejected (401)
+//! - `ApiError::Api` - status plus the message from the ErrorResponse body
+//! - `ApiError::Parse` - "Failed to parse response: {details}"
+//!
+//! Unauthorized and timed-out requests are also published on
+//! `ApiClient::failures()`, so the app can log out or warn once instead of
+//! every caller handling them.
 //!
 //! ### WalletService Errors
 //!
@@ -192,6 +216,7 @@
 //! - `WalletError::InvalidKeypair` - Invalid keypair format
 //! - `WalletError::RpcError` - Solana RPC connection failed
 //! - `WalletError::SigningError` - Transaction signing failed
+//! - `WalletError::PolicyDenied` - Auto-sign request not covered by a session grant
 //! - `WalletError::BalanceError` - Balance query failed
 //!
 //! ## Thread Safety
@@ -210,7 +235,9 @@
 //!
 //! ### ApiClient Configuration
 //!
-//! - Base URL: `http://127.0.0.1:3001` (hardcoded)
+//! - Base URL: active entry of the server list saved in Settings > Servers
+//!   (Settings > Servers), `http://127.0.0.1:3001` by default
+//! - Failover: moves to the next healthy server, see [`api::failover`]
 //! - HTTP client: `reqwest::Client` with default settings
 //! - No timeout (relies on OS defaults)
 //!
@@ -244,10 +271,16 @@
 //! ### WalletService
 //! - Hardware wallet support (Ledger, Trezor)
 //! - Multi-signature wallets
-//! - Wallet encryption at rest
-//! - Mnemonic phrase import/export
+//! - Mnemonic phrase export
 //! - Token account creation
 
 pub mod api;
 pub mod braid_client;
+pub mod keystore;
+pub mod memo;
+pub mod offline;
+pub mod protocol_handler;
+pub mod session;
+pub mod signer_policy;
+pub mod token_transfer;
 pub mod wallet;
@@ -0,0 +1,826 @@
+//! # Offline Demo Services
+//!
+//! Stand-ins for the backend and the wallet when the terminal is started
+//! with `--offline` (or `TERMINAL_OFFLINE=1`), so every screen can be shown
+//! without a server, an account or an RPC node.
+//!
+//! [`OfflineApi`] implements [`ApiService`] over a canned market:
+//!
+//! - Prices random-walk from fixed starting points, one step per
+//!   [`PRICE_STEP`]. Each symbol's walk is seeded from its name, so step `n`
+//!   is the same price on every run
+//! - Quotes price the trade against a constant-product pool per token, so
+//!   impact grows with size the way it does on a real route
+//! - Swaps land as soon as they are submitted and are kept, with the
+//!   balances they moved, in memory until the terminal exits
+//!
+//! What a demo can't fake sensibly (accounts, trade imports, analytics,
+//! backend health) fails with an [`ApiError::Api`] saying so.
+//!
+//! [`OfflineWallet`] implements [`WalletService`] over a throwaway keypair.
+//! It signs with the blockhash a transaction already carries, where the real
+//! wallet fetches a fresh one over RPC, so nothing it signs leaves the machine.
+
+use crate::core::service::{ApiService, WalletService};
+use crate::services::api::{
+    ApiError, PriceData, PriceResponse, RouteInfo, SwapExecuteResponse, SwapHistoryItem, SwapHistoryQuery,
+    SwapHistoryResponse, SwapQuoteResponse, TokenBalance, TokenListItem, TransactionHistory, TransactionSubmitResponse,
+    TransactionSummary, WalletBalance,
+};
+use crate::services::wallet::{WalletError, WalletTransaction};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use shared::dto::market::{
+    CandleBatchResponse, DepthResponse, DepthRung, MarketAnalyticsResponse, StreamedSymbolInfo, StreamedSymbolsResponse,
+    OHLC,
+};
+use shared::dto::transactions::{TransactionStatus, TransactionStatusUpdate, TransactionVersion};
+use shared::AuthResponse;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Time one step of the price walk takes
+pub const PRICE_STEP: Duration = Duration::from_secs(1);
+
+/// Name the demo session is shown under
+pub const DEMO_USERNAME: &str = "demo";
+
+/// Pull of the walk back towards its starting price, per step
+const MEAN_REVERSION: f64 = 0.002;
+
+/// Pool fee taken from every quote (0.25%)
+const POOL_FEE: f64 = 0.0025;
+
+/// Network fee of a demo transaction, in lamports
+const NETWORK_FEE_LAMPORTS: u64 = 5_000;
+
+/// Trade sizes of the depth ladder, in input tokens
+const DEPTH_SIZES: [f64; 5] = [0.1, 1.0, 10.0, 100.0, 1_000.0];
+
+/// Memo every demo swap transaction carries
+const DEMO_SWAP_MEMO: &str = "xforce offline demo swap";
+
+/// Amounts on the swap screen are in base units with 9 decimals
+const BASE_UNITS: f64 = 1_000_000_000.0;
+
+/// Token of the canned market
+struct DemoToken {
+    symbol: &'static str,
+    name: &'static str,
+    mint: &'static str,
+    decimals: u8,
+    /// Price the walk starts from, in USD
+    price: f64,
+    /// Largest move of one walk step, as a fraction of the price
+    volatility: f64,
+    /// USD on the token's side of its pool
+    liquidity: f64,
+    /// What the demo wallet starts with
+    balance: f64,
+}
+
+const TOKENS: [DemoToken; 8] = [
+    DemoToken { symbol: "SOL", name: "Solana", mint: "So11111111111111111111111111111111111111112", decimals: 9, price: 145.32, volatility: 0.0015, liquidity: 25_000_000.0, balance: 25.0 },
+    DemoToken { symbol: "USDC", name: "USD Coin", mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", decimals: 6, price: 1.0, volatility: 0.00005, liquidity: 40_000_000.0, balance: 2_500.0 },
+    DemoToken { symbol: "USDT", name: "Tether USD", mint: "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", decimals: 6, price: 1.0, volatility: 0.00005, liquidity: 15_000_000.0, balance: 0.0 },
+    DemoToken { symbol: "BTC", name: "Wrapped Bitcoin (Portal)", mint: "3NZ9JMVBmGAqocybic2c7LQCJScmgsAZ6vQqTDzcqmJh", decimals: 8, price: 64_250.0, volatility: 0.0008, liquidity: 8_000_000.0, balance: 0.0 },
+    DemoToken { symbol: "ETH", name: "Ether (Portal)", mint: "7vfCXTUXx5WJV5JADk17DUJ4ksgau7utNKj4b963voxs", decimals: 8, price: 3_100.5, volatility: 0.001, liquidity: 6_000_000.0, balance: 0.0 },
+    DemoToken { symbol: "JUP", name: "Jupiter", mint: "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN", decimals: 6, price: 0.92, volatility: 0.0025, liquidity: 3_000_000.0, balance: 400.0 },
+    DemoToken { symbol: "RAY", name: "Raydium", mint: "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R", decimals: 6, price: 2.15, volatility: 0.003, liquidity: 1_500_000.0, balance: 0.0 },
+    DemoToken { symbol: "BONK", name: "Bonk", mint: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263", decimals: 5, price: 0.000021, volatility: 0.005, liquidity: 800_000.0, balance: 5_000_000.0 },
+];
+
+fn token_by_symbol(symbol: &str) -> Option<&'static DemoToken> {
+    TOKENS.iter().find(|token| token.symbol.eq_ignore_ascii_case(symbol))
+}
+
+fn token_by_mint(mint: &str) -> Result<&'static DemoToken, ApiError> {
+    TOKENS
+        .iter()
+        .find(|token| token.mint == mint)
+        .ok_or_else(|| bad_request(format!("Unknown token {}", shared::format_address(mint, 4, 4))))
+}
+
+fn bad_request(message: String) -> ApiError {
+    ApiError::Api { status: 400, message }
+}
+
+/// Error of the calls the demo doesn't serve
+fn unavailable(what: &str) -> ApiError {
+    ApiError::Api { status: 503, message: format!("{} not available in the offline demo", what) }
+}
+
+/// Stable FNV-1a hash of `parts`, to seed the walks with
+fn seed(parts: &[&str]) -> u64 {
+    parts
+        .iter()
+        .flat_map(|part| part.bytes().chain([0]))
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Session token of the demo: JWT-shaped, unsigned, expiring in 2100
+pub fn demo_token() -> String {
+    let encode = |json: &str| URL_SAFE_NO_PAD.encode(json);
+    format!(
+        "{}.{}.offline",
+        encode(r#"{"alg":"none","typ":"JWT"}"#),
+        encode(&format!(r#"{{"sub":"0","username":"{}","exp":4102444800}}"#, DEMO_USERNAME)),
+    )
+}
+
+/// One symbol's price walk, advanced lazily
+struct Walk {
+    rng: StdRng,
+    step: u64,
+    price: f64,
+}
+
+impl Walk {
+    fn new(token: &DemoToken) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed(&[token.symbol])), step: 0, price: token.price }
+    }
+
+    /// Walk forward to `step`; the walk never goes back
+    fn advance_to(&mut self, step: u64, token: &DemoToken) {
+        while self.step < step {
+            let shock = self.rng.random::<f64>() * 2.0 - 1.0;
+            let reversion = MEAN_REVERSION * (token.price / self.price - 1.0);
+            self.price *= 1.0 + token.volatility * shock + reversion;
+            self.step += 1;
+        }
+    }
+}
+
+/// Pricing of a trade against the demo pools
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Quote {
+    /// Output in base units (9 decimals)
+    out_amount: u64,
+    /// Price impact in percent
+    price_impact_pct: f64,
+}
+
+/// Swap kept in the demo's history
+struct DemoSwap {
+    item: SwapHistoryItem,
+    /// Unix seconds it landed at
+    block_time: i64,
+}
+
+impl DemoSwap {
+    fn matches(&self, query: &SwapHistoryQuery) -> bool {
+        let item = &self.item;
+        query.from_ts.is_none_or(|from| self.block_time >= from)
+            && query.to_ts.is_none_or(|to| self.block_time < to)
+            && query.input_token.as_ref().is_none_or(|mint| item.input_mint == *mint)
+            && query.output_token.as_ref().is_none_or(|mint| item.output_mint == *mint)
+            && query.status.as_ref().is_none_or(|status| item.status.eq_ignore_ascii_case(status))
+            && query.signature.as_ref().is_none_or(|fragment| item.signature.contains(fragment.as_str()))
+    }
+}
+
+/// Balances and swaps of the demo wallet
+struct Ledger {
+    /// Balance per mint, in tokens
+    balances: HashMap<&'static str, f64>,
+    /// Newest first
+    swaps: Vec<DemoSwap>,
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self {
+            balances: TOKENS.iter().map(|token| (token.mint, token.balance)).collect(),
+            swaps: Vec::new(),
+        }
+    }
+}
+
+/// [`ApiService`] of the offline demo, see the [module docs](self)
+pub struct OfflineApi {
+    started: Instant,
+    walks: Mutex<HashMap<&'static str, Walk>>,
+    ledger: Mutex<Ledger>,
+}
+
+impl Default for OfflineApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OfflineApi {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            walks: Mutex::new(HashMap::new()),
+            ledger: Mutex::new(Ledger::default()),
+        }
+    }
+
+    fn current_step(&self) -> u64 {
+        (self.started.elapsed().as_secs_f64() / PRICE_STEP.as_secs_f64()) as u64
+    }
+
+    /// Price of `token` after `step` steps of its walk (or later, if the
+    /// walk is already past it)
+    fn price_at(&self, token: &'static DemoToken, step: u64) -> f64 {
+        let mut walks = self.walks.lock();
+        let walk = walks.entry(token.symbol).or_insert_with(|| Walk::new(token));
+        walk.advance_to(step, token);
+        walk.price
+    }
+
+    fn price(&self, token: &'static DemoToken) -> f64 {
+        self.price_at(token, self.current_step())
+    }
+
+    /// Swap `amount` base units of `input` into `output` through both tokens'
+    /// pools: the trade moves each pool by its share of the shallower one
+    fn quote(&self, input: &'static DemoToken, output: &'static DemoToken, amount: u64) -> Quote {
+        let value = amount as f64 / BASE_UNITS * self.price(input);
+        let depth = input.liquidity.min(output.liquidity);
+        let impact = value / (value + depth);
+        let out = value * (1.0 - impact) * (1.0 - POOL_FEE) / self.price(output);
+        Quote {
+            out_amount: (out * BASE_UNITS) as u64,
+            price_impact_pct: impact * 100.0,
+        }
+    }
+
+    /// `limit` candles of `timeframe_secs` ending at the current price
+    ///
+    /// The shape comes from a walk seeded by symbol and timeframe, scaled so
+    /// the last close is today's price.
+    fn candles(&self, token: &'static DemoToken, timeframe: &str, limit: usize) -> Result<Vec<OHLC>, ApiError> {
+        let bucket = match timeframe {
+            "1m" => 60,
+            "5m" => 300,
+            "15m" => 900,
+            "1h" => 3_600,
+            "4h" => 14_400,
+            "1d" => 86_400,
+            "1w" => 604_800,
+            other => return Err(bad_request(format!("Unsupported timeframe {}", other))),
+        };
+        // A candle moves like sqrt(its length) walk steps, capped so daily
+        // candles stay believable
+        let volatility = token.volatility * (bucket as f64).sqrt().min(100.0);
+        let mut rng = StdRng::seed_from_u64(seed(&[token.symbol, timeframe]));
+        let now = chrono::Utc::now().timestamp();
+        let last_start = now - now % bucket;
+
+        let mut close = self.price(token);
+        let mut candles = Vec::with_capacity(limit);
+        for i in 0..limit as i64 {
+            let open = close / (1.0 + volatility * (rng.random::<f64>() * 2.0 - 1.0));
+            let wick = volatility * rng.random::<f64>() * 0.5;
+            let high = open.max(close) * (1.0 + wick);
+            let low = open.min(close) * (1.0 - wick);
+            let volume = token.liquidity * 0.01 * (0.5 + rng.random::<f64>());
+            candles.push(OHLC::new(last_start - i * bucket, open, high, low, close, volume));
+            close = open;
+        }
+        candles.reverse();
+        Ok(candles)
+    }
+
+    fn history(&self, query: &SwapHistoryQuery) -> (Vec<SwapHistoryItem>, usize) {
+        let ledger = self.ledger.lock();
+        let matching: Vec<&DemoSwap> = ledger.swaps.iter().filter(|swap| swap.matches(query)).collect();
+        let page = matching
+            .iter()
+            .skip(query.offset)
+            .take(query.limit)
+            .map(|swap| swap.item.clone())
+            .collect();
+        (page, matching.len())
+    }
+}
+
+#[async_trait]
+impl ApiService for OfflineApi {
+    async fn login(&self, _email_or_username: String, _password: String) -> Result<AuthResponse, ApiError> {
+        Err(unavailable("Accounts are"))
+    }
+
+    async fn signup(&self, _username: String, _email: String, _password: String) -> Result<AuthResponse, ApiError> {
+        Err(unavailable("Accounts are"))
+    }
+
+    async fn logout(&self, _jwt_token: &str) -> Result<(), ApiError> {
+        Ok(())
+    }
+
+    async fn refresh_session(&self, _jwt_token: &str) -> Result<AuthResponse, ApiError> {
+        Err(unavailable("Accounts are"))
+    }
+
+    async fn change_password(&self, _jwt_token: &str, _current_password: String, _new_password: String) -> Result<AuthResponse, ApiError> {
+        Err(unavailable("Accounts are"))
+    }
+
+    async fn update_profile(&self, _jwt_token: &str, _email: String) -> Result<shared::UserInfo, ApiError> {
+        Err(unavailable("Accounts are"))
+    }
+
+    async fn get_prices(&self, symbols: &[&str]) -> Result<PriceResponse, ApiError> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let prices = symbols
+            .iter()
+            .filter_map(|symbol| token_by_symbol(symbol))
+            .map(|token| {
+                let price = self.price(token);
+                let data = PriceData {
+                    price,
+                    confidence: None,
+                    source: "offline".to_string(),
+                    change_24h: Some((price / token.price - 1.0) * 100.0),
+                    last_updated: now,
+                    publish_time: None,
+                    sources: BTreeMap::new(),
+                    divergent: false,
+                };
+                (token.symbol.to_string(), data)
+            })
+            .collect();
+        Ok(PriceResponse { prices })
+    }
+
+    async fn get_wallet_balance(&self, address: &str) -> Result<WalletBalance, ApiError> {
+        let balance_sol = self.ledger.lock().balances.get(TOKENS[0].mint).copied().unwrap_or(0.0);
+        Ok(WalletBalance {
+            address: address.to_string(),
+            balance_sol,
+            balance_lamports: (balance_sol * BASE_UNITS) as u64,
+        })
+    }
+
+    async fn get_transaction_history(&self, address: &str, limit: usize) -> Result<TransactionHistory, ApiError> {
+        let ledger = self.ledger.lock();
+        let transactions = ledger
+            .swaps
+            .iter()
+            .take(limit)
+            .map(|swap| TransactionSummary {
+                signature: swap.item.signature.clone(),
+                slot: swap.item.id as u64,
+                block_time: Some(swap.block_time),
+                status: "Success".to_string(),
+                memo: None,
+            })
+            .collect();
+        Ok(TransactionHistory { address: address.to_string(), transactions })
+    }
+
+    async fn get_swap_quote(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        _slippage_bps: u16,
+    ) -> Result<SwapQuoteResponse, ApiError> {
+        let (input, output) = (token_by_mint(input_mint)?, token_by_mint(output_mint)?);
+        let quote = self.quote(input, output, amount);
+        Ok(SwapQuoteResponse {
+            input_mint: input_mint.to_string(),
+            output_mint: output_mint.to_string(),
+            in_amount: amount.to_string(),
+            out_amount: quote.out_amount.to_string(),
+            price_impact_pct: quote.price_impact_pct,
+            routes: vec![RouteInfo {
+                dex: "Demo AMM".to_string(),
+                input_mint: input_mint.to_string(),
+                output_mint: output_mint.to_string(),
+                in_amount: amount.to_string(),
+                out_amount: quote.out_amount.to_string(),
+            }],
+        })
+    }
+
+    async fn execute_swap(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        _slippage_bps: u16,
+        user_pubkey: &str,
+        _jwt_token: &str,
+    ) -> Result<SwapExecuteResponse, ApiError> {
+        let (input, output) = (token_by_mint(input_mint)?, token_by_mint(output_mint)?);
+        let payer = Pubkey::from_str(user_pubkey).map_err(|e| bad_request(format!("Invalid wallet address: {}", e)))?;
+        let quote = self.quote(input, output, amount);
+
+        let mut transaction = Transaction::new_with_payer(&[], Some(&payer));
+        crate::services::memo::append_memo(&mut transaction, DEMO_SWAP_MEMO).map_err(|e| ApiError::Parse(e.to_string()))?;
+        let transaction = WalletTransaction::Legacy(transaction).encode().map_err(ApiError::Parse)?;
+
+        Ok(SwapExecuteResponse {
+            transaction,
+            version: TransactionVersion::Legacy,
+            last_valid_block_height: 0,
+            input_mint: input_mint.to_string(),
+            output_mint: output_mint.to_string(),
+            in_amount: amount.to_string(),
+            out_amount: quote.out_amount.to_string(),
+            price_impact_pct: quote.price_impact_pct,
+        })
+    }
+
+    async fn simulate_swap(
+        &self,
+        request: &shared::dto::simulation::SimulateSwapRequest,
+        _jwt_token: &str,
+    ) -> Result<shared::dto::simulation::SimulateSwapResponse, ApiError> {
+        token_by_mint(&request.input_mint)?;
+        token_by_mint(&request.output_mint)?;
+        Ok(shared::dto::simulation::SimulateSwapResponse {
+            success: true,
+            error: None,
+            compute_units: Some(1_400),
+            network_fee_lamports: NETWORK_FEE_LAMPORTS,
+            sol_change_lamports: -(NETWORK_FEE_LAMPORTS as i64),
+            token_changes: Vec::new(),
+            logs: vec![format!("Program log: Memo (len {}): \"{}\"", DEMO_SWAP_MEMO.len(), DEMO_SWAP_MEMO)],
+            log_tail: Vec::new(),
+        })
+    }
+
+    async fn submit_transaction(
+        &self,
+        signed_transaction: String,
+        version: TransactionVersion,
+        input_mint: String,
+        output_mint: String,
+        input_amount: i64,
+        output_amount: i64,
+        _price_impact: Option<f64>,
+        _slippage_bps: Option<i32>,
+        _jwt_token: &str,
+    ) -> Result<TransactionSubmitResponse, ApiError> {
+        let (input, output) = (token_by_mint(&input_mint)?, token_by_mint(&output_mint)?);
+        let signature = match WalletTransaction::decode(&signed_transaction, version).map_err(ApiError::Parse)? {
+            WalletTransaction::Legacy(transaction) => transaction.signatures.first().copied(),
+            WalletTransaction::V0(transaction) => transaction.signatures.first().copied(),
+        }
+        .filter(|signature| *signature != Signature::default())
+        .ok_or_else(|| bad_request("Transaction is not signed".to_string()))?;
+
+        let mut ledger = self.ledger.lock();
+        let (sold, bought) = (input_amount as f64 / BASE_UNITS, output_amount as f64 / BASE_UNITS);
+        let held = ledger.balances.get(input.mint).copied().unwrap_or(0.0);
+        if held < sold {
+            return Err(bad_request(format!("Insufficient {} balance: {} held, {} needed", input.symbol, held, sold)));
+        }
+        *ledger.balances.entry(input.mint).or_default() -= sold;
+        *ledger.balances.entry(output.mint).or_default() += bought;
+
+        let status = TransactionStatus::Finalized.as_str().to_string();
+        let now = chrono::Utc::now();
+        let id = ledger.swaps.len() as i64 + 1;
+        ledger.swaps.insert(0, DemoSwap {
+            item: SwapHistoryItem {
+                id,
+                signature: signature.to_string(),
+                input_mint,
+                output_mint,
+                input_amount,
+                output_amount,
+                status: status.clone(),
+                created_at: now.to_rfc3339(),
+            },
+            block_time: now.timestamp(),
+        });
+        Ok(TransactionSubmitResponse { signature: signature.to_string(), status })
+    }
+
+    async fn update_transaction_status(
+        &self,
+        signature: &str,
+        update: &TransactionStatusUpdate,
+        _jwt_token: &str,
+    ) -> Result<(), ApiError> {
+        let mut ledger = self.ledger.lock();
+        let swap = ledger
+            .swaps
+            .iter_mut()
+            .find(|swap| swap.item.signature == signature)
+            .ok_or_else(|| ApiError::Api { status: 404, message: "Transaction not found".to_string() })?;
+        swap.item.status = update.status.as_str().to_string();
+        Ok(())
+    }
+
+    async fn record_transaction_latency(
+        &self,
+        _signature: &str,
+        _latency: &shared::dto::latency::SwapLatency,
+        _jwt_token: &str,
+    ) -> Result<(), ApiError> {
+        Ok(())
+    }
+
+    async fn get_token_balances(&self, _address: &str) -> Result<Vec<TokenBalance>, ApiError> {
+        let ledger = self.ledger.lock();
+        let balances = TOKENS
+            .iter()
+            .skip(1) // SOL is the wallet balance
+            .filter_map(|token| {
+                let balance = ledger.balances.get(token.mint).copied().filter(|balance| *balance > 0.0)?;
+                Some(TokenBalance {
+                    mint: token.mint.to_string(),
+                    symbol: Some(token.symbol.to_string()),
+                    balance,
+                    ui_amount: balance.to_string(),
+                    amount: (balance * 10f64.powi(i32::from(token.decimals))) as u64,
+                    decimals: token.decimals,
+                    program: Default::default(),
+                    transfer_fee: None,
+                })
+            })
+            .collect();
+        Ok(balances)
+    }
+
+    async fn get_token_list(&self) -> Result<Vec<TokenListItem>, ApiError> {
+        Ok(TOKENS
+            .iter()
+            .map(|token| TokenListItem {
+                symbol: token.symbol.to_string(),
+                name: token.name.to_string(),
+                mint: token.mint.to_string(),
+                decimals: token.decimals,
+                logo_uri: None,
+                tags: vec!["verified".to_string()],
+                verified: true,
+                daily_volume: Some(token.liquidity * 3.0),
+            })
+            .collect())
+    }
+
+    async fn get_swap_history(&self, _jwt_token: &str, query: &SwapHistoryQuery) -> Result<SwapHistoryResponse, ApiError> {
+        let (swaps, total) = self.history(query);
+        Ok(SwapHistoryResponse { swaps, total_count: total as i64 })
+    }
+
+    async fn stream_history(
+        &self,
+        _jwt_token: &str,
+        query: &SwapHistoryQuery,
+        on_batch: &mut (dyn for<'b> FnMut(&'b [SwapHistoryItem]) -> Result<(), String> + Send),
+    ) -> Result<u64, String> {
+        let everything = SwapHistoryQuery { limit: usize::MAX, offset: 0, ..query.clone() };
+        let (swaps, _) = self.history(&everything);
+        on_batch(&swaps)?;
+        Ok(swaps.len() as u64)
+    }
+
+    async fn import_trades(&self, _jwt_token: &str, _request: &shared::dto::trades::TradeImportRequest) -> Result<shared::dto::trades::TradeImportResponse, ApiError> {
+        Err(unavailable("Trade imports are"))
+    }
+
+    async fn get_trade_stats(&self, _jwt_token: &str) -> Result<shared::dto::trades::TradeStatsResponse, ApiError> {
+        Err(unavailable("Trade statistics are"))
+    }
+
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: usize) -> Result<Vec<OHLC>, ApiError> {
+        let token = token_by_symbol(symbol).ok_or_else(|| bad_request(format!("Unknown symbol {}", symbol)))?;
+        self.candles(token, timeframe, limit)
+    }
+
+    async fn get_candles_batch(&self, symbols: &[String], timeframe: &str) -> Result<CandleBatchResponse, ApiError> {
+        let mut candles = BTreeMap::new();
+        let mut errors = BTreeMap::new();
+        for symbol in symbols {
+            let result = token_by_symbol(symbol)
+                .ok_or_else(|| bad_request(format!("Unknown symbol {}", symbol)))
+                .and_then(|token| self.candles(token, timeframe, 24));
+            match result {
+                Ok(series) => candles.insert(symbol.clone(), series),
+                Err(e) => errors.insert(symbol.clone(), e.to_string()),
+            };
+        }
+        Ok(CandleBatchResponse { timeframe: timeframe.to_string(), candles, errors })
+    }
+
+    async fn get_streamed_symbols(&self) -> Result<StreamedSymbolsResponse, ApiError> {
+        Ok(StreamedSymbolsResponse {
+            symbols: TOKENS
+                .iter()
+                .map(|token| StreamedSymbolInfo {
+                    symbol: token.symbol.to_string(),
+                    mint: token.mint.to_string(),
+                    require_pyth: false,
+                })
+                .collect(),
+        })
+    }
+
+    async fn get_token_unlocks(&self, _days: i64) -> Result<shared::dto::unlocks::TokenUnlocksResponse, ApiError> {
+        Ok(shared::dto::unlocks::TokenUnlocksResponse { unlocks: Vec::new() })
+    }
+
+    async fn get_depth(&self, input: &str, output: &str) -> Result<DepthResponse, ApiError> {
+        let unknown = |symbol: &str| bad_request(format!("Unknown symbol {}", symbol));
+        let input_token = token_by_symbol(input).ok_or_else(|| unknown(input))?;
+        let output_token = token_by_symbol(output).ok_or_else(|| unknown(output))?;
+        let rungs = DEPTH_SIZES
+            .iter()
+            .map(|&size| {
+                let quote = self.quote(input_token, output_token, (size * BASE_UNITS) as u64);
+                DepthRung {
+                    size,
+                    effective_price: Some(quote.out_amount as f64 / BASE_UNITS / size),
+                    price_impact_pct: Some(quote.price_impact_pct),
+                    error: None,
+                }
+            })
+            .collect();
+        Ok(DepthResponse {
+            input: input.to_string(),
+            output: output.to_string(),
+            rungs,
+            timestamp: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    async fn get_market_analytics(&self, _symbols: &[String], _window: usize) -> Result<MarketAnalyticsResponse, ApiError> {
+        Err(unavailable("Market analytics are"))
+    }
+
+    async fn get_health(&self) -> Result<shared::dto::system::HealthResponse, ApiError> {
+        Err(unavailable("Backend health is"))
+    }
+}
+
+/// [`WalletService`] of the offline demo, see the [module docs](self)
+///
+/// Its balance is the SOL the [`OfflineApi`] ledger holds.
+pub struct OfflineWallet {
+    keypair: Option<Keypair>,
+    api: Arc<OfflineApi>,
+}
+
+impl OfflineWallet {
+    /// Wallet with a fresh keypair, holding what `api` says it holds
+    pub fn new(api: Arc<OfflineApi>) -> Self {
+        Self { keypair: Some(Keypair::new()), api }
+    }
+
+    fn keypair(&self) -> Result<&Keypair, WalletError> {
+        self.keypair
+            .as_ref()
+            .ok_or_else(|| WalletError::SigningError("No keypair loaded".to_string()))
+    }
+}
+
+#[async_trait]
+impl WalletService for OfflineWallet {
+    fn get_public_key(&self) -> Option<String> {
+        self.keypair.as_ref().map(|keypair| keypair.pubkey().to_string())
+    }
+
+    fn sign_transaction(&self, transaction: &mut Transaction) -> Result<Signature, WalletError> {
+        let blockhash = transaction.message.recent_blockhash;
+        transaction
+            .try_sign(&[self.keypair()?], blockhash)
+            .map_err(|e| WalletError::SigningError(e.to_string()))?;
+        transaction
+            .signatures
+            .first()
+            .copied()
+            .ok_or_else(|| WalletError::SigningError("No signature generated".to_string()))
+    }
+
+    fn sign_versioned_transaction(&self, transaction: &mut VersionedTransaction) -> Result<Signature, WalletError> {
+        *transaction = VersionedTransaction::try_new(transaction.message.clone(), &[self.keypair()?])
+            .map_err(|e| WalletError::SigningError(e.to_string()))?;
+        transaction
+            .signatures
+            .first()
+            .copied()
+            .ok_or_else(|| WalletError::SigningError("No signature generated".to_string()))
+    }
+
+    async fn get_balance(&self) -> Result<f64, WalletError> {
+        let address = self
+            .get_public_key()
+            .ok_or_else(|| WalletError::BalanceError("No keypair loaded".to_string()))?;
+        self.api
+            .get_wallet_balance(&address)
+            .await
+            .map(|balance| balance.balance_sol)
+            .map_err(|e| WalletError::BalanceError(e.to_string()))
+    }
+
+    fn disconnect(&mut self) {
+        self.keypair = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sol() -> &'static DemoToken {
+        token_by_symbol("SOL").unwrap()
+    }
+
+    fn usdc() -> &'static DemoToken {
+        token_by_symbol("USDC").unwrap()
+    }
+
+    #[test]
+    fn test_price_walk_is_deterministic() {
+        let (a, b) = (OfflineApi::new(), OfflineApi::new());
+        let walked: Vec<f64> = (0..50).map(|step| a.price_at(sol(), step)).collect();
+        let replayed: Vec<f64> = (0..50).map(|step| b.price_at(sol(), step)).collect();
+        assert_eq!(walked, replayed);
+
+        assert_eq!(walked[0], sol().price);
+        assert!(walked.windows(2).any(|pair| pair[0] != pair[1]));
+        // Mean reversion keeps the walk near where it started
+        assert!(walked.iter().all(|price| (price / sol().price - 1.0).abs() < 0.1));
+
+        // Symbols walk independently
+        let bonk = token_by_symbol("bonk").unwrap();
+        assert_ne!(a.price_at(bonk, 10) / bonk.price, walked[10] / sol().price);
+    }
+
+    #[test]
+    fn test_quote_impact_grows_with_size() {
+        let api = OfflineApi::new();
+        let small = api.quote(sol(), usdc(), 1_000_000_000);
+        let large = api.quote(sol(), usdc(), 10_000 * 1_000_000_000);
+
+        assert!(small.price_impact_pct < 0.01, "{:?}", small);
+        assert!(large.price_impact_pct > 1.0, "{:?}", large);
+
+        // 1 SOL buys about one SOL price of USDC, less the pool fee
+        let out = small.out_amount as f64 / BASE_UNITS;
+        assert!((out / sol().price - (1.0 - POOL_FEE)).abs() < 0.001, "{}", out);
+        // Per unit, the large trade gets less
+        assert!(large.out_amount as f64 / 10_000.0 < small.out_amount as f64);
+    }
+
+    #[tokio::test]
+    async fn test_swap_lands_in_history_and_moves_balances() {
+        let api = Arc::new(OfflineApi::new());
+        let wallet = OfflineWallet::new(Arc::clone(&api));
+        let owner = wallet.get_public_key().unwrap();
+        let amount = 2 * 1_000_000_000;
+
+        let response = api.execute_swap(sol().mint, usdc().mint, amount, 50, &owner, "").await.unwrap();
+        let mut transaction = WalletTransaction::decode(&response.transaction, response.version).unwrap();
+        wallet.sign(&mut transaction).unwrap();
+
+        let out: i64 = response.out_amount.parse().unwrap();
+        let submitted = api
+            .submit_transaction(transaction.encode().unwrap(), response.version, sol().mint.to_string(), usdc().mint.to_string(), amount as i64, out, None, None, "")
+            .await
+            .unwrap();
+
+        let history = api.get_swap_history("", &SwapHistoryQuery { limit: 10, ..Default::default() }).await.unwrap();
+        assert_eq!(history.total_count, 1);
+        assert_eq!(history.swaps[0].signature, submitted.signature);
+        assert_eq!(history.swaps[0].status, "finalized");
+
+        let sol_balance = wallet.get_balance().await.unwrap();
+        assert!((sol_balance - 23.0).abs() < 1e-9);
+        let balances = api.get_token_balances(&owner).await.unwrap();
+        let usdc_balance = balances.iter().find(|b| b.mint == usdc().mint).unwrap().balance;
+        assert!((usdc_balance - (2_500.0 + out as f64 / BASE_UNITS)).abs() < 1e-6);
+
+        // Filters apply to the history as they would on the backend
+        let query = SwapHistoryQuery { limit: 10, input_token: Some(usdc().mint.to_string()), ..Default::default() };
+        assert_eq!(api.get_swap_history("", &query).await.unwrap().total_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_or_unaffordable_swaps_are_rejected() {
+        let api = Arc::new(OfflineApi::new());
+        let owner = OfflineWallet::new(Arc::clone(&api)).get_public_key().unwrap();
+        let response = api.execute_swap(sol().mint, usdc().mint, 1_000_000_000, 50, &owner, "").await.unwrap();
+
+        let unsigned = api
+            .submit_transaction(response.transaction.clone(), response.version, sol().mint.to_string(), usdc().mint.to_string(), 1_000_000_000, 1, None, None, "")
+            .await;
+        assert!(unsigned.unwrap_err().to_string().contains("not signed"));
+
+        let unknown = api.get_swap_quote(sol().mint, "NotAMint", 1, 50).await;
+        assert!(unknown.unwrap_err().to_string().contains("Unknown token"));
+    }
+
+    #[test]
+    fn test_demo_token_never_expires_soon() {
+        let expiry = crate::services::session::token_expiry(&demo_token()).unwrap();
+        assert!(expiry > chrono::Utc::now().timestamp() + 365 * 24 * 3600);
+    }
+}
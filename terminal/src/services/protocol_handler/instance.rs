@@ -0,0 +1,265 @@
+//! # Single Instance
+//!
+//! The first terminal to start listens on a loopback port. A later launch
+//! (the OS running `xforce-terminal xforce://...` when a link is clicked)
+//! finds the port taken, hands its link to the running instance and exits,
+//! so a link never opens a second window.
+//!
+//! ## Protocol
+//!
+//! One line per connection, answered with `OK` or `ERR`:
+//!
+//! ```text
+//! OPEN xforce://swap?in=SOL&out=USDC\n   -> OK\n
+//! FOCUS\n                                -> OK\n
+//! ```
+//!
+//! Lines are capped at [`MAX_LINE_LEN`] and must arrive within [`IO_TIMEOUT`].
+//! The link is only forwarded here; it is parsed and confirmed by the app.
+
+use super::link::MAX_LINK_LEN;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+
+/// Loopback port used when `XFORCE_INSTANCE_PORT` is not set
+pub const DEFAULT_PORT: u16 = 47615;
+
+/// Environment variable overriding the port (e.g. to run two profiles side by side)
+pub const PORT_ENV: &str = "XFORCE_INSTANCE_PORT";
+
+/// Longest request line, including the command word
+pub const MAX_LINE_LEN: usize = MAX_LINK_LEN + 16;
+
+/// How long either side waits for the other
+pub const IO_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What a second launch asks the running instance to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request {
+    /// Show the confirmation prompt for a link
+    Open(String),
+    /// Bring the window to the front (launched without a link)
+    Focus,
+}
+
+impl Request {
+    /// Parse one request line (without the trailing newline)
+    pub fn parse(line: &str) -> Option<Request> {
+        match line.split_once(' ') {
+            Some(("OPEN", link)) if !link.is_empty() && link.len() <= MAX_LINK_LEN => Some(Request::Open(link.to_string())),
+            None if line == "FOCUS" => Some(Request::Focus),
+            _ => None,
+        }
+    }
+
+    fn to_line(&self) -> String {
+        match self {
+            Request::Open(link) => format!("OPEN {}\n", link),
+            Request::Focus => "FOCUS\n".to_string(),
+        }
+    }
+}
+
+/// Outcome of [`claim_or_forward`]
+#[derive(Debug)]
+pub enum Startup {
+    /// No other instance is running; serve requests from later launches
+    Primary(InstanceServer),
+    /// The running instance took the request; this process should exit
+    Forwarded,
+    /// The port is held by something that doesn't answer the protocol;
+    /// run without single-instance handling
+    Standalone,
+}
+
+/// Address of the instance port, honouring `XFORCE_INSTANCE_PORT`
+pub fn instance_addr() -> SocketAddr {
+    let port = std::env::var(PORT_ENV)
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(DEFAULT_PORT);
+    SocketAddr::from((Ipv4Addr::LOCALHOST, port))
+}
+
+/// Become the running instance, or forward `request` to the one that already is
+pub fn claim_or_forward(addr: SocketAddr, request: &Request) -> io::Result<Startup> {
+    match TcpListener::bind(addr) {
+        Ok(listener) => Ok(Startup::Primary(InstanceServer { listener })),
+        Err(e) if e.kind() == io::ErrorKind::AddrInUse => match forward(addr, request) {
+            Ok(()) => Ok(Startup::Forwarded),
+            Err(e) => {
+                tracing::warn!("Instance port {} is taken but did not accept the request: {}", addr, e);
+                Ok(Startup::Standalone)
+            }
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// Send a request to the running instance and wait for its answer
+fn forward(addr: SocketAddr, request: &Request) -> io::Result<()> {
+    let mut stream = TcpStream::connect_timeout(&addr, IO_TIMEOUT)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    stream.write_all(request.to_line().as_bytes())?;
+
+    // Cap the reply so a stranger on the port can't feed us an endless line
+    let mut reply = String::new();
+    BufReader::new(io::Read::take(stream, 16)).read_line(&mut reply)?;
+    match reply.trim_end() {
+        "OK" => Ok(()),
+        other => Err(io::Error::new(io::ErrorKind::InvalidData, format!("unexpected reply {:?}", other))),
+    }
+}
+
+/// Listener held by the running instance
+#[derive(Debug)]
+pub struct InstanceServer {
+    listener: TcpListener,
+}
+
+impl InstanceServer {
+    /// Address the listener is bound to
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accept requests on the tokio runtime until the process exits.
+    /// `on_request` runs once per valid request.
+    pub fn spawn<F>(self, on_request: F) -> io::Result<tokio::task::JoinHandle<()>>
+    where
+        F: Fn(Request) + Send + 'static,
+    {
+        self.listener.set_nonblocking(true)?;
+        let listener = tokio::net::TcpListener::from_std(self.listener)?;
+        Ok(tokio::spawn(async move {
+            loop {
+                let mut stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        tracing::warn!("Instance listener accept failed: {}", e);
+                        continue;
+                    }
+                };
+                // One short request per connection, so they are served in turn
+                match tokio::time::timeout(IO_TIMEOUT, read_request(&mut stream)).await {
+                    Ok(Some(request)) => {
+                        let _ = stream.write_all(b"OK\n").await;
+                        on_request(request);
+                    }
+                    Ok(None) => {
+                        let _ = stream.write_all(b"ERR\n").await;
+                    }
+                    Err(_) => tracing::debug!("Instance request timed out"),
+                }
+            }
+        }))
+    }
+}
+
+/// Read and parse one request line, refusing anything over [`MAX_LINE_LEN`]
+async fn read_request(stream: &mut tokio::net::TcpStream) -> Option<Request> {
+    let mut reader = tokio::io::BufReader::new(stream).take(MAX_LINE_LEN as u64 + 1);
+    let mut line = Vec::new();
+    reader.read_until(b'\n', &mut line).await.ok()?;
+    if line.pop() != Some(b'\n') {
+        tracing::warn!("Rejected instance request: too long or not terminated");
+        return None;
+    }
+    let request = std::str::from_utf8(&line).ok().and_then(|line| Request::parse(line.trim_end_matches('\r')));
+    if request.is_none() {
+        tracing::warn!("Rejected malformed instance request");
+    }
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loopback() -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::LOCALHOST, 0))
+    }
+
+    /// Start a primary instance that reports its requests on a channel
+    fn start_primary() -> (SocketAddr, async_channel::Receiver<Request>) {
+        let Ok(Startup::Primary(server)) = claim_or_forward(loopback(), &Request::Focus) else {
+            panic!("first claim should become the primary instance");
+        };
+        let addr = server.local_addr().unwrap();
+        let (tx, rx) = async_channel::unbounded();
+        server.spawn(move |request| {
+            let _ = tx.try_send(request);
+        }).unwrap();
+        (addr, rx)
+    }
+
+    /// Send a raw line and return the reply
+    async fn send_raw(addr: SocketAddr, bytes: Vec<u8>) -> String {
+        tokio::task::spawn_blocking(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.set_read_timeout(Some(IO_TIMEOUT * 2)).unwrap();
+            let _ = stream.write_all(&bytes);
+            let mut reply = String::new();
+            let _ = BufReader::new(stream).read_line(&mut reply);
+            reply
+        })
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn test_request_parse() {
+        let link = "xforce://swap?in=SOL&out=USDC";
+        assert_eq!(Request::parse(Request::Open(link.to_string()).to_line().trim_end()), Some(Request::Open(link.to_string())));
+        assert_eq!(Request::parse("FOCUS"), Some(Request::Focus));
+        assert_eq!(Request::parse("OPEN "), None);
+        assert_eq!(Request::parse("open xforce://token/SOL"), None);
+        assert_eq!(Request::parse("RUN rm -rf"), None);
+        assert_eq!(Request::parse(&format!("OPEN {}", "a".repeat(MAX_LINK_LEN + 1))), None);
+    }
+
+    #[tokio::test]
+    async fn test_second_launch_forwards_to_primary() {
+        let (addr, requests) = start_primary();
+        let link = Request::Open("xforce://token/BONK".to_string());
+
+        let sent = link.clone();
+        let startup = tokio::task::spawn_blocking(move || claim_or_forward(addr, &sent)).await.unwrap();
+        assert!(matches!(startup, Ok(Startup::Forwarded)));
+        assert_eq!(requests.recv().await.unwrap(), link);
+
+        let startup = tokio::task::spawn_blocking(move || claim_or_forward(addr, &Request::Focus)).await.unwrap();
+        assert!(matches!(startup, Ok(Startup::Forwarded)));
+        assert_eq!(requests.recv().await.unwrap(), Request::Focus);
+    }
+
+    #[tokio::test]
+    async fn test_primary_rejects_bad_requests() {
+        let (addr, requests) = start_primary();
+
+        assert_eq!(send_raw(addr, b"HELLO\n".to_vec()).await, "ERR\n");
+        let mut oversized = b"OPEN xforce://".to_vec();
+        oversized.extend(std::iter::repeat_n(b'a', MAX_LINE_LEN));
+        oversized.push(b'\n');
+        // The connection may be reset before the reply arrives, so only check it wasn't accepted
+        assert_ne!(send_raw(addr, oversized).await, "OK\n");
+        assert_eq!(send_raw(addr, vec![0xff, 0xfe, b'\n']).await, "ERR\n");
+
+        // Still serving after rejecting junk
+        assert_eq!(send_raw(addr, b"OPEN xforce://token/SOL\r\n".to_vec()).await, "OK\n");
+        assert_eq!(requests.recv().await.unwrap(), Request::Open("xforce://token/SOL".to_string()));
+        assert!(requests.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_port_runs_standalone() {
+        // Something else owns the port and never answers
+        let squatter = TcpListener::bind(loopback()).unwrap();
+        let addr = squatter.local_addr().unwrap();
+        let startup = tokio::task::spawn_blocking(move || claim_or_forward(addr, &Request::Focus)).await.unwrap();
+        assert!(matches!(startup, Ok(Startup::Standalone)));
+    }
+}
@@ -0,0 +1,227 @@
+//! # Link Parsing
+//!
+//! Turns an `xforce://` URL into a [`DeepLink`]. Links come from anywhere
+//! (chat messages, web pages), so everything is validated here with the same
+//! parsers the command palette uses, and anything unexpected is rejected
+//! rather than guessed at.
+//!
+//! ## Supported Links
+//!
+//! ```text
+//! xforce://swap?in=SOL&out=USDC&amount=5
+//! xforce://token/BONK                      (or xforce://token?symbol=BONK)
+//! xforce://pay/<address>?amount=0.5&token=USDC&memo=invoice%2042
+//! ```
+
+use crate::app::commands::{self, CommandAction};
+use crate::services::memo;
+use reqwest::Url;
+use thiserror::Error;
+
+/// URL scheme registered with the OS
+pub const SCHEME: &str = "xforce";
+
+/// Longest link accepted; real links are well under this
+pub const MAX_LINK_LEN: usize = 2048;
+
+/// Screen a link opens, with the values to pre-fill
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    /// Swap form with both tokens and optionally the amount
+    Swap { input: String, output: String, amount: Option<f64> },
+    /// Token row in the terminal price list
+    Token { symbol: String },
+    /// Payment request to a wallet address
+    Pay {
+        recipient: String,
+        amount: Option<f64>,
+        token: Option<String>,
+        memo: Option<String>,
+    },
+}
+
+impl DeepLink {
+    /// One-line description for the confirmation prompt
+    pub fn describe(&self) -> String {
+        match self {
+            DeepLink::Swap { input, output, amount: Some(amount) } => format!("Swap {} {} for {}", amount, input, output),
+            DeepLink::Swap { input, output, amount: None } => format!("Swap {} for {}", input, output),
+            DeepLink::Token { symbol } => format!("Show {}", symbol),
+            DeepLink::Pay { recipient, amount, token, .. } => {
+                let token = token.as_deref().unwrap_or("SOL");
+                match amount {
+                    Some(amount) => format!("Pay {} {} to {}", amount, token, recipient),
+                    None => format!("Pay {} to {}", token, recipient),
+                }
+            }
+        }
+    }
+}
+
+/// Why a link was rejected
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum LinkError {
+    #[error("link is longer than {MAX_LINK_LEN} characters")]
+    TooLong,
+    #[error("not an {SCHEME}:// link")]
+    WrongScheme,
+    #[error("malformed link: {0}")]
+    Malformed(String),
+    #[error("unknown link action `{0}`")]
+    UnknownAction(String),
+    #[error("link is missing `{0}`")]
+    Missing(&'static str),
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// Parse and validate an `xforce://` link
+pub fn parse_link(link: &str) -> Result<DeepLink, LinkError> {
+    let link = link.trim();
+    if link.len() > MAX_LINK_LEN {
+        return Err(LinkError::TooLong);
+    }
+    let url = Url::parse(link).map_err(|e| LinkError::Malformed(e.to_string()))?;
+    if url.scheme() != SCHEME {
+        return Err(LinkError::WrongScheme);
+    }
+
+    // `xforce://swap?..` puts the action in the host, `xforce:swap?..` in the path
+    let mut segments: Vec<&str> = url.path().split('/').filter(|segment| !segment.is_empty()).collect();
+    let action = match url.host_str() {
+        Some(host) => host.to_lowercase(),
+        None if !segments.is_empty() => segments.remove(0).to_lowercase(),
+        None => return Err(LinkError::Missing("action")),
+    };
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.into_owned())
+    };
+
+    match action.as_str() {
+        "swap" => {
+            let input = query("in").ok_or(LinkError::Missing("in"))?;
+            let output = query("out").ok_or(LinkError::Missing("out"))?;
+            let amount = query("amount")
+                .map(|a| commands::parse_amount(&a))
+                .transpose()
+                .map_err(LinkError::Invalid)?;
+            match commands::parse_swap(&[&input, &output]).map_err(LinkError::Invalid)? {
+                CommandAction::Swap { input, output, .. } => Ok(DeepLink::Swap { input, output, amount }),
+                other => unreachable!("parse_swap returned {:?}", other),
+            }
+        }
+        "token" => {
+            let symbol = segments
+                .first()
+                .map(|s| s.to_string())
+                .or_else(|| query("symbol"))
+                .ok_or(LinkError::Missing("symbol"))?;
+            Ok(DeepLink::Token { symbol: commands::parse_symbol(&symbol).map_err(LinkError::Invalid)? })
+        }
+        "pay" => {
+            let recipient = segments
+                .first()
+                .map(|s| s.to_string())
+                .or_else(|| query("recipient"))
+                .or_else(|| query("to"))
+                .ok_or(LinkError::Missing("recipient"))?;
+            let amount = query("amount")
+                .map(|a| commands::parse_amount(&a))
+                .transpose()
+                .map_err(LinkError::Invalid)?;
+            let token = query("token")
+                .map(|t| commands::parse_symbol(&t))
+                .transpose()
+                .map_err(LinkError::Invalid)?;
+            let memo = query("memo")
+                .map(|m| memo::validate_memo(&m).map(str::to_string))
+                .transpose()
+                .map_err(|e| LinkError::Invalid(e.to_string()))?;
+            Ok(DeepLink::Pay { recipient: parse_address(&recipient)?, amount, token, memo })
+        }
+        _ => Err(LinkError::UnknownAction(action)),
+    }
+}
+
+/// A base58 wallet address (32 bytes)
+fn parse_address(address: &str) -> Result<String, LinkError> {
+    match bs58::decode(address).into_vec() {
+        Ok(bytes) if bytes.len() == 32 => Ok(address.to_string()),
+        _ => Err(LinkError::Invalid(format!("`{}` is not a wallet address", address))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    #[test]
+    fn test_parse_swap_link() {
+        assert_eq!(
+            parse_link("xforce://swap?in=SOL&out=USDC&amount=5"),
+            Ok(DeepLink::Swap { input: "SOL".to_string(), output: "USDC".to_string(), amount: Some(5.0) })
+        );
+        assert_eq!(
+            parse_link("XFORCE://Swap?out=usdc&in=$bonk"),
+            Ok(DeepLink::Swap { input: "BONK".to_string(), output: "USDC".to_string(), amount: None })
+        );
+        assert_eq!(
+            parse_link("xforce:swap?in=SOL&out=USDC"),
+            Ok(DeepLink::Swap { input: "SOL".to_string(), output: "USDC".to_string(), amount: None })
+        );
+    }
+
+    #[test]
+    fn test_parse_swap_link_rejects_bad_values() {
+        assert_eq!(parse_link("xforce://swap?in=SOL"), Err(LinkError::Missing("out")));
+        assert!(matches!(parse_link("xforce://swap?in=SOL&out=SOL"), Err(LinkError::Invalid(_))));
+        assert!(matches!(parse_link("xforce://swap?in=SOL&out=USDC&amount=-1"), Err(LinkError::Invalid(_))));
+        assert!(matches!(parse_link("xforce://swap?in=SOL&out=USDC&amount=inf"), Err(LinkError::Invalid(_))));
+        // Each value only fills its own slot
+        assert!(matches!(parse_link("xforce://swap?in=5&out=USDC"), Err(LinkError::Invalid(_))));
+        assert!(matches!(parse_link("xforce://swap?in=SOL&out=USDC&amount=BONK"), Err(LinkError::Invalid(_))));
+        assert!(matches!(parse_link("xforce://swap?in=SO%20L&out=USDC"), Err(LinkError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_parse_token_link() {
+        let bonk = Ok(DeepLink::Token { symbol: "BONK".to_string() });
+        assert_eq!(parse_link("xforce://token/bonk"), bonk);
+        assert_eq!(parse_link("xforce://token?symbol=BONK"), bonk);
+        assert_eq!(parse_link("xforce://token"), Err(LinkError::Missing("symbol")));
+    }
+
+    #[test]
+    fn test_parse_pay_link() {
+        let link = format!("xforce://pay/{}?amount=0.5&token=usdc&memo=invoice%2042", ADDRESS);
+        assert_eq!(
+            parse_link(&link),
+            Ok(DeepLink::Pay {
+                recipient: ADDRESS.to_string(),
+                amount: Some(0.5),
+                token: Some("USDC".to_string()),
+                memo: Some("invoice 42".to_string()),
+            })
+        );
+        assert!(matches!(parse_link("xforce://pay/notanaddress"), Err(LinkError::Invalid(_))));
+        assert_eq!(parse_link("xforce://pay"), Err(LinkError::Missing("recipient")));
+
+        let long_memo = format!("xforce://pay/{}?memo={}", ADDRESS, "a".repeat(memo::MAX_MEMO_BYTES + 1));
+        assert!(matches!(parse_link(&long_memo), Err(LinkError::Invalid(_))));
+        let control = format!("xforce://pay/{}?memo=a%0Ab", ADDRESS);
+        assert!(matches!(parse_link(&control), Err(LinkError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_parse_link_rejects_other_links() {
+        assert_eq!(parse_link("https://swap?in=SOL&out=USDC"), Err(LinkError::WrongScheme));
+        assert_eq!(parse_link("xforce://transfer?to=x"), Err(LinkError::UnknownAction("transfer".to_string())));
+        assert!(matches!(parse_link("not a link"), Err(LinkError::Malformed(_))));
+        let long = format!("xforce://swap?in=SOL&out=USDC&pad={}", "a".repeat(MAX_LINK_LEN));
+        assert_eq!(parse_link(&long), Err(LinkError::TooLong));
+    }
+}
@@ -0,0 +1,43 @@
+//! # Protocol Handler
+//!
+//! Opens the terminal from `xforce://` links, e.g. a
+//! `xforce://swap?in=SOL&out=USDC&amount=5` link posted in a chat.
+//!
+//! ## Module Structure
+//!
+//! ```text
+//! protocol_handler/
+//! ├── mod.rs          - Module exports and documentation
+//! ├── link.rs         - Parsing and validating links into a DeepLink
+//! ├── instance.rs     - Single instance: later launches forward their link
+//! └── registration.rs - Per-platform registration of the URL scheme
+//! ```
+//!
+//! ## Flow
+//!
+//! ```text
+//! click link ─> OS runs `xforce-terminal <link>`
+//!                 ├─ instance port free  -> this is the app; show the link
+//!                 └─ instance port taken -> send the link to it, exit
+//!
+//! app receives link -> parse_link -> confirmation prompt -> pre-filled screen
+//! ```
+//!
+//! A link only ever pre-fills a screen, and only after the user confirms the
+//! prompt. Swaps still need the Swap button and payments are never sent.
+
+pub mod instance;
+pub mod link;
+pub mod registration;
+
+pub use instance::{claim_or_forward, instance_addr, InstanceServer, Request, Startup};
+pub use link::{parse_link, DeepLink, LinkError, SCHEME};
+pub use registration::ensure_registered;
+
+/// The `xforce:` link among the process arguments, if launched from one
+pub fn link_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let scheme = format!("{}:", SCHEME);
+    args.into_iter()
+        .skip(1)
+        .find(|arg| arg.get(..scheme.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(&scheme)))
+}
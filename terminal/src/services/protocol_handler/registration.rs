@@ -0,0 +1,137 @@
+//! # Protocol Registration
+//!
+//! Registers this executable as the handler for `xforce://` links so the OS
+//! launches it with the link as its first argument.
+//!
+//! - **Linux**: a `xforce-terminal.desktop` entry in `~/.local/share/applications`
+//!   claiming `x-scheme-handler/xforce`, made the default with `xdg-mime`
+//! - **Windows**: `HKCU\Software\Classes\xforce` (per user, no elevation needed)
+//! - **macOS**: URL schemes can only be declared in the app bundle's
+//!   `Info.plist` (`CFBundleURLTypes`), so there is nothing to do at runtime
+//!
+//! Registration runs on first start and again whenever the executable moves;
+//! the registered path is remembered in the local database.
+
+use super::link::SCHEME;
+use crate::core::store::{store, Document};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What was last registered
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Registration {
+    scheme: String,
+    exe: PathBuf,
+}
+
+/// Register the `xforce://` handler unless this executable already is.
+/// Returns whether anything was registered.
+pub fn ensure_registered() -> io::Result<bool> {
+    let exe = std::env::current_exe()?;
+    let current = Registration { scheme: SCHEME.to_string(), exe };
+    let settings = store().settings();
+
+    let previous = settings.load::<Registration>(Document::ProtocolRegistration).ok().flatten();
+    if previous.as_ref() == Some(&current) {
+        return Ok(false);
+    }
+
+    if !register(&current.exe)? {
+        return Ok(false);
+    }
+    settings.save(Document::ProtocolRegistration, &current).map_err(io::Error::other)?;
+    tracing::info!("Registered {}:// links to {:?}", SCHEME, current.exe);
+    Ok(true)
+}
+
+#[cfg(target_os = "linux")]
+fn register(exe: &Path) -> io::Result<bool> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "neither XDG_DATA_HOME nor HOME is set"))?;
+    let applications = data_home.join("applications");
+    std::fs::create_dir_all(&applications)?;
+    std::fs::write(applications.join(DESKTOP_FILE), desktop_entry(exe))?;
+
+    let status = std::process::Command::new("xdg-mime")
+        .args(["default", DESKTOP_FILE, &format!("x-scheme-handler/{}", SCHEME)])
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("xdg-mime exited with {}", status)));
+    }
+    Ok(true)
+}
+
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE: &str = "xforce-terminal.desktop";
+
+/// Desktop entry that hands `xforce://` links to `exe`
+#[cfg(target_os = "linux")]
+fn desktop_entry(exe: &Path) -> String {
+    // Exec arguments are double-quoted with ", `, $ and \ backslash-escaped, and
+    // that backslash is itself escaped by the string rules, so it is written twice
+    let mut quoted = String::from("\"");
+    for c in exe.to_string_lossy().chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push_str("\\\\");
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=XForce Terminal\n\
+         Exec={} %u\n\
+         Terminal=false\n\
+         NoDisplay=true\n\
+         MimeType=x-scheme-handler/{};\n",
+        quoted, SCHEME
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn register(exe: &Path) -> io::Result<bool> {
+    let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+    let command = format!("\"{}\" \"%1\"", exe.display());
+    let entries: [(String, Option<&str>, String); 3] = [
+        (key.clone(), None, "URL:XForce Terminal".to_string()),
+        (key.clone(), Some("URL Protocol"), String::new()),
+        (format!(r"{}\shell\open\command", key), None, command),
+    ];
+
+    for (key, name, data) in entries {
+        let mut reg = std::process::Command::new("reg");
+        reg.args(["add", &key, "/f", "/t", "REG_SZ", "/d", &data]);
+        match name {
+            Some(name) => reg.args(["/v", name]),
+            None => reg.arg("/ve"),
+        };
+        let status = reg.status()?;
+        if !status.success() {
+            return Err(io::Error::other(format!("reg add {} exited with {}", key, status)));
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn register(_exe: &Path) -> io::Result<bool> {
+    tracing::debug!("{}:// is declared in the app bundle's Info.plist on this platform", SCHEME);
+    Ok(false)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop_entry_quotes_exec_path() {
+        let entry = desktop_entry(Path::new("/opt/x force/$bin"));
+        assert!(entry.contains("Exec=\"/opt/x force/\\\\$bin\" %u\n"));
+        assert!(entry.contains("MimeType=x-scheme-handler/xforce;\n"));
+    }
+}
@@ -0,0 +1,163 @@
+//! # Login Session Expiry
+//!
+//! Reads the `exp` claim of the backend JWT so the terminal knows when the
+//! session ends, and decides when to warn, when to refresh and when to refuse
+//! long operations.
+//!
+//! The payload is only base64-decoded, never verified: the backend checks the
+//! signature on every request, and all we need here is the expiry time.
+//!
+//! ## Timeline
+//!
+//! ```text
+//!  ... valid ... | 10 min: warn | 5 min: refresh via /api/auth/refresh | 1 min: no swaps | expired
+//! ```
+//!
+//! A refresh can only succeed while the token is still valid, so a failed
+//! attempt is retried every [`REFRESH_RETRY_SECS`] until expiry.
+//!
+//! All times are Unix seconds so the schedule can be tested with synthetic tokens.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::Deserialize;
+
+/// Start warning in the status bar this many seconds before expiry
+pub const WARN_BEFORE_SECS: i64 = 10 * 60;
+
+/// Refresh the token this many seconds before expiry
+pub const REFRESH_BEFORE_SECS: i64 = 5 * 60;
+
+/// Seconds between refresh attempts after one fails
+pub const REFRESH_RETRY_SECS: i64 = 30;
+
+/// Least session time left to start a swap or other long operation
+pub const MIN_SECS_FOR_LONG_OPERATION: i64 = 60;
+
+#[derive(Deserialize)]
+struct ExpiryClaim {
+    exp: i64,
+}
+
+/// Expiry of a JWT as Unix seconds, or `None` if it isn't a JWT with an `exp` claim
+pub fn token_expiry(token: &str) -> Option<i64> {
+    let payload = token.split('.').nth(1)?;
+    let json = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    serde_json::from_slice::<ExpiryClaim>(&json).ok().map(|claim| claim.exp)
+}
+
+/// How close the session is to expiring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatus {
+    Valid,
+    /// Under [`WARN_BEFORE_SECS`] left
+    ExpiringSoon,
+    Expired,
+}
+
+impl SessionStatus {
+    pub fn at(expires_at: i64, now: i64) -> Self {
+        match expires_at - now {
+            left if left <= 0 => Self::Expired,
+            left if left < WARN_BEFORE_SECS => Self::ExpiringSoon,
+            _ => Self::Valid,
+        }
+    }
+}
+
+/// Whether to ask the backend for a fresh token now.
+///
+/// Due inside the last [`REFRESH_BEFORE_SECS`] of a still-valid session,
+/// at most once every [`REFRESH_RETRY_SECS`].
+pub fn refresh_due(expires_at: i64, now: i64, last_attempt: Option<i64>) -> bool {
+    let left = expires_at - now;
+    let retry_elapsed = last_attempt.is_none_or(|attempt| now - attempt >= REFRESH_RETRY_SECS);
+    left > 0 && left <= REFRESH_BEFORE_SECS && retry_elapsed
+}
+
+/// Refuse to start a long operation that could outlive the session.
+/// Tokens without a readable expiry are left to the backend to judge.
+pub fn check_long_operation(expires_at: Option<i64>, now: i64) -> Result<(), String> {
+    match expires_at.map(|exp| exp - now) {
+        Some(left) if left <= 0 => Err("Your session has expired. Log in again to continue.".to_string()),
+        Some(left) if left < MIN_SECS_FOR_LONG_OPERATION => Err(format!(
+            "Your session expires in {}s. Log in again before starting a swap.",
+            left
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Time left as "1h 05m", "4m 09s" or "12s"
+pub fn format_countdown(secs: i64) -> String {
+    let secs = secs.max(0);
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn jwt(payload: &str) -> String {
+        format!("eyJhbGciOiJIUzI1NiJ9.{}.c2lnbmF0dXJl", URL_SAFE_NO_PAD.encode(payload))
+    }
+
+    #[test]
+    fn test_token_expiry_reads_exp_claim() {
+        let token = jwt(r#"{"sub":"1","username":"alice","exp":1700003600,"iat":1700000000,"jti":"x"}"#);
+        assert_eq!(token_expiry(&token), Some(1_700_003_600));
+
+        assert_eq!(token_expiry(&jwt(r#"{"sub":"1"}"#)), None);
+        assert_eq!(token_expiry("not-a-jwt"), None);
+        assert_eq!(token_expiry("a.!!!.c"), None);
+    }
+
+    #[test]
+    fn test_status_thresholds() {
+        assert_eq!(SessionStatus::at(NOW + WARN_BEFORE_SECS, NOW), SessionStatus::Valid);
+        assert_eq!(SessionStatus::at(NOW + WARN_BEFORE_SECS - 1, NOW), SessionStatus::ExpiringSoon);
+        assert_eq!(SessionStatus::at(NOW + 1, NOW), SessionStatus::ExpiringSoon);
+        assert_eq!(SessionStatus::at(NOW, NOW), SessionStatus::Expired);
+    }
+
+    #[test]
+    fn test_refresh_scheduling() {
+        // Not yet inside the refresh window
+        assert!(!refresh_due(NOW + REFRESH_BEFORE_SECS + 1, NOW, None));
+        assert!(refresh_due(NOW + REFRESH_BEFORE_SECS, NOW, None));
+
+        // A failed attempt is retried, but not every frame
+        let exp = NOW + 120;
+        assert!(!refresh_due(exp, NOW, Some(NOW - REFRESH_RETRY_SECS + 1)));
+        assert!(refresh_due(exp, NOW, Some(NOW - REFRESH_RETRY_SECS)));
+
+        // An expired token can't be refreshed
+        assert!(!refresh_due(NOW, NOW, None));
+    }
+
+    #[test]
+    fn test_long_operations_need_a_minute() {
+        assert!(check_long_operation(Some(NOW + MIN_SECS_FOR_LONG_OPERATION), NOW).is_ok());
+        assert!(check_long_operation(Some(NOW + MIN_SECS_FOR_LONG_OPERATION - 1), NOW)
+            .unwrap_err()
+            .contains("59s"));
+        assert!(check_long_operation(Some(NOW - 5), NOW).unwrap_err().contains("expired"));
+        assert!(check_long_operation(None, NOW).is_ok());
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(3 * 3600 + 5 * 60 + 9), "3h 05m");
+        assert_eq!(format_countdown(249), "4m 09s");
+        assert_eq!(format_countdown(12), "12s");
+        assert_eq!(format_countdown(-3), "0s");
+    }
+}
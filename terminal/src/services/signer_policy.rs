@@ -0,0 +1,686 @@
+//! # Session Signer Policy
+//!
+//! Scoped signing sessions that let schedulers (DCA, TWAP) sign without the
+//! user at the keyboard, and the policy engine that decides whether a given
+//! auto-sign request is covered by one of them.
+//!
+//! ## Grants
+//!
+//! A [`SessionGrant`] is created by the user (after unlocking the keystore)
+//! and limits what it may sign:
+//!
+//! | Limit              | Rule                                                  |
+//! |--------------------|-------------------------------------------------------|
+//! | `max_amount`       | Input amount per transaction, in `input_mint` units   |
+//! | `allowed_programs` | Every instruction's program must be in the list       |
+//! | `allowed_mints`    | The destination mint must be in the list              |
+//! | `expires_at`       | Requests at or after this instant are refused         |
+//!
+//! Empty allowlists match nothing. Evaluation is pure: the caller supplies the
+//! clock, so everything here is deterministic.
+//!
+//! ## Requests
+//!
+//! A [`SignRequest`] is read from the transaction by [`derive_request`], never
+//! taken from the scheduler asking for the signature:
+//!
+//! - the input is what the wallet pays out: System transfers of SOL (reported
+//!   under the wrapped SOL mint) and token `TransferChecked`s it signs
+//! - the destination mint is that of the associated token account the
+//!   transaction creates for the wallet (a swap's output), or the input mint
+//!   when it creates none (a plain transfer)
+//!
+//! Anything that would let the wallet's funds move in a way not accounted for
+//! above is refused: unchecked `Transfer`s (no mint to check), approvals,
+//! burns, authority changes, closing an account to someone else, spends of
+//! more than one mint, and spend accounts loaded from lookup tables.
+
+use chrono::{DateTime, Utc};
+use solana_sdk::message::compiled_instruction::CompiledInstruction;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+
+/// Compute Budget program (priority fees), part of almost every swap
+pub const COMPUTE_BUDGET_PROGRAM: &str = "ComputeBudget111111111111111111111111111111";
+/// Jupiter aggregator v6
+pub const JUPITER_PROGRAM: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUJoi5QNyTaV4";
+/// Associated Token Account program (destination account creation)
+pub const ASSOCIATED_TOKEN_PROGRAM: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+/// SPL Token program
+pub const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+/// System program (wrapping SOL)
+pub const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+/// Token-2022 program
+pub const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+/// Wrapped SOL mint; SOL the wallet pays out is reported under it
+pub const WRAPPED_SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// System Program `Transfer` instruction index
+const SYSTEM_TRANSFER_TAG: u32 = 2;
+
+/// Token instructions (same tags in both token programs)
+const TOKEN_INITIALIZE_ACCOUNT_TAG: u8 = 1;
+const TOKEN_CLOSE_ACCOUNT_TAG: u8 = 9;
+const TOKEN_TRANSFER_CHECKED_TAG: u8 = 12;
+const TOKEN_INITIALIZE_ACCOUNT_2_TAG: u8 = 16;
+const TOKEN_SYNC_NATIVE_TAG: u8 = 17;
+const TOKEN_INITIALIZE_ACCOUNT_3_TAG: u8 = 18;
+
+/// Programs a new grant allows unless the user edits the list
+pub const DEFAULT_ALLOWED_PROGRAMS: [&str; 5] = [
+    COMPUTE_BUDGET_PROGRAM,
+    JUPITER_PROGRAM,
+    ASSOCIATED_TOKEN_PROGRAM,
+    TOKEN_PROGRAM,
+    SYSTEM_PROGRAM,
+];
+
+/// A scoped, time-limited permission to sign without prompting
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionGrant {
+    pub id: u64,
+    /// Name shown in Settings and the audit log (e.g. "SOL DCA")
+    pub label: String,
+    /// Mint the amount cap is expressed in
+    pub input_mint: String,
+    /// Largest input amount per transaction, in `input_mint` base units
+    pub max_amount: u64,
+    pub allowed_programs: Vec<String>,
+    pub allowed_mints: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl SessionGrant {
+    /// Whether the grant can still match requests at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now < self.expires_at
+    }
+}
+
+/// What a scheduler wants signed
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignRequest {
+    pub input_mint: String,
+    /// Input amount in `input_mint` base units
+    pub amount: u64,
+    pub destination_mint: String,
+    /// Program ids invoked by the transaction's instructions
+    pub programs: Vec<String>,
+}
+
+/// Why a single grant does not cover a request
+#[derive(Debug, Clone, PartialEq)]
+pub enum Denial {
+    Expired,
+    InputMintMismatch { expected: String },
+    AmountExceeded { requested: u64, max: u64 },
+    ProgramNotAllowed(String),
+    MintNotAllowed(String),
+}
+
+impl fmt::Display for Denial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Denial::Expired => write!(f, "grant expired"),
+            Denial::InputMintMismatch { expected } => write!(f, "grant only spends {}", expected),
+            Denial::AmountExceeded { requested, max } => {
+                write!(f, "amount {} exceeds the cap of {}", requested, max)
+            }
+            Denial::ProgramNotAllowed(program) => write!(f, "program {} is not allowed", program),
+            Denial::MintNotAllowed(mint) => write!(f, "destination mint {} is not allowed", mint),
+        }
+    }
+}
+
+/// Why no grant covers a request
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyError {
+    /// There are no grants at all
+    NoGrants,
+    /// Every grant refused; one reason per grant id
+    Denied(Vec<(u64, Denial)>),
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyError::NoGrants => write!(f, "no signing session has been granted"),
+            PolicyError::Denied(denials) => {
+                write!(f, "no signing session allows this transaction")?;
+                for (id, denial) in denials {
+                    write!(f, "; grant #{}: {}", id, denial)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolicyError {}
+
+/// Why no [`SignRequest`] could be read from a transaction
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeriveError {
+    /// The wallet pays nothing out that could be checked against a cap
+    NoSpend,
+    /// It pays out more than one mint
+    MixedInputMints(Vec<String>),
+    /// It creates token accounts for more than one output mint
+    AmbiguousDestination(Vec<String>),
+    /// A token instruction with this tag is signed by the wallet
+    UnsupportedTokenInstruction(u8),
+    /// A System instruction other than `Transfer` is funded by the wallet
+    UnsupportedSystemInstruction,
+    /// An account the check needs is loaded from a lookup table
+    LookupTableAccount,
+    /// An instruction is too short for its type
+    Malformed,
+}
+
+impl fmt::Display for DeriveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeriveError::NoSpend => write!(f, "no transfer from this wallet to check against a grant"),
+            DeriveError::MixedInputMints(mints) => write!(f, "spends more than one mint ({})", mints.join(", ")),
+            DeriveError::AmbiguousDestination(mints) => {
+                write!(f, "creates token accounts for more than one mint ({})", mints.join(", "))
+            }
+            DeriveError::UnsupportedTokenInstruction(tag) => {
+                write!(f, "token instruction {} signed by this wallet is not supported", tag)
+            }
+            DeriveError::UnsupportedSystemInstruction => {
+                write!(f, "system instruction funded by this wallet is not a transfer")
+            }
+            DeriveError::LookupTableAccount => write!(f, "a spend account is loaded from a lookup table"),
+            DeriveError::Malformed => write!(f, "malformed instruction"),
+        }
+    }
+}
+
+impl std::error::Error for DeriveError {}
+
+/// Program ids invoked by a message's instructions, in first-use order
+///
+/// A dangling index can't name an allowed program, so it is kept visible to
+/// the policy as `<invalid program index N>` instead of being skipped.
+pub fn message_programs(message: &VersionedMessage) -> Vec<String> {
+    let keys = message.static_account_keys();
+    let mut programs: Vec<String> = Vec::new();
+    for instruction in message.instructions() {
+        let program = keys
+            .get(instruction.program_id_index as usize)
+            .map(|key| key.to_string())
+            .unwrap_or_else(|| format!("<invalid program index {}>", instruction.program_id_index));
+        if !programs.contains(&program) {
+            programs.push(program);
+        }
+    }
+    programs
+}
+
+/// Read what a message signed by `owner` spends and where it goes, see the
+/// [module docs](self#requests)
+pub fn derive_request(message: &VersionedMessage, owner: &Pubkey) -> Result<SignRequest, DeriveError> {
+    let system_program = Pubkey::from_str_const(SYSTEM_PROGRAM);
+    let token_programs = [Pubkey::from_str_const(TOKEN_PROGRAM), Pubkey::from_str_const(TOKEN_2022_PROGRAM)];
+    let associated_token_program = Pubkey::from_str_const(ASSOCIATED_TOKEN_PROGRAM);
+
+    let keys = message.static_account_keys();
+    // Accounts past the listed keys come from lookup tables, which we can't read here
+    let account = |instruction: &CompiledInstruction, position: usize| -> Result<&Pubkey, DeriveError> {
+        let index = *instruction.accounts.get(position).ok_or(DeriveError::Malformed)?;
+        keys.get(index as usize).ok_or(DeriveError::LookupTableAccount)
+    };
+
+    let mut spends: Vec<(Pubkey, u64)> = Vec::new();
+    let mut created_mints: Vec<Pubkey> = Vec::new();
+    for instruction in message.instructions() {
+        let Some(program) = keys.get(instruction.program_id_index as usize) else {
+            continue; // Reported by message_programs, which no grant allows
+        };
+        let data = instruction.data.as_slice();
+
+        if *program == system_program {
+            let funded_by_owner = instruction
+                .accounts
+                .first()
+                .is_some_and(|&index| keys.get(index as usize) == Some(owner));
+            if !funded_by_owner {
+                continue;
+            }
+            let tag = data.get(..4).and_then(|tag| tag.try_into().ok()).map(u32::from_le_bytes);
+            if tag != Some(SYSTEM_TRANSFER_TAG) {
+                return Err(DeriveError::UnsupportedSystemInstruction);
+            }
+            let lamports = read_u64(data, 4).ok_or(DeriveError::Malformed)?;
+            spends.push((Pubkey::from_str_const(WRAPPED_SOL_MINT), lamports));
+        } else if token_programs.contains(program) {
+            match data.first().copied() {
+                Some(TOKEN_TRANSFER_CHECKED_TAG) => {
+                    // source, mint, destination, authority; amount, decimals
+                    if account(instruction, 3)? != owner {
+                        continue;
+                    }
+                    let amount = read_u64(data, 1).ok_or(DeriveError::Malformed)?;
+                    spends.push((*account(instruction, 1)?, amount));
+                }
+                Some(TOKEN_CLOSE_ACCOUNT_TAG) => {
+                    // account, destination, owner: unwrapping SOL back to the wallet is fine
+                    if account(instruction, 1)? != owner {
+                        return Err(DeriveError::UnsupportedTokenInstruction(TOKEN_CLOSE_ACCOUNT_TAG));
+                    }
+                }
+                Some(
+                    TOKEN_INITIALIZE_ACCOUNT_TAG
+                    | TOKEN_INITIALIZE_ACCOUNT_2_TAG
+                    | TOKEN_INITIALIZE_ACCOUNT_3_TAG
+                    | TOKEN_SYNC_NATIVE_TAG,
+                ) => {}
+                Some(tag) => {
+                    let signed_by_owner = instruction
+                        .accounts
+                        .iter()
+                        .any(|&index| keys.get(index as usize) == Some(owner));
+                    if signed_by_owner {
+                        return Err(DeriveError::UnsupportedTokenInstruction(tag));
+                    }
+                }
+                None => return Err(DeriveError::Malformed),
+            }
+        } else if *program == associated_token_program {
+            // Create / CreateIdempotent: payer, account, wallet, mint, system, token program
+            if matches!(data, [] | [0] | [1]) && account(instruction, 2)? == owner {
+                let mint = *account(instruction, 3)?;
+                if !created_mints.contains(&mint) {
+                    created_mints.push(mint);
+                }
+            }
+        }
+    }
+
+    let Some(&(input_mint, _)) = spends.first() else {
+        return Err(DeriveError::NoSpend);
+    };
+    if spends.iter().any(|(mint, _)| *mint != input_mint) {
+        let mut mints: Vec<String> = Vec::new();
+        for (mint, _) in &spends {
+            if !mints.contains(&mint.to_string()) {
+                mints.push(mint.to_string());
+            }
+        }
+        return Err(DeriveError::MixedInputMints(mints));
+    }
+    let amount = spends
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(DeriveError::Malformed)?;
+
+    // Wrapping SOL creates the wallet's wrapped SOL account; that is the input side
+    created_mints.retain(|mint| *mint != input_mint);
+    let destination_mint = match created_mints.as_slice() {
+        [] => input_mint,
+        [mint] => *mint,
+        mints => return Err(DeriveError::AmbiguousDestination(mints.iter().map(Pubkey::to_string).collect())),
+    };
+
+    Ok(SignRequest {
+        input_mint: input_mint.to_string(),
+        amount,
+        destination_mint: destination_mint.to_string(),
+        programs: message_programs(message),
+    })
+}
+
+/// Little-endian `u64` at `offset` of instruction data
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)?.try_into().ok().map(u64::from_le_bytes)
+}
+
+/// Check one grant against a request. Reports the first failed rule, in
+/// table order (expiry first, so stale grants never leak other details).
+pub fn check_grant(grant: &SessionGrant, request: &SignRequest, now: DateTime<Utc>) -> Result<(), Denial> {
+    if !grant.is_active(now) {
+        return Err(Denial::Expired);
+    }
+    if request.input_mint != grant.input_mint {
+        return Err(Denial::InputMintMismatch { expected: grant.input_mint.clone() });
+    }
+    if request.amount > grant.max_amount {
+        return Err(Denial::AmountExceeded {
+            requested: request.amount,
+            max: grant.max_amount,
+        });
+    }
+    if let Some(program) = request
+        .programs
+        .iter()
+        .find(|program| !grant.allowed_programs.contains(program))
+    {
+        return Err(Denial::ProgramNotAllowed(program.clone()));
+    }
+    if !grant.allowed_mints.contains(&request.destination_mint) {
+        return Err(Denial::MintNotAllowed(request.destination_mint.clone()));
+    }
+    Ok(())
+}
+
+/// Find the first grant that covers `request`
+pub fn evaluate<'a>(
+    grants: &'a [SessionGrant],
+    request: &SignRequest,
+    now: DateTime<Utc>,
+) -> Result<&'a SessionGrant, PolicyError> {
+    if grants.is_empty() {
+        return Err(PolicyError::NoGrants);
+    }
+
+    let mut denials = Vec::with_capacity(grants.len());
+    for grant in grants {
+        match check_grant(grant, request, now) {
+            Ok(()) => return Ok(grant),
+            Err(denial) => denials.push((grant.id, denial)),
+        }
+    }
+    Err(PolicyError::Denied(denials))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use crate::services::token_transfer::{associated_token_address, sol_transfer_instruction, token_transfer_instructions};
+    use shared::dto::tokens::TokenProgram;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::{v0, AddressLookupTableAccount, Message};
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const SOL: &str = "So11111111111111111111111111111111111111112";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    const UNKNOWN_PROGRAM: &str = "Unknown111111111111111111111111111111111111";
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 2, 18, 12, 0, 0).unwrap()
+    }
+
+    fn grant(id: u64) -> SessionGrant {
+        SessionGrant {
+            id,
+            label: format!("grant {}", id),
+            input_mint: USDC.to_string(),
+            max_amount: 10_000_000,
+            allowed_programs: DEFAULT_ALLOWED_PROGRAMS.iter().map(|p| p.to_string()).collect(),
+            allowed_mints: vec![SOL.to_string()],
+            created_at: now() - Duration::hours(1),
+            expires_at: now() + Duration::hours(1),
+        }
+    }
+
+    fn request(amount: u64) -> SignRequest {
+        SignRequest {
+            input_mint: USDC.to_string(),
+            amount,
+            destination_mint: SOL.to_string(),
+            programs: vec![COMPUTE_BUDGET_PROGRAM.to_string(), JUPITER_PROGRAM.to_string()],
+        }
+    }
+
+    #[test]
+    fn test_request_within_limits_is_allowed() {
+        assert_eq!(check_grant(&grant(1), &request(5_000_000), now()), Ok(()));
+    }
+
+    #[test]
+    fn test_amount_cap_is_inclusive() {
+        assert_eq!(check_grant(&grant(1), &request(10_000_000), now()), Ok(()));
+        assert_eq!(
+            check_grant(&grant(1), &request(10_000_001), now()),
+            Err(Denial::AmountExceeded { requested: 10_000_001, max: 10_000_000 })
+        );
+    }
+
+    #[test]
+    fn test_zero_amount_is_within_cap() {
+        assert_eq!(check_grant(&grant(1), &request(0), now()), Ok(()));
+    }
+
+    #[test]
+    fn test_expiry_boundary() {
+        let grant = grant(1);
+        let just_before = grant.expires_at - Duration::milliseconds(1);
+        assert_eq!(check_grant(&grant, &request(1), just_before), Ok(()));
+        assert_eq!(check_grant(&grant, &request(1), grant.expires_at), Err(Denial::Expired));
+        assert_eq!(
+            check_grant(&grant, &request(1), grant.expires_at + Duration::days(1)),
+            Err(Denial::Expired)
+        );
+    }
+
+    #[test]
+    fn test_expiry_is_reported_before_other_violations() {
+        let expired = now() + Duration::hours(2);
+        let mut bad = request(u64::MAX);
+        bad.programs.push(UNKNOWN_PROGRAM.to_string());
+        bad.destination_mint = BONK.to_string();
+        assert_eq!(check_grant(&grant(1), &bad, expired), Err(Denial::Expired));
+    }
+
+    #[test]
+    fn test_input_mint_must_match() {
+        let mut other_input = request(1);
+        other_input.input_mint = SOL.to_string();
+        assert_eq!(
+            check_grant(&grant(1), &other_input, now()),
+            Err(Denial::InputMintMismatch { expected: USDC.to_string() })
+        );
+    }
+
+    #[test]
+    fn test_every_program_must_be_allowed() {
+        let mut request = request(1);
+        request.programs.push(UNKNOWN_PROGRAM.to_string());
+        assert_eq!(
+            check_grant(&grant(1), &request, now()),
+            Err(Denial::ProgramNotAllowed(UNKNOWN_PROGRAM.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_request_without_instructions_passes_program_check() {
+        let mut request = request(1);
+        request.programs.clear();
+        assert_eq!(check_grant(&grant(1), &request, now()), Ok(()));
+    }
+
+    #[test]
+    fn test_empty_program_allowlist_matches_nothing() {
+        let mut grant = grant(1);
+        grant.allowed_programs.clear();
+        assert_eq!(
+            check_grant(&grant, &request(1), now()),
+            Err(Denial::ProgramNotAllowed(COMPUTE_BUDGET_PROGRAM.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_destination_mint_must_be_allowed() {
+        let mut to_bonk = request(1);
+        to_bonk.destination_mint = BONK.to_string();
+        assert_eq!(
+            check_grant(&grant(1), &to_bonk, now()),
+            Err(Denial::MintNotAllowed(BONK.to_string()))
+        );
+
+        let mut no_mints = grant(1);
+        no_mints.allowed_mints.clear();
+        assert_eq!(
+            check_grant(&no_mints, &request(1), now()),
+            Err(Denial::MintNotAllowed(SOL.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_without_grants() {
+        assert_eq!(evaluate(&[], &request(1), now()), Err(PolicyError::NoGrants));
+    }
+
+    #[test]
+    fn test_evaluate_returns_first_matching_grant() {
+        let mut small = grant(1);
+        small.max_amount = 1_000;
+        let large = grant(2);
+        let also_large = grant(3);
+        let grants = [small, large, also_large];
+
+        assert_eq!(evaluate(&grants, &request(500), now()).unwrap().id, 1);
+        assert_eq!(evaluate(&grants, &request(5_000), now()).unwrap().id, 2);
+    }
+
+    #[test]
+    fn test_evaluate_skips_expired_grants() {
+        let mut expired = grant(1);
+        expired.expires_at = now() - Duration::seconds(1);
+        let grants = [expired, grant(2)];
+
+        assert_eq!(evaluate(&grants, &request(1), now()).unwrap().id, 2);
+    }
+
+    #[test]
+    fn test_evaluate_reports_every_denial() {
+        let mut expired = grant(1);
+        expired.expires_at = now();
+        let mut small = grant(2);
+        small.max_amount = 1;
+        let grants = [expired, small];
+
+        assert_eq!(
+            evaluate(&grants, &request(2), now()),
+            Err(PolicyError::Denied(vec![
+                (1, Denial::Expired),
+                (2, Denial::AmountExceeded { requested: 2, max: 1 }),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_policy_error_messages() {
+        assert_eq!(PolicyError::NoGrants.to_string(), "no signing session has been granted");
+        let denied = PolicyError::Denied(vec![(4, Denial::MintNotAllowed(BONK.to_string()))]);
+        assert_eq!(
+            denied.to_string(),
+            format!("no signing session allows this transaction; grant #4: destination mint {} is not allowed", BONK)
+        );
+    }
+
+    const OWNER: Pubkey = Pubkey::new_from_array([1; 32]);
+    const RECIPIENT: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+
+    fn message(instructions: &[Instruction]) -> VersionedMessage {
+        VersionedMessage::Legacy(Message::new(instructions, Some(&OWNER)))
+    }
+
+    fn token_instruction(accounts: Vec<AccountMeta>, data: Vec<u8>) -> Instruction {
+        Instruction { program_id: Pubkey::from_str_const(TOKEN_PROGRAM), accounts, data }
+    }
+
+    fn create_account_for(wallet: &Pubkey, mint: &str) -> Instruction {
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &OWNER,
+            wallet,
+            &Pubkey::from_str_const(mint),
+            &Pubkey::from_str_const(TOKEN_PROGRAM),
+        )
+    }
+
+    fn route(accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction { program_id: Pubkey::from_str_const(JUPITER_PROGRAM), accounts, data: vec![0xe5, 1, 2, 3] }
+    }
+
+    #[test]
+    fn test_derive_token_transfer() {
+        let instructions = token_transfer_instructions(&OWNER, RECIPIENT, USDC, TokenProgram::Spl, 2_500_000, 6).unwrap();
+        let request = derive_request(&message(&instructions), &OWNER).unwrap();
+        assert_eq!(
+            request,
+            SignRequest {
+                input_mint: USDC.to_string(),
+                amount: 2_500_000,
+                // The recipient's account isn't the wallet's, so the tokens stay USDC
+                destination_mint: USDC.to_string(),
+                programs: vec![ASSOCIATED_TOKEN_PROGRAM.to_string(), TOKEN_PROGRAM.to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_derive_sol_swap_goes_to_the_created_account() {
+        let wrapped = associated_token_address(&OWNER, &Pubkey::from_str_const(SOL), TokenProgram::Spl);
+        let instructions = [
+            create_account_for(&OWNER, SOL),
+            sol_transfer_instruction(&OWNER, &wrapped, 1_000_000_000),
+            token_instruction(vec![AccountMeta::new(wrapped, false)], vec![TOKEN_SYNC_NATIVE_TAG]),
+            create_account_for(&OWNER, BONK),
+            route(vec![AccountMeta::new(OWNER, true), AccountMeta::new(wrapped, false)]),
+            token_instruction(
+                vec![AccountMeta::new(wrapped, false), AccountMeta::new(OWNER, false), AccountMeta::new_readonly(OWNER, true)],
+                vec![TOKEN_CLOSE_ACCOUNT_TAG],
+            ),
+        ];
+
+        let request = derive_request(&message(&instructions), &OWNER).unwrap();
+        assert_eq!(request.input_mint, SOL);
+        assert_eq!(request.amount, 1_000_000_000);
+        assert_eq!(request.destination_mint, BONK);
+        assert!(request.programs.contains(&JUPITER_PROGRAM.to_string()));
+    }
+
+    #[test]
+    fn test_derive_refuses_what_it_cannot_account_for() {
+        let source = Pubkey::new_unique();
+        let owner_signs = |tag: u8| {
+            token_instruction(
+                vec![AccountMeta::new(source, false), AccountMeta::new(Pubkey::new_unique(), false), AccountMeta::new_readonly(OWNER, true)],
+                vec![tag, 1, 0, 0, 0, 0, 0, 0, 0],
+            )
+        };
+
+        // The amount of a bare route is inside the aggregator's data
+        let bare_route = route(vec![AccountMeta::new(OWNER, true)]);
+        assert_eq!(derive_request(&message(&[bare_route]), &OWNER), Err(DeriveError::NoSpend));
+        // Unchecked Transfer (no mint), Approve, and closing an account to someone else
+        for tag in [3, 4, TOKEN_CLOSE_ACCOUNT_TAG] {
+            assert_eq!(derive_request(&message(&[owner_signs(tag)]), &OWNER), Err(DeriveError::UnsupportedTokenInstruction(tag)));
+        }
+
+        let recipient = Pubkey::from_str_const(RECIPIENT);
+        let mut mixed = token_transfer_instructions(&OWNER, RECIPIENT, USDC, TokenProgram::Spl, 1, 6).unwrap();
+        mixed.push(sol_transfer_instruction(&OWNER, &recipient, 1));
+        assert_eq!(
+            derive_request(&message(&mixed), &OWNER),
+            Err(DeriveError::MixedInputMints(vec![USDC.to_string(), SOL.to_string()]))
+        );
+
+        let mut two_outputs = vec![sol_transfer_instruction(&OWNER, &recipient, 1)];
+        two_outputs.extend([create_account_for(&OWNER, USDC), create_account_for(&OWNER, BONK)]);
+        assert!(matches!(derive_request(&message(&two_outputs), &OWNER), Err(DeriveError::AmbiguousDestination(_))));
+
+        // CreateAccount funded by the wallet
+        let create = Instruction {
+            program_id: Pubkey::from_str_const(SYSTEM_PROGRAM),
+            accounts: vec![AccountMeta::new(OWNER, true), AccountMeta::new(Pubkey::new_unique(), true)],
+            data: vec![0; 52],
+        };
+        assert_eq!(derive_request(&message(&[create]), &OWNER), Err(DeriveError::UnsupportedSystemInstruction));
+    }
+
+    #[test]
+    fn test_derive_refuses_spend_accounts_from_lookup_tables() {
+        let instructions = token_transfer_instructions(&OWNER, RECIPIENT, USDC, TokenProgram::Spl, 1, 6).unwrap();
+        let table = AddressLookupTableAccount { key: Pubkey::new_unique(), addresses: vec![Pubkey::from_str_const(USDC)] };
+        let compiled = v0::Message::try_compile(&OWNER, &instructions, &[table], Default::default()).unwrap();
+        assert!(!compiled.address_table_lookups.is_empty());
+
+        assert_eq!(derive_request(&VersionedMessage::V0(compiled), &OWNER), Err(DeriveError::LookupTableAccount));
+    }
+}
@@ -0,0 +1,391 @@
+//! # Token Transfers
+//!
+//! Builds SOL and SPL token transfers for both token programs and works out
+//! what the recipient receives, for the Send flow's confirmation dialog.
+//!
+//! SOL moves with the System Program's `Transfer`; the rest of this module is
+//! about SPL tokens.
+//!
+//! ## Program Selection
+//!
+//! A transfer must go to the program that owns the mint. Token-2022 mints use
+//! a different program id, and their associated token accounts are derived
+//! with that id too, so the same wallet and mint give a different address than
+//! under the classic program. The program comes from the backend's balance
+//! list ([`crate::services::api::wallet::TokenBalance::program`]).
+//!
+//! ## Instructions
+//!
+//! 1. `CreateIdempotent` for the recipient's associated token account, which
+//!    is a no-op when it already exists
+//! 2. `TransferChecked`, which both programs accept and which Token-2022
+//!    requires for mints with extensions such as transfer fees
+//!
+//! SOL and token transfers alike end with an SPL Memo instruction signed by
+//! the sender when the Send form has a memo ([`with_memo`]).
+//!
+//! ## Transfer Fees
+//!
+//! The fee is withheld from the amount sent, so the sender pays `amount` and
+//! the recipient receives `amount - fee`. [`TransferPreview`] uses the fee
+//! the backend reported for the current epoch.
+
+use crate::services::memo::{self, MemoError};
+use shared::dto::tokens::{TokenProgram, TransferFee};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+use std::str::FromStr;
+
+/// `TransferChecked` instruction tag, shared by both token programs
+const TRANSFER_CHECKED_TAG: u8 = 12;
+
+/// System Program id
+const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::from_str_const("11111111111111111111111111111111");
+
+/// System Program `Transfer` instruction index
+const SYSTEM_TRANSFER_TAG: u32 = 2;
+
+/// Decimals of SOL, in lamports
+pub const SOL_DECIMALS: u8 = 9;
+
+/// Transfer building errors
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferError {
+    InvalidAddress(String),
+    ZeroAmount,
+    InsufficientBalance { amount: u64, balance: u64 },
+    Memo(MemoError),
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferError::InvalidAddress(address) => write!(f, "Invalid address: {}", address),
+            TransferError::ZeroAmount => write!(f, "Amount must be greater than zero"),
+            TransferError::InsufficientBalance { amount, balance } => {
+                write!(f, "Amount {} exceeds balance {}", amount, balance)
+            }
+            TransferError::Memo(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TransferError {}
+
+/// Program id of a token program
+pub fn program_id(program: TokenProgram) -> Pubkey {
+    Pubkey::from_str_const(program.program_id())
+}
+
+/// Associated token account of `wallet` for `mint` under `program`
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey, program: TokenProgram) -> Pubkey {
+    spl_associated_token_account::get_associated_token_address_with_program_id(wallet, mint, &program_id(program))
+}
+
+/// `TransferChecked` from `source` to `destination`, signed by `owner`
+pub fn transfer_checked_instruction(
+    program: TokenProgram,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+    decimals: u8,
+) -> Instruction {
+    let mut data = Vec::with_capacity(10);
+    data.push(TRANSFER_CHECKED_TAG);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(decimals);
+
+    Instruction {
+        program_id: program_id(program),
+        accounts: vec![
+            AccountMeta::new(*source, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data,
+    }
+}
+
+/// System Program transfer of `lamports` from `from` to `to`, signed by `from`
+pub fn sol_transfer_instruction(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+    let mut data = Vec::with_capacity(12);
+    data.extend_from_slice(&SYSTEM_TRANSFER_TAG.to_le_bytes());
+    data.extend_from_slice(&lamports.to_le_bytes());
+
+    Instruction {
+        program_id: SYSTEM_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+        data,
+    }
+}
+
+/// Instructions sending `lamports` from `owner` to `recipient`
+pub fn sol_transfer_instructions(owner: &Pubkey, recipient: &str, lamports: u64) -> Result<Vec<Instruction>, TransferError> {
+    let recipient = Pubkey::from_str(recipient.trim())
+        .map_err(|_| TransferError::InvalidAddress(recipient.to_string()))?;
+    if lamports == 0 {
+        return Err(TransferError::ZeroAmount);
+    }
+    Ok(vec![sol_transfer_instruction(owner, &recipient, lamports)])
+}
+
+/// Instructions sending `amount` base units of `mint` from `owner`'s
+/// associated token account to `recipient`'s, creating it if needed.
+/// `owner` pays for the account creation.
+pub fn token_transfer_instructions(
+    owner: &Pubkey,
+    recipient: &str,
+    mint: &str,
+    program: TokenProgram,
+    amount: u64,
+    decimals: u8,
+) -> Result<Vec<Instruction>, TransferError> {
+    let recipient = Pubkey::from_str(recipient.trim())
+        .map_err(|_| TransferError::InvalidAddress(recipient.to_string()))?;
+    let mint = Pubkey::from_str(mint).map_err(|_| TransferError::InvalidAddress(mint.to_string()))?;
+    if amount == 0 {
+        return Err(TransferError::ZeroAmount);
+    }
+
+    let token_program = program_id(program);
+    let source = associated_token_address(owner, &mint, program);
+    let destination = associated_token_address(&recipient, &mint, program);
+
+    Ok(vec![
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            owner,
+            &recipient,
+            &mint,
+            &token_program,
+        ),
+        transfer_checked_instruction(program, &source, &mint, &destination, owner, amount, decimals),
+    ])
+}
+
+/// `instructions` followed by a memo signed by `owner`, if there is one
+pub fn with_memo(mut instructions: Vec<Instruction>, owner: &Pubkey, memo: Option<&str>) -> Result<Vec<Instruction>, TransferError> {
+    if let Some(memo) = memo {
+        instructions.push(memo::memo_instruction(memo, &[*owner]).map_err(TransferError::Memo)?);
+    }
+    Ok(instructions)
+}
+
+/// What a transfer costs the sender and what reaches the recipient
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransferPreview {
+    /// Base units leaving the sender's account
+    pub amount: u64,
+    /// Base units withheld by the mint's transfer fee
+    pub fee: u64,
+    /// Base units credited to the recipient
+    pub received: u64,
+    pub decimals: u8,
+}
+
+impl TransferPreview {
+    /// Preview sending `amount` base units out of `balance`
+    pub fn new(amount: u64, balance: u64, decimals: u8, transfer_fee: Option<TransferFee>) -> Result<Self, TransferError> {
+        if amount == 0 {
+            return Err(TransferError::ZeroAmount);
+        }
+        if amount > balance {
+            return Err(TransferError::InsufficientBalance { amount, balance });
+        }
+        let fee = transfer_fee.map(|fee| fee.fee_for(amount)).unwrap_or(0);
+        Ok(Self {
+            amount,
+            fee,
+            received: amount - fee,
+            decimals,
+        })
+    }
+
+    /// Amount sent, in tokens
+    pub fn ui_amount(&self) -> f64 {
+        to_ui(self.amount, self.decimals)
+    }
+
+    /// Fee withheld, in tokens
+    pub fn ui_fee(&self) -> f64 {
+        to_ui(self.fee, self.decimals)
+    }
+
+    /// Amount received, in tokens
+    pub fn ui_received(&self) -> f64 {
+        to_ui(self.received, self.decimals)
+    }
+}
+
+/// Parse a token amount typed by the user into base units.
+///
+/// Works on the decimal string rather than through `f64`, so "0.1" of a
+/// 9-decimal token is exactly 100_000_000 base units.
+pub fn parse_token_amount(input: &str, decimals: u8) -> Option<u64> {
+    let input = input.trim();
+    let (whole, fraction) = input.split_once('.').unwrap_or((input, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if fraction.len() > decimals as usize || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let scale = 10u64.checked_pow(decimals as u32)?;
+    let whole = if whole.is_empty() { 0 } else { whole.parse::<u64>().ok()? };
+    let fraction = format!("{:0<width$}", fraction, width = decimals as usize);
+    let fraction = if fraction.is_empty() { 0 } else { fraction.parse::<u64>().ok()? };
+    whole.checked_mul(scale)?.checked_add(fraction)
+}
+
+/// Base units as a token amount the user can type back in, without
+/// trailing zeros: 1_500_000 with 6 decimals is "1.5"
+///
+/// The inverse of [`parse_token_amount`].
+pub fn format_token_amount(amount: u64, decimals: u8) -> String {
+    let scale = 10u128.pow(decimals as u32);
+    let (whole, fraction) = (amount as u128 / scale, amount as u128 % scale);
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0>width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+fn to_ui(amount: u64, decimals: u8) -> f64 {
+    amount as f64 / 10f64.powi(decimals as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const RECIPIENT: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+
+    #[test]
+    fn test_transfer_instructions_use_the_mint_program() {
+        let owner = Pubkey::new_unique();
+
+        for program in [TokenProgram::Spl, TokenProgram::Token2022] {
+            let instructions = token_transfer_instructions(&owner, RECIPIENT, MINT, program, 1_500, 6).unwrap();
+            assert_eq!(instructions.len(), 2);
+
+            let transfer = &instructions[1];
+            assert_eq!(transfer.program_id.to_string(), program.program_id());
+            assert_eq!(transfer.data[0], TRANSFER_CHECKED_TAG);
+            assert_eq!(u64::from_le_bytes(transfer.data[1..9].try_into().unwrap()), 1_500);
+            assert_eq!(transfer.data[9], 6);
+
+            let mint = Pubkey::from_str(MINT).unwrap();
+            let recipient = Pubkey::from_str(RECIPIENT).unwrap();
+            assert_eq!(transfer.accounts[0].pubkey, associated_token_address(&owner, &mint, program));
+            assert_eq!(transfer.accounts[2].pubkey, associated_token_address(&recipient, &mint, program));
+            assert!(transfer.accounts[3].is_signer);
+
+            // The ATA program is told which token program to create the account under
+            let create = &instructions[0];
+            assert!(create.accounts.iter().any(|meta| meta.pubkey == program_id(program)));
+        }
+    }
+
+    #[test]
+    fn test_sol_transfer_instruction() {
+        let owner = Pubkey::new_unique();
+        let instructions = sol_transfer_instructions(&owner, RECIPIENT, 1_500_000_000).unwrap();
+        assert_eq!(instructions.len(), 1);
+
+        let transfer = &instructions[0];
+        assert_eq!(transfer.program_id.to_string(), "11111111111111111111111111111111");
+        assert_eq!(transfer.data[..4], 2u32.to_le_bytes());
+        assert_eq!(u64::from_le_bytes(transfer.data[4..12].try_into().unwrap()), 1_500_000_000);
+        assert_eq!(transfer.accounts[0].pubkey, owner);
+        assert!(transfer.accounts[0].is_signer && transfer.accounts[0].is_writable);
+        assert_eq!(transfer.accounts[1].pubkey.to_string(), RECIPIENT);
+        assert!(!transfer.accounts[1].is_signer);
+
+        assert_eq!(sol_transfer_instructions(&owner, RECIPIENT, 0), Err(TransferError::ZeroAmount));
+        assert!(matches!(sol_transfer_instructions(&owner, "0xabc", 1), Err(TransferError::InvalidAddress(_))));
+    }
+
+    #[test]
+    fn test_transfer_instructions_validate_input() {
+        let owner = Pubkey::new_unique();
+        assert!(matches!(
+            token_transfer_instructions(&owner, "not-an-address", MINT, TokenProgram::Spl, 1, 6),
+            Err(TransferError::InvalidAddress(_))
+        ));
+        assert_eq!(
+            token_transfer_instructions(&owner, RECIPIENT, MINT, TokenProgram::Spl, 0, 6),
+            Err(TransferError::ZeroAmount)
+        );
+    }
+
+    #[test]
+    fn test_with_memo() {
+        let owner = Pubkey::new_unique();
+        let transfer = sol_transfer_instructions(&owner, RECIPIENT, 1).unwrap();
+        assert_eq!(with_memo(transfer.clone(), &owner, None).unwrap(), transfer);
+
+        let instructions = with_memo(transfer, &owner, Some("invoice 42")).unwrap();
+        assert_eq!(instructions.len(), 2);
+        let memo = &instructions[1];
+        assert_eq!(memo.program_id, memo::MEMO_PROGRAM_ID);
+        assert_eq!(memo.data, b"invoice 42");
+        assert_eq!(memo.accounts[0].pubkey, owner);
+        assert!(memo.accounts[0].is_signer);
+
+        assert_eq!(with_memo(Vec::new(), &owner, Some("a\u{7}b")), Err(TransferError::Memo(MemoError::ControlCharacter)));
+    }
+
+    #[test]
+    fn test_transfer_preview_with_fee() {
+        let fee = TransferFee { basis_points: 100, maximum_fee: 50_000 };
+
+        let preview = TransferPreview::new(1_000_000, 2_000_000, 6, Some(fee)).unwrap();
+        assert_eq!(preview.fee, 10_000);
+        assert_eq!(preview.received, 990_000);
+        assert!((preview.ui_received() - 0.99).abs() < 1e-12);
+
+        // The cap applies to large transfers
+        let preview = TransferPreview::new(10_000_000, 10_000_000, 6, Some(fee)).unwrap();
+        assert_eq!(preview.fee, 50_000);
+
+        let preview = TransferPreview::new(500, 500, 6, None).unwrap();
+        assert_eq!((preview.fee, preview.received), (0, 500));
+
+        assert_eq!(
+            TransferPreview::new(501, 500, 6, None),
+            Err(TransferError::InsufficientBalance { amount: 501, balance: 500 })
+        );
+    }
+
+    #[test]
+    fn test_parse_token_amount() {
+        assert_eq!(parse_token_amount("0.1", 9), Some(100_000_000));
+        assert_eq!(parse_token_amount("12", 6), Some(12_000_000));
+        assert_eq!(parse_token_amount(" 1.5 ", 2), Some(150));
+        assert_eq!(parse_token_amount(".5", 1), Some(5));
+        assert_eq!(parse_token_amount("7", 0), Some(7));
+        assert_eq!(parse_token_amount("1.234", 2), None);
+        assert_eq!(parse_token_amount("-1", 6), None);
+        assert_eq!(parse_token_amount("", 6), None);
+        assert_eq!(parse_token_amount("1e3", 6), None);
+    }
+
+    #[test]
+    fn test_format_token_amount_round_trips() {
+        assert_eq!(format_token_amount(1_500_000, 6), "1.5");
+        assert_eq!(format_token_amount(100_000_000, SOL_DECIMALS), "0.1");
+        assert_eq!(format_token_amount(12_000_000, 6), "12");
+        assert_eq!(format_token_amount(1, SOL_DECIMALS), "0.000000001");
+        assert_eq!(format_token_amount(7, 0), "7");
+        assert_eq!(format_token_amount(u64::MAX, 0), u64::MAX.to_string());
+
+        for (amount, decimals) in [(123_456_789, 9), (5, 2), (u64::MAX, 9), (0, 6)] {
+            assert_eq!(parse_token_amount(&format_token_amount(amount, decimals), decimals), Some(amount));
+        }
+    }
+}
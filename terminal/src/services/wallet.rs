@@ -5,16 +5,26 @@
 //! ## Features
 //! - Load keypair from file or environment variable
 //! - Generate new keypairs
-//! - Sign transactions
+//! - Sign legacy and versioned (v0) transactions
+//! - Build SOL and SPL / Token-2022 token transfers
+//! - Auto-sign within scoped session grants (see [`crate::services::signer_policy`])
 //! - Query wallet balance
 //! - RPC connection management
 
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use solana_sdk::{
+    hash::Hash,
+    message::VersionedMessage,
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, VersionedTransaction},
 };
 use solana_client::rpc_client::RpcClient;
+use crate::services::memo::{self, MemoError};
+use crate::services::signer_policy::{self, SessionGrant, SignRequest};
+use crate::services::token_transfer;
+use shared::dto::tokens::TokenProgram;
+use shared::dto::transactions::TransactionVersion;
 use std::error::Error;
 use std::fmt;
 use std::fs;
@@ -32,6 +42,8 @@ pub enum WalletError {
     RpcError(String),
     /// Transaction signing error
     SigningError(String),
+    /// Auto-sign request not covered by any session grant
+    PolicyDenied(String),
     /// Balance query error
     BalanceError(String),
     /// File I/O error
@@ -45,6 +57,7 @@ impl fmt::Display for WalletError {
             WalletError::InvalidKeypair(msg) => write!(f, "Invalid keypair: {}", msg),
             WalletError::RpcError(msg) => write!(f, "RPC error: {}", msg),
             WalletError::SigningError(msg) => write!(f, "Signing error: {}", msg),
+            WalletError::PolicyDenied(msg) => write!(f, "Auto-sign denied: {}", msg),
             WalletError::BalanceError(msg) => write!(f, "Balance error: {}", msg),
             WalletError::IoError(e) => write!(f, "I/O error: {}", e),
         }
@@ -59,6 +72,86 @@ impl From<std::io::Error> for WalletError {
     }
 }
 
+/// A transaction from the backend, in the message format it was built in
+///
+/// Swaps whose route touches more accounts than a legacy message can list
+/// come as v0 transactions loading accounts from lookup tables.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WalletTransaction {
+    Legacy(Transaction),
+    V0(VersionedTransaction),
+}
+
+impl WalletTransaction {
+    /// Decode a base64 (bincode) transaction of the declared format
+    pub fn decode(encoded: &str, version: TransactionVersion) -> Result<Self, String> {
+        let bytes = BASE64.decode(encoded).map_err(|e| format!("Decode failed: {}", e))?;
+        match version {
+            TransactionVersion::Legacy => bincode::deserialize(&bytes)
+                .map(WalletTransaction::Legacy)
+                .map_err(|e| format!("Deserialize failed: {}", e)),
+            TransactionVersion::V0 => {
+                let transaction: VersionedTransaction = bincode::deserialize(&bytes)
+                    .map_err(|e| format!("Deserialize failed: {}", e))?;
+                match transaction.message {
+                    VersionedMessage::V0(_) => Ok(WalletTransaction::V0(transaction)),
+                    VersionedMessage::Legacy(_) => Err("Expected a v0 transaction".to_string()),
+                }
+            }
+        }
+    }
+
+    /// Encode as base64 (bincode), the format the backend expects
+    pub fn encode(&self) -> Result<String, String> {
+        let bytes = match self {
+            WalletTransaction::Legacy(transaction) => bincode::serialize(transaction),
+            WalletTransaction::V0(transaction) => bincode::serialize(transaction),
+        }
+        .map_err(|e| format!("Serialize failed: {}", e))?;
+        Ok(BASE64.encode(bytes))
+    }
+
+    /// Message format, sent along with the signed transaction
+    pub fn version(&self) -> TransactionVersion {
+        match self {
+            WalletTransaction::Legacy(_) => TransactionVersion::Legacy,
+            WalletTransaction::V0(_) => TransactionVersion::V0,
+        }
+    }
+
+    /// Number of instructions
+    pub fn instruction_count(&self) -> usize {
+        match self {
+            WalletTransaction::Legacy(transaction) => transaction.message.instructions.len(),
+            WalletTransaction::V0(transaction) => transaction.message.instructions().len(),
+        }
+    }
+
+    /// The message, as a versioned message either way
+    pub fn message(&self) -> VersionedMessage {
+        match self {
+            WalletTransaction::Legacy(transaction) => VersionedMessage::Legacy(transaction.message.clone()),
+            WalletTransaction::V0(transaction) => transaction.message.clone(),
+        }
+    }
+
+    /// Blockhash the transaction was signed against, which bounds its lifetime
+    pub fn recent_blockhash(&self) -> Hash {
+        match self {
+            WalletTransaction::Legacy(transaction) => transaction.message.recent_blockhash,
+            WalletTransaction::V0(transaction) => *transaction.message.recent_blockhash(),
+        }
+    }
+
+    /// Attach a memo signed by the fee payer (see [`memo::append_memo`])
+    pub fn append_memo(&mut self, memo: &str) -> Result<(), MemoError> {
+        match self {
+            WalletTransaction::Legacy(transaction) => memo::append_memo(transaction, memo),
+            WalletTransaction::V0(transaction) => memo::append_memo_versioned(transaction, memo),
+        }
+    }
+}
+
 /// Wallet connection status
 #[derive(Debug, Clone, PartialEq)]
 pub enum WalletStatus {
@@ -93,6 +186,9 @@ pub struct WalletService {
     rpc_client: RpcClient,
     /// Current connection status
     status: WalletStatus,
+    /// Session grants for unattended signing
+    grants: Vec<SessionGrant>,
+    next_grant_id: u64,
 }
 
 impl WalletService {
@@ -113,6 +209,8 @@ impl WalletService {
             keypair: None,
             rpc_client,
             status: WalletStatus::Disconnected,
+            grants: Vec::new(),
+            next_grant_id: 1,
         }
     }
 
@@ -125,6 +223,8 @@ impl WalletService {
             keypair: Some(keypair),
             rpc_client,
             status: WalletStatus::Connected(pubkey),
+            grants: Vec::new(),
+            next_grant_id: 1,
         }
     }
 
@@ -263,6 +363,101 @@ impl WalletService {
             .ok_or_else(|| WalletError::SigningError("No signature generated".to_string()))
     }
 
+    /// Sign a versioned transaction
+    ///
+    /// Like [`Self::sign_transaction`], the blockhash is refreshed first. The
+    /// message is otherwise signed as is, so accounts a v0 message loads from
+    /// lookup tables stay untouched.
+    ///
+    /// # Returns
+    /// Signature of the fee payer
+    pub fn sign_versioned_transaction(&self, transaction: &mut VersionedTransaction) -> Result<Signature, WalletError> {
+        let keypair = self.keypair.as_ref()
+            .ok_or_else(|| WalletError::SigningError("No keypair loaded".to_string()))?;
+
+        let recent_blockhash = self.rpc_client
+            .get_latest_blockhash()
+            .map_err(|e| WalletError::RpcError(format!("Failed to get blockhash: {}", e)))?;
+
+        let mut message = transaction.message.clone();
+        message.set_recent_blockhash(recent_blockhash);
+        *transaction = VersionedTransaction::try_new(message, &[keypair])
+            .map_err(|e| WalletError::SigningError(e.to_string()))?;
+
+        transaction.signatures.first()
+            .copied()
+            .ok_or_else(|| WalletError::SigningError("No signature generated".to_string()))
+    }
+
+    /// Sign a transaction from the backend in whichever format it came
+    pub fn sign(&self, transaction: &mut WalletTransaction) -> Result<Signature, WalletError> {
+        match transaction {
+            WalletTransaction::Legacy(transaction) => self.sign_transaction(transaction),
+            WalletTransaction::V0(transaction) => self.sign_versioned_transaction(transaction),
+        }
+    }
+
+    /// Add a session grant and return it with its assigned id.
+    ///
+    /// The caller is responsible for having checked the keystore passphrase.
+    pub fn grant_session(&mut self, mut grant: SessionGrant) -> SessionGrant {
+        grant.id = self.next_grant_id;
+        self.next_grant_id += 1;
+        self.grants.push(grant.clone());
+        grant
+    }
+
+    /// Revoke a session grant. Returns false if it did not exist.
+    pub fn revoke_grant(&mut self, id: u64) -> bool {
+        let before = self.grants.len();
+        self.grants.retain(|grant| grant.id != id);
+        self.grants.len() != before
+    }
+
+    /// Revoke every session grant
+    pub fn revoke_all_grants(&mut self) {
+        self.grants.clear();
+    }
+
+    /// Session grants, including expired ones not yet pruned
+    pub fn grants(&self) -> &[SessionGrant] {
+        &self.grants
+    }
+
+    /// What `transaction` would spend from this wallet, read from its instructions
+    ///
+    /// See [`signer_policy::derive_request`]; a transaction whose spend can't
+    /// be worked out is refused with [`WalletError::PolicyDenied`].
+    pub fn sign_request(&self, transaction: &WalletTransaction) -> Result<SignRequest, WalletError> {
+        let owner = self.keypair.as_ref()
+            .ok_or_else(|| WalletError::SigningError("No keypair loaded".to_string()))?
+            .pubkey();
+        signer_policy::derive_request(&transaction.message(), &owner)
+            .map_err(|e| WalletError::PolicyDenied(e.to_string()))
+    }
+
+    /// Sign on behalf of a scheduler, if a session grant covers the request.
+    ///
+    /// The input mint, amount, destination mint and programs are all read
+    /// from the transaction itself ([`Self::sign_request`]); legacy and v0
+    /// transactions are both accepted. Expired grants are dropped first.
+    /// Returns the signature and the grant that allowed it.
+    pub fn auto_sign_transaction(
+        &mut self,
+        transaction: &mut WalletTransaction,
+    ) -> Result<(Signature, SessionGrant), WalletError> {
+        let now = chrono::Utc::now();
+        self.grants.retain(|grant| grant.is_active(now));
+
+        let request = self.sign_request(transaction)?;
+        let grant = signer_policy::evaluate(&self.grants, &request, now)
+            .map_err(|e| WalletError::PolicyDenied(e.to_string()))?
+            .clone();
+
+        let signature = self.sign(transaction)?;
+        Ok((signature, grant))
+    }
+
     /// Get wallet balance in SOL
     ///
     /// # Returns
@@ -314,6 +509,54 @@ impl WalletService {
             .ok_or_else(|| WalletError::BalanceError("No UI amount in response".to_string()))
     }
 
+    /// Ask the cluster's faucet for `lamports` (devnet and testnet only)
+    ///
+    /// Returns the airdrop's signature; it still has to be confirmed.
+    pub fn request_airdrop(&self, lamports: u64) -> Result<Signature, WalletError> {
+        let owner = self.keypair.as_ref()
+            .ok_or_else(|| WalletError::RpcError("No keypair loaded".to_string()))?
+            .pubkey();
+        request_airdrop_to(&self.rpc_client, &owner, lamports)
+    }
+
+    /// Build an unsigned SOL transfer of `lamports` from this wallet, with an optional memo
+    ///
+    /// Sign it with [`Self::sign_transaction`].
+    pub fn build_sol_transfer(&self, recipient: &str, lamports: u64, memo: Option<&str>) -> Result<Transaction, WalletError> {
+        let owner = self.keypair.as_ref()
+            .ok_or_else(|| WalletError::SigningError("No keypair loaded".to_string()))?
+            .pubkey();
+
+        let instructions = token_transfer::sol_transfer_instructions(&owner, recipient, lamports)
+            .and_then(|instructions| token_transfer::with_memo(instructions, &owner, memo))
+            .map_err(|e| WalletError::SigningError(e.to_string()))?;
+        Ok(Transaction::new_with_payer(&instructions, Some(&owner)))
+    }
+
+    /// Build an unsigned token transfer from this wallet
+    ///
+    /// Uses `TransferChecked` under the mint's program (classic SPL Token or
+    /// Token-2022) and creates the recipient's token account if it is missing.
+    /// A memo, if any, goes last. Sign it with [`Self::sign_transaction`].
+    pub fn build_token_transfer(
+        &self,
+        recipient: &str,
+        mint: &str,
+        program: TokenProgram,
+        amount: u64,
+        decimals: u8,
+        memo: Option<&str>,
+    ) -> Result<Transaction, WalletError> {
+        let owner = self.keypair.as_ref()
+            .ok_or_else(|| WalletError::SigningError("No keypair loaded".to_string()))?
+            .pubkey();
+
+        let instructions = token_transfer::token_transfer_instructions(&owner, recipient, mint, program, amount, decimals)
+            .and_then(|instructions| token_transfer::with_memo(instructions, &owner, memo))
+            .map_err(|e| WalletError::SigningError(e.to_string()))?;
+        Ok(Transaction::new_with_payer(&instructions, Some(&owner)))
+    }
+
     /// Export keypair to base58 string (for backup)
     ///
     /// # Returns
@@ -332,6 +575,7 @@ impl WalletService {
     pub fn disconnect(&mut self) {
         self.keypair = None;
         self.status = WalletStatus::Disconnected;
+        self.revoke_all_grants();
     }
 
     /// Get RPC client reference for advanced operations
@@ -339,14 +583,38 @@ impl WalletService {
         &self.rpc_client
     }
 
+    /// Replace the RPC client with a fresh one for the same endpoint, closing
+    /// its pooled connections (idle teardown)
+    pub fn reset_rpc_client(&mut self) {
+        self.rpc_client = RpcClient::new(self.rpc_client.url());
+    }
+
     /// Take the keypair from the wallet service (consumes the service)
     pub fn take_keypair(&mut self) -> Option<Keypair> {
         self.keypair.take()
     }
 }
 
+/// Ask the faucet behind `rpc` to send `lamports` to `recipient`
+///
+/// [`WalletService::request_airdrop`] without the service, for tasks that
+/// must not hold the app state while the RPC answers.
+pub fn request_airdrop_to(rpc: &RpcClient, recipient: &Pubkey, lamports: u64) -> Result<Signature, WalletError> {
+    rpc.request_airdrop(recipient, lamports)
+        .map_err(|e| WalletError::RpcError(e.to_string()))
+}
+
+/// Distinct program ids invoked by a transaction, in instruction order
+///
+/// See [`signer_policy::message_programs`].
+pub fn transaction_programs(transaction: &Transaction) -> Vec<String> {
+    signer_policy::message_programs(&VersionedMessage::Legacy(transaction.message.clone()))
+}
+
 /// Load default keypair from standard Solana CLI location
 ///
+/// Connects to the RPC endpoint of [`TerminalConfig::load`](crate::core::config::TerminalConfig::load).
+///
 /// # Returns
 /// WalletService with loaded keypair
 pub fn load_default_keypair() -> Result<WalletService, WalletError> {
@@ -359,8 +627,7 @@ pub fn load_default_keypair() -> Result<WalletService, WalletError> {
         .join("solana")
         .join("id.json");
 
-    let rpc_url = std::env::var("SOLANA_RPC_URL")
-        .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+    let rpc_url = crate::core::config::TerminalConfig::load().rpc_endpoint();
 
     let mut wallet = WalletService::new(&rpc_url);
     wallet.load_keypair_from_file(default_path)?;
@@ -400,6 +667,232 @@ mod tests {
         assert_eq!(wallet.get_status(), &WalletStatus::Disconnected);
     }
 
+    #[test]
+    fn test_build_token_2022_transfer() {
+        let mut wallet = WalletService::new("https://api.devnet.solana.com");
+        let owner = wallet.generate_new_keypair();
+
+        let transaction = wallet
+            .build_token_transfer(
+                "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU",
+                "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo",
+                TokenProgram::Token2022,
+                25_000_000,
+                6,
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(transaction.message.account_keys[0].to_string(), owner);
+        assert!(transaction_programs(&transaction).contains(&TokenProgram::Token2022.program_id().to_string()));
+
+        wallet.disconnect();
+        assert!(wallet
+            .build_token_transfer("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo", TokenProgram::Spl, 1, 6, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_build_sol_transfer() {
+        let mut wallet = WalletService::new("https://api.devnet.solana.com");
+        assert!(wallet.build_sol_transfer("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", 1, None).is_err());
+
+        let owner = wallet.generate_new_keypair();
+        let transaction = wallet.build_sol_transfer("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", 250_000_000, None).unwrap();
+        assert_eq!(transaction.message.account_keys[0].to_string(), owner);
+        assert_eq!(transaction_programs(&transaction), vec!["11111111111111111111111111111111".to_string()]);
+        assert_eq!(transaction.message.header.num_required_signatures, 1);
+
+        // The memo is signed by the sender, so still one signature
+        let transaction = wallet
+            .build_sol_transfer("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", 250_000_000, Some("rent for May"))
+            .unwrap();
+        assert_eq!(memo::memos_in_message(&transaction.message), vec!["rent for May".to_string()]);
+        assert_eq!(transaction.message.header.num_required_signatures, 1);
+    }
+
+    const PROGRAM: Pubkey = Pubkey::new_from_array([7; 32]);
+
+    fn grant(max_amount: u64) -> SessionGrant {
+        let now = chrono::Utc::now();
+        SessionGrant {
+            id: 0,
+            label: "DCA".to_string(),
+            input_mint: USDC.to_string(),
+            max_amount,
+            allowed_programs: vec![signer_policy::ASSOCIATED_TOKEN_PROGRAM.to_string(), signer_policy::TOKEN_PROGRAM.to_string()],
+            allowed_mints: vec![USDC.to_string()],
+            created_at: now,
+            expires_at: now + chrono::Duration::hours(1),
+        }
+    }
+
+    fn transfer(wallet: &WalletService) -> Transaction {
+        let payer = Pubkey::from_str(&wallet.get_public_key().unwrap()).unwrap();
+        let instruction = solana_sdk::instruction::Instruction {
+            program_id: PROGRAM,
+            accounts: vec![
+                solana_sdk::instruction::AccountMeta::new(payer, true),
+                solana_sdk::instruction::AccountMeta::new(Pubkey::new_unique(), false),
+            ],
+            data: vec![2, 0, 0, 0],
+        };
+        Transaction::new_with_payer(&[instruction], Some(&payer))
+    }
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn usdc_transfer(wallet: &WalletService, amount: u64) -> Transaction {
+        wallet
+            .build_token_transfer("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", USDC, TokenProgram::Spl, amount, 6, None)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_grant_and_revoke_session() {
+        let mut wallet = WalletService::new("https://api.devnet.solana.com");
+        let first = wallet.grant_session(grant(10));
+        let second = wallet.grant_session(grant(20));
+        assert_ne!(first.id, second.id);
+        assert_eq!(wallet.grants().len(), 2);
+
+        assert!(wallet.revoke_grant(first.id));
+        assert!(!wallet.revoke_grant(first.id));
+        assert_eq!(wallet.grants(), &[second]);
+
+        wallet.generate_new_keypair();
+        wallet.disconnect();
+        assert!(wallet.grants().is_empty());
+    }
+
+    #[test]
+    fn test_auto_sign_is_denied_without_matching_grant() {
+        let mut wallet = WalletService::new("https://api.devnet.solana.com");
+        wallet.generate_new_keypair();
+        let mut transaction = WalletTransaction::Legacy(usdc_transfer(&wallet, 5));
+
+        // No grants at all
+        let result = wallet.auto_sign_transaction(&mut transaction);
+        assert!(matches!(result, Err(WalletError::PolicyDenied(_))));
+
+        // Over the cap: refused before anything is signed or sent to RPC
+        wallet.grant_session(grant(1));
+        match wallet.auto_sign_transaction(&mut transaction) {
+            Err(WalletError::PolicyDenied(msg)) => assert!(msg.contains("exceeds the cap")),
+            other => panic!("Expected policy denial, got {:?}", other),
+        }
+        let WalletTransaction::Legacy(unsigned) = &transaction else { unreachable!() };
+        assert!(unsigned.signatures.iter().all(|s| *s == Signature::default()));
+    }
+
+    #[test]
+    fn test_auto_sign_reads_the_request_from_the_transaction() {
+        use solana_sdk::message::v0;
+
+        let mut wallet = WalletService::new("https://api.devnet.solana.com");
+        let owner = Pubkey::from_str(&wallet.generate_new_keypair()).unwrap();
+        wallet.grant_session(grant(1_000));
+
+        let legacy = usdc_transfer(&wallet, 250);
+        let request = wallet.sign_request(&WalletTransaction::Legacy(legacy.clone())).unwrap();
+        assert_eq!((request.input_mint.as_str(), request.amount, request.destination_mint.as_str()), (USDC, 250, USDC));
+
+        // The same transfer as a v0 message
+        let instructions = token_transfer::token_transfer_instructions(
+            &owner,
+            "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU",
+            USDC,
+            TokenProgram::Spl,
+            250,
+            6,
+        )
+        .unwrap();
+        let message = v0::Message::try_compile(&owner, &instructions, &[], Hash::default()).unwrap();
+        let v0 = WalletTransaction::V0(VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(message),
+        });
+        assert_eq!(wallet.sign_request(&v0).unwrap(), request);
+        let mut over_cap = WalletTransaction::Legacy(usdc_transfer(&wallet, 1_001));
+        match wallet.auto_sign_transaction(&mut over_cap) {
+            Err(WalletError::PolicyDenied(msg)) => assert!(msg.contains("exceeds the cap")),
+            other => panic!("Expected policy denial, got {:?}", other),
+        }
+
+        // Nothing to check the grant against: refused whatever the grants allow
+        let mut opaque = WalletTransaction::Legacy(transfer(&wallet));
+        match wallet.auto_sign_transaction(&mut opaque) {
+            Err(WalletError::PolicyDenied(msg)) => assert!(msg.contains("no transfer from this wallet")),
+            other => panic!("Expected policy denial, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_expired_grants_are_pruned_on_auto_sign() {
+        let mut wallet = WalletService::new("https://api.devnet.solana.com");
+        wallet.generate_new_keypair();
+        let mut expired = grant(10);
+        expired.expires_at = chrono::Utc::now() - chrono::Duration::seconds(1);
+        wallet.grant_session(expired);
+
+        let mut transaction = WalletTransaction::Legacy(usdc_transfer(&wallet, 5));
+        let result = wallet.auto_sign_transaction(&mut transaction);
+        assert!(matches!(result, Err(WalletError::PolicyDenied(_))));
+        assert!(wallet.grants().is_empty());
+    }
+
+    #[test]
+    fn test_transaction_programs() {
+        let mut wallet = WalletService::new("https://api.devnet.solana.com");
+        wallet.generate_new_keypair();
+        let mut transaction = transfer(&wallet);
+        assert_eq!(transaction_programs(&transaction), vec![PROGRAM.to_string()]);
+
+        // Duplicates collapse; a dangling index is reported instead of skipped
+        let first = transaction.message.instructions[0].clone();
+        transaction.message.instructions.push(first.clone());
+        let mut dangling = first;
+        dangling.program_id_index = 200;
+        transaction.message.instructions.push(dangling);
+        assert_eq!(
+            transaction_programs(&transaction),
+            vec![PROGRAM.to_string(), "<invalid program index 200>".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_wallet_transaction_round_trip() {
+        use solana_sdk::message::{v0, AddressLookupTableAccount};
+
+        let mut wallet = WalletService::new("https://api.devnet.solana.com");
+        wallet.generate_new_keypair();
+        let legacy = WalletTransaction::Legacy(transfer(&wallet));
+        let encoded = legacy.encode().unwrap();
+        assert_eq!(WalletTransaction::decode(&encoded, TransactionVersion::Legacy).unwrap(), legacy);
+        assert!(WalletTransaction::decode(&encoded, TransactionVersion::V0).is_err());
+
+        let payer = Pubkey::from_str(&wallet.get_public_key().unwrap()).unwrap();
+        let pool = Pubkey::new_unique();
+        let instruction = solana_sdk::instruction::Instruction {
+            program_id: PROGRAM,
+            accounts: vec![
+                solana_sdk::instruction::AccountMeta::new(payer, true),
+                solana_sdk::instruction::AccountMeta::new(pool, false),
+            ],
+            data: vec![2, 0, 0, 0],
+        };
+        let table = AddressLookupTableAccount { key: Pubkey::new_unique(), addresses: vec![pool] };
+        let message = v0::Message::try_compile(&payer, &[instruction], &[table], Default::default()).unwrap();
+        let versioned = WalletTransaction::V0(VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(message),
+        });
+        let decoded = WalletTransaction::decode(&versioned.encode().unwrap(), TransactionVersion::V0).unwrap();
+        assert_eq!(decoded, versioned);
+        assert_eq!(decoded.version(), TransactionVersion::V0);
+        assert_eq!(decoded.instruction_count(), 1);
+    }
+
     #[test]
     fn test_wallet_status_methods() {
         let status = WalletStatus::Connected("test_address".to_string());
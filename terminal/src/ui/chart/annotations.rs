@@ -0,0 +1,436 @@
+//! # Chart Annotations
+//!
+//! Horizontal levels and trendlines drawn on the chart, and the price alerts
+//! derived from them.
+//!
+//! Turning on "alert on touch" for a drawing gives it an [`AlertRule`]. A
+//! level triggers at its price; a trendline triggers at its price at the
+//! current time, extended along its slope past the two points it was drawn
+//! through, so the trigger moves as time advances. Every price update is
+//! compared with the previous one, so a tick that jumps across the line counts
+//! as touching it.
+//!
+//! A rule set to [`Rearm::Once`] disarms after firing; [`Rearm::EachTouch`]
+//! waits for the price to leave the line and fires again on the next touch.
+//! Moving a drawing re-arms its rule and forgets which side of the line the
+//! price was on, so the old position's history can't fire the new one.
+//! Deleting a drawing deletes its rule.
+
+use serde::{Deserialize, Serialize};
+
+/// A point on the chart: Unix time in seconds and price in USD
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChartPoint {
+    pub time: i64,
+    pub price: f64,
+}
+
+/// What was drawn
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Shape {
+    Level { price: f64 },
+    /// Line through two points, extended in both directions
+    Trendline { from: ChartPoint, to: ChartPoint },
+}
+
+impl Shape {
+    /// Price of the line at `time`
+    pub fn price_at(&self, time: i64) -> f64 {
+        match *self {
+            Shape::Level { price } => price,
+            Shape::Trendline { from, to } if from.time == to.time => (from.price + to.price) / 2.0,
+            Shape::Trendline { from, to } => {
+                let slope = (to.price - from.price) / (to.time - from.time) as f64;
+                from.price + slope * (time - from.time) as f64
+            }
+        }
+    }
+
+    /// The shape moved to `point`: a level to its price, a trendline by the
+    /// end closest in time
+    fn moved_to(&self, point: ChartPoint) -> Shape {
+        match *self {
+            Shape::Level { .. } => Shape::Level { price: point.price },
+            Shape::Trendline { from, to } => {
+                if (point.time - from.time).abs() <= (point.time - to.time).abs() {
+                    Shape::Trendline { from: point, to }
+                } else {
+                    Shape::Trendline { from, to: point }
+                }
+            }
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            Shape::Level { .. } => "level",
+            Shape::Trendline { .. } => "trendline",
+        }
+    }
+}
+
+/// What a rule does after firing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rearm {
+    /// Disarm until the drawing is moved or the alert turned on again
+    #[default]
+    Once,
+    /// Fire again each time the price comes back to the line
+    EachTouch,
+}
+
+impl Rearm {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Rearm::Once => "Fire once",
+            Rearm::EachTouch => "Re-arm after each touch",
+        }
+    }
+}
+
+/// Where the price was relative to the line on the last update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Below,
+    On,
+    Above,
+}
+
+impl Side {
+    fn of(price: f64, trigger: f64) -> Side {
+        // Prices are compared to a tenth of a basis point; closer counts as on the line
+        let tolerance = trigger.abs() * 1e-5;
+        if (price - trigger).abs() <= tolerance {
+            Side::On
+        } else if price < trigger {
+            Side::Below
+        } else {
+            Side::Above
+        }
+    }
+}
+
+/// Alert derived from a drawing
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub rearm: Rearm,
+    pub armed: bool,
+    /// Unknown until the first price update after arming or moving
+    #[serde(skip)]
+    last_side: Option<Side>,
+}
+
+impl AlertRule {
+    pub fn new(rearm: Rearm) -> Self {
+        Self { rearm, armed: true, last_side: None }
+    }
+
+    /// Feed a price; true when it touches the line and the rule is armed
+    fn observe(&mut self, price: f64, trigger: f64) -> bool {
+        let side = Side::of(price, trigger);
+        let touched = match (self.last_side, side) {
+            (Some(Side::On), Side::On) => false,
+            (_, Side::On) => true,
+            (Some(Side::Below), Side::Above) | (Some(Side::Above), Side::Below) => true,
+            _ => false,
+        };
+        self.last_side = Some(side);
+
+        if !touched || !self.armed {
+            return false;
+        }
+        if self.rearm == Rearm::Once {
+            self.armed = false;
+        }
+        true
+    }
+}
+
+/// A drawing on one symbol's chart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: u64,
+    pub symbol: String,
+    pub shape: Shape,
+    #[serde(default)]
+    pub alert: Option<AlertRule>,
+}
+
+/// A rule that fired on a price update
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertFiring {
+    pub annotation_id: u64,
+    pub symbol: String,
+    pub shape: Shape,
+    /// Price of the line when it fired
+    pub trigger_price: f64,
+    pub price: f64,
+}
+
+impl AlertFiring {
+    /// Notification text, e.g. "SOL touched your level at $150.0000 (now $150.0200)"
+    pub fn message(&self) -> String {
+        format!(
+            "{} touched your {} at ${:.4} (now ${:.4})",
+            self.symbol,
+            self.shape.describe(),
+            self.trigger_price,
+            self.price
+        )
+    }
+}
+
+/// Every drawing, saved in the local database
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChartAnnotations {
+    #[serde(default)]
+    pub items: Vec<Annotation>,
+    #[serde(default)]
+    next_id: u64,
+}
+
+impl ChartAnnotations {
+    /// Add a drawing without an alert; returns its ID
+    pub fn add(&mut self, symbol: &str, shape: Shape) -> u64 {
+        self.next_id += 1;
+        self.items.push(Annotation {
+            id: self.next_id,
+            symbol: symbol.to_uppercase(),
+            shape,
+            alert: None,
+        });
+        self.next_id
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Annotation> {
+        self.items.iter().find(|annotation| annotation.id == id)
+    }
+
+    /// Drawings on `symbol`'s chart
+    pub fn for_symbol<'a>(&'a self, symbol: &'a str) -> impl Iterator<Item = &'a Annotation> + 'a {
+        self.items.iter().filter(move |annotation| annotation.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    /// Move a drawing to `point`, re-arming its alert; false if it doesn't exist
+    pub fn move_to(&mut self, id: u64, point: ChartPoint) -> bool {
+        let Some(annotation) = self.items.iter_mut().find(|annotation| annotation.id == id) else {
+            return false;
+        };
+        annotation.shape = annotation.shape.moved_to(point);
+        if let Some(rule) = &mut annotation.alert {
+            *rule = AlertRule::new(rule.rearm);
+        }
+        true
+    }
+
+    /// Delete a drawing along with its alert
+    pub fn remove(&mut self, id: u64) -> Option<Annotation> {
+        let index = self.items.iter().position(|annotation| annotation.id == id)?;
+        Some(self.items.remove(index))
+    }
+
+    /// Turn a drawing's alert on with `rearm`, or off with `None`
+    pub fn set_alert(&mut self, id: u64, rearm: Option<Rearm>) {
+        if let Some(annotation) = self.items.iter_mut().find(|annotation| annotation.id == id) {
+            annotation.alert = rearm.map(AlertRule::new);
+        }
+    }
+
+    /// Check `symbol`'s alerts against its latest price at `now`
+    pub fn check(&mut self, symbol: &str, price: f64, now: i64) -> Vec<AlertFiring> {
+        let mut fired = Vec::new();
+        for annotation in &mut self.items {
+            if !annotation.symbol.eq_ignore_ascii_case(symbol) {
+                continue;
+            }
+            let Some(rule) = &mut annotation.alert else {
+                continue;
+            };
+            let trigger_price = annotation.shape.price_at(now);
+            if rule.observe(price, trigger_price) {
+                fired.push(AlertFiring {
+                    annotation_id: annotation.id,
+                    symbol: annotation.symbol.clone(),
+                    shape: annotation.shape,
+                    trigger_price,
+                    price,
+                });
+            }
+        }
+        fired
+    }
+}
+
+/// Drawing tool picked in the chart toolbar
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DrawingTool {
+    /// Clicks pan and zoom the chart as usual
+    #[default]
+    Pointer,
+    Level,
+    /// Waiting for the first point, then the second
+    Trendline { from: Option<ChartPoint> },
+    /// The next click moves this drawing
+    Move(u64),
+}
+
+impl DrawingTool {
+    /// Apply a click on `symbol`'s chart and return the tool to use next
+    ///
+    /// A finished drawing or move goes back to [`DrawingTool::Pointer`].
+    pub fn click(self, annotations: &mut ChartAnnotations, symbol: &str, point: ChartPoint) -> DrawingTool {
+        match self {
+            DrawingTool::Pointer => DrawingTool::Pointer,
+            DrawingTool::Level => {
+                annotations.add(symbol, Shape::Level { price: point.price });
+                DrawingTool::Pointer
+            }
+            DrawingTool::Trendline { from: None } => DrawingTool::Trendline { from: Some(point) },
+            DrawingTool::Trendline { from: Some(from) } => {
+                annotations.add(symbol, Shape::Trendline { from, to: point });
+                DrawingTool::Pointer
+            }
+            DrawingTool::Move(id) => {
+                annotations.move_to(id, point);
+                DrawingTool::Pointer
+            }
+        }
+    }
+}
+
+/// What the user did on the chart, for the app to apply
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartAction {
+    SelectTool(DrawingTool),
+    Click { symbol: &'static str, point: ChartPoint },
+    SetAlert { id: u64, rearm: Option<Rearm> },
+    Delete(u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(time: i64, price: f64) -> ChartPoint {
+        ChartPoint { time, price }
+    }
+
+    #[test]
+    fn test_trendline_price_at_extends_along_slope() {
+        let line = Shape::Trendline { from: point(1_000, 100.0), to: point(4_600, 136.0) };
+        assert_eq!(line.price_at(1_000), 100.0);
+        assert_eq!(line.price_at(2_800), 118.0);
+        // Past either end the line keeps its slope
+        assert_eq!(line.price_at(8_200), 172.0);
+        assert_eq!(line.price_at(0), 90.0);
+
+        // Drawn right to left it is the same line
+        let reversed = Shape::Trendline { from: point(4_600, 136.0), to: point(1_000, 100.0) };
+        assert_eq!(reversed.price_at(8_200), 172.0);
+
+        // Both clicks at the same time can't define a slope
+        let vertical = Shape::Trendline { from: point(1_000, 100.0), to: point(1_000, 110.0) };
+        assert_eq!(vertical.price_at(9_999), 105.0);
+        assert_eq!(Shape::Level { price: 150.0 }.price_at(9_999), 150.0);
+    }
+
+    #[test]
+    fn test_level_alert_fires_on_touch_and_disarms() {
+        let mut annotations = ChartAnnotations::default();
+        let id = annotations.add("sol", Shape::Level { price: 150.0 });
+        assert!(annotations.check("SOL", 140.0, 0).is_empty(), "no alert yet");
+
+        annotations.set_alert(id, Some(Rearm::Once));
+        assert!(annotations.check("SOL", 149.0, 0).is_empty());
+        assert!(annotations.check("BONK", 150.0, 0).is_empty());
+
+        // Jumping across the line counts as touching it
+        let fired = annotations.check("SOL", 151.0, 0);
+        assert_eq!(fired.len(), 1);
+        assert_eq!((fired[0].annotation_id, fired[0].trigger_price, fired[0].price), (id, 150.0, 151.0));
+        assert_eq!(fired[0].message(), "SOL touched your level at $150.0000 (now $151.0000)");
+        assert!(!annotations.get(id).unwrap().alert.unwrap().armed);
+
+        assert!(annotations.check("SOL", 149.0, 0).is_empty(), "disarmed after firing once");
+    }
+
+    #[test]
+    fn test_rearming_alert_fires_on_each_touch() {
+        let mut annotations = ChartAnnotations::default();
+        let id = annotations.add("SOL", Shape::Level { price: 150.0 });
+        annotations.set_alert(id, Some(Rearm::EachTouch));
+
+        annotations.check("SOL", 148.0, 0);
+        assert_eq!(annotations.check("SOL", 150.0, 0).len(), 1);
+        // Sitting on the line is one touch
+        assert!(annotations.check("SOL", 150.0, 0).is_empty());
+        assert!(annotations.check("SOL", 152.0, 0).is_empty());
+        assert_eq!(annotations.check("SOL", 149.0, 0).len(), 1);
+        assert!(annotations.get(id).unwrap().alert.unwrap().armed);
+    }
+
+    #[test]
+    fn test_trendline_alert_follows_time() {
+        let mut annotations = ChartAnnotations::default();
+        let id = annotations.add("SOL", Shape::Trendline { from: point(0, 100.0), to: point(100, 110.0) });
+        annotations.set_alert(id, Some(Rearm::Once));
+
+        // The price stands still while the rising line catches up with it
+        assert!(annotations.check("SOL", 115.0, 100).is_empty());
+        assert!(annotations.check("SOL", 115.0, 140).is_empty());
+        let fired = annotations.check("SOL", 115.0, 160);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].trigger_price, 116.0);
+        assert_eq!(fired[0].message(), "SOL touched your trendline at $116.0000 (now $115.0000)");
+    }
+
+    #[test]
+    fn test_moving_rearms_and_deleting_drops_the_rule() {
+        let mut annotations = ChartAnnotations::default();
+        let id = annotations.add("SOL", Shape::Level { price: 150.0 });
+        annotations.set_alert(id, Some(Rearm::Once));
+        annotations.check("SOL", 149.0, 0);
+        assert_eq!(annotations.check("SOL", 150.0, 0).len(), 1);
+
+        // Moved above the price: re-armed, and the old side doesn't carry over
+        assert!(annotations.move_to(id, point(0, 160.0)));
+        let rule = annotations.get(id).unwrap().alert.unwrap();
+        assert!(rule.armed);
+        assert_eq!(rule.rearm, Rearm::Once);
+        assert!(annotations.check("SOL", 155.0, 0).is_empty());
+        assert_eq!(annotations.check("SOL", 161.0, 0).len(), 1);
+
+        // A trendline moves by its nearest end
+        let line = annotations.add("SOL", Shape::Trendline { from: point(0, 100.0), to: point(100, 110.0) });
+        annotations.move_to(line, point(90, 120.0));
+        assert_eq!(
+            annotations.get(line).unwrap().shape,
+            Shape::Trendline { from: point(0, 100.0), to: point(90, 120.0) }
+        );
+
+        assert!(annotations.remove(id).is_some());
+        assert!(annotations.check("SOL", 160.0, 0).is_empty());
+        assert!(!annotations.move_to(id, point(0, 1.0)));
+        assert_eq!(annotations.for_symbol("sol").count(), 1);
+    }
+
+    #[test]
+    fn test_drawing_tool_clicks() {
+        let mut annotations = ChartAnnotations::default();
+
+        let tool = DrawingTool::Level.click(&mut annotations, "SOL", point(10, 150.0));
+        assert_eq!(tool, DrawingTool::Pointer);
+        assert_eq!(annotations.items[0].shape, Shape::Level { price: 150.0 });
+
+        let tool = DrawingTool::Trendline { from: None }.click(&mut annotations, "SOL", point(0, 100.0));
+        assert_eq!(tool, DrawingTool::Trendline { from: Some(point(0, 100.0)) });
+        assert_eq!(tool.click(&mut annotations, "SOL", point(50, 105.0)), DrawingTool::Pointer);
+        assert_eq!(annotations.items.len(), 2);
+        assert_ne!(annotations.items[0].id, annotations.items[1].id);
+
+        assert_eq!(DrawingTool::Pointer.click(&mut annotations, "SOL", point(0, 1.0)), DrawingTool::Pointer);
+        assert_eq!(annotations.items.len(), 2);
+    }
+}
@@ -0,0 +1,399 @@
+//! # Technical Indicators
+//!
+//! SMA, EMA and RSI computed over chart candles (close prices).
+//!
+//! Each indicator is a small streaming calculator that consumes one close at a
+//! time. [`IndicatorSeries`] keeps the calculator state from just before the
+//! last candle, so when the newest candle is updated in place or a single
+//! candle is appended, only the last value is recomputed instead of the whole
+//! series.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Indicator selection and periods (persisted with the settings)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndicatorConfig {
+    pub sma_enabled: bool,
+    pub sma_period: usize,
+    pub ema_enabled: bool,
+    pub ema_period: usize,
+    pub rsi_enabled: bool,
+    pub rsi_period: usize,
+}
+
+impl Default for IndicatorConfig {
+    fn default() -> Self {
+        Self {
+            sma_enabled: true,
+            sma_period: 20,
+            ema_enabled: false,
+            ema_period: 50,
+            rsi_enabled: false,
+            rsi_period: 14,
+        }
+    }
+}
+
+/// Simple moving average over a fixed window
+#[derive(Debug, Clone)]
+pub struct Sma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            window: VecDeque::with_capacity(period),
+            sum: 0.0,
+        }
+    }
+
+    /// Add a value, returning the average once the window is full
+    pub fn push(&mut self, value: f64) -> Option<f64> {
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > self.period {
+            if let Some(old) = self.window.pop_front() {
+                self.sum -= old;
+            }
+        }
+        (self.window.len() == self.period).then(|| self.sum / self.period as f64)
+    }
+}
+
+/// Exponential moving average, seeded with the SMA of the first `period` values
+#[derive(Debug, Clone)]
+pub struct Ema {
+    alpha: f64,
+    seed: Sma,
+    value: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            alpha: 2.0 / (period as f64 + 1.0),
+            seed: Sma::new(period),
+            value: None,
+        }
+    }
+
+    pub fn push(&mut self, value: f64) -> Option<f64> {
+        self.value = match self.value {
+            Some(prev) => Some(self.alpha * value + (1.0 - self.alpha) * prev),
+            None => self.seed.push(value),
+        };
+        self.value
+    }
+}
+
+/// Relative strength index with Wilder smoothing
+#[derive(Debug, Clone)]
+pub struct Rsi {
+    period: usize,
+    prev_close: Option<f64>,
+    changes: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            prev_close: None,
+            changes: 0,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+        }
+    }
+
+    pub fn push(&mut self, close: f64) -> Option<f64> {
+        let prev = self.prev_close.replace(close)?;
+        let change = close - prev;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let n = self.period as f64;
+
+        self.changes += 1;
+        if self.changes <= self.period {
+            // Initial averages are plain means of the first `period` changes
+            self.avg_gain += gain / n;
+            self.avg_loss += loss / n;
+            if self.changes < self.period {
+                return None;
+            }
+        } else {
+            self.avg_gain = (self.avg_gain * (n - 1.0) + gain) / n;
+            self.avg_loss = (self.avg_loss * (n - 1.0) + loss) / n;
+        }
+
+        if self.avg_loss == 0.0 {
+            return Some(if self.avg_gain == 0.0 { 50.0 } else { 100.0 });
+        }
+        let rs = self.avg_gain / self.avg_loss;
+        Some(100.0 - 100.0 / (1.0 + rs))
+    }
+}
+
+/// Calculator state for all indicators
+#[derive(Debug, Clone)]
+struct Calculators {
+    sma: Sma,
+    ema: Ema,
+    rsi: Rsi,
+}
+
+impl Calculators {
+    fn new(config: &IndicatorConfig) -> Self {
+        Self {
+            sma: Sma::new(config.sma_period),
+            ema: Ema::new(config.ema_period),
+            rsi: Rsi::new(config.rsi_period),
+        }
+    }
+}
+
+/// Indicator values aligned with the chart candles (`None` during warm-up)
+#[derive(Debug, Clone)]
+pub struct IndicatorSeries {
+    config: IndicatorConfig,
+    /// Candle timestamps the values belong to
+    timestamps: Vec<i64>,
+    pub sma: Vec<Option<f64>>,
+    pub ema: Vec<Option<f64>>,
+    pub rsi: Vec<Option<f64>>,
+    /// State after every candle except the last
+    before_last: Calculators,
+    /// State after the last candle
+    current: Calculators,
+}
+
+impl Default for IndicatorSeries {
+    fn default() -> Self {
+        Self::new(IndicatorConfig::default())
+    }
+}
+
+impl IndicatorSeries {
+    pub fn new(config: IndicatorConfig) -> Self {
+        Self {
+            config,
+            timestamps: Vec::new(),
+            sma: Vec::new(),
+            ema: Vec::new(),
+            rsi: Vec::new(),
+            before_last: Calculators::new(&config),
+            current: Calculators::new(&config),
+        }
+    }
+
+    pub fn config(&self) -> &IndicatorConfig {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    /// Timestamp of the candle at `index`
+    pub fn timestamp(&self, index: usize) -> Option<i64> {
+        self.timestamps.get(index).copied()
+    }
+
+    /// Bring the series in line with `candles` (sorted by time).
+    ///
+    /// - Last candle updated in place: recompute only the last value.
+    /// - One candle appended: compute only the new value.
+    /// - Anything else (new timeframe, backfill, config change): full recompute.
+    pub fn update(&mut self, candles: &[shared::dto::OHLC], config: &IndicatorConfig) {
+        if *config != self.config {
+            *self = Self::new(*config);
+        }
+
+        let n = self.timestamps.len();
+        let same_prefix = |len: usize| {
+            len <= candles.len()
+                && self.timestamps.first() == candles.first().map(|c| c.timestamp).as_ref()
+                && (len == 0 || self.timestamps[len - 1] == candles[len - 1].timestamp)
+        };
+
+        if n > 0 && candles.len() == n && same_prefix(n) {
+            self.pop_last();
+            self.push(&candles[n - 1]);
+        } else if n > 0 && candles.len() == n + 1 && same_prefix(n) {
+            self.push(&candles[n]);
+        } else {
+            *self = Self::new(*config);
+            for candle in candles {
+                self.push(candle);
+            }
+        }
+    }
+
+    fn push(&mut self, candle: &shared::dto::OHLC) {
+        self.before_last = self.current.clone();
+        self.timestamps.push(candle.timestamp);
+        self.sma.push(self.current.sma.push(candle.close));
+        self.ema.push(self.current.ema.push(candle.close));
+        self.rsi.push(self.current.rsi.push(candle.close));
+    }
+
+    /// Drop the last value and rewind the calculators to before it.
+    ///
+    /// Only one step of history is kept, so this must be followed by a push.
+    fn pop_last(&mut self) {
+        self.timestamps.pop();
+        self.sma.pop();
+        self.ema.pop();
+        self.rsi.pop();
+        self.current = self.before_last.clone();
+    }
+
+    /// Plot points `[timestamp, value]` for an indicator, skipping warm-up
+    pub fn points(&self, values: &[Option<f64>]) -> Vec<[f64; 2]> {
+        self.timestamps
+            .iter()
+            .zip(values)
+            .filter_map(|(ts, v)| v.map(|v| [*ts as f64, v]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::dto::OHLC;
+
+    fn candles(closes: &[f64]) -> Vec<OHLC> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, c)| OHLC::new(1_704_067_200 + i as i64 * 3600, *c, *c, *c, *c, 0.0))
+            .collect()
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("indicator value");
+        assert!((actual - expected).abs() < 0.01, "expected {expected}, got {actual}");
+    }
+
+    // Closes from the StockCharts EMA / RSI worked examples
+    const EMA_CLOSES: [f64; 12] = [22.27, 22.19, 22.08, 22.17, 22.18, 22.13, 22.23, 22.43, 22.24, 22.29, 22.15, 22.39];
+    const RSI_CLOSES: [f64; 17] = [
+        44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61, 46.28, 46.28,
+        46.00, 46.03,
+    ];
+
+    #[test]
+    fn test_sma_window() {
+        let mut sma = Sma::new(3);
+        let values: Vec<_> = [1.0, 2.0, 3.0, 4.0, 5.0].iter().map(|v| sma.push(*v)).collect();
+        assert_eq!(values, vec![None, None, Some(2.0), Some(3.0), Some(4.0)]);
+    }
+
+    #[test]
+    fn test_ema_fixture() {
+        let mut ema = Ema::new(10);
+        let values: Vec<_> = EMA_CLOSES.iter().map(|v| ema.push(*v)).collect();
+        assert!(values[..9].iter().all(Option::is_none));
+        assert_close(values[9], 22.22);
+        assert_close(values[10], 22.21);
+        assert_close(values[11], 22.24);
+    }
+
+    #[test]
+    fn test_rsi_fixture() {
+        let mut rsi = Rsi::new(14);
+        let values: Vec<_> = RSI_CLOSES.iter().map(|v| rsi.push(*v)).collect();
+        assert!(values[..14].iter().all(Option::is_none));
+        assert_close(values[14], 70.46);
+        assert_close(values[15], 66.25);
+        assert_close(values[16], 66.48);
+    }
+
+    #[test]
+    fn test_rsi_flat_and_rising() {
+        let mut flat = Rsi::new(3);
+        assert_eq!([1.0, 1.0, 1.0, 1.0].iter().filter_map(|v| flat.push(*v)).last(), Some(50.0));
+        let mut rising = Rsi::new(3);
+        assert_eq!([1.0, 2.0, 3.0, 4.0].iter().filter_map(|v| rising.push(*v)).last(), Some(100.0));
+    }
+
+    fn config() -> IndicatorConfig {
+        IndicatorConfig {
+            sma_enabled: true,
+            sma_period: 5,
+            ema_enabled: true,
+            ema_period: 10,
+            rsi_enabled: true,
+            rsi_period: 14,
+        }
+    }
+
+    fn full(candles: &[OHLC]) -> IndicatorSeries {
+        let mut series = IndicatorSeries::default();
+        series.update(candles, &config());
+        series
+    }
+
+    fn assert_same(a: &IndicatorSeries, b: &IndicatorSeries) {
+        assert_eq!(a.timestamps, b.timestamps);
+        for (x, y) in [(&a.sma, &b.sma), (&a.ema, &b.ema), (&a.rsi, &b.rsi)] {
+            assert_eq!(x.len(), y.len());
+            for (x, y) in x.iter().zip(y) {
+                match (x, y) {
+                    (Some(x), Some(y)) => assert!((x - y).abs() < 1e-9),
+                    (x, y) => assert_eq!(x, y),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_appended_candle_matches_full_recompute() {
+        let all = candles(&RSI_CLOSES);
+        let mut series = full(&all[..16]);
+        series.update(&all, &config());
+        assert_same(&series, &full(&all));
+    }
+
+    #[test]
+    fn test_updated_last_candle_matches_full_recompute() {
+        let mut all = candles(&RSI_CLOSES);
+        let mut series = full(&all);
+
+        // Live tick moves the close of the forming candle twice
+        for close in [46.50, 45.90] {
+            all.last_mut().unwrap().close = close;
+            series.update(&all, &config());
+            assert_same(&series, &full(&all));
+        }
+    }
+
+    #[test]
+    fn test_new_timeframe_or_config_recomputes() {
+        let all = candles(&RSI_CLOSES);
+        let mut series = full(&all[..10]);
+
+        // Unrelated candle set (different start) rebuilds from scratch
+        let shifted: Vec<OHLC> = all[3..].to_vec();
+        series.update(&shifted, &config());
+        assert_same(&series, &full(&shifted));
+
+        let other = IndicatorConfig { sma_period: 3, ..config() };
+        series.update(&shifted, &other);
+        assert_eq!(series.config(), &other);
+        assert!(series.sma[2].is_some());
+    }
+}
@@ -0,0 +1,795 @@
+//! # Chart Module
+//!
+//! Chart rendering using egui_plot for candlestick and line charts.
+//! Technical indicator math lives in [`indicators`]; drawn levels and
+//! trendlines, and the alerts derived from them, in [`annotations`].
+//!
+//! The candlestick chart keeps the candle and volume geometry it built in
+//! egui's temporary memory, keyed by the candles' data version
+//! (`TerminalState::chart_data_version`). A price tick that only moves the
+//! forming candle rebuilds that one candle instead of the whole series.
+
+pub mod annotations;
+pub mod indicators;
+
+use egui;
+use egui_plot::{Plot, PlotPoints, Line};
+use std::collections::VecDeque;
+
+/// OHLCV candlestick data point
+#[derive(Debug, Clone)]
+pub struct Candle {
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+/// Chart timeframe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timeframe {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+    FourHours,
+    OneDay,
+}
+
+impl Timeframe {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Timeframe::OneMinute => "1m",
+            Timeframe::FiveMinutes => "5m",
+            Timeframe::FifteenMinutes => "15m",
+            Timeframe::OneHour => "1h",
+            Timeframe::FourHours => "4h",
+            Timeframe::OneDay => "1d",
+        }
+    }
+
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Timeframe::OneMinute => 60,
+            Timeframe::FiveMinutes => 300,
+            Timeframe::FifteenMinutes => 900,
+            Timeframe::OneHour => 3600,
+            Timeframe::FourHours => 14400,
+            Timeframe::OneDay => 86400,
+        }
+    }
+}
+
+/// Chart state and data
+#[derive(Debug, Clone)]
+pub struct ChartData {
+    pub candles: VecDeque<Candle>,
+    pub timeframe: Timeframe,
+    pub symbol: String,
+    pub max_candles: usize,
+}
+
+impl ChartData {
+    pub fn new(symbol: String) -> Self {
+        Self {
+            candles: VecDeque::with_capacity(100),
+            timeframe: Timeframe::OneHour,
+            symbol,
+            max_candles: 100,
+        }
+    }
+
+    /// Add a new candle
+    pub fn add_candle(&mut self, candle: Candle) {
+        self.candles.push_back(candle);
+        while self.candles.len() > self.max_candles {
+            self.candles.pop_front();
+        }
+    }
+
+    /// Get price range for visible candles
+    pub fn price_range(&self) -> (f64, f64) {
+        if self.candles.is_empty() {
+            return (0.0, 100.0);
+        }
+
+        let mut min = f64::MAX;
+        let mut max = f64::MIN;
+
+        for candle in &self.candles {
+            if candle.low < min {
+                min = candle.low;
+            }
+            if candle.high > max {
+                max = candle.high;
+            }
+        }
+
+        // Add 5% padding
+        let padding = (max - min) * 0.05;
+        (min - padding, max + padding)
+    }
+
+    /// Generate demo/random candles for testing
+    pub fn generate_demo_data(&mut self, count: usize) {
+        use rand::Rng;
+        let mut rng = rand::rng();
+
+        let base_price: f64 = 150.0;
+        let mut current_price: f64 = base_price;
+        let now = chrono::Utc::now().timestamp();
+
+        for i in 0..count {
+            let timestamp = now - ((count - i - 1) as i64 * self.timeframe.seconds());
+
+            // Random walk
+            let change: f64 = rng.random_range(-5.0..5.0);
+            current_price += change;
+            current_price = current_price.max(base_price * 0.8).min(base_price * 1.2);
+
+            let open: f64 = current_price;
+            let close: f64 = open + rng.random_range(-3.0..3.0);
+            let high: f64 = open.max(close) + rng.random_range(0.0..2.0);
+            let low: f64 = open.min(close) - rng.random_range(0.0..2.0);
+            let volume: f64 = rng.random_range(1000.0..10000.0);
+
+            self.add_candle(Candle {
+                timestamp,
+                open,
+                high,
+                low,
+                close,
+                volume,
+            });
+
+            current_price = close;
+        }
+    }
+}
+
+/// Render candlestick chart using egui_plot
+pub fn render_chart(ui: &mut egui::Ui, chart_data: &ChartData) {
+    if chart_data.candles.is_empty() {
+        ui.label("No chart data available");
+        return;
+    }
+
+    // Prepare data for plotting
+    let mut close_points = Vec::new();
+    let mut high_points = Vec::new();
+    let mut low_points = Vec::new();
+
+    for (idx, candle) in chart_data.candles.iter().enumerate() {
+        let x = idx as f64;
+        close_points.push([x, candle.close]);
+        high_points.push([x, candle.high]);
+        low_points.push([x, candle.low]);
+    }
+
+    // Create plot
+    Plot::new("candlestick_chart")
+        .view_aspect(2.0)
+        .show(ui, |plot_ui| {
+            // Close price line
+            if !close_points.is_empty() {
+                plot_ui.line(
+                    Line::new("Close Price", PlotPoints::from(close_points))
+                        .color(egui::Color32::from_rgb(0, 255, 0))
+                        .width(2.0)
+                );
+            }
+
+            // High/Low range (optional, can be shown as error bars or area)
+            // For simplicity, just show close price line for now
+        });
+
+    // Show latest price info
+    if let Some(last_candle) = chart_data.candles.back() {
+        ui.horizontal(|ui| {
+            ui.label(format!("Current: ${:.2}", last_candle.close));
+            let price_change = last_candle.close - last_candle.open;
+            let percent_change = (price_change / last_candle.open) * 100.0;
+            if price_change >= 0.0 {
+                ui.colored_label(egui::Color32::from_rgb(0, 255, 0), format!("▲ {:.2}%", percent_change.abs()));
+            } else {
+                ui.colored_label(egui::Color32::from_rgb(255, 0, 0), format!("▼ {:.2}%", percent_change.abs()));
+            }
+        });
+    }
+}
+
+/// Fraction of a timeframe period covered by a candle body / volume bar
+const CANDLE_WIDTH_FRACTION: f64 = 0.7;
+
+/// Height of the volume panel below the price chart
+const VOLUME_PANEL_HEIGHT: f32 = 80.0;
+
+/// Height of the RSI panel below the volume panel
+const RSI_PANEL_HEIGHT: f32 = 90.0;
+
+/// Format a candle timestamp with a precision suited to the timeframe
+pub fn format_candle_time(timestamp: i64, timeframe: shared::dto::market::Timeframe) -> String {
+    use shared::dto::market::Timeframe;
+
+    let Some(dt) = chrono::DateTime::from_timestamp(timestamp, 0) else {
+        return timestamp.to_string();
+    };
+    let format = match timeframe {
+        Timeframe::OneMinute | Timeframe::FiveMinutes | Timeframe::FifteenMinutes => "%Y-%m-%d %H:%M",
+        Timeframe::OneHour | Timeframe::FourHours => "%Y-%m-%d %H:00",
+        Timeframe::OneDay => "%Y-%m-%d",
+        Timeframe::OneWeek => "Week of %Y-%m-%d",
+    };
+    dt.format(format).to_string()
+}
+
+/// Short axis label for a timestamp (time of day for intraday, date otherwise)
+fn format_axis_time(timestamp: i64, timeframe: shared::dto::market::Timeframe) -> String {
+    use shared::dto::market::Timeframe;
+
+    let Some(dt) = chrono::DateTime::from_timestamp(timestamp, 0) else {
+        return String::new();
+    };
+    match timeframe {
+        Timeframe::OneMinute | Timeframe::FiveMinutes | Timeframe::FifteenMinutes | Timeframe::OneHour => {
+            dt.format("%H:%M").to_string()
+        }
+        Timeframe::FourHours => dt.format("%m-%d %H:%M").to_string(),
+        Timeframe::OneDay | Timeframe::OneWeek => dt.format("%m-%d").to_string(),
+    }
+}
+
+/// Tooltip text for a hovered candle
+pub fn candle_tooltip(candle: &shared::dto::OHLC, timeframe: shared::dto::market::Timeframe) -> String {
+    format!(
+        "{}\nO: {:.4}\nH: {:.4}\nL: {:.4}\nC: {:.4}\nV: {:.2}",
+        format_candle_time(candle.timestamp, timeframe),
+        candle.open,
+        candle.high,
+        candle.low,
+        candle.close,
+        candle.volume,
+    )
+}
+
+/// X-axis bounds covering all candles, padded by one period on each side.
+///
+/// Candles are plotted at their timestamps so gaps in the data stay visible;
+/// the padding keeps a single candle from producing a zero-width range.
+pub fn time_bounds(candles: &[shared::dto::OHLC], timeframe: shared::dto::market::Timeframe) -> Option<(f64, f64)> {
+    let period = timeframe.duration_secs() as f64;
+    let min = candles.iter().map(|c| c.timestamp).min()?;
+    let max = candles.iter().map(|c| c.timestamp).max()?;
+    Some((min as f64 - period, max as f64 + period))
+}
+
+/// What the cached candle geometry was built for; any change but the data
+/// version forces a full rebuild
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GeometryKey {
+    data_version: u64,
+    timeframe: shared::dto::market::Timeframe,
+    forming: bool,
+    price_up: egui::Color32,
+    price_down: egui::Color32,
+}
+
+/// Candle bodies and volume bars built from one version of the candles
+#[derive(Clone, Default)]
+struct CandleGeometry {
+    key: Option<GeometryKey>,
+    /// Candles the geometry was built from
+    source: Vec<shared::dto::OHLC>,
+    boxes: Vec<egui_plot::BoxElem>,
+    bars: Vec<egui_plot::Bar>,
+    min_price: f64,
+    max_price: f64,
+}
+
+impl CandleGeometry {
+    /// Bring the geometry up to date with `candles`
+    ///
+    /// Candles dropped off the front are removed and those matching what the
+    /// geometry was built from are kept; the rest are rebuilt. The previously
+    /// last candle is always rebuilt, as the forming one is drawn lighter.
+    ///
+    /// # Returns
+    /// Number of candles rebuilt, 0 when the cache was current
+    fn update(&mut self, key: GeometryKey, candles: &[shared::dto::OHLC], theme: &crate::ui::theme::Theme) -> usize {
+        use egui_plot::{Bar, BoxElem, BoxSpread};
+
+        if self.key == Some(key) {
+            return 0;
+        }
+        let same_style = self.key.is_some_and(|old| GeometryKey { data_version: key.data_version, ..old } == key);
+        self.key = Some(key);
+        if !same_style {
+            self.source.clear();
+            self.boxes.clear();
+            self.bars.clear();
+        }
+
+        // Candles the chart dropped as new ones were appended
+        let first = candles.first().map_or(i64::MAX, |candle| candle.timestamp);
+        let dropped = self.source.iter().take_while(|candle| candle.timestamp < first).count();
+        self.source.drain(..dropped);
+        self.boxes.drain(..dropped);
+        self.bars.drain(..dropped);
+
+        let unchanged = self
+            .source
+            .iter()
+            .zip(candles)
+            .take_while(|(cached, candle)| cached == candle)
+            .count()
+            .min(self.source.len().saturating_sub(1));
+        self.source.truncate(unchanged);
+        self.boxes.truncate(unchanged);
+        self.bars.truncate(unchanged);
+
+        let width = key.timeframe.duration_secs() as f64 * CANDLE_WIDTH_FRACTION;
+        let forming_at = key.forming.then(|| candles.len().saturating_sub(1));
+        for (i, candle) in candles.iter().enumerate().skip(unchanged) {
+            let x = candle.timestamp as f64;
+            let mut color = if candle.close >= candle.open { theme.price_up } else { theme.price_down };
+            if forming_at == Some(i) {
+                color = color.gamma_multiply(0.5);
+            }
+            let body_top = candle.open.max(candle.close);
+            let body_bottom = candle.open.min(candle.close);
+            let tooltip = candle_tooltip(candle, key.timeframe);
+
+            self.boxes.push(
+                BoxElem::new(x, BoxSpread::new(candle.low, body_bottom, candle.close, body_top, candle.high))
+                    .name(&tooltip)
+                    .box_width(width)
+                    .whisker_width(0.0)
+                    .fill(color)
+                    .stroke(egui::Stroke::new(1.0, color)),
+            );
+            self.bars.push(
+                Bar::new(x, candle.volume)
+                    .name(tooltip)
+                    .width(width)
+                    .fill(color.gamma_multiply(0.6)),
+            );
+            self.source.push(candle.clone());
+        }
+
+        (self.min_price, self.max_price) = candles
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(lo, hi), c| (lo.min(c.low), hi.max(c.high)));
+        candles.len() - unchanged
+    }
+}
+
+/// Levels and trendlines to draw over a symbol's candles
+pub struct Drawings<'a> {
+    pub symbol: &'static str,
+    pub annotations: &'a annotations::ChartAnnotations,
+    pub tool: annotations::DrawingTool,
+}
+
+/// Screen distance within which a right click picks a drawing
+const ANNOTATION_HIT_PX: f32 = 8.0;
+
+/// Drawing tool buttons for the chart header
+pub fn render_drawing_toolbar(ui: &mut egui::Ui, tool: annotations::DrawingTool) -> Option<annotations::ChartAction> {
+    use annotations::{ChartAction, DrawingTool};
+
+    let mut action = None;
+    for (label, hint, choice) in [
+        ("Level", "Click the chart to draw a horizontal level", DrawingTool::Level),
+        ("Trendline", "Click two points on the chart to draw a trendline", DrawingTool::Trendline { from: None }),
+    ] {
+        let active = std::mem::discriminant(&tool) == std::mem::discriminant(&choice);
+        if ui.selectable_label(active, label).on_hover_text(hint).clicked() {
+            action = Some(ChartAction::SelectTool(if active { DrawingTool::Pointer } else { choice }));
+        }
+    }
+    if let DrawingTool::Move(_) = tool {
+        if ui.selectable_label(true, "Moving").on_hover_text("Click where the drawing should go").clicked() {
+            action = Some(ChartAction::SelectTool(DrawingTool::Pointer));
+        }
+    }
+    action
+}
+
+/// Render candlestick chart from real OHLC data, with a volume panel below
+/// that shares the time axis.
+///
+/// With `forming`, the last candle is still forming and is drawn in a lighter
+/// shade than the closed ones.
+///
+/// `data_version` identifies the candles and `forming` flag; the geometry is
+/// cached while it stays the same.
+///
+/// When `indicators` is given, enabled SMA/EMA are drawn over the candles and
+/// RSI gets its own panel under the volume.
+///
+/// When `drawings` is given, its levels and trendlines are drawn over the
+/// candles (with a bell when they carry an alert). Clicks with a drawing tool
+/// picked, and choices from a drawing's right-click menu, are returned for the
+/// app to apply.
+pub fn render_candlestick_chart(
+    ui: &mut egui::Ui,
+    candles: &[shared::dto::OHLC],
+    data_version: u64,
+    timeframe: shared::dto::market::Timeframe,
+    loading: bool,
+    forming: bool,
+    indicators: Option<&indicators::IndicatorSeries>,
+    drawings: Option<Drawings<'_>>,
+    theme: &crate::ui::theme::Theme,
+) -> Option<annotations::ChartAction> {
+    use annotations::{ChartAction, ChartPoint, DrawingTool};
+    use egui_plot::{BarChart, BoxPlot, HLine, Plot, Points};
+    use tracing::trace;
+
+    if loading {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.colored_label(theme.dim, format!("Loading {} candles...", timeframe.label()));
+        });
+    }
+
+    let Some((x_min, x_max)) = time_bounds(candles, timeframe) else {
+        if !loading {
+            ui.label("No chart data available");
+        }
+        return None;
+    };
+
+    trace!(candle_count = candles.len(), "Rendering candlestick chart");
+
+    let key = GeometryKey {
+        data_version,
+        timeframe,
+        forming,
+        price_up: theme.price_up,
+        price_down: theme.price_down,
+    };
+    let geometry_id = ui.id().with("candlestick_geometry");
+    let (boxes, bars, min_price, max_price) = ui.data_mut(|data| {
+        let geometry = data.get_temp_mut_or_default::<CandleGeometry>(geometry_id);
+        let started = std::time::Instant::now();
+        let rebuilt = geometry.update(key, candles, theme);
+        // egui_plot takes the elements by value, so even a cached frame copies
+        // them; the copy is part of what the frame costs
+        let (boxes, bars) = (geometry.boxes.clone(), geometry.bars.clone());
+        crate::debug::metrics::record_chart_geometry(started.elapsed(), rebuilt);
+        (boxes, bars, geometry.min_price, geometry.max_price)
+    });
+    // Flat series (or a single doji) still needs a visible range
+    let padding = ((max_price - min_price) * 0.1).max(max_price.abs() * 0.001).max(f64::EPSILON);
+
+    let link_group = ui.id().with("candlestick_link");
+
+    // Only use indicator values computed for these candles
+    let indicators = indicators.filter(|series| series.len() == candles.len());
+    let overlays: Vec<(String, Vec<[f64; 2]>, egui::Color32)> = indicators
+        .map(|series| {
+            let config = series.config();
+            let mut lines = Vec::new();
+            if config.sma_enabled {
+                lines.push((format!("SMA {}", config.sma_period), series.points(&series.sma), theme.info));
+            }
+            if config.ema_enabled {
+                lines.push((format!("EMA {}", config.ema_period), series.points(&series.ema), theme.warning));
+            }
+            lines
+        })
+        .unwrap_or_default();
+
+    let drawn: Vec<&annotations::Annotation> = drawings
+        .as_ref()
+        .map(|drawings| drawings.annotations.for_symbol(drawings.symbol).collect())
+        .unwrap_or_default();
+    let tool = drawings.as_ref().map_or(DrawingTool::Pointer, |drawings| drawings.tool);
+    let drawing = tool != DrawingTool::Pointer;
+
+    let price_plot = Plot::new("candlestick_chart")
+        .height((ui.available_width() / 2.5).max(150.0))
+        .include_x(x_min)
+        .include_x(x_max)
+        .include_y(min_price - padding)
+        .include_y(max_price + padding)
+        .link_axis(link_group, [true, false])
+        .link_cursor(link_group, [true, false])
+        // A drag while drawing would pan away from where the user aimed
+        .allow_drag(!drawing)
+        .show_x(false)
+        .label_formatter(|_, point| format!("{:.4}", point.y))
+        .x_axis_formatter(move |mark, _| format_axis_time(mark.value as i64, timeframe))
+        .show(ui, |plot_ui| {
+            plot_ui.box_plot(
+                BoxPlot::new("Price", boxes)
+                    .element_formatter(Box::new(|elem, _| elem.name.clone())),
+            );
+            for (name, points, color) in overlays {
+                plot_ui.line(Line::new(name, PlotPoints::from(points)).color(color).width(1.5));
+            }
+            for annotation in &drawn {
+                render_annotation(plot_ui, annotation, x_min, x_max, theme);
+            }
+            if let DrawingTool::Trendline { from: Some(from) } = tool {
+                plot_ui.points(
+                    Points::new("Trendline start", vec![[from.time as f64, from.price]])
+                        .radius(4.0)
+                        .color(theme.info),
+                );
+            }
+            plot_ui.pointer_coordinate()
+        });
+
+    let mut action = None;
+    if let Some(drawings) = &drawings {
+        let pointer = price_plot.inner;
+        if drawing && price_plot.response.clicked() {
+            action = pointer.map(|point| ChartAction::Click {
+                symbol: drawings.symbol,
+                point: ChartPoint { time: point.x.round() as i64, price: point.y },
+            });
+        }
+
+        // Remember which drawing was right-clicked while its menu stays open
+        let menu_id = ui.id().with("annotation_menu");
+        if price_plot.response.secondary_clicked() {
+            let hit = pointer.and_then(|point| annotation_near(&drawn, &price_plot.transform, point));
+            ui.data_mut(|data| data.insert_temp(menu_id, hit));
+        }
+        let target = ui.data(|data| data.get_temp::<Option<u64>>(menu_id)).flatten();
+        if let Some(annotation) = target.and_then(|id| drawings.annotations.get(id)) {
+            price_plot.response.context_menu(|ui| {
+                if let Some(choice) = annotation_menu(ui, annotation) {
+                    action = Some(choice);
+                    ui.close();
+                }
+            });
+        }
+    }
+
+    Plot::new("volume_chart")
+        .height(VOLUME_PANEL_HEIGHT)
+        .include_x(x_min)
+        .include_x(x_max)
+        .include_y(0.0)
+        .link_axis(link_group, [true, false])
+        .link_cursor(link_group, [true, false])
+        .allow_zoom([true, false])
+        .show_x(false)
+        .show_y(false)
+        .x_axis_formatter(move |mark, _| format_axis_time(mark.value as i64, timeframe))
+        .y_axis_formatter(|mark, _| format_volume(mark.value))
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(
+                BarChart::new("Volume", bars)
+                    .element_formatter(Box::new(|bar, _| bar.name.clone())),
+            );
+        });
+
+    if let Some(series) = indicators.filter(|series| series.config().rsi_enabled) {
+        let name = format!("RSI {}", series.config().rsi_period);
+        let points = series.points(&series.rsi);
+
+        Plot::new("rsi_chart")
+            .height(RSI_PANEL_HEIGHT)
+            .include_x(x_min)
+            .include_x(x_max)
+            .include_y(0.0)
+            .include_y(100.0)
+            .link_axis(link_group, [true, false])
+            .link_cursor(link_group, [true, false])
+            .allow_zoom([true, false])
+            .allow_drag([true, false])
+            .show_x(false)
+            .label_formatter(|name, point| format!("{} {:.1}", name, point.y))
+            .x_axis_formatter(move |mark, _| format_axis_time(mark.value as i64, timeframe))
+            .show(ui, |plot_ui| {
+                plot_ui.hline(HLine::new("Overbought", 70.0).color(theme.dim));
+                plot_ui.hline(HLine::new("Oversold", 30.0).color(theme.dim));
+                plot_ui.line(Line::new(name, PlotPoints::from(points)).color(theme.info).width(1.5));
+            });
+    }
+
+    action
+}
+
+/// Draw one level or trendline across the visible time range
+fn render_annotation(
+    plot_ui: &mut egui_plot::PlotUi<'_>,
+    annotation: &annotations::Annotation,
+    x_min: f64,
+    x_max: f64,
+    theme: &crate::ui::theme::Theme,
+) {
+    use crate::ui::widgets::icons::{material, size, Icons};
+    use annotations::Shape;
+    use egui_plot::{HLine, PlotPoint, Text};
+
+    let color = match annotation.alert {
+        Some(rule) if rule.armed => theme.warning,
+        Some(_) => theme.dim,
+        None => theme.info,
+    };
+    match annotation.shape {
+        Shape::Level { price } => {
+            plot_ui.hline(HLine::new(format!("Level ${:.4}", price), price).color(color).width(1.5));
+        }
+        Shape::Trendline { .. } => {
+            let points: Vec<[f64; 2]> = [x_min, x_max]
+                .into_iter()
+                .map(|x| [x, annotation.shape.price_at(x as i64)])
+                .collect();
+            plot_ui.line(Line::new("Trendline", PlotPoints::from(points)).color(color).width(1.5));
+        }
+    }
+
+    if annotation.alert.is_some() {
+        let anchor = PlotPoint::new(x_max, annotation.shape.price_at(x_max as i64));
+        plot_ui.text(
+            Text::new("Alert", anchor, Icons::icon_color(material::BELL, size::SMALL, color))
+                .anchor(egui::Align2::RIGHT_BOTTOM),
+        );
+    }
+}
+
+/// The drawing closest to `pointer` on screen, if any is within reach
+fn annotation_near(
+    drawn: &[&annotations::Annotation],
+    transform: &egui_plot::PlotTransform,
+    pointer: egui_plot::PlotPoint,
+) -> Option<u64> {
+    let pointer_y = transform.position_from_point(&pointer).y;
+    drawn
+        .iter()
+        .map(|annotation| {
+            let on_line = egui_plot::PlotPoint::new(pointer.x, annotation.shape.price_at(pointer.x as i64));
+            (annotation.id, (transform.position_from_point(&on_line).y - pointer_y).abs())
+        })
+        .filter(|(_, distance)| *distance <= ANNOTATION_HIT_PX)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(id, _)| id)
+}
+
+/// Right-click menu of a drawing: its alert, move and delete
+fn annotation_menu(ui: &mut egui::Ui, annotation: &annotations::Annotation) -> Option<annotations::ChartAction> {
+    use annotations::{ChartAction, DrawingTool, Rearm};
+
+    let id = annotation.id;
+    let mut alert_on = annotation.alert.is_some();
+    if ui.checkbox(&mut alert_on, "Alert on touch").changed() {
+        return Some(ChartAction::SetAlert { id, rearm: alert_on.then(Rearm::default) });
+    }
+    if let Some(rule) = annotation.alert {
+        for rearm in [Rearm::Once, Rearm::EachTouch] {
+            // Picking an option again re-arms a rule that already fired
+            if ui.radio(rule.rearm == rearm, rearm.label()).clicked() {
+                return Some(ChartAction::SetAlert { id, rearm: Some(rearm) });
+            }
+        }
+        if !rule.armed {
+            ui.label("Fired; pick an option or move the drawing to re-arm");
+        }
+    }
+
+    ui.separator();
+    if ui.button("Move").on_hover_text("Then click where it should go").clicked() {
+        return Some(ChartAction::SelectTool(DrawingTool::Move(id)));
+    }
+    if ui.button("Delete").clicked() {
+        return Some(ChartAction::Delete(id));
+    }
+    None
+}
+
+/// Compact volume label (1.2K, 3.4M)
+pub(crate) fn format_volume(volume: f64) -> String {
+    if volume >= 1_000_000.0 {
+        format!("{:.1}M", volume / 1_000_000.0)
+    } else if volume >= 1_000.0 {
+        format!("{:.1}K", volume / 1_000.0)
+    } else {
+        format!("{:.0}", volume)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::dto::market::Timeframe;
+    use shared::dto::OHLC;
+
+    #[test]
+    fn test_time_bounds_single_candle_is_not_degenerate() {
+        let candles = vec![OHLC::new(1_704_067_200, 100.0, 100.0, 100.0, 100.0, 0.0)];
+        let (min, max) = time_bounds(&candles, Timeframe::OneHour).unwrap();
+        assert_eq!(max - min, 7200.0);
+        assert!(time_bounds(&[], Timeframe::OneHour).is_none());
+    }
+
+    #[test]
+    fn test_time_bounds_with_gaps_and_unordered_input() {
+        let candles = vec![
+            OHLC::new(1_704_067_200 + 10 * 3600, 1.0, 2.0, 0.5, 1.5, 10.0),
+            OHLC::new(1_704_067_200, 1.0, 2.0, 0.5, 1.5, 10.0),
+        ];
+        let (min, max) = time_bounds(&candles, Timeframe::OneHour).unwrap();
+        assert_eq!(min, (1_704_067_200 - 3600) as f64);
+        assert_eq!(max, (1_704_067_200 + 11 * 3600) as f64);
+    }
+
+    #[test]
+    fn test_format_candle_time_per_timeframe() {
+        let ts = 1_704_067_200 + 3600 + 300; // 2024-01-01 01:05 UTC
+        assert_eq!(format_candle_time(ts, Timeframe::FiveMinutes), "2024-01-01 01:05");
+        assert_eq!(format_candle_time(ts, Timeframe::OneHour), "2024-01-01 01:00");
+        assert_eq!(format_candle_time(ts, Timeframe::OneDay), "2024-01-01");
+    }
+
+    fn key(data_version: u64, timeframe: Timeframe, forming: bool) -> GeometryKey {
+        let theme = crate::ui::theme::Theme::default();
+        GeometryKey { data_version, timeframe, forming, price_up: theme.price_up, price_down: theme.price_down }
+    }
+
+    fn hourly(count: i64, close: f64) -> Vec<OHLC> {
+        (0..count).map(|i| OHLC::new(i * 3600, 100.0, 110.0, 90.0, close, 5.0)).collect()
+    }
+
+    #[test]
+    fn test_geometry_rebuilds_only_what_changed() {
+        let theme = crate::ui::theme::Theme::default();
+        let mut geometry = CandleGeometry::default();
+        let mut candles = hourly(100, 105.0);
+
+        assert_eq!(geometry.update(key(1, Timeframe::OneHour, true), &candles, &theme), 100);
+        // Same version: drawn from cache
+        assert_eq!(geometry.update(key(1, Timeframe::OneHour, true), &candles, &theme), 0);
+
+        // A tick moves the forming candle
+        candles[99].close = 108.0;
+        assert_eq!(geometry.update(key(2, Timeframe::OneHour, true), &candles, &theme), 1);
+        assert_eq!(geometry.boxes[99].spread.median, 108.0);
+
+        // A new period: the closed candle loses its lighter shade, the new one is added
+        candles.push(OHLC::new(100 * 3600, 108.0, 109.0, 107.0, 108.5, 1.0));
+        candles.remove(0);
+        assert_eq!(geometry.update(key(3, Timeframe::OneHour, true), &candles, &theme), 2);
+        assert_eq!(geometry.boxes.len(), 100);
+        assert_eq!(geometry.boxes[0].argument, 3600.0);
+        assert_eq!(geometry.boxes[98].fill, theme.price_up);
+
+        // A reload that corrects an old candle rebuilds from there on
+        candles[10].close = 95.0;
+        assert_eq!(geometry.update(key(4, Timeframe::OneHour, true), &candles, &theme), 90);
+        assert_eq!(geometry.bars[10].fill, theme.price_down.gamma_multiply(0.6));
+    }
+
+    #[test]
+    fn test_geometry_invalidates_on_timeframe_and_symbol_change() {
+        let theme = crate::ui::theme::Theme::default();
+        let mut geometry = CandleGeometry::default();
+        let candles = hourly(50, 105.0);
+        geometry.update(key(1, Timeframe::OneHour, false), &candles, &theme);
+
+        // Candles still on screen while the new timeframe loads are redrawn at its width
+        assert_eq!(geometry.update(key(1, Timeframe::FourHours, false), &candles, &theme), 50);
+        assert_eq!(geometry.boxes[0].box_width, 4.0 * 3600.0 * CANDLE_WIDTH_FRACTION);
+
+        // Another symbol's candles share no prefix with the cached ones
+        let other: Vec<OHLC> = (0..50).map(|i| OHLC::new(i * 14_400, 1.0, 1.1, 0.9, 1.05, 5.0)).collect();
+        assert_eq!(geometry.update(key(2, Timeframe::FourHours, false), &other, &theme), 50);
+        assert_eq!(geometry.max_price, 1.1);
+    }
+
+    #[test]
+    fn test_candle_tooltip_contains_ohlcv() {
+        let candle = OHLC::new(1_704_067_200, 1.0, 2.0, 0.5, 1.5, 1234.0);
+        let tooltip = candle_tooltip(&candle, Timeframe::OneDay);
+        assert!(tooltip.starts_with("2024-01-01\n"));
+        assert!(tooltip.contains("H: 2.0000"));
+        assert!(tooltip.contains("V: 1234.00"));
+    }
+}
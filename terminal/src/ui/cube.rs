@@ -1,101 +1,107 @@
-//! # Rotating 3D Icosahedron
+//! # Rotating 3D Brand Mesh
 //!
-//! A simple 3D wireframe icosahedron that rotates on screen.
-//! Uses 2D projection of 3D coordinates.
+//! A 3D wireframe that rotates on screen behind the logo text, using a 2D
+//! projection of 3D coordinates. The built-in mesh is an icosahedron with
+//! "XF" at its center; white-label builds swap in their own through
+//! `branding.toml` (see [`crate::app::branding`]).
+//!
+//! ## Mesh Format
+//!
+//! Meshes are JSON with vertex coordinates and the edges between them, as
+//! indices into `vertices`:
+//!
+//! ```json
+//! {
+//!   "vertices": [[0.0, 1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, -1.0, 0.0]],
+//!   "edges": [[0, 1], [1, 2], [2, 0]]
+//! }
+//! ```
+//!
+//! Coordinates are in any unit: meshes are centered and scaled to fit the
+//! unit sphere on load, so they render at the configured size. Meshes without
+//! a visible shape (fewer than two distinct vertices, no edges) or above
+//! [`MAX_MESH_VERTICES`] / [`MAX_MESH_EDGES`] are rejected.
 
 use egui::{Color32, Painter, Pos2, FontId, FontFamily};
+use serde::Deserialize;
 use std::f32::consts::PI;
+use thiserror::Error;
 
-/// Rotating 3D icosahedron renderer
-pub struct RotatingCube {
-    /// Rotation angle around Y axis (in radians)
-    rotation_y: f32,
-    /// Rotation angle around X axis (in radians)
-    rotation_x: f32,
-    /// Size of the icosahedron
-    pub size: f32,
-}
+/// Most vertices a brand mesh may have
+pub const MAX_MESH_VERTICES: usize = 512;
 
-impl RotatingCube {
-    /// Create a new rotating icosahedron
-    pub fn new() -> Self {
-        Self {
-            rotation_y: 0.0,
-            rotation_x: 0.0,
-            size: 150.0, // Bigger default size
-        }
-    }
+/// Most edges a brand mesh may have
+pub const MAX_MESH_EDGES: usize = 2048;
 
-    /// Update the cube rotation (call every frame)
-    pub fn update(&mut self, delta_time: f32) {
-        // Rotate around Y axis (vertical)
-        self.rotation_y += delta_time * 1.0; // 1 radian per second
-        // Rotate around X axis (horizontal) - slower
-        self.rotation_x += delta_time * 0.5; // 0.5 radian per second
-        
-        // Keep angles in reasonable range to avoid overflow
-        if self.rotation_y > 2.0 * PI {
-            self.rotation_y -= 2.0 * PI;
-        }
-        if self.rotation_x > 2.0 * PI {
-            self.rotation_x -= 2.0 * PI;
-        }
-    }
+/// Why a brand mesh was rejected
+#[derive(Debug, Error, PartialEq)]
+pub enum MeshError {
+    #[error("invalid mesh JSON: {0}")]
+    Parse(String),
+    #[error("mesh has {0} vertices, at most {MAX_MESH_VERTICES} are allowed")]
+    TooManyVertices(usize),
+    #[error("mesh has {0} edges, at most {MAX_MESH_EDGES} are allowed")]
+    TooManyEdges(usize),
+    #[error("mesh has no edges")]
+    NoEdges,
+    #[error("vertex {0} has a non-finite coordinate")]
+    NonFiniteVertex(usize),
+    #[error("edge {edge} points at vertex {vertex}, but the mesh has {count} vertices")]
+    EdgeOutOfRange { edge: usize, vertex: usize, count: usize },
+    #[error("edge {0} connects a vertex to itself")]
+    SelfLoop(usize),
+    #[error("all vertices are at the same point")]
+    Degenerate,
+}
 
-    /// Render the cube
-    pub fn render(&self, painter: &Painter, center: Pos2, size: f32) {
-        self.render_with_size(painter, center, size);
-    }
+/// Wireframe mesh, centered and scaled to fit the unit sphere
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrandMesh {
+    pub vertices: Vec<[f32; 3]>,
+    pub edges: Vec<(usize, usize)>,
+}
 
-    /// Render the icosahedron with specific size
-    fn render_with_size(&self, painter: &Painter, center: Pos2, size: f32) {
+/// On-disk form of [`BrandMesh`]
+#[derive(Deserialize)]
+struct MeshFile {
+    vertices: Vec<[f32; 3]>,
+    edges: Vec<[usize; 2]>,
+}
+
+impl BrandMesh {
+    /// The built-in icosahedron
+    pub fn icosahedron() -> Self {
         // Golden ratio constant
         let phi = (1.0 + 5.0_f32.sqrt()) / 2.0;
-        let radius = size * 0.5;
-        
+
         // Define icosahedron vertices using golden ratio
         // Icosahedron has 12 vertices arranged in 3 orthogonal golden rectangles
         // Using standard icosahedron coordinates
-        let t = radius / phi.sqrt();
-        let vertices_3d = [
+        let t = 1.0 / phi.sqrt();
+        let vertices = vec![
             // Top and bottom vertices
-            [0.0, radius, 0.0],           // 0: top
-            [0.0, -radius, 0.0],          // 1: bottom
-            
+            [0.0, 1.0, 0.0],              // 0: top
+            [0.0, -1.0, 0.0],             // 1: bottom
+
             // First golden rectangle (in XY plane, rotated)
             [t, t / phi, 0.0],            // 2
             [-t, t / phi, 0.0],           // 3
             [t, -t / phi, 0.0],           // 4
             [-t, -t / phi, 0.0],          // 5
-            
+
             // Second golden rectangle (in XZ plane)
             [t / phi, 0.0, t],            // 6
             [-t / phi, 0.0, t],           // 7
             [t / phi, 0.0, -t],           // 8
             [-t / phi, 0.0, -t],          // 9
-            
+
             // Third golden rectangle (in YZ plane)
             [0.0, t / phi, t],            // 10
             [0.0, -t / phi, t],           // 11
         ];
 
-        // Rotate vertices
-        let rotated_vertices: Vec<[f32; 3]> = vertices_3d
-            .iter()
-            .map(|&v| self.rotate_vertex(v))
-            .collect();
-
-        // Project to 2D
-        let vertices_2d: Vec<Pos2> = rotated_vertices
-            .iter()
-            .map(|&v| self.project_to_2d(v, center))
-            .collect();
-
-        // Define edges - icosahedron has 30 edges
-        // Each vertex connects to 5 others
-        // Using proper icosahedron connectivity
         // Vertices: 0=top, 1=bottom, 2-5=first rectangle, 6-9=second rectangle, 10-11=third rectangle
-        let edges = [
+        let edges = vec![
             // Top vertex (0) connects to 5 vertices: 2, 3, 6, 7, 10
             (0, 2), (0, 3), (0, 6), (0, 7), (0, 10),
             // Bottom vertex (1) connects to 5 vertices: 4, 5, 8, 9, 11
@@ -112,83 +118,231 @@ impl RotatingCube {
             (6, 10), (7, 10), (8, 11), (9, 11),
             // Connect second rectangle vertices to each other: 6-8, 7-9
             (6, 8), (7, 9),
-            // Additional connections to complete icosahedron: 6-2, 7-3, 8-4, 9-5
-            // These are already covered above, but ensuring all 30 edges are present
         ];
 
-        // Draw edges with thicker lines in bright white
-        let color = Color32::from_rgb(255, 255, 255); // Bright white
-        let stroke_width = 4.0; // Thicker lines
-        
-        for &(i, j) in &edges {
+        Self { vertices, edges }
+    }
+
+    /// Parse and validate a mesh in the JSON format described in the module docs
+    pub fn from_json(json: &str) -> Result<Self, MeshError> {
+        let file: MeshFile = serde_json::from_str(json).map_err(|e| MeshError::Parse(e.to_string()))?;
+        let mesh = Self {
+            vertices: file.vertices,
+            edges: file.edges.into_iter().map(|[a, b]| (a, b)).collect(),
+        };
+        mesh.validate()?;
+        Ok(mesh.normalized())
+    }
+
+    /// Check the mesh is small enough to draw every frame and has a visible shape
+    pub fn validate(&self) -> Result<(), MeshError> {
+        if self.vertices.len() > MAX_MESH_VERTICES {
+            return Err(MeshError::TooManyVertices(self.vertices.len()));
+        }
+        if self.edges.len() > MAX_MESH_EDGES {
+            return Err(MeshError::TooManyEdges(self.edges.len()));
+        }
+        if self.edges.is_empty() {
+            return Err(MeshError::NoEdges);
+        }
+        if let Some(index) = self.vertices.iter().position(|v| v.iter().any(|c| !c.is_finite())) {
+            return Err(MeshError::NonFiniteVertex(index));
+        }
+        for (edge, &(a, b)) in self.edges.iter().enumerate() {
+            if let Some(vertex) = [a, b].into_iter().find(|&v| v >= self.vertices.len()) {
+                return Err(MeshError::EdgeOutOfRange { edge, vertex, count: self.vertices.len() });
+            }
+            if a == b {
+                return Err(MeshError::SelfLoop(edge));
+            }
+        }
+        // Distinct edge endpoints guarantee two vertices; they must not coincide
+        if self.radius_around(self.centroid()) <= f32::EPSILON {
+            return Err(MeshError::Degenerate);
+        }
+        Ok(())
+    }
+
+    /// Centered on the origin and scaled so the farthest vertex is at distance 1
+    fn normalized(mut self) -> Self {
+        let center = self.centroid();
+        let radius = self.radius_around(center);
+        for vertex in &mut self.vertices {
+            for (c, center) in vertex.iter_mut().zip(center) {
+                *c = (*c - center) / radius;
+            }
+        }
+        self
+    }
+
+    fn centroid(&self) -> [f32; 3] {
+        let n = self.vertices.len().max(1) as f32;
+        let mut sum = [0.0; 3];
+        for vertex in &self.vertices {
+            for (s, c) in sum.iter_mut().zip(vertex) {
+                *s += c;
+            }
+        }
+        sum.map(|s| s / n)
+    }
+
+    fn radius_around(&self, center: [f32; 3]) -> f32 {
+        self.vertices
+            .iter()
+            .map(|v| v.iter().zip(center).map(|(c, m)| (c - m).powi(2)).sum::<f32>().sqrt())
+            .fold(0.0, f32::max)
+    }
+}
+
+impl Default for BrandMesh {
+    fn default() -> Self {
+        Self::icosahedron()
+    }
+}
+
+/// How the mesh and its logo text are drawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshStyle {
+    /// Rotation around the Y axis, in radians per second
+    pub speed_y: f32,
+    /// Rotation around the X axis, in radians per second
+    pub speed_x: f32,
+    pub edge_color: Color32,
+    pub edge_width: f32,
+    /// Text drawn at the center, rotating against the mesh
+    pub logo_text: String,
+    pub logo_color: Color32,
+}
+
+impl Default for MeshStyle {
+    fn default() -> Self {
+        Self {
+            speed_y: 1.0,
+            speed_x: 0.5,
+            edge_color: Color32::from_rgb(255, 255, 255), // Bright white
+            edge_width: 4.0,                              // Thicker lines
+            logo_text: "XF".to_string(),
+            logo_color: Color32::from_rgb(204, 0, 0), // Xterminal red
+        }
+    }
+}
+
+/// Rotating 3D brand mesh renderer
+pub struct RotatingCube {
+    /// Rotation angle around Y axis (in radians)
+    rotation_y: f32,
+    /// Rotation angle around X axis (in radians)
+    rotation_x: f32,
+    /// Size of the mesh
+    pub size: f32,
+    mesh: std::sync::Arc<BrandMesh>,
+    style: MeshStyle,
+}
+
+impl RotatingCube {
+    /// Create a new rotating icosahedron
+    pub fn new() -> Self {
+        Self::with_mesh(std::sync::Arc::new(BrandMesh::icosahedron()), MeshStyle::default(), 150.0)
+    }
+
+    /// Create a renderer for the configured branding
+    pub fn from_branding(branding: &crate::app::branding::Branding) -> Self {
+        Self::with_mesh(branding.mesh.clone(), branding.mesh_style(), branding.mesh_size)
+    }
+
+    fn with_mesh(mesh: std::sync::Arc<BrandMesh>, style: MeshStyle, size: f32) -> Self {
+        Self {
+            rotation_y: 0.0,
+            rotation_x: 0.0,
+            size,
+            mesh,
+            style,
+        }
+    }
+
+    /// Update the mesh rotation (call every frame)
+    pub fn update(&mut self, delta_time: f32) {
+        self.rotation_y += delta_time * self.style.speed_y;
+        self.rotation_x += delta_time * self.style.speed_x;
+
+        // Keep angles in reasonable range to avoid overflow
+        self.rotation_y = self.rotation_y.rem_euclid(2.0 * PI);
+        self.rotation_x = self.rotation_x.rem_euclid(2.0 * PI);
+    }
+
+    /// Render the mesh
+    pub fn render(&self, painter: &Painter, center: Pos2, size: f32) {
+        self.render_with_size(painter, center, size);
+    }
+
+    /// Render the mesh with specific size
+    fn render_with_size(&self, painter: &Painter, center: Pos2, size: f32) {
+        let radius = size * 0.5;
+
+        // Scale, rotate and project to 2D
+        let vertices_2d: Vec<Pos2> = self
+            .mesh
+            .vertices
+            .iter()
+            .map(|&[x, y, z]| self.project_to_2d(self.rotate_vertex([x * radius, y * radius, z * radius]), center))
+            .collect();
+
+        let stroke = (self.style.edge_width, self.style.edge_color);
+        for &(i, j) in &self.mesh.edges {
             if i < vertices_2d.len() && j < vertices_2d.len() {
-                painter.line_segment([vertices_2d[i], vertices_2d[j]], (stroke_width, color));
+                painter.line_segment([vertices_2d[i], vertices_2d[j]], stroke);
             }
         }
 
-        // Draw "XF" text in RED capital letters at the center, rotating opposite to the shape
-        let xf_font = FontId::new(size * 0.4, FontFamily::Proportional); // Scale font size with icosahedron
-        let red_color = Color32::from_rgb(204, 0, 0); // Xterminal red
-        
-        // Rotate text in opposite direction to the icosahedron
-        // If icosahedron rotates +Y and +X, text rotates -Y and -X
+        // Draw the logo text at the center, rotating opposite to the shape
+        // If the mesh rotates +Y and +X, text rotates -Y and -X
+        let font = FontId::new(size * 0.4, FontFamily::Proportional); // Scale font size with the mesh
         let text_rotation = -(self.rotation_y + self.rotation_x);
-        
-        // Calculate rotated positions for "X" and "F" to create rotation effect
-        let cos_rot = text_rotation.cos();
-        let sin_rot = text_rotation.sin();
-        let char_spacing = size * 0.15; // Spacing between X and F
-        
-        // Calculate positions for X and F with rotation
-        // X position (left of center)
-        let x_offset = -char_spacing * 0.5;
-        let x_rotated_x = center.x + x_offset * cos_rot - 0.0 * sin_rot;
-        let x_rotated_y = center.y + x_offset * sin_rot + 0.0 * cos_rot;
-        let x_pos = Pos2::new(x_rotated_x, x_rotated_y);
-        
-        // F position (right of center)
-        let f_offset = char_spacing * 0.5;
-        let f_rotated_x = center.x + f_offset * cos_rot - 0.0 * sin_rot;
-        let f_rotated_y = center.y + f_offset * sin_rot + 0.0 * cos_rot;
-        let f_pos = Pos2::new(f_rotated_x, f_rotated_y);
-        
-        // Draw X and F separately with rotation effect
-        painter.text(x_pos, egui::Align2::CENTER_CENTER, "X", xf_font.clone(), red_color);
-        painter.text(f_pos, egui::Align2::CENTER_CENTER, "F", xf_font, red_color);
+        let (sin_rot, cos_rot) = text_rotation.sin_cos();
+        let char_spacing = size * 0.15; // Spacing between letters
+
+        // Letters are spread evenly around the center along the rotated baseline
+        let letters: Vec<char> = self.style.logo_text.chars().collect();
+        let middle = (letters.len() as f32 - 1.0) / 2.0;
+        for (i, letter) in letters.iter().enumerate() {
+            let offset = (i as f32 - middle) * char_spacing;
+            let pos = Pos2::new(center.x + offset * cos_rot, center.y + offset * sin_rot);
+            painter.text(pos, egui::Align2::CENTER_CENTER, letter, font.clone(), self.style.logo_color);
+        }
     }
 
     /// Rotate a 3D vertex around Y and X axes
     fn rotate_vertex(&self, vertex: [f32; 3]) -> [f32; 3] {
         let [x, y, z] = vertex;
-        
+
         // Rotate around Y axis
         let cos_y = self.rotation_y.cos();
         let sin_y = self.rotation_y.sin();
         let x1 = x * cos_y - z * sin_y;
         let z1 = x * sin_y + z * cos_y;
-        
+
         // Rotate around X axis
         let cos_x = self.rotation_x.cos();
         let sin_x = self.rotation_x.sin();
         let y1 = y * cos_x - z1 * sin_x;
         let z2 = y * sin_x + z1 * cos_x;
-        
+
         [x1, y1, z2]
     }
 
     /// Project 3D point to 2D screen coordinates
     fn project_to_2d(&self, vertex: [f32; 3], center: Pos2) -> Pos2 {
         let [x, y, z] = vertex;
-        
+
         // Simple perspective projection
         // Distance from camera
         let distance = 300.0;
         let scale = distance / (distance + z);
-        
+
         // Project to 2D
         let x_2d = x * scale;
         let y_2d = y * scale;
-        
+
         // Translate to center
         Pos2::new(center.x + x_2d, center.y - y_2d) // Negative y because screen Y is down
     }
@@ -211,3 +365,91 @@ impl Default for RotatingCube {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRIANGLE: &str = r#"{
+        "vertices": [[0.0, 10.0, 0.0], [10.0, -10.0, 0.0], [-10.0, -10.0, 0.0]],
+        "edges": [[0, 1], [1, 2], [2, 0]]
+    }"#;
+
+    #[test]
+    fn test_icosahedron_is_valid() {
+        let mesh = BrandMesh::icosahedron();
+        assert_eq!(mesh.vertices.len(), 12);
+        assert_eq!(mesh.edges.len(), 30);
+        assert_eq!(mesh.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_from_json_normalizes_to_unit_sphere() {
+        let mesh = BrandMesh::from_json(TRIANGLE).unwrap();
+        assert_eq!(mesh.edges, vec![(0, 1), (1, 2), (2, 0)]);
+
+        let center = mesh.centroid();
+        assert!(center.iter().all(|c| c.abs() < 1e-5));
+        assert!((mesh.radius_around(center) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_from_json_rejects_degenerate_meshes() {
+        let mesh = |vertices: &str, edges: &str| {
+            BrandMesh::from_json(&format!(r#"{{"vertices": {}, "edges": {}}}"#, vertices, edges))
+        };
+
+        assert!(matches!(mesh("[[0, 0, 0]]", "[[0]]"), Err(MeshError::Parse(_))));
+        assert_eq!(mesh("[[0, 0, 0], [1, 0, 0]]", "[]"), Err(MeshError::NoEdges));
+        assert_eq!(
+            mesh("[[0, 0, 0], [1, 0, 0]]", "[[0, 2]]"),
+            Err(MeshError::EdgeOutOfRange { edge: 0, vertex: 2, count: 2 })
+        );
+        assert_eq!(mesh("[[0, 0, 0], [1, 0, 0]]", "[[0, 1], [1, 1]]"), Err(MeshError::SelfLoop(1)));
+        assert_eq!(mesh("[[2, 2, 2], [2, 2, 2]]", "[[0, 1]]"), Err(MeshError::Degenerate));
+    }
+
+    #[test]
+    fn test_from_json_rejects_oversized_meshes() {
+        let vertices: Vec<[f32; 3]> = (0..=MAX_MESH_VERTICES).map(|i| [i as f32, 0.0, 0.0]).collect();
+        let json = serde_json::json!({ "vertices": vertices, "edges": [[0, 1]] }).to_string();
+        assert_eq!(BrandMesh::from_json(&json), Err(MeshError::TooManyVertices(MAX_MESH_VERTICES + 1)));
+
+        let edges: Vec<[usize; 2]> = vec![[0, 1]; MAX_MESH_EDGES + 1];
+        let json = serde_json::json!({ "vertices": [[0, 0, 0], [1, 0, 0]], "edges": edges }).to_string();
+        assert_eq!(BrandMesh::from_json(&json), Err(MeshError::TooManyEdges(MAX_MESH_EDGES + 1)));
+    }
+
+    /// Shapes the renderer paints for one frame
+    fn rendered_shapes(cube: &RotatingCube) -> Vec<egui::epaint::Shape> {
+        let ctx = egui::Context::default();
+        let output = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                cube.render(ui.painter(), Pos2::new(200.0, 200.0), cube.size);
+            });
+        });
+        output.shapes.into_iter().map(|clipped| clipped.shape).collect()
+    }
+
+    #[test]
+    fn test_render_draws_every_edge_and_logo_letter() {
+        let mesh = std::sync::Arc::new(BrandMesh::from_json(TRIANGLE).unwrap());
+        let style = MeshStyle {
+            logo_text: "ACME".to_string(),
+            ..MeshStyle::default()
+        };
+        let cube = RotatingCube::with_mesh(mesh, style, 100.0);
+
+        let shapes = rendered_shapes(&cube);
+        let lines = shapes.iter().filter(|s| matches!(s, egui::epaint::Shape::LineSegment { .. })).count();
+        let letters = shapes.iter().filter(|s| matches!(s, egui::epaint::Shape::Text(_))).count();
+        assert_eq!(lines, 3);
+        assert_eq!(letters, 4);
+
+        let default_shapes = rendered_shapes(&RotatingCube::new());
+        let lines = default_shapes
+            .iter()
+            .filter(|s| matches!(s, egui::epaint::Shape::LineSegment { .. }))
+            .count();
+        assert_eq!(lines, 30);
+    }
+}
@@ -1,10 +1,18 @@
 //! In-UI debug overlay (toggle with Ctrl+D)
 
 use egui;
+use egui_plot::{HLine, Line, Plot, PlotPoints, Points};
 
-use crate::debug::metrics::{get_frame_metrics, get_memory_metrics};
+use crate::debug::metrics::{
+    event_latency_history, event_overflow_counts, event_queue_depth, frame_time_history, get_frame_metrics,
+    get_memory_metrics, state_snapshot_history, state_snapshot_shared_bytes, chart_geometry_counts,
+    chart_geometry_history, Percentiles,
+};
 use crate::debug::task_tracker::active_task_count;
-use crate::debug::{get_trace_id, get_recent_errors, get_error_stats, total_error_count, pending_event_count};
+use crate::debug::{get_trace_id, get_recent_errors, get_error_stats, total_error_count, pending_event_count, recent_stall_reports};
+
+/// Frames slower than this (under 30 FPS) are marked red in the graph
+const SLOW_FRAME_MS: f64 = 33.0;
 
 /// Render debug overlay as an egui window
 pub fn render_debug_overlay(ctx: &egui::Context, state: &crate::app::AppState) {
@@ -59,6 +67,28 @@ pub fn render_debug_overlay(ctx: &egui::Context, state: &crate::app::AppState) {
                     }
                 }
 
+                // Shared bytes are what a deep clone would copy every frame
+                let snapshot = Percentiles::of(&state_snapshot_history());
+                ui.label(format!(
+                    "State snapshot: {:.3}ms p95, {:.1} KB shared",
+                    snapshot.p95,
+                    state_snapshot_shared_bytes() as f64 / 1024.0
+                ));
+
+                // Includes copying the geometry into the plot, which cached
+                // frames pay too; full rebuilds show up as spikes on top
+                let (cached, chart_frames, last_rebuilt) = chart_geometry_counts();
+                if chart_frames > 0 {
+                    let geometry = Percentiles::of(&chart_geometry_history());
+                    ui.label(format!(
+                        "Chart geometry (update + copy): {:.3}ms p95, {:.3}ms max, {}/{} frames cached, last rebuild {} candles",
+                        geometry.p95, geometry.max, cached, chart_frames, last_rebuilt
+                    ));
+                }
+
+                render_timing_graph(ui, "debug_frame_times", "Frame time", &frame_time_history(), Some(SLOW_FRAME_MS));
+                render_timing_graph(ui, "debug_event_latency", "Event processing", &event_latency_history(), None);
+
                 // Memory usage
                 if let Some(memory) = memory_metrics {
                     ui.label(format!("Memory: {:.1} MB", memory.process_mb));
@@ -69,6 +99,18 @@ pub fn render_debug_overlay(ctx: &egui::Context, state: &crate::app::AppState) {
                 // Task and Event Queue
                 ui.heading("Tasks & Events");
                 ui.label(format!("Active Tasks: {}", task_count));
+                let (queued, peak_queued) = event_queue_depth();
+                ui.label(format!(
+                    "Queued Events: {} / {} (peak {})",
+                    queued,
+                    crate::app::event_queue::capacity(),
+                    peak_queued
+                ));
+                let (dropped, coalesced) = event_overflow_counts();
+                ui.label(format!("Coalesced Events: {}", coalesced));
+                if dropped > 0 {
+                    ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("Dropped Events: {}", dropped));
+                }
                 let pending_events = pending_event_count();
                 if pending_events > 0 {
                     ui.colored_label(
@@ -81,6 +123,50 @@ pub fn render_debug_overlay(ctx: &egui::Context, state: &crate::app::AppState) {
 
                 ui.separator();
 
+                // Swap pipeline stages, this session
+                ui.heading("Swap Latency");
+                render_swap_latency(ui);
+
+                ui.separator();
+
+                // Main-thread stalls caught by the watchdog
+                ui.heading("Stalls (Last 5)");
+                let stalls = recent_stall_reports(5);
+                if stalls.is_empty() {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(0, 255, 0),
+                        "No stalls detected"
+                    );
+                } else {
+                    for stall in stalls {
+                        let started = chrono::DateTime::from_timestamp_millis(stall.started_at_ms as i64)
+                            .map(|t| t.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+                            .unwrap_or_default();
+                        let (color, status) = if stall.resolved {
+                            (egui::Color32::from_rgb(255, 165, 0), "")
+                        } else {
+                            (egui::Color32::from_rgb(255, 0, 0), " (ongoing)")
+                        };
+                        ui.colored_label(color, format!("{} {}ms{}", started, stall.duration_ms, status))
+                            .on_hover_text(stall.summary());
+                        if let Some(event) = &stall.last_event {
+                            ui.label(format!("  after {}", event));
+                        }
+                        for held in &stall.held_locks {
+                            let kind = if held.write { "write" } else { "read" };
+                            ui.label(format!("  {} {} by {} ({}ms)", held.lock, kind, held.caller, held.held_ms));
+                        }
+                        ui.label(format!(
+                            "  {} pending events, {} active tasks",
+                            stall.pending_events,
+                            stall.active_tasks.len()
+                        ));
+                        ui.add_space(4.0);
+                    }
+                }
+
+                ui.separator();
+
                 // Error Statistics
                 ui.heading("Error Statistics");
                 let error_stats = get_error_stats();
@@ -168,11 +254,12 @@ pub fn render_debug_overlay(ctx: &egui::Context, state: &crate::app::AppState) {
                 ui.heading("WebSocket & Charts");
                 
                 // WebSocket connection status
-                let ws_url = std::env::var("API_BASE_URL")
-                    .unwrap_or_else(|_| "http://localhost:3001".to_string())
-                    .replace("http://", "ws://")
-                    .replace("https://", "wss://")
-                    + "/api/ws/prices";
+                let base_url = state
+                    .api_client
+                    .as_ref()
+                    .map(|c| c.base_url())
+                    .unwrap_or_else(|| crate::services::api::failover::DEFAULT_SERVER.to_string());
+                let ws_url = state.config.price_stream_url(&base_url);
                 ui.label(format!("URL: {}", ws_url));
                 
                 if state.websocket_connected {
@@ -197,6 +284,21 @@ pub fn render_debug_overlay(ctx: &egui::Context, state: &crate::app::AppState) {
                 let total_messages = MESSAGE_COUNTER.load(Ordering::Relaxed);
                 let reconnect_attempts = RECONNECT_COUNTER.load(Ordering::Relaxed);
                 ui.label(format!("Price Updates: {total_messages}"));
+                // Any frame counts, so this stays fresh on a quiet market thanks to server pings
+                match state.websocket_status.last_message_at {
+                    Some(at) => {
+                        let silence = at.elapsed();
+                        let text = format!("Last Message: {:.1}s ago", silence.as_secs_f64());
+                        if silence > crate::services::api::websocket::STALL_TIMEOUT / 2 {
+                            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), text);
+                        } else {
+                            ui.label(text);
+                        }
+                    }
+                    None => {
+                        ui.label("Last Message: never");
+                    }
+                }
                 if reconnect_attempts > 0 {
                     ui.colored_label(
                         egui::Color32::from_rgb(255, 165, 0),
@@ -250,15 +352,88 @@ pub fn render_debug_overlay(ctx: &egui::Context, state: &crate::app::AppState) {
                 }
                 
                 // Token prices count
-                ui.label(format!("Tracked Tokens: {}", state.terminal.prices.len()));
+                ui.label(format!("Tracked Tokens: {}", state.terminal.prices.load().len()));
 
                 ui.separator();
 
-                ui.label("Press Ctrl+D to toggle this overlay");
+                ui.label(format!(
+                    "Press {} to toggle this overlay",
+                    state.settings.key_bindings.get(crate::app::keybindings::KeyAction::ToggleDebug)
+                ));
             });
         });
 }
 
+/// Small line chart of recent timings (ms) with p50/p95/max
+///
+/// With `slow_ms`, samples above it are marked with red points.
+fn render_timing_graph(ui: &mut egui::Ui, id: &str, title: &str, samples: &[f64], slow_ms: Option<f64>) {
+    let stats = Percentiles::of(samples);
+    ui.label(format!(
+        "{}: p50 {:.1}ms  p95 {:.1}ms  max {:.1}ms",
+        title, stats.p50, stats.p95, stats.max
+    ));
+    if samples.is_empty() {
+        return;
+    }
+
+    let points: Vec<[f64; 2]> = samples.iter().enumerate().map(|(i, ms)| [i as f64, *ms]).collect();
+    let slow: Vec<[f64; 2]> = match slow_ms {
+        Some(limit) => points.iter().copied().filter(|[_, ms]| *ms > limit).collect(),
+        None => Vec::new(),
+    };
+
+    Plot::new(id)
+        .height(80.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .show_x(false)
+        .include_y(0.0)
+        .y_axis_formatter(|mark, _range| format!("{:.0}ms", mark.value))
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new(title, PlotPoints::from(points)).color(egui::Color32::from_rgb(0, 200, 255)));
+            plot_ui.hline(HLine::new("p50", stats.p50).color(egui::Color32::from_rgb(0, 255, 0)).width(1.0));
+            plot_ui.hline(HLine::new("p95", stats.p95).color(egui::Color32::from_rgb(255, 165, 0)).width(1.0));
+            if !slow.is_empty() {
+                plot_ui.points(Points::new("Slow", PlotPoints::from(slow)).color(egui::Color32::from_rgb(255, 0, 0)).radius(2.5));
+            }
+        });
+}
+
+/// p50/p95 of each swap pipeline stage against its budget
+fn render_swap_latency(ui: &mut egui::Ui) {
+    let summary = crate::app::latency::session_summary();
+    if summary.swaps == 0 {
+        ui.label("No swaps acknowledged yet");
+        return;
+    }
+    ui.label(format!(
+        "{} swaps: p50 {}ms  p95 {}ms  max {}ms",
+        summary.swaps, summary.total.p50_ms, summary.total.p95_ms, summary.total.max_ms
+    ));
+
+    let budgets = crate::app::latency::budgets();
+    egui::Grid::new("debug_swap_latency").num_columns(4).striped(true).show(ui, |ui| {
+        for header in ["Stage", "p50", "p95", "Budget"] {
+            ui.strong(header);
+        }
+        ui.end_row();
+        for (stage, stats) in &summary.stages {
+            let budget = budgets.budget(*stage);
+            ui.label(stage.label());
+            ui.label(format!("{}ms", stats.p50_ms));
+            if stats.p95_ms > budget {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("{}ms", stats.p95_ms));
+            } else {
+                ui.label(format!("{}ms", stats.p95_ms));
+            }
+            ui.label(format!("{}ms", budget));
+            ui.end_row();
+        }
+    });
+}
+
 /// Check if debug overlay should be shown
 ///
 /// Controlled by:
@@ -0,0 +1,240 @@
+//! # Localization
+//!
+//! UI strings are looked up by key with [`tr!`](crate::tr) in the catalog of
+//! the active [`Locale`]. Catalogs are flat JSON objects embedded at compile
+//! time from `ui/i18n/<code>.json`; placeholders are written `{name}` and
+//! filled from the macro's `name = value` arguments.
+//!
+//! A key the active catalog lacks falls back to English, and to the key
+//! itself when English lacks it too. Switching locale logs the keys the new
+//! catalog is missing at debug level.
+//!
+//! Numbers shown next to translated text go through [`format_decimal`] and
+//! friends so they use the locale's decimal separator.
+
+use std::collections::{HashMap, HashSet};
+
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+
+/// Locale the UI is currently shown in
+static ACTIVE: Lazy<RwLock<Locale>> = Lazy::new(|| RwLock::new(Locale::default()));
+
+/// Parsed catalogs, one per locale
+static CATALOGS: Lazy<HashMap<Locale, HashMap<String, String>>> = Lazy::new(|| {
+    Locale::ALL
+        .into_iter()
+        .map(|locale| {
+            let catalog: HashMap<String, String> = serde_json::from_str(locale.source())
+                .unwrap_or_else(|e| panic!("ui/i18n/{}.json is not a flat JSON object: {}", locale.code(), e));
+            (locale, catalog)
+        })
+        .collect()
+});
+
+/// Keys found in no catalog, logged once each
+static REPORTED: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Language of the UI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Es];
+
+    /// Language code, also the catalog's file name
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+        }
+    }
+
+    /// Name of the language in the language itself, for the selector
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Es => "Español",
+        }
+    }
+
+    /// Separator between the integer and fractional digits
+    pub fn decimal_separator(self) -> char {
+        match self {
+            Locale::En => '.',
+            Locale::Es => ',',
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Locale::En => include_str!("en.json"),
+            Locale::Es => include_str!("es.json"),
+        }
+    }
+}
+
+/// Look up a UI string in the active locale
+///
+/// ```ignore
+/// ui.heading(tr!("settings.heading"));
+/// ui.label(tr!("status.wallet", address = short_addr, balance = format_decimal(sol, 4)));
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($key:literal) => {
+        $crate::ui::i18n::translate($key)
+    };
+    ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::ui::i18n::translate_with($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+/// Locale the UI is shown in
+pub fn locale() -> Locale {
+    *ACTIVE.read()
+}
+
+/// Switch the UI to `locale`; screens pick it up on the next frame
+pub fn set_locale(locale: Locale) {
+    *ACTIVE.write() = locale;
+    let missing = missing_keys(locale);
+    if !missing.is_empty() {
+        tracing::debug!(
+            locale = locale.code(),
+            missing = ?missing,
+            "{} UI strings are untranslated and fall back to English",
+            missing.len()
+        );
+    }
+}
+
+/// Keys of the English catalog that `locale` doesn't translate, sorted
+pub fn missing_keys(locale: Locale) -> Vec<&'static str> {
+    let catalog = &CATALOGS[&locale];
+    let mut missing: Vec<&'static str> = CATALOGS[&Locale::En]
+        .keys()
+        .filter(|key| !catalog.contains_key(*key))
+        .map(String::as_str)
+        .collect();
+    missing.sort_unstable();
+    missing
+}
+
+/// String for `key` in the active locale; see [`tr!`](crate::tr)
+pub fn translate(key: &'static str) -> &'static str {
+    translate_in(locale(), key)
+}
+
+/// String for `key` with its `{name}` placeholders filled in
+pub fn translate_with(key: &'static str, args: &[(&str, String)]) -> String {
+    fill(translate(key), args)
+}
+
+/// String for `key` in `locale`, falling back to English, then to the key
+pub fn translate_in(locale: Locale, key: &'static str) -> &'static str {
+    if let Some(text) = CATALOGS[&locale].get(key).or_else(|| CATALOGS[&Locale::En].get(key)) {
+        return text;
+    }
+    if REPORTED.lock().insert(key) {
+        tracing::debug!(key, "UI string missing from every locale");
+    }
+    key
+}
+
+/// Replace each `{name}` in `text` with its value
+fn fill(text: &str, args: &[(&str, String)]) -> String {
+    args.iter()
+        .fold(text.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+/// `value` with `decimals` fractional digits in the active locale
+pub fn format_decimal(value: f64, decimals: usize) -> String {
+    format_decimal_in(locale(), value, decimals)
+}
+
+/// Like [`format_decimal`], always with a sign
+pub fn format_signed(value: f64, decimals: usize) -> String {
+    localize(locale(), format!("{:+.*}", decimals, value))
+}
+
+/// Percent change with a sign and two decimals ("+1.25%")
+pub fn format_percent(value: f64) -> String {
+    format_percent_in(locale(), value)
+}
+
+/// [`format_decimal`] in `locale`
+pub fn format_decimal_in(locale: Locale, value: f64, decimals: usize) -> String {
+    localize(locale, format!("{:.*}", decimals, value))
+}
+
+/// [`format_percent`] in `locale`
+pub fn format_percent_in(locale: Locale, value: f64) -> String {
+    // -0.0 shows as +0.00%
+    let text = if value >= 0.0 { format!("+{:.2}%", value) } else { format!("{:.2}%", value) };
+    localize(locale, text)
+}
+
+/// Swap the decimal point of a formatted number for the locale's separator
+fn localize(locale: Locale, text: String) -> String {
+    match locale.decimal_separator() {
+        '.' => text,
+        separator => text.replacen('.', &separator.to_string(), 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Files migrated to `tr!`, to check every key they use exists
+    const MIGRATED: [&str; 6] = [
+        include_str!("../mod.rs"),
+        include_str!("../widgets/nav_bar.rs"),
+        include_str!("../screens/auth.rs"),
+        include_str!("../screens/wallet.rs"),
+        include_str!("../screens/settings.rs"),
+        include_str!("../screens/terminal.rs"),
+    ];
+
+    #[test]
+    fn test_every_locale_translates_every_key() {
+        for locale in Locale::ALL {
+            assert_eq!(missing_keys(locale), Vec::<&str>::new(), "{} catalog is incomplete", locale.code());
+        }
+    }
+
+    #[test]
+    fn test_every_key_used_is_in_the_english_catalog() {
+        let english = &CATALOGS[&Locale::En];
+        for source in MIGRATED {
+            for usage in source.split("tr!(\"").skip(1) {
+                let key = usage.split('"').next().unwrap();
+                assert!(english.contains_key(key), "{} is not in en.json", key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup_falls_back_and_fills_placeholders() {
+        assert_eq!(translate_in(Locale::Es, "nav.logout"), "Cerrar sesión");
+        assert_eq!(translate_in(Locale::Es, "no.such.key"), "no.such.key");
+        let text = fill(translate_in(Locale::En, "nav.messages_unread"), &[("count", "3".to_string())]);
+        assert_eq!(text, "Message [3]");
+    }
+
+    #[test]
+    fn test_numbers_use_the_locale_decimal_separator() {
+        assert_eq!(format_decimal_in(Locale::En, 1234.5, 4), "1234.5000");
+        assert_eq!(format_decimal_in(Locale::Es, 1234.5, 4), "1234,5000");
+        assert_eq!(format_percent_in(Locale::Es, 2.5), "+2,50%");
+        assert_eq!(format_percent_in(Locale::Es, -0.0), "+0,00%");
+        assert_eq!(format_percent_in(Locale::En, -1.257), "-1.26%");
+    }
+}
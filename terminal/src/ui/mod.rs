@@ -8,16 +8,21 @@ pub mod cube;
 pub mod debug_overlay;
 pub mod effects;
 pub mod fonts;
+pub mod i18n;
 pub mod screens;
 pub mod theme;
 pub mod widgets;
 
 use egui;
-use crate::app::{App, AppState, Screen};
+use crate::app::keybindings::{self, KeyAction};
+use crate::app::{usage, App, AppState, Screen, SwapField};
+use crate::tr;
 
 /// Main render function - called every frame by egui
 pub fn render(ctx: &egui::Context, app: &mut App, _notifications: &mut crate::ui::widgets::notifications::NotificationManager, cube: &mut crate::ui::cube::RotatingCube, _frame: &mut eframe::Frame) {
-    // Read state for rendering
+    // Read state for rendering; the large collections are shared with the
+    // live state rather than copied (see `crate::app::CowArc`)
+    let snapshot_started = std::time::Instant::now();
     let state = {
         match app.state.try_read() {
             Some(state_guard) => state_guard.clone(),
@@ -27,6 +32,26 @@ pub fn render(ctx: &egui::Context, app: &mut App, _notifications: &mut crate::ui
             }
         }
     }; // Lock released here - rendering happens without holding lock
+    crate::debug::metrics::record_state_snapshot(snapshot_started.elapsed(), state.shared_bytes());
+
+    // Command palette (Ctrl+K) goes first so its keys never reach the screen
+    if state.palette.open {
+        widgets::command_palette::render_command_palette(ctx, &state, app);
+    }
+
+    // Opened xforce:// link waiting for confirmation
+    if state.link_prompt.raise_window {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        app.state.write().link_prompt.raise_window = false;
+    }
+    widgets::link_prompt::render_link_prompt(ctx, &state, app);
+
+    // Simulated swap waiting to be signed
+    widgets::swap_confirmation::render_swap_confirmation(ctx, &state, app);
+
+    // Transfer from the Send panel waiting to be signed
+    widgets::transfer_confirmation::render_transfer_confirmation(ctx, &state, app);
 
     // Central panel - Main content area
     egui::CentralPanel::default().show(ctx, |ui| {
@@ -42,6 +67,8 @@ pub fn render(ctx: &egui::Context, app: &mut App, _notifications: &mut crate::ui
             screens::auth::render(ui, &state, app, cube);
             return;
         }
+        // Counted once per visit, and only if the user opted in
+        usage::capture(usage::screen_view(current_screen));
         
         // Render Bloomberg-style navigation bar (only when authenticated)
         if is_authenticated {
@@ -49,20 +76,46 @@ pub fn render(ctx: &egui::Context, app: &mut App, _notifications: &mut crate::ui
             ui.add_space(5.0);
             ui.separator();
             ui.add_space(5.0);
+            widgets::system_banner::render_system_notices(ui, &state, app);
         }
         
-        // Handle Tab key for screen navigation (excludes Messaging and Settings)
-        if ctx.input(|i| i.key_pressed(egui::Key::Tab) && !i.modifiers.shift) {
-            app.next_screen();
+        // A shortcut capture left behind by navigating away from Settings
+        // would keep every shortcut switched off
+        if state.settings.key_capture.action.is_some() && current_screen != Screen::Settings {
+            app.handle_key_capture(None);
         }
-        if ctx.input(|i| i.key_pressed(egui::Key::Tab) && i.modifiers.shift) {
-            app.previous_screen();
+
+        // Shortcuts from Settings > Shortcuts (new window and fullscreen are
+        // handled in main.rs)
+        for action in keybindings::pressed(ctx, &state.settings) {
+            match action {
+                // Screen navigation excludes Messaging and Settings; an open
+                // palette owns the keyboard
+                KeyAction::NextScreen if !state.search.open && !state.palette.open => app.next_screen(),
+                KeyAction::PrevScreen if !state.search.open && !state.palette.open => app.previous_screen(),
+                KeyAction::OpenCommandPalette => app.handle_command_palette_toggle(),
+                KeyAction::ToggleDebug => {
+                    let mut state_write = app.state.write();
+                    state_write.debug_overlay_visible = !state_write.debug_overlay_visible;
+                }
+                KeyAction::FocusSwapAmount if is_authenticated => {
+                    app.handle_screen_change(Screen::Terminal);
+                    let mut state_write = app.state.write();
+                    state_write.terminal.swap_panel_open = true;
+                    state_write.terminal.swap.focus_field = Some(SwapField::Amount);
+                }
+                _ => {}
+            }
+        }
+
+        // Handle Ctrl+F to open/close the search palette
+        if is_authenticated && ctx.input(|i| i.key_pressed(egui::Key::F) && i.modifiers.ctrl) {
+            app.handle_search_toggle();
         }
         
-        // Handle Ctrl+D to toggle debug overlay
-        if ctx.input(|i| i.key_pressed(egui::Key::D) && i.modifiers.ctrl) {
-            let mut state_write = app.state.write();
-            state_write.debug_overlay_visible = !state_write.debug_overlay_visible;
+        // Handle F5 (Cmd/Ctrl+R) to reload the current screen's data
+        if is_authenticated && refresh_pressed(ctx) {
+            app.refresh(current_screen);
         }
 
         match current_screen {
@@ -73,24 +126,25 @@ pub fn render(ctx: &egui::Context, app: &mut App, _notifications: &mut crate::ui
                 // Status bar at bottom
                 ui.add_space(10.0);
                 ui.separator();
-                render_status_bar(ui, &state);
+                render_status_bar(ui, &state, app);
             },
             Screen::PythFeed => {
                 screens::pyth_feed::render(ui, &state, app);
                 // Status bar at bottom
                 ui.add_space(10.0);
                 ui.separator();
-                render_status_bar(ui, &state);
+                render_status_bar(ui, &state, app);
             },
             Screen::JupiterFeed => {
                 screens::jupiter_feed::render(ui, &state, app);
                 // Status bar at bottom
                 ui.add_space(10.0);
                 ui.separator();
-                render_status_bar(ui, &state);
+                render_status_bar(ui, &state, app);
             },
             Screen::Wallet => screens::wallet::render(ui, &state, app),
-            Screen::Transactions => screens::transactions::render(ui, &state),
+            Screen::Portfolio => screens::portfolio::render(ui, &state, app),
+            Screen::Transactions => screens::transactions::render(ui, &state, app),
             Screen::Tokens => screens::tokens::render(ui, &state, app),
             Screen::Settings => screens::settings::render(ui, &state, app),
             Screen::Messaging => {
@@ -106,21 +160,21 @@ pub fn render(ctx: &egui::Context, app: &mut App, _notifications: &mut crate::ui
                 // Status bar at bottom
                 ui.add_space(10.0);
                 ui.separator();
-                render_status_bar(ui, &state);
+                render_status_bar(ui, &state, app);
             },
             Screen::LiveAssets => {
                 screens::live_assets::render(ui, &state, app);
                 // Status bar at bottom
                 ui.add_space(10.0);
                 ui.separator();
-                render_status_bar(ui, &state);
+                render_status_bar(ui, &state, app);
             },
             Screen::LiveTable => {
                 screens::live_table::render(ui, &state, app);
                 // Status bar at bottom
                 ui.add_space(10.0);
                 ui.separator();
-                render_status_bar(ui, &state);
+                render_status_bar(ui, &state, app);
             },
         }
     });
@@ -130,24 +184,64 @@ pub fn render(ctx: &egui::Context, app: &mut App, _notifications: &mut crate::ui
         widgets::token_picker::render_token_picker(ctx, &state, app);
     }
 
+    // Search palette (Ctrl+F)
+    if state.search.open && state.is_authenticated() {
+        widgets::search_palette::render_search_palette(ctx, &state, app);
+    }
+
     // Debug overlay (if enabled) - rendered as a window on top
     if debug_overlay::should_show_overlay(&state) {
         debug_overlay::render_debug_overlay(ctx, &state);
     }
 }
 
+/// Whether the refresh shortcut, F5 or Cmd/Ctrl+R, was pressed this frame
+pub fn refresh_pressed(ctx: &egui::Context) -> bool {
+    ctx.input(|i| i.key_pressed(egui::Key::F5) || (i.key_pressed(egui::Key::R) && i.modifiers.command))
+}
+
 /// Render status bar at the bottom (public version)
-pub fn render_status_bar(ui: &mut egui::Ui, state: &crate::app::AppState) {
-    render_status_bar_impl(ui, state);
+pub fn render_status_bar(ui: &mut egui::Ui, state: &crate::app::AppState, app: &mut impl crate::app::AppLike) {
+    render_status_bar_impl(ui, state, app);
 }
 
 /// Render status bar implementation
-fn render_status_bar_impl(ui: &mut egui::Ui, state: &crate::app::AppState) {
-    render_status_bar_bottom(ui, state);
+fn render_status_bar_impl(ui: &mut egui::Ui, state: &crate::app::AppState, app: &mut impl crate::app::AppLike) {
+    render_status_bar_bottom(ui, state, app);
+}
+
+/// Status bar color, label and tooltip for the last backend health poll
+fn backend_health_summary(
+    health: &crate::app::state::BackendHealthState,
+    theme: &crate::ui::theme::Theme,
+) -> (egui::Color32, &'static str, String) {
+    use shared::dto::system::HealthStatus;
+
+    if let Some(err) = &health.error {
+        return (theme.error, tr!("status.backend_unreachable"), err.clone());
+    }
+    let Some(report) = &health.report else {
+        return (theme.dim, tr!("status.checking"), tr!("status.waiting_for_health").to_string());
+    };
+
+    let (color, label) = match report.status {
+        HealthStatus::Ok => (theme.success, tr!("status.ready")),
+        HealthStatus::Degraded => (theme.warning, tr!("status.degraded")),
+        HealthStatus::Down => (theme.error, tr!("status.backend_down")),
+    };
+    let mut tooltip: Vec<String> = report
+        .degraded_subsystems()
+        .map(|s| format!("{}: {}", s.name, s.error.as_deref().unwrap_or(tr!("status.subsystem_degraded"))))
+        .collect();
+    if tooltip.is_empty() {
+        tooltip.push(tr!("status.all_healthy").to_string());
+    }
+    tooltip.push(tr!("status.uptime", hours = report.uptime_secs / 3600, minutes = report.uptime_secs % 3600 / 60));
+    (color, label, tooltip.join("\n"))
 }
 
 // Status bar implementation (keep this one, remove duplicate)
-fn render_status_bar_bottom(ui: &mut egui::Ui, state: &crate::app::AppState) {
+fn render_status_bar_bottom(ui: &mut egui::Ui, state: &crate::app::AppState, app: &mut impl crate::app::AppLike) {
     use crate::ui::widgets::icons::{Icons, material, size};
     use crate::ui::theme::Theme;
     
@@ -164,18 +258,54 @@ fn render_status_bar_bottom(ui: &mut egui::Ui, state: &crate::app::AppState) {
             };
             ui.colored_label(
                 theme.success,
-                format!("Wallet: {} ({:.4} SOL)", short_addr, wallet.sol_balance)
+                tr!("status.wallet", address = short_addr, balance = i18n::format_decimal(wallet.sol_balance, 4))
             );
         } else {
             ui.label(Icons::icon_dim(material::WALLET, size::SMALL));
-            ui.colored_label(theme.dim, "No Wallet");
+            ui.colored_label(theme.dim, tr!("status.no_wallet"));
         }
 
         ui.separator();
+
+        // No backend, stream or session to report on in the offline demo
+        if state.offline {
+            ui.label(Icons::icon_warning(material::INFO, size::SMALL));
+            ui.label(egui::RichText::new(tr!("status.offline_demo")).color(theme.warning).strong())
+                .on_hover_text(tr!("status.offline_hint"));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.colored_label(theme.dim, tr!("status.key_hints"));
+            });
+            return;
+        }
         
-        // Status indicator with red accent
-        ui.label(Icons::icon_red(material::INFO, size::SMALL));
-        ui.colored_label(theme.selected, "Ready");
+        // Backend health from the periodic /api/health poll; click for the per-dependency panel
+        let (health_color, health_label, health_tooltip) = backend_health_summary(&state.backend_health, &theme);
+        let panel_id = egui::Id::new("backend_health_panel_open");
+        let mut panel_open = ui.data(|d| d.get_temp::<bool>(panel_id)).unwrap_or(false);
+        ui.label(Icons::icon_color(material::INFO, size::SMALL, health_color))
+            .on_hover_text(&health_tooltip);
+        let health_response = ui
+            .add(egui::Label::new(egui::RichText::new(health_label).color(health_color)).sense(egui::Sense::click()))
+            .on_hover_text(format!("{}\n\n{}", health_tooltip, tr!("status.click_for_details")));
+        if health_response.clicked() {
+            panel_open = !panel_open;
+            if panel_open {
+                app.check_backend_health();
+            }
+        }
+        if panel_open {
+            egui::Window::new(tr!("status.backend_health"))
+                .id(egui::Id::new("backend_health_panel"))
+                .open(&mut panel_open)
+                .collapsible(false)
+                .resizable(false)
+                .pivot(egui::Align2::LEFT_BOTTOM)
+                .default_pos(health_response.rect.left_top())
+                .show(ui.ctx(), |ui| {
+                    widgets::health_panel::render_health_panel(ui, state, app, &theme);
+                });
+        }
+        ui.data_mut(|d| d.insert_temp(panel_id, panel_open));
 
         ui.separator();
 
@@ -186,22 +316,52 @@ fn render_status_bar_bottom(ui: &mut egui::Ui, state: &crate::app::AppState) {
                 ui.label(Icons::icon_success(material::NETWORK, size::SMALL));
                 let msg_count = state.websocket_status.messages_received;
                 if msg_count > 0 {
-                    ui.colored_label(theme.success, format!("WS: {} msgs", msg_count));
+                    ui.colored_label(theme.success, tr!("status.ws_messages", count = msg_count));
                 } else {
-                    ui.colored_label(theme.success, "WS Connected");
+                    ui.colored_label(theme.success, tr!("status.ws_connected"));
                 }
             }
-            WebSocketState::Connecting | WebSocketState::Reconnecting => {
+            WebSocketState::Connecting => {
+                ui.label(Icons::icon_dim(material::NETWORK, size::SMALL));
+                ui.colored_label(theme.warning, tr!("status.ws_connecting"));
+            }
+            WebSocketState::Reconnecting => {
                 ui.label(Icons::icon_dim(material::NETWORK, size::SMALL));
-                let attempts = state.websocket_status.connection_attempts;
-                ui.colored_label(
-                    theme.warning,
-                    format!("WS: Connecting... ({})", attempts)
-                );
+                let status = &state.websocket_status;
+                let text = match status.next_retry.map(|at| at.saturating_duration_since(std::time::Instant::now())) {
+                    Some(wait) if !wait.is_zero() => tr!("status.ws_reconnecting_in", seconds = i18n::format_decimal(wait.as_secs_f64(), 1)),
+                    _ => tr!("status.ws_reconnecting").to_string(),
+                };
+                let text = if status.connection_attempts > 0 {
+                    format!(
+                        "{} ({}/{})",
+                        text,
+                        status.connection_attempts,
+                        crate::services::api::websocket::MAX_CONNECTION_ATTEMPTS
+                    )
+                } else {
+                    text
+                };
+                let label = ui.colored_label(theme.warning, text);
+                if let Some(err) = &status.last_error {
+                    label.on_hover_text(err);
+                }
+                // Keep the countdown moving
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
             }
             WebSocketState::Disabled => {
                 ui.label(Icons::icon_dim(material::NETWORK, size::SMALL));
-                ui.colored_label(theme.error, "WS: Disabled");
+                let label = ui.colored_label(theme.error, tr!("status.ws_disabled"));
+                if let Some(err) = &state.websocket_status.last_error {
+                    label.on_hover_text(err);
+                }
+                if ui
+                    .small_button(tr!("status.reconnect"))
+                    .on_hover_text(tr!("status.reconnect_hint"))
+                    .clicked()
+                {
+                    app.handle_websocket_reconnect();
+                }
             }
             WebSocketState::Disconnected => {
                 ui.label(Icons::icon_dim(material::NETWORK, size::SMALL));
@@ -213,7 +373,7 @@ fn render_status_bar_bottom(ui: &mut egui::Ui, state: &crate::app::AppState) {
                     };
                     ui.colored_label(theme.error, format!("WS: {}", short_err));
                 } else {
-                    ui.colored_label(theme.dim, "WS Disconnected");
+                    ui.colored_label(theme.dim, tr!("status.ws_disconnected"));
                 }
             }
         }
@@ -222,15 +382,43 @@ fn render_status_bar_bottom(ui: &mut egui::Ui, state: &crate::app::AppState) {
 
         // API connection status with icon (moved to bottom)
         if state.auth_token.is_some() {
-            ui.label(Icons::icon_success(material::NETWORK, size::SMALL));
-            ui.colored_label(theme.success, "API Connected");
+            ui.label(Icons::icon_color(material::NETWORK, size::SMALL, health_color));
+            let label = if state.backend_health.error.is_some() { tr!("status.api_unreachable") } else { tr!("status.api_connected") };
+            ui.colored_label(health_color, label)
+                .on_hover_text(&health_tooltip);
         } else {
             ui.label(Icons::icon_dim(material::NETWORK, size::SMALL));
-            ui.colored_label(theme.dim, "API Disconnected");
+            ui.colored_label(theme.dim, tr!("status.api_disconnected"));
+        }
+
+        // Login session countdown once it gets close
+        if let Some(expires_at) = state.session_expires_at() {
+            use crate::services::session::{format_countdown, SessionStatus};
+            let now = chrono::Utc::now().timestamp();
+            match SessionStatus::at(expires_at, now) {
+                SessionStatus::Valid => {}
+                SessionStatus::ExpiringSoon => {
+                    ui.separator();
+                    ui.label(Icons::icon_warning(material::LOCK, size::SMALL));
+                    let hint = if state.session.refresh_error.is_some() {
+                        tr!("status.session_refresh_failed")
+                    } else {
+                        tr!("status.session_auto_extend")
+                    };
+                    ui.colored_label(theme.warning, tr!("status.session_expires_in", time = format_countdown(expires_at - now)))
+                        .on_hover_text(hint);
+                    ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+                }
+                SessionStatus::Expired => {
+                    ui.separator();
+                    ui.label(Icons::icon_error(material::LOCK, size::SMALL));
+                    ui.colored_label(theme.error, tr!("status.session_expired"));
+                }
+            }
         }
 
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-            ui.colored_label(theme.dim, "Q: Quit | Tab: Navigate | Enter: Select | Esc: Back");
+            ui.colored_label(theme.dim, tr!("status.key_hints"));
         });
     });
 }
@@ -6,8 +6,6 @@
 use egui;
 use crate::app::{AppState, AppLike};
 use crate::ui::theme::Theme;
-use std::sync::Arc;
-use parking_lot::RwLock;
 
 /// Render AI chat screen
 pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
@@ -16,106 +14,15 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
     // Clone app.state at the beginning to avoid borrow conflicts in closures
     let app_state = app.state().clone();
     
-    // Request immediate repaint if AI is typing (for smooth animation)
-    // Get context here but don't hold the borrow
-    if state.ai_chat.ai_typing {
-        let ctx = ui.ctx();
-        ctx.request_repaint();
+    // Repaint every frame while a reply is pending so the typing indicator
+    // animates and streamed text shows up as it arrives
+    if state.ai_chat.is_replying() {
+        ui.ctx().request_repaint();
     }
     
-    // Initialize conversation if not already done
-    if state.ai_chat.conversation_id.is_none() {
-        if let Some(current_user) = &state.current_user {
-            let mut state_write = app_state.write();
-            // Use conversation ID format: "0:{user_id}" where 0 is the AI bot user ID
-            // The format requires smaller ID first, so 0 comes before any positive user ID
-            let conversation_id = format!("0:{}", current_user.id);
-            state_write.ai_chat.conversation_id = Some(conversation_id.clone());
-            
-            // Start SSE subscription for AI conversation
-            let token_opt = state_write.auth_token.clone();
-            let conversation_id_for_sub = conversation_id.clone();
-            let state_for_task = app_state.clone();
-            
-            drop(state_write);
-            
-            if let Some(token) = token_opt {
-                let conversation_id_clone = conversation_id_for_sub.clone();
-                tokio::spawn(async move {
-                    // Subscribe to conversation updates
-                    let mut braid_client = crate::services::braid_client::BraidClient::new(
-                        conversation_id_for_sub,
-                        token,
-                    );
-                    
-                    match braid_client.subscribe().await {
-                        Ok(mut rx) => {
-                            {
-                                let mut state = state_for_task.write();
-                                state.ai_chat.subscribed = true;
-                            }
-                            tracing::info!(
-                                conversation_id = %conversation_id_clone,
-                                "SSE subscription established for AI chat - waiting for messages"
-                            );
-                            
-                            while let Some((messages, version)) = rx.recv().await {
-                                let message_count = messages.len();
-                                let ai_message_count = messages.iter()
-                                    .filter(|msg| {
-                                        let author_lower = msg.author.to_lowercase();
-                                        author_lower.contains("deepseek") 
-                                            || author_lower.contains("ai")
-                                            || author_lower.contains("assistant")
-                                            || msg.author == "DeepSeek AI"
-                                    })
-                                    .count();
-                                
-                                tracing::debug!(
-                                    conversation_id = %conversation_id_clone,
-                                    version = %version,
-                                    total_messages = message_count,
-                                    ai_messages = ai_message_count,
-                                    "SSE: Received message update - updating UI state"
-                                );
-                                
-                                let mut state = state_for_task.write();
-                                let old_count = state.ai_chat.messages.len();
-                                state.ai_chat.messages = messages.clone();
-                                state.ai_chat.ai_typing = false;
-                                drop(state);
-                                
-                                if message_count != old_count {
-                                    tracing::info!(
-                                        conversation_id = %conversation_id_clone,
-                                        old_count = old_count,
-                                        new_count = message_count,
-                                        ai_messages = ai_message_count,
-                                        "SSE: Message count changed - UI should update on next frame"
-                                    );
-                                }
-                                // UI will update on next frame
-                            }
-                            
-                            tracing::warn!(
-                                conversation_id = %conversation_id_clone,
-                                "SSE subscription ended - no more messages will be received"
-                            );
-                        }
-                        Err(e) => {
-                            tracing::error!(
-                                conversation_id = %conversation_id_clone,
-                                error = %e,
-                                "Failed to subscribe to AI conversation via SSE"
-                            );
-                            eprintln!("Failed to subscribe to AI conversation: {}", e);
-                            let mut state = state_for_task.write();
-                            state.ai_chat.subscribed = false;
-                        }
-                    }
-                });
-            }
-        }
+    // Subscribe to the AI conversation the first time the screen is shown
+    if state.ai_chat.conversation_id.is_none() && state.current_user.is_some() {
+        app.handle_ai_chat_connect();
     }
     
     use crate::ui::widgets::layouts;
@@ -187,9 +94,12 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
             
             // AI Response Box (top, read-only)
             ui.label("AI Response:");
+            let streaming_text = state.ai_chat.streaming.as_ref()
+                .filter(|reply| !reply.cancelled && !reply.text.is_empty())
+                .map(|reply| reply.text.clone());
             let ai_response_text = {
-                // Get the latest AI message
-                let latest_ai_message_text = state.ai_chat.messages.iter()
+                // The reply streaming in, otherwise the latest AI message
+                let latest_ai_message_text = streaming_text.clone().or_else(|| state.ai_chat.messages.iter()
                     .rev()
                     .find(|msg| {
                         let author_lower = msg.author.to_lowercase();
@@ -205,7 +115,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
                             "UI RENDER: Found latest AI message"
                         );
                         msg.text.clone()
-                    })
+                    }))
                     .unwrap_or_else(|| {
                         if state.ai_chat.messages.is_empty() {
                             tracing::debug!("UI RENDER: No messages yet - showing welcome message");
@@ -220,7 +130,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
                     });
                 
                 // Animated typing indicator
-                if state.ai_chat.ai_typing {
+                if state.ai_chat.is_replying() {
                     // Get current time for animation (get context inside closure)
                     let ctx = ui.ctx();
                     let current_time = ctx.input(|i| i.time);
@@ -234,7 +144,11 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
                         _ => "   ", // Empty for a brief moment before cycling
                     };
                     
-                    format!("{latest_ai_message_text}\n\n🤖 AI is thinking{}", typing_dots)
+                    if streaming_text.is_some() {
+                        format!("{latest_ai_message_text}{}", typing_dots.trim_end())
+                    } else {
+                        format!("{latest_ai_message_text}\n\n🤖 AI is thinking{}", typing_dots)
+                    }
                 } else {
                     latest_ai_message_text
                 }
@@ -271,8 +185,13 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
                         let message_text = state_write.ai_chat.message_input.clone();
                         drop(state_write);
                         
-                        let should_send = (response.lost_focus() && response.ctx.input(|i| i.key_pressed(egui::Key::Enter) && !i.modifiers.shift))
-                            || ui.button("Send").clicked();
+                        let mut should_send = response.lost_focus() && response.ctx.input(|i| i.key_pressed(egui::Key::Enter) && !i.modifiers.shift);
+                        ui.horizontal(|ui| {
+                            should_send |= ui.button("Send").clicked();
+                            if state.ai_chat.is_replying() && ui.button("Cancel").on_hover_text("Stop the AI reply").clicked() {
+                                app.handle_ai_chat_cancel();
+                            }
+                        });
                         
                         (message_text, should_send)
                     }
@@ -290,7 +209,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
             };
             
             if should_send && !message_text.trim().is_empty() {
-                send_message(app_state.clone(), message_text.clone());
+                app.handle_ai_chat_send(message_text.clone());
             }
             
             // Help text
@@ -299,98 +218,3 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
         });
     });
 }
-
-/// Send a message to the AI
-fn send_message(app_state: Arc<RwLock<AppState>>, text: String) {
-    // Use try_read to avoid blocking
-    let state_read = match app_state.try_read() {
-        Some(state) => state,
-        None => {
-            tracing::warn!("Failed to acquire read lock for sending message");
-            eprintln!("Failed to acquire read lock for sending message");
-            return;
-        }
-    };
-    
-    if let Some(conversation_id) = &state_read.ai_chat.conversation_id {
-        if let Some(token) = &state_read.auth_token {
-            // Get current user info
-            let author = state_read.current_user.as_ref()
-                .map(|u| u.username.clone())
-                .unwrap_or_else(|| "You".to_string());
-            let author_id = state_read.current_user.as_ref()
-                .map(|u| u.id)
-                .unwrap_or(0);
-            
-            let message = shared::dto::messaging::Message::new(text.clone(), author.clone(), author_id);
-            
-            tracing::info!(
-                conversation_id = %conversation_id,
-                author = %author,
-                author_id = author_id,
-                message_length = text.len(),
-                "Sending message to AI bot"
-            );
-            
-            let mut braid_client = crate::services::braid_client::BraidClient::new(
-                conversation_id.clone(),
-                token.clone(),
-            );
-            
-            let message_clone = message.clone();
-            let conversation_id_clone = conversation_id.clone();
-            let state_clone = app_state.clone();
-            
-            // Set typing indicator (use try_write to avoid blocking)
-            {
-                if let Some(mut state) = app_state.try_write() {
-                    state.ai_chat.ai_typing = true;
-                    state.ai_chat.message_input.clear();
-                    tracing::debug!("Set AI typing indicator to true");
-                }
-            }
-            
-            drop(state_read); // Release read lock before spawning async task
-            
-            tokio::spawn(async move {
-                tracing::debug!(
-                    conversation_id = %conversation_id_clone,
-                    "Calling braid_client.send_message()"
-                );
-                match braid_client.send_message(message_clone).await {
-                    Ok(_) => {
-                        tracing::info!(
-                            conversation_id = %conversation_id_clone,
-                            "Message sent successfully to AI bot - waiting for response via SSE"
-                        );
-                        // Message sent successfully
-                        // The SSE subscription will update the UI with the new message
-                        // and the AI response will come through the subscription
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            conversation_id = %conversation_id_clone,
-                            error = %e,
-                            "Failed to send message to AI bot"
-                        );
-                        eprintln!("Failed to send message to AI: {}", e);
-                        // Use try_write to avoid blocking
-                        if let Some(mut state) = state_clone.try_write() {
-                            state.ai_chat.ai_typing = false;
-                            state.pending_notifications.push((
-                                "error".to_string(),
-                                format!("Failed to send message: {}", e),
-                            ));
-                            tracing::debug!("Cleared AI typing indicator due to error");
-                        }
-                    }
-                }
-            });
-        } else {
-            tracing::warn!("Cannot send message - no auth token available");
-        }
-    } else {
-        tracing::warn!("Cannot send message - no conversation ID available");
-    }
-}
-
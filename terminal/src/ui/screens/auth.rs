@@ -1,10 +1,19 @@
 //! # Authentication Screen
 //!
 //! Login and signup forms using egui widgets.
+//!
+//! Tab and Shift+Tab move through each form in the order of its
+//! [`LoginField`]/[`SignupField`] variants, Enter in the last text field
+//! submits, and the focused field is kept in the form's `active_field` so it
+//! gets focus back when the screen is shown again.
 
 use egui;
-use crate::app::{AppState, AuthState, AppLike};
+use crate::app::{AppState, AuthState, AppLike, LoginField, SignupField};
+use crate::app::handlers::auth::{signup_form_errors, FIELD_CONFIRM_PASSWORD};
+use shared::validation::{field_messages, ValidationError, FIELD_EMAIL, FIELD_PASSWORD, FIELD_USERNAME};
+use crate::tr;
 use crate::ui::theme::Theme;
+use crate::ui::widgets::focus::{FocusChain, FocusUpdate};
 use crate::ui::widgets::{branding, forms};
 
 /// Signup form input values
@@ -24,7 +33,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, cube:
         // Left column - Branding
         columns[0].vertical_centered(|ui| {
             ui.add_space(100.0);
-            branding::render_branding_section(ui, "<Enter> to begin");
+            branding::render_branding_section(ui, &state.branding, tr!("auth.prompt"));
             branding::render_cube_section(ui, cube);
         });
 
@@ -38,16 +47,16 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, cube:
                     username,
                     password,
                     error,
-                    ..
-                } => render_login_form(ui, username, password, error.as_deref(), app, &theme),
+                    active_field,
+                } => render_login_form(ui, username, password, error.as_deref(), *active_field, app, &theme),
                 AuthState::Signup {
                     username,
                     email,
                     password,
                     confirm_password,
                     error,
-                    ..
-                } => render_signup_form(ui, &SignupFormInputs { username, email, password, confirm_password }, error.as_deref(), app, &theme),
+                    active_field,
+                } => render_signup_form(ui, &SignupFormInputs { username, email, password, confirm_password }, error.as_deref(), *active_field, app, &theme),
             }
         });
     });
@@ -62,25 +71,27 @@ fn render_login_form(
     username: &str,
     password: &str,
     error: Option<&str>,
+    active_field: LoginField,
     app: &mut impl AppLike,
     theme: &Theme,
 ) {
-    forms::render_form_heading(ui, "LOGIN", theme);
+    forms::render_form_heading(ui, tr!("auth.login_heading"), theme);
 
     // Create local mutable copies for text inputs
     let mut username_input = username.to_string();
     let mut password_input = password.to_string();
-    let mut submit = false;
+    let mut chain = FocusChain::new();
 
     // Username field
-    let _username_response = forms::render_text_input(
+    let username_response = forms::render_text_input(
         ui,
-        "Username:",
+        tr!("auth.username"),
         &mut username_input,
-        "Enter username",
+        tr!("auth.username_hint"),
         false,
         [250.0, 30.0],
     );
+    chain.text(LoginField::Username, &username_response);
 
     // Update state if changed
     {
@@ -95,17 +106,13 @@ fn render_login_form(
     // Password field
     let password_response = forms::render_text_input(
         ui,
-        "Password:",
+        tr!("auth.password"),
         &mut password_input,
-        "Enter password",
+        tr!("auth.password_hint"),
         true,
         [250.0, 30.0],
     );
-
-    // Check for Enter key press
-    if password_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-        submit = true;
-    }
+    chain.text(LoginField::Password, &password_response);
 
     // Update state if changed
     {
@@ -123,21 +130,33 @@ fn render_login_form(
     }
 
     // Actions with styled buttons - aligned with text input width (250.0)
-    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
-        ui.set_width(250.0);
-        if forms::render_button(ui, "Login", None, theme, Some(theme.selected), Some(egui::vec2(100.0, 35.0))).clicked() || submit {
-            app.handle_login_click(username_input.clone(), password_input.clone());
+    let (login_response, switch_response) = ui
+        .with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+            ui.set_width(250.0);
+            let login = forms::render_button(ui, tr!("auth.login"), None, theme, Some(theme.selected), Some(egui::vec2(100.0, 35.0)));
+            ui.add_space(10.0);
+            (login, ui.button(tr!("auth.switch_to_signup")))
+        })
+        .inner;
+    chain.widget(LoginField::Submit, &login_response);
+    chain.widget(LoginField::SwitchForm, &switch_response);
+
+    let update = finish_focus(ui, chain, active_field, theme);
+    if update.focused.is_some_and(|field| field != active_field) {
+        let mut state = app.state().write();
+        if let AuthState::Login { active_field, .. } = &mut state.auth {
+            *active_field = update.focused.unwrap_or(LoginField::Username);
         }
+    }
 
-        ui.add_space(10.0);
-
-        if ui.button("Switch to Signup").clicked() {
-            app.handle_switch_to_signup();
-        }
-    });
+    if login_response.clicked() || update.submit {
+        app.handle_login_click(username_input.clone(), password_input.clone());
+    } else if switch_response.clicked() {
+        app.handle_switch_to_signup();
+    }
 
     ui.add_space(10.0);
-    forms::render_hint(ui, "Press <Enter> to login", theme);
+    forms::render_hint(ui, tr!("auth.login_hint"), theme);
 }
 
 /// Render signup form
@@ -145,83 +164,87 @@ fn render_signup_form(
     ui: &mut egui::Ui,
     inputs: &SignupFormInputs,
     error: Option<&str>,
+    active_field: SignupField,
     app: &mut impl AppLike,
     theme: &Theme,
 ) {
-    forms::render_form_heading(ui, "SIGN UP", theme);
+    forms::render_form_heading(ui, tr!("auth.signup_heading"), theme);
 
     // Create local mutable copies for text inputs
     let mut username_input = inputs.username.to_string();
     let mut email_input = inputs.email.to_string();
     let mut password_input = inputs.password.to_string();
     let mut confirm_password_input = inputs.confirm_password.to_string();
-    let mut submit = false;
+    let mut chain = FocusChain::new();
+    let field_errors = signup_form_errors(inputs.username, inputs.email, inputs.password, inputs.confirm_password);
 
     // Username field
-    forms::render_text_input(
+    let username_response = forms::render_text_input(
         ui,
-        "Username:",
+        tr!("auth.username"),
         &mut username_input,
-        "Choose username",
+        tr!("auth.choose_username"),
         false,
         [250.0, 30.0],
     );
+    chain.text(SignupField::Username, &username_response);
     {
         let mut state = app.state().write();
         if let AuthState::Signup { username, .. } = &mut state.auth {
             *username = username_input.clone();
         }
     }
+    render_field_errors(ui, &field_errors, FIELD_USERNAME, inputs.username, theme);
     ui.add_space(10.0);
 
     // Email field
-    forms::render_text_input(
+    let email_response = forms::render_text_input(
         ui,
-        "Email:",
+        tr!("auth.email"),
         &mut email_input,
         "your@email.com",
         false,
         [250.0, 30.0],
     );
+    chain.text(SignupField::Email, &email_response);
     {
         let mut state = app.state().write();
         if let AuthState::Signup { email, .. } = &mut state.auth {
             *email = email_input.clone();
         }
     }
+    render_field_errors(ui, &field_errors, FIELD_EMAIL, inputs.email, theme);
     ui.add_space(10.0);
 
     // Password field
-    forms::render_text_input(
+    let password_response = forms::render_text_input(
         ui,
-        "Password:",
+        tr!("auth.password"),
         &mut password_input,
-        "Enter password",
+        tr!("auth.password_hint"),
         true,
         [250.0, 30.0],
     );
+    chain.text(SignupField::Password, &password_response);
     {
         let mut state = app.state().write();
         if let AuthState::Signup { password, .. } = &mut state.auth {
             *password = password_input.clone();
         }
     }
+    render_field_errors(ui, &field_errors, FIELD_PASSWORD, inputs.password, theme);
     ui.add_space(10.0);
 
     // Confirm password field
     let confirm_response = forms::render_text_input(
         ui,
-        "Confirm Password:",
+        tr!("auth.confirm_password"),
         &mut confirm_password_input,
-        "Confirm password",
+        tr!("auth.confirm_password_hint"),
         true,
         [250.0, 30.0],
     );
-
-    // Check for Enter key press
-    if confirm_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-        submit = true;
-    }
+    chain.text(SignupField::ConfirmPassword, &confirm_response);
 
     {
         let mut state = app.state().write();
@@ -229,6 +252,7 @@ fn render_signup_form(
             *confirm_password = confirm_password_input.clone();
         }
     }
+    render_field_errors(ui, &field_errors, FIELD_CONFIRM_PASSWORD, inputs.confirm_password, theme);
     ui.add_space(15.0);
 
     // Error message
@@ -237,19 +261,52 @@ fn render_signup_form(
     }
 
     // Actions with styled buttons - aligned with text input width (250.0)
-    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
-        ui.set_width(250.0);
-        if forms::render_button(ui, "Sign Up", None, theme, Some(theme.selected), Some(egui::vec2(100.0, 35.0))).clicked() || submit {
-            app.handle_signup_click(username_input.clone(), email_input.clone(), password_input.clone(), confirm_password_input.clone());
+    let (signup_response, switch_response) = ui
+        .with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+            ui.set_width(250.0);
+            let signup = forms::render_button(ui, tr!("auth.signup"), None, theme, Some(theme.selected), Some(egui::vec2(100.0, 35.0)));
+            ui.add_space(10.0);
+            (signup, ui.button(tr!("auth.switch_to_login")))
+        })
+        .inner;
+    chain.widget(SignupField::Submit, &signup_response);
+    chain.widget(SignupField::SwitchForm, &switch_response);
+
+    let update = finish_focus(ui, chain, active_field, theme);
+    if update.focused.is_some_and(|field| field != active_field) {
+        let mut state = app.state().write();
+        if let AuthState::Signup { active_field, .. } = &mut state.auth {
+            *active_field = update.focused.unwrap_or(SignupField::Username);
         }
+    }
 
-        ui.add_space(10.0);
-
-        if ui.button("Switch to Login").clicked() {
-            app.handle_switch_to_login();
-        }
-    });
+    if signup_response.clicked() || update.submit {
+        app.handle_signup_click(username_input.clone(), email_input.clone(), password_input.clone(), confirm_password_input.clone());
+    } else if switch_response.clicked() {
+        app.handle_switch_to_login();
+    }
 
     ui.add_space(10.0);
-    forms::render_hint(ui, "Press <Enter> to sign up", theme);
+    forms::render_hint(ui, tr!("auth.signup_hint"), theme);
+}
+
+/// Run the form's focus chain, then give focus back to `active_field` when
+/// nothing on screen has it
+fn finish_focus<F: Copy + PartialEq>(ui: &mut egui::Ui, chain: FocusChain<F>, active_field: F, theme: &Theme) -> FocusUpdate<F> {
+    let update = chain.finish(ui, theme);
+    // Enter in the last field drops its focus to submit; leave it dropped
+    if !update.submit && ui.memory(|mem| mem.focused().is_none()) {
+        chain.focus(active_field);
+    }
+    update
+}
+
+/// Messages for one signup field, once something has been typed into it
+fn render_field_errors(ui: &mut egui::Ui, errors: &[ValidationError], field: &str, value: &str, theme: &Theme) {
+    if value.is_empty() {
+        return;
+    }
+    for message in field_messages(errors, field) {
+        ui.label(egui::RichText::new(message).small().color(theme.error));
+    }
 }
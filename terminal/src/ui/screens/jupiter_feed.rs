@@ -40,7 +40,8 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, _app: &mut impl AppLike) {
     ui.add_space(10.0);
 
     // Filter prices by source
-    let jupiter_prices: Vec<_> = state.terminal.prices
+    let prices = state.terminal.prices.load();
+    let jupiter_prices: Vec<_> = prices
         .iter()
         .filter(|p| p.source.as_ref().map(|s| s == "jupiter").unwrap_or(false))
         .collect();
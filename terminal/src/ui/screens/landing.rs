@@ -7,13 +7,13 @@ use crate::app::{AppState, AppLike};
 use crate::ui::widgets::branding;
 
 /// Render landing screen (welcome/splash)
-pub fn render(ui: &mut egui::Ui, _state: &AppState, app: &mut impl AppLike, cube: &mut crate::ui::cube::RotatingCube) {
+pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, cube: &mut crate::ui::cube::RotatingCube) {
     // Split screen: Branding left, Empty right
     ui.columns(2, |columns| {
         // Left column - Branding
         columns[0].vertical_centered(|ui| {
             ui.add_space(100.0);
-            branding::render_branding_section(ui, "<Enter> to begin");
+            branding::render_branding_section(ui, &state.branding, "<Enter> to begin");
             branding::render_cube_section(ui, cube);
         });
 
@@ -1,34 +1,101 @@
 //! # Live Assets Screen
 //!
-//! Simple vertical list of assets with prices that updates in real-time.
+//! Simple vertical list of assets with prices that updates in real-time, and
+//! an Analytics tab with each asset's historical volatility and the
+//! correlation matrix of their daily returns (`GET /api/market/analytics`).
+//!
+//! Each row carries a mini chart of the asset's last 24 hourly closes and
+//! their volume; the candles for every row come from one
+//! `GET /api/market/candles/batch` request.
+//!
+//! The list sorts by any column header (clicking it again flips the
+//! direction); the sort is saved. While the pointer rests on a row the order
+//! is held, so streamed prices don't move rows under it.
+//!
+//! Clicking a row opens it on the Live Chart. Its right-click menu opens the
+//! token's detail view (hourly closes, stats and the token's upcoming
+//! unlocks). Resting the pointer on a row, or focusing it with the keyboard,
+//! prefetches that detail so it usually renders from cache; see
+//! [`crate::app::preload`].
 
 use egui;
-use crate::app::{AppState, AppLike};
+use crate::app::{AppState, AppLike, AssetSort, AssetSortColumn, FeatureGates, LiveAssetsTab, TokenUnlockState};
+use crate::app::handlers::live_assets::asset_row_order;
+use crate::app::handlers::unlocks::{format_unlock_amount, UNLOCK_FETCH_DAYS};
+use crate::app::preload::{TokenDetail, HOVER_INTENT_DELAY};
+use crate::ui::chart::format_volume;
 use crate::ui::theme::Theme;
 use crate::ui::widgets::icons::{Icons, material, size};
+use crate::ui::widgets::price_display;
+use egui_plot::{Line, Plot, PlotPoints};
+use shared::dto::market::MarketAnalyticsResponse;
+
+/// Analytics windows offered, in days
+const ANALYTICS_WINDOWS: [usize; 3] = [7, 30, 90];
+
+/// Width of one correlation matrix cell
+const CELL_WIDTH: f32 = 56.0;
+
+/// Size of a row's mini chart
+const SPARKLINE_SIZE: egui::Vec2 = egui::vec2(80.0, 18.0);
+
+/// Height of a price list row
+const ROW_HEIGHT: f32 = 20.0;
+
+/// Width of each sortable column of the price list
+fn column_width(column: AssetSortColumn) -> f32 {
+    match column {
+        AssetSortColumn::Symbol => 80.0,
+        AssetSortColumn::Price => 110.0,
+        AssetSortColumn::Change24h => 80.0,
+        AssetSortColumn::Volume24h => 80.0,
+    }
+}
 
 /// Render live assets list screen
-pub fn render(ui: &mut egui::Ui, state: &AppState, _app: &mut impl AppLike) {
+pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
     let theme = Theme::default();
+    let tab = state.live_assets.tab;
 
-    // Header with filter
+    // Header with tabs and filter
     ui.horizontal(|ui| {
         ui.label(Icons::icon_red(material::TOKEN, size::MEDIUM));
         ui.heading("Live Assets");
-        
-        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-            // Search/filter box
-            ui.add(egui::TextEdit::singleline(&mut String::new())
-                .hint_text("Filter by symbol...")
-                .desired_width(150.0));
-        });
+        ui.add_space(20.0);
+
+        for (label, value) in [("Prices", LiveAssetsTab::Prices), ("Analytics", LiveAssetsTab::Analytics)] {
+            if ui.selectable_label(tab == value, label).clicked() && tab != value {
+                app.state().write().live_assets.tab = value;
+            }
+        }
+
+        if tab == LiveAssetsTab::Prices {
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                // Search/filter box
+                ui.add(egui::TextEdit::singleline(&mut String::new())
+                    .hint_text("Filter by symbol...")
+                    .desired_width(150.0));
+            });
+        }
     });
     
     ui.separator();
     ui.add_space(10.0);
 
+    match tab {
+        LiveAssetsTab::Prices => match &state.live_assets.selected {
+            Some(symbol) => render_detail(ui, state, app, symbol, &theme),
+            None => render_prices(ui, state, app, &theme),
+        },
+        LiveAssetsTab::Analytics => render_analytics(ui, state, app, &theme),
+    }
+}
+
+/// Render the live price list
+fn render_prices(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
     // Check if prices are available
-    if state.terminal.prices.is_empty() {
+    let prices = state.terminal.prices.load();
+    if prices.is_empty() {
         ui.vertical_centered(|ui| {
             ui.add_space(100.0);
             ui.colored_label(theme.dim, "No assets available");
@@ -43,27 +110,76 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, _app: &mut impl AppLike) {
 
     // Check if data was recently updated
     let recently_updated = state.last_price_update_time.elapsed().as_millis() < 500;
-    
-    // Sort assets by symbol
-    let mut sorted_prices = state.terminal.prices.clone();
-    sorted_prices.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+
+    // Rows keep their identity (the symbol) while the sort keys move
+    let order = asset_row_order(state);
+    if order != state.live_assets.row_order {
+        app.state().write().live_assets.row_order = order.clone();
+    }
+
+    let gates = state.backend_health.gates();
+
+    // Throttled by the task, so asking every frame only fetches on changes
+    app.fetch_sparklines();
+
+    if let Some(column) = render_sort_header(ui, state.settings.asset_sort, theme) {
+        app.handle_asset_sort_click(column);
+    }
 
     // Render asset list with live updates
+    let mut hovered = None;
+    let mut focused = None;
+    let mut clicked = None;
+    let mut detail = None;
     egui::ScrollArea::vertical()
         .auto_shrink([false; 2])
         .show(ui, |ui| {
-            for price in &sorted_prices {
-                render_asset_row(ui, price, &theme, recently_updated);
+            for price in order.iter().filter_map(|symbol| prices.get(symbol)) {
+                let sparkline = state.live_assets.sparklines.get(&price.symbol).map(Vec::as_slice);
+                let volume = state.live_assets.volumes.get(&price.symbol).copied();
+                let row = render_asset_row(ui, price, sparkline, volume, &gates, theme, recently_updated);
+                if row.hovered() {
+                    hovered = Some(price.symbol.clone());
+                }
+                if row.gained_focus() {
+                    focused = Some(price.symbol.clone());
+                }
+                if row.clicked() {
+                    clicked = Some(price.symbol.clone());
+                }
+                row.context_menu(|ui| {
+                    if ui.button("Token details").clicked() {
+                        detail = Some(price.symbol.clone());
+                        ui.close();
+                    }
+                });
                 ui.add_space(2.0);
             }
         });
+
+    // Hover intent is judged by time, so keep frames coming while a row is hovered
+    if hovered.is_some() {
+        ui.ctx().request_repaint_after(HOVER_INTENT_DELAY);
+    }
+    if hovered.is_some() || state.live_assets.hover_intent.hovered().is_some() {
+        app.handle_asset_hover(hovered);
+    }
+    if let Some(symbol) = focused {
+        app.handle_asset_focus(symbol);
+    }
+    if let Some(symbol) = clicked {
+        app.handle_asset_chart_open(symbol);
+    }
+    if let Some(symbol) = detail {
+        app.handle_token_detail_open(symbol);
+    }
     
     ui.add_space(10.0);
     ui.separator();
     
     // Footer with stats
     ui.horizontal(|ui| {
-        ui.label(format!("Total Assets: {}", state.terminal.prices.len()));
+        ui.label(format!("Total Assets: {}", prices.len()));
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             if recently_updated {
                 let pulse = (std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() / 500) % 2;
@@ -81,30 +197,342 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, _app: &mut impl AppLike) {
     });
 }
 
-/// Render a single asset row
-fn render_asset_row(ui: &mut egui::Ui, price: &crate::app::PriceData, theme: &Theme, recently_updated: bool) {
+/// Column headers of the price list; returns the one clicked
+fn render_sort_header(ui: &mut egui::Ui, sort: AssetSort, theme: &Theme) -> Option<AssetSortColumn> {
+    let mut clicked = None;
     ui.horizontal(|ui| {
-        // Symbol
-        ui.label(format!("{}", price.symbol));
-        
+        for column in AssetSortColumn::ALL {
+            let text = if column == sort.column {
+                let arrow = if sort.descending { "▼" } else { "▲" };
+                egui::RichText::new(format!("{} {}", column.label(), arrow)).color(theme.selected)
+            } else {
+                egui::RichText::new(column.label()).color(theme.dim)
+            };
+            let header = cell(ui, column_width(column), |ui| ui.add(egui::Label::new(text).sense(egui::Sense::click())));
+            if header.on_hover_cursor(egui::CursorIcon::PointingHand).clicked() {
+                clicked = Some(column);
+            }
+        }
+        ui.colored_label(theme.dim, "24h chart");
+    });
+    ui.separator();
+    clicked
+}
+
+/// Lay out `add` in a fixed-width cell so the columns line up
+fn cell<R>(ui: &mut egui::Ui, width: f32, add: impl FnOnce(&mut egui::Ui) -> R) -> R {
+    let layout = egui::Layout::left_to_right(egui::Align::Center);
+    ui.allocate_ui_with_layout(egui::vec2(width, ROW_HEIGHT), layout, |ui| {
+        ui.set_min_width(width);
+        add(ui)
+    })
+    .inner
+}
+
+/// Render a single asset row; the response is for the whole row
+fn render_asset_row(
+    ui: &mut egui::Ui,
+    price: &crate::app::PriceData,
+    sparkline: Option<&[f64]>,
+    volume: Option<f64>,
+    gates: &FeatureGates,
+    theme: &Theme,
+    recently_updated: bool,
+) -> egui::Response {
+    let row = ui.horizontal(|ui| {
+        cell(ui, column_width(AssetSortColumn::Symbol), |ui| ui.label(&price.symbol));
+
         // Price with flash effect if recently updated
         let price_color = if recently_updated {
             theme.selected
         } else {
             theme.normal
         };
-        ui.colored_label(price_color, format!("${:.4}", price.price));
-        
+        cell(ui, column_width(AssetSortColumn::Price), |ui| {
+            ui.colored_label(price_color, format!("${:.4}", price.price));
+            if price.divergent {
+                ui.label(Icons::icon_color(material::WARNING, size::SMALL, theme.warning))
+                    .on_hover_text(price.sources_tooltip());
+            }
+        });
+
+        let (change_text, change_color) = theme.format_price_change(price.change_24h);
+        cell(ui, column_width(AssetSortColumn::Change24h), |ui| ui.colored_label(change_color, change_text));
+
+        cell(ui, column_width(AssetSortColumn::Volume24h), |ui| match volume {
+            Some(volume) => ui.monospace(format_volume(volume)),
+            None => ui.colored_label(theme.dim, "-"),
+        });
+
+        match sparkline {
+            Some(closes) => render_sparkline(ui, closes, theme),
+            None => {
+                ui.allocate_exact_size(SPARKLINE_SIZE, egui::Sense::hover());
+            }
+        }
+
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-            // Change 24h
+            // Source indicator
+            price_display::render_source_badge(ui, price, gates, theme);
+        });
+    });
+
+    let id = ui.id().with(("asset_row", &price.symbol));
+    let response = ui.interact(row.response.rect, id, egui::Sense::click());
+    if response.hovered() || response.has_focus() {
+        ui.painter().rect_stroke(
+            row.response.rect.expand(1.0),
+            2.0,
+            egui::Stroke::new(1.0, theme.dim),
+            egui::StrokeKind::Outside,
+        );
+    }
+    response.on_hover_cursor(egui::CursorIcon::PointingHand)
+}
+
+/// Mini chart of a row's closes, colored by their direction
+fn render_sparkline(ui: &mut egui::Ui, closes: &[f64], theme: &Theme) {
+    let (rect, _) = ui.allocate_exact_size(SPARKLINE_SIZE, egui::Sense::hover());
+    let (Some(first), Some(last)) = (closes.first(), closes.last()) else {
+        return;
+    };
+    if closes.len() < 2 {
+        return;
+    }
+
+    let (low, high) = closes.iter().fold((f64::MAX, f64::MIN), |(lo, hi), c| (lo.min(*c), hi.max(*c)));
+    let range = (high - low).max(f64::EPSILON);
+    let step = rect.width() / (closes.len() - 1) as f32;
+    let points = closes
+        .iter()
+        .enumerate()
+        .map(|(i, close)| {
+            let y = ((close - low) / range) as f32;
+            egui::pos2(rect.left() + i as f32 * step, rect.bottom() - y * rect.height())
+        })
+        .collect();
+    let color = if last >= first { theme.price_up } else { theme.price_down };
+    ui.painter().add(egui::Shape::line(points, egui::Stroke::new(1.0, color)));
+}
+
+/// Render the detail view of one token, from the cache when it has it
+fn render_detail(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, symbol: &str, theme: &Theme) {
+    let live_assets = &state.live_assets;
+    let price = state.terminal.prices.load().iter().find(|p| p.symbol == symbol).cloned();
+
+    ui.horizontal(|ui| {
+        if ui.button("← Back").clicked() {
+            app.handle_token_detail_close();
+        }
+        ui.heading(symbol);
+        if let Some(price) = &price {
+            ui.label(format!("${:.4}", price.price));
             let (change_text, change_color) = theme.format_price_change(price.change_24h);
             ui.colored_label(change_color, change_text);
-            
-            // Source indicator
-            if let Some(source) = &price.source {
-                ui.colored_label(theme.dim, format!("[{}]", source));
+        }
+        if live_assets.detail_loading {
+            ui.spinner();
+        }
+    });
+    ui.add_space(10.0);
+
+    // A stale entry is still worth showing while the refresh loads
+    let Some(detail) = live_assets.details.get(symbol) else {
+        if let Some(err) = &live_assets.detail_error {
+            ui.colored_label(theme.error, format!("Failed to load {}: {}", symbol, err));
+        } else {
+            ui.colored_label(theme.dim, "Loading...");
+        }
+        return;
+    };
+    if let Some(err) = &live_assets.detail_error {
+        ui.colored_label(theme.error, format!("Refresh failed: {}", err));
+    }
+    render_detail_stats(ui, detail, theme);
+    ui.add_space(10.0);
+
+    let points: Vec<[f64; 2]> = detail.candles.iter().map(|c| [c.timestamp as f64, c.close]).collect();
+    let color = match detail.stats {
+        Some(stats) if stats.change_pct < 0.0 => theme.error,
+        _ => theme.success,
+    };
+    Plot::new(("token_detail", symbol))
+        .height(220.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .show_x(false)
+        .show_axes([false, true])
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new(symbol, PlotPoints::from(points)).color(color).width(2.0));
+        });
+    ui.add_space(10.0);
+    render_unlock_timeline(ui, &state.token_unlocks, symbol, theme);
+}
+
+/// Scheduled unlocks of `symbol`, soonest first; approaching ones in the warning color
+fn render_unlock_timeline(ui: &mut egui::Ui, unlocks: &TokenUnlockState, symbol: &str, theme: &Theme) {
+    let now = chrono::Utc::now().timestamp();
+    ui.strong("Upcoming unlocks");
+    let upcoming: Vec<_> = unlocks.for_symbol(symbol).filter(|u| u.unlock_at > now).collect();
+    if upcoming.is_empty() {
+        let text = match &unlocks.error {
+            Some(err) => format!("Unlock schedule unavailable: {}", err),
+            None => format!("No scheduled unlocks in the next {} days", UNLOCK_FETCH_DAYS),
+        };
+        ui.colored_label(theme.dim, text);
+        return;
+    }
+
+    egui::Grid::new(("token_unlocks", symbol))
+        .spacing([16.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for header in ["Date", "In", "Amount", "Circulating", "Allocation"] {
+                ui.colored_label(theme.dim, header);
+            }
+            ui.end_row();
+
+            for unlock in upcoming {
+                let color = if unlock.is_approaching(now) { theme.warning } else { theme.text };
+                let date = chrono::DateTime::from_timestamp(unlock.unlock_at, 0)
+                    .map(|t| t.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                ui.colored_label(color, date);
+                ui.colored_label(color, format!("{}d", unlock.days_until(now)));
+                ui.monospace(format_unlock_amount(unlock.amount));
+                match unlock.pct_of_circulating {
+                    Some(pct) => ui.monospace(format!("{:.2}%", pct)),
+                    None => ui.colored_label(theme.dim, "-"),
+                };
+                ui.label(unlock.allocation.as_deref().unwrap_or("-"));
+                ui.end_row();
             }
         });
+}
+
+fn render_detail_stats(ui: &mut egui::Ui, detail: &TokenDetail, theme: &Theme) {
+    let Some(stats) = detail.stats else {
+        ui.colored_label(theme.dim, "No candles for this token");
+        return;
+    };
+    let (change_text, change_color) = theme.format_price_change(stats.change_pct);
+    ui.horizontal(|ui| {
+        ui.colored_label(theme.dim, format!("{} candles ({}):", detail.candles.len(), detail.timeframe.label()));
+        ui.colored_label(change_color, change_text);
+        ui.colored_label(theme.dim, "High");
+        ui.label(format!("${:.4}", stats.high));
+        ui.colored_label(theme.dim, "Low");
+        ui.label(format!("${:.4}", stats.low));
+        ui.colored_label(theme.dim, "Volume");
+        ui.label(format!("{:.0}", stats.volume));
     });
 }
 
+/// Render the volatility column and correlation matrix
+fn render_analytics(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    let live_assets = &state.live_assets;
+
+    ui.horizontal(|ui| {
+        ui.label("Window:");
+        for days in ANALYTICS_WINDOWS {
+            if ui.selectable_label(live_assets.window_days == days, format!("{}d", days)).clicked() {
+                app.state().write().live_assets.window_days = days;
+            }
+        }
+        if live_assets.analytics_loading {
+            ui.spinner();
+        }
+        if let Some(err) = &live_assets.analytics_error {
+            ui.colored_label(theme.error, err);
+        }
+    });
+    ui.add_space(10.0);
+
+    // Throttled by the task, so asking every frame only fetches on changes
+    app.fetch_market_analytics();
+
+    match &live_assets.analytics {
+        Some(analytics) if analytics.window_days == live_assets.window_days => {
+            egui::ScrollArea::both()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| render_analytics_grid(ui, analytics, theme));
+        }
+        _ if live_assets.analytics_loading => {
+            ui.colored_label(theme.dim, "Computing volatility and correlations...");
+        }
+        _ if state.terminal.prices.load().is_empty() => {
+            ui.colored_label(theme.dim, "Analytics appear once live prices are streaming");
+        }
+        _ => {}
+    }
+}
+
+/// Volatility per symbol followed by one heat-colored column per symbol
+fn render_analytics_grid(ui: &mut egui::Ui, analytics: &MarketAnalyticsResponse, theme: &Theme) {
+    let window = analytics.window_days;
+    egui::Grid::new("live_assets_analytics")
+        .spacing([4.0, 4.0])
+        .show(ui, |ui| {
+            ui.colored_label(theme.dim, "Asset");
+            ui.colored_label(theme.dim, "Volatility").on_hover_text(format!(
+                "Annualized volatility: standard deviation of the last {} daily log returns, \
+                 scaled by √365. Assets with fewer than {} daily closes are not computed.",
+                window,
+                window + 1
+            ));
+            for column in &analytics.symbols {
+                ui.colored_label(theme.dim, &column.symbol).on_hover_text(format!(
+                    "Pearson correlation of daily log returns with {} over the last {} days \
+                     (+1 move together, -1 move opposite)",
+                    column.symbol, window
+                ));
+            }
+            ui.end_row();
+
+            for (row, asset) in analytics.symbols.iter().enumerate() {
+                ui.label(&asset.symbol);
+                match asset.volatility {
+                    Some(volatility) => {
+                        ui.monospace(format!("{:.1}%", volatility * 100.0));
+                    }
+                    None => {
+                        ui.colored_label(theme.warning, "n/a").on_hover_text(format!(
+                            "Insufficient history: {} of {} daily returns",
+                            asset.observations, window
+                        ));
+                    }
+                }
+                for (column, other) in analytics.symbols.iter().enumerate() {
+                    let value = analytics.correlations.get(row).and_then(|r| r.get(column)).copied().flatten();
+                    render_correlation_cell(ui, value, theme).on_hover_text(match value {
+                        Some(v) => format!("{} / {}: {:.2} over {} days", asset.symbol, other.symbol, v, window),
+                        None => format!("{} / {}: not enough shared history", asset.symbol, other.symbol),
+                    });
+                }
+                ui.end_row();
+            }
+        });
+}
+
+/// One matrix cell, shaded by the strength and sign of the correlation
+fn render_correlation_cell(ui: &mut egui::Ui, value: Option<f64>, theme: &Theme) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(CELL_WIDTH, 22.0), egui::Sense::hover());
+    let painter = ui.painter();
+    let text = match value {
+        Some(v) => {
+            let base = if v >= 0.0 { theme.colors.red_primary } else { theme.info };
+            painter.rect_filled(rect, 2.0, base.gamma_multiply(v.abs() as f32));
+            format!("{:+.2}", v)
+        }
+        None => "-".to_string(),
+    };
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        text,
+        egui::FontId::monospace(12.0),
+        if value.is_some() { theme.normal } else { theme.dim },
+    );
+    response
+}
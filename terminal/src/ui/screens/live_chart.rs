@@ -1,17 +1,37 @@
 //! # Live Chart Screen
 //!
 //! Real-time candlestick chart that updates on price changes with live price overlay.
+//!
+//! Levels and trendlines drawn from the toolbar can raise an alert when the
+//! price touches them; right-click a drawing to turn it on.
 
 use egui;
-use crate::app::{AppState, AppLike};
+use crate::app::commands::{self, CommandAction, CommandRegistry, CommandSpec};
+use crate::app::{AppState, AppLike, Screen};
 use crate::ui::theme::Theme;
 use crate::ui::widgets::icons::{Icons, material, size};
+use crate::ui::widgets::share_menu::{self, ShareTarget};
 use crate::ui::chart;
 use shared::dto::market::Timeframe;
 
+/// Command palette commands for this screen
+pub fn register_commands(registry: &mut CommandRegistry) {
+    registry.register(
+        CommandSpec::with_args("set timeframe", "<timeframe>", "Change the chart timeframe", commands::parse_timeframe)
+            .alias("timeframe")
+            .on_screen(Screen::LiveChart),
+    );
+    registry.register(
+        CommandSpec::action("share chart", "Publish a read-only link to this chart", CommandAction::ShareChart)
+            .on_screen(Screen::LiveChart)
+            .requires_auth(),
+    );
+}
+
 /// Render live chart screen with real-time updates
 pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
     let theme = Theme::default();
+    let symbol = state.terminal.chart_symbol.as_str();
 
     // Header with symbol selector and timeframe controls
     ui.horizontal(|ui| {
@@ -42,15 +62,31 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
                     state_write.terminal.chart_timeframe = *tf;
                     state_write.terminal.chart_loading = true;
                     drop(state_write);
-                    app.fetch_candles("SOL", *tf);
+                    app.fetch_candles(symbol, *tf);
                 }
             }
             
+            ui.add_space(10.0);
+
+            render_indicator_menu(ui, state, app);
+
+            ui.add_space(10.0);
+
+            if let Some(action) = chart::render_drawing_toolbar(ui, state.terminal.chart_tool) {
+                app.handle_chart_action(action);
+            }
+
+            ui.add_space(10.0);
+
+            share_menu::render(ui, state, app, ShareTarget::Chart);
+
             ui.add_space(10.0);
             
-            // Symbol selector (default to SOL for now, can be expanded)
+            // Pick another symbol from its Live Assets row
+            if ui.button(symbol).on_hover_text("Pick another asset on Live Assets").clicked() {
+                app.handle_screen_change(Screen::LiveAssets);
+            }
             ui.label("Symbol:");
-            ui.selectable_label(true, "SOL"); // TODO: Make this selectable
         });
     });
     
@@ -58,9 +94,9 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
     ui.add_space(5.0);
 
     // Get current symbol's price for overlay
-    let current_price = state.terminal.prices
-        .iter()
-        .find(|p| p.symbol == "SOL")
+    let prices = state.terminal.prices.load();
+    let current_price = prices
+        .get(symbol)
         .map(|p| p.price)
         .unwrap_or(0.0);
     
@@ -82,9 +118,9 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
         ui.colored_label(price_color, format!("${:.4}", current_price));
         
         // Show change from previous price
-        if let Some(sol_price) = state.terminal.prices.iter().find(|p| p.symbol == "SOL") {
-            if let Some(prev_price) = sol_price.previous_price {
-                let change = sol_price.price - prev_price;
+        if let Some(price) = prices.get(symbol) {
+            if let Some(prev_price) = price.previous_price {
+                let change = price.price - prev_price;
                 let change_percent = (change / prev_price) * 100.0;
                 let change_color = if change >= 0.0 {
                     theme.success
@@ -122,6 +158,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
     if state.terminal.chart_loading && state.terminal.sol_candles.is_empty() {
         ui.vertical_centered(|ui| {
             ui.add_space(100.0);
+            ui.spinner();
             ui.colored_label(theme.dim, "Loading chart data...");
         });
     } else if state.terminal.sol_candles.is_empty() {
@@ -135,8 +172,25 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
             }
         });
     } else {
-        // Render candlestick chart - use existing chart rendering function
-        chart::render_candlestick_chart(ui, &state.terminal.sol_candles, &theme);
+        // Candlesticks with volume panel below
+        let drawings = chart::Drawings {
+            symbol,
+            annotations: &state.terminal.chart_annotations,
+            tool: state.terminal.chart_tool,
+        };
+        if let Some(action) = chart::render_candlestick_chart(
+            ui,
+            &state.terminal.sol_candles,
+            state.terminal.chart_data_version,
+            state.terminal.chart_timeframe,
+            state.terminal.chart_loading,
+            state.terminal.sol_candle_forming,
+            Some(&state.terminal.chart_indicators),
+            Some(drawings),
+            &theme,
+        ) {
+            app.handle_chart_action(action);
+        }
         
         // Show current price info with live update indicator
         if let Some(last_candle) = state.terminal.sol_candles.last() {
@@ -157,30 +211,26 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
             });
         }
     }
-    
-    ui.add_space(10.0);
-    
-    // Volume bars at bottom (if we have volume data)
-    if !state.terminal.sol_candles.is_empty() {
-        ui.label("Volume:");
-        ui.horizontal(|ui| {
-            let max_volume = state.terminal.sol_candles
-                .iter()
-                .map(|c| c.volume)
-                .fold(0.0, f64::max);
-            
-            let available_width = ui.available_width();
-            let bar_width = (available_width / state.terminal.sol_candles.len() as f32).max(2.0);
-            
-            for (i, candle) in state.terminal.sol_candles.iter().enumerate() {
-                let bar_height = ((candle.volume / max_volume * 100.0) as f32).max(5.0);
-                let rect = egui::Rect::from_min_size(
-                    egui::pos2(i as f32 * bar_width, 0.0),
-                    egui::vec2(bar_width - 1.0, bar_height),
-                );
-                ui.painter().rect_filled(rect, 0.0, theme.dim);
-            }
-        });
-    }
 }
 
+/// Indicator toggles and periods (applied and saved on change)
+fn render_indicator_menu(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
+    let mut config = state.settings.indicators;
+
+    ui.menu_button("Indicators", |ui| {
+        for (label, enabled, period, range) in [
+            ("SMA", &mut config.sma_enabled, &mut config.sma_period, 2..=200),
+            ("EMA", &mut config.ema_enabled, &mut config.ema_period, 2..=200),
+            ("RSI", &mut config.rsi_enabled, &mut config.rsi_period, 2..=50),
+        ] {
+            ui.horizontal(|ui| {
+                ui.checkbox(enabled, label);
+                ui.add(egui::DragValue::new(period).range(range).prefix("period "));
+            });
+        }
+    });
+
+    if config != state.settings.indicators {
+        app.handle_indicator_config_change(config);
+    }
+}
@@ -89,7 +89,8 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, _app: &mut impl AppLike) {
     ui.add_space(10.0);
 
     // Check if prices are available
-    if state.terminal.prices.is_empty() {
+    let prices = state.terminal.prices.load();
+    if prices.is_empty() {
         ui.vertical_centered(|ui| {
             ui.add_space(100.0);
             ui.colored_label(theme.dim, "No data available");
@@ -107,7 +108,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, _app: &mut impl AppLike) {
     }
 
     // Filter and sort prices
-    let mut filtered_prices: Vec<_> = state.terminal.prices
+    let mut filtered_prices: Vec<_> = prices
         .iter()
         .filter(|p| {
             table_state.filter_symbol.is_empty() 
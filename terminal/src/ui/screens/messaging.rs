@@ -2,10 +2,20 @@
 //!
 //! Messaging interface with friends list, friend requests, and direct messaging.
 //! Implements a Bloomberg Terminal-style messaging interface.
+//!
+//! Files are attached with the paperclip button: they're uploaded right away
+//! and sent with the next message. Images received show inline; other files
+//! get a download button.
 
 use egui;
-use crate::app::{AppState, AppLike};
+use crate::app::search::SearchTarget;
+use crate::app::{AppState, AppLike, MessagingState};
 use crate::ui::theme::Theme;
+use crate::ui::widgets::search_palette;
+use crate::ui::widgets::attachment_preview::{self, AttachmentPreview, MAX_PREVIEW_DIMENSION, MAX_UPLOAD_BYTES};
+use crate::ui::widgets::icons::{material, size, Icons};
+use crate::services::braid_client::BraidEvent;
+use shared::dto::messaging::{Attachment, ReceiptStatus};
 use chrono::DateTime;
 use std::sync::Arc;
 use parking_lot::RwLock;
@@ -16,26 +26,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
     
     // Load friends list if not loaded yet
     if state.messaging.friends.is_empty() && state.messaging.incoming_requests.is_empty() && state.messaging.outgoing_requests.is_empty() {
-        if let Some(api_client) = &state.api_client {
-            if let Some(token) = &state.auth_token {
-                let client = api_client.clone();
-                let token = token.clone();
-                let state_clone = app.state().clone();
-                tokio::spawn(async move {
-                    match client.get_friends(&token).await {
-                        Ok(friends_list) => {
-                            let mut state = state_clone.write();
-                            state.messaging.friends = friends_list.friends;
-                            state.messaging.incoming_requests = friends_list.incoming_requests;
-                            state.messaging.outgoing_requests = friends_list.outgoing_requests;
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to load friends: {}", e);
-                        }
-                    }
-                });
-            }
-        }
+        app.load_friends();
     }
     
     // Main layout: Friends list (30%) | Chat panel (70%)
@@ -137,9 +128,8 @@ fn render_friends_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppL
                                             // Refresh friends list
                                             if let Ok(friends_list) = client.get_friends(&token).await {
                                                 let mut state = state_clone.write();
-                                                state.messaging.friends = friends_list.friends;
-                                                state.messaging.incoming_requests = friends_list.incoming_requests;
-                                                state.messaging.outgoing_requests = friends_list.outgoing_requests;
+                                                let current_user_id = state.current_user.as_ref().map(|u| u.id);
+                                                state.messaging.apply_friends_list(friends_list, current_user_id);
                                             }
                                         }
                                         Err(e) => {
@@ -174,9 +164,8 @@ fn render_friends_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppL
                                         // Refresh friends list
                                         if let Ok(friends_list) = client.get_friends(&token).await {
                                             let mut state = state_clone.write();
-                                            state.messaging.friends = friends_list.friends;
-                                            state.messaging.incoming_requests = friends_list.incoming_requests;
-                                            state.messaging.outgoing_requests = friends_list.outgoing_requests;
+                                            let current_user_id = state.current_user.as_ref().map(|u| u.id);
+                                            state.messaging.apply_friends_list(friends_list, current_user_id);
                                         }
                                     }
                                 });
@@ -195,9 +184,8 @@ fn render_friends_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppL
                                         // Refresh friends list
                                         if let Ok(friends_list) = client.get_friends(&token).await {
                                             let mut state = state_clone.write();
-                                            state.messaging.friends = friends_list.friends;
-                                            state.messaging.incoming_requests = friends_list.incoming_requests;
-                                            state.messaging.outgoing_requests = friends_list.outgoing_requests;
+                                            let current_user_id = state.current_user.as_ref().map(|u| u.id);
+                                            state.messaging.apply_friends_list(friends_list, current_user_id);
                                         }
                                     }
                                 });
@@ -220,27 +208,23 @@ fn render_friends_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppL
             egui::ScrollArea::vertical()
                 .max_height(400.0)
                 .show(ui, |ui| {
+                    let current_user_id = state.current_user.as_ref().map(|u| u.id);
                     for friend in &state.messaging.friends {
                         let is_selected = state.messaging.selected_user_id == Some(friend.user_id);
+                        let unread = current_user_id
+                            .and_then(|me| state.messaging.unread_counts.get(&MessagingState::conversation_id(me, friend.user_id)))
+                            .copied()
+                            .unwrap_or(0);
                         
+                        let label = if unread > 0 {
+                            format!("[{}] {}", unread, friend.username)
+                        } else {
+                            friend.username.clone()
+                        };
                         let button = if is_selected {
-                            egui::Button::new(format!("{} {}", 
-                                if friend.unread_count > 0 {
-                                    format!("[{}] ", friend.unread_count)
-                                } else {
-                                    String::new()
-                                },
-                                friend.username
-                            )).fill(theme.selected)
+                            egui::Button::new(label).fill(theme.selected)
                         } else {
-                            egui::Button::new(format!("{} {}", 
-                                if friend.unread_count > 0 {
-                                    format!("[{}] ", friend.unread_count)
-                                } else {
-                                    String::new()
-                                },
-                                friend.username
-                            ))
+                            egui::Button::new(label)
                         };
                         
                         if ui.add(button).clicked() {
@@ -249,10 +233,7 @@ fn render_friends_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppL
                             
                             // Compute conversation ID from user IDs
                             if let Some(current_user) = &state_write.current_user {
-                                let conversation_id = format!("{}:{}",
-                                    std::cmp::min(current_user.id, friend.user_id),
-                                    std::cmp::max(current_user.id, friend.user_id)
-                                );
+                                let conversation_id = MessagingState::conversation_id(current_user.id, friend.user_id);
                                 // Clone conversation_id before moving it
                                 let conversation_id_clone = conversation_id.clone();
                                 state_write.messaging.active_conversation_id = Some(conversation_id);
@@ -263,6 +244,8 @@ fn render_friends_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppL
                                 
                                 if let Some(token) = token {
                                     drop(state_write);
+                                    // Focusing the conversation reads it
+                                    mark_conversation_read(app_state.clone(), conversation_id_clone.clone());
                                     tokio::spawn(async move {
                                         // Subscribe to conversation updates
                                         let mut braid_client = crate::services::braid_client::BraidClient::new(
@@ -272,9 +255,36 @@ fn render_friends_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppL
                                         
                                         match braid_client.subscribe().await {
                                             Ok(mut rx) => {
-                                                while let Some((messages, _version)) = rx.recv().await {
+                                                // The snapshot's unread messages are already counted
+                                                let mut snapshot = true;
+                                                while let Some(event) = rx.recv().await {
                                                     let mut state = state_clone.write();
-                                                    state.messaging.messages.insert(conversation_id_clone.clone(), messages);
+                                                    match event {
+                                                        BraidEvent::Messages(messages, _version) => {
+                                                            let current_user_id = state.current_user.as_ref().map(|u| u.id);
+                                                            let incoming = messages.iter()
+                                                                .filter(|m| Some(m.author_id) != current_user_id)
+                                                                .count() as u32;
+                                                            state.messaging.messages.insert(conversation_id_clone.clone(), messages);
+                                                            
+                                                            if !std::mem::take(&mut snapshot) && incoming > 0 {
+                                                                if state.messaging.active_conversation_id.as_ref() == Some(&conversation_id_clone) {
+                                                                    drop(state);
+                                                                    mark_conversation_read(state_clone.clone(), conversation_id_clone.clone());
+                                                                    continue;
+                                                                }
+                                                                *state.messaging.unread_counts.entry(conversation_id_clone.clone()).or_default() += incoming;
+                                                            }
+                                                        }
+                                                        BraidEvent::Receipt(receipt) => {
+                                                            state.messaging.receipts
+                                                                .entry(conversation_id_clone.clone())
+                                                                .or_default()
+                                                                .apply(receipt);
+                                                        }
+                                                        // Only the AI bot conversation streams replies
+                                                        BraidEvent::AiStream(_) => {}
+                                                    }
                                                     drop(state);
                                                     // UI will update on next frame
                                                 }
@@ -294,7 +304,7 @@ fn render_friends_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppL
 }
 
 /// Render chat panel (right side)
-fn render_chat_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, _theme: &Theme) {
+fn render_chat_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
     use crate::ui::widgets::layouts;
     
     // Clone app.state at the beginning to avoid borrow conflicts in closures
@@ -302,39 +312,81 @@ fn render_chat_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike
     
     layouts::render_panel(ui, None, |ui| {
         if let Some(conversation_id) = &state.messaging.active_conversation_id {
-            // Show conversation header with friend's name
-            if let Some(user_id) = state.messaging.selected_user_id {
-                if let Some(friend) = state.messaging.friends.iter().find(|f| f.user_id == user_id) {
-                    ui.heading(format!("Conversation with {}", friend.username));
+            // Show conversation header with friend's name and the conversation menu
+            ui.horizontal(|ui| {
+                if let Some(user_id) = state.messaging.selected_user_id {
+                    if let Some(friend) = state.messaging.friends.iter().find(|f| f.user_id == user_id) {
+                        ui.heading(format!("Conversation with {}", friend.username));
+                    } else {
+                        ui.heading("Conversation");
+                    }
                 } else {
                     ui.heading("Conversation");
                 }
-            } else {
-                ui.heading("Conversation");
-            }
+                
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.menu_button("Menu", |ui| {
+                        let summarizing = state.messaging.summary_pending.is_some();
+                        if ui.add_enabled(!summarizing, egui::Button::new("Summarize")).clicked() {
+                            summarize_conversation(app_state.clone(), conversation_id.clone());
+                            ui.close();
+                        }
+                    });
+                });
+            });
             
             ui.separator();
             
+            // AI summary of the conversation
+            if state.messaging.summary_pending.as_ref() == Some(conversation_id) {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Summarizing conversation...");
+                });
+                ui.separator();
+            } else if let Some(summary) = state.messaging.summaries.get(conversation_id) {
+                render_summary_panel(ui, summary, &app_state, theme);
+                ui.separator();
+            }
+            
             // Message history area
             egui::ScrollArea::vertical()
                 .auto_shrink([false, false])
                 .max_height(400.0)
                 .show(ui, |ui| {
                     if let Some(messages) = state.messaging.messages.get(conversation_id) {
-                        for message in messages {
-                            ui.horizontal(|ui| {
+                        let current_user_id = state.current_user.as_ref().map(|u| u.id);
+                        let receipts = state.messaging.receipts.get(conversation_id);
+                        let highlight = match &state.search.highlight {
+                            Some(SearchTarget::Message { conversation_id: id, index }) if id == conversation_id => Some(*index),
+                            _ => None,
+                        };
+                        for (index, message) in messages.iter().enumerate() {
+                            let response = ui.horizontal(|ui| {
                                 // Message bubble
                                 ui.group(|ui| {
                                     ui.horizontal(|ui| {
                                         ui.label(format!("{}:", message.author));
                                         ui.label(&message.text);
                                     });
-                                    // Timestamp
-                                    if let Ok(timestamp) = DateTime::parse_from_rfc3339(&message.timestamp) {
-                                        ui.label(format!("{}", timestamp.format("%H:%M")));
+                                    for attachment in &message.attachments {
+                                        render_attachment(ui, attachment, state, &app_state, theme);
                                     }
+                                    ui.horizontal(|ui| {
+                                        // Timestamp
+                                        if let Ok(timestamp) = DateTime::parse_from_rfc3339(&message.timestamp) {
+                                            ui.label(format!("{}", timestamp.format("%H:%M")));
+                                        }
+                                        // Checkmarks on our own messages
+                                        if current_user_id == Some(message.author_id) && message.version.is_some() {
+                                            render_receipt(ui, receipts.and_then(|r| r.status_of(messages, index)), theme);
+                                        }
+                                    });
                                 });
-                            });
+                            }).response;
+                            if highlight == Some(index) {
+                                search_palette::outline_highlight(ui, &response, state, theme);
+                            }
                             ui.add_space(5.0);
                         }
                     } else {
@@ -351,8 +403,35 @@ fn render_chat_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike
             
             ui.separator();
             
+            // Attachments to go out with the next message
+            let pending = state.messaging.pending_attachments.get(conversation_id).cloned().unwrap_or_default();
+            let uploading = state.messaging.attachment_uploading.as_ref() == Some(conversation_id);
+            if !pending.is_empty() || uploading {
+                ui.horizontal_wrapped(|ui| {
+                    for attachment in &pending {
+                        ui.label(Icons::icon_dim(material::ATTACH_FILE, size::SMALL));
+                        ui.label(format!("{} ({})", attachment.filename, attachment_preview::format_size(attachment.size)));
+                        if ui.add(egui::Button::new(material::CLOSE).small()).on_hover_text("Remove").clicked() {
+                            if let Some(pending) = app_state.write().messaging.pending_attachments.get_mut(conversation_id) {
+                                pending.retain(|a| a.id != attachment.id);
+                            }
+                        }
+                    }
+                    if uploading {
+                        ui.spinner();
+                        ui.label(egui::RichText::new("Uploading...").color(theme.dim));
+                    }
+                });
+            }
+            
             // Message input area
             ui.horizontal(|ui| {
+                let attach = ui.add_enabled(!uploading, egui::Button::new(material::ATTACH_FILE))
+                    .on_hover_text("Attach a file (images, PDF, text or CSV up to 5 MB)");
+                if attach.clicked() {
+                    pick_attachment(app_state.clone(), conversation_id.clone());
+                }
+                
                 let mut state_write = app_state.write();
                 let text_edit = egui::TextEdit::singleline(&mut state_write.messaging.message_input)
                     .desired_width(f32::INFINITY)
@@ -362,15 +441,16 @@ fn render_chat_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike
                 let message_text = state_write.messaging.message_input.clone();
                 drop(state_write);
                 
+                let sendable = !message_text.trim().is_empty() || !pending.is_empty();
                 if response.lost_focus() && response.ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
-                    if !message_text.trim().is_empty() {
-                        send_message(app_state.clone(), conversation_id.clone(), message_text.clone());
+                    if sendable {
+                        send_message(app_state.clone(), conversation_id.clone(), message_text.clone(), pending.clone());
                     }
                 }
                 
                 if ui.button("Send").clicked() {
-                    if !message_text.trim().is_empty() {
-                        send_message(app_state.clone(), conversation_id.clone(), message_text.clone());
+                    if sendable {
+                        send_message(app_state.clone(), conversation_id.clone(), message_text.clone(), pending.clone());
                     }
                 }
             });
@@ -384,8 +464,37 @@ fn render_chat_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike
     });
 }
 
-/// Send a message
-fn send_message(app_state: Arc<RwLock<AppState>>, conversation_id: String, text: String) {
+/// Checkmarks for a sent message: one when sent, two when delivered,
+/// two highlighted when read
+fn render_receipt(ui: &mut egui::Ui, status: Option<ReceiptStatus>, theme: &Theme) {
+    let (marks, color, tooltip) = match status {
+        None => ("✓", theme.dim, "Sent"),
+        Some(ReceiptStatus::Delivered) => ("✓✓", theme.dim, "Delivered"),
+        Some(ReceiptStatus::Read) => ("✓✓", theme.info, "Read"),
+    };
+    ui.label(egui::RichText::new(marks).small().color(color)).on_hover_text(tooltip);
+}
+
+/// Clear a conversation's unread count and tell the backend it has been read
+fn mark_conversation_read(app_state: Arc<RwLock<AppState>>, conversation_id: String) {
+    let (api_client, token) = {
+        let mut state = app_state.write();
+        state.messaging.unread_counts.remove(&conversation_id);
+        let (Some(api_client), Some(token)) = (state.api_client.clone(), state.auth_token.clone()) else {
+            return;
+        };
+        (api_client, token)
+    };
+    
+    tokio::spawn(async move {
+        if let Err(e) = api_client.mark_conversation_read(&token, &conversation_id).await {
+            eprintln!("Failed to mark conversation as read: {}", e);
+        }
+    });
+}
+
+/// Send a message, with any attachments uploaded for it
+fn send_message(app_state: Arc<RwLock<AppState>>, conversation_id: String, text: String, attachments: Vec<Attachment>) {
     let state_read = app_state.read();
     
     if let Some(token) = &state_read.auth_token {
@@ -397,7 +506,8 @@ fn send_message(app_state: Arc<RwLock<AppState>>, conversation_id: String, text:
             .map(|u| u.id)
             .unwrap_or(0);
         
-        let message = shared::dto::messaging::Message::new(text, author, author_id);
+        let mut message = shared::dto::messaging::Message::new(text, author, author_id);
+        message.attachments = attachments;
         
         let mut braid_client = crate::services::braid_client::BraidClient::new(
             conversation_id.clone(),
@@ -413,6 +523,9 @@ fn send_message(app_state: Arc<RwLock<AppState>>, conversation_id: String, text:
                     // Message sent successfully - clear input
                     let mut state = state_clone.write();
                     state.messaging.message_input.clear();
+                    if let Some(pending) = state.messaging.pending_attachments.get_mut(&conversation_id) {
+                        pending.retain(|a| !message.attachments.iter().any(|sent| sent.id == a.id));
+                    }
                     // The SSE subscription will update the UI with the new message
                 }
                 Err(e) => {
@@ -428,3 +541,267 @@ fn send_message(app_state: Arc<RwLock<AppState>>, conversation_id: String, text:
     }
 }
 
+/// Inline preview of an image attachment, or a download row for other files
+/// and images that failed to load
+fn render_attachment(
+    ui: &mut egui::Ui,
+    attachment: &Attachment,
+    state: &AppState,
+    app_state: &Arc<RwLock<AppState>>,
+    theme: &Theme,
+) {
+    let details = format!("{} ({})", attachment.filename, attachment_preview::format_size(attachment.size));
+    if attachment.is_image() {
+        match state.messaging.attachment_previews.get(&attachment.id) {
+            Some(AttachmentPreview::Ready(image)) => {
+                let response = attachment_preview::show(ui, &attachment.id, image)
+                    .on_hover_text(format!("{} - click to save", details));
+                if response.clicked() {
+                    save_attachment(app_state.clone(), attachment.clone());
+                }
+                return;
+            }
+            Some(AttachmentPreview::Failed(e)) => {
+                ui.label(egui::RichText::new(format!("Preview unavailable: {}", e)).small().color(theme.dim));
+            }
+            preview => {
+                // First time on screen: start the download
+                if preview.is_none() {
+                    load_preview(app_state.clone(), attachment.id.clone());
+                }
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(egui::RichText::new(&attachment.filename).color(theme.dim));
+                });
+                return;
+            }
+        }
+    }
+    
+    ui.horizontal(|ui| {
+        ui.label(Icons::icon_dim(material::ATTACH_FILE, size::SMALL));
+        ui.label(details);
+        if ui.button(format!("{} Download", material::DOWNLOAD)).clicked() {
+            save_attachment(app_state.clone(), attachment.clone());
+        }
+    });
+}
+
+/// Pick a file and upload it, to be sent with the next message
+fn pick_attachment(app_state: Arc<RwLock<AppState>>, conversation_id: String) {
+    let Some(path) = rfd::FileDialog::new()
+        .set_title("Attach a file")
+        .add_filter("Images and documents", &["png", "jpg", "jpeg", "gif", "webp", "pdf", "txt", "csv"])
+        .pick_file()
+    else {
+        return;
+    };
+    
+    let (api_client, token) = {
+        let mut state = app_state.write();
+        let (Some(api_client), Some(token)) = (state.api_client.clone(), state.auth_token.clone()) else {
+            return;
+        };
+        // Catch oversized files before uploading them
+        let too_large = std::fs::metadata(&path).map(|m| m.len() > MAX_UPLOAD_BYTES).unwrap_or(false);
+        if too_large {
+            state.pending_notifications.push((
+                "error".to_string(),
+                format!("Attachments are limited to {}", attachment_preview::format_size(MAX_UPLOAD_BYTES)),
+            ));
+            return;
+        }
+        state.messaging.attachment_uploading = Some(conversation_id.clone());
+        (api_client, token)
+    };
+    
+    let filename = path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "attachment".to_string());
+    tokio::spawn(async move {
+        let result = match tokio::fs::read(&path).await {
+            Ok(bytes) => api_client
+                .upload_attachment(&token, &conversation_id, filename, bytes)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(format!("Couldn't read {}: {}", path.display(), e)),
+        };
+        
+        let mut state = app_state.write();
+        state.messaging.attachment_uploading = None;
+        match result {
+            Ok(attachment) => {
+                state.messaging.pending_attachments.entry(conversation_id).or_default().push(attachment);
+            }
+            Err(e) => {
+                eprintln!("Failed to upload attachment: {}", e);
+                state.pending_notifications.push((
+                    "error".to_string(),
+                    format!("Couldn't attach file: {}", e),
+                ));
+            }
+        }
+    });
+}
+
+/// Download and decode an image attachment for its inline preview
+fn load_preview(app_state: Arc<RwLock<AppState>>, attachment_id: String) {
+    let (api_client, token) = {
+        let mut state = app_state.write();
+        let (Some(api_client), Some(token)) = (state.api_client.clone(), state.auth_token.clone()) else {
+            return;
+        };
+        state.messaging.attachment_previews.insert(attachment_id.clone(), AttachmentPreview::Loading);
+        (api_client, token)
+    };
+    
+    tokio::spawn(async move {
+        let preview = match api_client.download_attachment(&token, &attachment_id).await {
+            Ok(bytes) => {
+                // Decoding a large image takes a while; keep it off the runtime's workers
+                tokio::task::spawn_blocking(move || attachment_preview::decode_preview(&bytes, MAX_PREVIEW_DIMENSION))
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()))
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        let preview = match preview {
+            Ok(image) => AttachmentPreview::Ready(Arc::new(image)),
+            Err(e) => {
+                tracing::warn!("Failed to load attachment preview {}: {}", attachment_id, e);
+                AttachmentPreview::Failed(e)
+            }
+        };
+        app_state.write().messaging.attachment_previews.insert(attachment_id, preview);
+    });
+}
+
+/// Ask where to save an attachment, then download it there
+fn save_attachment(app_state: Arc<RwLock<AppState>>, attachment: Attachment) {
+    let Some(path) = rfd::FileDialog::new()
+        .set_title("Save attachment")
+        .set_file_name(&attachment.filename)
+        .save_file()
+    else {
+        return;
+    };
+    
+    let (api_client, token) = {
+        let state = app_state.read();
+        let (Some(api_client), Some(token)) = (state.api_client.clone(), state.auth_token.clone()) else {
+            return;
+        };
+        (api_client, token)
+    };
+    
+    tokio::spawn(async move {
+        let result = match api_client.download_attachment(&token, &attachment.id).await {
+            Ok(bytes) => tokio::fs::write(&path, bytes).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        
+        let notification = match result {
+            Ok(()) => ("success".to_string(), format!("Saved {}", path.display())),
+            Err(e) => ("error".to_string(), format!("Couldn't save {}: {}", attachment.filename, e)),
+        };
+        app_state.write().pending_notifications.push(notification);
+    });
+}
+
+/// Render the collapsible AI summary shown above the message list
+fn render_summary_panel(
+    ui: &mut egui::Ui,
+    summary: &shared::dto::messaging::ConversationSummaryResponse,
+    app_state: &Arc<RwLock<AppState>>,
+    theme: &Theme,
+) {
+    egui::CollapsingHeader::new("AI Summary")
+        .id_salt(("conversation_summary", &summary.conversation_id))
+        .default_open(true)
+        .show(ui, |ui| {
+            for point in &summary.summary {
+                ui.label(format!("• {}", point));
+            }
+            
+            if !summary.action_items.is_empty() {
+                ui.add_space(4.0);
+                ui.label(egui::RichText::new("Action items").strong());
+                for item in &summary.action_items {
+                    ui.label(format!("☐ {}", item));
+                }
+            }
+            
+            if !summary.tokens.is_empty() {
+                ui.add_space(4.0);
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(egui::RichText::new("Tokens:").strong());
+                    for token in &summary.tokens {
+                        ui.label(egui::RichText::new(token).color(theme.info));
+                    }
+                });
+            }
+            
+            ui.add_space(4.0);
+            let coverage = if summary.messages_omitted > 0 {
+                format!(
+                    "Covers the last {} messages ({} earlier not included)",
+                    summary.messages_summarized, summary.messages_omitted
+                )
+            } else {
+                format!("Covers all {} messages", summary.messages_summarized)
+            };
+            ui.label(egui::RichText::new(coverage).small().color(theme.dim));
+            
+            ui.horizontal(|ui| {
+                if ui.button("Insert into chat").clicked() {
+                    app_state.write().messaging.message_input = summary_as_message(summary);
+                }
+                if ui.button("Dismiss").clicked() {
+                    app_state.write().messaging.summaries.remove(&summary.conversation_id);
+                }
+            });
+        });
+}
+
+/// Single-line version of a summary for the message input
+fn summary_as_message(summary: &shared::dto::messaging::ConversationSummaryResponse) -> String {
+    let mut text = format!("Summary: {}", summary.summary.join("; "));
+    if !summary.action_items.is_empty() {
+        text.push_str(&format!(" | Action items: {}", summary.action_items.join("; ")));
+    }
+    if !summary.tokens.is_empty() {
+        text.push_str(&format!(" | Tokens: {}", summary.tokens.join(", ")));
+    }
+    text
+}
+
+/// Request an AI summary of a conversation
+fn summarize_conversation(app_state: Arc<RwLock<AppState>>, conversation_id: String) {
+    let (api_client, token) = {
+        let mut state = app_state.write();
+        let (Some(api_client), Some(token)) = (state.api_client.clone(), state.auth_token.clone()) else {
+            return;
+        };
+        state.messaging.summary_pending = Some(conversation_id.clone());
+        (api_client, token)
+    };
+    
+    tokio::spawn(async move {
+        let result = api_client.summarize_conversation(&token, &conversation_id, None).await;
+        
+        let mut state = app_state.write();
+        state.messaging.summary_pending = None;
+        match result {
+            Ok(summary) => {
+                state.messaging.summaries.insert(conversation_id, summary);
+            }
+            Err(e) => {
+                eprintln!("Failed to summarize conversation: {}", e);
+                state.pending_notifications.push((
+                    "error".to_string(),
+                    format!("Couldn't summarize conversation: {}", e),
+                ));
+            }
+        }
+    });
+}
@@ -11,6 +11,7 @@
 //! - **[`auth`]**: Authentication screen with login and signup forms
 //! - **[`terminal`]**: Main trading terminal with swaps, charts, and price feeds
 //! - **[`wallet`]**: Wallet management screen (connect, generate, view balance)
+//! - **[`portfolio`]**: Portfolio value, allocation, and 24h P&L across wallet holdings
 //! - **[`transactions`]**: Transaction history and monitoring screen
 //! - **[`swap_history`]**: Swap transaction history view
 //! - **[`token_explorer`]**: Token search and selection interface
@@ -51,6 +52,11 @@
 //! }
 //! ```
 //!
+//! ## Commands
+//!
+//! A screen can add its own commands to the command palette (Ctrl+K) with a
+//! `register_commands` function, called from [`register_commands`] at startup.
+//!
 //! ## Related Types
 //!
 //! - [`crate::app::Screen`]: Screen enum variants
@@ -64,6 +70,7 @@ pub mod ai_chat;
 pub mod terminal;
 pub mod transactions;
 pub mod wallet;
+pub mod portfolio;
 pub mod swap_history;
 pub mod token_explorer;
 pub mod pyth_feed;
@@ -73,3 +80,13 @@ pub mod settings;
 pub mod live_chart;
 pub mod live_assets;
 pub mod live_table;
+
+use crate::app::commands::CommandRegistry;
+
+/// Add the commands screens offer in the command palette while they show
+pub fn register_commands(mut registry: CommandRegistry) -> CommandRegistry {
+    terminal::register_commands(&mut registry);
+    live_chart::register_commands(&mut registry);
+    portfolio::register_commands(&mut registry);
+    registry
+}
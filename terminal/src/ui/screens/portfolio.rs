@@ -0,0 +1,501 @@
+//! # Portfolio Screen
+//!
+//! Total portfolio value, per-asset allocation, 24h P&L, and a 30-day value sparkline.
+//!
+//! Holdings combine the SOL balance with the wallet's SPL and Token-2022
+//! balances; Refresh in the header (or F5) reloads both.
+//!
+//! Holdings with a token unlock within the next week get an "Unlock Nd"
+//! chip next to the symbol; hovering it shows the size of the unlock.
+//!
+//! Under the totals, the exposure line shows the largest position and the
+//! Herfindahl index, with a badge for each risk threshold (Settings > Risk)
+//! the portfolio crosses.
+//!
+//! When more than one wallet has been connected this session, the wallet
+//! filter switches the totals and holdings between one wallet and all of them
+//! combined; each holding then shows chips for the wallets holding it.
+//!
+//! Below the holdings, the rebalancer edits the profile's target allocations
+//! and proposes the swaps that restore them; queued swaps go to the swap
+//! panel and are executed one by one from there.
+//!
+//! The "Monthly report" menu in the header picks one of the last twelve months
+//! and saves the backend's printable PDF report of it (holdings, swaps, fees,
+//! realized PnL, deposits and withdrawals).
+
+use egui;
+use egui_plot::{Bar, BarChart, Line, Plot, PlotPoints};
+use crate::app::commands::{CommandAction, CommandRegistry, CommandSpec};
+use crate::app::handlers::{portfolio as portfolio_handlers, rebalance, risk, unlocks};
+use crate::app::wallet_identity::WalletFilter;
+use crate::app::{AppState, AppLike, PortfolioHolding, PortfolioState, Screen, TargetAllocation};
+use crate::tr;
+use crate::ui::theme::Theme;
+use crate::ui::widgets::icons::{Icons, material, size};
+use crate::ui::widgets::share_menu::{self, ShareTarget};
+use crate::ui::widgets::{tables, wallet_chip};
+use shared::dto::reports::ReportMonth;
+use shared::TokenUnlockInfo;
+
+/// Months offered by the "Monthly report" menu, the current one included
+const REPORT_MONTHS: usize = 12;
+
+/// Command palette commands for this screen
+pub fn register_commands(registry: &mut CommandRegistry) {
+    registry.register(
+        CommandSpec::action("share portfolio", "Publish a read-only link to this allocation", CommandAction::SharePortfolio)
+            .on_screen(Screen::Portfolio)
+            .requires_auth(),
+    );
+}
+
+/// Render portfolio screen
+pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
+    let theme = Theme::default();
+
+    ui.horizontal(|ui| {
+        ui.label(Icons::icon_red(material::WALLET, size::MEDIUM));
+        ui.heading("Portfolio");
+
+        if state.wallet.is_some() {
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button(format!("{} {}", material::REFRESH, tr!("common.refresh"))).clicked() {
+                    app.refresh(Screen::Portfolio);
+                }
+                share_menu::render(ui, state, app, ShareTarget::Portfolio);
+                render_report_menu(ui, state, app);
+                let filter = &state.portfolio.wallet_filter;
+                if state.portfolio.wallets.len() > 1 || *filter != WalletFilter::All {
+                    let wallets: Vec<String> = state.portfolio.wallets.keys().cloned().collect();
+                    if let Some(filter) = wallet_chip::render_wallet_filter(
+                        ui,
+                        "portfolio_wallet_filter",
+                        &state.wallet_identities,
+                        &wallets,
+                        filter,
+                    ) {
+                        app.state().write().portfolio.wallet_filter = filter;
+                    }
+                }
+            });
+        }
+    });
+    ui.add_space(10.0);
+
+    if state.wallet.is_none() {
+        render_no_wallet(ui, app, &theme);
+        return;
+    }
+
+    let portfolio = &state.portfolio;
+    let (holdings, total_value, change_24h_value) = portfolio_handlers::visible_portfolio(state);
+
+    render_summary(ui, total_value, change_24h_value, &theme);
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+
+    if holdings.is_empty() {
+        tables::render_empty_state(
+            ui,
+            "No holdings to value yet",
+            Some("Balances will appear once prices and token accounts are loaded"),
+            &theme,
+        );
+        return;
+    }
+
+    render_exposure(ui, state, &theme);
+    ui.add_space(10.0);
+
+    ui.columns(2, |columns| {
+        columns[0].vertical(|ui| {
+            ui.heading("Allocation");
+            ui.add_space(5.0);
+            render_allocation_chart(ui, &holdings, &theme);
+        });
+
+        columns[1].vertical(|ui| {
+            ui.heading("Value (30 days)");
+            ui.add_space(5.0);
+            render_history_sparkline(ui, portfolio, &theme);
+        });
+    });
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+
+    render_holdings_table(ui, state, &holdings, &theme);
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+
+    render_rebalance(ui, state, app, &theme);
+}
+
+/// Render the "Monthly report" menu: a month picker and the download button
+fn render_report_menu(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
+    let months = ReportMonth::recent(chrono::Utc::now(), REPORT_MONTHS);
+    let pending = state.portfolio.report_pending;
+
+    ui.menu_button(if pending { "Monthly report…" } else { "Monthly report" }, |ui| {
+        let mut month = state.portfolio.report_month.unwrap_or(months[0]);
+        ui.label("Month (UTC)");
+        egui::ComboBox::from_id_salt("portfolio_report_month")
+            .selected_text(month.label())
+            .show_ui(ui, |ui| {
+                for option in &months {
+                    ui.selectable_value(&mut month, *option, option.label());
+                }
+            });
+        if state.portfolio.report_month != Some(month) {
+            app.state().write().portfolio.report_month = Some(month);
+        }
+
+        ui.separator();
+        let button = egui::Button::new(if pending { "Downloading…" } else { "Save PDF…" });
+        if ui.add_enabled(!pending, button).clicked() {
+            ui.close();
+            portfolio_handlers::handle_monthly_report_download(app.state().clone(), month);
+        }
+    });
+}
+
+/// Render total value and 24h change
+fn render_summary(ui: &mut egui::Ui, total_value: f64, change_24h_value: f64, theme: &Theme) {
+    ui.horizontal(|ui| {
+        ui.label("Total Value:");
+        ui.colored_label(theme.selected, egui::RichText::new(format!("${:.2}", total_value)).size(20.0));
+
+        ui.add_space(20.0);
+
+        ui.label("24h:");
+        let color = theme.price_change_color(change_24h_value);
+        let sign = if change_24h_value >= 0.0 { "+" } else { "-" };
+        ui.colored_label(color, format!("{}${:.2}", sign, change_24h_value.abs()));
+        let change_pct = portfolio_handlers::change_24h_pct(total_value, change_24h_value);
+        let (change_text, change_color) = theme.format_price_change(change_pct);
+        ui.colored_label(change_color, format!("({})", change_text));
+    });
+}
+
+/// Render concentration metrics and a badge per crossed risk threshold
+fn render_exposure(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
+    let volumes = risk::daily_volumes(&state.terminal.swap.token_list);
+    let metrics = risk::compute_exposure(&state.portfolio.holdings, &volumes);
+    let Some(largest) = metrics.largest() else {
+        return;
+    };
+
+    ui.horizontal(|ui| {
+        ui.colored_label(theme.dim, "Largest position:");
+        ui.label(format!("{} {:.1}%", largest.symbol, largest.weight_pct));
+        ui.add_space(20.0);
+        ui.colored_label(theme.dim, "Herfindahl index:");
+        ui.label(format!("{:.0}", metrics.hhi))
+            .on_hover_text("Sum of squared percentage weights: 10,000 is one asset, 2,500 four equal ones");
+    });
+
+    for warning in risk::risk_warnings(&metrics, &state.settings.risk) {
+        ui.horizontal(|ui| {
+            let badge = match &warning.symbol {
+                Some(symbol) => format!("[{} {}]", warning.kind.badge(), symbol),
+                None => format!("[{}]", warning.kind.badge()),
+            };
+            ui.colored_label(theme.warning, egui::RichText::new(badge).strong());
+            ui.label(&warning.explanation);
+        });
+    }
+}
+
+/// Render allocation as a horizontal bar chart (one bar per asset)
+fn render_allocation_chart(ui: &mut egui::Ui, holdings: &[PortfolioHolding], theme: &Theme) {
+    let palette = [
+        theme.selected,
+        theme.info,
+        theme.success,
+        theme.warning,
+        theme.colors.red_dark,
+        theme.dim,
+    ];
+
+    let bars: Vec<Bar> = holdings
+        .iter()
+        .enumerate()
+        .map(|(idx, holding)| {
+            Bar::new(idx as f64, holding.allocation_pct)
+                .name(format!("{} {:.1}%", holding.symbol, holding.allocation_pct))
+                .fill(palette[idx % palette.len()])
+                .width(0.7)
+        })
+        .collect();
+
+    let labels: Vec<String> = holdings.iter().map(|h| h.symbol.clone()).collect();
+
+    Plot::new("portfolio_allocation")
+        .height(180.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .include_y(0.0)
+        .include_y(100.0)
+        .y_axis_formatter(|mark, _range| format!("{:.0}%", mark.value))
+        .x_axis_formatter(move |mark, _range| {
+            let idx = mark.value.round();
+            if (mark.value - idx).abs() < f64::EPSILON && idx >= 0.0 {
+                labels.get(idx as usize).cloned().unwrap_or_default()
+            } else {
+                String::new()
+            }
+        })
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new("Allocation", bars));
+        });
+}
+
+/// Render portfolio value history as a small line chart
+fn render_history_sparkline(ui: &mut egui::Ui, portfolio: &PortfolioState, theme: &Theme) {
+    if portfolio.snapshots.len() < 2 {
+        ui.colored_label(theme.dim, "History builds up as daily snapshots are recorded");
+        return;
+    }
+
+    let points: Vec<[f64; 2]> = portfolio
+        .snapshots
+        .iter()
+        .enumerate()
+        .map(|(idx, snapshot)| [idx as f64, snapshot.total_value])
+        .collect();
+
+    let first = portfolio.snapshots.first().map(|s| s.total_value).unwrap_or(0.0);
+    let last = portfolio.snapshots.last().map(|s| s.total_value).unwrap_or(0.0);
+    let color = if last >= first { theme.price_up } else { theme.price_down };
+
+    Plot::new("portfolio_history")
+        .height(180.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .show_x(false)
+        .show_axes([false, true])
+        .show(ui, |plot_ui| {
+            plot_ui.line(
+                Line::new("Portfolio Value", PlotPoints::from(points))
+                    .color(color)
+                    .width(2.0),
+            );
+        });
+
+    if let (Some(first), Some(last)) = (portfolio.snapshots.first(), portfolio.snapshots.last()) {
+        ui.colored_label(theme.dim, format!("{} → {}", first.date, last.date));
+    }
+}
+
+/// Render per-asset holdings table
+fn render_holdings_table(ui: &mut egui::Ui, state: &AppState, holdings: &[PortfolioHolding], theme: &Theme) {
+    let now = chrono::Utc::now().timestamp();
+    let config = tables::TableConfig {
+        num_columns: 6,
+        spacing: [10.0, 5.0],
+        striped: true,
+        scrollable: true,
+    };
+
+    tables::render_table(
+        ui,
+        "portfolio_holdings",
+        config,
+        &["Asset", "Amount", "Price", "Value", "Allocation", "24h"],
+        theme,
+        |ui| {
+            for holding in holdings {
+                ui.horizontal(|ui| {
+                    ui.label(&holding.symbol);
+                    if let Some(unlock) = state.token_unlocks.approaching(&holding.symbol, now) {
+                        render_unlock_chip(ui, unlock, now, theme);
+                    }
+                    for (wallet, amount) in &holding.wallets {
+                        wallet_chip::render_wallet_chip(ui, &state.wallet_identities, wallet)
+                            .on_hover_text(format!("{:.6} {}", amount, holding.symbol));
+                    }
+                });
+                ui.monospace(format!("{:.6}", holding.amount));
+                match holding.price {
+                    Some(price) => ui.monospace(format!("${:.4}", price)),
+                    None => ui.colored_label(theme.dim, "-"),
+                };
+                ui.colored_label(theme.success, format!("${:.2}", holding.value));
+                ui.monospace(format!("{:.1}%", holding.allocation_pct));
+                let (change_text, change_color) = theme.format_price_change(holding.change_24h_pct);
+                ui.colored_label(change_color, change_text);
+                ui.end_row();
+            }
+        },
+    );
+}
+
+/// Warning chip for an unlock within [`shared::UNLOCK_WARNING_DAYS`]
+fn render_unlock_chip(ui: &mut egui::Ui, unlock: &TokenUnlockInfo, now: i64, theme: &Theme) {
+    let when = chrono::DateTime::from_timestamp(unlock.unlock_at, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+    let size = match unlock.pct_of_circulating {
+        Some(pct) => format!(" ({:.2}% of circulating supply)", pct),
+        None => String::new(),
+    };
+    ui.colored_label(
+        theme.warning,
+        egui::RichText::new(format!("Unlock {}d", unlock.days_until(now))).small().strong(),
+    )
+    .on_hover_text(format!(
+        "{} {} unlock{} on {}",
+        unlocks::format_unlock_amount(unlock.amount),
+        unlock.symbol,
+        size,
+        when
+    ));
+}
+
+/// Render target allocations and the swaps proposed to reach them
+fn render_rebalance(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    // Targets are per profile; reload them when someone else logged in
+    if state.portfolio.rebalance.profile.as_deref() != Some(rebalance::profile_key(state).as_str()) {
+        rebalance::sync_profile(&mut app.state().write());
+        return;
+    }
+    let form = &state.portfolio.rebalance;
+
+    ui.heading("Rebalance");
+    ui.colored_label(theme.dim, "Target allocations for this profile; they must add up to 100%");
+    ui.add_space(5.0);
+
+    // Edits are collected and applied after the grid
+    let mut targets = form.targets.clone();
+    let mut remove = None;
+    egui::Grid::new("portfolio_rebalance_targets").num_columns(4).show(ui, |ui| {
+        for (i, target) in targets.iter_mut().enumerate() {
+            ui.label(&target.symbol);
+            ui.add(egui::DragValue::new(&mut target.pct).range(0.0..=100.0).speed(0.5).suffix("%"));
+            let current = state
+                .portfolio
+                .holdings
+                .iter()
+                .find(|h| h.symbol.eq_ignore_ascii_case(&target.symbol))
+                .map(|h| h.allocation_pct)
+                .unwrap_or(0.0);
+            ui.colored_label(theme.dim, format!("now {:.1}%", current));
+            if ui.add(egui::Button::new(material::CLOSE).small()).on_hover_text("Remove").clicked() {
+                remove = Some(i);
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(i) = remove {
+        targets.remove(i);
+    }
+    if targets != form.targets {
+        app.state().write().portfolio.rebalance.targets = targets;
+    }
+
+    ui.horizontal(|ui| {
+        let mut state_write = app.state().write();
+        let form = &mut state_write.portfolio.rebalance;
+        ui.add(egui::TextEdit::singleline(&mut form.new_symbol).hint_text("JUP").desired_width(80.0));
+        let symbol = form.new_symbol.trim().to_uppercase();
+        if ui.button("Add").clicked() && !symbol.is_empty() && !form.targets.iter().any(|t| t.symbol == symbol) {
+            form.targets.push(TargetAllocation { symbol, pct: 0.0 });
+            form.new_symbol.clear();
+        }
+        ui.add_space(20.0);
+        ui.label("Minimum trade:");
+        ui.add(egui::DragValue::new(&mut form.min_trade_usd).range(0.0..=100_000.0).speed(1.0).prefix("$"));
+    });
+
+    let total: f64 = form.targets.iter().map(|t| t.pct).sum();
+    let total_color = if (total - 100.0).abs() > 0.01 { theme.warning } else { theme.dim };
+    ui.colored_label(total_color, format!("Total: {:.2}%", total));
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        if ui.button(format!("{} Save Targets", material::SAVE)).clicked() {
+            app.handle_rebalance_save();
+        }
+        if ui.button("Propose Swaps").clicked() {
+            app.handle_rebalance_propose();
+        }
+        let queue = ui.add_enabled(!form.proposals.is_empty(), egui::Button::new("Queue All"));
+        if queue.on_hover_text("Add the swaps to the swap panel's queue; each is reviewed and signed there").clicked() {
+            app.handle_rebalance_queue_all();
+        }
+    });
+    if let Some((is_error, message)) = &form.status {
+        ui.colored_label(if *is_error { theme.error } else { theme.success }, message);
+    }
+
+    if form.proposals.is_empty() {
+        return;
+    }
+    ui.add_space(5.0);
+
+    let config = tables::TableConfig {
+        num_columns: 5,
+        spacing: [10.0, 5.0],
+        striped: true,
+        scrollable: false,
+    };
+    tables::render_table(
+        ui,
+        "portfolio_rebalance_proposals",
+        config,
+        &["Swap", "Amount", "Value", "Impact", "Network Fee"],
+        theme,
+        |ui| {
+            for proposal in &form.proposals {
+                let swap = &proposal.swap;
+                ui.label(format!("{} → {}", swap.input_symbol, swap.output_symbol));
+                ui.monospace(format!("{} {}", swap.input_amount, swap.input_symbol));
+                ui.colored_label(theme.success, format!("${:.2}", swap.usd_value));
+                match &proposal.quote {
+                    None => {
+                        ui.colored_label(theme.dim, "Quoting...");
+                        ui.label("");
+                    }
+                    Some(Ok(quote)) => {
+                        // Impact costs roughly this share of the swap's value
+                        let impact_usd = swap.usd_value * quote.price_impact / 100.0;
+                        let impact_color = if quote.price_impact > 1.0 { theme.warning } else { theme.normal };
+                        ui.colored_label(impact_color, format!("{:.2}% (≈${:.2})", quote.price_impact, impact_usd));
+                        ui.monospace(format!("{:.6} SOL", quote.estimated_fee));
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(theme.error, "No quote").on_hover_text(e);
+                        ui.label("");
+                    }
+                }
+                ui.end_row();
+            }
+        },
+    );
+}
+
+/// Render no wallet connected message
+fn render_no_wallet(ui: &mut egui::Ui, app: &mut impl AppLike, theme: &Theme) {
+    use crate::ui::widgets::{layouts, forms};
+
+    layouts::render_centered(ui, |ui| {
+        ui.add_space(20.0);
+        ui.label(Icons::icon_error(material::WALLET, size::XLARGE));
+        ui.add_space(10.0);
+        ui.colored_label(theme.error, "No Wallet Connected");
+        ui.add_space(10.0);
+        forms::render_hint(ui, "Connect a wallet to see your portfolio", theme);
+        ui.add_space(20.0);
+
+        if forms::render_button(ui, "Go to Wallet", Some(material::WALLET), theme, Some(theme.selected), None).clicked() {
+            app.handle_screen_change(Screen::Wallet);
+        }
+    });
+}
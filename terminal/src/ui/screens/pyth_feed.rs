@@ -1,9 +1,13 @@
 //! # Pyth Network Price Feed Screen
 //!
 //! Displays real-time price feeds from Pyth Network oracle.
+//!
+//! Each price is shown with its confidence interval. Prices the oracle
+//! published longer ago than the configured threshold (Settings > Servers)
+//! are greyed out and badged STALE.
 
 use egui;
-use crate::app::{AppState, AppLike};
+use crate::app::{AppState, AppLike, Feature};
 use crate::ui::theme::Theme;
 use crate::ui::widgets::{icons::{Icons, material, size}, tables};
 
@@ -31,10 +35,19 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, _app: &mut impl AppLike) {
     });
     
     ui.separator();
+
+    // Oracle outage reported by the backend health check
+    if let Some(reason) = state.backend_health.gates().get(Feature::OraclePrices).reason() {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_color(material::WARNING, size::SMALL, theme.warning));
+            ui.colored_label(theme.warning, reason);
+        });
+    }
     ui.add_space(10.0);
 
     // Filter prices by source
-    let pyth_prices: Vec<_> = state.terminal.prices
+    let prices = state.terminal.prices.load();
+    let pyth_prices: Vec<_> = prices
         .iter()
         .filter(|p| p.source.as_ref().map(|s| s == "pyth").unwrap_or(false))
         .collect();
@@ -59,42 +72,61 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, _app: &mut impl AppLike) {
 
     // Check if data was recently updated for flash effect
     let recently_updated = state.last_price_update_time.elapsed().as_millis() < 500;
-    
+    let now = chrono::Utc::now().timestamp();
+    let stale_after = state.settings.network.stale_price_secs;
+
     tables::render_table(
         ui,
         "pyth_prices_table",
         config,
-        &["Symbol", "Price (USD)", "24h Change", "Last Update"],
+        &["Symbol", "Price (USD)", "24h Change", "Published"],
         &theme,
         |ui| {
             for price in &pyth_prices {
+                let stale = price.is_stale(now, stale_after);
+
                 // Symbol
-                ui.label(&price.symbol);
-                
-                // Price with flash effect if recently updated
-                let price_color = if recently_updated {
+                ui.horizontal(|ui| {
+                    ui.colored_label(if stale { theme.dim } else { theme.normal }, &price.symbol);
+                    if stale {
+                        ui.colored_label(theme.warning, "STALE").on_hover_text(format!(
+                            "Published more than {}s ago; the oracle may have stopped updating",
+                            stale_after
+                        ));
+                    }
+                });
+
+                // Price ± confidence, flashing if recently updated
+                let price_color = if stale {
+                    theme.dim
+                } else if recently_updated {
                     theme.selected
                 } else {
                     theme.normal
                 };
-                ui.colored_label(price_color, format!("${:.4}", price.price));
-                
+                let price_text = match price.confidence {
+                    Some(confidence) => format!("${:.4} ± {:.4}", price.price, confidence),
+                    None => format!("${:.4}", price.price),
+                };
+                ui.colored_label(price_color, price_text);
+
                 // 24h Change
                 let (change_text, change_color) = theme.format_price_change(price.change_24h);
-                ui.colored_label(change_color, change_text);
-                
-                // Last update time
-                let last_update = state.last_price_update_time.elapsed();
-                let update_text = if last_update.as_secs() < 60 {
-                    format!("{}s ago", last_update.as_secs())
+                ui.colored_label(if stale { theme.dim } else { change_color }, change_text);
+
+                // Age of the oracle price, or of the last update we received
+                let age = price
+                    .age_secs(now)
+                    .unwrap_or_else(|| state.last_price_update_time.elapsed().as_secs() as i64);
+                let update_text = if age < 60 {
+                    format!("{}s ago", age)
                 } else {
-                    format!("{}m ago", last_update.as_secs() / 60)
+                    format!("{}m ago", age / 60)
                 };
-                ui.colored_label(theme.dim, update_text);
-                
+                ui.colored_label(if stale { theme.warning } else { theme.dim }, update_text);
+
                 ui.end_row();
             }
         },
     );
 }
-
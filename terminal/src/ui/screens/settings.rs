@@ -1,43 +1,142 @@
 //! # Settings Screen
 //!
-//! UI customization screen with color pickers for theme configuration.
+//! UI customization screen with theme presets (previewed on hover), theme
+//! file import/export and color pickers for theme configuration, the
+//! active branding file, the backend server list, the network and endpoints
+//! (Connection), the keyboard shortcuts, the UI language, plus the account forms (password and email),
+//! session signer grants, wallet activity webhooks and API keys.
 
 use egui;
+use crate::app::search::{SearchTarget, SettingsSection};
+use crate::app::keybindings::{KeyAction, KeyCombo};
 use crate::app::AppState;
-use crate::ui::theme::ThemeConfig;
+use crate::tr;
+use crate::ui::i18n::{self, Locale};
+use crate::ui::theme::{ThemeConfig, ThemePreset};
 use crate::ui::widgets::icons::{Icons, material, size};
+use crate::ui::widgets::search_palette;
 
 /// Render settings screen
 pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl crate::app::AppLike) {
-    // Apply theme immediately when settings screen is shown
-    let ctx = ui.ctx();
-    crate::ui::theme::Theme::apply_custom_theme(ctx, &state.settings.theme_config);
     use crate::ui::theme::Theme;
     let theme = Theme::default();
+    // Preset under the pointer, shown until the pointer moves away
+    let mut preview = None;
 
     ui.vertical(|ui| {
         ui.horizontal(|ui| {
             ui.label(Icons::icon_red(material::SETTINGS, size::MEDIUM));
-            ui.heading("Settings");
+            ui.heading(tr!("settings.heading"));
         });
         ui.add_space(10.0);
 
         // Theme Colors Section
-        ui.group(|ui| {
+        let response = ui.group(|ui| {
             ui.horizontal(|ui| {
                 ui.label(Icons::icon_dim(material::PALETTE, size::SMALL));
-                ui.heading("Theme Colors");
+                ui.heading(tr!("settings.theme.heading"));
             });
             ui.add_space(10.0);
 
+            preview = render_presets(ui, &state.settings.theme_config, app);
+            ui.add_space(10.0);
+
             render_color_pickers(ui, &state.settings.theme_config, app, &theme);
-        });
+        }).response;
+        mark_section(ui, state, SettingsSection::Theme, &response, &theme);
+
+        ui.add_space(10.0);
+        render_branding(ui, state, &theme);
 
         ui.add_space(20.0);
 
         // Actions Section
         render_actions(ui, state, app, &theme);
+
+        ui.add_space(20.0);
+        render_servers(ui, state, app, &theme);
+
+        ui.add_space(20.0);
+        render_connection(ui, state, app, &theme);
+
+        ui.add_space(20.0);
+        render_risk(ui, state, app, &theme);
+
+        ui.add_space(20.0);
+        render_notifications(ui, state, app, &theme);
+
+        ui.add_space(20.0);
+        render_shortcuts(ui, state, app, &theme);
+
+        ui.add_space(20.0);
+        render_language(ui, state, app, &theme);
+
+        ui.add_space(20.0);
+        render_privacy(ui, state, app, &theme);
+
+        if state.is_authenticated() {
+            ui.add_space(20.0);
+            render_account(ui, state, app, &theme);
+
+            ui.add_space(20.0);
+            render_security(ui, state, app, &theme);
+
+            ui.add_space(20.0);
+            render_webhooks(ui, state, app, &theme);
+
+            ui.add_space(20.0);
+            render_api_keys(ui, state, app, &theme);
+        }
     });
+
+    // Apply the edited theme (or the previewed preset) as it changes
+    Theme::apply_custom_theme(ui.ctx(), preview.as_ref().unwrap_or(&state.settings.theme_config));
+}
+
+/// Outline the section a search result jumped to
+fn mark_section(
+    ui: &egui::Ui,
+    state: &AppState,
+    section: SettingsSection,
+    response: &egui::Response,
+    theme: &crate::ui::theme::Theme,
+) {
+    if state.search.highlight == Some(SearchTarget::Setting(section)) {
+        search_palette::outline_highlight(ui, response, state, theme);
+    }
+}
+
+/// Render the preset picker and theme file import/export
+///
+/// # Returns
+/// The preset being hovered, to preview
+fn render_presets(
+    ui: &mut egui::Ui,
+    config: &ThemeConfig,
+    app: &mut impl crate::app::AppLike,
+) -> Option<ThemeConfig> {
+    let mut preview = None;
+    let current = ThemePreset::matching(config);
+    ui.horizontal(|ui| {
+        ui.label(tr!("settings.theme.presets"));
+        for preset in ThemePreset::ALL {
+            let response = ui.selectable_label(current == Some(preset), preset.label());
+            if response.clicked() {
+                app.handle_theme_color_change(preset.config());
+            } else if response.hovered() {
+                preview = Some(preset.config());
+            }
+        }
+
+        ui.separator();
+        if ui.button(tr!("settings.theme.import")).on_hover_text(tr!("settings.theme.import_hint")).clicked() {
+            app.handle_theme_import();
+        }
+        if ui.button(tr!("settings.theme.export")).on_hover_text(tr!("settings.theme.export_hint")).clicked() {
+            app.handle_theme_export();
+        }
+    });
+    preview
 }
 
 /// Render color pickers for all theme colors
@@ -48,21 +147,19 @@ fn render_color_pickers(
     _theme: &crate::ui::theme::Theme,
 ) {
     // Primary Colors
-    ui.collapsing("Primary Colors", |ui| {
+    ui.collapsing(tr!("settings.theme.primary_colors"), |ui| {
         ui.horizontal(|ui| {
-            ui.label("Background:");
+            ui.label(tr!("settings.theme.background"));
             let mut color = egui::Color32::from_rgb(config.background[0], config.background[1], config.background[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
                 new_config.background = [color.r(), color.g(), color.b()];
-                app.handle_theme_color_change(new_config.clone());
-                // Apply immediately for preview
-                crate::ui::theme::Theme::apply_custom_theme(ui.ctx(), &new_config);
+                app.handle_theme_color_change(new_config);
             }
         });
 
         ui.horizontal(|ui| {
-            ui.label("Text:");
+            ui.label(tr!("settings.theme.text"));
             let mut color = egui::Color32::from_rgb(config.text[0], config.text[1], config.text[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -72,7 +169,7 @@ fn render_color_pickers(
         });
 
         ui.horizontal(|ui| {
-            ui.label("Primary Red:");
+            ui.label(tr!("settings.theme.red_primary"));
             let mut color = egui::Color32::from_rgb(config.red_primary[0], config.red_primary[1], config.red_primary[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -82,7 +179,7 @@ fn render_color_pickers(
         });
 
         ui.horizontal(|ui| {
-            ui.label("Dark Red:");
+            ui.label(tr!("settings.theme.red_dark"));
             let mut color = egui::Color32::from_rgb(config.red_dark[0], config.red_dark[1], config.red_dark[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -92,7 +189,7 @@ fn render_color_pickers(
         });
 
         ui.horizontal(|ui| {
-            ui.label("Red Highlight:");
+            ui.label(tr!("settings.theme.red_highlight"));
             let mut color = egui::Color32::from_rgb(config.red_highlight[0], config.red_highlight[1], config.red_highlight[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -103,9 +200,9 @@ fn render_color_pickers(
     });
 
     // Border Colors
-    ui.collapsing("Border Colors", |ui| {
+    ui.collapsing(tr!("settings.theme.border_colors"), |ui| {
         ui.horizontal(|ui| {
-            ui.label("Border Dark:");
+            ui.label(tr!("settings.theme.border_dark"));
             let mut color = egui::Color32::from_rgb(config.border_dark[0], config.border_dark[1], config.border_dark[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -115,7 +212,7 @@ fn render_color_pickers(
         });
 
         ui.horizontal(|ui| {
-            ui.label("Border Red Tint:");
+            ui.label(tr!("settings.theme.border_red_tint"));
             let mut color = egui::Color32::from_rgb(config.border_red_tint[0], config.border_red_tint[1], config.border_red_tint[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -126,9 +223,9 @@ fn render_color_pickers(
     });
 
     // Status Colors
-    ui.collapsing("Status Colors", |ui| {
+    ui.collapsing(tr!("settings.theme.status_colors"), |ui| {
         ui.horizontal(|ui| {
-            ui.label("Success Green:");
+            ui.label(tr!("settings.theme.green_success"));
             let mut color = egui::Color32::from_rgb(config.green_success[0], config.green_success[1], config.green_success[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -138,7 +235,7 @@ fn render_color_pickers(
         });
 
         ui.horizontal(|ui| {
-            ui.label("Error Red:");
+            ui.label(tr!("settings.theme.red_error"));
             let mut color = egui::Color32::from_rgb(config.red_error[0], config.red_error[1], config.red_error[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -148,7 +245,7 @@ fn render_color_pickers(
         });
 
         ui.horizontal(|ui| {
-            ui.label("Warning Yellow:");
+            ui.label(tr!("settings.theme.yellow_warning"));
             let mut color = egui::Color32::from_rgb(config.yellow_warning[0], config.yellow_warning[1], config.yellow_warning[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -158,7 +255,7 @@ fn render_color_pickers(
         });
 
         ui.horizontal(|ui| {
-            ui.label("Info Blue:");
+            ui.label(tr!("settings.theme.blue_info"));
             let mut color = egui::Color32::from_rgb(config.blue_info[0], config.blue_info[1], config.blue_info[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -169,9 +266,9 @@ fn render_color_pickers(
     });
 
     // Gray Colors
-    ui.collapsing("Gray Colors", |ui| {
+    ui.collapsing(tr!("settings.theme.gray_colors"), |ui| {
         ui.horizontal(|ui| {
-            ui.label("Gray Inactive:");
+            ui.label(tr!("settings.theme.gray_inactive"));
             let mut color = egui::Color32::from_rgb(config.gray_inactive[0], config.gray_inactive[1], config.gray_inactive[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -181,7 +278,7 @@ fn render_color_pickers(
         });
 
         ui.horizontal(|ui| {
-            ui.label("Gray Secondary:");
+            ui.label(tr!("settings.theme.gray_secondary"));
             let mut color = egui::Color32::from_rgb(config.gray_secondary[0], config.gray_secondary[1], config.gray_secondary[2]);
             if ui.color_edit_button_srgba(&mut color).changed() {
                 let mut new_config = config.clone();
@@ -199,32 +296,25 @@ fn render_actions(
     app: &mut impl crate::app::AppLike,
     theme: &crate::ui::theme::Theme,
 ) {
-    ui.group(|ui| {
+    let response = ui.group(|ui| {
         ui.horizontal(|ui| {
             ui.label(Icons::icon_dim(material::SETTINGS, size::SMALL));
-            ui.heading("Actions");
+            ui.heading(tr!("settings.actions.heading"));
         });
         ui.add_space(10.0);
 
         ui.horizontal(|ui| {
-            if ui.button(format!("{} Save Settings", material::SAVE)).clicked() {
+            // The theme itself is applied at the end of the Settings render
+            if ui.button(format!("{} {}", material::SAVE, tr!("settings.actions.save"))).clicked() {
                 app.handle_settings_save();
-                // Read updated state and apply theme
-                let updated_state = app.state().read();
-                crate::ui::theme::Theme::apply_custom_theme(ui.ctx(), &updated_state.settings.theme_config);
             }
 
-            if ui.button(format!("{} Reset to Defaults", material::REFRESH)).clicked() {
+            if ui.button(format!("{} {}", material::REFRESH, tr!("settings.actions.reset"))).clicked() {
                 app.handle_settings_reset();
-                // Read updated state and apply theme
-                let updated_state = app.state().read();
-                crate::ui::theme::Theme::apply_custom_theme(ui.ctx(), &updated_state.settings.theme_config);
             }
 
-            if ui.button(format!("{} Apply Changes", material::CHECK)).clicked() {
+            if ui.button(format!("{} {}", material::CHECK, tr!("settings.actions.apply"))).clicked() {
                 app.handle_settings_apply();
-                // Apply current theme config
-                crate::ui::theme::Theme::apply_custom_theme(ui.ctx(), &state.settings.theme_config);
             }
         });
 
@@ -234,14 +324,1144 @@ fn render_actions(
         if state.settings.unsaved_changes {
             ui.horizontal(|ui| {
                 ui.label(Icons::icon_warning(material::WARNING, size::SMALL));
-                ui.colored_label(theme.warning, "You have unsaved changes");
+                ui.colored_label(theme.warning, tr!("settings.actions.unsaved"));
             });
         } else {
             ui.horizontal(|ui| {
                 ui.label(Icons::icon_success(material::CHECK, size::SMALL));
-                ui.colored_label(theme.success, "All changes saved");
+                ui.colored_label(theme.success, tr!("settings.actions.saved"));
+            });
+        }
+    }).response;
+    mark_section(ui, state, SettingsSection::Actions, &response, theme);
+}
+
+/// Show which branding file the landing screen uses; it is read at startup only
+fn render_branding(ui: &mut egui::Ui, state: &AppState, theme: &crate::ui::theme::Theme) {
+    use crate::app::branding::BrandingSource;
+
+    ui.horizontal(|ui| {
+        ui.label(Icons::icon_dim(material::INFO, size::SMALL));
+        ui.label(tr!("settings.branding"));
+        let source = &state.branding.source;
+        match source {
+            BrandingSource::Fallback { error, .. } => {
+                ui.colored_label(theme.warning, source.describe()).on_hover_text(error);
+            }
+            _ => {
+                ui.colored_label(theme.dim, source.describe())
+                    .on_hover_text(tr!("settings.branding_hint"));
+            }
+        }
+    });
+}
+
+/// Render the backend server list (primary first, then failover standbys)
+fn render_servers(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    let form = &state.settings.servers;
+    let active = state.api_client.as_ref().map(|c| c.base_url());
+
+    let response = ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_dim(material::NETWORK, size::SMALL));
+            ui.heading(tr!("settings.servers.heading"));
+        });
+        ui.add_space(10.0);
+        ui.label(
+            egui::RichText::new(tr!("settings.servers.description"))
+                .small()
+                .color(theme.dim),
+        );
+        ui.add_space(5.0);
+
+        // Edits are collected and applied after the loop
+        let mut move_up = None;
+        let mut remove = None;
+        let last = form.entries.len().saturating_sub(1);
+        egui::Grid::new("settings_servers").num_columns(4).show(ui, |ui| {
+            for (i, entry) in form.entries.iter().enumerate() {
+                ui.label(if i == 0 { tr!("settings.servers.primary") } else { tr!("settings.servers.backup") });
+                let is_active = active.as_deref() == Some(entry.trim_end_matches('/'));
+                if is_active {
+                    ui.colored_label(theme.success, format!("{} {}", material::CHECK, entry));
+                } else {
+                    ui.label(entry);
+                }
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(i > 0, egui::Button::new(material::ARROW_UP).small()).clicked() {
+                        move_up = Some(i);
+                    }
+                    if ui.add_enabled(i < last, egui::Button::new(material::ARROW_DOWN).small()).clicked() {
+                        move_up = Some(i + 1);
+                    }
+                    if ui.add(egui::Button::new(material::CLOSE).small()).on_hover_text(tr!("settings.servers.remove")).clicked() {
+                        remove = Some(i);
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if let Some(i) = move_up {
+            app.state().write().settings.servers.entries.swap(i - 1, i);
+        }
+        if let Some(i) = remove {
+            app.state().write().settings.servers.entries.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            let mut state_write = app.state().write();
+            let servers = &mut state_write.settings.servers;
+            ui.add(egui::TextEdit::singleline(&mut servers.new_entry).hint_text("https://backup.example.com"));
+            if ui.button(tr!("settings.servers.add")).clicked() && !servers.new_entry.trim().is_empty() {
+                let entry = std::mem::take(&mut servers.new_entry);
+                servers.entries.push(entry.trim().to_string());
+            }
+        });
+
+        ui.add_space(5.0);
+        if ui.button(format!("{} {}", material::SAVE, tr!("settings.servers.save"))).clicked() {
+            app.handle_server_list_save();
+        }
+        if let Some((is_error, message)) = &form.status {
+            ui.horizontal(|ui| {
+                if *is_error {
+                    ui.label(Icons::icon_error(material::ERROR, size::SMALL));
+                    ui.colored_label(theme.error, message);
+                } else {
+                    ui.label(Icons::icon_success(material::CHECK, size::SMALL));
+                    ui.colored_label(theme.success, message);
+                }
+            });
+        }
+
+        ui.add_space(10.0);
+        let mut bandwidth_saver = state.settings.network.bandwidth_saver;
+        if ui
+            .checkbox(&mut bandwidth_saver, tr!("settings.servers.bandwidth_saver"))
+            .on_hover_text(tr!("settings.servers.bandwidth_saver_hint"))
+            .changed()
+        {
+            app.handle_bandwidth_saver_toggle(bandwidth_saver);
+        }
+
+        ui.horizontal(|ui| {
+            let mut stale_secs = state.settings.network.stale_price_secs;
+            ui.label(tr!("settings.servers.stale_prices"));
+            if ui.add(egui::DragValue::new(&mut stale_secs).range(1..=3600).suffix(" s")).changed() {
+                app.handle_stale_price_threshold_change(stale_secs);
+            }
+        })
+        .response
+        .on_hover_text(tr!("settings.servers.stale_prices_hint"));
+
+        ui.horizontal(|ui| {
+            let mut idle_mins = state.settings.network.idle_teardown_mins;
+            ui.label(tr!("settings.servers.idle_teardown"));
+            if ui
+                .add(egui::DragValue::new(&mut idle_mins).range(0..=1440).suffix(" min"))
+                .changed()
+            {
+                app.handle_idle_teardown_change(idle_mins);
+            }
+            ui.label(tr!("settings.servers.idle_teardown_suffix"));
+        })
+        .response
+        .on_hover_text(tr!("settings.servers.idle_teardown_hint"));
+    }).response;
+    mark_section(ui, state, SettingsSection::Servers, &response, theme);
+}
+
+/// Render the network and endpoint settings with "Apply & Reconnect"
+fn render_connection(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    use crate::core::config::{SolanaNetwork, FIELD_API_URL, FIELD_RPC_URL, FIELD_SLIPPAGE, FIELD_WS_URL};
+
+    let form = &state.settings.connection;
+    let config = &state.config;
+    let api_base = state
+        .api_client
+        .as_ref()
+        .map(|c| c.base_url())
+        .unwrap_or_else(|| crate::services::api::failover::DEFAULT_SERVER.to_string());
+
+    let response = ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_dim(material::NETWORK, size::SMALL));
+            ui.heading(tr!("settings.connection.heading"));
+        });
+        ui.add_space(10.0);
+        ui.label(
+            egui::RichText::new(tr!("settings.connection.description"))
+                .small()
+                .color(theme.dim),
+        );
+        ui.add_space(5.0);
+
+        {
+            let mut state_write = app.state().write();
+            let inputs = &mut state_write.settings.connection;
+            egui::Grid::new("settings_connection").num_columns(2).show(ui, |ui| {
+                ui.label(tr!("settings.connection.network"));
+                egui::ComboBox::from_id_salt("settings_network")
+                    .selected_text(inputs.network.label())
+                    .show_ui(ui, |ui| {
+                        for network in SolanaNetwork::ALL {
+                            ui.selectable_value(&mut inputs.network, network, network.label());
+                        }
+                    });
+                ui.end_row();
+
+                let fields = [
+                    (tr!("settings.connection.rpc"), FIELD_RPC_URL, &mut inputs.rpc_url, inputs.network.default_rpc_url().to_string()),
+                    (tr!("settings.connection.api"), FIELD_API_URL, &mut inputs.api_url, api_base.clone()),
+                    (tr!("settings.connection.price_stream"), FIELD_WS_URL, &mut inputs.ws_url, config.price_stream_url(&api_base)),
+                ];
+                for (label, field, value, hint) in fields {
+                    ui.label(label);
+                    ui.add(egui::TextEdit::singleline(value).hint_text(hint).desired_width(320.0));
+                    ui.end_row();
+                    render_field_errors(ui, &form.errors, field, theme);
+                }
+
+                ui.label(tr!("settings.connection.slippage"));
+                ui.add(egui::TextEdit::singleline(&mut inputs.default_slippage_bps).desired_width(80.0));
+                ui.end_row();
+                render_field_errors(ui, &form.errors, FIELD_SLIPPAGE, theme);
+            });
+        }
+
+        ui.add_space(5.0);
+        if form.network != config.network {
+            ui.colored_label(
+                theme.warning,
+                tr!("settings.connection.network_switch", to = form.network.label(), from = config.network.label()),
+            );
+        }
+        if ui.button(format!("{} {}", material::REFRESH, tr!("settings.connection.apply"))).clicked() {
+            app.handle_connection_apply();
+        }
+        if let Some((is_error, message)) = &form.status {
+            ui.horizontal(|ui| {
+                if *is_error {
+                    ui.label(Icons::icon_error(material::ERROR, size::SMALL));
+                    ui.colored_label(theme.error, message);
+                } else {
+                    ui.label(Icons::icon_success(material::CHECK, size::SMALL));
+                    ui.colored_label(theme.success, message);
+                }
+            });
+        }
+    }).response;
+    mark_section(ui, state, SettingsSection::Connection, &response, theme);
+}
+
+/// Grid row with the messages for one rejected Connection field
+fn render_field_errors(
+    ui: &mut egui::Ui,
+    errors: &[shared::validation::ValidationError],
+    field: &str,
+    theme: &crate::ui::theme::Theme,
+) {
+    let messages: Vec<&str> = shared::validation::field_messages(errors, field).collect();
+    if messages.is_empty() {
+        return;
+    }
+    ui.label("");
+    ui.label(egui::RichText::new(messages.join("\n")).small().color(theme.error));
+    ui.end_row();
+}
+
+/// Render portfolio risk warning thresholds
+fn render_risk(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    let response = ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_dim(material::WARNING, size::SMALL));
+            ui.heading(tr!("settings.risk.heading"));
+        });
+        ui.colored_label(theme.dim, tr!("settings.risk.description"));
+        ui.add_space(10.0);
+
+        let mut thresholds = state.settings.risk.clone();
+        egui::Grid::new("settings_risk_thresholds").num_columns(2).show(ui, |ui| {
+            ui.label(tr!("settings.risk.max_position"));
+            ui.add(egui::DragValue::new(&mut thresholds.max_position_pct).range(1.0..=100.0).speed(0.5).suffix("%"))
+                .on_hover_text(tr!("settings.risk.max_position_hint"));
+            ui.end_row();
+
+            ui.label(tr!("settings.risk.max_hhi"));
+            ui.add(egui::DragValue::new(&mut thresholds.max_hhi).range(1.0..=10_000.0).speed(50.0))
+                .on_hover_text(tr!("settings.risk.max_hhi_hint"));
+            ui.end_row();
+
+            ui.label(tr!("settings.risk.max_volume"));
+            ui.add(egui::DragValue::new(&mut thresholds.max_volume_multiple).range(0.001..=100.0).speed(0.01).suffix("x"))
+                .on_hover_text(tr!("settings.risk.max_volume_hint"));
+            ui.end_row();
+        });
+        if thresholds != state.settings.risk {
+            app.handle_risk_thresholds_change(thresholds);
+        }
+    }).response;
+    mark_section(ui, state, SettingsSection::Risk, &response, theme);
+}
+
+/// Render which categories of backend notices are shown
+fn render_notifications(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    let response = ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_dim(material::INFO, size::SMALL));
+            ui.heading(tr!("settings.notifications.heading"));
+        });
+        ui.colored_label(theme.dim, tr!("settings.notifications.description"));
+        ui.add_space(10.0);
+
+        for category in shared::NoticeCategory::ALL {
+            let mut enabled = state.settings.notifications.is_enabled(category);
+            if ui.checkbox(&mut enabled, category.label()).changed() {
+                app.handle_notification_category_toggle(category, enabled);
+            }
+        }
+    }).response;
+    mark_section(ui, state, SettingsSection::Notifications, &response, theme);
+}
+
+/// Render the keyboard shortcuts with "press a key to rebind" capture
+fn render_shortcuts(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    let bindings = &state.settings.key_bindings;
+    let capture = &state.settings.key_capture;
+
+    // The key pressed while an action waits for its shortcut; Escape cancels
+    if let Some(action) = capture.action {
+        let pressed = ui.input(|i| KeyCombo::captured(i));
+        match pressed {
+            Some(combo) if combo.key == egui::Key::Escape => app.handle_key_capture(None),
+            Some(combo) => app.handle_key_binding_change(action, combo),
+            None => {}
+        }
+    }
+
+    let response = ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_dim(material::KEYBOARD, size::SMALL));
+            ui.heading(tr!("settings.shortcuts.heading"));
+        });
+        ui.colored_label(theme.dim, tr!("settings.shortcuts.description"));
+        ui.add_space(10.0);
+
+        let conflicts = bindings.conflicts();
+        egui::Grid::new("settings_shortcuts").num_columns(2).show(ui, |ui| {
+            for action in KeyAction::ALL {
+                ui.label(action.label());
+                let capturing = capture.action == Some(action);
+                let text = if capturing { tr!("settings.shortcuts.capturing").to_string() } else { bindings.get(action).to_string() };
+                let mut button = egui::Button::new(egui::RichText::new(text).monospace()).selected(capturing);
+                if conflicts.contains(&action) {
+                    button = button.stroke(egui::Stroke::new(1.0, theme.warning));
+                }
+                let response = ui.add(button);
+                let response = if conflicts.contains(&action) {
+                    response.on_hover_text(tr!("settings.shortcuts.conflict"))
+                } else {
+                    response
+                };
+                if response.clicked() {
+                    app.handle_key_capture((!capturing).then_some(action));
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(error) = &capture.error {
+            ui.colored_label(theme.error, error);
+        }
+        ui.add_space(5.0);
+        if ui.button(tr!("settings.shortcuts.reset")).clicked() {
+            app.handle_key_bindings_reset();
+        }
+    }).response;
+    mark_section(ui, state, SettingsSection::Shortcuts, &response, theme);
+}
+
+/// Render the language selector; the new language shows on the next frame
+fn render_language(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    let response = ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_dim(material::LANGUAGE, size::SMALL));
+            ui.heading(tr!("settings.language.heading"));
+        });
+        ui.colored_label(
+            theme.dim,
+            tr!("settings.language.description", sample = i18n::format_decimal(1234.56, 2)),
+        );
+        ui.add_space(10.0);
+
+        let mut locale = state.settings.locale;
+        egui::ComboBox::from_id_salt("settings_language")
+            .selected_text(locale.native_name())
+            .show_ui(ui, |ui| {
+                for option in Locale::ALL {
+                    ui.selectable_value(&mut locale, option, option.native_name());
+                }
+            });
+        if locale != state.settings.locale {
+            app.handle_locale_change(locale);
+        }
+    }).response;
+    mark_section(ui, state, SettingsSection::Language, &response, theme);
+}
+
+/// Render the usage analytics opt-in, the next upload and its deletion
+fn render_privacy(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    let form = &state.settings.privacy;
+
+    let response = ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_dim(material::LOCK, size::SMALL));
+            ui.heading(tr!("settings.privacy.heading"));
+        });
+        ui.colored_label(
+            theme.dim,
+            tr!("settings.privacy.description"),
+        );
+        ui.add_space(10.0);
+
+        let mut enabled = form.usage.enabled;
+        if ui.checkbox(&mut enabled, tr!("settings.privacy.share")).changed() {
+            app.handle_usage_analytics_toggle(enabled);
+        }
+        if enabled {
+            ui.colored_label(theme.dim, tr!("settings.privacy.share_hint"));
+        }
+
+        if let Some(install_id) = &form.usage.install_id {
+            ui.horizontal(|ui| {
+                ui.colored_label(theme.dim, tr!("settings.privacy.install_id"));
+                ui.monospace(install_id);
+            });
+        }
+
+        if enabled {
+            ui.collapsing(tr!("settings.privacy.next_upload"), |ui| match &form.preview {
+                Some(json) => {
+                    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        ui.add(egui::Label::new(egui::RichText::new(json).monospace()).selectable(true));
+                    });
+                }
+                None => {
+                    ui.colored_label(theme.dim, tr!("settings.privacy.nothing_to_upload"));
+                }
+            });
+        }
+
+        ui.add_space(5.0);
+        let delete = ui
+            .add_enabled(!form.deleting, egui::Button::new(format!("{} {}", material::CLOSE, tr!("settings.privacy.delete"))))
+            .on_hover_text(tr!("settings.privacy.delete_hint"));
+        if delete.clicked() {
+            app.handle_usage_data_delete();
+        }
+        if form.deleting {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.colored_label(theme.dim, tr!("settings.privacy.deleting"));
+            });
+        } else if let Some((is_error, message)) = &form.status {
+            ui.horizontal(|ui| {
+                if *is_error {
+                    ui.label(Icons::icon_error(material::ERROR, size::SMALL));
+                    ui.colored_label(theme.error, message);
+                } else {
+                    ui.label(Icons::icon_success(material::CHECK, size::SMALL));
+                    ui.colored_label(theme.success, message);
+                }
             });
         }
+    }).response;
+    mark_section(ui, state, SettingsSection::Privacy, &response, theme);
+}
+
+/// Render account section (change password, update email)
+fn render_account(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    let form = &state.settings.account;
+
+    let response = ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_dim(material::LOCK, size::SMALL));
+            ui.heading(tr!("settings.account.heading"));
+        });
+        ui.add_space(10.0);
+
+        ui.collapsing(tr!("settings.account.change_password"), |ui| {
+            egui::Grid::new("account_password").num_columns(2).show(ui, |ui| {
+                password_row(ui, app, tr!("settings.account.current_password"), |form| &mut form.current_password);
+                password_row(ui, app, tr!("settings.account.new_password"), |form| &mut form.new_password);
+                password_row(ui, app, tr!("settings.account.confirm_password"), |form| &mut form.confirm_password);
+            });
+            ui.label(
+                egui::RichText::new(tr!("settings.account.password_hint"))
+                    .small()
+                    .color(theme.dim),
+            );
+            if ui
+                .add_enabled(!form.pending, egui::Button::new(format!("{} {}", material::LOCK, tr!("settings.account.change_password"))))
+                .clicked()
+            {
+                app.handle_change_password_click();
+            }
+        });
+
+        ui.collapsing(tr!("settings.account.email"), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr!("settings.account.new_email"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut app.state().write().settings.account.email)
+                        .hint_text("you@example.com"),
+                );
+                if ui
+                    .add_enabled(!form.pending, egui::Button::new(format!("{} {}", material::SAVE, tr!("settings.account.update_email"))))
+                    .clicked()
+                {
+                    app.handle_update_profile_click();
+                }
+            });
+        });
+
+        if form.pending {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.colored_label(theme.dim, tr!("settings.account.saving"));
+            });
+        } else if let Some((is_error, message)) = &form.status {
+            ui.horizontal(|ui| {
+                if *is_error {
+                    ui.label(Icons::icon_error(material::ERROR, size::SMALL));
+                    ui.colored_label(theme.error, message);
+                } else {
+                    ui.label(Icons::icon_success(material::CHECK, size::SMALL));
+                    ui.colored_label(theme.success, message);
+                }
+            });
+        }
+    }).response;
+    mark_section(ui, state, SettingsSection::Account, &response, theme);
+}
+
+/// Masked text field bound to one of the account form's password fields
+fn password_row(
+    ui: &mut egui::Ui,
+    app: &mut impl crate::app::AppLike,
+    label: &str,
+    field: fn(&mut crate::app::AccountFormState) -> &mut String,
+) {
+    ui.label(label);
+    let mut app_state = app.state().write();
+    ui.add(egui::TextEdit::singleline(field(&mut app_state.settings.account)).password(true));
+    ui.end_row();
+}
+
+/// Login token countdown with a manual refresh
+fn render_login_session(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    use crate::services::session::{format_countdown, SessionStatus};
+
+    ui.label(egui::RichText::new(tr!("settings.session.heading")).strong());
+    let Some(expires_at) = state.session_expires_at() else {
+        ui.colored_label(theme.dim, tr!("settings.session.logged_out"));
+        return;
+    };
+    let now = chrono::Utc::now().timestamp();
+    ui.horizontal(|ui| {
+        let until = chrono::DateTime::from_timestamp(expires_at, 0)
+            .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_default();
+        match SessionStatus::at(expires_at, now) {
+            SessionStatus::Valid => {
+                ui.label(tr!("settings.session.expires_in", time = format_countdown(expires_at - now)));
+            }
+            SessionStatus::ExpiringSoon => {
+                ui.colored_label(theme.warning, tr!("settings.session.expires_in", time = format_countdown(expires_at - now)));
+            }
+            SessionStatus::Expired => {
+                ui.colored_label(theme.error, tr!("settings.session.expired"));
+            }
+        }
+        ui.colored_label(theme.dim, format!("({})", until));
+
+        let refreshable = expires_at > now && !state.session.refreshing;
+        if ui
+            .add_enabled(refreshable, egui::Button::new(format!("{} {}", material::REFRESH, tr!("common.refresh"))))
+            .on_hover_text(tr!("settings.session.refresh_hint"))
+            .clicked()
+        {
+            app.handle_session_refresh();
+        }
+        if state.session.refreshing {
+            ui.spinner();
+        }
+    });
+    if let Some(err) = &state.session.refresh_error {
+        ui.colored_label(theme.error, tr!("settings.session.refresh_failed", error = err));
+    }
+    ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+}
+
+/// Render security section (session signer grants, the auto-sign audit log and the activity timeline)
+fn render_security(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    use shared::utils::truncate_address;
+
+    let security = &state.security;
+    let now = chrono::Utc::now();
+
+    let response = ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_dim(material::LOCK, size::SMALL));
+            ui.heading(tr!("settings.security.heading"));
+        });
+        ui.add_space(10.0);
+
+        render_login_session(ui, state, app, theme);
+        ui.add_space(10.0);
+
+        ui.label(egui::RichText::new(tr!("settings.security.grants")).strong());
+        if security.grants.is_empty() {
+            ui.colored_label(theme.dim, tr!("settings.security.no_grants"));
+        }
+        for grant in &security.grants {
+            ui.horizontal(|ui| {
+                let expired = !grant.is_active(now);
+                ui.label(egui::RichText::new(&grant.label).strong());
+                ui.colored_label(
+                    theme.dim,
+                    tr!(
+                        "settings.security.grant_summary",
+                        amount = grant.max_amount,
+                        mint = truncate_address(&grant.input_mint),
+                        destinations = grant.allowed_mints.iter().map(|m| truncate_address(m)).collect::<Vec<_>>().join(", "),
+                    ),
+                );
+                if expired {
+                    ui.colored_label(theme.warning, tr!("settings.security.expired"));
+                } else {
+                    ui.colored_label(theme.dim, tr!("settings.security.until", time = grant.expires_at.format("%Y-%m-%d %H:%M UTC")));
+                }
+                if ui.button(format!("{} {}", material::CLOSE, tr!("common.revoke"))).clicked() {
+                    app.handle_revoke_grant(grant.id);
+                }
+            });
+        }
+
+        ui.add_space(10.0);
+        ui.collapsing(tr!("settings.security.grant_heading"), |ui| {
+            egui::Grid::new("security_grant").num_columns(2).show(ui, |ui| {
+                grant_row(ui, app, tr!("common.label_field"), "SOL DCA", |form| &mut form.label_input);
+                grant_row(ui, app, tr!("settings.security.input_mint"), tr!("settings.security.input_mint_hint"), |form| &mut form.input_mint_input);
+                grant_row(ui, app, tr!("settings.security.max_amount"), tr!("settings.security.max_amount_hint"), |form| &mut form.max_amount_input);
+                grant_row(ui, app, tr!("settings.security.allowed_mints"), tr!("settings.security.comma_separated"), |form| &mut form.allowed_mints_input);
+                grant_row(ui, app, tr!("settings.security.allowed_programs"), tr!("settings.security.comma_separated"), |form| &mut form.allowed_programs_input);
+                grant_row(ui, app, tr!("settings.security.expiry"), "60", |form| &mut form.expiry_minutes_input);
+
+                ui.label(tr!("common.keystore_password"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut app.state().write().security.password_input).password(true),
+                );
+                ui.end_row();
+            });
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!security.granting, egui::Button::new(format!("{} {}", material::UNLOCK, tr!("settings.security.grant"))))
+                    .clicked()
+                {
+                    app.handle_grant_session();
+                }
+                if security.granting {
+                    ui.spinner();
+                }
+            });
+        });
+
+        ui.collapsing(tr!("settings.security.audit_log", count = security.audit_log.len()), |ui| {
+            if security.audit_log.is_empty() {
+                ui.colored_label(theme.dim, tr!("settings.security.no_audit"));
+            }
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                for entry in security.audit_log.iter().rev() {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(theme.dim, entry.at.format("%m-%d %H:%M:%S").to_string());
+                        ui.label(format!(
+                            "{} {} -> {}",
+                            entry.amount,
+                            truncate_address(&entry.input_mint),
+                            truncate_address(&entry.destination_mint),
+                        ));
+                        match (&entry.outcome, &entry.grant) {
+                            (Ok(signature), Some((id, label))) => {
+                                ui.colored_label(theme.success, tr!("settings.security.signed_via", id = id, label = label));
+                                ui.colored_label(theme.dim, truncate_address(signature));
+                            }
+                            (Ok(signature), None) => {
+                                ui.colored_label(theme.success, truncate_address(signature));
+                            }
+                            (Err(reason), _) => {
+                                ui.colored_label(theme.error, reason);
+                            }
+                        }
+                    });
+                }
+            });
+        });
+
+        render_activity_timeline(ui, state, app, theme);
+    }).response;
+    mark_section(ui, state, SettingsSection::Security, &response, theme);
+}
+
+/// Render the audit trail as a filterable timeline, newest first
+fn render_activity_timeline(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    use crate::app::audit::{self, AuditCategory, AuditReference};
+    use shared::utils::truncate_address;
+
+    let security = &state.security;
+    ui.collapsing(tr!("settings.activity.heading", count = security.trail.entries().len()), |ui| {
+        ui.horizontal(|ui| {
+            let mut category = security.trail_filter.category;
+            egui::ComboBox::from_id_salt("audit_trail_category")
+                .selected_text(category.map_or(tr!("settings.activity.all"), AuditCategory::label))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut category, None, tr!("settings.activity.all"));
+                    for option in AuditCategory::ALL {
+                        ui.selectable_value(&mut category, Some(option), option.label());
+                    }
+                });
+            if category != security.trail_filter.category {
+                app.state().write().security.trail_filter.category = category;
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut app.state().write().security.trail_filter.query)
+                    .hint_text(tr!("settings.activity.filter"))
+                    .desired_width(220.0),
+            );
+        });
+        ui.add_space(5.0);
+
+        let mut entries = audit::timeline(security.trail.entries(), &security.trail_filter).peekable();
+        if entries.peek().is_none() {
+            ui.colored_label(theme.dim, tr!("settings.activity.none"));
+        }
+        let mut open_transaction = None;
+        egui::ScrollArea::vertical().id_salt("audit_trail").max_height(260.0).show(ui, |ui| {
+            for entry in entries {
+                ui.horizontal(|ui| {
+                    ui.colored_label(theme.dim, entry.at.format("%m-%d %H:%M:%S").to_string());
+                    ui.label(egui::RichText::new(entry.category.label()).strong());
+                    ui.label(&entry.summary);
+                    for reference in &entry.references {
+                        match reference {
+                            AuditReference::Transaction(signature) => {
+                                if ui.link(truncate_address(signature)).on_hover_text(tr!("settings.activity.open_transaction")).clicked() {
+                                    open_transaction = Some(signature.clone());
+                                }
+                            }
+                            AuditReference::Request(id) => {
+                                ui.colored_label(theme.dim, id);
+                            }
+                            AuditReference::Account(address) => {
+                                ui.colored_label(theme.dim, truncate_address(address));
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        if let Some(signature) = open_transaction {
+            app.handle_search_select(SearchTarget::Transaction { signature });
+        }
     });
 }
 
+/// Render webhooks section (registered webhooks, add form, delivery log)
+fn render_webhooks(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    use shared::dto::webhooks::{WebhookEventType, MAX_WEBHOOKS_PER_USER};
+
+    let webhooks = &state.webhooks;
+    if !webhooks.loaded && !webhooks.loading {
+        app.handle_webhooks_refresh();
+    }
+
+    let response = ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_dim(material::SEND, size::SMALL));
+            ui.heading(tr!("settings.webhooks.heading"));
+            if ui
+                .add_enabled(!webhooks.loading, egui::Button::new(material::REFRESH))
+                .on_hover_text(tr!("settings.webhooks.reload"))
+                .clicked()
+            {
+                app.handle_webhooks_refresh();
+            }
+            if webhooks.loading {
+                ui.spinner();
+            }
+        });
+        ui.label(
+            egui::RichText::new(tr!("settings.webhooks.description"))
+                .small()
+                .color(theme.dim),
+        );
+        ui.add_space(10.0);
+
+        if let Some((_, secret)) = &webhooks.new_secret {
+            ui.horizontal(|ui| {
+                ui.colored_label(theme.warning, tr!("settings.webhooks.secret"));
+                ui.monospace(secret);
+                if ui.small_button(tr!("common.copy")).clicked() {
+                    ui.ctx().copy_text(secret.clone());
+                }
+                if ui.small_button(material::CLOSE).on_hover_text(tr!("common.hide")).clicked() {
+                    app.state().write().webhooks.new_secret = None;
+                }
+            });
+            ui.add_space(6.0);
+        }
+
+        if webhooks.loaded && webhooks.webhooks.is_empty() {
+            ui.colored_label(theme.dim, tr!("settings.webhooks.none"));
+        }
+        for webhook in &webhooks.webhooks {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(&webhook.url).strong());
+                let events: Vec<&str> = webhook.events.iter().map(|e| e.label()).collect();
+                ui.colored_label(theme.dim, events.join(", "));
+                if let Some(threshold) = webhook.balance_threshold_sol {
+                    ui.colored_label(theme.dim, format!("@ {} SOL", threshold));
+                }
+                if ui.button(format!("{} {}", material::HISTORY, tr!("settings.webhooks.deliveries"))).clicked() {
+                    app.handle_webhook_deliveries(webhook.id);
+                }
+                let testing = webhooks.testing == Some(webhook.id);
+                if ui
+                    .add_enabled(webhooks.testing.is_none(), egui::Button::new(format!("{} {}", material::SEND, tr!("settings.webhooks.test"))))
+                    .clicked()
+                {
+                    app.handle_webhook_test(webhook.id);
+                }
+                if testing {
+                    ui.spinner();
+                }
+                if ui.button(format!("{} {}", material::CLOSE, tr!("common.delete"))).clicked() {
+                    app.handle_webhook_delete(webhook.id);
+                }
+            });
+        }
+
+        if let Some(selected) = webhooks.selected {
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(tr!("settings.webhooks.delivery_log")).strong());
+                if ui
+                    .add_enabled(!webhooks.deliveries_loading, egui::Button::new(material::REFRESH))
+                    .clicked()
+                {
+                    app.handle_webhook_deliveries(selected);
+                }
+                if webhooks.deliveries_loading {
+                    ui.spinner();
+                }
+            });
+            if webhooks.deliveries.is_empty() && !webhooks.deliveries_loading {
+                ui.colored_label(theme.dim, tr!("settings.webhooks.no_deliveries"));
+            }
+            egui::ScrollArea::vertical().id_salt("webhook_deliveries").max_height(200.0).show(ui, |ui| {
+                for delivery in &webhooks.deliveries {
+                    ui.horizontal(|ui| {
+                        let at = chrono::DateTime::parse_from_rfc3339(&delivery.created_at)
+                            .map(|at| at.format("%m-%d %H:%M:%S").to_string())
+                            .unwrap_or_else(|_| delivery.created_at.clone());
+                        ui.colored_label(theme.dim, at);
+                        ui.label(delivery.event.label());
+                        let status = delivery.response_status.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string());
+                        if delivery.delivered {
+                            ui.colored_label(theme.success, tr!("settings.webhooks.delivered", status = status));
+                        } else {
+                            ui.colored_label(
+                                theme.error,
+                                tr!("settings.webhooks.failed", error = delivery.error.as_deref().unwrap_or(tr!("settings.webhooks.no_answer"))),
+                            );
+                        }
+                        ui.colored_label(
+                            theme.dim,
+                            if delivery.attempts == 1 {
+                                tr!("settings.webhooks.attempt", count = delivery.attempts)
+                            } else {
+                                tr!("settings.webhooks.attempts", count = delivery.attempts)
+                            },
+                        );
+                    });
+                }
+            });
+        }
+
+        ui.add_space(10.0);
+        ui.collapsing(tr!("settings.webhooks.add"), |ui| {
+            egui::Grid::new("webhook_form").num_columns(2).show(ui, |ui| {
+                ui.label(tr!("settings.webhooks.url"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut app.state().write().webhooks.url_input)
+                        .hint_text("https://example.com/hooks/xforce"),
+                );
+                ui.end_row();
+
+                ui.label(tr!("settings.webhooks.events"));
+                ui.vertical(|ui| {
+                    for event in WebhookEventType::SUBSCRIBABLE {
+                        let mut checked = webhooks.events_input.contains(&event);
+                        if ui.checkbox(&mut checked, event.label()).changed() {
+                            let mut app_state = app.state().write();
+                            let events = &mut app_state.webhooks.events_input;
+                            if checked {
+                                events.push(event);
+                            } else {
+                                events.retain(|e| *e != event);
+                            }
+                        }
+                    }
+                });
+                ui.end_row();
+
+                if webhooks.events_input.contains(&WebhookEventType::BalanceThreshold) {
+                    ui.label(tr!("settings.webhooks.threshold"));
+                    ui.add(egui::TextEdit::singleline(&mut app.state().write().webhooks.threshold_input).hint_text("1.5"));
+                    ui.end_row();
+                }
+            });
+            ui.horizontal(|ui| {
+                let full = webhooks.webhooks.len() >= MAX_WEBHOOKS_PER_USER;
+                if ui
+                    .add_enabled(!webhooks.creating && !full, egui::Button::new(format!("{} {}", material::SAVE, tr!("settings.webhooks.add"))))
+                    .clicked()
+                {
+                    app.handle_webhook_create();
+                }
+                if webhooks.creating {
+                    ui.spinner();
+                }
+                if full {
+                    ui.colored_label(theme.dim, tr!("settings.webhooks.limit", max = MAX_WEBHOOKS_PER_USER));
+                }
+            });
+        });
+
+        if let Some(err) = &webhooks.error {
+            ui.horizontal(|ui| {
+                ui.label(Icons::icon_error(material::ERROR, size::SMALL));
+                ui.colored_label(theme.error, err);
+            });
+        }
+    }).response;
+    mark_section(ui, state, SettingsSection::Webhooks, &response, theme);
+}
+
+/// Render API keys section (the user's keys with revoke buttons, create form)
+fn render_api_keys(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl crate::app::AppLike,
+    theme: &crate::ui::theme::Theme,
+) {
+    use shared::dto::api_keys::{ApiKeyScope, API_KEY_HEADER, MAX_API_KEYS_PER_USER};
+
+    let api_keys = &state.api_keys;
+    if !api_keys.loaded && !api_keys.loading {
+        app.handle_api_keys_refresh();
+    }
+
+    let response = ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_dim(material::LOCK, size::SMALL));
+            ui.heading(tr!("settings.api_keys.heading"));
+            if ui
+                .add_enabled(!api_keys.loading, egui::Button::new(material::REFRESH))
+                .on_hover_text(tr!("settings.api_keys.reload"))
+                .clicked()
+            {
+                app.handle_api_keys_refresh();
+            }
+            if api_keys.loading {
+                ui.spinner();
+            }
+        });
+        ui.label(
+            egui::RichText::new(tr!("settings.api_keys.description", header = API_KEY_HEADER))
+            .small()
+            .color(theme.dim),
+        );
+        ui.add_space(10.0);
+
+        if let Some((_, key)) = &api_keys.new_key {
+            ui.horizontal(|ui| {
+                ui.colored_label(theme.warning, tr!("settings.api_keys.new_key"));
+                ui.monospace(key);
+                if ui.small_button(tr!("common.copy")).clicked() {
+                    ui.ctx().copy_text(key.clone());
+                }
+                if ui.small_button(material::CLOSE).on_hover_text(tr!("common.hide")).clicked() {
+                    app.state().write().api_keys.new_key = None;
+                }
+            });
+            ui.add_space(6.0);
+        }
+
+        if api_keys.loaded && api_keys.keys.is_empty() {
+            ui.colored_label(theme.dim, tr!("settings.api_keys.none"));
+        }
+        if !api_keys.keys.is_empty() {
+            egui::Grid::new("api_keys").num_columns(5).spacing([12.0, 6.0]).striped(true).show(ui, |ui| {
+                ui.strong(tr!("common.label"));
+                ui.strong(tr!("settings.api_keys.key"));
+                ui.strong(tr!("settings.api_keys.scopes"));
+                ui.strong(tr!("settings.api_keys.last_used"));
+                ui.label("");
+                ui.end_row();
+
+                for key in &api_keys.keys {
+                    ui.label(&key.label);
+                    ui.monospace(format!("{}…", key.prefix));
+                    if key.can_write() {
+                        ui.colored_label(theme.warning, ApiKeyScope::Write.label());
+                    } else {
+                        ui.label(ApiKeyScope::Read.label());
+                    }
+                    let last_used = key
+                        .last_used_at
+                        .as_deref()
+                        .map(|at| {
+                            chrono::DateTime::parse_from_rfc3339(at)
+                                .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+                                .unwrap_or_else(|_| at.to_string())
+                        })
+                        .unwrap_or_else(|| tr!("settings.api_keys.never").to_string());
+                    ui.colored_label(theme.dim, last_used);
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(api_keys.revoking.is_none(), egui::Button::new(format!("{} {}", material::CLOSE, tr!("common.revoke"))))
+                            .clicked()
+                        {
+                            app.handle_api_key_revoke(key.id);
+                        }
+                        if api_keys.revoking == Some(key.id) {
+                            ui.spinner();
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        }
+
+        ui.add_space(10.0);
+        ui.collapsing(tr!("settings.api_keys.create_heading"), |ui| {
+            egui::Grid::new("api_key_form").num_columns(2).show(ui, |ui| {
+                ui.label(tr!("common.label_field"));
+                ui.add(egui::TextEdit::singleline(&mut app.state().write().api_keys.label_input).hint_text(tr!("settings.api_keys.label_hint")));
+                ui.end_row();
+
+                ui.label(tr!("settings.api_keys.access"));
+                ui.checkbox(&mut app.state().write().api_keys.write_input, tr!("settings.api_keys.write"));
+                ui.end_row();
+            });
+            ui.horizontal(|ui| {
+                let full = api_keys.keys.len() >= MAX_API_KEYS_PER_USER;
+                if ui
+                    .add_enabled(!api_keys.creating && !full, egui::Button::new(format!("{} {}", material::SAVE, tr!("settings.api_keys.create"))))
+                    .clicked()
+                {
+                    app.handle_api_key_create();
+                }
+                if api_keys.creating {
+                    ui.spinner();
+                }
+                if full {
+                    ui.colored_label(theme.dim, tr!("settings.api_keys.limit", max = MAX_API_KEYS_PER_USER));
+                }
+            });
+        });
+
+        if let Some(err) = &api_keys.error {
+            ui.horizontal(|ui| {
+                ui.label(Icons::icon_error(material::ERROR, size::SMALL));
+                ui.colored_label(theme.error, err);
+            });
+        }
+    }).response;
+    mark_section(ui, state, SettingsSection::ApiKeys, &response, theme);
+}
+
+/// Text field bound to one of the grant form inputs
+fn grant_row(
+    ui: &mut egui::Ui,
+    app: &mut impl crate::app::AppLike,
+    label: &str,
+    hint: &str,
+    field: fn(&mut crate::app::SecurityState) -> &mut String,
+) {
+    ui.label(label);
+    let mut app_state = app.state().write();
+    ui.add(egui::TextEdit::singleline(field(&mut app_state.security)).hint_text(hint));
+    ui.end_row();
+}
@@ -1,21 +1,31 @@
 //! # Swap History Screen
 //!
 //! Displays past swap transactions using egui widgets.
+//!
+//! Filtering and paging happen on the backend: the controls above the table
+//! edit `SwapState::history_filters` and refetch. Date edits apply on Enter or
+//! "Apply"; token and status dropdowns apply immediately. "Export CSV" writes
+//! every matching swap to a file, streamed from the backend.
 
 use egui;
-use crate::app::AppState;
+use crate::app::{history_page_count, AppLike, AppState, SwapHistoryFilters, HISTORY_PAGE_SIZE};
 use crate::ui::theme::Theme;
 use crate::ui::widgets::tables;
 
+const STATUSES: &[&str] = &["pending", "confirmed", "failed"];
+
 /// Render swap history content
-pub fn render(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
+pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
     // Check which tab is active
     use crate::app::SwapTab;
     if state.terminal.swap.active_tab != SwapTab::History {
         return;
     }
 
-    // Draw stats
+    render_filters(ui, state, app, theme);
+    ui.add_space(5.0);
+
+    // Draw stats (status counts are for the visible page)
     let swap_count = state.terminal.swap.swap_history.len();
     let successful = state
         .terminal
@@ -34,7 +44,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
     let failed = swap_count - successful - pending;
 
     tables::render_stats_summary(ui, &[
-        ("Total", swap_count),
+        ("Matching", state.terminal.swap.history_total),
         ("Success", successful),
         ("Pending", pending),
         ("Failed", failed),
@@ -48,9 +58,17 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
         tables::render_empty_state(
             ui,
             "No swap history available",
-            Some("Execute your first swap to see it here!"),
+            Some(if state.terminal.swap.history_filters == SwapHistoryFilters::default() {
+                "Execute your first swap to see it here!"
+            } else {
+                "No swaps match these filters"
+            }),
             theme,
         );
+        // An out-of-range page still needs a way back
+        if state.terminal.swap.history_filters.page > 0 {
+            render_pager(ui, state, app);
+        }
         return;
     }
 
@@ -70,7 +88,9 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
         |ui| {
             // Rows
             for swap in &state.terminal.swap.swap_history {
-                let time_ago = format!("{}s ago", swap.timestamp % 3600); // Simplified
+                let time = chrono::DateTime::from_timestamp(swap.timestamp, 0)
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "—".to_string());
                 let status_color = match swap.status.to_lowercase().as_str() {
                     "success" | "confirmed" => theme.success,
                     "pending" => theme.warning,
@@ -84,7 +104,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
                     swap.signature.clone()
                 };
 
-                ui.label(time_ago);
+                ui.label(time);
                 ui.label(&swap.input_symbol);
                 ui.label(&swap.output_symbol);
                 ui.label(format!("{:.4}", swap.input_amount));
@@ -95,4 +115,111 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
             }
         },
     );
+
+    ui.add_space(5.0);
+    render_pager(ui, state, app);
+}
+
+/// Render date range, token and status filters
+fn render_filters(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    let current = &state.terminal.swap.history_filters;
+    let mut filters = current.clone();
+    let mut apply = false;
+
+    ui.horizontal_wrapped(|ui| {
+        for (label, date) in [("From", &mut filters.from_date), ("To", &mut filters.to_date)] {
+            ui.label(label);
+            let response = ui.add(egui::TextEdit::singleline(date).hint_text("YYYY-MM-DD").desired_width(90.0));
+            apply |= response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        }
+
+        let tokens = &state.terminal.swap.token_list;
+        for (salt, label, mint) in [
+            ("swap_history_input", "From token", &mut filters.input_mint),
+            ("swap_history_output", "To token", &mut filters.output_mint),
+        ] {
+            let selected = mint
+                .as_deref()
+                .map(|m| tokens.iter().find(|t| t.mint == m).map_or_else(|| shared::truncate_address(m), |t| t.symbol.clone()))
+                .unwrap_or_else(|| "Any".to_string());
+            ui.label(label);
+            egui::ComboBox::from_id_salt(salt)
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    apply |= ui.selectable_value(mint, None, "Any").changed();
+                    for token in tokens {
+                        apply |= ui.selectable_value(mint, Some(token.mint.clone()), &token.symbol).changed();
+                    }
+                });
+        }
+
+        ui.label("Status");
+        egui::ComboBox::from_id_salt("swap_history_status")
+            .selected_text(filters.status.as_deref().unwrap_or("Any"))
+            .show_ui(ui, |ui| {
+                apply |= ui.selectable_value(&mut filters.status, None, "Any").changed();
+                for status in STATUSES {
+                    apply |= ui.selectable_value(&mut filters.status, Some(status.to_string()), *status).changed();
+                }
+            });
+
+        apply |= ui.button("Apply").clicked();
+        if ui.add_enabled(*current != SwapHistoryFilters::default(), egui::Button::new("Clear")).clicked() {
+            filters = SwapHistoryFilters::default();
+            apply = true;
+        }
+        if state.terminal.swap.history_loading {
+            ui.spinner();
+        }
+
+        ui.separator();
+        match state.terminal.swap.history_export {
+            Some(written) => {
+                ui.spinner();
+                ui.label(format!("Exporting… {} swaps", written));
+            }
+            None => {
+                if ui.button("Export CSV").on_hover_text("Every swap matching the filters, not just this page").clicked() {
+                    app.handle_swap_history_export();
+                }
+            }
+        }
+    });
+
+    if let Some(err) = &state.terminal.swap.history_error {
+        ui.colored_label(theme.error, err);
+    }
+
+    if filters != *current {
+        // Any filter change starts over at the first page
+        filters.page = 0;
+        app.state().write().terminal.swap.history_filters = filters;
+    }
+    if apply {
+        app.handle_swap_history_refresh();
+    }
+}
+
+/// Render previous/next page controls
+fn render_pager(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
+    let swap = &state.terminal.swap;
+    let page = swap.history_filters.page;
+    let pages = history_page_count(swap.history_total);
+
+    ui.horizontal(|ui| {
+        let mut target = None;
+        if ui.add_enabled(page > 0 && !swap.history_loading, egui::Button::new("◀ Prev")).clicked() {
+            target = Some(page - 1);
+        }
+        ui.label(format!("Page {} of {}", page + 1, pages));
+        if ui.add_enabled(page + 1 < pages && !swap.history_loading, egui::Button::new("Next ▶")).clicked() {
+            target = Some(page + 1);
+        }
+        ui.label(format!("{} per page", HISTORY_PAGE_SIZE));
+
+        if let Some(target) = target {
+            app.state().write().terminal.swap.history_filters.page = target;
+            app.handle_swap_history_refresh();
+        }
+    });
 }
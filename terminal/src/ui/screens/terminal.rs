@@ -2,11 +2,32 @@
 //!
 //! Main trading interface with SOL candlestick chart and real-time token prices.
 //! Redesigned with full-screen layout: Chart (60%) | Token List (40%) + collapsible swap panel.
+//! A route-quote depth ladder sits under the chart. The swap panel reads the
+//! backend feature gates, so a Jupiter or RPC outage shows up as a message
+//! instead of a spinning quote. Swaps queued by the rebalancer wait below the
+//! swap form until each is reviewed.
 
 use egui;
-use crate::app::{AppState, AppLike};
+use crate::app::recovery::RecoveryFlow;
+use crate::app::search::SearchTarget;
+use crate::app::commands::{self, CommandAction, CommandRegistry, CommandSpec};
+use crate::app::handlers::quote_stream::{quote_freshness, QuoteFreshness};
+use crate::app::{AppState, AppLike, Feature, Gate, Screen, SwapField};
+use crate::tr;
+use crate::ui::i18n;
 use crate::ui::theme::Theme;
 use crate::ui::widgets::icons::{Icons, material, size};
+use crate::ui::widgets::focus::FocusChain;
+use crate::ui::widgets::{command_palette, price_display, search_palette};
+
+/// Command palette commands for this screen (`swap` is global)
+pub fn register_commands(registry: &mut CommandRegistry) {
+    registry.register(
+        CommandSpec::with_args("set timeframe", "<timeframe>", "Change the chart timeframe", commands::parse_timeframe)
+            .alias("timeframe")
+            .on_screen(Screen::Terminal),
+    );
+}
 
 /// Render main trading terminal screen
 pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
@@ -23,7 +44,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
         
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             // Current SOL price display (if available)
-            if let Some(sol_price) = state.terminal.prices.iter().find(|p| p.symbol == "SOL") {
+            if let Some(sol_price) = state.terminal.prices.load().get("SOL") {
                 ui.colored_label(theme.selected, format!("SOL: ${:.2}", sol_price.price));
                 let (change_text, change_color) = theme.format_price_change(sol_price.change_24h);
                 ui.colored_label(change_color, change_text);
@@ -48,6 +69,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
             // Chart (middle, 60% of remaining)
             columns[1].vertical(|ui| {
                 render_chart_panel(ui, state, app, &theme);
+                render_depth_panel(ui, state, app, &theme);
             });
 
             // Token list (right, 40% of remaining)
@@ -61,6 +83,7 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
             // Chart (left, 60%)
             columns[0].vertical(|ui| {
                 render_chart_panel(ui, state, app, &theme);
+                render_depth_panel(ui, state, app, &theme);
             });
 
             // Token list (right, 40%)
@@ -71,15 +94,21 @@ pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
     }
 }
 
-/// Render SOL candlestick chart panel (left side, 60%)
+/// Render the candlestick chart panel (left side, 60%)
 fn render_chart_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
     use crate::ui::widgets::layouts;
+    let symbol = state.terminal.chart_symbol.as_str();
     
     layouts::render_panel(ui, None, |ui| {
         // Chart header with timeframe selector
         ui.horizontal(|ui| {
             ui.label(Icons::icon_red(material::CHART, size::MEDIUM));
-            ui.heading("SOL Price Chart");
+            ui.heading(format!("{} Price Chart", symbol));
+
+            ui.add_space(10.0);
+            if let Some(action) = crate::ui::chart::render_drawing_toolbar(ui, state.terminal.chart_tool) {
+                app.handle_chart_action(action);
+            }
             
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 // Timeframe selector buttons
@@ -118,7 +147,7 @@ fn render_chart_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLik
                         tracing::debug!(
                             old_timeframe = %old_str,
                             new_timeframe = %new_str,
-                            symbol = "SOL",
+                            symbol = %symbol,
                             "Timeframe changed by user"
                         );
                         
@@ -128,7 +157,7 @@ fn render_chart_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLik
                         drop(state_write);
                         
                         // Fetch new candles
-                        app.fetch_candles("SOL", new_timeframe);
+                        app.fetch_candles(symbol, new_timeframe);
                     }
                 }
             });
@@ -138,23 +167,43 @@ fn render_chart_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLik
         
         // Chart content
         if state.terminal.chart_loading && state.terminal.sol_candles.is_empty() {
-            ui.colored_label(theme.dim, "Loading chart data...");
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.colored_label(theme.dim, "Loading chart data...");
+            });
         } else if state.terminal.sol_candles.is_empty() {
             ui.colored_label(theme.dim, "No chart data available");
             if state.websocket_connected {
-                ui.label("Waiting for SOL price updates to generate candles...");
-                if let Some(sol_price) = state.terminal.prices.iter().find(|p| p.symbol == "SOL") {
-                    ui.colored_label(theme.success, format!("SOL price received: ${:.4}", sol_price.price));
+                ui.label(format!("Waiting for {} price updates to generate candles...", symbol));
+                if let Some(price) = state.terminal.prices.load().get(symbol) {
+                    ui.colored_label(theme.success, format!("{} price received: ${:.4}", symbol, price.price));
                     ui.label("Chart should load automatically...");
                 } else {
-                    ui.colored_label(theme.warning, "SOL price not yet received from WebSocket");
+                    ui.colored_label(theme.warning, format!("{} price not yet received from WebSocket", symbol));
                 }
             } else {
                 ui.label("Chart will display once WebSocket connection is established");
             }
         } else {
-            // Render candlestick chart
-            crate::ui::chart::render_candlestick_chart(ui, &state.terminal.sol_candles, theme);
+            // Render candlestick chart with volume panel and drawn levels
+            let drawings = crate::ui::chart::Drawings {
+                symbol,
+                annotations: &state.terminal.chart_annotations,
+                tool: state.terminal.chart_tool,
+            };
+            if let Some(action) = crate::ui::chart::render_candlestick_chart(
+                ui,
+                &state.terminal.sol_candles,
+                state.terminal.chart_data_version,
+                state.terminal.chart_timeframe,
+                state.terminal.chart_loading,
+                state.terminal.sol_candle_forming,
+                None,
+                Some(drawings),
+                theme,
+            ) {
+                app.handle_chart_action(action);
+            }
             
             // Show current price info
             if let Some(last_candle) = state.terminal.sol_candles.last() {
@@ -180,6 +229,37 @@ fn render_chart_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLik
     });
 }
 
+/// Render SOL/USDC depth ladder below the chart (refreshed every 10s)
+fn render_depth_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    use crate::app::DEPTH_REFRESH_INTERVAL;
+    use crate::ui::widgets::{depth_chart, layouts};
+
+    // Fetch is throttled in the task; this only avoids taking the state lock every frame
+    let since_fetch = state.terminal.last_depth_fetch.map(|t| t.elapsed());
+    if !state.terminal.depth_loading && since_fetch.is_none_or(|d| d >= DEPTH_REFRESH_INTERVAL) {
+        app.fetch_depth("SOL", "USDC");
+    }
+    ui.ctx().request_repaint_after(
+        DEPTH_REFRESH_INTERVAL.saturating_sub(since_fetch.unwrap_or_default()),
+    );
+
+    ui.add_space(5.0);
+    layouts::render_panel(ui, None, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(Icons::icon_red(material::CHART, size::MEDIUM));
+            ui.heading("Depth (SOL → USDC)");
+        });
+        ui.add_space(5.0);
+        depth_chart::render(
+            ui,
+            state.terminal.depth.as_ref(),
+            state.terminal.depth_loading,
+            state.terminal.depth_error.as_deref(),
+            theme,
+        );
+    });
+}
+
 /// Render token price list (right side, 40%)
 fn render_price_list(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
     use crate::ui::widgets::{layouts, tables};
@@ -193,7 +273,8 @@ fn render_price_list(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
 
         // Check if prices are available
         // Debug logging for UI rendering
-        let price_count = state.terminal.prices.len();
+        let prices = state.terminal.prices.load();
+        let price_count = prices.len();
         let websocket_status = &state.websocket_status;
         tracing::debug!(
             price_count = price_count,
@@ -203,7 +284,7 @@ fn render_price_list(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
             "UI RENDER: Rendering price table"
         );
         
-        if state.terminal.prices.is_empty() {
+        if prices.is_empty() {
             tracing::warn!(
                 price_count = 0,
                 websocket_connected = state.websocket_connected,
@@ -224,7 +305,7 @@ fn render_price_list(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
 
         tracing::info!(
             price_count = price_count,
-            symbols = ?state.terminal.prices.iter().map(|p| p.symbol.clone()).collect::<Vec<_>>(),
+            symbols = ?prices.iter().map(|p| p.symbol.clone()).collect::<Vec<_>>(),
             "UI RENDER: Rendering price table with data"
         );
 
@@ -240,11 +321,11 @@ fn render_price_list(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
             ui,
             "prices",
             config,
-            &["Symbol", "Price", "24h %", "Change"],
+            &[tr!("terminal.symbol"), tr!("terminal.price"), tr!("terminal.change_24h"), tr!("terminal.tick")],
             theme,
             |ui| {
                 // Sort prices by price (descending) - show ALL tokens, not just top 10
-                let mut sorted_prices: Vec<_> = state.terminal.prices.iter().collect();
+                let mut sorted_prices: Vec<_> = prices.iter().collect();
                 sorted_prices.sort_by(|a, b| b.price.partial_cmp(&a.price).unwrap_or(std::cmp::Ordering::Equal));
                 
                 tracing::debug!(
@@ -264,48 +345,34 @@ fn render_price_list(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
                         ""
                     };
 
-                    // Bloomberg-style flash effect: highlight price if it changed recently
-                    let (price_color, is_flashing) = if let Some(prev_price) = price.previous_price {
-                        let price_change = price.price - prev_price;
-                        let time_since_update = state.last_price_update_time.elapsed();
-                        
-                        // Flash for 500ms after price change
-                        if time_since_update.as_millis() < 500 && price_change.abs() > 0.0001 {
-                            if price_change > 0.0 {
-                                // Bright green for price increase
-                                (egui::Color32::from_rgb(0, 255, 0), true)
-                            } else {
-                                // Bright red for price decrease
-                                (egui::Color32::from_rgb(255, 0, 0), true)
-                            }
-                        } else {
-                            // Normal color
-                            (theme.selected, false)
+                    // Render symbol, outlined when a search result pointed here
+                    // and badged when the backend's price sources disagree
+                    ui.horizontal(|ui| {
+                        let response = ui.label(&price.symbol);
+                        if matches!(&state.search.highlight, Some(SearchTarget::Token { symbol }) if symbol.eq_ignore_ascii_case(&price.symbol)) {
+                            search_palette::outline_highlight(ui, &response, state, theme);
                         }
-                    } else {
-                        // Normal color for new tokens
-                        (theme.selected, false)
-                    };
-
-                    // Render symbol
-                    ui.label(&price.symbol);
+                        if price.divergent {
+                            ui.label(Icons::icon_color(material::WARNING, size::SMALL, theme.warning))
+                                .on_hover_text(price.sources_tooltip());
+                        }
+                    });
                     
-                    // Render price with flash effect (bright color when changing)
-                    if is_flashing {
-                        ui.colored_label(price_color, format!("${:.4}", price.price));
-                    } else {
-                        ui.monospace(format!("${:.4}", price.price));
-                    }
+                    // Bloomberg-style flash: the cell lights up green/red when
+                    // this symbol's price moves, fading out over 500ms
+                    let flash = price_display::price_flash(price, &state.terminal.price_flashes, theme);
+                    price_display::render_flashing_price(ui, format!("${}", i18n::format_decimal(price.price, 4)), flash);
                     
-                    // Render change percentage
-                    ui.colored_label(change_color, change_text);
+                    // Render change percentage with its direction icon
+                    ui.horizontal(|ui| {
+                        if !change_icon.is_empty() {
+                            ui.label(Icons::icon_color(change_icon, size::SMALL, change_color));
+                        }
+                        ui.colored_label(change_color, change_text);
+                    });
                     
-                    // Render change icon
-                    if !change_icon.is_empty() {
-                        ui.label(Icons::icon_color(change_icon, size::SMALL, change_color));
-                    } else {
-                        ui.label("");
-                    }
+                    // Render the move since the previous tick
+                    price_display::render_tick_delta(ui, price, theme);
                     ui.end_row();
                 }
             },
@@ -314,6 +381,9 @@ fn render_price_list(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
 }
 
 /// Render collapsible swap panel
+///
+/// Tab moves through the panel in [`SwapField`] order; Enter in the last text
+/// field executes the swap when the Execute button is enabled.
 fn render_swap_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
     use crate::ui::widgets::layouts;
     layouts::render_panel(ui, None, |ui| {
@@ -322,11 +392,16 @@ fn render_swap_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike
             ui.heading("Swap Tokens");
         });
         ui.add_space(10.0);
+        let mut chain = FocusChain::new();
 
         // From token
         ui.horizontal(|ui| {
             ui.label("From:");
-            if ui.button(&state.terminal.swap.input_token).clicked() {
+            let token = &state.terminal.swap.input_token;
+            let from = ui.button(token);
+            from.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, true, format!("Swap from {}", token)));
+            chain.widget(SwapField::InputToken, &from);
+            if from.clicked() {
                 {
                     let mut state_write = app.state().write();
                     app.open_token_picker_internal(&mut *state_write, crate::app::TokenPickerTarget::Input);
@@ -338,7 +413,11 @@ fn render_swap_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike
         // To token
         ui.horizontal(|ui| {
             ui.label("To:");
-            if ui.button(&state.terminal.swap.output_token).clicked() {
+            let token = &state.terminal.swap.output_token;
+            let to = ui.button(token);
+            to.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, true, format!("Swap to {}", token)));
+            chain.widget(SwapField::OutputToken, &to);
+            if to.clicked() {
                 {
                     let mut state_write = app.state().write();
                     app.open_token_picker_internal(&mut *state_write, crate::app::TokenPickerTarget::Output);
@@ -347,45 +426,207 @@ fn render_swap_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike
         });
         ui.add_space(5.0);
 
+        // Scam tokens copy real names and symbols; make an unverified output hard to miss
+        let swap = &state.terminal.swap;
+        if let Some(output) = swap.token_list.iter().find(|t| t.mint == swap.output_mint && !t.verified) {
+            egui::Frame::new()
+                .fill(theme.warning.gamma_multiply(0.15))
+                .stroke(egui::Stroke::new(1.0, theme.warning))
+                .corner_radius(4.0)
+                .inner_margin(8.0)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(Icons::icon_warning(material::WARNING, size::MEDIUM));
+                        ui.colored_label(theme.warning, format!("{} is not a verified token", output.symbol));
+                    });
+                    ui.colored_label(
+                        theme.dim,
+                        "Anyone can create a token with this name. Check the mint before swapping:",
+                    );
+                    ui.add(egui::Label::new(egui::RichText::new(&output.mint).monospace().small()).wrap());
+                });
+            ui.add_space(5.0);
+        }
+
         // Amount input
-        ui.label("Amount:");
+        let amount_label = ui.label("Amount:");
         let mut amount = state.terminal.swap.amount.clone();
-        let amount_response = ui.text_edit_singleline(&mut amount);
+        let amount_response = ui.text_edit_singleline(&mut amount).labelled_by(amount_label.id);
+        chain.text(SwapField::Amount, &amount_response);
         if amount_response.changed() {
             {
                 let mut state_write = app.state().write();
                 state_write.terminal.swap.amount = amount.clone();
             }
-            if !amount.is_empty() {
-                app.trigger_quote_fetch();
-            }
+            app.trigger_quote_fetch();
         }
-        if ui.button("Max").clicked() {
+        let max = ui.button("Max");
+        max.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, true, "Use maximum amount"));
+        chain.widget(SwapField::Max, &max);
+        if max.clicked() {
             app.set_max_amount();
             app.trigger_quote_fetch();
         }
+        ui.add_space(5.0);
+
+        // Advanced options
+        let advanced = egui::CollapsingHeader::new("Advanced")
+            .id_salt("swap_advanced")
+            .show(ui, |ui| {
+                let memo_label = ui.label("Memo (optional):");
+                let mut memo = state.terminal.swap.memo.clone();
+                let memo_response = ui
+                    .add(egui::TextEdit::singleline(&mut memo).hint_text("Attached on-chain via SPL Memo"))
+                    .labelled_by(memo_label.id);
+                if memo_response.changed() {
+                    app.state().write().terminal.swap.memo = memo.clone();
+                }
+                if !memo.trim().is_empty() {
+                    match crate::services::memo::validate_memo(&memo) {
+                        Ok(valid) => {
+                            ui.colored_label(
+                                theme.dim,
+                                format!("{}/{} bytes", valid.len(), crate::services::memo::MAX_MEMO_BYTES),
+                            );
+                        }
+                        Err(e) => {
+                            ui.colored_label(theme.error, e.to_string());
+                        }
+                    }
+                }
+                memo_response
+            });
+        chain.widget(SwapField::Advanced, &advanced.header_response);
+        if let Some(memo_response) = &advanced.body_returned {
+            chain.text(SwapField::Memo, memo_response);
+        }
         ui.add_space(10.0);
 
         // Quote display
-        if state.terminal.swap.quote_loading {
+        let gates = state.backend_health.gates();
+        let quotes_gate = gates.get(Feature::SwapQuotes);
+        let execution_gate = gates.get(Feature::SwapExecution);
+        if let Gate::Unavailable(reason) = quotes_gate {
+            ui.colored_label(theme.error, "Quotes unavailable");
+            ui.colored_label(theme.dim, reason);
+        } else if state.terminal.swap.quote_loading {
             ui.colored_label(theme.info, "Fetching quote...");
             ui.label("Please wait");
         } else if let Some(quote) = &state.terminal.swap.quote {
             ui.label("Estimated Output:");
             ui.colored_label(theme.success, format!("{:.6}", quote.output_amount));
+            match quote_freshness(&state.terminal.swap, std::time::Instant::now()) {
+                Some(QuoteFreshness::Live(age)) => {
+                    ui.colored_label(theme.success, format!("● Live · {}s", age.as_secs()));
+                }
+                Some(QuoteFreshness::Stale(age)) => {
+                    ui.colored_label(theme.warning, format!("● Stale · {}s", age.as_secs()));
+                }
+                Some(QuoteFreshness::Fetched(age)) => {
+                    ui.colored_label(theme.dim, format!("Quoted {}s ago", age.as_secs()));
+                }
+                None => {}
+            }
             ui.label("Price Impact:");
             ui.colored_label(theme.warning, format!("{:.2}%", quote.price_impact));
             ui.label("Est. Fee:");
             ui.colored_label(theme.dim, format!("{:.6}", quote.estimated_fee));
+            if !state.terminal.swap.memo.trim().is_empty() {
+                ui.label("Memo:");
+                ui.colored_label(theme.info, state.terminal.swap.memo.trim());
+            }
         } else {
             ui.colored_label(theme.dim, "No quote available");
             ui.label("Enter amount to get quote");
         }
         ui.add_space(10.0);
 
-        // Execute button
-        if ui.add(egui::Button::new(format!("{} Execute Swap", material::SEND)).fill(theme.selected)).clicked() {
+        // Execute button; a swap can take a while to sign and confirm, so it
+        // isn't started on a session about to expire
+        let session_check = crate::services::session::check_long_operation(
+            state.session_expires_at(),
+            chrono::Utc::now().timestamp(),
+        );
+        let maintenance = crate::app::handlers::maintenance::trade_block_reason(state);
+        let preparing = state.terminal.swap.preparing;
+        let label = if preparing { "Simulating..." } else { "Execute Swap" };
+        let enabled = execution_gate.is_usable() && session_check.is_ok() && maintenance.is_none() && !preparing;
+        let mut execute = ui.add_enabled(
+            enabled,
+            egui::Button::new(format!("{} {}", material::SEND, label)).fill(theme.selected),
+        );
+        // Announced without the icon glyph
+        execute.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, enabled, label));
+        if enabled {
+            chain.widget(SwapField::Execute, &execute);
+        }
+        if let Some(reason) = execution_gate.reason() {
+            execute = execute.on_disabled_hover_text(reason);
+        } else if let Err(reason) = &session_check {
+            execute = execute.on_disabled_hover_text(reason);
+        } else if let Some(reason) = maintenance {
+            execute = execute.on_disabled_hover_text(reason);
+        }
+
+        // Focus Swap Amount shortcut, or back from the token picker
+        if let Some(field) = state.terminal.swap.focus_field {
+            chain.focus(field);
+            app.state().write().terminal.swap.focus_field = None;
+        }
+        let focus = chain.finish(ui, theme);
+        if execute.clicked() || (focus.submit && enabled) {
             app.handle_swap_execute_click();
         }
+        ui.add_space(6.0);
+        crate::ui::widgets::recovery_card::render_recovery_card(ui, state, app, RecoveryFlow::Swap);
+
+        render_swap_queue(ui, state, app, theme);
     });
 }
+
+/// Render swaps queued for review (e.g. by the rebalancer)
+///
+/// "Review" loads a swap into the form above; it still goes through the quote,
+/// simulation and confirmation dialog like one typed by hand.
+fn render_swap_queue(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    let queue = &state.terminal.swap.queue;
+    if queue.is_empty() {
+        return;
+    }
+    ui.add_space(10.0);
+    ui.separator();
+    ui.label(format!("Queued swaps ({})", queue.len()));
+
+    let mut review = None;
+    let mut remove = None;
+    for (i, queued) in queue.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.monospace(format!("{} {} → {}", queued.amount, queued.input_symbol, queued.output_symbol));
+            ui.colored_label(theme.dim, format!("≈${:.2}", queued.usd_value));
+            if ui.small_button("Review").clicked() {
+                review = Some(i);
+            }
+            if ui.add(egui::Button::new(material::CLOSE).small()).on_hover_text("Remove").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if ui.small_button("Clear queue").clicked() {
+        app.state().write().terminal.swap.queue.clear();
+    }
+
+    if let Some(i) = remove {
+        app.state().write().terminal.swap.queue.remove(i);
+    }
+    if let Some(i) = review {
+        let queued = app.state().write().terminal.swap.queue.remove(i);
+        command_palette::run_command(
+            app,
+            CommandAction::Swap {
+                input: queued.input_symbol,
+                output: queued.output_symbol,
+                amount: Some(queued.amount),
+            },
+        );
+    }
+}
@@ -67,8 +67,8 @@ fn render_token_accounts(
     // Check if prices were recently updated for live USD values
     let recently_updated = state.last_price_update_time.elapsed().as_millis() < 500;
     
-    // Clone state for closure
-    let state_clone = state.clone();
+    // Live prices for USD values
+    let prices = state.terminal.prices.load();
     
     tables::render_table(
         ui,
@@ -80,9 +80,7 @@ fn render_token_accounts(
             // Data rows
             for balance in &wallet.token_balances {
                 // Find live price for this token
-                let live_price = state_clone.terminal.prices
-                    .iter()
-                    .find(|p| p.symbol == balance.symbol);
+                let live_price = prices.get(&balance.symbol);
                 
                 ui.label(&balance.symbol);
                 ui.monospace({
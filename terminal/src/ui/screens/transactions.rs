@@ -1,35 +1,193 @@
 //! # Transactions Screen
 //!
 //! Display transaction history using egui widgets.
+//!
+//! Clicking a signature opens a detail section below the table with the full
+//! signature and any memo attached to the transaction. Swaps submitted this
+//! session also show how long each stage of the pipeline took, with stages
+//! over their budget highlighted.
+//!
+//! Swaps submitted from the terminal are tracked on chain, so their status
+//! moves through processed, confirmed and finalized while the screen is open.
+//!
+//! Each row carries a chip for the wallet that made it, and the wallet filter
+//! narrows the table to one of them.
+//!
+//! Trades imported from other platforms are not listed here (they have no
+//! on-chain signature); they show up in the trade statistics section, which
+//! combines them with confirmed swaps.
 
 use egui;
-use crate::app::AppState;
+use crate::app::search::SearchTarget;
+use crate::app::wallet_identity::WalletFilter;
+use crate::app::{AppState, AppLike, TransactionItem};
 use crate::ui::theme::Theme;
-use crate::ui::widgets::tables;
+use crate::ui::widgets::{search_palette, tables, trade_import, wallet_chip};
 
 /// Render transactions screen
-pub fn render(ui: &mut egui::Ui, state: &AppState) {
+pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
     let theme = Theme::default();
 
+    ui.horizontal(|ui| {
+        ui.heading("Transaction History");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui
+                .add_enabled(!state.transactions.is_empty(), egui::Button::new("Export CSV"))
+                .clicked()
+            {
+                app.handle_transactions_export();
+            }
+            if ui.button("Import Trades").on_hover_text("Import trade history from another platform (CSV)").clicked() {
+                app.state().write().trade_import.open_wizard();
+            }
+            if ui.add_enabled(state.wallet.is_some(), egui::Button::new("Refresh")).clicked() {
+                app.handle_transactions_refresh();
+            }
+            let wallets = transaction_wallets(state);
+            if wallets.len() > 1 || state.transactions_wallet != WalletFilter::All {
+                if let Some(filter) = wallet_chip::render_wallet_filter(
+                    ui,
+                    "transactions_wallet_filter",
+                    &state.wallet_identities,
+                    &wallets,
+                    &state.transactions_wallet,
+                ) {
+                    app.state().write().transactions_wallet = filter;
+                }
+            }
+        });
+    });
+    ui.add_space(10.0);
+
+    render_search_miss(ui, state, &theme);
+
     if state.transactions.is_empty() {
         tables::render_empty_state(
             ui,
             "No Transactions Yet",
-            Some("Execute swaps to see transaction history"),
+            Some("Execute swaps or connect a wallet to see transaction history"),
             &theme,
         );
     } else {
         render_transactions_table(ui, state, &theme);
     }
+
+    ui.add_space(15.0);
+    ui.separator();
+    render_trade_stats(ui, state, app, &theme);
+
+    trade_import::render_trade_import_window(ui, state, app, &theme);
+}
+
+/// Wallets that made any of the listed transactions, in first-seen order
+fn transaction_wallets(state: &AppState) -> Vec<String> {
+    let mut wallets: Vec<String> = Vec::new();
+    for wallet in state.transactions.iter().filter_map(|tx| tx.wallet.as_ref()) {
+        if !wallets.contains(wallet) {
+            wallets.push(wallet.clone());
+        }
+    }
+    wallets
+}
+
+/// Searched-for transaction that isn't in the loaded wallet history (older
+/// swaps come from the backend search)
+fn render_search_miss(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
+    let Some(SearchTarget::Transaction { signature }) = &state.search.highlight else {
+        return;
+    };
+    if state.transactions.iter().any(|tx| tx.signature == *signature) {
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.colored_label(theme.warning, "Not in the loaded wallet history:");
+        ui.monospace(signature);
+        if ui.small_button("Copy").clicked() {
+            ui.ctx().copy_text(signature.clone());
+        }
+    });
+    ui.add_space(10.0);
+}
+
+/// Render realized PnL per pair over swaps and imported trades
+fn render_trade_stats(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    let import = &state.trade_import;
+
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("Trade Statistics").strong());
+        if import.stats_loading {
+            ui.spinner();
+        }
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.add_enabled(!import.stats_loading, egui::Button::new("Refresh")).clicked() {
+                app.handle_trade_stats_refresh();
+            }
+        });
+    });
+    ui.add_space(5.0);
+
+    let Some(stats) = &import.stats else {
+        ui.colored_label(theme.dim, "Refresh to load realized PnL from swaps and imported trades");
+        return;
+    };
+
+    tables::render_stats_summary(
+        ui,
+        &[("On-chain swaps", stats.onchain_trades), ("Imported trades", stats.imported_trades)],
+    );
+    ui.add_space(5.0);
+
+    if stats.pairs.is_empty() {
+        ui.colored_label(theme.dim, "No trades yet");
+        return;
+    }
+
+    tables::render_table(
+        ui,
+        "trade_stats",
+        tables::TableConfig { num_columns: 7, ..Default::default() },
+        &["Pair", "Trades", "Volume", "Fees", "Realized PnL", "Open", "Avg Cost"],
+        theme,
+        |ui| {
+            for pair in &stats.pairs {
+                ui.label(&pair.pair);
+                ui.monospace(pair.trades.to_string());
+                ui.monospace(format!("{:.2}", pair.volume));
+                ui.monospace(format!("{:.2}", pair.fees));
+                let pnl_color = if pair.realized_pnl >= 0.0 { theme.success } else { theme.error };
+                ui.colored_label(pnl_color, egui::RichText::new(format!("{:+.2}", pair.realized_pnl)).monospace());
+                ui.monospace(format!("{:.4}", pair.open_position));
+                if pair.open_position > 0.0 {
+                    ui.monospace(format!("{:.4}", pair.average_cost));
+                } else {
+                    ui.colored_label(theme.dim, "—");
+                }
+                ui.end_row();
+            }
+        },
+    );
 }
 
 /// Render transactions table
 fn render_transactions_table(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
-    ui.heading("Transaction History");
-    ui.add_space(10.0);
+    let selected_id = egui::Id::new("transactions_selected");
+    let mut selected: Option<String> = ui.data(|d| d.get_temp(selected_id));
+
+    // A search result selects its transaction once
+    let highlight = match &state.search.highlight {
+        Some(SearchTarget::Transaction { signature }) => Some(signature.as_str()),
+        _ => None,
+    };
+    if let Some(signature) = highlight {
+        let applied_id = egui::Id::new("transactions_search_seq");
+        if ui.data(|d| d.get_temp::<u64>(applied_id)) != Some(state.search.highlight_seq) {
+            selected = Some(signature.to_string());
+            ui.data_mut(|d| d.insert_temp(applied_id, state.search.highlight_seq));
+        }
+    }
 
     let config = tables::TableConfig {
-        num_columns: 5,
+        num_columns: 7,
         spacing: [10.0, 5.0],
         striped: true,
         scrollable: false,
@@ -39,31 +197,159 @@ fn render_transactions_table(ui: &mut egui::Ui, state: &AppState, theme: &Theme)
         ui,
         "transactions",
         config,
-        &["Time", "Type", "Amount", "Status", "Signature"],
+        &["Time", "Wallet", "Type", "Amount", "Status", "Memo", "Signature"],
         theme,
         |ui| {
             // Rows
-            for tx in &state.transactions {
+            for tx in state.transactions.iter().filter(|tx| state.transactions_wallet.matches(tx.wallet.as_deref())) {
                 // Format timestamp
                 let time = chrono::DateTime::from_timestamp(tx.timestamp, 0)
                     .map(|dt| dt.format("%H:%M:%S").to_string())
                     .unwrap_or_else(|| "Unknown".to_string());
 
-                // Status color
-                let status_color = match tx.status.as_str() {
-                    "confirmed" => theme.success,
-                    "pending" => theme.warning,
-                    "failed" => theme.error,
-                    _ => theme.dim,
-                };
-
                 ui.label(time);
+                match &tx.wallet {
+                    Some(wallet) => {
+                        wallet_chip::render_wallet_chip(ui, &state.wallet_identities, wallet);
+                    }
+                    None => {
+                        ui.colored_label(theme.dim, "—");
+                    }
+                }
                 ui.label(&tx.tx_type);
                 ui.label(&tx.amount);
-                ui.colored_label(status_color, &tx.status);
-                ui.label(&tx.signature[..8.min(tx.signature.len())]); // First 8 chars
+                ui.horizontal(|ui| {
+                    ui.colored_label(status_color(&tx.status, theme), &tx.status);
+                    // Tracking moves these on live; the spinner keeps the table repainting
+                    if is_in_flight(&tx.status) {
+                        ui.spinner();
+                    }
+                });
+                match &tx.memo {
+                    Some(memo) => {
+                        ui.label(truncate(memo, 24)).on_hover_text(memo);
+                    }
+                    None => {
+                        ui.colored_label(theme.dim, "—");
+                    }
+                }
+                let is_selected = selected.as_deref() == Some(tx.signature.as_str());
+                let response = ui.selectable_label(is_selected, &tx.signature[..8.min(tx.signature.len())]); // First 8 chars
+                if highlight == Some(tx.signature.as_str()) {
+                    search_palette::outline_highlight(ui, &response, state, theme);
+                }
+                if response.clicked() {
+                    selected = if is_selected { None } else { Some(tx.signature.clone()) };
+                }
                 ui.end_row();
             }
         },
     );
+
+    ui.data_mut(|d| d.insert_temp(selected_id, selected.clone()));
+
+    if let Some(tx) = selected
+        .as_deref()
+        .and_then(|sig| state.transactions.iter().find(|tx| tx.signature == sig))
+    {
+        ui.add_space(10.0);
+        ui.separator();
+        render_transaction_detail(ui, tx, theme);
+    }
+}
+
+/// Render the detail section for the selected transaction
+fn render_transaction_detail(ui: &mut egui::Ui, tx: &TransactionItem, theme: &Theme) {
+    ui.label(egui::RichText::new("Transaction Details").strong());
+    ui.add_space(5.0);
+
+    egui::Grid::new("transaction_detail")
+        .num_columns(2)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            ui.colored_label(theme.dim, "Signature");
+            ui.horizontal(|ui| {
+                ui.monospace(&tx.signature);
+                if ui.small_button("Copy").clicked() {
+                    ui.ctx().copy_text(tx.signature.clone());
+                }
+            });
+            ui.end_row();
+
+            ui.colored_label(theme.dim, "Time");
+            ui.label(
+                chrono::DateTime::from_timestamp(tx.timestamp, 0)
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                    .unwrap_or_else(|| "Unknown".to_string()),
+            );
+            ui.end_row();
+
+            if let Some(wallet) = &tx.wallet {
+                ui.colored_label(theme.dim, "Wallet");
+                ui.monospace(wallet);
+                ui.end_row();
+            }
+
+            ui.colored_label(theme.dim, "Type");
+            ui.label(&tx.tx_type);
+            ui.end_row();
+
+            ui.colored_label(theme.dim, "Status");
+            ui.colored_label(status_color(&tx.status, theme), &tx.status);
+            ui.end_row();
+
+            if !tx.amount.is_empty() {
+                ui.colored_label(theme.dim, "Amount");
+                ui.label(&tx.amount);
+                ui.end_row();
+            }
+
+            ui.colored_label(theme.dim, "Memo");
+            match &tx.memo {
+                Some(memo) => ui.add(egui::Label::new(memo).wrap()),
+                None => ui.colored_label(theme.dim, "None"),
+            };
+            ui.end_row();
+
+            if let Some(latency) = &tx.latency {
+                let budgets = crate::app::latency::budgets();
+                ui.colored_label(theme.dim, "Latency");
+                ui.label(format!("{}ms from Swap to RPC, without review", latency.total_ms()));
+                ui.end_row();
+                for (stage, ms) in latency.stages() {
+                    let budget = budgets.budget(stage);
+                    ui.colored_label(theme.dim, format!("  {}", stage.label()));
+                    if ms > budget {
+                        ui.colored_label(theme.warning, format!("{}ms (budget {}ms)", ms, budget));
+                    } else {
+                        ui.label(format!("{}ms", ms));
+                    }
+                    ui.end_row();
+                }
+            }
+        });
+}
+
+/// Status color
+fn status_color(status: &str, theme: &Theme) -> egui::Color32 {
+    match status {
+        "confirmed" | "finalized" => theme.success,
+        "pending" | "processed" => theme.warning,
+        "failed" | "expired" => theme.error,
+        _ => theme.dim,
+    }
+}
+
+/// Whether a submitted transaction hasn't been confirmed yet
+fn is_in_flight(status: &str) -> bool {
+    matches!(status, "pending" | "processed")
+}
+
+/// Shorten text to `max` characters with an ellipsis
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() > max {
+        format!("{}…", text.chars().take(max).collect::<String>())
+    } else {
+        text.to_string()
+    }
 }
@@ -1,26 +1,60 @@
 //! # Wallet Screen
 //!
 //! Display wallet address and token balances using egui widgets.
+//!
+//! The connected wallet's chip sits next to its address; "Edit label" picks
+//! the label and color it is shown with everywhere else. On devnet an
+//! airdrop button next to the balance asks the faucet for SOL.
+//!
+//! The Send panel below the balances moves SOL or a held token to another
+//! address; Review opens the transfer's confirmation dialog. The Receive
+//! panel next to it shows the address as text and as a QR code, optionally
+//! asking for an amount of SOL through a Solana Pay link.
+//!
+//! The Staking panel under them lists the wallet's stake accounts and
+//! rewards, and stakes SOL with a validator (see
+//! [`crate::ui::widgets::staking`]).
 
 use egui;
-use crate::app::AppState;
+use crate::app::recovery::RecoveryFlow;
+use crate::app::wallet_identity::PALETTE;
+use crate::app::{AppLike, AppState, TokenBalance, WalletLabelEdit};
+use crate::app::handlers::airdrop::{airdrop_blocked, AIRDROP_LAMPORTS};
+use crate::app::handlers::send::{max_amount, BASE_FEE_LAMPORTS};
+use crate::services::memo::{validate_memo, MAX_MEMO_BYTES};
+use crate::services::token_transfer::{format_token_amount, TransferPreview, SOL_DECIMALS};
+use shared::dto::tokens::TokenProgram;
+use crate::tr;
+use crate::ui::i18n;
 use crate::ui::theme::Theme;
 use crate::ui::widgets::icons::{Icons, material, size};
+use crate::ui::widgets::{receive_qr, staking, wallet_chip};
+use std::time::{Duration, Instant};
 
 /// Render wallet screen
 pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl crate::app::AppLike) {
     let theme = Theme::default();
 
+    crate::ui::widgets::recovery_card::render_recovery_card(ui, state, app, RecoveryFlow::Wallet);
+
     if let Some(wallet) = &state.wallet {
-        render_wallet_info(ui, wallet, app, &theme);
+        render_wallet_info(ui, state, wallet, app, &theme);
     } else {
         render_no_wallet(ui, app, &theme);
     }
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+    render_derived_accounts(ui, state, app, &theme);
+
+    crate::ui::widgets::onramp::render_onramp_window(ui, state, app, &theme);
 }
 
 /// Render wallet information
 fn render_wallet_info(
     ui: &mut egui::Ui,
+    state: &AppState,
     wallet: &crate::app::WalletState,
     app: &mut impl crate::app::AppLike,
     theme: &Theme,
@@ -28,23 +62,38 @@ fn render_wallet_info(
     ui.vertical(|ui| {
         ui.horizontal(|ui| {
             ui.label(Icons::icon_success(material::WALLET, size::MEDIUM));
-            ui.heading("Connected Wallet");
+            ui.heading(tr!("wallet.heading"));
         });
         ui.add_space(10.0);
 
         // Wallet address
         ui.horizontal(|ui| {
             ui.label(Icons::icon_dim(material::TOKEN, size::SMALL));
-            ui.label("Address:");
+            ui.label(tr!("wallet.address"));
             ui.monospace(&wallet.address);
+            wallet_chip::render_wallet_chip(ui, &state.wallet_identities, &wallet.address);
+            let editing = state.wallet_label_edit.as_ref().is_some_and(|edit| edit.address == wallet.address);
+            if !editing && ui.small_button(tr!("wallet.edit_label")).clicked() {
+                let identities = &state.wallet_identities;
+                app.state().write().wallet_label_edit = Some(WalletLabelEdit {
+                    address: wallet.address.clone(),
+                    label: identities.get(&wallet.address).map(|identity| identity.label.clone()).unwrap_or_default(),
+                    color: identities.color(&wallet.address),
+                });
+            }
         });
+        render_label_editor(ui, state, &wallet.address, app);
         ui.add_space(10.0);
 
         // SOL balance
         ui.horizontal(|ui| {
             ui.label(Icons::icon_red(material::TOKEN, size::SMALL));
-            ui.label("SOL Balance:");
-            ui.colored_label(theme.selected, format!("{:.6}", wallet.sol_balance));
+            ui.label(tr!("wallet.sol_balance"));
+            ui.colored_label(theme.selected, i18n::format_decimal(wallet.sol_balance, 6));
+            if ui.small_button(tr!("wallet.buy_sol")).clicked() {
+                app.handle_onramp_open();
+            }
+            render_airdrop_button(ui, state, app);
         });
         ui.add_space(10.0);
 
@@ -54,14 +103,17 @@ fn render_wallet_info(
         // Token balances table
         ui.horizontal(|ui| {
             ui.label(Icons::icon_red(material::TOKEN, size::MEDIUM));
-            ui.heading("Token Balances");
+            ui.heading(tr!("wallet.token_balances"));
+            if ui.small_button(format!("{} {}", material::REFRESH, tr!("common.refresh"))).clicked() {
+                app.handle_token_balances_refresh();
+            }
         });
         ui.add_space(5.0);
 
         // Enhanced table with scroll area
         use crate::ui::widgets::tables;
         let config = tables::TableConfig {
-            num_columns: 4,
+            num_columns: 5,
             spacing: [10.0, 5.0],
             striped: true,
             scrollable: true,
@@ -71,14 +123,26 @@ fn render_wallet_info(
             ui,
             "token_balances",
             config,
-            &["Token", "Amount", "USD Value", "Value"],
+            &[
+                tr!("wallet.token"),
+                tr!("wallet.amount"),
+                tr!("wallet.usd_value"),
+                tr!("wallet.value"),
+                tr!("wallet.transfer_fee"),
+            ],
             theme,
             |ui| {
                 // Data rows
                 for balance in &wallet.token_balances {
-                    ui.label(&balance.symbol);
-                    ui.monospace(format!("{:.6}", balance.amount));
-                    ui.colored_label(theme.success, format!("${:.2}", balance.usd_value));
+                    ui.horizontal(|ui| {
+                        ui.label(&balance.symbol).on_hover_text(&balance.mint);
+                        if balance.program == TokenProgram::Token2022 {
+                            ui.colored_label(theme.warning, "T22")
+                                .on_hover_text(tr!("wallet.token_2022"));
+                        }
+                    });
+                    ui.monospace(i18n::format_decimal(balance.amount, 6));
+                    ui.colored_label(theme.success, format!("${}", i18n::format_decimal(balance.usd_value, 2)));
                     let value_icon = if balance.usd_value > 0.0 {
                         material::ARROW_UP
                     } else {
@@ -89,20 +153,261 @@ fn render_wallet_info(
                     } else {
                         ui.label("");
                     }
+                    render_transfer_fee(ui, balance, theme);
                     ui.end_row();
                 }
             },
         );
 
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        render_send_panel(ui, state, wallet, app, theme);
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        render_receive_panel(ui, state, wallet, app, theme);
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        staking::render_staking_panel(ui, state, wallet, app, theme);
+
+        ui.add_space(10.0);
+        ui.separator();
         ui.add_space(10.0);
 
         // Disconnect button with icon
-        if ui.add(egui::Button::new(format!("{} Disconnect Wallet", material::CLOSE)).fill(theme.error)).clicked() {
+        if ui.add(egui::Button::new(format!("{} {}", material::CLOSE, tr!("wallet.disconnect"))).fill(theme.error)).clicked() {
             app.handle_wallet_disconnect_click();
         }
     });
 }
 
+/// "Airdrop 1 SOL (devnet)", disabled off devnet, while an airdrop is
+/// confirming and while the faucet's rate limit cools down
+fn render_airdrop_button(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
+    let blocked = airdrop_blocked(state, Instant::now());
+    let label = tr!("wallet.airdrop", amount = format_token_amount(AIRDROP_LAMPORTS, SOL_DECIMALS));
+    let button = ui.add_enabled(blocked.is_none(), egui::Button::new(label).small());
+    if state.airdrop.requesting {
+        ui.spinner();
+    }
+    if let Some(reason) = &blocked {
+        button.on_disabled_hover_text(reason);
+        // Repaint so the button comes back when the cooldown ends
+        if state.airdrop.cooldown_until.is_some() {
+            ui.ctx().request_repaint_after(Duration::from_secs(1));
+        }
+    } else if button.clicked() {
+        app.handle_airdrop_request();
+    }
+}
+
+/// Inline label and color editor, while `address` is being edited
+///
+/// An empty label goes back to the shortened address.
+fn render_label_editor(ui: &mut egui::Ui, state: &AppState, address: &str, app: &mut impl AppLike) {
+    if !state.wallet_label_edit.as_ref().is_some_and(|edit| edit.address == address) {
+        return;
+    }
+
+    let mut save = false;
+    let mut cancel = false;
+    ui.horizontal(|ui| {
+        let mut state_write = app.state().write();
+        let Some(edit) = state_write.wallet_label_edit.as_mut() else {
+            return;
+        };
+        ui.label(tr!("common.label_field"));
+        ui.add(egui::TextEdit::singleline(&mut edit.label).hint_text(tr!("wallet.label_hint")).desired_width(160.0));
+        for color in PALETTE {
+            let selected = edit.color == color;
+            let swatch = egui::Button::new("  ")
+                .fill(wallet_chip::chip_color(color))
+                .stroke(if selected { ui.visuals().selection.stroke } else { egui::Stroke::NONE });
+            if ui.add(swatch).clicked() {
+                edit.color = color;
+            }
+        }
+        save = ui.button(tr!("common.save")).clicked();
+        cancel = ui.button(tr!("common.cancel")).clicked();
+    });
+
+    if save {
+        app.handle_wallet_label_save();
+    } else if cancel {
+        app.state().write().wallet_label_edit = None;
+    }
+}
+
+/// Send form: recipient, token, amount, memo and the fee estimate
+fn render_send_panel(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    wallet: &crate::app::WalletState,
+    app: &mut impl AppLike,
+    theme: &Theme,
+) {
+    ui.horizontal(|ui| {
+        ui.label(Icons::icon_red(material::SEND, size::MEDIUM));
+        ui.heading(tr!("wallet.send"));
+    });
+    ui.add_space(5.0);
+
+    egui::Grid::new("send_form").num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+        let mut state_write = app.state().write();
+        let form = &mut state_write.send;
+
+        ui.label(tr!("wallet.recipient"));
+        ui.add(
+            egui::TextEdit::singleline(&mut form.recipient)
+                .hint_text(tr!("wallet.recipient_hint"))
+                .font(egui::TextStyle::Monospace)
+                .desired_width(380.0),
+        );
+        ui.end_row();
+
+        ui.label(tr!("wallet.token_field"));
+        let selected = form
+            .mint
+            .as_deref()
+            .and_then(|mint| wallet.token_balances.iter().find(|balance| balance.mint == mint))
+            .map_or_else(|| "SOL".to_string(), |balance| balance.symbol.clone());
+        egui::ComboBox::from_id_salt("send_token")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut form.mint, None, format!("SOL ({:.6})", wallet.sol_balance));
+                for balance in &wallet.token_balances {
+                    let text = format!("{} ({:.6})", balance.symbol, balance.amount);
+                    ui.selectable_value(&mut form.mint, Some(balance.mint.clone()), text);
+                }
+            });
+        ui.end_row();
+
+        ui.label(tr!("wallet.amount_field"));
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut form.amount).hint_text("0.0").desired_width(160.0));
+            if ui.small_button(tr!("wallet.max")).clicked() {
+                if let Some(max) = max_amount(form.mint.as_deref(), wallet) {
+                    form.amount = max;
+                }
+            }
+        });
+        ui.end_row();
+
+        ui.label(tr!("wallet.memo_field"));
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut form.memo).hint_text(tr!("wallet.optional")).desired_width(260.0));
+            if !form.memo.trim().is_empty() {
+                match validate_memo(&form.memo) {
+                    Ok(valid) => ui.colored_label(theme.dim, format!("{}/{} bytes", valid.len(), MAX_MEMO_BYTES)),
+                    Err(e) => ui.colored_label(theme.error, e.to_string()),
+                };
+            }
+        });
+        ui.end_row();
+
+        ui.label(tr!("wallet.network_fee"));
+        ui.colored_label(theme.dim, format!("≈ {} SOL", format_token_amount(BASE_FEE_LAMPORTS, SOL_DECIMALS)))
+            .on_hover_text(tr!("wallet.network_fee_hint"));
+        ui.end_row();
+    });
+    ui.add_space(5.0);
+
+    let form = &state.send;
+    let mut review = false;
+    ui.horizontal(|ui| {
+        let busy = form.preparing || form.sending;
+        review = ui.add_enabled(!busy, egui::Button::new(format!("{} {}", material::SEND, tr!("wallet.review")))).clicked();
+        if busy {
+            ui.spinner();
+            ui.colored_label(theme.dim, if form.sending { tr!("wallet.sending") } else { tr!("wallet.preparing") });
+        }
+    });
+    if let Some(error) = &form.error {
+        ui.colored_label(theme.error, error);
+    }
+
+    if review {
+        app.handle_send_review();
+    }
+}
+
+/// Receive panel: the full address, a copy button and its QR code
+fn render_receive_panel(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    wallet: &crate::app::WalletState,
+    app: &mut impl AppLike,
+    theme: &Theme,
+) {
+    ui.horizontal(|ui| {
+        ui.label(Icons::icon_success(material::RECEIVE, size::MEDIUM));
+        ui.heading(tr!("wallet.receive"));
+    });
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        ui.add(egui::Label::new(egui::RichText::new(&wallet.address).monospace().size(16.0)).selectable(true));
+        if ui.button(tr!("common.copy")).clicked() {
+            ui.ctx().copy_text(wallet.address.clone());
+            app.state()
+                .write()
+                .pending_notifications
+                .push(("success".to_string(), tr!("wallet.address_copied").to_string()));
+        }
+    });
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        ui.label(tr!("wallet.request_amount"));
+        let mut state_write = app.state().write();
+        ui.add(egui::TextEdit::singleline(&mut state_write.receive.amount).hint_text(tr!("wallet.optional")).desired_width(120.0));
+        ui.label("SOL");
+    });
+    ui.add_space(5.0);
+
+    match receive_qr::payment_request(&wallet.address, &state.receive.amount) {
+        Ok(request) => {
+            if request != wallet.address {
+                ui.colored_label(theme.dim, &request).on_hover_text(tr!("wallet.solana_pay_hint"));
+            }
+            receive_qr::show(ui, &request, 320.0);
+        }
+        Err(e) => {
+            ui.colored_label(theme.error, e);
+        }
+    }
+}
+
+/// Transfer fee cell: the rate, and what arrives when the whole balance is sent
+fn render_transfer_fee(ui: &mut egui::Ui, balance: &TokenBalance, theme: &Theme) {
+    match balance.transfer_fee {
+        Some(fee) if fee.basis_points > 0 => {
+            let preview = TransferPreview::new(balance.raw_amount, balance.raw_amount, balance.decimals, Some(fee));
+            let response = ui.colored_label(theme.warning, format!("{}%", i18n::format_decimal(fee.percent(), 2)));
+            if let Ok(preview) = preview {
+                response.on_hover_text(tr!(
+                    "wallet.transfer_fee_hint",
+                    amount = i18n::format_decimal(preview.ui_amount(), 6),
+                    symbol = balance.symbol,
+                    received = i18n::format_decimal(preview.ui_received(), 6),
+                    fee = i18n::format_decimal(preview.ui_fee(), 6),
+                    cap = i18n::format_decimal(fee.maximum_fee as f64 / 10f64.powi(balance.decimals as i32), 6),
+                ));
+            }
+        }
+        _ => {
+            ui.colored_label(theme.dim, "-");
+        }
+    }
+}
+
 /// Render no wallet connected message
 fn render_no_wallet(ui: &mut egui::Ui, app: &mut impl crate::app::AppLike, theme: &Theme) {
     use crate::ui::widgets::{layouts, forms};
@@ -111,17 +416,17 @@ fn render_no_wallet(ui: &mut egui::Ui, app: &mut impl crate::app::AppLike, theme
         ui.add_space(20.0);
         ui.label(Icons::icon_error(material::WALLET, size::XLARGE));
         ui.add_space(10.0);
-        ui.colored_label(theme.error, "No Wallet Connected");
+        ui.colored_label(theme.error, tr!("wallet.none"));
         ui.add_space(10.0);
-        forms::render_hint(ui, "Connect or generate a Solana wallet to get started", theme);
+        forms::render_hint(ui, tr!("wallet.none_hint"), theme);
         ui.add_space(20.0);
 
         ui.horizontal(|ui| {
-            if forms::render_button(ui, "Connect Wallet", Some(material::WALLET), theme, Some(theme.selected), None).clicked() {
+            if forms::render_button(ui, tr!("wallet.connect"), Some(material::WALLET), theme, Some(theme.selected), None).clicked() {
                 app.handle_wallet_connect_click();
             }
 
-            if forms::render_button(ui, "Generate New Wallet", Some(material::SETTINGS), theme, None, None).clicked() {
+            if forms::render_button(ui, tr!("wallet.generate"), Some(material::SETTINGS), theme, None, None).clicked() {
                 app.handle_wallet_generate_click();
             }
         });
@@ -129,7 +434,136 @@ fn render_no_wallet(ui: &mut egui::Ui, app: &mut impl crate::app::AppLike, theme
         ui.add_space(10.0);
         ui.horizontal(|ui| {
             ui.label(Icons::icon_info(material::INFO, size::SMALL));
-            forms::render_hint(ui, "Tip: Create a wallet with: solana-keygen new", theme);
+            forms::render_hint(ui, tr!("wallet.keygen_tip"), theme);
         });
     });
 }
+
+/// Render the derived accounts of the imported seed
+fn render_derived_accounts(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    app: &mut impl AppLike,
+    theme: &Theme,
+) {
+    use crate::ui::widgets::forms;
+
+    let derived = &state.derived_accounts;
+
+    ui.horizontal(|ui| {
+        ui.label(Icons::icon_dim(material::WALLET, size::MEDIUM));
+        ui.heading(tr!("wallet.derived"));
+        if derived.scanning {
+            ui.spinner();
+        }
+    });
+    ui.add_space(5.0);
+
+    let Some(keystore) = &derived.keystore else {
+        forms::render_hint(ui, tr!("wallet.derived_hint"), theme);
+        ui.add_space(5.0);
+
+        let import_clicked = {
+            let mut state_write = app.state().write();
+            let inputs = &mut state_write.derived_accounts;
+            forms::render_text_input(ui, tr!("wallet.recovery_phrase"), &mut inputs.mnemonic_input, tr!("wallet.recovery_phrase_hint"), true, [420.0, 24.0]);
+            forms::render_text_input(ui, tr!("wallet.passphrase"), &mut inputs.passphrase_input, tr!("wallet.passphrase_hint"), true, [420.0, 24.0]);
+            forms::render_text_input(ui, tr!("wallet.keystore_password"), &mut inputs.password_input, tr!("wallet.keystore_password_hint"), true, [420.0, 24.0]);
+            ui.add_space(5.0);
+            let ready = !inputs.mnemonic_input.trim().is_empty() && !inputs.password_input.is_empty();
+            ui.add_enabled(ready && !derived.scanning, egui::Button::new(format!("{} {}", material::LOCK, tr!("wallet.import_seed"))))
+                .clicked()
+        };
+        if import_clicked {
+            app.handle_mnemonic_import();
+        }
+        return;
+    };
+
+    // Password is consumed by the next scan or activation
+    let (scan_clicked, has_password) = ui
+        .horizontal(|ui| {
+            let mut state_write = app.state().write();
+            let password = &mut state_write.derived_accounts.password_input;
+            ui.label(tr!("common.keystore_password"));
+            ui.add(egui::TextEdit::singleline(password).password(true).desired_width(200.0));
+            let has_password = !password.is_empty();
+            let clicked = ui
+                .add_enabled(has_password && !derived.scanning, egui::Button::new(format!("{} {}", material::REFRESH, tr!("wallet.scan"))))
+                .on_hover_text(tr!("wallet.scan_hint"))
+                .clicked();
+            (clicked, has_password)
+        })
+        .inner;
+    if scan_clicked {
+        app.handle_derived_scan();
+    }
+    ui.add_space(5.0);
+
+    let mut activate = None;
+    let mut edit_label = None;
+    let mut save_label = None;
+
+    egui::Grid::new("derived_accounts")
+        .num_columns(5)
+        .spacing([10.0, 5.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for header in ["#", tr!("common.label"), tr!("common.address"), "SOL", ""] {
+                ui.colored_label(theme.selected, header);
+            }
+            ui.end_row();
+
+            for account in &keystore.accounts {
+                let is_active = derived.active_index == Some(account.index)
+                    && state.wallet.as_ref().is_some_and(|w| w.address == account.address);
+
+                ui.monospace(account.index.to_string());
+
+                match &derived.label_edit {
+                    Some((index, _)) if *index == account.index => {
+                        let mut state_write = app.state().write();
+                        if let Some((_, text)) = state_write.derived_accounts.label_edit.as_mut() {
+                            let response = ui.add(egui::TextEdit::singleline(text).desired_width(120.0));
+                            if response.lost_focus() {
+                                save_label = Some((account.index, text.clone()));
+                            }
+                        }
+                    }
+                    _ => {
+                        let label = account.label.as_deref().unwrap_or("-");
+                        if ui.link(label).on_hover_text(tr!("wallet.rename_hint")).clicked() {
+                            edit_label = Some((account.index, account.label.clone().unwrap_or_default()));
+                        }
+                    }
+                }
+
+                ui.monospace(&account.address);
+                match derived.balances.get(&account.index) {
+                    Some(balance) => ui.monospace(format!("{:.6}", balance)),
+                    None => ui.colored_label(theme.border, "-"),
+                };
+
+                if is_active {
+                    ui.colored_label(theme.success, tr!("wallet.active"));
+                } else if ui
+                    .add_enabled(has_password && !derived.scanning, egui::Button::new(tr!("wallet.activate")))
+                    .on_hover_text(tr!("wallet.activate_hint"))
+                    .clicked()
+                {
+                    activate = Some(account.index);
+                }
+                ui.end_row();
+            }
+        });
+
+    if let Some(edit) = edit_label {
+        app.state().write().derived_accounts.label_edit = Some(edit);
+    }
+    if let Some((index, label)) = save_label {
+        app.handle_derived_label(index, label);
+    }
+    if let Some(index) = activate {
+        app.handle_derived_activate(index);
+    }
+}
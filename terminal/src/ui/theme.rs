@@ -2,14 +2,23 @@
 //!
 //! Xterminal-style dark theme with red, white, and black colors for egui.
 //! Professional trading terminal aesthetic with high contrast and sharp edges.
+//!
+//! The colors come from a [`ThemeConfig`]: the saved one, a [`ThemePreset`],
+//! or a theme file imported in Settings. [`Theme::apply_custom_theme`] makes a
+//! config the active one, and [`Theme::default`] hands out its colors, so
+//! screens pick up a new theme on the next frame.
 
 use egui::{Color32, Visuals, Stroke, Context};
 use egui::Theme as EguiTheme;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
 use serde::{Serialize, Deserialize};
-use std::path::Path;
+
+/// Config last applied with [`Theme::apply_custom_theme`]
+static ACTIVE_CONFIG: Lazy<RwLock<ThemeConfig>> = Lazy::new(|| RwLock::new(ThemeConfig::default()));
 
 /// Serializable theme configuration for persistence
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ThemeConfig {
     /// Pure black background
     pub background: [u8; 3],
@@ -60,27 +69,21 @@ impl Default for ThemeConfig {
 }
 
 impl ThemeConfig {
-    /// Load theme configuration from a JSON file
-    pub fn load_from_file(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
-        if !path.exists() {
-            return Ok(Self::default());
-        }
-        
-        let content = std::fs::read_to_string(path)?;
-        let config: ThemeConfig = serde_json::from_str(&content)?;
-        Ok(config)
+    /// Parse a theme file exported from Settings
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("not a theme file: {}", e))
     }
 
-    /// Save theme configuration to a JSON file
-    pub fn save_to_file(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
-        // Create parent directory if it doesn't exist
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-        
-        let content = serde_json::to_string_pretty(self)?;
-        std::fs::write(path, content)?;
-        Ok(())
+    /// Theme file contents, as written by Settings > Export
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("theme config serializes")
+    }
+
+    /// Whether the background is light, so egui's light widget defaults apply
+    pub fn is_light(&self) -> bool {
+        let [r, g, b] = self.background;
+        // Rec. 601 luma
+        (299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000 > 127
     }
 
     /// Convert ThemeConfig to XterminalColors
@@ -122,6 +125,91 @@ impl ThemeConfig {
     }
 }
 
+/// Built-in themes offered in Settings > Theme Colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemePreset {
+    /// Black with red accents (the default)
+    Bloomberg,
+    Light,
+    HighContrast,
+    Solarized,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 4] = [
+        ThemePreset::Bloomberg,
+        ThemePreset::Light,
+        ThemePreset::HighContrast,
+        ThemePreset::Solarized,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemePreset::Bloomberg => "Bloomberg",
+            ThemePreset::Light => "Light",
+            ThemePreset::HighContrast => "High contrast",
+            ThemePreset::Solarized => "Solarized",
+        }
+    }
+
+    pub fn config(self) -> ThemeConfig {
+        match self {
+            ThemePreset::Bloomberg => ThemeConfig::default(),
+            ThemePreset::Light => ThemeConfig {
+                background: [245, 245, 245],
+                text: [20, 20, 20],
+                red_primary: [190, 0, 0],
+                red_dark: [140, 0, 0],
+                red_highlight: [220, 40, 40],
+                border_dark: [200, 200, 200],
+                border_red_tint: [235, 200, 200],
+                green_success: [0, 140, 60],
+                red_error: [200, 0, 0],
+                yellow_warning: [190, 120, 0],
+                blue_info: [30, 90, 200],
+                gray_inactive: [225, 225, 225],
+                gray_secondary: [100, 100, 100],
+            },
+            ThemePreset::HighContrast => ThemeConfig {
+                background: [0, 0, 0],
+                text: [255, 255, 255],
+                red_primary: [255, 220, 0],
+                red_dark: [200, 170, 0],
+                red_highlight: [255, 255, 0],
+                border_dark: [255, 255, 255],
+                border_red_tint: [255, 220, 0],
+                green_success: [0, 255, 0],
+                red_error: [255, 80, 80],
+                yellow_warning: [255, 170, 0],
+                blue_info: [0, 200, 255],
+                gray_inactive: [20, 20, 20],
+                gray_secondary: [220, 220, 220],
+            },
+            // Ethan Schoonover's Solarized (dark)
+            ThemePreset::Solarized => ThemeConfig {
+                background: [0, 43, 54],
+                text: [147, 161, 161],
+                red_primary: [220, 50, 47],
+                red_dark: [203, 75, 22],
+                red_highlight: [211, 54, 130],
+                border_dark: [7, 54, 66],
+                border_red_tint: [88, 110, 117],
+                green_success: [133, 153, 0],
+                red_error: [220, 50, 47],
+                yellow_warning: [181, 137, 0],
+                blue_info: [38, 139, 210],
+                gray_inactive: [7, 54, 66],
+                gray_secondary: [131, 148, 150],
+            },
+        }
+    }
+
+    /// Preset `config` is exactly, if any
+    pub fn matching(config: &ThemeConfig) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.config() == *config)
+    }
+}
+
 /// Xterminal color palette
 #[derive(Clone)]
 pub struct XterminalColors {
@@ -208,9 +296,17 @@ pub struct Theme {
     pub background: Color32,
 }
 
+/// Colors of the active theme (see [`Theme::apply_custom_theme`])
 impl Default for Theme {
     fn default() -> Self {
-        let colors = XterminalColors::default();
+        Self::from_config(&ACTIVE_CONFIG.read())
+    }
+}
+
+impl Theme {
+    /// Theme with the colors of `config`
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        let colors = config.to_xterminal_colors();
         Theme {
             colors: colors.clone(),
             normal: colors.text,
@@ -226,9 +322,7 @@ impl Default for Theme {
             background: colors.background,
         }
     }
-}
 
-impl Theme {
     /// Get color for price change percentage
     pub fn price_change_color(&self, change: f64) -> Color32 {
         if change > 0.0 {
@@ -240,31 +334,26 @@ impl Theme {
     }
     }
 
-    /// Format price change with color
+    /// Format price change with color, with the locale's decimal separator
     pub fn format_price_change(&self, change: f64) -> (String, Color32) {
-        let text = if change >= 0.0 {
-            format!("+{:.2}%", change)
-        } else {
-            format!("{:.2}%", change)
-        };
-        (text, self.price_change_color(change))
+        (crate::ui::i18n::format_percent(change), self.price_change_color(change))
     }
 
     /// Create Xterminal-style egui Visuals from ThemeConfig
     pub fn xterminal_visuals_from_config(config: &ThemeConfig) -> Visuals {
         let colors = config.to_xterminal_colors();
-        let mut visuals = Visuals::dark();
+        let mut visuals = if config.is_light() { Visuals::light() } else { Visuals::dark() };
         
         // Override text color
         visuals.override_text_color = Some(colors.text);
         
-        // Background colors - Complete black
-        visuals.faint_bg_color = Color32::from_rgb(0, 0, 0);
-        visuals.extreme_bg_color = Color32::from_rgb(0, 0, 0);
+        // Background colors - complete black in the default theme
+        visuals.faint_bg_color = colors.background;
+        visuals.extreme_bg_color = colors.background;
 
-        // Panel colors - Complete black
-        visuals.panel_fill = Color32::from_rgb(0, 0, 0);
-        visuals.window_fill = Color32::from_rgb(0, 0, 0);
+        // Panel colors
+        visuals.panel_fill = colors.background;
+        visuals.window_fill = colors.background;
         visuals.window_stroke = Stroke::new(1.0, colors.border_dark);
         
         // Non-interactive widgets
@@ -275,24 +364,25 @@ impl Theme {
         // Inactive widgets
         visuals.widgets.inactive.bg_fill = colors.gray_inactive;
         visuals.widgets.inactive.bg_stroke = Stroke::new(1.0, colors.border_dark);
-        visuals.widgets.inactive.weak_bg_fill = Color32::from_rgb(30, 30, 30);
+        visuals.widgets.inactive.weak_bg_fill = colors.gray_inactive;
         
-        // Hovered widgets - Red highlight
-        visuals.widgets.hovered.bg_fill = Color32::from_rgb(51, 0, 0); // Dark red background
+        // Hovered widgets - Red highlight (dark red over the black background)
+        visuals.widgets.hovered.bg_fill = colors.red_primary.gamma_multiply(0.25);
         visuals.widgets.hovered.bg_stroke = Stroke::new(2.0, colors.red_primary);
-        visuals.widgets.hovered.weak_bg_fill = Color32::from_rgb(40, 0, 0);
+        visuals.widgets.hovered.weak_bg_fill = colors.red_primary.gamma_multiply(0.2);
         
         // Active/pressed widgets - Bright red
-        visuals.widgets.active.bg_fill = Color32::from_rgb(102, 0, 0); // Medium red
+        visuals.widgets.active.bg_fill = colors.red_primary.gamma_multiply(0.5); // Medium red
         visuals.widgets.active.bg_stroke = Stroke::new(2.0, colors.red_primary);
-        visuals.widgets.active.weak_bg_fill = Color32::from_rgb(76, 0, 0);
+        visuals.widgets.active.weak_bg_fill = colors.red_primary.gamma_multiply(0.375);
         
         // Open (expanded) state
-        visuals.widgets.open.bg_fill = Color32::from_rgb(51, 0, 0);
+        visuals.widgets.open.bg_fill = colors.red_primary.gamma_multiply(0.25);
         visuals.widgets.open.bg_stroke = Stroke::new(2.0, colors.red_primary);
         
         // Selection highlight - Red with transparency
-        visuals.selection.bg_fill = Color32::from_rgba_unmultiplied(204, 0, 0, 76); // 30% opacity red
+        let [r, g, b] = config.red_primary;
+        visuals.selection.bg_fill = Color32::from_rgba_unmultiplied(r, g, b, 76); // 30% opacity
         visuals.selection.stroke = Stroke::new(2.0, colors.red_primary);
         
         // Hyperlinks
@@ -319,8 +409,18 @@ impl Theme {
         Self::xterminal_visuals_from_config(&ThemeConfig::default())
     }
 
-    /// Apply custom theme to an egui context
+    /// Apply custom theme to an egui context and make it the active theme
+    ///
+    /// Safe to call every frame (Settings does, for its live preview):
+    /// re-applying the config already in place does nothing, and nothing is
+    /// applied before the fonts have registered their text styles, so a call
+    /// that comes too early can't clobber them.
     pub fn apply_custom_theme(ctx: &Context, config: &ThemeConfig) {
+        let applied_id = egui::Id::new("xforce_applied_theme");
+        if ctx.data(|d| d.get_temp::<ThemeConfig>(applied_id)).as_ref() == Some(config) {
+            return;
+        }
+
         // Verify that required text styles exist (defensive check)
         let style_check = ctx.style();
         let required_styles = [
@@ -339,10 +439,11 @@ impl Theme {
         if !missing_styles.is_empty() {
             tracing::warn!(
                 "Missing text styles before theme application: {:?}. \
-                Font initialization may not have been called. \
-                Application may panic when rendering text.",
+                Font initialization may not have been called; \
+                theme not applied.",
                 missing_styles
             );
+            return;
         }
         
         let visuals = Self::xterminal_visuals_from_config(config);
@@ -374,6 +475,8 @@ impl Theme {
             style.spacing.tooltip_width = 400.0;
         });
         
+        ctx.data_mut(|d| d.insert_temp(applied_id, config.clone()));
+        *ACTIVE_CONFIG.write() = config.clone();
         tracing::debug!("Applied custom theme visuals and spacing using style_mut_of API");
     }
 
@@ -400,3 +503,29 @@ impl Theme {
         Self::apply_xterminal_theme(ctx);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_round_trip_through_theme_files() {
+        for preset in ThemePreset::ALL {
+            let config = preset.config();
+            assert_eq!(ThemeConfig::from_json(&config.to_json()), Ok(config.clone()));
+            assert_eq!(ThemePreset::matching(&config), Some(preset));
+        }
+        assert!(ThemePreset::Light.config().is_light());
+        assert!(!ThemePreset::Solarized.config().is_light());
+    }
+
+    #[test]
+    fn test_invalid_theme_files_are_rejected() {
+        assert!(ThemeConfig::from_json("not json").is_err());
+        // Missing colors
+        assert!(ThemeConfig::from_json(r#"{"background": [0, 0, 0]}"#).is_err());
+        // Channel out of range
+        let json = ThemeConfig::default().to_json().replace("204", "300");
+        assert!(ThemeConfig::from_json(&json).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! # Attachment Preview
+//!
+//! Inline previews of image attachments in the messaging screen.
+//!
+//! Images are downloaded and decoded off the UI thread, scaled down to fit
+//! [`MAX_PREVIEW_DIMENSION`], and uploaded to the GPU once per attachment.
+
+use egui;
+use std::sync::Arc;
+
+/// Longest side of a preview, in pixels
+pub const MAX_PREVIEW_DIMENSION: u32 = 240;
+
+/// Largest file the terminal will try to upload (matches the backend limit)
+pub const MAX_UPLOAD_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Preview of one image attachment
+#[derive(Debug, Clone)]
+pub enum AttachmentPreview {
+    /// Being downloaded and decoded
+    Loading,
+    /// Decoded, already scaled to fit [`MAX_PREVIEW_DIMENSION`]
+    Ready(Arc<egui::ColorImage>),
+    /// Download or decoding failed
+    Failed(String),
+}
+
+/// Size of a `width` × `height` image scaled down to fit within `max` × `max`,
+/// keeping its aspect ratio
+///
+/// Images that already fit are left as they are.
+pub fn fit_within(width: u32, height: u32, max: u32) -> (u32, u32) {
+    if width <= max && height <= max {
+        return (width, height);
+    }
+    let scale = max as f64 / width.max(height) as f64;
+    let scaled = |side: u32| ((side as f64 * scale).round() as u32).max(1);
+    (scaled(width), scaled(height))
+}
+
+/// Decode an image and scale it to fit within `max_dimension`
+pub fn decode_preview(bytes: &[u8], max_dimension: u32) -> Result<egui::ColorImage, String> {
+    let image = image::load_from_memory(bytes).map_err(|e| format!("Unreadable image: {}", e))?;
+    let (width, height) = fit_within(image.width(), image.height(), max_dimension);
+    let image = if (width, height) == (image.width(), image.height()) {
+        image
+    } else {
+        image.resize_exact(width, height, image::imageops::FilterType::Triangle)
+    };
+    let rgba = image.to_rgba8();
+    Ok(egui::ColorImage::from_rgba_unmultiplied([width as usize, height as usize], rgba.as_raw()))
+}
+
+/// Human-readable file size, e.g. "512 B", "14.2 KB", "3.1 MB"
+pub fn format_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    match bytes as f64 {
+        size if size >= MB => format!("{:.1} MB", size / MB),
+        size if size >= KB => format!("{:.1} KB", size / KB),
+        _ => format!("{} B", bytes),
+    }
+}
+
+/// Show a decoded preview, uploading its texture the first time it's shown
+pub fn show(ui: &mut egui::Ui, attachment_id: &str, image: &Arc<egui::ColorImage>) -> egui::Response {
+    let texture_id = egui::Id::new(("attachment_preview", attachment_id));
+    let texture = match ui.data(|data| data.get_temp::<egui::TextureHandle>(texture_id)) {
+        Some(texture) => texture,
+        None => {
+            let texture = ui.ctx().load_texture(
+                format!("attachment-{}", attachment_id),
+                egui::ColorImage::clone(image),
+                egui::TextureOptions::LINEAR,
+            );
+            ui.data_mut(|data| data.insert_temp(texture_id, texture.clone()));
+            texture
+        }
+    };
+    ui.add(egui::Image::new(&texture).sense(egui::Sense::click()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_within_keeps_aspect_ratio() {
+        assert_eq!(fit_within(100, 50, 240), (100, 50));
+        assert_eq!(fit_within(1920, 1080, 240), (240, 135));
+        assert_eq!(fit_within(300, 1200, 240), (60, 240));
+        // Never collapses to nothing
+        assert_eq!(fit_within(10_000, 1, 240), (240, 1));
+    }
+
+    #[test]
+    fn test_decode_preview_scales_down() {
+        let mut png = Vec::new();
+        image::RgbaImage::from_pixel(480, 120, image::Rgba([255, 0, 0, 255]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let preview = decode_preview(&png, MAX_PREVIEW_DIMENSION).unwrap();
+        assert_eq!(preview.size, [240, 60]);
+        assert_eq!(preview.pixels[0], egui::Color32::RED);
+
+        assert!(decode_preview(b"not an image", MAX_PREVIEW_DIMENSION).is_err());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(14_540), "14.2 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+}
@@ -1,25 +1,30 @@
 //! # Branding Components
 //!
 //! Reusable branding elements used across screens (landing, auth, etc.)
+//!
+//! Title, tagline and the rotating mesh come from [`Branding`], which
+//! `branding.toml` can override.
 
 use egui::{self, Color32};
+use crate::app::branding::Branding;
 
-/// Render the XFTerminal title with red XF accent
-pub fn render_title(ui: &mut egui::Ui, size: f32) {
+/// Render the title with its accent (the red "XF" of XFTerminal by default)
+pub fn render_title(ui: &mut egui::Ui, branding: &Branding, size: f32) {
     let title_font = crate::ui::fonts::FontConfig::get_avenir_font(ui.ctx(), size);
-    let red_color = Color32::from_rgb(204, 0, 0);
-    
+
     ui.horizontal(|ui| {
-        ui.label(egui::RichText::new("X").font(title_font.clone()).color(red_color).strong().italics());
-        ui.label(egui::RichText::new("F").font(title_font.clone()).color(red_color).strong().italics());
-        ui.label(egui::RichText::new("Terminal").font(title_font.clone()).color(Color32::WHITE).strong());
+        // One label per accent letter, matching the spacing of the original XF
+        for letter in branding.title_accent.chars() {
+            ui.label(egui::RichText::new(letter).font(title_font.clone()).color(branding.accent_color).strong().italics());
+        }
+        ui.label(egui::RichText::new(&branding.title).font(title_font.clone()).color(Color32::WHITE).strong());
     });
 }
 
-/// Render the subtitle "Trade anywhere"
-pub fn render_subtitle(ui: &mut egui::Ui, size: f32) {
+/// Render the tagline ("Trade anywhere" by default)
+pub fn render_subtitle(ui: &mut egui::Ui, branding: &Branding, size: f32) {
     let subtitle_font = crate::ui::fonts::FontConfig::get_avenir_font(ui.ctx(), size);
-    ui.label(egui::RichText::new("Trade anywhere").font(subtitle_font).color(Color32::WHITE).strong());
+    ui.label(egui::RichText::new(&branding.tagline).font(subtitle_font).color(Color32::WHITE).strong());
 }
 
 /// Render the prompt text (e.g., "<Enter> to begin")
@@ -29,11 +34,11 @@ pub fn render_prompt(ui: &mut egui::Ui, text: &str, size: f32) {
 }
 
 /// Render the complete branding section (title, subtitle, prompt)
-pub fn render_branding_section(ui: &mut egui::Ui, prompt_text: &str) {
+pub fn render_branding_section(ui: &mut egui::Ui, branding: &Branding, prompt_text: &str) {
     ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-        render_title(ui, 72.0);
+        render_title(ui, branding, 72.0);
         ui.add_space(5.0);
-        render_subtitle(ui, 56.0);
+        render_subtitle(ui, branding, 56.0);
         ui.add_space(8.0);
         render_prompt(ui, prompt_text, 18.0);
     });
@@ -67,7 +72,7 @@ pub fn render_footer(ui: &mut egui::Ui) {
     });
 }
 
-/// Render the rotating cube with proper spacing
+/// Render the rotating brand mesh with proper spacing
 pub fn render_cube_section(ui: &mut egui::Ui, cube: &mut crate::ui::cube::RotatingCube) {
     ui.add_space(30.0);
     
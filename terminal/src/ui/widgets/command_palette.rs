@@ -0,0 +1,193 @@
+//! # Command Palette Widget
+//!
+//! Ctrl+K overlay for running commands from the keyboard, e.g. `go to wallet`,
+//! `swap SOL USDC 5` or `set timeframe 15m` (see [`crate::app::commands`]).
+//! Commands are fuzzy-matched as you type. Arrow keys move the selection, Tab
+//! completes the selected name, Enter runs it and Escape closes the palette.
+//!
+//! The palette is drawn before the screen so it takes the keys it uses before
+//! any screen sees them, and its input keeps focus so typing stays in it.
+
+use egui;
+use crate::app::commands::{self, CommandAction};
+use crate::app::{AppLike, AppState, Screen, SwapTab, TokenPickerTarget};
+use crate::ui::theme::Theme;
+use crate::ui::widgets::icons::{Icons, material, size};
+
+/// Render the command palette
+pub fn render_command_palette(ctx: &egui::Context, state: &AppState, app: &mut impl AppLike) {
+    let theme = Theme::default();
+    let palette = &state.palette;
+    let suggestions = palette.registry.suggest(&palette.input, app.current_screen(), state.is_authenticated());
+    let count = suggestions.len();
+
+    let (up, down, enter, escape, tab) = ctx.input_mut(|i| {
+        (
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Tab),
+        )
+    });
+    if escape {
+        app.handle_command_palette_toggle();
+        return;
+    }
+
+    let mut selected = palette.selected.min(count.saturating_sub(1));
+    if count > 0 && (up || down) {
+        selected = if down { (selected + 1) % count } else { (selected + count - 1) % count };
+        app.state().write().palette.selected = selected;
+    }
+    if enter {
+        if let Some(suggestion) = suggestions.get(selected) {
+            run_selected(app, suggestion.action.clone());
+            return;
+        }
+    }
+
+    let mut input = palette.input.clone();
+    let completion = suggestions.get(selected).filter(|_| tab).map(|s| format!("{} ", s.name));
+    let completed = completion.is_some();
+    if let Some(text) = completion {
+        input = text;
+    }
+    let mut clicked = None;
+
+    egui::Window::new("Commands")
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 80.0])
+        .default_width(520.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(Icons::icon_dim(material::TERMINAL, size::MEDIUM));
+                let mut output = egui::TextEdit::singleline(&mut input)
+                    .hint_text("Type a command, e.g. go to wallet, swap SOL USDC 5")
+                    .desired_width(460.0)
+                    .show(ui);
+                output.response.request_focus();
+                if completed {
+                    // Continue typing arguments after the completed name
+                    let end = egui::text::CCursor::new(input.chars().count());
+                    output.state.cursor.set_char_range(Some(egui::text::CCursorRange::one(end)));
+                    output.state.store(ui.ctx(), output.response.id);
+                }
+            });
+            ui.separator();
+
+            if let Some(error) = &palette.error {
+                ui.colored_label(theme.error, error);
+            } else if count == 0 {
+                ui.colored_label(theme.dim, "No matching commands");
+            }
+
+            egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                for (index, suggestion) in suggestions.iter().enumerate() {
+                    let is_selected = index == selected;
+                    let response = ui.horizontal(|ui| {
+                        let response = ui.selectable_label(is_selected, &suggestion.name);
+                        if !suggestion.args.is_empty() {
+                            ui.colored_label(theme.dim, suggestion.args);
+                        }
+                        ui.colored_label(theme.dim, &suggestion.description);
+                        response
+                    });
+                    if is_selected && (up || down) {
+                        response.inner.scroll_to_me(None);
+                    }
+                    if response.inner.clicked() {
+                        clicked = Some(suggestion.action.clone());
+                    }
+                }
+            });
+
+            ui.separator();
+            ui.colored_label(theme.dim, "↑↓ select · Tab complete · Enter run · Esc close");
+        });
+
+    if let Some(action) = clicked {
+        run_selected(app, action);
+    } else if input != palette.input {
+        let mut state = app.state().write();
+        state.palette.input = input;
+        state.palette.selected = 0;
+        state.palette.error = None;
+    }
+}
+
+/// Close the palette and run a command, or say why it can't run yet
+fn run_selected(app: &mut impl AppLike, action: Result<CommandAction, String>) {
+    match action {
+        Ok(action) => {
+            app.handle_command_palette_toggle();
+            run_command(app, action);
+        }
+        Err(error) => app.state().write().palette.error = Some(error),
+    }
+}
+
+/// Run a command through the same handlers the screens' buttons use
+pub fn run_command(app: &mut impl AppLike, action: CommandAction) {
+    match action {
+        CommandAction::GoTo(screen) => app.handle_screen_change(screen),
+        CommandAction::Swap { input, output, amount } => {
+            let resolved = {
+                let state = app.state().read();
+                commands::resolve_swap_tokens(&state.terminal.swap.token_list, &input, &output)
+            };
+            let (input, output) = match resolved {
+                Ok(tokens) => tokens,
+                Err(error) => {
+                    app.state().write().pending_notifications.push(("error".to_string(), error));
+                    return;
+                }
+            };
+            app.handle_token_select(input, TokenPickerTarget::Input);
+            app.handle_token_select(output, TokenPickerTarget::Output);
+            {
+                let mut state = app.state().write();
+                if let Some(amount) = amount {
+                    state.terminal.swap.amount = amount.to_string();
+                }
+                state.terminal.swap_panel_open = true;
+            }
+            app.handle_screen_change(Screen::Terminal);
+            app.handle_swap_tab_change(SwapTab::Simple);
+            app.trigger_quote_fetch();
+        }
+        CommandAction::SetTimeframe(timeframe) => {
+            let symbol = {
+                let mut state = app.state().write();
+                state.terminal.chart_timeframe = timeframe;
+                state.terminal.chart_loading = true;
+                state.terminal.chart_symbol.clone()
+            };
+            app.fetch_candles(&symbol, timeframe);
+        }
+        CommandAction::ToggleDebugOverlay => {
+            let mut state = app.state().write();
+            state.debug_overlay_visible = !state.debug_overlay_visible;
+        }
+        CommandAction::NewWindow => {
+            let screen = app.current_screen();
+            crate::ui::widgets::window_controls::create_new_window(app, screen);
+        }
+        CommandAction::Search(query) => {
+            app.handle_search_toggle();
+            if !query.is_empty() {
+                app.handle_search_query(query);
+            }
+        }
+        CommandAction::ShareChart => app.handle_share_chart(),
+        CommandAction::SharePortfolio => app.handle_share_portfolio(),
+        CommandAction::Refresh => {
+            let screen = app.current_screen();
+            app.refresh(screen);
+        }
+        CommandAction::RefreshAll => app.refresh_all(),
+        CommandAction::Logout => app.handle_logout_click(),
+    }
+}
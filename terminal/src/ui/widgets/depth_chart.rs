@@ -0,0 +1,95 @@
+//! # Depth Chart Widget
+//!
+//! Effective swap price vs trade size, built from the backend's route-quote
+//! depth ladder (`GET /api/market/depth`).
+//!
+//! Sizes span several orders of magnitude, so the x axis is plotted as
+//! `log10(size)` and labelled with the original size.
+
+use egui;
+use egui_plot::{Line, Plot, PlotPoints, Points};
+use shared::dto::market::DepthResponse;
+use crate::ui::theme::Theme;
+
+/// Render the depth ladder plot and a per-rung summary
+pub fn render(
+    ui: &mut egui::Ui,
+    depth: Option<&DepthResponse>,
+    loading: bool,
+    error: Option<&str>,
+    theme: &Theme,
+) {
+    if let Some(err) = error {
+        ui.colored_label(theme.error, format!("Depth unavailable: {}", err));
+    }
+
+    let Some(depth) = depth else {
+        ui.horizontal(|ui| {
+            if loading {
+                ui.spinner();
+            }
+            ui.colored_label(theme.dim, "Waiting for route quotes...");
+        });
+        return;
+    };
+
+    let points: Vec<[f64; 2]> = depth
+        .rungs
+        .iter()
+        .filter_map(|r| r.effective_price.map(|p| [r.size.log10(), p]))
+        .collect();
+
+    let pair = format!("{}/{}", depth.input, depth.output);
+    Plot::new("depth_chart")
+        .height(140.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .x_axis_formatter(|mark, _| format_size(10f64.powf(mark.value)))
+        .label_formatter(|_, point| {
+            format!("size {}\nprice {:.4}", format_size(10f64.powf(point.x)), point.y)
+        })
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new(pair.clone(), PlotPoints::from(points.clone())).color(theme.info).width(1.5));
+            plot_ui.points(Points::new(pair, PlotPoints::from(points)).color(theme.info).radius(3.0));
+        });
+
+    egui::Grid::new("depth_rungs")
+        .num_columns(3)
+        .spacing([12.0, 2.0])
+        .show(ui, |ui| {
+            ui.colored_label(theme.dim, format!("Size ({})", depth.input));
+            ui.colored_label(theme.dim, "Price");
+            ui.colored_label(theme.dim, "Impact");
+            ui.end_row();
+
+            for rung in &depth.rungs {
+                ui.label(format_size(rung.size));
+                match (rung.effective_price, rung.price_impact_pct) {
+                    (Some(price), impact) => {
+                        ui.label(format!("{:.4}", price));
+                        let impact = impact.unwrap_or(0.0);
+                        let color = if impact >= 1.0 { theme.warning } else { theme.normal };
+                        ui.colored_label(color, format!("{:.2}%", impact));
+                    }
+                    (None, _) => {
+                        ui.colored_label(theme.dim, "—");
+                        ui.colored_label(theme.error, "no route")
+                            .on_hover_text(rung.error.as_deref().unwrap_or("Quote failed"));
+                    }
+                }
+                ui.end_row();
+            }
+        });
+}
+
+/// Compact size label (0.1, 1, 10, 1k)
+fn format_size(size: f64) -> String {
+    if size >= 1000.0 {
+        format!("{}k", (size / 1000.0).round())
+    } else if size >= 1.0 {
+        format!("{}", size.round())
+    } else {
+        format!("{:.1}", size)
+    }
+}
@@ -0,0 +1,181 @@
+//! # Focus Order
+//!
+//! Keyboard navigation for forms. A [`FocusChain`] collects a form's
+//! focusable widgets in the order they are visited, keyed by the form's field
+//! enum ([`LoginField`](crate::app::LoginField),
+//! [`SignupField`](crate::app::SignupField),
+//! [`SwapField`](crate::app::SwapField)). Once the form is drawn,
+//! [`FocusChain::finish`] moves focus on Tab and Shift+Tab, wrapping at both
+//! ends, instead of egui's layout-based order; Enter in a text field moves on
+//! to the next text field, and in the last one submits the form.
+//!
+//! The focused widget is outlined in the theme's accent color.
+
+use egui::{Key, Modifiers};
+use crate::ui::theme::Theme;
+
+/// Where a form's keyboard focus went this frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusUpdate<F> {
+    /// Field holding keyboard focus; None when focus is outside the form
+    pub focused: Option<F>,
+    /// Enter was pressed in the form's last text field
+    pub submit: bool,
+}
+
+struct Entry<F> {
+    field: F,
+    response: egui::Response,
+    text: bool,
+}
+
+/// A form's focusable widgets in Tab order
+pub struct FocusChain<F> {
+    entries: Vec<Entry<F>>,
+}
+
+impl<F: Copy + PartialEq> FocusChain<F> {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Add a text field; Enter in it moves on to the next text field
+    pub fn text(&mut self, field: F, response: &egui::Response) {
+        self.entries.push(Entry { field, response: response.clone(), text: true });
+    }
+
+    /// Add a button or other widget that only takes part in Tab order
+    pub fn widget(&mut self, field: F, response: &egui::Response) {
+        self.entries.push(Entry { field, response: response.clone(), text: false });
+    }
+
+    /// Move keyboard focus to `field`, if it was drawn
+    pub fn focus(&self, field: F) {
+        if let Some(entry) = self.entries.iter().find(|entry| entry.field == field) {
+            entry.response.request_focus();
+        }
+    }
+
+    /// Apply Tab, Shift+Tab and Enter, and outline the focused widget
+    pub fn finish(&self, ui: &mut egui::Ui, theme: &Theme) -> FocusUpdate<F> {
+        let count = self.entries.len();
+        let focused = self.entries.iter().position(|entry| entry.response.has_focus());
+        let mut target = None;
+        let mut submit = false;
+
+        if let Some(index) = focused {
+            // Tab is handled here, so egui must not also move focus in layout order
+            let entry = &self.entries[index];
+            let filter = egui::EventFilter { tab: true, horizontal_arrows: entry.text, ..Default::default() };
+            ui.memory_mut(|mem| mem.set_focus_lock_filter(entry.response.id, filter));
+
+            // Shift+Tab first: the unshifted pattern matches it too
+            let backward = ui.input_mut(|i| i.consume_key(Modifiers::SHIFT, Key::Tab));
+            let forward = !backward && ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Tab));
+            if forward {
+                target = Some((index + 1) % count);
+            } else if backward {
+                target = Some((index + count - 1) % count);
+            }
+        } else if ui.input(|i| i.key_pressed(Key::Enter)) {
+            // A single-line text field gives up focus on Enter
+            if let Some(index) = self.entries.iter().position(|entry| entry.text && entry.response.lost_focus()) {
+                match self.entries[index + 1..].iter().position(|entry| entry.text) {
+                    Some(offset) => target = Some(index + 1 + offset),
+                    None => submit = true,
+                }
+            }
+        }
+
+        if let Some(index) = target {
+            self.entries[index].response.request_focus();
+        }
+        let focused = target.or(focused);
+        if let Some(index) = focused {
+            outline(ui, &self.entries[index].response, theme);
+        }
+        FocusUpdate { focused: focused.map(|index| self.entries[index].field), submit }
+    }
+}
+
+impl<F: Copy + PartialEq> Default for FocusChain<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Outline a focused widget in the accent color
+pub fn outline(ui: &egui::Ui, response: &egui::Response, theme: &Theme) {
+    ui.painter().rect_stroke(
+        response.rect.expand(2.0),
+        3.0,
+        egui::Stroke::new(1.5, theme.selected),
+        egui::StrokeKind::Outside,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Field {
+        First,
+        Second,
+        Go,
+    }
+
+    fn key(key: Key, modifiers: Modifiers) -> egui::Event {
+        egui::Event::Key { key, physical_key: None, pressed: true, repeat: false, modifiers }
+    }
+
+    /// Draw a form whose button comes first in the layout but last in Tab
+    /// order, with `events` as this frame's input
+    fn frame(ctx: &egui::Context, events: Vec<egui::Event>, focus: Option<Field>) -> FocusUpdate<Field> {
+        let mut update = None;
+        let input = egui::RawInput { events, ..Default::default() };
+        let _ = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let (mut first, mut second) = (String::new(), String::new());
+                let go = ui.button("Go");
+                let first = ui.text_edit_singleline(&mut first);
+                let second = ui.text_edit_singleline(&mut second);
+
+                let mut chain = FocusChain::new();
+                chain.text(Field::First, &first);
+                chain.text(Field::Second, &second);
+                chain.widget(Field::Go, &go);
+                if let Some(field) = focus {
+                    chain.focus(field);
+                }
+                update = Some(chain.finish(ui, &Theme::default()));
+            });
+        });
+        update.unwrap()
+    }
+
+    #[test]
+    fn test_tab_cycles_in_chain_order_and_wraps() {
+        let ctx = egui::Context::default();
+        assert_eq!(frame(&ctx, vec![], Some(Field::First)).focused, Some(Field::First));
+
+        let tab = || vec![key(Key::Tab, Modifiers::NONE)];
+        assert_eq!(frame(&ctx, tab(), None).focused, Some(Field::Second));
+        assert_eq!(frame(&ctx, tab(), None).focused, Some(Field::Go));
+        assert_eq!(frame(&ctx, tab(), None).focused, Some(Field::First));
+        assert_eq!(frame(&ctx, vec![key(Key::Tab, Modifiers::SHIFT)], None).focused, Some(Field::Go));
+        assert_eq!(frame(&ctx, vec![], None).focused, Some(Field::Go));
+    }
+
+    #[test]
+    fn test_enter_moves_to_the_next_text_field_then_submits() {
+        let ctx = egui::Context::default();
+        frame(&ctx, vec![], Some(Field::First));
+
+        let enter = || vec![key(Key::Enter, Modifiers::NONE)];
+        let update = frame(&ctx, enter(), None);
+        assert_eq!(update, FocusUpdate { focused: Some(Field::Second), submit: false });
+        let update = frame(&ctx, enter(), None);
+        assert!(update.submit);
+    }
+}
@@ -15,7 +15,7 @@ pub fn render_text_input(
     size: [f32; 2],
 ) -> egui::Response {
     let label_font = crate::ui::fonts::FontConfig::get_avenir_font(ui.ctx(), 14.0);
-    ui.label(egui::RichText::new(label).font(label_font));
+    let label = ui.label(egui::RichText::new(label).font(label_font));
     let response = if password {
         ui.add_sized(
             size,
@@ -32,7 +32,8 @@ pub fn render_text_input(
                 .font(crate::ui::fonts::FontConfig::get_avenir_font(ui.ctx(), 14.0))
         )
     };
-    response
+    // Screen readers announce the field by its label
+    response.labelled_by(label.id)
 }
 
 /// Render a styled button with optional icon
@@ -0,0 +1,89 @@
+//! # Backend Health Panel
+//!
+//! Compact per-dependency view of the last `/api/health` report, with the
+//! backend's market cache hit rates, opened by clicking the health indicator
+//! in the status bar.
+
+use egui;
+use crate::app::{AppLike, AppState};
+use crate::ui::theme::Theme;
+use shared::dto::system::HealthStatus;
+
+/// Display name for a subsystem reported by the backend
+fn subsystem_label(name: &str) -> &str {
+    match name {
+        "database" => "Database",
+        "solana_rpc" => "Solana RPC",
+        "jupiter" => "Jupiter",
+        "pyth" => "Pyth",
+        "websocket" => "Price stream",
+        other => other,
+    }
+}
+
+/// Dot color for a health status
+fn status_color(status: HealthStatus, theme: &Theme) -> egui::Color32 {
+    match status {
+        HealthStatus::Ok => theme.success,
+        HealthStatus::Degraded => theme.warning,
+        HealthStatus::Down => theme.error,
+    }
+}
+
+/// Render the health panel contents
+pub fn render_health_panel(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    let health = &state.backend_health;
+
+    if let Some(err) = &health.error {
+        ui.colored_label(theme.error, "Backend unreachable");
+        ui.colored_label(theme.dim, err);
+    } else if let Some(report) = &health.report {
+        egui::Grid::new("backend_health_grid")
+            .num_columns(3)
+            .spacing([10.0, 4.0])
+            .show(ui, |ui| {
+                for subsystem in &report.subsystems {
+                    let color = status_color(subsystem.status, theme);
+                    let dot = ui.colored_label(color, "●");
+                    ui.label(subsystem_label(&subsystem.name));
+                    ui.colored_label(theme.dim, format!("{} ms", subsystem.latency_ms));
+                    if let Some(err) = &subsystem.error {
+                        dot.on_hover_text(err);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if !report.caches.is_empty() {
+            ui.add_space(4.0);
+            egui::Grid::new("backend_cache_grid")
+                .num_columns(3)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    for cache in &report.caches {
+                        ui.colored_label(theme.dim, format!("{} cache", cache.name));
+                        let rate = cache
+                            .hit_rate()
+                            .map(|rate| format!("{:.0}% hits", rate * 100.0))
+                            .unwrap_or_else(|| "unused".to_string());
+                        ui.label(rate)
+                            .on_hover_text(format!("{} hits, {} misses", cache.hits, cache.misses));
+                        ui.colored_label(theme.dim, format!("{} keys", cache.entries));
+                        ui.end_row();
+                    }
+                });
+        }
+    } else {
+        ui.colored_label(theme.dim, "Waiting for first health check");
+    }
+
+    ui.add_space(6.0);
+    ui.horizontal(|ui| {
+        if let Some(checked) = health.last_checked {
+            ui.colored_label(theme.dim, format!("Checked {}s ago", checked.elapsed().as_secs()));
+        }
+        if ui.small_button("Check now").clicked() {
+            app.check_backend_health();
+        }
+    });
+}
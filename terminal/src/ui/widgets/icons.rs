@@ -39,6 +39,10 @@ pub mod material {
     pub const WARNING: &str = "\u{e002}"; // warning
     /// Info icon
     pub const INFO: &str = "\u{e88e}"; // info
+    /// Keyboard icon
+    pub const KEYBOARD: &str = "\u{e312}"; // keyboard
+    /// Language icon
+    pub const LANGUAGE: &str = "\u{e894}"; // language
     /// Refresh icon
     pub const REFRESH: &str = "\u{e5d5}"; // refresh
     /// Search icon
@@ -69,6 +73,12 @@ pub mod material {
     pub const SAVE: &str = "\u{e161}"; // save
     /// Palette/Color icon
     pub const PALETTE: &str = "\u{e40a}"; // palette
+    /// Bell/Alert icon
+    pub const BELL: &str = "\u{e7f4}"; // notifications
+    /// Attach file icon
+    pub const ATTACH_FILE: &str = "\u{e226}"; // attach_file
+    /// Download icon
+    pub const DOWNLOAD: &str = "\u{f090}"; // download
 }
 
 /// Icon helper functions for rendering icons with Bloomberg theme
@@ -0,0 +1,84 @@
+//! # Link Prompt
+//!
+//! Confirmation shown when an `xforce://` link opens the terminal (see
+//! [`crate::services::protocol_handler`]). The link is spelled out and only
+//! pre-fills its screen once Open is clicked; there is deliberately no
+//! keyboard shortcut for Open so a stray Enter can't accept a link.
+
+use egui;
+use crate::app::commands::CommandAction;
+use crate::app::search::SearchTarget;
+use crate::app::{AppLike, AppState};
+use crate::services::protocol_handler::DeepLink;
+use crate::ui::theme::Theme;
+use crate::ui::widgets::command_palette;
+use crate::ui::widgets::forms;
+
+/// Render the prompt for the pending link, if any
+pub fn render_link_prompt(ctx: &egui::Context, state: &AppState, app: &mut impl AppLike) {
+    let Some((url, link)) = &state.link_prompt.pending else {
+        return;
+    };
+    let theme = Theme::default();
+    let authenticated = state.is_authenticated();
+    let unsupported = matches!(link, DeepLink::Pay { .. });
+
+    let mut open = ctx.input_mut(|i| !i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+    let mut confirmed = false;
+    egui::Window::new("Open Link")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            ui.heading(link.describe());
+            ui.add_space(5.0);
+            forms::render_hint(ui, "Only open links from people you trust.", &theme);
+            ui.add_space(5.0);
+            ui.add(egui::Label::new(egui::RichText::new(url).monospace().color(theme.dim)).wrap());
+            if let DeepLink::Pay { memo: Some(memo), .. } = link {
+                ui.horizontal(|ui| {
+                    ui.colored_label(theme.dim, "Memo:");
+                    ui.label(memo);
+                });
+            }
+            ui.add_space(10.0);
+
+            if unsupported {
+                ui.colored_label(theme.warning, "Payments can't be sent from the terminal yet");
+            } else if !authenticated {
+                ui.colored_label(theme.warning, "Log in to open this link");
+            } else if matches!(link, DeepLink::Swap { .. }) {
+                ui.colored_label(theme.dim, "Fills in the swap form; nothing is sent until you press Swap");
+            }
+
+            ui.horizontal(|ui| {
+                if ui.add_enabled(authenticated && !unsupported, egui::Button::new("Open")).clicked() {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    app.state().write().link_prompt.pending = None;
+                }
+            });
+        });
+
+    if !open {
+        app.state().write().link_prompt.pending = None;
+    } else if confirmed {
+        let link = link.clone();
+        app.state().write().link_prompt.pending = None;
+        open_link(app, link);
+    }
+}
+
+/// Pre-fill the screen a confirmed link points at
+fn open_link(app: &mut impl AppLike, link: DeepLink) {
+    match link {
+        DeepLink::Swap { input, output, amount } => {
+            command_palette::run_command(app, CommandAction::Swap { input, output, amount });
+        }
+        DeepLink::Token { symbol } => app.handle_search_select(SearchTarget::Token { symbol }),
+        DeepLink::Pay { .. } => {}
+    }
+}
@@ -8,6 +8,7 @@ pub mod notifications;
 pub mod icons;
 pub mod branding;
 pub mod forms;
+pub mod focus;
 pub mod tables;
 pub mod layouts;
 pub mod window_controls;
@@ -15,3 +16,19 @@ pub mod nav_bar;
 pub mod live_indicator;
 pub mod price_display;
 pub mod asset_card;
+pub mod system_banner;
+pub mod depth_chart;
+pub mod health_panel;
+pub mod trade_import;
+pub mod search_palette;
+pub mod command_palette;
+pub mod link_prompt;
+pub mod swap_confirmation;
+pub mod transfer_confirmation;
+pub mod receive_qr;
+pub mod share_menu;
+pub mod attachment_preview;
+pub mod onramp;
+pub mod staking;
+pub mod wallet_chip;
+pub mod recovery_card;
@@ -1,11 +1,20 @@
 //! # Bloomberg-Style Navigation Bar
 //!
-//! Navigation bar component with token selector, navigation arrows, and exclusive access
-//! to Messaging and Settings screens.
+//! Navigation bar component with token selector, navigation arrows, exclusive access
+//! to Messaging (with an unread badge) and Settings screens, the Windows menu,
+//! and the Logout button.
+//! Arrows and the token selector act on the window the bar is drawn in. A
+//! warning next to the selector means the price sources disagree on the
+//! selected token; hovering it lists their prices. The selected token's price
+//! ticks next to it, flashing as it moves.
 
 use egui;
-use crate::app::{AppState, AppLike, Screen};
+use crate::app::{AppState, AppLike, Screen, WindowView};
+use crate::tr;
+use crate::ui::i18n;
 use crate::ui::theme::Theme;
+use crate::ui::widgets::icons::{Icons, material, size};
+use crate::ui::widgets::price_display;
 
 /// Render Bloomberg-style navigation bar
 /// Only visible when user is authenticated
@@ -14,7 +23,9 @@ pub fn render_nav_bar(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLik
         return; // Only show when logged in
     }
 
-    let _theme = Theme::default();
+    let theme = Theme::default();
+    // Token selection is per window
+    let view = app.view();
     
     // Bloomberg-style dark background with white boxes
     ui.style_mut().visuals.panel_fill = egui::Color32::from_rgb(20, 20, 30); // Dark blue-grey
@@ -42,8 +53,8 @@ pub fn render_nav_bar(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLik
         
         // Token selector box (white rectangle)
         ui.horizontal(|ui| {
-            let selected_token = state.nav_bar_selected_token.as_deref().unwrap_or("SOL");
-            let token_display = format!("{} TOKEN Crypto", selected_token);
+            let selected_token = view.selected_token.as_deref().unwrap_or("SOL");
+            let token_display = tr!("nav.token", symbol = selected_token);
             
             // White box background
             let response = ui.allocate_response(
@@ -70,8 +81,21 @@ pub fn render_nav_bar(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLik
             
             // Handle click to show dropdown
             if response.clicked() {
-                let mut state_write = app.state().write();
-                state_write.nav_bar_show_token_picker = !state_write.nav_bar_show_token_picker;
+                app.set_view(WindowView { show_token_picker: !view.show_token_picker, ..view.clone() });
+            }
+
+            let prices = state.terminal.prices.load();
+            if let Some(price) = prices.get(selected_token) {
+                // Sources disagree on the selected token's price
+                if price.divergent {
+                    ui.label(Icons::icon_color(material::WARNING, size::SMALL, theme.warning))
+                        .on_hover_text(price.sources_tooltip());
+                }
+
+                // Ticker: live price, flashing on each move, and the last tick
+                let flash = price_display::price_flash(price, &state.terminal.price_flashes, &theme);
+                price_display::render_flashing_price(ui, format!("${}", i18n::format_decimal(price.price, 4)), flash);
+                price_display::render_tick_delta(ui, price, &theme);
             }
         });
         
@@ -119,7 +143,7 @@ pub fn render_nav_bar(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLik
             ui.painter().text(
                 rect.center(),
                 egui::Align2::CENTER_CENTER,
-                "Related Functions Menu",
+                tr!("nav.related_functions"),
                 egui::FontId::proportional(12.0),
                 egui::Color32::BLACK
             );
@@ -129,51 +153,63 @@ pub fn render_nav_bar(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLik
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             ui.add_space(10.0);
             
+            // Logout button (revokes the token and returns to the Auth screen)
+            if ui.button(tr!("nav.logout")).clicked() {
+                app.handle_logout_click();
+            }
+            
+            ui.add_space(10.0);
+            
             // Settings button
-            if ui.button("⚙ Settings").clicked() {
+            if ui.button(format!("⚙ {}", tr!("nav.settings"))).clicked() {
                 app.handle_screen_change(Screen::Settings);
             }
             
             ui.add_space(10.0);
             
-            // Message link (exclusive access to Messaging)
-            if ui.link("Message").clicked() {
+            // Window management (new window, save/reset layout)
+            crate::ui::widgets::window_controls::render_window_menu(ui, app);
+            
+            ui.add_space(10.0);
+            
+            // Message link (exclusive access to Messaging), with the unread total
+            let unread = state.messaging.total_unread();
+            let label = if unread > 0 {
+                tr!("nav.messages_unread", count = unread)
+            } else {
+                tr!("nav.messages").to_string()
+            };
+            if ui.link(label).clicked() {
                 app.handle_screen_change(Screen::Messaging);
             }
         });
     });
     
-    // Token picker dropdown (if open)
-    if state.nav_bar_show_token_picker {
-        // Get token list and selected token (clone to avoid borrow issues)
-        let (token_list, selected_token) = {
-            let state_read = app.state().read();
-            let token_list = state_read.terminal.swap.token_list.clone();
-            let selected_token = state_read.nav_bar_selected_token.as_deref().unwrap_or("SOL").to_string();
-            (token_list, selected_token)
-        };
+    // Token picker dropdown (if open); Escape closes it
+    if view.show_token_picker && ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+        app.set_view(WindowView { show_token_picker: false, ..view.clone() });
+    } else if view.show_token_picker {
+        let token_list = &state.terminal.swap.token_list;
+        let selected_token = view.selected_token.as_deref().unwrap_or("SOL");
         
         // Show dropdown menu
-        egui::Window::new("Select Token")
+        egui::Window::new(tr!("nav.select_token"))
+            .id(egui::Id::new("token_picker"))
             .collapsible(false)
             .resizable(false)
             .show(ui.ctx(), |ui| {
                 // Default SOL if no tokens loaded
                 if token_list.is_empty() {
-                    if ui.selectable_label(selected_token == "SOL", "SOL TOKEN Crypto").clicked() {
-                        let mut state_write = app.state().write();
-                        state_write.nav_bar_selected_token = Some("SOL".to_string());
-                        state_write.nav_bar_show_token_picker = false;
+                    if ui.selectable_label(selected_token == "SOL", tr!("nav.token", symbol = "SOL")).clicked() {
+                        app.set_view(WindowView { selected_token: Some("SOL".to_string()), show_token_picker: false });
                     }
                 } else {
                     for token in token_list.iter() {
-                        let token_display = format!("{} TOKEN Crypto", token.symbol);
+                        let token_display = tr!("nav.token", symbol = token.symbol);
                         let is_selected = selected_token == token.symbol;
                         
                         if ui.selectable_label(is_selected, &token_display).clicked() {
-                            let mut state_write = app.state().write();
-                            state_write.nav_bar_selected_token = Some(token.symbol.clone());
-                            state_write.nav_bar_show_token_picker = false;
+                            app.set_view(WindowView { selected_token: Some(token.symbol.clone()), show_token_picker: false });
                         }
                     }
                 }
@@ -0,0 +1,115 @@
+//! # Buy SOL Dialog
+//!
+//! Window opened from the Wallet screen listing the on-ramp providers that
+//! serve the picked region. "Buy" opens the provider's checkout in the
+//! browser with the active wallet address (and amount, if entered) filled in.
+
+use egui;
+use crate::app::handlers::onramp::{region_choices, visible_providers};
+use crate::app::{AppLike, AppState};
+use crate::ui::theme::Theme;
+use crate::ui::widgets::forms;
+
+/// Render the dialog while it is open
+pub fn render_onramp_window(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    let onramp = &state.onramp;
+    if !onramp.open {
+        return;
+    }
+
+    let mut open = true;
+    let mut buy = None;
+    egui::Window::new("Buy SOL")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(420.0)
+        .show(ui.ctx(), |ui| {
+            forms::render_hint(
+                ui,
+                "Buy SOL with a card or bank transfer from one of these providers. \
+                 Payment happens on their site; the SOL is sent straight to your wallet.",
+                theme,
+            );
+            ui.add_space(8.0);
+
+            {
+                let mut state_write = app.state().write();
+                let inputs = &mut state_write.onramp;
+                ui.horizontal(|ui| {
+                    ui.label("Region:");
+                    egui::ComboBox::from_id_salt("onramp_region")
+                        .selected_text(if inputs.region.is_empty() { "All regions" } else { inputs.region.as_str() })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut inputs.region, String::new(), "All regions");
+                            for region in region_choices(&onramp.providers) {
+                                let label = region.clone();
+                                ui.selectable_value(&mut inputs.region, region, label);
+                            }
+                        });
+                    ui.label("Amount (fiat):");
+                    ui.add(egui::TextEdit::singleline(&mut inputs.amount).hint_text("optional").desired_width(80.0));
+                });
+            }
+            ui.add_space(8.0);
+
+            if let Some(err) = &onramp.error {
+                forms::render_error(ui, err, theme);
+            }
+            if onramp.loading {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Loading providers...");
+                });
+                return;
+            }
+
+            let mut any = false;
+            egui::Grid::new("onramp_providers")
+                .num_columns(3)
+                .spacing([12.0, 6.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for provider in visible_providers(onramp) {
+                        any = true;
+                        ui.label(&provider.name);
+                        let regions = if provider.regions.is_empty() {
+                            "Worldwide".to_string()
+                        } else {
+                            provider.regions.join(", ")
+                        };
+                        ui.colored_label(theme.dim, regions);
+                        let enabled = state.wallet.is_some();
+                        if ui
+                            .add_enabled(enabled, egui::Button::new("Buy").fill(theme.selected))
+                            .on_disabled_hover_text("Connect a wallet first")
+                            .clicked()
+                        {
+                            buy = Some(provider.id.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            if !any && onramp.error.is_none() {
+                forms::render_hint(ui, "No on-ramp providers are available for this region.", theme);
+            }
+
+            if let Some(name) = &onramp.opened {
+                ui.add_space(8.0);
+                ui.colored_label(
+                    theme.success,
+                    format!(
+                        "{} opened in your browser. Your SOL balance updates here automatically once the purchase arrives.",
+                        name
+                    ),
+                );
+            }
+        });
+
+    if let Some(provider_id) = buy {
+        app.handle_onramp_buy(provider_id);
+    }
+    if !open {
+        app.state().write().onramp.open = false;
+    }
+}
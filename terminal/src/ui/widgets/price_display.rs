@@ -1,10 +1,25 @@
 //! # Price Display Widget
 //!
 //! Price display with animations and flash effects for live updates.
+//!
+//! Per-symbol flashes fade with the time since the symbol's price last moved
+//! (`TerminalState::price_flashes`), so they look the same at any frame rate.
+//! They rely on the main loop's repaints while updates arrive and never ask for
+//! repaints of their own.
 
 use egui;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use crate::app::{Feature, FeatureGates, PriceData};
+use crate::ui::i18n;
 use crate::ui::theme::Theme;
 
+/// How long a price cell flashes after its price moves
+pub const FLASH_DURATION: Duration = Duration::from_millis(500);
+
+/// Background opacity of a flash at full intensity
+const FLASH_ALPHA: f32 = 0.35;
+
 /// Price change direction
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PriceDirection {
@@ -102,6 +117,56 @@ pub fn render_price_with_flash(
     }
 }
 
+/// Flash intensity `elapsed` after a price moved: 1.0 at the move, easing
+/// out to 0.0 at [`FLASH_DURATION`]
+pub fn flash_intensity(elapsed: Duration) -> f32 {
+    let t = elapsed.as_secs_f32() / FLASH_DURATION.as_secs_f32();
+    if t >= 1.0 {
+        0.0
+    } else {
+        (1.0 - t) * (1.0 - t)
+    }
+}
+
+/// Background of `price`'s cell: green or red depending on the last tick,
+/// fading out after the move recorded in `flashes`
+pub fn price_flash(price: &PriceData, flashes: &HashMap<String, Instant>, theme: &Theme) -> Option<egui::Color32> {
+    let intensity = flash_intensity(flashes.get(&price.symbol)?.elapsed());
+    if intensity <= 0.0 {
+        return None;
+    }
+    let color = match PriceDirection::from_change(price.previous_price?, price.price) {
+        PriceDirection::Up => theme.success,
+        PriceDirection::Down => theme.error,
+        PriceDirection::Neutral => return None,
+    };
+    Some(color.gamma_multiply(FLASH_ALPHA * intensity))
+}
+
+/// Render `text` in monospace over the cell background `flash`
+pub fn render_flashing_price(ui: &mut egui::Ui, text: String, flash: Option<egui::Color32>) -> egui::Response {
+    egui::Frame::NONE
+        .fill(flash.unwrap_or(egui::Color32::TRANSPARENT))
+        .inner_margin(egui::Margin::symmetric(2, 0))
+        .corner_radius(2.0)
+        .show(ui, |ui| ui.monospace(text))
+        .inner
+}
+
+/// Render ▲/▼ with the price delta since the previous tick
+pub fn render_tick_delta(ui: &mut egui::Ui, price: &PriceData, theme: &Theme) {
+    let Some(previous) = price.previous_price else {
+        ui.colored_label(theme.dim, "–");
+        return;
+    };
+    let delta = price.price - previous;
+    match PriceDirection::from_change(previous, price.price) {
+        PriceDirection::Up => ui.colored_label(theme.success, format!("▲ {}", i18n::format_signed(delta, 4))),
+        PriceDirection::Down => ui.colored_label(theme.error, format!("▼ {}", i18n::format_signed(delta, 4))),
+        PriceDirection::Neutral => ui.colored_label(theme.dim, "–"),
+    };
+}
+
 /// Render price change with color coding
 pub fn render_price_change(
     ui: &mut egui::Ui,
@@ -123,3 +188,60 @@ pub fn render_price_change(
     });
 }
 
+/// Render a `[source]` badge for `price`; it turns into a warning when the
+/// backend reports the feed behind that source as degraded, or when the
+/// sources disagree on the price
+pub fn render_source_badge(ui: &mut egui::Ui, price: &PriceData, gates: &FeatureGates, theme: &Theme) {
+    let Some(source) = price.source.as_deref() else {
+        return;
+    };
+    match gates.get(Feature::for_price_source(source)).reason() {
+        Some(reason) => {
+            ui.colored_label(theme.warning, format!("[{} stale]", source))
+                .on_hover_text(reason);
+        }
+        None if price.divergent => {
+            ui.colored_label(theme.warning, format!("[{}]", source))
+                .on_hover_text(price.sources_tooltip());
+        }
+        None => {
+            ui.colored_label(theme.dim, format!("[{}]", source));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flash_fades_from_full_to_nothing() {
+        assert_eq!(flash_intensity(Duration::ZERO), 1.0);
+        assert_eq!(flash_intensity(FLASH_DURATION), 0.0);
+        assert_eq!(flash_intensity(FLASH_DURATION * 3), 0.0);
+
+        // Eases out: past half-bright well before the halfway point
+        let quarter = flash_intensity(FLASH_DURATION / 4);
+        let half = flash_intensity(FLASH_DURATION / 2);
+        assert!((quarter - 0.5625).abs() < 1e-6);
+        assert!((half - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_flash_depends_only_on_elapsed_time() {
+        // Sampling at 30 or 144 fps lands on the same curve, never rising
+        for fps in [30u64, 144] {
+            let frame = Duration::from_micros(1_000_000 / fps);
+            let mut last = f32::MAX;
+            let mut elapsed = Duration::ZERO;
+            while elapsed <= FLASH_DURATION {
+                let intensity = flash_intensity(elapsed);
+                assert!(intensity <= last);
+                let t = elapsed.as_secs_f32() / FLASH_DURATION.as_secs_f32();
+                assert!((intensity - (1.0 - t).powi(2)).abs() < 1e-6);
+                last = intensity;
+                elapsed += frame;
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! # Receive QR
+//!
+//! QR code for the Wallet screen's Receive panel. The code encodes the bare
+//! address, or a Solana Pay request (`solana:<address>?amount=…`) when an
+//! amount is asked for.
+//!
+//! The matrix is drawn one texel per module with nearest filtering and shown
+//! at a whole number of physical pixels per module, so it stays sharp at any
+//! panel size. The texture is rebuilt only when the encoded text changes,
+//! e.g. when another wallet becomes active.
+
+use egui;
+use crate::services::token_transfer::{format_token_amount, parse_token_amount, SOL_DECIMALS};
+use qrcode::{Color, QrCode};
+
+/// Light modules around the code, as the QR spec asks for
+pub const QUIET_ZONE: usize = 4;
+
+/// Text to encode for `address`, requesting `amount` SOL if one is typed
+///
+/// The amount is normalized ("0.50" becomes "0.5"), as Solana Pay expects.
+pub fn payment_request(address: &str, amount: &str) -> Result<String, String> {
+    if amount.trim().is_empty() {
+        return Ok(address.to_string());
+    }
+    match parse_token_amount(amount, SOL_DECIMALS) {
+        Some(0) => Err("Amount must be more than zero".to_string()),
+        Some(lamports) => Ok(format!("solana:{}?amount={}", address, format_token_amount(lamports, SOL_DECIMALS))),
+        None => Err(format!("Enter an amount in SOL, with at most {} decimals", SOL_DECIMALS)),
+    }
+}
+
+/// QR code for `data`, one pixel per module including the quiet zone
+pub fn qr_image(data: &str) -> Result<egui::ColorImage, String> {
+    let code = QrCode::new(data.as_bytes()).map_err(|e| format!("Failed to build QR code: {}", e))?;
+    let width = code.width();
+    let side = width + 2 * QUIET_ZONE;
+    let mut image = egui::ColorImage::filled([side, side], egui::Color32::WHITE);
+    for (index, color) in code.to_colors().into_iter().enumerate() {
+        if color == Color::Dark {
+            let (x, y) = (index % width + QUIET_ZONE, index / width + QUIET_ZONE);
+            image.pixels[y * side + x] = egui::Color32::BLACK;
+        }
+    }
+    Ok(image)
+}
+
+/// Side of the shown code, in points: the largest whole number of physical
+/// pixels per module that fits within `available` points
+pub fn crisp_side(modules: usize, available: f32, pixels_per_point: f32) -> f32 {
+    let pixels_per_module = ((available * pixels_per_point) / modules as f32).floor().max(1.0);
+    pixels_per_module * modules as f32 / pixels_per_point
+}
+
+/// Show the QR code for `data`, at most `max_side` points wide
+pub fn show(ui: &mut egui::Ui, data: &str, max_side: f32) {
+    let texture_id = egui::Id::new("receive_qr");
+    let cached = ui
+        .data(|d| d.get_temp::<(String, egui::TextureHandle)>(texture_id))
+        .filter(|(encoded, _)| encoded == data);
+    let texture = match cached {
+        Some((_, texture)) => texture,
+        None => match qr_image(data) {
+            Ok(image) => {
+                let texture = ui.ctx().load_texture("receive-qr", image, egui::TextureOptions::NEAREST);
+                ui.data_mut(|d| d.insert_temp(texture_id, (data.to_string(), texture.clone())));
+                texture
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+                return;
+            }
+        },
+    };
+
+    let modules = texture.size()[0];
+    let side = crisp_side(modules, max_side.min(ui.available_width()), ui.ctx().pixels_per_point());
+    ui.add(egui::Image::new(&texture).fit_to_exact_size(egui::vec2(side, side)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "8W6QginLcAydYyMYjxuyKQN56NzeakDE3aRFrAmocS6D";
+
+    #[test]
+    fn test_payment_request() {
+        assert_eq!(payment_request(ADDRESS, "  ").unwrap(), ADDRESS);
+        assert_eq!(payment_request(ADDRESS, "0.50").unwrap(), format!("solana:{}?amount=0.5", ADDRESS));
+        assert_eq!(payment_request(ADDRESS, "2").unwrap(), format!("solana:{}?amount=2", ADDRESS));
+        assert!(payment_request(ADDRESS, "0").is_err());
+        assert!(payment_request(ADDRESS, "1e3").is_err());
+        assert!(payment_request(ADDRESS, "0.0000000001").is_err());
+    }
+
+    #[test]
+    fn test_qr_image_has_quiet_zone_and_finder_patterns() {
+        let image = qr_image(ADDRESS).unwrap();
+        let [side, height] = image.size;
+        assert_eq!(side, height);
+        let pixel = |x: usize, y: usize| image.pixels[y * side + x];
+
+        // The quiet zone is light, the finder pattern's corner just inside it dark
+        for i in 0..side {
+            assert_eq!(pixel(i, 0), egui::Color32::WHITE);
+            assert_eq!(pixel(0, i), egui::Color32::WHITE);
+        }
+        assert_eq!(pixel(QUIET_ZONE, QUIET_ZONE), egui::Color32::BLACK);
+        assert_eq!(pixel(side - QUIET_ZONE - 1, QUIET_ZONE), egui::Color32::BLACK);
+        assert_eq!(pixel(QUIET_ZONE, side - QUIET_ZONE - 1), egui::Color32::BLACK);
+    }
+
+    #[test]
+    fn test_crisp_side_is_whole_pixels_per_module() {
+        assert_eq!(crisp_side(33, 200.0, 1.0), 198.0);
+        assert_eq!(crisp_side(33, 200.0, 2.0), 198.0);
+        assert_eq!(crisp_side(33, 100.0, 1.5), 88.0);
+        // Never smaller than a pixel per module
+        assert_eq!(crisp_side(33, 10.0, 1.0), 33.0);
+    }
+}
@@ -0,0 +1,85 @@
+//! # Recovery Card
+//!
+//! What went wrong with a wallet or RPC request, in plain words, with
+//! buttons for the usual fixes. The original error is one click away under
+//! "Details".
+
+use egui;
+use std::time::Instant;
+use crate::app::recovery::RecoveryFlow;
+use crate::app::{AppLike, AppState};
+use crate::ui::theme::Theme;
+use crate::ui::widgets::icons::{Icons, material, size};
+
+/// Render the recovery card of `flow`, if it has one
+pub fn render_recovery_card(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, flow: RecoveryFlow) {
+    let Some(card) = state.recovery.card(flow) else {
+        return;
+    };
+
+    let theme = Theme::default();
+    let mut action = None;
+    let mut dismissed = false;
+    let mut toggle_details = false;
+    let details_open = state.recovery.details_open == Some(flow);
+
+    egui::Frame::NONE
+        .stroke(egui::Stroke::new(1.0, theme.warning))
+        .inner_margin(egui::Margin::symmetric(8, 6))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(Icons::icon_warning(material::WARNING, size::SMALL));
+                ui.colored_label(theme.warning, egui::RichText::new(card.kind.title()).strong());
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button(material::CLOSE).on_hover_text("Dismiss").clicked() {
+                        dismissed = true;
+                    }
+                });
+            });
+            ui.label(card.kind.explanation());
+            ui.add_space(4.0);
+
+            ui.horizontal_wrapped(|ui| {
+                let retry_in = card.retry_at.map(|at| at.saturating_duration_since(Instant::now()));
+                for (i, candidate) in state.recovery.stats.ranked(card.kind).into_iter().enumerate() {
+                    let label = match (candidate, retry_in) {
+                        (crate::app::recovery::RecoveryAction::RetryWithBackoff, Some(wait)) => {
+                            format!("Retrying in {}s", wait.as_secs() + 1)
+                        }
+                        _ => candidate.label().to_string(),
+                    };
+                    // The best-ranked action stands out
+                    let mut button = egui::Button::new(label);
+                    if i == 0 {
+                        button = button.fill(theme.selected);
+                    }
+                    if ui.add_enabled(retry_in.is_none(), button).clicked() {
+                        action = Some(candidate);
+                    }
+                }
+                if ui.small_button(if details_open { "Hide details" } else { "Details" }).clicked() {
+                    toggle_details = true;
+                }
+            });
+
+            if details_open {
+                ui.add_space(4.0);
+                ui.colored_label(theme.dim, &card.message);
+            }
+        });
+    ui.add_space(6.0);
+
+    if card.retry_at.is_some() {
+        // Keep the countdown ticking
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(500));
+    }
+
+    if let Some(action) = action {
+        app.handle_recovery_action(flow, action);
+    } else if dismissed {
+        app.handle_recovery_dismiss(flow);
+    } else if toggle_details {
+        let mut state = app.state().write();
+        state.recovery.details_open = (!details_open).then_some(flow);
+    }
+}
@@ -0,0 +1,139 @@
+//! # Search Palette Widget
+//!
+//! Ctrl+F overlay that searches tokens, transactions, messages and settings
+//! (see [`crate::app::search`]). Arrow keys move the selection, Enter opens
+//! the selected result and Escape closes the palette. The query field keeps
+//! focus while the palette is open so typing never reaches the screen below.
+
+use egui;
+use crate::app::search::{self, SearchDomain};
+use crate::app::{AppLike, AppState};
+use crate::ui::theme::Theme;
+use crate::ui::widgets::icons::{Icons, material, size};
+
+/// Render the search palette
+pub fn render_search_palette(ctx: &egui::Context, state: &AppState, app: &mut impl AppLike) {
+    let theme = Theme::default();
+    let search = &state.search;
+    let count = search::result_count(&search.groups);
+
+    // Consume navigation keys before the text field sees them
+    let (up, down, enter, escape) = ctx.input_mut(|i| {
+        (
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+        )
+    });
+    if escape {
+        app.handle_search_toggle();
+        return;
+    }
+
+    let mut selected = search.selected.min(count.saturating_sub(1));
+    if count > 0 && (up || down) {
+        selected = if down { (selected + 1) % count } else { (selected + count - 1) % count };
+        app.state().write().search.selected = selected;
+    }
+    if enter {
+        if let Some(result) = search::result_at(&search.groups, selected) {
+            app.handle_search_select(result.target.clone());
+            return;
+        }
+    }
+
+    let mut query = search.query.clone();
+    let mut query_changed = false;
+    let mut clicked = None;
+
+    egui::Window::new("Search")
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 80.0])
+        .default_width(520.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(Icons::icon_dim(material::SEARCH, size::MEDIUM));
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut query)
+                        .hint_text("Search tokens, transactions, messages, settings")
+                        .desired_width(460.0),
+                );
+                response.request_focus();
+                query_changed = response.changed();
+                if search.remote_pending.is_some() {
+                    ui.spinner();
+                }
+            });
+            ui.separator();
+
+            if search.query.trim().is_empty() {
+                ui.colored_label(theme.dim, "Type a token, signature fragment, message or setting");
+            } else if count == 0 && search.remote_pending.is_none() {
+                ui.colored_label(theme.dim, "No results");
+            }
+
+            egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                let mut index = 0;
+                for group in &search.groups {
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        ui.label(Icons::icon_dim(domain_icon(group.domain), size::SMALL));
+                        ui.colored_label(theme.dim, group.domain.title());
+                    });
+                    for result in &group.results {
+                        let is_selected = index == selected;
+                        let response = ui.horizontal(|ui| {
+                            let response = ui.selectable_label(is_selected, &result.title);
+                            ui.colored_label(theme.dim, &result.detail);
+                            response
+                        });
+                        if is_selected && (up || down) {
+                            response.inner.scroll_to_me(None);
+                        }
+                        if response.inner.clicked() {
+                            clicked = Some(result.target.clone());
+                        }
+                        index += 1;
+                    }
+                }
+            });
+
+            ui.separator();
+            ui.colored_label(theme.dim, "↑↓ select · Enter open · Esc close");
+        });
+
+    if let Some(target) = clicked {
+        app.handle_search_select(target);
+    } else if query_changed {
+        app.handle_search_query(query);
+    }
+}
+
+fn domain_icon(domain: SearchDomain) -> &'static str {
+    match domain {
+        SearchDomain::Tokens => material::TOKEN,
+        SearchDomain::Transactions => material::HISTORY,
+        SearchDomain::Messages => material::MESSAGE,
+        SearchDomain::Settings => material::SETTINGS,
+    }
+}
+
+/// Outline the widget a search result navigated to, scrolling to it once per selection
+pub fn outline_highlight(ui: &egui::Ui, response: &egui::Response, state: &AppState, theme: &Theme) {
+    ui.painter().rect_stroke(
+        response.rect.expand(2.0),
+        4.0,
+        egui::Stroke::new(1.5, theme.warning),
+        egui::StrokeKind::Outside,
+    );
+
+    let scrolled_id = egui::Id::new("search_highlight_scrolled");
+    let seq = state.search.highlight_seq;
+    if ui.data(|d| d.get_temp::<u64>(scrolled_id)) != Some(seq) {
+        response.scroll_to_me(Some(egui::Align::Center));
+        ui.data_mut(|d| d.insert_temp(scrolled_id, seq));
+    }
+}
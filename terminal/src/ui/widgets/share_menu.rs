@@ -0,0 +1,77 @@
+//! # Share Menu
+//!
+//! "Share…" menu on the Live Chart and Portfolio screens: pick an expiry,
+//! publish a read-only link, and get it on the clipboard.
+
+use egui;
+use crate::app::{AppLike, AppState};
+use crate::ui::theme::Theme;
+
+/// What the menu shares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareTarget {
+    Chart,
+    Portfolio,
+}
+
+/// Expiry choices (hours; `None` never expires)
+const EXPIRY_OPTIONS: &[(&str, Option<i64>)] = &[
+    ("1 hour", Some(1)),
+    ("24 hours", Some(24)),
+    ("7 days", Some(24 * 7)),
+    ("30 days", Some(24 * 30)),
+    ("Never", None),
+];
+
+/// Render the "Share…" menu button for `target`
+pub fn render(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, target: ShareTarget) {
+    let theme = Theme::default();
+    let share = &state.share;
+
+    // A new link goes on the clipboard the frame after it arrives
+    if let Some(link) = share.link.as_ref().filter(|_| !share.copied) {
+        ui.ctx().copy_text(link.clone());
+        app.state().write().share.copied = true;
+    }
+
+    ui.menu_button("Share…", |ui| {
+        let mut expires_in_hours = share.expires_in_hours;
+        let mut anonymize = share.anonymize;
+
+        ui.label("Link expires after");
+        for (label, hours) in EXPIRY_OPTIONS {
+            ui.radio_value(&mut expires_in_hours, *hours, *label);
+        }
+        if target == ShareTarget::Portfolio {
+            ui.separator();
+            ui.checkbox(&mut anonymize, "Hide USD values")
+                .on_hover_text("Only allocation percentages are shared");
+        }
+
+        if expires_in_hours != share.expires_in_hours || anonymize != share.anonymize {
+            let mut state = app.state().write();
+            state.share.expires_in_hours = expires_in_hours;
+            state.share.anonymize = anonymize;
+        }
+
+        ui.separator();
+        let button = egui::Button::new(if share.pending { "Creating link…" } else { "Create link" });
+        if ui.add_enabled(!share.pending, button).clicked() {
+            match target {
+                ShareTarget::Chart => app.handle_share_chart(),
+                ShareTarget::Portfolio => app.handle_share_portfolio(),
+            }
+        }
+
+        if let Some(link) = &share.link {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.colored_label(theme.dim, link);
+                if ui.small_button("Copy").clicked() {
+                    ui.ctx().copy_text(link.clone());
+                    ui.close();
+                }
+            });
+        }
+    });
+}
@@ -0,0 +1,272 @@
+//! # Staking Panel
+//!
+//! Wallet screen section listing the wallet's stake accounts and their recent
+//! rewards, with a form that stakes SOL with a validator. Deactivating opens
+//! a confirmation window that warns about the cooldown first; withdrawing is
+//! offered once an account is inactive.
+
+use egui;
+use crate::app::handlers::staking::{cooldown_warning, StakeAction};
+use crate::app::{AppLike, AppState, WalletState};
+use crate::ui::theme::Theme;
+use crate::ui::widgets::forms;
+use crate::ui::widgets::icons::{material, size, Icons};
+use shared::dto::staking::{format_sol, StakeActivation, StakingInfo};
+
+/// Render the panel, loading the wallet's stake accounts the first time
+pub fn render_staking_panel(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    wallet: &WalletState,
+    app: &mut impl AppLike,
+    theme: &Theme,
+) {
+    let staking = &state.staking;
+    if staking.address.as_deref() != Some(wallet.address.as_str()) && !staking.loading {
+        app.handle_staking_refresh();
+    }
+
+    let mut refresh = false;
+    ui.horizontal(|ui| {
+        ui.label(Icons::icon_success(material::LOCK, size::MEDIUM));
+        ui.heading("Staking");
+        refresh = ui.add_enabled(!staking.loading, egui::Button::new(material::REFRESH).small()).clicked();
+        if staking.loading {
+            ui.spinner();
+        }
+    });
+    ui.add_space(5.0);
+
+    if let Some(err) = &staking.error {
+        forms::render_error(ui, err, theme);
+    }
+
+    let mut action = None;
+    if let Some(info) = &staking.info {
+        render_epoch(ui, info, theme);
+        ui.add_space(5.0);
+        render_accounts(ui, state, info, theme, &mut action);
+        ui.add_space(5.0);
+        render_rewards(ui, state, theme);
+        ui.add_space(10.0);
+        render_stake_form(ui, state, info, app, theme);
+    }
+
+    if refresh {
+        app.handle_staking_refresh();
+    }
+    match action {
+        Some((StakeAction::Deactivate, account)) => app.state().write().staking.confirm_deactivate = Some(account),
+        Some((action, account)) => app.handle_stake_action(action, Some(account)),
+        None => {}
+    }
+
+    render_deactivate_confirmation(ui, state, app, theme);
+}
+
+/// Current epoch, how far into it the cluster is, and the total staked
+fn render_epoch(ui: &mut egui::Ui, info: &StakingInfo, theme: &Theme) {
+    ui.horizontal(|ui| {
+        ui.label(format!("Epoch {}", info.epoch));
+        let progress = if info.slots_in_epoch > 0 {
+            info.slot_index as f32 / info.slots_in_epoch as f32
+        } else {
+            0.0
+        };
+        ui.add(egui::ProgressBar::new(progress).desired_width(160.0).show_percentage());
+        let minutes = info.epoch_remaining_secs() / 60;
+        ui.colored_label(theme.dim, format!("≈ {} h {} min left", minutes / 60, minutes % 60));
+    });
+    ui.label(format!("Staked: {} SOL", format_sol(info.total_delegated())));
+}
+
+/// One row per stake account, with the action its state allows
+fn render_accounts(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    info: &StakingInfo,
+    theme: &Theme,
+    action: &mut Option<(StakeAction, String)>,
+) {
+    if info.accounts.is_empty() {
+        forms::render_hint(ui, "This wallet has no stake accounts yet.", theme);
+        return;
+    }
+
+    let busy = state.staking.submitting.is_some();
+    egui::Grid::new("stake_accounts")
+        .num_columns(5)
+        .spacing([12.0, 6.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong("Account");
+            ui.strong("State");
+            ui.strong("Delegated");
+            ui.strong("Validator");
+            ui.label("");
+            ui.end_row();
+
+            for account in &info.accounts {
+                ui.monospace(shared::format_address(&account.address, 4, 4)).on_hover_text(&account.address);
+                let color = match account.state {
+                    StakeActivation::Active => theme.success,
+                    StakeActivation::Activating | StakeActivation::Deactivating => theme.warning,
+                    StakeActivation::Inactive => theme.dim,
+                };
+                ui.colored_label(color, account.state.as_str());
+                ui.label(format!("{} SOL", format_sol(account.delegated_lamports)));
+                match &account.voter {
+                    Some(voter) => {
+                        ui.monospace(shared::format_address(voter, 4, 4)).on_hover_text(voter);
+                    }
+                    None => {
+                        ui.colored_label(theme.dim, "-");
+                    }
+                }
+                if account.can_deactivate() {
+                    if ui.add_enabled(!busy, egui::Button::new("Deactivate").small()).clicked() {
+                        *action = Some((StakeAction::Deactivate, account.address.clone()));
+                    }
+                } else if account.can_withdraw() {
+                    let withdraw = egui::Button::new(format!("Withdraw {} SOL", format_sol(account.lamports))).small();
+                    if ui.add_enabled(!busy, withdraw).clicked() {
+                        *action = Some((StakeAction::Withdraw, account.address.clone()));
+                    }
+                } else if account.state == StakeActivation::Deactivating {
+                    ui.colored_label(theme.dim, format!("Withdrawable from epoch {}", info.epoch + 1));
+                } else {
+                    ui.colored_label(theme.dim, format!("Earning from epoch {}", info.epoch + 1));
+                }
+                ui.end_row();
+            }
+        });
+}
+
+/// Rewards paid over the last few epochs, newest first
+fn render_rewards(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
+    let rewards = &state.staking.rewards;
+    if rewards.is_empty() {
+        forms::render_hint(ui, "No staking rewards in the last few epochs.", theme);
+        return;
+    }
+
+    ui.label(format!(
+        "Rewards: {} SOL",
+        format_sol(rewards.iter().map(|reward| reward.lamports).sum())
+    ));
+    egui::Grid::new("stake_rewards")
+        .num_columns(4)
+        .spacing([12.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong("Epoch");
+            ui.strong("Account");
+            ui.strong("Reward");
+            ui.strong("Commission");
+            ui.end_row();
+
+            for reward in rewards {
+                ui.label(reward.epoch.to_string());
+                ui.monospace(shared::format_address(&reward.stake_account, 4, 4));
+                ui.colored_label(theme.success, format!("+{} SOL", format_sol(reward.lamports)));
+                ui.label(reward.commission.map_or_else(|| "-".to_string(), |commission| format!("{}%", commission)));
+                ui.end_row();
+            }
+        });
+}
+
+/// Validator vote account and amount, with the minimum and the reserve
+fn render_stake_form(ui: &mut egui::Ui, state: &AppState, info: &StakingInfo, app: &mut impl AppLike, theme: &Theme) {
+    egui::Grid::new("stake_form").num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+        let mut state_write = app.state().write();
+        let form = &mut state_write.staking;
+
+        ui.label("Validator:");
+        ui.add(
+            egui::TextEdit::singleline(&mut form.vote_account)
+                .hint_text("Vote account address")
+                .font(egui::TextStyle::Monospace)
+                .desired_width(380.0),
+        );
+        ui.end_row();
+
+        ui.label("Amount:");
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut form.amount).hint_text("0.0").desired_width(160.0));
+            ui.label("SOL");
+        });
+        ui.end_row();
+    });
+    ui.colored_label(
+        theme.dim,
+        format!(
+            "Minimum stake {} SOL. A new stake account also holds {} SOL as its rent-exempt reserve, \
+             returned when it's withdrawn.",
+            format_sol(info.minimum_delegation),
+            format_sol(info.rent_exempt_reserve),
+        ),
+    );
+    ui.add_space(5.0);
+
+    let submitting = state.staking.submitting;
+    let mut stake = false;
+    ui.horizontal(|ui| {
+        stake = ui
+            .add_enabled(submitting.is_none(), egui::Button::new(format!("{} Stake", material::LOCK)).fill(theme.selected))
+            .clicked();
+        if let Some(action) = submitting {
+            ui.spinner();
+            let label = match action {
+                StakeAction::Delegate => "Staking…",
+                StakeAction::Deactivate => "Deactivating…",
+                StakeAction::Withdraw => "Withdrawing…",
+            };
+            ui.colored_label(theme.dim, label);
+        }
+    });
+    if let Some(error) = &state.staking.form_error {
+        ui.colored_label(theme.error, error);
+    }
+
+    if stake {
+        app.handle_stake_action(StakeAction::Delegate, None);
+    }
+}
+
+/// Confirmation window for deactivating, with the cooldown warning
+fn render_deactivate_confirmation(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    let (Some(account), Some(info)) = (&state.staking.confirm_deactivate, &state.staking.info) else {
+        return;
+    };
+
+    let mut open = true;
+    let mut confirm = false;
+    let mut cancel = false;
+    egui::Window::new("Deactivate stake")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(420.0)
+        .show(ui.ctx(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Stake account:");
+                ui.monospace(account.as_str());
+            });
+            ui.add_space(8.0);
+            ui.horizontal_wrapped(|ui| {
+                ui.label(Icons::icon_warning(material::WARNING, size::SMALL));
+                ui.colored_label(theme.warning, cooldown_warning(info));
+            });
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                confirm = ui.add(egui::Button::new("Deactivate").fill(theme.error)).clicked();
+                cancel = ui.button("Cancel").clicked();
+            });
+        });
+
+    if confirm {
+        app.handle_stake_action(StakeAction::Deactivate, Some(account.clone()));
+    } else if cancel || !open {
+        app.state().write().staking.confirm_deactivate = None;
+    }
+}
@@ -1,12 +1,18 @@
 //! # Status Bar Widget
 //!
 //! Bottom status bar showing WebSocket status, update rates, and connection info.
+//!
+//! Announced maintenance shows as a countdown; while the backend is down for
+//! it, "MAINTENANCE" stands in for the connection status.
 
 use egui;
 use crate::app::AppState;
+use crate::app::idle::PowerState;
+use crate::app::maintenance::MaintenancePhase;
 use crate::ui::theme::Theme;
 use crate::ui::widgets::icons::{Icons, material, size};
 use crate::ui::widgets::live_indicator;
+use crate::ui::widgets::wallet_chip;
 
 /// Render enhanced status bar at bottom
 pub fn render_status_bar(ui: &mut egui::Ui, state: &AppState) {
@@ -19,18 +25,34 @@ pub fn render_status_bar(ui: &mut egui::Ui, state: &AppState) {
         let recently_updated = state.last_price_update_time.elapsed().as_millis() < 1000;
         
         // Live indicator
-        if is_connected && recently_updated {
-            live_indicator::render_live_indicator(ui, true, &theme);
-        } else if is_connected {
-            live_indicator::render_connection_status(
-                ui,
-                true,
-                state.websocket_status.messages_received,
-                &theme,
-            );
-        } else {
-            live_indicator::render_connection_status(ui, false, 0, &theme);
+        match state.idle.power() {
+            _ if state.maintenance.is_down() => {
+                ui.colored_label(theme.warning, "⚒ MAINTENANCE")
+                    .on_hover_text(format!("{}\nReconnects once the backend is ready", state.maintenance.message()));
+            }
+            PowerState::LowPower => {
+                ui.colored_label(theme.dim, "◌ LOW POWER")
+                    .on_hover_text("Connections closed while idle; move the mouse or press a key to reconnect");
+            }
+            PowerState::Reconnecting => {
+                ui.colored_label(theme.warning, "◌ RECONNECTING");
+            }
+            PowerState::Active if is_connected && recently_updated => {
+                live_indicator::render_live_indicator(ui, true, &theme);
+            }
+            PowerState::Active if is_connected => {
+                live_indicator::render_connection_status(
+                    ui,
+                    true,
+                    state.websocket_status.messages_received,
+                    &theme,
+                );
+            }
+            PowerState::Active => {
+                live_indicator::render_connection_status(ui, false, 0, &theme);
+            }
         }
+        render_maintenance(ui, state, &theme);
         
         ui.separator();
         
@@ -61,18 +83,57 @@ pub fn render_status_bar(ui: &mut egui::Ui, state: &AppState) {
         }
         
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            // Connected wallet
+            if let Some(wallet) = &state.wallet {
+                wallet_chip::render_wallet_chip(ui, &state.wallet_identities, &wallet.address);
+                ui.separator();
+            }
+            
             // Selected asset (if any)
-            if let Some(selected_token) = &state.nav_bar_selected_token {
+            if let Some(selected_token) = &state.root_view.selected_token {
                 ui.label(format!("Selected: {}", selected_token));
                 ui.separator();
             }
             
             // Price count
-            ui.label(format!("{} assets", state.terminal.prices.len()));
+            ui.label(format!("{} assets", state.terminal.prices.load().len()));
         });
     });
 }
 
+/// Countdown to announced maintenance, and the re-sync after it
+fn render_maintenance(ui: &mut egui::Ui, state: &AppState, theme: &Theme) {
+    let maintenance = &state.maintenance;
+    match maintenance.phase() {
+        MaintenancePhase::Scheduled | MaintenancePhase::Imminent => {
+            let Some(secs) = maintenance.countdown(chrono::Utc::now().timestamp()) else {
+                return;
+            };
+            let countdown = if secs >= 3600 {
+                format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+            } else {
+                format!("{}:{:02}", secs / 60, secs % 60)
+            };
+            let color = if maintenance.blocks_new_trades() { theme.error } else { theme.warning };
+            let mut hover = maintenance.message().to_string();
+            if let Some(duration) = maintenance.window().and_then(|w| w.expected_duration_secs) {
+                hover.push_str(&format!("\nExpected to last about {} min", duration.div_ceil(60)));
+            }
+            if maintenance.blocks_new_trades() {
+                hover.push_str("\nNew swaps are paused until it's over");
+            }
+            ui.separator();
+            ui.colored_label(color, format!("⚒ MAINTENANCE IN {}", countdown)).on_hover_text(hover);
+        }
+        MaintenancePhase::Recovering => {
+            ui.separator();
+            ui.colored_label(theme.info, "⚒ RE-SYNCING")
+                .on_hover_text("Maintenance is over; reloading balances and history");
+        }
+        MaintenancePhase::Normal | MaintenancePhase::Down => {}
+    }
+}
+
 /// Calculate update rate (messages per second)
 fn calculate_update_rate(state: &AppState) -> f64 {
     if let Some(last_message_time) = state.websocket_status.last_message {
@@ -0,0 +1,152 @@
+//! # Swap Confirmation
+//!
+//! Dialog between Execute Swap and signing. It shows what the backend's
+//! simulation of the exact transaction says will happen; when the simulation
+//! fails the reason and the program log tail are shown in red and Execute
+//! stays disabled, so a swap that would fail on-chain is never signed.
+//!
+//! The USD value of the input comes from the price feed; when that is an
+//! oracle price past the stale threshold the dialog says so. The same value
+//! is used to warn when the swap would push its output token past the
+//! position limit set under Settings > Risk.
+//!
+//! A chip names the wallet that will sign, so a swap is never sent from the
+//! wrong one by mistake.
+//!
+//! When the price sources disagree on either token of the pair, the dialog
+//! names the token and the spread, since the quote may be off by as much.
+
+use egui;
+use crate::app::handlers::risk::swap_concentration_warning;
+use crate::app::handlers::swap::{confirmation_summary, pair_divergence, usd_estimate};
+use crate::app::{AppLike, AppState};
+use crate::ui::theme::Theme;
+use crate::ui::widgets::wallet_chip;
+
+/// Render the confirmation dialog for the prepared swap, if any
+pub fn render_swap_confirmation(ctx: &egui::Context, state: &AppState, app: &mut impl AppLike) {
+    let Some(confirmation) = &state.terminal.swap.confirmation else {
+        return;
+    };
+    let theme = Theme::default();
+
+    let mut open = ctx.input_mut(|i| !i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+    let mut execute = false;
+    egui::Window::new("Confirm Swap")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .default_width(460.0)
+        .show(ctx, |ui| {
+            ui.heading(format!(
+                "{} {} → {}",
+                confirmation.ui_amount, confirmation.input_symbol, confirmation.output_symbol
+            ));
+            ui.horizontal(|ui| {
+                ui.colored_label(theme.dim, "From:");
+                wallet_chip::render_wallet_chip(ui, &state.wallet_identities, &confirmation.wallet);
+            });
+            ui.add_space(5.0);
+
+            match confirmation_summary(confirmation) {
+                Some(summary) => {
+                    ui.colored_label(theme.success, summary);
+                }
+                None => {
+                    let error = confirmation.error().unwrap_or("Simulation failed");
+                    ui.colored_label(theme.error, format!("This swap would fail: {}", error));
+                }
+            }
+
+            let now = chrono::Utc::now().timestamp();
+            let stale_after = state.settings.network.stale_price_secs;
+            let prices = state.terminal.prices.load();
+            for (symbol, pct) in pair_divergence(confirmation, &prices) {
+                let spread = pct.map(|pct| format!(" by {:.2}%", pct)).unwrap_or_default();
+                ui.colored_label(
+                    theme.warning,
+                    format!("⚠ Price sources disagree on {}{}; the quote may be off", symbol, spread),
+                );
+            }
+            if let Some(estimate) = usd_estimate(confirmation, &prices, now, stale_after) {
+                ui.horizontal(|ui| {
+                    ui.colored_label(theme.dim, "Value:");
+                    ui.label(format!("≈ ${:.2}", estimate.usd));
+                    if let Some(source) = &estimate.source {
+                        ui.colored_label(theme.dim, format!("({})", source));
+                    }
+                });
+                if let Some(age) = estimate.stale_secs {
+                    ui.colored_label(
+                        theme.warning,
+                        format!("⚠ The oracle price behind this estimate is {}s old and may be out of date", age),
+                    );
+                }
+                if let Some(warning) = swap_concentration_warning(
+                    &state.portfolio.holdings,
+                    &confirmation.input_symbol,
+                    &confirmation.output_symbol,
+                    estimate.usd,
+                    &state.settings.risk,
+                ) {
+                    ui.colored_label(theme.warning, format!("⚠ {}", warning.explanation));
+                }
+            }
+
+            ui.horizontal(|ui| {
+                ui.colored_label(theme.dim, "Price impact:");
+                ui.label(format!("{:.2}%", confirmation.quote.price_impact));
+                ui.colored_label(theme.dim, "Slippage:");
+                ui.label(format!("{:.2}%", confirmation.slippage_bps as f64 / 100.0));
+            });
+            if let Ok(Some(units)) = confirmation.simulation.as_ref().map(|s| s.compute_units) {
+                ui.horizontal(|ui| {
+                    ui.colored_label(theme.dim, "Compute units:");
+                    ui.label(units.to_string());
+                });
+            }
+            if !confirmation.memo.is_empty() {
+                ui.horizontal(|ui| {
+                    ui.colored_label(theme.dim, "Memo:");
+                    ui.label(&confirmation.memo);
+                });
+            }
+
+            // The log tail is what a bug report needs; keep it selectable
+            let logs = confirmation.log_tail();
+            if !confirmation.can_execute() && !logs.is_empty() {
+                ui.add_space(5.0);
+                ui.colored_label(theme.dim, "Program logs:");
+                egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                    let text = logs.join("\n");
+                    ui.add(
+                        egui::TextEdit::multiline(&mut text.as_str())
+                            .font(egui::TextStyle::Monospace)
+                            .text_color(theme.error)
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+            }
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                let button = ui.add_enabled(
+                    confirmation.can_execute(),
+                    egui::Button::new("Execute").fill(theme.selected),
+                );
+                if button.on_disabled_hover_text("Simulation failed; nothing will be signed").clicked() {
+                    execute = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    app.handle_swap_confirmation_cancel();
+                }
+            });
+        });
+
+    if !open {
+        app.handle_swap_confirmation_cancel();
+    } else if execute {
+        app.handle_swap_confirm();
+    }
+}
@@ -0,0 +1,53 @@
+//! # System Notice Banner
+//!
+//! Dismissable banners for notices pushed by the backend (e.g. an upgrade of a
+//! program the terminal routes swaps through).
+
+use egui;
+use shared::dto::system::NoticeLevel;
+use crate::app::{AppLike, AppState};
+use crate::ui::theme::Theme;
+use crate::ui::widgets::icons::{Icons, material, size};
+
+/// Render active system notices, newest first
+pub fn render_system_notices(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike) {
+    if state.system_notices.is_empty() {
+        return;
+    }
+
+    let theme = Theme::default();
+    let mut dismissed = None;
+
+    for (idx, notice) in state.system_notices.iter().enumerate().rev() {
+        let (icon, color) = match notice.level {
+            NoticeLevel::Info => (Icons::icon_info(material::INFO, size::SMALL), theme.info),
+            NoticeLevel::Warning => (Icons::icon_warning(material::WARNING, size::SMALL), theme.warning),
+            NoticeLevel::Critical => (Icons::icon_error(material::ERROR, size::SMALL), theme.error),
+        };
+
+        egui::Frame::NONE
+            .stroke(egui::Stroke::new(1.0, color))
+            .inner_margin(egui::Margin::symmetric(8, 4))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(icon);
+                    ui.colored_label(color, egui::RichText::new(&notice.title).strong());
+                    ui.label(&notice.message);
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button(material::CLOSE).on_hover_text("Dismiss").clicked() {
+                            dismissed = Some(idx);
+                        }
+                    });
+                });
+            });
+        ui.add_space(4.0);
+    }
+
+    if let Some(idx) = dismissed {
+        let mut state = app.state().write();
+        if idx < state.system_notices.len() {
+            state.system_notices.remove(idx);
+        }
+    }
+}
@@ -1,29 +1,95 @@
 //! # Token Picker Widget
 //!
-//! Interactive modal popup for selecting tokens using egui widgets.
+//! Modal popup for selecting swap tokens from the full Jupiter list.
+//!
+//! The search matches symbol, name or mint address, best match first (see
+//! [`crate::app::handlers::swap::rank_tokens`]). Starred favorites sort to the
+//! top and the last picked tokens are offered above the list while the search
+//! is empty; both are saved in the local database. Tokens Jupiter hasn't
+//! verified are hidden unless "Show unverified" is ticked (favorites always
+//! show), since scam tokens copy the names of real ones. Only the rows in view
+//! are laid out, so thousands of tokens scroll without dropping frames.
+//! Arrow keys move the selection, Enter picks it and Escape closes.
 
 use egui;
+use crate::app::handlers::swap::rank_tokens;
 use crate::app::{App, AppState, TokenInfo};
 use crate::ui::theme::Theme;
 use crate::ui::widgets::icons::{Icons, material, size};
 
+/// Height of the token list viewport
+const LIST_HEIGHT: f32 = 320.0;
+
+/// What the user did this frame, applied once the window is drawn
+enum PickerAction {
+    Select(TokenInfo),
+    ToggleFavorite(String),
+}
+
 /// Render token picker popup
-pub fn render_token_picker(ctx: &egui::Context, _state: &AppState, app: &mut App) {
+pub fn render_token_picker(ctx: &egui::Context, state: &AppState, app: &mut App) {
     let theme = Theme::default();
-    
+    let swap = &state.terminal.swap;
+
+    // Consume navigation keys before the search field sees them
+    let (up, down, enter, escape) = ctx.input_mut(|i| {
+        (
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+        )
+    });
+    if escape {
+        app.state.write().terminal.swap.close_token_picker();
+        return;
+    }
+
+    let matches = rank_tokens(&swap.token_list, &swap.token_filter);
+    let ranked: Vec<usize> = matches
+        .iter()
+        .copied()
+        .filter(|&idx| {
+            let token = &swap.token_list[idx];
+            token.verified || token.is_favorite || swap.show_unverified_tokens
+        })
+        .collect();
+    let hidden = matches.len() - ranked.len();
+    let count = ranked.len();
+    let mut selected = swap.selected_token_index.min(count.saturating_sub(1));
+    if count > 0 && (up || down) {
+        selected = if down { (selected + 1) % count } else { (selected + count - 1) % count };
+        app.state.write().terminal.swap.selected_token_index = selected;
+    }
+    if enter {
+        if let Some(&idx) = ranked.get(selected) {
+            app.handle_token_select(swap.token_list[idx].clone(), swap.token_picker_for);
+            return;
+        }
+    }
+
+    let recents: Vec<&TokenInfo> = if swap.token_filter.trim().is_empty() {
+        state
+            .settings
+            .tokens
+            .recents
+            .iter()
+            .filter_map(|mint| swap.token_list.iter().find(|token| &token.mint == mint))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut filter = swap.token_filter.clone();
+    let mut filter_changed = false;
+    let mut show_unverified = swap.show_unverified_tokens;
+    let mut action = None;
+
     egui::Window::new("Select Token")
         .collapsible(false)
         .resizable(true)
         .default_size([600.0, 400.0])
         .show(ctx, |ui| {
-            // Read state inside closure to get latest values
-            let state = app.state.read();
-            let token_picker_for = state.terminal.swap.token_picker_for;
-            let token_filter = state.terminal.swap.token_filter.clone();
-            let selected_token_index = state.terminal.swap.selected_token_index;
-            let token_list = state.terminal.swap.token_list.clone();
-            drop(state); // Release lock
-
             // Header with icon
             ui.horizontal(|ui| {
                 ui.label(Icons::icon_red(material::TOKEN, size::MEDIUM));
@@ -34,129 +100,203 @@ pub fn render_token_picker(ctx: &egui::Context, _state: &AppState, app: &mut App
             // Search input with icon
             ui.horizontal(|ui| {
                 ui.label(Icons::icon_dim(material::SEARCH, size::SMALL));
-                ui.label("Search tokens:");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut filter)
+                        .hint_text("Symbol, name or mint address")
+                        .desired_width(420.0),
+                );
+                response.request_focus();
+                filter_changed = response.changed();
+                ui.checkbox(&mut show_unverified, "Show unverified")
+                    .on_hover_text("Tokens Jupiter hasn't reviewed; anyone can list one under any name");
             });
-            let mut filter = token_filter.clone();
-            if ui.text_edit_singleline(&mut filter).changed() {
-                let mut state_write = app.state.write();
-                state_write.terminal.swap.token_filter = filter.clone();
-                state_write.terminal.swap.selected_token_index = 0; // Reset selection when filtering
+
+            if !recents.is_empty() {
+                ui.add_space(5.0);
+                ui.horizontal_wrapped(|ui| {
+                    ui.colored_label(theme.dim, "Recent:");
+                    for token in &recents {
+                        if ui.small_button(&token.symbol).on_hover_text(&token.name).clicked() {
+                            action = Some(PickerAction::Select((*token).clone()));
+                        }
+                    }
+                });
             }
 
             ui.separator();
-            ui.add_space(5.0);
 
-            // Filter tokens based on search
-            let filtered_tokens: Vec<&TokenInfo> = if filter.is_empty() {
-                token_list.iter().collect()
-            } else {
-                let filter_lower = filter.to_lowercase();
-                token_list
-                    .iter()
-                    .filter(|token| {
-                        token.symbol.to_lowercase().contains(&filter_lower)
-                            || token.name.to_lowercase().contains(&filter_lower)
-                    })
-                    .collect()
-            };
-
-            // Sort: favorites first, then by symbol
-            let mut sorted_tokens = filtered_tokens;
-            sorted_tokens.sort_by(|a, b| {
-                match (a.is_favorite, b.is_favorite) {
-                    (true, false) => std::cmp::Ordering::Less,
-                    (false, true) => std::cmp::Ordering::Greater,
-                    _ => a.symbol.cmp(&b.symbol),
-                }
+            // Column headers
+            let row_height = ui.spacing().interact_size.y;
+            ui.horizontal(|ui| {
+                ui.add_space(row_height + ui.spacing().item_spacing.x);
+                header_cell(ui, &theme, "Symbol", 90.0);
+                ui.add_space(row_height + ui.spacing().item_spacing.x);
+                header_cell(ui, &theme, "Name", 170.0);
+                header_cell(ui, &theme, "Price", 90.0);
+                header_cell(ui, &theme, "24h %", 70.0);
+                header_cell(ui, &theme, "Balance", 90.0);
             });
 
-            // Token table with enhanced styling
-            egui::ScrollArea::vertical()
-                .show(ui, |ui| {
-                    egui::Grid::new("token_picker")
-                        .num_columns(6)
-                        .spacing([10.0, 5.0])
-                        .striped(true)
-                        .show(ui, |ui| {
-                            // Header row with styling
-                            ui.colored_label(theme.selected, "Symbol");
-                            ui.colored_label(theme.selected, "Name");
-                            ui.colored_label(theme.selected, "Price");
-                            ui.colored_label(theme.selected, "24h %");
-                            ui.colored_label(theme.selected, "Balance");
-                            ui.colored_label(theme.selected, "");
-                            ui.end_row();
-
-                            // Rows
-                            for (idx, token) in sorted_tokens.iter().enumerate() {
-                                let is_selected = idx == selected_token_index;
-                                let (change_text, change_color) = theme.format_price_change(token.change_24h);
-
-                                let symbol_text = if token.is_favorite {
-                                    format!("★ {}", token.symbol)
-                                } else {
-                                    token.symbol.clone()
-                                };
-
-                                // Selectable row - highlight selected with red accent
-                                let response = if is_selected {
-                                    ui.colored_label(theme.selected, &symbol_text)
-                                } else {
-                                    ui.label(&symbol_text)
-                                };
-                                
-                                if response.clicked() {
-                                    app.handle_token_select((*token).clone(), token_picker_for);
-                                }
-                                
-                                // Update selection index on hover
-                                if response.hovered() {
-                                    let mut state_write = app.state.write();
-                                    state_write.terminal.swap.selected_token_index = idx;
-                                }
-                                
-                                ui.label(&token.name);
-                                ui.monospace(format!("${:.4}", token.price));
-                                ui.colored_label(change_color, change_text);
-                                ui.monospace(format!("{:.4}", token.balance));
-                                // Change indicator icon
-                                let change_icon = if token.change_24h > 0.0 {
-                                    material::ARROW_UP
-                                } else if token.change_24h < 0.0 {
-                                    material::ARROW_DOWN
-                                } else {
-                                    ""
-                                };
-                                if !change_icon.is_empty() {
-                                    ui.label(Icons::icon_color(change_icon, size::SMALL, change_color));
-                                } else {
-                                    ui.label("");
-                                }
-                                ui.end_row();
-                            }
-                        });
-                });
+            if count == 0 {
+                ui.colored_label(theme.dim, "No tokens match");
+            }
+            if hidden > 0 {
+                ui.colored_label(theme.dim, format!("{} unverified hidden", hidden));
+            }
+
+            // Keep the selected row in view when it moves by keyboard, using
+            // last frame's scroll position since unseen rows aren't laid out
+            let viewport_id = egui::Id::new("token_picker_viewport");
+            let mut scroll = egui::ScrollArea::vertical()
+                .max_height(LIST_HEIGHT)
+                .auto_shrink([false, true]);
+            if up || down {
+                let (offset, height) = ui
+                    .data(|d| d.get_temp::<(f32, f32)>(viewport_id))
+                    .unwrap_or((0.0, LIST_HEIGHT));
+                let top = selected as f32 * (row_height + ui.spacing().item_spacing.y);
+                if top < offset {
+                    scroll = scroll.vertical_scroll_offset(top);
+                } else if top + row_height > offset + height {
+                    scroll = scroll.vertical_scroll_offset(top + row_height - height);
+                }
+            }
+
+            let output = scroll.show_rows(ui, row_height, count, |ui, rows| {
+                for row in rows {
+                    let token = &swap.token_list[ranked[row]];
+                    let is_selected = row == selected;
+                    let (change_text, change_color) = theme.format_price_change(token.change_24h);
+
+                    ui.horizontal(|ui| {
+                        let (star, star_color) = if token.is_favorite {
+                            ("★", theme.warning)
+                        } else {
+                            ("☆", theme.dim)
+                        };
+                        let star = egui::Button::new(egui::RichText::new(star).color(star_color)).frame(false);
+                        if ui.add_sized([row_height, row_height], star).on_hover_text("Favorite").clicked() {
+                            action = Some(PickerAction::ToggleFavorite(token.mint.clone()));
+                        }
+
+                        let response = ui.add_sized(
+                            [90.0, row_height],
+                            egui::Button::selectable(is_selected, &token.symbol),
+                        );
+                        if response.clicked() {
+                            action = Some(PickerAction::Select(token.clone()));
+                        }
+                        if response.hovered() && !is_selected {
+                            app.state.write().terminal.swap.selected_token_index = row;
+                        }
+
+                        let (badge, badge_hint) = if token.verified {
+                            (Icons::icon_success(material::CHECK, size::SMALL), "Verified by Jupiter")
+                        } else {
+                            (Icons::icon_warning(material::WARNING, size::SMALL), "Not verified: may impersonate another token")
+                        };
+                        ui.add_sized([row_height, row_height], egui::Label::new(badge)).on_hover_text(badge_hint);
+
+                        ui.add_sized([170.0, row_height], egui::Label::new(&token.name).truncate())
+                            .on_hover_text(&token.mint);
+                        ui.add_sized([90.0, row_height], egui::Label::new(
+                            egui::RichText::new(format!("${:.4}", token.price)).monospace(),
+                        ));
+                        ui.add_sized([70.0, row_height], egui::Label::new(
+                            egui::RichText::new(change_text).color(change_color),
+                        ));
+                        ui.add_sized([90.0, row_height], egui::Label::new(
+                            egui::RichText::new(format!("{:.4}", token.balance)).monospace(),
+                        ));
+                    });
+                }
+            });
+            ui.data_mut(|d| d.insert_temp(viewport_id, (output.state.offset.y, output.inner_rect.height())));
 
             ui.separator();
             ui.add_space(5.0);
 
             // Actions with icons
             ui.horizontal(|ui| {
-                if ui.add(egui::Button::new(format!("{} Select", material::CHECK)).fill(theme.selected)).clicked() {
-                    // Get selected token
-                    let state = app.state.read();
-                    if let Some(token) = sorted_tokens.get(selected_token_index) {
-                        let token = (*token).clone();
-                        let target = state.terminal.swap.token_picker_for;
-                        drop(state);
-                        app.handle_token_select(token, target);
+                let select = egui::Button::new(format!("{} Select", material::CHECK)).fill(theme.selected);
+                if ui.add_enabled(count > 0, select).clicked() {
+                    if let Some(&idx) = ranked.get(selected) {
+                        action = Some(PickerAction::Select(swap.token_list[idx].clone()));
                     }
                 }
 
                 if ui.button(format!("{} Cancel", material::CLOSE)).clicked() {
-                    let mut state_write = app.state.write();
-                    state_write.terminal.swap.show_token_picker = false;
+                    app.state.write().terminal.swap.close_token_picker();
                 }
+
+                ui.colored_label(theme.dim, format!("{} tokens · ↑↓ select · Enter pick · Esc close", count));
             });
         });
+
+    match action {
+        Some(PickerAction::Select(token)) => app.handle_token_select(token, swap.token_picker_for),
+        Some(PickerAction::ToggleFavorite(mint)) => app.handle_token_favorite_toggle(mint),
+        None if filter_changed || show_unverified != swap.show_unverified_tokens => {
+            let mut state_write = app.state.write();
+            state_write.terminal.swap.token_filter = filter;
+            state_write.terminal.swap.show_unverified_tokens = show_unverified;
+            state_write.terminal.swap.selected_token_index = 0; // Reset selection when filtering
+        }
+        None => {}
+    }
+}
+
+fn header_cell(ui: &mut egui::Ui, theme: &Theme, text: &str, width: f32) {
+    ui.add_sized([width, 0.0], egui::Label::new(egui::RichText::new(text).color(theme.selected)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::SwapField;
+
+    fn frame(app: &mut App, events: Vec<egui::Event>) {
+        let ctx = egui::Context::default();
+        let state = app.state.read().clone();
+        let _ = ctx.run(egui::RawInput { events, ..Default::default() }, |ctx| {
+            render_token_picker(ctx, &state, app);
+        });
+    }
+
+    fn key(key: egui::Key) -> egui::Event {
+        egui::Event::Key { key, physical_key: None, pressed: true, repeat: false, modifiers: egui::Modifiers::NONE }
+    }
+
+    #[test]
+    fn test_picker_handles_a_large_token_list() {
+        let mut app = App::new();
+        {
+            let mut state = app.state.write();
+            state.terminal.swap.token_list = (0..10_000)
+                .map(|i| TokenInfo {
+                    symbol: format!("TKN{}", i),
+                    name: format!("Token {}", i),
+                    mint: format!("mint{}", i),
+                    decimals: 9,
+                    price: 1.0,
+                    balance: 0.0,
+                    change_24h: 0.0,
+                    is_favorite: false,
+                    verified: true,
+                    daily_volume: None,
+                })
+                .collect();
+            state.terminal.swap.show_token_picker = true;
+        }
+
+        frame(&mut app, vec![key(egui::Key::ArrowDown)]);
+        assert_eq!(app.state.read().terminal.swap.selected_token_index, 1);
+
+        // Escape closes and hands focus back to the "From" button
+        frame(&mut app, vec![key(egui::Key::Escape)]);
+        let state = app.state.read();
+        assert!(!state.terminal.swap.show_token_picker);
+        assert_eq!(state.terminal.swap.focus_field, Some(SwapField::InputToken));
+        assert_eq!(state.terminal.swap.token_list.len(), 10_000);
+    }
 }
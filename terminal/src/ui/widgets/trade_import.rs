@@ -0,0 +1,260 @@
+//! # Trade Import Wizard
+//!
+//! Window opened from the Transactions screen to import trades from another
+//! platform's CSV export: choose a file, map its columns when the headers are
+//! not the documented ones, review row errors, then import.
+
+use egui;
+use crate::app::{AppLike, AppState, TradeImportStep};
+use crate::ui::theme::Theme;
+use crate::ui::widgets::{forms, tables};
+use shared::dto::trades::{ColumnMapping, TradeImportResponse};
+
+/// Most error and preview rows listed; the counts always cover the whole file
+const MAX_LISTED_ROWS: usize = 50;
+
+/// Render the wizard window while an import is in progress
+pub fn render_trade_import_window(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    let import = &state.trade_import;
+    if import.step == TradeImportStep::Closed {
+        return;
+    }
+
+    let mut open = true;
+    egui::Window::new("Import Trades")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_width(560.0)
+        .show(ui.ctx(), |ui| {
+            if let Some(name) = &import.file_name {
+                ui.horizontal(|ui| {
+                    ui.colored_label(theme.dim, "File:");
+                    ui.label(name);
+                    if import.loading {
+                        ui.spinner();
+                    }
+                });
+                ui.add_space(5.0);
+            }
+            if let Some(err) = &import.error {
+                forms::render_error(ui, err, theme);
+            }
+
+            match import.step {
+                TradeImportStep::Closed => {}
+                TradeImportStep::SelectFile => render_select_file(ui, import.loading, app, theme),
+                TradeImportStep::Mapping => render_mapping(ui, state, app, theme),
+                TradeImportStep::Review => render_review(ui, state, app, theme),
+                TradeImportStep::Done => render_summary(ui, state, app, theme),
+            }
+        });
+
+    if !open {
+        app.state().write().trade_import.close_wizard();
+    }
+}
+
+fn render_select_file(ui: &mut egui::Ui, loading: bool, app: &mut impl AppLike, theme: &Theme) {
+    forms::render_hint(
+        ui,
+        "Choose a CSV export with timestamp, pair, side, amount, price and (optionally) fee columns. \
+         Other column names can be mapped in the next step.",
+        theme,
+    );
+    ui.add_space(10.0);
+    if ui.add_enabled(!loading, egui::Button::new("Choose CSV...")).clicked() {
+        app.handle_trade_import_pick_file();
+    }
+}
+
+fn render_mapping(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    let import = &state.trade_import;
+    let Some(preview) = &import.preview else {
+        return;
+    };
+
+    forms::render_hint(ui, "Some columns were not found. Pick the column holding each field:", theme);
+    ui.add_space(5.0);
+    for error in preview.errors.iter().filter(|e| e.row == 0) {
+        ui.colored_label(theme.warning, &error.message);
+    }
+    ui.add_space(5.0);
+
+    {
+        let mut state_write = app.state().write();
+        let mapping = &mut state_write.trade_import.mapping;
+        egui::Grid::new("trade_import_mapping")
+            .num_columns(2)
+            .spacing([12.0, 6.0])
+            .show(ui, |ui| {
+                render_mapping_rows(ui, mapping, &preview.headers);
+            });
+    }
+
+    ui.add_space(10.0);
+    ui.horizontal(|ui| {
+        if ui.add_enabled(!import.loading, egui::Button::new("Validate")).clicked() {
+            app.handle_trade_import_validate();
+        }
+        if ui.button("Choose another file").clicked() {
+            app.handle_trade_import_pick_file();
+        }
+    });
+}
+
+fn render_mapping_rows(ui: &mut egui::Ui, mapping: &mut ColumnMapping, headers: &[String]) {
+    let required = [
+        ("Timestamp", &mut mapping.timestamp),
+        ("Pair", &mut mapping.pair),
+        ("Side", &mut mapping.side),
+        ("Amount", &mut mapping.amount),
+        ("Price", &mut mapping.price),
+    ];
+    for (label, column) in required {
+        ui.label(label);
+        egui::ComboBox::from_id_salt(("trade_import_column", label))
+            .selected_text(column.as_str())
+            .show_ui(ui, |ui| {
+                for header in headers {
+                    ui.selectable_value(column, header.clone(), header.as_str());
+                }
+            });
+        ui.end_row();
+    }
+
+    ui.label("Fee");
+    let selected = mapping.fee.as_deref().unwrap_or("(none)").to_string();
+    egui::ComboBox::from_id_salt(("trade_import_column", "Fee"))
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut mapping.fee, None, "(none)");
+            for header in headers {
+                ui.selectable_value(&mut mapping.fee, Some(header.clone()), header.as_str());
+            }
+        });
+    ui.end_row();
+}
+
+fn render_review(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    let import = &state.trade_import;
+    let Some(preview) = &import.preview else {
+        return;
+    };
+
+    render_counts(ui, preview);
+    ui.add_space(8.0);
+
+    if !preview.errors.is_empty() {
+        ui.colored_label(theme.warning, "Rows with errors are skipped:");
+        egui::ScrollArea::vertical()
+            .id_salt("trade_import_errors")
+            .max_height(140.0)
+            .show(ui, |ui| {
+                tables::render_table(
+                    ui,
+                    "trade_import_errors_table",
+                    tables::TableConfig { num_columns: 3, ..Default::default() },
+                    &["Row", "Field", "Problem"],
+                    theme,
+                    |ui| {
+                        for error in preview.errors.iter().take(MAX_LISTED_ROWS) {
+                            ui.monospace(error.row.to_string());
+                            ui.label(error.field.as_deref().unwrap_or("-"));
+                            ui.colored_label(theme.error, &error.message);
+                            ui.end_row();
+                        }
+                    },
+                );
+                if preview.errors.len() > MAX_LISTED_ROWS {
+                    ui.colored_label(theme.dim, format!("... and {} more", preview.errors.len() - MAX_LISTED_ROWS));
+                }
+            });
+        ui.add_space(8.0);
+    }
+
+    if !preview.valid.is_empty() {
+        egui::ScrollArea::vertical()
+            .id_salt("trade_import_preview")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                tables::render_table(
+                    ui,
+                    "trade_import_preview_table",
+                    tables::TableConfig { num_columns: 6, ..Default::default() },
+                    &["Time", "Pair", "Side", "Amount", "Price", "Fee"],
+                    theme,
+                    |ui| {
+                        for trade in preview.valid.iter().take(MAX_LISTED_ROWS) {
+                            let time = chrono::DateTime::from_timestamp(trade.timestamp, 0)
+                                .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                                .unwrap_or_default();
+                            ui.label(time);
+                            ui.label(format!("{}/{}", trade.base, trade.quote));
+                            ui.label(trade.side.to_string());
+                            ui.monospace(format!("{}", trade.amount));
+                            ui.monospace(format!("{}", trade.price));
+                            ui.monospace(format!("{}", trade.fee));
+                            ui.end_row();
+                        }
+                    },
+                );
+            });
+        ui.add_space(8.0);
+    }
+
+    ui.horizontal(|ui| {
+        let label = format!("Import {} trades", preview.valid.len());
+        if ui
+            .add_enabled(!import.loading && !preview.valid.is_empty(), egui::Button::new(label))
+            .clicked()
+        {
+            app.handle_trade_import_commit();
+        }
+        if ui.button("Edit column mapping").clicked() {
+            app.state().write().trade_import.step = TradeImportStep::Mapping;
+        }
+    });
+}
+
+fn render_summary(ui: &mut egui::Ui, state: &AppState, app: &mut impl AppLike, theme: &Theme) {
+    let Some(result) = &state.trade_import.preview else {
+        return;
+    };
+
+    ui.colored_label(theme.success, format!("Imported {} trades", result.imported));
+    ui.add_space(5.0);
+    render_counts(ui, result);
+    ui.add_space(5.0);
+    forms::render_hint(
+        ui,
+        "Imported trades count toward trade statistics but are not reconciled against on-chain history.",
+        theme,
+    );
+    ui.add_space(10.0);
+    ui.horizontal(|ui| {
+        if ui.button("Import another file").clicked() {
+            app.state().write().trade_import.open_wizard();
+        }
+        if ui.button("Close").clicked() {
+            app.state().write().trade_import.close_wizard();
+        }
+    });
+}
+
+fn render_counts(ui: &mut egui::Ui, response: &TradeImportResponse) {
+    let error_rows = {
+        let mut rows: Vec<usize> = response.errors.iter().map(|e| e.row).collect();
+        rows.dedup();
+        rows.len()
+    };
+    tables::render_stats_summary(
+        ui,
+        &[
+            ("Rows", response.total_rows),
+            ("Valid", response.valid.len()),
+            ("Errors", error_rows),
+            ("Duplicates", response.duplicates),
+        ],
+    );
+}
@@ -0,0 +1,104 @@
+//! # Transfer Confirmation
+//!
+//! Dialog between the Send panel's Review and signing. Amounts are shown
+//! exactly, in lamports or the token's base units next to the decimal
+//! amount, together with the network fee the RPC quoted and any rent for a
+//! token account the transfer creates.
+//!
+//! A memo attached on the Send panel is shown as it will appear on chain.
+//!
+//! A chip names the wallet that will sign. Sending to an address that holds
+//! no SOL is flagged; SOL too small to open such an account keeps Send
+//! disabled, since the runtime would reject it.
+
+use egui;
+use crate::app::handlers::send::recipient_warning;
+use crate::app::{AppLike, AppState};
+use crate::services::token_transfer::{format_token_amount, SOL_DECIMALS};
+use crate::ui::theme::Theme;
+use crate::ui::widgets::wallet_chip;
+
+/// Render the confirmation dialog for the prepared transfer, if any
+pub fn render_transfer_confirmation(ctx: &egui::Context, state: &AppState, app: &mut impl AppLike) {
+    let Some(confirmation) = &state.send.confirmation else {
+        return;
+    };
+    let theme = Theme::default();
+    let units = if confirmation.mint.is_none() { "lamports" } else { "base units" };
+    let sol = |lamports: u64| format!("{} SOL ({} lamports)", format_token_amount(lamports, SOL_DECIMALS), lamports);
+
+    let mut open = ctx.input_mut(|i| !i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+    let mut send = false;
+    egui::Window::new("Confirm Transfer")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .default_width(460.0)
+        .show(ctx, |ui| {
+            ui.heading(format!(
+                "Send {} {}",
+                format_token_amount(confirmation.amount, confirmation.decimals),
+                confirmation.symbol
+            ));
+            ui.horizontal(|ui| {
+                ui.colored_label(theme.dim, "From:");
+                wallet_chip::render_wallet_chip(ui, &state.wallet_identities, &confirmation.wallet);
+            });
+            ui.horizontal(|ui| {
+                ui.colored_label(theme.dim, "To:");
+                ui.monospace(&confirmation.recipient);
+            });
+            if let Some(memo) = &confirmation.memo {
+                ui.horizontal(|ui| {
+                    ui.colored_label(theme.dim, "Memo:");
+                    ui.label(memo).on_hover_text("Public: anyone can read it on chain");
+                });
+            }
+            ui.add_space(5.0);
+
+            egui::Grid::new("transfer_confirmation").num_columns(2).spacing([10.0, 4.0]).show(ui, |ui| {
+                ui.colored_label(theme.dim, "Amount:");
+                ui.monospace(format!("{} {}", confirmation.amount, units));
+                ui.end_row();
+                if confirmation.received != confirmation.amount {
+                    ui.colored_label(theme.dim, "Recipient gets:");
+                    ui.colored_label(theme.warning, format!("{} {} (after the transfer fee)", confirmation.received, units));
+                    ui.end_row();
+                }
+                ui.colored_label(theme.dim, "Network fee:");
+                ui.monospace(sol(confirmation.fee_lamports));
+                ui.end_row();
+                if let Some(rent) = confirmation.token_account_rent {
+                    ui.colored_label(theme.dim, "Token account rent:");
+                    ui.monospace(sol(rent)).on_hover_text("The recipient has no account for this token yet; this wallet pays to open it");
+                    ui.end_row();
+                }
+                ui.colored_label(theme.dim, "Total SOL:");
+                ui.monospace(sol(confirmation.total_lamports()));
+                ui.end_row();
+            });
+
+            if let Some(warning) = recipient_warning(confirmation) {
+                ui.add_space(5.0);
+                ui.colored_label(if confirmation.can_send() { theme.warning } else { theme.error }, format!("⚠ {}", warning));
+            }
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                let button = ui.add_enabled(confirmation.can_send(), egui::Button::new("Send").fill(theme.selected));
+                if button.on_disabled_hover_text("The runtime would reject this transfer").clicked() {
+                    send = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    app.handle_send_cancel();
+                }
+            });
+        });
+
+    if !open {
+        app.handle_send_cancel();
+    } else if send {
+        app.handle_send_confirm();
+    }
+}
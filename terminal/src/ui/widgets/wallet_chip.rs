@@ -0,0 +1,65 @@
+//! # Wallet Chip
+//!
+//! Small tag in a wallet's color with its label, put next to anything that
+//! belongs to one wallet. Hovering shows the full address.
+
+use egui;
+use crate::app::wallet_identity::{WalletFilter, WalletIdentities};
+
+/// Render the chip for `address`
+pub fn render_wallet_chip(ui: &mut egui::Ui, identities: &WalletIdentities, address: &str) -> egui::Response {
+    let fill = chip_color(identities.color(address));
+    let text = egui::RichText::new(identities.label(address)).small().strong().color(text_color(fill));
+
+    egui::Frame::new()
+        .fill(fill)
+        .corner_radius(4.0)
+        .inner_margin(egui::Margin::symmetric(6, 1))
+        .show(ui, |ui| ui.label(text))
+        .response
+        .on_hover_text(address)
+}
+
+/// Render a wallet filter dropdown over `wallets`
+///
+/// Returns the new filter when the user picks a different one.
+pub fn render_wallet_filter(
+    ui: &mut egui::Ui,
+    id: &str,
+    identities: &WalletIdentities,
+    wallets: &[String],
+    filter: &WalletFilter,
+) -> Option<WalletFilter> {
+    let mut picked = filter.clone();
+    let selected_text = match filter {
+        WalletFilter::All => "All wallets".to_string(),
+        WalletFilter::Only(address) => identities.label(address),
+    };
+
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut picked, WalletFilter::All, "All wallets");
+            for address in wallets {
+                ui.selectable_value(&mut picked, WalletFilter::Only(address.clone()), identities.label(address))
+                    .on_hover_text(address);
+            }
+        });
+
+    (picked != *filter).then_some(picked)
+}
+
+/// egui color for a stored RGB triple
+pub fn chip_color([r, g, b]: [u8; 3]) -> egui::Color32 {
+    egui::Color32::from_rgb(r, g, b)
+}
+
+/// Black or white, whichever reads better on `fill`
+fn text_color(fill: egui::Color32) -> egui::Color32 {
+    let luma = 0.299 * fill.r() as f32 + 0.587 * fill.g() as f32 + 0.114 * fill.b() as f32;
+    if luma > 150.0 {
+        egui::Color32::BLACK
+    } else {
+        egui::Color32::WHITE
+    }
+}
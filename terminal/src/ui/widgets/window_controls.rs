@@ -1,13 +1,18 @@
 //! # Window Controls Widget
 //!
 //! UI controls for managing multiple windows (Bloomberg-style).
-//! Provides "New Window" button and window management UI.
+//! Provides "New Window" button, layout save/reset and window management UI.
 
 use egui;
-use crate::app::{App, Screen};
+use crate::app::{usage, App, AppLike, Screen};
+use crate::app::keybindings::KeyAction;
+use shared::dto::telemetry::{UsageEvent, UsageFeature};
+
+/// Main window size used when the layout is reset
+const DEFAULT_ROOT_SIZE: [f32; 2] = [1200.0, 800.0];
 
 /// Create a new window with the specified screen
-pub fn create_new_window(app: &mut App, screen: Screen) {
+pub fn create_new_window(app: &mut impl AppLike, screen: Screen) {
     use std::time::{SystemTime, UNIX_EPOCH};
     
     let timestamp = SystemTime::now()
@@ -18,11 +23,62 @@ pub fn create_new_window(app: &mut App, screen: Screen) {
     let viewport_id = egui::ViewportId::from_hash_of(format!("window_{}", timestamp));
     
     let window_id = {
-        let mut window_manager = app.window_manager.write();
+        let mut window_manager = app.window_manager().write();
         window_manager.create_window(viewport_id, screen, Some(format!("Terminal - {}", screen.title())))
     };
     
     tracing::info!("Created new window: {:?} with screen: {:?}", window_id, screen);
+    usage::capture(UsageEvent::Feature(UsageFeature::NewWindow));
+}
+
+/// Save the current window arrangement so the next launch reopens it
+pub fn save_layout(app: &mut impl AppLike) {
+    let result = app.window_manager().read().save_layout();
+    let notification = match result {
+        Ok(()) => ("success", "Window layout saved".to_string()),
+        Err(e) => ("error", format!("Failed to save window layout: {}", e)),
+    };
+    app.state().write().pending_notifications.push((notification.0.to_string(), notification.1));
+}
+
+/// Close secondary windows, restore the main window's default size and
+/// delete the saved layout
+pub fn reset_layout(ctx: &egui::Context, app: &mut impl AppLike) {
+    app.window_manager().write().reset_layout();
+
+    ctx.send_viewport_cmd_to(egui::ViewportId::ROOT, egui::ViewportCommand::Fullscreen(false));
+    ctx.send_viewport_cmd_to(egui::ViewportId::ROOT, egui::ViewportCommand::InnerSize(DEFAULT_ROOT_SIZE.into()));
+
+    let settings = crate::core::store::store().settings();
+    if let Err(e) = settings.remove(crate::core::store::Document::Layout) {
+        tracing::warn!("Failed to delete saved window layout: {}", e);
+    }
+    app.state().write().pending_notifications.push(("info".to_string(), "Window layout reset".to_string()));
+}
+
+/// Render the "Windows" menu (new window, save/reset layout)
+pub fn render_window_menu(ui: &mut egui::Ui, app: &mut impl AppLike) {
+    ui.menu_button("Windows", |ui| {
+        let shortcut = app.state().read().settings.key_bindings.get(KeyAction::NewWindow);
+        if ui.button(format!("New Window ({})", shortcut)).clicked() {
+            // Opens on whatever the window the menu is in shows
+            let screen = app.current_screen();
+            create_new_window(app, screen);
+            ui.close();
+        }
+        ui.separator();
+        if ui.button("Save layout").clicked() {
+            save_layout(app);
+            ui.close();
+        }
+        if ui.button("Reset layout").clicked() {
+            reset_layout(ui.ctx(), app);
+            ui.close();
+        }
+        ui.separator();
+        let count = app.window_manager().read().window_count();
+        ui.weak(format!("{} secondary window{} open", count, if count == 1 { "" } else { "s" }));
+    });
 }
 
 /// Render window list/management panel (optional)
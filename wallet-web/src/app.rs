@@ -18,6 +18,7 @@ use crate::pages::{
 };
 use crate::components::Starfield;
 use crate::state::wallet::provide_wallet_context;
+use crate::utils::url::router_base;
 
 #[component]
 pub fn App() -> impl IntoView {
@@ -120,7 +121,7 @@ pub fn App() -> impl IntoView {
     });
 
     view! {
-        <Router>
+        <Router base=router_base()>
             <div class="app-container">
                 <Starfield/>
                 <Routes fallback=|| view! { <NotFound/> }>
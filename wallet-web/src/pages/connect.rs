@@ -11,7 +11,7 @@ use crate::services::wallet::{
     sign_message_provider,
 };
 use crate::state::wallet::use_wallet_context;
-use crate::utils::url::get_query_param;
+use crate::utils::url::{api_base, api_url, get_query_param};
 use gloo_net::http::Request;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::JsValue;
@@ -91,7 +91,7 @@ pub fn ConnectPage() -> impl IntoView {
         if let Some(token_val) = token {
             log!("[AUTO-VALIDATE] Found token in URL, attempting auto-validation...");
             log!("[AUTO-VALIDATE] Token: {}...", &token_val[..token_val.len().min(20)]);
-            let validate_url = format!("http://localhost:3001/api/wallet/setup/validate?token={}", token_val);
+            let validate_url = format!("{}?token={}", api_url("/api/wallet/setup/validate"), token_val);
             log!("[AUTO-VALIDATE] URL: {}", validate_url);
             log!("[AUTO-VALIDATE] Sending GET request...");
             match Request::get(&validate_url).send().await {
@@ -151,7 +151,7 @@ pub fn ConnectPage() -> impl IntoView {
             log!("[WALLET CONNECT] Setup token length: {}", token.len());
             log!("[WALLET CONNECT] Setup token: {}...", &token[..token.len().min(20)]);
             
-            let validate_url = format!("http://localhost:3001/api/wallet/setup/validate?token={}", token);
+            let validate_url = format!("{}?token={}", api_url("/api/wallet/setup/validate"), token);
             log!("[WALLET CONNECT] Step 1: Validating setup token");
             log!("[WALLET CONNECT] Request URL: {}", validate_url);
             log!("[WALLET CONNECT] Sending GET request to backend...");
@@ -171,10 +171,10 @@ pub fn ConnectPage() -> impl IntoView {
                     log!("[WALLET CONNECT] Error details: {:?}", e);
                     log!("[WALLET CONNECT] URL attempted: {}", validate_url);
                     log!("[WALLET CONNECT] This usually means:");
-                    log!("[WALLET CONNECT]   1. Backend server is not running on port 3001");
+                    log!("[WALLET CONNECT]   1. Backend server is not running at {}", api_base());
                     log!("[WALLET CONNECT]   2. CORS is blocking the request");
                     log!("[WALLET CONNECT]   3. Network connectivity issue");
-                    set_error.set(Some(format!("Failed to connect to backend: {:?}. Is the server running on http://localhost:3001?", e)));
+                    set_error.set(Some(format!("Failed to connect to backend: {:?}. Is the server running on {}?", e, api_base())));
                     set_connecting.set(false);
                     return;
                 }
@@ -279,7 +279,7 @@ pub fn ConnectPage() -> impl IntoView {
                 challenge,
             };
 
-            let complete_url = "http://localhost:3001/api/wallet/setup/complete".to_string();
+            let complete_url = api_url("/api/wallet/setup/complete");
             let complete_response = match Request::post(&complete_url)
                 .json(&complete_req)
                 .unwrap()
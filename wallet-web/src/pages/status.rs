@@ -3,6 +3,7 @@
 use leptos::prelude::*;
 use leptos_router::hooks::use_navigate;
 use crate::state::wallet::use_wallet_context;
+use crate::utils::url::router_base;
 
 #[component]
 pub fn StatusPage() -> impl IntoView {
@@ -64,7 +65,7 @@ pub fn StatusPage() -> impl IntoView {
                                 <p style="text-align: center; color: var(--text-secondary); margin-bottom: var(--spacing-lg);">
                                     "No wallet connected"
                                 </p>
-                                <a href="/" class="btn" style="width: 100%; display: block; text-align: center; text-decoration: none;">
+                                <a href=format!("{}/", router_base()) class="btn" style="width: 100%; display: block; text-align: center; text-decoration: none;">
                                     "Connect Wallet"
                                 </a>
                             </div>
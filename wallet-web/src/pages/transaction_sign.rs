@@ -11,6 +11,7 @@ use crate::services::wallet::{
     WalletState,
 };
 use crate::state::wallet::use_wallet_context;
+use crate::utils::url::api_url;
 use gloo_net::http::Request;
 use serde::{Deserialize, Serialize};
 
@@ -145,7 +146,7 @@ pub fn TransactionSignPage() -> impl IntoView {
                 output_amount: output_amount_val,
             };
             
-            let submit_url = "http://localhost:3001/api/transactions/submit".to_string();
+            let submit_url = api_url("/api/transactions/submit");
             let submit_response = match Request::post(&submit_url)
                 .json(&submit_req)
                 .unwrap()
@@ -580,3 +580,65 @@ pub async fn sign_message_provider(
             }
         })
 }
+
+/// Log in with a linked wallet.
+///
+/// Fetches a single-use challenge from the backend, has the wallet sign the
+/// message it returns, then exchanges the signature for a JWT. Challenges are
+/// short-lived and consumed by the login, so this must run end-to-end for
+/// every attempt.
+pub async fn wallet_login(
+    provider: &WalletProvider,
+    wallet_address: &str,
+) -> Result<shared::dto::AuthResponse, String> {
+    use crate::utils::url::api_url;
+    use gloo_net::http::Request;
+    use shared::dto::{ErrorResponse, WalletLoginChallengeResponse, WalletLoginRequest};
+
+    async fn error_message(response: gloo_net::http::Response) -> String {
+        match response.json::<ErrorResponse>().await {
+            Ok(body) => body.error,
+            Err(_) => format!("HTTP {}", response.status()),
+        }
+    }
+
+    // 1. Server-issued challenge for this wallet
+    let challenge_url = format!("{}?wallet_address={}", api_url("/api/wallet/login/challenge"), wallet_address);
+    let response = Request::get(&challenge_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to request login challenge: {}", e))?;
+    if !response.ok() {
+        return Err(error_message(response).await);
+    }
+    let issued: WalletLoginChallengeResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid challenge response: {}", e))?;
+
+    // 2. Sign exactly the message the server built
+    let sign_result = sign_message_provider(provider, issued.message.as_bytes(), "utf8").await?;
+    let signature = js_sys::Reflect::get(&sign_result, &JsValue::from_str("signature"))
+        .map(|sig| bs58::encode(js_sys::Uint8Array::from(sig).to_vec()).into_string())
+        .map_err(|_| "Failed to extract signature".to_string())?;
+
+    // 3. Exchange the signature (and the challenge, which is consumed) for a token
+    let login_req = WalletLoginRequest {
+        wallet_address: wallet_address.to_string(),
+        signature,
+        challenge: issued.challenge,
+    };
+    let response = Request::post(&api_url("/api/auth/wallet-login"))
+        .json(&login_req)
+        .map_err(|e| format!("Failed to encode login request: {}", e))?
+        .send()
+        .await
+        .map_err(|e| format!("Failed to log in: {}", e))?;
+    if !response.ok() {
+        return Err(error_message(response).await);
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid login response: {}", e))
+}
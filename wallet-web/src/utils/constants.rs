@@ -1,7 +1,11 @@
 //! Application constants
 
+/// Backend used when the app runs on its own dev server
 pub const API_BASE: &str = "http://127.0.0.1:3001";
 
+/// Path the backend serves this app under when it embeds it
+pub const EMBEDDED_BASE: &str = "/wallet";
+
 // Token mints (Solana mainnet/devnet)
 pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
 pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
@@ -1,7 +1,45 @@
-//! URL utility functions for reading query parameters
+//! URL utility functions for reading query parameters and locating the backend
+//!
+//! When the backend embeds this app (its `embedded-wallet` feature) the page is
+//! served under [`EMBEDDED_BASE`] on the backend's own origin; the router and
+//! API calls follow it there. Otherwise the app is on the trunk dev server and
+//! talks to [`API_BASE`].
 
+use crate::utils::constants::{API_BASE, EMBEDDED_BASE};
 use web_sys::window;
 
+/// Whether the backend serves this page under [`EMBEDDED_BASE`]
+pub fn is_embedded() -> bool {
+    window()
+        .and_then(|w| w.location().pathname().ok())
+        .map(|path| path == EMBEDDED_BASE || path.starts_with(&format!("{}/", EMBEDDED_BASE)))
+        .unwrap_or(false)
+}
+
+/// Base path for the router: [`EMBEDDED_BASE`] when embedded, the root otherwise
+pub fn router_base() -> &'static str {
+    if is_embedded() {
+        EMBEDDED_BASE
+    } else {
+        ""
+    }
+}
+
+/// Backend URL for API calls: the page's origin when embedded, [`API_BASE`] otherwise
+pub fn api_base() -> String {
+    if is_embedded() {
+        if let Some(origin) = window().and_then(|w| w.location().origin().ok()) {
+            return origin;
+        }
+    }
+    API_BASE.to_string()
+}
+
+/// Absolute URL of a backend endpoint, e.g. `api_url("/api/health")`
+pub fn api_url(path: &str) -> String {
+    format!("{}{}", api_base(), path)
+}
+
 /// Get a query parameter from the current URL
 /// This is a fallback method that reads directly from window.location.search
 /// Use this when the router's query map might not be initialized yet